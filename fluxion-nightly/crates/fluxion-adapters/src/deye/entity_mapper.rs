@@ -0,0 +1,320 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use crate::deye::modes::DeyeWorkMode;
+use fluxion_core::{EntityChange, InverterOperationMode, ModeChangeRequest, VendorEntityMapper};
+
+/// Battery SOC that force discharge runs down to; FluxION normally stops it earlier
+const FORCE_DISCHARGE_SOC: u8 = 10;
+
+/// Deye/Sunsynk hybrid entity mapper for Home Assistant (Sunsynk and Solarman integrations)
+///
+/// Deye has no force charge or discharge mode. Both go through time-of-use
+/// program 1: its SOC is the battery target and its grid charging switch allows
+/// charging from the grid. Force discharge additionally selects "Selling First"
+/// so the battery exports down to the program SOC. With time of use off the
+/// inverter runs plain self-consumption.
+pub struct DeyeEntityMapper;
+
+impl DeyeEntityMapper {
+    /// Create a new Deye entity mapper
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn work_mode(mode: InverterOperationMode) -> DeyeWorkMode {
+        match mode {
+            InverterOperationMode::ForceDischarge => DeyeWorkMode::SellingFirst,
+            InverterOperationMode::SelfUse
+            | InverterOperationMode::BackUpMode
+            | InverterOperationMode::ForceCharge
+            | InverterOperationMode::NoChargeNoDischarge => DeyeWorkMode::ZeroExportToCt,
+        }
+    }
+
+    /// Program 1 target SOC (%) and grid charging, or None to turn time of use off
+    fn program(mode: InverterOperationMode) -> Option<(u8, bool)> {
+        match mode {
+            InverterOperationMode::SelfUse => None,
+            // Backup on Deye is a grid charge to full that is then held
+            InverterOperationMode::BackUpMode | InverterOperationMode::ForceCharge => {
+                Some((100, true))
+            }
+            // The battery never discharges below the program SOC, so 100 % holds it
+            InverterOperationMode::NoChargeNoDischarge => Some((100, false)),
+            InverterOperationMode::ForceDischarge => Some((FORCE_DISCHARGE_SOC, false)),
+        }
+    }
+}
+
+impl Default for DeyeEntityMapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VendorEntityMapper for DeyeEntityMapper {
+    fn vendor_name(&self) -> fluxion_core::InverterType {
+        fluxion_core::InverterType::Deye
+    }
+
+    fn map_mode_to_vendor(&self, mode: InverterOperationMode) -> i32 {
+        Self::work_mode(mode).to_i32()
+    }
+
+    fn map_mode_from_vendor(&self, vendor_mode: i32) -> Option<InverterOperationMode> {
+        match DeyeWorkMode::from_i32(vendor_mode)? {
+            DeyeWorkMode::SellingFirst => Some(InverterOperationMode::ForceDischarge),
+            // Charging and holding live in the time-of-use program, so they read back as self-use
+            DeyeWorkMode::ZeroExportToLoad | DeyeWorkMode::ZeroExportToCt => {
+                Some(InverterOperationMode::SelfUse)
+            }
+        }
+    }
+
+    fn get_work_mode_entity(&self, inverter_id: &str) -> String {
+        format!("select.{inverter_id}_work_mode")
+    }
+
+    fn get_mode_change_request(
+        &self,
+        inverter_id: &str,
+        mode: InverterOperationMode,
+    ) -> ModeChangeRequest {
+        // Program values go first so the inverter never runs time of use with stale targets
+        let mut entity_changes = Vec::new();
+        let program = Self::program(mode);
+        if let Some((soc_pct, grid_charge)) = program {
+            entity_changes.push(EntityChange {
+                entity_id: format!("number.{inverter_id}_program_1_soc"),
+                option: soc_pct.to_string(),
+            });
+            entity_changes.push(EntityChange {
+                entity_id: format!("switch.{inverter_id}_program_1_grid_charging"),
+                option: if grid_charge { "on" } else { "off" }.to_owned(),
+            });
+        }
+        entity_changes.push(EntityChange {
+            entity_id: format!("switch.{inverter_id}_time_of_use"),
+            option: if program.is_some() { "on" } else { "off" }.to_owned(),
+        });
+
+        let option = serde_json::to_value(Self::work_mode(mode))
+            .and_then(serde_json::from_value)
+            .expect("Failed to serialize work mode");
+        entity_changes.push(EntityChange {
+            entity_id: self.get_work_mode_entity(inverter_id),
+            option,
+        });
+
+        ModeChangeRequest { entity_changes }
+    }
+
+    fn get_battery_soc_entity(&self, inverter_id: &str) -> String {
+        format!("sensor.{inverter_id}_battery_soc")
+    }
+
+    fn get_grid_power_entity(&self, inverter_id: &str) -> String {
+        // Positive = import from grid, Negative = export to grid
+        format!("sensor.{inverter_id}_total_grid_power")
+    }
+
+    fn grid_power_export_positive(&self) -> bool {
+        false
+    }
+
+    fn get_battery_power_entity(&self, inverter_id: &str) -> String {
+        // Positive = discharging, Negative = charging
+        format!("sensor.{inverter_id}_battery_power")
+    }
+
+    fn battery_power_charge_positive(&self) -> bool {
+        false
+    }
+
+    fn get_pv_power_entity(&self, inverter_id: &str) -> String {
+        format!("sensor.{inverter_id}_pv_power")
+    }
+
+    fn get_export_limit_entity(&self, inverter_id: &str) -> String {
+        format!("number.{inverter_id}_max_sell_power")
+    }
+
+    fn get_charge_current_limit_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("number.{inverter_id}_battery_max_charge_current"))
+    }
+
+    // ============= Extended PV (Optional) =============
+
+    fn get_pv1_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_pv1_power"))
+    }
+
+    fn get_pv2_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_pv2_power"))
+    }
+
+    // ============= Three-Phase Data (Optional) =============
+
+    fn get_l1_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_l1_voltage"))
+    }
+
+    fn get_l1_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_l1_current"))
+    }
+
+    fn get_l1_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_l1_power"))
+    }
+
+    fn get_l2_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_l2_voltage"))
+    }
+
+    fn get_l2_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_l2_current"))
+    }
+
+    fn get_l2_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_l2_power"))
+    }
+
+    fn get_l3_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_l3_voltage"))
+    }
+
+    fn get_l3_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_l3_current"))
+    }
+
+    fn get_l3_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_l3_power"))
+    }
+
+    // ============= Battery Extended (Optional) =============
+
+    fn get_battery_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_voltage"))
+    }
+
+    fn get_battery_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_current"))
+    }
+
+    fn get_battery_input_energy_today_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_today_battery_charge"))
+    }
+
+    fn get_battery_output_energy_today_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_today_battery_discharge"))
+    }
+
+    // ============= Load & Grid Detailed (Optional) =============
+
+    fn get_house_load_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_load_power"))
+    }
+
+    fn get_grid_import_today_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_today_energy_import"))
+    }
+
+    fn get_grid_export_today_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_today_energy_export"))
+    }
+
+    fn get_inverter_frequency_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_frequency"))
+    }
+
+    // ============= Solar Energy (Optional) =============
+
+    fn get_today_solar_energy_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_today_production"))
+    }
+
+    fn get_total_solar_energy_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_total_production"))
+    }
+
+    // ============= Temperatures (Optional) =============
+
+    fn get_battery_temperature_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_temperature"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(request: &ModeChangeRequest) -> Vec<(&str, &str)> {
+        request
+            .entity_changes
+            .iter()
+            .map(|c| (c.entity_id.as_str(), c.option.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_deye_force_charge_programs_grid_charge_to_full() {
+        let mapper = DeyeEntityMapper::new();
+
+        let request = mapper.get_mode_change_request("deye", InverterOperationMode::ForceCharge);
+        assert_eq!(
+            steps(&request),
+            vec![
+                ("number.deye_program_1_soc", "100"),
+                ("switch.deye_program_1_grid_charging", "on"),
+                ("switch.deye_time_of_use", "on"),
+                ("select.deye_work_mode", "Zero Export To CT"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deye_force_discharge_sells_down_to_floor() {
+        let mapper = DeyeEntityMapper::new();
+
+        let request = mapper.get_mode_change_request("deye", InverterOperationMode::ForceDischarge);
+        assert_eq!(
+            steps(&request),
+            vec![
+                ("number.deye_program_1_soc", "10"),
+                ("switch.deye_program_1_grid_charging", "off"),
+                ("switch.deye_time_of_use", "on"),
+                ("select.deye_work_mode", "Selling First"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_deye_self_use_turns_time_of_use_off() {
+        let mapper = DeyeEntityMapper::new();
+
+        let request = mapper.get_mode_change_request("deye", InverterOperationMode::SelfUse);
+        assert_eq!(
+            steps(&request),
+            vec![
+                ("switch.deye_time_of_use", "off"),
+                ("select.deye_work_mode", "Zero Export To CT"),
+            ]
+        );
+        assert_eq!(
+            mapper.map_mode_from_vendor(0),
+            Some(InverterOperationMode::ForceDischarge)
+        );
+        assert!(!mapper.grid_power_export_positive());
+        assert!(!mapper.battery_power_charge_positive());
+    }
+}
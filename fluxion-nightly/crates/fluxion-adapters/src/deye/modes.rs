@@ -0,0 +1,68 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use serde::{Deserialize, Serialize};
+
+/// Deye/Sunsynk work mode - decides where surplus energy goes
+/// Order matches Home Assistant select.{inverter_id}_work_mode options
+/// These strings must match EXACTLY what HA expects (case-sensitive, including spaces)
+///
+/// Charging and discharging targets are not modes on Deye: they come from the
+/// time-of-use program, whose first slot FluxION rewrites.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[repr(i32)]
+pub enum DeyeWorkMode {
+    #[serde(rename = "Selling First")]
+    SellingFirst = 0,
+
+    #[serde(rename = "Zero Export To Load")]
+    ZeroExportToLoad = 1,
+
+    #[serde(rename = "Zero Export To CT")]
+    ZeroExportToCt = 2,
+}
+
+impl DeyeWorkMode {
+    /// Try to create from i32 discriminant
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::SellingFirst),
+            1 => Some(Self::ZeroExportToLoad),
+            2 => Some(Self::ZeroExportToCt),
+            _ => None,
+        }
+    }
+
+    /// Convert to i32 discriminant
+    pub fn to_i32(self) -> i32 {
+        self as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_work_mode_serde() {
+        let json = serde_json::to_string(&DeyeWorkMode::ZeroExportToCt).unwrap();
+        assert_eq!(json, "\"Zero Export To CT\"");
+
+        let mode: DeyeWorkMode = serde_json::from_str("\"Selling First\"").unwrap();
+        assert_eq!(mode, DeyeWorkMode::SellingFirst);
+
+        for value in 0..3 {
+            let mode = DeyeWorkMode::from_i32(value).unwrap();
+            assert_eq!(mode.to_i32(), value);
+        }
+    }
+}
@@ -0,0 +1,345 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use crate::goodwe::modes::GoodWeOperationMode;
+use fluxion_core::{EntityChange, InverterOperationMode, ModeChangeRequest, VendorEntityMapper};
+
+/// GoodWe ET/EH entity mapper for the Home Assistant `goodwe` integration
+///
+/// Force charge and discharge use the eco charge/discharge operation modes,
+/// whose power (percent of rated) and target SOC come from number entities
+/// that must be written before the mode is selected.
+pub struct GoodWeEntityMapper;
+
+impl GoodWeEntityMapper {
+    /// Create a new GoodWe entity mapper
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Operation mode plus the eco mode power (%) and target SOC (%) it needs, if any
+    fn vendor_mode(mode: InverterOperationMode) -> (GoodWeOperationMode, Option<(u8, u8)>) {
+        match mode {
+            InverterOperationMode::SelfUse => (GoodWeOperationMode::General, None),
+            InverterOperationMode::BackUpMode => (GoodWeOperationMode::Backup, None),
+            // Full-power grid charge until the battery is full
+            InverterOperationMode::ForceCharge => {
+                (GoodWeOperationMode::EcoCharge, Some((100, 100)))
+            }
+            // Full-power discharge; FluxION switches back before its own SOC floor
+            InverterOperationMode::ForceDischarge => {
+                (GoodWeOperationMode::EcoDischarge, Some((100, 0)))
+            }
+            // Eco charge at 0 % holds the battery: no grid charge and no discharge
+            InverterOperationMode::NoChargeNoDischarge => {
+                (GoodWeOperationMode::EcoCharge, Some((0, 100)))
+            }
+        }
+    }
+}
+
+impl Default for GoodWeEntityMapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VendorEntityMapper for GoodWeEntityMapper {
+    fn vendor_name(&self) -> fluxion_core::InverterType {
+        fluxion_core::InverterType::GoodWe
+    }
+
+    fn map_mode_to_vendor(&self, mode: InverterOperationMode) -> i32 {
+        Self::vendor_mode(mode).0.to_i32()
+    }
+
+    fn map_mode_from_vendor(&self, vendor_mode: i32) -> Option<InverterOperationMode> {
+        match GoodWeOperationMode::from_i32(vendor_mode)? {
+            GoodWeOperationMode::General => Some(InverterOperationMode::SelfUse),
+            GoodWeOperationMode::Backup => Some(InverterOperationMode::BackUpMode),
+            // Eco charge at 0 % (NoChargeNoDischarge) is indistinguishable without
+            // reading eco_mode_power, so it reads back as ForceCharge
+            GoodWeOperationMode::EcoCharge => Some(InverterOperationMode::ForceCharge),
+            GoodWeOperationMode::EcoDischarge => Some(InverterOperationMode::ForceDischarge),
+            GoodWeOperationMode::OffGrid
+            | GoodWeOperationMode::Eco
+            | GoodWeOperationMode::PeakShaving => None,
+        }
+    }
+
+    fn get_work_mode_entity(&self, inverter_id: &str) -> String {
+        format!("select.{inverter_id}_inverter_operation_mode")
+    }
+
+    fn get_mode_change_request(
+        &self,
+        inverter_id: &str,
+        mode: InverterOperationMode,
+    ) -> ModeChangeRequest {
+        let (operation_mode, eco) = Self::vendor_mode(mode);
+
+        // The integration applies the current eco numbers when the mode is selected
+        let mut entity_changes = Vec::new();
+        if let Some((power_pct, soc_pct)) = eco {
+            entity_changes.push(EntityChange {
+                entity_id: format!("number.{inverter_id}_eco_mode_power"),
+                option: power_pct.to_string(),
+            });
+            entity_changes.push(EntityChange {
+                entity_id: format!("number.{inverter_id}_eco_mode_soc"),
+                option: soc_pct.to_string(),
+            });
+        }
+
+        let option = serde_json::to_value(operation_mode)
+            .and_then(serde_json::from_value)
+            .expect("Failed to serialize operation mode");
+        entity_changes.push(EntityChange {
+            entity_id: self.get_work_mode_entity(inverter_id),
+            option,
+        });
+
+        ModeChangeRequest { entity_changes }
+    }
+
+    fn get_battery_soc_entity(&self, inverter_id: &str) -> String {
+        format!("sensor.{inverter_id}_battery_state_of_charge")
+    }
+
+    fn get_grid_power_entity(&self, inverter_id: &str) -> String {
+        // Positive = export to grid, Negative = import from grid
+        format!("sensor.{inverter_id}_active_power")
+    }
+
+    fn get_battery_power_entity(&self, inverter_id: &str) -> String {
+        // Positive = discharging, Negative = charging
+        format!("sensor.{inverter_id}_battery_power")
+    }
+
+    fn battery_power_charge_positive(&self) -> bool {
+        false
+    }
+
+    fn get_pv_power_entity(&self, inverter_id: &str) -> String {
+        format!("sensor.{inverter_id}_pv_power")
+    }
+
+    fn get_export_limit_entity(&self, inverter_id: &str) -> String {
+        format!("number.{inverter_id}_grid_export_limit")
+    }
+
+    // ============= Extended PV (Optional) =============
+
+    fn get_pv1_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_pv1_power"))
+    }
+
+    fn get_pv2_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_pv2_power"))
+    }
+
+    fn get_pv3_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_pv3_power"))
+    }
+
+    fn get_pv4_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_pv4_power"))
+    }
+
+    // ============= Three-Phase Data (Optional) =============
+
+    fn get_l1_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_on_grid_l1_voltage"))
+    }
+
+    fn get_l1_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_on_grid_l1_current"))
+    }
+
+    fn get_l1_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_on_grid_l1_power"))
+    }
+
+    fn get_l2_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_on_grid_l2_voltage"))
+    }
+
+    fn get_l2_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_on_grid_l2_current"))
+    }
+
+    fn get_l2_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_on_grid_l2_power"))
+    }
+
+    fn get_l3_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_on_grid_l3_voltage"))
+    }
+
+    fn get_l3_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_on_grid_l3_current"))
+    }
+
+    fn get_l3_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_on_grid_l3_power"))
+    }
+
+    // ============= Battery Extended (Optional) =============
+
+    fn get_battery_soh_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_state_of_health"))
+    }
+
+    fn get_battery_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_voltage"))
+    }
+
+    fn get_battery_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_current"))
+    }
+
+    fn get_battery_output_energy_total_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_total_battery_discharge"))
+    }
+
+    fn get_battery_input_energy_total_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_total_battery_charge"))
+    }
+
+    fn get_battery_input_energy_today_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_today_battery_charge"))
+    }
+
+    fn get_battery_output_energy_today_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_today_battery_discharge"))
+    }
+
+    // ============= Load & Grid Detailed (Optional) =============
+
+    fn get_house_load_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_house_consumption"))
+    }
+
+    fn get_grid_import_today_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_today_energy_import"))
+    }
+
+    fn get_grid_export_today_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_today_energy_export"))
+    }
+
+    fn get_grid_import_total_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_total_energy_import"))
+    }
+
+    fn get_grid_export_total_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_total_energy_export"))
+    }
+
+    fn get_inverter_frequency_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_on_grid_l1_frequency"))
+    }
+
+    // ============= Solar Energy (Optional) =============
+
+    fn get_today_solar_energy_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_today_s_pv_generation"))
+    }
+
+    fn get_total_solar_energy_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_total_pv_generation"))
+    }
+
+    // ============= Temperatures (Optional) =============
+
+    fn get_inverter_temperature_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_inverter_temperature_air"))
+    }
+
+    fn get_battery_temperature_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_temperature"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goodwe_mode_mapping_from_vendor() {
+        let mapper = GoodWeEntityMapper::new();
+
+        assert_eq!(
+            mapper.map_mode_from_vendor(0),
+            Some(InverterOperationMode::SelfUse)
+        );
+        assert_eq!(
+            mapper.map_mode_from_vendor(2),
+            Some(InverterOperationMode::BackUpMode)
+        );
+        assert_eq!(
+            mapper.map_mode_from_vendor(5),
+            Some(InverterOperationMode::ForceCharge)
+        );
+        assert_eq!(
+            mapper.map_mode_from_vendor(6),
+            Some(InverterOperationMode::ForceDischarge)
+        );
+        assert_eq!(mapper.map_mode_from_vendor(3), None); // Eco mode
+        assert_eq!(mapper.map_mode_from_vendor(99), None);
+    }
+
+    #[test]
+    fn test_goodwe_force_charge_sets_eco_numbers_before_mode() {
+        let mapper = GoodWeEntityMapper::new();
+
+        let request = mapper.get_mode_change_request("goodwe", InverterOperationMode::ForceCharge);
+        let steps: Vec<(&str, &str)> = request
+            .entity_changes
+            .iter()
+            .map(|c| (c.entity_id.as_str(), c.option.as_str()))
+            .collect();
+        assert_eq!(
+            steps,
+            vec![
+                ("number.goodwe_eco_mode_power", "100"),
+                ("number.goodwe_eco_mode_soc", "100"),
+                ("select.goodwe_inverter_operation_mode", "Eco charge mode"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_goodwe_hold_uses_zero_power_eco_charge() {
+        let mapper = GoodWeEntityMapper::new();
+
+        let request =
+            mapper.get_mode_change_request("goodwe", InverterOperationMode::NoChargeNoDischarge);
+        assert_eq!(request.entity_changes.len(), 3);
+        assert_eq!(request.entity_changes[0].option, "0");
+        assert_eq!(request.entity_changes[2].option, "Eco charge mode");
+    }
+
+    #[test]
+    fn test_goodwe_self_use_only_selects_mode() {
+        let mapper = GoodWeEntityMapper::new();
+
+        let request = mapper.get_mode_change_request("goodwe", InverterOperationMode::SelfUse);
+        assert_eq!(request.entity_changes.len(), 1);
+        assert_eq!(
+            request.entity_changes[0].entity_id,
+            "select.goodwe_inverter_operation_mode"
+        );
+        assert_eq!(request.entity_changes[0].option, "General mode");
+        assert!(!mapper.battery_power_charge_positive());
+    }
+}
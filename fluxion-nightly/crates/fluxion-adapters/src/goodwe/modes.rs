@@ -0,0 +1,85 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use serde::{Deserialize, Serialize};
+
+/// GoodWe operation mode - the only mode selector on ET/EH hybrids
+/// Order matches Home Assistant select.{inverter_id}_inverter_operation_mode options
+/// These strings must match EXACTLY what HA expects (case-sensitive, including spaces)
+///
+/// The eco charge/discharge modes run at the power and SOC held by the
+/// eco_mode_power and eco_mode_soc number entities, so those are set first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[repr(i32)]
+pub enum GoodWeOperationMode {
+    #[serde(rename = "General mode")]
+    General = 0,
+
+    #[serde(rename = "Off grid mode")]
+    OffGrid = 1,
+
+    #[serde(rename = "Backup mode")]
+    Backup = 2,
+
+    #[serde(rename = "Eco mode")]
+    Eco = 3,
+
+    #[serde(rename = "Peak shaving mode")]
+    PeakShaving = 4,
+
+    #[serde(rename = "Eco charge mode")]
+    EcoCharge = 5,
+
+    #[serde(rename = "Eco discharge mode")]
+    EcoDischarge = 6,
+}
+
+impl GoodWeOperationMode {
+    /// Try to create from i32 discriminant
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::General),
+            1 => Some(Self::OffGrid),
+            2 => Some(Self::Backup),
+            3 => Some(Self::Eco),
+            4 => Some(Self::PeakShaving),
+            5 => Some(Self::EcoCharge),
+            6 => Some(Self::EcoDischarge),
+            _ => None,
+        }
+    }
+
+    /// Convert to i32 discriminant
+    pub fn to_i32(self) -> i32 {
+        self as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operation_mode_serde() {
+        let json = serde_json::to_string(&GoodWeOperationMode::EcoCharge).unwrap();
+        assert_eq!(json, "\"Eco charge mode\"");
+
+        let mode: GoodWeOperationMode = serde_json::from_str("\"General mode\"").unwrap();
+        assert_eq!(mode, GoodWeOperationMode::General);
+
+        for value in 0..7 {
+            let mode = GoodWeOperationMode::from_i32(value).unwrap();
+            assert_eq!(mode.to_i32(), value);
+        }
+        assert_eq!(GoodWeOperationMode::from_i32(7), None);
+    }
+}
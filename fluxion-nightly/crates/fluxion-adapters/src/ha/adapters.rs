@@ -19,22 +19,31 @@ use tracing::{debug, info, warn};
 
 use crate::ha::client::HomeAssistantClient;
 use fluxion_core::pricing::parse_spot_price_response;
+use fluxion_core::setup_defaults::DetectedHardware;
 use fluxion_core::{
-    GenericInverterState, InverterCommand, InverterDataSource, InverterOperationMode,
-    PriceDataSource, SpotPriceData, VendorEntityMapper,
+    EntityChange, ExecutionBackend, GenericInverterState, InverterCommand, InverterDataSource,
+    InverterOperationMode, PriceDataSource, SpotPriceData, VendorEntityMapper,
 };
 
+/// Days of PV power history scanned to estimate the array size
+const PV_PEAK_HISTORY_DAYS: i64 = 7;
+
 /// Home Assistant adapter implementing InverterDataSource
 /// Uses VendorEntityMapper to map between generic and vendor-specific entities
 pub struct HomeAssistantInverterAdapter {
     client: Arc<HomeAssistantClient>,
     mapper: Arc<dyn VendorEntityMapper>,
+    execution: HomeAssistantExecutionBackend,
 }
 
 impl HomeAssistantInverterAdapter {
     /// Create a new HA inverter adapter
     pub fn new(client: Arc<HomeAssistantClient>, mapper: Arc<dyn VendorEntityMapper>) -> Self {
-        Self { client, mapper }
+        Self {
+            execution: HomeAssistantExecutionBackend::new(client.clone(), mapper.clone()),
+            client,
+            mapper,
+        }
     }
 
     /// Get reference to the underlying HA client (for history queries, etc.)
@@ -53,6 +62,85 @@ impl HomeAssistantInverterAdapter {
         self.read_sensor_float(&entity_id).await
     }
 
+    /// Read the hardware values used to propose cold-start control defaults
+    ///
+    /// Every value is optional: entities that are missing, unavailable or in
+    /// an unexpected unit are skipped. The PV array size is estimated from the
+    /// highest PV power recorded in the last [`PV_PEAK_HISTORY_DAYS`] days.
+    pub async fn detect_hardware(&self, inverter_id: &str) -> DetectedHardware {
+        let battery_capacity_kwh = match self.mapper.get_battery_capacity_entity(inverter_id) {
+            Some(entity) => self.read_energy_kwh(&entity).await,
+            None => None,
+        };
+
+        let bms_charge_current_a = self
+            .read_optional_sensor(inverter_id, |id| {
+                self.mapper.get_bms_charge_max_current_entity(id)
+            })
+            .await;
+        let battery_voltage_v = self
+            .read_optional_sensor(inverter_id, |id| self.mapper.get_battery_voltage_entity(id))
+            .await;
+        let max_charge_power_kw = bms_charge_current_a
+            .zip(battery_voltage_v)
+            .map(|(amps, volts)| amps * volts / 1000.0)
+            .filter(|kw| *kw > 0.0);
+
+        let export_limit_w = self
+            .read_sensor_float(&self.mapper.get_export_limit_entity(inverter_id))
+            .await
+            .ok()
+            .filter(|w| *w > 0.0)
+            .map(|w| w.round() as u32);
+
+        let pv_entity = self.mapper.get_pv_power_entity(inverter_id);
+        let history_start = chrono::Utc::now() - chrono::Duration::days(PV_PEAK_HISTORY_DAYS);
+        let pv_peak_kw = match self
+            .client
+            .get_history(&pv_entity, history_start, None)
+            .await
+        {
+            Ok(points) => points
+                .iter()
+                .map(|p| p.value)
+                .reduce(f32::max)
+                .filter(|w| *w > 0.0)
+                .map(|w| w / 1000.0),
+            Err(e) => {
+                warn!("⚠️ [ADAPTER] Could not read PV history from {pv_entity}: {e}");
+                None
+            }
+        };
+
+        let detected = DetectedHardware {
+            battery_capacity_kwh,
+            max_charge_power_kw,
+            pv_peak_kw,
+            export_limit_w,
+        };
+        info!("🔍 [ADAPTER] Detected hardware for {inverter_id}: {detected:?}");
+        detected
+    }
+
+    /// Read an energy sensor as kWh, converting from Wh; other units are rejected
+    async fn read_energy_kwh(&self, entity_id: &str) -> Option<f32> {
+        let state = self.client.get_state(entity_id).await.ok()?;
+        let value = state.state.parse::<f32>().ok()?;
+        let unit = state
+            .attributes
+            .get("unit_of_measurement")
+            .and_then(|u| u.as_str())
+            .unwrap_or_default();
+        match unit {
+            "kWh" => Some(value),
+            "Wh" => Some(value / 1000.0),
+            _ => {
+                debug!("[ADAPTER] Ignoring {entity_id}: unit '{unit}' is not an energy unit");
+                None
+            }
+        }
+    }
+
     /// Helper to read a sensor entity value as f32
     async fn read_sensor_float(&self, entity_id: &str) -> Result<f32> {
         debug!("📊 [ADAPTER] Reading float sensor: {}", entity_id);
@@ -141,13 +229,19 @@ impl InverterDataSource for HomeAssistantInverterAdapter {
             .read_sensor_float(&self.mapper.get_battery_soc_entity(inverter_id))
             .await?;
 
-        let grid_power = self
+        let mut grid_power = self
             .read_sensor_float(&self.mapper.get_grid_power_entity(inverter_id))
             .await?;
+        if !self.mapper.grid_power_export_positive() {
+            grid_power = -grid_power;
+        }
 
-        let battery_power = self
+        let mut battery_power = self
             .read_sensor_float(&self.mapper.get_battery_power_entity(inverter_id))
             .await?;
+        if !self.mapper.battery_power_charge_positive() {
+            battery_power = -battery_power;
+        }
 
         let pv_power = self
             .read_sensor_float(&self.mapper.get_pv_power_entity(inverter_id))
@@ -282,60 +376,7 @@ impl InverterDataSource for HomeAssistantInverterAdapter {
 
         match command {
             InverterCommand::SetMode(mode) => {
-                // Get all entity changes needed from vendor mapper
-                let mode_change = self.mapper.get_mode_change_request(inverter_id, *mode);
-
-                if mode_change.entity_changes.is_empty() {
-                    warn!(
-                        "No entity changes defined for mode {:?} by {:?}",
-                        mode,
-                        self.mapper.vendor_name()
-                    );
-                    return Ok(());
-                }
-
-                debug!(
-                    "   Executing {} entity change(s)",
-                    mode_change.entity_changes.len()
-                );
-
-                // Execute entity changes in sequence
-                for (idx, change) in mode_change.entity_changes.iter().enumerate() {
-                    debug!(
-                        "   Step {}/{}: {} = '{}'",
-                        idx + 1,
-                        mode_change.entity_changes.len(),
-                        change.entity_id,
-                        change.option
-                    );
-
-                    self.client
-                        .call_service(
-                            "select.select_option",
-                            serde_json::json!({
-                                "entity_id": change.entity_id,
-                                "option": change.option,
-                            }),
-                        )
-                        .await
-                        .with_context(|| {
-                            format!(
-                                "Failed to set {} to '{}' for mode {:?}",
-                                change.entity_id, change.option, mode
-                            )
-                        })?;
-
-                    info!(
-                        "✅ [ADAPTER] Set {} = '{}'",
-                        change.entity_id, change.option
-                    );
-                }
-
-                info!(
-                    "✅ [ADAPTER] Mode change complete: {:?} ({} entities changed)",
-                    mode,
-                    mode_change.entity_changes.len()
-                );
+                self.execution.apply_mode(inverter_id, *mode).await?;
             }
             InverterCommand::SetExportLimit(limit_w) => {
                 let entity_id = self.mapper.get_export_limit_entity(inverter_id);
@@ -361,6 +402,55 @@ impl InverterDataSource for HomeAssistantInverterAdapter {
 
                 info!("✅ [ADAPTER] Export limit set successfully");
             }
+            InverterCommand::SetChargePowerLimit(limit_w) => {
+                let Some(entity_id) = self.mapper.get_charge_current_limit_entity(inverter_id)
+                else {
+                    warn!(
+                        "⚠️ [ADAPTER] {} has no charge current control, ignoring {}W charge limit",
+                        inverter_id, limit_w
+                    );
+                    return Ok(());
+                };
+
+                // The inverter limits charge current, so convert at the present battery voltage
+                let battery_voltage_v = self
+                    .read_optional_sensor(inverter_id, |id| {
+                        self.mapper.get_battery_voltage_entity(id)
+                    })
+                    .await
+                    .filter(|v| *v > 0.0)
+                    .with_context(|| {
+                        format!(
+                            "No battery voltage for {} to convert a {}W charge limit",
+                            inverter_id, limit_w
+                        )
+                    })?;
+                let current_a = (*limit_w as f32 / battery_voltage_v * 10.0).round() / 10.0;
+
+                debug!("   Entity: {}", entity_id);
+                debug!(
+                    "   Limit: {}W = {}A at {:.1}V",
+                    limit_w, current_a, battery_voltage_v
+                );
+
+                self.client
+                    .call_service(
+                        "number.set_value",
+                        serde_json::json!({
+                            "entity_id": entity_id,
+                            "value": current_a,
+                        }),
+                    )
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to set charge current limit to {}A for {}",
+                            current_a, inverter_id
+                        )
+                    })?;
+
+                info!("✅ [ADAPTER] Charge power limit set to {}W", limit_w);
+            }
         }
         Ok(())
     }
@@ -373,6 +463,123 @@ impl InverterDataSource for HomeAssistantInverterAdapter {
     fn name(&self) -> &str {
         "HomeAssistant"
     }
+
+    fn supports_charge_power_limit(&self, inverter_id: &str) -> bool {
+        self.mapper
+            .get_charge_current_limit_entity(inverter_id)
+            .is_some()
+    }
+}
+
+/// Execution backend writing mode changes as Home Assistant entity changes
+pub struct HomeAssistantExecutionBackend {
+    client: Arc<HomeAssistantClient>,
+    mapper: Arc<dyn VendorEntityMapper>,
+}
+
+impl HomeAssistantExecutionBackend {
+    /// Create a backend applying `mapper`'s entity changes through `client`
+    pub fn new(client: Arc<HomeAssistantClient>, mapper: Arc<dyn VendorEntityMapper>) -> Self {
+        Self { client, mapper }
+    }
+}
+
+#[async_trait]
+impl ExecutionBackend for HomeAssistantExecutionBackend {
+    async fn apply_mode(&self, inverter_id: &str, mode: InverterOperationMode) -> Result<()> {
+        // Get all entity changes needed from vendor mapper
+        let mode_change = self.mapper.get_mode_change_request(inverter_id, mode);
+
+        if mode_change.entity_changes.is_empty() {
+            warn!(
+                "No entity changes defined for mode {:?} by {:?}",
+                mode,
+                self.mapper.vendor_name()
+            );
+            return Ok(());
+        }
+
+        debug!(
+            "   Executing {} entity change(s)",
+            mode_change.entity_changes.len()
+        );
+
+        // Execute entity changes in sequence
+        for (idx, change) in mode_change.entity_changes.iter().enumerate() {
+            debug!(
+                "   Step {}/{}: {} = '{}'",
+                idx + 1,
+                mode_change.entity_changes.len(),
+                change.entity_id,
+                change.option
+            );
+
+            let (service, data) = entity_change_service(change)?;
+            self.client
+                .call_service(service, data)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Failed to set {} to '{}' for mode {:?}",
+                        change.entity_id, change.option, mode
+                    )
+                })?;
+
+            info!(
+                "✅ [ADAPTER] Set {} = '{}'",
+                change.entity_id, change.option
+            );
+        }
+
+        info!(
+            "✅ [ADAPTER] Mode change complete: {:?} ({} entities changed)",
+            mode,
+            mode_change.entity_changes.len()
+        );
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "Home Assistant"
+    }
+}
+
+/// HA service call that applies a mode change step, chosen by the entity's domain
+fn entity_change_service(change: &EntityChange) -> Result<(&'static str, serde_json::Value)> {
+    let domain = change.entity_id.split('.').next().unwrap_or_default();
+    match domain {
+        "select" => Ok((
+            "select.select_option",
+            serde_json::json!({ "entity_id": change.entity_id, "option": change.option }),
+        )),
+        "number" => {
+            let value: f64 = change.option.parse().with_context(|| {
+                format!(
+                    "Invalid number '{}' for {}",
+                    change.option, change.entity_id
+                )
+            })?;
+            Ok((
+                "number.set_value",
+                serde_json::json!({ "entity_id": change.entity_id, "value": value }),
+            ))
+        }
+        "switch" => match change.option.as_str() {
+            "on" => Ok((
+                "switch.turn_on",
+                serde_json::json!({ "entity_id": change.entity_id }),
+            )),
+            "off" => Ok((
+                "switch.turn_off",
+                serde_json::json!({ "entity_id": change.entity_id }),
+            )),
+            other => anyhow::bail!("Invalid switch state '{}' for {}", other, change.entity_id),
+        },
+        _ => anyhow::bail!(
+            "Unsupported entity domain for mode change: {}",
+            change.entity_id
+        ),
+    }
 }
 
 /// Czech spot price adapter implementing PriceDataSource
@@ -431,6 +638,12 @@ impl CzSpotPriceAdapter {
         self.timezone.clone()
     }
 
+    /// Parse prices in the timezone of another adapter (see [`Self::timezone_handle`])
+    pub fn with_timezone_handle(mut self, timezone: Arc<RwLock<Option<Tz>>>) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
     /// Get the current timezone (for internal use)
     fn get_timezone(&self) -> Option<Tz> {
         *self.timezone.read()
@@ -597,18 +810,19 @@ impl CzSpotPriceAdapter {
 /// Configurable price data source that switches between spot and fixed prices
 /// based on configuration flags (use_spot_prices_to_buy/sell)
 pub struct ConfigurablePriceDataSource {
-    spot_adapter: Arc<CzSpotPriceAdapter>,
+    spot_adapter: Arc<dyn PriceDataSource>,
     use_spot_for_buy: bool,
     use_spot_for_sell: bool,
     fixed_buy_prices: Vec<f32>,
     #[expect(dead_code, reason = "Reserved for fixed sell price schedule support")]
     fixed_sell_prices: Vec<f32>,
     spot_sell_fee_czk: f32,
+    sell_price_source: Option<(Arc<dyn PriceDataSource>, f32)>,
 }
 
 impl ConfigurablePriceDataSource {
     pub fn new(
-        spot_adapter: Arc<CzSpotPriceAdapter>,
+        spot_adapter: Arc<dyn PriceDataSource>,
         use_spot_for_buy: bool,
         use_spot_for_sell: bool,
         fixed_buy_prices: Vec<f32>,
@@ -622,7 +836,55 @@ impl ConfigurablePriceDataSource {
             fixed_buy_prices,
             fixed_sell_prices,
             spot_sell_fee_czk,
+            sell_price_source: None,
+        }
+    }
+
+    /// Take export prices from a dedicated source (sell price sensor, OTE)
+    ///
+    /// Each block sells at the source's price for the block minus `margin_czk`.
+    /// Blocks the source has no price for keep the spot-based sell price.
+    pub fn with_sell_price_source(
+        mut self,
+        source: Arc<dyn PriceDataSource>,
+        margin_czk: f32,
+    ) -> Self {
+        self.sell_price_source = Some((source, margin_czk));
+        self
+    }
+
+    /// Overwrite block sell prices from the sell price source, when configured
+    async fn apply_sell_price_source(&self, data: &mut SpotPriceData) {
+        let Some((source, margin_czk)) = &self.sell_price_source else {
+            return;
+        };
+        let sell_data = match source.read_prices().await {
+            Ok(sell_data) => sell_data,
+            Err(e) => {
+                warn!(
+                    "⚠️ [ConfigurablePrice] Failed to read sell prices from {}: {e}",
+                    source.name()
+                );
+                return;
+            }
+        };
+
+        let mut matched = 0;
+        for block in &mut data.time_block_prices {
+            // Sell prices may be hourly while buy prices are per 15 minutes
+            if let Some(sell_block) = sell_data.time_block_prices.iter().find(|sb| {
+                let end =
+                    sb.block_start + chrono::Duration::minutes(i64::from(sb.duration_minutes));
+                sb.block_start <= block.block_start && block.block_start < end
+            }) {
+                block.spot_sell_price_czk_per_kwh = Some(sell_block.price_czk_per_kwh - margin_czk);
+                matched += 1;
+            }
         }
+        debug!(
+            "💰 [ConfigurablePrice] Sell prices from {} on {matched} blocks (margin: {margin_czk:.2} CZK/kWh)",
+            source.name()
+        );
     }
 
     /// Generate SpotPriceData from fixed hourly prices
@@ -709,6 +971,7 @@ impl PriceDataSource for ConfigurablePriceDataSource {
                 );
             }
 
+            self.apply_sell_price_source(&mut data).await;
             Ok(data)
         } else {
             // Use fixed prices for buying
@@ -747,6 +1010,7 @@ impl PriceDataSource for ConfigurablePriceDataSource {
                 }
             }
 
+            self.apply_sell_price_source(&mut data).await;
             Ok(data)
         }
     }
@@ -816,6 +1080,101 @@ impl fluxion_core::traits::ConsumptionHistoryDataSource for HaConsumptionHistory
     }
 }
 
+/// Sends alerts as Home Assistant persistent notifications
+pub struct HaAlertNotifier {
+    client: Arc<HomeAssistantClient>,
+}
+
+impl HaAlertNotifier {
+    pub fn new(client: Arc<HomeAssistantClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl fluxion_core::alerts::AlertNotifier for HaAlertNotifier {
+    async fn notify(&self, notification_id: &str, title: &str, message: &str) -> Result<()> {
+        self.client
+            .call_service(
+                "persistent_notification.create",
+                serde_json::json!({
+                    "notification_id": notification_id,
+                    "title": title,
+                    "message": message,
+                }),
+            )
+            .await
+            .context("Failed to create HA persistent notification")
+    }
+}
+
+/// Sets the target temperature of a `water_heater` or `climate` entity
+pub struct HaDhwController {
+    client: Arc<HomeAssistantClient>,
+}
+
+impl HaDhwController {
+    pub fn new(client: Arc<HomeAssistantClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl fluxion_core::dhw::DhwController for HaDhwController {
+    async fn set_target_temperature(&self, entity_id: &str, temp_c: f32) -> Result<()> {
+        // Both domains offer set_temperature with the same data
+        let domain = entity_id.split('.').next().unwrap_or("water_heater");
+        self.client
+            .call_service(
+                &format!("{domain}.set_temperature"),
+                serde_json::json!({
+                    "entity_id": entity_id,
+                    "temperature": temp_c,
+                }),
+            )
+            .await
+            .with_context(|| format!("Failed to set target temperature of {entity_id}"))
+    }
+}
+
+/// Reads appliance power sensors for sub-metering
+pub struct HaPowerSensorReader {
+    client: Arc<HomeAssistantClient>,
+}
+
+impl HaPowerSensorReader {
+    pub fn new(client: Arc<HomeAssistantClient>) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl fluxion_core::submeter::PowerSensorReader for HaPowerSensorReader {
+    async fn read_power_w(&self, entity_id: &str) -> Result<f32> {
+        let state = self
+            .client
+            .get_state(entity_id)
+            .await
+            .with_context(|| format!("Failed to read entity: {entity_id}"))?;
+        let value = state.state.parse::<f32>().with_context(|| {
+            format!(
+                "Failed to parse '{}' as power from {entity_id}",
+                state.state
+            )
+        })?;
+        let unit = state
+            .attributes
+            .get("unit_of_measurement")
+            .and_then(|u| u.as_str())
+            .unwrap_or("W");
+        match unit {
+            "W" => Ok(value),
+            "kW" => Ok(value * 1000.0),
+            _ => anyhow::bail!("{entity_id} reports '{unit}', expected W or kW"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -895,6 +1254,79 @@ mod tests {
         assert_eq!(adapter.name(), "HomeAssistant");
     }
 
+    struct StaticPrices {
+        blocks: Vec<(i64, u32, f32)>,
+    }
+
+    #[async_trait]
+    impl PriceDataSource for StaticPrices {
+        async fn read_prices(&self) -> Result<SpotPriceData> {
+            anyhow::ensure!(!self.blocks.is_empty(), "no prices");
+            let start = chrono::DateTime::from_timestamp(1_767_225_600, 0).unwrap();
+            Ok(SpotPriceData {
+                time_block_prices: self
+                    .blocks
+                    .iter()
+                    .map(
+                        |&(minute, duration_minutes, price)| fluxion_core::TimeBlockPrice {
+                            block_start: start + chrono::Duration::minutes(minute),
+                            duration_minutes,
+                            price_czk_per_kwh: price,
+                            effective_price_czk_per_kwh: price,
+                            spot_sell_price_czk_per_kwh: None,
+                        },
+                    )
+                    .collect(),
+                block_duration_minutes: 15,
+                fetched_at: start,
+                ha_last_updated: start,
+            })
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "static"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sell_price_source_overrides_spot_sell_prices() {
+        let spot = Arc::new(StaticPrices {
+            blocks: vec![(0, 15, 3.0), (15, 15, 3.2), (60, 15, 4.0)],
+        });
+        let source = |sell: StaticPrices| {
+            ConfigurablePriceDataSource::new(spot.clone(), true, true, vec![], vec![], 0.5)
+                .with_sell_price_source(Arc::new(sell), 0.3)
+        };
+
+        // An hourly sell price covers every 15-minute block of its hour
+        let data = source(StaticPrices {
+            blocks: vec![(0, 60, 2.0)],
+        })
+        .read_prices()
+        .await
+        .unwrap();
+        let sell: Vec<_> = data
+            .time_block_prices
+            .iter()
+            .map(|b| b.spot_sell_price_czk_per_kwh)
+            .collect();
+        assert_eq!(sell, vec![Some(1.7), Some(1.7), Some(3.5)]);
+
+        // Without sell prices the spot-based sell price stays
+        let data = source(StaticPrices { blocks: vec![] })
+            .read_prices()
+            .await
+            .unwrap();
+        assert_eq!(
+            data.time_block_prices[0].spot_sell_price_czk_per_kwh,
+            Some(2.5)
+        );
+    }
+
     #[test]
     fn test_cz_spot_price_adapter_creation() {
         let client =
@@ -904,4 +1336,84 @@ mod tests {
         assert_eq!(adapter.name(), "CzSpotPrice");
         assert_eq!(adapter.entity_id, "sensor.current_spot_electricity_prices");
     }
+
+    #[tokio::test]
+    async fn test_alert_notifier_creates_persistent_notification() {
+        use fluxion_core::alerts::AlertNotifier;
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/services/persistent_notification/create")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "notification_id": "fluxion_alert_a1",
+                "title": "Negative prices",
+                "message": "Negative price -0.50 CZK/kWh"
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = Arc::new(HomeAssistantClient::new(server.url(), "test_token").unwrap());
+        HaAlertNotifier::new(client)
+            .notify(
+                "fluxion_alert_a1",
+                "Negative prices",
+                "Negative price -0.50 CZK/kWh",
+            )
+            .await
+            .unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_dhw_controller_calls_domain_service() {
+        use fluxion_core::dhw::DhwController;
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/services/climate/set_temperature")
+            .match_body(mockito::Matcher::Json(serde_json::json!({
+                "entity_id": "climate.boiler",
+                "temperature": 55.0
+            })))
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = Arc::new(HomeAssistantClient::new(server.url(), "test_token").unwrap());
+        HaDhwController::new(client)
+            .set_target_temperature("climate.boiler", 55.0)
+            .await
+            .unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_power_sensor_reader_converts_kw() {
+        use fluxion_core::submeter::PowerSensorReader;
+
+        let mut server = mockito::Server::new_async().await;
+        let _mock = server
+            .mock("GET", "/api/states/sensor.heat_pump_power")
+            .with_status(200)
+            .with_body(
+                serde_json::json!({
+                    "entity_id": "sensor.heat_pump_power",
+                    "state": "1.5",
+                    "attributes": { "unit_of_measurement": "kW" },
+                    "last_changed": "2025-06-01T10:00:00+00:00",
+                    "last_updated": "2025-06-01T10:00:00+00:00"
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+
+        let client = Arc::new(HomeAssistantClient::new(server.url(), "test_token").unwrap());
+        let power = HaPowerSensorReader::new(client)
+            .read_power_w("sensor.heat_pump_power")
+            .await
+            .unwrap();
+        assert_eq!(power, 1500.0);
+    }
 }
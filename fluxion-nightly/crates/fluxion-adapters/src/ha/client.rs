@@ -13,6 +13,7 @@
 use crate::ha::errors::{HaError, HaResult};
 use crate::ha::types::{HaEntityState, HaHistoryState, HistoryDataPoint};
 use chrono::{DateTime, Utc};
+use fluxion_core::metrics::Metrics;
 use reqwest::{Client, StatusCode};
 use serde_json::Value;
 use std::time::Duration;
@@ -231,6 +232,54 @@ impl HomeAssistantClient {
         }
     }
 
+    /// Call a Home Assistant service that returns data, e.g. `weather.get_forecasts`
+    ///
+    /// Returns the `service_response` part of the answer.
+    pub async fn call_service_with_response(&self, service: &str, data: Value) -> HaResult<Value> {
+        let Some((domain, name)) = service.split_once('.') else {
+            return Err(HaError::ServiceCallFailed {
+                service: service.to_string(),
+                reason: "Invalid service format, expected 'domain.service'".to_string(),
+            });
+        };
+
+        let url = format!(
+            "{}/api/services/{}/{}?return_response",
+            self.base_url, domain, name
+        );
+        debug!("📞 [HA SERVICE] Calling with response: {}", service);
+
+        let response = self
+            .retry_request(|| async {
+                self.client
+                    .post(&url)
+                    .bearer_auth(&self.token)
+                    .json(&data)
+                    .send()
+                    .await
+            })
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => {
+                let mut body = response.json::<Value>().await?;
+                Ok(body
+                    .get_mut("service_response")
+                    .map(Value::take)
+                    .unwrap_or(Value::Null))
+            }
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(HaError::AuthenticationFailed),
+            status => Err(HaError::ServiceCallFailed {
+                service: service.to_string(),
+                reason: format!(
+                    "status {}: {}",
+                    status,
+                    response.text().await.unwrap_or_default()
+                ),
+            }),
+        }
+    }
+
     /// Health check - ping HA API
     pub async fn ping(&self) -> HaResult<bool> {
         let url = format!("{}/api/", self.base_url);
@@ -417,8 +466,14 @@ impl HomeAssistantClient {
         loop {
             attempts += 1;
             match request_fn().await {
-                Ok(response) => return Ok(response),
+                Ok(response) => {
+                    if !response.status().is_success() {
+                        Metrics::global().record_ha_query_error();
+                    }
+                    return Ok(response);
+                }
                 Err(e) if attempts >= self.max_retries => {
+                    Metrics::global().record_ha_query_error();
                     error!("Request failed after {} attempts: {}", attempts, e);
                     return Err(HaError::HttpError(e));
                 }
@@ -0,0 +1,378 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Checks a [`VendorEntityMapper`] against a snapshot of HA entity states.
+//!
+//! The same checks back the recorded-fixture tests in
+//! `tests/entity_mapper_fixtures.rs` and the live `/api/mapping/validate`
+//! endpoint, so a mapping that passes in CI is checked the same way on a
+//! user's installation.
+
+use super::client::HomeAssistantClient;
+use super::types::HaEntityState;
+use anyhow::Result;
+use async_trait::async_trait;
+use fluxion_core::mapping_check::{
+    EntityCheck, EntityCheckStatus, MappingReport, MappingValidator,
+};
+use fluxion_core::{InverterOperationMode, VendorEntityMapper};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+const ALL_MODES: [InverterOperationMode; 5] = [
+    InverterOperationMode::SelfUse,
+    InverterOperationMode::ForceCharge,
+    InverterOperationMode::ForceDischarge,
+    InverterOperationMode::BackUpMode,
+    InverterOperationMode::NoChargeNoDischarge,
+];
+
+type OptionalGetter = fn(&dyn VendorEntityMapper, &str) -> Option<String>;
+
+/// Optional sensors read by the inverter adapter and hardware detection
+const OPTIONAL_SENSORS: [(&str, OptionalGetter); 17] = [
+    ("house_load", |m, id| m.get_house_load_entity(id)),
+    ("grid_import_power", |m, id| {
+        m.get_grid_import_power_entity(id)
+    }),
+    ("grid_export_power", |m, id| {
+        m.get_grid_export_power_entity(id)
+    }),
+    ("grid_import_today", |m, id| {
+        m.get_grid_import_today_entity(id)
+    }),
+    ("grid_export_today", |m, id| {
+        m.get_grid_export_today_entity(id)
+    }),
+    ("inverter_frequency", |m, id| {
+        m.get_inverter_frequency_entity(id)
+    }),
+    ("inverter_voltage", |m, id| {
+        m.get_inverter_voltage_entity(id)
+    }),
+    ("inverter_current", |m, id| {
+        m.get_inverter_current_entity(id)
+    }),
+    ("inverter_power", |m, id| m.get_inverter_power_entity(id)),
+    ("battery_capacity", |m, id| {
+        m.get_battery_capacity_entity(id)
+    }),
+    ("battery_input_energy_today", |m, id| {
+        m.get_battery_input_energy_today_entity(id)
+    }),
+    ("battery_output_energy_today", |m, id| {
+        m.get_battery_output_energy_today_entity(id)
+    }),
+    ("today_solar_energy", |m, id| {
+        m.get_today_solar_energy_entity(id)
+    }),
+    ("total_solar_energy", |m, id| {
+        m.get_total_solar_energy_entity(id)
+    }),
+    ("battery_voltage", |m, id| m.get_battery_voltage_entity(id)),
+    ("bms_charge_max_current", |m, id| {
+        m.get_bms_charge_max_current_entity(id)
+    }),
+    ("pv1_power", |m, id| m.get_pv1_power_entity(id)),
+];
+
+/// Check every entity the mapper resolves for `inverter_id` against `states`
+pub fn check_entity_mapping(
+    mapper: &dyn VendorEntityMapper,
+    inverter_id: &str,
+    states: &[HaEntityState],
+) -> MappingReport {
+    let states: HashMap<&str, &HaEntityState> =
+        states.iter().map(|s| (s.entity_id.as_str(), s)).collect();
+    let mut checks = Vec::new();
+
+    checks.push(check_numeric(
+        &states,
+        "battery_soc",
+        &mapper.get_battery_soc_entity(inverter_id),
+        true,
+        Some((0.0, 100.0)),
+    ));
+    for (name, entity_id) in [
+        ("grid_power", mapper.get_grid_power_entity(inverter_id)),
+        (
+            "battery_power",
+            mapper.get_battery_power_entity(inverter_id),
+        ),
+        ("pv_power", mapper.get_pv_power_entity(inverter_id)),
+        ("export_limit", mapper.get_export_limit_entity(inverter_id)),
+    ] {
+        checks.push(check_numeric(&states, name, &entity_id, true, None));
+    }
+
+    checks.push(check_work_mode(
+        mapper,
+        &states,
+        &mapper.get_work_mode_entity(inverter_id),
+    ));
+
+    let mut seen = HashSet::new();
+    for mode in ALL_MODES {
+        for change in mapper
+            .get_mode_change_request(inverter_id, mode)
+            .entity_changes
+        {
+            if seen.insert((change.entity_id.clone(), change.option.clone())) {
+                checks.push(check_option(
+                    &states,
+                    &format!("mode_change:{mode:?}"),
+                    &change.entity_id,
+                    &change.option,
+                ));
+            }
+        }
+    }
+
+    for (name, getter) in OPTIONAL_SENSORS {
+        if let Some(entity_id) = getter(mapper, inverter_id) {
+            checks.push(check_numeric(&states, name, &entity_id, false, None));
+        }
+    }
+
+    MappingReport {
+        inverter_id: inverter_id.to_owned(),
+        vendor: mapper.vendor_name().display_name().to_owned(),
+        checks,
+    }
+}
+
+fn entity_check(
+    name: &str,
+    entity_id: &str,
+    required: bool,
+    status: EntityCheckStatus,
+    detail: Option<String>,
+) -> EntityCheck {
+    EntityCheck {
+        name: name.to_owned(),
+        entity_id: entity_id.to_owned(),
+        required,
+        status,
+        detail,
+    }
+}
+
+/// Look up an entity that reports a usable state
+fn available<'a>(
+    states: &HashMap<&str, &'a HaEntityState>,
+    entity_id: &str,
+) -> Result<&'a HaEntityState, EntityCheckStatus> {
+    let state = states
+        .get(entity_id)
+        .copied()
+        .ok_or(EntityCheckStatus::Missing)?;
+    if matches!(state.state.as_str(), "unavailable" | "unknown") {
+        return Err(EntityCheckStatus::Unavailable);
+    }
+    Ok(state)
+}
+
+fn check_numeric(
+    states: &HashMap<&str, &HaEntityState>,
+    name: &str,
+    entity_id: &str,
+    required: bool,
+    range: Option<(f32, f32)>,
+) -> EntityCheck {
+    let state = match available(states, entity_id) {
+        Ok(state) => state,
+        Err(status) => return entity_check(name, entity_id, required, status, None),
+    };
+    let detail = Some(state.state.clone());
+    let status = match state.state.parse::<f32>() {
+        Err(_) => EntityCheckStatus::NotNumeric,
+        Ok(value) if range.is_some_and(|(min, max)| !(min..=max).contains(&value)) => {
+            EntityCheckStatus::OutOfRange
+        }
+        Ok(_) => EntityCheckStatus::Ok,
+    };
+    entity_check(name, entity_id, required, status, detail)
+}
+
+/// The work mode must translate to a generic mode the same way the adapter reads it
+fn check_work_mode(
+    mapper: &dyn VendorEntityMapper,
+    states: &HashMap<&str, &HaEntityState>,
+    entity_id: &str,
+) -> EntityCheck {
+    let state = match available(states, entity_id) {
+        Ok(state) => state,
+        Err(status) => return entity_check("work_mode", entity_id, true, status, None),
+    };
+    let from_options = select_options(state)
+        .iter()
+        .position(|option| option == &state.state)
+        .and_then(|idx| mapper.map_mode_from_vendor(idx as i32));
+    let mode = from_options.or_else(|| {
+        state
+            .state
+            .parse()
+            .ok()
+            .and_then(|n| mapper.map_mode_from_vendor(n))
+    });
+
+    match mode {
+        Some(mode) => entity_check(
+            "work_mode",
+            entity_id,
+            true,
+            EntityCheckStatus::Ok,
+            Some(format!("{} → {mode:?}", state.state)),
+        ),
+        None => entity_check(
+            "work_mode",
+            entity_id,
+            true,
+            EntityCheckStatus::UnmappedMode,
+            Some(state.state.clone()),
+        ),
+    }
+}
+
+/// A mode change option must be one the select entity offers; other steps need the entity to exist
+fn check_option(
+    states: &HashMap<&str, &HaEntityState>,
+    name: &str,
+    entity_id: &str,
+    option: &str,
+) -> EntityCheck {
+    // Mode changes are still possible while the select is briefly unavailable
+    let Some(state) = states.get(entity_id) else {
+        return entity_check(name, entity_id, true, EntityCheckStatus::Missing, None);
+    };
+    // Only selects offer a fixed option list; number and switch steps just need the entity
+    let status =
+        if !entity_id.starts_with("select.") || select_options(state).iter().any(|o| o == option) {
+            EntityCheckStatus::Ok
+        } else {
+            EntityCheckStatus::InvalidOption
+        };
+    entity_check(name, entity_id, true, status, Some(option.to_owned()))
+}
+
+fn select_options(state: &HaEntityState) -> Vec<String> {
+    state
+        .attributes
+        .get("options")
+        .and_then(|v| v.as_array())
+        .map(|options| {
+            options
+                .iter()
+                .filter_map(|o| o.as_str().map(str::to_owned))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Runs [`check_entity_mapping`] against the live Home Assistant states
+pub struct HaMappingValidator {
+    client: Arc<HomeAssistantClient>,
+    mapper: Arc<dyn VendorEntityMapper>,
+    inverter_ids: Vec<String>,
+}
+
+impl HaMappingValidator {
+    pub fn new(
+        client: Arc<HomeAssistantClient>,
+        mapper: Arc<dyn VendorEntityMapper>,
+        inverter_ids: Vec<String>,
+    ) -> Self {
+        Self {
+            client,
+            mapper,
+            inverter_ids,
+        }
+    }
+}
+
+#[async_trait]
+impl MappingValidator for HaMappingValidator {
+    async fn validate(&self) -> Result<Vec<MappingReport>> {
+        let states = self.client.get_all_states().await?;
+        Ok(self
+            .inverter_ids
+            .iter()
+            .map(|id| check_entity_mapping(self.mapper.as_ref(), id, &states))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solax::SolaxEntityMapper;
+    use serde_json::json;
+
+    fn state(entity_id: &str, value: &str, attributes: serde_json::Value) -> HaEntityState {
+        HaEntityState {
+            entity_id: entity_id.to_owned(),
+            state: value.to_owned(),
+            attributes,
+            last_changed: String::new(),
+            last_updated: String::new(),
+        }
+    }
+
+    fn status_of(report: &MappingReport, name: &str) -> EntityCheckStatus {
+        report
+            .checks
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| c.status)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reports_missing_and_broken_required_entities() {
+        let states = vec![
+            state("sensor.solax_battery_capacity", "104", json!({})),
+            state("sensor.solax_grid_import", "unavailable", json!({})),
+            state("sensor.solax_battery_power_charge", "n/a", json!({})),
+            state(
+                "select.solax_charger_use_mode",
+                "Feedin Priority",
+                json!({"options": ["Self Use Mode", "Feedin Priority", "Back Up Mode", "Manual Mode"]}),
+            ),
+        ];
+
+        let report = check_entity_mapping(&SolaxEntityMapper::new(), "solax", &states);
+
+        assert!(!report.is_valid());
+        assert_eq!(
+            status_of(&report, "battery_soc"),
+            EntityCheckStatus::OutOfRange
+        );
+        assert_eq!(
+            status_of(&report, "grid_power"),
+            EntityCheckStatus::Unavailable
+        );
+        assert_eq!(
+            status_of(&report, "battery_power"),
+            EntityCheckStatus::NotNumeric
+        );
+        assert_eq!(status_of(&report, "pv_power"), EntityCheckStatus::Missing);
+        assert_eq!(
+            status_of(&report, "work_mode"),
+            EntityCheckStatus::UnmappedMode
+        );
+        // Manual modes need the missing manual_mode_select as well
+        assert!(report.failures().any(|c| {
+            c.entity_id == "select.solax_manual_mode_select"
+                && c.status == EntityCheckStatus::Missing
+        }));
+    }
+}
@@ -13,15 +13,18 @@
 pub mod adapters;
 pub mod client;
 pub mod errors;
+pub mod mapping_check;
 pub mod plugin;
 pub mod solar_forecast_fetcher;
 pub mod types;
 
 pub use adapters::{
-    ConfigurablePriceDataSource, CzSpotPriceAdapter, HaConsumptionHistoryAdapter,
+    ConfigurablePriceDataSource, CzSpotPriceAdapter, HaAlertNotifier, HaConsumptionHistoryAdapter,
+    HaDhwController, HaPowerSensorReader, HomeAssistantExecutionBackend,
     HomeAssistantInverterAdapter,
 };
 pub use client::HomeAssistantClient;
 pub use errors::{HaError, HaResult};
+pub use mapping_check::{HaMappingValidator, check_entity_mapping};
 pub use plugin::{HaClientResource, HaPlugin, PriceAdapterTimezoneHandle};
 pub use types::{HaEntityState, HaHistoryState, HistoryDataPoint};
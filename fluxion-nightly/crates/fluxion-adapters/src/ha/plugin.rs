@@ -337,69 +337,86 @@ fn spawn_backup_soc_fetcher(
         entity_prefix
     );
 
-    tokio::spawn(async move {
-        // Create entity ID for the backup_discharge_min_soc sensor
-        let entity_id = format!(
-            "number.{}_backup_discharge_min_soc",
-            entity_prefix.replace(".", "_")
-        );
+    fluxion_core::TaskSupervisor::global().spawn("backup_soc_fetcher", move || {
+        run_backup_soc_fetcher(
+            entity_prefix.clone(),
+            client.clone(),
+            sender.clone(),
+            hardware_min_soc,
+        )
+    });
+}
+
+async fn run_backup_soc_fetcher(
+    entity_prefix: String,
+    client: Arc<HomeAssistantClient>,
+    sender: crossbeam_channel::Sender<f32>,
+    hardware_min_soc: f32,
+) {
+    // Create entity ID for the backup_discharge_min_soc sensor
+    let entity_id = format!(
+        "number.{}_backup_discharge_min_soc",
+        entity_prefix.replace(".", "_")
+    );
+
+    // Fetch immediately on startup
+    tracing::debug!(
+        "📊 Fetching initial backup_discharge_min_soc from HA: {}",
+        entity_id
+    );
+    match client.get_state(&entity_id).await {
+        Ok(state) => {
+            if let Ok(value) = state.state.parse::<f32>() {
+                tracing::info!("✅ Initial backup_discharge_min_soc: {:.1}%", value);
+                let _ = sender.send(value);
+            } else {
+                tracing::warn!(
+                    "⚠️ Failed to parse backup_discharge_min_soc, using hardware min SOC: {:.1}%",
+                    hardware_min_soc
+                );
+                let _ = sender.send(hardware_min_soc);
+            }
+        }
+        Err(e) => {
+            tracing::warn!(
+                "⚠️ Failed to fetch backup_discharge_min_soc from HA: {}, using hardware min SOC: {:.1}%",
+                e,
+                hardware_min_soc
+            );
+            let _ = sender.send(hardware_min_soc);
+        }
+    }
+
+    // Poll every 5 minutes
+    loop {
+        tokio::time::sleep(Duration::from_secs(300)).await;
+        tracing::debug!("📊 Polling backup_discharge_min_soc from HA: {}", entity_id);
 
-        // Fetch immediately on startup
-        tracing::debug!(
-            "📊 Fetching initial backup_discharge_min_soc from HA: {}",
-            entity_id
-        );
         match client.get_state(&entity_id).await {
             Ok(state) => {
                 if let Ok(value) = state.state.parse::<f32>() {
-                    tracing::info!("✅ Initial backup_discharge_min_soc: {:.1}%", value);
+                    tracing::debug!("✅ Updated backup_discharge_min_soc: {:.1}%", value);
                     let _ = sender.send(value);
                 } else {
                     tracing::warn!(
-                        "⚠️ Failed to parse backup_discharge_min_soc, using hardware min SOC: {:.1}%",
-                        hardware_min_soc
+                        "⚠️ Failed to parse backup_discharge_min_soc, keeping previous value"
                     );
-                    let _ = sender.send(hardware_min_soc);
                 }
             }
             Err(e) => {
-                tracing::warn!(
-                    "⚠️ Failed to fetch backup_discharge_min_soc from HA: {}, using hardware min SOC: {:.1}%",
-                    e,
-                    hardware_min_soc
-                );
-                let _ = sender.send(hardware_min_soc);
+                tracing::warn!("⚠️ Failed to poll backup_discharge_min_soc: {}", e);
             }
         }
-
-        // Poll every 5 minutes
-        loop {
-            tokio::time::sleep(Duration::from_secs(300)).await;
-            tracing::debug!("📊 Polling backup_discharge_min_soc from HA: {}", entity_id);
-
-            match client.get_state(&entity_id).await {
-                Ok(state) => {
-                    if let Ok(value) = state.state.parse::<f32>() {
-                        tracing::debug!("✅ Updated backup_discharge_min_soc: {:.1}%", value);
-                        let _ = sender.send(value);
-                    } else {
-                        tracing::warn!(
-                            "⚠️ Failed to parse backup_discharge_min_soc, keeping previous value"
-                        );
-                    }
-                }
-                Err(e) => {
-                    tracing::warn!("⚠️ Failed to poll backup_discharge_min_soc: {}", e);
-                }
-            }
-        }
-    });
+    }
 }
 
 /// Update system: poll backup SOC channel and update resource
+///
+/// A changed value requests a replan.
 fn poll_backup_soc_channel(
     channel_query: Query<&fluxion_core::async_systems::BackupSocChannel>,
     mut backup_soc: Option<ResMut<fluxion_core::async_systems::BackupDischargeMinSoc>>,
+    price_cache: Option<Res<fluxion_core::PriceCache>>,
 ) {
     let Ok(channel) = channel_query.single() else {
         return; // Channel not yet created
@@ -416,6 +433,9 @@ fn poll_backup_soc_channel(
                 value
             );
             resource.value = value;
+            if let Some(cache) = &price_cache {
+                cache.request_replan();
+            }
         }
     }
 }
@@ -460,23 +480,31 @@ pub fn spawn_hdo_fetcher(
         hdo_sensor_entity
     );
 
-    tokio::spawn(async move {
-        // Fetch immediately on startup
-        tracing::debug!(
-            "📊 Fetching initial HDO schedule from HA: {}",
-            hdo_sensor_entity
-        );
-        fetch_and_send_hdo(&client, &hdo_sensor_entity, &sender).await;
-
-        // Poll every 60 minutes (HDO schedules typically update daily)
-        loop {
-            tokio::time::sleep(Duration::from_secs(3600)).await;
-            tracing::debug!("📊 Polling HDO schedule from HA: {}", hdo_sensor_entity);
-            fetch_and_send_hdo(&client, &hdo_sensor_entity, &sender).await;
-        }
+    fluxion_core::TaskSupervisor::global().spawn("hdo_fetcher", move || {
+        run_hdo_fetcher(client.clone(), hdo_sensor_entity.clone(), sender.clone())
     });
 }
 
+async fn run_hdo_fetcher(
+    client: Arc<HomeAssistantClient>,
+    hdo_sensor_entity: String,
+    sender: crossbeam_channel::Sender<fluxion_core::async_systems::HdoUpdateMessage>,
+) {
+    // Fetch immediately on startup
+    tracing::debug!(
+        "📊 Fetching initial HDO schedule from HA: {}",
+        hdo_sensor_entity
+    );
+    fetch_and_send_hdo(&client, &hdo_sensor_entity, &sender).await;
+
+    // Poll every 60 minutes (HDO schedules typically update daily)
+    loop {
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+        tracing::debug!("📊 Polling HDO schedule from HA: {}", hdo_sensor_entity);
+        fetch_and_send_hdo(&client, &hdo_sensor_entity, &sender).await;
+    }
+}
+
 /// Resolve the HDO entity: try exact match first, then prefix search.
 /// This handles the CEZ HDO integration renaming sensors with a suffix.
 async fn resolve_hdo_entity(
@@ -914,4 +942,51 @@ mod tests {
 
         assert!(periods.is_empty(), "Expected no periods for non-HDO sensor");
     }
+
+    struct NoPrices;
+
+    #[async_trait::async_trait]
+    impl fluxion_core::PriceDataSource for NoPrices {
+        async fn read_prices(&self) -> anyhow::Result<fluxion_core::SpotPriceData> {
+            anyhow::bail!("not used")
+        }
+
+        async fn health_check(&self) -> anyhow::Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "none"
+        }
+    }
+
+    #[test]
+    fn test_backup_soc_change_requests_replan() {
+        use bevy_ecs::system::RunSystemOnce;
+        use fluxion_core::async_systems::{BackupDischargeMinSoc, BackupSocChannel};
+
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let mut world = World::new();
+        world.spawn(BackupSocChannel { receiver });
+        world.insert_resource(BackupDischargeMinSoc { value: 10.0 });
+        world.insert_resource(fluxion_core::PriceCache::new(Arc::new(NoPrices), 60));
+
+        // The same value again is not a change
+        sender.send(10.0).unwrap();
+        world.run_system_once(poll_backup_soc_channel).unwrap();
+        assert!(
+            !world
+                .resource::<fluxion_core::PriceCache>()
+                .take_replan_request()
+        );
+
+        sender.send(30.0).unwrap();
+        world.run_system_once(poll_backup_soc_channel).unwrap();
+        assert_eq!(world.resource::<BackupDischargeMinSoc>().value, 30.0);
+        assert!(
+            world
+                .resource::<fluxion_core::PriceCache>()
+                .take_replan_request()
+        );
+    }
 }
@@ -14,10 +14,10 @@ use crate::ha::HaClientResource;
 use crate::ha::client::HomeAssistantClient;
 use crate::ha::types::HaEntityState;
 use bevy_ecs::prelude::*;
-use fluxion_core::SystemConfig;
 use fluxion_core::async_systems::{
     SolarForecastChannel, SolarForecastData, SolarForecastSender, SolarForecastUpdate,
 };
+use fluxion_core::{PriceCache, SolarForecastDataSourceResource, SystemConfig};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
@@ -36,7 +36,12 @@ pub fn spawn_solar_forecast_fetcher(
     ha_client: Option<Res<HaClientResource>>,
     sender: Option<Res<SolarForecastSender>>,
     system_config: Res<SystemConfig>,
+    native_source: Option<Res<SolarForecastDataSourceResource>>,
 ) {
+    if native_source.is_some() {
+        tracing::info!("ℹ️ Native solar forecast provider configured, skipping HA sensors");
+        return;
+    }
     let Some(client_res) = ha_client else {
         tracing::warn!("⚠️ HaClientResource not available, cannot fetch solar forecast");
         return;
@@ -63,28 +68,36 @@ pub fn spawn_solar_forecast_fetcher(
         config.sensor_tomorrow_pattern
     );
 
-    tokio::spawn(async move {
-        // Shared state for discovered sensors (discovered once, reused for updates)
-        let discovered = Arc::new(RwLock::new(DiscoveredSensors::default()));
+    fluxion_core::TaskSupervisor::global().spawn("solar_forecast_fetcher", move || {
+        run_solar_forecast_fetcher(client.clone(), config.clone(), sender.clone())
+    });
+}
 
-        // Discover sensors on startup
-        tracing::info!("🔍 Discovering solar forecast sensors from HA...");
-        if let Err(e) = discover_sensors(&client, &config, &discovered).await {
-            tracing::error!("❌ Failed to discover solar forecast sensors: {}", e);
-            return;
-        }
+async fn run_solar_forecast_fetcher(
+    client: Arc<HomeAssistantClient>,
+    config: fluxion_core::SolarForecastConfigCore,
+    sender: crossbeam_channel::Sender<SolarForecastUpdate>,
+) {
+    // Shared state for discovered sensors (discovered once, reused for updates)
+    let discovered = Arc::new(RwLock::new(DiscoveredSensors::default()));
 
-        // Fetch immediately on startup
-        tracing::debug!("📊 Fetching initial solar forecast from HA");
-        fetch_and_send_solar_forecast(&client, &discovered, &sender).await;
+    // Discover sensors on startup
+    tracing::info!("🔍 Discovering solar forecast sensors from HA...");
+    if let Err(e) = discover_sensors(&client, &config, &discovered).await {
+        tracing::error!("❌ Failed to discover solar forecast sensors: {}", e);
+        return;
+    }
 
-        // Poll at configured interval (default 60 seconds)
-        loop {
-            tokio::time::sleep(Duration::from_secs(config.fetch_interval_seconds)).await;
-            tracing::debug!("📊 Polling solar forecast from HA");
-            fetch_and_send_solar_forecast(&client, &discovered, &sender).await;
-        }
-    });
+    // Fetch immediately on startup
+    tracing::debug!("📊 Fetching initial solar forecast from HA");
+    fetch_and_send_solar_forecast(&client, &discovered, &sender).await;
+
+    // Poll at configured interval (default 60 seconds)
+    loop {
+        tokio::time::sleep(Duration::from_secs(config.fetch_interval_seconds)).await;
+        tracing::debug!("📊 Polling solar forecast from HA");
+        fetch_and_send_solar_forecast(&client, &discovered, &sender).await;
+    }
 }
 
 /// Discover sensors matching the configured patterns
@@ -217,6 +230,7 @@ async fn fetch_and_send_solar_forecast(
         total_today_kwh: total_today,
         remaining_today_kwh: remaining_today,
         tomorrow_kwh: tomorrow,
+        blocks: Vec::new(),
     };
 
     tracing::debug!(
@@ -267,9 +281,12 @@ async fn fetch_and_sum_sensors(client: &HomeAssistantClient, entity_ids: &[Strin
 }
 
 /// Update system: poll solar forecast channel and update resource
+///
+/// A changed forecast requests a replan.
 pub fn poll_solar_forecast_channel(
     channel_query: Query<&SolarForecastChannel>,
     mut forecast_data: Option<ResMut<SolarForecastData>>,
+    price_cache: Option<Res<PriceCache>>,
 ) {
     let Ok(channel) = channel_query.single() else {
         return; // Channel not yet created
@@ -291,10 +308,16 @@ pub fn poll_solar_forecast_channel(
                     update.tomorrow_kwh
                 );
             }
+            if (changed || data.blocks != update.blocks)
+                && let Some(cache) = &price_cache
+            {
+                cache.request_replan();
+            }
 
             data.total_today_kwh = update.total_today_kwh;
             data.remaining_today_kwh = update.remaining_today_kwh;
             data.tomorrow_kwh = update.tomorrow_kwh;
+            data.blocks = update.blocks;
             data.last_updated = std::time::Instant::now();
         }
     }
@@ -381,4 +404,48 @@ mod tests {
             "sensor.energy_production_today"
         ));
     }
+
+    struct NoPrices;
+
+    #[async_trait::async_trait]
+    impl fluxion_core::PriceDataSource for NoPrices {
+        async fn read_prices(&self) -> anyhow::Result<fluxion_core::SpotPriceData> {
+            anyhow::bail!("not used")
+        }
+
+        async fn health_check(&self) -> anyhow::Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "none"
+        }
+    }
+
+    #[test]
+    fn test_solar_forecast_change_requests_replan() {
+        use bevy_ecs::system::RunSystemOnce;
+
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let mut world = World::new();
+        world.spawn(SolarForecastChannel { receiver });
+        world.init_resource::<SolarForecastData>();
+        world.insert_resource(PriceCache::new(Arc::new(NoPrices), 60));
+        let update = |tomorrow_kwh| SolarForecastUpdate {
+            total_today_kwh: 0.0,
+            remaining_today_kwh: 0.0,
+            tomorrow_kwh,
+            blocks: Vec::new(),
+        };
+
+        // Matches the default, empty forecast
+        sender.send(update(0.0)).unwrap();
+        world.run_system_once(poll_solar_forecast_channel).unwrap();
+        assert!(!world.resource::<PriceCache>().take_replan_request());
+
+        sender.send(update(12.5)).unwrap();
+        world.run_system_once(poll_solar_forecast_channel).unwrap();
+        assert_eq!(world.resource::<SolarForecastData>().tomorrow_kwh, 12.5);
+        assert!(world.resource::<PriceCache>().take_replan_request());
+    }
 }
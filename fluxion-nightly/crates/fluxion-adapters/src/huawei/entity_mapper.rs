@@ -0,0 +1,325 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use crate::huawei::modes::HuaweiStorageWorkingMode;
+use fluxion_core::{EntityChange, InverterOperationMode, ModeChangeRequest, VendorEntityMapper};
+
+/// Huawei SUN2000 + LUNA2000 entity mapper for the Home Assistant `huawei_solar` integration
+///
+/// Expects the inverter, battery and power meter entities under one prefix.
+/// Grid charging is a switch rather than a working mode, so force charge keeps
+/// self-consumption and turns charge-from-grid on up to the cutoff SOC; holding
+/// and discharging use the fixed charge/discharge and fully-fed-to-grid modes.
+pub struct HuaweiEntityMapper;
+
+impl HuaweiEntityMapper {
+    /// Create a new Huawei entity mapper
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn working_mode(mode: InverterOperationMode) -> HuaweiStorageWorkingMode {
+        match mode {
+            // LUNA2000 has no separate backup reserve mode in HA, so backup
+            // charges from the grid to full like force charge
+            InverterOperationMode::SelfUse
+            | InverterOperationMode::BackUpMode
+            | InverterOperationMode::ForceCharge => {
+                HuaweiStorageWorkingMode::MaximiseSelfConsumption
+            }
+            // Without configured periods the battery neither charges nor discharges
+            InverterOperationMode::NoChargeNoDischarge => {
+                HuaweiStorageWorkingMode::FixedChargeDischarge
+            }
+            InverterOperationMode::ForceDischarge => HuaweiStorageWorkingMode::FullyFedToGrid,
+        }
+    }
+}
+
+impl Default for HuaweiEntityMapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl VendorEntityMapper for HuaweiEntityMapper {
+    fn vendor_name(&self) -> fluxion_core::InverterType {
+        fluxion_core::InverterType::Huawei
+    }
+
+    fn map_mode_to_vendor(&self, mode: InverterOperationMode) -> i32 {
+        Self::working_mode(mode).to_i32()
+    }
+
+    fn map_mode_from_vendor(&self, vendor_mode: i32) -> Option<InverterOperationMode> {
+        match HuaweiStorageWorkingMode::from_i32(vendor_mode)? {
+            // Grid charging lives on the charge-from-grid switch, so a charging
+            // battery still reads back as self-use
+            HuaweiStorageWorkingMode::MaximiseSelfConsumption => {
+                Some(InverterOperationMode::SelfUse)
+            }
+            HuaweiStorageWorkingMode::FixedChargeDischarge => {
+                Some(InverterOperationMode::NoChargeNoDischarge)
+            }
+            HuaweiStorageWorkingMode::FullyFedToGrid => Some(InverterOperationMode::ForceDischarge),
+            HuaweiStorageWorkingMode::Adaptive
+            | HuaweiStorageWorkingMode::TimeOfUseLg
+            | HuaweiStorageWorkingMode::TimeOfUseLuna2000 => None,
+        }
+    }
+
+    fn get_work_mode_entity(&self, inverter_id: &str) -> String {
+        format!("select.{inverter_id}_battery_working_mode")
+    }
+
+    fn get_mode_change_request(
+        &self,
+        inverter_id: &str,
+        mode: InverterOperationMode,
+    ) -> ModeChangeRequest {
+        let charge_from_grid = matches!(
+            mode,
+            InverterOperationMode::ForceCharge | InverterOperationMode::BackUpMode
+        );
+
+        let mut entity_changes = Vec::new();
+        if charge_from_grid {
+            entity_changes.push(EntityChange {
+                entity_id: format!("number.{inverter_id}_battery_grid_charge_cutoff_soc"),
+                option: "100".to_owned(),
+            });
+        }
+        entity_changes.push(EntityChange {
+            entity_id: format!("switch.{inverter_id}_battery_charge_from_grid"),
+            option: if charge_from_grid { "on" } else { "off" }.to_owned(),
+        });
+
+        let option = serde_json::to_value(Self::working_mode(mode))
+            .and_then(serde_json::from_value)
+            .expect("Failed to serialize working mode");
+        entity_changes.push(EntityChange {
+            entity_id: self.get_work_mode_entity(inverter_id),
+            option,
+        });
+
+        ModeChangeRequest { entity_changes }
+    }
+
+    fn get_battery_soc_entity(&self, inverter_id: &str) -> String {
+        format!("sensor.{inverter_id}_battery_state_of_capacity")
+    }
+
+    fn get_grid_power_entity(&self, inverter_id: &str) -> String {
+        // Positive = export to grid, Negative = import from grid
+        format!("sensor.{inverter_id}_power_meter_active_power")
+    }
+
+    fn get_battery_power_entity(&self, inverter_id: &str) -> String {
+        // Positive = charging, Negative = discharging
+        format!("sensor.{inverter_id}_battery_charge_discharge_power")
+    }
+
+    fn get_pv_power_entity(&self, inverter_id: &str) -> String {
+        format!("sensor.{inverter_id}_input_power")
+    }
+
+    fn get_export_limit_entity(&self, inverter_id: &str) -> String {
+        format!("number.{inverter_id}_maximum_feed_grid_power")
+    }
+
+    // ============= Three-Phase Data (Optional) =============
+
+    fn get_l1_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_phase_a_voltage"))
+    }
+
+    fn get_l1_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_phase_a_current"))
+    }
+
+    fn get_l1_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!(
+            "sensor.{inverter_id}_power_meter_phase_a_active_power"
+        ))
+    }
+
+    fn get_l2_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_phase_b_voltage"))
+    }
+
+    fn get_l2_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_phase_b_current"))
+    }
+
+    fn get_l2_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!(
+            "sensor.{inverter_id}_power_meter_phase_b_active_power"
+        ))
+    }
+
+    fn get_l3_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_phase_c_voltage"))
+    }
+
+    fn get_l3_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_phase_c_current"))
+    }
+
+    fn get_l3_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!(
+            "sensor.{inverter_id}_power_meter_phase_c_active_power"
+        ))
+    }
+
+    // ============= Battery Extended (Optional) =============
+
+    fn get_battery_voltage_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_bus_voltage"))
+    }
+
+    fn get_battery_current_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_bus_current"))
+    }
+
+    fn get_battery_output_energy_total_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_total_discharge"))
+    }
+
+    fn get_battery_input_energy_total_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_total_charge"))
+    }
+
+    fn get_battery_input_energy_today_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_day_charge"))
+    }
+
+    fn get_battery_output_energy_today_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_day_discharge"))
+    }
+
+    // ============= Load & Grid Detailed (Optional) =============
+
+    fn get_grid_import_total_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_accumulated_energy"))
+    }
+
+    fn get_grid_export_total_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_exported_energy"))
+    }
+
+    fn get_inverter_frequency_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_grid_frequency"))
+    }
+
+    fn get_inverter_power_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_active_power"))
+    }
+
+    // ============= Solar Energy (Optional) =============
+
+    fn get_today_yield_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_daily_yield"))
+    }
+
+    fn get_total_yield_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_total_yield"))
+    }
+
+    // ============= Temperatures (Optional) =============
+
+    fn get_inverter_temperature_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_internal_temperature"))
+    }
+
+    fn get_battery_temperature_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("sensor.{inverter_id}_battery_temperature"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(request: &ModeChangeRequest) -> Vec<(&str, &str)> {
+        request
+            .entity_changes
+            .iter()
+            .map(|c| (c.entity_id.as_str(), c.option.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_huawei_force_charge_turns_on_grid_charging() {
+        let mapper = HuaweiEntityMapper::new();
+
+        let request = mapper.get_mode_change_request("luna", InverterOperationMode::ForceCharge);
+        assert_eq!(
+            steps(&request),
+            vec![
+                ("number.luna_battery_grid_charge_cutoff_soc", "100"),
+                ("switch.luna_battery_charge_from_grid", "on"),
+                (
+                    "select.luna_battery_working_mode",
+                    "maximise_self_consumption"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_huawei_other_modes_turn_off_grid_charging() {
+        let mapper = HuaweiEntityMapper::new();
+
+        for (mode, working_mode) in [
+            (InverterOperationMode::SelfUse, "maximise_self_consumption"),
+            (
+                InverterOperationMode::NoChargeNoDischarge,
+                "fixed_charge_discharge",
+            ),
+            (InverterOperationMode::ForceDischarge, "fully_fed_to_grid"),
+        ] {
+            let request = mapper.get_mode_change_request("luna", mode);
+            assert_eq!(
+                steps(&request),
+                vec![
+                    ("switch.luna_battery_charge_from_grid", "off"),
+                    ("select.luna_battery_working_mode", working_mode),
+                ],
+                "{mode:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_huawei_mode_mapping_from_vendor() {
+        let mapper = HuaweiEntityMapper::new();
+
+        for mode in [
+            InverterOperationMode::SelfUse,
+            InverterOperationMode::NoChargeNoDischarge,
+            InverterOperationMode::ForceDischarge,
+        ] {
+            assert_eq!(
+                mapper.map_mode_from_vendor(mapper.map_mode_to_vendor(mode)),
+                Some(mode)
+            );
+        }
+        // Grid charging is a switch, so the working mode still reads self-use
+        assert_eq!(
+            mapper.map_mode_from_vendor(
+                mapper.map_mode_to_vendor(InverterOperationMode::ForceCharge)
+            ),
+            Some(InverterOperationMode::SelfUse)
+        );
+        assert_eq!(mapper.map_mode_from_vendor(5), None); // Time of use
+    }
+}
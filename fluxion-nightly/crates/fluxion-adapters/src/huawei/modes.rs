@@ -0,0 +1,82 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use serde::{Deserialize, Serialize};
+
+/// Huawei LUNA2000 storage working mode
+/// Order matches Home Assistant select.{inverter_id}_battery_working_mode options
+/// These strings must match EXACTLY what HA expects (the `huawei_solar` option keys)
+///
+/// Grid charging is not a mode on Huawei: it is the separate charge-from-grid
+/// switch, limited by the grid charge cutoff SOC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(i32)]
+pub enum HuaweiStorageWorkingMode {
+    Adaptive = 0,
+
+    FixedChargeDischarge = 1,
+
+    MaximiseSelfConsumption = 2,
+
+    #[serde(rename = "time_of_use_lg")]
+    TimeOfUseLg = 3,
+
+    FullyFedToGrid = 4,
+
+    #[serde(rename = "time_of_use_luna2000")]
+    TimeOfUseLuna2000 = 5,
+}
+
+impl HuaweiStorageWorkingMode {
+    /// Try to create from i32 discriminant
+    pub fn from_i32(value: i32) -> Option<Self> {
+        match value {
+            0 => Some(Self::Adaptive),
+            1 => Some(Self::FixedChargeDischarge),
+            2 => Some(Self::MaximiseSelfConsumption),
+            3 => Some(Self::TimeOfUseLg),
+            4 => Some(Self::FullyFedToGrid),
+            5 => Some(Self::TimeOfUseLuna2000),
+            _ => None,
+        }
+    }
+
+    /// Convert to i32 discriminant
+    pub fn to_i32(self) -> i32 {
+        self as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_working_mode_serde() {
+        let json =
+            serde_json::to_string(&HuaweiStorageWorkingMode::MaximiseSelfConsumption).unwrap();
+        assert_eq!(json, "\"maximise_self_consumption\"");
+
+        let json = serde_json::to_string(&HuaweiStorageWorkingMode::TimeOfUseLuna2000).unwrap();
+        assert_eq!(json, "\"time_of_use_luna2000\"");
+
+        let mode: HuaweiStorageWorkingMode =
+            serde_json::from_str("\"fixed_charge_discharge\"").unwrap();
+        assert_eq!(mode, HuaweiStorageWorkingMode::FixedChargeDischarge);
+
+        for value in 0..6 {
+            let mode = HuaweiStorageWorkingMode::from_i32(value).unwrap();
+            assert_eq!(mode.to_i32(), value);
+        }
+    }
+}
@@ -10,17 +10,60 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
+pub mod deye;
+pub mod goodwe;
 pub mod ha;
+pub mod huawei;
+pub mod nordpool;
+pub mod solar_forecast;
 pub mod solax;
+pub mod tibber;
+pub mod weather;
+
+use fluxion_core::{InverterType, VendorEntityMapper};
+use std::sync::Arc;
 
 // Re-export commonly used types for convenience
+pub use deye::{DeyeEntityMapper, DeyeWorkMode};
+
+pub use goodwe::{GoodWeEntityMapper, GoodWeOperationMode};
+
 pub use ha::{
-    ConfigurablePriceDataSource, CzSpotPriceAdapter, HaClientResource, HaConsumptionHistoryAdapter,
-    HaEntityState, HaError, HaHistoryState, HaPlugin, HaResult, HistoryDataPoint,
-    HomeAssistantClient, HomeAssistantInverterAdapter, PriceAdapterTimezoneHandle,
+    ConfigurablePriceDataSource, CzSpotPriceAdapter, HaAlertNotifier, HaClientResource,
+    HaConsumptionHistoryAdapter, HaDhwController, HaEntityState, HaError, HaHistoryState,
+    HaMappingValidator, HaPlugin, HaPowerSensorReader, HaResult, HistoryDataPoint,
+    HomeAssistantClient, HomeAssistantExecutionBackend, HomeAssistantInverterAdapter,
+    PriceAdapterTimezoneHandle, check_entity_mapping,
 };
 
+pub use huawei::{HuaweiEntityMapper, HuaweiStorageWorkingMode};
+
+pub use nordpool::NordPoolPriceAdapter;
+
+pub use solar_forecast::{ForecastSolarAdapter, PvString, SolcastAdapter};
+
 pub use solax::{
-    SolaxChargerUseMode, SolaxEntityMapper, SolaxManualMode, SolaxUltraEntityMapper,
-    create_entity_mapper,
+    SolaxChargerUseMode, SolaxEntityMapper, SolaxLocalApiAdapter, SolaxLocalConfig,
+    SolaxLocalDeviceConfig, SolaxManualMode, SolaxUltraEntityMapper,
 };
+
+pub use tibber::{TibberConsumptionAdapter, TibberPriceAdapter};
+
+pub use weather::{HaWeatherAdapter, OpenMeteoAdapter};
+
+/// Factory function to create the appropriate entity mapper for a given inverter type
+///
+/// # Arguments
+/// * `inverter_type` - The type of inverter to create a mapper for
+///
+/// # Returns
+/// An Arc-wrapped VendorEntityMapper trait object
+pub fn create_entity_mapper(inverter_type: InverterType) -> Arc<dyn VendorEntityMapper> {
+    match inverter_type {
+        InverterType::Solax => Arc::new(SolaxEntityMapper::new()),
+        InverterType::SolaxUltra => Arc::new(SolaxUltraEntityMapper::new()),
+        InverterType::GoodWe => Arc::new(GoodWeEntityMapper::new()),
+        InverterType::Huawei => Arc::new(HuaweiEntityMapper::new()),
+        InverterType::Deye => Arc::new(DeyeEntityMapper::new()),
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Nord Pool day-ahead prices for the Nordic and Baltic bidding areas.
+//!
+//! Prices are read from the public Nord Pool data portal in the configured
+//! currency per MWh and converted to the per-kWh block prices the scheduler
+//! works with. Hourly market time units are expanded into 15-minute blocks.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono_tz::Tz;
+use fluxion_core::{PriceDataSource, SpotPriceData, TimeBlockPrice};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+/// Nord Pool data portal API
+pub const DEFAULT_BASE_URL: &str = "https://dataportal-api.nordpoolgroup.com";
+
+/// Delivery days are defined in CET
+const MARKET_TIMEZONE: Tz = chrono_tz::Europe::Oslo;
+
+/// Block length the scheduler works with (minutes)
+const BLOCK_MINUTES: i64 = 15;
+
+/// Bidding areas with day-ahead prices
+pub const NORD_POOL_AREAS: &[&str] = &[
+    "SE1", "SE2", "SE3", "SE4", "FI", "DK1", "DK2", "NO1", "NO2", "NO3", "NO4", "NO5", "EE", "LV",
+    "LT",
+];
+
+/// Currencies the data portal publishes prices in
+pub const NORD_POOL_CURRENCIES: &[&str] = &["EUR", "SEK", "NOK", "DKK"];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DayAheadResponse {
+    #[serde(default)]
+    multi_area_entries: Vec<AreaEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AreaEntry {
+    delivery_start: DateTime<Utc>,
+    delivery_end: DateTime<Utc>,
+    entry_per_area: HashMap<String, Option<f32>>,
+}
+
+/// Parse a `DayAheadPrices` response into 15-minute blocks for `area`
+///
+/// # Errors
+///
+/// Returns an error if the JSON is malformed or has no price for `area`.
+pub fn parse_day_ahead(json: &str, area: &str) -> Result<Vec<TimeBlockPrice>> {
+    let response: DayAheadResponse =
+        serde_json::from_str(json).context("Failed to parse Nord Pool response")?;
+
+    let mut blocks = Vec::new();
+    for entry in &response.multi_area_entries {
+        let Some(price_per_mwh) = entry.entry_per_area.get(area).copied().flatten() else {
+            continue;
+        };
+        let price_per_kwh = price_per_mwh / 1000.0;
+
+        let mut block_start = entry.delivery_start;
+        while block_start < entry.delivery_end {
+            blocks.push(TimeBlockPrice {
+                block_start,
+                duration_minutes: 15,
+                price_czk_per_kwh: price_per_kwh,
+                // Effective price will be calculated by scheduler with HDO fees
+                effective_price_czk_per_kwh: price_per_kwh,
+                spot_sell_price_czk_per_kwh: None,
+            });
+            block_start += Duration::minutes(BLOCK_MINUTES);
+        }
+    }
+
+    anyhow::ensure!(!blocks.is_empty(), "No Nord Pool prices for area {area}");
+    blocks.sort_by_key(|b| b.block_start);
+    Ok(blocks)
+}
+
+/// Nord Pool day-ahead price adapter implementing PriceDataSource
+pub struct NordPoolPriceAdapter {
+    client: reqwest::Client,
+    base_url: String,
+    area: String,
+    currency: String,
+    days: Mutex<HashMap<NaiveDate, Vec<TimeBlockPrice>>>,
+}
+
+impl std::fmt::Debug for NordPoolPriceAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NordPoolPriceAdapter")
+            .field("area", &self.area)
+            .field("currency", &self.currency)
+            .finish_non_exhaustive()
+    }
+}
+
+impl NordPoolPriceAdapter {
+    /// Create an adapter for bidding `area` (e.g. `SE3`) with prices in `currency`
+    pub fn new(area: impl Into<String>, currency: impl Into<String>) -> Self {
+        Self::with_base_url(DEFAULT_BASE_URL, area, currency)
+    }
+
+    /// Create an adapter reading from another data portal URL
+    pub fn with_base_url(
+        base_url: impl Into<String>,
+        area: impl Into<String>,
+        currency: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            area: area.into().to_uppercase(),
+            currency: currency.into().to_uppercase(),
+            days: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn fetch_day(&self, date: NaiveDate) -> Result<Vec<TimeBlockPrice>> {
+        let url = format!("{}/api/DayAheadPrices", self.base_url);
+        debug!(
+            "💰 [NORD POOL] Downloading {} prices for {date} from: {url}",
+            self.area
+        );
+
+        let response = self
+            .client
+            .get(&url)
+            .query(&[
+                ("date", date.format("%Y-%m-%d").to_string()),
+                ("market", "DayAhead".to_owned()),
+                ("deliveryArea", self.area.clone()),
+                ("currency", self.currency.clone()),
+            ])
+            .send()
+            .await
+            .context("Failed to send request to Nord Pool")?
+            .error_for_status()
+            .context("Nord Pool request failed")?;
+
+        // The portal answers 204 until the auction results are published
+        anyhow::ensure!(
+            response.status() != reqwest::StatusCode::NO_CONTENT,
+            "Nord Pool prices for {date} not published yet"
+        );
+        let body = response
+            .text()
+            .await
+            .context("Failed to read Nord Pool response")?;
+        parse_day_ahead(&body, &self.area)
+    }
+
+    /// Prices of `date`, from the cache or Nord Pool
+    async fn day(&self, date: NaiveDate) -> Result<Vec<TimeBlockPrice>> {
+        if let Some(blocks) = self.days.lock().get(&date) {
+            return Ok(blocks.clone());
+        }
+        let blocks = self.fetch_day(date).await?;
+        info!(
+            "✅ [NORD POOL] Fetched {} {} price blocks for {date}",
+            blocks.len(),
+            self.area
+        );
+        self.days.lock().insert(date, blocks.clone());
+        Ok(blocks)
+    }
+}
+
+#[async_trait]
+impl PriceDataSource for NordPoolPriceAdapter {
+    async fn read_prices(&self) -> Result<SpotPriceData> {
+        let now = Utc::now();
+        let today = now.with_timezone(&MARKET_TIMEZONE).date_naive();
+        self.days.lock().retain(|date, _| *date >= today);
+
+        let mut time_block_prices = self
+            .day(today)
+            .await
+            .with_context(|| format!("Failed to fetch Nord Pool prices for {today}"))?;
+
+        if let Some(tomorrow) = today.succ_opt() {
+            match self.day(tomorrow).await {
+                Ok(blocks) => time_block_prices.extend(blocks),
+                Err(e) => debug!("💰 [NORD POOL] Prices for {tomorrow} not available yet: {e:#}"),
+            }
+        }
+
+        Ok(SpotPriceData {
+            time_block_prices,
+            block_duration_minutes: 15,
+            fetched_at: now,
+            ha_last_updated: now,
+        })
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        let today = Utc::now().with_timezone(&MARKET_TIMEZONE).date_naive();
+        Ok(self.day(today).await.is_ok())
+    }
+
+    fn name(&self) -> &str {
+        "Nord Pool"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+
+    fn response(start: &str, end: &str, price: f32) -> serde_json::Value {
+        json!({
+            "deliveryDateCET": "2025-09-30",
+            "market": "DayAhead",
+            "multiAreaEntries": [{
+                "deliveryStart": start,
+                "deliveryEnd": end,
+                "entryPerArea": { "SE3": price, "FI": 1.0 }
+            }]
+        })
+    }
+
+    #[test]
+    fn test_hourly_prices_are_expanded_to_blocks() {
+        let body = response("2025-09-29T22:00:00Z", "2025-09-29T23:00:00Z", 48.0).to_string();
+
+        let blocks = parse_day_ahead(&body, "SE3").unwrap();
+
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(
+            blocks[3].block_start,
+            "2025-09-29T22:45:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert!((blocks[0].price_czk_per_kwh - 0.048).abs() < 1e-6);
+        assert!(parse_day_ahead(&body, "NO2").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_prices_are_fetched_for_the_configured_area() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/DayAheadPrices")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("deliveryArea".into(), "SE3".into()),
+                Matcher::UrlEncoded("currency".into(), "SEK".into()),
+                Matcher::UrlEncoded("date".into(), "2025-09-30".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(response("2025-09-29T22:00:00Z", "2025-09-29T22:15:00Z", 512.5).to_string())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let adapter = NordPoolPriceAdapter::with_base_url(server.url(), "se3", "sek");
+        let date = NaiveDate::from_ymd_opt(2025, 9, 30).unwrap();
+        let blocks = adapter.day(date).await.unwrap();
+        // Served from the cache the second time
+        adapter.day(date).await.unwrap();
+
+        assert_eq!(blocks.len(), 1);
+        assert!((blocks[0].price_czk_per_kwh - 0.5125).abs() < 1e-6);
+        mock.assert_async().await;
+    }
+}
@@ -0,0 +1,316 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Forecast.Solar production estimates.
+//!
+//! The public API allows 12 calls per hour and IP address and needs no
+//! account; an API key raises the limit. Every string costs one call per
+//! refresh. The limit reported with each response sets how often the
+//! forecast is refreshed.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use fluxion_core::{SolarForecastBlock, SolarForecastDataSource};
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tracing::{debug, info};
+
+use super::{BlockSums, Fetched, ForecastCache, PvString};
+
+/// Forecast.Solar API
+pub const DEFAULT_BASE_URL: &str = "https://api.forecast.solar";
+
+/// Forecasts are not refreshed more often than this, whatever the limit
+const MIN_REFRESH_INTERVAL: Duration = Duration::minutes(30);
+
+/// Longest period between two estimates; longer gaps span the night
+const MAX_PERIOD: Duration = Duration::hours(1);
+
+#[derive(Debug, Deserialize)]
+struct EstimateResponse {
+    /// Wh produced in the period ending at each timestamp
+    result: BTreeMap<String, f32>,
+    #[serde(default)]
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    #[serde(default)]
+    ratelimit: Option<RateLimit>,
+}
+
+/// Calls allowed per period, as reported by the API
+#[derive(Debug, Clone, Copy, Deserialize)]
+struct RateLimit {
+    /// Period length in seconds
+    period: i64,
+    limit: i64,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            period: 3600,
+            limit: 12,
+        }
+    }
+}
+
+fn parse_timestamp(timestamp: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|t| t.and_utc())
+        })
+}
+
+/// Forecast.Solar measures azimuth from south: -90 = east, 90 = west
+fn forecast_solar_azimuth(compass: f32) -> f32 {
+    compass.rem_euclid(360.0) - 180.0
+}
+
+/// Add a `watthours/period` response to `sums`, returning the reported rate limit
+fn add_estimate(json: &str, sums: &mut BlockSums) -> Result<Option<RateLimit>> {
+    let response: EstimateResponse =
+        serde_json::from_str(json).context("Failed to parse Forecast.Solar response")?;
+
+    let mut periods: Vec<(DateTime<Utc>, f32)> = response
+        .result
+        .iter()
+        .filter_map(|(timestamp, wh)| Some((parse_timestamp(timestamp)?, *wh)))
+        .collect();
+    periods.sort_by_key(|(end, _)| *end);
+
+    let mut previous: Option<DateTime<Utc>> = None;
+    for (end, wh) in periods {
+        // The first estimate of a day is sunrise, with nothing produced before it
+        if let Some(start) = previous.filter(|start| end - *start <= MAX_PERIOD) {
+            sums.add_period(start, end, wh.max(0.0) / 1000.0);
+        }
+        previous = Some(end);
+    }
+
+    Ok(response.message.and_then(|m| m.ratelimit))
+}
+
+/// Forecast.Solar adapter implementing SolarForecastDataSource
+pub struct ForecastSolarAdapter {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    latitude: f64,
+    longitude: f64,
+    strings: Vec<PvString>,
+    rate_limit: Mutex<RateLimit>,
+    cache: ForecastCache,
+}
+
+impl std::fmt::Debug for ForecastSolarAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ForecastSolarAdapter")
+            .field("strings", &self.strings)
+            .field("rate_limit", &*self.rate_limit.lock())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ForecastSolarAdapter {
+    /// Create an adapter forecasting `strings` at the given location
+    ///
+    /// Without an API key the public, rate-limited API is used.
+    pub fn new(
+        api_key: Option<String>,
+        latitude: f64,
+        longitude: f64,
+        strings: Vec<PvString>,
+    ) -> Self {
+        Self::with_base_url(DEFAULT_BASE_URL, api_key, latitude, longitude, strings)
+    }
+
+    /// Create an adapter reading from another API URL
+    pub fn with_base_url(
+        base_url: impl Into<String>,
+        api_key: Option<String>,
+        latitude: f64,
+        longitude: f64,
+        strings: Vec<PvString>,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            api_key: api_key.filter(|key| !key.is_empty()),
+            latitude,
+            longitude,
+            strings,
+            rate_limit: Mutex::new(RateLimit::default()),
+            cache: ForecastCache::default(),
+        }
+    }
+
+    /// Time between refreshes that keeps all strings within the rate limit
+    fn refresh_interval(&self) -> Duration {
+        let rate_limit = *self.rate_limit.lock();
+        let calls = i64::try_from(self.strings.len().max(1)).unwrap_or(i64::MAX);
+        let interval = Duration::seconds(rate_limit.period * calls / rate_limit.limit.max(1));
+        interval.max(MIN_REFRESH_INTERVAL)
+    }
+
+    fn string_url(&self, string: &PvString) -> String {
+        let key = self
+            .api_key
+            .as_ref()
+            .map(|key| format!("/{key}"))
+            .unwrap_or_default();
+        format!(
+            "{}{key}/estimate/watthours/period/{}/{}/{}/{}/{}",
+            self.base_url,
+            self.latitude,
+            self.longitude,
+            string.tilt,
+            forecast_solar_azimuth(string.azimuth),
+            string.kwp
+        )
+    }
+
+    async fn fetch(&self) -> Result<Fetched> {
+        let mut sums = BlockSums::default();
+
+        for string in &self.strings {
+            debug!("☀️ [FORECAST.SOLAR] Downloading estimate for {string:?}");
+            let response = self
+                .client
+                .get(self.string_url(string))
+                .query(&[("time", "utc")])
+                .header(reqwest::header::ACCEPT, "application/json")
+                .send()
+                .await
+                .context("Failed to send request to Forecast.Solar")?;
+
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                let retry_at = response
+                    .headers()
+                    .get("x-ratelimit-retry-at")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(parse_timestamp)
+                    .unwrap_or_else(|| Utc::now() + Duration::hours(1));
+                return Ok(Fetched::RateLimited(retry_at));
+            }
+            let body = response
+                .error_for_status()
+                .context("Forecast.Solar request failed")?
+                .text()
+                .await
+                .context("Failed to read Forecast.Solar response")?;
+            if let Some(rate_limit) = add_estimate(&body, &mut sums)? {
+                *self.rate_limit.lock() = rate_limit;
+            }
+        }
+
+        let blocks = sums.into_blocks();
+        info!(
+            "✅ [FORECAST.SOLAR] Fetched {} forecast blocks",
+            blocks.len()
+        );
+        Ok(Fetched::Blocks(blocks))
+    }
+}
+
+#[async_trait]
+impl SolarForecastDataSource for ForecastSolarAdapter {
+    async fn read_forecast(&self) -> Result<Vec<SolarForecastBlock>> {
+        let now = Utc::now();
+        if let Some(blocks) = self.cache.fresh(now, self.refresh_interval()) {
+            return Ok(blocks);
+        }
+        let fetched = self.fetch().await;
+        self.cache.update(fetched, now)
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        // A request just to check would count against the rate limit
+        Ok(self.cache.has_forecast())
+    }
+
+    fn name(&self) -> &str {
+        "Forecast.Solar"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Server;
+    use serde_json::json;
+
+    fn estimate() -> String {
+        json!({
+            "result": {
+                "2025-06-01T03:52:00+00:00": 0,
+                "2025-06-01T04:00:00+00:00": 40,
+                "2025-06-01T05:00:00+00:00": 1000,
+                "2025-06-02T03:52:00+00:00": 0
+            },
+            "message": {
+                "code": 0,
+                "type": "success",
+                "ratelimit": { "zone": "IP", "period": 3600, "limit": 12, "remaining": 11 }
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_estimate_is_split_into_blocks() {
+        let mut sums = BlockSums::default();
+
+        let rate_limit = add_estimate(&estimate(), &mut sums).unwrap().unwrap();
+        let blocks = sums.into_blocks();
+
+        assert_eq!(rate_limit.limit, 12);
+        assert_eq!(blocks.len(), 5);
+        assert!((blocks[0].kwh - 0.04).abs() < 1e-6);
+        assert!(blocks[1..].iter().all(|b| (b.kwh - 0.25).abs() < 1e-6));
+        assert!((forecast_solar_azimuth(90.0) + 90.0).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_request_is_not_repeated() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/estimate/watthours/period/50/14/35/0/5")
+            .match_query(mockito::Matcher::UrlEncoded("time".into(), "utc".into()))
+            .with_status(429)
+            .with_header("x-ratelimit-retry-at", "2099-01-01T00:00:00+00:00")
+            .expect(1)
+            .create_async()
+            .await;
+        let string = PvString {
+            azimuth: 180.0,
+            tilt: 35.0,
+            kwp: 5.0,
+        };
+
+        let adapter =
+            ForecastSolarAdapter::with_base_url(server.url(), None, 50.0, 14.0, vec![string]);
+
+        assert!(adapter.read_forecast().await.is_err());
+        // Backing off until the retry time, so no second request
+        assert!(adapter.read_forecast().await.unwrap().is_empty());
+        mock.assert_async().await;
+    }
+}
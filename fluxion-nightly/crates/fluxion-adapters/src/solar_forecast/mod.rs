@@ -0,0 +1,245 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Solar forecasts read directly from Solcast or Forecast.Solar.
+//!
+//! Each PV string is forecast separately from its orientation and the results
+//! are summed into the 15-minute blocks the scheduler works with. Both services
+//! have tight free-tier rate limits, so forecasts are cached and only refreshed
+//! as often as the limit allows; when a provider answers "too many requests"
+//! the cached forecast is served until it allows requests again.
+
+pub mod forecast_solar;
+pub mod solcast;
+
+pub use forecast_solar::ForecastSolarAdapter;
+pub use solcast::SolcastAdapter;
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::SolarForecastBlock;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+
+/// Block length the scheduler works with (minutes)
+const BLOCK_MINUTES: i64 = 15;
+
+/// How long forecasts for past blocks are kept for today's total
+const KEEP_PAST: Duration = Duration::days(2);
+
+/// Wait before retrying after a failed request
+const ERROR_BACKOFF: Duration = Duration::minutes(15);
+
+/// Orientation and size of one PV string
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PvString {
+    /// Compass direction the panels face: 0 = north, 90 = east, 180 = south, 270 = west
+    pub azimuth: f32,
+    /// Panel tilt from horizontal in degrees: 0 = flat, 90 = vertical
+    pub tilt: f32,
+    /// Peak power (kWp)
+    pub kwp: f32,
+}
+
+/// Accumulates production over arbitrary periods into 15-minute blocks
+#[derive(Debug, Default)]
+struct BlockSums(BTreeMap<DateTime<Utc>, f32>);
+
+impl BlockSums {
+    /// Spread `kwh` produced during `[start, end)` over the blocks it overlaps
+    fn add_period(&mut self, start: DateTime<Utc>, end: DateTime<Utc>, kwh: f32) {
+        let total_seconds = (end - start).num_seconds();
+        if total_seconds <= 0 {
+            return;
+        }
+
+        let mut block_start = block_start_of(start);
+        while block_start < end {
+            let block_end = block_start + Duration::minutes(BLOCK_MINUTES);
+            let overlap = (block_end.min(end) - block_start.max(start)).num_seconds();
+            #[expect(clippy::cast_precision_loss)]
+            let share = overlap as f32 / total_seconds as f32;
+            *self.0.entry(block_start).or_default() += kwh * share;
+            block_start = block_end;
+        }
+    }
+
+    fn into_blocks(self) -> Vec<SolarForecastBlock> {
+        self.0
+            .into_iter()
+            .map(|(block_start, kwh)| SolarForecastBlock {
+                block_start,
+                duration_minutes: 15,
+                kwh,
+            })
+            .collect()
+    }
+}
+
+/// Start of the 15-minute block `time` falls in
+fn block_start_of(time: DateTime<Utc>) -> DateTime<Utc> {
+    let seconds = BLOCK_MINUTES * 60;
+    let timestamp = time.timestamp() - time.timestamp().rem_euclid(seconds);
+    DateTime::from_timestamp(timestamp, 0).unwrap_or(time)
+}
+
+/// Outcome of one attempt to refresh a forecast
+enum Fetched {
+    Blocks(Vec<SolarForecastBlock>),
+    /// The provider refused the request; retry no earlier than this
+    RateLimited(DateTime<Utc>),
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    blocks: Vec<SolarForecastBlock>,
+    fetched_at: Option<DateTime<Utc>>,
+    retry_at: Option<DateTime<Utc>>,
+}
+
+/// Last forecast of a provider and when it may be refreshed
+#[derive(Debug, Default)]
+struct ForecastCache(Mutex<CacheState>);
+
+impl ForecastCache {
+    /// Cached forecast, unless it is older than `refresh_interval` and the
+    /// provider accepts requests again
+    fn fresh(
+        &self,
+        now: DateTime<Utc>,
+        refresh_interval: Duration,
+    ) -> Option<Vec<SolarForecastBlock>> {
+        let state = self.0.lock();
+        let backing_off = state.retry_at.is_some_and(|retry_at| now < retry_at);
+        let fresh = state
+            .fetched_at
+            .is_some_and(|fetched_at| now - fetched_at < refresh_interval);
+        (backing_off || fresh).then(|| state.blocks.clone())
+    }
+
+    /// Record the outcome of a refresh and return the forecast to serve
+    ///
+    /// New blocks replace cached ones; cached blocks before the new forecast
+    /// starts are kept so today's total still covers the morning.
+    fn update(
+        &self,
+        fetched: Result<Fetched>,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<SolarForecastBlock>> {
+        let mut state = self.0.lock();
+        let error = match fetched {
+            Ok(Fetched::Blocks(blocks)) => {
+                let first_new = blocks.first().map_or(now, |b| b.block_start);
+                state
+                    .blocks
+                    .retain(|b| b.block_start < first_new && now - b.block_start < KEEP_PAST);
+                state.blocks.extend(blocks);
+                state.fetched_at = Some(now);
+                state.retry_at = None;
+                return Ok(state.blocks.clone());
+            }
+            Ok(Fetched::RateLimited(retry_at)) => {
+                state.retry_at = Some(retry_at);
+                anyhow::anyhow!("rate limit reached, retrying at {retry_at}")
+            }
+            Err(e) => {
+                state.retry_at = Some(now + ERROR_BACKOFF);
+                e
+            }
+        };
+
+        if state.blocks.is_empty() {
+            return Err(error);
+        }
+        tracing::warn!("⚠️ Using cached solar forecast: {error:#}");
+        Ok(state.blocks.clone())
+    }
+
+    fn has_forecast(&self) -> bool {
+        !self.0.lock().blocks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_periods_are_spread_over_blocks() {
+        let mut sums = BlockSums::default();
+        sums.add_period(
+            utc("2025-06-01T10:00:00Z"),
+            utc("2025-06-01T11:00:00Z"),
+            2.0,
+        );
+        // Forecast.Solar reports sunrise at odd minutes
+        sums.add_period(
+            utc("2025-06-01T09:50:00Z"),
+            utc("2025-06-01T10:00:00Z"),
+            0.1,
+        );
+
+        let blocks = sums.into_blocks();
+
+        assert_eq!(blocks.len(), 5);
+        assert_eq!(blocks[0].block_start, utc("2025-06-01T09:45:00Z"));
+        assert!((blocks[0].kwh - 0.1).abs() < 1e-6);
+        assert!(blocks[1..].iter().all(|b| (b.kwh - 0.5).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_cache_backs_off_when_rate_limited() {
+        let cache = ForecastCache::default();
+        let now = utc("2025-06-01T10:00:00Z");
+        let block = |start: &str| SolarForecastBlock {
+            block_start: utc(start),
+            duration_minutes: 15,
+            kwh: 1.0,
+        };
+        assert!(cache.fresh(now, Duration::hours(1)).is_none());
+
+        let fetched = vec![block("2025-06-01T07:00:00Z"), block("2025-06-01T12:00:00Z")];
+        cache.update(Ok(Fetched::Blocks(fetched)), now).unwrap();
+        assert!(
+            cache
+                .fresh(now + Duration::minutes(59), Duration::hours(1))
+                .is_some()
+        );
+
+        // Rate limited: the cached forecast is served until the retry time
+        let later = now + Duration::hours(2);
+        let served = cache
+            .update(Ok(Fetched::RateLimited(later + Duration::hours(1))), later)
+            .unwrap();
+        assert_eq!(served.len(), 2);
+        assert!(
+            cache
+                .fresh(later + Duration::minutes(30), Duration::hours(1))
+                .is_some()
+        );
+        assert!(
+            cache
+                .fresh(later + Duration::hours(1), Duration::hours(1))
+                .is_none()
+        );
+
+        // A refresh starting at noon keeps the morning block
+        let refreshed = vec![block("2025-06-01T12:00:00Z"), block("2025-06-01T12:15:00Z")];
+        let served = cache.update(Ok(Fetched::Blocks(refreshed)), later).unwrap();
+        assert_eq!(served.len(), 3);
+        assert_eq!(served[0].block_start, utc("2025-06-01T07:00:00Z"));
+    }
+}
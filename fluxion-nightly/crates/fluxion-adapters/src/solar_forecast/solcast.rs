@@ -0,0 +1,295 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Solcast rooftop PV power forecasts.
+//!
+//! Every string costs one API call per refresh. Solcast counts calls per UTC
+//! day, so refreshes are spread evenly over the day to stay within the
+//! configured daily limit (10 for hobbyist accounts).
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::{SolarForecastBlock, SolarForecastDataSource};
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use super::{BlockSums, Fetched, ForecastCache, PvString};
+
+/// Solcast API
+pub const DEFAULT_BASE_URL: &str = "https://api.solcast.com.au";
+
+/// API calls per day of a hobbyist account
+pub const DEFAULT_DAILY_LIMIT: u32 = 10;
+
+#[derive(Debug, Deserialize)]
+struct ForecastResponse {
+    #[serde(default)]
+    forecasts: Vec<ForecastPeriod>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForecastPeriod {
+    /// Average power over the period (kW)
+    #[serde(alias = "pv_estimate")]
+    pv_power_rooftop: f32,
+    period_end: DateTime<Utc>,
+    /// ISO 8601 duration, e.g. `PT30M`
+    period: String,
+}
+
+/// Length of an ISO 8601 duration like `PT30M` or `PT1H`
+fn parse_period(period: &str) -> Option<Duration> {
+    let time = period.strip_prefix("PT")?;
+    if let Some(minutes) = time.strip_suffix('M') {
+        return minutes.parse().ok().map(Duration::minutes);
+    }
+    time.strip_suffix('H')?.parse().ok().map(Duration::hours)
+}
+
+/// Solcast measures azimuth from north with east negative: -90 = east, 90 = west
+fn solcast_azimuth(compass: f32) -> f32 {
+    let azimuth = compass.rem_euclid(360.0);
+    if azimuth > 180.0 {
+        360.0 - azimuth
+    } else {
+        -azimuth
+    }
+}
+
+/// Add a Solcast forecast response to `sums`
+fn add_forecast(json: &str, sums: &mut BlockSums) -> Result<()> {
+    let response: ForecastResponse =
+        serde_json::from_str(json).context("Failed to parse Solcast response")?;
+    for forecast in &response.forecasts {
+        let length = parse_period(&forecast.period)
+            .with_context(|| format!("Unsupported Solcast period {}", forecast.period))?;
+        #[expect(clippy::cast_precision_loss)]
+        let kwh = forecast.pv_power_rooftop.max(0.0) * length.num_minutes() as f32 / 60.0;
+        sums.add_period(forecast.period_end - length, forecast.period_end, kwh);
+    }
+    Ok(())
+}
+
+/// Solcast forecast adapter implementing SolarForecastDataSource
+pub struct SolcastAdapter {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    latitude: f64,
+    longitude: f64,
+    strings: Vec<PvString>,
+    daily_limit: u32,
+    cache: ForecastCache,
+}
+
+impl std::fmt::Debug for SolcastAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SolcastAdapter")
+            .field("strings", &self.strings)
+            .field("daily_limit", &self.daily_limit)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SolcastAdapter {
+    /// Create an adapter forecasting `strings` at the given location
+    pub fn new(
+        api_key: impl Into<String>,
+        latitude: f64,
+        longitude: f64,
+        strings: Vec<PvString>,
+        daily_limit: u32,
+    ) -> Self {
+        Self::with_base_url(
+            DEFAULT_BASE_URL,
+            api_key,
+            latitude,
+            longitude,
+            strings,
+            daily_limit,
+        )
+    }
+
+    /// Create an adapter reading from another API URL
+    pub fn with_base_url(
+        base_url: impl Into<String>,
+        api_key: impl Into<String>,
+        latitude: f64,
+        longitude: f64,
+        strings: Vec<PvString>,
+        daily_limit: u32,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            api_key: api_key.into(),
+            latitude,
+            longitude,
+            strings,
+            daily_limit: daily_limit.max(1),
+            cache: ForecastCache::default(),
+        }
+    }
+
+    /// Time between refreshes that keeps all strings within the daily limit
+    fn refresh_interval(&self) -> Duration {
+        let calls = i32::try_from(self.strings.len().max(1)).unwrap_or(i32::MAX);
+        let limit = i32::try_from(self.daily_limit).unwrap_or(i32::MAX);
+        Duration::minutes(24 * 60) * calls / limit
+    }
+
+    async fn fetch(&self) -> Result<Fetched> {
+        let url = format!("{}/data/forecast/rooftop_pv_power", self.base_url);
+        let mut sums = BlockSums::default();
+
+        for string in &self.strings {
+            debug!("☀️ [SOLCAST] Downloading forecast for {string:?}");
+            let response = self
+                .client
+                .get(&url)
+                .bearer_auth(&self.api_key)
+                .query(&[
+                    ("latitude", self.latitude.to_string()),
+                    ("longitude", self.longitude.to_string()),
+                    ("capacity", string.kwp.to_string()),
+                    ("tilt", string.tilt.to_string()),
+                    ("azimuth", solcast_azimuth(string.azimuth).to_string()),
+                    ("period", "PT30M".to_owned()),
+                    ("hours", "48".to_owned()),
+                    ("format", "json".to_owned()),
+                ])
+                .send()
+                .await
+                .context("Failed to send request to Solcast")?;
+
+            // The daily quota resets at midnight UTC
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                let tomorrow = Utc::now().date_naive().succ_opt().unwrap_or_default();
+                return Ok(Fetched::RateLimited(
+                    tomorrow.and_time(chrono::NaiveTime::MIN).and_utc(),
+                ));
+            }
+            let body = response
+                .error_for_status()
+                .context("Solcast request failed")?
+                .text()
+                .await
+                .context("Failed to read Solcast response")?;
+            add_forecast(&body, &mut sums)?;
+        }
+
+        let blocks = sums.into_blocks();
+        info!("✅ [SOLCAST] Fetched {} forecast blocks", blocks.len());
+        Ok(Fetched::Blocks(blocks))
+    }
+}
+
+#[async_trait]
+impl SolarForecastDataSource for SolcastAdapter {
+    async fn read_forecast(&self) -> Result<Vec<SolarForecastBlock>> {
+        let now = Utc::now();
+        if let Some(blocks) = self.cache.fresh(now, self.refresh_interval()) {
+            return Ok(blocks);
+        }
+        let fetched = self.fetch().await;
+        self.cache.update(fetched, now)
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        // A request just to check would use up the daily quota
+        Ok(self.cache.has_forecast())
+    }
+
+    fn name(&self) -> &str {
+        "Solcast"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+
+    #[test]
+    fn test_azimuth_is_converted_to_solcast_convention() {
+        assert!((solcast_azimuth(90.0) + 90.0).abs() < 1e-6);
+        assert!((solcast_azimuth(270.0) - 90.0).abs() < 1e-6);
+        assert!((solcast_azimuth(180.0).abs() - 180.0).abs() < 1e-6);
+        assert!(solcast_azimuth(0.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_refresh_interval_respects_daily_limit() {
+        let string = PvString {
+            azimuth: 180.0,
+            tilt: 35.0,
+            kwp: 5.0,
+        };
+        let adapter = SolcastAdapter::new("key", 50.0, 14.0, vec![string, string], 10);
+
+        assert_eq!(adapter.refresh_interval(), Duration::minutes(288));
+    }
+
+    #[tokio::test]
+    async fn test_strings_are_summed_into_blocks() {
+        let mut server = Server::new_async().await;
+        let body = json!({
+            "forecasts": [{
+                "pv_power_rooftop": 2.0,
+                "period_end": "2025-06-01T10:30:00Z",
+                "period": "PT30M"
+            }]
+        })
+        .to_string();
+        let mock = server
+            .mock("GET", "/data/forecast/rooftop_pv_power")
+            .match_header("authorization", "Bearer key")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("capacity".into(), "5".into()),
+                Matcher::UrlEncoded("format".into(), "json".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body)
+            .expect(2)
+            .create_async()
+            .await;
+        let string = PvString {
+            azimuth: 180.0,
+            tilt: 35.0,
+            kwp: 5.0,
+        };
+
+        let adapter = SolcastAdapter::with_base_url(
+            server.url(),
+            "key",
+            50.0,
+            14.0,
+            vec![string, string],
+            10,
+        );
+        let blocks = adapter.read_forecast().await.unwrap();
+        // Served from the cache the second time
+        adapter.read_forecast().await.unwrap();
+
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(
+            blocks[0].block_start,
+            "2025-06-01T10:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        // 2 kW for 15 minutes from each of the two strings
+        assert!((blocks[0].kwh - 1.0).abs() < 1e-6);
+        mock.assert_async().await;
+    }
+}
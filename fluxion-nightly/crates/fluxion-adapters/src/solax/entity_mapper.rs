@@ -159,6 +159,10 @@ impl VendorEntityMapper for SolaxEntityMapper {
         format!("number.{}_export_control_user_limit", inverter_id)
     }
 
+    fn get_charge_current_limit_entity(&self, inverter_id: &str) -> Option<String> {
+        Some(format!("number.{}_battery_charge_max_current", inverter_id))
+    }
+
     // ============= Extended PV (Optional) =============
 
     fn get_pv1_power_entity(&self, inverter_id: &str) -> Option<String> {
@@ -440,6 +444,10 @@ impl VendorEntityMapper for SolaxUltraEntityMapper {
         SolaxEntityMapper::new().get_export_limit_entity(inverter_id)
     }
 
+    fn get_charge_current_limit_entity(&self, inverter_id: &str) -> Option<String> {
+        SolaxEntityMapper::new().get_charge_current_limit_entity(inverter_id)
+    }
+
     // All optional entity methods delegate to standard Solax
     fn get_pv1_power_entity(&self, inverter_id: &str) -> Option<String> {
         // Solax Ultra uses same naming
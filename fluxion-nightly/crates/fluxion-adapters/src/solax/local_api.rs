@@ -0,0 +1,573 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Direct Solax local API (Pocket WiFi / LAN dongle) inverter source.
+//!
+//! The dongle answers `POST http://<dongle>/` with the form
+//! `optType=ReadRealTimeData&pwd=<registration number>` with a JSON object whose
+//! `Data` array holds the inverter's real-time registers. Mode changes are sent
+//! as `optType=setReg` with the charger use mode and manual mode registers, the
+//! same values the Solax HA integration writes through its select entities.
+//!
+//! The real-time data does not include the work mode, so the mode reported by
+//! [`SolaxLocalApiAdapter`] is the last one it wrote (Self Use until then).
+
+use super::modes::SolaxChargerUseMode;
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use fluxion_core::{
+    GenericInverterState, InverterCommand, InverterDataSource, InverterOperationMode,
+    SharedDebugMode,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// `type` reported by X3-Hybrid-G4 inverters, the layout [`RealTimeData`] decodes
+const X3_HYBRID_G4: u16 = 14;
+
+/// Holding registers written through `setReg`
+const CHARGER_USE_MODE_REGISTER: u16 = 0x1F;
+const MANUAL_MODE_REGISTER: u16 = 0x20;
+const EXPORT_LIMIT_REGISTER: u16 = 0x42;
+
+/// Manual mode register values
+const MANUAL_STOP: u16 = 0;
+const MANUAL_FORCE_CHARGE: u16 = 1;
+const MANUAL_FORCE_DISCHARGE: u16 = 2;
+
+/// Run modes of a faulted inverter
+const RUN_MODE_FAULT: u16 = 3;
+const RUN_MODE_PERMANENT_FAULT: u16 = 4;
+
+/// Solax local API settings (`[solax_local]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolaxLocalConfig {
+    pub enabled: bool,
+    pub devices: Vec<SolaxLocalDeviceConfig>,
+    /// How long a reading is reused before the dongle is asked again
+    pub poll_interval_secs: u64,
+    pub timeout_ms: u64,
+}
+
+impl Default for SolaxLocalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            devices: Vec::new(),
+            poll_interval_secs: 5,
+            timeout_ms: 5000,
+        }
+    }
+}
+
+/// One dongle, mapped to a configured inverter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolaxLocalDeviceConfig {
+    /// ID of the inverter in `[[inverters]]` this dongle serves
+    pub inverter_id: String,
+    /// Dongle address, e.g. `192.168.1.60` or `http://192.168.1.60`
+    pub host: String,
+    /// Registration number printed on the dongle, the API password
+    pub serial: String,
+}
+
+impl SolaxLocalConfig {
+    /// Check the settings
+    ///
+    /// # Errors
+    /// Describes the first invalid setting
+    pub fn validate(&self) -> Result<(), String> {
+        if self.devices.is_empty() {
+            return Err("at least one device is required".to_owned());
+        }
+        let mut ids = HashSet::new();
+        for device in &self.devices {
+            if device.inverter_id.is_empty() {
+                return Err("device inverter_id cannot be empty".to_owned());
+            }
+            if !ids.insert(device.inverter_id.as_str()) {
+                return Err(format!(
+                    "inverter '{}' has more than one device",
+                    device.inverter_id
+                ));
+            }
+            if device.host.trim().is_empty() || device.serial.trim().is_empty() {
+                return Err(format!(
+                    "device '{}' needs a host and a serial",
+                    device.inverter_id
+                ));
+            }
+        }
+        if !(1..=3600).contains(&self.poll_interval_secs) {
+            return Err("poll_interval_secs must be between 1 and 3600".to_owned());
+        }
+        if !(100..=60_000).contains(&self.timeout_ms) {
+            return Err("timeout_ms must be between 100 and 60000".to_owned());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RealTimeResponse {
+    #[serde(rename = "type")]
+    inverter_type: u16,
+    #[serde(rename = "Data")]
+    data: Vec<u16>,
+}
+
+/// Real-time registers in the X3-Hybrid-G4 layout
+struct RealTimeData<'a>(&'a [u16]);
+
+impl RealTimeData<'_> {
+    fn unsigned(&self, index: usize) -> Option<f32> {
+        self.0.get(index).map(|v| f32::from(*v))
+    }
+
+    fn signed(&self, index: usize) -> Option<f32> {
+        self.0
+            .get(index)
+            .map(|v| f32::from(i16::from_be_bytes(v.to_be_bytes())))
+    }
+
+    fn scaled(&self, index: usize, divisor: f32) -> Option<f32> {
+        self.unsigned(index).map(|v| v / divisor)
+    }
+
+    fn signed_scaled(&self, index: usize, divisor: f32) -> Option<f32> {
+        self.signed(index).map(|v| v / divisor)
+    }
+
+    /// 32-bit value stored low word first
+    fn pair(&self, low: usize) -> Option<u32> {
+        let low_word = *self.0.get(low)?;
+        let high_word = *self.0.get(low + 1)?;
+        Some(u32::from(high_word) << 16 | u32::from(low_word))
+    }
+
+    #[expect(clippy::cast_precision_loss, reason = "energy counters fit f32")]
+    fn pair_scaled(&self, low: usize, divisor: f32) -> Option<f32> {
+        self.pair(low).map(|v| v as f32 / divisor)
+    }
+
+    #[expect(clippy::cast_precision_loss, reason = "power values fit f32")]
+    fn signed_pair(&self, low: usize) -> Option<f32> {
+        self.pair(low)
+            .map(|v| i32::from_be_bytes(v.to_be_bytes()) as f32)
+    }
+
+    fn state(
+        &self,
+        inverter_id: &str,
+        work_mode: InverterOperationMode,
+    ) -> Result<GenericInverterState> {
+        let battery_soc = self
+            .unsigned(103)
+            .context("real-time data has no battery SOC")?;
+        let pv1 = self.unsigned(14);
+        let pv2 = self.unsigned(15);
+        let pv_power_w = pv1.unwrap_or_default() + pv2.unwrap_or_default();
+        // Feed-in power: positive = export, as FluxION expects
+        let grid_power_w = self.signed_pair(34).unwrap_or_default();
+        // Positive = charging
+        let battery_power_w = self.signed(41).unwrap_or_default();
+        let phase_power = [self.signed(6), self.signed(7), self.signed(8)];
+
+        Ok(GenericInverterState {
+            inverter_id: inverter_id.to_owned(),
+            battery_soc,
+            work_mode,
+            grid_power_w,
+            battery_power_w,
+            pv_power_w,
+            online: self
+                .0
+                .get(19)
+                .is_some_and(|m| *m != RUN_MODE_FAULT && *m != RUN_MODE_PERMANENT_FAULT),
+            house_load_w: Some((pv_power_w - battery_power_w - grid_power_w).max(0.0)),
+            grid_import_w: Some((-grid_power_w).max(0.0)),
+            grid_export_w: Some(grid_power_w.max(0.0)),
+            inverter_frequency_hz: self.scaled(16, 100.0),
+            inverter_power_w: Some(phase_power.iter().flatten().sum()),
+            pv1_power_w: pv1,
+            pv2_power_w: pv2,
+            l1_voltage_v: self.scaled(0, 10.0),
+            l1_current_a: self.signed_scaled(3, 10.0),
+            l1_power_w: phase_power[0],
+            l2_voltage_v: self.scaled(1, 10.0),
+            l2_current_a: self.signed_scaled(4, 10.0),
+            l2_power_w: phase_power[1],
+            l3_voltage_v: self.scaled(2, 10.0),
+            l3_current_a: self.signed_scaled(5, 10.0),
+            l3_power_w: phase_power[2],
+            battery_voltage_v: self.scaled(39, 100.0),
+            battery_current_a: self.signed_scaled(40, 100.0),
+            battery_output_energy_today_kwh: self.scaled(78, 10.0),
+            battery_input_energy_today_kwh: self.scaled(79, 10.0),
+            grid_export_total_kwh: self.pair_scaled(86, 100.0),
+            grid_import_total_kwh: self.pair_scaled(88, 100.0),
+            total_yield_kwh: self.pair_scaled(68, 10.0),
+            today_yield_kwh: self.scaled(70, 10.0),
+            inverter_temperature_c: self.signed(54),
+            battery_temperature_c: self.signed(105),
+            ..GenericInverterState::default()
+        })
+    }
+}
+
+#[derive(Debug)]
+struct Dongle {
+    url: String,
+    serial: String,
+    last_poll: Mutex<Option<(Instant, GenericInverterState)>>,
+    /// Last mode written; the real-time data does not report it
+    mode: Mutex<InverterOperationMode>,
+}
+
+/// Inverter data source talking to Solax dongles directly
+#[derive(Debug)]
+pub struct SolaxLocalApiAdapter {
+    client: reqwest::Client,
+    dongles: HashMap<String, Dongle>,
+    poll_interval: Duration,
+    debug_mode: SharedDebugMode,
+}
+
+impl SolaxLocalApiAdapter {
+    /// One dongle per configured device; writes are skipped while `debug_mode` is on
+    ///
+    /// # Errors
+    /// Returns error if the HTTP client cannot be created
+    pub fn new(config: &SolaxLocalConfig, debug_mode: SharedDebugMode) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(config.timeout_ms))
+            .build()
+            .context("Failed to create HTTP client")?;
+        let dongles = config
+            .devices
+            .iter()
+            .map(|d| {
+                let url = if d.host.starts_with("http://") || d.host.starts_with("https://") {
+                    d.host.clone()
+                } else {
+                    format!("http://{}/", d.host)
+                };
+                let dongle = Dongle {
+                    url,
+                    serial: d.serial.clone(),
+                    last_poll: Mutex::default(),
+                    mode: Mutex::new(InverterOperationMode::SelfUse),
+                };
+                (d.inverter_id.clone(), dongle)
+            })
+            .collect();
+        Ok(Self {
+            client,
+            dongles,
+            poll_interval: Duration::from_secs(config.poll_interval_secs),
+            debug_mode,
+        })
+    }
+
+    fn dongle(&self, inverter_id: &str) -> Result<&Dongle> {
+        self.dongles
+            .get(inverter_id)
+            .with_context(|| format!("no Solax dongle configured for {inverter_id}"))
+    }
+
+    async fn poll(&self, inverter_id: &str, dongle: &Dongle) -> Result<GenericInverterState> {
+        let response: RealTimeResponse = self
+            .client
+            .post(&dongle.url)
+            .form(&[("optType", "ReadRealTimeData"), ("pwd", &dongle.serial)])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("Solax dongle of {inverter_id} unreachable"))?
+            .json()
+            .await
+            .with_context(|| format!("Unexpected real-time data from {inverter_id}"))?;
+        if response.inverter_type != X3_HYBRID_G4 {
+            bail!(
+                "{inverter_id} is inverter type {}, the local API adapter supports X3-Hybrid-G4 ({X3_HYBRID_G4}) only",
+                response.inverter_type
+            );
+        }
+        RealTimeData(&response.data).state(inverter_id, *dongle.mode.lock())
+    }
+
+    /// Write `registers`; returns false when debug mode only logged them
+    async fn set_registers(
+        &self,
+        inverter_id: &str,
+        dongle: &Dongle,
+        registers: &[(u16, u16)],
+    ) -> Result<bool> {
+        let data = serde_json::json!({
+            "num": registers.len(),
+            "Data": registers
+                .iter()
+                .map(|(reg, val)| serde_json::json!({"reg": reg, "val": val.to_string()}))
+                .collect::<Vec<_>>(),
+        });
+        if self.debug_mode.is_enabled() {
+            info!("🔍 DEBUG MODE: Would write Solax registers of {inverter_id}: {data}");
+            return Ok(false);
+        }
+
+        self.client
+            .post(&dongle.url)
+            .form(&[
+                ("optType", "setReg"),
+                ("pwd", &dongle.serial),
+                ("data", &data.to_string()),
+            ])
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .with_context(|| format!("Failed to write Solax registers of {inverter_id}"))?;
+        *dongle.last_poll.lock() = None;
+        debug!("Wrote Solax registers of {inverter_id}: {data}");
+        Ok(true)
+    }
+}
+
+/// Charger use mode and, for manual mode, the manual mode register values
+fn mode_registers(mode: InverterOperationMode) -> Vec<(u16, u16)> {
+    let manual = |value| {
+        vec![
+            (
+                CHARGER_USE_MODE_REGISTER,
+                use_mode(SolaxChargerUseMode::ManualMode),
+            ),
+            (MANUAL_MODE_REGISTER, value),
+        ]
+    };
+    match mode {
+        InverterOperationMode::SelfUse => vec![(
+            CHARGER_USE_MODE_REGISTER,
+            use_mode(SolaxChargerUseMode::SelfUseMode),
+        )],
+        InverterOperationMode::BackUpMode => vec![(
+            CHARGER_USE_MODE_REGISTER,
+            use_mode(SolaxChargerUseMode::BackUpMode),
+        )],
+        InverterOperationMode::ForceCharge => manual(MANUAL_FORCE_CHARGE),
+        InverterOperationMode::ForceDischarge => manual(MANUAL_FORCE_DISCHARGE),
+        InverterOperationMode::NoChargeNoDischarge => manual(MANUAL_STOP),
+    }
+}
+
+fn use_mode(mode: SolaxChargerUseMode) -> u16 {
+    u16::try_from(mode.to_i32()).unwrap_or_default()
+}
+
+#[async_trait]
+impl InverterDataSource for SolaxLocalApiAdapter {
+    async fn read_state(&self, inverter_id: &str) -> Result<GenericInverterState> {
+        let dongle = self.dongle(inverter_id)?;
+        if let Some((at, state)) = dongle.last_poll.lock().as_ref()
+            && at.elapsed() < self.poll_interval
+        {
+            return Ok(state.clone());
+        }
+
+        let state = self.poll(inverter_id, dongle).await?;
+        *dongle.last_poll.lock() = Some((Instant::now(), state.clone()));
+        Ok(state)
+    }
+
+    async fn write_command(&self, inverter_id: &str, command: &InverterCommand) -> Result<()> {
+        let dongle = self.dongle(inverter_id)?;
+        match command {
+            InverterCommand::SetMode(mode) => {
+                if self
+                    .set_registers(inverter_id, dongle, &mode_registers(*mode))
+                    .await?
+                {
+                    *dongle.mode.lock() = *mode;
+                    info!("✅ {inverter_id} set to {mode:?} over the Solax local API");
+                }
+                Ok(())
+            }
+            InverterCommand::SetExportLimit(watts) => {
+                let watts = u16::try_from(*watts)
+                    .with_context(|| format!("export limit {watts} W out of range"))?;
+                self.set_registers(inverter_id, dongle, &[(EXPORT_LIMIT_REGISTER, watts)])
+                    .await
+                    .map(|_| ())
+            }
+            InverterCommand::SetChargePowerLimit(_) => {
+                bail!("charge power limits are not supported by the Solax local API")
+            }
+        }
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        for (inverter_id, dongle) in &self.dongles {
+            self.poll(inverter_id, dongle).await?;
+        }
+        Ok(true)
+    }
+
+    fn name(&self) -> &'static str {
+        "SolaxLocalApi"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    fn raw(value: i16) -> u16 {
+        u16::from_be_bytes(value.to_be_bytes())
+    }
+
+    fn real_time_json() -> String {
+        let mut data = vec![0_u16; 150];
+        data[0] = 2301; // L1 230.1 V
+        data[6] = 1000;
+        data[7] = 1000;
+        data[8] = 1000;
+        data[14] = 3000; // PV1
+        data[15] = 1500; // PV2
+        data[16] = 5001;
+        data[19] = 2; // Normal
+        data[34] = 500; // Exporting 500 W
+        data[41] = raw(1000); // Charging
+        data[103] = 64;
+        data[105] = raw(-3);
+        // Yield total 100000 (0x1_86A0) in 0.1 kWh, low word first
+        data[68] = 0x86A0;
+        data[69] = 1;
+        serde_json::json!({"sn": "SXXXXXXXXX", "ver": "3.008.10", "type": 14, "Data": data})
+            .to_string()
+    }
+
+    fn adapter(url: &str, debug_mode: bool) -> SolaxLocalApiAdapter {
+        let config = SolaxLocalConfig {
+            enabled: true,
+            devices: vec![SolaxLocalDeviceConfig {
+                inverter_id: "inv1".to_owned(),
+                host: url.to_owned(),
+                serial: "SXXXXXXXXX".to_owned(),
+            }],
+            ..SolaxLocalConfig::default()
+        };
+        SolaxLocalApiAdapter::new(&config, SharedDebugMode::new(debug_mode)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_read_state_decodes_real_time_data_and_caches_it() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("optType".into(), "ReadRealTimeData".into()),
+                Matcher::UrlEncoded("pwd".into(), "SXXXXXXXXX".into()),
+            ]))
+            .with_body(real_time_json())
+            .expect(1)
+            .create_async()
+            .await;
+
+        let adapter = adapter(&server.url(), false);
+        let state = adapter.read_state("inv1").await.unwrap();
+        adapter.read_state("inv1").await.unwrap();
+        mock.assert_async().await;
+
+        assert!((state.battery_soc - 64.0).abs() < f32::EPSILON);
+        assert!((state.pv_power_w - 4500.0).abs() < f32::EPSILON);
+        assert!((state.grid_power_w - 500.0).abs() < f32::EPSILON);
+        assert!((state.battery_power_w - 1000.0).abs() < f32::EPSILON);
+        assert_eq!(state.house_load_w, Some(3000.0));
+        assert_eq!(state.inverter_power_w, Some(3000.0));
+        assert_eq!(state.battery_temperature_c, Some(-3.0));
+        assert_eq!(state.total_yield_kwh, Some(10000.0));
+        assert_eq!(state.work_mode, InverterOperationMode::SelfUse);
+        assert!(state.online);
+    }
+
+    #[tokio::test]
+    async fn test_force_charge_writes_manual_mode_registers() {
+        let mut server = Server::new_async().await;
+        let data = r#"{"Data":[{"reg":31,"val":"3"},{"reg":32,"val":"1"}],"num":2}"#;
+        let write = server
+            .mock("POST", "/")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("optType".into(), "setReg".into()),
+                Matcher::UrlEncoded("data".into(), data.into()),
+            ]))
+            .with_body("Y")
+            .create_async()
+            .await;
+        let read = server
+            .mock("POST", "/")
+            .match_body(Matcher::UrlEncoded(
+                "optType".into(),
+                "ReadRealTimeData".into(),
+            ))
+            .with_body(real_time_json())
+            .create_async()
+            .await;
+
+        let adapter = adapter(&server.url(), false);
+        adapter
+            .write_command(
+                "inv1",
+                &InverterCommand::SetMode(InverterOperationMode::ForceCharge),
+            )
+            .await
+            .unwrap();
+        write.assert_async().await;
+
+        let state = adapter.read_state("inv1").await.unwrap();
+        read.assert_async().await;
+        assert_eq!(state.work_mode, InverterOperationMode::ForceCharge);
+    }
+
+    #[tokio::test]
+    async fn test_debug_mode_never_writes() {
+        let mut server = Server::new_async().await;
+        let mock = server.mock("POST", "/").expect(0).create_async().await;
+
+        adapter(&server.url(), true)
+            .write_command(
+                "inv1",
+                &InverterCommand::SetMode(InverterOperationMode::ForceDischarge),
+            )
+            .await
+            .unwrap();
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_inverter_type_is_an_error() {
+        let mut server = Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .with_body(r#"{"sn": "S", "ver": "3", "type": 4, "Data": [0, 0]}"#)
+            .create_async()
+            .await;
+
+        let error = adapter(&server.url(), false)
+            .read_state("inv1")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("X3-Hybrid-G4"));
+    }
+}
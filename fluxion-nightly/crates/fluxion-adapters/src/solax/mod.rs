@@ -11,27 +11,9 @@
 // For commercial licensing, please contact: info@solare.cz
 
 mod entity_mapper;
+mod local_api;
 mod modes;
 
 pub use entity_mapper::{SolaxEntityMapper, SolaxUltraEntityMapper};
+pub use local_api::{SolaxLocalApiAdapter, SolaxLocalConfig, SolaxLocalDeviceConfig};
 pub use modes::{SolaxChargerUseMode, SolaxManualMode};
-
-use fluxion_core::{InverterType, VendorEntityMapper};
-use std::sync::Arc;
-
-/// Factory function to create the appropriate entity mapper for a given inverter type
-///
-/// # Arguments
-/// * `inverter_type` - The type of inverter to create a mapper for
-///
-/// # Returns
-/// An Arc-wrapped VendorEntityMapper trait object
-///
-/// # Panics
-/// Panics if an unsupported inverter type is passed (should never happen with the enum)
-pub fn create_entity_mapper(inverter_type: InverterType) -> Arc<dyn VendorEntityMapper> {
-    match inverter_type {
-        InverterType::Solax => Arc::new(SolaxEntityMapper::new()),
-        InverterType::SolaxUltra => Arc::new(SolaxUltraEntityMapper::new()),
-    }
-}
@@ -0,0 +1,487 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Tibber dynamic tariffs over the Tibber GraphQL API.
+//!
+//! [`TibberPriceAdapter`] reads the prices of the user's own contract, i.e.
+//! the `total` Tibber bills including energy tax, VAT and grid fees where
+//! Tibber includes them. [`TibberConsumptionAdapter`] reads metered
+//! consumption (near real-time with a Tibber Pulse) as an alternative to the
+//! Home Assistant consumption sensor.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use fluxion_core::traits::{ConsumptionHistoryDataSource, HistoryDataPoint};
+use fluxion_core::{PriceDataSource, SpotPriceData, TimeBlockPrice};
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use tracing::{debug, info};
+
+/// Tibber GraphQL endpoint
+pub const DEFAULT_API_URL: &str = "https://api.tibber.com/v1-beta/gql";
+
+/// Block length the scheduler works with (minutes)
+const BLOCK_MINUTES: i64 = 15;
+
+/// Longest consumption history Tibber returns in one request (hours)
+const MAX_CONSUMPTION_HOURS: i64 = 24 * 62;
+
+const PRICE_QUERY: &str = "{ viewer { homes { id currentSubscription { \
+    priceInfo(resolution: QUARTER_HOURLY) { \
+    today { total startsAt currency } tomorrow { total startsAt currency } } } } } }";
+
+const CONSUMPTION_QUERY: &str = "query Consumption($last: Int!) { viewer { homes { id \
+    consumption(resolution: HOURLY, last: $last) { nodes { from to consumption } } } } }";
+
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Viewer<H> {
+    viewer: Homes<H>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Homes<H> {
+    homes: Vec<H>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PriceHome {
+    id: String,
+    current_subscription: Option<Subscription>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Subscription {
+    price_info: Option<PriceInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PriceInfo {
+    #[serde(default)]
+    today: Vec<PriceEntry>,
+    #[serde(default)]
+    tomorrow: Vec<PriceEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PriceEntry {
+    total: f32,
+    starts_at: DateTime<FixedOffset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConsumptionHome {
+    id: String,
+    consumption: Option<Connection>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Connection {
+    #[serde(default)]
+    nodes: Vec<ConsumptionNode>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConsumptionNode {
+    from: DateTime<FixedOffset>,
+    consumption: Option<f32>,
+}
+
+trait HasId {
+    fn id(&self) -> &str;
+}
+
+impl HasId for PriceHome {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+impl HasId for ConsumptionHome {
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+/// Connection to the Tibber API for one home
+#[derive(Clone)]
+struct TibberClient {
+    client: reqwest::Client,
+    api_url: String,
+    token: String,
+    home_id: Option<String>,
+}
+
+impl TibberClient {
+    async fn query<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<T> {
+        let response: GraphQlResponse<T> = self
+            .client
+            .post(&self.api_url)
+            .bearer_auth(&self.token)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .context("Failed to send request to Tibber")?
+            .error_for_status()
+            .context("Tibber request failed")?
+            .json()
+            .await
+            .context("Failed to parse Tibber response")?;
+
+        if let Some(error) = response.errors.first() {
+            anyhow::bail!("Tibber API error: {}", error.message);
+        }
+        response.data.context("Tibber response has no data")
+    }
+
+    /// The configured home, or the first home of the account
+    fn select_home<H: HasId>(&self, homes: Vec<H>) -> Result<H> {
+        match &self.home_id {
+            Some(id) => homes
+                .into_iter()
+                .find(|home| home.id() == id)
+                .with_context(|| format!("Tibber home {id} not found")),
+            None => homes
+                .into_iter()
+                .next()
+                .context("Tibber account has no homes"),
+        }
+    }
+}
+
+/// Split price entries into 15-minute blocks
+///
+/// Each entry lasts until the next one starts; hourly prices become four blocks.
+fn expand_prices(entries: &[PriceEntry]) -> Vec<TimeBlockPrice> {
+    let mut blocks = Vec::with_capacity(entries.len() * 4);
+    let mut entry_minutes = 60;
+    for (i, entry) in entries.iter().enumerate() {
+        if let Some(next) = entries.get(i + 1) {
+            entry_minutes = (next.starts_at - entry.starts_at)
+                .num_minutes()
+                .clamp(BLOCK_MINUTES, 60);
+        }
+        let start = entry.starts_at.with_timezone(&Utc);
+        for offset in (0..entry_minutes).step_by(15) {
+            blocks.push(TimeBlockPrice {
+                block_start: start + Duration::minutes(offset),
+                duration_minutes: 15,
+                price_czk_per_kwh: entry.total,
+                // Tibber totals already include taxes and fees
+                effective_price_czk_per_kwh: entry.total,
+                spot_sell_price_czk_per_kwh: None,
+            });
+        }
+    }
+    blocks
+}
+
+/// Running consumption total that resets at local midnight
+///
+/// Matches the daily energy sensors FluxION reads from Home Assistant: each
+/// point is stamped with the start of its hour and holds the total so far.
+fn cumulative_daily(nodes: &[ConsumptionNode]) -> Vec<HistoryDataPoint> {
+    let mut points = Vec::with_capacity(nodes.len());
+    let mut day = None;
+    let mut total = 0.0;
+    for node in nodes {
+        let Some(consumption) = node.consumption else {
+            continue;
+        };
+        if day != Some(node.from.date_naive()) {
+            day = Some(node.from.date_naive());
+            total = 0.0;
+        }
+        total += consumption;
+        points.push(HistoryDataPoint {
+            timestamp: node.from.with_timezone(&Utc),
+            value: total,
+        });
+    }
+    points
+}
+
+/// Tibber price adapter implementing PriceDataSource
+#[derive(Clone)]
+pub struct TibberPriceAdapter {
+    client: TibberClient,
+}
+
+impl std::fmt::Debug for TibberPriceAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TibberPriceAdapter")
+            .field("home_id", &self.client.home_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TibberPriceAdapter {
+    /// Create an adapter for the home `home_id` (first home when `None`)
+    pub fn new(token: impl Into<String>, home_id: Option<String>) -> Self {
+        Self::with_api_url(DEFAULT_API_URL, token, home_id)
+    }
+
+    /// Create an adapter talking to another GraphQL endpoint
+    pub fn with_api_url(
+        api_url: impl Into<String>,
+        token: impl Into<String>,
+        home_id: Option<String>,
+    ) -> Self {
+        Self {
+            client: TibberClient {
+                client: reqwest::Client::new(),
+                api_url: api_url.into(),
+                token: token.into(),
+                home_id,
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl PriceDataSource for TibberPriceAdapter {
+    async fn read_prices(&self) -> Result<SpotPriceData> {
+        debug!("💰 [TIBBER] Fetching price info");
+        let data: Viewer<PriceHome> = self.client.query(PRICE_QUERY, json!({})).await?;
+        let home = self.client.select_home(data.viewer.homes)?;
+        let price_info = home
+            .current_subscription
+            .and_then(|subscription| subscription.price_info)
+            .with_context(|| format!("Tibber home {} has no active subscription", home.id))?;
+        anyhow::ensure!(
+            !price_info.today.is_empty(),
+            "Tibber returned no prices for today"
+        );
+
+        let mut entries = price_info.today;
+        entries.extend(price_info.tomorrow);
+        let time_block_prices = expand_prices(&entries);
+        info!(
+            "✅ [TIBBER] Fetched {} price blocks",
+            time_block_prices.len()
+        );
+
+        let now = Utc::now();
+        Ok(SpotPriceData {
+            time_block_prices,
+            block_duration_minutes: 15,
+            fetched_at: now,
+            ha_last_updated: now,
+        })
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(self.read_prices().await.is_ok())
+    }
+
+    fn name(&self) -> &str {
+        "Tibber"
+    }
+}
+
+/// Tibber consumption adapter implementing ConsumptionHistoryDataSource
+///
+/// The entity id is ignored; consumption always comes from the Tibber home.
+/// Solar production is not metered by Tibber and comes back empty.
+#[derive(Clone)]
+pub struct TibberConsumptionAdapter {
+    client: TibberClient,
+    solar_entity: String,
+}
+
+impl std::fmt::Debug for TibberConsumptionAdapter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TibberConsumptionAdapter")
+            .field("home_id", &self.client.home_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TibberConsumptionAdapter {
+    /// Create an adapter for the home `home_id` (first home when `None`)
+    ///
+    /// History requests for `solar_entity` return no data.
+    pub fn new(
+        token: impl Into<String>,
+        home_id: Option<String>,
+        solar_entity: impl Into<String>,
+    ) -> Self {
+        Self::with_api_url(DEFAULT_API_URL, token, home_id, solar_entity)
+    }
+
+    /// Create an adapter talking to another GraphQL endpoint
+    pub fn with_api_url(
+        api_url: impl Into<String>,
+        token: impl Into<String>,
+        home_id: Option<String>,
+        solar_entity: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: TibberClient {
+                client: reqwest::Client::new(),
+                api_url: api_url.into(),
+                token: token.into(),
+                home_id,
+            },
+            solar_entity: solar_entity.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ConsumptionHistoryDataSource for TibberConsumptionAdapter {
+    async fn get_history(
+        &self,
+        entity_id: &str,
+        start_time: DateTime<Utc>,
+        end_time: Option<DateTime<Utc>>,
+    ) -> Result<Vec<HistoryDataPoint>> {
+        if entity_id == self.solar_entity {
+            return Ok(Vec::new());
+        }
+
+        // Tibber counts back from the last metered hour
+        let hours = (Utc::now() - start_time)
+            .num_hours()
+            .clamp(1, MAX_CONSUMPTION_HOURS);
+        let data: Viewer<ConsumptionHome> = self
+            .client
+            .query(CONSUMPTION_QUERY, json!({ "last": hours }))
+            .await?;
+        let home = self.client.select_home(data.viewer.homes)?;
+        let nodes = home.consumption.map(|c| c.nodes).unwrap_or_default();
+
+        let end_time = end_time.unwrap_or_else(Utc::now);
+        Ok(cumulative_daily(&nodes)
+            .into_iter()
+            .filter(|point| point.timestamp >= start_time && point.timestamp <= end_time)
+            .collect())
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        let data: Result<Viewer<ConsumptionHome>> = self
+            .client
+            .query(CONSUMPTION_QUERY, json!({ "last": 1 }))
+            .await;
+        Ok(data.is_ok_and(|data| self.client.select_home(data.viewer.homes).is_ok()))
+    }
+
+    fn name(&self) -> &str {
+        "TibberConsumption"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+
+    fn entry(starts_at: &str, total: f32) -> PriceEntry {
+        PriceEntry {
+            total,
+            starts_at: starts_at.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_hourly_prices_are_expanded_to_blocks() {
+        let entries = [
+            entry("2025-01-15T00:00:00+01:00", 0.25),
+            entry("2025-01-15T01:00:00+01:00", 0.30),
+        ];
+
+        let blocks = expand_prices(&entries);
+
+        assert_eq!(blocks.len(), 8);
+        assert_eq!(
+            blocks[0].block_start,
+            "2025-01-14T23:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(blocks[4].price_czk_per_kwh, 0.30);
+        assert_eq!(
+            blocks[7].block_start,
+            "2025-01-15T00:45:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_consumption_resets_at_local_midnight() {
+        let node = |from: &str, consumption| ConsumptionNode {
+            from: from.parse().unwrap(),
+            consumption: Some(consumption),
+        };
+        let nodes = [
+            node("2025-01-15T22:00:00+01:00", 1.0),
+            node("2025-01-15T23:00:00+01:00", 0.5),
+            node("2025-01-16T00:00:00+01:00", 0.25),
+        ];
+
+        let points = cumulative_daily(&nodes);
+
+        let values: Vec<f32> = points.iter().map(|p| p.value).collect();
+        assert_eq!(values, vec![1.0, 1.5, 0.25]);
+    }
+
+    #[tokio::test]
+    async fn test_prices_are_read_for_the_configured_home() {
+        let mut server = Server::new_async().await;
+        let body = json!({
+            "data": { "viewer": { "homes": [
+                { "id": "other", "currentSubscription": null },
+                { "id": "home-2", "currentSubscription": { "priceInfo": {
+                    "today": [
+                        { "total": 1.25, "startsAt": "2025-01-15T00:00:00.000+01:00", "currency": "SEK" },
+                        { "total": 1.5, "startsAt": "2025-01-15T00:15:00.000+01:00", "currency": "SEK" }
+                    ],
+                    "tomorrow": []
+                } } }
+            ] } }
+        });
+        let mock = server
+            .mock("POST", "/")
+            .match_header("authorization", "Bearer secret")
+            .match_body(Matcher::Regex("QUARTER_HOURLY".into()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(body.to_string())
+            .create_async()
+            .await;
+
+        let adapter =
+            TibberPriceAdapter::with_api_url(server.url(), "secret", Some("home-2".to_owned()));
+        let data = adapter.read_prices().await.unwrap();
+
+        assert_eq!(data.time_block_prices.len(), 2);
+        assert_eq!(data.time_block_prices[1].effective_price_czk_per_kwh, 1.5);
+        mock.assert_async().await;
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Hourly forecast of a Home Assistant weather entity.
+//!
+//! Read with the `weather.get_forecasts` service. Temperatures are taken as
+//! reported, so the entity is expected to use °C.
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use fluxion_core::{WeatherDataSource, WeatherPoint};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tracing::info;
+
+use crate::ha::HomeAssistantClient;
+
+#[derive(Debug, Deserialize)]
+struct EntityForecast {
+    #[serde(default)]
+    forecast: Vec<ForecastHour>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForecastHour {
+    datetime: DateTime<Utc>,
+    #[serde(default)]
+    temperature: Option<f32>,
+    #[serde(default)]
+    cloud_coverage: Option<f32>,
+}
+
+/// Forecast points of `entity_id` in a `weather.get_forecasts` response
+fn parse_forecast(response: &Value, entity_id: &str) -> Result<Vec<WeatherPoint>> {
+    let entity = response
+        .get(entity_id)
+        .with_context(|| format!("No forecast for {entity_id} in the response"))?;
+    let forecast = EntityForecast::deserialize(entity)
+        .with_context(|| format!("Failed to parse the forecast of {entity_id}"))?;
+    let mut points: Vec<WeatherPoint> = forecast
+        .forecast
+        .into_iter()
+        .map(|hour| WeatherPoint {
+            time: hour.datetime,
+            cloud_cover_pct: hour.cloud_coverage,
+            temperature_c: hour.temperature,
+        })
+        .collect();
+    points.sort_by_key(|point| point.time);
+    Ok(points)
+}
+
+/// Home Assistant weather entity implementing WeatherDataSource
+pub struct HaWeatherAdapter {
+    client: Arc<HomeAssistantClient>,
+    entity_id: String,
+}
+
+impl HaWeatherAdapter {
+    pub fn new(client: Arc<HomeAssistantClient>, entity_id: impl Into<String>) -> Self {
+        Self {
+            client,
+            entity_id: entity_id.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl WeatherDataSource for HaWeatherAdapter {
+    async fn read_forecast(&self) -> Result<Vec<WeatherPoint>> {
+        let response = self
+            .client
+            .call_service_with_response(
+                "weather.get_forecasts",
+                json!({ "entity_id": self.entity_id, "type": "hourly" }),
+            )
+            .await
+            .context("Failed to read the weather forecast from HA")?;
+
+        let points = parse_forecast(&response, &self.entity_id)?;
+        info!(
+            "✅ [HA WEATHER] Fetched {} forecast hours from {}",
+            points.len(),
+            self.entity_id
+        );
+        Ok(points)
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        self.client.ping().await.map_err(|e| anyhow::anyhow!(e))
+    }
+
+    fn name(&self) -> &str {
+        "HaWeather"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_hourly_forecast_is_read() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/api/services/weather/get_forecasts")
+            .match_query(mockito::Matcher::Regex("return_response".to_owned()))
+            .match_body(mockito::Matcher::Json(json!({
+                "entity_id": "weather.home",
+                "type": "hourly"
+            })))
+            .with_body(
+                json!({
+                    "changed_states": [],
+                    "service_response": {
+                        "weather.home": {
+                            "forecast": [
+                                {
+                                    "datetime": "2025-01-10T01:00:00+00:00",
+                                    "temperature": 1.0,
+                                    "condition": "sunny"
+                                },
+                                {
+                                    "datetime": "2025-01-10T00:00:00+00:00",
+                                    "temperature": -2.0,
+                                    "cloud_coverage": 80
+                                }
+                            ]
+                        }
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let client = Arc::new(HomeAssistantClient::new(server.url(), "token").unwrap());
+        let adapter = HaWeatherAdapter::new(client, "weather.home");
+
+        let points = adapter.read_forecast().await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].cloud_cover_pct, Some(80.0));
+        assert_eq!(points[0].temperature_c, Some(-2.0));
+        assert_eq!(points[1].cloud_cover_pct, None);
+    }
+}
@@ -0,0 +1,21 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Weather forecasts from a Home Assistant weather entity or Open-Meteo.
+//!
+//! Both return hourly cloud cover and temperature points for the planner.
+
+pub mod home_assistant;
+pub mod open_meteo;
+
+pub use home_assistant::HaWeatherAdapter;
+pub use open_meteo::OpenMeteoAdapter;
@@ -0,0 +1,169 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Open-Meteo hourly forecast, free for non-commercial use without an API key.
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use fluxion_core::{WeatherDataSource, WeatherPoint};
+use serde::Deserialize;
+use tracing::{debug, info};
+
+/// Open-Meteo API
+pub const DEFAULT_BASE_URL: &str = "https://api.open-meteo.com";
+
+/// Days requested, covering the price horizon
+const FORECAST_DAYS: u32 = 3;
+
+#[derive(Debug, Deserialize)]
+struct ForecastResponse {
+    hourly: HourlyForecast,
+}
+
+#[derive(Debug, Deserialize)]
+struct HourlyForecast {
+    /// Unix timestamps of the hours
+    time: Vec<i64>,
+    #[serde(default)]
+    temperature_2m: Vec<Option<f32>>,
+    #[serde(default)]
+    cloud_cover: Vec<Option<f32>>,
+}
+
+/// Forecast points of an Open-Meteo response
+fn parse_forecast(body: &str) -> Result<Vec<WeatherPoint>> {
+    let response: ForecastResponse =
+        serde_json::from_str(body).context("Failed to parse Open-Meteo response")?;
+    let hourly = response.hourly;
+    Ok(hourly
+        .time
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &timestamp)| {
+            Some(WeatherPoint {
+                time: DateTime::<Utc>::from_timestamp(timestamp, 0)?,
+                cloud_cover_pct: hourly.cloud_cover.get(index).copied().flatten(),
+                temperature_c: hourly.temperature_2m.get(index).copied().flatten(),
+            })
+        })
+        .collect())
+}
+
+/// Open-Meteo adapter implementing WeatherDataSource
+#[derive(Debug)]
+pub struct OpenMeteoAdapter {
+    client: reqwest::Client,
+    base_url: String,
+    latitude: f64,
+    longitude: f64,
+}
+
+impl OpenMeteoAdapter {
+    /// Create an adapter forecasting the weather at the given location
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self::with_base_url(DEFAULT_BASE_URL, latitude, longitude)
+    }
+
+    /// Create an adapter reading from another API URL
+    pub fn with_base_url(base_url: impl Into<String>, latitude: f64, longitude: f64) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_owned(),
+            latitude,
+            longitude,
+        }
+    }
+}
+
+#[async_trait]
+impl WeatherDataSource for OpenMeteoAdapter {
+    async fn read_forecast(&self) -> Result<Vec<WeatherPoint>> {
+        debug!(
+            "🌦️ [OPEN-METEO] Downloading forecast for {}, {}",
+            self.latitude, self.longitude
+        );
+        let body = self
+            .client
+            .get(format!("{}/v1/forecast", self.base_url))
+            .query(&[
+                ("latitude", self.latitude.to_string()),
+                ("longitude", self.longitude.to_string()),
+                ("hourly", "temperature_2m,cloud_cover".to_owned()),
+                ("forecast_days", FORECAST_DAYS.to_string()),
+                ("timeformat", "unixtime".to_owned()),
+            ])
+            .send()
+            .await
+            .context("Failed to send request to Open-Meteo")?
+            .error_for_status()
+            .context("Open-Meteo request failed")?
+            .text()
+            .await
+            .context("Failed to read Open-Meteo response")?;
+
+        let points = parse_forecast(&body)?;
+        info!("✅ [OPEN-METEO] Fetched {} forecast hours", points.len());
+        Ok(points)
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    fn name(&self) -> &str {
+        "Open-Meteo"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::{Matcher, Server};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_hourly_forecast_is_read() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/v1/forecast")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("latitude".into(), "50".into()),
+                Matcher::UrlEncoded("hourly".into(), "temperature_2m,cloud_cover".into()),
+            ]))
+            .with_body(
+                json!({
+                    "hourly": {
+                        "time": [1736467200, 1736470800],
+                        "temperature_2m": [-1.5, null],
+                        "cloud_cover": [90, 75]
+                    }
+                })
+                .to_string(),
+            )
+            .create_async()
+            .await;
+        let adapter = OpenMeteoAdapter::with_base_url(server.url(), 50.0, 14.0);
+
+        let points = adapter.read_forecast().await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(points.len(), 2);
+        assert_eq!(
+            points[0].time,
+            "2025-01-10T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(points[0].temperature_c, Some(-1.5));
+        assert_eq!(points[1].temperature_c, None);
+        assert_eq!(points[1].cloud_cover_pct, Some(75.0));
+    }
+}
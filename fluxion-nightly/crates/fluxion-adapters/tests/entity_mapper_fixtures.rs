@@ -0,0 +1,101 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Mapping coverage from recorded Home Assistant entity dumps.
+//!
+//! Every `fixtures/entity_mappers/<inverter-type>/<name>.json` is checked with
+//! the mapper for `<inverter-type>` (the kebab-case config value, e.g.
+//! `solax-ultra`). A fixture holds the entity prefix and the output of
+//! `GET /api/states`:
+//!
+//! ```json
+//! { "inverter_id": "solax", "states": [ ... ] }
+//! ```
+//!
+//! Adding a brand needs no new test code: drop a dump into a new directory.
+
+use fluxion_adapters::{HaEntityState, check_entity_mapping, create_entity_mapper};
+use fluxion_core::InverterType;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize)]
+struct Fixture {
+    inverter_id: String,
+    states: Vec<HaEntityState>,
+}
+
+fn fixture_files() -> Vec<(InverterType, PathBuf)> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/entity_mappers");
+    let mut files = Vec::new();
+    for dir in std::fs::read_dir(&root).expect("fixture directory") {
+        let dir = dir.unwrap().path();
+        let name = dir.file_name().unwrap().to_string_lossy().into_owned();
+        let inverter_type: InverterType = serde_json::from_value(serde_json::Value::String(name))
+            .unwrap_or_else(|_| panic!("{} is not an inverter type", dir.display()));
+        for file in std::fs::read_dir(&dir).unwrap() {
+            let path = file.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                files.push((inverter_type, path));
+            }
+        }
+    }
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    files
+}
+
+#[test]
+fn test_recorded_entities_satisfy_mappers() {
+    let files = fixture_files();
+    assert!(!files.is_empty(), "no entity mapper fixtures found");
+
+    let mut broken = Vec::new();
+    for (inverter_type, path) in files {
+        let fixture: Fixture = serde_json::from_str(&std::fs::read_to_string(&path).unwrap())
+            .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        let mapper = create_entity_mapper(inverter_type);
+        let report = check_entity_mapping(mapper.as_ref(), &fixture.inverter_id, &fixture.states);
+
+        for check in report.failures() {
+            broken.push(format!(
+                "{}: {} ({}) is {:?}{}",
+                path.display(),
+                check.name,
+                check.entity_id,
+                check.status,
+                check
+                    .detail
+                    .as_ref()
+                    .map(|d| format!(" [{d}]"))
+                    .unwrap_or_default()
+            ));
+        }
+    }
+
+    assert!(
+        broken.is_empty(),
+        "mapping failures:\n{}",
+        broken.join("\n")
+    );
+}
+
+#[test]
+fn test_every_inverter_type_has_a_fixture() {
+    let covered: Vec<InverterType> = fixture_files().into_iter().map(|(t, _)| t).collect();
+    for inverter_type in InverterType::all() {
+        assert!(
+            covered.contains(inverter_type),
+            "no recorded fixture for {}",
+            inverter_type.display_name()
+        );
+    }
+}
@@ -6,6 +6,7 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use chrono::{NaiveDate, TimeZone, Utc};
+use fluxion_core::TimeFormatter;
 use rusqlite::Connection;
 
 use crate::types::{HistoricalRecord, PriceRecord};
@@ -28,9 +29,13 @@ pub trait DataSource: Send + Sync {
 
 /// SQLite-based data source for historical plant data.
 /// Used during development with the solax_data.db test database.
+///
+/// Days are energy days on the local clock of the configured timezone
+/// (UTC by default).
 #[derive(Debug, Clone)]
 pub struct SqliteDataSource {
     db_path: PathBuf,
+    time_formatter: TimeFormatter,
 }
 
 impl SqliteDataSource {
@@ -38,9 +43,23 @@ impl SqliteDataSource {
     pub fn new<P: AsRef<Path>>(db_path: P) -> Self {
         Self {
             db_path: db_path.as_ref().to_path_buf(),
+            time_formatter: TimeFormatter::default(),
         }
     }
 
+    /// Cut days at local midnight in the timezone of `formatter`
+    #[must_use]
+    pub fn with_time_formatter(mut self, formatter: TimeFormatter) -> Self {
+        self.time_formatter = formatter;
+        self
+    }
+
+    /// Unix timestamp range `[start, end)` of the energy day `date`
+    fn day_range(&self, date: NaiveDate) -> (i64, i64) {
+        let day = self.time_formatter.energy_day(date);
+        (day.start.timestamp(), day.end.timestamp())
+    }
+
     fn connect(&self) -> Result<Connection> {
         Connection::open(&self.db_path)
             .with_context(|| format!("Failed to open database at {}", self.db_path.display()))
@@ -51,20 +70,20 @@ impl DataSource for SqliteDataSource {
     fn get_available_days(&self) -> Result<Vec<NaiveDate>> {
         let conn = self.connect()?;
 
+        // 15-minute buckets line up with every UTC offset in use
         let mut stmt = conn.prepare(
-            "SELECT DISTINCT date(timestamp, 'unixepoch') as day
+            "SELECT DISTINCT timestamp / 900 * 900 as bucket
              FROM historical_plant_data
-             ORDER BY day ASC",
+             ORDER BY bucket ASC",
         )?;
 
-        let days: Vec<NaiveDate> = stmt
-            .query_map([], |row| {
-                let day_str: String = row.get(0)?;
-                Ok(day_str)
-            })?
+        let mut days: Vec<NaiveDate> = stmt
+            .query_map([], |row| row.get::<_, i64>(0))?
             .filter_map(std::result::Result::ok)
-            .filter_map(|day_str| NaiveDate::parse_from_str(&day_str, "%Y-%m-%d").ok())
+            .filter_map(|ts| Utc.timestamp_opt(ts, 0).single())
+            .map(|time| self.time_formatter.local_date(time))
             .collect();
+        days.dedup();
 
         Ok(days)
     }
@@ -72,17 +91,12 @@ impl DataSource for SqliteDataSource {
     fn get_day_data(&self, date: NaiveDate) -> Result<Vec<HistoricalRecord>> {
         let conn = self.connect()?;
 
-        // Calculate Unix timestamp range for the day
-        let start_of_day = date.and_hms_opt(0, 0, 0).expect("valid time");
-        let end_of_day = date.and_hms_opt(23, 59, 59).expect("valid time");
-
-        let start_ts = Utc.from_utc_datetime(&start_of_day).timestamp();
-        let end_ts = Utc.from_utc_datetime(&end_of_day).timestamp();
+        let (start_ts, end_ts) = self.day_range(date);
 
         let mut stmt = conn.prepare(
             "SELECT timestamp, battery_soc, pv_power_w, battery_power_w, grid_power_w, house_load_w
              FROM historical_plant_data
-             WHERE timestamp >= ?1 AND timestamp <= ?2
+             WHERE timestamp >= ?1 AND timestamp < ?2
              ORDER BY timestamp ASC",
         )?;
 
@@ -108,16 +122,11 @@ impl DataSource for SqliteDataSource {
     fn get_prices(&self, date: NaiveDate) -> Result<Vec<PriceRecord>> {
         let conn = self.connect()?;
 
-        // Calculate Unix timestamp range for the day
-        let start_of_day = date.and_hms_opt(0, 0, 0).expect("valid time");
-        let end_of_day = date.and_hms_opt(23, 59, 59).expect("valid time");
-
-        let start_ts = Utc.from_utc_datetime(&start_of_day).timestamp();
-        let end_ts = Utc.from_utc_datetime(&end_of_day).timestamp();
+        let (start_ts, end_ts) = self.day_range(date);
 
         let mut stmt = conn.prepare(
             "SELECT ts, price FROM prices
-             WHERE ts >= ?1 AND ts <= ?2
+             WHERE ts >= ?1 AND ts < ?2
              ORDER BY ts ASC",
         )?;
 
@@ -188,4 +197,37 @@ mod tests {
         let ds = SqliteDataSource::new("/tmp/test.db");
         assert_eq!(ds.db_path, PathBuf::from("/tmp/test.db"));
     }
+
+    #[test]
+    fn test_days_follow_local_midnight() {
+        let path =
+            std::env::temp_dir().join(format!("fluxion_energy_day_{}.db", std::process::id()));
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE historical_plant_data (timestamp INTEGER, battery_soc REAL,
+                 pv_power_w REAL, battery_power_w REAL, grid_power_w REAL, house_load_w REAL);
+             -- 2025-01-15 23:30 and 2025-01-16 00:30 in Prague
+             INSERT INTO historical_plant_data VALUES (1736980200, 50, 0, 0, 0, 300);
+             INSERT INTO historical_plant_data VALUES (1736983800, 50, 0, 0, 0, 300);",
+        )
+        .unwrap();
+
+        let ds = SqliteDataSource::new(&path)
+            .with_time_formatter(TimeFormatter::from_timezone_name(Some("Europe/Prague")));
+        let days = ds.get_available_days().unwrap();
+        let records = ds
+            .get_day_data(NaiveDate::from_ymd_opt(2025, 1, 16).unwrap())
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            days,
+            vec![
+                NaiveDate::from_ymd_opt(2025, 1, 15).unwrap(),
+                NaiveDate::from_ymd_opt(2025, 1, 16).unwrap(),
+            ]
+        );
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].timestamp.timestamp(), 1_736_983_800);
+    }
 }
@@ -21,15 +21,27 @@
 //! - **Strategy Simulation**: Run strategies against historical data
 //! - **Cost Analysis**: Calculate grid costs, battery value, and savings
 //! - **Comparison**: Compare actual vs simulated performance
+//! - **Date Ranges**: Simulate many days with the battery carried across midnight
+//! - **Parameter Sweeps**: Grid search for the cheapest strategy configuration
+//! - **Optimal Baseline**: Perfect-foresight optimum as a lower bound on cost
 
 pub mod actual;
 pub mod db;
 pub mod metrics;
+pub mod optimal;
+pub mod range;
 pub mod simulation;
+pub mod sweep;
 pub mod types;
 
 pub use actual::analyze_actual_day;
 pub use db::{DataSource, SqliteDataSource};
-pub use metrics::{ComparisonDiff, calculate_comparison};
-pub use simulation::simulate_day;
+pub use metrics::{ComparisonDiff, OptimalityGap, calculate_comparison, calculate_optimality_gap};
+pub use optimal::simulate_optimal;
+pub use range::{MAX_RANGE_DAYS, RangeAnalysis, StrategyRange, simulate_range};
+pub use simulation::{simulate_day, simulate_day_from_soc};
+pub use sweep::{
+    MAX_SWEEP_COMBINATIONS, ParameterRange, SweepCandidate, SweepParameter, SweepRequest,
+    SweepResult, run_sweep, sweep_size,
+};
 pub use types::*;
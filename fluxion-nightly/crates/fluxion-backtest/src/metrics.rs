@@ -65,6 +65,42 @@ pub fn calculate_comparison(left: &DayAnalysis, right: &DayAnalysis) -> Comparis
     }
 }
 
+/// How close a strategy came to the perfect-foresight optimum
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimalityGap {
+    /// Net cost of the optimal hindsight schedule in CZK
+    pub optimal_net_cost_czk: f64,
+
+    /// Extra cost over the optimum (candidate - optimal) in CZK
+    pub gap_czk: f64,
+
+    /// Share of the possible savings over the baseline that the candidate achieved:
+    /// ((baseline.net_cost - candidate.net_cost) / (baseline.net_cost - optimal.net_cost)) * 100
+    /// `None` when the baseline is already optimal
+    pub captured_percent: Option<f64>,
+}
+
+/// Calculate how far `candidate` is from the `optimal` schedule
+///
+/// `baseline` is the naive reference (typically self-use) the captured share
+/// of savings is measured from.
+#[must_use]
+pub fn calculate_optimality_gap(
+    baseline: &DayAnalysis,
+    candidate: &DayAnalysis,
+    optimal: &DayAnalysis,
+) -> OptimalityGap {
+    let possible_savings = baseline.net_cost_czk - optimal.net_cost_czk;
+    let captured_percent = (possible_savings > 0.001)
+        .then(|| ((baseline.net_cost_czk - candidate.net_cost_czk) / possible_savings) * 100.0);
+
+    OptimalityGap {
+        optimal_net_cost_czk: optimal.net_cost_czk,
+        gap_czk: candidate.net_cost_czk - optimal.net_cost_czk,
+        captured_percent,
+    }
+}
+
 /// Format a savings percentage for display
 #[must_use]
 pub fn format_savings(savings_percent: f64) -> String {
@@ -136,6 +172,21 @@ mod tests {
         assert!((diff.savings_percent - (-25.0)).abs() < 0.001);
     }
 
+    #[test]
+    fn test_optimality_gap() {
+        let baseline = mock_day_analysis(100.0, 0.0);
+        let candidate = mock_day_analysis(70.0, 0.0);
+        let optimal = mock_day_analysis(40.0, 0.0);
+
+        let gap = calculate_optimality_gap(&baseline, &candidate, &optimal);
+
+        assert!((gap.gap_czk - 30.0).abs() < 0.001);
+        assert!((gap.captured_percent.unwrap() - 50.0).abs() < 0.001);
+
+        let none = calculate_optimality_gap(&optimal, &candidate, &optimal);
+        assert!(none.captured_percent.is_none());
+    }
+
     #[test]
     fn test_format_savings() {
         assert_eq!(format_savings(15.5), "-15.5%");
@@ -0,0 +1,379 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+
+//! Perfect-foresight ("optimal hindsight") baseline.
+//!
+//! Knowing the whole day's prices, PV and load in advance, this module finds
+//! the battery schedule with the lowest net grid cost. It uses the same energy
+//! model as the strategy simulations (lossless battery, 10% minimum SOC,
+//! export at a fixed fraction of the import price), so the result is a lower
+//! bound the real strategies can be measured against.
+//!
+//! # Approximation
+//!
+//! The optimum is computed by dynamic programming over a discretized SOC grid
+//! ([`SOC_STEP_PERCENT`] of capacity per step), not by a linear program. An LP
+//! cannot express this cost model exactly: at negative prices the export price
+//! is above the import price, which makes the per-interval cost concave, while
+//! the DP handles any cost shape.
+//!
+//! The result is therefore an approximation. Each interval's SOC move is
+//! limited to whole grid steps (the rate limit rounded down to a multiple of
+//! the step). Against the continuous optimum under those limits, the DP cost
+//! is higher by at most
+//!
+//! ```text
+//! step_kwh × Σ |import_price(t)|   over all intervals t
+//! ```
+//!
+//! Rounding each SOC point of the continuous optimum to the nearest grid step
+//! keeps it feasible and moves each interval's battery energy by at most one
+//! step. Grid cost changes by at most |import price| per kWh, because
+//! |export price| ≤ |import price|. With the 10 kWh default battery, one step
+//! is 0.01 kWh, so a day at an average of 3 CZK/kWh is within 8.6 CZK. In
+//! practice the gap is far smaller, as the rounding errors mostly cancel.
+
+use chrono::NaiveDate;
+
+use crate::db::find_price_at_timestamp;
+use crate::simulation::{
+    DEFAULT_BATTERY_CAPACITY_KWH, DEFAULT_EXPORT_PRICE_RATIO, DEFAULT_MAX_BATTERY_RATE_KW,
+    EnergyTotals, empty_day_analysis,
+};
+use crate::types::{DayAnalysis, HistoricalRecord, HourlyDataPoint, PriceRecord};
+
+/// Resolution of the SOC grid used by the optimizer (percent)
+pub const SOC_STEP_PERCENT: f32 = 0.1;
+
+/// Lowest SOC the optimizer may discharge to (percent), same as self-use
+const MIN_SOC_PERCENT: f32 = 10.0;
+
+const STRATEGY_NAME: &str = "Optimal (Hindsight)";
+
+/// Tolerance for classifying intervals as forced charge/discharge (kWh)
+const MODE_EPSILON_KWH: f32 = 1e-4;
+
+/// Compute the lowest-cost schedule for the day with perfect foresight
+///
+/// See the [module docs](self) for the error bound of the SOC grid.
+#[must_use]
+pub fn simulate_optimal(
+    date: NaiveDate,
+    records: &[HistoricalRecord],
+    prices: &[PriceRecord],
+) -> DayAnalysis {
+    simulate_optimal_with_step(date, records, prices, SOC_STEP_PERCENT, None)
+}
+
+/// [`simulate_optimal`] with the battery starting at `start_soc` (percent)
+pub(crate) fn simulate_optimal_from_soc(
+    date: NaiveDate,
+    records: &[HistoricalRecord],
+    prices: &[PriceRecord],
+    start_soc: Option<f32>,
+) -> DayAnalysis {
+    simulate_optimal_with_step(date, records, prices, SOC_STEP_PERCENT, start_soc)
+}
+
+fn simulate_optimal_with_step(
+    date: NaiveDate,
+    records: &[HistoricalRecord],
+    prices: &[PriceRecord],
+    step_percent: f32,
+    start_soc: Option<f32>,
+) -> DayAnalysis {
+    if records.is_empty() {
+        return empty_day_analysis(date, STRATEGY_NAME);
+    }
+
+    let interval_hours = 5.0 / 60.0;
+    let grid = SocGrid { step_percent };
+    let step_kwh = DEFAULT_BATTERY_CAPACITY_KWH * step_percent / 100.0;
+    let state_count = grid.index(100.0) + 1;
+
+    let start_soc = start_soc.unwrap_or_else(|| records.first().map_or(50.0, |r| r.battery_soc));
+    let start_idx = grid.index(start_soc);
+    // A battery that starts below the floor may stay there
+    let min_idx = grid.index(MIN_SOC_PERCENT).min(start_idx);
+
+    let intervals: Vec<Interval> = records
+        .iter()
+        .map(|record| {
+            let price = find_price_at_timestamp(prices, record.timestamp);
+            let net_load_kwh = (record.house_load_w - record.pv_power_w) / 1000.0 * interval_hours;
+            // Self-consumption is never rate limited in the simulations, so
+            // the optimizer may always move at least the net load
+            let max_move_kwh =
+                (DEFAULT_MAX_BATTERY_RATE_KW * interval_hours).max(net_load_kwh.abs());
+            Interval {
+                price,
+                export_price: price * DEFAULT_EXPORT_PRICE_RATIO,
+                net_load_kwh,
+                max_steps: energy_to_steps(max_move_kwh, step_kwh),
+            }
+        })
+        .collect();
+
+    let path = optimal_soc_path(&intervals, start_idx, min_idx, state_count, step_kwh);
+
+    let mut totals = EnergyTotals::default();
+    let mut hourly_data = Vec::with_capacity(records.len());
+
+    for (t, (record, interval)) in records.iter().zip(&intervals).enumerate() {
+        let from = path.get(t).copied().unwrap_or(start_idx);
+        let to = path.get(t + 1).copied().unwrap_or(from);
+        let charge_kwh = steps_to_energy(to, from, step_kwh);
+        let grid_kwh = interval.net_load_kwh + charge_kwh;
+
+        let grid_import_kwh = grid_kwh.max(0.0);
+        let grid_export_kwh = (-grid_kwh).max(0.0);
+        let battery_charge_kwh = charge_kwh.max(0.0);
+        let battery_discharge_kwh = (-charge_kwh).max(0.0);
+
+        let mode = if battery_charge_kwh > (-interval.net_load_kwh).max(0.0) + MODE_EPSILON_KWH {
+            "ForceCharge"
+        } else if battery_discharge_kwh > interval.net_load_kwh.max(0.0) + MODE_EPSILON_KWH {
+            "ForceDischarge"
+        } else {
+            "SelfUse"
+        };
+
+        totals.pv_generation_kwh += f64::from(record.pv_power_w / 1000.0 * interval_hours);
+        totals.consumption_kwh += f64::from(record.house_load_w / 1000.0 * interval_hours);
+        totals.grid_import_kwh += f64::from(grid_import_kwh);
+        totals.grid_export_kwh += f64::from(grid_export_kwh);
+        totals.battery_charge_kwh += f64::from(battery_charge_kwh);
+        totals.battery_discharge_kwh += f64::from(battery_discharge_kwh);
+
+        totals.grid_import_cost_czk += f64::from(grid_import_kwh * interval.price);
+        totals.grid_export_revenue_czk += f64::from(grid_export_kwh * interval.export_price);
+        totals.battery_value_czk += f64::from(battery_discharge_kwh * interval.price);
+
+        hourly_data.push(HourlyDataPoint {
+            timestamp: record.timestamp,
+            price_czk: f64::from(interval.price),
+            mode: mode.to_owned(),
+            soc_percent: f64::from(grid.soc(to)),
+            grid_import_w: f64::from(grid_import_kwh / interval_hours * 1000.0),
+            grid_export_w: f64::from(grid_export_kwh / interval_hours * 1000.0),
+            pv_power_w: f64::from(record.pv_power_w),
+            battery_power_w: f64::from(-charge_kwh / interval_hours * 1000.0),
+            house_load_w: f64::from(record.house_load_w),
+        });
+    }
+
+    totals.into_day_analysis(date, STRATEGY_NAME, hourly_data)
+}
+
+/// Cheapest SOC index path through the intervals (one entry more than intervals)
+fn optimal_soc_path(
+    intervals: &[Interval],
+    start_idx: usize,
+    min_idx: usize,
+    state_count: usize,
+    step_kwh: f32,
+) -> Vec<usize> {
+    // cost[i] = cheapest cost to reach SOC index i after the processed intervals
+    let mut cost = vec![f64::INFINITY; state_count];
+    if let Some(c) = cost.get_mut(start_idx) {
+        *c = 0.0;
+    }
+    let mut predecessors: Vec<Vec<usize>> = Vec::with_capacity(intervals.len());
+
+    for interval in intervals {
+        let mut next = vec![f64::INFINITY; state_count];
+        let mut prev = vec![usize::MAX; state_count];
+
+        for (from, &from_cost) in cost.iter().enumerate() {
+            if !from_cost.is_finite() {
+                continue;
+            }
+            let lo = from.saturating_sub(interval.max_steps).max(min_idx);
+            let hi = (from + interval.max_steps).min(state_count - 1);
+            for to in lo..=hi {
+                let charge_kwh = steps_to_energy(to, from, step_kwh);
+                let total = from_cost + interval.grid_cost(charge_kwh);
+                if let (Some(best), Some(p)) = (next.get_mut(to), prev.get_mut(to))
+                    && total < *best
+                {
+                    *best = total;
+                    *p = from;
+                }
+            }
+        }
+
+        cost = next;
+        predecessors.push(prev);
+    }
+
+    // The day's end SOC is left free, as it is for the other strategies
+    let mut idx = cost
+        .iter()
+        .enumerate()
+        .min_by(|a, b| a.1.total_cmp(b.1))
+        .map_or(start_idx, |(i, _)| i);
+
+    // Walk back through the predecessors to recover the SOC path
+    let mut path = vec![idx; intervals.len() + 1];
+    for (t, prev) in predecessors.iter().enumerate().rev() {
+        idx = prev.get(idx).copied().unwrap_or(start_idx);
+        if let Some(slot) = path.get_mut(t) {
+            *slot = idx;
+        }
+    }
+
+    path
+}
+
+/// Per-interval inputs for the optimizer
+struct Interval {
+    price: f32,
+    export_price: f32,
+    /// House load minus PV for the interval (kWh), positive = deficit
+    net_load_kwh: f32,
+    /// Largest SOC move (in grid steps) allowed in the interval
+    max_steps: usize,
+}
+
+impl Interval {
+    /// Grid cost of the interval when the battery absorbs `charge_kwh`
+    /// (negative = discharge)
+    fn grid_cost(&self, charge_kwh: f32) -> f64 {
+        let grid_kwh = self.net_load_kwh + charge_kwh;
+        if grid_kwh > 0.0 {
+            f64::from(grid_kwh * self.price)
+        } else {
+            f64::from(grid_kwh * self.export_price)
+        }
+    }
+}
+
+/// SOC grid of the optimizer, index 0 is 0% SOC
+struct SocGrid {
+    step_percent: f32,
+}
+
+impl SocGrid {
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn index(&self, soc: f32) -> usize {
+        (soc.clamp(0.0, 100.0) / self.step_percent).round() as usize
+    }
+
+    #[expect(clippy::cast_precision_loss)]
+    fn soc(&self, idx: usize) -> f32 {
+        idx as f32 * self.step_percent
+    }
+}
+
+#[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn energy_to_steps(kwh: f32, step_kwh: f32) -> usize {
+    // Small tolerance so exact multiples are not lost to float error
+    (kwh / step_kwh + 1e-4).floor().max(0.0) as usize
+}
+
+#[expect(clippy::cast_precision_loss)]
+fn steps_to_energy(to: usize, from: usize, step_kwh: f32) -> f32 {
+    (to as f32 - from as f32) * step_kwh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn day() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 12, 14).unwrap()
+    }
+
+    /// Build a day of 5-minute records with constant load, no PV and
+    /// hourly prices from `hourly_price`
+    fn flat_day(
+        soc: f32,
+        hourly_price: impl Fn(u32) -> f32,
+    ) -> (Vec<HistoricalRecord>, Vec<PriceRecord>) {
+        let start = Utc.with_ymd_and_hms(2024, 12, 14, 0, 0, 0).unwrap();
+        let records = (0..288)
+            .map(|i| HistoricalRecord {
+                timestamp: start + Duration::minutes(5 * i),
+                battery_soc: soc,
+                pv_power_w: 0.0,
+                battery_power_w: 0.0,
+                grid_power_w: 0.0,
+                house_load_w: 500.0,
+            })
+            .collect();
+        let prices = (0..24)
+            .map(|h| PriceRecord {
+                timestamp: start + Duration::hours(i64::from(h)),
+                price_czk_per_kwh: hourly_price(h),
+            })
+            .collect();
+        (records, prices)
+    }
+
+    #[test]
+    fn test_optimal_not_worse_than_self_use() {
+        let (records, prices) = flat_day(50.0, |h| if (17..21).contains(&h) { 6.0 } else { 2.0 });
+
+        let optimal = simulate_optimal(day(), &records, &prices);
+        let self_use =
+            crate::simulation::simulate_self_use(day(), &records, &prices, None).unwrap();
+
+        assert!(optimal.net_cost_czk <= self_use.net_cost_czk + 0.01);
+        assert_eq!(optimal.hourly_data.len(), records.len());
+        assert!(
+            optimal
+                .hourly_data
+                .iter()
+                .all(|p| p.soc_percent >= f64::from(MIN_SOC_PERCENT) - 0.01)
+        );
+    }
+
+    #[test]
+    fn test_optimal_charges_cheap_and_discharges_expensive() {
+        // Cheap night, very expensive evening: worth charging from grid
+        let (records, prices) = flat_day(10.0, |h| match h {
+            0..=5 => 1.0,
+            18..=21 => 10.0,
+            _ => 4.0,
+        });
+
+        let optimal = simulate_optimal(day(), &records, &prices);
+
+        let night_charge = optimal
+            .hourly_data
+            .iter()
+            .take(72)
+            .any(|p| p.mode == "ForceCharge");
+        assert!(night_charge);
+
+        // 4 evening hours × 0.5 kW should be covered entirely by the battery
+        let evening_import: f64 = optimal
+            .hourly_data
+            .iter()
+            .skip(18 * 12)
+            .take(4 * 12)
+            .map(|p| p.grid_import_w)
+            .sum();
+        assert!(evening_import < 1.0);
+    }
+
+    #[test]
+    fn test_grid_error_within_documented_bound() {
+        // Prices that change every hour, including negative ones
+        let (records, prices) = flat_day(40.0, |h| f32::from(u8::try_from(h).unwrap()) * 0.7 - 3.0);
+        let fine = simulate_optimal_with_step(day(), &records, &prices, 0.05, None);
+        let coarse = simulate_optimal_with_step(day(), &records, &prices, 1.0, None);
+
+        let price_sum: f64 = records
+            .iter()
+            .map(|r| f64::from(find_price_at_timestamp(&prices, r.timestamp).abs()))
+            .sum();
+        let bound = f64::from(DEFAULT_BATTERY_CAPACITY_KWH) * 0.01 * price_sum;
+
+        // The finer grid contains the coarse one, so it can only be cheaper
+        assert!(fine.net_cost_czk <= coarse.net_cost_czk + 1e-6);
+        assert!(coarse.net_cost_czk - fine.net_cost_czk <= bound);
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+
+//! Multi-day backtests.
+//!
+//! Simulating each day on its own restarts the battery at the recorded SOC
+//! every midnight, which hides what a strategy leaves for the next day. Here
+//! the simulated SOC at the end of one day is the start SOC of the next, as
+//! long as the days are consecutive. Days without data break the chain.
+
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::db::DataSource;
+use crate::metrics::{ComparisonDiff, calculate_comparison};
+use crate::simulation::simulate_day_from_soc;
+use crate::types::{DayAnalysis, StrategyChoice, StrategyConfigOverrides};
+
+/// Longest range simulated at once (days)
+pub const MAX_RANGE_DAYS: i64 = 92;
+
+/// Results of all strategies over a date range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeAnalysis {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Days in the range with recorded data
+    pub days: Vec<NaiveDate>,
+    /// Strategy the others are compared against (the first one requested)
+    pub baseline: String,
+    pub strategies: Vec<StrategyRange>,
+}
+
+/// One strategy over a date range
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyRange {
+    pub strategy: String,
+    /// Daily results without time series, in date order
+    pub days: Vec<DayAnalysis>,
+    /// Sum over all days; `date` is the first day of the range
+    pub total: DayAnalysis,
+    /// Daily difference to the baseline
+    pub day_comparisons: Vec<ComparisonDiff>,
+    /// Difference of the totals to the baseline
+    pub total_comparison: ComparisonDiff,
+}
+
+/// Simulate `strategies` over the days with data in `[from, to]`
+///
+/// `config_overrides` apply to every strategy that has parameters.
+pub fn simulate_range<D: DataSource>(
+    data_source: &D,
+    from: NaiveDate,
+    to: NaiveDate,
+    strategies: &[StrategyChoice],
+    config_overrides: Option<&StrategyConfigOverrides>,
+) -> Result<RangeAnalysis> {
+    if from > to {
+        bail!("Range start {from} is after its end {to}");
+    }
+    if (to - from).num_days() >= MAX_RANGE_DAYS {
+        bail!("Range is longer than {MAX_RANGE_DAYS} days");
+    }
+    if strategies.is_empty() {
+        bail!("No strategy to simulate");
+    }
+
+    let days: Vec<NaiveDate> = data_source
+        .get_available_days()?
+        .into_iter()
+        .filter(|day| (from..=to).contains(day))
+        .collect();
+
+    let mut results = Vec::with_capacity(strategies.len());
+    for strategy in strategies {
+        let mut analyses = Vec::with_capacity(days.len());
+        let mut carried: Option<(NaiveDate, f32)> = None;
+        for &day in &days {
+            let start_soc = carried
+                .filter(|(previous, _)| previous.succ_opt() == Some(day))
+                .map(|(_, soc)| soc);
+            let mut analysis =
+                simulate_day_from_soc(data_source, day, strategy, config_overrides, start_soc)?;
+            #[expect(clippy::cast_possible_truncation)]
+            let end_soc = analysis.hourly_data.last().map(|p| p.soc_percent as f32);
+            carried = end_soc.map(|soc| (day, soc));
+            // A month of 5-minute points per strategy is too much to return
+            analysis.hourly_data = Vec::new();
+            analyses.push(analysis);
+        }
+        let total = sum_days(from, strategy_name(strategy, &analyses), &analyses);
+        results.push((analyses, total));
+    }
+
+    let (baseline_days, baseline_total) = results[0].clone();
+    let strategies = results
+        .into_iter()
+        .map(|(days, total)| StrategyRange {
+            strategy: total.strategy.clone(),
+            day_comparisons: baseline_days
+                .iter()
+                .zip(&days)
+                .map(|(baseline, day)| calculate_comparison(baseline, day))
+                .collect(),
+            total_comparison: calculate_comparison(&baseline_total, &total),
+            days,
+            total,
+        })
+        .collect();
+
+    Ok(RangeAnalysis {
+        from,
+        to,
+        days,
+        baseline: baseline_total.strategy,
+        strategies,
+    })
+}
+
+/// Display name of a strategy, as the day simulations report it
+fn strategy_name(strategy: &StrategyChoice, days: &[DayAnalysis]) -> String {
+    days.first()
+        .map_or_else(|| format!("{strategy:?}"), |day| day.strategy.clone())
+}
+
+/// Totals of `days` as one analysis starting on `from`
+fn sum_days(from: NaiveDate, strategy: String, days: &[DayAnalysis]) -> DayAnalysis {
+    let sum = |value: fn(&DayAnalysis) -> f64| days.iter().map(value).sum::<f64>();
+    DayAnalysis {
+        date: from,
+        strategy,
+        is_actual: days.iter().all(|d| d.is_actual) && !days.is_empty(),
+        pv_generation_kwh: sum(|d| d.pv_generation_kwh),
+        grid_import_kwh: sum(|d| d.grid_import_kwh),
+        grid_export_kwh: sum(|d| d.grid_export_kwh),
+        battery_charge_kwh: sum(|d| d.battery_charge_kwh),
+        battery_discharge_kwh: sum(|d| d.battery_discharge_kwh),
+        consumption_kwh: sum(|d| d.consumption_kwh),
+        grid_import_cost_czk: sum(|d| d.grid_import_cost_czk),
+        grid_export_revenue_czk: sum(|d| d.grid_export_revenue_czk),
+        battery_value_czk: sum(|d| d.battery_value_czk),
+        net_cost_czk: sum(|d| d.net_cost_czk),
+        hourly_data: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{HistoricalRecord, PriceRecord};
+    use chrono::{Duration, TimeZone, Utc};
+    use fluxion_types::inverter::InverterOperationMode;
+    use fluxion_types::{FixedTimeSlot, UserControlState};
+
+    /// Days of constant grid-powered load with no PV, recorded at 50% SOC
+    struct MemorySource {
+        days: Vec<NaiveDate>,
+    }
+
+    impl DataSource for MemorySource {
+        fn get_available_days(&self) -> Result<Vec<NaiveDate>> {
+            Ok(self.days.clone())
+        }
+
+        fn get_day_data(&self, date: NaiveDate) -> Result<Vec<HistoricalRecord>> {
+            let start = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+            Ok((0..288)
+                .map(|i| HistoricalRecord {
+                    timestamp: start + Duration::minutes(5 * i),
+                    battery_soc: 50.0,
+                    pv_power_w: 0.0,
+                    battery_power_w: 0.0,
+                    grid_power_w: 500.0,
+                    house_load_w: 500.0,
+                })
+                .collect())
+        }
+
+        fn get_prices(&self, date: NaiveDate) -> Result<Vec<PriceRecord>> {
+            let start = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
+            Ok(vec![PriceRecord {
+                timestamp: start,
+                price_czk_per_kwh: 3.0,
+            }])
+        }
+
+        fn get_all_prices(&self) -> Result<Vec<PriceRecord>> {
+            Ok(vec![])
+        }
+    }
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, d).unwrap()
+    }
+
+    #[test]
+    fn test_soc_is_carried_across_midnight() {
+        let source = MemorySource {
+            days: vec![date(1), date(2), date(4)],
+        };
+
+        let range = simulate_range(
+            &source,
+            date(1),
+            date(31),
+            &[StrategyChoice::Actual, StrategyChoice::SelfUse],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(range.days, vec![date(1), date(2), date(4)]);
+        assert_eq!(range.baseline, "Actual");
+        let self_use = &range.strategies[1];
+        // 12 kWh a day drains 40% of the 10 kWh battery on day one; day two
+        // starts empty while day four (after a gap) starts at the recorded 50%
+        assert!(self_use.days[1].net_cost_czk > self_use.days[0].net_cost_czk + 1.0);
+        assert!((self_use.days[2].net_cost_czk - self_use.days[0].net_cost_czk).abs() < 1e-6);
+        assert!(self_use.days.iter().all(|d| d.hourly_data.is_empty()));
+
+        let summed: f64 = self_use.days.iter().map(|d| d.net_cost_czk).sum();
+        assert!((self_use.total.net_cost_czk - summed).abs() < 1e-6);
+        assert_eq!(self_use.day_comparisons.len(), 3);
+        // The recorded days imported the whole load, so self-use is cheaper
+        assert!(self_use.total_comparison.cost_diff_czk < 0.0);
+    }
+
+    #[test]
+    fn test_user_control_is_replayed() {
+        let source = MemorySource {
+            days: vec![date(1), date(2)],
+        };
+        let start = Utc.from_utc_datetime(&date(2).and_hms_opt(1, 0, 0).unwrap());
+        let mut user_control = UserControlState::default();
+        user_control.fixed_time_slots.push(FixedTimeSlot::new(
+            start,
+            start + Duration::hours(2),
+            InverterOperationMode::ForceCharge,
+            None,
+        ));
+        let overrides = StrategyConfigOverrides {
+            user_control: Some(user_control),
+            ..Default::default()
+        };
+
+        let strategies = [StrategyChoice::WinterAdaptive];
+        let free = simulate_range(&source, date(1), date(2), &strategies, None).unwrap();
+        let locked =
+            simulate_range(&source, date(1), date(2), &strategies, Some(&overrides)).unwrap();
+
+        // Only the day with the slot changes
+        let (free, locked) = (&free.strategies[0].days, &locked.strategies[0].days);
+        assert!((free[0].net_cost_czk - locked[0].net_cost_czk).abs() < 1e-6);
+        // Flat prices make the extra grid charge cost-neutral, so check the flows
+        assert!(locked[1].battery_charge_kwh > free[1].battery_charge_kwh + 1.0);
+    }
+
+    #[test]
+    fn test_invalid_ranges_are_rejected() {
+        let source = MemorySource { days: vec![] };
+        let strategies = [StrategyChoice::SelfUse];
+
+        assert!(simulate_range(&source, date(2), date(1), &strategies, None).is_err());
+        assert!(
+            simulate_range(
+                &source,
+                date(1),
+                date(1) + Duration::days(MAX_RANGE_DAYS),
+                &strategies,
+                None
+            )
+            .is_err()
+        );
+        assert!(simulate_range(&source, date(1), date(1), &[], None).is_err());
+    }
+}
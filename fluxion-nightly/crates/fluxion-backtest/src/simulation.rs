@@ -8,7 +8,7 @@
 //! against historical solar/consumption data.
 
 use anyhow::Result;
-use chrono::{NaiveDate, Timelike};
+use chrono::{Duration, NaiveDate};
 
 use fluxion_core::strategy::{
     EconomicStrategy, EvaluationContext, WinterAdaptiveConfig, WinterAdaptiveStrategy,
@@ -24,13 +24,13 @@ use crate::types::{
 };
 
 /// Default grid export price as a fraction of import price
-const DEFAULT_EXPORT_PRICE_RATIO: f32 = 0.8;
+pub(crate) const DEFAULT_EXPORT_PRICE_RATIO: f32 = 0.8;
 
 /// Default battery capacity (kWh)
-const DEFAULT_BATTERY_CAPACITY_KWH: f32 = 10.0;
+pub(crate) const DEFAULT_BATTERY_CAPACITY_KWH: f32 = 10.0;
 
 /// Default max charge/discharge rate (kW)
-const DEFAULT_MAX_BATTERY_RATE_KW: f32 = 3.0;
+pub(crate) const DEFAULT_MAX_BATTERY_RATE_KW: f32 = 3.0;
 
 /// Simulate a day using the specified strategy
 pub fn simulate_day<D: DataSource>(
@@ -38,6 +38,20 @@ pub fn simulate_day<D: DataSource>(
     date: NaiveDate,
     strategy: &StrategyChoice,
     config_overrides: Option<&StrategyConfigOverrides>,
+) -> Result<DayAnalysis> {
+    simulate_day_from_soc(data_source, date, strategy, config_overrides, None)
+}
+
+/// Simulate a day with the battery starting at `start_soc` (percent)
+///
+/// Without a start SOC the simulation starts at the first recorded SOC of the
+/// day. Actual data always shows the recorded SOC.
+pub fn simulate_day_from_soc<D: DataSource>(
+    data_source: &D,
+    date: NaiveDate,
+    strategy: &StrategyChoice,
+    config_overrides: Option<&StrategyConfigOverrides>,
+    start_soc: Option<f32>,
 ) -> Result<DayAnalysis> {
     // For actual data, delegate to the actual analysis module
     if *strategy == StrategyChoice::Actual {
@@ -56,19 +70,23 @@ pub fn simulate_day<D: DataSource>(
 
     match strategy {
         StrategyChoice::Actual => unreachable!(), // Handled above
-        StrategyChoice::SelfUse => simulate_self_use(date, &records, &prices),
+        StrategyChoice::SelfUse => simulate_self_use(date, &records, &prices, start_soc),
         StrategyChoice::WinterAdaptive => {
-            simulate_winter_adaptive(date, &records, &prices, config_overrides)
+            simulate_winter_adaptive(date, &records, &prices, config_overrides, start_soc)
         }
+        StrategyChoice::OptimalHindsight => Ok(crate::optimal::simulate_optimal_from_soc(
+            date, &records, &prices, start_soc,
+        )),
     }
 }
 
 /// Simulate with simple self-use logic (no optimization)
 #[expect(clippy::unnecessary_wraps)]
-fn simulate_self_use(
+pub(crate) fn simulate_self_use(
     date: NaiveDate,
     records: &[HistoricalRecord],
     prices: &[PriceRecord],
+    start_soc: Option<f32>,
 ) -> Result<DayAnalysis> {
     if records.is_empty() {
         return Ok(empty_day_analysis(date, "Self-Use"));
@@ -77,8 +95,8 @@ fn simulate_self_use(
     let interval_hours = 5.0 / 60.0;
     let battery_capacity = DEFAULT_BATTERY_CAPACITY_KWH;
 
-    // Start with the first recorded SOC
-    let mut soc = records.first().map_or(50.0, |r| r.battery_soc);
+    // Start with the carried-over or the first recorded SOC
+    let mut soc = start_soc.unwrap_or_else(|| records.first().map_or(50.0, |r| r.battery_soc));
 
     let mut totals = EnergyTotals::default();
     let mut hourly_data = Vec::with_capacity(records.len());
@@ -159,6 +177,7 @@ fn simulate_winter_adaptive(
     records: &[HistoricalRecord],
     prices: &[PriceRecord],
     config_overrides: Option<&StrategyConfigOverrides>,
+    start_soc: Option<f32>,
 ) -> Result<DayAnalysis> {
     if records.is_empty() {
         return Ok(empty_day_analysis(date, "Winter Adaptive"));
@@ -179,16 +198,34 @@ fn simulate_winter_adaptive(
         if let Some(v) = overrides.charge_safety_multiplier {
             config.charge_safety_multiplier = v;
         }
+        if let Some(v) = overrides.grid_export_price_threshold {
+            config.grid_export_price_threshold = v;
+        }
     }
 
     let strategy = WinterAdaptiveStrategy::new(config);
 
-    let control_config = ControlConfig {
+    let mut control_config = ControlConfig {
         battery_capacity_kwh: DEFAULT_BATTERY_CAPACITY_KWH,
         max_battery_charge_rate_kw: DEFAULT_MAX_BATTERY_RATE_KW,
         battery_efficiency: 0.95,
         ..Default::default()
     };
+    // Self-use discharges down to 10% unless a minimum SOC is given
+    let mut discharge_floor_soc = 10.0;
+    if let Some(overrides) = config_overrides {
+        if let Some(v) = overrides.min_battery_soc {
+            control_config.min_battery_soc = v;
+            discharge_floor_soc = v;
+        }
+        if let Some(v) = overrides.force_charge_hours {
+            control_config.force_charge_hours = v;
+        }
+        if let Some(v) = overrides.battery_wear_cost_czk_per_kwh {
+            control_config.battery_wear_cost_czk_per_kwh = v;
+        }
+    }
+    let user_control = config_overrides.and_then(|o| o.user_control.as_ref());
 
     let interval_hours = 5.0 / 60.0;
 
@@ -204,22 +241,15 @@ fn simulate_winter_adaptive(
         })
         .collect();
 
-    // Start with the first recorded SOC
-    let mut soc = records.first().map_or(50.0, |r| r.battery_soc);
+    // Start with the carried-over or the first recorded SOC
+    let mut soc = start_soc.unwrap_or_else(|| records.first().map_or(50.0, |r| r.battery_soc));
 
     let mut totals = EnergyTotals::default();
     let mut hourly_data = Vec::with_capacity(records.len());
+    // Records cover a single energy day, so consumption accumulates from local midnight
     let mut cumulative_consumption_kwh: f32 = 0.0;
-    let mut current_day = records.first().map(|r| r.timestamp.date_naive());
 
     for record in records {
-        // Reset consumption tracking on new day
-        let record_date = record.timestamp.date_naive();
-        if Some(record_date) != current_day {
-            cumulative_consumption_kwh = 0.0;
-            current_day = Some(record_date);
-        }
-
         let price = find_price_at_timestamp(prices, record.timestamp);
         let export_price = price * DEFAULT_EXPORT_PRICE_RATIO;
 
@@ -228,11 +258,8 @@ fn simulate_winter_adaptive(
 
         cumulative_consumption_kwh += load_kw * interval_hours;
 
-        // Find the current price block for strategy evaluation
-        let minute_of_day = record.timestamp.hour() * 60 + record.timestamp.minute();
-        let total_minutes: u32 = 24 * 60;
-        #[expect(clippy::integer_division)]
-        let price_idx = (minute_of_day as usize * time_block_prices.len()) / total_minutes as usize;
+        // Find the price block containing this record; energy days have
+        // 92-100 blocks around DST changes, so look up by time, not index
         let fallback_price_block = TimeBlockPrice {
             block_start: record.timestamp,
             price_czk_per_kwh: price,
@@ -241,7 +268,12 @@ fn simulate_winter_adaptive(
             spot_sell_price_czk_per_kwh: None,
         };
         let price_block = time_block_prices
-            .get(price_idx % time_block_prices.len().max(1))
+            .iter()
+            .find(|b| {
+                b.block_start <= record.timestamp
+                    && record.timestamp
+                        < b.block_start + Duration::minutes(i64::from(b.duration_minutes))
+            })
             .unwrap_or(&fallback_price_block);
 
         // Evaluate strategy
@@ -265,8 +297,17 @@ fn simulate_winter_adaptive(
 
         let evaluation = strategy.evaluate(&context);
 
+        // User restrictions and fixed slots override the decision like they do live
+        let mode = user_control.map_or(evaluation.mode, |uc| {
+            uc.mode_at(
+                record.timestamp,
+                evaluation.mode,
+                control_config.default_battery_mode,
+            )
+        });
+
         // Apply the strategy decision
-        let (grid_kw, bat_kw, mode_str) = match evaluation.mode {
+        let (grid_kw, bat_kw, mode_str) = match mode {
             InverterOperationMode::ForceCharge => {
                 let max_charge = control_config.max_battery_charge_rate_kw;
                 let bat_kw = -max_charge;
@@ -285,7 +326,7 @@ fn simulate_winter_adaptive(
                 // Self-use logic for other modes
                 let net_load = load_kw - pv_kw;
                 if net_load > 0.0 {
-                    if soc > 10.0 {
+                    if soc > discharge_floor_soc {
                         (0.0, net_load, "SelfUse")
                     } else {
                         (net_load, 0.0, "SelfUse")
@@ -343,20 +384,20 @@ fn simulate_winter_adaptive(
 
 /// Helper struct to accumulate energy totals
 #[derive(Default)]
-struct EnergyTotals {
-    pv_generation_kwh: f64,
-    grid_import_kwh: f64,
-    grid_export_kwh: f64,
-    battery_charge_kwh: f64,
-    battery_discharge_kwh: f64,
-    consumption_kwh: f64,
-    grid_import_cost_czk: f64,
-    grid_export_revenue_czk: f64,
-    battery_value_czk: f64,
+pub(crate) struct EnergyTotals {
+    pub(crate) pv_generation_kwh: f64,
+    pub(crate) grid_import_kwh: f64,
+    pub(crate) grid_export_kwh: f64,
+    pub(crate) battery_charge_kwh: f64,
+    pub(crate) battery_discharge_kwh: f64,
+    pub(crate) consumption_kwh: f64,
+    pub(crate) grid_import_cost_czk: f64,
+    pub(crate) grid_export_revenue_czk: f64,
+    pub(crate) battery_value_czk: f64,
 }
 
 impl EnergyTotals {
-    fn into_day_analysis(
+    pub(crate) fn into_day_analysis(
         self,
         date: NaiveDate,
         strategy: &str,
@@ -384,7 +425,7 @@ impl EnergyTotals {
 }
 
 /// Create an empty day analysis for days with no data
-fn empty_day_analysis(date: NaiveDate, strategy: &str) -> DayAnalysis {
+pub(crate) fn empty_day_analysis(date: NaiveDate, strategy: &str) -> DayAnalysis {
     DayAnalysis {
         date,
         strategy: strategy.to_owned(),
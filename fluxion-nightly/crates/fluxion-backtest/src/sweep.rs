@@ -0,0 +1,357 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+
+//! Parameter sweeps.
+//!
+//! Runs the Winter Adaptive strategy over a date range for every combination
+//! of the requested parameter values (a plain grid search) and ranks the
+//! configurations by net cost. Day data is loaded once up front, so each
+//! combination only costs the simulation itself.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::db::DataSource;
+use crate::range::{MAX_RANGE_DAYS, simulate_range};
+use crate::types::{HistoricalRecord, PriceRecord, StrategyChoice, StrategyConfigOverrides};
+use utoipa::ToSchema;
+
+/// Most combinations a single sweep may evaluate
+pub const MAX_SWEEP_COMBINATIONS: usize = 500;
+
+/// Configurations kept in the ranking
+const TOP_CANDIDATES: usize = 20;
+
+/// Strategy parameter that can be swept
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SweepParameter {
+    DailyChargingTargetSoc,
+    ConservationThresholdSoc,
+    TopExpensiveBlocks,
+    ChargeSafetyMultiplier,
+    GridExportPriceThreshold,
+    MinBatterySoc,
+    ForceChargeHours,
+    BatteryWearCostCzkPerKwh,
+}
+
+impl SweepParameter {
+    /// Set this parameter in `overrides`; counts are rounded to whole numbers
+    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn apply(self, overrides: &mut StrategyConfigOverrides, value: f32) {
+        match self {
+            Self::DailyChargingTargetSoc => overrides.daily_charging_target_soc = Some(value),
+            Self::ConservationThresholdSoc => overrides.conservation_threshold_soc = Some(value),
+            Self::TopExpensiveBlocks => {
+                overrides.top_expensive_blocks = Some(value.round().max(0.0) as usize);
+            }
+            Self::ChargeSafetyMultiplier => overrides.charge_safety_multiplier = Some(value),
+            Self::GridExportPriceThreshold => overrides.grid_export_price_threshold = Some(value),
+            Self::MinBatterySoc => overrides.min_battery_soc = Some(value),
+            Self::ForceChargeHours => {
+                overrides.force_charge_hours = Some(value.round().max(0.0) as usize);
+            }
+            Self::BatteryWearCostCzkPerKwh => {
+                overrides.battery_wear_cost_czk_per_kwh = Some(value);
+            }
+        }
+    }
+}
+
+/// Values of one parameter: `min`, `min + step`, ... up to `max`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ParameterRange {
+    pub parameter: SweepParameter,
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+}
+
+impl ParameterRange {
+    fn values(&self) -> Result<Vec<f32>> {
+        let valid = self.step > 0.0 && self.min <= self.max;
+        if !valid {
+            bail!(
+                "Invalid range for {:?}: {}..={} step {}",
+                self.parameter,
+                self.min,
+                self.max,
+                self.step
+            );
+        }
+        let mut values = Vec::new();
+        let mut i: u16 = 0;
+        loop {
+            let value = self.min + self.step * f32::from(i);
+            // Tolerate rounding so `max` itself is included
+            if value > self.max + self.step * 1e-3 {
+                break;
+            }
+            if values.len() >= MAX_SWEEP_COMBINATIONS {
+                bail!("Too many values for {:?}", self.parameter);
+            }
+            values.push(value.min(self.max));
+            i += 1;
+        }
+        Ok(values)
+    }
+}
+
+/// What to sweep and over which days
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SweepRequest {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub ranges: Vec<ParameterRange>,
+}
+
+/// One evaluated configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepCandidate {
+    pub overrides: StrategyConfigOverrides,
+    /// Net cost over the whole range (CZK)
+    pub net_cost_czk: f64,
+    /// Saving against the default configuration (CZK, positive = cheaper)
+    pub savings_czk: f64,
+}
+
+/// Outcome of a sweep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepResult {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Days in the range with recorded data
+    pub days: usize,
+    /// Combinations evaluated
+    pub evaluated: usize,
+    /// Net cost of the default configuration (CZK)
+    pub baseline_cost_czk: f64,
+    pub best: SweepCandidate,
+    /// Best configurations, cheapest first
+    pub ranking: Vec<SweepCandidate>,
+}
+
+/// Number of combinations `request` would evaluate
+pub fn sweep_size(request: &SweepRequest) -> Result<usize> {
+    if request.ranges.is_empty() {
+        bail!("No parameter to sweep");
+    }
+    let mut total: usize = 1;
+    for range in &request.ranges {
+        total = total.saturating_mul(range.values()?.len());
+    }
+    if total > MAX_SWEEP_COMBINATIONS {
+        bail!("Sweep has {total} combinations, at most {MAX_SWEEP_COMBINATIONS} are allowed");
+    }
+    Ok(total)
+}
+
+/// Run the sweep described by `request`
+///
+/// `progress` is called with (completed, total) after each combination,
+/// the default configuration included.
+pub fn run_sweep<D: DataSource>(
+    data_source: &D,
+    request: &SweepRequest,
+    mut progress: impl FnMut(usize, usize),
+) -> Result<SweepResult> {
+    let combinations = combinations(request)?;
+    if request.from > request.to || (request.to - request.from).num_days() >= MAX_RANGE_DAYS {
+        bail!("Range must be ordered and shorter than {MAX_RANGE_DAYS} days");
+    }
+    let preloaded = Preloaded::load(data_source, request.from, request.to)?;
+    if preloaded.days.is_empty() {
+        bail!(
+            "No recorded data between {} and {}",
+            request.from,
+            request.to
+        );
+    }
+
+    let total = combinations.len() + 1;
+    let net_cost = |overrides: Option<&StrategyConfigOverrides>| -> Result<f64> {
+        let range = simulate_range(
+            &preloaded,
+            request.from,
+            request.to,
+            &[StrategyChoice::WinterAdaptive],
+            overrides,
+        )?;
+        Ok(range.strategies[0].total.net_cost_czk)
+    };
+
+    let baseline_cost_czk = net_cost(None)?;
+    progress(1, total);
+
+    let mut candidates = Vec::with_capacity(combinations.len());
+    for (i, overrides) in combinations.into_iter().enumerate() {
+        let net_cost_czk = net_cost(Some(&overrides))?;
+        candidates.push(SweepCandidate {
+            overrides,
+            net_cost_czk,
+            savings_czk: baseline_cost_czk - net_cost_czk,
+        });
+        progress(i + 2, total);
+    }
+
+    candidates.sort_by(|a, b| a.net_cost_czk.total_cmp(&b.net_cost_czk));
+    let evaluated = candidates.len();
+    candidates.truncate(TOP_CANDIDATES);
+
+    Ok(SweepResult {
+        from: request.from,
+        to: request.to,
+        days: preloaded.days.len(),
+        evaluated,
+        baseline_cost_czk,
+        best: candidates[0].clone(),
+        ranking: candidates,
+    })
+}
+
+/// Every combination of the requested values, as overrides
+fn combinations(request: &SweepRequest) -> Result<Vec<StrategyConfigOverrides>> {
+    sweep_size(request)?;
+    let mut combinations = vec![StrategyConfigOverrides::default()];
+    for range in &request.ranges {
+        let values = range.values()?;
+        combinations = combinations
+            .into_iter()
+            .flat_map(|base| {
+                values.iter().map(move |&value| {
+                    let mut overrides = base.clone();
+                    range.parameter.apply(&mut overrides, value);
+                    overrides
+                })
+            })
+            .collect();
+    }
+    Ok(combinations)
+}
+
+/// Day data of a range held in memory
+struct Preloaded {
+    days: Vec<NaiveDate>,
+    records: HashMap<NaiveDate, Vec<HistoricalRecord>>,
+    prices: HashMap<NaiveDate, Vec<PriceRecord>>,
+}
+
+impl Preloaded {
+    fn load<D: DataSource>(data_source: &D, from: NaiveDate, to: NaiveDate) -> Result<Self> {
+        let days: Vec<NaiveDate> = data_source
+            .get_available_days()?
+            .into_iter()
+            .filter(|day| (from..=to).contains(day))
+            .collect();
+        let mut records = HashMap::with_capacity(days.len());
+        let mut prices = HashMap::with_capacity(days.len());
+        for &day in &days {
+            records.insert(day, data_source.get_day_data(day)?);
+            prices.insert(day, data_source.get_prices(day)?);
+        }
+        Ok(Self {
+            days,
+            records,
+            prices,
+        })
+    }
+}
+
+impl DataSource for Preloaded {
+    fn get_available_days(&self) -> Result<Vec<NaiveDate>> {
+        Ok(self.days.clone())
+    }
+
+    fn get_day_data(&self, date: NaiveDate) -> Result<Vec<HistoricalRecord>> {
+        Ok(self.records.get(&date).cloned().unwrap_or_default())
+    }
+
+    fn get_prices(&self, date: NaiveDate) -> Result<Vec<PriceRecord>> {
+        Ok(self.prices.get(&date).cloned().unwrap_or_default())
+    }
+
+    fn get_all_prices(&self) -> Result<Vec<PriceRecord>> {
+        let mut all: Vec<PriceRecord> = self.prices.values().flatten().cloned().collect();
+        all.sort_by_key(|p| p.timestamp);
+        Ok(all)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(parameter: SweepParameter, min: f32, max: f32, step: f32) -> ParameterRange {
+        ParameterRange {
+            parameter,
+            min,
+            max,
+            step,
+        }
+    }
+
+    fn request(ranges: Vec<ParameterRange>) -> SweepRequest {
+        let day = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        SweepRequest {
+            from: day,
+            to: day,
+            ranges,
+        }
+    }
+
+    #[test]
+    fn test_grid_covers_every_combination() {
+        let request = request(vec![
+            range(SweepParameter::MinBatterySoc, 10.0, 30.0, 10.0),
+            range(SweepParameter::ForceChargeHours, 2.0, 3.0, 1.0),
+        ]);
+
+        assert_eq!(sweep_size(&request).unwrap(), 6);
+        let combinations = combinations(&request).unwrap();
+        assert_eq!(combinations.len(), 6);
+        assert_eq!(combinations[0].min_battery_soc, Some(10.0));
+        assert_eq!(combinations[0].force_charge_hours, Some(2));
+        assert_eq!(combinations[5].min_battery_soc, Some(30.0));
+        assert_eq!(combinations[5].force_charge_hours, Some(3));
+        assert!(
+            combinations
+                .iter()
+                .all(|c| c.top_expensive_blocks.is_none())
+        );
+    }
+
+    #[test]
+    fn test_oversized_or_invalid_sweeps_are_rejected() {
+        assert!(sweep_size(&request(vec![])).is_err());
+        assert!(
+            sweep_size(&request(vec![range(
+                SweepParameter::MinBatterySoc,
+                10.0,
+                5.0,
+                1.0
+            )]))
+            .is_err()
+        );
+        assert!(
+            sweep_size(&request(vec![range(
+                SweepParameter::ChargeSafetyMultiplier,
+                1.0,
+                2.0,
+                0.0
+            )]))
+            .is_err()
+        );
+        assert!(
+            sweep_size(&request(vec![
+                range(SweepParameter::DailyChargingTargetSoc, 50.0, 100.0, 1.0),
+                range(SweepParameter::ConservationThresholdSoc, 50.0, 100.0, 1.0),
+            ]))
+            .is_err()
+        );
+    }
+}
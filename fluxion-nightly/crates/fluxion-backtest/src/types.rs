@@ -3,7 +3,9 @@
 // This file is part of FluxION.
 
 use chrono::{DateTime, NaiveDate, Utc};
+use fluxion_types::UserControlState;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// A single historical plant data record (typically 5-minute intervals)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,10 +50,12 @@ pub enum StrategyChoice {
     SelfUse,
     /// Winter Adaptive Strategy
     WinterAdaptive,
+    /// Perfect-foresight optimum, the lower bound on achievable cost
+    OptimalHindsight,
 }
 
 /// Overrides for strategy configuration parameters
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
 pub struct StrategyConfigOverrides {
     /// Target SOC for daily charging (50-100%)
     pub daily_charging_target_soc: Option<f32>,
@@ -61,6 +65,18 @@ pub struct StrategyConfigOverrides {
     pub top_expensive_blocks: Option<usize>,
     /// Safety multiplier for charge calculations (1.0-2.0)
     pub charge_safety_multiplier: Option<f32>,
+    /// Price above which stored energy is exported (CZK/kWh)
+    pub grid_export_price_threshold: Option<f32>,
+    /// Lowest SOC the battery is discharged to (%)
+    pub min_battery_soc: Option<f32>,
+    /// Hours of force charging per day
+    pub force_charge_hours: Option<usize>,
+    /// Battery wear cost per cycled kWh (CZK/kWh)
+    pub battery_wear_cost_czk_per_kwh: Option<f32>,
+    /// Restrictions and fixed slots to replay on top of the strategy decisions
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub user_control: Option<UserControlState>,
 }
 
 /// Complete analysis of a single day
@@ -149,6 +165,12 @@ impl BacktestMetadata {
                 description: "Optimized strategy for winter/low-solar conditions".to_owned(),
                 has_parameters: true,
             },
+            StrategyInfo {
+                id: "optimal_hindsight".to_owned(),
+                name: "Optimal (Hindsight)".to_owned(),
+                description: "Best possible cost with perfect knowledge of the day".to_owned(),
+                has_parameters: false,
+            },
         ]
     }
 }
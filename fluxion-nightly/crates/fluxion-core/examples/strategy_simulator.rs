@@ -373,6 +373,9 @@ fn main() -> Result<(), Box<dyn Error>> {
         hdo_sensor_entity: "sensor.cez_hdo_raw_data".to_string(),
         hdo_low_tariff_czk: 0.50,
         hdo_high_tariff_czk: 1.80,
+        hdo_source: Default::default(),
+        hdo_preset: Default::default(),
+        hdo_windows: Vec::new(),
     };
 
     // Buy fee added to import (grid fees are now handled via HDO tariffs)
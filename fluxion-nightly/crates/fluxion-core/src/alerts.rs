@@ -0,0 +1,727 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! User-defined price and battery alerts.
+//!
+//! Rules are managed through `/api/alerts` and kept in `./data/alerts.json`.
+//! [`alert_rules_system`] checks them against the loaded prices and battery
+//! SOC. A rule fires once when its condition starts to hold and re-arms when
+//! it clears. While it holds, rules with the dashboard action show a banner;
+//! the HA persistent notification and webhook POST are sent by the dispatcher
+//! task started with [`AlertManager::with_dispatcher`].
+
+use crate::components::{RawInverterState, SpotPriceData};
+use crate::time_format::TimeFormatter;
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use fluxion_types::pricing::TimeBlockPrice;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// Default path for the alert rules
+pub const DEFAULT_ALERTS_PATH: &str = "./data/alerts.json";
+
+/// Minimum time between two evaluations
+const EVALUATION_INTERVAL: Duration = Duration::seconds(30);
+
+/// Fired alerts kept for the API
+const MAX_HISTORY: usize = 50;
+
+/// Webhook requests taking longer than this are abandoned
+const WEBHOOK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// When a rule fires
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// Tomorrow's highest spot price is above the threshold
+    TomorrowMaxPriceAbove { threshold_czk_per_kwh: f32 },
+    /// An upcoming block has a negative spot price
+    NegativePrice,
+    /// Battery SOC has stayed below `soc_percent` for `duration_minutes`
+    BatteryBelow {
+        soc_percent: f32,
+        duration_minutes: u32,
+    },
+}
+
+/// What happens when a rule fires
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertAction {
+    /// Banner on the dashboard while the condition holds
+    Dashboard,
+    /// Home Assistant persistent notification
+    HaNotification,
+    /// JSON POST of the [`AlertEvent`]
+    Webhook { url: String },
+}
+
+/// User-editable part of a rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AlertRuleSpec {
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub condition: AlertCondition,
+    pub actions: Vec<AlertAction>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl AlertRuleSpec {
+    /// Check the spec before it is stored
+    ///
+    /// # Errors
+    /// Describes the first invalid field
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("name must not be empty".to_owned());
+        }
+        if self.actions.is_empty() {
+            return Err("at least one action is required".to_owned());
+        }
+        match self.condition {
+            AlertCondition::TomorrowMaxPriceAbove {
+                threshold_czk_per_kwh,
+            } if !threshold_czk_per_kwh.is_finite() => {
+                return Err("threshold_czk_per_kwh must be a number".to_owned());
+            }
+            AlertCondition::BatteryBelow {
+                soc_percent,
+                duration_minutes,
+            } => {
+                if !(0.0..=100.0).contains(&soc_percent) {
+                    return Err("soc_percent must be between 0 and 100".to_owned());
+                }
+                if duration_minutes == 0 {
+                    return Err("duration_minutes must be at least 1".to_owned());
+                }
+            }
+            _ => {}
+        }
+        for action in &self.actions {
+            if let AlertAction::Webhook { url } = action
+                && !(url.starts_with("http://") || url.starts_with("https://"))
+            {
+                return Err(format!(
+                    "webhook URL must start with http:// or https://: {url}"
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Stored rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AlertRule {
+    pub id: String,
+    #[serde(flatten)]
+    pub spec: AlertRuleSpec,
+}
+
+/// One firing of a rule, also the webhook payload
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub message: String,
+    pub fired_at: DateTime<Utc>,
+}
+
+/// Rules, alerts showing on the dashboard and recent firings
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertOverview {
+    pub rules: Vec<AlertRule>,
+    /// Dashboard alerts whose condition still holds
+    pub active: Vec<AlertEvent>,
+    /// Newest first
+    pub recent: Vec<AlertEvent>,
+}
+
+/// Values the conditions are checked against
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AlertInputs {
+    /// Highest spot price of the next energy day, None until published
+    pub tomorrow_max_price_czk_per_kwh: Option<f32>,
+    /// Cheapest current or future block
+    pub lowest_upcoming_price: Option<(DateTime<Utc>, f32)>,
+    /// Lowest SOC of the reporting inverters
+    pub battery_soc_percent: Option<f32>,
+}
+
+impl AlertInputs {
+    pub fn new(
+        prices: &[TimeBlockPrice],
+        battery_soc_percent: Option<f32>,
+        formatter: &TimeFormatter,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let today = formatter.energy_day_at(now).date;
+        let tomorrow = formatter.energy_day(today.succ_opt().unwrap_or(today));
+        let tomorrow_max_price_czk_per_kwh = prices
+            .iter()
+            .filter(|b| tomorrow.contains(b.block_start))
+            .map(|b| b.price_czk_per_kwh)
+            .reduce(f32::max);
+        let lowest_upcoming_price = prices
+            .iter()
+            .filter(|b| b.block_start + Duration::minutes(i64::from(b.duration_minutes)) > now)
+            .map(|b| (b.block_start, b.price_czk_per_kwh))
+            .reduce(|lowest, block| if block.1 < lowest.1 { block } else { lowest });
+        Self {
+            tomorrow_max_price_czk_per_kwh,
+            lowest_upcoming_price,
+            battery_soc_percent,
+        }
+    }
+}
+
+/// Result of checking one condition
+enum Check {
+    Holds(String),
+    Clear,
+    /// Input missing; keep the previous state
+    Unknown,
+}
+
+#[derive(Debug, Default)]
+struct RuleState {
+    firing: Option<AlertEvent>,
+    below_since: Option<DateTime<Utc>>,
+}
+
+/// Rule evaluation without I/O
+#[derive(Debug, Default)]
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    states: HashMap<String, RuleState>,
+    history: VecDeque<AlertEvent>,
+}
+
+impl AlertEngine {
+    pub fn with_rules(rules: Vec<AlertRule>) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    /// Replace the rules; edited rules re-arm
+    pub fn set_rules(&mut self, rules: Vec<AlertRule>) {
+        self.states.retain(|id, _| {
+            self.rules
+                .iter()
+                .find(|r| r.id == *id)
+                .is_some_and(|old| rules.iter().any(|new| new == old))
+        });
+        self.rules = rules;
+    }
+
+    /// Check every enabled rule; returns the rules that started firing
+    pub fn evaluate(
+        &mut self,
+        inputs: &AlertInputs,
+        formatter: &TimeFormatter,
+        now: DateTime<Utc>,
+    ) -> Vec<(AlertEvent, Vec<AlertAction>)> {
+        let mut fired = Vec::new();
+        for rule in &self.rules {
+            if !rule.spec.enabled {
+                self.states.remove(&rule.id);
+                continue;
+            }
+            let state = self.states.entry(rule.id.clone()).or_default();
+            match check(&rule.spec.condition, state, inputs, formatter, now) {
+                Check::Holds(message) if state.firing.is_none() => {
+                    let event = AlertEvent {
+                        rule_id: rule.id.clone(),
+                        rule_name: rule.spec.name.clone(),
+                        message,
+                        fired_at: now,
+                    };
+                    state.firing = Some(event.clone());
+                    self.history.push_front(event.clone());
+                    self.history.truncate(MAX_HISTORY);
+                    fired.push((event, rule.spec.actions.clone()));
+                }
+                Check::Holds(_) | Check::Unknown => {}
+                Check::Clear => state.firing = None,
+            }
+        }
+        fired
+    }
+
+    pub fn overview(&self) -> AlertOverview {
+        let active = self
+            .rules
+            .iter()
+            .filter(|r| r.spec.actions.contains(&AlertAction::Dashboard))
+            .filter_map(|r| self.states.get(&r.id)?.firing.clone())
+            .collect();
+        AlertOverview {
+            rules: self.rules.clone(),
+            active,
+            recent: self.history.iter().cloned().collect(),
+        }
+    }
+}
+
+fn check(
+    condition: &AlertCondition,
+    state: &mut RuleState,
+    inputs: &AlertInputs,
+    formatter: &TimeFormatter,
+    now: DateTime<Utc>,
+) -> Check {
+    match *condition {
+        AlertCondition::TomorrowMaxPriceAbove {
+            threshold_czk_per_kwh,
+        } => match inputs.tomorrow_max_price_czk_per_kwh {
+            Some(max) if max > threshold_czk_per_kwh => Check::Holds(format!(
+                "Tomorrow's highest price {max:.2} CZK/kWh is above {threshold_czk_per_kwh:.2} CZK/kWh"
+            )),
+            // Tomorrow's prices are missing after midnight until published
+            _ => Check::Clear,
+        },
+        AlertCondition::NegativePrice => match inputs.lowest_upcoming_price {
+            Some((at, price)) if price < 0.0 => Check::Holds(format!(
+                "Negative price {price:.2} CZK/kWh at {}",
+                formatter.date_time(at)
+            )),
+            Some(_) => Check::Clear,
+            None => Check::Unknown,
+        },
+        AlertCondition::BatteryBelow {
+            soc_percent,
+            duration_minutes,
+        } => {
+            let Some(soc) = inputs.battery_soc_percent else {
+                return Check::Unknown;
+            };
+            if soc >= soc_percent {
+                state.below_since = None;
+                return Check::Clear;
+            }
+            let since = *state.below_since.get_or_insert(now);
+            if now - since >= Duration::minutes(i64::from(duration_minutes)) {
+                Check::Holds(format!(
+                    "Battery at {soc:.0}% has been below {soc_percent:.0}% since {}",
+                    formatter.hour_minute(since)
+                ))
+            } else {
+                Check::Clear
+            }
+        }
+    }
+}
+
+/// Sends alerts as Home Assistant persistent notifications
+#[expect(clippy::double_must_use, reason = "generated by async_trait")]
+#[async_trait]
+pub trait AlertNotifier: Send + Sync {
+    /// Create or replace the notification `notification_id`
+    async fn notify(&self, notification_id: &str, title: &str, message: &str) -> Result<()>;
+}
+
+#[derive(Debug)]
+struct Dispatch {
+    event: AlertEvent,
+    actions: Vec<AlertAction>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AlertRulesFile {
+    rules: Vec<AlertRule>,
+}
+
+/// Shared alert state: evaluated by the ECS, edited through the web API
+#[derive(Resource, Clone, Default)]
+pub struct AlertManager {
+    engine: Arc<RwLock<AlertEngine>>,
+    path: Option<PathBuf>,
+    sender: Option<mpsc::UnboundedSender<Dispatch>>,
+}
+
+impl std::fmt::Debug for AlertManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlertManager")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AlertManager {
+    /// Manager persisting to `path`, starting with the rules saved there
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let file = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable alert rules {}: {e}", path.display());
+                AlertRulesFile::default()
+            }),
+            Err(_) => AlertRulesFile::default(),
+        };
+        Self {
+            engine: Arc::new(RwLock::new(AlertEngine::with_rules(file.rules))),
+            path: Some(path),
+            sender: None,
+        }
+    }
+
+    /// Deliver HA notifications and webhooks from a supervised background task
+    ///
+    /// Without a `notifier`, HA notification actions are skipped with a warning.
+    /// Must be called within a tokio runtime.
+    #[must_use]
+    pub fn with_dispatcher(mut self, notifier: Option<Arc<dyn AlertNotifier>>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+        crate::TaskSupervisor::global().spawn("alert_dispatcher", move || {
+            run_dispatcher(Arc::clone(&receiver), notifier.clone())
+        });
+        self.sender = Some(sender);
+        self
+    }
+
+    pub fn overview(&self) -> AlertOverview {
+        self.engine.read().overview()
+    }
+
+    /// Add a rule
+    ///
+    /// # Errors
+    /// When the spec is invalid or the rules cannot be saved
+    pub fn create(&self, spec: AlertRuleSpec) -> Result<AlertRule> {
+        validate(&spec)?;
+        let mut rules = self.engine.read().rules().to_vec();
+        let mut id = format!("alert_{}", Utc::now().timestamp_millis());
+        while rules.iter().any(|r| r.id == id) {
+            id.push('_');
+        }
+        let rule = AlertRule { id, spec };
+        rules.push(rule.clone());
+        self.replace(rules)?;
+        Ok(rule)
+    }
+
+    /// Replace the spec of rule `id`; None when it doesn't exist
+    ///
+    /// # Errors
+    /// When the spec is invalid or the rules cannot be saved
+    pub fn update(&self, id: &str, spec: AlertRuleSpec) -> Result<Option<AlertRule>> {
+        validate(&spec)?;
+        let mut rules = self.engine.read().rules().to_vec();
+        let Some(rule) = rules.iter_mut().find(|r| r.id == id) else {
+            return Ok(None);
+        };
+        rule.spec = spec;
+        let rule = rule.clone();
+        self.replace(rules)?;
+        Ok(Some(rule))
+    }
+
+    /// Remove rule `id`; false when it doesn't exist
+    ///
+    /// # Errors
+    /// When the rules cannot be saved
+    pub fn delete(&self, id: &str) -> Result<bool> {
+        let mut rules = self.engine.read().rules().to_vec();
+        let count = rules.len();
+        rules.retain(|r| r.id != id);
+        if rules.len() == count {
+            return Ok(false);
+        }
+        self.replace(rules)?;
+        Ok(true)
+    }
+
+    fn replace(&self, rules: Vec<AlertRule>) -> Result<()> {
+        if let Some(path) = &self.path {
+            let json = serde_json::to_string_pretty(&AlertRulesFile {
+                rules: rules.clone(),
+            })?;
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(path, json)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+        }
+        self.engine.write().set_rules(rules);
+        Ok(())
+    }
+
+    /// Check the rules and hand new alerts to the dispatcher
+    pub fn evaluate(&self, inputs: &AlertInputs, formatter: &TimeFormatter, now: DateTime<Utc>) {
+        let fired = self.engine.write().evaluate(inputs, formatter, now);
+        for (event, actions) in fired {
+            info!("🔔 Alert '{}': {}", event.rule_name, event.message);
+            if let Some(sender) = &self.sender {
+                let _ = sender.send(Dispatch { event, actions });
+            }
+        }
+    }
+}
+
+fn validate(spec: &AlertRuleSpec) -> Result<()> {
+    if let Err(e) = spec.validate() {
+        bail!("Invalid alert rule: {e}");
+    }
+    Ok(())
+}
+
+async fn run_dispatcher(
+    receiver: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Dispatch>>>,
+    notifier: Option<Arc<dyn AlertNotifier>>,
+) {
+    let client = match reqwest::Client::builder().timeout(WEBHOOK_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Alert webhooks disabled, failed to build HTTP client: {e}");
+            return;
+        }
+    };
+
+    let mut receiver = receiver.lock().await;
+    while let Some(Dispatch { event, actions }) = receiver.recv().await {
+        for action in &actions {
+            match action {
+                AlertAction::Dashboard => {}
+                AlertAction::HaNotification => {
+                    let Some(notifier) = &notifier else {
+                        warn!("Alert '{}': no Home Assistant connection", event.rule_name);
+                        continue;
+                    };
+                    let id = format!("fluxion_alert_{}", event.rule_id);
+                    if let Err(e) = notifier.notify(&id, &event.rule_name, &event.message).await {
+                        warn!("Alert '{}': HA notification failed: {e:#}", event.rule_name);
+                    }
+                }
+                AlertAction::Webhook { url } => {
+                    let result = client
+                        .post(url)
+                        .json(&event)
+                        .send()
+                        .await
+                        .and_then(reqwest::Response::error_for_status);
+                    if let Err(e) = result {
+                        warn!("Alert '{}': webhook {url} failed: {e}", event.rule_name);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Evaluate the [`AlertManager`] rules against the current prices and SOC
+pub fn alert_rules_system(
+    manager: Res<AlertManager>,
+    formatter: Res<TimeFormatter>,
+    price_data: Query<&SpotPriceData>,
+    states: Query<&RawInverterState>,
+    mut last_run: Local<Option<DateTime<Utc>>>,
+) {
+    let now = Utc::now();
+    if last_run.is_some_and(|last| now - last < EVALUATION_INTERVAL) {
+        return;
+    }
+    *last_run = Some(now);
+
+    let prices = price_data
+        .iter()
+        .next()
+        .map(|p| p.time_block_prices.as_slice())
+        .unwrap_or_default();
+    let soc = states
+        .iter()
+        .filter(|s| s.state.online)
+        .map(|s| s.state.battery_soc)
+        .reduce(f32::min);
+    let inputs = AlertInputs::new(prices, soc, &formatter, now);
+    manager.evaluate(&inputs, &formatter, now);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn rule(id: &str, condition: AlertCondition) -> AlertRule {
+        AlertRule {
+            id: id.to_owned(),
+            spec: AlertRuleSpec {
+                name: id.to_owned(),
+                enabled: true,
+                condition,
+                actions: vec![AlertAction::Dashboard],
+            },
+        }
+    }
+
+    fn block(start: DateTime<Utc>, price: f32) -> TimeBlockPrice {
+        TimeBlockPrice {
+            block_start: start,
+            duration_minutes: 15,
+            price_czk_per_kwh: price,
+            effective_price_czk_per_kwh: price,
+            spot_sell_price_czk_per_kwh: None,
+        }
+    }
+
+    #[test]
+    fn test_inputs_split_tomorrow_and_upcoming() {
+        let now = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let prices = [
+            block(now - Duration::hours(1), -2.0),
+            block(now, 1.5),
+            block(now + Duration::hours(4), 0.5),
+            block(now + Duration::hours(14), 6.0),
+            block(now + Duration::hours(20), 4.0),
+        ];
+
+        let inputs = AlertInputs::new(&prices, Some(40.0), &TimeFormatter::default(), now);
+
+        assert_eq!(inputs.tomorrow_max_price_czk_per_kwh, Some(6.0));
+        assert_eq!(
+            inputs.lowest_upcoming_price,
+            Some((now + Duration::hours(4), 0.5))
+        );
+    }
+
+    #[test]
+    fn test_rule_fires_once_and_rearms() {
+        let formatter = TimeFormatter::default();
+        let now = Utc::now();
+        let mut engine = AlertEngine::with_rules(vec![rule(
+            "peak",
+            AlertCondition::TomorrowMaxPriceAbove {
+                threshold_czk_per_kwh: 5.0,
+            },
+        )]);
+        let high = AlertInputs {
+            tomorrow_max_price_czk_per_kwh: Some(6.0),
+            ..AlertInputs::default()
+        };
+
+        assert_eq!(engine.evaluate(&high, &formatter, now).len(), 1);
+        assert!(engine.evaluate(&high, &formatter, now).is_empty());
+        assert_eq!(engine.overview().active.len(), 1);
+
+        // Cleared when tomorrow's prices are gone, fires again once republished
+        assert!(
+            engine
+                .evaluate(&AlertInputs::default(), &formatter, now)
+                .is_empty()
+        );
+        assert!(engine.overview().active.is_empty());
+        assert_eq!(engine.evaluate(&high, &formatter, now).len(), 1);
+        assert_eq!(engine.overview().recent.len(), 2);
+    }
+
+    #[test]
+    fn test_battery_must_stay_low_for_the_duration() {
+        let formatter = TimeFormatter::default();
+        let start = Utc::now();
+        let mut engine = AlertEngine::with_rules(vec![rule(
+            "low",
+            AlertCondition::BatteryBelow {
+                soc_percent: 20.0,
+                duration_minutes: 120,
+            },
+        )]);
+        let soc = |soc| AlertInputs {
+            battery_soc_percent: Some(soc),
+            ..AlertInputs::default()
+        };
+
+        assert!(engine.evaluate(&soc(15.0), &formatter, start).is_empty());
+        // Missing telemetry doesn't restart the timer
+        let later = start + Duration::minutes(90);
+        assert!(
+            engine
+                .evaluate(&AlertInputs::default(), &formatter, later)
+                .is_empty()
+        );
+        let fired = engine.evaluate(&soc(12.0), &formatter, start + Duration::minutes(120));
+        assert_eq!(fired.len(), 1);
+        assert!(fired[0].0.message.contains("below 20%"));
+
+        // Recovery resets the timer
+        engine.evaluate(&soc(25.0), &formatter, start + Duration::minutes(130));
+        let again = start + Duration::minutes(140);
+        assert!(engine.evaluate(&soc(10.0), &formatter, again).is_empty());
+    }
+
+    #[test]
+    fn test_spec_validation() {
+        let mut spec = rule("neg", AlertCondition::NegativePrice).spec;
+        assert!(spec.validate().is_ok());
+
+        spec.actions = vec![AlertAction::Webhook {
+            url: "ftp://example.com".to_owned(),
+        }];
+        assert!(spec.validate().is_err());
+
+        spec.actions.clear();
+        assert!(spec.validate().is_err());
+
+        spec.actions.push(AlertAction::HaNotification);
+        spec.condition = AlertCondition::BatteryBelow {
+            soc_percent: 120.0,
+            duration_minutes: 30,
+        };
+        assert!(spec.validate().is_err());
+    }
+
+    #[test]
+    fn test_manager_persists_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("alerts.json");
+        let manager = AlertManager::load(&path);
+
+        let created = manager
+            .create(rule("x", AlertCondition::NegativePrice).spec)
+            .unwrap();
+        let mut spec = created.spec.clone();
+        spec.name = "Negative prices".to_owned();
+        assert!(manager.update(&created.id, spec).unwrap().is_some());
+        assert!(
+            manager
+                .update("missing", created.spec.clone())
+                .unwrap()
+                .is_none()
+        );
+
+        let reloaded = AlertManager::load(&path).overview().rules;
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded[0].spec.name, "Negative prices");
+
+        assert!(manager.delete(&created.id).unwrap());
+        assert!(!manager.delete(&created.id).unwrap());
+        assert!(AlertManager::load(&path).overview().rules.is_empty());
+    }
+}
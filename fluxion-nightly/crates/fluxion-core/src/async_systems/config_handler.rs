@@ -11,17 +11,25 @@
 // For commercial licensing, please contact: info@solare.cz
 
 use bevy_ecs::prelude::*;
-use tracing::{error, info, trace};
+use tracing::{error, info, trace, warn};
 
 use crate::{
     PluginManagerResource,
+    calendar::with_calendar,
     components::*,
     config_events::{ConfigSection, UserControlChangeType},
     debug::DebugModeConfig,
+    grid_quality::{GridQualityMonitor, planning_control_config},
+    plugin_adapters::init_plugin_manager,
     pricing::analyze_prices,
     resources::{SystemConfig, UserControlResource},
-    scheduling::{ScheduleConfig, generate_schedule_with_optimizer},
+    scheduling::{ScheduleConfig, multi_inverter::CoordinatedBatteries},
+    strategy::with_battery_temperature,
+    tariff::with_tariffs,
+    time_format::TimeFormatter,
+    weather::with_weather,
     web_bridge::{ConfigUpdateChannel, UserControlUpdateChannel},
+    what_if::PlanningInputs,
 };
 
 use super::BackupDischargeMinSoc;
@@ -114,6 +122,12 @@ pub struct ConfigEventParams<'w, 's> {
     inverter_raw_state_query: Query<'w, 's, &'static RawInverterState>,
     plugin_manager_res: Res<'w, PluginManagerResource>,
     user_control: Option<Res<'w, crate::resources::UserControlResource>>,
+    logging_reload: Option<Res<'w, crate::resources::LoggingReloadHandle>>,
+    grid_quality: Option<Res<'w, GridQualityMonitor>>,
+    solar_forecast: Option<Res<'w, super::SolarForecastData>>,
+    time_formatter: Option<Res<'w, TimeFormatter>>,
+    weather: Option<Res<'w, crate::weather::WeatherForecast>>,
+    what_if: Option<Res<'w, crate::what_if::WhatIfPlanner>>,
 }
 
 /// System that processes config update events from the web UI
@@ -179,6 +193,17 @@ pub fn config_event_handler(mut params: ConfigEventParams) {
             }
         }
 
+        // Apply logging changes to the running subscriber
+        if old_config.logging != params.system_config.logging
+            && let Some(reload) = &params.logging_reload
+        {
+            info!(
+                "📝 Applying logging config: {}",
+                params.system_config.logging.filter_directives()
+            );
+            (reload.0)(&params.system_config.logging);
+        }
+
         info!("✅ SystemConfig updated from web UI");
 
         // Log which sections changed
@@ -189,11 +214,26 @@ pub fn config_event_handler(mut params: ConfigEventParams) {
                 ConfigSection::Pricing => info!("  - Pricing configuration updated"),
                 ConfigSection::Control => info!("  - Control parameters updated"),
                 ConfigSection::Strategies => info!("  - Strategy configuration updated"),
+                ConfigSection::Logging => info!("  - Logging configuration updated"),
             }
         }
 
+        // Rebuild the built-in strategies so enable/priority/parameter changes
+        // apply to the next plan; external plugins stay registered
+        let strategies_changed = serde_json::to_value(&old_config.strategies_config).ok()
+            != serde_json::to_value(&params.system_config.strategies_config).ok();
+        if event.section_changed(ConfigSection::Strategies) && strategies_changed {
+            init_plugin_manager(
+                &mut params.plugin_manager_res.0.write(),
+                Some(&params.system_config.strategies_config),
+                &params.system_config.control_config,
+            );
+            info!("🔌 Built-in strategies re-initialized from updated config");
+        }
+
         // Check if we need to recalculate schedule
         let needs_schedule_recalc = event.section_changed(ConfigSection::Control)
+            || event.section_changed(ConfigSection::Inverters)
             || event.section_changed(ConfigSection::Pricing)
             || event.section_changed(ConfigSection::Strategies);
 
@@ -250,7 +290,7 @@ pub fn config_event_handler(mut params: ConfigEventParams) {
                 .unwrap_or(params.system_config.control_config.hardware_min_battery_soc);
 
             // Generate consumption forecast from available data
-            let consumption_forecast = generate_consumption_forecast(
+            let mut consumption_forecast = generate_consumption_forecast(
                 &params.consumption_history,
                 params.inverter_raw_state_query.iter().next(),
                 &params.system_config.control_config,
@@ -282,26 +322,100 @@ pub fn config_event_handler(mut params: ConfigEventParams) {
             let plugin_manager = params.plugin_manager_res.0.read();
             let hdo_raw_data = params.hdo_data.as_ref().and_then(|h| h.raw_data.clone());
             let user_control_state = params.user_control.as_ref().map(|uc| &uc.state);
-            let new_schedule = generate_schedule_with_optimizer(
+            let control_config =
+                planning_control_config(params.grid_quality.as_deref(), &params.system_config);
+            // Separate batteries behind several inverters: plan them as one, then split
+            let coordinated = CoordinatedBatteries::from_config(
+                &params.system_config,
+                params.inverter_raw_state_query.iter().map(|raw| &raw.state),
+            );
+            let control_config = match &coordinated {
+                Some(batteries) => batteries.planning_config(&control_config),
+                None => control_config,
+            };
+            let control_config = with_battery_temperature(
+                control_config,
+                params.inverter_raw_state_query.iter().map(|raw| &raw.state),
+            );
+            let control_config = with_calendar(
+                control_config,
+                consumption_forecast.as_deref_mut(),
                 &price_data.time_block_prices,
-                &params.system_config.control_config,
-                &schedule_config,
-                current_soc,
-                None,                            // Future: Solar forecast
-                consumption_forecast.as_deref(), // Enhanced consumption forecast
+                &params.consumption_history,
+                &params
+                    .time_formatter
+                    .as_deref()
+                    .copied()
+                    .unwrap_or_default(),
+                user_control_state,
+            );
+            let control_config = with_weather(
+                control_config,
+                params.weather.as_deref(),
+                &params
+                    .time_formatter
+                    .as_deref()
+                    .copied()
+                    .unwrap_or_default(),
+                chrono::Utc::now(),
+            );
+            let control_config = with_tariffs(
+                control_config,
+                &params.system_config.pricing_config,
+                params.hdo_data.as_deref(),
+                &params
+                    .time_formatter
+                    .as_deref()
+                    .copied()
+                    .unwrap_or_default(),
+                chrono::Utc::now(),
+            );
+            let current_soc = coordinated
+                .as_ref()
+                .map_or(current_soc, CoordinatedBatteries::soc_percent);
+            let solar_forecast_blocks = params
+                .solar_forecast
+                .as_ref()
+                .and_then(|sf| sf.per_block(&price_data.time_block_prices));
+            let (solar_total_today, solar_remaining_today, solar_tomorrow) = params
+                .solar_forecast
+                .as_ref()
+                .map_or((0.0, 0.0, 0.0), |sf| {
+                    (sf.total_today_kwh, sf.remaining_today_kwh, sf.tomorrow_kwh)
+                });
+            let inputs = PlanningInputs {
+                time_block_prices: price_data.time_block_prices.clone(),
+                control_config,
+                schedule_config,
+                current_battery_soc: current_soc,
+                solar_forecast: solar_forecast_blocks,
+                consumption_forecast,
                 backup_discharge_min_soc,
                 grid_import_today_kwh,
-                &plugin_manager,
                 hdo_raw_data,
-                0.0, // TODO: Wire up solar_forecast_total_today_kwh from SolarForecastData resource
-                0.0, // TODO: Wire up solar_forecast_remaining_today_kwh from SolarForecastData resource
-                0.0, // TODO: Wire up solar_forecast_tomorrow_kwh from SolarForecastData resource
-                user_control_state,
-                params
+                solar_forecast_total_today_kwh: solar_total_today,
+                solar_forecast_remaining_today_kwh: solar_remaining_today,
+                solar_forecast_tomorrow_kwh: solar_tomorrow,
+                user_control: user_control_state.cloned(),
+                hourly_consumption_profile: params
                     .consumption_history
                     .hourly_profile()
-                    .map(|p| &p.hourly_avg_kwh),
-            );
+                    .map(|p| p.hourly_avg_kwh),
+            };
+            let mut new_schedule = inputs.plan(&plugin_manager);
+
+            if let Some(batteries) = &coordinated {
+                new_schedule.inverter_blocks = batteries.plan(
+                    &new_schedule,
+                    &inputs.time_block_prices,
+                    inputs.solar_forecast.as_deref(),
+                    inputs.consumption_forecast.as_deref(),
+                    &inputs.control_config,
+                );
+            }
+            if let Some(planner) = &params.what_if {
+                planner.record(inputs, &new_schedule);
+            }
 
             // Update schedule
             if let Ok(mut schedule) = params.schedule_query.single_mut() {
@@ -358,6 +472,11 @@ pub struct UserControlEventParams<'w, 's> {
     consumption_history: Res<'w, crate::components::ConsumptionHistory>,
     inverter_raw_state_query: Query<'w, 's, &'static RawInverterState>,
     plugin_manager_res: Res<'w, PluginManagerResource>,
+    grid_quality: Option<Res<'w, GridQualityMonitor>>,
+    solar_forecast: Option<Res<'w, super::SolarForecastData>>,
+    time_formatter: Option<Res<'w, TimeFormatter>>,
+    weather: Option<Res<'w, crate::weather::WeatherForecast>>,
+    what_if: Option<Res<'w, crate::what_if::WhatIfPlanner>>,
 }
 
 /// System that processes user control update events from the web UI
@@ -378,6 +497,17 @@ pub fn user_control_event_handler(mut params: UserControlEventParams) {
             params.user_control.state.fixed_time_slots.len()
         );
 
+        if event.change_type == UserControlChangeType::SafeStateChanged {
+            match &params.user_control.state.safe_state {
+                Some(safe_state) => warn!(
+                    "🛑 Safe state ENGAGED by {} at {} - scheduler suspended",
+                    safe_state.triggered_by,
+                    safe_state.triggered_at.to_rfc3339()
+                ),
+                None => info!("▶️ Safe state cleared - scheduler resumed"),
+            }
+        }
+
         // Determine if schedule recalculation is needed
         let needs_schedule_recalc = matches!(
             event.change_type,
@@ -385,6 +515,10 @@ pub fn user_control_event_handler(mut params: UserControlEventParams) {
                 | UserControlChangeType::SlotModified
                 | UserControlChangeType::SlotRemoved
                 | UserControlChangeType::RestrictionsChanged
+                | UserControlChangeType::SafeStateChanged
+                | UserControlChangeType::VacationsChanged
+                | UserControlChangeType::PinsChanged
+                | UserControlChangeType::BackupReserveChanged
         );
 
         if needs_schedule_recalc {
@@ -427,7 +561,7 @@ pub fn user_control_event_handler(mut params: UserControlEventParams) {
                 .unwrap_or(params.system_config.control_config.hardware_min_battery_soc);
 
             // Generate consumption forecast
-            let consumption_forecast = generate_consumption_forecast(
+            let mut consumption_forecast = generate_consumption_forecast(
                 &params.consumption_history,
                 params.inverter_raw_state_query.iter().next(),
                 &params.system_config.control_config,
@@ -444,26 +578,100 @@ pub fn user_control_event_handler(mut params: UserControlEventParams) {
             // Generate new schedule with updated user control
             let plugin_manager = params.plugin_manager_res.0.read();
             let hdo_raw_data = params.hdo_data.as_ref().and_then(|h| h.raw_data.clone());
-            let new_schedule = generate_schedule_with_optimizer(
+            let control_config =
+                planning_control_config(params.grid_quality.as_deref(), &params.system_config);
+            // Separate batteries behind several inverters: plan them as one, then split
+            let coordinated = CoordinatedBatteries::from_config(
+                &params.system_config,
+                params.inverter_raw_state_query.iter().map(|raw| &raw.state),
+            );
+            let control_config = match &coordinated {
+                Some(batteries) => batteries.planning_config(&control_config),
+                None => control_config,
+            };
+            let control_config = with_battery_temperature(
+                control_config,
+                params.inverter_raw_state_query.iter().map(|raw| &raw.state),
+            );
+            let control_config = with_calendar(
+                control_config,
+                consumption_forecast.as_deref_mut(),
                 &price_data.time_block_prices,
-                &params.system_config.control_config,
-                &schedule_config,
-                current_soc,
-                None,
-                consumption_forecast.as_deref(),
+                &params.consumption_history,
+                &params
+                    .time_formatter
+                    .as_deref()
+                    .copied()
+                    .unwrap_or_default(),
+                Some(&params.user_control.state),
+            );
+            let control_config = with_weather(
+                control_config,
+                params.weather.as_deref(),
+                &params
+                    .time_formatter
+                    .as_deref()
+                    .copied()
+                    .unwrap_or_default(),
+                chrono::Utc::now(),
+            );
+            let control_config = with_tariffs(
+                control_config,
+                &params.system_config.pricing_config,
+                params.hdo_data.as_deref(),
+                &params
+                    .time_formatter
+                    .as_deref()
+                    .copied()
+                    .unwrap_or_default(),
+                chrono::Utc::now(),
+            );
+            let current_soc = coordinated
+                .as_ref()
+                .map_or(current_soc, CoordinatedBatteries::soc_percent);
+            let solar_forecast_blocks = params
+                .solar_forecast
+                .as_ref()
+                .and_then(|sf| sf.per_block(&price_data.time_block_prices));
+            let (solar_total_today, solar_remaining_today, solar_tomorrow) = params
+                .solar_forecast
+                .as_ref()
+                .map_or((0.0, 0.0, 0.0), |sf| {
+                    (sf.total_today_kwh, sf.remaining_today_kwh, sf.tomorrow_kwh)
+                });
+            let inputs = PlanningInputs {
+                time_block_prices: price_data.time_block_prices.clone(),
+                control_config,
+                schedule_config,
+                current_battery_soc: current_soc,
+                solar_forecast: solar_forecast_blocks,
+                consumption_forecast,
                 backup_discharge_min_soc,
                 grid_import_today_kwh,
-                &plugin_manager,
                 hdo_raw_data,
-                0.0,
-                0.0,
-                0.0,
-                Some(&params.user_control.state),
-                params
+                solar_forecast_total_today_kwh: solar_total_today,
+                solar_forecast_remaining_today_kwh: solar_remaining_today,
+                solar_forecast_tomorrow_kwh: solar_tomorrow,
+                user_control: Some(params.user_control.state.clone()),
+                hourly_consumption_profile: params
                     .consumption_history
                     .hourly_profile()
-                    .map(|p| &p.hourly_avg_kwh),
-            );
+                    .map(|p| p.hourly_avg_kwh),
+            };
+            let mut new_schedule = inputs.plan(&plugin_manager);
+
+            if let Some(batteries) = &coordinated {
+                new_schedule.inverter_blocks = batteries.plan(
+                    &new_schedule,
+                    &inputs.time_block_prices,
+                    inputs.solar_forecast.as_deref(),
+                    inputs.consumption_forecast.as_deref(),
+                    &inputs.control_config,
+                );
+            }
+            if let Some(planner) = &params.what_if {
+                planner.record(inputs, &new_schedule);
+            }
 
             // Update schedule
             if let Ok(mut schedule) = params.schedule_query.single_mut() {
@@ -21,6 +21,7 @@ use crate::{
     async_tasks::*,
     components::*,
     resources::{ConsumptionHistoryConfig, ConsumptionHistoryDataSourceResource},
+    time_format::TimeFormatter,
 };
 
 /// Spawns the consumption history fetcher worker task
@@ -28,6 +29,7 @@ pub fn spawn_history_fetcher_worker(
     commands: &mut Commands,
     history_source: &ConsumptionHistoryDataSourceResource,
     history_config: &ConsumptionHistoryConfig,
+    formatter: TimeFormatter,
 ) {
     info!("📊 Setting up consumption history fetcher...");
 
@@ -35,45 +37,13 @@ pub fn spawn_history_fetcher_worker(
     let history_source_clone = history_source.0.clone();
     let history_config = history_config.clone();
 
-    tokio::spawn(async move {
-        info!("📊 Consumption history fetcher started");
-
-        // Fetch immediately on startup
-        debug!("Fetching initial consumption history from HA...");
-        if let Err(e) =
-            fetch_consumption_history(&history_source_clone, &history_config, &history_tx).await
-        {
-            error!("❌ Failed to fetch initial consumption history: {e}");
-        }
-
-        // Then continue with daily polling (fetch at midnight)
-        loop {
-            // Sleep until next midnight + 5 minutes (to ensure daily sensors have reset)
-            let now = chrono::Local::now();
-            let tomorrow_midnight = (now + chrono::Duration::days(1))
-                .date_naive()
-                .and_hms_opt(0, 5, 0)
-                .unwrap()
-                .and_local_timezone(chrono::Local)
-                .unwrap();
-            let sleep_duration = (tomorrow_midnight - now)
-                .to_std()
-                .unwrap_or(Duration::from_secs(3600));
-
-            info!(
-                "💤 Consumption history fetcher: sleeping until {} ({} seconds)",
-                tomorrow_midnight.format("%Y-%m-%d %H:%M:%S"),
-                sleep_duration.as_secs()
-            );
-            Delay::new(sleep_duration).await;
-
-            debug!("Fetching consumption history from HA (daily update)...");
-            if let Err(e) =
-                fetch_consumption_history(&history_source_clone, &history_config, &history_tx).await
-            {
-                error!("❌ Failed to fetch consumption history: {e}");
-            }
-        }
+    crate::TaskSupervisor::global().spawn("consumption_history_fetcher", move || {
+        run_history_fetcher(
+            history_source_clone.clone(),
+            history_config.clone(),
+            history_tx.clone(),
+            formatter,
+        )
     });
 
     commands.spawn((
@@ -89,6 +59,58 @@ pub fn spawn_history_fetcher_worker(
     info!("✅ Consumption history fetcher entity created");
 }
 
+/// Fetch history on startup, then once a day shortly after local midnight
+async fn run_history_fetcher(
+    history_source_clone: Arc<dyn crate::traits::ConsumptionHistoryDataSource>,
+    history_config: ConsumptionHistoryConfig,
+    history_tx: crossbeam_channel::Sender<ConsumptionHistoryUpdate>,
+    formatter: TimeFormatter,
+) {
+    info!("📊 Consumption history fetcher started");
+
+    // Fetch immediately on startup
+    debug!("Fetching initial consumption history from HA...");
+    if let Err(e) = fetch_consumption_history(
+        &history_source_clone,
+        &history_config,
+        &history_tx,
+        &formatter,
+    )
+    .await
+    {
+        error!("❌ Failed to fetch initial consumption history: {e}");
+    }
+
+    // Then continue with daily polling (fetch at midnight)
+    loop {
+        // Sleep until the next energy day + 5 minutes (to ensure daily sensors have reset)
+        let now = chrono::Utc::now();
+        let next_fetch = formatter.energy_day_at(now).end + chrono::Duration::minutes(5);
+        let sleep_duration = (next_fetch - now)
+            .to_std()
+            .unwrap_or(Duration::from_secs(3600));
+
+        info!(
+            "💤 Consumption history fetcher: sleeping until {} ({} seconds)",
+            formatter.date_time(next_fetch),
+            sleep_duration.as_secs()
+        );
+        Delay::new(sleep_duration).await;
+
+        debug!("Fetching consumption history from HA (daily update)...");
+        if let Err(e) = fetch_consumption_history(
+            &history_source_clone,
+            &history_config,
+            &history_tx,
+            &formatter,
+        )
+        .await
+        {
+            error!("❌ Failed to fetch consumption history: {e}");
+        }
+    }
+}
+
 /// System that polls the consumption history channel and updates the ConsumptionHistory resource
 pub fn poll_consumption_history_channel(
     history_channel: Query<&ConsumptionHistoryChannel>,
@@ -132,6 +154,7 @@ async fn fetch_consumption_history(
     source: &Arc<dyn crate::traits::ConsumptionHistoryDataSource>,
     config: &ConsumptionHistoryConfig,
     tx: &crossbeam_channel::Sender<ConsumptionHistoryUpdate>,
+    formatter: &TimeFormatter,
 ) -> Result<()> {
     info!(
         "📊 Fetching consumption history for last {} days",
@@ -175,8 +198,11 @@ async fn fetch_consumption_history(
     );
 
     // Aggregate into daily summaries
-    let summaries =
-        crate::components::aggregate_daily_consumption(&consumption_history, &solar_history);
+    let summaries = crate::components::aggregate_daily_consumption(
+        &consumption_history,
+        &solar_history,
+        formatter,
+    );
 
     // Compute hourly consumption profile
     let hourly_profile = crate::components::aggregate_hourly_consumption(&consumption_history);
@@ -20,6 +20,7 @@
 //! - `history_fetcher`: Consumption history fetching
 //! - `state_reader`: Inverter state polling and decomposition
 //! - `config_handler`: Configuration update processing
+//! - `solar_forecast_fetcher`: Native solar forecast provider polling
 
 use bevy_ecs::prelude::*;
 use tracing::info;
@@ -29,6 +30,7 @@ mod health_checker;
 mod history_fetcher;
 mod inverter_writer;
 mod price_fetcher;
+mod solar_forecast_fetcher;
 mod state_reader;
 
 // Re-export public functions and types
@@ -37,12 +39,16 @@ pub use health_checker::{check_health_system, setup_health_checker};
 pub use history_fetcher::{poll_consumption_history_channel, spawn_history_fetcher_worker};
 pub use inverter_writer::setup_async_inverter_writer;
 pub use price_fetcher::{setup_price_cache, update_prices_system};
+pub use solar_forecast_fetcher::{spawn_solar_forecast_worker, summarize_forecast};
 pub use state_reader::{
     decompose_inverter_state, read_inverter_states_system, setup_inverter_state_reader,
 };
 
 use super::{InverterDataSourceResource, PriceDataSourceResource};
-use crate::resources::{ConsumptionHistoryDataSourceResource, SystemConfig};
+use crate::resources::{
+    ConsumptionHistoryDataSourceResource, SolarForecastDataSourceResource, SystemConfig,
+    TimezoneConfig,
+};
 
 /// Resource to store the backup discharge minimum SOC read from HA sensor
 /// This is read from number.<prefix>_backup_discharge_min_soc
@@ -118,12 +124,16 @@ const HDO_CHANNEL_CAPACITY: usize = 5;
 
 /// Startup system that spawns all long-running async worker tasks
 /// These tasks run in the background and communicate via channels
+#[allow(clippy::too_many_arguments)]
 pub fn setup_async_workers(
     mut commands: Commands,
     price_source: Res<PriceDataSourceResource>,
     inverter_source: Res<InverterDataSourceResource>,
     history_source: Res<ConsumptionHistoryDataSourceResource>,
     config: Res<SystemConfig>,
+    timezone: Option<Res<TimezoneConfig>>,
+    solar_forecast_source: Option<Res<SolarForecastDataSourceResource>>,
+    weather_source: Option<Res<crate::resources::WeatherDataSourceResource>>,
 ) {
     use bevy_tasks::AsyncComputeTaskPool;
 
@@ -150,7 +160,11 @@ pub fn setup_async_workers(
     setup_inverter_state_reader(&mut commands, &inverter_source);
 
     // ============= Consumption History Fetcher Worker =============
-    spawn_history_fetcher_worker(&mut commands, &history_source, &config.history);
+    // Daily summaries follow the HA timezone the daily sensors reset in
+    let formatter = timezone
+        .map(|tz| crate::TimeFormatter::from(&*tz))
+        .unwrap_or_default();
+    spawn_history_fetcher_worker(&mut commands, &history_source, &config.history, formatter);
 
     // ============= Backup Discharge Min SOC Fetcher Worker =============
     // Note: This fetcher requires HaClientResource which is inserted by main.rs
@@ -178,11 +192,22 @@ pub fn setup_async_workers(
     commands.insert_resource(HdoSender { sender: hdo_tx });
 
     // ============= Solar Forecast Fetcher Worker =============
-    // Note: This fetcher requires HaClientResource which is inserted by main.rs
-    // The actual worker will be spawned in a separate startup system
-    info!("☀️ Solar forecast fetcher will be initialized after HaClientResource is available");
-
+    // A native provider (Solcast, Forecast.Solar) is polled from here. Otherwise the
+    // HA sensor fetcher is spawned in a separate startup system, as it requires
+    // HaClientResource which is inserted by main.rs
     let (solar_forecast_tx, solar_forecast_rx) = crossbeam_channel::bounded(10);
+    match solar_forecast_source {
+        Some(source) => spawn_solar_forecast_worker(
+            &source,
+            solar_forecast_tx.clone(),
+            config.solar_forecast.fetch_interval_seconds,
+            formatter,
+        ),
+        None => info!(
+            "☀️ Solar forecast fetcher will be initialized after HaClientResource is available"
+        ),
+    }
+
     commands.spawn(SolarForecastChannel {
         receiver: solar_forecast_rx,
     });
@@ -191,6 +216,14 @@ pub fn setup_async_workers(
     });
     commands.init_resource::<SolarForecastData>();
 
+    // ============= Weather Forecast Fetcher Worker =============
+    if let Some(source) = weather_source {
+        commands.insert_resource(crate::weather::spawn_weather_worker(
+            &source,
+            config.weather.fetch_interval_seconds,
+        ));
+    }
+
     info!("🎉 All async workers initialized successfully");
 }
 
@@ -211,6 +244,9 @@ pub struct SolarForecastData {
     /// Solar production forecast for tomorrow (kWh)
     pub tomorrow_kwh: f32,
 
+    /// Per-block forecast; only native providers deliver one
+    pub blocks: Vec<crate::traits::SolarForecastBlock>,
+
     /// Last successful update timestamp
     pub last_updated: Instant,
 }
@@ -221,16 +257,41 @@ impl Default for SolarForecastData {
             total_today_kwh: 0.0,
             remaining_today_kwh: 0.0,
             tomorrow_kwh: 0.0,
+            blocks: Vec::new(),
             last_updated: Instant::now(),
         }
     }
 }
 
+impl SolarForecastData {
+    /// Forecast production for each price block (kWh), if a per-block forecast is available
+    ///
+    /// Blocks the forecast does not cover, such as the night, produce nothing.
+    #[must_use]
+    pub fn per_block(&self, prices: &[crate::components::TimeBlockPrice]) -> Option<Vec<f32>> {
+        if self.blocks.is_empty() {
+            return None;
+        }
+        let by_start: std::collections::HashMap<_, _> = self
+            .blocks
+            .iter()
+            .map(|block| (block.block_start, block.kwh))
+            .collect();
+        Some(
+            prices
+                .iter()
+                .map(|price| by_start.get(&price.block_start).copied().unwrap_or(0.0))
+                .collect(),
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SolarForecastUpdate {
     pub total_today_kwh: f32,
     pub remaining_today_kwh: f32,
     pub tomorrow_kwh: f32,
+    pub blocks: Vec<crate::traits::SolarForecastBlock>,
 }
 
 #[derive(Resource)]
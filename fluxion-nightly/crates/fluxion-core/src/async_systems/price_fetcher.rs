@@ -17,10 +17,16 @@ use tracing::{debug, error, info, trace};
 
 use crate::{
     PluginManagerResource, PriceDataSourceResource,
+    calendar::with_calendar,
     components::*,
+    grid_quality::{GridQualityMonitor, planning_control_config},
     pricing::analyze_prices,
     resources::SystemConfig,
-    scheduling::{ScheduleConfig, generate_schedule_with_optimizer},
+    scheduling::{ScheduleConfig, multi_inverter::CoordinatedBatteries},
+    strategy::with_battery_temperature,
+    time_format::TimeFormatter,
+    weather::with_weather,
+    what_if::PlanningInputs,
 };
 use fluxion_types::config::ControlConfig;
 
@@ -119,18 +125,34 @@ pub fn update_prices_system(
     mut price_data_query: Query<(Entity, &mut SpotPriceData)>,
     mut price_analysis_query: Query<(Entity, &mut PriceAnalysis)>,
     mut schedule_query: Query<&mut OperationSchedule>,
-    _battery_query: Query<&BatteryStatus>,
     config: Res<SystemConfig>,
     backup_soc: Option<Res<BackupDischargeMinSoc>>,
     hdo_data: Option<Res<super::HdoScheduleData>>,
-    hdo_cache: Option<Res<crate::resources::GlobalHdoCache>>,
     consumption_history: Res<crate::components::ConsumptionHistory>,
     inverter_raw_state_query: Query<&RawInverterState>,
     plugin_manager_res: Res<PluginManagerResource>,
     user_control: Option<Res<crate::resources::UserControlResource>>,
+    grid_quality: Option<Res<GridQualityMonitor>>,
+    solar_forecast: Option<Res<super::SolarForecastData>>,
+    (time_formatter, weather): (
+        Option<Res<TimeFormatter>>,
+        Option<Res<crate::weather::WeatherForecast>>,
+    ),
+    (what_if, mut planned_plugin_revision): (
+        Option<Res<crate::what_if::WhatIfPlanner>>,
+        Local<Option<u64>>,
+    ),
 ) {
-    // Only fetch if cache is stale (non-blocking check)
-    if !price_cache.is_stale() {
+    // Plugins are changed through the web API, outside the ECS
+    let plugin_revision = plugin_manager_res.0.read().revision();
+    if planned_plugin_revision.is_some_and(|planned| planned != plugin_revision) {
+        debug!("🔌 Strategy plugins changed since the last plan");
+        price_cache.request_replan();
+    }
+
+    // Only fetch if cache is stale (non-blocking check), unless a replan was requested
+    let replan_requested = price_cache.take_replan_request();
+    if !replan_requested && !price_cache.is_stale() {
         return;
     }
 
@@ -146,9 +168,16 @@ pub fn update_prices_system(
     };
 
     // Calculate effective prices (spot + grid fees) using HDO tariff data
+    let formatter = time_formatter.as_deref().copied().unwrap_or_default();
+    let tariffs = crate::tariff::tariff_schedule(
+        &config.pricing_config,
+        hdo_data.as_deref(),
+        &formatter,
+        chrono::Utc::now(),
+    );
     crate::scheduling::calculate_effective_prices(
         &mut new_prices.time_block_prices,
-        hdo_cache.as_deref(),
+        &tariffs,
         &config.pricing_config,
     );
 
@@ -164,6 +193,15 @@ pub fn update_prices_system(
 
     let is_day_ahead_arrival = new_block_count > old_block_count + 10;
 
+    // The plan is kept while prices stay the same, so the executor can hold the battery
+    // to its SOC trajectory. SOC drift (see `schedule_drift_system`) and changes to the
+    // other planning inputs (solar and weather forecasts, over-voltage export limit,
+    // backup discharge SOC, plugins) request a replan instead.
+    let prices_changed = price_data_query
+        .single()
+        .ok()
+        .is_none_or(|(_, data)| data.time_block_prices != new_prices.time_block_prices);
+
     if is_day_ahead_arrival {
         info!(
             "📊 Day-ahead prices arrived! Old: {} blocks ({:.1}h), New: {} blocks ({:.1}h). Will recalculate schedule.",
@@ -186,12 +224,19 @@ pub fn update_prices_system(
         commands.spawn(new_prices.clone());
     }
 
+    if !prices_changed && !replan_requested && !schedule_query.is_empty() {
+        trace!("💰 Prices unchanged, keeping the current schedule");
+        return;
+    }
+
     debug!(
         "🔄 Regenerating schedule due to: {}",
         if is_day_ahead_arrival {
             "day-ahead prices arrival"
-        } else {
+        } else if prices_changed {
             "price data update"
+        } else {
+            "replan request"
         }
     );
 
@@ -233,7 +278,7 @@ pub fn update_prices_system(
         .unwrap_or(config.control_config.hardware_min_battery_soc);
 
     // Generate consumption forecast from available data
-    let consumption_forecast = generate_consumption_forecast(
+    let mut consumption_forecast = generate_consumption_forecast(
         &consumption_history,
         inverter_raw_state_query.iter().next(),
         &config.control_config,
@@ -263,25 +308,82 @@ pub fn update_prices_system(
     let plugin_manager = plugin_manager_res.0.read();
     let hdo_raw_data = hdo_data.as_ref().and_then(|h| h.raw_data.clone());
     let user_control_state = user_control.as_ref().map(|uc| &uc.state);
-    let new_schedule = generate_schedule_with_optimizer(
+    let control_config = planning_control_config(grid_quality.as_deref(), &config);
+    // Separate batteries behind several inverters: plan them as one, then split
+    let coordinated = CoordinatedBatteries::from_config(
+        &config,
+        inverter_raw_state_query.iter().map(|raw| &raw.state),
+    );
+    let control_config = match &coordinated {
+        Some(batteries) => batteries.planning_config(&control_config),
+        None => control_config,
+    };
+    let control_config = with_battery_temperature(
+        control_config,
+        inverter_raw_state_query.iter().map(|raw| &raw.state),
+    );
+    let control_config = with_calendar(
+        control_config,
+        consumption_forecast.as_deref_mut(),
         &new_prices.time_block_prices,
-        &config.control_config,
-        &schedule_config,
-        current_soc,
-        None, // Future: Add solar forecast integration (Solcast/Forecast.Solar API)
-        consumption_forecast.as_deref(), // Enhanced consumption forecast
+        &consumption_history,
+        &time_formatter.as_deref().copied().unwrap_or_default(),
+        user_control_state,
+    );
+    let control_config = with_weather(
+        control_config,
+        weather.as_deref(),
+        &time_formatter.as_deref().copied().unwrap_or_default(),
+        chrono::Utc::now(),
+    );
+    let control_config = ControlConfig {
+        tariff_schedule: tariffs,
+        ..control_config
+    };
+    let current_soc = coordinated
+        .as_ref()
+        .map_or(current_soc, CoordinatedBatteries::soc_percent);
+    // Per-block solar forecast from a native provider (Solcast/Forecast.Solar)
+    let solar_forecast_blocks = solar_forecast
+        .as_ref()
+        .and_then(|sf| sf.per_block(&new_prices.time_block_prices));
+    let (solar_total_today, solar_remaining_today, solar_tomorrow) =
+        solar_forecast.as_ref().map_or((0.0, 0.0, 0.0), |sf| {
+            (sf.total_today_kwh, sf.remaining_today_kwh, sf.tomorrow_kwh)
+        });
+    let inputs = PlanningInputs {
+        time_block_prices: new_prices.time_block_prices.clone(),
+        control_config,
+        schedule_config,
+        current_battery_soc: current_soc,
+        solar_forecast: solar_forecast_blocks,
+        consumption_forecast, // Enhanced consumption forecast
         backup_discharge_min_soc,
         grid_import_today_kwh,
-        &plugin_manager,
         hdo_raw_data,
-        0.0, // TODO: Wire up solar_forecast_total_today_kwh from SolarForecastData resource
-        0.0, // TODO: Wire up solar_forecast_remaining_today_kwh from SolarForecastData resource
-        0.0, // TODO: Wire up solar_forecast_tomorrow_kwh from SolarForecastData resource
-        user_control_state,
-        consumption_history
+        solar_forecast_total_today_kwh: solar_total_today,
+        solar_forecast_remaining_today_kwh: solar_remaining_today,
+        solar_forecast_tomorrow_kwh: solar_tomorrow,
+        user_control: user_control_state.cloned(),
+        hourly_consumption_profile: consumption_history
             .hourly_profile()
-            .map(|p| &p.hourly_avg_kwh),
-    );
+            .map(|p| p.hourly_avg_kwh),
+    };
+    let mut new_schedule = inputs.plan(&plugin_manager);
+    *planned_plugin_revision = Some(plugin_revision);
+
+    if let Some(batteries) = &coordinated {
+        new_schedule.inverter_blocks = batteries.plan(
+            &new_schedule,
+            &inputs.time_block_prices,
+            inputs.solar_forecast.as_deref(),
+            inputs.consumption_forecast.as_deref(),
+            &inputs.control_config,
+        );
+    }
+    if let Some(planner) = &what_if {
+        planner.record(inputs, &new_schedule);
+    }
 
     // Update or create PriceAnalysis entity
     if let Ok((_, mut price_analysis)) = price_analysis_query.single_mut() {
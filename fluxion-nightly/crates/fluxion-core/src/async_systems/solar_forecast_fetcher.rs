@@ -0,0 +1,149 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use chrono::{DateTime, Duration, Utc};
+use futures_timer::Delay;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use super::SolarForecastUpdate;
+use crate::{
+    resources::SolarForecastDataSourceResource, time_format::TimeFormatter,
+    traits::SolarForecastBlock,
+};
+
+/// Spawns the worker polling a native solar forecast provider
+///
+/// Providers cache their forecast and only call their API when their rate
+/// limit allows, so polling often just keeps `remaining_today` current.
+pub fn spawn_solar_forecast_worker(
+    source: &SolarForecastDataSourceResource,
+    sender: crossbeam_channel::Sender<SolarForecastUpdate>,
+    fetch_interval_seconds: u64,
+    formatter: TimeFormatter,
+) {
+    let source = source.0.clone();
+    info!("☀️ Spawning solar forecast fetcher ({})", source.name());
+
+    crate::TaskSupervisor::global().spawn("solar_forecast_fetcher", move || {
+        run_solar_forecast_worker(
+            source.clone(),
+            sender.clone(),
+            fetch_interval_seconds,
+            formatter,
+        )
+    });
+}
+
+async fn run_solar_forecast_worker(
+    source: Arc<dyn crate::traits::SolarForecastDataSource>,
+    sender: crossbeam_channel::Sender<SolarForecastUpdate>,
+    fetch_interval_seconds: u64,
+    formatter: TimeFormatter,
+) {
+    loop {
+        match source.read_forecast().await {
+            Ok(blocks) => {
+                let update = summarize_forecast(blocks, &formatter, Utc::now());
+                debug!(
+                    "📊 Solar forecast from {}: today={:.1} kWh, remaining={:.1} kWh, tomorrow={:.1} kWh",
+                    source.name(),
+                    update.total_today_kwh,
+                    update.remaining_today_kwh,
+                    update.tomorrow_kwh
+                );
+                let _ = sender.send(update);
+            }
+            Err(e) => warn!(
+                "⚠️ Failed to read solar forecast from {}: {e:#}",
+                source.name()
+            ),
+        }
+
+        Delay::new(std::time::Duration::from_secs(
+            fetch_interval_seconds.max(1),
+        ))
+        .await;
+    }
+}
+
+/// Daily totals of a per-block forecast on the local clock
+///
+/// The block in progress counts towards `remaining_today` with its remaining share.
+#[must_use]
+pub fn summarize_forecast(
+    blocks: Vec<SolarForecastBlock>,
+    formatter: &TimeFormatter,
+    now: DateTime<Utc>,
+) -> SolarForecastUpdate {
+    let today = formatter.energy_day_at(now);
+    let tomorrow = formatter.energy_day(today.date.succ_opt().unwrap_or(today.date));
+
+    let mut update = SolarForecastUpdate {
+        total_today_kwh: 0.0,
+        remaining_today_kwh: 0.0,
+        tomorrow_kwh: 0.0,
+        blocks: Vec::new(),
+    };
+    for block in &blocks {
+        if today.contains(block.block_start) {
+            update.total_today_kwh += block.kwh;
+
+            let block_end =
+                block.block_start + Duration::minutes(i64::from(block.duration_minutes));
+            if block.block_start >= now {
+                update.remaining_today_kwh += block.kwh;
+            } else if block_end > now {
+                #[expect(clippy::cast_precision_loss)]
+                let share = (block_end - now).num_seconds() as f32
+                    / (block_end - block.block_start).num_seconds() as f32;
+                update.remaining_today_kwh += block.kwh * share;
+            }
+        } else if tomorrow.contains(block.block_start) {
+            update.tomorrow_kwh += block.kwh;
+        }
+    }
+    update.blocks = blocks;
+    update
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(start: &str, kwh: f32) -> SolarForecastBlock {
+        SolarForecastBlock {
+            block_start: start.parse().unwrap(),
+            duration_minutes: 15,
+            kwh,
+        }
+    }
+
+    #[test]
+    fn test_forecast_is_summarized_per_local_day() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        let blocks = vec![
+            // 22:00 UTC is already tomorrow in Prague
+            block("2025-06-01T09:00:00Z", 1.0),
+            block("2025-06-01T10:00:00Z", 2.0),
+            block("2025-06-01T22:00:00Z", 0.5),
+            block("2025-06-02T09:00:00Z", 3.0),
+        ];
+
+        let update = summarize_forecast(blocks, &prague, "2025-06-01T09:05:00Z".parse().unwrap());
+
+        assert!((update.total_today_kwh - 3.0).abs() < 1e-4);
+        assert!((update.remaining_today_kwh - (2.0 + 10.0 / 15.0)).abs() < 1e-4);
+        assert!((update.tomorrow_kwh - 3.5).abs() < 1e-4);
+        assert_eq!(update.blocks.len(), 4);
+    }
+}
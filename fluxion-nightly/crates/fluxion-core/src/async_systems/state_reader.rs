@@ -99,12 +99,13 @@ type InverterComponentsQuery<'a> = (
 /// System that decomposes RawInverterState into individual ECS components
 /// This ensures BatteryStatus, GridPower, and PowerGeneration components are always up-to-date
 /// Also populates extended components if data is available
-/// Also collects battery SOC history for visualization
+/// Also collects battery SOC, PV and grid power history for visualization
 pub fn decompose_inverter_state(
     mut inverters: Query<InverterComponentsQuery>,
     mut commands: Commands,
     mut battery_history: ResMut<BatteryHistory>,
     mut pv_history: ResMut<PvHistory>,
+    mut grid_history: ResMut<GridHistory>,
     mut last_history_update: Local<Option<std::time::Instant>>,
 ) {
     for (
@@ -165,13 +166,22 @@ pub fn decompose_inverter_state(
             };
 
             pv_history.add_point(pv_history_point);
+
+            // And grid import/export, to correlate mode decisions with power flows
+            grid_history.add_point(GridHistoryPoint::from_readings(
+                chrono::Utc::now(),
+                state.grid_power_w,
+                state.grid_import_w,
+                state.grid_export_w,
+            ));
             *last_history_update = Some(now);
 
             info!(
-                "📊 Collected history: Battery {:.1}% ({:.0}W), PV {:.0}W (battery: {} pts, PV: {} pts)",
+                "📊 Collected history: Battery {:.1}% ({:.0}W), PV {:.0}W, Grid {:.0}W (battery: {} pts, PV: {} pts)",
                 state.battery_soc,
                 state.battery_power_w,
                 state.pv_power_w,
+                state.grid_power_w,
                 battery_history.len(),
                 pv_history.len()
             );
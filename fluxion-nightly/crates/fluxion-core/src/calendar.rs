@@ -0,0 +1,248 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Holiday and vacation awareness for planning.
+//!
+//! Before a schedule is generated, the holidays and vacations in the planning
+//! horizon are resolved to UTC ranges on the local clock and put into the
+//! planning [`ControlConfig`], where strategies find them with
+//! [`ControlConfig::special_day_at`]. The consumption forecast is scaled to
+//! the weekend or workday average of the history for each block's day.
+
+use chrono::{DateTime, Utc};
+use fluxion_types::calendar::{CalendarConfig, DayKind, SpecialDay, VacationRange};
+use fluxion_types::config::ControlConfig;
+use fluxion_types::pricing::TimeBlockPrice;
+use fluxion_types::user_control::UserControlState;
+use tracing::debug;
+
+use crate::components::{ConsumptionHistory, DailyEnergySummary};
+use crate::time_format::TimeFormatter;
+
+/// Days looked ahead when resolving special days (covers the price horizon)
+const HORIZON_DAYS: u64 = 3;
+
+/// History days of each kind needed before the forecast is scaled
+const MIN_DAYS_PER_KIND: usize = 2;
+
+/// Holidays and vacations among the `days` local days starting with the day of `from`
+pub fn special_days(
+    formatter: &TimeFormatter,
+    config: &CalendarConfig,
+    vacations: &[VacationRange],
+    from: DateTime<Utc>,
+    days: u64,
+) -> Vec<SpecialDay> {
+    formatter
+        .local_date(from)
+        .iter_days()
+        .take(usize::try_from(days).unwrap_or(usize::MAX))
+        .filter_map(|date| {
+            let kind = config.day_kind(date, vacations);
+            let name = match kind {
+                DayKind::Holiday => fluxion_types::calendar::czech_public_holiday(date)?.to_owned(),
+                DayKind::Vacation => vacations
+                    .iter()
+                    .find(|vacation| vacation.contains(date))
+                    .and_then(|vacation| vacation.note.clone())
+                    .unwrap_or_else(|| "Vacation".to_owned()),
+                DayKind::Workday | DayKind::Weekend => return None,
+            };
+            let day = formatter.energy_day(date);
+            Some(SpecialDay {
+                date,
+                start: day.start,
+                end: day.end,
+                kind,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// Planning config with the special days of the next few days filled in
+pub fn with_special_days(
+    mut control_config: ControlConfig,
+    formatter: &TimeFormatter,
+    vacations: &[VacationRange],
+    now: DateTime<Utc>,
+) -> ControlConfig {
+    control_config.special_days = special_days(
+        formatter,
+        &control_config.calendar,
+        vacations,
+        now,
+        HORIZON_DAYS,
+    );
+    for day in &control_config.special_days {
+        debug!("📅 {} is a {:?} day ({})", day.date, day.kind, day.name);
+    }
+    control_config
+}
+
+/// Apply the calendar before planning: fill in the special days of
+/// `control_config` and scale `consumption_forecast` to their day kinds
+pub fn with_calendar(
+    control_config: ControlConfig,
+    consumption_forecast: Option<&mut [f32]>,
+    time_block_prices: &[TimeBlockPrice],
+    history: &ConsumptionHistory,
+    formatter: &TimeFormatter,
+    user_control: Option<&UserControlState>,
+) -> ControlConfig {
+    let vacations = user_control.map_or(&[][..], |uc| uc.vacations.as_slice());
+    if let Some(forecast) = consumption_forecast {
+        adjust_consumption_forecast(
+            forecast,
+            time_block_prices.iter().map(|block| block.block_start),
+            history,
+            formatter,
+            &control_config.calendar,
+            vacations,
+        );
+    }
+    with_special_days(control_config, formatter, vacations, Utc::now())
+}
+
+/// Scale a per-block consumption forecast to the day kind of each block
+///
+/// The history is split into weekend-like days (weekends, holidays,
+/// vacations) and workdays; blocks on each kind of day get the ratio of that
+/// kind's average to the overall average. Without at least two days of each
+/// kind the forecast is left as is.
+pub fn adjust_consumption_forecast(
+    forecast: &mut [f32],
+    block_starts: impl IntoIterator<Item = DateTime<Utc>>,
+    history: &ConsumptionHistory,
+    formatter: &TimeFormatter,
+    config: &CalendarConfig,
+    vacations: &[VacationRange],
+) {
+    let is_weekend_like = |at: DateTime<Utc>| {
+        config
+            .day_kind(formatter.local_date(at), vacations)
+            .is_weekend_like()
+    };
+    let (weekend, workday): (Vec<_>, Vec<_>) = history
+        .summaries()
+        .iter()
+        .partition(|summary| is_weekend_like(summary.date));
+    if weekend.len() < MIN_DAYS_PER_KIND || workday.len() < MIN_DAYS_PER_KIND {
+        return;
+    }
+
+    let mean = |days: &[&DailyEnergySummary]| {
+        days.iter().map(|day| day.consumption_kwh).sum::<f32>() / days.len() as f32
+    };
+    let overall = mean(&[weekend.as_slice(), workday.as_slice()].concat());
+    if overall <= 0.0 {
+        return;
+    }
+    let weekend_factor = mean(&weekend) / overall;
+    let workday_factor = mean(&workday) / overall;
+
+    for (value, start) in forecast.iter_mut().zip(block_starts) {
+        *value *= if is_weekend_like(start) {
+            weekend_factor
+        } else {
+            workday_factor
+        };
+    }
+    debug!(
+        "📅 Consumption forecast scaled by {:.2} on weekend-like days and {:.2} on workdays",
+        weekend_factor, workday_factor
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, NaiveDate, TimeZone};
+
+    fn formatter() -> TimeFormatter {
+        TimeFormatter::new(Some(chrono_tz::Europe::Prague))
+    }
+
+    #[test]
+    fn test_special_days_in_local_time() {
+        let vacations = vec![VacationRange::new(
+            NaiveDate::from_ymd_opt(2025, 12, 27).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 12, 28).unwrap(),
+            None,
+        )];
+        // 23:30 UTC on the 23rd is already the 24th in Prague
+        let from = Utc.with_ymd_and_hms(2025, 12, 23, 23, 30, 0).unwrap();
+
+        let days = special_days(
+            &formatter(),
+            &CalendarConfig::default(),
+            &vacations,
+            from,
+            5,
+        );
+
+        let dates: Vec<_> = days.iter().map(|d| (d.date.to_string(), d.kind)).collect();
+        assert_eq!(
+            dates,
+            vec![
+                ("2025-12-24".to_owned(), DayKind::Holiday),
+                ("2025-12-25".to_owned(), DayKind::Holiday),
+                ("2025-12-26".to_owned(), DayKind::Holiday),
+                ("2025-12-27".to_owned(), DayKind::Vacation),
+                ("2025-12-28".to_owned(), DayKind::Vacation),
+            ]
+        );
+        assert_eq!(days[0].start, from - Duration::minutes(30));
+        assert_eq!(days[0].name, "Christmas Eve");
+
+        let config = ControlConfig {
+            special_days: days,
+            ..ControlConfig::default()
+        };
+        assert!(config.special_day_at(from + Duration::hours(12)).is_some());
+        assert!(config.special_day_at(from - Duration::hours(1)).is_none());
+    }
+
+    #[test]
+    fn test_forecast_follows_weekend_profile() {
+        let tz = formatter();
+        let mut history = ConsumptionHistory::new(14);
+        // Mon 2025-06-02 .. Sun 2025-06-15: 10 kWh workdays, 20 kWh weekends
+        for day in 2..=15 {
+            let date = NaiveDate::from_ymd_opt(2025, 6, day).unwrap();
+            let weekend = matches!(day, 7 | 8 | 14 | 15);
+            history.add_summary(DailyEnergySummary {
+                date: tz.energy_day(date).start,
+                consumption_kwh: if weekend { 20.0 } else { 10.0 },
+                solar_production_kwh: 0.0,
+                grid_import_kwh: 0.0,
+            });
+        }
+        let monday = tz.energy_day(NaiveDate::from_ymd_opt(2025, 6, 16).unwrap());
+        let holiday = tz.energy_day(NaiveDate::from_ymd_opt(2025, 7, 7).unwrap());
+        let vacations = vec![VacationRange::new(holiday.date, holiday.date, None)];
+        let mut forecast = vec![1.0, 1.0];
+
+        adjust_consumption_forecast(
+            &mut forecast,
+            [monday.start, holiday.start],
+            &history,
+            &tz,
+            &CalendarConfig::default(),
+            &vacations,
+        );
+
+        // Overall mean is 12.857 kWh
+        assert!((forecast[0] - 10.0 / (180.0 / 14.0)).abs() < 1e-4);
+        assert!((forecast[1] - 20.0 / (180.0 / 14.0)).abs() < 1e-4);
+    }
+}
@@ -12,6 +12,7 @@
 
 use super::{InverterOperationMode, OperationSchedule};
 use crate::resources::ControlConfig;
+use crate::strategy::{PowerProfile, SubBlockBattery, simulate_self_use};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
@@ -135,8 +136,11 @@ pub fn predict_battery_soc(
 
         match block.mode {
             InverterOperationMode::ForceCharge => {
-                // Energy charged in this block
-                let energy_kwh = charge_rate * duration_hours;
+                // Energy charged in this block (partial-power blocks charge slower)
+                let block_rate = block
+                    .charge_power_kw
+                    .map_or(charge_rate, |kw| kw.min(charge_rate));
+                let energy_kwh = block_rate * duration_hours;
                 let soc_increase = calculate_soc_change(energy_kwh, battery_capacity);
                 soc = (soc + soc_increase).min(max_soc);
             }
@@ -162,22 +166,31 @@ pub fn predict_battery_soc(
 
                 let pv_power_kw = current_pv_power_w.map(|w| w / 1000.0).unwrap_or(0.0);
 
-                // Net power: positive = excess solar (charging), negative = deficit (discharging)
-                let net_power_kw = pv_power_kw - house_load_kw;
-
-                if net_power_kw > 0.0 {
-                    // Excess solar - battery charges from surplus
-                    let energy_kwh = net_power_kw * duration_hours;
-                    let soc_increase = calculate_soc_change(energy_kwh, battery_capacity);
-                    soc = (soc + soc_increase).min(max_soc);
-                } else if net_power_kw < 0.0 {
-                    // Power deficit - battery discharges to cover household load
-                    let energy_kwh = net_power_kw.abs() * duration_hours;
-                    let soc_decrease = calculate_soc_change(energy_kwh, battery_capacity);
+                // Surplus charges and deficit discharges the battery, within the
+                // inverter's charge/discharge rates; the rest goes to the grid
+                let profile = PowerProfile::flat(
+                    pv_power_kw * duration_hours,
+                    house_load_kw * duration_hours,
+                    block.duration_minutes,
+                    1,
+                );
+                let battery = SubBlockBattery {
+                    soc_percent: soc,
+                    capacity_kwh: battery_capacity,
+                    efficiency: 1.0,
                     // Use hardware minimum SOC enforced by inverter firmware
-                    soc = (soc - soc_decrease).max(hardware_min_soc);
+                    min_soc_percent: hardware_min_soc,
+                    max_soc_percent: max_soc,
+                    max_charge_kw: charge_rate,
+                    max_discharge_kw: discharge_rate,
+                    export_limit_kw: None,
+                };
+                let flows = simulate_self_use(&profile, block.duration_minutes, &battery).flows;
+                let net_energy_kwh = flows.battery_charge_kwh - flows.battery_discharge_kwh;
+                if net_energy_kwh != 0.0 {
+                    soc += calculate_soc_change(net_energy_kwh, battery_capacity);
                 }
-                // If net_power_kw == 0.0, SOC remains stable (solar exactly matches load)
+                // If solar exactly matches load, SOC remains stable
             }
         }
 
@@ -236,11 +249,17 @@ mod tests {
                 target_inverters: None,
                 mode: InverterOperationMode::ForceCharge,
                 reason: "Test charge".to_string(),
+                decision_reason: None,
                 decision_uid: None,
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: None,
                 debug_info: None,
             }],
             generated_at: now,
             based_on_price_version: now,
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
         };
 
         let config = create_test_config();
@@ -255,6 +274,49 @@ mod tests {
         assert!(point.soc_percent < 55.0); // Should be around 53.6%
     }
 
+    #[test]
+    fn test_battery_prediction_partial_force_charge() {
+        let now = Utc::now();
+        let block = |charge_power_kw| ScheduledMode {
+            block_start: now,
+            duration_minutes: 15,
+            target_inverters: None,
+            mode: InverterOperationMode::ForceCharge,
+            reason: "Test charge".to_string(),
+            decision_reason: None,
+            decision_uid: None,
+            charge_power_kw,
+            target_soc: None,
+            forecast: None,
+            debug_info: None,
+        };
+        let predict = |charge_power_kw| {
+            let schedule = OperationSchedule {
+                scheduled_blocks: vec![block(charge_power_kw)],
+                generated_at: now,
+                based_on_price_version: now,
+                initial_soc: None,
+                inverter_blocks: Vec::new(),
+            };
+            predict_battery_soc(
+                &schedule,
+                &create_test_config(),
+                50.0,
+                Some(4.0),
+                None,
+                None,
+                None,
+            )
+            .points()[0]
+                .soc_percent
+        };
+
+        let full_gain = predict(None) - 50.0;
+        let half_gain = predict(Some(2.0)) - 50.0;
+
+        assert!((half_gain * 2.0 - full_gain).abs() < 1e-3);
+    }
+
     #[test]
     fn test_battery_prediction_force_discharge() {
         let now = Utc::now();
@@ -265,11 +327,17 @@ mod tests {
                 target_inverters: None,
                 mode: InverterOperationMode::ForceDischarge,
                 reason: "Test discharge".to_string(),
+                decision_reason: None,
                 decision_uid: None,
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: None,
                 debug_info: None,
             }],
             generated_at: now,
             based_on_price_version: now,
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
         };
 
         let config = create_test_config();
@@ -294,11 +362,17 @@ mod tests {
                 target_inverters: None,
                 mode: InverterOperationMode::ForceCharge,
                 reason: "Test".to_string(),
+                decision_reason: None,
                 decision_uid: None,
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: None,
                 debug_info: None,
             }],
             generated_at: now,
             based_on_price_version: now,
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
         };
 
         let config = create_test_config();
@@ -323,7 +397,11 @@ mod tests {
                     target_inverters: None,
                     mode: InverterOperationMode::ForceCharge,
                     reason: "Charge".to_string(),
+                    decision_reason: None,
                     decision_uid: None,
+                    charge_power_kw: None,
+                    target_soc: None,
+                    forecast: None,
                     debug_info: None,
                 },
                 ScheduledMode {
@@ -332,7 +410,11 @@ mod tests {
                     target_inverters: None,
                     mode: InverterOperationMode::ForceCharge,
                     reason: "Charge".to_string(),
+                    decision_reason: None,
                     decision_uid: None,
+                    charge_power_kw: None,
+                    target_soc: None,
+                    forecast: None,
                     debug_info: None,
                 },
                 ScheduledMode {
@@ -341,12 +423,18 @@ mod tests {
                     target_inverters: None,
                     mode: InverterOperationMode::ForceDischarge,
                     reason: "Discharge".to_string(),
+                    decision_reason: None,
                     decision_uid: None,
+                    charge_power_kw: None,
+                    target_soc: None,
+                    forecast: None,
                     debug_info: None,
                 },
             ],
             generated_at: now,
             based_on_price_version: now,
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
         };
 
         let config = create_test_config();
@@ -373,11 +461,17 @@ mod tests {
                 target_inverters: None,
                 mode: InverterOperationMode::SelfUse,
                 reason: "Self use".to_string(),
+                decision_reason: None,
                 decision_uid: None,
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: None,
                 debug_info: None,
             }],
             generated_at: now,
             based_on_price_version: now,
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
         };
 
         let config = create_test_config();
@@ -408,11 +502,17 @@ mod tests {
                 target_inverters: None,
                 mode: InverterOperationMode::SelfUse,
                 reason: "Self use".to_string(),
+                decision_reason: None,
                 decision_uid: None,
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: None,
                 debug_info: None,
             }],
             generated_at: now,
             based_on_price_version: now,
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
         };
 
         let config = create_test_config();
@@ -438,11 +538,17 @@ mod tests {
                 target_inverters: None,
                 mode: InverterOperationMode::SelfUse,
                 reason: "Self use".to_string(),
+                decision_reason: None,
                 decision_uid: None,
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: None,
                 debug_info: None,
             }],
             generated_at: now,
             based_on_price_version: now,
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
         };
 
         let config = create_test_config();
@@ -464,4 +570,43 @@ mod tests {
         assert!(prediction.points()[0].soc_percent > 50.0);
         assert!(prediction.points()[0].soc_percent < 51.0);
     }
+
+    #[test]
+    fn test_battery_prediction_self_use_respects_charge_rate() {
+        let now = Utc::now();
+        let schedule = OperationSchedule {
+            scheduled_blocks: vec![ScheduledMode {
+                block_start: now,
+                duration_minutes: 15,
+                target_inverters: None,
+                mode: InverterOperationMode::SelfUse,
+                reason: "Self use".to_string(),
+                decision_reason: None,
+                decision_uid: None,
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: None,
+                debug_info: None,
+            }],
+            generated_at: now,
+            based_on_price_version: now,
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
+        };
+
+        let config = create_test_config();
+        // 8 kW surplus, but the battery only takes 2 kW
+        let prediction = predict_battery_soc(
+            &schedule,
+            &config,
+            50.0,
+            Some(2.0),
+            None,
+            Some(0.0),
+            Some(8000.0),
+        );
+
+        let expected = 50.0 + calculate_soc_change(2.0 * 0.25, config.battery_capacity_kwh);
+        assert!((prediction.points()[0].soc_percent - expected).abs() < 1e-3);
+    }
 }
@@ -0,0 +1,214 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+
+use super::{Inverter, RawInverterState};
+
+/// Maximum number of blocks to keep (48 hours at 15-minute intervals = 192 blocks)
+const MAX_BLOCKS: usize = 192;
+
+/// Block length the realized energy is bucketed into (minutes)
+const BLOCK_MINUTES: i64 = 15;
+
+/// Gaps between two reads longer than this are not integrated (seconds)
+const MAX_SAMPLE_GAP_SECS: i64 = 300;
+
+/// Energy measured during one block, summed over all inverters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BlockActual {
+    pub block_start: DateTime<Utc>,
+    /// Realized solar generation (kWh)
+    pub solar_kwh: f32,
+    /// Realized household consumption (kWh)
+    pub consumption_kwh: f32,
+    /// Energy imported from the grid (kWh)
+    #[serde(default)]
+    pub grid_import_kwh: f32,
+    /// Energy exported to the grid (kWh)
+    #[serde(default)]
+    pub grid_export_kwh: f32,
+}
+
+/// Last telemetry read of one inverter
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    at: DateTime<Utc>,
+    pv_power_w: f32,
+    house_load_w: Option<f32>,
+    grid_power_w: f32,
+}
+
+/// Resource storing realized solar, consumption and grid energy per block
+///
+/// Used to annotate exported price blocks with what actually happened next
+/// to the forecast the strategy decided on, and to account realized savings.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct BlockActuals {
+    /// Measured blocks (newest first)
+    blocks: VecDeque<BlockActual>,
+    /// Previous read per inverter, integrated once the next read arrives
+    last_samples: HashMap<String, Sample>,
+}
+
+impl BlockActuals {
+    /// Create empty block actuals
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a telemetry read of `inverter_id`
+    ///
+    /// `grid_power_w` is positive while exporting. The power of the previous
+    /// read is held until `at` and credited to the block that read fell in.
+    pub fn record(
+        &mut self,
+        inverter_id: &str,
+        at: DateTime<Utc>,
+        pv_power_w: f32,
+        house_load_w: Option<f32>,
+        grid_power_w: f32,
+    ) {
+        let sample = Sample {
+            at,
+            pv_power_w,
+            house_load_w,
+            grid_power_w,
+        };
+        let Some(previous) = self.last_samples.insert(inverter_id.to_owned(), sample) else {
+            return;
+        };
+
+        let elapsed_secs = (at - previous.at).num_seconds();
+        if elapsed_secs <= 0 || elapsed_secs > MAX_SAMPLE_GAP_SECS {
+            return;
+        }
+        let hours = elapsed_secs as f32 / 3600.0;
+
+        let block = self.block_mut(block_start_of(previous.at));
+        block.solar_kwh += previous.pv_power_w.max(0.0) * hours / 1000.0;
+        block.consumption_kwh += previous.house_load_w.unwrap_or(0.0).max(0.0) * hours / 1000.0;
+        block.grid_import_kwh += (-previous.grid_power_w).max(0.0) * hours / 1000.0;
+        block.grid_export_kwh += previous.grid_power_w.max(0.0) * hours / 1000.0;
+    }
+
+    /// Realized energy of the block starting at `block_start`
+    pub fn get(&self, block_start: DateTime<Utc>) -> Option<&BlockActual> {
+        self.blocks.iter().find(|b| b.block_start == block_start)
+    }
+
+    /// Get the number of stored blocks
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Check if nothing has been measured yet
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    fn block_mut(&mut self, block_start: DateTime<Utc>) -> &mut BlockActual {
+        let index = match self
+            .blocks
+            .iter()
+            .position(|b| b.block_start == block_start)
+        {
+            Some(index) => index,
+            None => {
+                self.blocks.push_front(BlockActual {
+                    block_start,
+                    solar_kwh: 0.0,
+                    consumption_kwh: 0.0,
+                    grid_import_kwh: 0.0,
+                    grid_export_kwh: 0.0,
+                });
+                self.blocks.truncate(MAX_BLOCKS);
+                0
+            }
+        };
+        &mut self.blocks[index]
+    }
+}
+
+/// Start of the 15-minute block containing `at`
+fn block_start_of(at: DateTime<Utc>) -> DateTime<Utc> {
+    at.duration_trunc(Duration::minutes(BLOCK_MINUTES))
+        .unwrap_or(at)
+}
+
+/// Feed each fresh telemetry read into [`BlockActuals`]
+pub fn block_actuals_system(
+    mut actuals: ResMut<BlockActuals>,
+    states: Query<(&Inverter, &RawInverterState), Changed<RawInverterState>>,
+) {
+    for (inverter, raw) in states.iter() {
+        // Prefer the separate import/export sensors where the inverter has them
+        let grid_power_w = match (raw.state.grid_import_w, raw.state.grid_export_w) {
+            (Some(import), Some(export)) => export - import,
+            _ => raw.state.grid_power_w,
+        };
+        actuals.record(
+            &inverter.id,
+            raw.last_updated,
+            raw.state.pv_power_w,
+            raw.state.house_load_w,
+            grid_power_w,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(minute: u32, second: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 6, 1, 12, minute, second)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_energy_is_integrated_per_block() {
+        let mut actuals = BlockActuals::new();
+
+        // 4 kW of solar, 1 kW of load and 3 kW of export for the whole first block
+        for minute in 0..=15 {
+            actuals.record("main", at(minute, 0), 4000.0, Some(1000.0), 3000.0);
+        }
+
+        let block = actuals.get(at(0, 0)).unwrap();
+        assert!((block.solar_kwh - 1.0).abs() < 1e-4);
+        assert!((block.consumption_kwh - 0.25).abs() < 1e-4);
+        assert!((block.grid_export_kwh - 0.75).abs() < 1e-4);
+        assert!(block.grid_import_kwh.abs() < 1e-4);
+        assert!(actuals.get(at(15, 0)).is_none());
+    }
+
+    #[test]
+    fn test_inverters_are_summed_and_gaps_skipped() {
+        let mut actuals = BlockActuals::new();
+
+        actuals.record("master", at(0, 0), 2000.0, Some(600.0), 0.0);
+        actuals.record("slave", at(0, 0), 2000.0, None, 0.0);
+        actuals.record("master", at(3, 0), 2000.0, Some(600.0), 0.0);
+        actuals.record("slave", at(3, 0), 2000.0, None, 0.0);
+        // Thirteen minutes without data are not filled in
+        actuals.record("master", at(16, 0), 0.0, Some(0.0), -1000.0);
+
+        let block = actuals.get(at(0, 0)).unwrap();
+        assert!((block.solar_kwh - 0.2).abs() < 1e-4);
+        assert!((block.consumption_kwh - 0.03).abs() < 1e-4);
+    }
+}
@@ -11,7 +11,7 @@
 // For commercial licensing, please contact: info@solare.cz
 
 use bevy_ecs::prelude::*;
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 
@@ -21,7 +21,7 @@ pub use fluxion_types::history::{ConsumptionHistoryConfig, HourlyConsumptionProf
 /// Daily energy summary for a specific date
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DailyEnergySummary {
-    /// Date of this summary (midnight UTC)
+    /// Start of the energy day of this summary (local midnight)
     pub date: DateTime<Utc>,
 
     /// Total consumption for the day (kWh)
@@ -196,25 +196,27 @@ impl ConsumptionHistory {
 /// Utility function to calculate daily consumption from HA history data
 ///
 /// For sensors that reset at midnight, this takes the LAST value before reset
-/// (which represents the daily total) for each day.
+/// (which represents the daily total) for each day. Days are energy days on
+/// the local clock of `formatter`, matching when the sensors reset.
 ///
 /// # Arguments
 /// * `history_points` - Historical data points
+/// * `formatter` - Timezone the daily sensors reset in
 ///
 /// # Returns
 /// Vector of daily summaries, newest first
 pub fn aggregate_daily_consumption(
     history_points: &[crate::traits::HistoryDataPoint],
     solar_points: &[crate::traits::HistoryDataPoint],
+    formatter: &crate::TimeFormatter,
 ) -> Vec<DailyEnergySummary> {
     use std::collections::HashMap;
 
     // Helper to get daily max values
-    let get_daily_max = |points: &[crate::traits::HistoryDataPoint]| -> HashMap<String, f32> {
-        let mut map: HashMap<String, f32> = HashMap::new();
+    let get_daily_max = |points: &[crate::traits::HistoryDataPoint]| -> HashMap<NaiveDate, f32> {
+        let mut map: HashMap<NaiveDate, f32> = HashMap::new();
         for point in points {
-            let date_key = point.timestamp.format("%Y-%m-%d").to_string();
-            map.entry(date_key)
+            map.entry(formatter.local_date(point.timestamp))
                 .and_modify(|max| *max = max.max(point.value))
                 .or_insert(point.value);
         }
@@ -227,14 +229,10 @@ pub fn aggregate_daily_consumption(
     // Convert to daily summaries
     let mut summaries: Vec<DailyEnergySummary> = consumption_map
         .into_iter()
-        .filter_map(|(date_str, consumption)| {
-            // Parse date
-            let date = chrono::NaiveDate::parse_from_str(&date_str, "%Y-%m-%d")
-                .ok()?
-                .and_hms_opt(0, 0, 0)?
-                .and_utc();
+        .filter_map(|(local_date, consumption)| {
+            let date = formatter.energy_day(local_date).start;
 
-            let solar_production = *solar_map.get(&date_str).unwrap_or(&0.0);
+            let solar_production = *solar_map.get(&local_date).unwrap_or(&0.0);
 
             // Only include if consumption is reasonable (0-200 kWh per day)
             if (0.0..200.0).contains(&consumption) {
@@ -248,7 +246,7 @@ pub fn aggregate_daily_consumption(
                 tracing::warn!(
                     "Skipping unreasonable consumption value: {:.2} kWh for {}",
                     consumption,
-                    date_str
+                    local_date
                 );
                 None
             }
@@ -351,6 +349,7 @@ pub fn aggregate_hourly_consumption(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::TimeFormatter;
     use crate::traits::HistoryDataPoint;
     use chrono::Timelike;
 
@@ -429,7 +428,8 @@ mod tests {
         ];
         let solar_points = vec![];
 
-        let summaries = aggregate_daily_consumption(&history_points, &solar_points);
+        let summaries =
+            aggregate_daily_consumption(&history_points, &solar_points, &TimeFormatter::default());
         assert_eq!(summaries.len(), 1);
         assert_eq!(summaries[0].consumption_kwh, 25.0); // Max value (last before reset)
     }
@@ -463,7 +463,8 @@ mod tests {
         ];
         let solar_points = vec![];
 
-        let summaries = aggregate_daily_consumption(&history_points, &solar_points);
+        let summaries =
+            aggregate_daily_consumption(&history_points, &solar_points, &TimeFormatter::default());
         assert_eq!(summaries.len(), 3);
 
         // Should be sorted newest first
@@ -471,4 +472,29 @@ mod tests {
         assert_eq!(summaries[1].consumption_kwh, 22.0); // Yesterday
         assert_eq!(summaries[2].consumption_kwh, 20.0); // Two days ago
     }
+
+    #[test]
+    fn test_aggregate_uses_local_energy_days() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        let point = |timestamp: &str, value| HistoryDataPoint {
+            timestamp: timestamp.parse().unwrap(),
+            value,
+        };
+        let history_points = vec![
+            // 23:50 local on the 15th, before the sensor resets
+            point("2025-01-15T22:50:00Z", 18.0),
+            // 00:30 local on the 16th, same UTC date as above
+            point("2025-01-15T23:30:00Z", 0.4),
+        ];
+
+        let summaries = aggregate_daily_consumption(&history_points, &[], &prague);
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(
+            summaries[0].date,
+            "2025-01-15T23:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(summaries[0].consumption_kwh, 0.4);
+        assert_eq!(summaries[1].consumption_kwh, 18.0);
+    }
 }
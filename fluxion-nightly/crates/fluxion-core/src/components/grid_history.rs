@@ -0,0 +1,123 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Maximum number of history points to keep (48 hours at 15-minute intervals = 192 points)
+const MAX_HISTORY_POINTS: usize = 192;
+
+/// Single grid power data point
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridHistoryPoint {
+    pub timestamp: DateTime<Utc>,
+    pub import_w: f32, // Power drawn from the grid
+    pub export_w: f32, // Power fed into the grid
+}
+
+impl GridHistoryPoint {
+    /// Build a point from the inverter readings
+    ///
+    /// Dedicated import/export sensors win; otherwise the signed grid power
+    /// (positive = export) is split.
+    pub fn from_readings(
+        timestamp: DateTime<Utc>,
+        grid_power_w: f32,
+        import_w: Option<f32>,
+        export_w: Option<f32>,
+    ) -> Self {
+        Self {
+            timestamp,
+            import_w: import_w.unwrap_or((-grid_power_w).max(0.0)),
+            export_w: export_w.unwrap_or(grid_power_w.max(0.0)),
+        }
+    }
+}
+
+/// Resource for storing grid import/export history over time
+#[derive(Resource, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GridHistory {
+    /// Historical grid power data points (newest first)
+    points: VecDeque<GridHistoryPoint>,
+}
+
+impl GridHistory {
+    /// Add a new data point to the history
+    /// Automatically maintains the size limit
+    pub fn add_point(&mut self, point: GridHistoryPoint) {
+        self.points.push_front(point);
+        self.points.truncate(MAX_HISTORY_POINTS);
+    }
+
+    /// Get history points in chronological order (oldest first)
+    pub fn points_chronological(&self) -> Vec<&GridHistoryPoint> {
+        self.points.iter().rev().collect()
+    }
+
+    /// Get the most recent data point
+    pub fn latest(&self) -> Option<&GridHistoryPoint> {
+        self.points.front()
+    }
+
+    /// Get the number of stored points
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Check if history is empty
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_signed_grid_power() {
+        let now = Utc::now();
+
+        let importing = GridHistoryPoint::from_readings(now, -1500.0, None, None);
+        assert_eq!(importing.import_w, 1500.0);
+        assert_eq!(importing.export_w, 0.0);
+
+        let exporting = GridHistoryPoint::from_readings(now, 800.0, None, None);
+        assert_eq!(exporting.import_w, 0.0);
+        assert_eq!(exporting.export_w, 800.0);
+
+        // Dedicated sensors take precedence over the net reading
+        let metered = GridHistoryPoint::from_readings(now, 800.0, Some(100.0), Some(900.0));
+        assert_eq!(metered.import_w, 100.0);
+        assert_eq!(metered.export_w, 900.0);
+    }
+
+    #[test]
+    fn test_grid_history_size_limit() {
+        let mut history = GridHistory::default();
+        for i in 0..250 {
+            history.add_point(GridHistoryPoint::from_readings(
+                Utc::now() + chrono::Duration::minutes(i),
+                i as f32,
+                None,
+                None,
+            ));
+        }
+
+        assert_eq!(history.len(), MAX_HISTORY_POINTS);
+        assert_eq!(history.latest().unwrap().export_w, 249.0);
+        let chronological = history.points_chronological();
+        assert!(chronological[0].timestamp < chronological[1].timestamp);
+    }
+}
@@ -12,17 +12,21 @@
 
 pub mod battery_history;
 pub mod battery_predictor;
+pub mod block_actuals;
 pub mod consumption_history;
+pub mod grid_history;
 pub mod pv_history;
 
 pub use battery_history::{BatteryHistory, BatteryHistoryPoint};
 pub use battery_predictor::{
     BatteryPrediction, BatteryPredictionPoint, calculate_soc_change, predict_battery_soc,
 };
+pub use block_actuals::{BlockActual, BlockActuals, block_actuals_system};
 pub use consumption_history::{
     ConsumptionHistory, ConsumptionHistoryConfig, DailyEnergySummary, HourlyConsumptionProfile,
     aggregate_daily_consumption, aggregate_hourly_consumption,
 };
+pub use grid_history::{GridHistory, GridHistoryPoint};
 pub use pv_history::{PvHistory, PvHistoryPoint};
 
 use bevy_ecs::prelude::*;
@@ -155,7 +159,8 @@ pub use fluxion_types::pricing::{
 
 // ============= Scheduling Components (Imported from fluxion-types) =============
 pub use fluxion_types::inverter::InverterOperationMode;
-pub use fluxion_types::scheduling::{CurrentMode, OperationSchedule, ScheduledMode};
+pub use fluxion_types::reason::DecisionReason;
+pub use fluxion_types::scheduling::{BlockForecast, CurrentMode, OperationSchedule, ScheduledMode};
 
 /// Pending command to execute on an inverter
 #[derive(Component, Debug, Clone)]
@@ -181,4 +186,7 @@ pub enum InverterCommand {
 
     /// Set export power limit (watts)
     SetExportLimit(u32),
+
+    /// Limit battery charging power (watts), used for partial-power force charging
+    SetChargePowerLimit(u32),
 }
@@ -45,6 +45,14 @@ pub enum UserControlChangeType {
     SlotRemoved,
     /// Fixed time slot modified
     SlotModified,
+    /// Emergency safe state engaged or resumed
+    SafeStateChanged,
+    /// Vacation added or removed
+    VacationsChanged,
+    /// Schedule block pinned or unpinned
+    PinsChanged,
+    /// Backup reserve set or cleared
+    BackupReserveChanged,
     /// Full state update
     FullUpdate,
 }
@@ -83,6 +91,11 @@ impl UserControlUpdateEvent {
         Self::new(new_state, UserControlChangeType::SlotModified)
     }
 
+    /// Create an event for safe state engaged/resumed
+    pub fn safe_state_changed(new_state: UserControlState) -> Self {
+        Self::new(new_state, UserControlChangeType::SafeStateChanged)
+    }
+
     /// Create a full update event
     pub fn full_update(new_state: UserControlState) -> Self {
         Self::new(new_state, UserControlChangeType::FullUpdate)
@@ -102,6 +115,8 @@ pub enum ConfigSection {
     Control,
     /// Strategy configuration (enable/disable, parameters)
     Strategies,
+    /// Logging configuration (levels, file output)
+    Logging,
 }
 
 impl ConfigUpdateEvent {
@@ -121,6 +136,7 @@ impl ConfigUpdateEvent {
         changed_sections.insert(ConfigSection::Pricing);
         changed_sections.insert(ConfigSection::Control);
         changed_sections.insert(ConfigSection::Strategies);
+        changed_sections.insert(ConfigSection::Logging);
 
         Self {
             new_config,
@@ -12,7 +12,7 @@
 
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use std::sync::Arc;
 use tracing::{debug, info, trace, warn};
 
@@ -56,6 +56,94 @@ pub struct InitialModeSyncTracker {
     pub synced_inverters: std::collections::HashSet<String>,
 }
 
+/// How often the battery SOC is compared against the planned trajectory
+const DRIFT_CHECK_INTERVAL_MINUTES: i64 = 5;
+/// Consecutive checks over the threshold before the remaining blocks are replanned
+const DRIFT_CHECKS_TO_REPLAN: u32 = 2;
+/// Minimum time between two drift-triggered replans
+const DRIFT_REPLAN_COOLDOWN_MINUTES: i64 = 30;
+
+/// Closed-loop tracking of the battery SOC against the planned trajectory
+/// Replans only when the drift persists, and not again until the cooldown has passed
+#[derive(Resource, Debug, Default)]
+pub struct ScheduleDriftMonitor {
+    last_check: Option<DateTime<Utc>>,
+    exceeded_checks: u32,
+    last_replan: Option<DateTime<Utc>>,
+}
+
+impl ScheduleDriftMonitor {
+    /// Whether the check interval has elapsed since the last check
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.last_check
+            .is_none_or(|last| now - last >= Duration::minutes(DRIFT_CHECK_INTERVAL_MINUTES))
+    }
+
+    /// Record the drift of one check, returning true when the schedule should be replanned
+    pub fn record(&mut self, now: DateTime<Utc>, drift_pct: f32, threshold_pct: f32) -> bool {
+        self.last_check = Some(now);
+        if drift_pct.abs() <= threshold_pct {
+            self.exceeded_checks = 0;
+            return false;
+        }
+
+        self.exceeded_checks += 1;
+        let cooled_down = self
+            .last_replan
+            .is_none_or(|last| now - last >= Duration::minutes(DRIFT_REPLAN_COOLDOWN_MINUTES));
+        if self.exceeded_checks < DRIFT_CHECKS_TO_REPLAN || !cooled_down {
+            return false;
+        }
+
+        self.exceeded_checks = 0;
+        self.last_replan = Some(now);
+        true
+    }
+}
+
+/// System that replans the remaining blocks when the battery SOC drifts away from the plan
+/// The replan itself runs in `update_prices_system` from the cached prices and current SOC
+pub fn schedule_drift_system(
+    mut monitor: ResMut<ScheduleDriftMonitor>,
+    schedule_query: Query<&OperationSchedule>,
+    raw_state_query: Query<&RawInverterState>,
+    system_config: Res<crate::resources::SystemConfig>,
+    price_cache: Res<crate::resources::PriceCache>,
+) {
+    let threshold = system_config.control_config.replan_drift_pct;
+    let now = Utc::now();
+    if threshold <= 0.0 || !monitor.is_due(now) {
+        return;
+    }
+
+    let Ok(schedule) = schedule_query.single() else {
+        return;
+    };
+    let Some(planned_soc) = crate::scheduling::trajectory::planned_soc_at(schedule, now) else {
+        return;
+    };
+    let count = raw_state_query.iter().count();
+    if count == 0 {
+        return;
+    }
+    let current_soc = raw_state_query
+        .iter()
+        .map(|raw| raw.state.battery_soc)
+        .sum::<f32>()
+        / count as f32;
+
+    let drift = current_soc - planned_soc;
+    if monitor.record(now, drift, threshold) {
+        info!(
+            "🔁 SOC {:.1}% drifted {:+.1}% from the planned {:.1}%, replanning remaining blocks",
+            current_soc, drift, planned_soc
+        );
+        price_cache.request_replan();
+    } else {
+        trace!("SOC drift from plan: {:+.1}%", drift);
+    }
+}
+
 /// System that executes scheduled mode changes
 /// Runs every update cycle to check if mode changes are needed
 ///
@@ -76,9 +164,52 @@ pub fn schedule_execution_system(
     system_config: Res<crate::resources::SystemConfig>,
     mut sync_tracker: ResMut<InitialModeSyncTracker>,
     user_control: Option<Res<crate::resources::UserControlResource>>,
+    grid_quality: Option<Res<crate::grid_quality::GridQualityMonitor>>,
+    peak_demand: Option<Res<crate::peak_demand::PeakDemandMonitor>>,
+    mut charge_power_limits: Local<std::collections::HashMap<String, u32>>,
 ) {
     let now = Utc::now();
 
+    // Emergency safe state takes precedence over everything else:
+    // hold every controllable inverter in the safe mode and skip the schedule entirely
+    if let Some(ref uc) = user_control
+        && let Some(safe_state) = &uc.state.safe_state
+    {
+        let safe_mode = safe_state.effective_mode(system_config.control_config.safe_state_mode);
+        for (mut current_mode, inverter, _, raw_state) in current_mode_query.iter_mut() {
+            if is_slave_inverter(&system_config, &inverter.id) {
+                continue;
+            }
+
+            // Compare against the hardware mode too, so a mode changed behind our back is re-forced
+            let actual_mode = raw_state.map(|r| r.state.work_mode);
+            if current_mode.mode == safe_mode && actual_mode.is_none_or(|m| m == safe_mode) {
+                continue;
+            }
+
+            if debug.enabled {
+                info!(
+                    "🔧 [DEBUG] Safe state active - would set {} to {:?} (triggered by {})",
+                    inverter.id, safe_mode, safe_state.triggered_by
+                );
+            } else {
+                warn!(
+                    "🛑 Safe state active - forcing {} to {:?} (triggered by {} at {})",
+                    inverter.id,
+                    safe_mode,
+                    safe_state.triggered_by,
+                    safe_state.triggered_at.to_rfc3339()
+                );
+                let command = InverterCommand::SetMode(safe_mode);
+                async_writer.write_command_async(inverter.id.clone(), command);
+            }
+            current_mode.mode = safe_mode;
+            current_mode.set_at = now;
+            current_mode.reason = format!("Safe state engaged by {}", safe_state.triggered_by);
+        }
+        return; // Scheduler suspended until manually resumed
+    }
+
     // Check if FluxION is disabled by user
     // When disabled: set all inverters to SelfUse and stop sending mode commands
     if let Some(ref uc) = user_control
@@ -143,15 +274,18 @@ pub fn schedule_execution_system(
                 }
             }
 
-            // Get current scheduled mode
-            if let Some(scheduled_mode) = schedule.get_current_mode(now) {
+            // Get current scheduled mode (per-inverter block in coordinated plans)
+            if let Some(scheduled_mode) = schedule.get_current_mode_for_inverter(now, &inverter.id)
+            {
                 // Clone the scheduled mode so we can potentially modify it
                 let mut effective_mode = scheduled_mode.clone();
 
                 // Check if user has a fixed slot override active RIGHT NOW
                 // This ensures newly created slots take effect immediately
-                if let Some(ref uc) = user_control
-                    && let Some(fixed_slot) = uc.state.get_fixed_slot_at(now)
+                let fixed_slot = user_control
+                    .as_ref()
+                    .and_then(|uc| uc.state.get_fixed_slot_at(now));
+                if let Some(fixed_slot) = fixed_slot
                     && effective_mode.mode != fixed_slot.mode
                 {
                     info!(
@@ -159,12 +293,76 @@ pub fn schedule_execution_system(
                         effective_mode.mode, fixed_slot.mode, fixed_slot.id
                     );
                     effective_mode.mode = fixed_slot.mode;
+                    effective_mode.charge_power_kw = None;
                     effective_mode.reason = format!(
                         "Fixed slot: {}",
                         fixed_slot.note.as_deref().unwrap_or("User override")
                     );
                 }
 
+                // SOC drifted out of the tolerance band around the planned trajectory:
+                // charge earlier, pause charging or stop discharging to get back on it
+                if fixed_slot.is_none()
+                    && let Some(battery) = battery_status
+                    && let Some(correction) = correct_soc_drift(
+                        schedule,
+                        &effective_mode,
+                        current_mode.mode,
+                        battery.soc_percent as f32,
+                        now,
+                        &system_config.control_config,
+                    )
+                    && user_control
+                        .as_ref()
+                        .is_none_or(|uc| uc.state.is_mode_allowed(correction.mode))
+                {
+                    debug!(
+                        "🎯 SOC {:.1}% off the planned {:.1}%: running {:?} instead of {:?}",
+                        battery.soc_percent,
+                        correction.planned_soc,
+                        correction.mode,
+                        effective_mode.mode
+                    );
+                    effective_mode.mode = correction.mode;
+                    effective_mode.charge_power_kw = None;
+                    effective_mode.reason = format!(
+                        "SOC off the planned {:.0}% (planned: {})",
+                        correction.planned_soc, effective_mode.reason
+                    );
+                }
+
+                // Sustained grid over-voltage: keep PV surplus out of the grid
+                if let Some(ref gq) = grid_quality
+                    && let Some(mode) =
+                        gq.substitute_mode(effective_mode.mode, &system_config.grid_quality)
+                {
+                    debug!(
+                        "⚡ Grid over-voltage: running {:?} instead of {:?}",
+                        mode, effective_mode.mode
+                    );
+                    effective_mode.mode = mode;
+                    effective_mode.reason =
+                        format!("Grid over-voltage (planned: {})", effective_mode.reason);
+                }
+
+                // Quarter-hour import near the contracted maximum: cover the load from the battery
+                if let Some(ref pd) = peak_demand
+                    && let Some(mode) = pd.substitute_mode(
+                        &inverter.id,
+                        effective_mode.mode,
+                        &system_config.peak_demand,
+                    )
+                {
+                    debug!(
+                        "📈 Peak demand: running {:?} instead of {:?}",
+                        mode, effective_mode.mode
+                    );
+                    effective_mode.mode = mode;
+                    effective_mode.charge_power_kw = None;
+                    effective_mode.reason =
+                        format!("Peak demand limit (planned: {})", effective_mode.reason);
+                }
+
                 // Check if this scheduled mode applies to this inverter
                 if !should_execute_for_inverter(&effective_mode, &inverter.id) {
                     continue;
@@ -173,6 +371,52 @@ pub fn schedule_execution_system(
                 // Use effective_mode (with potential fixed slot override) for the rest
                 let scheduled_mode = &effective_mode;
 
+                // Partial-power or modulated force charging: limit the charge power during
+                // the block and restore the full rate afterwards. Peak demand shaving lowers
+                // the limit further while the quarter-hour import is near the maximum.
+                let peak_limit_w = peak_demand.as_ref().and_then(|pd| {
+                    pd.charge_limit_w(
+                        &inverter.id,
+                        scheduled_mode.mode,
+                        &system_config.peak_demand,
+                    )
+                });
+                // Backends without charge power control never get a limit
+                if async_writer.supports_charge_power_limit(&inverter.id)
+                    && (system_config.control_config.partial_charge_enabled
+                        || scheduled_mode.charge_power_kw.is_some()
+                        || peak_limit_w.is_some()
+                        || charge_power_limits.contains_key(&inverter.id))
+                {
+                    let full_rate_kw = inv_cfg.battery.as_ref().map_or(
+                        system_config.control_config.max_battery_charge_rate_kw,
+                        |battery| battery.max_charge_rate_kw,
+                    );
+                    let limit_kw = match scheduled_mode.charge_power_kw {
+                        Some(kw) if scheduled_mode.mode == InverterOperationMode::ForceCharge => {
+                            kw.min(full_rate_kw)
+                        }
+                        _ => full_rate_kw,
+                    };
+                    let full_limit_w = (limit_kw * 1000.0).round() as u32;
+                    let limit_w = peak_limit_w.map_or(full_limit_w, |w| w.min(full_limit_w));
+                    if charge_power_limits.get(&inverter.id) != Some(&limit_w) {
+                        if debug.enabled {
+                            info!(
+                                "🔧 [DEBUG] Would limit {} charge power to {}W",
+                                inverter.id, limit_w
+                            );
+                        } else {
+                            info!("📤 Limiting {} charge power to {}W", inverter.id, limit_w);
+                            async_writer.write_command_async(
+                                inverter.id.clone(),
+                                InverterCommand::SetChargePowerLimit(limit_w),
+                            );
+                        }
+                        charge_power_limits.insert(inverter.id.clone(), limit_w);
+                    }
+                }
+
                 // Check if this is the initial sync for this inverter
                 let is_initial_sync = !sync_tracker.synced_inverters.contains(&inverter.id);
 
@@ -292,6 +536,7 @@ pub fn schedule_execution_system(
                         current_mode.set_at = now;
                         current_mode.reason = scheduled_mode.reason.clone();
                     }
+                    crate::metrics::Metrics::global().record_mode_change();
 
                     // Mark inverter as synced after first mode change
                     if is_initial_sync {
@@ -313,6 +558,14 @@ pub fn schedule_execution_system(
     }
 }
 
+/// Check whether an inverter is a slave (controlled through its master)
+fn is_slave_inverter(system_config: &crate::resources::SystemConfig, inverter_id: &str) -> bool {
+    system_config.inverters.iter().any(|i| {
+        i.id == inverter_id
+            && matches!(i.topology, crate::resources::InverterTopology::Slave { .. })
+    })
+}
+
 /// System for initializing inverter entities on startup
 pub fn initialize_inverters_system(
     mut commands: Commands,
@@ -448,10 +701,16 @@ impl Plugin for ContinuousSystemsPlugin {
             .init_resource::<BatteryHistoryInitialized>()
             // Initialize PV generation history resources
             .init_resource::<PvHistory>()
+            // Initialize grid import/export history resources
+            .init_resource::<GridHistory>()
+            // Initialize realized energy per block for export annotations
+            .init_resource::<BlockActuals>()
             // Initialize consumption history for winter adaptive strategy
             .init_resource::<crate::components::ConsumptionHistory>()
             // Initialize mode sync tracker for initial sync bypass
             .init_resource::<InitialModeSyncTracker>()
+            // Initialize closed-loop SOC drift tracking
+            .init_resource::<ScheduleDriftMonitor>()
             .add_systems(
                 Startup,
                 (
@@ -473,6 +732,8 @@ impl Plugin for ContinuousSystemsPlugin {
                     crate::async_systems::read_inverter_states_system,
                     // Decompose RawInverterState into individual components
                     crate::async_systems::decompose_inverter_state,
+                    // Integrate realized solar and consumption per block
+                    block_actuals_system,
                     // Keep schedule execution but update to use channels
                     schedule_execution_system,
                     // Trigger battery history fetch periodically
@@ -482,6 +743,433 @@ impl Plugin for ContinuousSystemsPlugin {
                     // New channel-based systems (non-blocking)
                     crate::async_systems::update_prices_system,
                 ),
+            )
+            // Replan the remaining blocks when SOC drifts from the trajectory
+            .add_systems(
+                Update,
+                schedule_drift_system
+                    .after(schedule_execution_system)
+                    .before(crate::async_systems::update_prices_system)
+                    .run_if(resource_exists::<crate::resources::PriceCache>),
+            )
+            // Log executed decisions once main.rs inserts the decision log
+            .add_systems(
+                Update,
+                crate::decision_log::decision_log_system
+                    .after(schedule_execution_system)
+                    .run_if(resource_exists::<crate::decision_log::DecisionLog>),
+            )
+            // Post schedule and mode changes once main.rs inserts the dispatcher
+            .add_systems(
+                Update,
+                crate::webhooks::webhook_system
+                    .after(schedule_execution_system)
+                    .run_if(resource_exists::<crate::webhooks::WebhookDispatcher>),
+            )
+            // Snapshot key state for the developer inspector once main.rs inserts it
+            .add_systems(
+                Update,
+                crate::inspector::ecs_inspector_system
+                    .after(schedule_execution_system)
+                    .run_if(resource_exists::<crate::inspector::EcsInspector>),
+            )
+            // Sample telemetry into the historian once main.rs inserts it
+            .add_systems(
+                Startup,
+                crate::historian::seed_chart_history_system
+                    .run_if(resource_exists::<crate::historian::Historian>),
+            )
+            .add_systems(
+                Update,
+                crate::historian::historian_system
+                    .after(crate::async_systems::decompose_inverter_state)
+                    .run_if(resource_exists::<crate::historian::Historian>),
+            )
+            .add_systems(
+                Update,
+                crate::submeter::submeter_system
+                    .run_if(resource_exists::<crate::submeter::SubmeterChannel>)
+                    .run_if(resource_exists::<crate::historian::Historian>),
+            )
+            // Account realized savings once main.rs inserts the ledger
+            .add_systems(
+                Update,
+                crate::savings::savings_system
+                    .after(block_actuals_system)
+                    .run_if(resource_exists::<crate::savings::SavingsLedger>),
             );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::GenericInverterState;
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use bevy_ecs::system::RunSystemOnce;
+    use fluxion_types::pricing::{SpotPriceData, TimeBlockPrice};
+
+    struct FlatPrices {
+        first_block: DateTime<Utc>,
+    }
+
+    #[async_trait]
+    impl PriceDataSource for FlatPrices {
+        async fn read_prices(&self) -> Result<SpotPriceData> {
+            Ok(SpotPriceData {
+                time_block_prices: (0..16)
+                    .map(|i| TimeBlockPrice {
+                        block_start: self.first_block + Duration::minutes(15 * i),
+                        duration_minutes: 15,
+                        price_czk_per_kwh: 2.0,
+                        effective_price_czk_per_kwh: 2.0,
+                        spot_sell_price_czk_per_kwh: None,
+                    })
+                    .collect(),
+                ..SpotPriceData::default()
+            })
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "flat"
+        }
+    }
+
+    /// Minimal config; everything not listed takes its serde default
+    fn system_config() -> crate::resources::SystemConfig {
+        serde_json::from_value(serde_json::json!({
+            "inverters": [],
+            "pricing": {
+                "spot_price_entity": "sensor.spot_price",
+                "use_spot_prices_to_buy": true,
+                "use_spot_prices_to_sell": true,
+                "fixed_buy_price_czk": 4.0,
+                "fixed_sell_price_czk": 2.0,
+            },
+            "control": crate::ControlConfig::default(),
+            "system": {
+                "update_interval_secs": 60,
+                "debug_mode": true,
+                "display_currency": crate::Currency::CZK,
+            },
+        }))
+        .unwrap()
+    }
+
+    fn schedule(world: &mut World) -> OperationSchedule {
+        world
+            .query::<&OperationSchedule>()
+            .single(world)
+            .unwrap()
+            .clone()
+    }
+
+    /// Put the battery `offset` SOC % off the plan for two drift checks, then refresh prices
+    fn drift_and_refresh(world: &mut World, offset: f32) {
+        let planned = crate::scheduling::trajectory::planned_soc_at(&schedule(world), Utc::now())
+            .expect("schedule covers now");
+        for mut raw in world.query::<&mut RawInverterState>().iter_mut(world) {
+            raw.state.battery_soc = planned + offset;
+        }
+        for _ in 0..DRIFT_CHECKS_TO_REPLAN {
+            world.resource_mut::<ScheduleDriftMonitor>().last_check = None;
+            world.run_system_once(schedule_drift_system).unwrap();
+        }
+        world
+            .run_system_once(crate::async_systems::update_prices_system)
+            .unwrap();
+    }
+
+    /// World that plans from flat prices with the default strategies
+    fn planning_world(config: crate::resources::SystemConfig) -> World {
+        let mut world = World::new();
+        let plugin_manager = crate::plugin_adapters::create_plugin_manager(
+            Some(&config.strategies_config),
+            &config.control_config,
+            None,
+        );
+        world.insert_resource(crate::PluginManagerResource(Arc::new(
+            parking_lot::RwLock::new(plugin_manager),
+        )));
+        world.insert_resource(config);
+        world.insert_resource(ConsumptionHistory::default());
+        world.insert_resource(ScheduleDriftMonitor::default());
+        // Stale on every run, like the price cache refreshing every few seconds
+        world.insert_resource(crate::resources::PriceCache::new(
+            Arc::new(FlatPrices {
+                first_block: Utc::now() - Duration::minutes(5),
+            }),
+            0,
+        ));
+        world.spawn(RawInverterState {
+            state: GenericInverterState {
+                battery_soc: 50.0,
+                ..GenericInverterState::default()
+            },
+            last_updated: Utc::now(),
+        });
+        world
+    }
+
+    /// Refresh prices and return when the current schedule was generated
+    fn refresh(world: &mut World) -> DateTime<Utc> {
+        world
+            .run_system_once(crate::async_systems::update_prices_system)
+            .unwrap();
+        schedule(world).generated_at
+    }
+
+    #[test]
+    fn test_schedule_replanned_only_outside_drift_band() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _runtime = runtime.enter();
+
+        let mut config = system_config();
+        config.control_config.replan_drift_pct = 10.0;
+        let mut world = planning_world(config);
+        let planned = refresh(&mut world);
+
+        // Refreshed prices that didn't change keep the plan
+        assert_eq!(refresh(&mut world), planned);
+
+        // Drift within the band keeps it too
+        drift_and_refresh(&mut world, 3.0);
+        assert_eq!(schedule(&mut world).generated_at, planned);
+
+        // Drift outside the band replans the remaining blocks
+        drift_and_refresh(&mut world, 25.0);
+        assert_ne!(schedule(&mut world).generated_at, planned);
+    }
+
+    #[test]
+    fn test_plugin_change_replans() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _runtime = runtime.enter();
+
+        let mut world = planning_world(system_config());
+        // The system remembers which plugins it planned with between runs
+        let mut update = Schedule::default();
+        update.add_systems(crate::async_systems::update_prices_system);
+        let mut refresh = |world: &mut World| {
+            update.run(world);
+            schedule(world).generated_at
+        };
+        let planned = refresh(&mut world);
+        assert_eq!(refresh(&mut world), planned);
+
+        {
+            let manager = world.resource::<crate::PluginManagerResource>().0.clone();
+            let mut manager = manager.write();
+            let name = manager.list_plugins()[0].0.to_owned();
+            assert!(manager.set_priority(&name, 1));
+        }
+        assert_ne!(refresh(&mut world), planned);
+    }
+
+    #[test]
+    fn test_weather_change_replans() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _runtime = runtime.enter();
+
+        let mut world = planning_world(system_config());
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        world.insert_resource(crate::weather::WeatherChannel { receiver });
+        world.init_resource::<crate::weather::WeatherForecast>();
+        let points = vec![fluxion_types::weather::WeatherPoint {
+            time: Utc::now(),
+            cloud_cover_pct: Some(90.0),
+            temperature_c: Some(-5.0),
+        }];
+        let mut poll_and_refresh = |points: &Vec<_>| {
+            sender.send(points.clone()).unwrap();
+            world
+                .run_system_once(crate::weather::poll_weather_channel)
+                .unwrap();
+            refresh(&mut world)
+        };
+
+        let planned = poll_and_refresh(&points);
+        // The same forecast again keeps the plan
+        assert_eq!(poll_and_refresh(&points), planned);
+        let mut clearer = points.clone();
+        clearer[0].cloud_cover_pct = Some(10.0);
+        assert_ne!(poll_and_refresh(&clearer), planned);
+    }
+
+    #[test]
+    fn test_overvoltage_replans() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _runtime = runtime.enter();
+
+        let mut config = system_config();
+        config.grid_quality.sustained_minutes = 1;
+        let mut world = planning_world(config);
+        let monitor = crate::grid_quality::GridQualityMonitor::default();
+        world.insert_resource(monitor.clone());
+        let inverter = world
+            .query_filtered::<Entity, With<RawInverterState>>()
+            .single(&world)
+            .unwrap();
+        world.entity_mut(inverter).insert(Inverter {
+            id: "main".to_owned(),
+            inverter_type: crate::InverterType::Solax,
+        });
+        let planned = refresh(&mut world);
+
+        // Feed high voltage until the over-voltage event starts
+        let mut samples = 0;
+        while !monitor.overvoltage_active() {
+            samples += 1;
+            assert!(samples < 100, "over-voltage never became active");
+            let mut raw = world.get_mut::<RawInverterState>(inverter).unwrap();
+            raw.state.inverter_voltage_v = Some(260.0);
+            raw.last_updated += Duration::seconds(5);
+            world
+                .run_system_once(crate::grid_quality::grid_quality_observer_system)
+                .unwrap();
+            if !monitor.overvoltage_active() {
+                assert_eq!(refresh(&mut world), planned);
+            }
+        }
+
+        // The reduced export limit takes effect in a new plan
+        assert_ne!(refresh(&mut world), planned);
+    }
+
+    /// Records writes; charge power limits only when `charge_power_limit` is set
+    #[derive(Default)]
+    struct LimitSource {
+        charge_power_limit: bool,
+        writes: parking_lot::Mutex<Vec<InverterCommand>>,
+    }
+
+    #[async_trait]
+    impl crate::traits::InverterDataSource for LimitSource {
+        async fn read_state(&self, inverter_id: &str) -> Result<GenericInverterState> {
+            Ok(GenericInverterState {
+                inverter_id: inverter_id.to_owned(),
+                ..GenericInverterState::default()
+            })
+        }
+
+        async fn write_command(&self, _inverter_id: &str, command: &InverterCommand) -> Result<()> {
+            self.writes.lock().push(command.clone());
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "limit"
+        }
+
+        fn supports_charge_power_limit(&self, _inverter_id: &str) -> bool {
+            self.charge_power_limit
+        }
+    }
+
+    /// Run one execution cycle of a 3 kW force-charge block against `source`
+    fn execute_partial_charge(source: Arc<LimitSource>) -> Vec<InverterCommand> {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _runtime = runtime.enter();
+
+        let mut config = system_config();
+        config.control_config.charge_power_modulation_enabled = true;
+        config.inverters = vec![crate::resources::InverterConfig {
+            id: "main".to_owned(),
+            inverter_type: crate::InverterType::Solax,
+            entity_prefix: "solax".to_owned(),
+            topology: crate::resources::InverterTopology::Independent,
+            battery: None,
+        }];
+        let mut world = World::new();
+        world.insert_resource(config);
+        world.insert_resource(crate::resources::AsyncInverterWriter::new(source.clone()));
+        world.insert_resource(ExecutionConfig::default());
+        world.insert_resource(DebugModeConfig::disabled());
+        world.init_resource::<InitialModeSyncTracker>();
+        world.spawn(OperationSchedule {
+            scheduled_blocks: vec![ScheduledMode {
+                block_start: Utc::now() - Duration::minutes(5),
+                duration_minutes: 15,
+                target_inverters: None,
+                mode: InverterOperationMode::ForceCharge,
+                reason: "cheap".to_owned(),
+                decision_reason: None,
+                decision_uid: None,
+                charge_power_kw: Some(3.0),
+                target_soc: None,
+                forecast: None,
+                debug_info: None,
+            }],
+            ..OperationSchedule::default()
+        });
+        world.spawn((
+            Inverter {
+                id: "main".to_owned(),
+                inverter_type: crate::InverterType::Solax,
+            },
+            CurrentMode {
+                mode: InverterOperationMode::ForceCharge,
+                set_at: Utc::now(),
+                reason: "planned".to_owned(),
+            },
+        ));
+        world.run_system_once(schedule_execution_system).unwrap();
+
+        // Let the spawned writes land
+        runtime.block_on(tokio::time::sleep(std::time::Duration::from_millis(100)));
+        source.writes.lock().clone()
+    }
+
+    #[test]
+    fn test_charge_power_limit_only_sent_to_capable_backends() {
+        let capable = execute_partial_charge(Arc::new(LimitSource {
+            charge_power_limit: true,
+            ..LimitSource::default()
+        }));
+        assert!(
+            capable
+                .iter()
+                .any(|command| matches!(command, InverterCommand::SetChargePowerLimit(3000))),
+            "{capable:?}"
+        );
+
+        let incapable = execute_partial_charge(Arc::new(LimitSource::default()));
+        assert!(
+            !incapable
+                .iter()
+                .any(|command| matches!(command, InverterCommand::SetChargePowerLimit(_))),
+            "{incapable:?}"
+        );
+    }
+
+    #[test]
+    fn test_drift_monitor_hysteresis() {
+        let start = Utc::now();
+        let at = |minutes: i64| start + Duration::minutes(minutes);
+        let mut monitor = ScheduleDriftMonitor::default();
+
+        assert!(monitor.is_due(at(0)));
+        // A single excursion is not enough
+        assert!(!monitor.record(at(0), 12.0, 10.0));
+        assert!(!monitor.is_due(at(4)));
+        assert!(monitor.is_due(at(5)));
+        // Back within the threshold resets the count
+        assert!(!monitor.record(at(5), -4.0, 10.0));
+        assert!(!monitor.record(at(10), -15.0, 10.0));
+        assert!(monitor.record(at(15), -15.0, 10.0));
+
+        // Cooldown blocks a new replan even when drift persists
+        assert!(!monitor.record(at(20), 20.0, 10.0));
+        assert!(!monitor.record(at(25), 20.0, 10.0));
+        assert!(monitor.record(at(45), 20.0, 10.0));
+    }
+}
@@ -12,6 +12,8 @@
 
 use bevy_ecs::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use tracing::{info, warn};
 
 /// Debug mode configuration resource
@@ -111,6 +113,40 @@ impl DebugModeConfig {
     }
 }
 
+/// Debug mode flag readable outside the ECS world
+///
+/// Inverter adapters that write directly to hardware hold a clone of this and
+/// check it before every write. [`sync_shared_debug_mode_system`] keeps it in
+/// step with [`DebugModeConfig`] when debug mode is toggled at runtime.
+#[derive(Resource, Debug, Clone)]
+pub struct SharedDebugMode(Arc<AtomicBool>);
+
+impl Default for SharedDebugMode {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+impl SharedDebugMode {
+    pub fn new(enabled: bool) -> Self {
+        Self(Arc::new(AtomicBool::new(enabled)))
+    }
+
+    /// Check if debug mode is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    pub fn set(&self, enabled: bool) {
+        self.0.store(enabled, Ordering::Relaxed);
+    }
+}
+
+/// Mirror [`DebugModeConfig`] into [`SharedDebugMode`]
+pub fn sync_shared_debug_mode_system(debug: Res<DebugModeConfig>, shared: Res<SharedDebugMode>) {
+    shared.set(debug.enabled);
+}
+
 /// Result type for debug mode operations
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum DebugExecutionResult {
@@ -0,0 +1,375 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Persistent log of executed block decisions.
+//!
+//! Schedules are replaced every planning cycle, so the reason behind a past
+//! charge or discharge is otherwise only found in the daily export. Every
+//! block the executor runs is appended to `./data/decisions.db` with the
+//! planned and the executed mode, the reason and decision UID, the SOC when
+//! the block started and the SOC predicted for its end. Once the block is over
+//! the measured SOC is added, so `/api/decisions` shows both why FluxION acted
+//! and how the prediction held up. Rows older than the retention are deleted.
+
+use crate::components::{
+    CurrentMode, Inverter, InverterOperationMode, OperationSchedule, RawInverterState,
+    ScheduledMode, predict_battery_soc,
+};
+use crate::debug::DebugModeConfig;
+use crate::resources::{InverterTopology, SystemConfig};
+use anyhow::{Context, Result};
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use rusqlite::{Connection, params};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Default path for the decision log database
+pub const DEFAULT_DECISION_LOG_PATH: &str = "./data/decisions.db";
+
+/// One executed block of one inverter
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecisionRecord {
+    pub inverter_id: String,
+    pub block_start: DateTime<Utc>,
+    pub duration_minutes: u32,
+    /// Mode the schedule planned for the block
+    pub planned_mode: InverterOperationMode,
+    /// Mode the inverter was set to (differs on user overrides, safe state or debounce)
+    pub executed_mode: InverterOperationMode,
+    pub reason: String,
+    pub decision_uid: Option<String>,
+    /// Why the executed mode differs from the planned one
+    pub override_reason: Option<String>,
+    /// Debug mode: the mode was only simulated
+    pub debug_mode: bool,
+    /// Battery SOC when the block was logged (%)
+    pub soc_start: Option<f32>,
+    /// Battery SOC predicted for the end of the block (%)
+    pub predicted_soc_end: Option<f32>,
+    /// Battery SOC measured after the block (%)
+    pub actual_soc_end: Option<f32>,
+}
+
+/// SQLite decision log shared by the ECS observer and the web API
+#[derive(Resource, Clone)]
+pub struct DecisionLog {
+    conn: Arc<Mutex<Connection>>,
+    retention: Duration,
+}
+
+impl std::fmt::Debug for DecisionLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DecisionLog")
+            .field("retention", &self.retention)
+            .finish_non_exhaustive()
+    }
+}
+
+fn mode_name(mode: InverterOperationMode) -> String {
+    format!("{mode:?}")
+}
+
+fn parse_mode(name: &str) -> InverterOperationMode {
+    serde_json::from_value(serde_json::Value::String(name.to_owned())).unwrap_or_default()
+}
+
+impl DecisionLog {
+    /// Open (or create) the log at `path`, keeping `retention_days` of decisions
+    pub fn open(path: impl AsRef<Path>, retention_days: u32) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open decision log {}", path.display()))?;
+        Self::init(conn, retention_days)
+    }
+
+    /// Open an in-memory log (nothing survives a restart)
+    pub fn open_in_memory(retention_days: u32) -> Result<Self> {
+        Self::init(Connection::open_in_memory()?, retention_days)
+    }
+
+    fn init(conn: Connection, retention_days: u32) -> Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS decisions (
+                inverter_id        TEXT NOT NULL,
+                block_start        INTEGER NOT NULL,
+                duration_minutes   INTEGER NOT NULL,
+                planned_mode       TEXT NOT NULL,
+                executed_mode      TEXT NOT NULL,
+                reason             TEXT NOT NULL,
+                decision_uid       TEXT,
+                override_reason    TEXT,
+                debug_mode         INTEGER NOT NULL,
+                soc_start          REAL,
+                predicted_soc_end  REAL,
+                actual_soc_end     REAL,
+                PRIMARY KEY (inverter_id, block_start)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_decisions_block_start
+                ON decisions(block_start);",
+        )
+        .context("Failed to create decision log table")?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            retention: Duration::days(i64::from(retention_days.max(1))),
+        })
+    }
+
+    /// Append a block, or update the executed mode of a block logged before
+    ///
+    /// Decisions older than the retention are deleted on the way.
+    pub fn record(&self, record: &DecisionRecord) -> Result<()> {
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO decisions (
+                inverter_id, block_start, duration_minutes, planned_mode, executed_mode,
+                reason, decision_uid, override_reason, debug_mode, soc_start,
+                predicted_soc_end, actual_soc_end
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+            ON CONFLICT(inverter_id, block_start) DO UPDATE SET
+                executed_mode = excluded.executed_mode,
+                override_reason = excluded.override_reason",
+            params![
+                record.inverter_id,
+                record.block_start.timestamp(),
+                record.duration_minutes,
+                mode_name(record.planned_mode),
+                mode_name(record.executed_mode),
+                record.reason,
+                record.decision_uid,
+                record.override_reason,
+                record.debug_mode,
+                record.soc_start,
+                record.predicted_soc_end,
+                record.actual_soc_end,
+            ],
+        )?;
+        conn.execute(
+            "DELETE FROM decisions WHERE block_start < ?1",
+            params![(record.block_start - self.retention).timestamp()],
+        )?;
+        Ok(())
+    }
+
+    /// Add the SOC measured after a block
+    pub fn record_outcome(
+        &self,
+        inverter_id: &str,
+        block_start: DateTime<Utc>,
+        actual_soc_end: f32,
+    ) -> Result<()> {
+        self.conn.lock().execute(
+            "UPDATE decisions SET actual_soc_end = ?3
+             WHERE inverter_id = ?1 AND block_start = ?2",
+            params![inverter_id, block_start.timestamp(), actual_soc_end],
+        )?;
+        Ok(())
+    }
+
+    /// Decisions for blocks starting in `[from, to)`, oldest first
+    pub fn query(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<DecisionRecord>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT inverter_id, block_start, duration_minutes, planned_mode, executed_mode,
+                    reason, decision_uid, override_reason, debug_mode, soc_start,
+                    predicted_soc_end, actual_soc_end
+             FROM decisions
+             WHERE block_start >= ?1 AND block_start < ?2
+             ORDER BY block_start, inverter_id",
+        )?;
+        let rows = stmt.query_map(params![from.timestamp(), to.timestamp()], |row| {
+            let planned: String = row.get(3)?;
+            let executed: String = row.get(4)?;
+            Ok(DecisionRecord {
+                inverter_id: row.get(0)?,
+                block_start: DateTime::from_timestamp(row.get(1)?, 0).unwrap_or_default(),
+                duration_minutes: row.get(2)?,
+                planned_mode: parse_mode(&planned),
+                executed_mode: parse_mode(&executed),
+                reason: row.get(5)?,
+                decision_uid: row.get(6)?,
+                override_reason: row.get(7)?,
+                debug_mode: row.get(8)?,
+                soc_start: row.get(9)?,
+                predicted_soc_end: row.get(10)?,
+                actual_soc_end: row.get(11)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+/// SOC at the end of `block` when it starts at `soc`
+fn predict_block_end_soc(
+    block: &ScheduledMode,
+    system_config: &SystemConfig,
+    soc: f32,
+    raw: &RawInverterState,
+) -> Option<f32> {
+    let single_block = OperationSchedule {
+        scheduled_blocks: vec![block.clone()],
+        ..OperationSchedule::default()
+    };
+    let rate_kw = system_config.control_config.max_battery_charge_rate_kw;
+    let prediction = predict_battery_soc(
+        &single_block,
+        &system_config.control_config,
+        soc,
+        Some(rate_kw),
+        Some(rate_kw),
+        raw.state.house_load_w,
+        Some(raw.state.pv_power_w),
+    );
+    prediction.points().back().map(|point| point.soc_percent)
+}
+
+/// Log each block the executor runs and the SOC measured after it
+///
+/// Runs after the schedule executor so [`CurrentMode`] holds the mode it set.
+pub fn decision_log_system(
+    log: Res<DecisionLog>,
+    debug: Res<DebugModeConfig>,
+    system_config: Res<SystemConfig>,
+    schedule_query: Query<&OperationSchedule>,
+    inverters: Query<(&Inverter, &CurrentMode, Option<&RawInverterState>)>,
+    mut logged: Local<HashMap<String, (DateTime<Utc>, InverterOperationMode)>>,
+) {
+    let Ok(schedule) = schedule_query.single() else {
+        return;
+    };
+    let now = Utc::now();
+
+    for (inverter, current_mode, raw) in &inverters {
+        // Slaves follow their master's decisions
+        let is_slave = system_config
+            .inverters
+            .iter()
+            .any(|i| i.id == inverter.id && matches!(i.topology, InverterTopology::Slave { .. }));
+        if is_slave {
+            continue;
+        }
+        let Some(block) = schedule.get_current_mode_for_inverter(now, &inverter.id) else {
+            continue;
+        };
+        let previous = logged.get(&inverter.id).copied();
+        if previous == Some((block.block_start, current_mode.mode)) {
+            continue;
+        }
+
+        let soc = raw.map(|r| r.state.battery_soc);
+        if let Some((previous_start, _)) = previous
+            && previous_start != block.block_start
+            && let Some(soc) = soc
+            && let Err(e) = log.record_outcome(&inverter.id, previous_start, soc)
+        {
+            warn!("Failed to log outcome of block {previous_start}: {e:#}");
+        }
+
+        let record = DecisionRecord {
+            inverter_id: inverter.id.clone(),
+            block_start: block.block_start,
+            duration_minutes: block.duration_minutes,
+            planned_mode: block.mode,
+            executed_mode: current_mode.mode,
+            reason: block.reason.clone(),
+            decision_uid: block.decision_uid.clone(),
+            override_reason: (current_mode.mode != block.mode).then(|| current_mode.reason.clone()),
+            debug_mode: debug.is_enabled(),
+            soc_start: soc,
+            predicted_soc_end: raw.and_then(|raw| {
+                predict_block_end_soc(block, &system_config, raw.state.battery_soc, raw)
+            }),
+            actual_soc_end: None,
+        };
+        if let Err(e) = log.record(&record) {
+            warn!("Failed to log decision for {}: {e:#}", inverter.id);
+        }
+        logged.insert(inverter.id.clone(), (block.block_start, current_mode.mode));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(block_start: DateTime<Utc>, executed_mode: InverterOperationMode) -> DecisionRecord {
+        DecisionRecord {
+            inverter_id: "solax".to_owned(),
+            block_start,
+            duration_minutes: 15,
+            planned_mode: InverterOperationMode::ForceCharge,
+            executed_mode,
+            reason: "Cheapest block".to_owned(),
+            decision_uid: Some("winter_adaptive_v20:scheduled_charge".to_owned()),
+            override_reason: None,
+            debug_mode: false,
+            soc_start: Some(40.0),
+            predicted_soc_end: Some(45.0),
+            actual_soc_end: None,
+        }
+    }
+
+    #[test]
+    fn test_decisions_are_logged_with_outcome() {
+        let log = DecisionLog::open_in_memory(30).unwrap();
+        let start: DateTime<Utc> = "2025-06-01T02:00:00Z".parse().unwrap();
+
+        log.record(&record(start, InverterOperationMode::SelfUse))
+            .unwrap();
+        // The debounced mode change lands later in the same block
+        log.record(&record(start, InverterOperationMode::ForceCharge))
+            .unwrap();
+        log.record_outcome("solax", start, 44.5).unwrap();
+
+        let decisions = log.query(start, start + Duration::minutes(15)).unwrap();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(
+            decisions[0].executed_mode,
+            InverterOperationMode::ForceCharge
+        );
+        assert_eq!(
+            decisions[0].decision_uid.as_deref(),
+            Some("winter_adaptive_v20:scheduled_charge")
+        );
+        assert_eq!(decisions[0].predicted_soc_end, Some(45.0));
+        assert_eq!(decisions[0].actual_soc_end, Some(44.5));
+        assert!(
+            log.query(start - Duration::hours(1), start)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_old_decisions_are_deleted() {
+        let log = DecisionLog::open_in_memory(30).unwrap();
+        let old: DateTime<Utc> = "2025-04-01T02:00:00Z".parse().unwrap();
+        let recent: DateTime<Utc> = "2025-06-01T02:00:00Z".parse().unwrap();
+
+        log.record(&record(old, InverterOperationMode::ForceCharge))
+            .unwrap();
+        log.record(&record(recent, InverterOperationMode::ForceCharge))
+            .unwrap();
+
+        let decisions = log.query(old, recent + Duration::days(1)).unwrap();
+        assert_eq!(decisions.len(), 1);
+        assert_eq!(decisions[0].block_start, recent);
+    }
+}
@@ -0,0 +1,467 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Price- and solar-aware hot water (DHW) target temperatures.
+//!
+//! [`dhw_system`] plans a target temperature for every upcoming block: the
+//! boost target while PV surplus is expected or the price is in the cheapest
+//! quarter, the eco target in the most expensive quarter and the normal target
+//! otherwise, so the tank stores heat when energy is cheap. The current
+//! block's target is written to the configured Home Assistant `water_heater`
+//! or `climate` entity whenever it changes. The plan is served at `/api/dhw`
+//! and drawn under the dashboard price chart.
+
+use crate::components::{OperationSchedule, SpotPriceData};
+use crate::debug::DebugModeConfig;
+use anyhow::Result;
+use async_trait::async_trait;
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use fluxion_types::pricing::TimeBlockPrice;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Minimum time between two plan updates
+const PLANNING_INTERVAL: Duration = Duration::seconds(60);
+
+/// Blocks at or below this price percentile get the boost target
+const CHEAP_PERCENTILE: f32 = 0.25;
+
+/// Blocks at or above this price percentile get the eco target
+const EXPENSIVE_PERCENTILE: f32 = 0.75;
+
+/// Hot water scheduler settings (`[dhw]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DhwConfig {
+    pub enabled: bool,
+    /// `water_heater.*` or `climate.*` entity receiving the target temperature
+    pub entity_id: String,
+    /// Target in ordinary blocks (°C)
+    pub normal_temp_c: f32,
+    /// Target during PV surplus and in the cheapest blocks (°C)
+    pub boost_temp_c: f32,
+    /// Target in the most expensive blocks (°C)
+    pub eco_temp_c: f32,
+    /// Expected PV surplus (production minus household load) that counts as solar heating
+    pub solar_surplus_kw: f32,
+}
+
+impl Default for DhwConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            entity_id: String::new(),
+            normal_temp_c: 50.0,
+            boost_temp_c: 60.0,
+            eco_temp_c: 45.0,
+            solar_surplus_kw: 1.5,
+        }
+    }
+}
+
+impl DhwConfig {
+    /// Check the settings
+    ///
+    /// # Errors
+    /// Describes the first invalid setting
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.entity_id.starts_with("water_heater.") && !self.entity_id.starts_with("climate.") {
+            return Err(format!(
+                "entity_id must be a water_heater or climate entity, got '{}'",
+                self.entity_id
+            ));
+        }
+        let temps = [self.eco_temp_c, self.normal_temp_c, self.boost_temp_c];
+        if temps.iter().any(|t| !(20.0..=90.0).contains(t)) {
+            return Err("temperatures must be between 20 and 90 °C".to_owned());
+        }
+        if !(self.eco_temp_c <= self.normal_temp_c && self.normal_temp_c <= self.boost_temp_c) {
+            return Err("expected eco_temp_c <= normal_temp_c <= boost_temp_c".to_owned());
+        }
+        if self.solar_surplus_kw <= 0.0 {
+            return Err("solar_surplus_kw must be positive".to_owned());
+        }
+        Ok(())
+    }
+}
+
+/// Why a block got its target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DhwReason {
+    /// Expected PV surplus heats the water
+    Solar,
+    /// Price in the cheapest quarter
+    Cheap,
+    Normal,
+    /// Price in the most expensive quarter
+    Expensive,
+}
+
+/// Planned target temperature of one block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DhwBlock {
+    pub block_start: DateTime<Utc>,
+    pub duration_minutes: u32,
+    pub target_temp_c: f32,
+    pub reason: DhwReason,
+    pub price_czk_per_kwh: f32,
+}
+
+/// Plan targets for the current and future blocks of `prices`
+///
+/// PV surplus comes from the per-block forecasts of `schedule`.
+#[must_use]
+pub fn plan_dhw(
+    config: &DhwConfig,
+    prices: &[TimeBlockPrice],
+    schedule: Option<&OperationSchedule>,
+    now: DateTime<Utc>,
+) -> Vec<DhwBlock> {
+    let upcoming: Vec<&TimeBlockPrice> = prices
+        .iter()
+        .filter(|b| b.block_start + Duration::minutes(i64::from(b.duration_minutes)) > now)
+        .collect();
+    let mut sorted: Vec<f32> = upcoming
+        .iter()
+        .map(|b| b.effective_price_czk_per_kwh)
+        .filter(|p| p.is_finite())
+        .collect();
+    sorted.sort_by(f32::total_cmp);
+    let cheap = percentile(&sorted, CHEAP_PERCENTILE);
+    let expensive = percentile(&sorted, EXPENSIVE_PERCENTILE);
+    // Flat prices leave nothing to shift
+    let flat = sorted.first() == sorted.last();
+
+    upcoming
+        .into_iter()
+        .map(|block| {
+            let price = block.effective_price_czk_per_kwh;
+            let surplus_kw = schedule
+                .and_then(|s| s.get_mode_at(block.block_start))
+                .and_then(|b| b.forecast.as_ref())
+                .map(|f| f.solar_kwh - f.consumption_kwh)
+                .and_then(|kwh| per_hour(kwh, block.duration_minutes));
+            let reason = if surplus_kw.is_some_and(|kw| kw >= config.solar_surplus_kw) {
+                DhwReason::Solar
+            } else if !flat && cheap.is_some_and(|cheap| price <= cheap) {
+                DhwReason::Cheap
+            } else if !flat && expensive.is_some_and(|expensive| price >= expensive) {
+                DhwReason::Expensive
+            } else {
+                DhwReason::Normal
+            };
+            let target_temp_c = match reason {
+                DhwReason::Solar | DhwReason::Cheap => config.boost_temp_c,
+                DhwReason::Normal => config.normal_temp_c,
+                DhwReason::Expensive => config.eco_temp_c,
+            };
+            DhwBlock {
+                block_start: block.block_start,
+                duration_minutes: block.duration_minutes,
+                target_temp_c,
+                reason,
+                price_czk_per_kwh: price,
+            }
+        })
+        .collect()
+}
+
+/// Average power (kW) of `kwh` spread over `duration_minutes`
+fn per_hour(kwh: f32, duration_minutes: u32) -> Option<f32> {
+    let minutes = u16::try_from(duration_minutes).ok().filter(|m| *m > 0)?;
+    Some(kwh * 60.0 / f32::from(minutes))
+}
+
+/// Nearest-rank percentile of ascending `sorted` prices
+#[expect(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn percentile(sorted: &[f32], fraction: f32) -> Option<f32> {
+    let last = sorted.len().checked_sub(1)?;
+    let index = (last as f32 * fraction).round() as usize;
+    sorted.get(index.min(last)).copied()
+}
+
+/// Writes the target temperature to the hot water entity
+#[expect(clippy::double_must_use, reason = "generated by async_trait")]
+#[async_trait]
+pub trait DhwController: Send + Sync {
+    async fn set_target_temperature(&self, entity_id: &str, temp_c: f32) -> Result<()>;
+}
+
+/// Current plan and the state of the entity, as served by `/api/dhw`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DhwOverview {
+    pub enabled: bool,
+    pub entity_id: String,
+    /// Target of the current block
+    pub current_target_c: Option<f32>,
+    /// Last target confirmed written to the entity
+    pub written_target_c: Option<f32>,
+    pub plan: Vec<DhwBlock>,
+}
+
+#[derive(Debug, Default)]
+struct DhwState {
+    plan: Vec<DhwBlock>,
+    /// Target handed to the writer, cleared when the write fails so it is retried
+    requested: Option<f32>,
+    written: Option<f32>,
+}
+
+/// Hot water planner shared by the ECS and the web API
+///
+/// The default instance is disabled.
+#[derive(Resource, Clone, Default)]
+pub struct DhwPlanner {
+    config: Arc<DhwConfig>,
+    state: Arc<RwLock<DhwState>>,
+    sender: Option<mpsc::UnboundedSender<f32>>,
+}
+
+impl std::fmt::Debug for DhwPlanner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DhwPlanner")
+            .field("config", &self.config)
+            .field("writer", &self.sender.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl DhwPlanner {
+    pub fn new(config: DhwConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            ..Self::default()
+        }
+    }
+
+    /// Write targets through `controller` from a supervised background task
+    ///
+    /// Must be called within a tokio runtime.
+    #[must_use]
+    pub fn with_controller(mut self, controller: Arc<dyn DhwController>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+        let state = Arc::clone(&self.state);
+        let entity_id = self.config.entity_id.clone();
+        crate::TaskSupervisor::global().spawn("dhw_writer", move || {
+            run_writer(
+                Arc::clone(&receiver),
+                Arc::clone(&controller),
+                Arc::clone(&state),
+                entity_id.clone(),
+            )
+        });
+        self.sender = Some(sender);
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub fn overview(&self, now: DateTime<Utc>) -> DhwOverview {
+        let state = self.state.read();
+        DhwOverview {
+            enabled: self.config.enabled,
+            entity_id: self.config.entity_id.clone(),
+            current_target_c: current_target(&state.plan, now),
+            written_target_c: state.written,
+            plan: state.plan.clone(),
+        }
+    }
+
+    /// Replan and return the current target when it still has to be written
+    fn update(
+        &self,
+        prices: &[TimeBlockPrice],
+        schedule: Option<&OperationSchedule>,
+        now: DateTime<Utc>,
+    ) -> Option<f32> {
+        let plan = plan_dhw(&self.config, prices, schedule, now);
+        let mut state = self.state.write();
+        let target = current_target(&plan, now);
+        state.plan = plan;
+        let target = target.filter(|t| state.requested != Some(*t))?;
+        state.requested = Some(target);
+        Some(target)
+    }
+}
+
+fn current_target(plan: &[DhwBlock], now: DateTime<Utc>) -> Option<f32> {
+    plan.iter()
+        .find(|b| {
+            b.block_start <= now
+                && now < b.block_start + Duration::minutes(i64::from(b.duration_minutes))
+        })
+        .map(|b| b.target_temp_c)
+}
+
+async fn run_writer(
+    receiver: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<f32>>>,
+    controller: Arc<dyn DhwController>,
+    state: Arc<RwLock<DhwState>>,
+    entity_id: String,
+) {
+    let mut receiver = receiver.lock().await;
+    while let Some(temp_c) = receiver.recv().await {
+        match controller.set_target_temperature(&entity_id, temp_c).await {
+            Ok(()) => {
+                info!("🚿 Hot water target set to {temp_c:.0} °C");
+                state.write().written = Some(temp_c);
+            }
+            Err(e) => {
+                warn!("Failed to set hot water target on {entity_id}: {e:#}");
+                let mut state = state.write();
+                if state.requested == Some(temp_c) {
+                    state.requested = None;
+                }
+            }
+        }
+    }
+}
+
+/// Plan hot water targets and write the current one when it changes
+pub fn dhw_system(
+    planner: Res<DhwPlanner>,
+    debug: Res<DebugModeConfig>,
+    price_data: Query<&SpotPriceData>,
+    schedule_query: Query<&OperationSchedule>,
+    mut last_run: Local<Option<DateTime<Utc>>>,
+) {
+    if !planner.is_enabled() {
+        return;
+    }
+    let now = Utc::now();
+    if last_run.is_some_and(|last| now - last < PLANNING_INTERVAL) {
+        return;
+    }
+    *last_run = Some(now);
+
+    let Some(prices) = price_data.iter().next() else {
+        return;
+    };
+    let Some(target) = planner.update(&prices.time_block_prices, schedule_query.iter().next(), now)
+    else {
+        return;
+    };
+
+    if debug.is_enabled() {
+        info!(
+            "🔧 [DEBUG] Would set hot water target on {} to {target:.0} °C",
+            planner.config.entity_id
+        );
+        planner.state.write().written = Some(target);
+    } else if let Some(sender) = &planner.sender {
+        let _ = sender.send(target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{BlockForecast, InverterOperationMode, ScheduledMode};
+
+    fn prices(start: DateTime<Utc>, values: &[f32]) -> Vec<TimeBlockPrice> {
+        (0_i64..)
+            .zip(values)
+            .map(|(i, &price)| TimeBlockPrice {
+                block_start: start + Duration::minutes(15 * i),
+                duration_minutes: 15,
+                price_czk_per_kwh: price,
+                effective_price_czk_per_kwh: price,
+                spot_sell_price_czk_per_kwh: None,
+            })
+            .collect()
+    }
+
+    fn config() -> DhwConfig {
+        DhwConfig {
+            enabled: true,
+            entity_id: "water_heater.boiler".to_owned(),
+            ..DhwConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_targets_follow_prices_and_solar_surplus() {
+        let now = Utc::now();
+        let start = now - Duration::minutes(5);
+        let prices = prices(start, &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 4.5]);
+        // 1 kWh surplus in a quarter hour is 4 kW, above the 1.5 kW threshold
+        let schedule = OperationSchedule {
+            scheduled_blocks: vec![ScheduledMode {
+                block_start: prices[7].block_start,
+                duration_minutes: 15,
+                target_inverters: None,
+                mode: InverterOperationMode::SelfUse,
+                reason: String::new(),
+                decision_reason: None,
+                decision_uid: None,
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: Some(BlockForecast {
+                    solar_kwh: 1.25,
+                    consumption_kwh: 0.25,
+                }),
+                debug_info: None,
+            }],
+            ..OperationSchedule::default()
+        };
+
+        let plan = plan_dhw(&config(), &prices, Some(&schedule), now);
+        let reasons: Vec<DhwReason> = plan.iter().map(|b| b.reason).collect();
+        assert_eq!(reasons[0], DhwReason::Cheap);
+        assert_eq!(reasons[4], DhwReason::Normal);
+        assert_eq!(reasons[6], DhwReason::Expensive);
+        assert_eq!(reasons[7], DhwReason::Solar);
+        assert!((plan[0].target_temp_c - 60.0).abs() < f32::EPSILON);
+        assert!((plan[6].target_temp_c - 45.0).abs() < f32::EPSILON);
+
+        let flat = plan_dhw(&config(), &self::prices(start, &[3.0; 8]), None, now);
+        assert!(flat.iter().all(|b| b.reason == DhwReason::Normal));
+    }
+
+    #[test]
+    fn test_current_target_is_requested_once() {
+        let now = Utc::now();
+        let planner = DhwPlanner::new(config());
+        let prices = prices(now - Duration::minutes(5), &[1.0, 5.0, 9.0]);
+
+        assert_eq!(planner.update(&prices, None, now), Some(60.0));
+        assert_eq!(planner.update(&prices, None, now), None);
+        assert_eq!(planner.overview(now).current_target_c, Some(60.0));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(config().validate().is_ok());
+        let sensor = DhwConfig {
+            entity_id: "sensor.boiler".to_owned(),
+            ..config()
+        };
+        assert!(sensor.validate().is_err());
+        let inverted = DhwConfig {
+            eco_temp_c: 55.0,
+            ..config()
+        };
+        assert!(inverted.validate().is_err());
+    }
+}
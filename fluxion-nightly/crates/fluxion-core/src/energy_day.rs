@@ -0,0 +1,142 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Energy days: calendar days on the local clock.
+//!
+//! Data is stored in UTC, but users (and the daily sensors in Home Assistant)
+//! count days from local midnight to local midnight. An [`EnergyDay`] is that
+//! span as a UTC range, so it is 23 or 25 hours long on DST transition days.
+//! Daily KPIs, exports and backtests all cut days with [`TimeFormatter::energy_day`].
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::time_format::TimeFormatter;
+
+/// A local calendar day as a half-open UTC range `[start, end)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnergyDay {
+    /// Local calendar date
+    pub date: NaiveDate,
+    /// Local midnight starting the day
+    pub start: DateTime<Utc>,
+    /// Local midnight starting the next day
+    pub end: DateTime<Utc>,
+}
+
+impl EnergyDay {
+    /// Whether `time` falls within this day
+    #[must_use]
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.start <= time && time < self.end
+    }
+
+    /// Length of the day: 24 hours, or 23/25 hours on DST transition days
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.end - self.start
+    }
+
+    /// Number of `block_minutes` blocks in the day, e.g. 92/96/100 for 15 minutes
+    #[must_use]
+    pub fn block_count(&self, block_minutes: u32) -> usize {
+        usize::try_from(self.duration().num_minutes() / i64::from(block_minutes.max(1)))
+            .unwrap_or(0)
+    }
+}
+
+impl TimeFormatter {
+    /// The energy day of the local calendar `date`
+    #[must_use]
+    pub fn energy_day(&self, date: NaiveDate) -> EnergyDay {
+        let next = date.succ_opt().unwrap_or(date);
+        EnergyDay {
+            date,
+            start: self.local_midnight(date),
+            end: self.local_midnight(next),
+        }
+    }
+
+    /// The energy day `time` falls in
+    #[must_use]
+    pub fn energy_day_at(&self, time: DateTime<Utc>) -> EnergyDay {
+        self.energy_day(self.local_date(time))
+    }
+
+    /// Local calendar date of `time`
+    #[must_use]
+    pub fn local_date(&self, time: DateTime<Utc>) -> NaiveDate {
+        self.to_local(time).date_naive()
+    }
+
+    /// First instant of `date` on the local clock
+    ///
+    /// Where midnight falls in a DST gap the day starts when the clock resumes.
+    fn local_midnight(&self, date: NaiveDate) -> DateTime<Utc> {
+        let midnight = date.and_time(NaiveTime::MIN);
+        (0..=8)
+            .find_map(|quarter| self.local_to_utc(midnight + Duration::minutes(quarter * 15)))
+            .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_energy_day_starts_at_local_midnight() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+
+        let day = prague.energy_day_at(utc("2025-01-15T23:30:00Z"));
+
+        assert_eq!(day.date, date(2025, 1, 16));
+        assert_eq!(day.start, utc("2025-01-15T23:00:00Z"));
+        assert_eq!(day.end, utc("2025-01-16T23:00:00Z"));
+        assert!(day.contains(utc("2025-01-15T23:00:00Z")));
+        assert!(!day.contains(utc("2025-01-16T23:00:00Z")));
+        assert_eq!(day.block_count(15), 96);
+    }
+
+    #[test]
+    fn test_energy_day_length_on_dst_days() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+
+        let spring = prague.energy_day(date(2025, 3, 30));
+        assert_eq!(spring.duration(), Duration::hours(23));
+        assert_eq!(spring.block_count(15), 92);
+
+        let autumn = prague.energy_day(date(2025, 10, 26));
+        assert_eq!(autumn.duration(), Duration::hours(25));
+        assert_eq!(autumn.block_count(15), 100);
+        assert_eq!(autumn.end, prague.energy_day(date(2025, 10, 27)).start);
+    }
+
+    #[test]
+    fn test_energy_day_handles_midnight_dst_gap() {
+        // Santiago skipped from 00:00 to 01:00 on 2024-09-08
+        let santiago = TimeFormatter::from_timezone_name(Some("America/Santiago"));
+
+        let day = santiago.energy_day(date(2024, 9, 8));
+
+        assert_eq!(day.start, utc("2024-09-08T04:00:00Z"));
+        assert_eq!(day.duration(), Duration::hours(23));
+    }
+}
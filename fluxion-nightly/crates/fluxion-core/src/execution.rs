@@ -13,9 +13,19 @@
 use crate::components::*;
 use crate::debug::DebugModeConfig;
 use crate::debug_execute;
+use crate::resources::ControlConfig;
+use crate::traits::{
+    GenericInverterState, InverterDataSource, ModeChangeRequest, VendorEntityMapper,
+};
+use anyhow::Result;
+use async_trait::async_trait;
 use bevy_ecs::prelude::*;
 use chrono::{DateTime, Utc};
-use tracing::{error, info};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, error, info};
 
 /// Configuration for schedule execution
 #[derive(Resource, Debug, Clone)]
@@ -162,6 +172,298 @@ pub fn should_execute_for_inverter(scheduled_mode: &ScheduledMode, inverter_id:
     }
 }
 
+// ============= SOC Trajectory Tracking =============
+
+/// How far ahead a planned force charge is pulled forward when SOC lags the plan
+const CATCH_UP_LOOKAHEAD_MINUTES: i64 = 60;
+
+/// Mode to run instead of the planned one to bring SOC back to the trajectory
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SocDriftCorrection {
+    pub mode: InverterOperationMode,
+    /// SOC the trajectory plans for now (%)
+    pub planned_soc: f32,
+}
+
+/// Correct drift from the planned SOC trajectory of `schedule`
+///
+/// Outside `soc_tracking_tolerance_pct` around the plan:
+/// - SOC lags before a force charge planned within the next hour: start charging now;
+/// - SOC lags during a force discharge: stop discharging;
+/// - SOC is past the block target during a force charge: pause charging.
+///
+/// A correction that is already running continues until SOC is back on the plan,
+/// so the mode doesn't flap at the edge of the band. Coordinated multi-inverter
+/// plans are not corrected since their trajectory covers all batteries together.
+pub fn correct_soc_drift(
+    schedule: &OperationSchedule,
+    scheduled: &ScheduledMode,
+    current_mode: InverterOperationMode,
+    soc: f32,
+    now: DateTime<Utc>,
+    config: &ControlConfig,
+) -> Option<SocDriftCorrection> {
+    let tolerance = config.soc_tracking_tolerance_pct;
+    if tolerance <= 0.0 || !schedule.inverter_blocks.is_empty() {
+        return None;
+    }
+    let planned_soc = crate::scheduling::trajectory::planned_soc_at(schedule, now)?;
+    let block_target = scheduled.target_soc?;
+    let fallback = config.default_battery_mode;
+
+    let mode = match scheduled.mode {
+        InverterOperationMode::ForceCharge => {
+            let paused = current_mode != InverterOperationMode::ForceCharge;
+            let limit = if paused {
+                block_target
+            } else {
+                block_target + tolerance
+            };
+            (soc >= limit).then_some(fallback)
+        }
+        InverterOperationMode::ForceDischarge => {
+            let stopped = current_mode != InverterOperationMode::ForceDischarge;
+            let lagging = if stopped {
+                soc < planned_soc
+            } else {
+                soc <= planned_soc - tolerance
+            };
+            lagging.then_some(fallback)
+        }
+        InverterOperationMode::SelfUse
+        | InverterOperationMode::BackUpMode
+        | InverterOperationMode::NoChargeNoDischarge => {
+            let catching_up = current_mode == InverterOperationMode::ForceCharge;
+            let lagging = if catching_up {
+                soc < planned_soc
+            } else {
+                soc <= planned_soc - tolerance
+            };
+            let lookahead_end = now + chrono::Duration::minutes(CATCH_UP_LOOKAHEAD_MINUTES);
+            let charge_ahead = schedule.scheduled_blocks.iter().any(|block| {
+                block.mode == InverterOperationMode::ForceCharge
+                    && block.block_start > now
+                    && block.block_start <= lookahead_end
+            });
+            (lagging && charge_ahead).then_some(InverterOperationMode::ForceCharge)
+        }
+    }?;
+
+    (mode != scheduled.mode).then_some(SocDriftCorrection { mode, planned_soc })
+}
+
+// ============= Mode Planning =============
+
+/// What an inverter needs written to enter a generic operation mode
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModeExecutionPlan {
+    /// Mode-based inverters: select/number/switch entity writes
+    EntityChanges(ModeChangeRequest),
+    /// Setpoint-based inverters (e.g. Victron ESS): a grid power target
+    GridSetpoint(GridSetpointPlan),
+}
+
+/// Grid setpoint control, as used by energy storage systems that regulate
+/// grid power instead of switching battery modes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridSetpointPlan {
+    /// Grid power the system regulates to; positive = import, negative = export
+    pub grid_setpoint_w: i32,
+    /// Battery discharge cap; None = no limit, Some(0) = battery may not discharge
+    pub max_discharge_w: Option<u32>,
+}
+
+/// Translates generic operation modes into what a vendor's inverter understands
+pub trait ModePlanner: Send + Sync {
+    /// Plan the writes that put `inverter_id` into `mode`
+    fn plan_mode(&self, inverter_id: &str, mode: InverterOperationMode) -> ModeExecutionPlan;
+}
+
+impl<M: VendorEntityMapper + ?Sized> ModePlanner for M {
+    fn plan_mode(&self, inverter_id: &str, mode: InverterOperationMode) -> ModeExecutionPlan {
+        ModeExecutionPlan::EntityChanges(self.get_mode_change_request(inverter_id, mode))
+    }
+}
+
+/// Mode planner for ESS-style systems: every mode is a grid setpoint
+///
+/// Force charge imports from the grid, force discharge exports to it, and
+/// holding the battery keeps the idle setpoint with discharge capped at zero
+/// (PV surplus still charges). Backup charges like force charge and then holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridSetpointPlanner {
+    /// Setpoint for self-use; a small import keeps regulation errors from exporting battery energy
+    pub idle_setpoint_w: i32,
+    /// Grid import while force charging
+    pub charge_power_w: u32,
+    /// Grid export while force discharging
+    pub discharge_power_w: u32,
+}
+
+impl GridSetpointPlanner {
+    /// Setpoint plan for `mode`
+    pub fn setpoint(&self, mode: InverterOperationMode) -> GridSetpointPlan {
+        let import = i32::try_from(self.charge_power_w).unwrap_or(i32::MAX);
+        let export = i32::try_from(self.discharge_power_w).unwrap_or(i32::MAX);
+        let (grid_setpoint_w, max_discharge_w) = match mode {
+            InverterOperationMode::SelfUse => (self.idle_setpoint_w, None),
+            InverterOperationMode::BackUpMode | InverterOperationMode::ForceCharge => {
+                (import, Some(0))
+            }
+            InverterOperationMode::ForceDischarge => (-export, None),
+            InverterOperationMode::NoChargeNoDischarge => (self.idle_setpoint_w, Some(0)),
+        };
+        GridSetpointPlan {
+            grid_setpoint_w,
+            max_discharge_w,
+        }
+    }
+
+    /// Generic mode a read-back setpoint corresponds to
+    ///
+    /// Backup is indistinguishable from force charge and reads back as force charge.
+    pub fn mode_of(&self, plan: &GridSetpointPlan) -> InverterOperationMode {
+        let blocked = plan.max_discharge_w == Some(0);
+        if plan.grid_setpoint_w > self.idle_setpoint_w && blocked {
+            InverterOperationMode::ForceCharge
+        } else if plan.grid_setpoint_w < self.idle_setpoint_w.min(0) {
+            InverterOperationMode::ForceDischarge
+        } else if blocked {
+            InverterOperationMode::NoChargeNoDischarge
+        } else {
+            InverterOperationMode::SelfUse
+        }
+    }
+}
+
+impl ModePlanner for GridSetpointPlanner {
+    fn plan_mode(&self, _inverter_id: &str, mode: InverterOperationMode) -> ModeExecutionPlan {
+        ModeExecutionPlan::GridSetpoint(self.setpoint(mode))
+    }
+}
+
+// ============= Execution Backends =============
+
+/// Hardware path a mode change takes, selected per inverter (`execution` in `[[inverters]]`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionBackendKind {
+    /// Entity writes through Home Assistant
+    #[serde(rename = "ha", alias = "home_assistant")]
+    HomeAssistant,
+    /// Direct register writes through the Modbus adapter
+    Modbus,
+    /// Plan and log the mode change without touching the inverter
+    DryRun,
+}
+
+impl ExecutionBackendKind {
+    /// Value used in config files
+    pub fn config_value(self) -> &'static str {
+        match self {
+            Self::HomeAssistant => "ha",
+            Self::Modbus => "modbus",
+            Self::DryRun => "dry_run",
+        }
+    }
+}
+
+/// Puts an inverter into the operation mode the scheduler picked
+#[async_trait]
+pub trait ExecutionBackend: Send + Sync {
+    /// Apply `mode` to `inverter_id`
+    async fn apply_mode(&self, inverter_id: &str, mode: InverterOperationMode) -> Result<()>;
+
+    /// Backend name for logging
+    fn name(&self) -> &'static str;
+}
+
+/// Backend that only records what it would have written
+///
+/// Used for inverters configured with `execution = "dry_run"` and as a mock
+/// backend in tests.
+pub struct DryRunBackend<P: ModePlanner + ?Sized> {
+    planner: Arc<P>,
+    applied: Mutex<Vec<(String, ModeExecutionPlan)>>,
+}
+
+impl<P: ModePlanner + ?Sized> DryRunBackend<P> {
+    /// Create a dry-run backend planning modes with `planner`
+    pub fn new(planner: Arc<P>) -> Self {
+        Self {
+            planner,
+            applied: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Every plan "applied" so far, oldest first
+    pub fn applied(&self) -> Vec<(String, ModeExecutionPlan)> {
+        self.applied.lock().clone()
+    }
+}
+
+#[async_trait]
+impl<P: ModePlanner + ?Sized> ExecutionBackend for DryRunBackend<P> {
+    async fn apply_mode(&self, inverter_id: &str, mode: InverterOperationMode) -> Result<()> {
+        let plan = self.planner.plan_mode(inverter_id, mode);
+        info!("🧪 [DRY RUN] {inverter_id} -> {mode:?}: {plan:?}");
+        self.applied.lock().push((inverter_id.to_owned(), plan));
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "Dry run"
+    }
+}
+
+/// Inverter source that sends mode changes through per-inverter backends
+///
+/// Reads, health checks and every other command go to the wrapped source, as
+/// do mode changes for inverters without a backend of their own.
+pub struct ExecutionRouter {
+    source: Arc<dyn InverterDataSource>,
+    backends: HashMap<String, Arc<dyn ExecutionBackend>>,
+}
+
+impl ExecutionRouter {
+    /// Route mode changes of the inverters in `backends`, everything else to `source`
+    pub fn new(
+        source: Arc<dyn InverterDataSource>,
+        backends: HashMap<String, Arc<dyn ExecutionBackend>>,
+    ) -> Self {
+        Self { source, backends }
+    }
+}
+
+#[async_trait]
+impl InverterDataSource for ExecutionRouter {
+    async fn read_state(&self, inverter_id: &str) -> Result<GenericInverterState> {
+        self.source.read_state(inverter_id).await
+    }
+
+    async fn write_command(&self, inverter_id: &str, command: &InverterCommand) -> Result<()> {
+        match (command, self.backends.get(inverter_id)) {
+            (InverterCommand::SetMode(mode), Some(backend)) => {
+                debug!("Mode change for {inverter_id} via {}", backend.name());
+                backend.apply_mode(inverter_id, *mode).await
+            }
+            _ => self.source.write_command(inverter_id, command).await,
+        }
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        self.source.health_check().await
+    }
+
+    fn name(&self) -> &str {
+        self.source.name()
+    }
+
+    fn supports_charge_power_limit(&self, inverter_id: &str) -> bool {
+        self.source.supports_charge_power_limit(inverter_id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -176,7 +478,11 @@ mod tests {
                     target_inverters: None,
                     mode: InverterOperationMode::ForceCharge,
                     reason: "Test charge".to_string(),
+                    decision_reason: None,
                     decision_uid: None,
+                    charge_power_kw: None,
+                    target_soc: None,
+                    forecast: None,
                     debug_info: None,
                 },
                 ScheduledMode {
@@ -185,12 +491,18 @@ mod tests {
                     target_inverters: None,
                     mode: InverterOperationMode::ForceDischarge,
                     reason: "Test discharge".to_string(),
+                    decision_reason: None,
                     decision_uid: None,
+                    charge_power_kw: None,
+                    target_soc: None,
+                    forecast: None,
                     debug_info: None,
                 },
             ],
             generated_at: now,
             based_on_price_version: now,
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
         }
     }
 
@@ -226,6 +538,23 @@ mod tests {
         assert!(current.is_none());
     }
 
+    #[test]
+    fn test_inverter_block_overrides_shared_plan() {
+        let mut schedule = create_test_schedule();
+        let shared = schedule.scheduled_blocks[0].clone();
+        schedule.inverter_blocks.push(ScheduledMode {
+            target_inverters: Some(vec!["inv2".to_string()]),
+            mode: InverterOperationMode::SelfUse,
+            ..shared
+        });
+        let now = Utc::now();
+
+        let inv1 = schedule.get_current_mode_for_inverter(now, "inv1").unwrap();
+        let inv2 = schedule.get_current_mode_for_inverter(now, "inv2").unwrap();
+        assert_eq!(inv1.mode, InverterOperationMode::ForceCharge);
+        assert_eq!(inv2.mode, InverterOperationMode::SelfUse);
+    }
+
     #[test]
     fn test_should_change_mode_true() {
         let schedule = create_test_schedule();
@@ -327,7 +656,11 @@ mod tests {
             target_inverters: None,
             mode: InverterOperationMode::ForceCharge,
             reason: "Test".to_string(),
+            decision_reason: None,
             decision_uid: None,
+            charge_power_kw: None,
+            target_soc: None,
+            forecast: None,
             debug_info: None,
         };
 
@@ -343,7 +676,11 @@ mod tests {
             target_inverters: Some(vec!["inv1".to_string(), "inv2".to_string()]),
             mode: InverterOperationMode::ForceCharge,
             reason: "Test".to_string(),
+            decision_reason: None,
             decision_uid: None,
+            charge_power_kw: None,
+            target_soc: None,
+            forecast: None,
             debug_info: None,
         };
 
@@ -365,4 +702,234 @@ mod tests {
             _ => panic!("Expected SetMode command"),
         }
     }
+
+    /// 15-minute blocks with planned end-of-block SOCs
+    fn trajectory_schedule(
+        start: DateTime<Utc>,
+        blocks: &[(InverterOperationMode, f32)],
+    ) -> OperationSchedule {
+        OperationSchedule {
+            scheduled_blocks: blocks
+                .iter()
+                .enumerate()
+                .map(|(i, &(mode, target))| ScheduledMode {
+                    block_start: start + chrono::Duration::minutes(15 * i as i64),
+                    duration_minutes: 15,
+                    target_inverters: None,
+                    mode,
+                    reason: "test".to_string(),
+                    decision_reason: None,
+                    decision_uid: None,
+                    charge_power_kw: None,
+                    target_soc: Some(target),
+                    forecast: None,
+                    debug_info: None,
+                })
+                .collect(),
+            ..OperationSchedule::default()
+        }
+    }
+
+    #[test]
+    fn test_correct_soc_drift() {
+        let start = DateTime::parse_from_rfc3339("2025-01-15T02:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let config = ControlConfig::default();
+        let schedule = trajectory_schedule(
+            start,
+            &[
+                (InverterOperationMode::SelfUse, 40.0),
+                (InverterOperationMode::SelfUse, 40.0),
+                (InverterOperationMode::ForceCharge, 60.0),
+                (InverterOperationMode::ForceDischarge, 50.0),
+            ],
+        );
+        let at = |block: usize| start + chrono::Duration::minutes(15 * block as i64 + 14);
+        let correct = |block: usize, current: InverterOperationMode, soc: f32| {
+            let scheduled = &schedule.scheduled_blocks[block];
+            correct_soc_drift(&schedule, scheduled, current, soc, at(block), &config)
+                .map(|c| c.mode)
+        };
+        use InverterOperationMode::{ForceCharge, ForceDischarge, SelfUse};
+
+        // Within the band: follow the plan
+        assert_eq!(correct(1, SelfUse, 37.0), None);
+        // Lagging before a planned charge: start charging early, until back on the plan
+        assert_eq!(correct(1, SelfUse, 34.0), Some(ForceCharge));
+        assert_eq!(correct(1, ForceCharge, 39.0), Some(ForceCharge));
+        assert_eq!(correct(1, ForceCharge, 40.0), None);
+        // Past the block target while charging: pause
+        assert_eq!(correct(2, ForceCharge, 64.0), None);
+        assert_eq!(correct(2, ForceCharge, 65.0), Some(SelfUse));
+        assert_eq!(correct(2, SelfUse, 61.0), Some(SelfUse));
+        // Lagging while discharging: stop
+        assert_eq!(correct(3, ForceDischarge, 48.0), None);
+        assert_eq!(correct(3, ForceDischarge, 45.0), Some(SelfUse));
+
+        // Disabled and coordinated plans are not corrected
+        let off = ControlConfig {
+            soc_tracking_tolerance_pct: 0.0,
+            ..ControlConfig::default()
+        };
+        let scheduled = &schedule.scheduled_blocks[1];
+        assert_eq!(
+            correct_soc_drift(&schedule, scheduled, SelfUse, 20.0, at(1), &off),
+            None
+        );
+        let mut coordinated = schedule.clone();
+        coordinated.inverter_blocks.push(scheduled.clone());
+        assert_eq!(
+            correct_soc_drift(&coordinated, scheduled, SelfUse, 20.0, at(1), &config),
+            None
+        );
+    }
+
+    #[test]
+    fn test_correct_soc_drift_mid_block() {
+        let start = DateTime::parse_from_rfc3339("2025-01-15T10:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let config = ControlConfig::default();
+        use InverterOperationMode::{ForceCharge, SelfUse};
+        // Solar charges the battery in self-use ahead of a planned charge
+        let mut schedule = trajectory_schedule(
+            start,
+            &[(SelfUse, 50.0), (SelfUse, 60.0), (ForceCharge, 80.0)],
+        );
+        schedule.generated_at = start;
+        schedule.initial_soc = Some(40.0);
+        let correct = |block: usize, minutes: i64, soc: f32| {
+            let scheduled = &schedule.scheduled_blocks[block];
+            let now = start + chrono::Duration::minutes(minutes);
+            correct_soc_drift(&schedule, scheduled, SelfUse, soc, now, &config).map(|c| c.mode)
+        };
+
+        // A third into a block SOC is on its way to the block target: no early grid charging
+        assert_eq!(correct(0, 5, 42.0), None);
+        assert_eq!(correct(1, 20, 52.0), None);
+        // Lagging the interpolated plan beyond the tolerance still catches up
+        assert_eq!(correct(1, 20, 47.0), Some(ForceCharge));
+    }
+
+    #[test]
+    fn test_grid_setpoint_planner_round_trips_modes() {
+        let planner = GridSetpointPlanner {
+            idle_setpoint_w: 50,
+            charge_power_w: 4000,
+            discharge_power_w: 3000,
+        };
+
+        assert_eq!(
+            planner.plan_mode("victron", InverterOperationMode::ForceCharge),
+            ModeExecutionPlan::GridSetpoint(GridSetpointPlan {
+                grid_setpoint_w: 4000,
+                max_discharge_w: Some(0),
+            })
+        );
+        assert_eq!(
+            planner
+                .setpoint(InverterOperationMode::ForceDischarge)
+                .grid_setpoint_w,
+            -3000
+        );
+
+        for mode in [
+            InverterOperationMode::SelfUse,
+            InverterOperationMode::ForceCharge,
+            InverterOperationMode::ForceDischarge,
+            InverterOperationMode::NoChargeNoDischarge,
+        ] {
+            assert_eq!(planner.mode_of(&planner.setpoint(mode)), mode);
+        }
+        assert_eq!(
+            planner.mode_of(&planner.setpoint(InverterOperationMode::BackUpMode)),
+            InverterOperationMode::ForceCharge
+        );
+    }
+
+    #[derive(Default)]
+    struct RecordingSource {
+        writes: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl InverterDataSource for RecordingSource {
+        async fn read_state(&self, inverter_id: &str) -> Result<GenericInverterState> {
+            Ok(GenericInverterState {
+                inverter_id: inverter_id.to_owned(),
+                ..GenericInverterState::default()
+            })
+        }
+
+        async fn write_command(&self, inverter_id: &str, command: &InverterCommand) -> Result<()> {
+            self.writes
+                .lock()
+                .push(format!("{inverter_id}: {command:?}"));
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execution_router_sends_modes_to_inverter_backend() {
+        let source = Arc::new(RecordingSource::default());
+        let backend = Arc::new(DryRunBackend::new(Arc::new(GridSetpointPlanner {
+            idle_setpoint_w: 50,
+            charge_power_w: 3000,
+            discharge_power_w: 3000,
+        })));
+        let router = ExecutionRouter::new(
+            source.clone(),
+            HashMap::from([(
+                "victron".to_owned(),
+                backend.clone() as Arc<dyn ExecutionBackend>,
+            )]),
+        );
+
+        router
+            .write_command(
+                "victron",
+                &InverterCommand::SetMode(InverterOperationMode::ForceDischarge),
+            )
+            .await
+            .unwrap();
+        router
+            .write_command("victron", &InverterCommand::SetExportLimit(4000))
+            .await
+            .unwrap();
+        router
+            .write_command(
+                "main",
+                &InverterCommand::SetMode(InverterOperationMode::SelfUse),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            backend.applied(),
+            vec![(
+                "victron".to_owned(),
+                ModeExecutionPlan::GridSetpoint(GridSetpointPlan {
+                    grid_setpoint_w: -3000,
+                    max_discharge_w: None,
+                })
+            )]
+        );
+        assert_eq!(
+            *source.writes.lock(),
+            vec![
+                "victron: SetExportLimit(4000)".to_owned(),
+                "main: SetMode(SelfUse)".to_owned(),
+            ]
+        );
+        assert_eq!(router.name(), "recording");
+    }
 }
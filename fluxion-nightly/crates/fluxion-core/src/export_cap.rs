@@ -0,0 +1,575 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Scheduled grid export caps.
+//!
+//! Czech distributors occasionally announce windows (e.g. grid testing)
+//! during which a PV installation must not export, or only up to a cap. The
+//! user enters them as `control.export_cap_windows`. The scheduler prices
+//! the cap into every overlapping block and never plans force discharge in
+//! them; during a window the executor writes the cap to the inverters'
+//! export limit and restores the configured limit afterwards.
+//!
+//! Grid export measured during each window is kept in
+//! `./data/export_cap.json` so the user can show the distributor that the
+//! cap was respected.
+
+use crate::components::{Inverter, InverterCommand, RawInverterState};
+use crate::debug::DebugModeConfig;
+use crate::resources::{
+    AsyncInverterWriter, ExportCapWindow, InverterTopology, SystemConfig, UserControlResource,
+};
+use anyhow::{Context, Result};
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Default path for the export cap compliance log
+pub const DEFAULT_EXPORT_CAP_PATH: &str = "./data/export_cap.json";
+
+/// Windows kept in the log
+const MAX_WINDOWS: usize = 100;
+
+/// A gap between samples longer than this isn't counted as exporting time
+const MAX_SAMPLE_GAP_SECS: i64 = 60;
+
+/// Export above the cap by less than this is treated as meter noise (W)
+const TOLERANCE_W: f32 = 50.0;
+
+/// Measured export during one window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowCompliance {
+    pub window: ExportCapWindow,
+    pub samples: u32,
+    /// Highest grid export seen (W)
+    pub peak_export_w: f32,
+    /// Energy exported during the window (kWh)
+    pub exported_kwh: f32,
+    /// Time spent above the cap (seconds)
+    pub seconds_over_cap: u32,
+    /// When the export first went above the cap
+    pub first_violation_at: Option<DateTime<Utc>>,
+}
+
+impl WindowCompliance {
+    fn new(window: ExportCapWindow) -> Self {
+        Self {
+            window,
+            samples: 0,
+            peak_export_w: 0.0,
+            exported_kwh: 0.0,
+            seconds_over_cap: 0,
+            first_violation_at: None,
+        }
+    }
+}
+
+/// Compliance verdict of a window
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceStatus {
+    /// The window hasn't started yet
+    Upcoming,
+    /// The window is running and the cap has held so far
+    Active,
+    /// The window ended without export above the cap
+    Compliant,
+    /// Export went above the cap
+    Violated,
+    /// The window passed without any telemetry
+    NotMonitored,
+}
+
+/// Persisted compliance records
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExportCapLog {
+    pub windows: Vec<WindowCompliance>,
+}
+
+/// One window in the report
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowReport {
+    pub status: ComplianceStatus,
+    #[serde(flatten)]
+    pub compliance: WindowCompliance,
+}
+
+/// Snapshot for the web API
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportCapReport {
+    /// Cap in force right now (W)
+    pub active_cap_w: Option<u32>,
+    /// Configured and past windows, newest first
+    pub windows: Vec<WindowReport>,
+}
+
+/// Tracks export against the configured windows
+#[derive(Debug, Default)]
+pub struct ExportCapTracker {
+    log: ExportCapLog,
+    /// Windows from the latest configuration
+    windows: Vec<ExportCapWindow>,
+    last_sample: HashMap<String, DateTime<Utc>>,
+    active: bool,
+}
+
+impl ExportCapTracker {
+    /// Start from a previously saved log
+    pub fn with_log(log: ExportCapLog) -> Self {
+        Self {
+            log,
+            ..Self::default()
+        }
+    }
+
+    pub fn log(&self) -> &ExportCapLog {
+        &self.log
+    }
+
+    /// Add an export sample; returns true when the log should be saved
+    ///
+    /// That is when a window starts or ends, or export first exceeds its cap.
+    pub fn record(
+        &mut self,
+        windows: &[ExportCapWindow],
+        inverter_id: &str,
+        at: DateTime<Utc>,
+        export_w: f32,
+    ) -> bool {
+        if self.windows != windows {
+            self.windows = windows.to_vec();
+        }
+
+        let elapsed_secs = self
+            .last_sample
+            .insert(inverter_id.to_owned(), at)
+            .map(|last| (at - last).num_seconds())
+            .filter(|secs| (0..=MAX_SAMPLE_GAP_SECS).contains(secs))
+            .unwrap_or(0);
+
+        let mut changed = false;
+        let mut active = false;
+        for window in windows.iter().filter(|w| w.contains(at)) {
+            active = true;
+            let index = match self.log.windows.iter().position(|c| c.window == *window) {
+                Some(index) => index,
+                None => {
+                    self.log.windows.push(WindowCompliance::new(window.clone()));
+                    if self.log.windows.len() > MAX_WINDOWS {
+                        self.log.windows.remove(0);
+                    }
+                    changed = true;
+                    self.log.windows.len() - 1
+                }
+            };
+
+            let compliance = &mut self.log.windows[index];
+            compliance.samples += 1;
+            compliance.peak_export_w = compliance.peak_export_w.max(export_w);
+            compliance.exported_kwh += export_w / 1000.0 * elapsed_secs as f32 / 3600.0;
+            if export_w > window.max_export_w as f32 + TOLERANCE_W {
+                compliance.seconds_over_cap += elapsed_secs as u32;
+                if compliance.first_violation_at.is_none() {
+                    compliance.first_violation_at = Some(at);
+                    changed = true;
+                }
+            }
+        }
+
+        changed |= self.active && !active;
+        self.active = active;
+        changed
+    }
+
+    /// Report of all windows at `now`
+    pub fn report(&self, now: DateTime<Utc>) -> ExportCapReport {
+        let status = |c: &WindowCompliance| {
+            if c.seconds_over_cap > 0 || c.first_violation_at.is_some() {
+                ComplianceStatus::Violated
+            } else if now < c.window.start {
+                ComplianceStatus::Upcoming
+            } else if now < c.window.end {
+                ComplianceStatus::Active
+            } else if c.samples == 0 {
+                ComplianceStatus::NotMonitored
+            } else {
+                ComplianceStatus::Compliant
+            }
+        };
+
+        let upcoming = self
+            .windows
+            .iter()
+            .filter(|w| !self.log.windows.iter().any(|c| c.window == **w))
+            .map(|w| WindowCompliance::new(w.clone()));
+        let mut windows: Vec<WindowReport> = self
+            .log
+            .windows
+            .iter()
+            .cloned()
+            .chain(upcoming)
+            .map(|compliance| WindowReport {
+                status: status(&compliance),
+                compliance,
+            })
+            .collect();
+        windows.sort_by_key(|w| std::cmp::Reverse(w.compliance.window.start));
+
+        ExportCapReport {
+            active_cap_w: self
+                .windows
+                .iter()
+                .filter(|w| w.contains(now))
+                .map(|w| w.max_export_w)
+                .min(),
+            windows,
+        }
+    }
+}
+
+/// Shared compliance state: written by the ECS observer, read by the web API
+#[derive(Resource, Clone, Default)]
+pub struct ExportCapMonitor {
+    tracker: Arc<RwLock<ExportCapTracker>>,
+    path: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for ExportCapMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExportCapMonitor")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExportCapMonitor {
+    /// Monitor persisting to `path`, continuing the log saved there
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let log = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable export cap log {}: {e}", path.display());
+                ExportCapLog::default()
+            }),
+            Err(_) => ExportCapLog::default(),
+        };
+        Self {
+            tracker: Arc::new(RwLock::new(ExportCapTracker::with_log(log))),
+            path: Some(path),
+        }
+    }
+
+    /// Add an export sample and persist the log when a window changes
+    pub fn record(
+        &self,
+        windows: &[ExportCapWindow],
+        inverter_id: &str,
+        at: DateTime<Utc>,
+        export_w: f32,
+    ) {
+        let changed = self
+            .tracker
+            .write()
+            .record(windows, inverter_id, at, export_w);
+        if changed && let Err(e) = self.save() {
+            warn!("Failed to save export cap log: {e:#}");
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        // Serialize under the lock, write without it
+        let json = serde_json::to_string_pretty(self.tracker.read().log())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn report(&self) -> ExportCapReport {
+        self.tracker.read().report(Utc::now())
+    }
+}
+
+fn is_slave(system_config: &SystemConfig, inverter_id: &str) -> bool {
+    system_config
+        .inverters
+        .iter()
+        .any(|i| i.id == inverter_id && matches!(i.topology, InverterTopology::Slave { .. }))
+}
+
+/// Feed each fresh telemetry read into the [`ExportCapMonitor`]
+pub fn export_cap_observer_system(
+    monitor: Res<ExportCapMonitor>,
+    system_config: Res<SystemConfig>,
+    states: Query<(&Inverter, &RawInverterState), Changed<RawInverterState>>,
+) {
+    let windows = &system_config.control_config.export_cap_windows;
+    for (inverter, raw) in states.iter() {
+        // Slaves share the master's grid connection
+        if is_slave(&system_config, &inverter.id) {
+            continue;
+        }
+        let export_w = raw
+            .state
+            .grid_export_w
+            .unwrap_or(raw.state.grid_power_w)
+            .max(0.0);
+        monitor.record(windows, &inverter.id, raw.last_updated, export_w);
+    }
+}
+
+/// Write the export limit of the active window to the inverters and restore
+/// the configured limit once it ends
+///
+/// The cap applies at the grid connection, so it is split evenly between the
+/// controllable inverters. Nothing is written while the safe state holds the inverters.
+pub fn export_cap_execution_system(
+    async_writer: Res<AsyncInverterWriter>,
+    debug: Res<DebugModeConfig>,
+    system_config: Res<SystemConfig>,
+    user_control: Option<Res<UserControlResource>>,
+    inverters: Query<&Inverter>,
+    mut applied_limits: Local<HashMap<String, u32>>,
+) {
+    if user_control.is_some_and(|uc| uc.state.safe_state.is_some()) {
+        return;
+    }
+
+    let control = &system_config.control_config;
+    let cap_w = control.export_cap_at(Utc::now());
+    if cap_w.is_none() && applied_limits.is_empty() {
+        return;
+    }
+
+    let controllable: Vec<&Inverter> = inverters
+        .iter()
+        .filter(|inverter| !is_slave(&system_config, &inverter.id))
+        .collect();
+    for inverter in &controllable {
+        let limit_w = match cap_w {
+            Some(cap_w) => cap_w / controllable.len() as u32,
+            None => {
+                if !applied_limits.contains_key(&inverter.id) {
+                    continue;
+                }
+                applied_limits.remove(&inverter.id);
+                if control.maximum_export_power_w == 0 {
+                    warn!(
+                        "Export cap window ended; no maximum_export_power_w configured, leaving {} limited",
+                        inverter.id
+                    );
+                    continue;
+                }
+                control.maximum_export_power_w
+            }
+        };
+        if cap_w.is_some() && applied_limits.get(&inverter.id) == Some(&limit_w) {
+            continue;
+        }
+
+        if debug.enabled {
+            info!(
+                "🔧 [DEBUG] Would set {} export limit to {}W",
+                inverter.id, limit_w
+            );
+        } else {
+            info!("📤 Setting {} export limit to {}W", inverter.id, limit_w);
+            async_writer.write_command_async(
+                inverter.id.clone(),
+                InverterCommand::SetExportLimit(limit_w),
+            );
+        }
+        if cap_w.is_some() {
+            applied_limits.insert(inverter.id.clone(), limit_w);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn window(start: DateTime<Utc>, minutes: i64, max_export_w: u32) -> ExportCapWindow {
+        ExportCapWindow {
+            start,
+            end: start + Duration::minutes(minutes),
+            max_export_w,
+            note: None,
+        }
+    }
+
+    fn feed(
+        tracker: &mut ExportCapTracker,
+        windows: &[ExportCapWindow],
+        start: DateTime<Utc>,
+        minutes: i64,
+        export_w: f32,
+    ) -> (DateTime<Utc>, bool) {
+        let mut at = start;
+        let mut changed = false;
+        for _ in 0..minutes * 6 {
+            changed |= tracker.record(windows, "main", at, export_w);
+            at += Duration::seconds(10);
+        }
+        (at, changed)
+    }
+
+    #[test]
+    fn test_window_without_export_is_compliant() {
+        let start = Utc::now();
+        let windows = [window(start, 30, 0)];
+        let mut tracker = ExportCapTracker::default();
+
+        let (at, changed) = feed(&mut tracker, &windows, start, 30, 20.0);
+        assert!(changed);
+        assert_eq!(
+            tracker.report(at - Duration::minutes(1)).windows[0].status,
+            ComplianceStatus::Active
+        );
+        // First sample after the window closes it
+        assert!(tracker.record(&windows, "main", at, 3000.0));
+
+        let report = tracker.report(at);
+        assert_eq!(report.windows[0].status, ComplianceStatus::Compliant);
+        assert_eq!(report.windows[0].compliance.seconds_over_cap, 0);
+        assert!(report.windows[0].compliance.exported_kwh < 0.02);
+        assert_eq!(report.active_cap_w, None);
+    }
+
+    #[test]
+    fn test_export_above_cap_is_a_violation() {
+        let start = Utc::now();
+        let windows = [window(start, 30, 1000)];
+        let mut tracker = ExportCapTracker::default();
+
+        let (at, _) = feed(&mut tracker, &windows, start, 10, 800.0);
+        let (at, _) = feed(&mut tracker, &windows, at, 5, 2000.0);
+
+        let report = tracker.report(at);
+        let compliance = &report.windows[0].compliance;
+        assert_eq!(report.windows[0].status, ComplianceStatus::Violated);
+        assert_eq!(
+            compliance.first_violation_at,
+            Some(at - Duration::minutes(5))
+        );
+        assert!(compliance.seconds_over_cap >= 4 * 60);
+        assert!((compliance.peak_export_w - 2000.0).abs() < f32::EPSILON);
+        assert_eq!(report.active_cap_w, Some(1000));
+    }
+
+    #[test]
+    fn test_upcoming_windows_are_reported() {
+        let now = Utc::now();
+        let windows = [window(now + Duration::hours(2), 60, 0)];
+        let mut tracker = ExportCapTracker::default();
+
+        assert!(!tracker.record(&windows, "main", now, 500.0));
+
+        let report = tracker.report(now);
+        assert_eq!(report.windows.len(), 1);
+        assert_eq!(report.windows[0].status, ComplianceStatus::Upcoming);
+        assert!(tracker.log().windows.is_empty());
+    }
+
+    #[derive(Default)]
+    struct RecordingSource {
+        writes: parking_lot::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::traits::InverterDataSource for RecordingSource {
+        async fn read_state(&self, inverter_id: &str) -> Result<crate::GenericInverterState> {
+            Ok(crate::GenericInverterState {
+                inverter_id: inverter_id.to_owned(),
+                ..crate::GenericInverterState::default()
+            })
+        }
+
+        async fn write_command(&self, inverter_id: &str, command: &InverterCommand) -> Result<()> {
+            self.writes
+                .lock()
+                .push(format!("{inverter_id}: {command:?}"));
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+    }
+
+    #[test]
+    fn test_safe_state_holds_export_limit() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let _runtime = runtime.enter();
+
+        let mut config: SystemConfig = serde_json::from_value(serde_json::json!({
+            "inverters": [],
+            "pricing": {
+                "spot_price_entity": "sensor.spot_price",
+                "use_spot_prices_to_buy": true,
+                "use_spot_prices_to_sell": true,
+                "fixed_buy_price_czk": 4.0,
+                "fixed_sell_price_czk": 2.0,
+            },
+            "control": crate::ControlConfig::default(),
+            "system": {
+                "update_interval_secs": 60,
+                "debug_mode": false,
+                "display_currency": crate::Currency::CZK,
+            },
+        }))
+        .unwrap();
+        config.control_config.export_cap_windows =
+            vec![window(Utc::now() - Duration::minutes(5), 60, 0)];
+        let source = Arc::new(RecordingSource::default());
+        let mut user_control = crate::UserControlState::default();
+        user_control.safe_state = Some(fluxion_types::user_control::SafeStateActivation::new(
+            "test", None, None,
+        ));
+
+        let mut world = World::new();
+        world.insert_resource(config);
+        world.insert_resource(AsyncInverterWriter::new(source.clone()));
+        world.insert_resource(DebugModeConfig::disabled());
+        world.insert_resource(UserControlResource::new(user_control));
+        world.spawn(Inverter {
+            id: "main".to_owned(),
+            inverter_type: crate::InverterType::Solax,
+        });
+        let mut schedule = Schedule::default();
+        schedule.add_systems(export_cap_execution_system);
+        let mut run = |world: &mut World| {
+            schedule.run(world);
+            runtime.block_on(tokio::time::sleep(std::time::Duration::from_millis(100)));
+            std::mem::take(&mut *source.writes.lock())
+        };
+
+        assert!(run(&mut world).is_empty());
+
+        // The cap is written once the safe state is released
+        world.resource_mut::<UserControlResource>().state.safe_state = None;
+        assert_eq!(run(&mut world), ["main: SetExportLimit(0)"]);
+    }
+}
@@ -0,0 +1,478 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Inverter telemetry with a direct source and a Home Assistant fallback.
+//!
+//! [`FailoverInverterSource`] reads from the primary (direct, low-latency)
+//! source and switches to the fallback when it fails. While on the fallback,
+//! the primary is retried after [`PRIMARY_RETRY_INTERVAL`]. Every
+//! [`RECONCILE_EVERY_READS`] primary reads the fallback is read as well and
+//! discrepancies beyond the tolerances are logged; the primary values win.
+//! The returned state names the live source in
+//! [`GenericInverterState::telemetry_source`].
+//!
+//! [`FallbackPriceSource`] does the same for prices: it reads the primary
+//! (Home Assistant sensor) source and uses the fallback (e.g. OTE) whenever
+//! the primary fails or has no price for the current block.
+
+use crate::components::InverterCommand;
+use crate::traits::{GenericInverterState, InverterDataSource, PriceDataSource};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use fluxion_types::pricing::SpotPriceData;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// How long to stay on the fallback before trying the primary again
+pub const PRIMARY_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Compare both sources every this many primary reads
+pub const RECONCILE_EVERY_READS: u32 = 10;
+
+/// SOC difference between the sources worth reporting (percentage points)
+const SOC_TOLERANCE_PERCENT: f32 = 2.0;
+
+/// Power difference between the sources worth reporting (W)
+const POWER_TOLERANCE_W: f32 = 300.0;
+
+#[derive(Debug, Default)]
+struct FailoverState {
+    on_fallback: bool,
+    last_primary_failure: Option<Instant>,
+    reads_since_reconcile: u32,
+}
+
+/// Inverter source preferring a direct connection over Home Assistant
+pub struct FailoverInverterSource {
+    primary: Arc<dyn InverterDataSource>,
+    fallback: Arc<dyn InverterDataSource>,
+    state: Mutex<FailoverState>,
+}
+
+impl FailoverInverterSource {
+    pub fn new(
+        primary: Arc<dyn InverterDataSource>,
+        fallback: Arc<dyn InverterDataSource>,
+    ) -> Self {
+        Self {
+            primary,
+            fallback,
+            state: Mutex::new(FailoverState::default()),
+        }
+    }
+
+    /// Name of the source currently delivering telemetry
+    pub fn live_source(&self) -> &str {
+        if self.state.lock().on_fallback {
+            self.fallback.name()
+        } else {
+            self.primary.name()
+        }
+    }
+
+    fn should_try_primary(&self) -> bool {
+        let state = self.state.lock();
+        !state.on_fallback
+            || state
+                .last_primary_failure
+                .is_none_or(|at| at.elapsed() >= PRIMARY_RETRY_INTERVAL)
+    }
+
+    fn primary_failed(&self, error: &anyhow::Error) {
+        let mut state = self.state.lock();
+        if !state.on_fallback {
+            warn!(
+                "⚠️ Telemetry source {} failed ({error}), switching to {}",
+                self.primary.name(),
+                self.fallback.name()
+            );
+        }
+        state.on_fallback = true;
+        state.last_primary_failure = Some(Instant::now());
+    }
+
+    /// Record a successful primary read; returns true when it is time to reconcile
+    fn primary_succeeded(&self) -> bool {
+        let mut state = self.state.lock();
+        if state.on_fallback {
+            info!(
+                "✅ Telemetry source {} is back, leaving {}",
+                self.primary.name(),
+                self.fallback.name()
+            );
+            state.on_fallback = false;
+        }
+        state.reads_since_reconcile += 1;
+        if state.reads_since_reconcile >= RECONCILE_EVERY_READS {
+            state.reads_since_reconcile = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    async fn reconcile(&self, inverter_id: &str, primary: &GenericInverterState) {
+        let Ok(fallback) = self.fallback.read_state(inverter_id).await else {
+            return;
+        };
+        let discrepancies = discrepancies(primary, &fallback);
+        if !discrepancies.is_empty() {
+            warn!(
+                "⚠️ {} and {} disagree for {inverter_id}: {}; using {}",
+                self.primary.name(),
+                self.fallback.name(),
+                discrepancies.join(", "),
+                self.primary.name()
+            );
+        }
+    }
+}
+
+/// Differences between two readings of the same inverter beyond the tolerances
+fn discrepancies(primary: &GenericInverterState, fallback: &GenericInverterState) -> Vec<String> {
+    let mut found = Vec::new();
+    if (primary.battery_soc - fallback.battery_soc).abs() > SOC_TOLERANCE_PERCENT {
+        found.push(format!(
+            "SOC {:.1}% vs {:.1}%",
+            primary.battery_soc, fallback.battery_soc
+        ));
+    }
+    for (label, a, b) in [
+        ("grid", primary.grid_power_w, fallback.grid_power_w),
+        ("battery", primary.battery_power_w, fallback.battery_power_w),
+        ("PV", primary.pv_power_w, fallback.pv_power_w),
+    ] {
+        if (a - b).abs() > POWER_TOLERANCE_W {
+            found.push(format!("{label} {a:.0} W vs {b:.0} W"));
+        }
+    }
+    if primary.work_mode != fallback.work_mode {
+        found.push(format!(
+            "mode {:?} vs {:?}",
+            primary.work_mode, fallback.work_mode
+        ));
+    }
+    found
+}
+
+#[async_trait]
+impl InverterDataSource for FailoverInverterSource {
+    async fn read_state(&self, inverter_id: &str) -> Result<GenericInverterState> {
+        if self.should_try_primary() {
+            match self.primary.read_state(inverter_id).await {
+                Ok(mut state) => {
+                    if self.primary_succeeded() {
+                        self.reconcile(inverter_id, &state).await;
+                    }
+                    state.telemetry_source = Some(self.primary.name().to_owned());
+                    return Ok(state);
+                }
+                Err(e) => self.primary_failed(&e),
+            }
+        }
+
+        let mut state = self.fallback.read_state(inverter_id).await?;
+        state.telemetry_source = Some(self.fallback.name().to_owned());
+        Ok(state)
+    }
+
+    async fn write_command(&self, inverter_id: &str, command: &InverterCommand) -> Result<()> {
+        if self.should_try_primary() {
+            match self.primary.write_command(inverter_id, command).await {
+                Ok(()) => return Ok(()),
+                Err(e) => self.primary_failed(&e),
+            }
+        }
+        self.fallback.write_command(inverter_id, command).await
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        if self.primary.health_check().await.unwrap_or(false) {
+            return Ok(true);
+        }
+        self.fallback.health_check().await
+    }
+
+    fn name(&self) -> &str {
+        "failover"
+    }
+
+    /// Either source may end up writing the limit
+    fn supports_charge_power_limit(&self, inverter_id: &str) -> bool {
+        self.primary.supports_charge_power_limit(inverter_id)
+            && self.fallback.supports_charge_power_limit(inverter_id)
+    }
+}
+
+/// Price source falling back to a second source when the first is missing or stale
+pub struct FallbackPriceSource {
+    primary: Arc<dyn PriceDataSource>,
+    fallback: Arc<dyn PriceDataSource>,
+    name: String,
+    on_fallback: Mutex<bool>,
+}
+
+impl FallbackPriceSource {
+    pub fn new(primary: Arc<dyn PriceDataSource>, fallback: Arc<dyn PriceDataSource>) -> Self {
+        let name = format!("{} (fallback: {})", primary.name(), fallback.name());
+        Self {
+            primary,
+            fallback,
+            name,
+            on_fallback: Mutex::new(false),
+        }
+    }
+
+    fn set_on_fallback(&self, on_fallback: bool, why: &str) {
+        let mut state = self.on_fallback.lock();
+        if *state != on_fallback {
+            if on_fallback {
+                warn!(
+                    "⚠️ Price source {} {why}, switching to {}",
+                    self.primary.name(),
+                    self.fallback.name()
+                );
+            } else {
+                info!(
+                    "✅ Price source {} is back, leaving {}",
+                    self.primary.name(),
+                    self.fallback.name()
+                );
+            }
+            *state = on_fallback;
+        }
+    }
+}
+
+/// Whether the prices include the block containing `now`
+fn covers(prices: &SpotPriceData, now: DateTime<Utc>) -> bool {
+    prices.time_block_prices.iter().any(|b| {
+        b.block_start <= now
+            && now < b.block_start + chrono::Duration::minutes(b.duration_minutes.into())
+    })
+}
+
+#[async_trait]
+impl PriceDataSource for FallbackPriceSource {
+    async fn read_prices(&self) -> Result<SpotPriceData> {
+        let primary = self.primary.read_prices().await;
+        let why = match &primary {
+            Ok(prices) if covers(prices, Utc::now()) => {
+                self.set_on_fallback(false, "");
+                return primary;
+            }
+            Ok(_) => "has no price for the current block".to_owned(),
+            Err(e) => format!("failed ({e:#})"),
+        };
+        self.set_on_fallback(true, &why);
+
+        match self.fallback.read_prices().await {
+            Ok(prices) => Ok(prices),
+            Err(e) => {
+                warn!(
+                    "⚠️ Fallback price source {} failed: {e:#}",
+                    self.fallback.name()
+                );
+                // Stale prices are still better than none
+                primary
+            }
+        }
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        if self.primary.health_check().await.unwrap_or(false) {
+            return Ok(true);
+        }
+        self.fallback.health_check().await
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+    struct TestSource {
+        name: &'static str,
+        soc: f32,
+        up: AtomicBool,
+        reads: AtomicU32,
+    }
+
+    impl TestSource {
+        fn new(name: &'static str, soc: f32) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                soc,
+                up: AtomicBool::new(true),
+                reads: AtomicU32::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl InverterDataSource for TestSource {
+        async fn read_state(&self, inverter_id: &str) -> Result<GenericInverterState> {
+            self.reads.fetch_add(1, Ordering::Relaxed);
+            anyhow::ensure!(self.up.load(Ordering::Relaxed), "{} is down", self.name);
+            Ok(GenericInverterState {
+                inverter_id: inverter_id.to_owned(),
+                battery_soc: self.soc,
+                ..GenericInverterState::default()
+            })
+        }
+
+        async fn write_command(&self, _: &str, _: &InverterCommand) -> Result<()> {
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(self.up.load(Ordering::Relaxed))
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prefers_primary_and_falls_back() {
+        let modbus = TestSource::new("modbus", 55.0);
+        let ha = TestSource::new("home_assistant", 54.0);
+        let source = FailoverInverterSource::new(modbus.clone(), ha.clone());
+
+        let state = source.read_state("main").await.unwrap();
+        assert_eq!(state.telemetry_source.as_deref(), Some("modbus"));
+        assert_eq!(ha.reads.load(Ordering::Relaxed), 0);
+
+        modbus.up.store(false, Ordering::Relaxed);
+        let state = source.read_state("main").await.unwrap();
+        assert_eq!(state.telemetry_source.as_deref(), Some("home_assistant"));
+        assert_eq!(source.live_source(), "home_assistant");
+
+        // The primary is not retried before the retry interval
+        modbus.up.store(true, Ordering::Relaxed);
+        let modbus_reads = modbus.reads.load(Ordering::Relaxed);
+        source.read_state("main").await.unwrap();
+        assert_eq!(modbus.reads.load(Ordering::Relaxed), modbus_reads);
+        assert!(source.health_check().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_reconciles_periodically() {
+        let modbus = TestSource::new("modbus", 55.0);
+        let ha = TestSource::new("home_assistant", 40.0);
+        let source = FailoverInverterSource::new(modbus, ha.clone());
+
+        for _ in 0..RECONCILE_EVERY_READS {
+            let state = source.read_state("main").await.unwrap();
+            assert!((state.battery_soc - 55.0).abs() < f32::EPSILON);
+        }
+        assert_eq!(ha.reads.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_discrepancies_respect_tolerances() {
+        let a = GenericInverterState {
+            battery_soc: 50.0,
+            grid_power_w: 1000.0,
+            ..GenericInverterState::default()
+        };
+        let close = GenericInverterState {
+            battery_soc: 51.0,
+            grid_power_w: 1100.0,
+            ..GenericInverterState::default()
+        };
+        let far = GenericInverterState {
+            battery_soc: 60.0,
+            grid_power_w: 2000.0,
+            ..GenericInverterState::default()
+        };
+
+        assert!(discrepancies(&a, &close).is_empty());
+        assert_eq!(discrepancies(&a, &far).len(), 2);
+    }
+
+    struct TestPrices {
+        name: &'static str,
+        /// Start of the first block, `None` when the source is down
+        start: Mutex<Option<DateTime<Utc>>>,
+    }
+
+    #[async_trait]
+    impl PriceDataSource for TestPrices {
+        async fn read_prices(&self) -> Result<SpotPriceData> {
+            let start =
+                (*self.start.lock()).ok_or_else(|| anyhow::anyhow!("{} is down", self.name))?;
+            Ok(SpotPriceData {
+                time_block_prices: (0..4)
+                    .map(|i| fluxion_types::pricing::TimeBlockPrice {
+                        block_start: start + chrono::Duration::minutes(15 * i),
+                        duration_minutes: 15,
+                        price_czk_per_kwh: 2.0,
+                        effective_price_czk_per_kwh: 2.0,
+                        spot_sell_price_czk_per_kwh: None,
+                    })
+                    .collect(),
+                ..SpotPriceData::default()
+            })
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(self.start.lock().is_some())
+        }
+
+        fn name(&self) -> &str {
+            self.name
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prices_fall_back_when_missing_or_stale() {
+        let now = Utc::now();
+        let ha = Arc::new(TestPrices {
+            name: "ha",
+            start: Mutex::new(Some(now - chrono::Duration::minutes(5))),
+        });
+        let ote = Arc::new(TestPrices {
+            name: "ote",
+            start: Mutex::new(Some(now - chrono::Duration::minutes(10))),
+        });
+        let source = FallbackPriceSource::new(ha.clone(), ote.clone());
+        let first_block = |prices: SpotPriceData| prices.time_block_prices[0].block_start;
+
+        // Fresh primary prices cover the current block
+        let expected = now - chrono::Duration::minutes(5);
+        assert_eq!(first_block(source.read_prices().await.unwrap()), expected);
+
+        // Stale primary: yesterday's prices only
+        *ha.start.lock() = Some(now - chrono::Duration::days(1));
+        let expected = now - chrono::Duration::minutes(10);
+        assert_eq!(first_block(source.read_prices().await.unwrap()), expected);
+
+        // Missing primary
+        *ha.start.lock() = None;
+        assert_eq!(first_block(source.read_prices().await.unwrap()), expected);
+        assert!(source.health_check().await.unwrap());
+
+        // Both down
+        *ote.start.lock() = None;
+        assert!(source.read_prices().await.is_err());
+    }
+}
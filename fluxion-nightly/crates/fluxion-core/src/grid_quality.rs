@@ -0,0 +1,653 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Grid voltage and frequency observer.
+//!
+//! With many PV systems on one feeder, the local grid voltage rises whenever
+//! they all export; once it stays above the limit the inverter trips or
+//! derates on its own. The observer averages the voltage reported with each
+//! telemetry read and, while the average stays above the threshold for the
+//! configured time, the scheduler plans with a reduced export limit and
+//! execution keeps surplus PV in the battery. Daily statistics and every
+//! over-voltage or frequency excursion are kept in `./data/grid_quality.json`
+//! so the user can hand them to their distributor.
+
+use crate::components::{Inverter, RawInverterState};
+use crate::resources::{ControlConfig, GridQualityConfigCore, PriceCache, SystemConfig};
+use anyhow::{Context, Result};
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use fluxion_types::inverter::InverterOperationMode;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Default path for the grid quality log
+pub const DEFAULT_GRID_QUALITY_PATH: &str = "./data/grid_quality.json";
+
+/// Days of statistics kept in the log
+const MAX_DAYS: usize = 90;
+
+/// Events kept in the log
+const MAX_EVENTS: usize = 500;
+
+/// A gap between samples longer than this restarts the averaging window
+const MAX_SAMPLE_GAP_SECS: i64 = 60;
+
+/// Kind of grid excursion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GridEventKind {
+    /// Average voltage above the threshold for the sustained period
+    Overvoltage,
+    /// Frequency below the normal band
+    FrequencyLow,
+    /// Frequency above the normal band
+    FrequencyHigh,
+}
+
+/// One grid excursion
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GridEvent {
+    pub kind: GridEventKind,
+    pub inverter_id: String,
+    pub started_at: DateTime<Utc>,
+    /// `None` while the excursion is ongoing
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Highest voltage (V) or furthest frequency (Hz) seen during the event
+    pub peak: f32,
+}
+
+/// Grid statistics for one UTC day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyGridStats {
+    pub date: NaiveDate,
+    pub samples: u32,
+    pub min_voltage_v: f32,
+    pub max_voltage_v: f32,
+    pub avg_voltage_v: f32,
+    /// Time the instantaneous voltage was above the threshold
+    pub seconds_above_threshold: u64,
+    pub min_frequency_hz: Option<f32>,
+    pub max_frequency_hz: Option<f32>,
+    /// Time the frequency was outside the normal band
+    pub seconds_outside_frequency_band: u64,
+}
+
+impl DailyGridStats {
+    fn new(date: NaiveDate) -> Self {
+        Self {
+            date,
+            samples: 0,
+            min_voltage_v: f32::MAX,
+            max_voltage_v: f32::MIN,
+            avg_voltage_v: 0.0,
+            seconds_above_threshold: 0,
+            min_frequency_hz: None,
+            max_frequency_hz: None,
+            seconds_outside_frequency_band: 0,
+        }
+    }
+}
+
+/// Persisted statistics and events
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GridQualityLog {
+    pub days: Vec<DailyGridStats>,
+    pub events: Vec<GridEvent>,
+}
+
+/// Snapshot for the web API
+#[derive(Debug, Clone, Serialize)]
+pub struct GridQualityReport {
+    /// Sustained over-voltage is being compensated for right now
+    pub overvoltage_active: bool,
+    /// Current voltage average per inverter over the sustained period
+    pub average_voltage_v: HashMap<String, f32>,
+    pub days: Vec<DailyGridStats>,
+    pub events: Vec<GridEvent>,
+}
+
+/// Rolling voltage window of one inverter
+#[derive(Debug, Default)]
+struct InverterWindow {
+    samples: VecDeque<(DateTime<Utc>, f32)>,
+    /// The window spans the whole sustained period
+    full: bool,
+    last_sample: Option<DateTime<Utc>>,
+    /// Index into `GridQualityLog::events` of the open over-voltage event
+    overvoltage_event: Option<usize>,
+    /// Index of the open frequency event
+    frequency_event: Option<usize>,
+}
+
+impl InverterWindow {
+    fn average(&self) -> Option<f32> {
+        (!self.samples.is_empty())
+            .then(|| self.samples.iter().map(|(_, v)| v).sum::<f32>() / self.samples.len() as f32)
+    }
+}
+
+/// Tracks grid quality across inverters
+#[derive(Debug, Default)]
+pub struct GridQualityTracker {
+    log: GridQualityLog,
+    windows: HashMap<String, InverterWindow>,
+}
+
+impl GridQualityTracker {
+    /// Start from a previously saved log
+    pub fn with_log(mut log: GridQualityLog) -> Self {
+        // Events still open from before a restart can't be tracked further
+        for event in log.events.iter_mut().filter(|e| e.ended_at.is_none()) {
+            event.ended_at = Some(event.started_at);
+        }
+        Self {
+            log,
+            windows: HashMap::new(),
+        }
+    }
+
+    pub fn log(&self) -> &GridQualityLog {
+        &self.log
+    }
+
+    /// Sustained over-voltage on any inverter
+    pub fn overvoltage_active(&self) -> bool {
+        self.windows.values().any(|w| w.overvoltage_event.is_some())
+    }
+
+    /// Add a telemetry sample; returns true when an event started or ended
+    pub fn record(
+        &mut self,
+        config: &GridQualityConfigCore,
+        inverter_id: &str,
+        at: DateTime<Utc>,
+        voltage_v: Option<f32>,
+        frequency_hz: Option<f32>,
+    ) -> bool {
+        // Adapters report 0 for sensors they couldn't read
+        let voltage_v = voltage_v.filter(|v| *v > 0.0);
+        let frequency_hz = frequency_hz.filter(|f| *f > 0.0);
+        let Some(voltage_v) = voltage_v else {
+            return false;
+        };
+
+        let window = self.windows.entry(inverter_id.to_owned()).or_default();
+        let elapsed = window
+            .last_sample
+            .map(|last| (at - last).num_seconds())
+            .unwrap_or(0);
+        if !(0..=MAX_SAMPLE_GAP_SECS).contains(&elapsed) {
+            window.samples.clear();
+            window.full = false;
+        }
+        window.last_sample = Some(at);
+
+        let day = day_stats(&mut self.log.days, at.date_naive());
+        update_day(
+            day,
+            config,
+            voltage_v,
+            frequency_hz,
+            elapsed.clamp(0, MAX_SAMPLE_GAP_SECS),
+        );
+
+        let mut changed = update_frequency_event(
+            &mut self.log.events,
+            window,
+            config,
+            inverter_id,
+            at,
+            frequency_hz,
+        );
+
+        window.samples.push_back((at, voltage_v));
+        let cutoff = at - Duration::minutes(i64::from(config.sustained_minutes.max(1)));
+        while window.samples.front().is_some_and(|(ts, _)| *ts < cutoff) {
+            window.samples.pop_front();
+            window.full = true;
+        }
+        let average = window.average().unwrap_or(voltage_v);
+
+        match window.overvoltage_event {
+            None if window.full && average > config.overvoltage_threshold_v => {
+                warn!(
+                    "⚡ Sustained grid over-voltage on {}: {:.1} V average over {} min (limit {:.1} V)",
+                    inverter_id, average, config.sustained_minutes, config.overvoltage_threshold_v
+                );
+                window.overvoltage_event = Some(self.log.events.len());
+                self.log.events.push(GridEvent {
+                    kind: GridEventKind::Overvoltage,
+                    inverter_id: inverter_id.to_owned(),
+                    started_at: at,
+                    ended_at: None,
+                    peak: voltage_v,
+                });
+                changed = true;
+            }
+            Some(idx) if average <= config.overvoltage_threshold_v => {
+                if let Some(event) = self.log.events.get_mut(idx) {
+                    event.ended_at = Some(at);
+                    info!(
+                        "⚡ Grid voltage on {} back below {:.1} V after {} min (peak {:.1} V)",
+                        inverter_id,
+                        config.overvoltage_threshold_v,
+                        (at - event.started_at).num_minutes(),
+                        event.peak
+                    );
+                }
+                window.overvoltage_event = None;
+                changed = true;
+            }
+            Some(idx) => {
+                if let Some(event) = self.log.events.get_mut(idx) {
+                    event.peak = event.peak.max(voltage_v);
+                }
+            }
+            None => {}
+        }
+
+        if changed {
+            self.trim_events();
+        }
+        changed
+    }
+
+    /// Current average voltage per inverter
+    pub fn averages(&self) -> HashMap<String, f32> {
+        self.windows
+            .iter()
+            .filter_map(|(id, w)| w.average().map(|avg| (id.clone(), avg)))
+            .collect()
+    }
+
+    /// Drop the oldest closed events beyond [`MAX_EVENTS`], keeping open indices valid
+    fn trim_events(&mut self) {
+        let excess = self.log.events.len().saturating_sub(MAX_EVENTS);
+        if excess == 0 {
+            return;
+        }
+        let removable = self
+            .log
+            .events
+            .iter()
+            .take(excess)
+            .take_while(|e| e.ended_at.is_some())
+            .count();
+        self.log.events.drain(..removable);
+        for window in self.windows.values_mut() {
+            for idx in [&mut window.overvoltage_event, &mut window.frequency_event]
+                .into_iter()
+                .flatten()
+            {
+                *idx -= removable;
+            }
+        }
+    }
+}
+
+fn day_stats(days: &mut Vec<DailyGridStats>, date: NaiveDate) -> &mut DailyGridStats {
+    if days.last().is_none_or(|d| d.date != date) {
+        days.push(DailyGridStats::new(date));
+        if days.len() > MAX_DAYS {
+            days.remove(0);
+        }
+    }
+    let last = days.len() - 1;
+    &mut days[last]
+}
+
+fn update_day(
+    day: &mut DailyGridStats,
+    config: &GridQualityConfigCore,
+    voltage_v: f32,
+    frequency_hz: Option<f32>,
+    elapsed_secs: i64,
+) {
+    let elapsed_secs = elapsed_secs as u64;
+    day.samples += 1;
+    day.min_voltage_v = day.min_voltage_v.min(voltage_v);
+    day.max_voltage_v = day.max_voltage_v.max(voltage_v);
+    day.avg_voltage_v += (voltage_v - day.avg_voltage_v) / day.samples as f32;
+    if voltage_v > config.overvoltage_threshold_v {
+        day.seconds_above_threshold += elapsed_secs;
+    }
+    if let Some(hz) = frequency_hz {
+        day.min_frequency_hz = Some(day.min_frequency_hz.map_or(hz, |m| m.min(hz)));
+        day.max_frequency_hz = Some(day.max_frequency_hz.map_or(hz, |m| m.max(hz)));
+        if !(config.frequency_min_hz..=config.frequency_max_hz).contains(&hz) {
+            day.seconds_outside_frequency_band += elapsed_secs;
+        }
+    }
+}
+
+/// Open, extend or close the frequency excursion of one inverter
+fn update_frequency_event(
+    events: &mut Vec<GridEvent>,
+    window: &mut InverterWindow,
+    config: &GridQualityConfigCore,
+    inverter_id: &str,
+    at: DateTime<Utc>,
+    frequency_hz: Option<f32>,
+) -> bool {
+    let Some(hz) = frequency_hz else {
+        return false;
+    };
+    let kind = if hz < config.frequency_min_hz {
+        Some(GridEventKind::FrequencyLow)
+    } else if hz > config.frequency_max_hz {
+        Some(GridEventKind::FrequencyHigh)
+    } else {
+        None
+    };
+
+    let open = window.frequency_event.and_then(|idx| events.get_mut(idx));
+    match (open, kind) {
+        (Some(event), Some(kind)) if event.kind == kind => {
+            event.peak = if kind == GridEventKind::FrequencyLow {
+                event.peak.min(hz)
+            } else {
+                event.peak.max(hz)
+            };
+            false
+        }
+        (open, kind) => {
+            if let Some(event) = open {
+                event.ended_at = Some(at);
+            }
+            window.frequency_event = kind.map(|kind| {
+                warn!("⚡ Grid frequency on {inverter_id} out of band: {hz:.2} Hz");
+                events.push(GridEvent {
+                    kind,
+                    inverter_id: inverter_id.to_owned(),
+                    started_at: at,
+                    ended_at: None,
+                    peak: hz,
+                });
+                events.len() - 1
+            });
+            true
+        }
+    }
+}
+
+/// Shared grid quality state: written by the ECS observer, read by the
+/// scheduler, execution and the web API
+#[derive(Resource, Clone, Default)]
+pub struct GridQualityMonitor {
+    tracker: Arc<RwLock<GridQualityTracker>>,
+    path: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for GridQualityMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GridQualityMonitor")
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GridQualityMonitor {
+    /// Monitor persisting to `path`, continuing the log saved there
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let log = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!(
+                    "Ignoring unreadable grid quality log {}: {e}",
+                    path.display()
+                );
+                GridQualityLog::default()
+            }),
+            Err(_) => GridQualityLog::default(),
+        };
+        Self {
+            tracker: Arc::new(RwLock::new(GridQualityTracker::with_log(log))),
+            path: Some(path),
+        }
+    }
+
+    /// Add a telemetry sample and persist the log when an event changes
+    pub fn record(
+        &self,
+        config: &GridQualityConfigCore,
+        inverter_id: &str,
+        at: DateTime<Utc>,
+        voltage_v: Option<f32>,
+        frequency_hz: Option<f32>,
+    ) {
+        let changed = self
+            .tracker
+            .write()
+            .record(config, inverter_id, at, voltage_v, frequency_hz);
+        if changed && let Err(e) = self.save() {
+            warn!("Failed to save grid quality log: {e:#}");
+        }
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        // Serialize under the lock, write without it
+        let json = serde_json::to_string_pretty(self.tracker.read().log())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn overvoltage_active(&self) -> bool {
+        self.tracker.read().overvoltage_active()
+    }
+
+    pub fn report(&self) -> GridQualityReport {
+        let tracker = self.tracker.read();
+        GridQualityReport {
+            overvoltage_active: tracker.overvoltage_active(),
+            average_voltage_v: tracker.averages(),
+            days: tracker.log().days.clone(),
+            events: tracker.log().events.clone(),
+        }
+    }
+
+    /// Control config for planning: a reduced export limit while over-voltage persists
+    pub fn planning_control_config(
+        &self,
+        control: &ControlConfig,
+        config: &GridQualityConfigCore,
+    ) -> ControlConfig {
+        let mut control = control.clone();
+        if config.enabled && self.overvoltage_active() {
+            // 0 W means "not configured"; fall back to the inverter rating
+            let base_w = if control.maximum_export_power_w > 0 {
+                control.maximum_export_power_w
+            } else {
+                control.inverter_max_ac_power_w
+            };
+            if base_w > 0 {
+                let share = config.overvoltage_export_percent.clamp(0.0, 100.0) / 100.0;
+                control.maximum_export_power_w = ((base_w as f32 * share) as u32).max(1);
+            }
+        }
+        control
+    }
+
+    /// Mode to run instead of `mode` while over-voltage persists
+    ///
+    /// Force discharge pushes battery energy into the grid and
+    /// no-charge-no-discharge exports all PV surplus; self-use stores the
+    /// surplus instead.
+    pub fn substitute_mode(
+        &self,
+        mode: InverterOperationMode,
+        config: &GridQualityConfigCore,
+    ) -> Option<InverterOperationMode> {
+        let exporting = matches!(
+            mode,
+            InverterOperationMode::ForceDischarge | InverterOperationMode::NoChargeNoDischarge
+        );
+        (config.enabled && exporting && self.overvoltage_active())
+            .then_some(InverterOperationMode::SelfUse)
+    }
+}
+
+/// Control config the scheduler should plan with
+pub fn planning_control_config(
+    monitor: Option<&GridQualityMonitor>,
+    system_config: &SystemConfig,
+) -> ControlConfig {
+    match monitor {
+        Some(monitor) => monitor
+            .planning_control_config(&system_config.control_config, &system_config.grid_quality),
+        None => system_config.control_config.clone(),
+    }
+}
+
+/// Feed each fresh telemetry read into the [`GridQualityMonitor`]
+///
+/// Over-voltage starting or ending changes the planning export limit, so it
+/// requests a replan.
+pub fn grid_quality_observer_system(
+    monitor: Res<GridQualityMonitor>,
+    system_config: Res<SystemConfig>,
+    states: Query<(&Inverter, &RawInverterState), Changed<RawInverterState>>,
+    price_cache: Option<Res<PriceCache>>,
+) {
+    let config = &system_config.grid_quality;
+    if !config.enabled {
+        return;
+    }
+    let overvoltage_before = monitor.overvoltage_active();
+    for (inverter, raw) in states.iter() {
+        monitor.record(
+            config,
+            &inverter.id,
+            raw.last_updated,
+            raw.state.inverter_voltage_v,
+            raw.state.inverter_frequency_hz,
+        );
+    }
+    if monitor.overvoltage_active() != overvoltage_before
+        && let Some(cache) = &price_cache
+    {
+        cache.request_replan();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(
+        tracker: &mut GridQualityTracker,
+        config: &GridQualityConfigCore,
+        start: DateTime<Utc>,
+        minutes: i64,
+        voltage_v: f32,
+    ) -> DateTime<Utc> {
+        let mut at = start;
+        for _ in 0..minutes * 12 {
+            tracker.record(config, "main", at, Some(voltage_v), Some(50.0));
+            at += Duration::seconds(5);
+        }
+        at
+    }
+
+    #[test]
+    fn test_sustained_overvoltage_opens_and_closes_event() {
+        let config = GridQualityConfigCore::default();
+        let mut tracker = GridQualityTracker::default();
+        let start = Utc::now();
+
+        // A short peak doesn't count, the window isn't full yet
+        let at = feed(&mut tracker, &config, start, 5, 256.0);
+        assert!(!tracker.overvoltage_active());
+
+        let at = feed(&mut tracker, &config, at, 6, 256.0);
+        assert!(tracker.overvoltage_active());
+
+        feed(&mut tracker, &config, at, 11, 240.0);
+        assert!(!tracker.overvoltage_active());
+
+        let events = &tracker.log().events;
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, GridEventKind::Overvoltage);
+        assert!(events[0].ended_at.is_some());
+        assert!(tracker.log().days[0].seconds_above_threshold >= 10 * 60);
+    }
+
+    #[test]
+    fn test_frequency_excursion_is_logged() {
+        let config = GridQualityConfigCore::default();
+        let mut tracker = GridQualityTracker::default();
+        let at = Utc::now();
+
+        tracker.record(&config, "main", at, Some(230.0), Some(49.7));
+        tracker.record(
+            &config,
+            "main",
+            at + Duration::seconds(5),
+            Some(230.0),
+            Some(49.6),
+        );
+        tracker.record(
+            &config,
+            "main",
+            at + Duration::seconds(10),
+            Some(230.0),
+            Some(50.0),
+        );
+
+        let event = &tracker.log().events[0];
+        assert_eq!(event.kind, GridEventKind::FrequencyLow);
+        assert!((event.peak - 49.6).abs() < 1e-4);
+        assert_eq!(event.ended_at, Some(at + Duration::seconds(10)));
+    }
+
+    #[test]
+    fn test_overvoltage_reduces_export_and_discharge() {
+        let config = GridQualityConfigCore::default();
+        let monitor = GridQualityMonitor::default();
+        let control = ControlConfig {
+            maximum_export_power_w: 8000,
+            ..ControlConfig::default()
+        };
+        assert_eq!(
+            monitor
+                .planning_control_config(&control, &config)
+                .maximum_export_power_w,
+            8000
+        );
+
+        feed(&mut monitor.tracker.write(), &config, Utc::now(), 11, 258.0);
+
+        assert_eq!(
+            monitor
+                .planning_control_config(&control, &config)
+                .maximum_export_power_w,
+            4000
+        );
+        assert_eq!(
+            monitor.substitute_mode(InverterOperationMode::ForceDischarge, &config),
+            Some(InverterOperationMode::SelfUse)
+        );
+        assert_eq!(
+            monitor.substitute_mode(InverterOperationMode::ForceCharge, &config),
+            None
+        );
+    }
+}
@@ -0,0 +1,402 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Heat pump pre-heating in very cheap winter blocks.
+//!
+//! The thermal mass of the house stores cheap energy as well as the battery
+//! does. In the configured winter months, [`heat_pump_system`] raises the heat
+//! pump's `climate` setpoint to the top of the comfort band in blocks priced
+//! at or below `max_price_czk_per_kwh` (and always when the price is
+//! negative), and keeps it at the bottom of the band otherwise. Pre-heat
+//! blocks are noted in the reasons of the battery schedule.
+
+use crate::components::{OperationSchedule, SpotPriceData};
+use crate::debug::DebugModeConfig;
+use crate::dhw::DhwController;
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Datelike, Duration, Utc};
+use fluxion_types::pricing::TimeBlockPrice;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Minimum time between two plan updates
+const PLANNING_INTERVAL: Duration = Duration::seconds(60);
+
+/// Marks schedule reasons already annotated with the pre-heat
+const REASON_NOTE: &str = "heat pump pre-heat";
+
+/// Heat pump coordination settings (`[heat_pump]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeatPumpConfig {
+    pub enabled: bool,
+    /// `climate.*` entity of the heat pump
+    pub entity_id: String,
+    /// Setpoint outside pre-heat blocks, the bottom of the comfort band (°C)
+    pub comfort_min_temp_c: f32,
+    /// Pre-heat setpoint, the top of the comfort band (°C)
+    pub comfort_max_temp_c: f32,
+    /// Blocks at or below this price pre-heat; negative prices always do
+    pub max_price_czk_per_kwh: f32,
+    /// Months (1-12) pre-heating is allowed in
+    pub winter_months: Vec<u32>,
+}
+
+impl Default for HeatPumpConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            entity_id: String::new(),
+            comfort_min_temp_c: 21.0,
+            comfort_max_temp_c: 23.0,
+            max_price_czk_per_kwh: 1.0,
+            winter_months: vec![1, 2, 3, 10, 11, 12],
+        }
+    }
+}
+
+impl HeatPumpConfig {
+    /// Check the settings
+    ///
+    /// # Errors
+    /// Describes the first invalid setting
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.entity_id.starts_with("climate.") {
+            return Err(format!(
+                "entity_id must be a climate entity, got '{}'",
+                self.entity_id
+            ));
+        }
+        let temps = [self.comfort_min_temp_c, self.comfort_max_temp_c];
+        if temps.iter().any(|t| !(10.0..=30.0).contains(t)) {
+            return Err("comfort temperatures must be between 10 and 30 °C".to_owned());
+        }
+        if self.comfort_min_temp_c >= self.comfort_max_temp_c {
+            return Err("comfort_min_temp_c must be below comfort_max_temp_c".to_owned());
+        }
+        if !self.max_price_czk_per_kwh.is_finite() {
+            return Err("max_price_czk_per_kwh must be a number".to_owned());
+        }
+        if self.winter_months.is_empty() || self.winter_months.iter().any(|m| !(1..=12).contains(m))
+        {
+            return Err("winter_months must list months between 1 and 12".to_owned());
+        }
+        Ok(())
+    }
+}
+
+/// Planned setpoint of one block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeatPumpBlock {
+    pub block_start: DateTime<Utc>,
+    pub duration_minutes: u32,
+    pub target_temp_c: f32,
+    pub preheat: bool,
+    pub price_czk_per_kwh: f32,
+}
+
+/// Plan setpoints for the current and future blocks of `prices`
+#[must_use]
+pub fn plan_heat_pump(
+    config: &HeatPumpConfig,
+    prices: &[TimeBlockPrice],
+    now: DateTime<Utc>,
+) -> Vec<HeatPumpBlock> {
+    prices
+        .iter()
+        .filter(|b| b.block_start + Duration::minutes(i64::from(b.duration_minutes)) > now)
+        .map(|block| {
+            let price = block.effective_price_czk_per_kwh;
+            let preheat = config.winter_months.contains(&block.block_start.month())
+                && (price < 0.0 || price <= config.max_price_czk_per_kwh);
+            HeatPumpBlock {
+                block_start: block.block_start,
+                duration_minutes: block.duration_minutes,
+                target_temp_c: if preheat {
+                    config.comfort_max_temp_c
+                } else {
+                    config.comfort_min_temp_c
+                },
+                preheat,
+                price_czk_per_kwh: price,
+            }
+        })
+        .collect()
+}
+
+/// Note the pre-heat blocks of `plan` in the schedule reasons
+fn annotate_schedule(plan: &[HeatPumpBlock], schedule: &mut OperationSchedule) {
+    for block in &mut schedule.scheduled_blocks {
+        let Some(preheat) = plan
+            .iter()
+            .find(|p| p.preheat && p.block_start == block.block_start)
+        else {
+            continue;
+        };
+        if !block.reason.contains(REASON_NOTE) {
+            block.reason = format!(
+                "{} + {REASON_NOTE} to {:.1} °C ({:.2} CZK/kWh)",
+                block.reason, preheat.target_temp_c, preheat.price_czk_per_kwh
+            );
+        }
+    }
+}
+
+fn needs_annotation(plan: &[HeatPumpBlock], schedule: &OperationSchedule) -> bool {
+    schedule.scheduled_blocks.iter().any(|block| {
+        !block.reason.contains(REASON_NOTE)
+            && plan
+                .iter()
+                .any(|p| p.preheat && p.block_start == block.block_start)
+    })
+}
+
+#[derive(Debug, Default)]
+struct HeatPumpState {
+    plan: Vec<HeatPumpBlock>,
+    /// Setpoint handed to the writer, cleared when the write fails so it is retried
+    requested: Option<f32>,
+    written: Option<f32>,
+}
+
+/// Heat pump coordinator shared by the ECS and the writer task
+///
+/// The default instance is disabled.
+#[derive(Resource, Clone, Default)]
+pub struct HeatPumpCoordinator {
+    config: Arc<HeatPumpConfig>,
+    state: Arc<RwLock<HeatPumpState>>,
+    sender: Option<mpsc::UnboundedSender<f32>>,
+}
+
+impl std::fmt::Debug for HeatPumpCoordinator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HeatPumpCoordinator")
+            .field("config", &self.config)
+            .field("writer", &self.sender.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl HeatPumpCoordinator {
+    pub fn new(config: HeatPumpConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            ..Self::default()
+        }
+    }
+
+    /// Write setpoints through `controller` from a supervised background task
+    ///
+    /// Must be called within a tokio runtime.
+    #[must_use]
+    pub fn with_controller(mut self, controller: Arc<dyn DhwController>) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+        let state = Arc::clone(&self.state);
+        let entity_id = self.config.entity_id.clone();
+        crate::TaskSupervisor::global().spawn("heat_pump_writer", move || {
+            run_writer(
+                Arc::clone(&receiver),
+                Arc::clone(&controller),
+                Arc::clone(&state),
+                entity_id.clone(),
+            )
+        });
+        self.sender = Some(sender);
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Replan and return the current setpoint when it still has to be written
+    fn update(&self, prices: &[TimeBlockPrice], now: DateTime<Utc>) -> Option<f32> {
+        let plan = plan_heat_pump(&self.config, prices, now);
+        let mut state = self.state.write();
+        let target = plan
+            .iter()
+            .find(|b| {
+                b.block_start <= now
+                    && now < b.block_start + Duration::minutes(i64::from(b.duration_minutes))
+            })
+            .map(|b| b.target_temp_c);
+        state.plan = plan;
+        let target = target.filter(|t| state.requested != Some(*t))?;
+        state.requested = Some(target);
+        Some(target)
+    }
+}
+
+async fn run_writer(
+    receiver: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<f32>>>,
+    controller: Arc<dyn DhwController>,
+    state: Arc<RwLock<HeatPumpState>>,
+    entity_id: String,
+) {
+    let mut receiver = receiver.lock().await;
+    while let Some(temp_c) = receiver.recv().await {
+        match controller.set_target_temperature(&entity_id, temp_c).await {
+            Ok(()) => {
+                info!("🌡️ Heat pump setpoint set to {temp_c:.1} °C");
+                state.write().written = Some(temp_c);
+            }
+            Err(e) => {
+                warn!("Failed to set heat pump setpoint on {entity_id}: {e:#}");
+                let mut state = state.write();
+                if state.requested == Some(temp_c) {
+                    state.requested = None;
+                }
+            }
+        }
+    }
+}
+
+/// Plan heat pump setpoints, write the current one when it changes and note
+/// pre-heat blocks in the schedule reasons
+pub fn heat_pump_system(
+    coordinator: Res<HeatPumpCoordinator>,
+    debug: Res<DebugModeConfig>,
+    price_data: Query<&SpotPriceData>,
+    mut schedule_query: Query<&mut OperationSchedule>,
+    mut last_run: Local<Option<DateTime<Utc>>>,
+) {
+    if !coordinator.is_enabled() {
+        return;
+    }
+    let now = Utc::now();
+    if last_run.is_none_or(|last| now - last >= PLANNING_INTERVAL)
+        && let Some(prices) = price_data.iter().next()
+    {
+        *last_run = Some(now);
+        if let Some(target) = coordinator.update(&prices.time_block_prices, now) {
+            if debug.is_enabled() {
+                info!(
+                    "🔧 [DEBUG] Would set heat pump setpoint on {} to {target:.1} °C",
+                    coordinator.config.entity_id
+                );
+                coordinator.state.write().written = Some(target);
+            } else if let Some(sender) = &coordinator.sender {
+                let _ = sender.send(target);
+            }
+        }
+    }
+
+    // New schedules arrive without the notes; only touch the schedule when one is missing
+    let state = coordinator.state.read();
+    for mut schedule in &mut schedule_query {
+        if needs_annotation(&state.plan, &schedule) {
+            annotate_schedule(&state.plan, &mut schedule);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{InverterOperationMode, ScheduledMode};
+    use chrono::TimeZone;
+
+    fn prices(start: DateTime<Utc>, values: &[f32]) -> Vec<TimeBlockPrice> {
+        (0_i64..)
+            .zip(values)
+            .map(|(i, &price)| TimeBlockPrice {
+                block_start: start + Duration::minutes(15 * i),
+                duration_minutes: 15,
+                price_czk_per_kwh: price,
+                effective_price_czk_per_kwh: price,
+                spot_sell_price_czk_per_kwh: None,
+            })
+            .collect()
+    }
+
+    fn config() -> HeatPumpConfig {
+        HeatPumpConfig {
+            enabled: true,
+            entity_id: "climate.heat_pump".to_owned(),
+            ..HeatPumpConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_preheats_cheap_and_negative_winter_blocks_only() {
+        let january = Utc.with_ymd_and_hms(2026, 1, 15, 2, 0, 0).unwrap();
+        let plan = plan_heat_pump(&config(), &prices(january, &[-0.5, 0.8, 1.5, 4.0]), january);
+        let preheat: Vec<bool> = plan.iter().map(|b| b.preheat).collect();
+        assert_eq!(preheat, [true, true, false, false]);
+        assert!((plan[0].target_temp_c - 23.0).abs() < f32::EPSILON);
+        assert!((plan[2].target_temp_c - 21.0).abs() < f32::EPSILON);
+
+        let july = Utc.with_ymd_and_hms(2026, 7, 15, 12, 0, 0).unwrap();
+        let plan = plan_heat_pump(&config(), &prices(july, &[-0.5, 0.8]), july);
+        assert!(plan.iter().all(|b| !b.preheat));
+    }
+
+    #[test]
+    fn test_schedule_reasons_are_annotated_once() {
+        let january = Utc.with_ymd_and_hms(2026, 1, 15, 2, 0, 0).unwrap();
+        let plan = plan_heat_pump(&config(), &prices(january, &[-0.5, 4.0]), january);
+        let mut schedule = OperationSchedule {
+            scheduled_blocks: plan
+                .iter()
+                .map(|b| ScheduledMode {
+                    block_start: b.block_start,
+                    duration_minutes: 15,
+                    target_inverters: None,
+                    mode: InverterOperationMode::ForceCharge,
+                    reason: "Cheap charge".to_owned(),
+                    decision_reason: None,
+                    decision_uid: None,
+                    charge_power_kw: None,
+                    target_soc: None,
+                    forecast: None,
+                    debug_info: None,
+                })
+                .collect(),
+            ..OperationSchedule::default()
+        };
+
+        assert!(needs_annotation(&plan, &schedule));
+        annotate_schedule(&plan, &mut schedule);
+        assert!(!needs_annotation(&plan, &schedule));
+        annotate_schedule(&plan, &mut schedule);
+        assert_eq!(
+            schedule.scheduled_blocks[0].reason,
+            "Cheap charge + heat pump pre-heat to 23.0 °C (-0.50 CZK/kWh)"
+        );
+        assert_eq!(schedule.scheduled_blocks[1].reason, "Cheap charge");
+    }
+
+    #[test]
+    fn test_config_validation() {
+        assert!(config().validate().is_ok());
+        let water_heater = HeatPumpConfig {
+            entity_id: "water_heater.boiler".to_owned(),
+            ..config()
+        };
+        assert!(water_heater.validate().is_err());
+        let inverted = HeatPumpConfig {
+            comfort_min_temp_c: 24.0,
+            ..config()
+        };
+        assert!(inverted.validate().is_err());
+        let bad_month = HeatPumpConfig {
+            winter_months: vec![13],
+            ..config()
+        };
+        assert!(bad_month.validate().is_err());
+    }
+}
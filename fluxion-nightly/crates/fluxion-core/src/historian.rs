@@ -0,0 +1,657 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+//! Built-in historian for plant telemetry.
+//!
+//! The dashboard charts only kept the last 48 hours in memory, and longer
+//! history had to come from Home Assistant, which purges its recorder after a
+//! few days. The historian samples every inverter every few seconds into
+//! `./data/historian.db`. Raw samples are kept for a week; complete 5-minute
+//! buckets are averaged into a second tier that is kept forever. The
+//! `historical_plant_data` view and the `prices` table follow the backtest
+//! database layout, so backtests run straight from the historian.
+
+use crate::components::{
+    BatteryHistory, BatteryHistoryPoint, GridHistory, GridHistoryPoint, Inverter, PvHistory,
+    PvHistoryPoint, RawInverterState, SpotPriceData,
+};
+use crate::submeter::{SubmeterEnergy, SubmeterReading};
+use anyhow::{Context, Result};
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use parking_lot::Mutex;
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Default path for the historian database
+pub const DEFAULT_HISTORIAN_PATH: &str = "./data/historian.db";
+
+/// Length of a downsampled bucket
+pub const DOWNSAMPLE_SECS: i64 = 300;
+
+/// How much history is loaded into the dashboard charts at startup
+const SEED_HOURS: i64 = 48;
+
+/// One telemetry sample of one inverter
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlantSample {
+    pub inverter_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub battery_soc: f32,
+    /// Positive while charging
+    pub battery_power_w: f32,
+    pub pv_power_w: f32,
+    /// Positive while exporting
+    pub grid_power_w: f32,
+    pub house_load_w: f32,
+}
+
+impl PlantSample {
+    /// Sample of the current inverter state
+    pub fn from_state(inverter_id: &str, timestamp: DateTime<Utc>, raw: &RawInverterState) -> Self {
+        let state = &raw.state;
+        Self {
+            inverter_id: inverter_id.to_owned(),
+            timestamp,
+            battery_soc: state.battery_soc,
+            battery_power_w: state.battery_power_w,
+            pv_power_w: state.pv_power_w,
+            grid_power_w: state.grid_power_w,
+            // Energy balance when the inverter doesn't report the load
+            house_load_w: state.house_load_w.unwrap_or_else(|| {
+                (state.pv_power_w - state.battery_power_w - state.grid_power_w).max(0.0)
+            }),
+        }
+    }
+}
+
+/// Resolution of a history query
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryResolution {
+    /// Every sample, available for the raw retention
+    Raw,
+    #[default]
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "15m")]
+    FifteenMinutes,
+    #[serde(rename = "1h")]
+    Hour,
+}
+
+impl HistoryResolution {
+    /// Bucket length, `None` for raw samples
+    pub fn bucket_secs(self) -> Option<i64> {
+        match self {
+            HistoryResolution::Raw => None,
+            HistoryResolution::FiveMinutes => Some(DOWNSAMPLE_SECS),
+            HistoryResolution::FifteenMinutes => Some(15 * 60),
+            HistoryResolution::Hour => Some(3600),
+        }
+    }
+}
+
+/// Whole-plant telemetry at one point in time
+///
+/// Battery SOC is averaged over the inverters, powers are summed.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HistoryPoint {
+    pub timestamp: DateTime<Utc>,
+    pub battery_soc: f32,
+    pub battery_power_w: f32,
+    pub pv_power_w: f32,
+    pub grid_power_w: f32,
+    pub house_load_w: f32,
+}
+
+/// SQLite historian shared by the ECS sampler, the web API and backtests
+#[derive(Resource, Clone)]
+pub struct Historian {
+    conn: Arc<Mutex<Connection>>,
+    path: Option<PathBuf>,
+    raw_retention: Duration,
+    sample_interval: std::time::Duration,
+}
+
+impl std::fmt::Debug for Historian {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Historian")
+            .field("path", &self.path)
+            .field("raw_retention", &self.raw_retention)
+            .field("sample_interval", &self.sample_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+const SAMPLE_COLUMNS: &str =
+    "inverter_id, timestamp, battery_soc, battery_power_w, pv_power_w, grid_power_w, house_load_w";
+
+impl Historian {
+    /// Open (or create) the historian at `path`
+    pub fn open(
+        path: impl AsRef<Path>,
+        raw_retention_days: u32,
+        sample_interval_secs: u64,
+    ) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open historian {}", path.display()))?;
+        let mut historian = Self::init(conn, raw_retention_days, sample_interval_secs)?;
+        historian.path = Some(path.to_path_buf());
+        Ok(historian)
+    }
+
+    /// Open an in-memory historian (nothing survives a restart)
+    pub fn open_in_memory(raw_retention_days: u32, sample_interval_secs: u64) -> Result<Self> {
+        Self::init(
+            Connection::open_in_memory()?,
+            raw_retention_days,
+            sample_interval_secs,
+        )
+    }
+
+    fn init(conn: Connection, raw_retention_days: u32, sample_interval_secs: u64) -> Result<Self> {
+        // Backtests read the file while the sampler writes
+        conn.execute_batch("PRAGMA journal_mode = WAL;")
+            .context("Failed to enable the historian write-ahead log")?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS samples_raw (
+                inverter_id      TEXT NOT NULL,
+                timestamp        INTEGER NOT NULL,
+                battery_soc      REAL NOT NULL,
+                battery_power_w  REAL NOT NULL,
+                pv_power_w       REAL NOT NULL,
+                grid_power_w     REAL NOT NULL,
+                house_load_w     REAL NOT NULL,
+                PRIMARY KEY (inverter_id, timestamp)
+            );
+
+            CREATE TABLE IF NOT EXISTS samples_5min (
+                inverter_id      TEXT NOT NULL,
+                timestamp        INTEGER NOT NULL,
+                battery_soc      REAL NOT NULL,
+                battery_power_w  REAL NOT NULL,
+                pv_power_w       REAL NOT NULL,
+                grid_power_w     REAL NOT NULL,
+                house_load_w     REAL NOT NULL,
+                PRIMARY KEY (inverter_id, timestamp)
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_samples_raw_timestamp ON samples_raw(timestamp);
+            CREATE INDEX IF NOT EXISTS idx_samples_5min_timestamp ON samples_5min(timestamp);
+
+            CREATE TABLE IF NOT EXISTS prices (
+                ts     INTEGER PRIMARY KEY,
+                price  REAL NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS submeter_samples (
+                device      TEXT NOT NULL,
+                timestamp   INTEGER NOT NULL,
+                power_w     REAL NOT NULL,
+                energy_kwh  REAL NOT NULL,
+                PRIMARY KEY (device, timestamp)
+            );
+
+            -- Layout of the backtest database, one row per 5-minute bucket
+            CREATE VIEW IF NOT EXISTS historical_plant_data AS
+                SELECT timestamp,
+                       AVG(battery_soc) AS battery_soc,
+                       SUM(pv_power_w) AS pv_power_w,
+                       SUM(battery_power_w) AS battery_power_w,
+                       SUM(grid_power_w) AS grid_power_w,
+                       SUM(house_load_w) AS house_load_w
+                FROM samples_5min
+                GROUP BY timestamp;",
+        )
+        .context("Failed to create historian tables")?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            path: None,
+            raw_retention: Duration::days(i64::from(raw_retention_days.max(1))),
+            sample_interval: std::time::Duration::from_secs(sample_interval_secs.max(1)),
+        })
+    }
+
+    /// Database file, `None` when in memory
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Time between two samples
+    pub fn sample_interval(&self) -> std::time::Duration {
+        self.sample_interval
+    }
+
+    /// Store samples taken at `now`, downsample complete buckets and prune old raw samples
+    pub fn record(&self, samples: &[PlantSample], now: DateTime<Utc>) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        for sample in samples {
+            tx.execute(
+                &format!(
+                    "INSERT OR REPLACE INTO samples_raw ({SAMPLE_COLUMNS})
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+                ),
+                params![
+                    sample.inverter_id,
+                    sample.timestamp.timestamp(),
+                    sample.battery_soc,
+                    sample.battery_power_w,
+                    sample.pv_power_w,
+                    sample.grid_power_w,
+                    sample.house_load_w,
+                ],
+            )?;
+        }
+
+        // Buckets before the one `now` falls into are complete
+        let current_bucket = now.timestamp().div_euclid(DOWNSAMPLE_SECS) * DOWNSAMPLE_SECS;
+        let next_pending: i64 = tx.query_row(
+            "SELECT COALESCE(MAX(timestamp) + ?1, 0) FROM samples_5min",
+            params![DOWNSAMPLE_SECS],
+            |row| row.get(0),
+        )?;
+        tx.execute(
+            &format!(
+                "INSERT OR REPLACE INTO samples_5min ({SAMPLE_COLUMNS})
+                 SELECT inverter_id, timestamp / ?1 * ?1, AVG(battery_soc), AVG(battery_power_w),
+                        AVG(pv_power_w), AVG(grid_power_w), AVG(house_load_w)
+                 FROM samples_raw
+                 WHERE timestamp >= ?2 AND timestamp < ?3
+                 GROUP BY inverter_id, timestamp / ?1"
+            ),
+            params![DOWNSAMPLE_SECS, next_pending, current_bucket],
+        )?;
+        tx.execute(
+            "DELETE FROM samples_raw WHERE timestamp < ?1",
+            params![(now - self.raw_retention).timestamp()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Store block prices (CZK/kWh), replacing earlier values of the same blocks
+    pub fn record_prices(&self, prices: &[(DateTime<Utc>, f32)]) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        for (start, price) in prices {
+            tx.execute(
+                "INSERT OR REPLACE INTO prices (ts, price) VALUES (?1, ?2)",
+                params![start.timestamp(), price],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Store appliance readings of the sub-metering worker
+    pub fn record_submeter(&self, readings: &[SubmeterReading]) -> Result<()> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        for reading in readings {
+            tx.execute(
+                "INSERT OR REPLACE INTO submeter_samples (device, timestamp, power_w, energy_kwh)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    reading.device,
+                    reading.timestamp.timestamp(),
+                    reading.power_w,
+                    reading.energy_kwh
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Appliance energy in `[from, to)` with the spot price in effect at each reading
+    pub fn query_submeter(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<SubmeterEnergy>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT s.device, s.timestamp, s.energy_kwh,
+                    (SELECT p.price FROM prices p WHERE p.ts <= s.timestamp
+                     ORDER BY p.ts DESC LIMIT 1)
+             FROM submeter_samples s
+             WHERE s.timestamp >= ?1 AND s.timestamp < ?2
+             ORDER BY s.timestamp",
+        )?;
+        #[expect(clippy::cast_possible_truncation)]
+        let rows = stmt.query_map(params![from.timestamp(), to.timestamp()], |row| {
+            Ok(SubmeterEnergy {
+                device: row.get(0)?,
+                timestamp: DateTime::from_timestamp(row.get(1)?, 0).unwrap_or_default(),
+                energy_kwh: row.get::<_, f64>(2)? as f32,
+                price_czk_per_kwh: row.get::<_, Option<f64>>(3)?.map(|p| p as f32),
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Block prices in effect during `[from, to)`, including the block running at `from`
+    pub fn query_prices(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<(DateTime<Utc>, f32)>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT ts, price FROM prices
+             WHERE ts < ?2
+               AND ts >= (SELECT COALESCE(MAX(ts), ?1) FROM prices WHERE ts <= ?1)
+             ORDER BY ts",
+        )?;
+        #[expect(clippy::cast_possible_truncation)]
+        let rows = stmt.query_map(params![from.timestamp(), to.timestamp()], |row| {
+            Ok((
+                DateTime::from_timestamp(row.get(0)?, 0).unwrap_or_default(),
+                row.get::<_, f64>(1)? as f32,
+            ))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+
+    /// Plant history in `[from, to)`, oldest first
+    ///
+    /// Raw samples are only available for the raw retention; coarser
+    /// resolutions come from the 5-minute tier.
+    pub fn query(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        resolution: HistoryResolution,
+    ) -> Result<Vec<HistoryPoint>> {
+        let (table, bucket) = match resolution.bucket_secs() {
+            None => ("samples_raw", 1),
+            Some(bucket) => ("samples_5min", bucket),
+        };
+        // Average each inverter over the bucket, then combine the inverters
+        let sql = format!(
+            "SELECT bucket, AVG(battery_soc), SUM(battery_power_w), SUM(pv_power_w),
+                    SUM(grid_power_w), SUM(house_load_w)
+             FROM (
+                 SELECT inverter_id, timestamp / ?3 * ?3 AS bucket,
+                        AVG(battery_soc) AS battery_soc, AVG(battery_power_w) AS battery_power_w,
+                        AVG(pv_power_w) AS pv_power_w, AVG(grid_power_w) AS grid_power_w,
+                        AVG(house_load_w) AS house_load_w
+                 FROM {table}
+                 WHERE timestamp >= ?1 AND timestamp < ?2
+                 GROUP BY inverter_id, bucket
+             )
+             GROUP BY bucket
+             ORDER BY bucket"
+        );
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(&sql)?;
+        #[expect(clippy::cast_possible_truncation)]
+        let rows = stmt.query_map(params![from.timestamp(), to.timestamp(), bucket], |row| {
+            Ok(HistoryPoint {
+                timestamp: DateTime::from_timestamp(row.get(0)?, 0).unwrap_or_default(),
+                battery_soc: row.get::<_, f64>(1)? as f32,
+                battery_power_w: row.get::<_, f64>(2)? as f32,
+                pv_power_w: row.get::<_, f64>(3)? as f32,
+                grid_power_w: row.get::<_, f64>(4)? as f32,
+                house_load_w: row.get::<_, f64>(5)? as f32,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+/// Sample every inverter at the historian's interval and store new prices
+pub fn historian_system(
+    historian: Res<Historian>,
+    inverters: Query<(&Inverter, &RawInverterState)>,
+    price_data: Query<&SpotPriceData>,
+    mut last_sample: Local<Option<std::time::Instant>>,
+    mut last_prices: Local<Option<DateTime<Utc>>>,
+) {
+    if let Ok(prices) = price_data.single()
+        && *last_prices != Some(prices.fetched_at)
+    {
+        let blocks: Vec<(DateTime<Utc>, f32)> = prices
+            .time_block_prices
+            .iter()
+            .map(|block| (block.block_start, block.price_czk_per_kwh))
+            .collect();
+        if let Err(e) = historian.record_prices(&blocks) {
+            warn!("Failed to store prices in the historian: {e:#}");
+        }
+        *last_prices = Some(prices.fetched_at);
+    }
+
+    let now = std::time::Instant::now();
+    if last_sample.is_some_and(|last| now.duration_since(last) < historian.sample_interval()) {
+        return;
+    }
+    let timestamp = Utc::now();
+    let samples: Vec<PlantSample> = inverters
+        .iter()
+        .map(|(inverter, raw)| PlantSample::from_state(&inverter.id, timestamp, raw))
+        .collect();
+    if samples.is_empty() {
+        return;
+    }
+    if let Err(e) = historian.record(&samples, timestamp) {
+        warn!("Failed to store telemetry in the historian: {e:#}");
+    }
+    *last_sample = Some(now);
+}
+
+/// Load the last two days of chart history from the historian at startup
+///
+/// Keeps the dashboard charts filled across restarts without Home Assistant.
+pub fn seed_chart_history_system(
+    historian: Res<Historian>,
+    mut battery_history: ResMut<BatteryHistory>,
+    mut pv_history: ResMut<PvHistory>,
+    mut grid_history: ResMut<GridHistory>,
+) {
+    let now = Utc::now();
+    // Same 15-minute spacing as the live collection
+    let points = match historian.query(
+        now - Duration::hours(SEED_HOURS),
+        now,
+        HistoryResolution::FifteenMinutes,
+    ) {
+        Ok(points) => points,
+        Err(e) => {
+            warn!("Failed to load chart history from the historian: {e:#}");
+            return;
+        }
+    };
+    for point in &points {
+        battery_history.add_point(BatteryHistoryPoint {
+            timestamp: point.timestamp,
+            soc: point.battery_soc,
+            power_w: point.battery_power_w,
+            voltage_v: None,
+        });
+        pv_history.add_point(PvHistoryPoint {
+            timestamp: point.timestamp,
+            power_w: point.pv_power_w,
+            pv1_power_w: None,
+            pv2_power_w: None,
+        });
+        grid_history.add_point(GridHistoryPoint::from_readings(
+            point.timestamp,
+            point.grid_power_w,
+            None,
+            None,
+        ));
+    }
+    if !points.is_empty() {
+        info!(
+            "📊 Loaded {} chart history points from the historian",
+            points.len()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(inverter_id: &str, timestamp: DateTime<Utc>, soc: f32, pv: f32) -> PlantSample {
+        PlantSample {
+            inverter_id: inverter_id.to_owned(),
+            timestamp,
+            battery_soc: soc,
+            battery_power_w: 0.0,
+            pv_power_w: pv,
+            grid_power_w: 0.0,
+            house_load_w: 500.0,
+        }
+    }
+
+    #[test]
+    fn test_complete_buckets_are_downsampled() {
+        let historian = Historian::open_in_memory(7, 10).unwrap();
+        let start: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+
+        for minute in 0..7 {
+            let at = start + Duration::minutes(minute);
+            historian
+                .record(
+                    &[
+                        sample("main", at, 50.0 + minute as f32, 1000.0),
+                        sample("garage", at, 70.0, 500.0),
+                    ],
+                    at,
+                )
+                .unwrap();
+        }
+
+        // Only the bucket starting at 10:00 is complete
+        let buckets = historian
+            .query(
+                start,
+                start + Duration::hours(1),
+                HistoryResolution::FiveMinutes,
+            )
+            .unwrap();
+        assert_eq!(buckets.len(), 1);
+        assert_eq!(buckets[0].timestamp, start);
+        assert_eq!(buckets[0].battery_soc, (52.0 + 70.0) / 2.0);
+        assert_eq!(buckets[0].pv_power_w, 1500.0);
+
+        let raw = historian
+            .query(start, start + Duration::hours(1), HistoryResolution::Raw)
+            .unwrap();
+        assert_eq!(raw.len(), 7);
+    }
+
+    #[test]
+    fn test_raw_samples_expire_but_buckets_remain() {
+        let historian = Historian::open_in_memory(7, 10).unwrap();
+        let old: DateTime<Utc> = "2025-05-01T10:00:00Z".parse().unwrap();
+        let now: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+
+        historian
+            .record(&[sample("main", old, 40.0, 0.0)], old)
+            .unwrap();
+        historian
+            .record(&[sample("main", now, 60.0, 0.0)], now)
+            .unwrap();
+
+        let day = |at: DateTime<Utc>| (at, at + Duration::days(1));
+        let (from, to) = day(old);
+        assert_eq!(
+            historian
+                .query(from, to, HistoryResolution::Raw)
+                .unwrap()
+                .len(),
+            0
+        );
+        let hourly = historian.query(from, to, HistoryResolution::Hour).unwrap();
+        assert_eq!(hourly.len(), 1);
+        assert_eq!(hourly[0].battery_soc, 40.0);
+    }
+
+    #[test]
+    fn test_query_prices_includes_running_block() {
+        let historian = Historian::open_in_memory(7, 10).unwrap();
+        let start: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+        let block = |n: i64| start + Duration::minutes(15 * n);
+        historian
+            .record_prices(&[(block(0), 1.0), (block(1), 2.0), (block(2), 3.0)])
+            .unwrap();
+
+        let prices = historian
+            .query_prices(block(1) + Duration::minutes(5), block(2))
+            .unwrap();
+        assert_eq!(prices, vec![(block(1), 2.0)]);
+    }
+
+    #[test]
+    fn test_submeter_energy_is_priced_at_reading() {
+        let historian = Historian::open_in_memory(7, 10).unwrap();
+        let start: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+        let reading = |minutes: i64| SubmeterReading {
+            device: "Boiler".to_owned(),
+            timestamp: start + Duration::minutes(minutes),
+            power_w: 2000.0,
+            energy_kwh: 0.5,
+        };
+        historian
+            .record_submeter(&[reading(-5), reading(10), reading(20)])
+            .unwrap();
+        historian
+            .record_prices(&[(start, 2.0), (start + Duration::minutes(15), 4.0)])
+            .unwrap();
+
+        let energy = historian
+            .query_submeter(start - Duration::hours(1), start + Duration::hours(1))
+            .unwrap();
+        let prices: Vec<Option<f32>> = energy.iter().map(|e| e.price_czk_per_kwh).collect();
+        assert_eq!(prices, vec![None, Some(2.0), Some(4.0)]);
+    }
+
+    #[test]
+    fn test_backtest_layout() {
+        let historian = Historian::open_in_memory(7, 10).unwrap();
+        let start: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+        historian
+            .record(&[sample("main", start, 50.0, 800.0)], start)
+            .unwrap();
+        historian
+            .record(
+                &[sample("main", start + Duration::minutes(5), 51.0, 800.0)],
+                start + Duration::minutes(5),
+            )
+            .unwrap();
+        historian.record_prices(&[(start, 2.5)]).unwrap();
+
+        let conn = historian.conn.lock();
+        let (soc, load): (f64, f64) = conn
+            .query_row(
+                "SELECT battery_soc, house_load_w FROM historical_plant_data WHERE timestamp = ?1",
+                params![start.timestamp()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        let price: f64 = conn
+            .query_row("SELECT price FROM prices", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!((soc, load, price), (50.0, 500.0, 2.5));
+    }
+}
@@ -0,0 +1,238 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Developer-mode snapshot of key ECS state.
+//!
+//! With `system.developer_mode` enabled, main.rs inserts an [`EcsInspector`]
+//! and [`ecs_inspector_system`] copies the prices, schedule, control config,
+//! health and user control into it about once a second. The web server serves
+//! the latest snapshot as JSON, so state mismatches between the ECS and the UI
+//! can be diagnosed without attaching a debugger.
+
+use crate::components::{HealthStatus, Inverter, InverterStatus, OperationSchedule, SpotPriceData};
+use crate::debug::DebugModeConfig;
+use crate::resources::{ControlConfig, SystemConfig, UserControlResource};
+use crate::task_supervisor::{TaskStatus, TaskSupervisor};
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use fluxion_types::UserControlState;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Minimum time between two snapshots
+const REFRESH_INTERVAL: Duration = Duration::seconds(1);
+
+/// Overview of the loaded spot prices (the blocks themselves are on the dashboard)
+#[derive(Debug, Clone, Serialize)]
+pub struct PriceSummary {
+    pub blocks: usize,
+    pub block_duration_minutes: u32,
+    pub first_block: Option<DateTime<Utc>>,
+    pub last_block: Option<DateTime<Utc>>,
+    /// Spot price range and mean (CZK/kWh)
+    pub min_czk_per_kwh: Option<f32>,
+    pub max_czk_per_kwh: Option<f32>,
+    pub avg_czk_per_kwh: Option<f32>,
+    pub fetched_at: DateTime<Utc>,
+    pub ha_last_updated: DateTime<Utc>,
+}
+
+impl PriceSummary {
+    #[expect(clippy::cast_precision_loss)]
+    fn new(data: &SpotPriceData) -> Self {
+        let prices = &data.time_block_prices;
+        let spot = || prices.iter().map(|p| p.price_czk_per_kwh);
+        Self {
+            blocks: prices.len(),
+            block_duration_minutes: data.block_duration_minutes,
+            first_block: prices.first().map(|p| p.block_start),
+            last_block: prices.last().map(|p| p.block_start),
+            min_czk_per_kwh: spot().reduce(f32::min),
+            max_czk_per_kwh: spot().reduce(f32::max),
+            avg_czk_per_kwh: (!prices.is_empty())
+                .then(|| spot().sum::<f32>() / prices.len() as f32),
+            fetched_at: data.fetched_at,
+            ha_last_updated: data.ha_last_updated,
+        }
+    }
+}
+
+/// Connection state of one inverter entity
+#[derive(Debug, Clone, Serialize)]
+pub struct InverterHealth {
+    pub id: String,
+    /// None until the first state was read
+    pub connection_healthy: Option<bool>,
+    pub error_code: Option<u16>,
+    pub last_update: Option<DateTime<Utc>>,
+}
+
+/// Health of data sources, inverters and background tasks
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthSnapshot {
+    pub sources: Vec<HealthStatus>,
+    pub inverters: Vec<InverterHealth>,
+    pub tasks: Vec<TaskStatus>,
+}
+
+/// Key ECS resources at one point in time
+#[derive(Debug, Clone, Serialize)]
+pub struct EcsSnapshot {
+    pub captured_at: DateTime<Utc>,
+    pub debug_mode: bool,
+    pub prices: Option<PriceSummary>,
+    pub schedule: Option<OperationSchedule>,
+    pub control_config: ControlConfig,
+    pub health: HealthSnapshot,
+    /// None when user control is not set up
+    pub user_control: Option<UserControlState>,
+}
+
+/// Latest ECS snapshot, written by the ECS and read by the web API
+#[derive(Resource, Clone, Default)]
+pub struct EcsInspector {
+    snapshot: Arc<RwLock<Option<EcsSnapshot>>>,
+}
+
+impl std::fmt::Debug for EcsInspector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EcsInspector").finish_non_exhaustive()
+    }
+}
+
+impl EcsInspector {
+    /// Latest snapshot, None until the ECS has run once
+    pub fn snapshot(&self) -> Option<EcsSnapshot> {
+        self.snapshot.read().clone()
+    }
+
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.snapshot
+            .read()
+            .as_ref()
+            .is_some_and(|s| now - s.captured_at < REFRESH_INTERVAL)
+    }
+
+    fn store(&self, snapshot: EcsSnapshot) {
+        *self.snapshot.write() = Some(snapshot);
+    }
+}
+
+/// ECS system that refreshes the [`EcsInspector`] snapshot
+#[allow(clippy::too_many_arguments)]
+pub fn ecs_inspector_system(
+    inspector: Res<EcsInspector>,
+    debug_config: Res<DebugModeConfig>,
+    system_config: Res<SystemConfig>,
+    user_control: Option<Res<UserControlResource>>,
+    price_data: Query<&SpotPriceData>,
+    schedule: Query<&OperationSchedule>,
+    health: Query<&HealthStatus>,
+    inverters: Query<(&Inverter, Option<&InverterStatus>)>,
+) {
+    let now = Utc::now();
+    if inspector.is_fresh(now) {
+        return;
+    }
+
+    let inverters = inverters
+        .iter()
+        .map(|(inverter, status)| InverterHealth {
+            id: inverter.id.clone(),
+            connection_healthy: status.map(|s| s.connection_healthy),
+            error_code: status.map(|s| s.error_code),
+            last_update: status.and_then(|s| s.last_update),
+        })
+        .collect();
+
+    inspector.store(EcsSnapshot {
+        captured_at: now,
+        debug_mode: debug_config.is_enabled(),
+        prices: price_data.iter().next().map(PriceSummary::new),
+        schedule: schedule.iter().next().cloned(),
+        control_config: system_config.control_config.clone(),
+        health: HealthSnapshot {
+            sources: health.iter().cloned().collect(),
+            inverters,
+            tasks: TaskSupervisor::global().snapshot(),
+        },
+        user_control: user_control.map(|uc| uc.state.clone()),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::TimeBlockPrice;
+
+    fn block(hour: u32, price: f32) -> TimeBlockPrice {
+        let block_start = DateTime::parse_from_rfc3339("2026-01-15T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+            + Duration::hours(i64::from(hour));
+        TimeBlockPrice {
+            block_start,
+            duration_minutes: 15,
+            price_czk_per_kwh: price,
+            effective_price_czk_per_kwh: price,
+            spot_sell_price_czk_per_kwh: None,
+        }
+    }
+
+    #[test]
+    fn price_summary_covers_all_blocks() {
+        let data = SpotPriceData {
+            time_block_prices: vec![block(0, 2.0), block(1, 4.0), block(2, 3.0)],
+            ..Default::default()
+        };
+
+        let summary = PriceSummary::new(&data);
+        assert_eq!(summary.blocks, 3);
+        assert_eq!(
+            summary.first_block,
+            Some(data.time_block_prices[0].block_start)
+        );
+        assert_eq!(
+            summary.last_block,
+            Some(data.time_block_prices[2].block_start)
+        );
+        assert_eq!(summary.min_czk_per_kwh, Some(2.0));
+        assert_eq!(summary.max_czk_per_kwh, Some(4.0));
+        assert_eq!(summary.avg_czk_per_kwh, Some(3.0));
+    }
+
+    #[test]
+    fn snapshot_stays_fresh_for_a_second() {
+        let inspector = EcsInspector::default();
+        let now = Utc::now();
+        assert!(inspector.snapshot().is_none());
+        assert!(!inspector.is_fresh(now));
+
+        inspector.store(EcsSnapshot {
+            captured_at: now,
+            debug_mode: true,
+            prices: None,
+            schedule: None,
+            control_config: ControlConfig::default(),
+            health: HealthSnapshot {
+                sources: Vec::new(),
+                inverters: Vec::new(),
+                tasks: Vec::new(),
+            },
+            user_control: None,
+        });
+        assert!(inspector.is_fresh(now + Duration::milliseconds(500)));
+        assert!(!inspector.is_fresh(now + Duration::seconds(1)));
+        assert!(inspector.snapshot().is_some());
+    }
+}
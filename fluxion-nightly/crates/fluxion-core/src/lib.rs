@@ -10,23 +10,47 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
+pub mod alerts;
 pub mod async_systems;
 pub mod async_tasks;
+pub mod calendar;
 pub mod components;
 pub mod config_events;
 pub mod continuous_systems;
 pub mod day_profiling;
 pub mod debug;
+pub mod decision_log;
+pub mod dhw;
+pub mod energy_day;
 pub mod execution;
+pub mod export_cap;
+pub mod failover_source;
+pub mod grid_quality;
+pub mod heat_pump;
+pub mod historian;
+pub mod inspector;
+pub mod mapping_check;
+pub mod metrics;
+pub mod peak_demand;
 pub mod plugin_adapters;
 pub mod pricing;
 pub mod resources;
+pub mod savings;
 pub mod scheduling;
+pub mod self_test;
+pub mod setup_defaults;
 pub mod strategy;
+pub mod submeter;
+pub mod tariff;
+pub mod task_supervisor;
+pub mod time_format;
 pub mod traits;
 pub mod user_control_persistence;
 pub mod utils;
+pub mod weather;
 pub mod web_bridge;
+pub mod webhooks;
+pub mod what_if;
 
 pub use async_tasks::*;
 use bevy_app::prelude::*;
@@ -42,6 +66,11 @@ use std::sync::Arc;
 /// the web API (for plugin registration) and the scheduling system (for evaluations).
 #[derive(Resource, Clone)]
 pub struct PluginManagerResource(pub Arc<RwLock<PluginManager>>);
+
+/// Persisted external plugin registrations, whose reachability warnings are
+/// shown on the dashboard
+#[derive(Resource, Clone)]
+pub struct PluginRegistryResource(pub Arc<fluxion_plugins::PluginRegistry>);
 pub use components::*;
 pub use config_events::{
     ConfigSection, ConfigUpdateEvent, UserControlChangeType, UserControlUpdateEvent,
@@ -51,21 +80,25 @@ pub use continuous_systems::{
     schedule_execution_system,
 };
 pub use debug::*;
+pub use energy_day::EnergyDay;
 pub use execution::*;
 pub use fluxion_types::inverter::InverterType;
 pub use pricing::ote as ote_market_data;
 pub use resources::TimezoneConfig;
 pub use resources::*;
+pub use task_supervisor::{TaskState, TaskStatus, TaskSupervisor};
+pub use time_format::TimeFormatter;
 pub use traits::{
     EntityChange, GenericInverterState, InverterDataSource, ModeChangeRequest, PriceDataSource,
-    VendorEntityMapper,
+    SolarForecastBlock, SolarForecastDataSource, VendorEntityMapper, WeatherDataSource,
 };
 pub use user_control_persistence::{DEFAULT_USER_CONTROL_PATH, UserControlPersistence};
 pub use utils::*;
 pub use web_bridge::{
-    ConfigUpdateChannel, ConfigUpdateSender, InverterData, PriceBlockData, PriceData,
-    PvGenerationHistoryPoint, ScheduleData, SystemHealthData, UserControlUpdateChannel,
-    UserControlUpdateSender, WebQueryChannel, WebQueryResponse, WebQuerySender, web_query_system,
+    BatteryPowerHistoryPoint, ConfigUpdateChannel, ConfigUpdateSender, GridPowerHistoryPoint,
+    InverterData, PriceBlockData, PriceData, PvGenerationHistoryPoint, ScheduleData,
+    SystemHealthData, UserControlUpdateChannel, UserControlUpdateSender, WebQueryChannel,
+    WebQueryHealth, WebQueryResponse, WebQuerySender, web_query_system,
 };
 
 /// Core plugin that registers fundamental ECS resources and systems
@@ -76,8 +109,49 @@ impl Plugin for FluxionCorePlugin {
         app
             // Initialize debug mode (default: enabled for safety)
             .init_resource::<DebugModeConfig>()
+            .init_resource::<SharedDebugMode>()
+            .add_systems(
+                Update,
+                debug::sync_shared_debug_mode_system
+                    .run_if(resource_exists_and_changed::<DebugModeConfig>),
+            )
+            // Timestamp formatting follows the HA timezone once TimezoneConfig is inserted
+            .init_resource::<TimeFormatter>()
+            .add_systems(
+                Update,
+                time_format::sync_time_formatter_system
+                    .run_if(resource_exists_and_changed::<TimezoneConfig>),
+            )
             // Note: ExecutionConfig is now inserted by main.rs with configured values
             .add_systems(Startup, debug_mode_startup_system)
+            // In-memory until main.rs inserts the persisted monitor
+            .init_resource::<grid_quality::GridQualityMonitor>()
+            .add_systems(Update, grid_quality::grid_quality_observer_system)
+            .init_resource::<export_cap::ExportCapMonitor>()
+            .add_systems(
+                Update,
+                (
+                    export_cap::export_cap_observer_system,
+                    export_cap::export_cap_execution_system,
+                ),
+            )
+            .init_resource::<peak_demand::PeakDemandMonitor>()
+            .add_systems(Update, peak_demand::peak_demand_observer_system)
+            // Filled once setup_async_workers spawns the weather worker
+            .init_resource::<weather::WeatherForecast>()
+            .add_systems(
+                Update,
+                weather::poll_weather_channel.run_if(resource_exists::<weather::WeatherChannel>),
+            )
+            // In-memory until main.rs inserts the persisted rules
+            .init_resource::<alerts::AlertManager>()
+            .add_systems(Update, alerts::alert_rules_system)
+            // Disabled until main.rs inserts the configured planner
+            .init_resource::<dhw::DhwPlanner>()
+            .add_systems(Update, dhw::dhw_system)
+            // Disabled until main.rs inserts the configured coordinator
+            .init_resource::<heat_pump::HeatPumpCoordinator>()
+            .add_systems(Update, heat_pump::heat_pump_system)
             // Add continuous systems plugin
             .add_plugins(ContinuousSystemsPlugin);
     }
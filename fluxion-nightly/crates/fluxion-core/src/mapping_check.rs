@@ -0,0 +1,127 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Results of checking a [`crate::VendorEntityMapper`] against real entities.
+//!
+//! The checks themselves live next to the Home Assistant client in
+//! `fluxion-adapters`; the same checks run against recorded fixtures in tests
+//! and against the live instance through [`MappingValidator`].
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+/// Outcome of a single entity check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityCheckStatus {
+    Ok,
+    /// The entity does not exist
+    Missing,
+    /// The entity exists but reports `unavailable` or `unknown`
+    Unavailable,
+    /// A sensor state that is not a number
+    NotNumeric,
+    /// A value outside its physical range (e.g. SOC above 100 %)
+    OutOfRange,
+    /// The work mode entity reports a mode the mapper cannot translate
+    UnmappedMode,
+    /// A mode change would select an option the entity does not offer
+    InvalidOption,
+}
+
+/// One mapped entity and what was found for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityCheck {
+    /// What the entity is used for (e.g. `battery_soc`, `mode_change:ForceCharge`)
+    pub name: String,
+    pub entity_id: String,
+    /// Required entities make the mapping invalid when they fail
+    pub required: bool,
+    pub status: EntityCheckStatus,
+    /// Raw state or the reason for the failure
+    pub detail: Option<String>,
+}
+
+impl EntityCheck {
+    pub fn is_ok(&self) -> bool {
+        self.status == EntityCheckStatus::Ok
+    }
+}
+
+/// All checks for one inverter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MappingReport {
+    pub inverter_id: String,
+    pub vendor: String,
+    pub checks: Vec<EntityCheck>,
+}
+
+impl MappingReport {
+    /// True when every required check passed
+    pub fn is_valid(&self) -> bool {
+        self.checks.iter().all(|c| c.is_ok() || !c.required)
+    }
+
+    /// Failed required checks
+    pub fn failures(&self) -> impl Iterator<Item = &EntityCheck> {
+        self.checks.iter().filter(|c| c.required && !c.is_ok())
+    }
+
+    /// Failed optional checks
+    pub fn warnings(&self) -> impl Iterator<Item = &EntityCheck> {
+        self.checks.iter().filter(|c| !c.required && !c.is_ok())
+    }
+}
+
+/// Runs the mapping checks against the live entity states
+#[expect(clippy::double_must_use, reason = "generated by async_trait")]
+#[async_trait]
+pub trait MappingValidator: Send + Sync {
+    /// One report per configured inverter
+    async fn validate(&self) -> Result<Vec<MappingReport>>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(required: bool, status: EntityCheckStatus) -> EntityCheck {
+        EntityCheck {
+            name: "battery_soc".to_owned(),
+            entity_id: "sensor.solax_battery_capacity".to_owned(),
+            required,
+            status,
+            detail: None,
+        }
+    }
+
+    #[test]
+    fn test_only_required_failures_invalidate_the_report() {
+        let mut report = MappingReport {
+            inverter_id: "solax".to_owned(),
+            vendor: "Solax".to_owned(),
+            checks: vec![
+                check(true, EntityCheckStatus::Ok),
+                check(false, EntityCheckStatus::Missing),
+            ],
+        };
+        assert!(report.is_valid());
+        assert_eq!(report.warnings().count(), 1);
+
+        report
+            .checks
+            .push(check(true, EntityCheckStatus::NotNumeric));
+        assert!(!report.is_valid());
+        assert_eq!(report.failures().count(), 1);
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Process-wide event counters exported on the `/metrics` endpoint.
+//!
+//! Gauges (SOC, power, price) are read from the dashboard snapshot; only
+//! events that are not kept in the ECS state are counted here.
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Monotonic counters shared by all crates
+#[derive(Debug, Default)]
+pub struct Metrics {
+    mode_changes: AtomicU64,
+    ha_query_errors: AtomicU64,
+    web_query_overflows: AtomicU64,
+    web_query_timeouts: AtomicU64,
+    schedule_generations: AtomicU64,
+    /// Duration of the latest schedule generation in milliseconds
+    last_schedule_generation_ms: AtomicU64,
+}
+
+impl Metrics {
+    /// Process-wide counters
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<Metrics> = OnceLock::new();
+        GLOBAL.get_or_init(Self::default)
+    }
+
+    /// An inverter was switched to a different operation mode
+    pub fn record_mode_change(&self) {
+        self.mode_changes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A Home Assistant API request failed or returned an error status
+    pub fn record_ha_query_error(&self) {
+        self.ha_query_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A web query was refused because too many were waiting for the ECS
+    pub fn record_web_query_overflow(&self) {
+        self.web_query_overflows.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// The ECS did not answer a web query in time
+    pub fn record_web_query_timeout(&self) {
+        self.web_query_timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A schedule was generated in `elapsed`
+    pub fn record_schedule_generation(&self, elapsed: Duration) {
+        let ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+        self.last_schedule_generation_ms
+            .store(ms, Ordering::Relaxed);
+        self.schedule_generations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn mode_changes(&self) -> u64 {
+        self.mode_changes.load(Ordering::Relaxed)
+    }
+
+    pub fn ha_query_errors(&self) -> u64 {
+        self.ha_query_errors.load(Ordering::Relaxed)
+    }
+
+    pub fn web_query_overflows(&self) -> u64 {
+        self.web_query_overflows.load(Ordering::Relaxed)
+    }
+
+    pub fn web_query_timeouts(&self) -> u64 {
+        self.web_query_timeouts.load(Ordering::Relaxed)
+    }
+
+    pub fn schedule_generations(&self) -> u64 {
+        self.schedule_generations.load(Ordering::Relaxed)
+    }
+
+    /// Duration of the latest schedule generation, `None` before the first one
+    pub fn last_schedule_generation(&self) -> Option<Duration> {
+        (self.schedule_generations() > 0).then(|| {
+            Duration::from_millis(self.last_schedule_generation_ms.load(Ordering::Relaxed))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_are_independent() {
+        let metrics = Metrics::default();
+        metrics.record_mode_change();
+        metrics.record_mode_change();
+        metrics.record_ha_query_error();
+        metrics.record_web_query_timeout();
+
+        assert_eq!(metrics.mode_changes(), 2);
+        assert_eq!(metrics.ha_query_errors(), 1);
+        assert_eq!(metrics.web_query_overflows(), 0);
+        assert_eq!(metrics.web_query_timeouts(), 1);
+        assert_eq!(metrics.schedule_generations(), 0);
+    }
+
+    #[test]
+    fn test_last_schedule_generation() {
+        let metrics = Metrics::default();
+        assert_eq!(metrics.last_schedule_generation(), None);
+
+        metrics.record_schedule_generation(Duration::from_millis(1200));
+        metrics.record_schedule_generation(Duration::from_micros(350_900));
+        assert_eq!(metrics.schedule_generations(), 2);
+        assert_eq!(
+            metrics.last_schedule_generation(),
+            Some(Duration::from_millis(350))
+        );
+
+        metrics.record_schedule_generation(Duration::MAX);
+        assert_eq!(
+            metrics.last_schedule_generation(),
+            Some(Duration::from_millis(u64::MAX))
+        );
+    }
+}
@@ -0,0 +1,353 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Quarter-hour peak demand limiting.
+//!
+//! Czech C02d and many commercial tariffs bill the highest 15-minute average
+//! grid import against a contracted maximum. The observer keeps a rolling
+//! 15-minute average of the import reported with each telemetry read. Once it
+//! reaches `trigger_percent` of `max_import_kw`, execution lowers the
+//! force-charge power by the import above the trigger and runs self-use instead
+//! of holding the battery (or of charging, when the house alone is above the
+//! trigger) so the battery covers the load. Shaving stops once the average is
+//! back below the trigger.
+
+use crate::components::{Inverter, RawInverterState};
+use crate::resources::{InverterTopology, PeakDemandConfigCore, SystemConfig};
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, Utc};
+use fluxion_types::inverter::InverterOperationMode;
+use parking_lot::RwLock;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Length of the averaging window, the metering period of the tariffs
+const WINDOW_MINUTES: i64 = 15;
+
+/// A gap between samples longer than this restarts the averaging window
+const MAX_SAMPLE_GAP_SECS: i64 = 60;
+
+/// Shaving stops once the average falls this far below the trigger (percentage points)
+const RELEASE_HYSTERESIS_PERCENT: f32 = 5.0;
+
+/// Rolling import window of one grid connection
+#[derive(Debug, Default)]
+struct ImportWindow {
+    samples: VecDeque<(DateTime<Utc>, f32)>,
+    last_sample: Option<DateTime<Utc>>,
+    /// Latest grid import (W)
+    import_w: f32,
+    /// Latest battery charging power (W, 0 while discharging)
+    charge_w: f32,
+    shaving: bool,
+}
+
+impl ImportWindow {
+    fn average(&self) -> Option<f32> {
+        (!self.samples.is_empty())
+            .then(|| self.samples.iter().map(|(_, w)| w).sum::<f32>() / self.samples.len() as f32)
+    }
+}
+
+/// Tracks grid import against the contracted quarter-hour maximum
+#[derive(Debug, Default)]
+pub struct PeakDemandTracker {
+    windows: HashMap<String, ImportWindow>,
+}
+
+impl PeakDemandTracker {
+    /// Add a telemetry sample; returns true when shaving starts or stops
+    pub fn record(
+        &mut self,
+        config: &PeakDemandConfigCore,
+        inverter_id: &str,
+        at: DateTime<Utc>,
+        import_w: f32,
+        battery_power_w: f32,
+    ) -> bool {
+        let window = self.windows.entry(inverter_id.to_owned()).or_default();
+        let elapsed = window
+            .last_sample
+            .map(|last| (at - last).num_seconds())
+            .unwrap_or(0);
+        if !(0..=MAX_SAMPLE_GAP_SECS).contains(&elapsed) {
+            window.samples.clear();
+        }
+        window.last_sample = Some(at);
+        window.import_w = import_w.max(0.0);
+        window.charge_w = battery_power_w.max(0.0);
+
+        window.samples.push_back((at, window.import_w));
+        let cutoff = at - Duration::minutes(WINDOW_MINUTES);
+        while window.samples.front().is_some_and(|(ts, _)| *ts <= cutoff) {
+            window.samples.pop_front();
+        }
+        let average = window.average().unwrap_or(window.import_w);
+
+        let trigger_w = config.trigger_w();
+        let release_w = trigger_w * (1.0 - RELEASE_HYSTERESIS_PERCENT / 100.0);
+        let shaving = if window.shaving {
+            average >= release_w
+        } else {
+            average >= trigger_w
+        };
+        if shaving == window.shaving {
+            return false;
+        }
+        window.shaving = shaving;
+        if shaving {
+            warn!(
+                "📈 Grid import on {inverter_id} near the contracted maximum: {:.2} kW average over {WINDOW_MINUTES} min (limit {:.2} kW)",
+                average / 1000.0,
+                config.max_import_kw
+            );
+        } else {
+            info!(
+                "📉 Grid import on {inverter_id} back to {:.2} kW average, peak shaving stopped",
+                average / 1000.0
+            );
+        }
+        true
+    }
+
+    /// Rolling 15-minute average import (W)
+    pub fn average_import_w(&self, inverter_id: &str) -> Option<f32> {
+        self.windows
+            .get(inverter_id)
+            .and_then(ImportWindow::average)
+    }
+
+    fn shaving_window(
+        &self,
+        inverter_id: &str,
+        config: &PeakDemandConfigCore,
+    ) -> Option<&ImportWindow> {
+        self.windows
+            .get(inverter_id)
+            .filter(|window| config.enabled && window.shaving)
+    }
+
+    /// Mode to run instead of `mode` while shaving
+    ///
+    /// Holding the battery leaves the whole load on the grid, and force
+    /// charging can't be reduced below the trigger when the house alone
+    /// imports more; self-use covers the load from the battery instead.
+    pub fn substitute_mode(
+        &self,
+        inverter_id: &str,
+        mode: InverterOperationMode,
+        config: &PeakDemandConfigCore,
+    ) -> Option<InverterOperationMode> {
+        let window = self.shaving_window(inverter_id, config)?;
+        let substitute = match mode {
+            InverterOperationMode::BackUpMode | InverterOperationMode::NoChargeNoDischarge => true,
+            InverterOperationMode::ForceCharge => {
+                window.import_w - window.charge_w >= config.trigger_w()
+            }
+            InverterOperationMode::SelfUse | InverterOperationMode::ForceDischarge => false,
+        };
+        substitute.then_some(InverterOperationMode::SelfUse)
+    }
+
+    /// Charge power limit while force charging and shaving (W)
+    ///
+    /// The current charging power less the import above the trigger, so the
+    /// limit settles where the import sits at the trigger.
+    pub fn charge_limit_w(
+        &self,
+        inverter_id: &str,
+        mode: InverterOperationMode,
+        config: &PeakDemandConfigCore,
+    ) -> Option<u32> {
+        let window = self
+            .shaving_window(inverter_id, config)
+            .filter(|_| mode == InverterOperationMode::ForceCharge)?;
+        let excess_w = window.import_w - config.trigger_w();
+        // Whole 100 W steps, so telemetry noise doesn't rewrite the limit every read
+        let limit_w = ((window.charge_w - excess_w).max(0.0) / 100.0).floor() * 100.0;
+        Some(limit_w as u32)
+    }
+}
+
+/// Shared peak demand state: written by the ECS observer, read by execution
+#[derive(Resource, Debug, Clone, Default)]
+pub struct PeakDemandMonitor {
+    tracker: Arc<RwLock<PeakDemandTracker>>,
+}
+
+impl PeakDemandMonitor {
+    /// Add a telemetry sample
+    pub fn record(
+        &self,
+        config: &PeakDemandConfigCore,
+        inverter_id: &str,
+        at: DateTime<Utc>,
+        import_w: f32,
+        battery_power_w: f32,
+    ) {
+        self.tracker
+            .write()
+            .record(config, inverter_id, at, import_w, battery_power_w);
+    }
+
+    pub fn average_import_w(&self, inverter_id: &str) -> Option<f32> {
+        self.tracker.read().average_import_w(inverter_id)
+    }
+
+    pub fn substitute_mode(
+        &self,
+        inverter_id: &str,
+        mode: InverterOperationMode,
+        config: &PeakDemandConfigCore,
+    ) -> Option<InverterOperationMode> {
+        self.tracker
+            .read()
+            .substitute_mode(inverter_id, mode, config)
+    }
+
+    pub fn charge_limit_w(
+        &self,
+        inverter_id: &str,
+        mode: InverterOperationMode,
+        config: &PeakDemandConfigCore,
+    ) -> Option<u32> {
+        self.tracker
+            .read()
+            .charge_limit_w(inverter_id, mode, config)
+    }
+}
+
+/// Feed each fresh telemetry read into the [`PeakDemandMonitor`]
+pub fn peak_demand_observer_system(
+    monitor: Res<PeakDemandMonitor>,
+    system_config: Res<SystemConfig>,
+    states: Query<(&Inverter, &RawInverterState), Changed<RawInverterState>>,
+) {
+    let config = &system_config.peak_demand;
+    if !config.enabled {
+        return;
+    }
+    for (inverter, raw) in states.iter() {
+        // Slaves share the master's grid connection
+        let slave = system_config
+            .inverters
+            .iter()
+            .any(|i| i.id == inverter.id && matches!(i.topology, InverterTopology::Slave { .. }));
+        if slave {
+            continue;
+        }
+        let import_w = raw.state.grid_import_w.unwrap_or(-raw.state.grid_power_w);
+        monitor.record(
+            config,
+            &inverter.id,
+            raw.last_updated,
+            import_w,
+            raw.state.battery_power_w,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> PeakDemandConfigCore {
+        PeakDemandConfigCore {
+            enabled: true,
+            max_import_kw: 10.0,
+            trigger_percent: 90.0,
+        }
+    }
+
+    fn feed(
+        tracker: &mut PeakDemandTracker,
+        start: DateTime<Utc>,
+        minutes: i64,
+        import_w: f32,
+        battery_power_w: f32,
+    ) -> DateTime<Utc> {
+        let mut at = start;
+        for _ in 0..minutes * 6 {
+            tracker.record(&config(), "main", at, import_w, battery_power_w);
+            at += Duration::seconds(10);
+        }
+        at
+    }
+
+    #[test]
+    fn test_short_spike_does_not_trigger_shaving() {
+        let mut tracker = PeakDemandTracker::default();
+        let at = feed(&mut tracker, Utc::now(), 14, 4000.0, 0.0);
+        feed(&mut tracker, at, 1, 12_000.0, 0.0);
+
+        assert!(tracker.average_import_w("main").unwrap() < config().trigger_w());
+        assert_eq!(
+            tracker.substitute_mode(
+                "main",
+                InverterOperationMode::NoChargeNoDischarge,
+                &config()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_force_charge_is_throttled_near_the_limit() {
+        let mut tracker = PeakDemandTracker::default();
+        // 3 kW house load plus 7 kW charging
+        feed(&mut tracker, Utc::now(), 15, 10_000.0, 7000.0);
+
+        // 1 kW above the 9 kW trigger comes off the charging power
+        assert_eq!(
+            tracker.charge_limit_w("main", InverterOperationMode::ForceCharge, &config()),
+            Some(6000)
+        );
+        assert_eq!(
+            tracker.substitute_mode("main", InverterOperationMode::ForceCharge, &config()),
+            None
+        );
+        assert_eq!(
+            tracker.substitute_mode("main", InverterOperationMode::BackUpMode, &config()),
+            Some(InverterOperationMode::SelfUse)
+        );
+        assert_eq!(
+            tracker.charge_limit_w("main", InverterOperationMode::SelfUse, &config()),
+            None
+        );
+
+        let disabled = PeakDemandConfigCore {
+            enabled: false,
+            ..config()
+        };
+        assert_eq!(
+            tracker.charge_limit_w("main", InverterOperationMode::ForceCharge, &disabled),
+            None
+        );
+    }
+
+    #[test]
+    fn test_house_load_above_trigger_discharges_and_releases_with_hysteresis() {
+        let mut tracker = PeakDemandTracker::default();
+        let at = feed(&mut tracker, Utc::now(), 15, 9500.0, 0.0);
+        assert_eq!(
+            tracker.substitute_mode("main", InverterOperationMode::ForceCharge, &config()),
+            Some(InverterOperationMode::SelfUse)
+        );
+
+        // Just below the trigger keeps shaving, well below it stops
+        let at = feed(&mut tracker, at, 15, 8800.0, 0.0);
+        assert!(tracker.shaving_window("main", &config()).is_some());
+        feed(&mut tracker, at, 15, 5000.0, 0.0);
+        assert!(tracker.shaving_window("main", &config()).is_none());
+    }
+}
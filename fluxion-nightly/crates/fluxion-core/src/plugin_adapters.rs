@@ -13,7 +13,7 @@
 //! Plugin adapters for wrapping Fluxion strategies as plugins.
 
 use crate::strategy::{
-    EconomicStrategy, EvaluationContext,
+    CurtailmentLimits, DegradationModel, EconomicStrategy, EvaluationContext,
     fixed_price_arbitrage::{FixedPriceArbitrageConfig, FixedPriceArbitrageStrategy},
     winter_adaptive::{WinterAdaptiveConfig, WinterAdaptiveStrategy},
     winter_adaptive_v2::{WinterAdaptiveV2Config, WinterAdaptiveV2Strategy},
@@ -26,7 +26,10 @@ use crate::strategy::{
     winter_adaptive_v10::{WinterAdaptiveV10Config, WinterAdaptiveV10Strategy},
     winter_adaptive_v20::{WinterAdaptiveV20Config, WinterAdaptiveV20Strategy},
 };
-use fluxion_plugins::{BlockDecision, EvaluationRequest, Plugin, PluginManager};
+use fluxion_plugins::{
+    BlockDecision, ConsideredAlternative, EvaluationRequest, Plugin, PluginDocs, PluginManager,
+    PluginRegistry, WasmPluginDirectory,
+};
 use fluxion_types::config::ControlConfig;
 use fluxion_types::inverter::InverterOperationMode;
 use fluxion_types::pricing::TimeBlockPrice;
@@ -71,6 +74,10 @@ impl<S: EconomicStrategy + 'static> Plugin for StrategyPlugin<S> {
         self.strategy.is_enabled()
     }
 
+    fn describe(&self) -> PluginDocs {
+        self.strategy.describe()
+    }
+
     fn evaluate(&self, request: &EvaluationRequest) -> anyhow::Result<BlockDecision> {
         let (price_block, all_blocks) = convert_request(request);
 
@@ -96,9 +103,23 @@ impl<S: EconomicStrategy + 'static> Plugin for StrategyPlugin<S> {
                 .and_then(|v| <&[f32; 24]>::try_from(v.as_slice()).ok()),
         };
 
-        let eval = self.strategy.evaluate(&context);
+        let mut eval = self.strategy.evaluate(&context);
         let strategy_name = self.strategy.name().to_owned();
 
+        // Strategies assume all PV surplus can leave the inverter; clip their
+        // own flows to the inverter and grid limits before costing. Flows
+        // within the limits are left unchanged.
+        let mut limits =
+            CurtailmentLimits::from_control_config(&self.control_config, eval.duration_minutes);
+        let block_end = eval.block_start + chrono::Duration::minutes(eval.duration_minutes.into());
+        if let Some(cap_w) = self
+            .control_config
+            .export_cap_during(eval.block_start, block_end)
+        {
+            limits = limits.with_export_cap_w(cap_w, eval.duration_minutes);
+        }
+        let curtailment = limits.apply(&mut eval.energy_flows);
+
         // Calculate net profit from energy flows (centralized cost calculation)
         let net_profit = calculate_net_profit(
             &eval,
@@ -107,16 +128,46 @@ impl<S: EconomicStrategy + 'static> Plugin for StrategyPlugin<S> {
             request.battery_avg_charge_price_czk_per_kwh,
         );
 
+        // Cycle depth, temperature and calendar aging (0 unless the model is enabled)
+        let degradation_cost = DegradationModel::from_control_config(
+            &self.control_config,
+            request.battery.temperature_c,
+        )
+        .block_cost(
+            &eval.energy_flows,
+            request.battery.current_soc_percent,
+            request.battery.capacity_kwh,
+            eval.duration_minutes,
+        );
+        let net_profit = net_profit - degradation_cost;
+
+        // Modes the strategy scored itself but did not pick
+        let considered_alternatives = eval
+            .debug_info
+            .iter()
+            .flat_map(|debug| &debug.evaluated_strategies)
+            .filter(|candidate| candidate.mode != eval.mode)
+            .map(|candidate| ConsideredAlternative {
+                mode: candidate.mode.into(),
+                expected_profit_czk: Some(candidate.net_profit_czk),
+                rejection_reason: candidate.reason.clone(),
+            })
+            .collect();
+
         Ok(BlockDecision {
             block_start: eval.block_start,
             duration_minutes: eval.duration_minutes,
             mode: eval.mode.into(),
             reason: eval.reason,
+            decision_reason: eval.decision_reason,
             priority: self.priority,
             strategy_name: Some(strategy_name),
             confidence: None,
             expected_profit_czk: Some(net_profit),
             decision_uid: eval.decision_uid,
+            curtailed_solar_kwh: (curtailment.curtailed_solar_kwh > 0.0)
+                .then_some(curtailment.curtailed_solar_kwh),
+            considered_alternatives,
         })
     }
 }
@@ -495,14 +546,197 @@ pub fn init_plugin_manager(
 /// # Arguments
 /// * `strategies_config` - Optional strategies configuration (uses defaults if None)
 /// * `control_config` - Control configuration for battery parameters
+/// * `registry` - Persisted external plugins to register again (none if None)
 ///
 /// # Returns
 /// A new PluginManager with built-in strategies registered
 pub fn create_plugin_manager(
     strategies_config: Option<&fluxion_types::config::StrategiesConfigCore>,
     control_config: &ControlConfig,
+    registry: Option<&PluginRegistry>,
 ) -> PluginManager {
     let mut manager = PluginManager::new();
     init_plugin_manager(&mut manager, strategies_config, control_config);
+    if let Some(registry) = registry {
+        registry.register_all(&mut manager);
+    }
     manager
 }
+
+/// Check once in the background that the persisted external plugins answer
+///
+/// Unreachable plugins stay registered; they are listed as dashboard health
+/// warnings. Must be called within a tokio runtime.
+pub fn spawn_plugin_reachability_check(registry: Arc<PluginRegistry>) {
+    if registry.plugins().is_empty() {
+        return;
+    }
+    tokio::task::spawn_blocking(move || registry.check_reachability());
+}
+
+/// Keep the `.wasm` strategies in `dir` registered in the shared manager
+///
+/// Scans every `scan_interval_secs` from a supervised background task, so
+/// modules dropped in, replaced or deleted take effect without a restart.
+/// Must be called within a tokio runtime.
+pub fn spawn_wasm_plugin_watcher(
+    dir: std::path::PathBuf,
+    scan_interval_secs: u64,
+    manager: Arc<parking_lot::RwLock<PluginManager>>,
+) {
+    tracing::info!("🧩 Watching {} for WASM strategy plugins", dir.display());
+    let directory = Arc::new(parking_lot::Mutex::new(WasmPluginDirectory::new(dir)));
+    crate::TaskSupervisor::global().spawn("wasm_plugin_watcher", move || {
+        let directory = Arc::clone(&directory);
+        let manager = Arc::clone(&manager);
+        async move {
+            loop {
+                // Compiling modules blocks
+                let scan = Arc::clone(&directory);
+                match tokio::task::spawn_blocking(move || scan.lock().scan()).await {
+                    Ok(changes) => {
+                        if !changes.is_empty() {
+                            let mut manager = manager.write();
+                            for change in changes {
+                                change.apply(&mut manager);
+                            }
+                        }
+                    }
+                    Err(e) => tracing::warn!("WASM plugin scan failed: {e}"),
+                }
+                futures_timer::Delay::new(std::time::Duration::from_secs(
+                    scan_interval_secs.max(1),
+                ))
+                .await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::{BlockEvaluation, EnergyFlows};
+    use chrono::Utc;
+    use fluxion_plugins::{BatteryState, ForecastData, HistoricalData, PriceBlock};
+
+    /// Exports 1.5 kWh of PV in a 15-minute block (6 kW)
+    struct ExportingStrategy;
+
+    impl EconomicStrategy for ExportingStrategy {
+        fn name(&self) -> &str {
+            "Exporting"
+        }
+
+        fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
+            let mut eval = BlockEvaluation::new(
+                context.price_block.block_start,
+                context.price_block.duration_minutes,
+                InverterOperationMode::SelfUse,
+                self.name().to_owned(),
+            );
+            eval.energy_flows = EnergyFlows {
+                solar_generation_kwh: 2.0,
+                household_consumption_kwh: 0.5,
+                grid_export_kwh: 1.5,
+                ..EnergyFlows::default()
+            };
+            eval
+        }
+    }
+
+    fn request() -> EvaluationRequest {
+        let block = PriceBlock {
+            block_start: Utc::now(),
+            duration_minutes: 15,
+            price_czk_per_kwh: 2.0,
+            effective_price_czk_per_kwh: 3.0,
+            spot_sell_price_czk_per_kwh: None,
+            tariff: None,
+        };
+        EvaluationRequest {
+            all_blocks: vec![block.clone()],
+            block,
+            battery: BatteryState {
+                current_soc_percent: 50.0,
+                capacity_kwh: 10.0,
+                max_charge_rate_kw: 5.0,
+                min_soc_percent: 10.0,
+                max_soc_percent: 100.0,
+                efficiency: 0.95,
+                wear_cost_czk_per_kwh: 0.0,
+                temperature_c: None,
+            },
+            forecast: ForecastData {
+                solar_kwh: 2.0,
+                consumption_kwh: 0.5,
+                grid_export_price_czk_per_kwh: 1.0,
+            },
+            historical: HistoricalData {
+                grid_import_today_kwh: None,
+                consumption_today_kwh: None,
+                hourly_consumption_profile: None,
+            },
+            backup_discharge_min_soc: 10.0,
+            hdo_raw_data: None,
+            solar_forecast_total_today_kwh: 0.0,
+            solar_forecast_remaining_today_kwh: 0.0,
+            solar_forecast_tomorrow_kwh: 0.0,
+            battery_avg_charge_price_czk_per_kwh: 0.0,
+            weather: None,
+        }
+    }
+
+    fn decide(maximum_export_power_w: u32, inverter_max_ac_power_w: u32) -> BlockDecision {
+        let control_config = ControlConfig {
+            maximum_export_power_w,
+            inverter_max_ac_power_w,
+            ..ControlConfig::default()
+        };
+        StrategyPlugin::new(ExportingStrategy, 50, control_config)
+            .evaluate(&request())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_limits_that_do_not_bind_leave_profit_unchanged() {
+        let unlimited = decide(0, 0);
+        let generous = decide(10_000, 8_000);
+
+        assert_eq!(generous.expected_profit_czk, unlimited.expected_profit_czk);
+        assert_eq!(generous.curtailed_solar_kwh, None);
+        assert_eq!(unlimited.expected_profit_czk, Some(1.5));
+    }
+
+    #[test]
+    fn test_binding_export_cap_prices_curtailment() {
+        // 4 kW over 15 minutes allows 1.0 kWh of the planned 1.5 kWh export
+        let capped = decide(4_000, 0);
+
+        let curtailed = capped.curtailed_solar_kwh.unwrap();
+        assert!((curtailed - 0.5).abs() < 1e-4);
+        assert!((capped.expected_profit_czk.unwrap() - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_builtin_strategies_are_documented() {
+        let mut manager = PluginManager::new();
+        init_plugin_manager(&mut manager, None, &ControlConfig::default());
+
+        let descriptions = manager.describe_plugins();
+        assert!(!descriptions.is_empty());
+        for description in &descriptions {
+            assert!(!description.docs.summary.is_empty(), "{}", description.name);
+            assert!(
+                !description.docs.decision_logic.is_empty(),
+                "{}",
+                description.name
+            );
+            assert!(
+                !description.docs.parameters.is_empty(),
+                "{}",
+                description.name
+            );
+        }
+    }
+}
@@ -0,0 +1,363 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Exchange rates between market and billing currencies.
+//!
+//! [`ExchangeRateService`] reads the daily fixing of the Czech National Bank
+//! (CNB), which quotes every listed currency against CZK, so any two listed
+//! currencies can be converted through CZK. Fixings are cached per day.
+//! [`ConvertedPriceSource`] wraps a [`PriceDataSource`] publishing prices in
+//! one currency (e.g. EUR at OTE or Nord Pool) and converts them into the
+//! billing currency, so the planner, charts and reports all see one currency.
+
+use crate::traits::PriceDataSource;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate, Utc};
+use chrono_tz::Europe::Prague;
+use fluxion_types::pricing::SpotPriceData;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// CNB daily exchange rate fixing
+pub const CNB_DAILY_URL: &str = "https://www.cnb.cz/en/financial-markets/foreign-exchange-market/central-bank-exchange-rate-fixing/central-bank-exchange-rate-fixing/daily.txt";
+
+/// Days of fixings kept in the cache
+const CACHED_DAYS: i64 = 7;
+
+/// CZK per unit of every currency in a CNB daily fixing (`daily.txt`)
+///
+/// CZK itself is included with a rate of 1.
+pub fn parse_cnb_rates(text: &str) -> Result<HashMap<String, f32>> {
+    let mut rates = HashMap::from([("CZK".to_owned(), 1.0)]);
+    // The first line is the fixing date, the second the column header
+    for line in text.lines().skip(2) {
+        let fields: Vec<&str> = line.split('|').collect();
+        let [_, _, amount, code, rate] = fields[..] else {
+            continue;
+        };
+        let amount: f32 = amount
+            .trim()
+            .parse()
+            .with_context(|| format!("Invalid {code} amount"))?;
+        let rate: f32 = rate
+            .trim()
+            .replace(',', ".")
+            .parse()
+            .with_context(|| format!("Invalid {code} rate"))?;
+        rates.insert(code.trim().to_uppercase(), rate / amount);
+    }
+    anyhow::ensure!(rates.len() > 1, "CNB rate list has no rates");
+    Ok(rates)
+}
+
+/// Daily exchange rates from the CNB, optionally pinned to fixed values
+pub struct ExchangeRateService {
+    client: reqwest::Client,
+    url: String,
+    /// CZK per unit, used instead of the fixing
+    fixed: HashMap<String, f32>,
+    fixings: Mutex<HashMap<NaiveDate, Arc<HashMap<String, f32>>>>,
+}
+
+impl std::fmt::Debug for ExchangeRateService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExchangeRateService")
+            .field("fixed", &self.fixed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for ExchangeRateService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExchangeRateService {
+    pub fn new() -> Self {
+        Self::with_url(CNB_DAILY_URL)
+    }
+
+    /// Read fixings from another `daily.txt` URL
+    pub fn with_url(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            fixed: HashMap::new(),
+            fixings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Pin `currency` to `czk_per_unit` instead of the CNB fixing
+    #[must_use]
+    pub fn with_fixed_rate(mut self, currency: &str, czk_per_unit: f32) -> Self {
+        self.fixed.insert(currency.to_uppercase(), czk_per_unit);
+        self
+    }
+
+    /// Units of `to` per unit of `from` on `date`
+    ///
+    /// When the CNB cannot be reached the most recent cached fixing is used.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no fixing is available or a currency is not listed.
+    pub async fn rate(&self, from: &str, to: &str, date: NaiveDate) -> Result<f32> {
+        let (from, to) = (from.to_uppercase(), to.to_uppercase());
+        if from == to {
+            return Ok(1.0);
+        }
+        let pinned = |code: &str| {
+            if code == "CZK" {
+                Some(1.0)
+            } else {
+                self.fixed.get(code).copied()
+            }
+        };
+        if let (Some(from), Some(to)) = (pinned(&from), pinned(&to)) {
+            return Ok(from / to);
+        }
+
+        let fixing = self.fixing(date).await?;
+        let czk_per_unit = |code: &str| {
+            pinned(code)
+                .or_else(|| fixing.get(code).copied())
+                .with_context(|| format!("CNB fixing has no rate for {code}"))
+        };
+        Ok(czk_per_unit(&from)? / czk_per_unit(&to)?)
+    }
+
+    /// `amount` in `from` converted to `to` at the rate of `date`
+    ///
+    /// # Errors
+    ///
+    /// See [`Self::rate`].
+    pub async fn convert(&self, amount: f32, from: &str, to: &str, date: NaiveDate) -> Result<f32> {
+        Ok(amount * self.rate(from, to, date).await?)
+    }
+
+    /// Fixing of `date`, from the cache or the CNB
+    async fn fixing(&self, date: NaiveDate) -> Result<Arc<HashMap<String, f32>>> {
+        if let Some(fixing) = self.fixings.lock().get(&date) {
+            return Ok(Arc::clone(fixing));
+        }
+        match self.fetch_fixing(date).await {
+            Ok(rates) => {
+                let rates = Arc::new(rates);
+                info!("✅ [CNB] Fetched exchange rates for {date}");
+                let mut fixings = self.fixings.lock();
+                fixings.retain(|day, _| *day > date - Duration::days(CACHED_DAYS));
+                fixings.insert(date, Arc::clone(&rates));
+                Ok(rates)
+            }
+            Err(e) => {
+                let fixings = self.fixings.lock();
+                let (day, latest) = fixings
+                    .iter()
+                    .max_by_key(|(day, _)| **day)
+                    .with_context(|| format!("No exchange rates for {date}: {e:#}"))?;
+                warn!("⚠️ [CNB] Failed to fetch exchange rates ({e:#}), using those of {day}");
+                Ok(Arc::clone(latest))
+            }
+        }
+    }
+
+    async fn fetch_fixing(&self, date: NaiveDate) -> Result<HashMap<String, f32>> {
+        debug!("💱 [CNB] Downloading exchange rates for {date}");
+        let text = self
+            .client
+            .get(&self.url)
+            .query(&[("date", date.format("%d.%m.%Y").to_string())])
+            .send()
+            .await
+            .context("Failed to send request to CNB")?
+            .error_for_status()
+            .context("CNB rate request failed")?
+            .text()
+            .await
+            .context("Failed to read CNB response")?;
+        parse_cnb_rates(&text)
+    }
+}
+
+/// Multiply every price of `prices` by `rate`
+pub fn convert_prices(prices: &mut SpotPriceData, rate: f32) {
+    for block in &mut prices.time_block_prices {
+        block.price_czk_per_kwh *= rate;
+        block.effective_price_czk_per_kwh *= rate;
+        if let Some(sell) = block.spot_sell_price_czk_per_kwh.as_mut() {
+            *sell *= rate;
+        }
+    }
+}
+
+/// Price source converting the prices of another source into a second currency
+///
+/// All blocks are converted at the rate of the current day, as the fixing of
+/// tomorrow is only published tomorrow.
+pub struct ConvertedPriceSource {
+    inner: Arc<dyn PriceDataSource>,
+    rates: Arc<ExchangeRateService>,
+    from: String,
+    to: String,
+    name: String,
+}
+
+impl std::fmt::Debug for ConvertedPriceSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConvertedPriceSource")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConvertedPriceSource {
+    /// Convert the prices of `inner` from currency `from` into `to`
+    pub fn new(
+        inner: Arc<dyn PriceDataSource>,
+        rates: Arc<ExchangeRateService>,
+        from: &str,
+        to: &str,
+    ) -> Self {
+        let (from, to) = (from.to_uppercase(), to.to_uppercase());
+        let name = format!("{} ({from} → {to})", inner.name());
+        Self {
+            inner,
+            rates,
+            from,
+            to,
+            name,
+        }
+    }
+}
+
+#[async_trait]
+impl PriceDataSource for ConvertedPriceSource {
+    async fn read_prices(&self) -> Result<SpotPriceData> {
+        let mut prices = self.inner.read_prices().await?;
+        let today = Utc::now().with_timezone(&Prague).date_naive();
+        let rate = self
+            .rates
+            .rate(&self.from, &self.to, today)
+            .await
+            .with_context(|| format!("No {}/{} exchange rate", self.from, self.to))?;
+        convert_prices(&mut prices, rate);
+        Ok(prices)
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        self.inner.health_check().await
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_types::pricing::TimeBlockPrice;
+
+    const FIXING: &str = "17.10.2025 #201\n\
+                          Country|Currency|Amount|Code|Rate\n\
+                          EMU|euro|1|EUR|24,325\n\
+                          Japan|yen|100|JPY|13,952\n\
+                          Sweden|krona|1|SEK|2,215\n";
+
+    struct FixedSource;
+
+    #[async_trait]
+    impl PriceDataSource for FixedSource {
+        async fn read_prices(&self) -> Result<SpotPriceData> {
+            let now = Utc::now();
+            Ok(SpotPriceData {
+                time_block_prices: vec![TimeBlockPrice {
+                    block_start: now,
+                    duration_minutes: 15,
+                    price_czk_per_kwh: 0.1,
+                    effective_price_czk_per_kwh: 0.12,
+                    spot_sell_price_czk_per_kwh: Some(0.08),
+                }],
+                block_duration_minutes: 15,
+                fetched_at: now,
+                ha_last_updated: now,
+            })
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "Fixed"
+        }
+    }
+
+    #[test]
+    fn test_parse_cnb_rates() {
+        let rates = parse_cnb_rates(FIXING).unwrap();
+
+        assert!((rates["EUR"] - 24.325).abs() < 1e-4);
+        // Quoted per 100 yen
+        assert!((rates["JPY"] - 0.139_52).abs() < 1e-6);
+        assert!((rates["CZK"] - 1.0).abs() < f32::EPSILON);
+        assert!(parse_cnb_rates("17.10.2025 #201\n").is_err());
+        assert!(parse_cnb_rates("17.10.2025 #201\nheader\nEMU|euro|1|EUR|n/a\n").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cached_fixing_converts_between_listed_currencies() {
+        // Unreachable URL: only the cached fixing can answer
+        let service = ExchangeRateService::with_url("http://127.0.0.1:9/daily.txt");
+        let date = NaiveDate::from_ymd_opt(2025, 10, 17).unwrap();
+        service
+            .fixings
+            .lock()
+            .insert(date, Arc::new(parse_cnb_rates(FIXING).unwrap()));
+
+        let eur_czk = service.rate("eur", "CZK", date).await.unwrap();
+        assert!((eur_czk - 24.325).abs() < 1e-4);
+        let sek = service.convert(10.0, "EUR", "SEK", date).await.unwrap();
+        assert!((sek - 109.819).abs() < 1e-2);
+        assert!(service.rate("EUR", "USD", date).await.is_err());
+
+        // A failed fetch for a later day falls back to the latest fixing
+        let later = date.succ_opt().unwrap();
+        assert!((service.rate("EUR", "CZK", later).await.unwrap() - eur_czk).abs() < 1e-6);
+    }
+
+    #[tokio::test]
+    async fn test_converted_source_uses_fixed_rate() {
+        let rates = Arc::new(
+            ExchangeRateService::with_url("http://127.0.0.1:9/daily.txt")
+                .with_fixed_rate("EUR", 25.0),
+        );
+        let source = ConvertedPriceSource::new(Arc::new(FixedSource), rates, "EUR", "CZK");
+
+        let prices = source.read_prices().await.unwrap();
+
+        let block = &prices.time_block_prices[0];
+        assert!((block.price_czk_per_kwh - 2.5).abs() < 1e-5);
+        assert!((block.effective_price_czk_per_kwh - 3.0).abs() < 1e-5);
+        assert_eq!(
+            block
+                .spot_sell_price_czk_per_kwh
+                .map(|p| (p * 10.0).round()),
+            Some(20.0)
+        );
+        assert_eq!(source.name(), "Fixed (EUR → CZK)");
+    }
+}
@@ -10,6 +10,7 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
+pub mod exchange_rate;
 pub mod ote;
 
 use anyhow::{Context, Result};
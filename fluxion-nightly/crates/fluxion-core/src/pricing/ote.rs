@@ -10,12 +10,46 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
+//! OTE (Czech electricity market operator) day-ahead prices.
+//!
+//! [`OteMarketData`] downloads historical days for offline tools.
+//! [`OtePriceDataSource`] is a [`PriceDataSource`] fetching today's and
+//! tomorrow's prices directly from OTE, converted from EUR/MWh to CZK/kWh, for
+//! use as a fallback when the Home Assistant price sensor is missing or stale.
+
+use super::exchange_rate::{ExchangeRateService, parse_cnb_rates};
+use crate::traits::PriceDataSource;
 use anyhow::{Context, Result};
+use async_trait::async_trait;
 use calamine::{Reader, Xlsx};
-use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use chrono_tz::{Europe::Prague, Tz};
+use fluxion_types::pricing::{SpotPriceData, TimeBlockPrice};
+use parking_lot::Mutex;
 use reqwest::blocking::Client;
+use std::collections::HashMap;
 use std::io::Cursor;
-use tracing::{info, warn};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+/// EUR/CZK rate used when the CNB daily rate cannot be fetched
+pub const DEFAULT_EUR_CZK_RATE: f32 = 25.0;
+
+/// Market time zone the OTE periods are counted in
+const OTE_TIMEZONE: Tz = Prague;
+
+/// Highest period number of a day (100 on the day DST ends)
+const MAX_PERIODS: u32 = 100;
+
+/// One 15-minute period of an OTE day-ahead report
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OtePeriodPrice {
+    /// 1-based period of the market day
+    pub period: u32,
+    pub price_eur_mwh: f32,
+    /// Price in CZK/MWh, when the report has a CZK column
+    pub price_czk_mwh: Option<f32>,
+}
 
 #[derive(Debug, Clone)]
 pub struct PriceRecord {
@@ -44,15 +78,7 @@ impl OteMarketData {
     /// Download Excel file for a specific date
     /// URL pattern: https://www.ote-cr.cz/pubweb/attachments/01/{year}/month{month:02}/day{day:02}/DM_15MIN_{day:02}_{month:02}_{year}_EN.xlsx
     fn download_excel(&self, date: NaiveDate) -> Result<Vec<u8>> {
-        let url = format!(
-            "https://www.ote-cr.cz/pubweb/attachments/01/{}/month{:02}/day{:02}/DM_15MIN_{:02}_{:02}_{}_EN.xlsx",
-            date.year(),
-            date.month(),
-            date.day(),
-            date.day(),
-            date.month(),
-            date.year()
-        );
+        let url = day_report_url(date);
 
         info!("Downloading OTE data from: {}", url);
 
@@ -72,103 +98,32 @@ impl OteMarketData {
 
     /// Parse Excel file and extract price records
     fn parse_excel(&self, bytes: &[u8], date: NaiveDate) -> Result<Vec<PriceRecord>> {
-        let cursor = Cursor::new(bytes);
-        let mut workbook: Xlsx<_> = Xlsx::new(cursor).context("Failed to open Excel workbook")?;
+        let mut records = Vec::new();
 
-        let sheet_names = workbook.sheet_names().to_vec();
-        if sheet_names.is_empty() {
-            anyhow::bail!("No sheets found in Excel file");
-        }
+        for period in parse_day_report(bytes)? {
+            if !(1..=96).contains(&period.period) {
+                continue; // Invalid period
+            }
 
-        let range = workbook
-            .worksheet_range(&sheet_names[0])
-            .context("Failed to read worksheet")?;
+            // Convert period (1-based) to hour and minute
+            // Period 1 = 00:00-00:15, Period 2 = 00:15-00:30, etc.
+            let total_minutes = (period.period - 1) * 15;
+            let hour = total_minutes / 60;
+            let minute = total_minutes % 60;
 
-        let mut records = Vec::new();
-        let mut found_table_header = false;
-        let mut hour_col_idx = None;
-        let mut price_eur_col_idx = None;
-        let mut price_czk_col_idx = None;
-
-        // Find the data table header (looks for "Period" and "15 min price")
-        for (row_idx, row) in range.rows().enumerate() {
-            if found_table_header {
-                // Parse data rows - skip empty rows
-                if row.is_empty() || row.iter().all(|cell| matches!(cell, calamine::Data::Empty)) {
-                    // Skip empty row but don't break - data might be coming
-                    // Only break if we've already seen data (records is not empty)
-                    if !records.is_empty() {
-                        break;
-                    }
-                    continue;
-                }
+            // Offline tools have no exchange rate service; prefer the CZK column
+            let price_czk = period
+                .price_czk_mwh
+                .unwrap_or(period.price_eur_mwh * DEFAULT_EUR_CZK_RATE);
 
-                // Extract period number (1-96 for 15-min intervals)
-                let period = match hour_col_idx.and_then(|idx| row.get(idx)) {
-                    Some(val) => match val {
-                        calamine::Data::Int(p) => *p as u32,
-                        calamine::Data::Float(p) => *p as u32,
-                        _ => continue,
-                    },
-                    _ => continue,
-                };
-
-                if !(1..=96).contains(&period) {
-                    continue; // Invalid period
-                }
+            let time = date.and_hms_opt(hour, minute, 0).context("Invalid time")?;
+            let datetime = time.and_utc();
 
-                // Convert period (1-based) to hour and minute
-                // Period 1 = 00:00-00:15, Period 2 = 00:15-00:30, etc.
-                let total_minutes = (period - 1) * 15;
-                let hour = total_minutes / 60;
-                let minute = total_minutes % 60;
-
-                // Extract 15-min price in EUR
-                let price_eur = match price_eur_col_idx.and_then(|idx| row.get(idx)) {
-                    Some(val) => match val {
-                        calamine::Data::Float(p) => *p as f32,
-                        calamine::Data::Int(p) => *p as f32,
-                        _ => continue,
-                    },
-                    _ => continue,
-                };
-
-                // For CZK, we would need EUR/CZK exchange rate
-                // For now, leave it at 0 or calculate using a fixed rate
-                let price_czk = price_eur * 24.0; // Approximate EUR/CZK rate
-
-                let time = date.and_hms_opt(hour, minute, 0).context("Invalid time")?;
-                let datetime = time.and_utc();
-
-                records.push(PriceRecord {
-                    datetime,
-                    price_eur,
-                    price_czk,
-                });
-            } else {
-                // Look for table header
-                // We're looking for a row with "Period" and "15 min price"
-                for (col_idx, cell) in row.iter().enumerate() {
-                    if let calamine::Data::String(s) = cell {
-                        let lower = s.to_lowercase();
-                        if lower.contains("period") {
-                            hour_col_idx = Some(col_idx);
-                        } else if lower.contains("15 min price") || lower.contains("15min price") {
-                            price_eur_col_idx = Some(col_idx);
-                        } else if lower.contains("czk") && lower.contains("mwh") {
-                            price_czk_col_idx = Some(col_idx);
-                        }
-                    }
-                }
-
-                if hour_col_idx.is_some() && price_eur_col_idx.is_some() {
-                    found_table_header = true;
-                    info!(
-                        "Found price table header at row {}, period_col={:?}, eur_col={:?}, czk_col={:?}",
-                        row_idx, hour_col_idx, price_eur_col_idx, price_czk_col_idx
-                    );
-                }
-            }
+            records.push(PriceRecord {
+                datetime,
+                price_eur: period.price_eur_mwh,
+                price_czk,
+            });
         }
 
         if records.is_empty() {
@@ -206,3 +161,360 @@ impl OteMarketData {
         Ok(all_records)
     }
 }
+
+/// URL of the day-ahead 15-minute report for `date`
+/// URL pattern: https://www.ote-cr.cz/pubweb/attachments/01/{year}/month{month:02}/day{day:02}/DM_15MIN_{day:02}_{month:02}_{year}_EN.xlsx
+fn day_report_url(date: NaiveDate) -> String {
+    format!(
+        "https://www.ote-cr.cz/pubweb/attachments/01/{}/month{:02}/day{:02}/DM_15MIN_{:02}_{:02}_{}_EN.xlsx",
+        date.year(),
+        date.month(),
+        date.day(),
+        date.day(),
+        date.month(),
+        date.year()
+    )
+}
+
+/// Extract the period prices from a day-ahead report workbook
+pub fn parse_day_report(bytes: &[u8]) -> Result<Vec<OtePeriodPrice>> {
+    let cursor = Cursor::new(bytes);
+    let mut workbook: Xlsx<_> = Xlsx::new(cursor).context("Failed to open Excel workbook")?;
+
+    let sheet_names = workbook.sheet_names().to_vec();
+    if sheet_names.is_empty() {
+        anyhow::bail!("No sheets found in Excel file");
+    }
+
+    let range = workbook
+        .worksheet_range(&sheet_names[0])
+        .context("Failed to read worksheet")?;
+
+    let mut periods = Vec::new();
+    let mut found_table_header = false;
+    let mut period_col_idx = None;
+    let mut price_eur_col_idx = None;
+    let mut price_czk_col_idx = None;
+
+    // Find the data table header (looks for "Period" and "15 min price")
+    for (row_idx, row) in range.rows().enumerate() {
+        if found_table_header {
+            // Parse data rows - skip empty rows
+            if row.is_empty() || row.iter().all(|cell| matches!(cell, calamine::Data::Empty)) {
+                // Skip empty row but don't break - data might be coming
+                // Only break if we've already seen data
+                if !periods.is_empty() {
+                    break;
+                }
+                continue;
+            }
+
+            // Extract period number (1-96 for 15-min intervals, up to 100 when DST ends)
+            let Some(period) = period_col_idx
+                .and_then(|idx| row.get(idx))
+                .and_then(cell_f32)
+            else {
+                continue;
+            };
+            let period = period as u32;
+            if !(1..=MAX_PERIODS).contains(&period) {
+                continue; // Invalid period
+            }
+
+            // Extract 15-min price in EUR
+            let Some(price_eur_mwh) = price_eur_col_idx
+                .and_then(|idx| row.get(idx))
+                .and_then(cell_f32)
+            else {
+                continue;
+            };
+
+            periods.push(OtePeriodPrice {
+                period,
+                price_eur_mwh,
+                price_czk_mwh: price_czk_col_idx
+                    .and_then(|idx| row.get(idx))
+                    .and_then(cell_f32),
+            });
+        } else {
+            // Look for table header
+            // We're looking for a row with "Period" and "15 min price"
+            for (col_idx, cell) in row.iter().enumerate() {
+                if let calamine::Data::String(s) = cell {
+                    let lower = s.to_lowercase();
+                    if lower.contains("period") {
+                        period_col_idx = Some(col_idx);
+                    } else if lower.contains("15 min price") || lower.contains("15min price") {
+                        price_eur_col_idx = Some(col_idx);
+                    } else if lower.contains("czk") && lower.contains("mwh") {
+                        price_czk_col_idx = Some(col_idx);
+                    }
+                }
+            }
+
+            if period_col_idx.is_some() && price_eur_col_idx.is_some() {
+                found_table_header = true;
+                info!(
+                    "Found price table header at row {}, period_col={:?}, eur_col={:?}, czk_col={:?}",
+                    row_idx, period_col_idx, price_eur_col_idx, price_czk_col_idx
+                );
+            }
+        }
+    }
+
+    Ok(periods)
+}
+
+fn cell_f32(cell: &calamine::Data) -> Option<f32> {
+    match cell {
+        calamine::Data::Float(v) => Some(*v as f32),
+        calamine::Data::Int(v) => Some(*v as f32),
+        _ => None,
+    }
+}
+
+/// Convert the periods of the market day `date` into price blocks (CZK/kWh)
+///
+/// Periods are counted from local midnight in Prague, so the 92 periods of the
+/// spring DST day and the 100 of the autumn one map onto continuous UTC time.
+pub fn period_blocks(
+    date: NaiveDate,
+    periods: &[OtePeriodPrice],
+    eur_czk_rate: f32,
+) -> Result<Vec<TimeBlockPrice>> {
+    let midnight = date.and_hms_opt(0, 0, 0).context("Invalid date")?;
+    let day_start = OTE_TIMEZONE
+        .from_local_datetime(&midnight)
+        .earliest()
+        .context("Market day has no local midnight")?
+        .with_timezone(&Utc);
+
+    Ok(periods
+        .iter()
+        .map(|period| {
+            let price_czk_mwh = period
+                .price_czk_mwh
+                .unwrap_or(period.price_eur_mwh * eur_czk_rate);
+            let price_czk_per_kwh = price_czk_mwh / 1000.0;
+            TimeBlockPrice {
+                block_start: day_start + Duration::minutes(15 * i64::from(period.period - 1)),
+                duration_minutes: 15,
+                price_czk_per_kwh,
+                // Effective price will be calculated by scheduler with HDO fees
+                effective_price_czk_per_kwh: price_czk_per_kwh,
+                spot_sell_price_czk_per_kwh: None,
+            }
+        })
+        .collect())
+}
+
+/// EUR/CZK rate from a CNB daily exchange rate fixing (`daily.txt`)
+pub fn parse_cnb_eur_rate(text: &str) -> Result<f32> {
+    parse_cnb_rates(text)?
+        .get("EUR")
+        .copied()
+        .context("CNB rate list has no EUR line")
+}
+
+/// Day-ahead prices fetched directly from OTE
+///
+/// Fetched days are cached; tomorrow is retried until OTE publishes it
+/// (around 13:00 local time).
+pub struct OtePriceDataSource {
+    client: reqwest::Client,
+    rates: Arc<ExchangeRateService>,
+    days: Mutex<HashMap<NaiveDate, Vec<TimeBlockPrice>>>,
+}
+
+impl std::fmt::Debug for OtePriceDataSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OtePriceDataSource")
+            .field("rates", &self.rates)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OtePriceDataSource {
+    /// Source converting EUR prices to CZK with `rates`
+    pub fn new(rates: Arc<ExchangeRateService>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            rates,
+            days: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn eur_czk_rate(&self, date: NaiveDate) -> f32 {
+        match self.rates.rate("EUR", "CZK", date).await {
+            Ok(rate) => rate,
+            Err(e) => {
+                warn!("⚠️ [OTE] No EUR/CZK exchange rate ({e:#}), using {DEFAULT_EUR_CZK_RATE}");
+                DEFAULT_EUR_CZK_RATE
+            }
+        }
+    }
+
+    async fn fetch_day(&self, date: NaiveDate) -> Result<Vec<TimeBlockPrice>> {
+        let url = day_report_url(date);
+        debug!("💰 [OTE] Downloading day-ahead prices from: {url}");
+
+        let bytes = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to send request to OTE")?
+            .error_for_status()
+            .context("OTE report not available")?
+            .bytes()
+            .await
+            .context("Failed to read response bytes")?;
+
+        let periods = parse_day_report(&bytes)?;
+        if periods.is_empty() {
+            anyhow::bail!("No price records found in OTE report for {date}");
+        }
+        let rate = if periods.iter().all(|p| p.price_czk_mwh.is_some()) {
+            1.0 // Unused, the report has CZK prices
+        } else {
+            self.eur_czk_rate(date).await
+        };
+        period_blocks(date, &periods, rate)
+    }
+
+    /// Prices of `date`, from the cache or OTE
+    async fn day(&self, date: NaiveDate) -> Result<Vec<TimeBlockPrice>> {
+        if let Some(blocks) = self.days.lock().get(&date) {
+            return Ok(blocks.clone());
+        }
+        let blocks = self.fetch_day(date).await?;
+        info!("✅ [OTE] Fetched {} price blocks for {date}", blocks.len());
+        self.days.lock().insert(date, blocks.clone());
+        Ok(blocks)
+    }
+}
+
+#[async_trait]
+impl PriceDataSource for OtePriceDataSource {
+    async fn read_prices(&self) -> Result<SpotPriceData> {
+        let now = Utc::now();
+        let today = now.with_timezone(&OTE_TIMEZONE).date_naive();
+        self.days.lock().retain(|date, _| *date >= today);
+
+        let mut time_block_prices = self
+            .day(today)
+            .await
+            .with_context(|| format!("Failed to fetch OTE prices for {today}"))?;
+
+        if let Some(tomorrow) = today.succ_opt() {
+            match self.day(tomorrow).await {
+                Ok(blocks) => time_block_prices.extend(blocks),
+                Err(e) => debug!("💰 [OTE] Prices for {tomorrow} not available yet: {e:#}"),
+            }
+        }
+
+        Ok(SpotPriceData {
+            time_block_prices,
+            block_duration_minutes: 15,
+            fetched_at: now,
+            ha_last_updated: now,
+        })
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        let today = Utc::now().with_timezone(&OTE_TIMEZONE).date_naive();
+        Ok(self.day(today).await.is_ok())
+    }
+
+    fn name(&self) -> &str {
+        "OTE"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn periods(count: u32) -> Vec<OtePeriodPrice> {
+        (1..=count)
+            .map(|period| OtePeriodPrice {
+                period,
+                price_eur_mwh: 100.0,
+                price_czk_mwh: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_periods_start_at_prague_midnight() {
+        let date = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+
+        let blocks = period_blocks(date, &periods(96), 25.0).unwrap();
+
+        assert_eq!(blocks.len(), 96);
+        // CEST is UTC+2
+        assert_eq!(
+            blocks[0].block_start,
+            Utc.with_ymd_and_hms(2025, 6, 30, 22, 0, 0).unwrap()
+        );
+        assert_eq!(
+            blocks[95].block_start,
+            Utc.with_ymd_and_hms(2025, 7, 1, 21, 45, 0).unwrap()
+        );
+        // 100 EUR/MWh at 25 CZK/EUR
+        assert!((blocks[0].price_czk_per_kwh - 2.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_dst_days_stay_continuous() {
+        // Clocks go back: 100 periods from 22:00 UTC to 23:00 UTC the next day
+        let autumn = NaiveDate::from_ymd_opt(2025, 10, 26).unwrap();
+        let blocks = period_blocks(autumn, &periods(100), 25.0).unwrap();
+        assert_eq!(
+            blocks[0].block_start,
+            Utc.with_ymd_and_hms(2025, 10, 25, 22, 0, 0).unwrap()
+        );
+        assert_eq!(
+            blocks[99].block_start + Duration::minutes(15),
+            Utc.with_ymd_and_hms(2025, 10, 26, 23, 0, 0).unwrap()
+        );
+
+        // Clocks go forward: 92 periods from 23:00 UTC to 22:00 UTC the next day
+        let spring = NaiveDate::from_ymd_opt(2026, 3, 29).unwrap();
+        let blocks = period_blocks(spring, &periods(92), 25.0).unwrap();
+        assert_eq!(
+            blocks[0].block_start,
+            Utc.with_ymd_and_hms(2026, 3, 28, 23, 0, 0).unwrap()
+        );
+        assert_eq!(
+            blocks[91].block_start + Duration::minutes(15),
+            Utc.with_ymd_and_hms(2026, 3, 29, 22, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_czk_column_wins_over_conversion() {
+        let date = NaiveDate::from_ymd_opt(2025, 7, 1).unwrap();
+        let periods = [OtePeriodPrice {
+            period: 1,
+            price_eur_mwh: 100.0,
+            price_czk_mwh: Some(2430.0),
+        }];
+
+        let blocks = period_blocks(date, &periods, 25.0).unwrap();
+
+        assert!((blocks[0].price_czk_per_kwh - 2.43).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_parse_cnb_eur_rate() {
+        let text = "17.10.2025 #201\n\
+                    Country|Currency|Amount|Code|Rate\n\
+                    Australia|dollar|1|AUD|13.677\n\
+                    EMU|euro|1|EUR|24,325\n\
+                    Japan|yen|100|JPY|13.952\n";
+
+        assert!((parse_cnb_eur_rate(text).unwrap() - 24.325).abs() < 1e-4);
+        assert!(parse_cnb_eur_rate("17.10.2025 #201\n").is_err());
+    }
+}
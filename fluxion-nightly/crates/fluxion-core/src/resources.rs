@@ -19,16 +19,35 @@ use std::sync::Arc;
 use std::time::Duration;
 
 // ============= System Configuration (Imported from fluxion-types) =============
+pub use fluxion_types::calendar::CalendarConfig;
 pub use fluxion_types::config::{
-    ControlConfig, Currency, FixedPriceArbitrageConfigCore, InverterConfig, InverterTopology,
-    PriceSchedule, PricingConfig, RemoteAccessConfigCore, SolarAwareChargingConfigCore,
+    BatteryDegradationConfig, CONFIG_SCHEMA_VERSION, ControlConfig, Currency, ExportCapWindow,
+    FixedPriceArbitrageConfigCore, GridQualityConfigCore, InverterBatteryConfig, InverterConfig,
+    InverterTopology, LoggingConfigCore, MigrationReport, PeakDemandConfigCore, PriceSchedule,
+    PricingConfig, RemoteAccessConfigCore, ScheduleGuardMode, SolarAwareChargingConfigCore,
     SolarForecastConfigCore, StrategiesConfigCore, StrategyEnabledConfigCore, SystemConfig,
-    SystemSettingsConfig, WinterAdaptiveConfigCore, WinterAdaptiveV2ConfigCore,
-    WinterAdaptiveV3ConfigCore, WinterAdaptiveV4ConfigCore, WinterAdaptiveV5ConfigCore,
-    WinterAdaptiveV7ConfigCore, WinterAdaptiveV8ConfigCore, WinterAdaptiveV9ConfigCore,
-    WinterAdaptiveV10ConfigCore, WinterAdaptiveV20ConfigCore, WinterPeakDischargeConfigCore,
+    SystemSettingsConfig, UNVERSIONED_SCHEMA_VERSION, WinterAdaptiveConfigCore,
+    WinterAdaptiveV2ConfigCore, WinterAdaptiveV3ConfigCore, WinterAdaptiveV4ConfigCore,
+    WinterAdaptiveV5ConfigCore, WinterAdaptiveV7ConfigCore, WinterAdaptiveV8ConfigCore,
+    WinterAdaptiveV9ConfigCore, WinterAdaptiveV10ConfigCore, WinterAdaptiveV20ConfigCore,
+    WinterPeakDischargeConfigCore, migrate_config,
 };
 pub use fluxion_types::history::ConsumptionHistoryConfig;
+pub use fluxion_types::tariff::{
+    HdoPreset, HdoSource, Tariff, TariffPeriod, TariffSchedule, TariffWindow,
+};
+pub use fluxion_types::weather::{
+    WeatherConfigCore, WeatherOutlook, WeatherPlanningConfig, WeatherPoint, WeatherSource,
+};
+
+// ============= Logging =============
+
+/// Callback that applies a new logging configuration to the running subscriber.
+///
+/// The binary owning the tracing subscriber installs this; the config handler calls it
+/// when the `logging` section changes so levels and file output change without a restart.
+#[derive(Resource, Clone)]
+pub struct LoggingReloadHandle(pub Arc<dyn Fn(&LoggingConfigCore) + Send + Sync>);
 
 // ============= Timezone Configuration =============
 
@@ -172,40 +191,15 @@ pub struct ConsumptionHistoryDataSourceResource(
     pub Arc<dyn crate::traits::ConsumptionHistoryDataSource>,
 );
 
-// ============= HDO (Czech Grid Tariff) Cache Resource =============
-
-/// Global HDO cache resource for centralized grid fee calculation
-/// This cache is shared by all strategies to ensure consistent pricing
+/// Wrapper resource for a native solar forecast provider (Solcast, Forecast.Solar)
+///
+/// Only present when one is configured; HA forecast sensors are used otherwise.
 #[derive(Resource)]
-pub struct GlobalHdoCache(pub crate::strategy::pricing::HdoCache);
-
-impl GlobalHdoCache {
-    /// Create a new global HDO cache with specified TTL in seconds
-    pub fn new(ttl_secs: u64) -> Self {
-        Self(crate::strategy::pricing::HdoCache::new(ttl_secs))
-    }
-
-    /// Check if cache needs refresh based on TTL
-    pub fn needs_refresh(&self) -> bool {
-        self.0.needs_refresh()
-    }
-
-    /// Update cache with new HDO schedules
-    pub fn update(&self, schedules: Vec<crate::strategy::pricing::HdoDaySchedule>) {
-        self.0.update(schedules)
-    }
+pub struct SolarForecastDataSourceResource(pub Arc<dyn crate::traits::SolarForecastDataSource>);
 
-    /// Check if a given time is in low tariff period
-    pub fn is_low_tariff(&self, dt: chrono::DateTime<chrono::Utc>) -> Option<bool> {
-        self.0.is_low_tariff(dt)
-    }
-}
-
-impl Default for GlobalHdoCache {
-    fn default() -> Self {
-        Self::new(3600) // 1 hour default TTL
-    }
-}
+/// Wrapper resource for the weather forecast provider, present when `[weather]` is enabled
+#[derive(Resource)]
+pub struct WeatherDataSourceResource(pub Arc<dyn crate::traits::WeatherDataSource>);
 
 // ============= Async Cache Resources =============
 
@@ -218,6 +212,7 @@ pub struct PriceCache {
     last_error: parking_lot::Mutex<Option<String>>,
     source: Arc<dyn crate::traits::PriceDataSource>,
     fetch_interval: Duration,
+    replan_requested: std::sync::atomic::AtomicBool,
 }
 
 impl PriceCache {
@@ -231,6 +226,7 @@ impl PriceCache {
             last_error: parking_lot::Mutex::new(None),
             source,
             fetch_interval: Duration::from_secs(interval_secs),
+            replan_requested: std::sync::atomic::AtomicBool::new(false),
         }
     }
 
@@ -262,6 +258,19 @@ impl PriceCache {
         self.last_fetch.lock().elapsed() > self.fetch_interval
     }
 
+    /// Ask for the schedule to be regenerated from the cached prices on the next update,
+    /// even when the cache is still fresh
+    pub fn request_replan(&self) {
+        self.replan_requested
+            .store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Consume a pending replan request, returning whether one was set
+    pub fn take_replan_request(&self) -> bool {
+        self.replan_requested
+            .swap(false, std::sync::atomic::Ordering::Relaxed)
+    }
+
     /// Get the last error that occurred during fetching
     pub fn last_error(&self) -> Option<String> {
         self.last_error.lock().clone()
@@ -291,6 +300,11 @@ impl AsyncInverterWriter {
         Self { source }
     }
 
+    /// Whether the source can apply charge power limits to `inverter_id`
+    pub fn supports_charge_power_limit(&self, inverter_id: &str) -> bool {
+        self.source.supports_charge_power_limit(inverter_id)
+    }
+
     /// Write a command synchronously (blocks until completion)
     /// Use this when you need immediate confirmation of success/failure
     pub fn write_command(
@@ -0,0 +1,440 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Realized versus expected profit.
+//!
+//! Strategies put the profit they expect from each block into its reason, but
+//! nothing checked whether it materialized. Once a block is over, its expected
+//! profit, prices and measured grid energy are stored in `./data/savings.db`.
+//! The realized profit of a block is what the house would have paid without
+//! the battery (its net load at the block's prices) minus what the grid
+//! actually cost. Arbitrage pays in a different block than it costs, so the
+//! two are compared per day, week or month rather than per block.
+
+use crate::components::{BlockActuals, OperationSchedule, SpotPriceData};
+use crate::debug::DebugModeConfig;
+use crate::resources::SystemConfig;
+use crate::time_format::TimeFormatter;
+use anyhow::{Context, Result};
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use parking_lot::Mutex;
+use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Default path for the savings database
+pub const DEFAULT_SAVINGS_PATH: &str = "./data/savings.db";
+
+/// Wait after a block ends until its last telemetry read has been integrated
+const SETTLE_MINUTES: i64 = 5;
+
+/// One finished block: the profit planned for it and the energy measured
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BlockSavings {
+    pub block_start: DateTime<Utc>,
+    pub duration_minutes: u32,
+    /// Profit the strategy expected from the block (CZK)
+    pub expected_profit_czk: Option<f32>,
+    /// Effective import price including grid fees (CZK/kWh)
+    pub import_price_czk_per_kwh: f32,
+    pub export_price_czk_per_kwh: f32,
+    pub solar_kwh: f32,
+    pub consumption_kwh: f32,
+    pub grid_import_kwh: f32,
+    pub grid_export_kwh: f32,
+}
+
+impl BlockSavings {
+    /// What the grid cost during the block (CZK, negative when exports earned more)
+    #[must_use]
+    pub fn actual_cost_czk(&self) -> f32 {
+        self.grid_import_kwh * self.import_price_czk_per_kwh
+            - self.grid_export_kwh * self.export_price_czk_per_kwh
+    }
+
+    /// What the grid would have cost without the battery (CZK)
+    #[must_use]
+    pub fn baseline_cost_czk(&self) -> f32 {
+        let net_load_kwh = self.consumption_kwh - self.solar_kwh;
+        if net_load_kwh > 0.0 {
+            net_load_kwh * self.import_price_czk_per_kwh
+        } else {
+            net_load_kwh * self.export_price_czk_per_kwh
+        }
+    }
+
+    /// Money the battery saved during the block (CZK)
+    #[must_use]
+    pub fn realized_profit_czk(&self) -> f32 {
+        self.baseline_cost_czk() - self.actual_cost_czk()
+    }
+}
+
+/// Length of the periods savings are summed over
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SavingsPeriod {
+    #[default]
+    Day,
+    /// Monday to Sunday
+    Week,
+    Month,
+}
+
+impl SavingsPeriod {
+    /// First local date of the period containing `date`
+    #[must_use]
+    pub fn start_of(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => date - Duration::days(i64::from(date.weekday().num_days_from_monday())),
+            Self::Month => date.with_day(1).unwrap_or(date),
+        }
+    }
+}
+
+/// Expected and realized profit summed over a period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SavingsSummary {
+    /// First local date of the period
+    pub period_start: NaiveDate,
+    pub blocks: usize,
+    pub expected_profit_czk: f32,
+    pub realized_profit_czk: f32,
+    pub actual_cost_czk: f32,
+    pub baseline_cost_czk: f32,
+    pub grid_import_kwh: f32,
+    pub grid_export_kwh: f32,
+}
+
+impl SavingsSummary {
+    fn new(period_start: NaiveDate) -> Self {
+        Self {
+            period_start,
+            blocks: 0,
+            expected_profit_czk: 0.0,
+            realized_profit_czk: 0.0,
+            actual_cost_czk: 0.0,
+            baseline_cost_czk: 0.0,
+            grid_import_kwh: 0.0,
+            grid_export_kwh: 0.0,
+        }
+    }
+
+    fn add(&mut self, block: &BlockSavings) {
+        self.blocks += 1;
+        self.expected_profit_czk += block.expected_profit_czk.unwrap_or(0.0);
+        self.realized_profit_czk += block.realized_profit_czk();
+        self.actual_cost_czk += block.actual_cost_czk();
+        self.baseline_cost_czk += block.baseline_cost_czk();
+        self.grid_import_kwh += block.grid_import_kwh;
+        self.grid_export_kwh += block.grid_export_kwh;
+    }
+}
+
+/// Sum `blocks` per local day, week or month, oldest period first
+#[must_use]
+pub fn summarize(
+    blocks: &[BlockSavings],
+    period: SavingsPeriod,
+    formatter: &TimeFormatter,
+) -> Vec<SavingsSummary> {
+    let mut summaries: Vec<SavingsSummary> = Vec::new();
+    for block in blocks {
+        let start = period.start_of(formatter.local_date(block.block_start));
+        match summaries.iter_mut().find(|s| s.period_start == start) {
+            Some(summary) => summary.add(block),
+            None => {
+                let mut summary = SavingsSummary::new(start);
+                summary.add(block);
+                summaries.push(summary);
+            }
+        }
+    }
+    summaries.sort_by_key(|s| s.period_start);
+    summaries
+}
+
+/// Sum of all `blocks`, starting at the local date of the first one
+#[must_use]
+pub fn total(blocks: &[BlockSavings], formatter: &TimeFormatter) -> Option<SavingsSummary> {
+    let first = blocks.iter().map(|b| b.block_start).min()?;
+    let mut summary = SavingsSummary::new(formatter.local_date(first));
+    for block in blocks {
+        summary.add(block);
+    }
+    Some(summary)
+}
+
+/// SQLite ledger of finished blocks shared by the ECS and the web API
+#[derive(Resource, Clone)]
+pub struct SavingsLedger {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl std::fmt::Debug for SavingsLedger {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SavingsLedger").finish_non_exhaustive()
+    }
+}
+
+impl SavingsLedger {
+    /// Open (or create) the ledger at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open savings ledger {}", path.display()))?;
+        Self::init(conn)
+    }
+
+    /// Open an in-memory ledger (nothing survives a restart)
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        // About 35 000 rows a year, so nothing is deleted
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS block_savings (
+                block_start          INTEGER PRIMARY KEY,
+                duration_minutes     INTEGER NOT NULL,
+                expected_profit_czk  REAL,
+                import_price         REAL NOT NULL,
+                export_price         REAL NOT NULL,
+                solar_kwh            REAL NOT NULL,
+                consumption_kwh      REAL NOT NULL,
+                grid_import_kwh      REAL NOT NULL,
+                grid_export_kwh      REAL NOT NULL
+            );",
+        )
+        .context("Failed to create savings table")?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// Store a finished block, replacing an earlier record of it
+    pub fn record(&self, block: &BlockSavings) -> Result<()> {
+        self.conn.lock().execute(
+            "INSERT OR REPLACE INTO block_savings (
+                block_start, duration_minutes, expected_profit_czk, import_price,
+                export_price, solar_kwh, consumption_kwh, grid_import_kwh, grid_export_kwh
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                block.block_start.timestamp(),
+                block.duration_minutes,
+                block.expected_profit_czk,
+                block.import_price_czk_per_kwh,
+                block.export_price_czk_per_kwh,
+                block.solar_kwh,
+                block.consumption_kwh,
+                block.grid_import_kwh,
+                block.grid_export_kwh,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Blocks starting in `[from, to)`, oldest first
+    pub fn query(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<BlockSavings>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT block_start, duration_minutes, expected_profit_czk, import_price,
+                    export_price, solar_kwh, consumption_kwh, grid_import_kwh, grid_export_kwh
+             FROM block_savings
+             WHERE block_start >= ?1 AND block_start < ?2
+             ORDER BY block_start",
+        )?;
+        let rows = stmt.query_map(params![from.timestamp(), to.timestamp()], |row| {
+            Ok(BlockSavings {
+                block_start: DateTime::from_timestamp(row.get(0)?, 0).unwrap_or_default(),
+                duration_minutes: row.get(1)?,
+                expected_profit_czk: row.get(2)?,
+                import_price_czk_per_kwh: row.get(3)?,
+                export_price_czk_per_kwh: row.get(4)?,
+                solar_kwh: row.get(5)?,
+                consumption_kwh: row.get(6)?,
+                grid_import_kwh: row.get(7)?,
+                grid_export_kwh: row.get(8)?,
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+/// A running block, accounted once its energy has been integrated
+#[derive(Debug, Clone)]
+pub struct PendingBlock {
+    block_start: DateTime<Utc>,
+    duration_minutes: u32,
+    expected_profit_czk: Option<f32>,
+    import_price_czk_per_kwh: f32,
+    export_price_czk_per_kwh: f32,
+}
+
+/// Store each finished block with its expected profit and measured energy
+///
+/// Expected profit and prices are taken while the block runs, since the
+/// schedule and price data may be replaced before it is accounted.
+pub fn savings_system(
+    ledger: Res<SavingsLedger>,
+    debug: Res<DebugModeConfig>,
+    system_config: Res<SystemConfig>,
+    actuals: Res<BlockActuals>,
+    schedule_query: Query<&OperationSchedule>,
+    price_query: Query<&SpotPriceData>,
+    mut pending: Local<Vec<PendingBlock>>,
+) {
+    let now = Utc::now();
+
+    // Simulated modes do not move energy, so their blocks say nothing about the plan
+    if !debug.is_enabled()
+        && let Ok(schedule) = schedule_query.single()
+        && let Some(block) = schedule.get_current_mode(now)
+        && !pending.iter().any(|p| p.block_start == block.block_start)
+        && let Ok(prices) = price_query.single()
+        && let Some(price) = prices
+            .time_block_prices
+            .iter()
+            .find(|p| p.block_start == block.block_start)
+    {
+        pending.push(PendingBlock {
+            block_start: block.block_start,
+            duration_minutes: block.duration_minutes,
+            expected_profit_czk: crate::web_bridge::extract_strategy_info(&block.reason).1,
+            import_price_czk_per_kwh: price.effective_price_czk_per_kwh,
+            export_price_czk_per_kwh: price
+                .spot_sell_price_czk_per_kwh
+                .unwrap_or(system_config.control_config.grid_export_fee_czk_per_kwh),
+        });
+    }
+
+    pending.retain(|block| {
+        let settled_at = block.block_start
+            + Duration::minutes(i64::from(block.duration_minutes) + SETTLE_MINUTES);
+        if now < settled_at {
+            return true;
+        }
+        // Without telemetry for the block there is nothing to compare
+        if let Some(actual) = actuals.get(block.block_start) {
+            let savings = BlockSavings {
+                block_start: block.block_start,
+                duration_minutes: block.duration_minutes,
+                expected_profit_czk: block.expected_profit_czk,
+                import_price_czk_per_kwh: block.import_price_czk_per_kwh,
+                export_price_czk_per_kwh: block.export_price_czk_per_kwh,
+                solar_kwh: actual.solar_kwh,
+                consumption_kwh: actual.consumption_kwh,
+                grid_import_kwh: actual.grid_import_kwh,
+                grid_export_kwh: actual.grid_export_kwh,
+            };
+            if let Err(e) = ledger.record(&savings) {
+                warn!(
+                    "Failed to record savings of block {}: {e:#}",
+                    block.block_start
+                );
+            }
+        }
+        false
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(block_start: &str, grid_import_kwh: f32, grid_export_kwh: f32) -> BlockSavings {
+        BlockSavings {
+            block_start: block_start.parse().unwrap(),
+            duration_minutes: 15,
+            expected_profit_czk: Some(1.0),
+            import_price_czk_per_kwh: 4.0,
+            export_price_czk_per_kwh: 1.0,
+            solar_kwh: 0.0,
+            consumption_kwh: 0.5,
+            grid_import_kwh,
+            grid_export_kwh,
+        }
+    }
+
+    #[test]
+    fn test_realized_profit_compares_against_no_battery() {
+        // Battery covered the whole 0.5 kWh load at 4 CZK/kWh
+        let discharge = block("2025-06-02T18:00:00Z", 0.0, 0.0);
+        assert!((discharge.baseline_cost_czk() - 2.0).abs() < 1e-4);
+        assert!((discharge.realized_profit_czk() - 2.0).abs() < 1e-4);
+
+        // Charging 1 kWh on top of the load costs extra now
+        let charge = block("2025-06-02T02:00:00Z", 1.5, 0.0);
+        assert!((charge.actual_cost_czk() - 6.0).abs() < 1e-4);
+        assert!((charge.realized_profit_czk() + 4.0).abs() < 1e-4);
+
+        // Surplus solar would have been exported at the sell price
+        let sunny = BlockSavings {
+            solar_kwh: 1.5,
+            ..block("2025-06-02T11:00:00Z", 0.0, 0.25)
+        };
+        assert!((sunny.baseline_cost_czk() + 1.0).abs() < 1e-4);
+        assert!((sunny.realized_profit_czk() + 0.75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_blocks_are_summarized_per_local_period() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        let blocks = vec![
+            block("2025-06-01T21:00:00Z", 0.0, 0.0), // Sunday 23:00 local
+            block("2025-06-01T22:00:00Z", 0.0, 0.0), // Monday 00:00 local
+            block("2025-06-02T12:00:00Z", 0.5, 0.0),
+        ];
+
+        let days = summarize(&blocks, SavingsPeriod::Day, &prague);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[1].period_start, "2025-06-02".parse().unwrap());
+        assert_eq!(days[1].blocks, 2);
+        assert!((days[1].expected_profit_czk - 2.0).abs() < 1e-4);
+        assert!((days[1].realized_profit_czk - 2.0).abs() < 1e-4);
+
+        let weeks = summarize(&blocks, SavingsPeriod::Week, &prague);
+        assert_eq!(weeks.len(), 2);
+        assert_eq!(weeks[0].period_start, "2025-05-26".parse().unwrap());
+
+        let months = summarize(&blocks, SavingsPeriod::Month, &prague);
+        assert_eq!(months.len(), 1);
+        assert_eq!(months[0].period_start, "2025-06-01".parse().unwrap());
+
+        let total = total(&blocks, &prague).unwrap();
+        assert_eq!(total.blocks, 3);
+        assert!((total.realized_profit_czk - 4.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ledger_round_trip() {
+        let ledger = SavingsLedger::open_in_memory().unwrap();
+        let first = block("2025-06-02T02:00:00Z", 1.5, 0.0);
+        ledger.record(&first).unwrap();
+        ledger.record(&first).unwrap();
+        ledger
+            .record(&block("2025-06-02T02:15:00Z", 0.0, 0.0))
+            .unwrap();
+
+        let stored = ledger
+            .query(first.block_start, first.block_start + Duration::minutes(15))
+            .unwrap();
+        assert_eq!(stored, vec![first]);
+    }
+}
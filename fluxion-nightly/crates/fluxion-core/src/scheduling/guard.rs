@@ -0,0 +1,358 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Physical feasibility check of an optimized schedule.
+//!
+//! Strategies decide block by block and post-processing reshapes runs, so a
+//! finished schedule can ask for things the battery cannot do. [`guard_schedule`]
+//! replays the schedule from the current SOC and flags:
+//!
+//! - force charging without headroom below `max_battery_soc`;
+//! - force discharging with no energy stored above the minimum SOC;
+//! - partial charge powers above the maximum charge rate.
+//!
+//! With [`ScheduleGuardMode::Repair`] impossible blocks fall back to the
+//! default battery mode and excessive powers are clamped. Findings are always
+//! added to the block's debug conditions.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use fluxion_types::config::{ControlConfig, ScheduleGuardMode};
+use fluxion_types::inverter::InverterOperationMode;
+use fluxion_types::pricing::TimeBlockPrice;
+use fluxion_types::scheduling::{BlockDebugInfo, OperationSchedule, ScheduledMode};
+use tracing::warn;
+
+/// Energy below which a battery counts as full or empty (kWh)
+pub(super) const ENERGY_EPSILON_KWH: f32 = 0.01;
+
+/// Prefix of the debug conditions added by the guard
+const CONDITION_PREFIX: &str = "Schedule guard";
+
+/// An impossible block found by the guard
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuardFinding {
+    pub block_start: DateTime<Utc>,
+    /// Mode as planned
+    pub mode: InverterOperationMode,
+    pub issue: String,
+    /// Whether the block was changed
+    pub repaired: bool,
+}
+
+/// Check `schedule` for physically impossible blocks, starting at `current_soc`
+///
+/// Forecasts are indexed like `time_block_prices` (kWh per block); without a
+/// consumption forecast the average household load is assumed.
+pub fn guard_schedule(
+    schedule: &mut OperationSchedule,
+    time_block_prices: &[TimeBlockPrice],
+    current_soc: f32,
+    solar_forecast: Option<&[f32]>,
+    consumption_forecast: Option<&[f32]>,
+    config: &ControlConfig,
+) -> Vec<GuardFinding> {
+    let capacity = config.battery_capacity_kwh;
+    if config.schedule_guard == ScheduleGuardMode::Off || capacity <= 0.0 {
+        return Vec::new();
+    }
+    let repair = config.schedule_guard == ScheduleGuardMode::Repair;
+
+    let price_index: HashMap<DateTime<Utc>, usize> = time_block_prices
+        .iter()
+        .enumerate()
+        .map(|(idx, block)| (block.block_start, idx))
+        .collect();
+    let forecast_kwh = |forecast: Option<&[f32]>, block: &ScheduledMode| {
+        price_index
+            .get(&block.block_start)
+            .and_then(|&idx| forecast.and_then(|f| f.get(idx).copied()))
+    };
+
+    let to_kwh = |soc: f32| soc / 100.0 * capacity;
+    let floor_kwh = to_kwh(config.min_battery_soc.max(config.hardware_min_battery_soc));
+    let ceiling_kwh = to_kwh(config.max_battery_soc);
+    let mut stored_kwh = to_kwh(current_soc.clamp(0.0, 100.0));
+    let mut findings = Vec::new();
+
+    for block in &mut schedule.scheduled_blocks {
+        let hours = block.duration_minutes as f32 / 60.0;
+        let solar_kwh = forecast_kwh(solar_forecast, block).unwrap_or(0.0);
+        let consumption_kwh = forecast_kwh(consumption_forecast, block)
+            .unwrap_or(config.average_household_load_kw * hours);
+        let soc = stored_kwh / capacity * 100.0;
+        let planned = block.mode;
+
+        let mut issues = Vec::new();
+        let mut fall_back = false;
+        match planned {
+            InverterOperationMode::ForceCharge => {
+                if let Some(power_kw) = block.charge_power_kw
+                    && power_kw > config.max_battery_charge_rate_kw
+                {
+                    issues.push(format!(
+                        "charge power {power_kw:.1} kW exceeds the {:.1} kW limit",
+                        config.max_battery_charge_rate_kw
+                    ));
+                    if repair {
+                        block.charge_power_kw = Some(config.max_battery_charge_rate_kw);
+                    }
+                }
+                if ceiling_kwh - stored_kwh <= ENERGY_EPSILON_KWH {
+                    issues.push(format!(
+                        "no headroom to charge at {soc:.1}% SOC (max {:.1}%)",
+                        config.max_battery_soc
+                    ));
+                    fall_back = true;
+                }
+            }
+            InverterOperationMode::ForceDischarge => {
+                if stored_kwh - floor_kwh <= ENERGY_EPSILON_KWH {
+                    issues.push(format!(
+                        "nothing to discharge at {soc:.1}% SOC (min {:.1}%)",
+                        floor_kwh / capacity * 100.0
+                    ));
+                    fall_back = true;
+                }
+            }
+            InverterOperationMode::SelfUse
+            | InverterOperationMode::BackUpMode
+            | InverterOperationMode::NoChargeNoDischarge => {}
+        }
+
+        if fall_back && repair {
+            block.mode = config.default_battery_mode;
+            block.charge_power_kw = None;
+            block.reason = format!(
+                "Converted from {planned:?} to {:?} (schedule guard: {})",
+                config.default_battery_mode,
+                issues.last().map_or("", String::as_str)
+            );
+            block.decision_reason = Some(fluxion_types::DecisionReason::ScheduleGuard {
+                from: planned,
+                issue: issues.last().cloned().unwrap_or_default(),
+            });
+        }
+        for issue in &issues {
+            record(block, issue, repair);
+            findings.push(GuardFinding {
+                block_start: block.block_start,
+                mode: planned,
+                issue: issue.clone(),
+                repaired: repair,
+            });
+        }
+
+        stored_kwh = next_energy(
+            block,
+            stored_kwh,
+            solar_kwh - consumption_kwh,
+            floor_kwh,
+            ceiling_kwh,
+            capacity,
+            config,
+        );
+    }
+
+    if !findings.is_empty() {
+        warn!(
+            "🛡️ Schedule guard found {} impossible block(s){}",
+            findings.len(),
+            if repair { ", repaired" } else { "" }
+        );
+    }
+    findings
+}
+
+/// Add `issue` to the debug conditions of `block`
+fn record(block: &mut ScheduledMode, issue: &str, repaired: bool) {
+    let debug_info = block.debug_info.get_or_insert_with(|| BlockDebugInfo {
+        evaluated_strategies: Vec::new(),
+        winning_reason: String::new(),
+        conditions: Vec::new(),
+    });
+    let action = if repaired { "repaired" } else { "flagged" };
+    debug_info
+        .conditions
+        .push(format!("{CONDITION_PREFIX} ({action}): {issue}"));
+}
+
+/// Stored energy after `block`, given the block's solar surplus (kWh, negative = deficit)
+pub(super) fn next_energy(
+    block: &ScheduledMode,
+    stored_kwh: f32,
+    surplus_kwh: f32,
+    floor_kwh: f32,
+    ceiling_kwh: f32,
+    capacity: f32,
+    config: &ControlConfig,
+) -> f32 {
+    let hours = block.duration_minutes as f32 / 60.0;
+    let next = match block.mode {
+        InverterOperationMode::ForceCharge => {
+            let power_kw = block
+                .charge_power_kw
+                .unwrap_or(config.max_battery_charge_rate_kw)
+                .min(config.max_battery_charge_rate_kw);
+            (stored_kwh + power_kw * hours).min(ceiling_kwh.max(stored_kwh))
+        }
+        InverterOperationMode::ForceDischarge => {
+            let export_w = config
+                .export_cap_at(block.block_start)
+                .unwrap_or(config.maximum_export_power_w);
+            let export_kwh = export_w as f32 / 1000.0 * hours;
+            (stored_kwh - export_kwh + surplus_kwh.min(0.0)).max(floor_kwh.min(stored_kwh))
+        }
+        InverterOperationMode::SelfUse | InverterOperationMode::BackUpMode => {
+            stored_kwh + surplus_kwh
+        }
+        InverterOperationMode::NoChargeNoDischarge => stored_kwh,
+    };
+    // Self-use stops at the hardware floor and a full battery
+    let hardware_floor_kwh = config.hardware_min_battery_soc / 100.0 * capacity;
+    next.clamp(hardware_floor_kwh.min(stored_kwh), capacity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn block(start: DateTime<Utc>, mode: InverterOperationMode) -> ScheduledMode {
+        ScheduledMode {
+            block_start: start,
+            duration_minutes: 15,
+            target_inverters: None,
+            mode,
+            reason: "test".to_owned(),
+            decision_reason: None,
+            decision_uid: None,
+            charge_power_kw: None,
+            target_soc: None,
+            forecast: None,
+            debug_info: None,
+        }
+    }
+
+    fn schedule(modes: &[InverterOperationMode]) -> OperationSchedule {
+        let start = DateTime::parse_from_rfc3339("2025-01-15T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        OperationSchedule {
+            scheduled_blocks: modes
+                .iter()
+                .enumerate()
+                .map(|(i, &mode)| block(start + Duration::minutes(15 * i as i64), mode))
+                .collect(),
+            ..OperationSchedule::default()
+        }
+    }
+
+    fn config(mode: ScheduleGuardMode) -> ControlConfig {
+        ControlConfig {
+            battery_capacity_kwh: 10.0,
+            min_battery_soc: 20.0,
+            hardware_min_battery_soc: 10.0,
+            max_battery_soc: 90.0,
+            max_battery_charge_rate_kw: 8.0,
+            maximum_export_power_w: 8000,
+            average_household_load_kw: 0.0,
+            schedule_guard: mode,
+            ..ControlConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_charge_run_is_cut_when_battery_is_full() {
+        use InverterOperationMode::{ForceCharge, SelfUse};
+        // 8 kW for 15 minutes adds 2 kWh: 70% -> 90%, then nothing is left
+        let mut schedule = schedule(&[ForceCharge, ForceCharge, ForceCharge]);
+
+        let findings = guard_schedule(
+            &mut schedule,
+            &[],
+            70.0,
+            None,
+            None,
+            &config(ScheduleGuardMode::Repair),
+        );
+
+        let modes: Vec<_> = schedule.scheduled_blocks.iter().map(|b| b.mode).collect();
+        assert_eq!(modes, vec![ForceCharge, SelfUse, SelfUse]);
+        assert_eq!(findings.len(), 2);
+        assert!(findings.iter().all(|f| f.repaired));
+        let repaired = &schedule.scheduled_blocks[1];
+        assert!(repaired.reason.contains("schedule guard: no headroom"));
+        let conditions = &repaired.debug_info.as_ref().unwrap().conditions;
+        assert!(conditions[0].starts_with("Schedule guard (repaired)"));
+    }
+
+    #[test]
+    fn test_discharge_stops_at_min_soc_and_power_is_clamped() {
+        use InverterOperationMode::{ForceCharge, ForceDischarge, SelfUse};
+        // 8 kW for 15 minutes removes 2 kWh: 40% -> 20%, the minimum
+        let mut schedule = schedule(&[ForceDischarge, ForceDischarge, ForceCharge]);
+        schedule.scheduled_blocks[2].charge_power_kw = Some(12.0);
+
+        let findings = guard_schedule(
+            &mut schedule,
+            &[],
+            40.0,
+            None,
+            None,
+            &config(ScheduleGuardMode::Repair),
+        );
+
+        let blocks = &schedule.scheduled_blocks;
+        assert_eq!(blocks[0].mode, ForceDischarge);
+        assert_eq!(blocks[1].mode, SelfUse);
+        assert_eq!(blocks[2].mode, ForceCharge);
+        assert_eq!(blocks[2].charge_power_kw, Some(8.0));
+        assert_eq!(findings.len(), 2);
+        assert!(findings[1].issue.contains("exceeds the 8.0 kW limit"));
+    }
+
+    #[test]
+    fn test_flag_mode_keeps_the_plan() {
+        use InverterOperationMode::ForceCharge;
+        let mut schedule = schedule(&[ForceCharge]);
+
+        let findings = guard_schedule(
+            &mut schedule,
+            &[],
+            95.0,
+            None,
+            None,
+            &config(ScheduleGuardMode::Flag),
+        );
+
+        assert_eq!(findings.len(), 1);
+        assert!(!findings[0].repaired);
+        let block = &schedule.scheduled_blocks[0];
+        assert_eq!(block.mode, ForceCharge);
+        assert_eq!(block.reason, "test");
+        let conditions = &block.debug_info.as_ref().unwrap().conditions;
+        assert!(conditions[0].starts_with("Schedule guard (flagged)"));
+
+        let mut schedule = self::schedule(&[ForceCharge]);
+        let off = guard_schedule(
+            &mut schedule,
+            &[],
+            95.0,
+            None,
+            None,
+            &config(ScheduleGuardMode::Off),
+        );
+        assert!(off.is_empty());
+    }
+}
@@ -10,21 +10,31 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
+pub mod guard;
+pub mod multi_inverter;
+pub mod partial_charge;
+pub mod trajectory;
+
 use crate::strategy::BlockEvaluation;
 use chrono::Utc;
 use fluent::fluent_args;
 use fluxion_i18n::I18n;
 use fluxion_plugins::{
-    BatteryState, BlockDecision, EvaluationRequest, ForecastData, HistoricalData, OperationMode,
-    PluginManager, PriceBlock,
+    BatteryState, BlockDecision, EvaluationRequest, FALLBACK_DECISION_UID, FallbackScheduler,
+    ForecastData, HistoricalData, OperationMode, PluginManager, PriceBlock,
 };
-use fluxion_types::UserControlState;
 use fluxion_types::config::{ControlConfig, Currency, PricingConfig};
 use fluxion_types::inverter::InverterOperationMode;
 use fluxion_types::pricing::{PriceAnalysis, TimeBlockPrice};
-use fluxion_types::scheduling::{OperationSchedule, ScheduledMode};
+use fluxion_types::scheduling::{BlockForecast, OperationSchedule, ScheduledMode};
+use fluxion_types::tariff::{Tariff, TariffSchedule};
+use fluxion_types::{DecisionReason, UserControlState};
+use std::time::Instant;
 use tracing::{debug, info, warn};
 
+/// Decision UID of blocks past `control.optimization_horizon_hours`
+pub const HORIZON_DECISION_UID: &str = "horizon:price_percentile";
+
 /// Check if debug logging is enabled based on log level
 fn is_debug_enabled() -> bool {
     tracing::enabled!(tracing::Level::DEBUG)
@@ -84,59 +94,89 @@ fn calculate_initial_battery_price(time_block_prices: &[TimeBlockPrice]) -> f32
 
 /// Calculate effective prices (spot price + grid fees) for all time blocks
 ///
-/// Uses the GlobalHdoCache to determine whether each block is in low or high tariff period,
+/// Uses the HDO tariff schedule to determine whether each block is in low or high tariff period,
 /// then adds the appropriate grid fee from PricingConfig to the spot price.
 ///
 /// # Arguments
 /// * `time_block_prices` - Mutable slice of price blocks to update
-/// * `hdo_cache` - Optional global HDO cache for tariff period lookup
+/// * `tariffs` - HDO tariff schedule, see [`crate::tariff::tariff_schedule`]
 /// * `pricing_config` - Pricing configuration with grid fee amounts
 ///
 /// # Note
-/// If HDO cache is not available or returns None for a given block, defaults to
-/// hdo_high_tariff_czk (conservative: avoids underpricing unknown periods)
+/// Blocks the schedule does not cover are priced with hdo_high_tariff_czk
+/// (conservative: avoids underpricing unknown periods)
 pub fn calculate_effective_prices(
     time_block_prices: &mut [TimeBlockPrice],
-    hdo_cache: Option<&crate::resources::GlobalHdoCache>,
+    tariffs: &TariffSchedule,
     pricing_config: &PricingConfig,
 ) {
+    let mut unknown = 0;
     for block in time_block_prices.iter_mut() {
-        let grid_fee = if let Some(cache) = hdo_cache {
-            // Try to get HDO tariff period from cache
-            match cache.is_low_tariff(block.block_start) {
-                Some(true) => {
-                    // Low tariff period
-                    pricing_config.hdo_low_tariff_czk
-                }
-                Some(false) => {
-                    // High tariff period
-                    pricing_config.hdo_high_tariff_czk
-                }
-                None => {
-                    // HDO data not available for this time — default to high tariff
-                    // (conservative: avoids charging during unknown periods that may be expensive)
-                    warn!(
-                        "HDO tariff data not available for {}, defaulting to high tariff",
-                        block.block_start
-                    );
-                    pricing_config.hdo_high_tariff_czk
-                }
+        let grid_fee = match tariffs.tariff_at(block.block_start) {
+            Some(Tariff::Low) => pricing_config.hdo_low_tariff_czk,
+            Some(Tariff::High) => pricing_config.hdo_high_tariff_czk,
+            None => {
+                unknown += 1;
+                pricing_config.hdo_high_tariff_czk
             }
-        } else {
-            // No HDO cache available, default to high tariff (conservative)
-            pricing_config.hdo_high_tariff_czk
         };
 
         // Calculate effective price = spot price + grid fee
         block.effective_price_czk_per_kwh = block.price_czk_per_kwh + grid_fee;
     }
 
+    // Without any schedule every block is high tariff by design
+    if unknown > 0 && !tariffs.is_empty() {
+        warn!(
+            "HDO tariff data not available for {} blocks, defaulting to high tariff",
+            unknown
+        );
+    }
     debug!(
         "Calculated effective prices for {} blocks using HDO tariff data",
         time_block_prices.len()
     );
 }
 
+/// Number of current and future blocks planned by the fallback scheduler
+///
+/// Non-zero means every strategy plugin is disabled or failing for those blocks.
+pub fn fallback_block_count(schedule: &OperationSchedule, now: chrono::DateTime<Utc>) -> usize {
+    schedule
+        .scheduled_blocks
+        .iter()
+        .filter(|b| {
+            b.block_start + chrono::Duration::minutes(i64::from(b.duration_minutes)) > now
+                && b.decision_uid.as_deref() == Some(FALLBACK_DECISION_UID)
+        })
+        .count()
+}
+
+/// Decide a block with the strategy plugins, or with the cheap price-percentile
+/// heuristic once it starts at or after `horizon_end`
+///
+/// Also returns the decisions of the plugins that were outranked, best first.
+fn evaluate_block(
+    plugin_manager: &PluginManager,
+    request: &EvaluationRequest,
+    horizon_end: Option<chrono::DateTime<Utc>>,
+) -> (BlockDecision, Vec<BlockDecision>) {
+    if horizon_end.is_none_or(|end| request.block.block_start < end) {
+        return plugin_manager.evaluate_ranked(request);
+    }
+    let mut decision = FallbackScheduler.decide(request);
+    decision.reason = format!(
+        "Beyond optimization horizon: {:?} by price percentile",
+        decision.mode
+    );
+    decision.decision_reason = Some(DecisionReason::BeyondHorizon {
+        mode: decision.mode.into(),
+    });
+    decision.strategy_name = Some("Horizon heuristic".to_owned());
+    decision.decision_uid = Some(HORIZON_DECISION_UID.to_owned());
+    (decision, Vec::new())
+}
+
 /// Configuration for schedule generation
 #[derive(Debug, Clone)]
 pub struct ScheduleConfig {
@@ -180,7 +220,7 @@ impl Default for ScheduleConfig {
 /// * `current_battery_soc` - Current battery state of charge (%)
 /// * `solar_forecast` - Optional solar generation forecast per block (kWh)
 /// * `consumption_forecast` - Optional consumption forecast per block (kWh)
-/// * Export price is taken from control_config.grid_export_fee_czk_per_kwh (fixed fee)
+/// * Export price is the block's sell price, else control_config.grid_export_fee_czk_per_kwh
 /// * `backup_discharge_min_soc` - Minimum SOC from HA sensor (backup_discharge_min_soc)
 /// * `grid_import_today_kwh` - Optional grid import energy consumed today (kWh)
 /// * `plugin_manager` - The shared plugin manager with registered strategies
@@ -213,12 +253,18 @@ pub fn generate_schedule_with_optimizer(
         info!("Cannot generate schedule from empty price data");
         return OperationSchedule::default();
     }
+    let started = Instant::now();
     let mut scheduled_blocks = Vec::new();
     let mut total_profit = 0.0;
 
     // Track predicted SOC throughout schedule for realistic future evaluations
     let now = Utc::now();
 
+    // Only blocks before the horizon go through the strategy plugins
+    let horizon_end = (control_config.optimization_horizon_hours > 0).then(|| {
+        now + chrono::Duration::hours(i64::from(control_config.optimization_horizon_hours))
+    });
+
     // IMPORTANT: Filter price blocks to only include current and future blocks
     // This prevents the scheduler from using current SOC for past blocks when regenerating mid-day,
     // which would cause incorrect SOC predictions for the rest of today.
@@ -310,8 +356,8 @@ pub fn generate_schedule_with_optimizer(
             hourly_consumption_profile,
         );
 
-        let decision = plugin_manager.evaluate(&request);
-        let evaluation = convert_decision_to_evaluation(&decision, &request);
+        let (decision, outranked) = evaluate_block(plugin_manager, &request, horizon_end);
+        let evaluation = convert_decision_to_evaluation(&decision, &outranked, &request);
         temp_predicted_soc = update_soc_prediction(
             temp_predicted_soc,
             &evaluation,
@@ -422,7 +468,17 @@ pub fn generate_schedule_with_optimizer(
                 },
                 mode: fixed_evaluation.mode,
                 reason: format!("User Override - {}", fixed_evaluation.reason),
+                decision_reason: Some(DecisionReason::UserOverride {
+                    slot_id: fixed_slot.id.clone(),
+                    note: fixed_slot.note.clone(),
+                }),
                 decision_uid: fixed_evaluation.decision_uid.clone(),
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: Some(BlockForecast {
+                    solar_kwh,
+                    consumption_kwh,
+                }),
                 debug_info: None,
             });
 
@@ -449,8 +505,41 @@ pub fn generate_schedule_with_optimizer(
         );
 
         // Get decision from plugin manager
-        let decision = plugin_manager.evaluate(&request);
-        let mut evaluation = convert_decision_to_evaluation(&decision, &request);
+        let (decision, outranked) = evaluate_block(plugin_manager, &request, horizon_end);
+        let mut evaluation = convert_decision_to_evaluation(&decision, &outranked, &request);
+
+        // Backup reserve ahead of a storm or planned outage: charge up to the raised
+        // minimum SOC and hold it there instead of discharging below it
+        if let Some(uc) = user_control
+            && let Some(reserve_soc) = uc.backup_reserve_soc_at(price_block.block_start)
+            && update_soc_prediction(
+                soc_for_evaluation,
+                &evaluation,
+                control_config,
+                solar_kwh,
+                consumption_kwh,
+            ) < reserve_soc
+        {
+            let original_mode = evaluation.mode;
+            let (mode, action) = if soc_for_evaluation < reserve_soc {
+                (InverterOperationMode::ForceCharge, "charging to")
+            } else {
+                (InverterOperationMode::NoChargeNoDischarge, "holding")
+            };
+            evaluation.mode = mode;
+            evaluation.reason = format!(
+                "{} (converted from {:?} - {} {:.0}% backup reserve)",
+                evaluation.reason, original_mode, action, reserve_soc
+            );
+            evaluation.decision_reason = Some(DecisionReason::BackupReserve {
+                from: original_mode,
+                min_soc: reserve_soc,
+            });
+            debug!(
+                "Block {}: Backup reserve {:.0}% at SOC {:.1}%, using {:?} instead of {:?}",
+                local_idx, reserve_soc, soc_for_evaluation, mode, original_mode
+            );
+        }
 
         // Apply user control restrictions (disallow charge/discharge)
         if let Some(uc) = user_control
@@ -471,6 +560,9 @@ pub fn generate_schedule_with_optimizer(
                     "discharge disallowed"
                 }
             );
+            evaluation.decision_reason = Some(DecisionReason::UserRestriction {
+                from: original_mode,
+            });
 
             debug!(
                 "Block {}: Mode {:?} restricted by user, using {:?} instead",
@@ -478,6 +570,91 @@ pub fn generate_schedule_with_optimizer(
             );
         }
 
+        // Holidays and vacations: keep the battery for the house instead of selling
+        if evaluation.mode == InverterOperationMode::ForceDischarge
+            && control_config.calendar.suppress_discharge
+            && let Some(day) = control_config.special_day_at(price_block.block_start)
+        {
+            evaluation.mode = schedule_config.default_battery_mode;
+            evaluation.reason = format!(
+                "{} (converted from ForceDischarge - {})",
+                evaluation.reason, day.name
+            );
+            evaluation.decision_reason = Some(DecisionReason::SpecialDay {
+                name: day.name.clone(),
+            });
+            debug!(
+                "Block {}: Forced discharge suppressed on {} ({:?})",
+                local_idx, day.date, day.kind
+            );
+        }
+
+        // Cold cloudy day ahead: keep the reserve for the house instead of selling it
+        if evaluation.mode == InverterOperationMode::ForceDischarge
+            && let Some(reserve_soc) =
+                control_config.weather_reserve_soc_at(price_block.block_start)
+            && soc_for_evaluation <= reserve_soc
+        {
+            evaluation.mode = schedule_config.default_battery_mode;
+            evaluation.reason = format!(
+                "{} (converted from ForceDischarge - keeping {:.0}% before a cold cloudy day)",
+                evaluation.reason, reserve_soc
+            );
+            evaluation.decision_reason = Some(DecisionReason::WeatherReserve {
+                min_soc: reserve_soc,
+            });
+            debug!(
+                "Block {}: Forced discharge below weather reserve {:.0}%",
+                local_idx, reserve_soc
+            );
+        }
+
+        // Clear day: solar refills the battery, so skip charging from the grid in the morning
+        if evaluation.mode == InverterOperationMode::ForceCharge
+            && let Some(day) = control_config.clear_morning_at(price_block.block_start)
+        {
+            evaluation.mode = schedule_config.default_battery_mode;
+            evaluation.reason = format!(
+                "{} (converted from ForceCharge - clear day, {:.0}% cloud cover)",
+                evaluation.reason,
+                day.cloud_cover_pct.unwrap_or_default()
+            );
+            evaluation.decision_reason = Some(DecisionReason::ClearDay {
+                cloud_cover_pct: day.cloud_cover_pct.unwrap_or_default(),
+            });
+            debug!(
+                "Block {}: Morning pre-charge skipped on clear day {}",
+                local_idx, day.date
+            );
+        }
+
+        // Export cap window (e.g. distributor testing): never force energy into the grid
+        let block_end = price_block.block_start
+            + chrono::Duration::minutes(price_block.duration_minutes.into());
+        if evaluation.mode == InverterOperationMode::ForceDischarge
+            && let Some(cap_w) =
+                control_config.export_cap_during(price_block.block_start, block_end)
+        {
+            evaluation.mode = InverterOperationMode::SelfUse;
+            evaluation.reason = format!(
+                "{} (converted from ForceDischarge - export cap window {}W)",
+                evaluation.reason, cap_w
+            );
+            evaluation.decision_reason = Some(DecisionReason::ExportCap { cap_w });
+            debug!(
+                "Block {}: ForceDischarge inside export cap window, using SelfUse",
+                local_idx
+            );
+        }
+
+        // Curtailment is already priced into the strategy's profit
+        if evaluation.energy_flows.curtailed_solar_kwh >= 0.01 {
+            debug!(
+                "Block {}: {:.2} kWh PV curtailed by inverter/export limits",
+                local_idx, evaluation.energy_flows.curtailed_solar_kwh
+            );
+        }
+
         // Update battery cost tracking based on the decision
         let current_price = price_block.effective_price_czk_per_kwh;
         match evaluation.mode {
@@ -553,7 +730,14 @@ pub fn generate_schedule_with_optimizer(
                 "{} - {} (expected profit: {:.2} CZK)",
                 evaluation.strategy_name, evaluation.reason, evaluation.net_profit_czk
             ),
+            decision_reason: evaluation.decision_reason,
             decision_uid: evaluation.decision_uid.clone(),
+            charge_power_kw: None,
+            target_soc: None,
+            forecast: Some(BlockForecast {
+                solar_kwh: evaluation.assumptions.solar_forecast_kwh,
+                consumption_kwh: evaluation.assumptions.consumption_forecast_kwh,
+            }),
             debug_info: evaluation.debug_info,
         });
     }
@@ -562,6 +746,8 @@ pub fn generate_schedule_with_optimizer(
         scheduled_blocks,
         generated_at: Utc::now(),
         based_on_price_version: Utc::now(), // Current time as no pre-analysis
+        initial_soc: None,
+        inverter_blocks: Vec::new(),
     };
 
     // Count modes
@@ -590,10 +776,69 @@ pub fn generate_schedule_with_optimizer(
         total_profit
     );
 
+    let fallback_count = fallback_block_count(&schedule, now);
+    if fallback_count > 0 {
+        warn!(
+            "⚠️ No strategy plugin produced a valid decision for {} blocks, using the price-percentile fallback",
+            fallback_count
+        );
+    }
+
     // Post-process to ensure minimum consecutive charge blocks
     // Simply remove any force-charge sequences shorter than the minimum required
     remove_short_force_sequences(&mut schedule, control_config);
 
+    // Spread force charging over more blocks at reduced power where the
+    // inverter supports it and that is cheaper or keeps import under the breaker
+    if control_config.partial_charge_enabled {
+        partial_charge::apply_partial_charging(
+            &mut schedule,
+            time_block_prices,
+            consumption_forecast,
+            control_config,
+        );
+    }
+
+    // Size each force-charge run to the energy the battery can still take
+    if control_config.charge_power_modulation_enabled {
+        partial_charge::modulate_charge_power(
+            &mut schedule,
+            time_block_prices,
+            current_battery_soc,
+            solar_forecast,
+            consumption_forecast,
+            control_config,
+        );
+    }
+
+    // Catch blocks the battery cannot carry out before they reach the executor
+    guard::guard_schedule(
+        &mut schedule,
+        time_block_prices,
+        current_battery_soc,
+        solar_forecast,
+        consumption_forecast,
+        control_config,
+    );
+
+    // Fix the SOC path the executor holds the battery to
+    trajectory::plan_soc_trajectory(
+        &mut schedule,
+        time_block_prices,
+        current_battery_soc,
+        solar_forecast,
+        consumption_forecast,
+        control_config,
+    );
+
+    let elapsed = started.elapsed();
+    crate::metrics::Metrics::global().record_schedule_generation(elapsed);
+    debug!(
+        "Schedule generated in {} ms ({} blocks)",
+        elapsed.as_millis(),
+        schedule.scheduled_blocks.len()
+    );
+
     schedule
 }
 
@@ -628,7 +873,8 @@ pub fn generate_schedule(
     for (idx, price_block) in time_block_prices.iter().enumerate() {
         // Determine mode for this block based on analysis
         let currency_symbol = config.display_currency.symbol();
-        let (mode, reason) = if analysis.charge_blocks.contains(&idx) {
+        let price = price_block.price_czk_per_kwh;
+        let (mode, reason, decision_reason) = if analysis.charge_blocks.contains(&idx) {
             let reason = if let Some(i18n_res) = i18n {
                 i18n_res
                     .format(
@@ -650,7 +896,11 @@ pub fn generate_schedule(
                     currency_symbol, price_block.price_czk_per_kwh
                 )
             };
-            (InverterOperationMode::ForceCharge, reason)
+            (
+                InverterOperationMode::ForceCharge,
+                reason,
+                DecisionReason::CheapestBlock { price },
+            )
         } else if analysis.discharge_blocks.contains(&idx) {
             let reason = if let Some(i18n_res) = i18n {
                 i18n_res
@@ -673,7 +923,11 @@ pub fn generate_schedule(
                     currency_symbol, price_block.price_czk_per_kwh
                 )
             };
-            (InverterOperationMode::ForceDischarge, reason)
+            (
+                InverterOperationMode::ForceDischarge,
+                reason,
+                DecisionReason::PeakPrice { price },
+            )
         } else {
             let reason = if let Some(i18n_res) = i18n {
                 i18n_res
@@ -696,7 +950,11 @@ pub fn generate_schedule(
                     currency_symbol, price_block.price_czk_per_kwh
                 )
             };
-            (config.default_battery_mode, reason)
+            (
+                config.default_battery_mode,
+                reason,
+                DecisionReason::NormalOperation { price },
+            )
         };
 
         scheduled_blocks.push(ScheduledMode {
@@ -709,8 +967,12 @@ pub fn generate_schedule(
             },
             mode,
             reason,
+            decision_reason: Some(decision_reason),
             decision_uid: None, // Legacy scheduler doesn't generate decision UIDs
-            debug_info: None,   // Legacy scheduler doesn't generate debug info
+            charge_power_kw: None,
+            target_soc: None,
+            forecast: None,
+            debug_info: None, // Legacy scheduler doesn't generate debug info
         });
     }
 
@@ -718,6 +980,8 @@ pub fn generate_schedule(
         scheduled_blocks,
         generated_at: Utc::now(),
         based_on_price_version: analysis.analyzed_at,
+        initial_soc: None,
+        inverter_blocks: Vec::new(),
     };
 
     info!(
@@ -824,6 +1088,12 @@ fn remove_short_force_sequences(schedule: &mut OperationSchedule, config: &Contr
                     "Converted from {} to Self-Use ({}-block sequence < {} min required)",
                     mode_name, consecutive, min_consecutive
                 );
+                schedule.scheduled_blocks[i + j].decision_reason =
+                    Some(DecisionReason::ShortSequence {
+                        from: mode,
+                        blocks: consecutive,
+                        min_blocks: min_consecutive,
+                    });
             }
             changes += consecutive;
         }
@@ -937,6 +1207,9 @@ fn create_evaluation_request(
             price_czk_per_kwh: price_block.price_czk_per_kwh,
             effective_price_czk_per_kwh: price_block.effective_price_czk_per_kwh,
             spot_sell_price_czk_per_kwh: price_block.spot_sell_price_czk_per_kwh,
+            tariff: control_config
+                .tariff_schedule
+                .tariff_at(price_block.block_start),
         },
         battery: BatteryState {
             current_soc_percent: current_soc,
@@ -946,6 +1219,7 @@ fn create_evaluation_request(
             max_soc_percent: 100.0,
             efficiency: control_config.battery_efficiency,
             wear_cost_czk_per_kwh: control_config.battery_wear_cost_czk_per_kwh,
+            temperature_c: control_config.battery_degradation.battery_temperature_c,
         },
         forecast: ForecastData {
             solar_kwh,
@@ -960,6 +1234,7 @@ fn create_evaluation_request(
                 price_czk_per_kwh: b.price_czk_per_kwh,
                 effective_price_czk_per_kwh: b.effective_price_czk_per_kwh,
                 spot_sell_price_czk_per_kwh: b.spot_sell_price_czk_per_kwh,
+                tariff: control_config.tariff_schedule.tariff_at(b.block_start),
             })
             .collect(),
         historical: HistoricalData {
@@ -973,16 +1248,88 @@ fn create_evaluation_request(
         solar_forecast_remaining_today_kwh,
         solar_forecast_tomorrow_kwh,
         battery_avg_charge_price_czk_per_kwh,
+        weather: crate::weather::conditions_at(
+            &control_config.weather_outlook,
+            price_block.block_start,
+        ),
     }
 }
 
+/// Display name of the plugin that made a decision
+fn decision_strategy_name(decision: &BlockDecision) -> String {
+    decision
+        .strategy_name
+        .clone()
+        .unwrap_or_else(|| format!("Plugin (priority {})", decision.priority))
+}
+
+/// Why `loser` was outranked by `winner` under the merge rules of the
+/// plugin manager
+fn outranked_reason(winner: &BlockDecision, loser: &BlockDecision) -> String {
+    let winner_name = decision_strategy_name(winner);
+    if loser.priority < winner.priority {
+        format!(
+            "Priority {} below {} of {winner_name}",
+            loser.priority, winner.priority
+        )
+    } else if loser.confidence.unwrap_or(0.0) < winner.confidence.unwrap_or(0.0) {
+        format!(
+            "Confidence {:.2} below {:.2} of {winner_name}",
+            loser.confidence.unwrap_or(0.0),
+            winner.confidence.unwrap_or(0.0)
+        )
+    } else {
+        format!("Expected profit not above {winner_name}")
+    }
+}
+
+/// Comparison table of a block: the winning decision first, then the
+/// alternatives it considered, then each outranked plugin decision followed
+/// by its own alternatives
+fn compare_decisions(
+    winner: &BlockDecision,
+    outranked: &[BlockDecision],
+) -> Vec<fluxion_types::scheduling::StrategyEvaluation> {
+    use fluxion_types::scheduling::StrategyEvaluation;
+
+    let mut rows = Vec::new();
+    for (decision, rejection_reason) in std::iter::once((winner, None)).chain(
+        outranked
+            .iter()
+            .map(|loser| (loser, Some(outranked_reason(winner, loser)))),
+    ) {
+        let strategy_name = decision_strategy_name(decision);
+        rows.push(StrategyEvaluation {
+            strategy_name: strategy_name.clone(),
+            mode: decision.mode.into(),
+            net_profit_czk: decision.expected_profit_czk.unwrap_or(0.0),
+            reason: decision.reason.clone(),
+            rejection_reason,
+        });
+        rows.extend(decision.considered_alternatives.iter().map(|alternative| {
+            StrategyEvaluation {
+                strategy_name: strategy_name.clone(),
+                mode: alternative.mode.into(),
+                net_profit_czk: alternative.expected_profit_czk.unwrap_or(0.0),
+                reason: format!("Alternative considered by {strategy_name}"),
+                rejection_reason: Some(alternative.rejection_reason.clone()),
+            }
+        }));
+    }
+    rows
+}
+
 /// Convert a plugin decision back to a BlockEvaluation for compatibility
+///
+/// With debug logging the debug info compares the winning decision with its
+/// own alternatives and with the `outranked` decisions of other plugins.
 fn convert_decision_to_evaluation(
     decision: &BlockDecision,
+    outranked: &[BlockDecision],
     request: &EvaluationRequest,
 ) -> BlockEvaluation {
     use crate::strategy::{Assumptions, EnergyFlows};
-    use fluxion_types::scheduling::{BlockDebugInfo, StrategyEvaluation};
+    use fluxion_types::scheduling::BlockDebugInfo;
 
     let mode = match decision.mode {
         OperationMode::SelfUse => InverterOperationMode::SelfUse,
@@ -995,10 +1342,7 @@ fn convert_decision_to_evaluation(
     let net_profit = decision.expected_profit_czk.unwrap_or(0.0);
 
     // Use actual strategy name from decision, falling back to priority-based name
-    let strategy_name = decision
-        .strategy_name
-        .clone()
-        .unwrap_or_else(|| format!("Plugin (priority {})", decision.priority));
+    let strategy_name = decision_strategy_name(decision);
 
     BlockEvaluation {
         block_start: decision.block_start,
@@ -1007,7 +1351,10 @@ fn convert_decision_to_evaluation(
         revenue_czk: if net_profit > 0.0 { net_profit } else { 0.0 },
         cost_czk: if net_profit < 0.0 { -net_profit } else { 0.0 },
         net_profit_czk: net_profit,
-        energy_flows: EnergyFlows::default(),
+        energy_flows: EnergyFlows {
+            curtailed_solar_kwh: decision.curtailed_solar_kwh.unwrap_or(0.0),
+            ..EnergyFlows::default()
+        },
         assumptions: Assumptions {
             solar_forecast_kwh: request.forecast.solar_kwh,
             consumption_forecast_kwh: request.forecast.consumption_kwh,
@@ -1018,16 +1365,15 @@ fn convert_decision_to_evaluation(
             grid_export_price_czk_per_kwh: request.forecast.grid_export_price_czk_per_kwh,
         },
         reason: decision.reason.clone(),
-        strategy_name: strategy_name.clone(),
+        decision_reason: decision
+            .decision_reason
+            .clone()
+            .or_else(|| DecisionReason::parse(&decision.reason)),
+        strategy_name,
         decision_uid: decision.decision_uid.clone(),
         debug_info: if is_debug_enabled() {
             Some(BlockDebugInfo {
-                evaluated_strategies: vec![StrategyEvaluation {
-                    strategy_name,
-                    mode,
-                    net_profit_czk: net_profit,
-                    reason: decision.reason.clone(),
-                }],
+                evaluated_strategies: compare_decisions(decision, outranked),
                 winning_reason: decision.reason.clone(),
                 conditions: vec![
                     format!("SOC: {:.1}%", request.battery.current_soc_percent),
@@ -1039,3 +1385,66 @@ fn convert_decision_to_evaluation(
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_plugins::ConsideredAlternative;
+
+    fn decision(name: &str, mode: OperationMode, priority: u8, profit: f32) -> BlockDecision {
+        BlockDecision {
+            block_start: Utc::now(),
+            duration_minutes: 15,
+            mode,
+            reason: format!("{name} reason"),
+            decision_reason: None,
+            priority,
+            strategy_name: Some(name.to_owned()),
+            confidence: None,
+            expected_profit_czk: Some(profit),
+            decision_uid: None,
+            curtailed_solar_kwh: None,
+            considered_alternatives: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_compare_decisions_lists_alternatives_and_outranked_plugins() {
+        let mut winner = decision("V2", OperationMode::SelfUse, 90, 0.4);
+        winner.considered_alternatives.push(ConsideredAlternative {
+            mode: OperationMode::ForceCharge,
+            expected_profit_czk: Some(-0.2),
+            rejection_reason: "Price above tomorrow's cheapest block".to_owned(),
+        });
+        let loser = decision("V1", OperationMode::ForceCharge, 80, 1.0);
+
+        let rows = compare_decisions(&winner, &[loser]);
+        let summary: Vec<(&str, InverterOperationMode, Option<&str>)> = rows
+            .iter()
+            .map(|r| {
+                (
+                    r.strategy_name.as_str(),
+                    r.mode,
+                    r.rejection_reason.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("V2", InverterOperationMode::SelfUse, None),
+                (
+                    "V2",
+                    InverterOperationMode::ForceCharge,
+                    Some("Price above tomorrow's cheapest block")
+                ),
+                (
+                    "V1",
+                    InverterOperationMode::ForceCharge,
+                    Some("Priority 80 below 90 of V2")
+                ),
+            ]
+        );
+        assert!((rows[1].net_profit_czk + 0.2).abs() < f32::EPSILON);
+    }
+}
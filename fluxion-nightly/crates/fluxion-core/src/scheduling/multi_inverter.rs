@@ -0,0 +1,669 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Coordinated scheduling across inverters with separate batteries.
+//!
+//! The optimizer plans one combined battery (summed capacity and charge rate,
+//! export capped by `control.maximum_export_power_w` at the grid connection).
+//! [`CoordinatedBatteries::plan`] then splits every forced block between the
+//! real batteries, cheapest-to-cycle first:
+//!
+//! - a charge window only buys the energy used before the next window, filling
+//!   the cheapest battery first, each during the cheapest part of the window;
+//! - a discharge block takes the export from the cheapest battery first, each
+//!   within its own export limit.
+//!
+//! Batteries left out of a forced block run the default mode. Those blocks
+//! are returned as per-inverter [`ScheduledMode`]s with `target_inverters` set.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use fluxion_types::config::{ControlConfig, InverterBatteryConfig, InverterTopology, SystemConfig};
+use fluxion_types::inverter::InverterOperationMode;
+use fluxion_types::pricing::TimeBlockPrice;
+use fluxion_types::scheduling::{OperationSchedule, ScheduledMode};
+use tracing::debug;
+
+use crate::traits::GenericInverterState;
+
+/// Default consumption per block when no forecast is available (kWh)
+const DEFAULT_CONSUMPTION_KWH: f32 = 0.25;
+
+/// Energy below which a battery's share of a block is ignored (kWh)
+const MIN_SHARE_KWH: f32 = 0.01;
+
+/// One inverter's battery and its current SOC
+#[derive(Debug, Clone, PartialEq)]
+pub struct InverterBattery {
+    pub inverter_id: String,
+    pub soc_percent: f32,
+    pub config: InverterBatteryConfig,
+}
+
+/// Batteries planned separately, sorted cheapest-to-cycle first
+#[derive(Debug, Clone)]
+pub struct CoordinatedBatteries {
+    batteries: Vec<InverterBattery>,
+}
+
+impl CoordinatedBatteries {
+    /// Coordinate the given batteries; `None` unless there are at least two
+    #[must_use]
+    pub fn new(mut batteries: Vec<InverterBattery>) -> Option<Self> {
+        if batteries.len() < 2 {
+            return None;
+        }
+        batteries.sort_by(|a, b| {
+            a.config
+                .wear_cost_czk_per_kwh
+                .total_cmp(&b.config.wear_cost_czk_per_kwh)
+        });
+        Some(Self { batteries })
+    }
+
+    /// Batteries of the commanded inverters that have a `battery` section
+    ///
+    /// Returns `None` (plan one shared battery) with fewer than two such
+    /// inverters or while one of them has not reported its SOC yet.
+    pub fn from_config<'a>(
+        system_config: &SystemConfig,
+        states: impl IntoIterator<Item = &'a GenericInverterState>,
+    ) -> Option<Self> {
+        let socs: HashMap<&str, f32> = states
+            .into_iter()
+            .map(|state| (state.inverter_id.as_str(), state.battery_soc))
+            .collect();
+
+        let mut batteries = Vec::new();
+        for inverter in &system_config.inverters {
+            if matches!(inverter.topology, InverterTopology::Slave { .. }) {
+                continue;
+            }
+            let Some(config) = &inverter.battery else {
+                continue;
+            };
+            let Some(&soc_percent) = socs.get(inverter.id.as_str()) else {
+                debug!(
+                    "No SOC from {} yet - planning a shared battery",
+                    inverter.id
+                );
+                return None;
+            };
+            batteries.push(InverterBattery {
+                inverter_id: inverter.id.clone(),
+                soc_percent,
+                config: config.clone(),
+            });
+        }
+        Self::new(batteries)
+    }
+
+    /// Batteries in allocation order
+    #[must_use]
+    pub fn batteries(&self) -> &[InverterBattery] {
+        &self.batteries
+    }
+
+    /// Control config describing all batteries as one for the optimizer
+    #[must_use]
+    pub fn planning_config(&self, control_config: &ControlConfig) -> ControlConfig {
+        let capacity_kwh: f32 = self.batteries.iter().map(|b| b.config.capacity_kwh).sum();
+        let export_w: u32 = self
+            .batteries
+            .iter()
+            .map(|b| b.config.maximum_export_power_w)
+            .sum();
+        let wear_cost = if capacity_kwh > 0.0 {
+            self.batteries
+                .iter()
+                .map(|b| b.config.wear_cost_czk_per_kwh * b.config.capacity_kwh)
+                .sum::<f32>()
+                / capacity_kwh
+        } else {
+            control_config.battery_wear_cost_czk_per_kwh
+        };
+
+        ControlConfig {
+            battery_capacity_kwh: capacity_kwh,
+            max_battery_charge_rate_kw: self
+                .batteries
+                .iter()
+                .map(|b| b.config.max_charge_rate_kw)
+                .sum(),
+            maximum_export_power_w: export_w.min(control_config.maximum_export_power_w),
+            battery_wear_cost_czk_per_kwh: wear_cost,
+            ..control_config.clone()
+        }
+    }
+
+    /// SOC of the combined battery (%)
+    #[must_use]
+    pub fn soc_percent(&self) -> f32 {
+        let capacity_kwh: f32 = self.batteries.iter().map(|b| b.config.capacity_kwh).sum();
+        if capacity_kwh <= 0.0 {
+            return 0.0;
+        }
+        self.batteries
+            .iter()
+            .map(|b| b.soc_percent * b.config.capacity_kwh)
+            .sum::<f32>()
+            / capacity_kwh
+    }
+
+    /// Split the combined schedule into per-inverter deviations
+    ///
+    /// `control_config` is the [`planning_config`](Self::planning_config) the
+    /// schedule was generated with; forecasts are indexed like
+    /// `time_block_prices`.
+    #[must_use]
+    pub fn plan(
+        &self,
+        schedule: &OperationSchedule,
+        time_block_prices: &[TimeBlockPrice],
+        solar_forecast: Option<&[f32]>,
+        consumption_forecast: Option<&[f32]>,
+        control_config: &ControlConfig,
+    ) -> Vec<ScheduledMode> {
+        let blocks = &schedule.scheduled_blocks;
+        let price_index: HashMap<DateTime<Utc>, usize> = time_block_prices
+            .iter()
+            .enumerate()
+            .map(|(idx, block)| (block.block_start, idx))
+            .collect();
+        let lookup = |forecast: Option<&[f32]>, start: DateTime<Utc>, default: f32| {
+            price_index
+                .get(&start)
+                .and_then(|idx| forecast.and_then(|f| f.get(*idx).copied()))
+                .unwrap_or(default)
+        };
+        let net_load: Vec<f32> = blocks
+            .iter()
+            .map(|block| {
+                lookup(
+                    consumption_forecast,
+                    block.block_start,
+                    DEFAULT_CONSUMPTION_KWH,
+                ) - lookup(solar_forecast, block.block_start, 0.0)
+            })
+            .collect();
+        let prices: Vec<f32> = blocks
+            .iter()
+            .map(|block| {
+                price_index.get(&block.block_start).map_or(0.0, |idx| {
+                    time_block_prices[*idx].effective_price_czk_per_kwh
+                })
+            })
+            .collect();
+
+        let mut sim = Simulation::new(&self.batteries, control_config);
+        // Per battery: whether it charges in each block of the current window
+        let mut charging: Vec<Vec<bool>> = vec![Vec::new(); self.batteries.len()];
+        let mut window_start = 0;
+        let mut inverter_blocks = Vec::new();
+
+        for (idx, block) in blocks.iter().enumerate() {
+            let hours = block.duration_minutes as f32 / 60.0;
+            let coordinated = !block
+                .decision_uid
+                .as_deref()
+                .is_some_and(|uid| uid.starts_with("user_override:"));
+
+            let modes: Vec<InverterOperationMode> = match block.mode {
+                InverterOperationMode::ForceCharge if coordinated => {
+                    if idx == 0 || blocks[idx - 1].mode != InverterOperationMode::ForceCharge {
+                        window_start = idx;
+                        charging = self.allocate_charge_window(
+                            &sim,
+                            blocks,
+                            &prices,
+                            &net_load,
+                            idx,
+                            control_config,
+                        );
+                    }
+                    let offset = idx - window_start;
+                    let modes: Vec<_> = charging
+                        .iter()
+                        .map(|plan| {
+                            if plan.get(offset).copied().unwrap_or(true) {
+                                InverterOperationMode::ForceCharge
+                            } else {
+                                control_config.default_battery_mode
+                            }
+                        })
+                        .collect();
+                    sim.step(&modes, net_load[idx], hours, None);
+                    modes
+                }
+                InverterOperationMode::ForceDischarge if coordinated => {
+                    let shares = sim.allocate_export(control_config, hours);
+                    let modes: Vec<_> = shares
+                        .iter()
+                        .map(|share| {
+                            if *share >= MIN_SHARE_KWH {
+                                InverterOperationMode::ForceDischarge
+                            } else {
+                                control_config.default_battery_mode
+                            }
+                        })
+                        .collect();
+                    sim.step(&modes, net_load[idx], hours, Some(&shares));
+                    modes
+                }
+                mode => {
+                    let modes = vec![mode; self.batteries.len()];
+                    sim.step(&modes, net_load[idx], hours, None);
+                    modes
+                }
+            };
+
+            inverter_blocks.extend(self.deviations(block, &modes));
+        }
+
+        debug!(
+            "Coordinated {} batteries: {} per-inverter blocks",
+            self.batteries.len(),
+            inverter_blocks.len()
+        );
+        inverter_blocks
+    }
+
+    /// Decide which blocks of the charge window starting at `start` each battery charges in
+    fn allocate_charge_window(
+        &self,
+        sim: &Simulation,
+        blocks: &[ScheduledMode],
+        prices: &[f32],
+        net_load: &[f32],
+        start: usize,
+        control_config: &ControlConfig,
+    ) -> Vec<Vec<bool>> {
+        let end = blocks[start..]
+            .iter()
+            .position(|b| b.mode != InverterOperationMode::ForceCharge)
+            .map_or(blocks.len(), |len| start + len);
+        let window_len = end - start;
+        let block_hours = blocks[start].duration_minutes as f32 / 60.0;
+
+        // Energy drawn from the batteries until the next charge window; without
+        // a later window the horizon is unknown, so every battery fills up
+        let next_window = blocks[end..]
+            .iter()
+            .position(|b| b.mode == InverterOperationMode::ForceCharge);
+        let mut needed = match next_window {
+            Some(len) => {
+                let used: f32 = blocks[end..end + len]
+                    .iter()
+                    .zip(&net_load[end..end + len])
+                    .map(|(block, net)| match block.mode {
+                        InverterOperationMode::ForceDischarge => {
+                            control_config.maximum_export_power_w as f32 / 1000.0
+                                * block.duration_minutes as f32
+                                / 60.0
+                                + net.max(0.0)
+                        }
+                        InverterOperationMode::NoChargeNoDischarge => 0.0,
+                        _ => *net,
+                    })
+                    .sum();
+                (used.max(0.0) - sim.total_available()).max(0.0)
+            }
+            None => f32::INFINITY,
+        };
+
+        let efficiency = sim.efficiency;
+        self.batteries
+            .iter()
+            .enumerate()
+            .map(|(i, battery)| {
+                let per_block = battery.config.max_charge_rate_kw * block_hours * efficiency;
+                let stored = needed.min(sim.room(i)).min(per_block * window_len as f32);
+                if stored < MIN_SHARE_KWH || per_block <= 0.0 {
+                    return vec![false; window_len];
+                }
+                needed -= stored;
+
+                let blocks_needed = ((stored / per_block - 1e-3).ceil() as usize)
+                    .max(control_config.min_consecutive_force_blocks)
+                    .clamp(1, window_len);
+                let first = cheapest_run(&prices[start..end], blocks_needed);
+                (0..window_len)
+                    .map(|offset| offset >= first && offset < first + blocks_needed)
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Per-inverter blocks for the batteries whose mode differs from the shared block
+    fn deviations(
+        &self,
+        block: &ScheduledMode,
+        modes: &[InverterOperationMode],
+    ) -> Vec<ScheduledMode> {
+        let mut groups: Vec<(InverterOperationMode, Vec<String>)> = Vec::new();
+        for (battery, mode) in self.batteries.iter().zip(modes) {
+            if *mode == block.mode {
+                continue;
+            }
+            match groups.iter_mut().find(|(m, _)| m == mode) {
+                Some((_, ids)) => ids.push(battery.inverter_id.clone()),
+                None => groups.push((*mode, vec![battery.inverter_id.clone()])),
+            }
+        }
+
+        groups
+            .into_iter()
+            .map(|(mode, ids)| {
+                let reason = match block.mode {
+                    InverterOperationMode::ForceCharge => {
+                        "Coordinated: charge not needed from this battery"
+                    }
+                    _ => "Coordinated: export covered by cheaper batteries",
+                };
+                ScheduledMode {
+                    block_start: block.block_start,
+                    duration_minutes: block.duration_minutes,
+                    target_inverters: Some(ids),
+                    mode,
+                    reason: format!("{reason} ({})", block.reason),
+                    decision_reason: None,
+                    decision_uid: Some("multi_inverter:coordinated".to_owned()),
+                    charge_power_kw: None,
+                    target_soc: None,
+                    forecast: None,
+                    debug_info: None,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Start of the cheapest run of `len` consecutive blocks
+fn cheapest_run(prices: &[f32], len: usize) -> usize {
+    (0..=prices.len().saturating_sub(len))
+        .min_by(|&a, &b| {
+            let cost = |start: usize| prices[start..start + len].iter().sum::<f32>();
+            cost(a).total_cmp(&cost(b))
+        })
+        .unwrap_or(0)
+}
+
+/// Stored energy of each battery while stepping through the schedule
+struct Simulation<'a> {
+    batteries: &'a [InverterBattery],
+    stored_kwh: Vec<f32>,
+    min_kwh: Vec<f32>,
+    max_kwh: Vec<f32>,
+    efficiency: f32,
+}
+
+impl<'a> Simulation<'a> {
+    fn new(batteries: &'a [InverterBattery], control_config: &ControlConfig) -> Self {
+        let energy = |percent: f32| -> Vec<f32> {
+            batteries
+                .iter()
+                .map(|b| b.config.capacity_kwh * percent / 100.0)
+                .collect()
+        };
+        Self {
+            batteries,
+            stored_kwh: batteries
+                .iter()
+                .map(|b| b.config.capacity_kwh * b.soc_percent / 100.0)
+                .collect(),
+            min_kwh: energy(control_config.min_battery_soc),
+            max_kwh: energy(control_config.max_battery_soc),
+            efficiency: control_config.battery_efficiency.clamp(0.01, 1.0),
+        }
+    }
+
+    fn available(&self, i: usize) -> f32 {
+        (self.stored_kwh[i] - self.min_kwh[i]).max(0.0)
+    }
+
+    fn total_available(&self) -> f32 {
+        (0..self.batteries.len()).map(|i| self.available(i)).sum()
+    }
+
+    fn room(&self, i: usize) -> f32 {
+        (self.max_kwh[i] - self.stored_kwh[i]).max(0.0)
+    }
+
+    /// Export of a discharge block, taken from the cheapest batteries first
+    fn allocate_export(&self, control_config: &ControlConfig, hours: f32) -> Vec<f32> {
+        let mut remaining = control_config.maximum_export_power_w as f32 / 1000.0 * hours;
+        self.batteries
+            .iter()
+            .enumerate()
+            .map(|(i, battery)| {
+                let limit_kw = (battery.config.maximum_export_power_w as f32 / 1000.0)
+                    .min(battery.config.max_charge_rate_kw);
+                let share = remaining.min(limit_kw * hours).min(self.available(i));
+                remaining -= share;
+                share
+            })
+            .collect()
+    }
+
+    /// Advance one block; the net load is shared by the batteries in self-use
+    fn step(
+        &mut self,
+        modes: &[InverterOperationMode],
+        net_load_kwh: f32,
+        hours: f32,
+        export_shares: Option<&[f32]>,
+    ) {
+        let self_use: Vec<usize> = modes
+            .iter()
+            .enumerate()
+            .filter(|(_, mode)| {
+                matches!(
+                    mode,
+                    InverterOperationMode::SelfUse | InverterOperationMode::BackUpMode
+                )
+            })
+            .map(|(i, _)| i)
+            .collect();
+        let self_use_capacity: f32 = self_use
+            .iter()
+            .map(|&i| self.batteries[i].config.capacity_kwh)
+            .sum();
+
+        for (i, mode) in modes.iter().enumerate() {
+            let rate_kwh = self.batteries[i].config.max_charge_rate_kw * hours;
+            match mode {
+                InverterOperationMode::ForceCharge => {
+                    self.stored_kwh[i] += (rate_kwh * self.efficiency).min(self.room(i));
+                }
+                InverterOperationMode::ForceDischarge => {
+                    let share = export_shares.map_or(rate_kwh, |shares| shares[i]);
+                    self.stored_kwh[i] -= share.min(self.available(i));
+                }
+                InverterOperationMode::SelfUse | InverterOperationMode::BackUpMode => {
+                    if self_use_capacity <= 0.0 {
+                        continue;
+                    }
+                    let net =
+                        net_load_kwh * self.batteries[i].config.capacity_kwh / self_use_capacity;
+                    if net > 0.0 {
+                        self.stored_kwh[i] -= net.min(rate_kwh).min(self.available(i));
+                    } else {
+                        self.stored_kwh[i] +=
+                            (-net).min(rate_kwh).min(self.room(i) / self.efficiency)
+                                * self.efficiency;
+                    }
+                }
+                InverterOperationMode::NoChargeNoDischarge => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn battery(id: &str, wear_cost: f32, soc_percent: f32) -> InverterBattery {
+        InverterBattery {
+            inverter_id: id.to_owned(),
+            soc_percent,
+            config: InverterBatteryConfig {
+                capacity_kwh: 10.0,
+                max_charge_rate_kw: 5.0,
+                maximum_export_power_w: 5000,
+                wear_cost_czk_per_kwh: wear_cost,
+            },
+        }
+    }
+
+    fn control_config() -> ControlConfig {
+        ControlConfig {
+            min_battery_soc: 10.0,
+            max_battery_soc: 100.0,
+            battery_efficiency: 1.0,
+            maximum_export_power_w: 5000,
+            min_consecutive_force_blocks: 2,
+            default_battery_mode: InverterOperationMode::SelfUse,
+            ..ControlConfig::default()
+        }
+    }
+
+    fn schedule(modes: &[InverterOperationMode]) -> (OperationSchedule, Vec<TimeBlockPrice>) {
+        let start = Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap();
+        let at = |idx: usize| start + chrono::Duration::minutes(15 * idx as i64);
+        let blocks = modes
+            .iter()
+            .enumerate()
+            .map(|(idx, mode)| ScheduledMode {
+                block_start: at(idx),
+                duration_minutes: 15,
+                target_inverters: None,
+                mode: *mode,
+                reason: "test".to_owned(),
+                decision_reason: None,
+                decision_uid: None,
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: None,
+                debug_info: None,
+            })
+            .collect();
+        // Prices rise through the day
+        let prices = (0..modes.len())
+            .map(|idx| TimeBlockPrice {
+                block_start: at(idx),
+                duration_minutes: 15,
+                price_czk_per_kwh: idx as f32,
+                effective_price_czk_per_kwh: idx as f32,
+                spot_sell_price_czk_per_kwh: None,
+            })
+            .collect();
+        (
+            OperationSchedule {
+                scheduled_blocks: blocks,
+                ..OperationSchedule::default()
+            },
+            prices,
+        )
+    }
+
+    #[test]
+    fn test_planning_config_combines_batteries() {
+        let coordinated =
+            CoordinatedBatteries::new(vec![battery("b", 2.0, 80.0), battery("a", 1.0, 20.0)])
+                .unwrap();
+        let config = coordinated.planning_config(&control_config());
+
+        assert_eq!(coordinated.batteries()[0].inverter_id, "a");
+        assert!((config.battery_capacity_kwh - 20.0).abs() < 1e-4);
+        assert!((config.max_battery_charge_rate_kw - 10.0).abs() < 1e-4);
+        // 2 x 5 kW inverters behind a 5 kW grid connection
+        assert_eq!(config.maximum_export_power_w, 5000);
+        assert!((coordinated.soc_percent() - 50.0).abs() < 1e-4);
+        assert!(CoordinatedBatteries::new(vec![battery("a", 1.0, 50.0)]).is_none());
+    }
+
+    #[test]
+    fn test_discharge_uses_cheapest_battery_within_export_limit() {
+        use InverterOperationMode::{ForceDischarge, SelfUse};
+        let coordinated = CoordinatedBatteries::new(vec![
+            battery("worn", 3.0, 90.0),
+            battery("fresh", 1.0, 90.0),
+        ])
+        .unwrap();
+        let config = coordinated.planning_config(&control_config());
+        let (schedule, prices) = schedule(&[ForceDischarge, ForceDischarge, SelfUse]);
+
+        let blocks = coordinated.plan(&schedule, &prices, None, None, &config);
+
+        // The 5 kW grid limit is covered by the fresh battery alone
+        assert_eq!(blocks.len(), 2);
+        for block in &blocks {
+            assert_eq!(block.mode, SelfUse);
+            assert_eq!(block.target_inverters, Some(vec!["worn".to_owned()]));
+        }
+    }
+
+    #[test]
+    fn test_charge_window_fills_cheapest_battery_first() {
+        use InverterOperationMode::{ForceCharge, ForceDischarge};
+        let coordinated = CoordinatedBatteries::new(vec![
+            battery("worn", 3.0, 10.0),
+            battery("fresh", 1.0, 10.0),
+        ])
+        .unwrap();
+        let config = coordinated.planning_config(&control_config());
+        let (schedule, prices) = schedule(&[
+            ForceCharge,
+            ForceCharge,
+            ForceCharge,
+            ForceCharge,
+            ForceDischarge,
+            ForceDischarge,
+            ForceDischarge,
+            ForceDischarge,
+            ForceCharge,
+            ForceCharge,
+        ]);
+
+        let blocks = coordinated.plan(&schedule, &prices, None, None, &config);
+        let worn_skips: Vec<DateTime<Utc>> = blocks
+            .iter()
+            .filter(|b| {
+                b.mode != ForceCharge && b.target_inverters == Some(vec!["worn".to_owned()])
+            })
+            .map(|b| b.block_start)
+            .collect();
+
+        // 6 kWh is used before the next window: the fresh battery takes 5 kWh
+        // over the whole window, the worn one 1 kWh in the two cheapest blocks
+        let charge_skips: Vec<DateTime<Utc>> = worn_skips
+            .into_iter()
+            .filter(|start| *start < schedule.scheduled_blocks[4].block_start)
+            .collect();
+        assert_eq!(
+            charge_skips,
+            vec![
+                schedule.scheduled_blocks[2].block_start,
+                schedule.scheduled_blocks[3].block_start
+            ]
+        );
+        assert!(
+            !blocks
+                .iter()
+                .any(|b| b.target_inverters == Some(vec!["fresh".to_owned()])
+                    && b.block_start < schedule.scheduled_blocks[4].block_start)
+        );
+    }
+}
@@ -0,0 +1,580 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Partial-power force charging.
+//!
+//! Strategies plan force-charge blocks at the full charge rate. When the
+//! inverter can limit its charge current, the same energy can be bought over
+//! more blocks at lower power. [`apply_partial_charging`] reshapes every
+//! force-charge run into the window that buys its energy cheapest:
+//!
+//! - the window covers the original run and only grows into neighbouring
+//!   default-mode blocks (never into user overrides or other forced blocks);
+//! - charge power plus the expected household load stays under
+//!   `control.max_grid_import_kw`, so a run that would trip the main breaker
+//!   is always spread out;
+//! - with a breaker limit set, the lowest power wins among equally cheap
+//!   windows.
+//!
+//! [`modulate_charge_power`] then sizes each run to the energy the battery
+//! can still take: a run that would fill the battery before it ends charges
+//! evenly across all its blocks at reduced power instead.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use fluxion_types::config::ControlConfig;
+use fluxion_types::inverter::InverterOperationMode;
+use fluxion_types::pricing::TimeBlockPrice;
+use fluxion_types::scheduling::OperationSchedule;
+use tracing::debug;
+
+use super::guard::{ENERGY_EPSILON_KWH, next_energy};
+
+/// Fractions of the maximum charge rate tried for each run, highest first
+const CHARGE_FRACTIONS: [f32; 4] = [1.0, 0.75, 0.5, 0.25];
+
+/// Default consumption per block when no forecast is available (kWh)
+const DEFAULT_CONSUMPTION_KWH: f32 = 0.25;
+
+/// Cost difference treated as equal (CZK)
+const COST_EPSILON: f32 = 0.001;
+
+/// Lowest charge power a run is modulated to (kW)
+const MIN_CHARGE_POWER_KW: f32 = 0.5;
+
+/// A candidate window for one force-charge run
+#[derive(Debug, Clone, Copy)]
+struct ChargeWindow {
+    start: usize,
+    len: usize,
+    power_kw: f32,
+    cost_czk: f32,
+}
+
+/// Reshape force-charge runs into partial-power windows where that is cheaper
+/// or needed to respect the grid import limit
+///
+/// `consumption_forecast` is indexed like `time_block_prices` (kWh per block).
+pub fn apply_partial_charging(
+    schedule: &mut OperationSchedule,
+    time_block_prices: &[TimeBlockPrice],
+    consumption_forecast: Option<&[f32]>,
+    config: &ControlConfig,
+) {
+    let rate_kw = config.max_battery_charge_rate_kw;
+    if rate_kw <= 0.0 || schedule.scheduled_blocks.is_empty() {
+        return;
+    }
+
+    let price_index: HashMap<DateTime<Utc>, usize> = time_block_prices
+        .iter()
+        .enumerate()
+        .map(|(idx, block)| (block.block_start, idx))
+        .collect();
+    let blocks = &schedule.scheduled_blocks;
+    let prices: Vec<Option<f32>> = blocks
+        .iter()
+        .map(|block| {
+            price_index
+                .get(&block.block_start)
+                .map(|&idx| time_block_prices[idx].effective_price_czk_per_kwh)
+        })
+        .collect();
+    let load_kw: Vec<f32> = blocks
+        .iter()
+        .map(|block| {
+            let hours = block.duration_minutes as f32 / 60.0;
+            let kwh = price_index
+                .get(&block.block_start)
+                .and_then(|&idx| consumption_forecast.and_then(|f| f.get(idx).copied()))
+                .unwrap_or(DEFAULT_CONSUMPTION_KWH);
+            if hours > 0.0 { kwh / hours } else { 0.0 }
+        })
+        .collect();
+
+    let mut changed_runs = 0;
+    for (start, end) in force_charge_runs(schedule) {
+        if let Some(window) = best_window(schedule, &prices, &load_kw, start, end, config) {
+            rewrite_run(schedule, start, end, window, config);
+            changed_runs += 1;
+        }
+    }
+
+    if changed_runs > 0 {
+        debug!(
+            "Partial charging reshaped {} force-charge run(s)",
+            changed_runs
+        );
+    }
+}
+
+/// Consecutive force-charge blocks as `(start, end)` index ranges
+fn force_charge_runs(schedule: &OperationSchedule) -> Vec<(usize, usize)> {
+    let blocks = &schedule.scheduled_blocks;
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < blocks.len() {
+        if blocks[i].mode != InverterOperationMode::ForceCharge {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < blocks.len() && blocks[i].mode == InverterOperationMode::ForceCharge {
+            i += 1;
+        }
+        runs.push((start, i));
+    }
+    runs
+}
+
+/// The window to charge a run in, or `None` to keep the run as planned
+fn best_window(
+    schedule: &OperationSchedule,
+    prices: &[Option<f32>],
+    load_kw: &[f32],
+    start: usize,
+    end: usize,
+    config: &ControlConfig,
+) -> Option<ChargeWindow> {
+    let blocks = &schedule.scheduled_blocks;
+    let is_free = |idx: usize| {
+        let block = &blocks[idx];
+        block.mode == config.default_battery_mode
+            && prices[idx].is_some()
+            && !block
+                .decision_uid
+                .as_deref()
+                .is_some_and(|uid| uid.starts_with("user_override"))
+    };
+    // The original run must be priced to be compared at all
+    if (start..end).any(|idx| prices[idx].is_none()) {
+        return None;
+    }
+
+    let mut lo = start;
+    while lo > 0 && is_free(lo - 1) {
+        lo -= 1;
+    }
+    let mut hi = end;
+    while hi < blocks.len() && is_free(hi) {
+        hi += 1;
+    }
+
+    let rate_kw = config.max_battery_charge_rate_kw;
+    let hours = blocks[start].duration_minutes as f32 / 60.0;
+    let energy_kwh = (end - start) as f32 * rate_kw * hours;
+    let import_limit_kw = (config.max_grid_import_kw > 0.0).then_some(config.max_grid_import_kw);
+    let fits_breaker = |idx: usize, power_kw: f32| {
+        import_limit_kw.is_none_or(|limit| power_kw + load_kw[idx] <= limit + f32::EPSILON)
+    };
+
+    let mut best: Option<ChargeWindow> = None;
+    for fraction in CHARGE_FRACTIONS {
+        let power_kw = rate_kw * fraction;
+        let len = (energy_kwh / (power_kw * hours) - 1e-4).ceil() as usize;
+        if len == 0 || len > hi - lo {
+            continue;
+        }
+
+        // Windows cover the whole original run
+        let first = lo.max(end.saturating_sub(len));
+        let last = start.min(hi - len);
+        for window_start in first..=last {
+            let range = window_start..window_start + len;
+            if !range.clone().all(|idx| fits_breaker(idx, power_kw)) {
+                continue;
+            }
+            let avg_price = range.clone().filter_map(|idx| prices[idx]).sum::<f32>() / len as f32;
+            let candidate = ChargeWindow {
+                start: window_start,
+                len,
+                power_kw,
+                cost_czk: avg_price * energy_kwh,
+            };
+            let better = match best {
+                None => true,
+                Some(current) => {
+                    candidate.cost_czk < current.cost_czk - COST_EPSILON
+                        || (import_limit_kw.is_some()
+                            && candidate.power_kw < current.power_kw
+                            && candidate.cost_czk <= current.cost_czk + COST_EPSILON)
+                }
+            };
+            if better {
+                best = Some(candidate);
+            }
+        }
+    }
+
+    let Some(best) = best else {
+        debug!(
+            "Partial charging: no window for run {}..{} fits the {:.1} kW import limit",
+            start, end, config.max_grid_import_kw
+        );
+        return None;
+    };
+    let unchanged = best.start == start && best.len == end - start && best.power_kw >= rate_kw;
+    (!unchanged).then_some(best)
+}
+
+/// Extend a force-charge run over `window` at the window's charge power
+fn rewrite_run(
+    schedule: &mut OperationSchedule,
+    start: usize,
+    end: usize,
+    window: ChargeWindow,
+    config: &ControlConfig,
+) {
+    let rate_kw = config.max_battery_charge_rate_kw;
+    let target_inverters = schedule.scheduled_blocks[start].target_inverters.clone();
+    let window_range = window.start..window.start + window.len;
+    let charge_power_kw = (window.power_kw < rate_kw).then_some(window.power_kw);
+
+    for idx in window_range {
+        let block = &mut schedule.scheduled_blocks[idx];
+        if block.mode == InverterOperationMode::ForceCharge {
+            block.reason = format!("{} (charging at {:.1} kW)", block.reason, window.power_kw);
+        } else {
+            block.mode = InverterOperationMode::ForceCharge;
+            block.reason = format!(
+                "Partial charge at {:.1} kW ({} blocks instead of {})",
+                window.power_kw,
+                window.len,
+                end - start
+            );
+            block.decision_uid = Some("partial_charge:spread".to_string());
+            block.target_inverters = target_inverters.clone();
+        }
+        block.charge_power_kw = charge_power_kw;
+    }
+
+    debug!(
+        "Partial charging: run {}..{} -> {}..{} at {:.1} kW (est. {:.2} CZK)",
+        start,
+        end,
+        window.start,
+        window.start + window.len,
+        window.power_kw,
+        window.cost_czk
+    );
+}
+
+/// Lower the charge power of force-charge runs that would fill the battery
+/// before they end, so the energy still needed is spread across the run
+///
+/// The schedule is replayed from `current_soc` like the schedule guard does;
+/// forecasts are indexed like `time_block_prices` (kWh per block).
+pub fn modulate_charge_power(
+    schedule: &mut OperationSchedule,
+    time_block_prices: &[TimeBlockPrice],
+    current_soc: f32,
+    solar_forecast: Option<&[f32]>,
+    consumption_forecast: Option<&[f32]>,
+    config: &ControlConfig,
+) {
+    let capacity = config.battery_capacity_kwh;
+    let rate_kw = config.max_battery_charge_rate_kw;
+    if capacity <= 0.0 || rate_kw <= 0.0 {
+        return;
+    }
+
+    let price_index: HashMap<DateTime<Utc>, usize> = time_block_prices
+        .iter()
+        .enumerate()
+        .map(|(idx, block)| (block.block_start, idx))
+        .collect();
+    let forecast_kwh = |forecast: Option<&[f32]>, block_start: &DateTime<Utc>| {
+        price_index
+            .get(block_start)
+            .and_then(|&idx| forecast.and_then(|f| f.get(idx).copied()))
+    };
+
+    let to_kwh = |soc: f32| soc / 100.0 * capacity;
+    let floor_kwh = to_kwh(config.min_battery_soc.max(config.hardware_min_battery_soc));
+    let ceiling_kwh = to_kwh(config.max_battery_soc);
+    let mut stored_kwh = to_kwh(current_soc.clamp(0.0, 100.0));
+    let run_ends: HashMap<usize, usize> = force_charge_runs(schedule).into_iter().collect();
+
+    let mut modulated_runs = 0;
+    for idx in 0..schedule.scheduled_blocks.len() {
+        if let Some(&end) = run_ends.get(&idx) {
+            let needed_kwh = ceiling_kwh - stored_kwh;
+            if modulate_run(schedule, idx, end, needed_kwh, config) {
+                modulated_runs += 1;
+            }
+        }
+
+        let block = &schedule.scheduled_blocks[idx];
+        let hours = block.duration_minutes as f32 / 60.0;
+        let solar_kwh = forecast_kwh(solar_forecast, &block.block_start).unwrap_or(0.0);
+        let consumption_kwh = forecast_kwh(consumption_forecast, &block.block_start)
+            .unwrap_or(config.average_household_load_kw * hours);
+        stored_kwh = next_energy(
+            block,
+            stored_kwh,
+            solar_kwh - consumption_kwh,
+            floor_kwh,
+            ceiling_kwh,
+            capacity,
+            config,
+        );
+    }
+
+    if modulated_runs > 0 {
+        debug!(
+            "Charge power modulated for {} force-charge run(s)",
+            modulated_runs
+        );
+    }
+}
+
+/// Spread `needed_kwh` evenly over the run `start..end` if it would otherwise
+/// fill the battery early; returns whether the run was changed
+fn modulate_run(
+    schedule: &mut OperationSchedule,
+    start: usize,
+    end: usize,
+    needed_kwh: f32,
+    config: &ControlConfig,
+) -> bool {
+    let rate_kw = config.max_battery_charge_rate_kw;
+    let run = &schedule.scheduled_blocks[start..end];
+    let run_hours: f32 = run
+        .iter()
+        .map(|block| block.duration_minutes as f32 / 60.0)
+        .sum();
+    let planned_kwh: f32 = run
+        .iter()
+        .map(|block| {
+            block.charge_power_kw.unwrap_or(rate_kw).min(rate_kw) * block.duration_minutes as f32
+                / 60.0
+        })
+        .sum();
+    // A full battery is the schedule guard's job
+    if needed_kwh <= ENERGY_EPSILON_KWH
+        || run_hours <= 0.0
+        || planned_kwh - needed_kwh <= ENERGY_EPSILON_KWH
+    {
+        return false;
+    }
+
+    // Round up to 100 W so the battery still reaches the target
+    let power_kw =
+        ((needed_kwh / run_hours * 10.0).ceil() / 10.0).clamp(MIN_CHARGE_POWER_KW, rate_kw);
+    let mut changed = false;
+    for block in &mut schedule.scheduled_blocks[start..end] {
+        if block.charge_power_kw.unwrap_or(rate_kw) > power_kw {
+            block.charge_power_kw = Some(power_kw);
+            block.reason = format!(
+                "{} (modulated to {:.1} kW for {:.1} kWh)",
+                block.reason, power_kw, needed_kwh
+            );
+            changed = true;
+        }
+    }
+    if changed {
+        debug!(
+            "Charge power modulation: run {}..{} charges {:.2} kWh at {:.1} kW instead of {:.2} kWh",
+            start, end, needed_kwh, power_kw, planned_kwh
+        );
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use fluxion_types::scheduling::ScheduledMode;
+
+    fn control_config(max_grid_import_kw: f32) -> ControlConfig {
+        ControlConfig {
+            max_battery_charge_rate_kw: 10.0,
+            partial_charge_enabled: true,
+            max_grid_import_kw,
+            default_battery_mode: InverterOperationMode::SelfUse,
+            ..ControlConfig::default()
+        }
+    }
+
+    fn schedule(
+        modes: &[InverterOperationMode],
+        block_prices: &[f32],
+    ) -> (OperationSchedule, Vec<TimeBlockPrice>) {
+        let start = Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap();
+        let at = |idx: usize| start + chrono::Duration::minutes(15 * idx as i64);
+        let blocks = modes
+            .iter()
+            .enumerate()
+            .map(|(idx, mode)| ScheduledMode {
+                block_start: at(idx),
+                duration_minutes: 15,
+                target_inverters: None,
+                mode: *mode,
+                reason: "test".to_owned(),
+                decision_reason: None,
+                decision_uid: None,
+                charge_power_kw: None,
+                target_soc: None,
+                forecast: None,
+                debug_info: None,
+            })
+            .collect();
+        let prices = block_prices
+            .iter()
+            .enumerate()
+            .map(|(idx, price)| TimeBlockPrice {
+                block_start: at(idx),
+                duration_minutes: 15,
+                price_czk_per_kwh: *price,
+                effective_price_czk_per_kwh: *price,
+                spot_sell_price_czk_per_kwh: None,
+            })
+            .collect();
+        let schedule = OperationSchedule {
+            scheduled_blocks: blocks,
+            generated_at: start,
+            based_on_price_version: start,
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
+        };
+        (schedule, prices)
+    }
+
+    fn charged_kwh(schedule: &OperationSchedule, rate_kw: f32) -> f32 {
+        schedule
+            .scheduled_blocks
+            .iter()
+            .filter(|b| b.mode == InverterOperationMode::ForceCharge)
+            .map(|b| b.charge_power_kw.unwrap_or(rate_kw) * 0.25)
+            .sum()
+    }
+
+    use InverterOperationMode::{ForceCharge as C, SelfUse as S};
+
+    #[test]
+    fn test_spreads_charge_into_cheaper_neighbours() {
+        // The run's second block is expensive; half power over the cheap
+        // blocks before it buys the same energy for less
+        let (mut schedule, prices) = schedule(&[S, S, C, C, S, S], &[1.0, 1.0, 1.0, 3.0, 5.0, 5.0]);
+
+        apply_partial_charging(&mut schedule, &prices, None, &control_config(0.0));
+
+        let modes: Vec<_> = schedule.scheduled_blocks.iter().map(|b| b.mode).collect();
+        assert_eq!(modes, vec![C, C, C, C, S, S]);
+        assert!(
+            schedule.scheduled_blocks[..4]
+                .iter()
+                .all(|b| b.charge_power_kw == Some(5.0))
+        );
+        assert!((charged_kwh(&schedule, 10.0) - 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_keeps_full_rate_when_not_cheaper() {
+        let (mut schedule, prices) = schedule(&[S, C, C, S], &[2.0, 1.0, 1.0, 2.0]);
+
+        apply_partial_charging(&mut schedule, &prices, None, &control_config(0.0));
+
+        let modes: Vec<_> = schedule.scheduled_blocks.iter().map(|b| b.mode).collect();
+        assert_eq!(modes, vec![S, C, C, S]);
+        assert!(
+            schedule
+                .scheduled_blocks
+                .iter()
+                .all(|b| b.charge_power_kw.is_none())
+        );
+    }
+
+    #[test]
+    fn test_respects_grid_import_limit() {
+        // 10 kW charging plus 1 kW of load would exceed a 7 kW breaker
+        let modes = [S, S, S, C, C, S, S, S, S, S];
+        let (mut schedule, prices) = schedule(&modes, &[2.0; 10]);
+        let consumption = [0.25; 10];
+
+        apply_partial_charging(
+            &mut schedule,
+            &prices,
+            Some(&consumption),
+            &control_config(7.0),
+        );
+
+        for block in &schedule.scheduled_blocks {
+            if block.mode == InverterOperationMode::ForceCharge {
+                let power = block.charge_power_kw.unwrap();
+                assert!(power + 1.0 <= 7.0, "{power} kW exceeds the breaker");
+            }
+        }
+        assert!(charged_kwh(&schedule, 10.0) >= 5.0 - 1e-4);
+        assert!(schedule.scheduled_blocks[3].mode == InverterOperationMode::ForceCharge);
+    }
+
+    #[test]
+    fn test_modulates_run_to_the_energy_needed() {
+        // 8 kWh of headroom (60% -> 100% of 20 kWh) over six 15-minute
+        // blocks planned at 10 kW (15 kWh)
+        let modes = [S, C, C, C, C, C, C, S];
+        let (mut schedule, prices) = schedule(&modes, &[2.0; 8]);
+        let consumption = [0.0; 8];
+        let config = ControlConfig {
+            battery_capacity_kwh: 20.0,
+            max_battery_soc: 100.0,
+            ..control_config(0.0)
+        };
+
+        modulate_charge_power(
+            &mut schedule,
+            &prices,
+            60.0,
+            None,
+            Some(&consumption),
+            &config,
+        );
+
+        // 8 kWh / 1.5 h = 5.33 kW, rounded up to 5.4 kW
+        for block in &schedule.scheduled_blocks[1..7] {
+            assert_eq!(block.charge_power_kw, Some(5.4));
+        }
+        assert!(charged_kwh(&schedule, 10.0) >= 8.0);
+        assert_eq!(schedule.scheduled_blocks[0].charge_power_kw, None);
+    }
+
+    #[test]
+    fn test_keeps_power_when_the_run_is_needed_in_full() {
+        let (mut schedule, prices) = schedule(&[S, C, C, S], &[2.0; 4]);
+        let consumption = [0.0; 4];
+        let config = ControlConfig {
+            battery_capacity_kwh: 20.0,
+            max_battery_soc: 100.0,
+            ..control_config(0.0)
+        };
+
+        // 10 kWh of headroom, the run charges 5 kWh
+        modulate_charge_power(
+            &mut schedule,
+            &prices,
+            50.0,
+            None,
+            Some(&consumption),
+            &config,
+        );
+
+        assert!(
+            schedule
+                .scheduled_blocks
+                .iter()
+                .all(|b| b.charge_power_kw.is_none())
+        );
+    }
+}
@@ -0,0 +1,209 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Planned SOC trajectory of a schedule.
+//!
+//! [`plan_soc_trajectory`] replays the finished schedule from the current SOC
+//! and stores the SOC expected at the end of every block in
+//! [`ScheduledMode::target_soc`]. Unlike the dashboard prediction, which is
+//! recomputed from the live SOC, the trajectory stays as planned until the
+//! schedule is replanned (new prices, SOC drift or a settings change), so the
+//! executor can measure drift against it (see
+//! [`crate::execution::correct_soc_drift`]).
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use fluxion_types::config::ControlConfig;
+use fluxion_types::pricing::TimeBlockPrice;
+use fluxion_types::scheduling::{OperationSchedule, ScheduledMode};
+
+use super::guard::next_energy;
+
+/// Store the planned end-of-block SOC in every block of `schedule`
+///
+/// Forecasts are indexed like `time_block_prices` (kWh per block); without a
+/// consumption forecast the average household load is assumed.
+pub fn plan_soc_trajectory(
+    schedule: &mut OperationSchedule,
+    time_block_prices: &[TimeBlockPrice],
+    current_soc: f32,
+    solar_forecast: Option<&[f32]>,
+    consumption_forecast: Option<&[f32]>,
+    config: &ControlConfig,
+) {
+    let capacity = config.battery_capacity_kwh;
+    if capacity <= 0.0 {
+        return;
+    }
+    schedule.initial_soc = Some(current_soc.clamp(0.0, 100.0));
+
+    let price_index: HashMap<DateTime<Utc>, usize> = time_block_prices
+        .iter()
+        .enumerate()
+        .map(|(idx, block)| (block.block_start, idx))
+        .collect();
+    let forecast_kwh = |forecast: Option<&[f32]>, block: &ScheduledMode| {
+        price_index
+            .get(&block.block_start)
+            .and_then(|&idx| forecast.and_then(|f| f.get(idx).copied()))
+    };
+
+    let to_kwh = |soc: f32| soc / 100.0 * capacity;
+    let floor_kwh = to_kwh(config.min_battery_soc.max(config.hardware_min_battery_soc));
+    let ceiling_kwh = to_kwh(config.max_battery_soc);
+    let mut stored_kwh = to_kwh(current_soc.clamp(0.0, 100.0));
+
+    for block in &mut schedule.scheduled_blocks {
+        let hours = block.duration_minutes as f32 / 60.0;
+        let solar_kwh = forecast_kwh(solar_forecast, block).unwrap_or(0.0);
+        let consumption_kwh = forecast_kwh(consumption_forecast, block)
+            .unwrap_or(config.average_household_load_kw * hours);
+        stored_kwh = next_energy(
+            block,
+            stored_kwh,
+            solar_kwh - consumption_kwh,
+            floor_kwh,
+            ceiling_kwh,
+            capacity,
+            config,
+        );
+        block.target_soc = Some((stored_kwh / capacity * 1000.0).round() / 10.0);
+    }
+}
+
+/// SOC the trajectory plans for `now`, interpolated within the current block
+///
+/// A block starts at the target of the previous one; the first block starts at
+/// the SOC the schedule was planned from, when it was generated. Without a
+/// known start SOC the block's end target is used.
+pub fn planned_soc_at(schedule: &OperationSchedule, now: DateTime<Utc>) -> Option<f32> {
+    let block_end = |block: &ScheduledMode| {
+        block.block_start + chrono::Duration::minutes(block.duration_minutes.into())
+    };
+    let blocks = &schedule.scheduled_blocks;
+    let idx = blocks
+        .iter()
+        .position(|block| now >= block.block_start && now < block_end(block))?;
+    let block = &blocks[idx];
+    let end_time = block_end(block);
+    let end = block.target_soc?;
+    let (start_time, start) = match idx.checked_sub(1) {
+        Some(prev) => (block.block_start, blocks[prev].target_soc),
+        None => (
+            schedule.generated_at.clamp(block.block_start, end_time),
+            schedule.initial_soc,
+        ),
+    };
+    let Some(start) = start else {
+        return Some(end);
+    };
+
+    let span = (end_time - start_time).num_seconds() as f32;
+    if span <= 0.0 {
+        return Some(end);
+    }
+    let fraction = ((now - start_time).num_seconds() as f32 / span).clamp(0.0, 1.0);
+    Some(start + (end - start) * fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use fluxion_types::inverter::InverterOperationMode;
+
+    fn schedule(start: DateTime<Utc>, modes: &[InverterOperationMode]) -> OperationSchedule {
+        OperationSchedule {
+            scheduled_blocks: modes
+                .iter()
+                .enumerate()
+                .map(|(i, &mode)| ScheduledMode {
+                    block_start: start + Duration::minutes(15 * i as i64),
+                    duration_minutes: 15,
+                    target_inverters: None,
+                    mode,
+                    reason: "test".to_owned(),
+                    decision_reason: None,
+                    decision_uid: None,
+                    charge_power_kw: None,
+                    target_soc: None,
+                    forecast: None,
+                    debug_info: None,
+                })
+                .collect(),
+            ..OperationSchedule::default()
+        }
+    }
+
+    #[test]
+    fn test_trajectory_follows_modes_and_interpolates() {
+        let start = DateTime::parse_from_rfc3339("2025-01-15T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut schedule = schedule(
+            start,
+            &[
+                InverterOperationMode::ForceCharge,
+                InverterOperationMode::ForceCharge,
+                InverterOperationMode::NoChargeNoDischarge,
+            ],
+        );
+        let config = ControlConfig {
+            battery_capacity_kwh: 10.0,
+            max_battery_charge_rate_kw: 4.0,
+            ..ControlConfig::default()
+        };
+
+        schedule.generated_at = start;
+        plan_soc_trajectory(&mut schedule, &[], 50.0, None, None, &config);
+        assert_eq!(schedule.initial_soc, Some(50.0));
+
+        let targets: Vec<Option<f32>> = schedule
+            .scheduled_blocks
+            .iter()
+            .map(|b| b.target_soc)
+            .collect();
+        assert_eq!(targets, [Some(60.0), Some(70.0), Some(70.0)]);
+
+        // Halfway through the second block: halfway between 60% and 70%
+        let midway = start + Duration::minutes(22) + Duration::seconds(30);
+        assert_eq!(planned_soc_at(&schedule, midway), Some(65.0));
+        // The first block starts at the SOC the schedule was planned from
+        assert_eq!(planned_soc_at(&schedule, start), Some(50.0));
+        assert_eq!(planned_soc_at(&schedule, start + Duration::hours(1)), None);
+    }
+
+    #[test]
+    fn test_first_block_interpolates_from_generation() {
+        let start = DateTime::parse_from_rfc3339("2025-01-15T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut schedule = schedule(start, &[InverterOperationMode::SelfUse]);
+        schedule.scheduled_blocks[0].target_soc = Some(60.0);
+        // Planned 5 minutes into the block, at 50%
+        schedule.generated_at = start + Duration::minutes(5);
+        schedule.initial_soc = Some(50.0);
+
+        assert_eq!(planned_soc_at(&schedule, start), Some(50.0));
+        assert_eq!(
+            planned_soc_at(&schedule, start + Duration::minutes(10)),
+            Some(55.0)
+        );
+        // Without a start SOC only the end target is known
+        schedule.initial_soc = None;
+        assert_eq!(
+            planned_soc_at(&schedule, start + Duration::minutes(10)),
+            Some(60.0)
+        );
+    }
+}
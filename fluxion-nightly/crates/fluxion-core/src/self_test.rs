@@ -0,0 +1,406 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! End-to-end self-test of the control path for installers.
+//!
+//! Runs the same data sources the scheduler uses, without changing anything:
+//! every inverter is read, its current work mode is written back (a no-op;
+//! simulated in debug mode), the Home Assistant round-trip is timed and the
+//! price data is checked to cover the current block.
+
+use crate::components::InverterCommand;
+use crate::traits::{InverterDataSource, PriceDataSource};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Instant;
+use utoipa::ToSchema;
+
+/// Round-trips slower than this are reported as a warning (ms)
+const SLOW_ROUND_TRIP_MS: u64 = 2000;
+
+/// Outcome of one check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    /// Works, but needs attention
+    Warn,
+    Fail,
+    /// Not run, e.g. the write in debug mode or after a failed read
+    Skipped,
+}
+
+/// One row of the pass/fail matrix
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SelfTestCheck {
+    /// `inverter_read`, `inverter_write`, `ha_round_trip` or `price_freshness`
+    pub name: String,
+    /// Inverter the check ran against, `None` for system-wide checks
+    pub inverter_id: Option<String>,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+/// Result of a self-test run
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SelfTestReport {
+    /// No check failed
+    pub passed: bool,
+    pub debug_mode: bool,
+    pub started_at: DateTime<Utc>,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+/// Self-test over the inverter and price data sources
+pub struct SelfTest {
+    inverter_source: Arc<dyn InverterDataSource>,
+    price_source: Arc<dyn PriceDataSource>,
+    inverter_ids: Vec<String>,
+}
+
+impl std::fmt::Debug for SelfTest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SelfTest")
+            .field("inverter_source", &self.inverter_source.name())
+            .field("price_source", &self.price_source.name())
+            .field("inverter_ids", &self.inverter_ids)
+            .finish()
+    }
+}
+
+impl SelfTest {
+    pub fn new(
+        inverter_source: Arc<dyn InverterDataSource>,
+        price_source: Arc<dyn PriceDataSource>,
+        inverter_ids: Vec<String>,
+    ) -> Self {
+        Self {
+            inverter_source,
+            price_source,
+            inverter_ids,
+        }
+    }
+
+    /// Run all checks; in debug mode the inverter write is only simulated
+    pub async fn run(&self, debug_mode: bool) -> SelfTestReport {
+        let started_at = Utc::now();
+        let mut checks = Vec::new();
+
+        for inverter_id in &self.inverter_ids {
+            self.check_inverter(inverter_id, debug_mode, &mut checks)
+                .await;
+        }
+        checks.push(self.check_round_trip().await);
+        checks.push(self.check_prices(started_at).await);
+
+        SelfTestReport {
+            passed: checks.iter().all(|c| c.status != CheckStatus::Fail),
+            debug_mode,
+            started_at,
+            checks,
+        }
+    }
+
+    async fn check_inverter(
+        &self,
+        inverter_id: &str,
+        debug_mode: bool,
+        checks: &mut Vec<SelfTestCheck>,
+    ) {
+        let check = |name: &str, status, detail: String, started: Instant| SelfTestCheck {
+            name: name.to_owned(),
+            inverter_id: Some(inverter_id.to_owned()),
+            status,
+            detail,
+            duration_ms: elapsed_ms(started),
+        };
+
+        let started = Instant::now();
+        let state = match self.inverter_source.read_state(inverter_id).await {
+            Ok(state) => {
+                checks.push(check(
+                    "inverter_read",
+                    CheckStatus::Pass,
+                    format!(
+                        "SOC {:.0}%, mode {}, grid {:.0} W",
+                        state.battery_soc, state.work_mode, state.grid_power_w
+                    ),
+                    started,
+                ));
+                state
+            }
+            Err(e) => {
+                checks.push(check(
+                    "inverter_read",
+                    CheckStatus::Fail,
+                    format!("{e:#}"),
+                    started,
+                ));
+                checks.push(check(
+                    "inverter_write",
+                    CheckStatus::Skipped,
+                    "Inverter state could not be read".to_owned(),
+                    Instant::now(),
+                ));
+                return;
+            }
+        };
+
+        // Writing the mode the inverter is already in changes nothing
+        let started = Instant::now();
+        let command = InverterCommand::SetMode(state.work_mode);
+        checks.push(if debug_mode {
+            check(
+                "inverter_write",
+                CheckStatus::Skipped,
+                format!("Debug mode: would write {command:?}"),
+                started,
+            )
+        } else {
+            match self
+                .inverter_source
+                .write_command(inverter_id, &command)
+                .await
+            {
+                Ok(()) => check(
+                    "inverter_write",
+                    CheckStatus::Pass,
+                    format!("Wrote {command:?} (unchanged)"),
+                    started,
+                ),
+                Err(e) => check(
+                    "inverter_write",
+                    CheckStatus::Fail,
+                    format!("{e:#}"),
+                    started,
+                ),
+            }
+        });
+    }
+
+    async fn check_round_trip(&self) -> SelfTestCheck {
+        let started = Instant::now();
+        let result = self.inverter_source.health_check().await;
+        let duration_ms = elapsed_ms(started);
+        let (status, detail) = match result {
+            Ok(true) if duration_ms > SLOW_ROUND_TRIP_MS => (
+                CheckStatus::Warn,
+                format!("{} responded slowly", self.inverter_source.name()),
+            ),
+            Ok(true) => (
+                CheckStatus::Pass,
+                format!("{} responded", self.inverter_source.name()),
+            ),
+            Ok(false) => (
+                CheckStatus::Fail,
+                format!("{} reported unhealthy", self.inverter_source.name()),
+            ),
+            Err(e) => (CheckStatus::Fail, format!("{e:#}")),
+        };
+        SelfTestCheck {
+            name: "ha_round_trip".to_owned(),
+            inverter_id: None,
+            status,
+            detail,
+            duration_ms,
+        }
+    }
+
+    async fn check_prices(&self, now: DateTime<Utc>) -> SelfTestCheck {
+        let started = Instant::now();
+        let (status, detail) = match self.price_source.read_prices().await {
+            Ok(prices) => {
+                let covers_now = prices.time_block_prices.iter().any(|b| {
+                    b.block_start <= now
+                        && now < b.block_start + Duration::minutes(b.duration_minutes.into())
+                });
+                let last_end = prices
+                    .time_block_prices
+                    .iter()
+                    .map(|b| b.block_start + Duration::minutes(b.duration_minutes.into()))
+                    .max();
+                match last_end {
+                    Some(end) if covers_now => (
+                        CheckStatus::Pass,
+                        format!(
+                            "{} blocks until {}, updated {}",
+                            prices.time_block_prices.len(),
+                            end.to_rfc3339(),
+                            prices.ha_last_updated.to_rfc3339()
+                        ),
+                    ),
+                    _ => (
+                        CheckStatus::Fail,
+                        format!(
+                            "No price for the current block ({} blocks, updated {})",
+                            prices.time_block_prices.len(),
+                            prices.ha_last_updated.to_rfc3339()
+                        ),
+                    ),
+                }
+            }
+            Err(e) => (CheckStatus::Fail, format!("{e:#}")),
+        };
+        SelfTestCheck {
+            name: "price_freshness".to_owned(),
+            inverter_id: None,
+            status,
+            detail,
+            duration_ms: elapsed_ms(started),
+        }
+    }
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::GenericInverterState;
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use fluxion_types::pricing::{SpotPriceData, TimeBlockPrice};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Default)]
+    struct TestInverter {
+        writes: AtomicU32,
+        down: bool,
+    }
+
+    #[async_trait]
+    impl InverterDataSource for TestInverter {
+        async fn read_state(&self, inverter_id: &str) -> Result<GenericInverterState> {
+            anyhow::ensure!(!self.down, "Home Assistant unreachable");
+            Ok(GenericInverterState {
+                inverter_id: inverter_id.to_owned(),
+                battery_soc: 60.0,
+                ..GenericInverterState::default()
+            })
+        }
+
+        async fn write_command(&self, _: &str, _: &InverterCommand) -> Result<()> {
+            self.writes.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(!self.down)
+        }
+
+        fn name(&self) -> &str {
+            "test"
+        }
+    }
+
+    struct TestPrices {
+        block_start: DateTime<Utc>,
+    }
+
+    #[async_trait]
+    impl PriceDataSource for TestPrices {
+        async fn read_prices(&self) -> Result<SpotPriceData> {
+            Ok(SpotPriceData {
+                time_block_prices: (0..8)
+                    .map(|i| TimeBlockPrice {
+                        block_start: self.block_start + Duration::minutes(15 * i),
+                        duration_minutes: 15,
+                        price_czk_per_kwh: 2.0,
+                        effective_price_czk_per_kwh: 2.0,
+                        spot_sell_price_czk_per_kwh: None,
+                    })
+                    .collect(),
+                ..SpotPriceData::default()
+            })
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &str {
+            "test"
+        }
+    }
+
+    fn status(report: &SelfTestReport, name: &str) -> CheckStatus {
+        report
+            .checks
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| c.status)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_healthy_installation_passes() {
+        let inverter = Arc::new(TestInverter::default());
+        let self_test = SelfTest::new(
+            inverter.clone(),
+            Arc::new(TestPrices {
+                block_start: Utc::now() - Duration::minutes(20),
+            }),
+            vec!["main".to_owned()],
+        );
+
+        let report = self_test.run(false).await;
+
+        assert!(report.passed);
+        assert_eq!(status(&report, "inverter_write"), CheckStatus::Pass);
+        assert_eq!(status(&report, "price_freshness"), CheckStatus::Pass);
+        assert_eq!(inverter.writes.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_debug_mode_simulates_the_write() {
+        let inverter = Arc::new(TestInverter::default());
+        let self_test = SelfTest::new(
+            inverter.clone(),
+            Arc::new(TestPrices {
+                block_start: Utc::now(),
+            }),
+            vec!["main".to_owned()],
+        );
+
+        let report = self_test.run(true).await;
+
+        assert!(report.passed);
+        assert_eq!(status(&report, "inverter_write"), CheckStatus::Skipped);
+        assert_eq!(inverter.writes.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_inverter_and_stale_prices_fail() {
+        let self_test = SelfTest::new(
+            Arc::new(TestInverter {
+                down: true,
+                ..TestInverter::default()
+            }),
+            Arc::new(TestPrices {
+                block_start: Utc::now() - Duration::days(2),
+            }),
+            vec!["main".to_owned()],
+        );
+
+        let report = self_test.run(false).await;
+
+        assert!(!report.passed);
+        assert_eq!(status(&report, "inverter_read"), CheckStatus::Fail);
+        assert_eq!(status(&report, "inverter_write"), CheckStatus::Skipped);
+        assert_eq!(status(&report, "ha_round_trip"), CheckStatus::Fail);
+        assert_eq!(status(&report, "price_freshness"), CheckStatus::Fail);
+    }
+}
@@ -0,0 +1,229 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Cold-start control defaults derived from the detected hardware.
+//!
+//! The shipped [`ControlConfig`] defaults assume a 20 kWh battery charging at
+//! 10 kW. On first run the adapters read what the inverter actually reports
+//! (battery capacity, BMS charge limits, PV output, export limit) into
+//! [`DetectedHardware`], and [`propose_control_defaults`] turns that into a
+//! [`SetupProposal`] the user can accept or modify in the setup wizard.
+
+use fluxion_types::config::ControlConfig;
+use serde::{Deserialize, Serialize};
+
+/// Energy (kWh) the proposed minimum SOC keeps in reserve
+const RESERVE_ENERGY_KWH: f32 = 1.0;
+
+/// Bounds for the proposed minimum SOC (%)
+const MIN_SOC_FLOOR: f32 = 10.0;
+const MIN_SOC_CEILING: f32 = 30.0;
+
+/// Charge rate used when the BMS does not report limits (C-rate)
+const FALLBACK_C_RATE: f32 = 0.5;
+
+/// Hardware values read from the inverter on first run
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DetectedHardware {
+    /// Usable battery capacity (kWh)
+    pub battery_capacity_kwh: Option<f32>,
+    /// Maximum charge power allowed by the BMS (kW), from max current × voltage
+    pub max_charge_power_kw: Option<f32>,
+    /// Highest PV output seen recently (kW), an estimate of the array size
+    pub pv_peak_kw: Option<f32>,
+    /// Export limit currently set on the inverter (W)
+    pub export_limit_w: Option<u32>,
+}
+
+impl DetectedHardware {
+    /// True when nothing could be read
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.battery_capacity_kwh.is_none()
+            && self.max_charge_power_kw.is_none()
+            && self.pv_peak_kw.is_none()
+            && self.export_limit_w.is_none()
+    }
+}
+
+/// A single proposed change to the control configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProposedSetting {
+    /// Field name in the `control` config section
+    pub key: String,
+    /// Value currently configured
+    pub current: f32,
+    /// Value derived from the detected hardware
+    pub proposed: f32,
+    /// Why this value was chosen
+    pub reason: String,
+}
+
+/// Proposed control defaults together with what was detected
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SetupProposal {
+    pub detected: DetectedHardware,
+    pub settings: Vec<ProposedSetting>,
+}
+
+/// Derive control defaults from the detected hardware
+///
+/// Only settings backed by a detected value are proposed; values equal to
+/// the current configuration are still listed so the wizard can show them.
+#[must_use]
+pub fn propose_control_defaults(
+    detected: &DetectedHardware,
+    current: &ControlConfig,
+) -> SetupProposal {
+    let mut settings = Vec::new();
+    let capacity = detected
+        .battery_capacity_kwh
+        .filter(|kwh| *kwh > 0.0)
+        .map(|kwh| round_to(kwh, 0.1));
+
+    if let Some(capacity) = capacity {
+        settings.push(ProposedSetting {
+            key: "battery_capacity_kwh".to_owned(),
+            current: current.battery_capacity_kwh,
+            proposed: capacity,
+            reason: "Reported by the battery".to_owned(),
+        });
+    }
+
+    let charge_rate = match (
+        detected.max_charge_power_kw.filter(|kw| *kw > 0.0),
+        capacity,
+    ) {
+        (Some(kw), _) => Some((
+            round_to(kw, 0.1),
+            "BMS maximum charge current × battery voltage".to_owned(),
+        )),
+        (None, Some(capacity)) => Some((
+            round_to(capacity * FALLBACK_C_RATE, 0.1),
+            format!("BMS limit not reported; {FALLBACK_C_RATE}C of the battery capacity"),
+        )),
+        (None, None) => None,
+    };
+    if let Some((kw, reason)) = charge_rate {
+        settings.push(ProposedSetting {
+            key: "max_battery_charge_rate_kw".to_owned(),
+            current: current.max_battery_charge_rate_kw,
+            proposed: kw,
+            reason,
+        });
+    }
+
+    if let Some(capacity) = capacity {
+        // Keep roughly RESERVE_ENERGY_KWH in the battery, in 5% steps
+        let reserve_pct = (RESERVE_ENERGY_KWH / capacity * 100.0 / 5.0).ceil() * 5.0;
+        let min_soc = reserve_pct
+            .clamp(MIN_SOC_FLOOR, MIN_SOC_CEILING)
+            .max(current.hardware_min_battery_soc);
+        settings.push(ProposedSetting {
+            key: "min_battery_soc".to_owned(),
+            current: current.min_battery_soc,
+            proposed: min_soc,
+            reason: format!(
+                "Keeps about {RESERVE_ENERGY_KWH} kWh in reserve, not below the hardware minimum"
+            ),
+        });
+    }
+
+    let export_cap = match (
+        detected.export_limit_w.filter(|w| *w > 0),
+        detected.pv_peak_kw.filter(|kw| *kw > 0.0),
+    ) {
+        (Some(watts), _) => Some((watts, "Export limit set on the inverter".to_owned())),
+        (None, Some(kw)) => Some((
+            (kw * 10.0).round() as u32 * 100,
+            "No inverter export limit found; using the observed PV peak".to_owned(),
+        )),
+        (None, None) => None,
+    };
+    if let Some((watts, reason)) = export_cap {
+        settings.push(ProposedSetting {
+            key: "maximum_export_power_w".to_owned(),
+            current: current.maximum_export_power_w as f32,
+            proposed: watts as f32,
+            reason,
+        });
+    }
+
+    SetupProposal {
+        detected: detected.clone(),
+        settings,
+    }
+}
+
+fn round_to(value: f32, step: f32) -> f32 {
+    (value / step).round() * step
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proposed(proposal: &SetupProposal, key: &str) -> Option<f32> {
+        proposal
+            .settings
+            .iter()
+            .find(|s| s.key == key)
+            .map(|s| s.proposed)
+    }
+
+    #[test]
+    fn test_small_battery_proposal() {
+        let detected = DetectedHardware {
+            battery_capacity_kwh: Some(5.8),
+            max_charge_power_kw: Some(2.9),
+            pv_peak_kw: Some(4.2),
+            export_limit_w: Some(3000),
+        };
+
+        let proposal = propose_control_defaults(&detected, &ControlConfig::default());
+
+        assert!((proposed(&proposal, "battery_capacity_kwh").unwrap() - 5.8).abs() < 1e-3);
+        assert!((proposed(&proposal, "max_battery_charge_rate_kw").unwrap() - 2.9).abs() < 1e-3);
+        // 1 kWh of 5.8 kWh is 17.2% → 20%
+        assert_eq!(proposed(&proposal, "min_battery_soc"), Some(20.0));
+        assert_eq!(proposed(&proposal, "maximum_export_power_w"), Some(3000.0));
+    }
+
+    #[test]
+    fn test_fallbacks_without_bms_or_export_limit() {
+        let detected = DetectedHardware {
+            battery_capacity_kwh: Some(15.0),
+            pv_peak_kw: Some(9.87),
+            ..DetectedHardware::default()
+        };
+        let current = ControlConfig {
+            hardware_min_battery_soc: 15.0,
+            ..ControlConfig::default()
+        };
+
+        let proposal = propose_control_defaults(&detected, &current);
+
+        assert!((proposed(&proposal, "max_battery_charge_rate_kw").unwrap() - 7.5).abs() < 1e-3);
+        // Reserve would be 10%, but the inverter floor is 15%
+        assert_eq!(proposed(&proposal, "min_battery_soc"), Some(15.0));
+        assert_eq!(proposed(&proposal, "maximum_export_power_w"), Some(9900.0));
+    }
+
+    #[test]
+    fn test_nothing_detected_proposes_nothing() {
+        let proposal =
+            propose_control_defaults(&DetectedHardware::default(), &ControlConfig::default());
+
+        assert!(proposal.detected.is_empty());
+        assert!(proposal.settings.is_empty());
+    }
+}
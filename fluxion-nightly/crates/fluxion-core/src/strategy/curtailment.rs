@@ -0,0 +1,262 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! PV curtailment from inverter AC limiting and grid export caps.
+//!
+//! Strategies compute energy flows as if every kWh of PV surplus could leave
+//! the inverter. In practice the inverter clips its AC output at rated power
+//! and the grid connection caps export, so on sunny days part of the surplus
+//! is lost. [`CurtailmentLimits::apply`] enforces both limits on a block's
+//! [`EnergyFlows`] and reports what was cut.
+//!
+//! When a limit is hit, battery discharge is reduced first (that energy stays
+//! in the battery), and only then is PV curtailed.
+
+use fluxion_types::config::ControlConfig;
+
+use super::EnergyFlows;
+
+/// Per-block energy limits of the inverter and grid connection
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CurtailmentLimits {
+    /// Maximum energy the inverter can deliver on the AC side in the block (kWh)
+    pub ac_output_kwh: Option<f32>,
+
+    /// Maximum energy that may be exported to the grid in the block (kWh)
+    pub export_kwh: Option<f32>,
+}
+
+/// What a block lost to the limits
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CurtailmentOutcome {
+    /// PV energy that could not be used or exported (kWh)
+    pub curtailed_solar_kwh: f32,
+
+    /// Planned battery discharge that was held back (kWh)
+    pub limited_discharge_kwh: f32,
+
+    /// Reduction of grid export compared to the unconstrained flows (kWh)
+    pub lost_export_kwh: f32,
+}
+
+impl CurtailmentOutcome {
+    /// True when neither limit was reached
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.curtailed_solar_kwh <= 0.0 && self.limited_discharge_kwh <= 0.0
+    }
+}
+
+impl CurtailmentLimits {
+    /// Build limits from the control configuration for a block of `duration_minutes`
+    ///
+    /// A power of 0 W means the limit is not configured.
+    #[must_use]
+    pub fn from_control_config(config: &ControlConfig, duration_minutes: u32) -> Self {
+        Self::from_kw(
+            watts_to_kw(config.inverter_max_ac_power_w),
+            watts_to_kw(config.maximum_export_power_w),
+            duration_minutes,
+        )
+    }
+
+    /// Build limits from power ratings in kW for a block of `duration_minutes`
+    #[must_use]
+    pub fn from_kw(
+        ac_output_kw: Option<f32>,
+        export_kw: Option<f32>,
+        duration_minutes: u32,
+    ) -> Self {
+        let hours = duration_minutes as f32 / 60.0;
+        Self {
+            ac_output_kwh: ac_output_kw.map(|kw| kw * hours),
+            export_kwh: export_kw.map(|kw| kw * hours),
+        }
+    }
+
+    /// Tighten the export limit to `cap_w` watts (0 W means no export at all)
+    #[must_use]
+    pub fn with_export_cap_w(mut self, cap_w: u32, duration_minutes: u32) -> Self {
+        let cap_kwh = cap_w as f32 / 1000.0 * duration_minutes as f32 / 60.0;
+        self.export_kwh = Some(self.export_kwh.map_or(cap_kwh, |kwh| kwh.min(cap_kwh)));
+        self
+    }
+
+    /// True when no limit is configured
+    #[must_use]
+    pub fn is_unlimited(&self) -> bool {
+        self.ac_output_kwh.is_none() && self.export_kwh.is_none()
+    }
+
+    /// Enforce the limits on `flows` in place
+    ///
+    /// `flows.solar_generation_kwh` stays the available PV; the clipped part is
+    /// added to `flows.curtailed_solar_kwh`.
+    pub fn apply(&self, flows: &mut EnergyFlows) -> CurtailmentOutcome {
+        let export_before = flows.grid_export_kwh;
+        let mut outcome = CurtailmentOutcome::default();
+
+        if let Some(limit) = self.ac_output_kwh {
+            // PV charging the battery on the DC side bypasses the AC stage
+            let ac_output = (flows.solar_generation_kwh + flows.battery_discharge_kwh
+                - flows.battery_charge_kwh)
+                .max(0.0);
+            let mut excess = ac_output - limit.max(0.0);
+
+            if excess > 0.0 {
+                excess -= hold_back_discharge(flows, excess, &mut outcome);
+            }
+            if excess > 0.0 {
+                // Clipped PV no longer reaches the grid, then no longer covers the house
+                let from_export = excess.min(flows.grid_export_kwh);
+                flows.grid_export_kwh -= from_export;
+                flows.grid_import_kwh += excess - from_export;
+                outcome.curtailed_solar_kwh += excess;
+            }
+        }
+
+        if let Some(limit) = self.export_kwh {
+            let mut over = flows.grid_export_kwh - limit.max(0.0);
+
+            if over > 0.0 {
+                over -= hold_back_discharge(flows, over, &mut outcome);
+            }
+            if over > 0.0 {
+                flows.grid_export_kwh -= over;
+                outcome.curtailed_solar_kwh += over;
+            }
+        }
+
+        flows.curtailed_solar_kwh += outcome.curtailed_solar_kwh;
+        outcome.lost_export_kwh = (export_before - flows.grid_export_kwh).max(0.0);
+        outcome
+    }
+}
+
+/// Reduce battery discharge by up to `excess` kWh, taking it out of export
+/// first and then out of what the battery was covering for the house.
+/// Returns the amount held back.
+fn hold_back_discharge(
+    flows: &mut EnergyFlows,
+    excess: f32,
+    outcome: &mut CurtailmentOutcome,
+) -> f32 {
+    let held = excess.min(flows.battery_discharge_kwh);
+    if held <= 0.0 {
+        return 0.0;
+    }
+    flows.battery_discharge_kwh -= held;
+    let from_export = held.min(flows.grid_export_kwh);
+    flows.grid_export_kwh -= from_export;
+    flows.grid_import_kwh += held - from_export;
+    outcome.limited_discharge_kwh += held;
+    held
+}
+
+fn watts_to_kw(watts: u32) -> Option<f32> {
+    (watts > 0).then(|| watts as f32 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flows(solar: f32, load: f32, charge: f32, discharge: f32) -> EnergyFlows {
+        let net = load + charge - solar - discharge;
+        EnergyFlows {
+            grid_import_kwh: net.max(0.0),
+            grid_export_kwh: (-net).max(0.0),
+            battery_charge_kwh: charge,
+            battery_discharge_kwh: discharge,
+            solar_generation_kwh: solar,
+            household_consumption_kwh: load,
+            curtailed_solar_kwh: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_export_cap_curtails_pv_surplus() {
+        // 2 kWh PV, 0.2 kWh load, battery full: 1.8 kWh surplus, cap 1.0 kWh
+        let limits = CurtailmentLimits::from_kw(None, Some(4.0), 15);
+        let mut f = flows(2.0, 0.2, 0.0, 0.0);
+
+        let outcome = limits.apply(&mut f);
+
+        assert!((f.grid_export_kwh - 1.0).abs() < 1e-4);
+        assert!((outcome.curtailed_solar_kwh - 0.8).abs() < 1e-4);
+        assert!((outcome.lost_export_kwh - 0.8).abs() < 1e-4);
+        assert!((f.curtailed_solar_kwh - 0.8).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_export_cap_holds_back_discharge_before_curtailing() {
+        // Force discharge 1.5 kWh with 0.5 kWh PV surplus, cap 1.0 kWh
+        let limits = CurtailmentLimits::from_kw(None, Some(4.0), 15);
+        let mut f = flows(0.7, 0.2, 0.0, 1.5);
+
+        let outcome = limits.apply(&mut f);
+
+        assert!((f.grid_export_kwh - 1.0).abs() < 1e-4);
+        assert!((outcome.limited_discharge_kwh - 1.0).abs() < 1e-4);
+        assert!(outcome.curtailed_solar_kwh.abs() < 1e-4);
+        assert!((f.battery_discharge_kwh - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_zero_export_cap_window_blocks_all_export() {
+        // Force discharge 1.0 kWh with 0.5 kWh PV surplus during a 0 W window
+        let limits = CurtailmentLimits::from_kw(None, Some(4.0), 15).with_export_cap_w(0, 15);
+        let mut f = flows(0.7, 0.2, 0.0, 1.0);
+
+        let outcome = limits.apply(&mut f);
+
+        assert!(f.grid_export_kwh.abs() < 1e-4);
+        assert!(f.battery_discharge_kwh.abs() < 1e-4);
+        assert!((outcome.curtailed_solar_kwh - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ac_limit_ignores_dc_battery_charging() {
+        // 3 kWh PV, 2 kWh into battery on DC side, AC limit 1.25 kWh (5 kW)
+        let limits = CurtailmentLimits::from_kw(Some(5.0), None, 15);
+        let mut f = flows(3.0, 0.5, 2.0, 0.0);
+
+        let outcome = limits.apply(&mut f);
+
+        assert!(outcome.is_empty());
+        assert!((f.grid_export_kwh - 0.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ac_limit_clips_pv() {
+        // 3 kWh PV with a full battery, AC limit 1.25 kWh (5 kW)
+        let limits = CurtailmentLimits::from_kw(Some(5.0), Some(10.0), 15);
+        let mut f = flows(3.0, 0.5, 0.0, 0.0);
+
+        let outcome = limits.apply(&mut f);
+
+        assert!((outcome.curtailed_solar_kwh - 1.75).abs() < 1e-4);
+        assert!((f.grid_export_kwh - 0.75).abs() < 1e-4);
+        assert!(f.grid_import_kwh.abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_zero_watts_means_unlimited() {
+        let config = ControlConfig {
+            maximum_export_power_w: 0,
+            inverter_max_ac_power_w: 0,
+            ..ControlConfig::default()
+        };
+
+        assert!(CurtailmentLimits::from_control_config(&config, 15).is_unlimited());
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Battery degradation cost of a block.
+//!
+//! Lithium cells lose capacity in two ways:
+//!
+//! - **cycle aging**: cycle life falls roughly as `depth^-k`, so a kWh cycled
+//!   deep costs more than a kWh cycled shallow. The flat
+//!   `battery_wear_cost_czk_per_kwh` is taken as the cost at the reference
+//!   depth; a kWh at depth `d` costs `(d / reference)^(k - 1)` times that.
+//! - **calendar aging**: a fixed cost per day that grows while the battery
+//!   sits at high SOC.
+//!
+//! Both double for every `temperature_doubling_c` above the reference
+//! temperature. [`DegradationModel::block_cost`] prices one block's flows;
+//! the plugin adapter subtracts it from the block's expected profit.
+
+use fluxion_types::config::{BatteryDegradationConfig, ControlConfig};
+
+use super::EnergyFlows;
+use crate::traits::GenericInverterState;
+
+/// Depth of discharge floor, so near-full blocks still carry some wear (%)
+const MIN_DEPTH_PERCENT: f32 = 1.0;
+
+/// Degradation cost model built from the control configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct DegradationModel {
+    config: BatteryDegradationConfig,
+    wear_cost_czk_per_kwh: f32,
+    efficiency: f32,
+    temperature_c: Option<f32>,
+}
+
+impl DegradationModel {
+    /// Model for the configured battery at `temperature_c` (reference temperature if `None`)
+    #[must_use]
+    pub fn from_control_config(config: &ControlConfig, temperature_c: Option<f32>) -> Self {
+        Self {
+            config: config.battery_degradation.clone(),
+            wear_cost_czk_per_kwh: config.battery_wear_cost_czk_per_kwh,
+            efficiency: config.battery_efficiency,
+            temperature_c,
+        }
+    }
+
+    /// Whether the model adds any cost
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Wear multiplier for the cell temperature (1.0 at or below the reference)
+    #[must_use]
+    pub fn temperature_factor(&self) -> f32 {
+        let Some(temperature) = self.temperature_c else {
+            return 1.0;
+        };
+        let above = temperature - self.config.reference_temperature_c;
+        if above <= 0.0 || self.config.temperature_doubling_c <= 0.0 {
+            return 1.0;
+        }
+        2f32.powf(above / self.config.temperature_doubling_c)
+    }
+
+    /// Wear cost of one kWh cycled at `depth_percent` depth of discharge (CZK/kWh)
+    #[must_use]
+    pub fn cycle_cost_per_kwh(&self, depth_percent: f32) -> f32 {
+        let reference = self.config.reference_depth_percent.max(MIN_DEPTH_PERCENT);
+        let depth = depth_percent.clamp(MIN_DEPTH_PERCENT, 100.0);
+        let depth_factor = (depth / reference).powf(self.config.depth_exponent - 1.0);
+        self.wear_cost_czk_per_kwh * depth_factor * self.temperature_factor()
+    }
+
+    /// Calendar aging over `duration_minutes` at `soc_percent` (CZK)
+    #[must_use]
+    pub fn calendar_cost(&self, soc_percent: f32, duration_minutes: u32) -> f32 {
+        let soc_factor = if soc_percent > self.config.high_soc_threshold_percent {
+            self.config.high_soc_calendar_multiplier
+        } else {
+            1.0
+        };
+        self.config.calendar_aging_czk_per_day
+            * (duration_minutes as f32 / 1440.0)
+            * soc_factor
+            * self.temperature_factor()
+    }
+
+    /// Degradation cost of a block's flows starting at `soc_percent` (CZK)
+    ///
+    /// The cycle depth is measured from full to the lowest SOC the block
+    /// reaches. Returns 0 when the model is disabled.
+    #[must_use]
+    pub fn block_cost(
+        &self,
+        flows: &EnergyFlows,
+        soc_percent: f32,
+        capacity_kwh: f32,
+        duration_minutes: u32,
+    ) -> f32 {
+        if !self.config.enabled {
+            return 0.0;
+        }
+
+        let soc_after = if capacity_kwh > 0.0 {
+            let stored_kwh =
+                flows.battery_charge_kwh * self.efficiency - flows.battery_discharge_kwh;
+            (soc_percent + stored_kwh / capacity_kwh * 100.0).clamp(0.0, 100.0)
+        } else {
+            soc_percent
+        };
+
+        let throughput_kwh = flows.battery_charge_kwh + flows.battery_discharge_kwh;
+        let depth_percent = 100.0 - soc_percent.min(soc_after);
+        let cycle_cost = throughput_kwh * self.cycle_cost_per_kwh(depth_percent);
+
+        cycle_cost + self.calendar_cost((soc_percent + soc_after) / 2.0, duration_minutes)
+    }
+}
+
+/// Control config carrying the hottest reported battery temperature for planning
+#[must_use]
+pub fn with_battery_temperature<'a>(
+    mut control: ControlConfig,
+    states: impl IntoIterator<Item = &'a GenericInverterState>,
+) -> ControlConfig {
+    control.battery_degradation.battery_temperature_c = states
+        .into_iter()
+        .filter_map(|state| state.battery_temperature_c)
+        .reduce(f32::max);
+    control
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(temperature_c: Option<f32>) -> DegradationModel {
+        let config = ControlConfig {
+            battery_wear_cost_czk_per_kwh: 0.2,
+            battery_efficiency: 1.0,
+            battery_degradation: BatteryDegradationConfig {
+                enabled: true,
+                ..BatteryDegradationConfig::default()
+            },
+            ..ControlConfig::default()
+        };
+        DegradationModel::from_control_config(&config, temperature_c)
+    }
+
+    fn discharge(kwh: f32) -> EnergyFlows {
+        EnergyFlows {
+            battery_discharge_kwh: kwh,
+            ..EnergyFlows::default()
+        }
+    }
+
+    #[test]
+    fn test_reference_depth_costs_flat_wear() {
+        let model = model(None);
+
+        assert!((model.cycle_cost_per_kwh(80.0) - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_deep_cycles_cost_more_than_shallow() {
+        let model = model(None);
+
+        // 1 kWh of a 10 kWh battery: 90% -> 80% vs 30% -> 20%
+        let shallow = model.block_cost(&discharge(1.0), 90.0, 10.0, 15);
+        let deep = model.block_cost(&discharge(1.0), 30.0, 10.0, 15);
+
+        assert!(deep > shallow);
+        assert!((deep - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_heat_doubles_wear() {
+        let cool = model(Some(20.0)).block_cost(&discharge(1.0), 30.0, 10.0, 15);
+        let hot = model(Some(35.0)).block_cost(&discharge(1.0), 30.0, 10.0, 15);
+
+        assert!((cool - 0.2).abs() < 1e-5);
+        assert!((hot - 0.4).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_calendar_aging_grows_at_high_soc() {
+        let config = ControlConfig {
+            battery_degradation: BatteryDegradationConfig {
+                enabled: true,
+                calendar_aging_czk_per_day: 9.6,
+                ..BatteryDegradationConfig::default()
+            },
+            ..ControlConfig::default()
+        };
+        let model = DegradationModel::from_control_config(&config, None);
+
+        let idle = EnergyFlows::default();
+        assert!((model.block_cost(&idle, 50.0, 10.0, 15) - 0.1).abs() < 1e-5);
+        assert!((model.block_cost(&idle, 95.0, 10.0, 15) - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_disabled_model_costs_nothing() {
+        let model = DegradationModel::from_control_config(&ControlConfig::default(), Some(40.0));
+
+        assert_eq!(model.block_cost(&discharge(2.0), 20.0, 10.0, 15), 0.0);
+    }
+
+    #[test]
+    fn test_hottest_inverter_temperature_is_used() {
+        let states = [
+            GenericInverterState {
+                battery_temperature_c: Some(22.0),
+                ..GenericInverterState::default()
+            },
+            GenericInverterState {
+                battery_temperature_c: Some(31.0),
+                ..GenericInverterState::default()
+            },
+            GenericInverterState::default(),
+        ];
+
+        let control = with_battery_temperature(ControlConfig::default(), &states);
+
+        assert_eq!(
+            control.battery_degradation.battery_temperature_c,
+            Some(31.0)
+        );
+    }
+}
@@ -20,6 +20,7 @@ use crate::strategy::{
     Assumptions, BlockEvaluation, EconomicStrategy, EnergyFlows, EvaluationContext,
 };
 use chrono::Timelike;
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::inverter::InverterOperationMode;
 use serde::{Deserialize, Serialize};
 
@@ -62,6 +63,35 @@ impl EconomicStrategy for FixedPriceArbitrageStrategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "For fixed-price contracts that sell at spot: charges at the fixed buy price and discharges to the grid when spot sell prices spike.".to_owned(),
+            inputs: [
+                "Effective buy prices (fixed price + HDO grid fee)",
+                "Spot sell prices for the planning horizon",
+                "Current battery SOC",
+                "Hourly consumption profile from the last 7 days",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Find blocks where the spot sell price exceeds the cheapest buy price by the profit threshold",
+                "Charge in the cheapest remaining blocks, preferably before the first spike",
+                "Force discharge to the grid during the spike blocks",
+                "Otherwise self-use",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "min_profit_threshold_czk",
+                    self.config.min_profit_threshold_czk,
+                    "Minimum sell - buy spread to trigger arbitrage (CZK/kWh)",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let block_start = context.price_block.block_start;
         let duration_minutes = context.price_block.duration_minutes;
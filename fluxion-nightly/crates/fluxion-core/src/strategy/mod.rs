@@ -10,10 +10,13 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
+pub mod curtailment;
+pub mod degradation;
 pub mod fixed_price_arbitrage;
 pub mod locking;
 pub mod pricing;
 pub mod seasonal;
+pub mod sub_block;
 pub mod utils;
 pub mod winter_adaptive;
 pub mod winter_adaptive_v10;
@@ -26,6 +29,12 @@ pub mod winter_adaptive_v7;
 pub mod winter_adaptive_v8;
 pub mod winter_adaptive_v9;
 
+// Re-export curtailment modeling
+pub use curtailment::{CurtailmentLimits, CurtailmentOutcome};
+
+// Re-export battery degradation modeling
+pub use degradation::{DegradationModel, with_battery_temperature};
+
 // Re-export shared locking utilities
 pub use locking::{LockedBlock, ScheduleLockState};
 
@@ -36,6 +45,7 @@ pub use pricing::{
 
 // Re-export shared seasonal utilities
 pub use seasonal::{DayEnergyBalance, SeasonalMode};
+pub use sub_block::{PowerProfile, SubBlockBattery, SubBlockOutcome, simulate_self_use};
 
 // Re-export strategies
 // Note: DayEnergyBalance is re-exported from seasonal module above
@@ -52,6 +62,7 @@ pub use winter_adaptive_v10::{WinterAdaptiveV10Config, WinterAdaptiveV10Strategy
 pub use winter_adaptive_v20::{WinterAdaptiveV20Config, WinterAdaptiveV20Strategy};
 
 use chrono::{DateTime, Utc};
+use fluxion_plugins::PluginDocs;
 use fluxion_types::config::ControlConfig;
 use fluxion_types::inverter::InverterOperationMode;
 use fluxion_types::pricing::TimeBlockPrice;
@@ -78,6 +89,10 @@ pub struct EnergyFlows {
 
     /// Household consumption (kWh)
     pub household_consumption_kwh: f32,
+
+    /// Solar energy lost to inverter AC limiting or the export cap (kWh)
+    #[serde(default)]
+    pub curtailed_solar_kwh: f32,
 }
 
 impl Default for EnergyFlows {
@@ -89,6 +104,7 @@ impl Default for EnergyFlows {
             battery_discharge_kwh: 0.0,
             solar_generation_kwh: 0.0,
             household_consumption_kwh: 0.0,
+            curtailed_solar_kwh: 0.0,
         }
     }
 }
@@ -162,6 +178,9 @@ pub struct BlockEvaluation {
     /// Human-readable reason for this decision
     pub reason: String,
 
+    /// Structured reason for this decision, None when only `reason` is set
+    pub decision_reason: Option<fluxion_types::DecisionReason>,
+
     /// Name of the strategy that generated this evaluation
     pub strategy_name: String,
 
@@ -191,6 +210,7 @@ impl BlockEvaluation {
             energy_flows: EnergyFlows::default(),
             assumptions: Assumptions::default(),
             reason: String::new(),
+            decision_reason: None,
             strategy_name,
             decision_uid: None,
             debug_info: None,
@@ -292,6 +312,11 @@ pub trait EconomicStrategy: Send + Sync {
     fn is_enabled(&self) -> bool {
         true // By default, strategies are enabled
     }
+
+    /// Describe the strategy's inputs, decision logic and current parameters
+    fn describe(&self) -> PluginDocs {
+        PluginDocs::default()
+    }
 }
 
 /// Helper functions for economic calculations
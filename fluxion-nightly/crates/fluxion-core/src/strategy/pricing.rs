@@ -27,7 +27,8 @@
 //! The effective price for grid import is: `spot_price + grid_fee`
 //! where grid_fee depends on whether the current time is in HDO low or high tariff.
 
-use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc, Weekday};
+use fluxion_types::tariff::TariffWindow;
 use std::collections::HashMap;
 use std::sync::RwLock;
 
@@ -268,6 +269,102 @@ pub fn parse_hdo_sensor_data(raw_data: &str) -> Vec<HdoDaySchedule> {
     schedules
 }
 
+/// Parse weekly HDO tables from raw JSON sensor data
+///
+/// EG.D publishes the low tariff times of an HDO code per range of weekdays
+/// rather than per date:
+/// ```json
+/// {
+///   "sazby": [{
+///     "dny": [{ "od": 1, "do": 5, "casy": [{ "od": "00:00", "do": "06:00" }] }]
+///   }]
+/// }
+/// ```
+/// Days are numbered 1 (Monday) to 7 (Sunday). The `sazby` array is looked up
+/// at the top level, under `data` and under `attributes`.
+pub fn parse_hdo_weekly_tables(raw_data: &str) -> Vec<TariffWindow> {
+    let Ok(json) = serde_json::from_str::<serde_json::Value>(raw_data) else {
+        return Vec::new();
+    };
+
+    let tables = [
+        json.get("sazby"),
+        json.get("data").and_then(|d| d.get("sazby")),
+        json.get("attributes").and_then(|a| a.get("sazby")),
+        json.get("attributes")
+            .and_then(|a| a.get("data"))
+            .and_then(|d| d.get("sazby")),
+    ]
+    .into_iter()
+    .flatten()
+    .find_map(|s| s.as_array());
+    let Some(tables) = tables else {
+        return Vec::new();
+    };
+
+    let weekday = |value: Option<&serde_json::Value>| {
+        value
+            .and_then(serde_json::Value::as_u64)
+            .filter(|day| (1..=7).contains(day))
+            .and_then(|day| u8::try_from(day - 1).ok())
+            .and_then(|day| Weekday::try_from(day).ok())
+    };
+
+    let mut windows = Vec::new();
+    for days in tables
+        .iter()
+        .filter_map(|table| table.get("dny").and_then(|d| d.as_array()))
+        .flatten()
+    {
+        let (Some(from), Some(to)) = (weekday(days.get("od")), weekday(days.get("do"))) else {
+            continue;
+        };
+        let mut weekdays = vec![from];
+        let mut day = from;
+        while day != to {
+            day = day.succ();
+            weekdays.push(day);
+        }
+
+        for times in days
+            .get("casy")
+            .and_then(|c| c.as_array())
+            .into_iter()
+            .flatten()
+        {
+            let (Some(start), Some(end)) = (
+                times
+                    .get("od")
+                    .and_then(|t| t.as_str())
+                    .and_then(parse_time),
+                times
+                    .get("do")
+                    .and_then(|t| t.as_str())
+                    .and_then(parse_time),
+            ) else {
+                continue;
+            };
+            windows.push(TariffWindow {
+                days: weekdays.clone(),
+                start,
+                end: end_of_day_as_midnight(end),
+            });
+        }
+    }
+
+    tracing::debug!("Parsed {} weekly HDO windows", windows.len());
+    windows
+}
+
+/// Map the 23:59:59 that "24:00" parses to back to midnight
+pub fn end_of_day_as_midnight(end: NaiveTime) -> NaiveTime {
+    if end == NaiveTime::from_hms_opt(23, 59, 59).unwrap_or(NaiveTime::MIN) {
+        NaiveTime::MIN
+    } else {
+        end
+    }
+}
+
 // ============================================================================
 // Effective Price Calculation
 // ============================================================================
@@ -380,6 +477,37 @@ mod tests {
         assert_eq!(cache.is_low_tariff(dt4), None);
     }
 
+    #[test]
+    fn test_parse_hdo_weekly_tables() {
+        let json_data = r#"{
+            "attributes": {
+                "sazby": [{
+                    "dny": [
+                        { "od": 1, "do": 5, "casy": [{ "od": "00:00", "do": "06:00" }, { "od": "20:00", "do": "24:00" }] },
+                        { "od": 6, "do": 7, "casy": [{ "od": "00:00", "do": "08:00" }] }
+                    ]
+                }]
+            }
+        }"#;
+
+        let windows = parse_hdo_weekly_tables(json_data);
+        assert_eq!(windows.len(), 3);
+        assert_eq!(
+            windows[0].days,
+            vec![
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri
+            ]
+        );
+        assert_eq!(windows[1].end, NaiveTime::MIN);
+        assert!(windows[1].wraps_midnight());
+        assert_eq!(windows[2].days, vec![Weekday::Sat, Weekday::Sun]);
+        assert!(parse_hdo_weekly_tables(r#"{"data": {"signals": []}}"#).is_empty());
+    }
+
     #[test]
     fn test_parse_hdo_sensor_data() {
         let json_data = r#"{
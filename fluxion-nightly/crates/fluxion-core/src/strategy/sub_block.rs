@@ -0,0 +1,265 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Self-use energy flows from power profiles within a block.
+//!
+//! Netting block-average solar against block-average load hides what happens
+//! inside the block: a 5-minute solar peak above the charge rate is exported
+//! (or curtailed) instead of stored, and a kettle spike above the discharge
+//! rate is imported even though the block average looks covered. Stepping
+//! through a [`PowerProfile`] applies the battery and export limits at the
+//! resolution the inverter actually sees.
+
+use serde::{Deserialize, Serialize};
+
+use super::EnergyFlows;
+
+/// Solar and load power for equal steps covering one block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PowerProfile {
+    /// PV power per step (kW)
+    pub solar_kw: Vec<f32>,
+    /// Household load per step (kW)
+    pub load_kw: Vec<f32>,
+}
+
+impl PowerProfile {
+    /// A constant profile with the given block energies
+    #[must_use]
+    pub fn flat(solar_kwh: f32, load_kwh: f32, duration_minutes: u32, steps: usize) -> Self {
+        let steps = steps.max(1);
+        let hours = duration_minutes as f32 / 60.0;
+        Self {
+            solar_kw: vec![solar_kwh / hours; steps],
+            load_kw: vec![load_kwh / hours; steps],
+        }
+    }
+
+    /// Number of steps (the shorter series wins if they differ)
+    #[must_use]
+    pub fn steps(&self) -> usize {
+        self.solar_kw.len().min(self.load_kw.len())
+    }
+
+    /// Total solar energy over a block of `duration_minutes` (kWh)
+    #[must_use]
+    pub fn solar_kwh(&self, duration_minutes: u32) -> f32 {
+        self.step_hours(duration_minutes) * self.solar_kw.iter().take(self.steps()).sum::<f32>()
+    }
+
+    /// Total load energy over a block of `duration_minutes` (kWh)
+    #[must_use]
+    pub fn load_kwh(&self, duration_minutes: u32) -> f32 {
+        self.step_hours(duration_minutes) * self.load_kw.iter().take(self.steps()).sum::<f32>()
+    }
+
+    /// Keep the shape but rescale each series to the given block energies
+    ///
+    /// Used when the block totals are overridden; a series that is all zero
+    /// becomes flat.
+    #[must_use]
+    pub fn scaled_to(&self, solar_kwh: f32, load_kwh: f32, duration_minutes: u32) -> Self {
+        let steps = self.steps();
+        let flat = Self::flat(solar_kwh, load_kwh, duration_minutes, steps);
+        let rescale = |series: &[f32], current_kwh: f32, target_kwh: f32, flat: &[f32]| {
+            if current_kwh > 0.0 {
+                series
+                    .iter()
+                    .take(steps)
+                    .map(|kw| kw * target_kwh / current_kwh)
+                    .collect()
+            } else {
+                flat.to_vec()
+            }
+        };
+        Self {
+            solar_kw: rescale(
+                &self.solar_kw,
+                self.solar_kwh(duration_minutes),
+                solar_kwh,
+                &flat.solar_kw,
+            ),
+            load_kw: rescale(
+                &self.load_kw,
+                self.load_kwh(duration_minutes),
+                load_kwh,
+                &flat.load_kw,
+            ),
+        }
+    }
+
+    fn step_hours(&self, duration_minutes: u32) -> f32 {
+        let steps = self.steps();
+        if steps == 0 {
+            return 0.0;
+        }
+        duration_minutes as f32 / 60.0 / steps as f32
+    }
+}
+
+/// Battery and grid limits for stepping through a block
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubBlockBattery {
+    pub soc_percent: f32,
+    pub capacity_kwh: f32,
+    /// Charging efficiency (energy stored per kWh charged)
+    pub efficiency: f32,
+    pub min_soc_percent: f32,
+    pub max_soc_percent: f32,
+    pub max_charge_kw: f32,
+    pub max_discharge_kw: f32,
+    /// Grid export cap (kW); `None` = unlimited
+    pub export_limit_kw: Option<f32>,
+}
+
+/// Result of stepping through a block in self-use
+#[derive(Debug, Clone)]
+pub struct SubBlockOutcome {
+    pub flows: EnergyFlows,
+    /// SOC at the end of the block (%)
+    pub end_soc_percent: f32,
+}
+
+/// Self-use over a block: surplus charges the battery, deficit discharges it,
+/// and the rest goes to or comes from the grid, step by step.
+#[must_use]
+pub fn simulate_self_use(
+    profile: &PowerProfile,
+    duration_minutes: u32,
+    battery: &SubBlockBattery,
+) -> SubBlockOutcome {
+    let step_hours = profile.step_hours(duration_minutes);
+    let efficiency = battery.efficiency.clamp(0.01, 1.0);
+    let mut stored_kwh = battery.capacity_kwh * battery.soc_percent / 100.0;
+    let min_kwh = battery.capacity_kwh * battery.min_soc_percent / 100.0;
+    let max_kwh = battery.capacity_kwh * battery.max_soc_percent / 100.0;
+    let mut flows = EnergyFlows::default();
+
+    for (&solar_kw, &load_kw) in profile.solar_kw.iter().zip(&profile.load_kw) {
+        let solar = solar_kw.max(0.0) * step_hours;
+        let load = load_kw.max(0.0) * step_hours;
+        flows.solar_generation_kwh += solar;
+        flows.household_consumption_kwh += load;
+
+        let net = solar - load;
+        if net > 0.0 {
+            let room = ((max_kwh - stored_kwh) / efficiency).max(0.0);
+            let charge = net.min(battery.max_charge_kw * step_hours).min(room);
+            stored_kwh += charge * efficiency;
+            flows.battery_charge_kwh += charge;
+
+            let surplus = net - charge;
+            let export = battery
+                .export_limit_kw
+                .map_or(surplus, |kw| surplus.min(kw.max(0.0) * step_hours));
+            flows.grid_export_kwh += export;
+            flows.curtailed_solar_kwh += surplus - export;
+        } else if net < 0.0 {
+            let deficit = -net;
+            let available = (stored_kwh - min_kwh).max(0.0);
+            let discharge = deficit
+                .min(battery.max_discharge_kw * step_hours)
+                .min(available);
+            stored_kwh -= discharge;
+            flows.battery_discharge_kwh += discharge;
+            flows.grid_import_kwh += deficit - discharge;
+        }
+    }
+
+    let end_soc_percent = if battery.capacity_kwh > 0.0 {
+        stored_kwh / battery.capacity_kwh * 100.0
+    } else {
+        battery.soc_percent
+    };
+    SubBlockOutcome {
+        flows,
+        end_soc_percent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battery(soc_percent: f32) -> SubBlockBattery {
+        SubBlockBattery {
+            soc_percent,
+            capacity_kwh: 10.0,
+            efficiency: 1.0,
+            min_soc_percent: 10.0,
+            max_soc_percent: 100.0,
+            max_charge_kw: 3.0,
+            max_discharge_kw: 3.0,
+            export_limit_kw: None,
+        }
+    }
+
+    #[test]
+    fn test_flat_profile_matches_block_average() {
+        let profile = PowerProfile::flat(0.5, 0.25, 15, 15);
+        let outcome = simulate_self_use(&profile, 15, &battery(50.0));
+
+        assert!((outcome.flows.battery_charge_kwh - 0.25).abs() < 1e-4);
+        assert!(outcome.flows.grid_export_kwh.abs() < 1e-4);
+        assert!((outcome.end_soc_percent - 52.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_solar_peak_above_charge_rate_is_exported() {
+        // Same 1 kWh of solar as a flat 4 kW block, but all of it in 5 minutes
+        let mut solar_kw = vec![0.0; 15];
+        solar_kw[..5].fill(12.0);
+        let profile = PowerProfile {
+            solar_kw,
+            load_kw: vec![0.0; 15],
+        };
+
+        let outcome = simulate_self_use(&profile, 15, &battery(50.0));
+
+        // 3 kW for 5 minutes is all the battery takes
+        assert!((outcome.flows.battery_charge_kwh - 0.25).abs() < 1e-4);
+        assert!((outcome.flows.grid_export_kwh - 0.75).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_load_spike_above_discharge_rate_is_imported() {
+        let mut load_kw = vec![0.5; 15];
+        load_kw[7] = 9.5;
+        let profile = PowerProfile {
+            solar_kw: vec![0.0; 15],
+            load_kw,
+        };
+        let limited = SubBlockBattery {
+            export_limit_kw: Some(0.0),
+            ..battery(50.0)
+        };
+
+        let outcome = simulate_self_use(&profile, 15, &limited);
+
+        // The spike minute needs 9.5 kW; the battery covers 3 kW of it
+        let spike_import = (9.5 - 3.0) / 60.0;
+        assert!((outcome.flows.grid_import_kwh - spike_import).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_scaled_profile_keeps_shape() {
+        let profile = PowerProfile {
+            solar_kw: vec![1.0, 3.0, 2.0],
+            load_kw: vec![0.0, 0.0, 0.0],
+        };
+        let scaled = profile.scaled_to(1.5, 0.3, 15);
+
+        assert!((scaled.solar_kwh(15) - 1.5).abs() < 1e-4);
+        assert!((scaled.solar_kw[1] / scaled.solar_kw[0] - 3.0).abs() < 1e-4);
+        assert!((scaled.load_kwh(15) - 0.3).abs() < 1e-4);
+    }
+}
@@ -15,6 +15,7 @@ use crate::strategy::{
 };
 use crate::utils::calculate_ema;
 use chrono::{DateTime, Datelike, Timelike, Utc};
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::inverter::InverterOperationMode;
 use fluxion_types::pricing::TimeBlockPrice;
 use serde::{Deserialize, Serialize};
@@ -1399,6 +1400,84 @@ impl EconomicStrategy for WinterAdaptiveStrategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "Charges the battery for the day's consumption in the cheapest blocks and discharges during the most expensive ones, adapting to the season.".to_owned(),
+            inputs: [
+                "Effective block prices (spot + HDO grid fee) for the planning horizon",
+                "Current battery SOC",
+                "Block solar and consumption forecast",
+                "Consumption history (EMA over recent days)",
+                "Tomorrow's prices, when published",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Negative price: charge (optionally even when the battery is full)",
+                "Estimate the day's energy need from the consumption EMA and the solar forecast",
+                "Charge in the cheapest blocks, preferring consecutive blocks within the consolidation tolerance, until the need (times the safety multiplier) is covered",
+                "Discharge during the most expensive blocks of the day",
+                "Preserve charge when tomorrow's peak is notably higher than today's",
+                "Export to the grid on price spikes while keeping the minimum export SOC",
+                "Otherwise self-use",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "daily_charging_target_soc",
+                    self.config.daily_charging_target_soc,
+                    "Target SOC for daily charging (%)",
+                ),
+                PluginParameterDoc::new(
+                    "conservation_threshold_soc",
+                    self.config.conservation_threshold_soc,
+                    "Below this SOC discharging is more careful (%)",
+                ),
+                PluginParameterDoc::new(
+                    "top_expensive_blocks",
+                    self.config.top_expensive_blocks,
+                    "Number of most expensive blocks targeted for discharge",
+                ),
+                PluginParameterDoc::new(
+                    "ema_period_days",
+                    self.config.ema_period_days,
+                    "Days of consumption history in the EMA",
+                ),
+                PluginParameterDoc::new(
+                    "tomorrow_preservation_threshold",
+                    self.config.tomorrow_preservation_threshold,
+                    "Tomorrow/today peak ratio that triggers preserving charge",
+                ),
+                PluginParameterDoc::new(
+                    "grid_export_price_threshold",
+                    self.config.grid_export_price_threshold,
+                    "Price at which grid export is considered (CZK/kWh)",
+                ),
+                PluginParameterDoc::new(
+                    "min_soc_for_export",
+                    self.config.min_soc_for_export,
+                    "Minimum SOC kept when exporting (%)",
+                ),
+                PluginParameterDoc::new(
+                    "negative_price_handling_enabled",
+                    self.config.negative_price_handling_enabled,
+                    "Charge on negative prices",
+                ),
+                PluginParameterDoc::new(
+                    "min_consecutive_charge_blocks",
+                    self.config.min_consecutive_charge_blocks,
+                    "Minimum consecutive charge blocks",
+                ),
+                PluginParameterDoc::new(
+                    "charge_safety_multiplier",
+                    self.config.charge_safety_multiplier,
+                    "Multiplier on the calculated charge requirement",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let mut eval = BlockEvaluation::new(
             context.price_block.block_start,
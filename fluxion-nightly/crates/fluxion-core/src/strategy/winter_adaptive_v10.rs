@@ -49,6 +49,7 @@ use chrono::Timelike;
 use serde::{Deserialize, Serialize};
 
 use crate::strategy::{Assumptions, BlockEvaluation, EconomicStrategy, EvaluationContext};
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::{inverter::InverterOperationMode, pricing::TimeBlockPrice};
 
 /// Configuration for Winter Adaptive V10 strategy
@@ -703,6 +704,79 @@ impl EconomicStrategy for WinterAdaptiveV10Strategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "Allocates the finite battery budget to the blocks where it saves the most, instead of using fixed time windows or modes.".to_owned(),
+            inputs: [
+                "Effective block prices (spot + HDO grid fee) for the planning horizon",
+                "Current battery SOC",
+                "Solar forecast (remaining today and tomorrow)",
+                "Hourly consumption profile from the last 7 days",
+                "Average charge price of the energy stored in the battery",
+                "Grid export price",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Negative price: charge",
+                "Price below the opportunistic threshold: charge",
+                "Estimate net consumption per block and the battery budget from SOC plus scheduled charges",
+                "Assign the battery to blocks from the most expensive down while the saving over the average charge price reaches the threshold",
+                "Add charge blocks when cheap blocks make it profitable; upgrade very expensive blocks to export",
+                "Solar excess blocks: self-use; remaining blocks: grid powered",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "target_battery_soc",
+                    self.config.target_battery_soc,
+                    "Target SOC for charging (%)",
+                ),
+                PluginParameterDoc::new(
+                    "min_discharge_soc",
+                    self.config.min_discharge_soc,
+                    "Hardware minimum SOC (%)",
+                ),
+                PluginParameterDoc::new(
+                    "min_savings_threshold_czk",
+                    self.config.min_savings_threshold_czk,
+                    "Minimum saving per kWh to use the battery (CZK)",
+                ),
+                PluginParameterDoc::new(
+                    "opportunistic_charge_threshold_czk",
+                    self.config.opportunistic_charge_threshold_czk,
+                    "Price below which the battery always charges (CZK/kWh)",
+                ),
+                PluginParameterDoc::new(
+                    "min_export_spread_czk",
+                    self.config.min_export_spread_czk,
+                    "Minimum spread for grid export (CZK)",
+                ),
+                PluginParameterDoc::new(
+                    "min_soc_after_export",
+                    self.config.min_soc_after_export,
+                    "Minimum SOC after export (%)",
+                ),
+                PluginParameterDoc::new(
+                    "solar_threshold_kwh",
+                    self.config.solar_threshold_kwh,
+                    "Solar forecast considered as excess (kWh)",
+                ),
+                PluginParameterDoc::new(
+                    "solar_confidence_factor",
+                    self.config.solar_confidence_factor,
+                    "Share of the solar forecast relied upon",
+                ),
+                PluginParameterDoc::new(
+                    "battery_round_trip_efficiency",
+                    self.config.battery_round_trip_efficiency,
+                    "Round-trip battery efficiency",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let mut eval = BlockEvaluation::new(
             context.price_block.block_start,
@@ -41,6 +41,7 @@ use crate::strategy::{
 };
 use crate::utils::calculate_ema;
 use chrono::{DateTime, Datelike, Utc};
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::inverter::InverterOperationMode;
 use fluxion_types::pricing::TimeBlockPrice;
 use serde::{Deserialize, Serialize};
@@ -1480,6 +1481,83 @@ impl EconomicStrategy for WinterAdaptiveV2Strategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "Plans arbitrage windows across the horizon using conservative (P90) consumption and solar estimates, reserving charge for price spikes.".to_owned(),
+            inputs: [
+                "Effective block prices (spot + HDO grid fee) for the planning horizon",
+                "Current battery SOC",
+                "Per-slot consumption and solar history",
+                "Solar forecast (remaining today and tomorrow)",
+                "Grid export price",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Negative price: charge",
+                "Estimate the energy need with the P90 consumption multiplier and discounted solar, plus the safety margin",
+                "Charge in the cheapest blocks within the price tolerance of the cheapest block; relax up to the median when an SOC deficit is predicted",
+                "Reserve SOC for blocks above the spike threshold and discharge there",
+                "Feed in to the grid when enabled and the spread is large enough",
+                "Lock the schedule to avoid mode oscillation; otherwise self-use",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "daily_charging_target_soc",
+                    self.config.daily_charging_target_soc,
+                    "Target SOC for daily charging (%)",
+                ),
+                PluginParameterDoc::new(
+                    "min_soc_pct",
+                    self.config.min_soc_pct,
+                    "Minimum SOC to maintain (%)",
+                ),
+                PluginParameterDoc::new(
+                    "safety_margin_pct",
+                    self.config.safety_margin_pct,
+                    "Safety margin on the charge calculation",
+                ),
+                PluginParameterDoc::new(
+                    "consumption_p90_multiplier",
+                    self.config.consumption_p90_multiplier,
+                    "Multiplier for the conservative consumption estimate",
+                ),
+                PluginParameterDoc::new(
+                    "solar_p90_discount",
+                    self.config.solar_p90_discount,
+                    "Discount on the solar forecast",
+                ),
+                PluginParameterDoc::new(
+                    "spike_threshold_czk",
+                    self.config.spike_threshold_czk,
+                    "Price counted as a spike (CZK/kWh)",
+                ),
+                PluginParameterDoc::new(
+                    "min_soc_for_spike_export",
+                    self.config.min_soc_for_spike_export,
+                    "SOC reserved for spike export (%)",
+                ),
+                PluginParameterDoc::new(
+                    "feedin_enabled",
+                    self.config.feedin_enabled,
+                    "Allow feed-in to the grid",
+                ),
+                PluginParameterDoc::new(
+                    "feedin_min_spread_czk",
+                    self.config.feedin_min_spread_czk,
+                    "Minimum spread for feed-in (CZK/kWh)",
+                ),
+                PluginParameterDoc::new(
+                    "charge_price_tolerance_percent",
+                    self.config.charge_price_tolerance_percent,
+                    "Accepted premium over the cheapest block (%)",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let mut eval = BlockEvaluation::new(
             context.price_block.block_start,
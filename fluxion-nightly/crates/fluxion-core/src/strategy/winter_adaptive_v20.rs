@@ -44,6 +44,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::day_profiling::{compute_day_metrics, estimate_daily_consumption};
 use crate::strategy::{Assumptions, BlockEvaluation, EconomicStrategy, EvaluationContext};
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::day_profile::DayMetrics;
 use fluxion_types::{inverter::InverterOperationMode, pricing::TimeBlockPrice};
 
@@ -833,6 +834,81 @@ impl EconomicStrategy for WinterAdaptiveV20Strategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "V10 budget allocation with parameters resolved from the day's measured characteristics (volatility, price level, solar, tomorrow's outlook).".to_owned(),
+            inputs: [
+                "Effective block prices (spot + HDO grid fee) for the planning horizon",
+                "Current battery SOC",
+                "Solar forecast (remaining today and tomorrow)",
+                "Hourly consumption profile from the last 7 days",
+                "Average charge price of the energy stored in the battery",
+                "Tomorrow's prices, when published",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Compute day metrics: price volatility, price level, solar ratio, tomorrow's price ratio and negative price share",
+                "Adjust parameters: lower savings threshold on volatile days, higher on expensive days, wider daylight window on high solar, fewer discharge blocks when tomorrow is expensive",
+                "Run the V10 budget allocation with the resolved parameters",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "target_battery_soc",
+                    self.config.target_battery_soc,
+                    "Target SOC for charging (%)",
+                ),
+                PluginParameterDoc::new(
+                    "min_discharge_soc",
+                    self.config.min_discharge_soc,
+                    "Hardware minimum SOC (%)",
+                ),
+                PluginParameterDoc::new(
+                    "min_savings_threshold_czk",
+                    self.config.min_savings_threshold_czk,
+                    "Base minimum saving per kWh to use the battery (CZK)",
+                ),
+                PluginParameterDoc::new(
+                    "opportunistic_charge_threshold_czk",
+                    self.config.opportunistic_charge_threshold_czk,
+                    "Price below which the battery always charges (CZK/kWh)",
+                ),
+                PluginParameterDoc::new(
+                    "volatile_cv_threshold",
+                    self.config.volatile_cv_threshold,
+                    "Coefficient of variation above which the day is volatile",
+                ),
+                PluginParameterDoc::new(
+                    "expensive_level_threshold",
+                    self.config.expensive_level_threshold,
+                    "Price level above which the day is expensive",
+                ),
+                PluginParameterDoc::new(
+                    "high_solar_ratio_threshold",
+                    self.config.high_solar_ratio_threshold,
+                    "Solar ratio above which solar is high",
+                ),
+                PluginParameterDoc::new(
+                    "low_solar_ratio_threshold",
+                    self.config.low_solar_ratio_threshold,
+                    "Solar ratio below which solar is low",
+                ),
+                PluginParameterDoc::new(
+                    "tomorrow_expensive_ratio",
+                    self.config.tomorrow_expensive_ratio,
+                    "Tomorrow price ratio counted as expensive",
+                ),
+                PluginParameterDoc::new(
+                    "tomorrow_cheap_ratio",
+                    self.config.tomorrow_cheap_ratio,
+                    "Tomorrow price ratio counted as cheap",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let mut eval = BlockEvaluation::new(
             context.price_block.block_start,
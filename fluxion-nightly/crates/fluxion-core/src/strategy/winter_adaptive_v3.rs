@@ -27,6 +27,7 @@ use crate::strategy::{
     seasonal::SeasonalMode,
 };
 use chrono::Utc;
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::inverter::InverterOperationMode;
 use fluxion_types::pricing::TimeBlockPrice;
 use serde::{Deserialize, Serialize};
@@ -494,6 +495,63 @@ impl EconomicStrategy for WinterAdaptiveV3Strategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "Charges in the cheapest blocks and discharges only in the day's top expensive blocks when the battery is well charged and the price beats the median by a buffer.".to_owned(),
+            inputs: [
+                "Effective block prices (spot + HDO grid fee) for the planning horizon",
+                "Current battery SOC",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Negative price: charge",
+                "Charge in the cheapest blocks within the price tolerance until the target SOC",
+                "Discharge only if SOC is at least the winter discharge minimum, the block is among the top expensive blocks of the day, and its price exceeds median + high grid fee + buffer",
+                "Otherwise self-use",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "daily_charging_target_soc",
+                    self.config.daily_charging_target_soc,
+                    "Target SOC for charging (%)",
+                ),
+                PluginParameterDoc::new(
+                    "winter_discharge_min_soc",
+                    self.config.winter_discharge_min_soc,
+                    "Minimum SOC for discharge (%)",
+                ),
+                PluginParameterDoc::new(
+                    "top_discharge_blocks_per_day",
+                    self.config.top_discharge_blocks_per_day,
+                    "Number of top expensive blocks allowed for discharge",
+                ),
+                PluginParameterDoc::new(
+                    "discharge_arbitrage_buffer",
+                    self.config.discharge_arbitrage_buffer,
+                    "Required price margin above median + high grid fee",
+                ),
+                PluginParameterDoc::new(
+                    "min_consecutive_charge_blocks",
+                    self.config.min_consecutive_charge_blocks,
+                    "Minimum consecutive charge blocks",
+                ),
+                PluginParameterDoc::new(
+                    "charge_price_tolerance_percent",
+                    self.config.charge_price_tolerance_percent,
+                    "Accepted premium over the cheapest block (%)",
+                ),
+                PluginParameterDoc::new(
+                    "negative_price_handling_enabled",
+                    self.config.negative_price_handling_enabled,
+                    "Charge on negative prices",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let mut eval = BlockEvaluation::new(
             context.price_block.block_start,
@@ -47,6 +47,7 @@ use crate::strategy::{
     Assumptions, BlockEvaluation, EconomicStrategy, EvaluationContext, economics,
 };
 use chrono::{DateTime, Utc};
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::inverter::InverterOperationMode;
 use fluxion_types::pricing::TimeBlockPrice;
 use serde::{Deserialize, Serialize};
@@ -493,6 +494,54 @@ impl EconomicStrategy for WinterAdaptiveV4Strategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "Pure price optimization: charges in the globally cheapest blocks of the horizon and discharges in the most expensive ones, regardless of current SOC.".to_owned(),
+            inputs: [
+                "Effective block prices (spot + HDO grid fee) for the planning horizon",
+                "Current battery SOC",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Negative price: charge",
+                "Rank all blocks in the planning horizon by effective price",
+                "Charge if the block is among the N cheapest needed to reach the target SOC",
+                "Discharge if the block is among the most expensive blocks and beats the average charge price by the minimum spread",
+                "Otherwise self-use",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "target_battery_soc",
+                    self.config.target_battery_soc,
+                    "Target SOC for charging (%)",
+                ),
+                PluginParameterDoc::new(
+                    "discharge_blocks_per_day",
+                    self.config.discharge_blocks_per_day,
+                    "Number of expensive blocks used for discharge",
+                ),
+                PluginParameterDoc::new(
+                    "min_discharge_spread_czk",
+                    self.config.min_discharge_spread_czk,
+                    "Minimum spread between discharge and charge price (CZK)",
+                ),
+                PluginParameterDoc::new(
+                    "planning_horizon_hours",
+                    self.config.planning_horizon_hours,
+                    "How far ahead blocks are ranked (hours)",
+                ),
+                PluginParameterDoc::new(
+                    "negative_price_handling_enabled",
+                    self.config.negative_price_handling_enabled,
+                    "Charge on negative prices",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let mut eval = BlockEvaluation::new(
             context.price_block.block_start,
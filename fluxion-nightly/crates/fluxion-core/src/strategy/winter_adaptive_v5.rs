@@ -58,6 +58,7 @@ use crate::strategy::{
     Assumptions, BlockEvaluation, EconomicStrategy, EvaluationContext, economics,
 };
 use chrono::{DateTime, Utc};
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::inverter::InverterOperationMode;
 use fluxion_types::pricing::TimeBlockPrice;
 use serde::{Deserialize, Serialize};
@@ -514,6 +515,65 @@ impl EconomicStrategy for WinterAdaptiveV5Strategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "Splits the horizon into cheap, middle and expensive blocks: charges aggressively when cheap, freezes SOC in the middle and discharges at peaks above a reserve.".to_owned(),
+            inputs: [
+                "Effective block prices (spot + HDO grid fee) for the planning horizon",
+                "Current battery SOC",
+                "Consumption forecast",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Negative price: charge",
+                "Cheap block (bottom percentile) and SOC below target: force charge",
+                "Expensive block (top percentile), SOC above the discharge minimum and spread above the minimum: self-use discharge",
+                "Middle block: hold SOC and use the grid",
+                "Never buy from the grid during expensive blocks when the battery can cover the load",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "target_battery_soc",
+                    self.config.target_battery_soc,
+                    "Target SOC for charging (%)",
+                ),
+                PluginParameterDoc::new(
+                    "min_discharge_soc",
+                    self.config.min_discharge_soc,
+                    "Minimum SOC before discharge is allowed (%)",
+                ),
+                PluginParameterDoc::new(
+                    "cheap_block_percentile",
+                    self.config.cheap_block_percentile,
+                    "Price percentile below which blocks are cheap",
+                ),
+                PluginParameterDoc::new(
+                    "expensive_block_percentile",
+                    self.config.expensive_block_percentile,
+                    "Price percentile above which blocks are expensive",
+                ),
+                PluginParameterDoc::new(
+                    "min_discharge_spread_czk",
+                    self.config.min_discharge_spread_czk,
+                    "Minimum spread between discharge and charge price (CZK)",
+                ),
+                PluginParameterDoc::new(
+                    "safety_margin_pct",
+                    self.config.safety_margin_pct,
+                    "Safety margin on the energy need",
+                ),
+                PluginParameterDoc::new(
+                    "planning_horizon_hours",
+                    self.config.planning_horizon_hours,
+                    "Planning horizon (hours)",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let mut eval = BlockEvaluation::new(
             context.price_block.block_start,
@@ -70,6 +70,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::strategy::{Assumptions, BlockEvaluation, EconomicStrategy, EvaluationContext};
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::{inverter::InverterOperationMode, pricing::TimeBlockPrice};
 
 /// Configuration for Winter Adaptive V7 strategy
@@ -853,6 +854,83 @@ impl EconomicStrategy for WinterAdaptiveV7Strategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "Unconstrained multi-cycle arbitrage: finds every profitable valley-peak pair on volatile days and falls back to percentile scheduling on stable days.".to_owned(),
+            inputs: [
+                "Effective block prices (spot + HDO grid fee) for the planning horizon",
+                "Current battery SOC",
+                "Solar forecast (remaining today and tomorrow)",
+                "Consumption forecast",
+                "Grid export price",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Negative price or price below the opportunistic threshold: charge",
+                "Volatile day (coefficient of variation above threshold): pair price valleys with peaks and keep cycles earning at least the minimum cycle profit",
+                "Stable day: charge in the cheap percentile, discharge in the expensive percentile when the spread allows",
+                "Reduce grid charging by the expected solar, keeping the minimum number of charge blocks",
+                "Export to the grid only when the spread exceeds the export minimum and predicted SOC stays above the export floor",
+                "Otherwise self-use",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "target_battery_soc",
+                    self.config.target_battery_soc,
+                    "Target SOC for charging (%)",
+                ),
+                PluginParameterDoc::new(
+                    "min_discharge_soc",
+                    self.config.min_discharge_soc,
+                    "Hardware minimum SOC (%)",
+                ),
+                PluginParameterDoc::new(
+                    "min_cycle_profit_czk",
+                    self.config.min_cycle_profit_czk,
+                    "Minimum profit of a charge/discharge cycle (CZK)",
+                ),
+                PluginParameterDoc::new(
+                    "volatility_threshold_cv",
+                    self.config.volatility_threshold_cv,
+                    "Coefficient of variation separating volatile and stable days",
+                ),
+                PluginParameterDoc::new(
+                    "cheap_block_percentile",
+                    self.config.cheap_block_percentile,
+                    "Cheap percentile on stable days",
+                ),
+                PluginParameterDoc::new(
+                    "expensive_block_percentile",
+                    self.config.expensive_block_percentile,
+                    "Expensive percentile on stable days",
+                ),
+                PluginParameterDoc::new(
+                    "min_export_spread_czk",
+                    self.config.min_export_spread_czk,
+                    "Minimum spread for grid export (CZK)",
+                ),
+                PluginParameterDoc::new(
+                    "min_soc_after_export",
+                    self.config.min_soc_after_export,
+                    "Minimum predicted SOC after export (%)",
+                ),
+                PluginParameterDoc::new(
+                    "opportunistic_charge_threshold_czk",
+                    self.config.opportunistic_charge_threshold_czk,
+                    "Price below which the battery always charges (CZK/kWh)",
+                ),
+                PluginParameterDoc::new(
+                    "solar_aware_charging_enabled",
+                    self.config.solar_aware_charging_enabled,
+                    "Reduce grid charging by expected solar",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let mut eval = BlockEvaluation::new(
             context.price_block.block_start,
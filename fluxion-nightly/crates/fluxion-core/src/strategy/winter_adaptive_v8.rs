@@ -62,6 +62,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::strategy::{Assumptions, BlockEvaluation, EconomicStrategy, EvaluationContext};
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::{inverter::InverterOperationMode, pricing::TimeBlockPrice};
 
 /// Configuration for Winter Adaptive V8 strategy
@@ -601,6 +602,78 @@ impl EconomicStrategy for WinterAdaptiveV8Strategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "Concentrates discharge in the top N price peaks and charges just enough in the cheapest blocks to reach them.".to_owned(),
+            inputs: [
+                "Effective block prices (spot + HDO grid fee) for the planning horizon",
+                "Current battery SOC",
+                "Solar forecast (remaining today and tomorrow)",
+                "Consumption forecast",
+                "Grid export price",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Negative price or price below the opportunistic threshold: charge",
+                "Select the top N most expensive blocks as discharge candidates",
+                "Discharge only if (average peak price - average charge price) times efficiency reaches the minimum spread",
+                "Simulate SOC across the horizon and charge in the cheapest blocks so the battery still has energy at the peaks",
+                "Reduce grid charging by the reserved solar share, keeping the minimum charge blocks",
+                "Export only when the spread exceeds the export minimum and SOC stays above the export floor; otherwise self-use",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "target_battery_soc",
+                    self.config.target_battery_soc,
+                    "Target SOC for charging (%)",
+                ),
+                PluginParameterDoc::new(
+                    "min_discharge_soc",
+                    self.config.min_discharge_soc,
+                    "Hardware minimum SOC (%)",
+                ),
+                PluginParameterDoc::new(
+                    "top_discharge_blocks_count",
+                    self.config.top_discharge_blocks_count,
+                    "Number of top price blocks used for discharge",
+                ),
+                PluginParameterDoc::new(
+                    "min_discharge_spread_czk",
+                    self.config.min_discharge_spread_czk,
+                    "Minimum discharge/charge spread (CZK)",
+                ),
+                PluginParameterDoc::new(
+                    "cheap_block_percentile",
+                    self.config.cheap_block_percentile,
+                    "Price percentile used for charging",
+                ),
+                PluginParameterDoc::new(
+                    "battery_round_trip_efficiency",
+                    self.config.battery_round_trip_efficiency,
+                    "Round-trip battery efficiency",
+                ),
+                PluginParameterDoc::new(
+                    "min_export_spread_czk",
+                    self.config.min_export_spread_czk,
+                    "Minimum spread for grid export (CZK)",
+                ),
+                PluginParameterDoc::new(
+                    "opportunistic_charge_threshold_czk",
+                    self.config.opportunistic_charge_threshold_czk,
+                    "Price below which the battery always charges (CZK/kWh)",
+                ),
+                PluginParameterDoc::new(
+                    "solar_capacity_reservation_factor",
+                    self.config.solar_capacity_reservation_factor,
+                    "Share of expected solar kept free in the battery",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let mut eval = BlockEvaluation::new(
             context.price_block.block_start,
@@ -64,6 +64,7 @@ use chrono::{DateTime, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::strategy::{Assumptions, BlockEvaluation, EconomicStrategy, EvaluationContext};
+use fluxion_plugins::{PluginDocs, PluginParameterDoc};
 use fluxion_types::{inverter::InverterOperationMode, pricing::TimeBlockPrice};
 
 /// Configuration for Winter Adaptive V9 strategy
@@ -1021,6 +1022,82 @@ impl EconomicStrategy for WinterAdaptiveV9Strategy {
         self.config.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        PluginDocs {
+            summary: "Solar-first planning: on sunny days grid-charges only enough to cover the morning peak; on dull days falls back to V7-style arbitrage.".to_owned(),
+            inputs: [
+                "Effective block prices (spot + HDO grid fee) for the planning horizon",
+                "Current battery SOC",
+                "Solar forecast (remaining today and tomorrow)",
+                "Consumption forecast",
+                "Grid export price",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            decision_logic: [
+                "Negative price or price below the opportunistic threshold: charge",
+                "Solar remaining today above threshold: charge overnight just enough to end the morning peak at the target SOC",
+                "Solar below threshold: arbitrage between the cheap percentile and the top discharge blocks when the spread allows",
+                "Always keep the minimum number of overnight charge blocks",
+                "Export only when the spread exceeds the export minimum and SOC stays above the export floor; otherwise self-use",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            parameters: vec![
+                PluginParameterDoc::new(
+                    "target_battery_soc",
+                    self.config.target_battery_soc,
+                    "Target SOC for charging (%)",
+                ),
+                PluginParameterDoc::new(
+                    "min_discharge_soc",
+                    self.config.min_discharge_soc,
+                    "Hardware minimum SOC (%)",
+                ),
+                PluginParameterDoc::new(
+                    "morning_peak_start_hour",
+                    self.config.morning_peak_start_hour,
+                    "Morning peak start hour",
+                ),
+                PluginParameterDoc::new(
+                    "morning_peak_end_hour",
+                    self.config.morning_peak_end_hour,
+                    "Morning peak end hour",
+                ),
+                PluginParameterDoc::new(
+                    "target_soc_after_morning_peak",
+                    self.config.target_soc_after_morning_peak,
+                    "Target SOC at the end of the morning peak (%)",
+                ),
+                PluginParameterDoc::new(
+                    "solar_threshold_kwh",
+                    self.config.solar_threshold_kwh,
+                    "Solar forecast that enables solar-first mode (kWh)",
+                ),
+                PluginParameterDoc::new(
+                    "solar_confidence_factor",
+                    self.config.solar_confidence_factor,
+                    "Share of the solar forecast relied upon",
+                ),
+                PluginParameterDoc::new(
+                    "min_arbitrage_spread_czk",
+                    self.config.min_arbitrage_spread_czk,
+                    "Minimum spread for arbitrage (CZK)",
+                ),
+                PluginParameterDoc::new(
+                    "top_discharge_blocks_count",
+                    self.config.top_discharge_blocks_count,
+                    "Number of top price blocks used for discharge",
+                ),
+                PluginParameterDoc::new(
+                    "min_overnight_charge_blocks",
+                    self.config.min_overnight_charge_blocks,
+                    "Minimum overnight charge blocks",
+                ),
+            ],
+        }
+    }
+
     fn evaluate(&self, context: &EvaluationContext) -> BlockEvaluation {
         let mut eval = BlockEvaluation::new(
             context.price_block.block_start,
@@ -0,0 +1,347 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+//! Per-appliance cost attribution from Home Assistant power sensors.
+//!
+//! A supervised worker reads the configured power sensors (heat pump, EV
+//! charger, boiler) every `sample_interval_secs` and [`submeter_system`]
+//! stores the readings in the [`Historian`]. A reading's energy covers the
+//! time since the device's previous reading, at most twice the interval so an
+//! outage is not billed, and is priced at the spot price in effect when it was
+//! taken. `/api/submeter` sums kWh and cost per device and local day or month.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, NaiveDate, Utc};
+use futures_timer::Delay;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::historian::Historian;
+use crate::savings::SavingsPeriod;
+use crate::time_format::TimeFormatter;
+
+/// One sub-metered appliance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubmeterDevice {
+    /// Name in reports, e.g. "Heat pump"
+    pub name: String,
+    /// `sensor.*` entity reporting the appliance's power in W or kW
+    pub entity_id: String,
+}
+
+/// Sub-metering settings (`[submeter]`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubmeterConfig {
+    pub enabled: bool,
+    /// Time between two readings of the power sensors
+    pub sample_interval_secs: u64,
+    pub devices: Vec<SubmeterDevice>,
+}
+
+impl Default for SubmeterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_interval_secs: 60,
+            devices: Vec::new(),
+        }
+    }
+}
+
+impl SubmeterConfig {
+    /// Check the settings
+    ///
+    /// # Errors
+    /// Describes the first invalid setting
+    pub fn validate(&self) -> Result<(), String> {
+        if !(10..=3600).contains(&self.sample_interval_secs) {
+            return Err("sample_interval_secs must be between 10 and 3600".to_owned());
+        }
+        if self.devices.is_empty() {
+            return Err("at least one device is required".to_owned());
+        }
+        for (i, device) in self.devices.iter().enumerate() {
+            if device.name.trim().is_empty() {
+                return Err(format!("devices[{i}].name must not be empty"));
+            }
+            if !device.entity_id.starts_with("sensor.") {
+                return Err(format!(
+                    "devices[{i}].entity_id must be a sensor entity, got '{}'",
+                    device.entity_id
+                ));
+            }
+            if self.devices[..i].iter().any(|d| d.name == device.name) {
+                return Err(format!("device name '{}' is used twice", device.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the current power of an appliance
+#[expect(clippy::double_must_use, reason = "generated by async_trait")]
+#[async_trait]
+pub trait PowerSensorReader: Send + Sync {
+    /// Power in W
+    async fn read_power_w(&self, entity_id: &str) -> Result<f32>;
+}
+
+/// One reading of one appliance
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubmeterReading {
+    pub device: String,
+    pub timestamp: DateTime<Utc>,
+    pub power_w: f32,
+    /// Energy since the device's previous reading
+    pub energy_kwh: f32,
+}
+
+/// Receives readings from the sub-metering worker
+#[derive(Resource)]
+pub struct SubmeterChannel {
+    pub receiver: crossbeam_channel::Receiver<Vec<SubmeterReading>>,
+}
+
+/// Spawns the worker polling the appliance power sensors
+///
+/// Must be called within a tokio runtime.
+pub fn spawn_submeter_worker(
+    config: &SubmeterConfig,
+    reader: Arc<dyn PowerSensorReader>,
+) -> SubmeterChannel {
+    let (sender, receiver) = crossbeam_channel::bounded(16);
+    let devices = config.devices.clone();
+    let interval_secs = config.sample_interval_secs.max(1);
+    info!("🔌 Sub-metering {} appliances", devices.len());
+
+    crate::TaskSupervisor::global().spawn("submeter_reader", move || {
+        run_submeter_worker(
+            Arc::clone(&reader),
+            devices.clone(),
+            sender.clone(),
+            interval_secs,
+        )
+    });
+    SubmeterChannel { receiver }
+}
+
+async fn run_submeter_worker(
+    reader: Arc<dyn PowerSensorReader>,
+    devices: Vec<SubmeterDevice>,
+    sender: crossbeam_channel::Sender<Vec<SubmeterReading>>,
+    interval_secs: u64,
+) {
+    let mut previous: HashMap<String, DateTime<Utc>> = HashMap::new();
+    loop {
+        let now = Utc::now();
+        let mut readings = Vec::with_capacity(devices.len());
+        for device in &devices {
+            match reader.read_power_w(&device.entity_id).await {
+                Ok(power_w) => {
+                    let last = previous.insert(device.name.clone(), now);
+                    readings.push(reading(device, now, power_w, last, interval_secs));
+                }
+                Err(e) => warn!("⚠️ Failed to read {}: {e:#}", device.entity_id),
+            }
+        }
+        debug!("🔌 {} sub-meter readings", readings.len());
+        if !readings.is_empty() {
+            let _ = sender.send(readings);
+        }
+
+        Delay::new(std::time::Duration::from_secs(interval_secs)).await;
+    }
+}
+
+/// Reading covering the time since `last`, at most two intervals
+fn reading(
+    device: &SubmeterDevice,
+    at: DateTime<Utc>,
+    power_w: f32,
+    last: Option<DateTime<Utc>>,
+    interval_secs: u64,
+) -> SubmeterReading {
+    let max_secs = i64::try_from(interval_secs.saturating_mul(2)).unwrap_or(i64::MAX);
+    let secs = last.map_or(0, |last| (at - last).num_seconds().clamp(0, max_secs));
+    #[expect(clippy::cast_precision_loss)]
+    let hours = secs as f32 / 3600.0;
+    SubmeterReading {
+        device: device.name.clone(),
+        timestamp: at,
+        power_w,
+        energy_kwh: power_w.max(0.0) / 1000.0 * hours,
+    }
+}
+
+/// Update system: store readings from the worker in the historian
+pub fn submeter_system(channel: Res<SubmeterChannel>, historian: Res<Historian>) {
+    while let Ok(readings) = channel.receiver.try_recv() {
+        if let Err(e) = historian.record_submeter(&readings) {
+            warn!("Failed to store sub-meter readings: {e:#}");
+        }
+    }
+}
+
+/// Energy of one reading with the price it was billed at
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubmeterEnergy {
+    pub device: String,
+    pub timestamp: DateTime<Utc>,
+    pub energy_kwh: f32,
+    /// Spot price in effect at the reading, `None` before the first stored price
+    pub price_czk_per_kwh: Option<f32>,
+}
+
+/// Energy and cost of one appliance over a period
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DeviceUsage {
+    pub device: String,
+    /// First local date of the period
+    pub period_start: NaiveDate,
+    pub energy_kwh: f32,
+    pub cost_czk: f32,
+    /// Energy read while no price was known, not included in the cost
+    pub unpriced_kwh: f32,
+}
+
+impl DeviceUsage {
+    fn add(&mut self, energy: &SubmeterEnergy) {
+        self.energy_kwh += energy.energy_kwh;
+        match energy.price_czk_per_kwh {
+            Some(price) => self.cost_czk += energy.energy_kwh * price,
+            None => self.unpriced_kwh += energy.energy_kwh,
+        }
+    }
+}
+
+/// Sum `energy` per device and local day, week or month, oldest period first
+#[must_use]
+pub fn summarize(
+    energy: &[SubmeterEnergy],
+    period: SavingsPeriod,
+    formatter: &TimeFormatter,
+) -> Vec<DeviceUsage> {
+    let mut usage: Vec<DeviceUsage> = Vec::new();
+    for reading in energy {
+        let start = period.start_of(formatter.local_date(reading.timestamp));
+        match usage
+            .iter_mut()
+            .find(|u| u.period_start == start && u.device == reading.device)
+        {
+            Some(entry) => entry.add(reading),
+            None => {
+                let mut entry = DeviceUsage {
+                    device: reading.device.clone(),
+                    period_start: start,
+                    energy_kwh: 0.0,
+                    cost_czk: 0.0,
+                    unpriced_kwh: 0.0,
+                };
+                entry.add(reading);
+                usage.push(entry);
+            }
+        }
+    }
+    usage.sort_by(|a, b| {
+        a.period_start
+            .cmp(&b.period_start)
+            .then_with(|| a.device.cmp(&b.device))
+    });
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn device(name: &str) -> SubmeterDevice {
+        SubmeterDevice {
+            name: name.to_owned(),
+            entity_id: format!("sensor.{}_power", name.to_lowercase()),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut config = SubmeterConfig {
+            enabled: true,
+            devices: vec![device("Boiler")],
+            ..SubmeterConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.devices.push(device("Boiler"));
+        assert!(config.validate().unwrap_err().contains("used twice"));
+
+        config.devices[1] = SubmeterDevice {
+            name: "EV".to_owned(),
+            entity_id: "switch.ev".to_owned(),
+        };
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .contains("devices[1].entity_id")
+        );
+    }
+
+    #[test]
+    fn test_reading_energy_is_capped_after_a_gap() {
+        let at: DateTime<Utc> = "2025-06-01T12:00:00Z".parse().unwrap();
+        let boiler = device("Boiler");
+
+        let first = reading(&boiler, at, 2000.0, None, 60);
+        assert_eq!(first.energy_kwh, 0.0);
+        let regular = reading(&boiler, at, 2000.0, Some(at - Duration::seconds(90)), 60);
+        assert_eq!(regular.energy_kwh, 0.05);
+        let after_outage = reading(&boiler, at, 2000.0, Some(at - Duration::hours(3)), 60);
+        assert_eq!(after_outage.energy_kwh, 2000.0 / 1000.0 * 120.0 / 3600.0);
+    }
+
+    #[test]
+    fn test_summarize_per_local_day() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        let energy = |device: &str, at: &str, kwh: f32, price: Option<f32>| SubmeterEnergy {
+            device: device.to_owned(),
+            timestamp: at.parse().unwrap(),
+            energy_kwh: kwh,
+            price_czk_per_kwh: price,
+        };
+        // 23:30 UTC is already the next day in Prague
+        let readings = [
+            energy("Boiler", "2025-06-01T10:00:00Z", 1.0, Some(3.0)),
+            energy("Boiler", "2025-06-01T23:30:00Z", 2.0, Some(1.0)),
+            energy("EV", "2025-06-01T11:00:00Z", 4.0, None),
+        ];
+
+        let days = summarize(&readings, SavingsPeriod::Day, &prague);
+        let june = |day| NaiveDate::from_ymd_opt(2025, 6, day).unwrap();
+        assert_eq!(days.len(), 3);
+        assert_eq!(
+            (days[0].device.as_str(), days[0].period_start),
+            ("Boiler", june(1))
+        );
+        assert_eq!(days[0].cost_czk, 3.0);
+        assert_eq!((days[1].device.as_str(), days[1].unpriced_kwh), ("EV", 4.0));
+        assert_eq!((days[2].period_start, days[2].cost_czk), (june(2), 2.0));
+
+        let months = summarize(&readings, SavingsPeriod::Month, &prague);
+        assert_eq!(months.len(), 2);
+        assert_eq!(months[0].energy_kwh, 3.0);
+    }
+}
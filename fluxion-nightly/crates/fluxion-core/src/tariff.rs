@@ -0,0 +1,367 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! HDO tariff schedule for pricing, planning and the dashboard.
+//!
+//! The configured [`HdoSource`] is resolved into a [`TariffSchedule`] of the
+//! local days from today on:
+//!
+//! - `sensor`: the distributor's schedule read from `hdo_sensor_entity`, either
+//!   dated (ČEZ `signals`), weekly tables (EG.D `sazby`) or a plain list of
+//!   `HH:MM-HH:MM` ranges repeated every day (e.g. PRE integrations)
+//! - `manual`: the weekly `hdo_windows` from the config
+//! - `preset`: the typical windows of the `hdo_preset` rate
+//!
+//! Blocks outside the schedule have an unknown tariff and are priced with the
+//! high tariff fee.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, Utc};
+use fluxion_types::config::{ControlConfig, PricingConfig};
+use fluxion_types::tariff::{HdoSource, TariffPeriod, TariffSchedule, TariffWindow};
+use tracing::debug;
+
+use crate::async_systems::HdoScheduleData;
+use crate::strategy::pricing::{
+    end_of_day_as_midnight, parse_hdo_sensor_data, parse_hdo_weekly_tables,
+};
+use crate::time_format::TimeFormatter;
+
+/// Local days covered, starting today (covers the price horizon)
+const HORIZON_DAYS: usize = 3;
+
+/// Resolve the configured HDO source into the tariff schedule around `now`
+pub fn tariff_schedule(
+    pricing: &PricingConfig,
+    hdo_data: Option<&HdoScheduleData>,
+    formatter: &TimeFormatter,
+    now: DateTime<Utc>,
+) -> TariffSchedule {
+    let dates: Vec<NaiveDate> = formatter
+        .local_date(now)
+        .iter_days()
+        .take(HORIZON_DAYS)
+        .collect();
+
+    let schedule = match pricing.hdo_source {
+        HdoSource::Manual => weekly(&pricing.hdo_windows, &dates, formatter),
+        HdoSource::Preset => weekly(&pricing.hdo_preset.windows(), &dates, formatter),
+        HdoSource::Sensor => hdo_data
+            .map(|data| from_sensor(data, &dates, formatter))
+            .unwrap_or_default(),
+    };
+    TariffSchedule {
+        source: pricing.hdo_source,
+        ..schedule
+    }
+}
+
+/// Control config carrying the tariff schedule for planning
+#[must_use]
+pub fn with_tariffs(
+    mut control_config: ControlConfig,
+    pricing: &PricingConfig,
+    hdo_data: Option<&HdoScheduleData>,
+    formatter: &TimeFormatter,
+    now: DateTime<Utc>,
+) -> ControlConfig {
+    control_config.tariff_schedule = tariff_schedule(pricing, hdo_data, formatter, now);
+    debug!(
+        "⚡ HDO tariff schedule ({:?}): {} low tariff periods over {} days",
+        control_config.tariff_schedule.source,
+        control_config.tariff_schedule.low_periods.len(),
+        control_config.tariff_schedule.days.len()
+    );
+    control_config
+}
+
+/// Low tariff periods of the local `date` as local "HH:MM" pairs, for display
+pub fn low_tariff_periods_on(
+    schedule: &TariffSchedule,
+    date: NaiveDate,
+    formatter: &TimeFormatter,
+) -> Vec<(String, String)> {
+    let day = formatter.energy_day(date);
+    schedule
+        .low_periods
+        .iter()
+        .filter(|period| period.start < day.end && day.start < period.end)
+        .map(|period| {
+            let start = period.start.max(day.start);
+            let end = period.end.min(day.end);
+            let end = if end == day.end {
+                "24:00".to_owned()
+            } else {
+                formatter.hour_minute(end)
+            };
+            (formatter.hour_minute(start), end)
+        })
+        .collect()
+}
+
+/// Schedule of weekly windows over `dates`
+fn weekly(
+    windows: &[TariffWindow],
+    dates: &[NaiveDate],
+    formatter: &TimeFormatter,
+) -> TariffSchedule {
+    // Windows starting the day before may run past midnight into the first day
+    let first = dates.first().and_then(|date| date.pred_opt());
+    let periods = first
+        .into_iter()
+        .chain(dates.iter().copied())
+        .flat_map(|date| {
+            windows
+                .iter()
+                .filter(move |window| window.applies_on(date.weekday()))
+                .filter_map(move |window| window_period(window, date, formatter))
+        })
+        .collect();
+
+    build(dates, periods, formatter)
+}
+
+/// Schedule from the sensor data, in the most specific format it carries
+fn from_sensor(
+    data: &HdoScheduleData,
+    dates: &[NaiveDate],
+    formatter: &TimeFormatter,
+) -> TariffSchedule {
+    if let Some(raw) = &data.raw_data {
+        let dated = parse_hdo_sensor_data(raw);
+        if !dated.is_empty() {
+            let known: Vec<NaiveDate> = dates
+                .iter()
+                .copied()
+                .filter(|date| dated.iter().any(|day| day.date == *date))
+                .collect();
+            let periods = dated
+                .iter()
+                .flat_map(|day| {
+                    day.low_tariff_ranges.iter().filter_map(|range| {
+                        let window = TariffWindow {
+                            days: Vec::new(),
+                            start: range.start,
+                            end: end_of_day_as_midnight(range.end),
+                        };
+                        window_period(&window, day.date, formatter)
+                    })
+                })
+                .collect();
+            return build(&known, periods, formatter);
+        }
+
+        let tables = parse_hdo_weekly_tables(raw);
+        if !tables.is_empty() {
+            return weekly(&tables, dates, formatter);
+        }
+    }
+
+    let daily: Vec<TariffWindow> = data
+        .low_tariff_periods
+        .iter()
+        .filter_map(|(start, end)| {
+            Some(TariffWindow {
+                days: Vec::new(),
+                start: parse_hour_minute(start)?,
+                end: parse_hour_minute(end)?,
+            })
+        })
+        .collect();
+    if daily.is_empty() {
+        return TariffSchedule::default();
+    }
+    weekly(&daily, dates, formatter)
+}
+
+/// "HH:MM", with "24:00" as midnight
+fn parse_hour_minute(s: &str) -> Option<NaiveTime> {
+    match s.trim() {
+        "24:00" => Some(NaiveTime::MIN),
+        s => NaiveTime::parse_from_str(s, "%H:%M").ok(),
+    }
+}
+
+/// Absolute period of `window` starting on the local `date`
+fn window_period(
+    window: &TariffWindow,
+    date: NaiveDate,
+    formatter: &TimeFormatter,
+) -> Option<TariffPeriod> {
+    let end_date = if window.wraps_midnight() {
+        date.succ_opt()?
+    } else {
+        date
+    };
+    let start = local_instant(date, window.start, formatter)?;
+    let end = local_instant(end_date, window.end, formatter)?;
+    (start < end).then_some(TariffPeriod { start, end })
+}
+
+/// `time` on the local `date`; in a DST gap, when the clock resumes
+fn local_instant(
+    date: NaiveDate,
+    time: NaiveTime,
+    formatter: &TimeFormatter,
+) -> Option<DateTime<Utc>> {
+    if time == NaiveTime::MIN {
+        return Some(formatter.energy_day(date).start);
+    }
+    let local = date.and_time(time);
+    formatter
+        .local_to_utc(local)
+        .or_else(|| formatter.local_to_utc(local + Duration::hours(1)))
+}
+
+/// Schedule covering `dates`, with `periods` merged and sorted
+fn build(
+    dates: &[NaiveDate],
+    mut periods: Vec<TariffPeriod>,
+    formatter: &TimeFormatter,
+) -> TariffSchedule {
+    periods.sort_by_key(|period| period.start);
+    let mut low_periods: Vec<TariffPeriod> = Vec::with_capacity(periods.len());
+    for period in periods {
+        match low_periods.last_mut() {
+            Some(last) if period.start <= last.end => last.end = last.end.max(period.end),
+            _ => low_periods.push(period),
+        }
+    }
+
+    TariffSchedule {
+        source: HdoSource::default(),
+        days: dates
+            .iter()
+            .map(|date| {
+                let day = formatter.energy_day(*date);
+                TariffPeriod {
+                    start: day.start,
+                    end: day.end,
+                }
+            })
+            .collect(),
+        low_periods,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_types::tariff::{HdoPreset, Tariff};
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    fn pricing(source: HdoSource) -> PricingConfig {
+        PricingConfig {
+            spot_price_entity: String::new(),
+            tomorrow_price_entity: None,
+            use_spot_prices_to_buy: true,
+            use_spot_prices_to_sell: false,
+            fixed_buy_price_czk: fluxion_types::config::PriceSchedule::default(),
+            fixed_sell_price_czk: fluxion_types::config::PriceSchedule::default(),
+            spot_buy_fee_czk: 0.0,
+            spot_sell_fee_czk: 0.0,
+            hdo_sensor_entity: String::new(),
+            hdo_low_tariff_czk: 0.5,
+            hdo_high_tariff_czk: 1.8,
+            hdo_source: source,
+            hdo_preset: HdoPreset::D25d,
+            hdo_windows: vec![TariffWindow {
+                days: vec![chrono::Weekday::Sat],
+                start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_manual_windows_follow_local_clock_past_midnight() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        // Saturday 2025-01-11, Prague is UTC+1
+        let schedule = tariff_schedule(
+            &pricing(HdoSource::Manual),
+            None,
+            &prague,
+            at("2025-01-11T12:00:00Z"),
+        );
+
+        assert_eq!(schedule.source, HdoSource::Manual);
+        assert_eq!(schedule.days.len(), 3);
+        assert_eq!(
+            schedule.low_periods,
+            vec![TariffPeriod {
+                start: at("2025-01-11T21:00:00Z"),
+                end: at("2025-01-12T05:00:00Z"),
+            }]
+        );
+        assert_eq!(
+            schedule.tariff_at(at("2025-01-12T04:45:00Z")),
+            Some(Tariff::Low)
+        );
+        assert_eq!(
+            schedule.tariff_at(at("2025-01-12T21:00:00Z")),
+            Some(Tariff::High)
+        );
+        assert_eq!(schedule.tariff_at(at("2025-01-15T02:00:00Z")), None);
+        assert_eq!(
+            low_tariff_periods_on(&schedule, "2025-01-12".parse().unwrap(), &prague),
+            vec![("00:00".to_owned(), "06:00".to_owned())]
+        );
+    }
+
+    #[test]
+    fn test_preset_and_sensor_sources() {
+        let utc = TimeFormatter::default();
+        let now = at("2025-01-10T12:00:00Z");
+
+        let preset = tariff_schedule(&pricing(HdoSource::Preset), None, &utc, now);
+        assert_eq!(
+            preset.tariff_at(at("2025-01-10T13:30:00Z")),
+            Some(Tariff::Low)
+        );
+        assert_eq!(
+            preset.tariff_at(at("2025-01-10T12:30:00Z")),
+            Some(Tariff::High)
+        );
+
+        // Dated ČEZ signals cover only the dates they list
+        let cez = HdoScheduleData {
+            raw_data: Some(
+                r#"{"data": {"signals": [{"datum": "10.01.2025", "casy": "17:00-24:00"}]}}"#
+                    .to_owned(),
+            ),
+            ..HdoScheduleData::default()
+        };
+        let sensor = tariff_schedule(&pricing(HdoSource::Sensor), Some(&cez), &utc, now);
+        assert_eq!(sensor.days.len(), 1);
+        assert_eq!(
+            sensor.tariff_at(at("2025-01-10T23:45:00Z")),
+            Some(Tariff::Low)
+        );
+        assert_eq!(sensor.tariff_at(at("2025-01-11T00:15:00Z")), None);
+
+        // Plain ranges repeat every day
+        let plain = HdoScheduleData {
+            low_tariff_periods: vec![("00:00".to_owned(), "06:00".to_owned())],
+            ..HdoScheduleData::default()
+        };
+        let sensor = tariff_schedule(&pricing(HdoSource::Sensor), Some(&plain), &utc, now);
+        assert_eq!(
+            sensor.tariff_at(at("2025-01-12T05:00:00Z")),
+            Some(Tariff::Low)
+        );
+
+        let unknown = tariff_schedule(&pricing(HdoSource::Sensor), None, &utc, now);
+        assert!(unknown.is_empty());
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Supervisor for long-running background tasks.
+//!
+//! Background loops (scheduled export, heartbeat, HA fetchers) used to be plain
+//! `tokio::spawn` calls, so a panic silently killed them. Tasks spawned through the
+//! supervisor are tracked by name, restarted with exponential backoff after a panic,
+//! and their status is available for health reporting.
+
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// First restart delay after a panic
+const INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+
+/// Upper bound for the restart delay
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// A task running at least this long is considered healthy again and its backoff resets
+const STABLE_RUN: Duration = Duration::from_secs(600);
+
+/// Lifecycle state of a supervised task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    /// Panicked, waiting for the backoff before restarting
+    Restarting,
+    /// Returned normally (e.g. feature disabled at runtime); not restarted
+    Finished,
+}
+
+/// Status of a supervised task, as reported by the health endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskStatus {
+    pub name: String,
+    pub state: TaskState,
+    pub restarts: u32,
+    pub started_at: DateTime<Utc>,
+    pub last_panic: Option<String>,
+    pub last_panic_at: Option<DateTime<Utc>>,
+}
+
+/// Tracks named background tasks and restarts them when they panic
+#[derive(Debug, Clone, Default)]
+pub struct TaskSupervisor {
+    tasks: Arc<RwLock<BTreeMap<String, TaskStatus>>>,
+}
+
+impl TaskSupervisor {
+    /// Process-wide supervisor shared by all crates
+    pub fn global() -> &'static Self {
+        static GLOBAL: OnceLock<TaskSupervisor> = OnceLock::new();
+        GLOBAL.get_or_init(Self::default)
+    }
+
+    /// Spawn a supervised task on the current tokio runtime.
+    ///
+    /// `factory` is called again for every restart, so it must produce a fresh future
+    /// from cloned inputs each time.
+    pub fn spawn<F, Fut>(&self, name: impl Into<String>, factory: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.into();
+        let tasks = Arc::clone(&self.tasks);
+
+        tasks.write().insert(
+            name.clone(),
+            TaskStatus {
+                name: name.clone(),
+                state: TaskState::Running,
+                restarts: 0,
+                started_at: Utc::now(),
+                last_panic: None,
+                last_panic_at: None,
+            },
+        );
+
+        tokio::spawn(async move {
+            let mut backoff = INITIAL_BACKOFF;
+            loop {
+                let started = Instant::now();
+                let result = tokio::spawn(factory()).await;
+
+                let Err(join_error) = result else {
+                    info!("🧵 Background task '{name}' finished");
+                    update(&tasks, &name, |s| s.state = TaskState::Finished);
+                    return;
+                };
+
+                if !join_error.is_panic() {
+                    // Cancelled (runtime shutting down)
+                    update(&tasks, &name, |s| s.state = TaskState::Finished);
+                    return;
+                }
+
+                let message = panic_message(join_error.into_panic());
+                if started.elapsed() >= STABLE_RUN {
+                    backoff = INITIAL_BACKOFF;
+                }
+                error!(
+                    "💥 Background task '{name}' panicked: {message} - restarting in {}s",
+                    backoff.as_secs()
+                );
+                update(&tasks, &name, |s| {
+                    s.state = TaskState::Restarting;
+                    s.last_panic = Some(message.clone());
+                    s.last_panic_at = Some(Utc::now());
+                });
+
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+
+                warn!("🔁 Restarting background task '{name}'");
+                update(&tasks, &name, |s| {
+                    s.state = TaskState::Running;
+                    s.restarts += 1;
+                    s.started_at = Utc::now();
+                });
+            }
+        });
+    }
+
+    /// Status of all tasks, ordered by name
+    pub fn snapshot(&self) -> Vec<TaskStatus> {
+        self.tasks.read().values().cloned().collect()
+    }
+
+    /// True when every task is running or finished normally
+    pub fn all_healthy(&self) -> bool {
+        self.tasks
+            .read()
+            .values()
+            .all(|s| s.state != TaskState::Restarting)
+    }
+}
+
+fn update(
+    tasks: &RwLock<BTreeMap<String, TaskStatus>>,
+    name: &str,
+    f: impl FnOnce(&mut TaskStatus),
+) {
+    if let Some(status) = tasks.write().get_mut(name) {
+        f(status);
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_owned()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test(start_paused = true)]
+    async fn test_panicking_task_is_restarted() {
+        let supervisor = TaskSupervisor::default();
+        let runs = Arc::new(AtomicU32::new(0));
+
+        let runs_clone = Arc::clone(&runs);
+        supervisor.spawn("flaky", move || {
+            let runs = Arc::clone(&runs_clone);
+            async move {
+                if runs.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("boom");
+                }
+            }
+        });
+
+        // Let the first run panic, then advance past the backoff
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let status = &supervisor.snapshot()[0];
+        assert_eq!(status.state, TaskState::Restarting);
+        assert_eq!(status.last_panic.as_deref(), Some("boom"));
+        assert!(!supervisor.all_healthy());
+
+        tokio::time::sleep(INITIAL_BACKOFF * 2).await;
+        let status = &supervisor.snapshot()[0];
+        assert_eq!(status.state, TaskState::Finished);
+        assert_eq!(status.restarts, 1);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert!(supervisor.all_healthy());
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Timezone-aware formatting of timestamps for the UI, exports and API responses.
+//!
+//! All timestamps are stored in UTC. [`TimeFormatter`] converts them to the
+//! Home Assistant timezone from [`TimezoneConfig`] (UTC when unknown), so every
+//! page, chart label and export file uses the same clock. The ECS keeps a
+//! [`TimeFormatter`] resource in sync with [`TimezoneConfig`]; the web layer
+//! rebuilds it from [`WebQueryResponse::timezone`](crate::WebQueryResponse).
+
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, NaiveTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::resources::TimezoneConfig;
+
+/// Formats UTC timestamps in the configured timezone
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimeFormatter {
+    tz: Option<Tz>,
+}
+
+impl TimeFormatter {
+    /// Formatter for `tz`, or UTC when `None`
+    #[must_use]
+    pub fn new(tz: Option<Tz>) -> Self {
+        Self { tz }
+    }
+
+    /// Formatter for an IANA timezone name; unknown names fall back to UTC
+    #[must_use]
+    pub fn from_timezone_name(name: Option<&str>) -> Self {
+        Self::new(name.and_then(|name| name.parse::<Tz>().ok()))
+    }
+
+    /// IANA name of the timezone, `None` when formatting in UTC
+    #[must_use]
+    pub fn timezone_name(&self) -> Option<&'static str> {
+        self.tz.map(|tz| tz.name())
+    }
+
+    /// Convert a UTC timestamp to local time
+    #[must_use]
+    pub fn to_local(&self, time: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self.tz {
+            Some(tz) => time.with_timezone(&tz).fixed_offset(),
+            None => time.fixed_offset(),
+        }
+    }
+
+    /// Current local time
+    #[must_use]
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.to_local(Utc::now())
+    }
+
+    /// Offset from UTC at `time` in minutes (positive east of Greenwich)
+    #[must_use]
+    pub fn utc_offset_minutes(&self, time: DateTime<Utc>) -> i32 {
+        self.to_local(time).offset().fix().local_minus_utc() / 60
+    }
+
+    /// `2025-03-30 14:05:00`
+    #[must_use]
+    pub fn date_time(&self, time: DateTime<Utc>) -> String {
+        self.to_local(time).format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+
+    /// `14:05:00`
+    #[must_use]
+    pub fn time(&self, time: DateTime<Utc>) -> String {
+        self.to_local(time).format("%H:%M:%S").to_string()
+    }
+
+    /// `14:05`
+    #[must_use]
+    pub fn hour_minute(&self, time: DateTime<Utc>) -> String {
+        self.to_local(time).format("%H:%M").to_string()
+    }
+
+    /// Chart axis label: `03-30 14:05`
+    #[must_use]
+    pub fn chart_label(&self, time: DateTime<Utc>) -> String {
+        self.to_local(time).format("%m-%d %H:%M").to_string()
+    }
+
+    /// Timestamp for file names: `20250330_140500`
+    #[must_use]
+    pub fn file_stamp(&self, time: DateTime<Utc>) -> String {
+        self.to_local(time).format("%Y%m%d_%H%M%S").to_string()
+    }
+
+    /// RFC 3339 with the local offset: `2025-03-30T14:05:00+02:00`
+    #[must_use]
+    pub fn rfc3339(&self, time: DateTime<Utc>) -> String {
+        self.to_local(time).to_rfc3339()
+    }
+
+    /// Next occurrence of the local wall-clock `time` strictly after `after`
+    ///
+    /// Days where `time` does not exist (DST gap) are skipped.
+    #[must_use]
+    pub fn next_daily(&self, time: NaiveTime, after: DateTime<Utc>) -> DateTime<Utc> {
+        let today = self.to_local(after).date_naive();
+        (0..=2)
+            .filter_map(|days| self.local_to_utc(today.and_time(time) + Duration::days(days)))
+            .find(|candidate| *candidate > after)
+            .unwrap_or(after + Duration::days(1))
+    }
+
+    pub(crate) fn local_to_utc(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self.tz {
+            Some(tz) => tz
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|t| t.with_timezone(&Utc)),
+            None => Some(Utc.from_utc_datetime(&local)),
+        }
+    }
+}
+
+impl From<&TimezoneConfig> for TimeFormatter {
+    fn from(config: &TimezoneConfig) -> Self {
+        Self::new(config.tz)
+    }
+}
+
+/// Keep the [`TimeFormatter`] resource in sync with [`TimezoneConfig`]
+pub fn sync_time_formatter_system(
+    timezone_config: Res<TimezoneConfig>,
+    mut formatter: ResMut<TimeFormatter>,
+) {
+    let updated = TimeFormatter::from(&*timezone_config);
+    if *formatter != updated {
+        *formatter = updated;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_formats_in_configured_timezone() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        let time = utc("2025-07-01T12:05:00Z");
+
+        assert_eq!(prague.date_time(time), "2025-07-01 14:05:00");
+        assert_eq!(prague.chart_label(time), "07-01 14:05");
+        assert_eq!(prague.file_stamp(time), "20250701_140500");
+        assert_eq!(prague.rfc3339(time), "2025-07-01T14:05:00+02:00");
+        assert_eq!(prague.utc_offset_minutes(time), 120);
+        assert_eq!(prague.timezone_name(), Some("Europe/Prague"));
+    }
+
+    #[test]
+    fn test_unknown_timezone_falls_back_to_utc() {
+        let formatter = TimeFormatter::from_timezone_name(Some("Mars/Olympus"));
+        let time = utc("2025-01-15T08:30:00Z");
+
+        assert_eq!(formatter, TimeFormatter::default());
+        assert_eq!(formatter.time(time), "08:30:00");
+        assert_eq!(formatter.utc_offset_minutes(time), 0);
+    }
+
+    #[test]
+    fn test_next_daily_uses_local_clock() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        let export_time = NaiveTime::from_hms_opt(23, 55, 0).unwrap();
+
+        // 20:00 local, still ahead today
+        let next = prague.next_daily(export_time, utc("2025-01-15T19:00:00Z"));
+        assert_eq!(next, utc("2025-01-15T22:55:00Z"));
+
+        // 23:56 local, already passed
+        let next = prague.next_daily(export_time, utc("2025-01-15T22:56:00Z"));
+        assert_eq!(next, utc("2025-01-16T22:55:00Z"));
+    }
+
+    #[test]
+    fn test_next_daily_skips_dst_gap() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        // 02:30 does not exist on 2025-03-30 in Prague
+        let time = NaiveTime::from_hms_opt(2, 30, 0).unwrap();
+
+        let next = prague.next_daily(time, utc("2025-03-29T23:00:00Z"));
+        assert_eq!(next, utc("2025-03-31T00:30:00Z"));
+    }
+}
@@ -23,13 +23,15 @@ pub use fluxion_types::inverter::InverterType;
 pub struct EntityChange {
     /// Entity ID (e.g., "select.solax_charger_use_mode")
     pub entity_id: String,
-    /// Option value to set (e.g., "Self Use Mode")
+    /// Value to set, interpreted by the entity's domain:
+    /// a select option (e.g., "Self Use Mode"), a number value (e.g., "100"),
+    /// or "on"/"off" for a switch
     pub option: String,
 }
 
 /// Represents all entity changes needed for a mode change
 /// Vendors can return multiple entity changes that should be executed in sequence
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ModeChangeRequest {
     /// List of entity changes to execute in order
     /// For Solax: [charger_use_mode, manual_mode_select]
@@ -196,6 +198,10 @@ pub struct GenericInverterState {
 
     /// DC bus voltage (V)
     pub bus_voltage_v: Option<f32>,
+
+    // ============= Source =============
+    /// Telemetry source the state was read from, when several are configured
+    pub telemetry_source: Option<String>,
 }
 
 /// Generic data source for reading inverter state
@@ -213,6 +219,11 @@ pub trait InverterDataSource: Send + Sync {
 
     /// Get data source name for logging
     fn name(&self) -> &str;
+
+    /// Whether [`InverterCommand::SetChargePowerLimit`] can be applied to this inverter
+    fn supports_charge_power_limit(&self, _inverter_id: &str) -> bool {
+        false
+    }
 }
 
 /// Generic data source for reading price data
@@ -308,10 +319,29 @@ pub trait VendorEntityMapper: Send + Sync {
     /// Example: "sensor.{inverter_id}_pv_power_total"
     fn get_pv_power_entity(&self, inverter_id: &str) -> String;
 
+    /// Whether the grid power sensor reports export as positive (FluxION convention).
+    /// Vendors that report import as positive return false and get their readings negated.
+    fn grid_power_export_positive(&self) -> bool {
+        true
+    }
+
+    /// Whether the battery power sensor reports charging as positive (FluxION convention).
+    /// Vendors that report discharge as positive return false and get their readings negated.
+    fn battery_power_charge_positive(&self) -> bool {
+        true
+    }
+
     /// Get the entity ID for export power limit control (Required)
     /// Example: "number.{inverter_id}_export_control_user_limit"
     fn get_export_limit_entity(&self, inverter_id: &str) -> String;
 
+    /// Get the entity ID for battery charge current limit control (Optional)
+    /// Example: "number.{inverter_id}_battery_charge_max_current"
+    /// Required for partial-power force charging; None = not supported
+    fn get_charge_current_limit_entity(&self, _inverter_id: &str) -> Option<String> {
+        None
+    }
+
     // ============= Extended PV (Optional) =============
 
     /// Get entity ID for individual PV string 1 power
@@ -596,3 +626,44 @@ pub trait ConsumptionHistoryDataSource: Send + Sync {
     /// Get data source name for logging
     fn name(&self) -> &str;
 }
+
+// ============= Solar Forecast Traits =============
+
+/// Forecast solar production for one time block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SolarForecastBlock {
+    /// Start of the block
+    pub block_start: chrono::DateTime<chrono::Utc>,
+    /// Block length in minutes
+    pub duration_minutes: u32,
+    /// Expected production of all PV strings in the block (kWh)
+    pub kwh: f32,
+}
+
+/// Trait for solar forecast providers queried directly instead of through HA
+#[async_trait]
+pub trait SolarForecastDataSource: Send + Sync {
+    /// Read the per-block production forecast, sorted by block start
+    async fn read_forecast(&self) -> Result<Vec<SolarForecastBlock>>;
+
+    /// Check if data source is available
+    async fn health_check(&self) -> Result<bool>;
+
+    /// Get data source name for logging
+    fn name(&self) -> &str;
+}
+
+// ============= Weather Forecast Traits =============
+
+/// Trait for weather forecast providers (HA weather entity, Open-Meteo)
+#[async_trait]
+pub trait WeatherDataSource: Send + Sync {
+    /// Read the hourly weather forecast, sorted by time
+    async fn read_forecast(&self) -> Result<Vec<fluxion_types::weather::WeatherPoint>>;
+
+    /// Check if data source is available
+    async fn health_check(&self) -> Result<bool>;
+
+    /// Get data source name for logging
+    fn name(&self) -> &str;
+}
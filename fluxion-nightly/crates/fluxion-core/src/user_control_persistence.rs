@@ -220,4 +220,27 @@ mod tests {
         let loaded = persistence.load().unwrap();
         assert!(loaded.fixed_time_slots.is_empty());
     }
+
+    #[test]
+    fn test_safe_state_survives_reload() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("user_control.json");
+        let persistence = UserControlPersistence::new(path);
+
+        let state = UserControlState {
+            safe_state: Some(fluxion_types::SafeStateActivation::new(
+                "electrician",
+                None,
+                Some("Working on DB board".to_string()),
+            )),
+            ..Default::default()
+        };
+
+        persistence.save(&state).unwrap();
+        let loaded = persistence.load().unwrap();
+
+        let safe_state = loaded.safe_state.expect("safe state should persist");
+        assert_eq!(safe_state.triggered_by, "electrician");
+        assert!(safe_state.mode.is_none());
+    }
 }
@@ -0,0 +1,281 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Weather forecast for planning.
+//!
+//! A worker polls the configured [`WeatherDataSource`](crate::traits::WeatherDataSource)
+//! into the [`WeatherForecast`] resource. Before a schedule is generated the
+//! forecast is summarized per local day and put into the planning
+//! [`ControlConfig`], where the scheduler finds it with
+//! [`ControlConfig::weather_reserve_soc_at`] and [`ControlConfig::clear_morning_at`].
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Duration, NaiveTime, Timelike, Utc};
+use fluxion_plugins::WeatherConditions;
+use fluxion_types::config::ControlConfig;
+use fluxion_types::weather::{DayWeather, WeatherOutlook, WeatherPlanningConfig, WeatherPoint};
+use futures_timer::Delay;
+use tracing::{debug, info, warn};
+
+use crate::resources::{PriceCache, WeatherDataSourceResource};
+use crate::time_format::TimeFormatter;
+
+/// Days summarized for planning, starting today (covers the price horizon)
+const HORIZON_DAYS: usize = 3;
+
+/// Local hours whose cloud cover decides whether a day is clear or cloudy
+const DAYLIGHT_HOURS: Range<u32> = 8..16;
+
+/// Latest weather forecast, empty until the first successful fetch
+#[derive(Resource, Debug, Default)]
+pub struct WeatherForecast {
+    /// Forecast points sorted by time
+    pub points: Vec<WeatherPoint>,
+    pub last_updated: Option<DateTime<Utc>>,
+}
+
+/// Receives forecasts from the weather worker
+#[derive(Resource)]
+pub struct WeatherChannel {
+    pub receiver: crossbeam_channel::Receiver<Vec<WeatherPoint>>,
+}
+
+/// Spawns the worker polling the weather forecast provider
+pub fn spawn_weather_worker(
+    source: &WeatherDataSourceResource,
+    fetch_interval_seconds: u64,
+) -> WeatherChannel {
+    let source = source.0.clone();
+    let (sender, receiver) = crossbeam_channel::bounded(2);
+    info!("🌦️ Spawning weather forecast fetcher ({})", source.name());
+
+    crate::TaskSupervisor::global().spawn("weather_fetcher", move || {
+        run_weather_worker(source.clone(), sender.clone(), fetch_interval_seconds)
+    });
+    WeatherChannel { receiver }
+}
+
+async fn run_weather_worker(
+    source: Arc<dyn crate::traits::WeatherDataSource>,
+    sender: crossbeam_channel::Sender<Vec<WeatherPoint>>,
+    fetch_interval_seconds: u64,
+) {
+    loop {
+        match source.read_forecast().await {
+            Ok(points) => {
+                debug!(
+                    "🌦️ Weather forecast from {}: {} points",
+                    source.name(),
+                    points.len()
+                );
+                let _ = sender.send(points);
+            }
+            Err(e) => warn!(
+                "⚠️ Failed to read weather forecast from {}: {e:#}",
+                source.name()
+            ),
+        }
+
+        Delay::new(std::time::Duration::from_secs(
+            fetch_interval_seconds.max(1),
+        ))
+        .await;
+    }
+}
+
+/// Update system: move fetched forecasts into [`WeatherForecast`]
+///
+/// A forecast that differs from the previous one requests a replan.
+pub fn poll_weather_channel(
+    channel: Res<WeatherChannel>,
+    mut forecast: ResMut<WeatherForecast>,
+    price_cache: Option<Res<PriceCache>>,
+) {
+    while let Ok(points) = channel.receiver.try_recv() {
+        if forecast.points.is_empty() && !points.is_empty() {
+            info!("🌦️ Weather forecast received ({} points)", points.len());
+        }
+        if forecast.points != points
+            && let Some(cache) = &price_cache
+        {
+            cache.request_replan();
+        }
+        forecast.points = points;
+        forecast.last_updated = Some(Utc::now());
+    }
+}
+
+/// Per-day summary of `points` for the local days starting with the day of `now`
+///
+/// Days without any forecast point are left out.
+pub fn outlook(
+    points: &[WeatherPoint],
+    config: &WeatherPlanningConfig,
+    formatter: &TimeFormatter,
+    now: DateTime<Utc>,
+) -> WeatherOutlook {
+    let days = formatter
+        .local_date(now)
+        .iter_days()
+        .take(HORIZON_DAYS)
+        .filter_map(|date| {
+            let day = formatter.energy_day(date);
+            let day_points: Vec<_> = points
+                .iter()
+                .filter(|point| day.contains(point.time))
+                .collect();
+            if day_points.is_empty() {
+                return None;
+            }
+
+            let daylight_cover: Vec<f32> = day_points
+                .iter()
+                .filter(|point| DAYLIGHT_HOURS.contains(&formatter.to_local(point.time).hour()))
+                .filter_map(|point| point.cloud_cover_pct)
+                .collect();
+            #[expect(clippy::cast_precision_loss)]
+            let cloud_cover_pct = (!daylight_cover.is_empty())
+                .then(|| daylight_cover.iter().sum::<f32>() / daylight_cover.len() as f32);
+            let min_temperature_c = day_points
+                .iter()
+                .filter_map(|point| point.temperature_c)
+                .reduce(f32::min);
+            let morning_end = NaiveTime::from_hms_opt(config.morning_end_hour.min(23), 0, 0)
+                .and_then(|time| formatter.local_to_utc(date.and_time(time)))
+                .unwrap_or(day.start + Duration::hours(i64::from(config.morning_end_hour)));
+
+            Some(DayWeather {
+                date,
+                start: day.start,
+                end: day.end,
+                morning_end,
+                cloud_cover_pct,
+                min_temperature_c,
+            })
+        })
+        .collect();
+
+    WeatherOutlook {
+        points: points.to_vec(),
+        days,
+    }
+}
+
+/// Control config carrying the weather outlook for planning
+#[must_use]
+pub fn with_weather(
+    mut control_config: ControlConfig,
+    forecast: Option<&WeatherForecast>,
+    formatter: &TimeFormatter,
+    now: DateTime<Utc>,
+) -> ControlConfig {
+    let Some(forecast) = forecast.filter(|forecast| !forecast.points.is_empty()) else {
+        return control_config;
+    };
+
+    control_config.weather_outlook =
+        outlook(&forecast.points, &control_config.weather, formatter, now);
+    for day in &control_config.weather_outlook.days {
+        debug!(
+            "🌦️ {}: cloud cover {:?}%, min temperature {:?}°C",
+            day.date, day.cloud_cover_pct, day.min_temperature_c
+        );
+    }
+    control_config
+}
+
+/// Weather for a strategy evaluation of the block starting at `at`
+pub fn conditions_at(outlook: &WeatherOutlook, at: DateTime<Utc>) -> Option<WeatherConditions> {
+    let point = outlook.point_at(at);
+    let next_day = outlook.next_day_after(at);
+    if point.is_none() && next_day.is_none() {
+        return None;
+    }
+    Some(WeatherConditions {
+        cloud_cover_pct: point.and_then(|point| point.cloud_cover_pct),
+        temperature_c: point.and_then(|point| point.temperature_c),
+        next_day_cloud_cover_pct: next_day.and_then(|day| day.cloud_cover_pct),
+        next_day_min_temperature_c: next_day.and_then(|day| day.min_temperature_c),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    /// Hourly points over two Prague days (UTC+1 in January)
+    fn forecast(tomorrow_cover: f32, tomorrow_temperature: f32) -> WeatherForecast {
+        let start = at("2025-01-09T23:00:00Z");
+        let points = (0..48)
+            .map(|hour| {
+                let tomorrow = hour >= 24;
+                WeatherPoint {
+                    time: start + Duration::hours(hour),
+                    cloud_cover_pct: Some(if tomorrow { tomorrow_cover } else { 50.0 }),
+                    temperature_c: Some(if tomorrow { tomorrow_temperature } else { 5.0 }),
+                }
+            })
+            .collect();
+        WeatherForecast {
+            points,
+            last_updated: None,
+        }
+    }
+
+    #[test]
+    fn test_outlook_summarizes_local_days() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        let config = WeatherPlanningConfig::default();
+
+        let outlook = outlook(
+            &forecast(90.0, -4.0).points,
+            &config,
+            &prague,
+            at("2025-01-10T12:00:00Z"),
+        );
+
+        assert_eq!(outlook.days.len(), 2);
+        assert_eq!(outlook.days[0].start, at("2025-01-09T23:00:00Z"));
+        assert_eq!(outlook.days[0].morning_end, at("2025-01-10T09:00:00Z"));
+        assert_eq!(outlook.days[1].cloud_cover_pct, Some(90.0));
+        assert_eq!(outlook.days[1].min_temperature_c, Some(-4.0));
+    }
+
+    #[test]
+    fn test_reserve_before_cold_cloudy_day_and_clear_mornings() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        let now = at("2025-01-10T12:00:00Z");
+        let control = ControlConfig {
+            min_battery_soc: 10.0,
+            ..ControlConfig::default()
+        };
+
+        let cold = with_weather(control.clone(), Some(&forecast(90.0, -4.0)), &prague, now);
+        assert_eq!(cold.weather_reserve_soc_at(now), Some(30.0));
+        assert_eq!(cold.clear_morning_at(at("2025-01-11T06:00:00Z")), None);
+
+        let clear = with_weather(control.clone(), Some(&forecast(10.0, 2.0)), &prague, now);
+        assert_eq!(clear.weather_reserve_soc_at(now), None);
+        assert!(clear.clear_morning_at(at("2025-01-11T06:00:00Z")).is_some());
+        assert_eq!(clear.clear_morning_at(at("2025-01-11T11:00:00Z")), None);
+
+        let unchanged = with_weather(control, None, &prague, now);
+        assert_eq!(unchanged.weather_outlook, WeatherOutlook::default());
+    }
+}
@@ -13,6 +13,8 @@
 use bevy_ecs::prelude::*;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
 use tokio::sync::mpsc;
 use tracing::{debug, trace, warn};
 
@@ -21,13 +23,23 @@ use crate::{
     components::*,
     config_events::{ConfigUpdateEvent, UserControlUpdateEvent},
     debug::DebugModeConfig,
-    resources::{SystemConfig, TimezoneConfig},
+    resources::{HdoSource, SystemConfig, Tariff},
+    time_format::TimeFormatter,
 };
 
+/// Web queries waiting for the ECS before new ones are refused
+pub const WEB_QUERY_CAPACITY: usize = 32;
+
+/// How long a web query waits for the ECS to answer
+pub const WEB_QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// The ECS counts as not keeping up for this long after a refused or timed-out query
+const OVERLOAD_HOLD_SECONDS: i64 = 60;
+
 /// Channel for web query requests
 #[derive(Resource)]
 pub struct WebQueryChannel {
-    pub receiver: mpsc::UnboundedReceiver<WebQueryRequest>,
+    pub receiver: mpsc::Receiver<WebQueryRequest>,
 }
 
 /// Channel for config update events
@@ -45,7 +57,42 @@ pub struct UserControlUpdateChannel {
 /// Clonable sender for web queries
 #[derive(Clone)]
 pub struct WebQuerySender {
-    sender: mpsc::UnboundedSender<WebQueryRequest>,
+    sender: mpsc::Sender<WebQueryRequest>,
+    stats: Arc<QueryStats>,
+    timeout: std::time::Duration,
+}
+
+/// Refused and timed-out queries of one channel
+#[derive(Debug, Default)]
+struct QueryStats {
+    overflows: AtomicU64,
+    timeouts: AtomicU64,
+    /// Unix time of the last refused or timed-out query (0 = never)
+    last_failure: AtomicI64,
+}
+
+impl QueryStats {
+    /// Record a failure, returning true if the ECS was keeping up until now
+    fn record_failure(&self, counter: &AtomicU64, now: DateTime<Utc>) -> bool {
+        counter.fetch_add(1, Ordering::Relaxed);
+        let previous = self.last_failure.swap(now.timestamp(), Ordering::Relaxed);
+        now.timestamp() - previous >= OVERLOAD_HOLD_SECONDS
+    }
+}
+
+/// How well the ECS keeps up with web queries
+#[derive(Debug, Clone, Serialize)]
+pub struct WebQueryHealth {
+    /// False shortly after a query was refused or timed out
+    pub keeping_up: bool,
+    /// Queries waiting for the ECS
+    pub pending: usize,
+    pub capacity: usize,
+    /// Queries refused because the queue was full, since start
+    pub overflows: u64,
+    /// Queries the ECS did not answer in time, since start
+    pub timeouts: u64,
+    pub last_failure_at: Option<DateTime<Utc>>,
 }
 
 /// Clonable sender for config updates
@@ -82,22 +129,74 @@ impl std::fmt::Debug for UserControlUpdateSender {
 impl WebQuerySender {
     /// Create a new sender/receiver pair
     pub fn new() -> (Self, WebQueryChannel) {
-        let (sender, receiver) = mpsc::unbounded_channel();
-        (Self { sender }, WebQueryChannel { receiver })
+        Self::with_limits(WEB_QUERY_CAPACITY, WEB_QUERY_TIMEOUT)
+    }
+
+    /// Create a pair holding at most `capacity` pending queries, each waiting up to `timeout`
+    pub fn with_limits(capacity: usize, timeout: std::time::Duration) -> (Self, WebQueryChannel) {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let sender = Self {
+            sender,
+            stats: Arc::default(),
+            timeout,
+        };
+        (sender, WebQueryChannel { receiver })
     }
 
     /// Request dashboard data
+    ///
+    /// Fails right away with [`QueryError::Overloaded`] when the queue is full
+    /// instead of piling up requests the ECS cannot answer.
     pub async fn query_dashboard(&self) -> Result<WebQueryResponse, QueryError> {
         let (response_tx, response_rx) = tokio::sync::oneshot::channel();
 
-        self.sender
-            .send(WebQueryRequest {
-                query_type: QueryType::Dashboard,
-                response_tx,
-            })
-            .map_err(|_| QueryError::ChannelClosed)?;
+        let request = WebQueryRequest {
+            query_type: QueryType::Dashboard,
+            response_tx,
+        };
+        match self.sender.try_send(request) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                crate::metrics::Metrics::global().record_web_query_overflow();
+                if self.stats.record_failure(&self.stats.overflows, Utc::now()) {
+                    warn!("⚠️ Web query queue is full, the ECS is not keeping up");
+                }
+                return Err(QueryError::Overloaded);
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => return Err(QueryError::ChannelClosed),
+        }
 
-        response_rx.await.map_err(|_| QueryError::ResponseTimeout)
+        match tokio::time::timeout(self.timeout, response_rx).await {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(_)) => Err(QueryError::ResponseDropped),
+            Err(_) => {
+                crate::metrics::Metrics::global().record_web_query_timeout();
+                if self.stats.record_failure(&self.stats.timeouts, Utc::now()) {
+                    warn!(
+                        "⚠️ ECS did not answer a web query within {}s",
+                        self.timeout.as_secs()
+                    );
+                }
+                Err(QueryError::ResponseTimeout)
+            }
+        }
+    }
+
+    /// Queue state and failures, for health reporting
+    pub fn health(&self) -> WebQueryHealth {
+        let now = Utc::now();
+        let last_failure = self.stats.last_failure.load(Ordering::Relaxed);
+        let capacity = self.sender.max_capacity();
+        WebQueryHealth {
+            keeping_up: now.timestamp() - last_failure >= OVERLOAD_HOLD_SECONDS,
+            pending: capacity - self.sender.capacity(),
+            capacity,
+            overflows: self.stats.overflows.load(Ordering::Relaxed),
+            timeouts: self.stats.timeouts.load(Ordering::Relaxed),
+            last_failure_at: (last_failure > 0)
+                .then(|| DateTime::from_timestamp(last_failure, 0))
+                .flatten(),
+        }
     }
 
     /// Request health check data
@@ -185,6 +284,21 @@ pub struct PvGenerationHistoryPoint {
     pub power_w: f32,
 }
 
+/// Battery power history point for visualization (positive = charge)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryPowerHistoryPoint {
+    pub timestamp: DateTime<Utc>,
+    pub power_w: f32,
+}
+
+/// Grid import/export history point for visualization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridPowerHistoryPoint {
+    pub timestamp: DateTime<Utc>,
+    pub import_w: f32,
+    pub export_w: f32,
+}
+
 /// Aggregated consumption statistics used by strategies
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsumptionStats {
@@ -204,6 +318,12 @@ pub struct ConsumptionStats {
 /// HDO (High/Low tariff) schedule information for chart display
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct HdoScheduleInfo {
+    /// Where the low tariff windows come from
+    #[serde(default)]
+    pub source: HdoSource,
+    /// Tariff in effect now, `None` when the schedule does not cover it
+    #[serde(default)]
+    pub current_tariff: Option<Tariff>,
     /// Low tariff periods for today: (start "HH:MM", end "HH:MM")
     pub low_tariff_periods: Vec<(String, String)>,
     /// Low tariff grid fee in CZK/kWh
@@ -254,6 +374,8 @@ pub struct WebQueryResponse {
     pub battery_soc_history: Option<Vec<BatterySocHistoryPoint>>,
     pub battery_soc_prediction: Option<Vec<BatterySocPredictionPoint>>,
     pub pv_generation_history: Option<Vec<PvGenerationHistoryPoint>>,
+    pub battery_power_history: Option<Vec<BatteryPowerHistoryPoint>>,
+    pub grid_power_history: Option<Vec<GridPowerHistoryPoint>>,
     /// Aggregated consumption statistics (EMA, imports)
     pub consumption_stats: Option<ConsumptionStats>,
     /// HDO (grid tariff) schedule for chart display
@@ -264,6 +386,14 @@ pub struct WebQueryResponse {
     pub solar_forecast: Option<SolarForecastInfo>,
 }
 
+impl WebQueryResponse {
+    /// Formatter for the timezone this snapshot was taken in
+    #[must_use]
+    pub fn time_formatter(&self) -> TimeFormatter {
+        TimeFormatter::from_timezone_name(self.timezone.as_deref())
+    }
+}
+
 /// Inverter component data bundle
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InverterData {
@@ -278,6 +408,9 @@ pub struct InverterData {
     pub actual_mode: Option<String>,
     // Whether actual mode matches the planned mode
     pub mode_synced: bool,
+    // Telemetry source currently delivering data (when a failover source is configured)
+    #[serde(default)]
+    pub telemetry_source: Option<String>,
 
     // Battery
     pub battery_soc: f32,
@@ -328,6 +461,8 @@ pub struct InverterData {
 pub struct ScheduleData {
     pub current_mode: String,
     pub current_reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_decision_reason: Option<DecisionReason>, // Structured reason of the current block
     pub current_strategy: Option<String>, // Strategy that chose this mode
     pub expected_profit: Option<f32>,     // Expected profit for current block (CZK)
     pub next_change: Option<DateTime<Utc>>,
@@ -368,15 +503,25 @@ pub struct PriceData {
 pub struct PriceBlockData {
     pub timestamp: DateTime<Utc>,
     pub price: f32,
-    pub block_type: String,           // "charge", "discharge", "self-use"
-    pub target_soc: Option<f32>,      // Target SOC for charge/discharge blocks
+    pub block_type: String,      // "charge", "discharge", "self-use"
+    pub target_soc: Option<f32>, // Target SOC for charge/discharge blocks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub planned_soc: Option<f32>, // Planned SOC at the end of the block (trajectory the executor follows)
     pub strategy: Option<String>,     // Strategy that chose this mode
     pub expected_profit: Option<f32>, // Expected profit for this block (CZK)
     pub reason: Option<String>,       // Detailed reason for the decision
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision_reason: Option<DecisionReason>, // Structured reason (localizable, filterable by kind)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub decision_uid: Option<String>, // Decision UID for debugging (e.g., "winter_adaptive_v2:scheduled_charge")
     #[serde(skip_serializing_if = "Option::is_none")]
     pub debug_info: Option<crate::strategy::BlockDebugInfo>, // Debug info (only when log_level=debug)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forecast: Option<BlockForecast>, // Solar/consumption forecast used at decision time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<BlockActual>, // Realized solar/consumption (past and current blocks)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tariff: Option<Tariff>, // HDO grid tariff, when the tariff schedule covers the block
     pub is_historical: bool, // True if block is in the past (shows regenerated schedule, not actual history)
 }
 
@@ -390,17 +535,32 @@ pub struct SystemHealthData {
 }
 
 /// Query error types
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum QueryError {
+    /// The ECS has stopped
     ChannelClosed,
+    /// Too many queries are waiting for the ECS
+    Overloaded,
+    /// The ECS did not answer in time
     ResponseTimeout,
+    /// The ECS dropped the query without answering
+    ResponseDropped,
+}
+
+impl QueryError {
+    /// The ECS is alive but too busy; retrying later may succeed
+    pub fn is_overload(self) -> bool {
+        matches!(self, Self::Overloaded | Self::ResponseTimeout)
+    }
 }
 
 impl std::fmt::Display for QueryError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::ChannelClosed => write!(f, "Query channel closed"),
+            Self::Overloaded => write!(f, "Query queue full"),
             Self::ResponseTimeout => write!(f, "Response timeout"),
+            Self::ResponseDropped => write!(f, "Query dropped without response"),
         }
     }
 }
@@ -435,7 +595,7 @@ type InverterQuery<'a> = (
 
 /// Extract strategy name and expected profit from reason string
 /// Format: "Strategy - reason (expected profit: X.XX CZK)"
-fn extract_strategy_info(reason: &str) -> (Option<String>, Option<f32>) {
+pub(crate) fn extract_strategy_info(reason: &str) -> (Option<String>, Option<f32>) {
     // Try to extract strategy name (before first " - ")
     let strategy = reason.split(" - ").next().map(|s| s.trim().to_string());
 
@@ -459,7 +619,7 @@ fn extract_strategy_info(reason: &str) -> (Option<String>, Option<f32>) {
 pub fn web_query_system(
     debug_config: Res<DebugModeConfig>,
     system_config: Res<SystemConfig>,
-    timezone_config: Option<Res<TimezoneConfig>>,
+    time_formatter: Option<Res<TimeFormatter>>,
     mut channel: ResMut<WebQueryChannel>,
     inverters: Query<InverterQuery>,
     schedule: Query<&OperationSchedule>,
@@ -467,34 +627,52 @@ pub fn web_query_system(
     price_analysis: Query<&PriceAnalysis>,
     battery_history: Res<BatteryHistory>,
     pv_history: Res<PvHistory>,
+    grid_history: Res<GridHistory>,
+    block_actuals: Res<BlockActuals>,
     consumption_history: Option<Res<ConsumptionHistory>>,
     consumption_history_config: Option<Res<ConsumptionHistoryConfig>>,
-    hdo_data: Option<Res<crate::async_systems::HdoScheduleData>>,
-    solar_forecast: Option<Res<crate::async_systems::SolarForecastData>>,
+    (hdo_data, solar_forecast): (
+        Option<Res<crate::async_systems::HdoScheduleData>>,
+        Option<Res<crate::async_systems::SolarForecastData>>,
+    ),
+    plugin_registry: Option<Res<crate::PluginRegistryResource>>,
 ) {
-    // Process all pending queries
+    // Process all pending queries; the dashboard is built once per update and
+    // shared, so a burst of queries costs no more than a single one
+    let mut dashboard: Option<WebQueryResponse> = None;
     while let Ok(request) = channel.receiver.try_recv() {
         trace!(
             "Processing web query: {:?}",
             std::any::type_name_of_val(&request.query_type)
         );
+        // The web handler already gave up on it
+        if request.response_tx.is_closed() {
+            continue;
+        }
 
         let response = match request.query_type {
-            QueryType::Dashboard => build_dashboard_response(
-                &debug_config,
-                &system_config,
-                timezone_config.as_deref(),
-                &inverters,
-                &schedule,
-                &price_data,
-                &price_analysis,
-                &battery_history,
-                &pv_history,
-                consumption_history.as_deref(),
-                consumption_history_config.as_deref(),
-                hdo_data.as_deref(),
-                solar_forecast.as_deref(),
-            ),
+            QueryType::Dashboard => dashboard
+                .get_or_insert_with(|| {
+                    build_dashboard_response(
+                        &debug_config,
+                        &system_config,
+                        time_formatter.as_deref(),
+                        &inverters,
+                        &schedule,
+                        &price_data,
+                        &price_analysis,
+                        &battery_history,
+                        &pv_history,
+                        &grid_history,
+                        &block_actuals,
+                        consumption_history.as_deref(),
+                        consumption_history_config.as_deref(),
+                        hdo_data.as_deref(),
+                        solar_forecast.as_deref(),
+                        plugin_registry.as_deref(),
+                    )
+                })
+                .clone(),
         };
 
         // Send response (ignore if receiver dropped)
@@ -507,19 +685,27 @@ pub fn web_query_system(
 fn build_dashboard_response(
     debug_config: &DebugModeConfig,
     system_config: &SystemConfig,
-    timezone_config: Option<&TimezoneConfig>,
+    time_formatter: Option<&TimeFormatter>,
     inverters: &Query<InverterQuery>,
     schedule: &Query<&OperationSchedule>,
     price_data: &Query<&SpotPriceData>,
     price_analysis: &Query<&PriceAnalysis>,
     battery_history: &BatteryHistory,
     pv_history: &PvHistory,
+    grid_history: &GridHistory,
+    block_actuals: &BlockActuals,
     consumption_history: Option<&ConsumptionHistory>,
     consumption_history_config: Option<&ConsumptionHistoryConfig>,
     hdo_data: Option<&crate::async_systems::HdoScheduleData>,
     solar_forecast_data: Option<&crate::async_systems::SolarForecastData>,
+    plugin_registry: Option<&crate::PluginRegistryResource>,
 ) -> WebQueryResponse {
     let now = Utc::now();
+    // Daily figures follow energy days on the HA clock
+    let formatter = time_formatter.copied().unwrap_or_default();
+    let today = formatter.energy_day_at(now);
+    let tariffs =
+        crate::tariff::tariff_schedule(&system_config.pricing_config, hdo_data, &formatter, now);
 
     // Compute consumption statistics (EMA and imports) early for use in inverter data
     let consumption_stats = {
@@ -540,11 +726,11 @@ fn build_dashboard_response(
             }
 
             // Today and yesterday imports from history summaries (newest first)
-            let today_date = now.date_naive();
+            let today_date = today.date;
             let yesterday_date = today_date.pred_opt().unwrap_or(today_date);
 
             for summary in history.summaries().iter() {
-                let date = summary.date.date_naive();
+                let date = formatter.local_date(summary.date);
                 if date == today_date && today_import_kwh.is_none() {
                     today_import_kwh = Some(summary.grid_import_kwh);
                 } else if date == yesterday_date && yesterday_import_kwh.is_none() {
@@ -597,6 +783,7 @@ fn build_dashboard_response(
                 mode_synced: raw_state
                     .map(|r| r.state.work_mode == mode.mode)
                     .unwrap_or(false),
+                telemetry_source: raw_state.and_then(|r| r.state.telemetry_source.clone()),
 
                 // Battery
                 battery_soc: battery.map(|b| b.soc_percent as f32).unwrap_or(0.0),
@@ -676,6 +863,7 @@ fn build_dashboard_response(
             ScheduleData {
                 current_mode: format!("{}", current.mode),
                 current_reason: current.reason.clone(),
+                current_decision_reason: current.decision_reason.clone(),
                 current_strategy: strategy,
                 expected_profit: profit,
                 next_change,
@@ -710,6 +898,11 @@ fn build_dashboard_response(
                         // CRITICAL: Match schedule blocks by TIMESTAMP, not array index
                         // This is essential because schedule may have filtered past blocks,
                         // causing index misalignment with price data blocks.
+                        let scheduled_block = sched.and_then(|s| {
+                            s.scheduled_blocks
+                                .iter()
+                                .find(|sb| sb.block_start == block.block_start)
+                        });
                         let (
                             block_type,
                             target_soc,
@@ -718,13 +911,8 @@ fn build_dashboard_response(
                             reason,
                             decision_uid,
                             debug_info,
-                        ) = sched
-                            .and_then(|s| {
-                                // Find the scheduled block that matches this price block's timestamp
-                                s.scheduled_blocks
-                                    .iter()
-                                    .find(|sb| sb.block_start == block.block_start)
-                            })
+                            forecast,
+                        ) = scheduled_block
                             .map(|sb| {
                                 let (strat, prof) = extract_strategy_info(&sb.reason);
                                 let block_type_str = match sb.mode {
@@ -755,6 +943,7 @@ fn build_dashboard_response(
                                     Some(sb.reason.clone()),
                                     sb.decision_uid.clone(),
                                     sb.debug_info.clone(),
+                                    sb.forecast,
                                 )
                             })
                             .unwrap_or_else(|| {
@@ -772,6 +961,7 @@ fn build_dashboard_response(
                                         )),
                                         None,
                                         None,
+                                        None,
                                     )
                                 } else if analysis.discharge_blocks.contains(&idx) {
                                     (
@@ -785,6 +975,7 @@ fn build_dashboard_response(
                                         )),
                                         None,
                                         None,
+                                        None,
                                     )
                                 } else {
                                     // Default to self-use with strategy name
@@ -799,6 +990,7 @@ fn build_dashboard_response(
                                         )),
                                         None,
                                         None,
+                                        None,
                                     )
                                 }
                             });
@@ -808,11 +1000,19 @@ fn build_dashboard_response(
                             price: block.price_czk_per_kwh,
                             block_type,
                             target_soc,
+                            planned_soc: scheduled_block.and_then(|sb| sb.target_soc),
                             strategy,
                             expected_profit: profit,
+                            decision_reason: match scheduled_block {
+                                Some(sb) => sb.decision_reason.clone(),
+                                None => reason.as_deref().and_then(DecisionReason::parse),
+                            },
                             reason,
                             decision_uid,
                             debug_info,
+                            forecast,
+                            actual: block_actuals.get(block.block_start).copied(),
+                            tariff: tariffs.tariff_at(block.block_start),
                             is_historical: block.block_start < now, // Mark past blocks as historical (regenerated, not actual)
                         }
                     })
@@ -826,21 +1026,20 @@ fn build_dashboard_response(
                     .map(|b| b.price_czk_per_kwh)
                     .unwrap_or(0.0);
 
-                // Separate today and tomorrow prices based on dates
-                let today_date = now.date_naive();
-                let tomorrow_date = today_date + chrono::Duration::days(1);
+                // Separate today and tomorrow prices by energy day
+                let tomorrow = formatter.energy_day(today.date.succ_opt().unwrap_or(today.date));
 
                 let today_prices: Vec<f32> = prices
                     .time_block_prices
                     .iter()
-                    .filter(|b| b.block_start.date_naive() == today_date)
+                    .filter(|b| today.contains(b.block_start))
                     .map(|b| b.price_czk_per_kwh)
                     .collect();
 
                 let tomorrow_prices: Vec<f32> = prices
                     .time_block_prices
                     .iter()
-                    .filter(|b| b.block_start.date_naive() == tomorrow_date)
+                    .filter(|b| tomorrow.contains(b.block_start))
                     .map(|b| b.price_czk_per_kwh)
                     .collect();
 
@@ -907,11 +1106,25 @@ fn build_dashboard_response(
     let has_inverter_data = !inverter_data.is_empty();
     let has_price_data = price_data.single().is_ok();
 
+    let mut health_errors = Vec::new();
+    let fallback_blocks = schedule.single().map_or(0, |sched| {
+        crate::scheduling::fallback_block_count(sched, now)
+    });
+    if fallback_blocks > 0 {
+        health_errors.push(format!(
+            "No strategy plugin produced a valid decision for {fallback_blocks} upcoming blocks: \
+             running the price-percentile fallback schedule. Check the enabled strategies and logs."
+        ));
+    }
+    if let Some(registry) = plugin_registry {
+        health_errors.extend(registry.0.warnings());
+    }
+
     let health = SystemHealthData {
         inverter_source: has_inverter_data,
         price_source: has_price_data,
         last_update: now,
-        errors: vec![],
+        errors: health_errors,
     };
 
     // Fallback for today's import from live inverter data if history is missing
@@ -978,6 +1191,29 @@ fn build_dashboard_response(
         None
     };
 
+    // Battery and grid power flows, sampled together with the SOC and PV history
+    let battery_power_history = (!battery_history.is_empty()).then(|| {
+        battery_history
+            .points_chronological()
+            .iter()
+            .map(|point| BatteryPowerHistoryPoint {
+                timestamp: point.timestamp,
+                power_w: point.power_w,
+            })
+            .collect::<Vec<_>>()
+    });
+    let grid_power_history = (!grid_history.is_empty()).then(|| {
+        grid_history
+            .points_chronological()
+            .iter()
+            .map(|point| GridPowerHistoryPoint {
+                timestamp: point.timestamp,
+                import_w: point.import_w,
+                export_w: point.export_w,
+            })
+            .collect::<Vec<_>>()
+    });
+
     trace!(
         "Built dashboard response: {} inverters, schedule={}, prices={}, battery_history={}, pv_history={}",
         inverter_data.len(),
@@ -1035,26 +1271,33 @@ fn build_dashboard_response(
     });
 
     // Build HDO schedule info for chart display
-    let hdo_schedule = hdo_data.map(|hdo| {
+    let hdo_schedule = (!tariffs.is_empty() || hdo_data.is_some()).then(|| {
+        let low_tariff_periods =
+            crate::tariff::low_tariff_periods_on(&tariffs, today.date, &formatter);
         // Log HDO data being sent to web
-        if hdo.low_tariff_periods.is_empty() {
+        if low_tariff_periods.is_empty() {
             warn!(
-                "⚠️ HDO schedule has 0 low tariff periods! last_updated: {:?}",
-                hdo.last_updated
+                "⚠️ HDO schedule has 0 low tariff periods! source: {:?}, last_updated: {:?}",
+                tariffs.source,
+                hdo_data.and_then(|hdo| hdo.last_updated)
             );
         } else {
             debug!(
                 "📊 HDO schedule for web: {} periods, low={:.2} CZK, high={:.2} CZK",
-                hdo.low_tariff_periods.len(),
-                hdo.low_tariff_czk,
-                hdo.high_tariff_czk
+                low_tariff_periods.len(),
+                system_config.pricing_config.hdo_low_tariff_czk,
+                system_config.pricing_config.hdo_high_tariff_czk
             );
         }
         HdoScheduleInfo {
-            low_tariff_periods: hdo.low_tariff_periods.clone(),
-            low_tariff_czk: hdo.low_tariff_czk,
-            high_tariff_czk: hdo.high_tariff_czk,
-            last_updated: hdo.last_updated.map(|t| t.to_rfc3339()),
+            source: tariffs.source,
+            current_tariff: tariffs.tariff_at(now),
+            low_tariff_periods,
+            low_tariff_czk: system_config.pricing_config.hdo_low_tariff_czk,
+            high_tariff_czk: system_config.pricing_config.hdo_high_tariff_czk,
+            last_updated: hdo_data
+                .and_then(|hdo| hdo.last_updated)
+                .map(|t| t.to_rfc3339()),
         }
     });
 
@@ -1115,12 +1358,15 @@ fn build_dashboard_response(
         schedule: schedule_data,
         prices: price_data_result,
         health,
-        timezone: timezone_config
-            .and_then(|tz| tz.timezone.clone())
+        timezone: time_formatter
+            .and_then(TimeFormatter::timezone_name)
+            .map(str::to_owned)
             .or_else(|| system_config.system_config.timezone.clone()),
         battery_soc_history,
         battery_soc_prediction,
         pv_generation_history,
+        battery_power_history,
+        grid_power_history,
         consumption_stats,
         hdo_schedule,
         pricing_fees,
@@ -1159,3 +1405,46 @@ fn get_topology_string(inverter_id: &str, config: &SystemConfig) -> String {
         "Unknown".to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_full_queue_is_refused_and_reported() {
+        let (sender, _channel) = WebQuerySender::with_limits(1, Duration::from_millis(50));
+
+        // Nobody answers, so the first query times out while holding the only slot
+        let first = sender.clone();
+        let pending = tokio::spawn(async move { first.query_dashboard().await });
+        tokio::task::yield_now().await;
+
+        assert_eq!(
+            sender.query_dashboard().await.unwrap_err(),
+            QueryError::Overloaded
+        );
+        assert_eq!(
+            pending.await.unwrap().unwrap_err(),
+            QueryError::ResponseTimeout
+        );
+
+        let health = sender.health();
+        assert!(!health.keeping_up);
+        assert_eq!(health.capacity, 1);
+        assert_eq!(health.overflows, 1);
+        assert_eq!(health.timeouts, 1);
+        assert!(health.last_failure_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_closed_channel_is_not_an_overload() {
+        let (sender, channel) = WebQuerySender::with_limits(1, Duration::from_millis(50));
+        drop(channel);
+
+        let error = sender.query_dashboard().await.unwrap_err();
+        assert_eq!(error, QueryError::ChannelClosed);
+        assert!(!error.is_overload());
+        assert!(sender.health().keeping_up);
+    }
+}
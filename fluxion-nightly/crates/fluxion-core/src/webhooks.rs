@@ -0,0 +1,362 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Outbound webhooks for schedule and mode changes.
+//!
+//! Every newly generated schedule and every inverter mode change is POSTed as
+//! JSON to the configured URLs, so users can drive their own automations off
+//! FluxION's decisions. Deliveries run in the background and failed ones are
+//! retried with exponential backoff.
+
+use crate::components::{CurrentMode, Inverter, InverterOperationMode, OperationSchedule};
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+
+/// Requests taking longer than this count as failed
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Wait before the first retry, doubled for every further one
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+
+/// Longest wait between two attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// One upcoming block of a generated schedule
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookBlock {
+    pub block_start: DateTime<Utc>,
+    pub duration_minutes: u32,
+    pub mode: InverterOperationMode,
+    pub reason: String,
+}
+
+/// Payload POSTed to the webhook URLs, tagged by `event`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A new schedule was generated; `blocks` are the current and future ones
+    ScheduleGenerated {
+        generated_at: DateTime<Utc>,
+        current_mode: Option<InverterOperationMode>,
+        blocks: Vec<WebhookBlock>,
+    },
+    /// An inverter was switched to a different mode
+    ModeChanged {
+        inverter_id: String,
+        previous_mode: InverterOperationMode,
+        mode: InverterOperationMode,
+        reason: String,
+        changed_at: DateTime<Utc>,
+    },
+}
+
+impl WebhookEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::ScheduleGenerated { .. } => "schedule_generated",
+            Self::ModeChanged { .. } => "mode_changed",
+        }
+    }
+}
+
+/// Queue of webhook events, delivered by a background task
+///
+/// The default instance has no URLs and drops all events.
+#[derive(Resource, Clone, Default)]
+pub struct WebhookDispatcher {
+    sender: Option<mpsc::UnboundedSender<WebhookEvent>>,
+}
+
+impl std::fmt::Debug for WebhookDispatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookDispatcher")
+            .field("enabled", &self.sender.is_some())
+            .finish()
+    }
+}
+
+impl WebhookDispatcher {
+    /// Deliver events to `urls` from a supervised background task
+    ///
+    /// A failed delivery is retried up to `max_retries` times.
+    /// Must be called within a tokio runtime.
+    #[must_use]
+    pub fn spawn(urls: Vec<String>, max_retries: u32) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+        let urls: Arc<[String]> = urls.into();
+        crate::TaskSupervisor::global().spawn("webhook_dispatcher", move || {
+            run_dispatcher(Arc::clone(&receiver), Arc::clone(&urls), max_retries)
+        });
+        Self {
+            sender: Some(sender),
+        }
+    }
+
+    pub fn send(&self, event: WebhookEvent) {
+        if let Some(sender) = &self.sender {
+            let _ = sender.send(event);
+        }
+    }
+}
+
+async fn run_dispatcher(
+    receiver: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<WebhookEvent>>>,
+    urls: Arc<[String]>,
+    max_retries: u32,
+) {
+    let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Webhooks disabled, failed to build HTTP client: {e}");
+            return;
+        }
+    };
+
+    let mut receiver = receiver.lock().await;
+    while let Some(event) = receiver.recv().await {
+        let event = Arc::new(event);
+        // Each URL retries on its own so a dead endpoint doesn't hold up the others
+        for url in urls.iter() {
+            tokio::spawn(deliver(
+                client.clone(),
+                url.clone(),
+                Arc::clone(&event),
+                max_retries,
+                INITIAL_BACKOFF,
+            ));
+        }
+    }
+}
+
+/// POST `event` to `url`, retrying with exponential backoff; true once delivered
+async fn deliver(
+    client: reqwest::Client,
+    url: String,
+    event: Arc<WebhookEvent>,
+    max_retries: u32,
+    initial_backoff: Duration,
+) -> bool {
+    let mut backoff = initial_backoff;
+    for attempt in 0..=max_retries {
+        let result = client
+            .post(&url)
+            .json(event.as_ref())
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+        match result {
+            Ok(_) => {
+                debug!("Webhook {} delivered to {url}", event.name());
+                return true;
+            }
+            Err(e) if attempt < max_retries => {
+                debug!(
+                    "Webhook {} to {url} failed ({e}), retrying in {}s",
+                    event.name(),
+                    backoff.as_secs()
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            Err(e) => {
+                warn!(
+                    "Webhook {} to {url} failed after {} attempts: {e}",
+                    event.name(),
+                    attempt + 1
+                );
+            }
+        }
+    }
+    false
+}
+
+/// Queue webhooks for new schedules and inverter mode changes
+///
+/// Runs after the schedule executor so [`CurrentMode`] holds the mode it set.
+pub fn webhook_system(
+    dispatcher: Res<WebhookDispatcher>,
+    schedule_query: Query<&OperationSchedule>,
+    inverters: Query<(&Inverter, &CurrentMode)>,
+    mut last_schedule: Local<Option<DateTime<Utc>>>,
+    mut last_modes: Local<HashMap<String, InverterOperationMode>>,
+) {
+    let now = Utc::now();
+
+    if let Ok(schedule) = schedule_query.single()
+        && !schedule.scheduled_blocks.is_empty()
+        && *last_schedule != Some(schedule.generated_at)
+    {
+        *last_schedule = Some(schedule.generated_at);
+        let blocks = schedule
+            .scheduled_blocks
+            .iter()
+            .filter(|b| {
+                b.block_start + chrono::Duration::minutes(i64::from(b.duration_minutes)) > now
+            })
+            .map(|b| WebhookBlock {
+                block_start: b.block_start,
+                duration_minutes: b.duration_minutes,
+                mode: b.mode,
+                reason: b.reason.clone(),
+            })
+            .collect();
+        dispatcher.send(WebhookEvent::ScheduleGenerated {
+            generated_at: schedule.generated_at,
+            current_mode: schedule.get_current_mode(now).map(|b| b.mode),
+            blocks,
+        });
+    }
+
+    for (inverter, current_mode) in &inverters {
+        // The first observation is the mode found at startup, not a change
+        let Some(previous) = last_modes.insert(inverter.id.clone(), current_mode.mode) else {
+            continue;
+        };
+        if previous != current_mode.mode {
+            dispatcher.send(WebhookEvent::ModeChanged {
+                inverter_id: inverter.id.clone(),
+                previous_mode: previous,
+                mode: current_mode.mode,
+                reason: current_mode.reason.clone(),
+                changed_at: current_mode.set_at,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::ScheduledMode;
+    use fluxion_types::inverter::InverterType;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn block(start: DateTime<Utc>, mode: InverterOperationMode) -> ScheduledMode {
+        ScheduledMode {
+            block_start: start,
+            duration_minutes: 15,
+            target_inverters: None,
+            mode,
+            reason: "test".to_owned(),
+            decision_reason: None,
+            decision_uid: None,
+            charge_power_kw: None,
+            target_soc: None,
+            forecast: None,
+            debug_info: None,
+        }
+    }
+
+    #[test]
+    fn test_schedule_and_mode_changes_are_queued_once() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut world = World::new();
+        world.insert_resource(WebhookDispatcher {
+            sender: Some(sender),
+        });
+        let now = Utc::now();
+        world.spawn(OperationSchedule {
+            scheduled_blocks: vec![
+                block(
+                    now - chrono::Duration::hours(1),
+                    InverterOperationMode::SelfUse,
+                ),
+                block(
+                    now - chrono::Duration::minutes(5),
+                    InverterOperationMode::ForceCharge,
+                ),
+            ],
+            ..OperationSchedule::default()
+        });
+        let inverter = world
+            .spawn((
+                Inverter {
+                    id: "main".to_owned(),
+                    inverter_type: InverterType::Solax,
+                },
+                CurrentMode::default(),
+            ))
+            .id();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(webhook_system);
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+        let Ok(WebhookEvent::ScheduleGenerated {
+            current_mode,
+            blocks,
+            ..
+        }) = receiver.try_recv()
+        else {
+            panic!("expected a schedule event");
+        };
+        assert_eq!(current_mode, Some(InverterOperationMode::ForceCharge));
+        assert_eq!(blocks.len(), 1);
+        assert!(receiver.try_recv().is_err());
+
+        world.get_mut::<CurrentMode>(inverter).unwrap().mode = InverterOperationMode::ForceCharge;
+        schedule.run(&mut world);
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(WebhookEvent::ModeChanged {
+                previous_mode: InverterOperationMode::SelfUse,
+                mode: InverterOperationMode::ForceCharge,
+                ..
+            })
+        ));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    /// Answer each connection with the next status, then stop listening
+    async fn serve(statuses: &'static [u16]) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = [0_u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 {status} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_delivery_retries_until_success() {
+        let event = Arc::new(WebhookEvent::ModeChanged {
+            inverter_id: "main".to_owned(),
+            previous_mode: InverterOperationMode::SelfUse,
+            mode: InverterOperationMode::ForceCharge,
+            reason: "cheap".to_owned(),
+            changed_at: Utc::now(),
+        });
+        let client = reqwest::Client::new();
+        let backoff = Duration::from_millis(10);
+
+        let url = serve(&[500, 503, 200]).await;
+        assert!(deliver(client.clone(), url, Arc::clone(&event), 2, backoff).await);
+
+        let url = serve(&[500, 500]).await;
+        assert!(!deliver(client, url, event, 1, backoff).await);
+    }
+}
@@ -0,0 +1,444 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! "What if" schedules against the live planning inputs.
+//!
+//! Every time the live schedule is generated, the inputs it was planned from
+//! (prices, forecasts, SOC, effective control config) are kept in the
+//! [`WhatIfPlanner`]. A what-if request applies hypothetical overrides to a
+//! copy of them (another SOC, changed prices, disabled strategies) and plans
+//! again with a fork of the plugin manager, so the live schedule and the
+//! plugins' health are never touched.
+
+use crate::components::TimeBlockPrice;
+use crate::resources::ControlConfig;
+use crate::scheduling::{ScheduleConfig, generate_schedule_with_optimizer};
+use crate::web_bridge::extract_strategy_info;
+use bevy_ecs::prelude::*;
+use chrono::{DateTime, Utc};
+use fluxion_plugins::PluginManager;
+use fluxion_types::UserControlState;
+use fluxion_types::inverter::InverterOperationMode;
+use fluxion_types::scheduling::OperationSchedule;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+/// Everything a schedule is planned from
+#[derive(Debug, Clone)]
+pub struct PlanningInputs {
+    pub time_block_prices: Vec<TimeBlockPrice>,
+    /// Control config after grid quality, calendar, weather and tariff adjustments
+    pub control_config: ControlConfig,
+    pub schedule_config: ScheduleConfig,
+    pub current_battery_soc: f32,
+    pub solar_forecast: Option<Vec<f32>>,
+    pub consumption_forecast: Option<Vec<f32>>,
+    pub backup_discharge_min_soc: f32,
+    pub grid_import_today_kwh: Option<f32>,
+    pub hdo_raw_data: Option<String>,
+    pub solar_forecast_total_today_kwh: f32,
+    pub solar_forecast_remaining_today_kwh: f32,
+    pub solar_forecast_tomorrow_kwh: f32,
+    pub user_control: Option<UserControlState>,
+    pub hourly_consumption_profile: Option<[f32; 24]>,
+}
+
+impl PlanningInputs {
+    /// Plan a schedule from these inputs
+    pub fn plan(&self, plugin_manager: &PluginManager) -> OperationSchedule {
+        generate_schedule_with_optimizer(
+            &self.time_block_prices,
+            &self.control_config,
+            &self.schedule_config,
+            self.current_battery_soc,
+            self.solar_forecast.as_deref(),
+            self.consumption_forecast.as_deref(),
+            self.backup_discharge_min_soc,
+            self.grid_import_today_kwh,
+            plugin_manager,
+            self.hdo_raw_data.clone(),
+            self.solar_forecast_total_today_kwh,
+            self.solar_forecast_remaining_today_kwh,
+            self.solar_forecast_tomorrow_kwh,
+            self.user_control.as_ref(),
+            self.hourly_consumption_profile.as_ref(),
+        )
+    }
+}
+
+/// Hypothetical price for the blocks starting in `[from, to)`
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct PriceOverride {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Replace the spot price (CZK/kWh)
+    pub price_czk_per_kwh: Option<f32>,
+    /// Multiply the spot price instead
+    pub multiplier: Option<f32>,
+}
+
+/// Overrides for a what-if schedule; anything left out keeps its live value
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct WhatIfRequest {
+    /// Battery SOC to start from (%)
+    pub battery_soc: Option<f32>,
+    #[serde(default)]
+    pub prices: Vec<PriceOverride>,
+    /// Plugin names as listed by `/api/plugins`
+    #[serde(default)]
+    pub disabled_strategies: Vec<String>,
+}
+
+/// A block the what-if schedule decides differently
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WhatIfBlockChange {
+    pub block_start: DateTime<Utc>,
+    #[schema(value_type = String)]
+    pub live_mode: InverterOperationMode,
+    #[schema(value_type = String)]
+    pub what_if_mode: InverterOperationMode,
+    pub live_strategy: Option<String>,
+    pub what_if_strategy: Option<String>,
+    pub what_if_reason: String,
+}
+
+/// Alternative schedule and how it differs from the live one
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct WhatIfResponse {
+    /// When the live schedule the inputs come from was planned
+    pub inputs_captured_at: DateTime<Utc>,
+    pub battery_soc: f32,
+    /// Sum of the strategies' expected profit (CZK)
+    pub live_expected_profit_czk: f32,
+    pub what_if_expected_profit_czk: f32,
+    pub changed_blocks: Vec<WhatIfBlockChange>,
+    #[schema(value_type = Object)]
+    pub schedule: OperationSchedule,
+}
+
+/// Why a what-if schedule could not be planned
+#[derive(Debug, Clone, PartialEq)]
+pub enum WhatIfError {
+    /// No live schedule has been planned yet
+    NoLiveSchedule,
+    /// An override is out of range or inconsistent
+    InvalidOverride(String),
+    /// A disabled strategy is not registered
+    UnknownStrategy(String),
+}
+
+impl std::fmt::Display for WhatIfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoLiveSchedule => write!(f, "No live schedule has been planned yet"),
+            Self::InvalidOverride(message) => write!(f, "Invalid override: {message}"),
+            Self::UnknownStrategy(name) => write!(f, "Unknown strategy '{name}'"),
+        }
+    }
+}
+
+impl std::error::Error for WhatIfError {}
+
+/// Inputs and result of the latest live planning run
+#[derive(Debug)]
+struct LivePlan {
+    inputs: PlanningInputs,
+    schedule: OperationSchedule,
+    captured_at: DateTime<Utc>,
+}
+
+/// Latest live planning inputs, written by the ECS and read by the web API
+#[derive(Resource, Clone)]
+pub struct WhatIfPlanner {
+    plugin_manager: Arc<RwLock<PluginManager>>,
+    live: Arc<RwLock<Option<LivePlan>>>,
+}
+
+impl std::fmt::Debug for WhatIfPlanner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WhatIfPlanner").finish_non_exhaustive()
+    }
+}
+
+impl WhatIfPlanner {
+    /// Planner sharing the live plugin manager
+    pub fn new(plugin_manager: Arc<RwLock<PluginManager>>) -> Self {
+        Self {
+            plugin_manager,
+            live: Arc::default(),
+        }
+    }
+
+    /// Keep the inputs of a live schedule for later what-if requests
+    pub fn record(&self, inputs: PlanningInputs, schedule: &OperationSchedule) {
+        *self.live.write() = Some(LivePlan {
+            inputs,
+            schedule: schedule.clone(),
+            captured_at: Utc::now(),
+        });
+    }
+
+    /// Plan the live inputs again with `request` applied
+    ///
+    /// Can take as long as a live planning run, so call it off the async runtime.
+    pub fn run(&self, request: &WhatIfRequest) -> Result<WhatIfResponse, WhatIfError> {
+        let (mut inputs, live_schedule, captured_at) = {
+            let live = self.live.read();
+            let live = live.as_ref().ok_or(WhatIfError::NoLiveSchedule)?;
+            (live.inputs.clone(), live.schedule.clone(), live.captured_at)
+        };
+        apply_overrides(&mut inputs, request)?;
+
+        let plugin_manager = {
+            let live_manager = self.plugin_manager.read();
+            let registered: Vec<&str> = live_manager
+                .list_plugins()
+                .into_iter()
+                .map(|(name, _, _)| name)
+                .collect();
+            if let Some(unknown) = request
+                .disabled_strategies
+                .iter()
+                .find(|name| !registered.contains(&name.as_str()))
+            {
+                return Err(WhatIfError::UnknownStrategy(unknown.clone()));
+            }
+            live_manager.fork(&request.disabled_strategies)
+        };
+
+        let schedule = inputs.plan(&plugin_manager);
+        Ok(WhatIfResponse {
+            inputs_captured_at: captured_at,
+            battery_soc: inputs.current_battery_soc,
+            live_expected_profit_czk: expected_profit(&live_schedule),
+            what_if_expected_profit_czk: expected_profit(&schedule),
+            changed_blocks: changed_blocks(&live_schedule, &schedule),
+            schedule,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum PriceChange {
+    Set(f32),
+    Scale(f32),
+}
+
+impl PriceChange {
+    fn apply(self, price: f32) -> f32 {
+        match self {
+            Self::Set(value) => value,
+            Self::Scale(factor) => price * factor,
+        }
+    }
+}
+
+/// Apply the SOC and price overrides of `request` to `inputs`
+fn apply_overrides(
+    inputs: &mut PlanningInputs,
+    request: &WhatIfRequest,
+) -> Result<(), WhatIfError> {
+    if let Some(soc) = request.battery_soc {
+        if !(0.0..=100.0).contains(&soc) {
+            return Err(WhatIfError::InvalidOverride(format!(
+                "battery_soc {soc} is outside 0-100"
+            )));
+        }
+        inputs.current_battery_soc = soc;
+    }
+
+    for price in &request.prices {
+        if price.from >= price.to {
+            return Err(WhatIfError::InvalidOverride(
+                "price override 'from' must be before 'to'".to_owned(),
+            ));
+        }
+        let change = match (price.price_czk_per_kwh, price.multiplier) {
+            (Some(value), None) if value.is_finite() => PriceChange::Set(value),
+            (None, Some(factor)) if factor.is_finite() => PriceChange::Scale(factor),
+            _ => {
+                return Err(WhatIfError::InvalidOverride(
+                    "a price override needs either a finite price_czk_per_kwh or multiplier"
+                        .to_owned(),
+                ));
+            }
+        };
+        for block in inputs
+            .time_block_prices
+            .iter_mut()
+            .filter(|b| b.block_start >= price.from && b.block_start < price.to)
+        {
+            // Fees and tariffs stay the same, so shift every derived price by the spot change
+            let delta = change.apply(block.price_czk_per_kwh) - block.price_czk_per_kwh;
+            block.price_czk_per_kwh += delta;
+            block.effective_price_czk_per_kwh += delta;
+            if let Some(sell) = block.spot_sell_price_czk_per_kwh.as_mut() {
+                *sell += delta;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn expected_profit(schedule: &OperationSchedule) -> f32 {
+    schedule
+        .scheduled_blocks
+        .iter()
+        .filter_map(|block| extract_strategy_info(&block.reason).1)
+        .sum()
+}
+
+/// Blocks of `what_if` whose mode differs from the live block with the same start
+fn changed_blocks(live: &OperationSchedule, what_if: &OperationSchedule) -> Vec<WhatIfBlockChange> {
+    what_if
+        .scheduled_blocks
+        .iter()
+        .filter_map(|block| {
+            let live_block = live
+                .scheduled_blocks
+                .iter()
+                .find(|b| b.block_start == block.block_start)?;
+            (live_block.mode != block.mode).then(|| WhatIfBlockChange {
+                block_start: block.block_start,
+                live_mode: live_block.mode,
+                what_if_mode: block.mode,
+                live_strategy: extract_strategy_info(&live_block.reason).0,
+                what_if_strategy: extract_strategy_info(&block.reason).0,
+                what_if_reason: block.reason.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn inputs(prices: &[f32]) -> PlanningInputs {
+        let start = Utc::now() + Duration::hours(1);
+        PlanningInputs {
+            time_block_prices: prices
+                .iter()
+                .enumerate()
+                .map(|(i, &price)| TimeBlockPrice {
+                    block_start: start + Duration::minutes(15 * i as i64),
+                    duration_minutes: 15,
+                    price_czk_per_kwh: price,
+                    effective_price_czk_per_kwh: price + 0.5,
+                    spot_sell_price_czk_per_kwh: Some(price - 0.2),
+                })
+                .collect(),
+            control_config: ControlConfig::default(),
+            schedule_config: ScheduleConfig::default(),
+            current_battery_soc: 50.0,
+            solar_forecast: None,
+            consumption_forecast: None,
+            backup_discharge_min_soc: 10.0,
+            grid_import_today_kwh: None,
+            hdo_raw_data: None,
+            solar_forecast_total_today_kwh: 0.0,
+            solar_forecast_remaining_today_kwh: 0.0,
+            solar_forecast_tomorrow_kwh: 0.0,
+            user_control: None,
+            hourly_consumption_profile: None,
+        }
+    }
+
+    #[test]
+    fn test_price_overrides_shift_derived_prices() {
+        let mut inputs = inputs(&[2.0, 2.0, 2.0]);
+        let second = inputs.time_block_prices[1].block_start;
+        let request = WhatIfRequest {
+            prices: vec![PriceOverride {
+                from: second,
+                to: second + Duration::hours(1),
+                price_czk_per_kwh: None,
+                multiplier: Some(3.0),
+            }],
+            ..WhatIfRequest::default()
+        };
+
+        apply_overrides(&mut inputs, &request).unwrap();
+
+        let prices: Vec<_> = inputs
+            .time_block_prices
+            .iter()
+            .map(|b| {
+                (
+                    b.price_czk_per_kwh,
+                    b.effective_price_czk_per_kwh,
+                    b.spot_sell_price_czk_per_kwh,
+                )
+            })
+            .collect();
+        assert_eq!(
+            prices,
+            vec![
+                (2.0, 2.5, Some(1.8)),
+                (6.0, 6.5, Some(5.8)),
+                (6.0, 6.5, Some(5.8))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_leaves_live_schedule_and_rejects_bad_overrides() {
+        let planner = WhatIfPlanner::new(Arc::new(RwLock::new(PluginManager::new())));
+        assert_eq!(
+            planner.run(&WhatIfRequest::default()).unwrap_err(),
+            WhatIfError::NoLiveSchedule
+        );
+
+        let live_inputs = inputs(&[1.0, 5.0, 1.0, 5.0]);
+        let live = live_inputs.plan(&planner.plugin_manager.read());
+        planner.record(live_inputs, &live);
+
+        let response = planner
+            .run(&WhatIfRequest {
+                battery_soc: Some(90.0),
+                ..WhatIfRequest::default()
+            })
+            .unwrap();
+        assert_eq!(response.battery_soc, 90.0);
+        assert_eq!(response.schedule.scheduled_blocks.len(), 4);
+        assert_eq!(
+            planner
+                .live
+                .read()
+                .as_ref()
+                .unwrap()
+                .inputs
+                .current_battery_soc,
+            50.0
+        );
+
+        let too_full = WhatIfRequest {
+            battery_soc: Some(120.0),
+            ..WhatIfRequest::default()
+        };
+        assert!(matches!(
+            planner.run(&too_full),
+            Err(WhatIfError::InvalidOverride(_))
+        ));
+        let unknown = WhatIfRequest {
+            disabled_strategies: vec!["Nope".to_owned()],
+            ..WhatIfRequest::default()
+        };
+        assert_eq!(
+            planner.run(&unknown).unwrap_err(),
+            WhatIfError::UnknownStrategy("Nope".to_owned())
+        );
+    }
+}
@@ -0,0 +1,96 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+// Temporary backup reserve raising the minimum SOC during schedule generation
+
+use chrono::{Duration, DurationRound, Utc};
+use fluxion_core::scheduling::{ScheduleConfig, generate_schedule_with_optimizer};
+use fluxion_plugins::PluginManager;
+use fluxion_types::DecisionReason;
+use fluxion_types::config::ControlConfig;
+use fluxion_types::inverter::InverterOperationMode;
+use fluxion_types::pricing::TimeBlockPrice;
+use fluxion_types::user_control::{BackupReserve, UserControlState};
+
+/// 12 hours of flat prices starting in an hour
+fn prices() -> Vec<TimeBlockPrice> {
+    let start = Utc::now().duration_trunc(Duration::minutes(15)).unwrap() + Duration::hours(1);
+    (0..48)
+        .map(|i| TimeBlockPrice {
+            block_start: start + Duration::minutes(15 * i),
+            duration_minutes: 15,
+            price_czk_per_kwh: 3.0,
+            effective_price_czk_per_kwh: 3.0,
+            spot_sell_price_czk_per_kwh: None,
+        })
+        .collect()
+}
+
+#[test]
+fn reserve_charges_up_and_holds_until_it_ends() {
+    let prices = prices();
+    let reserve_end = prices[24].block_start;
+    let user_control = UserControlState {
+        backup_reserve: Some(BackupReserve::new(
+            80.0,
+            Utc::now(),
+            reserve_end,
+            Some("Storm warning".to_owned()),
+        )),
+        ..Default::default()
+    };
+
+    let schedule = generate_schedule_with_optimizer(
+        &prices,
+        &ControlConfig::default(),
+        &ScheduleConfig::default(),
+        30.0,
+        None,
+        None,
+        10.0,
+        None,
+        &PluginManager::new(),
+        None,
+        0.0,
+        0.0,
+        0.0,
+        Some(&user_control),
+        None,
+    );
+
+    let (within, after): (Vec<_>, Vec<_>) = schedule
+        .scheduled_blocks
+        .iter()
+        .partition(|b| b.block_start < reserve_end);
+    assert_eq!(within[0].mode, InverterOperationMode::ForceCharge);
+    assert!(within[0].reason.contains("charging to 80% backup reserve"));
+    assert_eq!(
+        within[0].decision_reason.as_ref().map(DecisionReason::kind),
+        Some("backup_reserve")
+    );
+    assert!(
+        within
+            .iter()
+            .all(|b| b.mode != InverterOperationMode::SelfUse
+                && b.mode != InverterOperationMode::ForceDischarge)
+    );
+    assert!(
+        within
+            .iter()
+            .any(|b| b.mode == InverterOperationMode::NoChargeNoDischarge)
+    );
+    assert!(
+        after
+            .iter()
+            .all(|b| b.mode != InverterOperationMode::NoChargeNoDischarge)
+    );
+}
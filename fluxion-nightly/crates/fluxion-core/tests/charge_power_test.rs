@@ -0,0 +1,104 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+// Force-charge power sized to the energy the battery can still take
+
+use chrono::{Duration, DurationRound, Utc};
+use fluxion_core::scheduling::{ScheduleConfig, generate_schedule_with_optimizer};
+use fluxion_plugins::PluginManager;
+use fluxion_types::config::ControlConfig;
+use fluxion_types::inverter::InverterOperationMode;
+use fluxion_types::pricing::TimeBlockPrice;
+use fluxion_types::scheduling::OperationSchedule;
+
+/// 12 hours starting in an hour, cheap for the first 90 minutes
+fn prices() -> Vec<TimeBlockPrice> {
+    let start = Utc::now().duration_trunc(Duration::minutes(15)).unwrap() + Duration::hours(1);
+    (0..48)
+        .map(|i| {
+            let price = if i < 6 { 1.0 } else { 5.0 };
+            TimeBlockPrice {
+                block_start: start + Duration::minutes(15 * i),
+                duration_minutes: 15,
+                price_czk_per_kwh: price,
+                effective_price_czk_per_kwh: price,
+                spot_sell_price_czk_per_kwh: None,
+            }
+        })
+        .collect()
+}
+
+fn plan(control_config: &ControlConfig) -> OperationSchedule {
+    generate_schedule_with_optimizer(
+        &prices(),
+        control_config,
+        &ScheduleConfig::default(),
+        40.0,
+        Some(&[0.0; 48]),
+        Some(&[0.0; 48]),
+        10.0,
+        None,
+        &PluginManager::new(),
+        None,
+        0.0,
+        0.0,
+        0.0,
+        None,
+        None,
+    )
+}
+
+#[test]
+fn charge_that_fills_the_battery_early_runs_at_reduced_power() {
+    let control_config = ControlConfig {
+        battery_capacity_kwh: 10.0,
+        max_battery_charge_rate_kw: 10.0,
+        charge_power_modulation_enabled: true,
+        ..ControlConfig::default()
+    };
+    assert!(!control_config.partial_charge_enabled);
+
+    let schedule = plan(&control_config);
+    let charging: Vec<_> = schedule
+        .scheduled_blocks
+        .iter()
+        .filter(|b| b.mode == InverterOperationMode::ForceCharge)
+        .collect();
+    assert!(!charging.is_empty());
+    for block in &charging {
+        let power_kw = block.charge_power_kw.expect("modulated charge power");
+        assert!(power_kw < control_config.max_battery_charge_rate_kw);
+        assert_eq!(Some(power_kw), charging[0].charge_power_kw);
+    }
+    // The 6 kWh from 40% to 100% are spread over the whole charge
+    let charged_kwh: f32 = charging
+        .iter()
+        .map(|b| b.charge_power_kw.unwrap() * b.duration_minutes as f32 / 60.0)
+        .sum();
+    assert!(
+        (charged_kwh - 6.0).abs() < 0.01,
+        "charged {charged_kwh} kWh"
+    );
+
+    // Off by default, so existing installs keep their charge current
+    let disabled = ControlConfig {
+        charge_power_modulation_enabled: ControlConfig::default().charge_power_modulation_enabled,
+        ..control_config
+    };
+    assert!(!disabled.charge_power_modulation_enabled);
+    assert!(
+        plan(&disabled)
+            .scheduled_blocks
+            .iter()
+            .all(|b| b.charge_power_kw.is_none())
+    );
+}
@@ -33,6 +33,7 @@ fn test_config_update_flow() {
 
     // Create initial system config
     let mut initial_config = SystemConfig {
+        schema_version: fluxion_core::CONFIG_SCHEMA_VERSION,
         inverters: vec![],
         pricing_config: fluxion_core::PricingConfig {
             spot_price_entity: "sensor.spot_price".to_string(),
@@ -46,6 +47,9 @@ fn test_config_update_flow() {
             hdo_sensor_entity: "sensor.cez_hdo_raw_data".to_string(),
             hdo_low_tariff_czk: 0.50,
             hdo_high_tariff_czk: 1.80,
+            hdo_source: Default::default(),
+            hdo_preset: Default::default(),
+            hdo_windows: Vec::new(),
         },
         control_config: fluxion_core::ControlConfig {
             force_charge_hours: 2,
@@ -65,6 +69,10 @@ fn test_config_update_flow() {
         history: Default::default(),
         solar_forecast: Default::default(),
         remote_access: Default::default(),
+        logging: Default::default(),
+        grid_quality: Default::default(),
+        peak_demand: Default::default(),
+        weather: Default::default(),
     };
 
     // Create config update channel
@@ -74,6 +82,7 @@ fn test_config_update_flow() {
     let plugin_manager = create_plugin_manager(
         Some(&initial_config.strategies_config),
         &initial_config.control_config,
+        None,
     );
     let plugin_manager_res = PluginManagerResource(Arc::new(RwLock::new(plugin_manager)));
 
@@ -155,6 +164,7 @@ fn test_config_update_no_schedule_recalc_when_not_needed() {
 
     // Create initial system config
     let mut initial_config = SystemConfig {
+        schema_version: fluxion_core::CONFIG_SCHEMA_VERSION,
         inverters: vec![],
         pricing_config: fluxion_core::PricingConfig {
             spot_price_entity: "sensor.spot_price".to_string(),
@@ -168,6 +178,9 @@ fn test_config_update_no_schedule_recalc_when_not_needed() {
             hdo_sensor_entity: "sensor.cez_hdo_raw_data".to_string(),
             hdo_low_tariff_czk: 0.50,
             hdo_high_tariff_czk: 1.80,
+            hdo_source: Default::default(),
+            hdo_preset: Default::default(),
+            hdo_windows: Vec::new(),
         },
         control_config: Default::default(),
         system_config: fluxion_core::SystemSettingsConfig {
@@ -181,6 +194,10 @@ fn test_config_update_no_schedule_recalc_when_not_needed() {
         history: Default::default(),
         solar_forecast: Default::default(),
         remote_access: Default::default(),
+        logging: Default::default(),
+        grid_quality: Default::default(),
+        peak_demand: Default::default(),
+        weather: Default::default(),
     };
 
     // Create config update channel
@@ -190,6 +207,7 @@ fn test_config_update_no_schedule_recalc_when_not_needed() {
     let plugin_manager = create_plugin_manager(
         Some(&initial_config.strategies_config),
         &initial_config.control_config,
+        None,
     );
     let plugin_manager_res = PluginManagerResource(Arc::new(RwLock::new(plugin_manager)));
 
@@ -233,3 +251,87 @@ fn test_config_update_no_schedule_recalc_when_not_needed() {
         "Schedule should not be created for System config changes"
     );
 }
+
+#[test]
+fn test_strategy_update_reinitializes_builtin_strategies() {
+    let mut app = App::new();
+
+    let mut initial_config = SystemConfig {
+        schema_version: fluxion_core::CONFIG_SCHEMA_VERSION,
+        inverters: vec![],
+        pricing_config: fluxion_core::PricingConfig {
+            spot_price_entity: "sensor.spot_price".to_string(),
+            tomorrow_price_entity: None,
+            use_spot_prices_to_buy: true,
+            use_spot_prices_to_sell: true,
+            fixed_buy_price_czk: fluxion_core::PriceSchedule::Flat(4.0),
+            fixed_sell_price_czk: fluxion_core::PriceSchedule::Flat(2.0),
+            spot_buy_fee_czk: 0.5,
+            spot_sell_fee_czk: 0.5,
+            hdo_sensor_entity: "sensor.cez_hdo_raw_data".to_string(),
+            hdo_low_tariff_czk: 0.50,
+            hdo_high_tariff_czk: 1.80,
+            hdo_source: Default::default(),
+            hdo_preset: Default::default(),
+            hdo_windows: Vec::new(),
+        },
+        control_config: Default::default(),
+        system_config: fluxion_core::SystemSettingsConfig {
+            update_interval_secs: 60,
+            debug_mode: true,
+            display_currency: fluxion_core::Currency::CZK,
+            language: Language::English,
+            timezone: None,
+        },
+        strategies_config: Default::default(),
+        history: Default::default(),
+        solar_forecast: Default::default(),
+        remote_access: Default::default(),
+        logging: Default::default(),
+        grid_quality: Default::default(),
+        peak_demand: Default::default(),
+        weather: Default::default(),
+    };
+
+    let (config_sender, config_channel) = ConfigUpdateSender::new();
+    let plugin_manager = create_plugin_manager(
+        Some(&initial_config.strategies_config),
+        &initial_config.control_config,
+        None,
+    );
+    let plugin_manager = Arc::new(RwLock::new(plugin_manager));
+
+    app.insert_resource(initial_config.clone());
+    app.insert_resource(config_channel);
+    app.insert_resource(DebugModeConfig::default());
+    app.insert_resource(ConsumptionHistory::default());
+    app.insert_resource(PluginManagerResource(Arc::clone(&plugin_manager)));
+
+    let priority_of = |name: &str| {
+        plugin_manager
+            .read()
+            .list_plugins()
+            .into_iter()
+            .find(|(plugin, _, _)| *plugin == name)
+            .map(|(_, priority, _)| priority)
+    };
+    let plugin_count = plugin_manager.read().list_plugins().len();
+    assert_ne!(priority_of("FP-Arbitrage"), Some(42));
+
+    // Reprioritize one strategy, as PUT /api/config/strategies/{key} does
+    initial_config
+        .strategies_config
+        .fixed_price_arbitrage
+        .priority = 42;
+    let config_json = serde_json::to_value(&initial_config).expect("Failed to serialize config");
+    config_sender
+        .send_update(ConfigUpdateEvent::full_update(config_json))
+        .expect("Failed to send config update");
+
+    app.world_mut()
+        .run_system_once(fluxion_core::async_systems::config_event_handler)
+        .expect("Failed to run config event handler");
+
+    assert_eq!(priority_of("FP-Arbitrage"), Some(42));
+    assert_eq!(plugin_manager.read().list_plugins().len(), plugin_count);
+}
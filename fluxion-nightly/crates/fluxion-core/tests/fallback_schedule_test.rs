@@ -0,0 +1,137 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+// Schedule generation without any usable strategy plugin
+
+use chrono::{Duration, DurationRound, Utc};
+use fluxion_core::metrics::Metrics;
+use fluxion_core::plugin_adapters::create_plugin_manager;
+use fluxion_core::scheduling::{
+    HORIZON_DECISION_UID, ScheduleConfig, fallback_block_count, generate_schedule_with_optimizer,
+};
+use fluxion_plugins::{FALLBACK_DECISION_UID, PluginManager};
+use fluxion_types::config::ControlConfig;
+use fluxion_types::inverter::InverterOperationMode;
+use fluxion_types::pricing::TimeBlockPrice;
+
+/// 12 hours starting in an hour: cheap night, expensive evening
+fn prices() -> Vec<TimeBlockPrice> {
+    let start = Utc::now().duration_trunc(Duration::minutes(15)).unwrap() + Duration::hours(1);
+    (0..48)
+        .map(|i| {
+            let price = if i < 12 {
+                1.0
+            } else if i >= 36 {
+                6.0
+            } else {
+                3.0
+            };
+            TimeBlockPrice {
+                block_start: start + Duration::minutes(15 * i),
+                duration_minutes: 15,
+                price_czk_per_kwh: price,
+                effective_price_czk_per_kwh: price,
+                spot_sell_price_czk_per_kwh: None,
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn empty_plugin_manager_uses_price_percentile_fallback() {
+    let schedule = generate_schedule_with_optimizer(
+        &prices(),
+        &ControlConfig::default(),
+        &ScheduleConfig::default(),
+        30.0,
+        None,
+        None,
+        10.0,
+        None,
+        &PluginManager::new(),
+        None,
+        0.0,
+        0.0,
+        0.0,
+        None,
+        None,
+    );
+
+    assert_eq!(schedule.scheduled_blocks.len(), 48);
+    assert!(
+        schedule
+            .scheduled_blocks
+            .iter()
+            .all(|b| b.decision_uid.as_deref() == Some(FALLBACK_DECISION_UID))
+    );
+    assert_eq!(fallback_block_count(&schedule, Utc::now()), 48);
+
+    let modes: Vec<InverterOperationMode> =
+        schedule.scheduled_blocks.iter().map(|b| b.mode).collect();
+    assert_eq!(modes[0], InverterOperationMode::ForceCharge);
+    assert!(
+        modes[36..]
+            .iter()
+            .all(|m| *m == InverterOperationMode::SelfUse)
+    );
+}
+
+#[test]
+fn blocks_beyond_horizon_use_heuristic_without_flagging_fallback() {
+    let control = ControlConfig {
+        optimization_horizon_hours: 6,
+        ..ControlConfig::default()
+    };
+    let plugin_manager = create_plugin_manager(None, &control, None);
+    let horizon_end = Utc::now() + Duration::hours(6);
+
+    let schedule = generate_schedule_with_optimizer(
+        &prices(),
+        &control,
+        &ScheduleConfig::default(),
+        30.0,
+        None,
+        None,
+        10.0,
+        None,
+        &plugin_manager,
+        None,
+        0.0,
+        0.0,
+        0.0,
+        None,
+        None,
+    );
+
+    // The strategies plan up to the horizon, the heuristic takes over from there
+    let switch = schedule
+        .scheduled_blocks
+        .iter()
+        .position(|b| b.decision_uid.as_deref() == Some(HORIZON_DECISION_UID))
+        .expect("blocks past the horizon");
+    let (within, beyond) = schedule.scheduled_blocks.split_at(switch);
+    assert!(!within.is_empty());
+    assert!(within.iter().all(|b| b.block_start < horizon_end));
+    assert!(beyond[0].block_start >= horizon_end);
+    assert!(within.iter().all(|b| {
+        b.decision_uid
+            .as_deref()
+            .is_some_and(|uid| uid != HORIZON_DECISION_UID && uid != FALLBACK_DECISION_UID)
+    }));
+    assert!(
+        beyond
+            .iter()
+            .all(|b| b.decision_uid.as_deref() == Some(HORIZON_DECISION_UID))
+    );
+    assert_eq!(fallback_block_count(&schedule, Utc::now()), 0);
+    assert!(Metrics::global().last_schedule_generation().is_some());
+}
@@ -0,0 +1,463 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Conversions between the web bridge types and the gRPC messages.
+
+use crate::proto;
+use chrono::{DateTime, Utc};
+use fluxion_core::web_bridge::{
+    InverterData, PriceData, ScheduleData, SystemHealthData, WebQueryResponse,
+};
+use fluxion_types::user_control::{FixedTimeSlot, UserControlIssue};
+use fluxion_types::{InverterOperationMode, UserControlState};
+
+impl From<InverterOperationMode> for proto::Mode {
+    fn from(mode: InverterOperationMode) -> Self {
+        match mode {
+            InverterOperationMode::SelfUse => Self::SelfUse,
+            InverterOperationMode::BackUpMode => Self::BackUp,
+            InverterOperationMode::ForceCharge => Self::ForceCharge,
+            InverterOperationMode::ForceDischarge => Self::ForceDischarge,
+            InverterOperationMode::NoChargeNoDischarge => Self::NoChargeNoDischarge,
+        }
+    }
+}
+
+impl proto::Mode {
+    /// The operation mode, or None for [`proto::Mode::Unspecified`]
+    #[must_use]
+    pub fn operation_mode(self) -> Option<InverterOperationMode> {
+        match self {
+            Self::Unspecified => None,
+            Self::SelfUse => Some(InverterOperationMode::SelfUse),
+            Self::BackUp => Some(InverterOperationMode::BackUpMode),
+            Self::ForceCharge => Some(InverterOperationMode::ForceCharge),
+            Self::ForceDischarge => Some(InverterOperationMode::ForceDischarge),
+            Self::NoChargeNoDischarge => Some(InverterOperationMode::NoChargeNoDischarge),
+        }
+    }
+}
+
+fn count(n: usize) -> u32 {
+    u32::try_from(n).unwrap_or(u32::MAX)
+}
+
+impl From<&WebQueryResponse> for proto::Dashboard {
+    fn from(response: &WebQueryResponse) -> Self {
+        Self {
+            timestamp: response.timestamp.timestamp(),
+            debug_mode: response.debug_mode,
+            inverters: response.inverters.iter().map(Into::into).collect(),
+            schedule: response.schedule.as_ref().map(Into::into),
+            prices: response.prices.as_ref().map(Into::into),
+            health: Some((&response.health).into()),
+            timezone: response.timezone.clone(),
+        }
+    }
+}
+
+impl From<&InverterData> for proto::Inverter {
+    fn from(inverter: &InverterData) -> Self {
+        Self {
+            id: inverter.id.clone(),
+            topology: inverter.topology.clone(),
+            mode: inverter.mode.clone(),
+            mode_reason: inverter.mode_reason.clone(),
+            actual_mode: inverter.actual_mode.clone(),
+            mode_synced: inverter.mode_synced,
+            battery_soc: inverter.battery_soc,
+            battery_power_w: inverter.battery_power_w,
+            battery_temperature_c: inverter.battery_temperature_c,
+            grid_power_w: inverter.grid_power_w,
+            pv_power_w: inverter.pv_power_w,
+            house_load_w: inverter.house_load_w,
+            daily_energy_kwh: inverter.daily_energy_kwh,
+            online: inverter.online,
+            run_mode: inverter.run_mode.clone(),
+            error_code: u32::from(inverter.error_code),
+            inverter_temperature_c: inverter.inverter_temperature_c,
+        }
+    }
+}
+
+impl From<&ScheduleData> for proto::ScheduleSummary {
+    fn from(schedule: &ScheduleData) -> Self {
+        Self {
+            current_mode: schedule.current_mode.clone(),
+            current_reason: schedule.current_reason.clone(),
+            current_strategy: schedule.current_strategy.clone(),
+            expected_profit: schedule.expected_profit,
+            next_change: schedule.next_change.map(|t| t.timestamp()),
+            target_soc_max: schedule.target_soc_max,
+            target_soc_min: schedule.target_soc_min,
+            total_expected_profit: schedule.total_expected_profit,
+            total_blocks_scheduled: count(schedule.total_blocks_scheduled),
+            generated_at: schedule.schedule_generated_at.timestamp(),
+            ends_at: schedule.schedule_ends_at.map(|t| t.timestamp()),
+        }
+    }
+}
+
+impl From<&PriceData> for proto::Prices {
+    fn from(prices: &PriceData) -> Self {
+        Self {
+            current_price: prices.current_price,
+            min_price: prices.min_price,
+            max_price: prices.max_price,
+            avg_price: prices.avg_price,
+        }
+    }
+}
+
+impl From<&SystemHealthData> for proto::Health {
+    fn from(health: &SystemHealthData) -> Self {
+        Self {
+            inverter_source: health.inverter_source,
+            price_source: health.price_source,
+            last_update: health.last_update.timestamp(),
+            errors: health.errors.clone(),
+        }
+    }
+}
+
+impl From<&WebQueryResponse> for proto::Schedule {
+    fn from(response: &WebQueryResponse) -> Self {
+        Self {
+            generated_at: response
+                .schedule
+                .as_ref()
+                .map(|s| s.schedule_generated_at.timestamp()),
+            blocks: response
+                .prices
+                .iter()
+                .flat_map(|p| &p.blocks)
+                .map(|block| proto::ScheduleBlock {
+                    start: block.timestamp.timestamp(),
+                    price: block.price,
+                    block_type: block.block_type.clone(),
+                    target_soc: block.target_soc,
+                    strategy: block.strategy.clone(),
+                    expected_profit: block.expected_profit,
+                    reason: block.reason.clone(),
+                    is_historical: block.is_historical,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&WebQueryResponse> for proto::Telemetry {
+    fn from(response: &WebQueryResponse) -> Self {
+        Self {
+            timestamp: response.timestamp.timestamp(),
+            inverters: response.inverters.iter().map(Into::into).collect(),
+            current_price: response.prices.as_ref().map(|p| p.current_price),
+            current_mode: response.schedule.as_ref().map(|s| s.current_mode.clone()),
+        }
+    }
+}
+
+impl From<&FixedTimeSlot> for proto::Slot {
+    fn from(slot: &FixedTimeSlot) -> Self {
+        Self {
+            id: slot.id.clone(),
+            from: slot.from.timestamp(),
+            to: slot.to.timestamp(),
+            mode: proto::Mode::from(slot.mode).into(),
+            note: slot.note.clone(),
+        }
+    }
+}
+
+impl proto::UserControl {
+    /// Current user control state together with the warnings of the last change
+    #[must_use]
+    pub fn new(state: &UserControlState, warnings: &[UserControlIssue]) -> Self {
+        Self {
+            enabled: state.enabled,
+            disallow_charge: state.disallow_charge,
+            disallow_discharge: state.disallow_discharge,
+            slots: state.fixed_time_slots.iter().map(Into::into).collect(),
+            warnings: warnings.iter().map(|w| w.message.clone()).collect(),
+            last_modified: state.last_modified.map(|t| t.timestamp()),
+        }
+    }
+}
+
+/// Unix seconds to a timestamp, None when out of range
+pub(crate) fn timestamp(seconds: i64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(seconds, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_core::web_bridge::PriceBlockData;
+    use prost::Message;
+
+    /// Send a message over the wire and back
+    fn reencode<M: Message + Default>(message: &M) -> M {
+        M::decode(message.encode_to_vec().as_slice()).unwrap()
+    }
+
+    fn at(rfc3339: &str) -> DateTime<Utc> {
+        rfc3339.parse().unwrap()
+    }
+
+    fn block(timestamp: &str, block_type: &str, is_historical: bool) -> PriceBlockData {
+        PriceBlockData {
+            timestamp: at(timestamp),
+            price: 2.5,
+            block_type: block_type.to_owned(),
+            target_soc: Some(80.0),
+            planned_soc: None,
+            decision_reason: None,
+            strategy: Some("Winter-Adaptive".to_owned()),
+            expected_profit: Some(1.25),
+            reason: Some("Cheapest block".to_owned()),
+            decision_uid: None,
+            debug_info: None,
+            forecast: None,
+            actual: None,
+            tariff: None,
+            is_historical,
+        }
+    }
+
+    fn response() -> WebQueryResponse {
+        let inverter: InverterData = serde_json::from_value(serde_json::json!({
+            "id": "main",
+            "topology": "independent",
+            "mode": "ForceCharge",
+            "mode_reason": "Cheapest block",
+            "actual_mode": "ForceCharge",
+            "mode_synced": true,
+            "battery_soc": 55.0,
+            "battery_power_w": 3000.0,
+            "battery_voltage_v": 400.0,
+            "battery_current_a": 7.5,
+            "battery_temperature_c": 21.0,
+            "grid_power_w": -3500.0,
+            "grid_voltage_v": 230.0,
+            "grid_frequency_hz": 50.0,
+            "pv_power_w": 0.0,
+            "pv1_power_w": 0.0,
+            "pv2_power_w": 0.0,
+            "daily_energy_kwh": 4.5,
+            "total_energy_kwh": 1234.0,
+            "online": true,
+            "run_mode": "Normal",
+            "error_code": 513,
+            "inverter_temperature_c": 35.0,
+            "house_load_w": 500.0,
+        }))
+        .unwrap();
+        WebQueryResponse {
+            timestamp: at("2025-06-01T10:05:00Z"),
+            debug_mode: false,
+            inverters: vec![inverter],
+            schedule: Some(ScheduleData {
+                current_mode: "ForceCharge".to_owned(),
+                current_reason: "Cheapest block".to_owned(),
+                current_decision_reason: None,
+                current_strategy: Some("Winter-Adaptive".to_owned()),
+                expected_profit: Some(1.25),
+                next_change: Some(at("2025-06-01T10:15:00Z")),
+                blocks_today: 96,
+                target_soc_max: 100.0,
+                target_soc_min: 10.0,
+                total_expected_profit: None,
+                total_blocks_scheduled: 140,
+                schedule_hours: 35.0,
+                schedule_generated_at: at("2025-06-01T10:00:00Z"),
+                schedule_ends_at: None,
+            }),
+            prices: Some(PriceData {
+                current_price: 2.5,
+                min_price: 1.0,
+                max_price: 6.0,
+                avg_price: 3.0,
+                blocks: vec![
+                    block("2025-06-01T09:45:00Z", "charge", true),
+                    block("2025-06-01T10:00:00Z", "self-use", false),
+                ],
+                today_min_price: 1.0,
+                today_max_price: 6.0,
+                today_avg_price: 3.0,
+                today_median_price: 2.8,
+                tomorrow_min_price: None,
+                tomorrow_max_price: None,
+                tomorrow_avg_price: None,
+                tomorrow_median_price: None,
+            }),
+            health: SystemHealthData {
+                inverter_source: true,
+                price_source: false,
+                last_update: at("2025-06-01T10:04:00Z"),
+                errors: vec!["prices stale".to_owned()],
+            },
+            timezone: Some("Europe/Prague".to_owned()),
+            battery_soc_history: None,
+            battery_soc_prediction: None,
+            pv_generation_history: None,
+            battery_power_history: None,
+            grid_power_history: None,
+            consumption_stats: None,
+            hdo_schedule: None,
+            pricing_fees: None,
+            solar_forecast: None,
+        }
+    }
+
+    #[test]
+    fn test_every_mode_round_trips() {
+        for mode in [
+            InverterOperationMode::SelfUse,
+            InverterOperationMode::BackUpMode,
+            InverterOperationMode::ForceCharge,
+            InverterOperationMode::ForceDischarge,
+            InverterOperationMode::NoChargeNoDischarge,
+        ] {
+            let wire = proto::Mode::try_from(i32::from(proto::Mode::from(mode))).unwrap();
+            assert_eq!(wire.operation_mode(), Some(mode));
+        }
+        assert_eq!(proto::Mode::Unspecified.operation_mode(), None);
+    }
+
+    #[test]
+    fn test_timestamps_round_trip() {
+        for time in ["1970-01-01T00:00:00Z", "2025-06-01T10:15:00Z"] {
+            assert_eq!(timestamp(at(time).timestamp()), Some(at(time)));
+        }
+        assert_eq!(timestamp(i64::MAX), None);
+    }
+
+    #[test]
+    fn test_dashboard_round_trips() {
+        let response = response();
+
+        let dashboard = reencode(&proto::Dashboard::from(&response));
+
+        assert_eq!(timestamp(dashboard.timestamp), Some(response.timestamp));
+        assert!(!dashboard.debug_mode);
+        assert_eq!(dashboard.timezone.as_deref(), Some("Europe/Prague"));
+
+        let inverter = &dashboard.inverters[0];
+        assert_eq!(inverter.id, "main");
+        assert_eq!(inverter.actual_mode.as_deref(), Some("ForceCharge"));
+        assert!(inverter.mode_synced);
+        assert_eq!(inverter.battery_soc, 55.0);
+        assert_eq!(inverter.grid_power_w, -3500.0);
+        assert_eq!(inverter.house_load_w, Some(500.0));
+        assert_eq!(inverter.error_code, 513);
+
+        let schedule = dashboard.schedule.unwrap();
+        assert_eq!(
+            schedule.current_strategy.as_deref(),
+            Some("Winter-Adaptive")
+        );
+        assert_eq!(
+            schedule.next_change.and_then(timestamp),
+            Some(at("2025-06-01T10:15:00Z"))
+        );
+        assert_eq!(schedule.total_blocks_scheduled, 140);
+        assert_eq!(schedule.total_expected_profit, None);
+        assert_eq!(schedule.ends_at, None);
+
+        let prices = dashboard.prices.unwrap();
+        assert_eq!((prices.min_price, prices.max_price), (1.0, 6.0));
+
+        let health = dashboard.health.unwrap();
+        assert!(!health.price_source);
+        assert_eq!(
+            timestamp(health.last_update),
+            Some(response.health.last_update)
+        );
+        assert_eq!(health.errors, ["prices stale"]);
+    }
+
+    #[test]
+    fn test_schedule_and_telemetry_round_trip() {
+        let response = response();
+
+        let schedule = reencode(&proto::Schedule::from(&response));
+        assert_eq!(
+            schedule.generated_at.and_then(timestamp),
+            Some(at("2025-06-01T10:00:00Z"))
+        );
+        assert_eq!(schedule.blocks.len(), 2);
+        assert_eq!(
+            timestamp(schedule.blocks[1].start),
+            Some(at("2025-06-01T10:00:00Z"))
+        );
+        assert_eq!(schedule.blocks[1].block_type, "self-use");
+        assert_eq!(schedule.blocks[1].target_soc, Some(80.0));
+        assert!(schedule.blocks[0].is_historical);
+        assert!(!schedule.blocks[1].is_historical);
+
+        let telemetry = reencode(&proto::Telemetry::from(&response));
+        assert_eq!(telemetry.current_price, Some(2.5));
+        assert_eq!(telemetry.current_mode.as_deref(), Some("ForceCharge"));
+        assert_eq!(telemetry.inverters.len(), 1);
+
+        // Nothing planned yet
+        let empty = WebQueryResponse {
+            schedule: None,
+            prices: None,
+            ..response
+        };
+        let schedule = reencode(&proto::Schedule::from(&empty));
+        assert_eq!(schedule.generated_at, None);
+        assert!(schedule.blocks.is_empty());
+        assert_eq!(
+            reencode(&proto::Telemetry::from(&empty)).current_price,
+            None
+        );
+    }
+
+    #[test]
+    fn test_user_control_round_trips() {
+        let slot = FixedTimeSlot::new(
+            at("2025-06-01T18:00:00Z"),
+            at("2025-06-01T19:00:00Z"),
+            InverterOperationMode::ForceDischarge,
+            Some("Evening peak".to_owned()),
+        );
+        let state = UserControlState {
+            enabled: true,
+            disallow_charge: true,
+            fixed_time_slots: vec![slot.clone()],
+            last_modified: Some(at("2025-06-01T12:00:00Z")),
+            ..UserControlState::default()
+        };
+
+        let user_control = reencode(&proto::UserControl::new(&state, &[]));
+
+        assert!(user_control.enabled);
+        assert!(user_control.disallow_charge);
+        assert!(!user_control.disallow_discharge);
+        assert!(user_control.warnings.is_empty());
+        assert_eq!(
+            user_control.last_modified.and_then(timestamp),
+            state.last_modified
+        );
+        let wire = &user_control.slots[0];
+        assert_eq!(wire.id, slot.id);
+        assert_eq!(timestamp(wire.from), Some(slot.from));
+        assert_eq!(timestamp(wire.to), Some(slot.to));
+        assert_eq!(
+            proto::Mode::try_from(wire.mode).unwrap().operation_mode(),
+            Some(slot.mode)
+        );
+        assert_eq!(wire.note.as_deref(), Some("Evening peak"));
+    }
+}
@@ -0,0 +1,166 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! gRPC API for external orchestrators.
+//!
+//! Exposes the `fluxion.v1` services defined in `proto/fluxion.proto`:
+//! - **Query**: dashboard state and schedule, the same data the web UI shows
+//! - **Control**: user control overrides, validated and persisted like web UI changes
+//! - **Streaming**: periodic telemetry updates
+//!
+//! Requests go through the same [`WebQuerySender`] and [`UserControlApiState`] as the
+//! web server, and are checked against its API keys when any are configured.
+
+mod convert;
+pub mod proto;
+mod service;
+
+pub use service::{ControlService, QueryService, StreamingService};
+
+use fluxion_core::WebQuerySender;
+use fluxion_web::{ApiKeyCheck, ApiKeyScope, ApiKeyStore, UserControlApiState};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use tonic::Status;
+use tonic::codegen::http;
+use tracing::{info, warn};
+
+/// Port the gRPC server listens on by default
+pub const DEFAULT_GRPC_PORT: u16 = 50051;
+
+/// Address the gRPC server binds to by default, reachable from this host only
+pub const DEFAULT_GRPC_BIND_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+/// State shared by all gRPC services
+#[derive(Clone)]
+pub struct GrpcState {
+    pub query_sender: WebQuerySender,
+    pub user_control: UserControlApiState,
+    /// Keys of the web API; None or an empty store leaves the API open
+    pub api_keys: Option<Arc<ApiKeyStore>>,
+}
+
+impl std::fmt::Debug for GrpcState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GrpcState")
+            .field("user_control", &self.user_control)
+            .field("api_keys", &self.api_keys.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl GrpcState {
+    /// Whether serving on `addr` would leave the API open to other hosts
+    ///
+    /// Without API keys every call is accepted, so such a server must stay on loopback.
+    #[must_use]
+    pub fn is_open_on(&self, addr: SocketAddr) -> bool {
+        !addr.ip().is_loopback() && !self.api_keys.as_ref().is_some_and(|s| s.has_keys())
+    }
+
+    /// Check the API key in the request metadata against `scope`
+    fn authorize<B>(&self, request: &http::Request<B>, scope: ApiKeyScope) -> Result<(), Status> {
+        let Some(store) = self.api_keys.as_ref().filter(|s| s.has_keys()) else {
+            return Ok(());
+        };
+        let key = presented_key(request.headers())
+            .ok_or_else(|| Status::unauthenticated("API key required"))?;
+
+        match store.check(&key, scope, request.uri().path()) {
+            ApiKeyCheck::Granted { .. } => Ok(()),
+            ApiKeyCheck::MissingScope { key_id } => {
+                warn!(
+                    "🔑 API key {key_id} denied: missing scope {} for gRPC {}",
+                    scope.as_str(),
+                    request.uri().path()
+                );
+                Err(Status::permission_denied(format!(
+                    "API key lacks scope {}",
+                    scope.as_str()
+                )))
+            }
+            ApiKeyCheck::UnknownKey => {
+                warn!("🔑 Unknown API key on gRPC {}", request.uri().path());
+                Err(Status::unauthenticated("Invalid API key"))
+            }
+        }
+    }
+}
+
+/// Key from `authorization: Bearer <key>` or `x-api-key` metadata
+fn presented_key(headers: &http::HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get(http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let header = headers.get("x-api-key").and_then(|v| v.to_str().ok());
+
+    bearer
+        .or(header)
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// Serve the Query, Control and Streaming services on `addr`
+///
+/// # Errors
+/// Returns error if the server fails to bind or serve
+pub async fn start_grpc_server(
+    state: GrpcState,
+    addr: SocketAddr,
+) -> Result<(), tonic::transport::Error> {
+    info!("📡 gRPC API listening on {addr}");
+    tonic::transport::Server::builder()
+        .add_service(QueryService::new(state.clone()))
+        .add_service(ControlService::new(state.clone()))
+        .add_service(StreamingService::new(state))
+        .serve(addr)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_types::UserControlState;
+
+    fn state(dir: &tempfile::TempDir, api_keys: Option<Arc<ApiKeyStore>>) -> GrpcState {
+        GrpcState {
+            query_sender: WebQuerySender::new().0,
+            user_control: UserControlApiState::new(
+                UserControlState::default(),
+                dir.path().join("user_control.json").to_string_lossy(),
+                None,
+            ),
+            api_keys,
+        }
+    }
+
+    #[test]
+    fn test_open_api_only_on_loopback() {
+        let dir = tempfile::tempdir().unwrap();
+        let loopback = SocketAddr::new(DEFAULT_GRPC_BIND_ADDRESS, DEFAULT_GRPC_PORT);
+        let everywhere = SocketAddr::from(([0, 0, 0, 0], DEFAULT_GRPC_PORT));
+
+        let no_store = state(&dir, None);
+        assert!(!no_store.is_open_on(loopback));
+        assert!(no_store.is_open_on(everywhere));
+
+        let store = Arc::new(ApiKeyStore::new(dir.path()));
+        let keyed = state(&dir, Some(store.clone()));
+        assert!(keyed.is_open_on(everywhere));
+        store
+            .create("orchestrator", &[ApiKeyScope::ReadTelemetry])
+            .unwrap();
+        assert!(!keyed.is_open_on(everywhere));
+    }
+}
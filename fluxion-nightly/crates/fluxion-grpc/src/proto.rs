@@ -0,0 +1,250 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Messages of the `fluxion.v1` package.
+//!
+//! Written by hand so the build doesn't need `protoc`; field tags must match
+//! `proto/fluxion.proto`, which is the contract clients generate code from.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Mode {
+    Unspecified = 0,
+    SelfUse = 1,
+    BackUp = 2,
+    ForceCharge = 3,
+    ForceDischarge = 4,
+    NoChargeNoDischarge = 5,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct GetDashboardRequest {}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct GetScheduleRequest {}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct GetUserControlRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Dashboard {
+    #[prost(int64, tag = "1")]
+    pub timestamp: i64,
+    #[prost(bool, tag = "2")]
+    pub debug_mode: bool,
+    #[prost(message, repeated, tag = "3")]
+    pub inverters: Vec<Inverter>,
+    #[prost(message, optional, tag = "4")]
+    pub schedule: Option<ScheduleSummary>,
+    #[prost(message, optional, tag = "5")]
+    pub prices: Option<Prices>,
+    #[prost(message, optional, tag = "6")]
+    pub health: Option<Health>,
+    #[prost(string, optional, tag = "7")]
+    pub timezone: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Inverter {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub topology: String,
+    #[prost(string, tag = "3")]
+    pub mode: String,
+    #[prost(string, tag = "4")]
+    pub mode_reason: String,
+    #[prost(string, optional, tag = "5")]
+    pub actual_mode: Option<String>,
+    #[prost(bool, tag = "6")]
+    pub mode_synced: bool,
+    #[prost(float, tag = "7")]
+    pub battery_soc: f32,
+    #[prost(float, tag = "8")]
+    pub battery_power_w: f32,
+    #[prost(float, tag = "9")]
+    pub battery_temperature_c: f32,
+    #[prost(float, tag = "10")]
+    pub grid_power_w: f32,
+    #[prost(float, tag = "11")]
+    pub pv_power_w: f32,
+    #[prost(float, optional, tag = "12")]
+    pub house_load_w: Option<f32>,
+    #[prost(float, tag = "13")]
+    pub daily_energy_kwh: f32,
+    #[prost(bool, tag = "14")]
+    pub online: bool,
+    #[prost(string, tag = "15")]
+    pub run_mode: String,
+    #[prost(uint32, tag = "16")]
+    pub error_code: u32,
+    #[prost(float, tag = "17")]
+    pub inverter_temperature_c: f32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScheduleSummary {
+    #[prost(string, tag = "1")]
+    pub current_mode: String,
+    #[prost(string, tag = "2")]
+    pub current_reason: String,
+    #[prost(string, optional, tag = "3")]
+    pub current_strategy: Option<String>,
+    #[prost(float, optional, tag = "4")]
+    pub expected_profit: Option<f32>,
+    #[prost(int64, optional, tag = "5")]
+    pub next_change: Option<i64>,
+    #[prost(float, tag = "6")]
+    pub target_soc_max: f32,
+    #[prost(float, tag = "7")]
+    pub target_soc_min: f32,
+    #[prost(float, optional, tag = "8")]
+    pub total_expected_profit: Option<f32>,
+    #[prost(uint32, tag = "9")]
+    pub total_blocks_scheduled: u32,
+    #[prost(int64, tag = "10")]
+    pub generated_at: i64,
+    #[prost(int64, optional, tag = "11")]
+    pub ends_at: Option<i64>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Prices {
+    #[prost(float, tag = "1")]
+    pub current_price: f32,
+    #[prost(float, tag = "2")]
+    pub min_price: f32,
+    #[prost(float, tag = "3")]
+    pub max_price: f32,
+    #[prost(float, tag = "4")]
+    pub avg_price: f32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Health {
+    #[prost(bool, tag = "1")]
+    pub inverter_source: bool,
+    #[prost(bool, tag = "2")]
+    pub price_source: bool,
+    #[prost(int64, tag = "3")]
+    pub last_update: i64,
+    #[prost(string, repeated, tag = "4")]
+    pub errors: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Schedule {
+    #[prost(int64, optional, tag = "1")]
+    pub generated_at: Option<i64>,
+    #[prost(message, repeated, tag = "2")]
+    pub blocks: Vec<ScheduleBlock>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ScheduleBlock {
+    #[prost(int64, tag = "1")]
+    pub start: i64,
+    #[prost(float, tag = "2")]
+    pub price: f32,
+    #[prost(string, tag = "3")]
+    pub block_type: String,
+    #[prost(float, optional, tag = "4")]
+    pub target_soc: Option<f32>,
+    #[prost(string, optional, tag = "5")]
+    pub strategy: Option<String>,
+    #[prost(float, optional, tag = "6")]
+    pub expected_profit: Option<f32>,
+    #[prost(string, optional, tag = "7")]
+    pub reason: Option<String>,
+    #[prost(bool, tag = "8")]
+    pub is_historical: bool,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct UserControl {
+    #[prost(bool, tag = "1")]
+    pub enabled: bool,
+    #[prost(bool, tag = "2")]
+    pub disallow_charge: bool,
+    #[prost(bool, tag = "3")]
+    pub disallow_discharge: bool,
+    #[prost(message, repeated, tag = "4")]
+    pub slots: Vec<Slot>,
+    #[prost(string, repeated, tag = "5")]
+    pub warnings: Vec<String>,
+    #[prost(int64, optional, tag = "6")]
+    pub last_modified: Option<i64>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Slot {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(int64, tag = "2")]
+    pub from: i64,
+    #[prost(int64, tag = "3")]
+    pub to: i64,
+    #[prost(enumeration = "Mode", tag = "4")]
+    pub mode: i32,
+    #[prost(string, optional, tag = "5")]
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct SetEnabledRequest {
+    #[prost(bool, tag = "1")]
+    pub enabled: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct SetRestrictionsRequest {
+    #[prost(bool, optional, tag = "1")]
+    pub disallow_charge: Option<bool>,
+    #[prost(bool, optional, tag = "2")]
+    pub disallow_discharge: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct AddSlotRequest {
+    #[prost(int64, tag = "1")]
+    pub from: i64,
+    #[prost(int64, tag = "2")]
+    pub to: i64,
+    #[prost(enumeration = "Mode", tag = "3")]
+    pub mode: i32,
+    #[prost(string, optional, tag = "4")]
+    pub note: Option<String>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct RemoveSlotRequest {
+    #[prost(string, tag = "1")]
+    pub id: String,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct StreamTelemetryRequest {
+    #[prost(uint32, tag = "1")]
+    pub interval_seconds: u32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Telemetry {
+    #[prost(int64, tag = "1")]
+    pub timestamp: i64,
+    #[prost(message, repeated, tag = "2")]
+    pub inverters: Vec<Inverter>,
+    #[prost(float, optional, tag = "3")]
+    pub current_price: Option<f32>,
+    #[prost(string, optional, tag = "4")]
+    pub current_mode: Option<String>,
+}
@@ -0,0 +1,575 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Query, Control and Streaming services.
+//!
+//! Each service routes on the request path, like `tonic-build` generated servers,
+//! and runs the matching handler through [`tonic::server::Grpc`].
+
+use crate::GrpcState;
+use crate::convert::timestamp;
+use crate::proto::{
+    AddSlotRequest, Dashboard, GetDashboardRequest, GetScheduleRequest, GetUserControlRequest,
+    Mode, RemoveSlotRequest, Schedule, SetEnabledRequest, SetRestrictionsRequest,
+    StreamTelemetryRequest, Telemetry, UserControl,
+};
+use chrono::Utc;
+use fluxion_core::UserControlChangeType;
+use fluxion_core::web_bridge::QueryError;
+use fluxion_types::UserControlState;
+use fluxion_types::user_control::FixedTimeSlot;
+use fluxion_web::{ApiKeyScope, UserControlChangeError};
+use std::convert::Infallible;
+use std::future::Future;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::Status;
+use tonic::body::Body;
+use tonic::codegen::{BoxFuture, Service, http};
+use tonic::server::{Grpc, NamedService};
+use tonic_prost::ProstCodec;
+use tracing::{debug, info};
+
+/// Telemetry interval when the client asks for 0 seconds
+const DEFAULT_TELEMETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Define a service that checks `scope` and hands the request to `$route`
+macro_rules! grpc_service {
+    ($(#[$meta:meta])* $name:ident, $service:literal, $scope:expr, $route:path) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone)]
+        pub struct $name {
+            state: GrpcState,
+        }
+
+        impl $name {
+            #[must_use]
+            pub fn new(state: GrpcState) -> Self {
+                Self { state }
+            }
+        }
+
+        impl NamedService for $name {
+            const NAME: &'static str = $service;
+        }
+
+        impl Service<http::Request<Body>> for $name {
+            type Response = http::Response<Body>;
+            type Error = Infallible;
+            type Future = BoxFuture<Self::Response, Self::Error>;
+
+            fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn call(&mut self, request: http::Request<Body>) -> Self::Future {
+                let state = self.state.clone();
+                Box::pin(async move {
+                    Ok(match state.authorize(&request, $scope) {
+                        Ok(()) => $route(state, request).await,
+                        Err(status) => status.into_http(),
+                    })
+                })
+            }
+        }
+    };
+}
+
+grpc_service!(
+    /// `fluxion.v1.Query`: dashboard state and schedule
+    QueryService,
+    "fluxion.v1.Query",
+    ApiKeyScope::ReadTelemetry,
+    route_query
+);
+
+grpc_service!(
+    /// `fluxion.v1.Control`: user control overrides
+    ControlService,
+    "fluxion.v1.Control",
+    ApiKeyScope::WriteUserControl,
+    route_control
+);
+
+grpc_service!(
+    /// `fluxion.v1.Streaming`: live telemetry
+    StreamingService,
+    "fluxion.v1.Streaming",
+    ApiKeyScope::ReadTelemetry,
+    route_streaming
+);
+
+async fn route_query(state: GrpcState, request: http::Request<Body>) -> http::Response<Body> {
+    match request.uri().path() {
+        "/fluxion.v1.Query/GetDashboard" => unary(state, request, get_dashboard).await,
+        "/fluxion.v1.Query/GetSchedule" => unary(state, request, get_schedule).await,
+        path => unimplemented(path),
+    }
+}
+
+async fn route_control(state: GrpcState, request: http::Request<Body>) -> http::Response<Body> {
+    match request.uri().path() {
+        "/fluxion.v1.Control/GetUserControl" => unary(state, request, get_user_control).await,
+        "/fluxion.v1.Control/SetEnabled" => unary(state, request, set_enabled).await,
+        "/fluxion.v1.Control/SetRestrictions" => unary(state, request, set_restrictions).await,
+        "/fluxion.v1.Control/AddSlot" => unary(state, request, add_slot).await,
+        "/fluxion.v1.Control/RemoveSlot" => unary(state, request, remove_slot).await,
+        path => unimplemented(path),
+    }
+}
+
+async fn route_streaming(state: GrpcState, request: http::Request<Body>) -> http::Response<Body> {
+    match request.uri().path() {
+        "/fluxion.v1.Streaming/StreamTelemetry" => {
+            let service =
+                tower::service_fn(move |request: tonic::Request<StreamTelemetryRequest>| {
+                    let stream = stream_telemetry(state.clone(), *request.get_ref());
+                    async move { Ok::<_, Status>(tonic::Response::new(stream)) }
+                });
+            Grpc::new(ProstCodec::default())
+                .server_streaming(service, request)
+                .await
+        }
+        path => unimplemented(path),
+    }
+}
+
+fn unimplemented(path: &str) -> http::Response<Body> {
+    Status::unimplemented(format!("Unknown method {path}")).into_http()
+}
+
+/// Decode the request, run `handler` and encode its reply
+async fn unary<Req, Reply, F, Fut>(
+    state: GrpcState,
+    request: http::Request<Body>,
+    handler: F,
+) -> http::Response<Body>
+where
+    Req: prost::Message + Default + Send + 'static,
+    Reply: prost::Message + Send + 'static,
+    F: Fn(GrpcState, Req) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = Result<Reply, Status>> + Send + 'static,
+{
+    let service = tower::service_fn(move |request: tonic::Request<Req>| {
+        let reply = handler(state.clone(), request.into_inner());
+        async move { reply.await.map(tonic::Response::new) }
+    });
+    Grpc::new(ProstCodec::default())
+        .unary(service, request)
+        .await
+}
+
+fn query_status(error: QueryError) -> Status {
+    if error.is_overload() {
+        Status::unavailable(error.to_string())
+    } else {
+        Status::internal(error.to_string())
+    }
+}
+
+fn change_status(error: &UserControlChangeError) -> Status {
+    let message = error.message();
+    match error {
+        UserControlChangeError::Conflict(_) => Status::failed_precondition(message),
+        UserControlChangeError::Status(status) => match status.as_u16() {
+            400 => Status::invalid_argument(message),
+            404 => Status::not_found(message),
+            _ => Status::internal(message),
+        },
+    }
+}
+
+// ==================== Query ====================
+
+async fn get_dashboard(state: GrpcState, _: GetDashboardRequest) -> Result<Dashboard, Status> {
+    let response = state
+        .query_sender
+        .query_dashboard()
+        .await
+        .map_err(query_status)?;
+    Ok((&response).into())
+}
+
+async fn get_schedule(state: GrpcState, _: GetScheduleRequest) -> Result<Schedule, Status> {
+    let response = state
+        .query_sender
+        .query_dashboard()
+        .await
+        .map_err(query_status)?;
+    Ok((&response).into())
+}
+
+// ==================== Control ====================
+
+async fn get_user_control(
+    state: GrpcState,
+    _: GetUserControlRequest,
+) -> Result<UserControl, Status> {
+    let mut current = state.user_control.state.read().clone();
+    current.cleanup_expired_slots();
+    let warnings = current.validate(Utc::now()).warnings;
+    Ok(UserControl::new(&current, &warnings))
+}
+
+/// Apply a change through the user control API and return the new state
+fn update(
+    state: &GrpcState,
+    change_type: UserControlChangeType,
+    change: impl FnOnce(&mut UserControlState) -> Result<(), http::StatusCode>,
+) -> Result<UserControl, Status> {
+    let (new_state, warnings) = state
+        .user_control
+        .update(change_type, change)
+        .map_err(|e| change_status(&e))?;
+    Ok(UserControl::new(&new_state, &warnings))
+}
+
+async fn set_enabled(state: GrpcState, request: SetEnabledRequest) -> Result<UserControl, Status> {
+    let reply = update(
+        &state,
+        UserControlChangeType::EnabledChanged,
+        |user_state| {
+            user_state.enabled = request.enabled;
+            Ok(())
+        },
+    )?;
+    info!(
+        "🎛️ User control (gRPC): FluxION {}",
+        if request.enabled {
+            "ENABLED"
+        } else {
+            "DISABLED"
+        }
+    );
+    Ok(reply)
+}
+
+async fn set_restrictions(
+    state: GrpcState,
+    request: SetRestrictionsRequest,
+) -> Result<UserControl, Status> {
+    let reply = update(
+        &state,
+        UserControlChangeType::RestrictionsChanged,
+        |user_state| {
+            if let Some(dc) = request.disallow_charge {
+                user_state.disallow_charge = dc;
+            }
+            if let Some(dd) = request.disallow_discharge {
+                user_state.disallow_discharge = dd;
+            }
+            Ok(())
+        },
+    )?;
+    info!(
+        "🎛️ User control (gRPC) restrictions: disallow_charge={}, disallow_discharge={}",
+        reply.disallow_charge, reply.disallow_discharge
+    );
+    Ok(reply)
+}
+
+async fn add_slot(state: GrpcState, request: AddSlotRequest) -> Result<UserControl, Status> {
+    let mode = Mode::try_from(request.mode)
+        .ok()
+        .and_then(Mode::operation_mode)
+        .ok_or_else(|| Status::invalid_argument("mode is required"))?;
+    let (Some(from), Some(to)) = (timestamp(request.from), timestamp(request.to)) else {
+        return Err(Status::invalid_argument("from/to out of range"));
+    };
+
+    let slot = FixedTimeSlot::new(from, to, mode, request.note);
+    let reply = update(&state, UserControlChangeType::SlotAdded, |user_state| {
+        user_state.fixed_time_slots.push(slot.clone());
+        Ok(())
+    })?;
+    info!(
+        "🎛️ User control (gRPC): Created fixed slot {} ({:?}) from {} to {}",
+        slot.id,
+        slot.mode,
+        slot.from.format("%H:%M"),
+        slot.to.format("%H:%M")
+    );
+    Ok(reply)
+}
+
+async fn remove_slot(state: GrpcState, request: RemoveSlotRequest) -> Result<UserControl, Status> {
+    let reply = update(&state, UserControlChangeType::SlotRemoved, |user_state| {
+        user_state
+            .archive_slot(&request.id, "grpc")
+            .map(|_| ())
+            .ok_or(http::StatusCode::NOT_FOUND)
+    })?;
+    info!(
+        "🎛️ User control (gRPC): Deleted fixed slot {} (archived)",
+        request.id
+    );
+    Ok(reply)
+}
+
+// ==================== Streaming ====================
+
+/// Telemetry every `interval_seconds` until the client disconnects
+///
+/// Ticks the ECS is too busy to answer are skipped; other failures end the stream.
+fn stream_telemetry(
+    state: GrpcState,
+    request: StreamTelemetryRequest,
+) -> ReceiverStream<Result<Telemetry, Status>> {
+    let interval = match request.interval_seconds {
+        0 => DEFAULT_TELEMETRY_INTERVAL,
+        seconds => Duration::from_secs(u64::from(seconds)),
+    };
+    let (sender, receiver) = mpsc::channel(4);
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticker.tick().await;
+            let item = match state.query_sender.query_dashboard().await {
+                Ok(response) => Ok(Telemetry::from(&response)),
+                Err(e) if e.is_overload() => {
+                    debug!("Skipping gRPC telemetry update: {e}");
+                    continue;
+                }
+                Err(e) => Err(query_status(e)),
+            };
+            let failed = item.is_err();
+            if sender.send(item).await.is_err() || failed {
+                break;
+            }
+        }
+    });
+
+    ReceiverStream::new(receiver)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_core::WebQuerySender;
+    use fluxion_core::web_bridge::{SystemHealthData, WebQueryResponse};
+    use fluxion_web::{ApiKeyStore, UserControlApiState};
+    use http_body_util::{BodyExt, Full};
+    use prost::Message;
+    use std::sync::Arc;
+    use tonic::Code;
+    use tonic::codegen::Bytes;
+
+    fn test_state(dir: &tempfile::TempDir) -> GrpcState {
+        let (query_sender, mut channel) = WebQuerySender::new();
+        tokio::spawn(async move {
+            while let Some(request) = channel.receiver.recv().await {
+                let _ = request.response_tx.send(WebQueryResponse {
+                    timestamp: Utc::now(),
+                    debug_mode: true,
+                    inverters: Vec::new(),
+                    schedule: None,
+                    prices: None,
+                    health: SystemHealthData {
+                        inverter_source: true,
+                        price_source: false,
+                        last_update: Utc::now(),
+                        errors: vec!["prices stale".to_owned()],
+                    },
+                    timezone: Some("Europe/Prague".to_owned()),
+                    battery_soc_history: None,
+                    battery_soc_prediction: None,
+                    pv_generation_history: None,
+                    battery_power_history: None,
+                    grid_power_history: None,
+                    consumption_stats: None,
+                    hdo_schedule: None,
+                    pricing_fees: None,
+                    solar_forecast: None,
+                });
+            }
+        });
+        GrpcState {
+            query_sender,
+            user_control: UserControlApiState::new(
+                UserControlState::default(),
+                dir.path().join("user_control.json").to_string_lossy(),
+                None,
+            ),
+            api_keys: None,
+        }
+    }
+
+    fn request(path: &str, message: &impl Message) -> http::Request<Body> {
+        let payload = message.encode_to_vec();
+        let mut frame = vec![0];
+        frame.extend_from_slice(&u32::try_from(payload.len()).unwrap().to_be_bytes());
+        frame.extend(payload);
+        http::Request::post(path)
+            .header("content-type", "application/grpc")
+            .body(Body::new(Full::new(Bytes::from(frame))))
+            .unwrap()
+    }
+
+    /// Call a unary method and decode its reply
+    async fn call<R: Message + Default>(
+        service: &mut impl Service<http::Request<Body>, Response = http::Response<Body>>,
+        request: http::Request<Body>,
+    ) -> Result<R, Code> {
+        let Ok(response) = service.call(request).await else {
+            panic!("service failed");
+        };
+        if let Some(status) = Status::from_header_map(response.headers()) {
+            return Err(status.code());
+        }
+        let body = response.into_body().collect().await.unwrap();
+        if let Some(status) = body.trailers().and_then(Status::from_header_map)
+            && status.code() != Code::Ok
+        {
+            return Err(status.code());
+        }
+        let bytes = body.to_bytes();
+        Ok(R::decode(bytes.slice(5..)).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_query_dashboard_and_unknown_method() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut service = QueryService::new(test_state(&dir));
+
+        let dashboard: Dashboard = call(
+            &mut service,
+            request("/fluxion.v1.Query/GetDashboard", &GetDashboardRequest {}),
+        )
+        .await
+        .unwrap();
+        assert!(dashboard.debug_mode);
+        assert_eq!(dashboard.timezone.as_deref(), Some("Europe/Prague"));
+        let health = dashboard.health.unwrap();
+        assert!(!health.price_source);
+        assert_eq!(health.errors, vec!["prices stale".to_owned()]);
+
+        let unknown = call::<Dashboard>(
+            &mut service,
+            request("/fluxion.v1.Query/Nope", &GetDashboardRequest {}),
+        )
+        .await;
+        assert_eq!(unknown, Err(Code::Unimplemented));
+    }
+
+    #[tokio::test]
+    async fn test_control_validates_and_persists_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(&dir);
+        let mut service = ControlService::new(state.clone());
+        let from = Utc::now() + chrono::Duration::hours(1);
+
+        let added: UserControl = call(
+            &mut service,
+            request(
+                "/fluxion.v1.Control/AddSlot",
+                &AddSlotRequest {
+                    from: from.timestamp(),
+                    to: (from + chrono::Duration::hours(1)).timestamp(),
+                    mode: Mode::ForceCharge.into(),
+                    note: Some("community peak".to_owned()),
+                },
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(added.slots.len(), 1);
+        assert_eq!(added.slots[0].mode(), Mode::ForceCharge);
+        assert!(dir.path().join("user_control.json").exists());
+
+        // Disallowing charging now conflicts with the force-charge slot
+        let conflict = call::<UserControl>(
+            &mut service,
+            request(
+                "/fluxion.v1.Control/SetRestrictions",
+                &SetRestrictionsRequest {
+                    disallow_charge: Some(true),
+                    disallow_discharge: None,
+                },
+            ),
+        )
+        .await;
+        assert_eq!(conflict, Err(Code::FailedPrecondition));
+        assert!(!state.user_control.state.read().disallow_charge);
+
+        let missing_mode = call::<UserControl>(
+            &mut service,
+            request("/fluxion.v1.Control/AddSlot", &AddSlotRequest::default()),
+        )
+        .await;
+        assert_eq!(missing_mode, Err(Code::InvalidArgument));
+
+        let removed: UserControl = call(
+            &mut service,
+            request(
+                "/fluxion.v1.Control/RemoveSlot",
+                &RemoveSlotRequest {
+                    id: added.slots[0].id.clone(),
+                },
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(removed.slots.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_scopes_are_enforced() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(ApiKeyStore::new(dir.path()));
+        let (_, key) = store
+            .create("dashboard", &[ApiKeyScope::ReadTelemetry])
+            .unwrap();
+        let state = GrpcState {
+            api_keys: Some(store),
+            ..test_state(&dir)
+        };
+        let bearer = format!("Bearer {key}");
+        let with_key = |mut request: http::Request<Body>| {
+            request
+                .headers_mut()
+                .insert("authorization", bearer.parse().unwrap());
+            request
+        };
+
+        let mut query = QueryService::new(state.clone());
+        let anonymous = call::<Dashboard>(
+            &mut query,
+            request("/fluxion.v1.Query/GetDashboard", &GetDashboardRequest {}),
+        )
+        .await;
+        assert_eq!(anonymous, Err(Code::Unauthenticated));
+        let dashboard = call::<Dashboard>(
+            &mut query,
+            with_key(request(
+                "/fluxion.v1.Query/GetDashboard",
+                &GetDashboardRequest {},
+            )),
+        )
+        .await;
+        assert!(dashboard.is_ok());
+
+        let mut control = ControlService::new(state);
+        let denied = call::<UserControl>(
+            &mut control,
+            with_key(request(
+                "/fluxion.v1.Control/SetEnabled",
+                &SetEnabledRequest { enabled: false },
+            )),
+        )
+        .await;
+        assert_eq!(denied, Err(Code::PermissionDenied));
+    }
+}
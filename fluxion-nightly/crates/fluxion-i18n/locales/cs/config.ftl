@@ -1,6 +1,6 @@
 # Konfigurace - Stránka
 config-page-title = Konfigurace
-config-page-subtitle = Správa nastavení FluxION
+config-page-subtitle = Správa nastavení { $product }
 config-save-button = Uložit změny
 config-cancel-button = Zrušit
 config-reset-button = Obnovit výchozí
@@ -12,10 +12,10 @@ config-section-system = Systémová nastavení
 config-section-system-desc = Obecná konfigurace systému a chování
 
 config-system-debug-mode = Debug režim
-config-system-debug-mode-help = Pokud je zapnuto, FluxION neprovede skutečné změny na měniči (bezpečný režim pro testování)
+config-system-debug-mode-help = Pokud je zapnuto, { $product } neprovede skutečné změny na měniči (bezpečný režim pro testování)
 
 config-system-update-interval = Interval aktualizace
-config-system-update-interval-help = Jak často FluxION kontroluje ceny a aktualizuje plán (v sekundách, minimum 10)
+config-system-update-interval-help = Jak často { $product } kontroluje ceny a aktualizuje plán (v sekundách, minimum 10)
 
 config-system-log-level = Úroveň logování
 config-system-log-level-help = Podrobnost logování (error, warn, info, debug, trace)
@@ -0,0 +1,37 @@
+# Slovníček / Nápověda
+# Každé téma má název, jednořádkové shrnutí (tooltip) a text v Markdownu
+# poskytovaný přes /api/help/{topic}.
+
+help-hdo-title = HDO (nízký/vysoký tarif)
+help-hdo-summary = Distribuční tarif přepínaný signálem hromadného dálkového ovládání
+help-hdo-body =
+    Signál **HDO** (hromadné dálkové ovládání) slouží distributorovi k přepínání mezi **nízkým** a **vysokým** distribučním tarifem.
+
+    { $product } čte rozpis HDO z nastaveného senzoru a ke spotové ceně každého bloku přičte odpovídající distribuční poplatek. Období nízkého tarifu jsou v cenovém grafu podbarvena.
+
+    Nabíjení ze sítě se obvykle vyplatí jen v nízkém tarifu, protože poplatek ve vysokém tarifu často převýší rozdíl spotových cen.
+
+help-effective-price-title = Efektivní cena
+help-effective-price-summary = Spotová cena plus nákupní poplatek a distribuční tarif – kolik za kWh skutečně zaplatíte
+help-effective-price-body =
+    Jako **efektivní cenu** označujeme celkové náklady na odběr 1 kWh v daném bloku:
+
+    spotová cena + nákupní poplatek + distribuční poplatek HDO
+
+    Všechna rozhodnutí plánovače porovnávají efektivní ceny, takže levná spotová hodina ve vysokém tarifu může stát více než průměrná hodina v nízkém tarifu. Tuto hodnotu ukazuje řada *Celkem* v cenovém grafu.
+
+help-soc-floor-title = Minimální SOC
+help-soc-floor-summary = Nejnižší nabití baterie, na které { $product } při běžném provozu vybíjí
+help-soc-floor-body =
+    Jako **minimální SOC** označujeme stav nabití, který { $product } drží v záloze. Pod tuto hodnotu se baterie pro vlastní spotřebu ani prodej nevybíjí.
+
+    Nikdy nemůže být nižší než hardwarové minimum hlášené střídačem. Vyšší hodnota ponechá více energie pro výpadky, ale méně kapacity pro arbitráž.
+
+help-eeprom-protection-title = Ochrana EEPROM
+help-eeprom-protection-summary = Omezuje, jak často se přepisuje režim střídače, aby se šetřila jeho paměť
+help-eeprom-protection-body =
+    Některé střídače ukládají pracovní režim do paměti **EEPROM**, která vydrží jen omezený počet zápisů.
+
+    { $product } proto zbytečně nemění režimy: krátké osamocené bloky nuceného nabíjení/vybíjení jsou sloučeny nebo vynechány (viz *minimální počet po sobě jdoucích bloků*) a režim se zapisuje jen tehdy, když se liší od aktuálního.
+
+    Provedený plán tak může být o něco hrubší než ideální plán optimalizátoru.
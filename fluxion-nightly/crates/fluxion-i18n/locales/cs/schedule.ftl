@@ -48,3 +48,16 @@ reason-winter-soc-at-target = Aktuální SOC { $soc }% <= bezpečný cíl { $tar
 reason-winter-not-profitable = Neziskové po nákladech za cenu { $price } { $currency }/kWh
 reason-solar-aware-soc-reached = Aktuální SOC { $soc }% >= cíl { $target }%
 reason-solar-aware-price-high = Cena { $price } > 1.2×průměr { $avg }
+
+# Úpravy plánu
+reason-user-override = Přepsáno uživatelem
+reason-user-override-note = Přepsáno uživatelem: { $note }
+reason-beyond-horizon = Mimo horizont optimalizace: { $mode } podle cenového percentilu
+reason-override-backup-reserve = { $from } změněno kvůli záložní rezervě { $min_soc }%
+reason-override-user-restriction = { $from } zakázáno uživatelem
+reason-override-special-day = Nucené vybíjení vynecháno: { $name }
+reason-override-weather-reserve = Nucené vybíjení vynecháno, držím { $min_soc }% před studeným zataženým dnem
+reason-override-clear-day = Nabíjení ze sítě vynecháno za jasného dne ({ $cloud_cover }% oblačnosti)
+reason-override-export-cap = Nucené vybíjení vynecháno v okně omezení dodávky ({ $cap_w } W)
+reason-override-short-sequence = Sekvence { $from } o { $blocks } blocích je kratší než minimum { $min_blocks }
+reason-override-schedule-guard = { $from } opraveno kontrolou plánu: { $issue }
@@ -1,5 +1,5 @@
 # Hlavní panel
-dashboard-title = FluxION Dashboard
+dashboard-title = { $product } Dashboard
 dashboard-subtitle = Systém řízení solární energie
 
 # Sekce
@@ -22,6 +22,7 @@ inverter-voltage-total = Napětí střídače
 inverter-current-total = Proudové zatížení střídače
 inverter-power-total = Výkon střídače
 inverter-frequency = Frekvence střídače
+inverter-telemetry-source = Zdroj dat
 
 # Baterie
 battery-soc = Stav nabití
@@ -104,3 +105,10 @@ debug-strategy-mode = Režim
 debug-strategy-profit = Zisk
 debug-strategy-reason = Důvod
 debug-no-info = Ladicí informace nejsou k dispozici (nastavte log_level = "debug" v konfiguraci)
+
+# Backtest
+backtest-optimal = Optimum (zpětně)
+backtest-optimal-gap = ztráta
+backtest-optimal-captured = možných úspor
+backtest-left = Vlevo
+backtest-right = Vpravo
@@ -1,6 +1,6 @@
 # Configuration Page
 config-page-title = Configuration
-config-page-subtitle = Manage FluxION settings
+config-page-subtitle = Manage { $product } settings
 config-save-button = Save Changes
 config-cancel-button = Cancel
 config-reset-button = Reset to Defaults
@@ -12,10 +12,10 @@ config-section-system = System Settings
 config-section-system-desc = General system configuration and behavior
 
 config-system-debug-mode = Debug Mode
-config-system-debug-mode-help = When enabled, FluxION will not make actual changes to your inverter (safe mode for testing)
+config-system-debug-mode-help = When enabled, { $product } will not make actual changes to your inverter (safe mode for testing)
 
 config-system-update-interval = Update Interval
-config-system-update-interval-help = How often FluxION checks prices and updates schedule (in seconds, minimum 10)
+config-system-update-interval-help = How often { $product } checks prices and updates schedule (in seconds, minimum 10)
 
 config-system-log-level = Log Level
 config-system-log-level-help = Verbosity of logging (error, warn, info, debug, trace)
@@ -0,0 +1,37 @@
+# Glossary / Inline Help
+# Each topic has a title, a one-line summary (tooltip) and a Markdown body
+# served by /api/help/{topic}.
+
+help-hdo-title = HDO (Low/High Tariff)
+help-hdo-summary = Distribution tariff switched by the grid operator's ripple control signal
+help-hdo-body =
+    Your distributor uses the **HDO** (hromadné dálkové ovládání) ripple control signal to switch between the **low** and **high** distribution tariff.
+
+    { $product } reads the HDO schedule from the configured sensor and adds the matching distribution fee to the spot price of every block. Low tariff periods are shaded on the price chart.
+
+    Charging from the grid is usually only worthwhile during low tariff, because the high tariff fee often outweighs the spot price difference.
+
+help-effective-price-title = Effective Price
+help-effective-price-summary = Spot price plus buy fee and distribution tariff – what you actually pay per kWh
+help-effective-price-body =
+    The **effective price** is the full cost of importing 1 kWh in a given block:
+
+    spot price + spot buy fee + HDO distribution fee
+
+    All scheduling decisions compare effective prices, so a cheap spot hour in high tariff can still cost more than a moderate one in low tariff. The price chart's *Total* series shows this value.
+
+help-soc-floor-title = SOC Floor
+help-soc-floor-summary = Lowest battery charge { $product } will discharge to during normal operation
+help-soc-floor-body =
+    The **SOC floor** (minimum battery SOC) is the state of charge { $product } keeps in reserve. The battery is not discharged below it for self-use or export.
+
+    It can never be lower than the hardware minimum reported by the inverter. A higher floor keeps more backup energy for outages but leaves less capacity for arbitrage.
+
+help-eeprom-protection-title = EEPROM Protection
+help-eeprom-protection-summary = Limits how often the inverter mode is rewritten to protect its memory
+help-eeprom-protection-body =
+    Some inverters store the work mode in **EEPROM**, which wears out after a limited number of writes.
+
+    { $product } therefore avoids needless mode changes: short isolated force charge/discharge blocks are merged or dropped (see *min consecutive force blocks*) and a mode is only written when it differs from the current one.
+
+    This can make the executed schedule slightly coarser than the optimizer's ideal plan.
@@ -46,3 +46,16 @@ reason-winter-soc-at-target = Current SOC { $soc }% <= safe target { $target }%
 reason-winter-not-profitable = Not profitable after costs at price { $price } { $currency }/kWh
 reason-solar-aware-soc-reached = Current SOC { $soc }% >= target { $target }%
 reason-solar-aware-price-high = Price { $price } > 1.2×avg { $avg }
+
+# Schedule Overrides
+reason-user-override = User override
+reason-user-override-note = User override: { $note }
+reason-beyond-horizon = Beyond optimization horizon: { $mode } by price percentile
+reason-override-backup-reserve = { $from } converted to keep the { $min_soc }% backup reserve
+reason-override-user-restriction = { $from } disallowed by user
+reason-override-special-day = Forced discharge skipped on { $name }
+reason-override-weather-reserve = Forced discharge skipped, keeping { $min_soc }% before a cold cloudy day
+reason-override-clear-day = Grid charge skipped on a clear day ({ $cloud_cover }% cloud cover)
+reason-override-export-cap = Forced discharge skipped in export cap window ({ $cap_w } W)
+reason-override-short-sequence = { $from } sequence of { $blocks } blocks is shorter than the minimum { $min_blocks }
+reason-override-schedule-guard = { $from } repaired by schedule guard: { $issue }
@@ -1,5 +1,5 @@
 # Dashboard
-dashboard-title = FluxION Dashboard
+dashboard-title = { $product } Dashboard
 dashboard-subtitle = Solar Energy Management System
 
 # Sections
@@ -22,6 +22,7 @@ inverter-voltage-total = Inverter Voltage
 inverter-current-total = Inverter Current
 inverter-power-total = Inverter Power
 inverter-frequency = Inverter Frequency
+inverter-telemetry-source = Telemetry Source
 
 # Battery
 battery-soc = State of Charge
@@ -104,3 +105,10 @@ debug-strategy-mode = Mode
 debug-strategy-profit = Profit
 debug-strategy-reason = Reason
 debug-no-info = Debug info not available (set log_level = "debug" in config)
+
+# Backtest
+backtest-optimal = Optimal (hindsight)
+backtest-optimal-gap = gap
+backtest-optimal-captured = of possible savings
+backtest-left = Left
+backtest-right = Right
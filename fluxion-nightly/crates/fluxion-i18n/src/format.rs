@@ -0,0 +1,162 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Locale-aware number, currency and date formatting.
+//!
+//! Follows the conventions of each supported language, e.g. `1 234,56 Kč`
+//! in Czech and `1,234.56 CZK` in English. Thousands are separated by a
+//! no-break space where the locale uses a space, so values never wrap.
+
+use chrono::NaiveDate;
+
+use crate::Language;
+
+/// No-break space used as thousands separator
+const NBSP: char = '\u{a0}';
+
+impl Language {
+    /// BCP 47 locale tag, e.g. for `Intl.NumberFormat` in the browser
+    #[must_use]
+    pub fn locale_tag(&self) -> &'static str {
+        match self {
+            Self::English => "en-GB",
+            Self::Czech => "cs-CZ",
+            Self::German => "de-DE",
+            Self::Slovak => "sk-SK",
+            Self::Polish => "pl-PL",
+            Self::French => "fr-FR",
+        }
+    }
+
+    /// Decimal separator
+    #[must_use]
+    pub fn decimal_separator(&self) -> char {
+        match self {
+            Self::English => '.',
+            Self::Czech | Self::German | Self::Slovak | Self::Polish | Self::French => ',',
+        }
+    }
+
+    /// Thousands separator
+    #[must_use]
+    pub fn group_separator(&self) -> char {
+        match self {
+            Self::English => ',',
+            Self::German => '.',
+            Self::Czech | Self::Slovak | Self::Polish | Self::French => NBSP,
+        }
+    }
+
+    /// Symbol shown for an ISO 4217 currency code
+    ///
+    /// Unknown codes are shown as they are.
+    #[must_use]
+    pub fn currency_symbol<'a>(&self, currency: &'a str) -> &'a str {
+        match (currency, self) {
+            ("CZK", Self::Czech | Self::Slovak) => "Kč",
+            ("EUR", _) => "€",
+            _ => currency,
+        }
+    }
+
+    /// Format `value` with `decimals` fractional digits and grouped thousands
+    #[must_use]
+    pub fn format_number(&self, value: f64, decimals: usize) -> String {
+        let formatted = format!("{:.decimals$}", value.abs());
+        let (integer, fraction) = formatted
+            .split_once('.')
+            .map_or((formatted.as_str(), None), |(i, f)| (i, Some(f)));
+
+        let mut result = String::with_capacity(formatted.len() + integer.len() / 3 + 1);
+        // Rounding may turn a tiny negative value into zero, which has no sign
+        if value.is_sign_negative() && formatted.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            result.push('-');
+        }
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                result.push(self.group_separator());
+            }
+            result.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            result.push(self.decimal_separator());
+            result.push_str(fraction);
+        }
+        result
+    }
+
+    /// Format an amount of `currency`, e.g. `1 234,56 Kč` or `1,234.56 CZK`
+    #[must_use]
+    pub fn format_currency(&self, value: f64, decimals: usize, currency: &str) -> String {
+        let number = self.format_number(value, decimals);
+        let symbol = self.currency_symbol(currency);
+        if *self == Self::English && symbol == "€" {
+            match number.strip_prefix('-') {
+                Some(positive) => format!("-€{positive}"),
+                None => format!("€{number}"),
+            }
+        } else {
+            format!("{number}{NBSP}{symbol}")
+        }
+    }
+
+    /// Format a calendar date, e.g. `17. 10. 2026` or `17/10/2026`
+    #[must_use]
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        let pattern = match self {
+            Self::English | Self::French => "%d/%m/%Y",
+            Self::Czech | Self::Slovak => "%-d.\u{a0}%-m.\u{a0}%Y",
+            Self::German | Self::Polish => "%d.%m.%Y",
+        };
+        date.format(pattern).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number_follows_locale() {
+        assert_eq!(Language::English.format_number(1234.564, 2), "1,234.56");
+        assert_eq!(Language::Czech.format_number(1234.564, 2), "1\u{a0}234,56");
+        assert_eq!(Language::German.format_number(1_234_567.0, 0), "1.234.567");
+        assert_eq!(Language::English.format_number(-0.004, 2), "0.00");
+        assert_eq!(Language::English.format_number(-999.5, 0), "-1,000");
+    }
+
+    #[test]
+    fn test_currency_uses_local_symbol() {
+        assert_eq!(
+            Language::Czech.format_currency(1234.56, 2, "CZK"),
+            "1\u{a0}234,56\u{a0}Kč"
+        );
+        assert_eq!(
+            Language::English.format_currency(1234.56, 2, "CZK"),
+            "1,234.56\u{a0}CZK"
+        );
+        assert_eq!(Language::English.format_currency(-3.5, 2, "EUR"), "-€3.50");
+        assert_eq!(
+            Language::German.format_currency(3.5, 2, "EUR"),
+            "3,50\u{a0}€"
+        );
+    }
+
+    #[test]
+    fn test_date_follows_locale() {
+        let date = NaiveDate::from_ymd_opt(2026, 3, 7).unwrap();
+
+        assert_eq!(Language::Czech.format_date(date), "7.\u{a0}3.\u{a0}2026");
+        assert_eq!(Language::German.format_date(date), "07.03.2026");
+        assert_eq!(Language::English.format_date(date), "07/03/2026");
+    }
+}
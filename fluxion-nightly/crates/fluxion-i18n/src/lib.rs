@@ -10,14 +10,33 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
+mod format;
+
 use fluent::{FluentArgs, FluentBundle, FluentResource};
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use thiserror::Error;
 use unic_langid::LanguageIdentifier;
 
+/// Directory with runtime locale files, laid out as `<code>/<domain>.ftl`
+///
+/// Locales found here are loaded on top of the embedded ones, so the same
+/// layout can add a language (e.g. `de/web.ftl`) or override embedded strings.
+pub const DEFAULT_LOCALES_DIR: &str = "./data/locales";
+
+/// Product name used in messages until [`I18n::set_product_name`] is called
+pub const DEFAULT_PRODUCT_NAME: &str = "FluxION";
+
+/// Translation domains loaded for every language
+const DOMAINS: [&str; 5] = ["main", "web", "schedule", "config", "help"];
+
 /// Supported languages
+///
+/// English and Czech are embedded in the binary; the others are loaded from
+/// the locales directory at runtime, with missing strings falling back to
+/// embedded English.
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, Default,
 )]
@@ -28,6 +47,14 @@ pub enum Language {
     English,
     /// Czech
     Czech,
+    /// German (runtime locale)
+    German,
+    /// Slovak (runtime locale)
+    Slovak,
+    /// Polish (runtime locale)
+    Polish,
+    /// French (runtime locale)
+    French,
 }
 
 impl Language {
@@ -37,6 +64,10 @@ impl Language {
         match self {
             Self::English => "en",
             Self::Czech => "cs",
+            Self::German => "de",
+            Self::Slovak => "sk",
+            Self::Polish => "pl",
+            Self::French => "fr",
         }
     }
 
@@ -46,11 +77,45 @@ impl Language {
         match self {
             Self::English => "English",
             Self::Czech => "Čeština",
+            Self::German => "Deutsch",
+            Self::Slovak => "Slovenčina",
+            Self::Polish => "Polski",
+            Self::French => "Français",
         }
     }
 
     /// List all supported languages
-    pub const ALL: [Language; 2] = [Language::English, Language::Czech];
+    pub const ALL: [Language; 6] = [
+        Language::English,
+        Language::Czech,
+        Language::German,
+        Language::Slovak,
+        Language::Polish,
+        Language::French,
+    ];
+
+    /// Whether the translations are embedded in the binary
+    #[must_use]
+    pub fn is_embedded(&self) -> bool {
+        matches!(self, Self::English | Self::Czech)
+    }
+
+    /// Languages that can be selected: the embedded ones plus every language
+    /// with at least one FTL file in `locales_dir`
+    #[must_use]
+    pub fn available_in(locales_dir: &Path) -> Vec<Language> {
+        Self::ALL
+            .into_iter()
+            .filter(|language| {
+                language.is_embedded()
+                    || std::fs::read_dir(locales_dir.join(language.code())).is_ok_and(|entries| {
+                        entries
+                            .flatten()
+                            .any(|entry| entry.path().extension().is_some_and(|ext| ext == "ftl"))
+                    })
+            })
+            .collect()
+    }
 
     /// Parse language from string code
     ///
@@ -61,6 +126,10 @@ impl Language {
         match code.to_lowercase().as_str() {
             "en" | "english" => Ok(Self::English),
             "cs" | "czech" | "cz" => Ok(Self::Czech),
+            "de" | "german" | "deutsch" => Ok(Self::German),
+            "sk" | "slovak" => Ok(Self::Slovak),
+            "pl" | "polish" => Ok(Self::Polish),
+            "fr" | "french" => Ok(Self::French),
             _ => Err(I18nError::UnsupportedLanguage(code.to_string())),
         }
     }
@@ -99,22 +168,32 @@ pub enum I18nError {
     FormatError(String),
 }
 
+type Bundles = HashMap<String, FluentBundle<FluentResource>>;
+
 /// Main i18n interface
+///
+/// The language can be switched at runtime with [`I18n::set_language`], so a
+/// single shared `Arc<I18n>` follows the user's choice without a restart.
 pub struct I18n {
-    bundles: Arc<Mutex<HashMap<String, FluentBundle<FluentResource>>>>,
-    language: Language,
+    bundles: Arc<Mutex<Bundles>>,
+    /// Embedded English bundles for keys missing from `bundles`
+    fallback: Arc<Mutex<Bundles>>,
+    language: RwLock<Language>,
+    locales_dir: PathBuf,
+    /// Passed to every message as `$product`, so white-label builds can rename the product
+    product_name: RwLock<String>,
 }
 
 // Safety: I18n is safe to send between threads and share between threads
 // because all access to the non-Send FluentBundle is protected by a Mutex.
-// The bundles HashMap is never accessed without first acquiring the lock.
+// The bundle HashMaps are never accessed without first acquiring the lock.
 unsafe impl Send for I18n {}
 unsafe impl Sync for I18n {}
 
 impl std::fmt::Debug for I18n {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("I18n")
-            .field("language", &self.language)
+            .field("language", &self.language())
             .field("bundles", &"<FluentBundle>")
             .finish()
     }
@@ -123,32 +202,124 @@ impl std::fmt::Debug for I18n {
 impl I18n {
     /// Create a new i18n instance for the specified language
     ///
+    /// Runtime locales are read from [`DEFAULT_LOCALES_DIR`].
+    ///
     /// # Errors
     ///
-    /// Returns `I18nError::LoadError` if translation files cannot be loaded.
+    /// Returns `I18nError::LoadError` if the embedded translations cannot be loaded.
     pub fn new(language: Language) -> Result<Self, I18nError> {
+        Self::with_locales_dir(language, Path::new(DEFAULT_LOCALES_DIR))
+    }
+
+    /// Create a new i18n instance reading runtime locales from `locales_dir`
+    ///
+    /// Each domain is taken from `<locales_dir>/<code>/<domain>.ftl` when that
+    /// file exists, otherwise from the embedded translations. Keys missing for
+    /// the language are looked up in embedded English.
+    ///
+    /// # Errors
+    ///
+    /// Returns `I18nError::LoadError` if the embedded translations cannot be loaded.
+    pub fn with_locales_dir(language: Language, locales_dir: &Path) -> Result<Self, I18nError> {
+        let (bundles, fallback) = Self::load_bundles(language, locales_dir)?;
         #[allow(clippy::arc_with_non_send_sync)]
         // Safe: We implement Send/Sync manually with proper justification
-        let bundles = Arc::new(Mutex::new(HashMap::new()));
-        let mut i18n = Self { bundles, language };
+        let bundles = Arc::new(Mutex::new(bundles));
+        #[allow(clippy::arc_with_non_send_sync)]
+        let fallback = Arc::new(Mutex::new(fallback));
+        Ok(Self {
+            bundles,
+            fallback,
+            language: RwLock::new(language),
+            locales_dir: locales_dir.to_path_buf(),
+            product_name: RwLock::new(DEFAULT_PRODUCT_NAME.to_owned()),
+        })
+    }
+
+    /// Switch to another language, reloading all translation domains
+    ///
+    /// On error the current language stays active.
+    ///
+    /// # Errors
+    ///
+    /// Returns `I18nError::LoadError` if the embedded translations cannot be loaded.
+    pub fn set_language(&self, language: Language) -> Result<(), I18nError> {
+        let (bundles, fallback) = Self::load_bundles(language, &self.locales_dir)?;
+        // Hold the language lock while swapping so readers never see a mix
+        let mut current = self.language.write();
+        *self.bundles.lock() = bundles;
+        *self.fallback.lock() = fallback;
+        *current = language;
+        Ok(())
+    }
 
-        // Load all translation domains
-        i18n.load_domain("main")?;
-        i18n.load_domain("web")?;
-        i18n.load_domain("schedule")?;
-        i18n.load_domain("config")?;
+    /// Product name passed to every message as `$product`
+    pub fn set_product_name(&self, name: &str) {
+        *self.product_name.write() = name.to_owned();
+    }
 
-        Ok(i18n)
+    /// Product name passed to every message as `$product`
+    #[must_use]
+    pub fn product_name(&self) -> String {
+        self.product_name.read().clone()
+    }
+
+    /// Load every translation domain for `language`, plus the English fallback
+    fn load_bundles(
+        language: Language,
+        locales_dir: &Path,
+    ) -> Result<(Bundles, Bundles), I18nError> {
+        let mut bundles = HashMap::new();
+        let mut fallback = HashMap::new();
+        for domain in DOMAINS {
+            Self::load_domain(language, locales_dir, domain, &mut bundles, &mut fallback)?;
+        }
+        Ok((bundles, fallback))
     }
 
     /// Load a translation domain (e.g., "main", "web", "schedule")
-    fn load_domain(&mut self, domain: &str) -> Result<(), I18nError> {
-        let lang_code = self.language.code();
-        let ftl_content = Self::load_ftl_file(lang_code, domain)?;
+    fn load_domain(
+        language: Language,
+        locales_dir: &Path,
+        domain: &str,
+        bundles: &mut Bundles,
+        fallback: &mut Bundles,
+    ) -> Result<(), I18nError> {
+        let lang_code = language.code();
+        let runtime_file = locales_dir.join(lang_code).join(format!("{domain}.ftl"));
+
+        // Runtime files are user-supplied: keep the messages that parse
+        let resource = match std::fs::read_to_string(&runtime_file) {
+            Ok(content) => Some(FluentResource::try_new(content).unwrap_or_else(|(res, _)| res)),
+            Err(_) => match Self::embedded_ftl(lang_code, domain) {
+                Some(content) => Some(Self::parse_embedded(content, domain)?),
+                None => None,
+            },
+        };
+        if let Some(resource) = resource {
+            let bundle = Self::build_bundle(lang_code, resource)?;
+            bundles.insert(domain.to_string(), bundle);
+        }
 
-        let resource = FluentResource::try_new(ftl_content)
-            .map_err(|e| I18nError::LoadError(format!("Failed to parse {domain}.ftl: {e:?}")))?;
+        if language != Language::English {
+            let content = Self::embedded_ftl("en", domain).ok_or_else(|| {
+                I18nError::LoadError(format!("Translation file not found: en/{domain}.ftl"))
+            })?;
+            let bundle = Self::build_bundle("en", Self::parse_embedded(content, domain)?)?;
+            fallback.insert(domain.to_string(), bundle);
+        }
+        Ok(())
+    }
 
+    fn parse_embedded(content: &str, domain: &str) -> Result<FluentResource, I18nError> {
+        FluentResource::try_new(content.to_string())
+            .map_err(|e| I18nError::LoadError(format!("Failed to parse {domain}.ftl: {e:?}")))
+    }
+
+    fn build_bundle(
+        lang_code: &str,
+        resource: FluentResource,
+    ) -> Result<FluentBundle<FluentResource>, I18nError> {
         let lang_id: LanguageIdentifier = lang_code
             .parse()
             .map_err(|e| I18nError::LoadError(format!("Invalid language ID: {e}")))?;
@@ -157,26 +328,23 @@ impl I18n {
         bundle
             .add_resource(resource)
             .map_err(|e| I18nError::LoadError(format!("Failed to add resource: {e:?}")))?;
-
-        self.bundles.lock().insert(domain.to_string(), bundle);
-        Ok(())
+        Ok(bundle)
     }
 
-    /// Load FTL file content
-    fn load_ftl_file(lang_code: &str, domain: &str) -> Result<String, I18nError> {
-        // For embedded resources in the binary
+    /// FTL content embedded in the binary
+    fn embedded_ftl(lang_code: &str, domain: &str) -> Option<&'static str> {
         match (lang_code, domain) {
-            ("en", "main") => Ok(include_str!("../locales/en/main.ftl").to_string()),
-            ("en", "web") => Ok(include_str!("../locales/en/web.ftl").to_string()),
-            ("en", "schedule") => Ok(include_str!("../locales/en/schedule.ftl").to_string()),
-            ("en", "config") => Ok(include_str!("../locales/en/config.ftl").to_string()),
-            ("cs", "main") => Ok(include_str!("../locales/cs/main.ftl").to_string()),
-            ("cs", "web") => Ok(include_str!("../locales/cs/web.ftl").to_string()),
-            ("cs", "schedule") => Ok(include_str!("../locales/cs/schedule.ftl").to_string()),
-            ("cs", "config") => Ok(include_str!("../locales/cs/config.ftl").to_string()),
-            _ => Err(I18nError::LoadError(format!(
-                "Translation file not found: {lang_code}/{domain}.ftl"
-            ))),
+            ("en", "main") => Some(include_str!("../locales/en/main.ftl")),
+            ("en", "web") => Some(include_str!("../locales/en/web.ftl")),
+            ("en", "schedule") => Some(include_str!("../locales/en/schedule.ftl")),
+            ("en", "config") => Some(include_str!("../locales/en/config.ftl")),
+            ("en", "help") => Some(include_str!("../locales/en/help.ftl")),
+            ("cs", "main") => Some(include_str!("../locales/cs/main.ftl")),
+            ("cs", "web") => Some(include_str!("../locales/cs/web.ftl")),
+            ("cs", "schedule") => Some(include_str!("../locales/cs/schedule.ftl")),
+            ("cs", "config") => Some(include_str!("../locales/cs/config.ftl")),
+            ("cs", "help") => Some(include_str!("../locales/cs/help.ftl")),
+            _ => None,
         }
     }
 
@@ -196,20 +364,29 @@ impl I18n {
     /// Returns `I18nError::KeyNotFound` if the translation key is not found.
     /// Returns `I18nError::FormatError` if formatting fails.
     pub fn format(&self, key: &str, args: Option<&FluentArgs>) -> Result<String, I18nError> {
-        // Try each domain until we find the key
-        let bundles = self.bundles.lock();
-        for bundle in bundles.values() {
-            if let Some(message) = bundle.get_message(key).and_then(|msg| msg.value()) {
-                let mut errors = vec![];
-                let value = bundle.format_pattern(message, args, &mut errors);
-
-                if !errors.is_empty() {
-                    return Err(I18nError::FormatError(format!(
-                        "Formatting errors: {errors:?}"
-                    )));
+        let mut all_args = FluentArgs::new();
+        all_args.set("product", self.product_name());
+        for (name, value) in args.into_iter().flat_map(FluentArgs::iter) {
+            all_args.set(name, value.clone());
+        }
+        let args = Some(&all_args);
+
+        // Try each domain until we find the key, then the English fallback
+        for bundles in [&self.bundles, &self.fallback] {
+            let bundles = bundles.lock();
+            for bundle in bundles.values() {
+                if let Some(message) = bundle.get_message(key).and_then(|msg| msg.value()) {
+                    let mut errors = vec![];
+                    let value = bundle.format_pattern(message, args, &mut errors);
+
+                    if !errors.is_empty() {
+                        return Err(I18nError::FormatError(format!(
+                            "Formatting errors: {errors:?}"
+                        )));
+                    }
+
+                    return Ok(value.to_string());
                 }
-
-                return Ok(value.to_string());
             }
         }
 
@@ -219,7 +396,25 @@ impl I18n {
     /// Get the current language
     #[must_use]
     pub fn language(&self) -> Language {
-        self.language
+        *self.language.read()
+    }
+
+    /// Format a number in the current language, see [`Language::format_number`]
+    #[must_use]
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        self.language().format_number(value, decimals)
+    }
+
+    /// Format a currency amount in the current language, see [`Language::format_currency`]
+    #[must_use]
+    pub fn currency(&self, value: f64, decimals: usize, currency: &str) -> String {
+        self.language().format_currency(value, decimals, currency)
+    }
+
+    /// Format a date in the current language, see [`Language::format_date`]
+    #[must_use]
+    pub fn date(&self, date: chrono::NaiveDate) -> String {
+        self.language().format_date(date)
     }
 }
 
@@ -253,7 +448,8 @@ mod tests {
         assert_eq!(Language::from_code("en").unwrap(), Language::English);
         assert_eq!(Language::from_code("cs").unwrap(), Language::Czech);
         assert_eq!(Language::from_code("EN").unwrap(), Language::English);
-        assert!(Language::from_code("fr").is_err());
+        assert_eq!(Language::from_code("de").unwrap(), Language::German);
+        assert!(Language::from_code("xx").is_err());
     }
 
     #[test]
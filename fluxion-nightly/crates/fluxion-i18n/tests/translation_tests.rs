@@ -102,6 +102,7 @@ const REQUIRED_KEYS: &[&str] = &[
     "inverter-current-total",
     "inverter-power-total",
     "inverter-frequency",
+    "inverter-telemetry-source",
     // Web - Battery Extended
     "battery-capacity",
     "battery-input-today",
@@ -146,6 +147,7 @@ const REQUIRED_KEYS: &[&str] = &[
     "reason-battery-protection",
     "reason-temperature-limit",
     "reason-manual-mode",
+    "reason-user-override",
     // Schedule - States
     "state-charging",
     "state-discharging",
@@ -161,6 +163,25 @@ const REQUIRED_KEYS: &[&str] = &[
     "block-duration",
     "block-energy",
     "block-savings",
+    // Web - Backtest
+    "backtest-optimal",
+    "backtest-optimal-gap",
+    "backtest-optimal-captured",
+    "backtest-left",
+    "backtest-right",
+    // Help - Glossary
+    "help-hdo-title",
+    "help-hdo-summary",
+    "help-hdo-body",
+    "help-effective-price-title",
+    "help-effective-price-summary",
+    "help-effective-price-body",
+    "help-soc-floor-title",
+    "help-soc-floor-summary",
+    "help-soc-floor-body",
+    "help-eeprom-protection-title",
+    "help-eeprom-protection-summary",
+    "help-eeprom-protection-body",
 ];
 
 #[test]
@@ -320,6 +341,21 @@ fn test_language_switching() {
     assert_eq!(cs_text, "Vlastní spotřeba");
 }
 
+#[test]
+fn test_set_language_at_runtime() {
+    let i18n = I18n::new(Language::English).expect("Failed to load English");
+    assert_eq!(i18n.get("mode-self-use").unwrap(), "Self Use");
+
+    i18n.set_language(Language::Czech)
+        .expect("Failed to switch to Czech");
+    assert_eq!(i18n.language(), Language::Czech);
+    assert_eq!(i18n.get("mode-self-use").unwrap(), "Vlastní spotřeba");
+
+    i18n.set_language(Language::English)
+        .expect("Failed to switch back to English");
+    assert_eq!(i18n.get("mode-self-use").unwrap(), "Self Use");
+}
+
 #[test]
 fn test_variable_interpolation() {
     use fluent::fluent_args;
@@ -372,3 +408,56 @@ fn test_pluralization_czech() {
         );
     }
 }
+
+#[test]
+fn test_runtime_locale_falls_back_to_english() {
+    let dir = tempfile::tempdir().expect("Failed to create locales dir");
+    std::fs::create_dir(dir.path().join("de")).unwrap();
+    std::fs::write(
+        dir.path().join("de/main.ftl"),
+        "mode-self-use = Eigenverbrauch\n",
+    )
+    .unwrap();
+
+    let de = I18n::with_locales_dir(Language::German, dir.path()).expect("Failed to load German");
+
+    assert_eq!(de.get("mode-self-use").unwrap(), "Eigenverbrauch");
+    // Not translated yet: embedded English
+    assert_eq!(de.get("mode-force-charge").unwrap(), "Force Charge");
+}
+
+#[test]
+fn test_available_languages_include_runtime_locales() {
+    let dir = tempfile::tempdir().expect("Failed to create locales dir");
+    std::fs::create_dir(dir.path().join("sk")).unwrap();
+    std::fs::write(dir.path().join("sk/web.ftl"), "dashboard-title = FluxION\n").unwrap();
+    // A directory without FTL files does not count
+    std::fs::create_dir(dir.path().join("pl")).unwrap();
+
+    let available = Language::available_in(dir.path());
+
+    assert_eq!(
+        available,
+        vec![Language::English, Language::Czech, Language::Slovak]
+    );
+}
+
+#[test]
+fn test_product_name_argument() {
+    let i18n = I18n::new(Language::Czech).expect("Failed to load Czech");
+    assert!(i18n.get("dashboard-title").unwrap().contains("FluxION"));
+
+    i18n.set_product_name("SolarBox");
+    let title = i18n.get("dashboard-title").unwrap();
+    assert!(title.contains("SolarBox"));
+    assert!(!title.contains("FluxION"));
+
+    // Explicit arguments are passed alongside the product name
+    let reason = i18n
+        .format(
+            "reason-cheapest-block",
+            Some(&fluent::fluent_args!["price" => 0.5, "currency" => "Kč"]),
+        )
+        .unwrap();
+    assert!(reason.contains("Kč"));
+}
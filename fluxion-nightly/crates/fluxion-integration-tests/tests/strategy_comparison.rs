@@ -118,6 +118,11 @@ impl SelfUseStrategy {
 
 #[test]
 fn compare_strategies() {
+    // Opening a missing database would create an empty one
+    if !std::path::Path::new("solax_data.db").exists() {
+        eprintln!("No solax_data.db found. Run fetch_data first.");
+        return;
+    }
     let data = load_data();
     println!("Loaded {} price blocks", data.prices.len());
     println!("Loaded {} historical records", data.history.len());
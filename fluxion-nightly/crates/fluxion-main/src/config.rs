@@ -13,11 +13,12 @@
 mod persistence;
 mod validation;
 
-pub use persistence::load_config_with_fallback;
+pub use persistence::{is_first_run, load_config_with_fallback};
 pub use validation::ValidationResult;
 
 use anyhow::{Context, Result};
 use bevy_ecs::prelude::*;
+use fluxion_adapters::nordpool::{NORD_POOL_AREAS, NORD_POOL_CURRENCIES};
 use fluxion_i18n::Language;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
@@ -25,6 +26,10 @@ use tracing::{info, warn};
 /// Main application configuration - FluxION MVP
 #[derive(Resource, Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// Version of the config layout; older configs are migrated when loaded
+    #[serde(default = "default_schema_version")]
+    pub schema_version: u32,
+
     /// Inverter configurations (one or more)
     pub inverters: Vec<InverterConfig>,
 
@@ -56,6 +61,98 @@ pub struct AppConfig {
     /// Server heartbeat configuration
     #[serde(default, rename = "server_heartbeat")]
     pub server_heartbeat: ServerHeartbeatConfig,
+
+    /// Commercial license key, validated against the heartbeat server
+    #[serde(default)]
+    pub license: LicenseConfig,
+
+    /// Outbound healthchecks.io-style ping after each planning cycle
+    #[serde(default, rename = "healthcheck_ping")]
+    pub healthcheck_ping: HealthcheckPingConfig,
+
+    /// Outbound webhooks for new schedules and mode changes
+    #[serde(default)]
+    pub webhooks: WebhooksConfig,
+
+    /// Price- and solar-aware hot water target temperatures
+    #[serde(default)]
+    pub dhw: fluxion_core::dhw::DhwConfig,
+
+    /// Heat pump pre-heating in very cheap winter blocks
+    #[serde(default)]
+    pub heat_pump: fluxion_core::heat_pump::HeatPumpConfig,
+
+    /// gRPC API for external orchestrators
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+
+    /// Direct Modbus TCP access to SunSpec inverters, with Home Assistant as the fallback
+    #[serde(default)]
+    pub modbus: fluxion_modbus::ModbusConfig,
+
+    /// Direct Solax local API (Pocket WiFi / LAN dongle), with Home Assistant as the fallback
+    #[serde(default)]
+    pub solax_local: fluxion_adapters::SolaxLocalConfig,
+
+    /// Victron GX ESS control over Modbus TCP; modes become grid setpoints
+    #[serde(default)]
+    pub victron: fluxion_modbus::VictronConfig,
+
+    /// Liveness watchdog for systemd and Docker supervisors
+    #[serde(default)]
+    pub watchdog: WatchdogConfig,
+
+    /// MQTT telemetry publisher with Home Assistant discovery
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+
+    /// Logging configuration (per-module levels, rotating file logs)
+    #[serde(default)]
+    pub logging: LoggingConfig,
+
+    /// Grid voltage/frequency observer
+    #[serde(default)]
+    pub grid_quality: GridQualityConfig,
+
+    /// Quarter-hour peak demand limiting against the contracted maximum
+    #[serde(default)]
+    pub peak_demand: PeakDemandConfig,
+
+    /// Weather forecast adjusting planning to cold, cloudy and clear days
+    #[serde(default)]
+    pub weather: WeatherConfig,
+
+    /// Naming of scheduled and downloaded data exports
+    #[serde(default)]
+    pub export: ExportConfig,
+
+    /// SQLite log of executed block decisions
+    #[serde(default)]
+    pub decision_log: DecisionLogConfig,
+
+    /// Realized versus expected profit accounting
+    #[serde(default)]
+    pub savings: SavingsConfig,
+
+    /// Built-in telemetry historian for charts and backtests
+    #[serde(default)]
+    pub historian: HistorianConfig,
+
+    /// Sandboxed WebAssembly strategy plugins
+    #[serde(default)]
+    pub wasm_plugins: WasmPluginsConfig,
+
+    /// Per-appliance cost attribution from HA power sensors
+    #[serde(default)]
+    pub submeter: fluxion_core::submeter::SubmeterConfig,
+
+    /// Login and API tokens for the standalone web server
+    #[serde(default)]
+    pub web_auth: fluxion_web::WebAuthConfig,
+
+    /// Installer product name, logo and colors
+    #[serde(default)]
+    pub branding: fluxion_web::BrandingConfig,
 }
 
 /// Configuration for a single inverter
@@ -65,8 +162,7 @@ pub struct InverterConfig {
     pub id: String,
 
     /// Inverter type (enum defining supported inverter models)
-    /// Accepts both "inverter_type" (config.toml) and "vendor" (HA addon options.json)
-    #[serde(alias = "vendor")]
+    /// "vendor" from the HA addon options.json is renamed by the config migration
     pub inverter_type: fluxion_core::InverterType,
 
     /// Entity prefix in HA (e.g., "solax", "solax_<ip>")
@@ -76,14 +172,23 @@ pub struct InverterConfig {
     pub topology: String,
 
     /// Slave inverter IDs (if topology = master)
-    /// Accepts both "slaves" (config.toml) and "slave_ids" (HA addon options.json)
-    #[serde(default, alias = "slave_ids")]
+    /// "slave_ids" from the HA addon options.json is renamed by the config migration
+    #[serde(default)]
     pub slaves: Option<Vec<String>>,
 
     /// Master inverter ID (if topology = slave)
-    /// Accepts both "master" (config.toml) and "master_id" (HA addon options.json)
-    #[serde(alias = "master_id")]
+    /// "master_id" from the HA addon options.json is renamed by the config migration
     pub master: Option<String>,
+
+    /// Battery behind this inverter (capacity, charge rate, export limit, wear cost)
+    /// Set on two or more commanded inverters for coordinated multi-inverter scheduling
+    #[serde(default)]
+    pub battery: Option<fluxion_core::InverterBatteryConfig>,
+
+    /// How mode changes reach this inverter: "ha", "modbus" or "dry_run"
+    /// Unset = the configured inverter data source (direct connection with HA failover)
+    #[serde(default)]
+    pub execution: Option<fluxion_core::ExecutionBackendKind>,
 }
 
 /// Pricing configuration
@@ -129,6 +234,121 @@ pub struct PricingConfig {
     /// Grid fee during HDO high tariff periods (CZK/kWh)
     #[serde(default = "default_hdo_high_tariff_czk")]
     pub hdo_high_tariff_czk: f32,
+
+    /// Where the HDO low tariff windows come from: "sensor", "manual" or "preset"
+    #[serde(default)]
+    pub hdo_source: fluxion_core::HdoSource,
+
+    /// Distribution rate for the "preset" source: "d57d" or "d25d"
+    #[serde(default)]
+    pub hdo_preset: fluxion_core::HdoPreset,
+
+    /// Weekly low tariff windows for the "manual" source (local time)
+    #[serde(default)]
+    pub hdo_windows: Vec<fluxion_core::TariffWindow>,
+
+    /// Fetch day-ahead prices directly from OTE when the spot price sensor is missing or stale
+    #[serde(default)]
+    pub ote_fallback: OteFallbackConfig,
+
+    /// Read day-ahead prices from Nord Pool instead of the Home Assistant sensor
+    #[serde(default)]
+    pub nord_pool: NordPoolConfig,
+
+    /// Read contract prices (and optionally consumption) from Tibber
+    #[serde(default)]
+    pub tibber: TibberConfig,
+
+    /// Currency prices are planned, shown and reported in (e.g. "CZK");
+    /// OTE and Nord Pool prices in another currency are converted at the CNB
+    /// daily rate. Each source keeps its own currency when unset.
+    #[serde(default)]
+    pub billing_currency: Option<String>,
+
+    /// Export prices from a separate source instead of spot price minus spot_sell_fee
+    #[serde(default)]
+    pub sell_price: SellPriceConfig,
+}
+
+impl PricingConfig {
+    fn hdo_error(&self) -> Option<&'static str> {
+        if self.hdo_source == fluxion_core::HdoSource::Manual && self.hdo_windows.is_empty() {
+            Some("the manual source needs at least one window")
+        } else if self.hdo_windows.iter().any(|w| w.start == w.end) {
+            Some("window start and end must differ")
+        } else {
+            None
+        }
+    }
+}
+
+/// Dynamic export price source (`[pricing.sell_price]`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SellPriceConfig {
+    /// HA sensor with sell prices, in the same format as the spot price sensor
+    pub entity: Option<String>,
+    /// Sell at the OTE day-ahead price
+    pub ote: bool,
+    /// Subtracted from the source price (CZK/kWh), e.g. the buyer's margin
+    pub margin_czk: f32,
+}
+
+impl SellPriceConfig {
+    fn error(&self) -> Option<&'static str> {
+        if self.entity.as_deref().is_some_and(|e| e.trim().is_empty()) {
+            Some("entity cannot be empty")
+        } else if self.entity.is_some() && self.ote {
+            Some("set either entity or ote, not both")
+        } else if !self.margin_czk.is_finite() {
+            Some("margin_czk must be a number")
+        } else {
+            None
+        }
+    }
+}
+
+/// Native OTE day-ahead price source used as a fallback for the HA sensor
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OteFallbackConfig {
+    pub enabled: bool,
+    /// Fixed EUR/CZK rate; the CNB daily rate when unset
+    pub eur_czk_rate: Option<f32>,
+}
+
+/// Nord Pool day-ahead price source for the Nordic and Baltic bidding areas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NordPoolConfig {
+    pub enabled: bool,
+    /// Bidding area, e.g. "SE3", "FI", "DK1" or "NO2"
+    pub area: String,
+    /// Price currency: EUR, SEK, NOK or DKK
+    pub currency: String,
+}
+
+/// Tibber dynamic tariff integration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TibberConfig {
+    pub enabled: bool,
+    /// Personal access token from developer.tibber.com
+    pub api_token: String,
+    /// Home to read; the first home of the account when unset
+    pub home_id: Option<String>,
+    /// Read consumption history from Tibber (Pulse) instead of the HA sensor
+    pub use_for_consumption: bool,
+}
+
+impl Default for NordPoolConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            area: "SE3".to_owned(),
+            currency: "EUR".to_owned(),
+        }
+    }
 }
 
 /// Control configuration
@@ -137,6 +357,11 @@ pub struct ControlConfig {
     /// Maximum export power limit (watts)
     pub maximum_export_power_w: u32,
 
+    /// Rated AC output power of the inverter (watts)
+    /// Used to model PV clipping in planning; 0 disables AC limit modeling
+    #[serde(default)]
+    pub inverter_max_ac_power_w: u32,
+
     /// Number of cheapest hours to force-charge
     pub force_charge_hours: usize,
 
@@ -208,6 +433,83 @@ pub struct ControlConfig {
     /// - NoChargeNoDischarge: Hold battery charge, grid powers house directly
     #[serde(default = "default_battery_mode")]
     pub default_battery_mode: String,
+
+    /// Mode to force inverters into when the emergency safe state is engaged
+    /// Options: "NoChargeNoDischarge" (default), "SelfUse", or "BackUpMode"
+    #[serde(default = "default_safe_state_mode")]
+    pub safe_state_mode: String,
+
+    /// Plan force-charge blocks below the maximum charge rate (default: false)
+    /// Only enable for inverters with charge-current control
+    #[serde(default)]
+    pub partial_charge_enabled: bool,
+
+    /// Spread a force charge that would fill the battery early over the whole run
+    /// at reduced power (default: false)
+    #[serde(default)]
+    pub charge_power_modulation_enabled: bool,
+
+    /// Main breaker limit for grid import in kW (default: 0 = unlimited)
+    #[serde(default)]
+    pub max_grid_import_kw: f32,
+
+    /// Hours ahead planned by the strategies (default: 0 = whole price horizon)
+    /// Later blocks use a cheap price-percentile heuristic
+    #[serde(default)]
+    pub optimization_horizon_hours: u32,
+
+    /// Depth-, temperature- and calendar-aware battery wear costing (default: disabled)
+    #[serde(default)]
+    pub battery_degradation: fluxion_core::BatteryDegradationConfig,
+
+    /// Announced windows with a grid export cap (e.g. distributor testing)
+    /// The scheduler and executor keep export under `max_export_w` during each
+    #[serde(default)]
+    pub export_cap_windows: Vec<fluxion_core::ExportCapWindow>,
+
+    /// Feasibility check of each new schedule: "repair" (default), "flag" or "off"
+    #[serde(default)]
+    pub schedule_guard: fluxion_core::ScheduleGuardMode,
+
+    /// Tolerance band in SOC % around the planned SOC trajectory (default: 5, 0 = off)
+    /// Outside the band execution charges earlier, pauses charging or stops discharging
+    #[serde(default = "default_soc_tracking_tolerance")]
+    pub soc_tracking_tolerance_pct: f32,
+
+    /// Drift in SOC % from the planned trajectory that replans the remaining blocks
+    /// (default: 10, 0 = off)
+    #[serde(default = "default_replan_drift")]
+    pub replan_drift_pct: f32,
+
+    /// Czech public holidays and vacations planned like weekends, without forced discharge
+    #[serde(default)]
+    pub calendar: fluxion_core::CalendarConfig,
+}
+
+/// Log the migrations applied to the config read from `source`
+pub(crate) fn log_migration(report: &fluxion_core::MigrationReport, source: &str) {
+    if report.is_newer() {
+        warn!(
+            "{source} has config schema version {}, newer than the supported {}; unknown keys are ignored",
+            report.from_version,
+            fluxion_core::CONFIG_SCHEMA_VERSION
+        );
+        return;
+    }
+    if !report.migrated() {
+        return;
+    }
+    info!(
+        "🔧 Migrated {source} from config schema version {} to {}",
+        report.from_version, report.to_version
+    );
+    for applied in &report.applied {
+        info!("   - {applied}");
+    }
+}
+
+fn default_schema_version() -> u32 {
+    fluxion_core::UNVERSIONED_SCHEMA_VERSION
 }
 
 fn default_battery_capacity() -> f32 {
@@ -246,6 +548,18 @@ fn default_battery_mode() -> String {
     "SelfUse".to_string() // Default to self-use mode for backward compatibility
 }
 
+fn default_safe_state_mode() -> String {
+    "NoChargeNoDischarge".to_string()
+}
+
+fn default_soc_tracking_tolerance() -> f32 {
+    5.0
+}
+
+fn default_replan_drift() -> f32 {
+    10.0
+}
+
 fn default_spot_buy_fee() -> f32 {
     0.5
 }
@@ -266,12 +580,43 @@ fn default_hdo_high_tariff_czk() -> f32 {
     1.80
 }
 
+/// Port the web server listens on
+const WEB_PORT: u16 = 8099;
+
+/// Shortest accepted web API token, so tokens cannot be guessed
+const MIN_WEB_TOKEN_LEN: usize = 16;
+
+/// Whether `code` looks like an ISO 4217 currency code
+fn is_currency_code(code: &str) -> bool {
+    code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// Shortest license key FluxION Server issues
+const MIN_LICENSE_KEY_LEN: usize = 16;
+
+/// Characters that would break out of HTML, JS strings or email subjects
+const BRANDING_FORBIDDEN_CHARS: &[char] = &['<', '>', '&', '"', '\'', '`'];
+
+/// Whether `path` is an existing image the dashboard can show as a logo
+fn is_logo_file(path: &std::path::Path) -> bool {
+    let image = path.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+        ["png", "jpg", "jpeg", "svg", "webp"]
+            .iter()
+            .any(|ext| e.eq_ignore_ascii_case(ext))
+    });
+    image && path.is_file()
+}
+
 /// System configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemConfig {
     /// Debug mode (default: true for safety)
     pub debug_mode: bool,
 
+    /// Serve a JSON snapshot of the ECS state at /api/debug/ecs
+    #[serde(default)]
+    pub developer_mode: bool,
+
     /// Update interval (seconds)
     pub update_interval_secs: u64,
 
@@ -1329,6 +1674,74 @@ pub struct SolarForecastConfig {
     /// Fetch interval in seconds
     #[serde(default)]
     pub fetch_interval_seconds: u64,
+
+    /// Latitude of the PV system, for Solcast and Forecast.Solar
+    #[serde(default)]
+    pub latitude: f64,
+
+    /// Longitude of the PV system, for Solcast and Forecast.Solar
+    #[serde(default)]
+    pub longitude: f64,
+
+    /// PV strings forecast by Solcast or Forecast.Solar
+    #[serde(default)]
+    pub strings: Vec<PvStringConfig>,
+
+    /// Read the forecast from Solcast instead of HA sensors
+    #[serde(default)]
+    pub solcast: SolcastConfig,
+
+    /// Read the forecast from Forecast.Solar instead of HA sensors
+    #[serde(default)]
+    pub forecast_solar: ForecastSolarConfig,
+}
+
+impl SolarForecastConfig {
+    /// Whether a native provider replaces the HA sensors
+    pub fn uses_native_provider(&self) -> bool {
+        self.solcast.enabled || self.forecast_solar.enabled
+    }
+}
+
+/// Orientation and size of one PV string
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PvStringConfig {
+    /// Compass direction the panels face: 0 = north, 90 = east, 180 = south, 270 = west
+    pub azimuth: f32,
+    /// Panel tilt from horizontal in degrees
+    pub tilt: f32,
+    /// Peak power (kWp)
+    pub kwp: f32,
+}
+
+/// Solcast rooftop PV forecast
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SolcastConfig {
+    pub enabled: bool,
+    /// API key from the Solcast account
+    pub api_key: String,
+    /// API calls per day allowed for the account (10 for hobbyist accounts)
+    pub daily_limit: u32,
+}
+
+impl Default for SolcastConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_key: String::new(),
+            daily_limit: 10,
+        }
+    }
+}
+
+/// Forecast.Solar production estimate
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ForecastSolarConfig {
+    pub enabled: bool,
+    /// Personal API key; the public API is used when unset
+    pub api_key: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1338,6 +1751,186 @@ pub struct RemoteAccessConfig {
     pub enabled: bool,
 }
 
+/// Logging configuration. The default level comes from `system.log_level`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfig {
+    /// Per-module overrides as `target=level`, e.g. `fluxion_core::scheduling=debug`
+    pub module_levels: Vec<String>,
+    /// Also write logs to daily rotated files
+    pub file_enabled: bool,
+    /// Directory for log files
+    pub directory: String,
+    /// Number of daily log files to keep
+    pub max_files: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            module_levels: Vec::new(),
+            file_enabled: false,
+            directory: "/data/logs".to_string(),
+            max_files: 7,
+        }
+    }
+}
+
+/// Grid voltage/frequency observer. Sustained over-voltage reduces planned
+/// export and keeps PV surplus in the battery.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridQualityConfig {
+    pub enabled: bool,
+    /// Voltage considered too high (EN 50160: 230 V + 10 %)
+    pub overvoltage_threshold_v: f32,
+    /// How long the average voltage must stay above the threshold (minutes)
+    pub sustained_minutes: u32,
+    /// Normal frequency band (Hz)
+    pub frequency_min_hz: f32,
+    pub frequency_max_hz: f32,
+    /// Share of the export limit kept while over-voltage persists (%)
+    pub overvoltage_export_percent: f32,
+}
+
+impl Default for GridQualityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            overvoltage_threshold_v: 253.0,
+            sustained_minutes: 10,
+            frequency_min_hz: 49.8,
+            frequency_max_hz: 50.2,
+            overvoltage_export_percent: 50.0,
+        }
+    }
+}
+
+/// Quarter-hour peak demand limiting (`[peak_demand]`). Shaves the rolling
+/// 15-minute import average before it exceeds the contracted maximum.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PeakDemandConfig {
+    pub enabled: bool,
+    /// Contracted quarter-hour maximum import (kW)
+    pub max_import_kw: f32,
+    /// Share of the maximum at which shaving starts (%)
+    pub trigger_percent: f32,
+}
+
+impl Default for PeakDemandConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_import_kw: 10.0,
+            trigger_percent: 90.0,
+        }
+    }
+}
+
+impl PeakDemandConfig {
+    fn error(&self) -> Option<(&'static str, &'static str)> {
+        if self.enabled && (self.max_import_kw.is_nan() || self.max_import_kw <= 0.0) {
+            Some(("max_import_kw", "must be greater than 0"))
+        } else if !(1.0..=100.0).contains(&self.trigger_percent) {
+            Some(("trigger_percent", "must be between 1 and 100%"))
+        } else {
+            None
+        }
+    }
+}
+
+/// Weather forecast (`[weather]`). Keeps a reserve before cold cloudy days
+/// and skips the morning pre-charge before clear days.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeatherConfig {
+    pub enabled: bool,
+    /// `home_assistant` (weather entity) or `open_meteo`
+    pub source: fluxion_core::WeatherSource,
+    /// Weather entity with an hourly forecast
+    pub entity_id: String,
+    /// Location for Open-Meteo
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Fetch interval in seconds
+    pub fetch_interval_seconds: u64,
+    /// Thresholds and adjustments
+    #[serde(flatten)]
+    pub planning: fluxion_core::WeatherPlanningConfig,
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        let source = fluxion_core::WeatherConfigCore::default();
+        Self {
+            enabled: source.enabled,
+            source: source.source,
+            entity_id: source.entity_id,
+            latitude: source.latitude,
+            longitude: source.longitude,
+            fetch_interval_seconds: source.fetch_interval_seconds,
+            planning: fluxion_core::WeatherPlanningConfig::default(),
+        }
+    }
+}
+
+impl WeatherConfig {
+    fn error(&self) -> Option<(&'static str, &'static str)> {
+        let planning = &self.planning;
+        if !self.enabled {
+            None
+        } else if self.source == fluxion_core::WeatherSource::HomeAssistant
+            && !self.entity_id.starts_with("weather.")
+        {
+            Some(("entity_id", "must be a weather.* entity"))
+        } else if self.source == fluxion_core::WeatherSource::OpenMeteo
+            && (!(-90.0..=90.0).contains(&self.latitude)
+                || !(-180.0..=180.0).contains(&self.longitude))
+        {
+            Some(("latitude", "latitude/longitude are out of range"))
+        } else if self.fetch_interval_seconds < 60 {
+            Some(("fetch_interval_seconds", "must be at least 60 seconds"))
+        } else if !(0.0..=100.0).contains(&planning.cloudy_threshold_pct)
+            || !(0.0..=planning.cloudy_threshold_pct).contains(&planning.clear_threshold_pct)
+        {
+            Some((
+                "clear_threshold_pct",
+                "must be between 0 and cloudy_threshold_pct (at most 100%)",
+            ))
+        } else if !(0.0..=100.0).contains(&planning.cold_cloudy_reserve_soc) {
+            Some(("cold_cloudy_reserve_soc", "must be between 0 and 100%"))
+        } else if planning.morning_end_hour > 23 {
+            Some(("morning_end_hour", "must be between 0 and 23"))
+        } else {
+            None
+        }
+    }
+}
+
+/// Export file naming, so fleets collecting exports centrally can tell sites apart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// File name without extension; tokens `{kind}`, `{date}`, `{time}`,
+    /// `{stamp}`, `{site}` and `{version}`
+    pub filename_template: String,
+    /// Installation name for `{site}`
+    pub site_name: String,
+    /// Formats of the daily export: `json`, `csv` and/or `parquet`
+    pub formats: Vec<fluxion_web::ExportFormat>,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            filename_template: fluxion_web::DEFAULT_EXPORT_FILENAME_TEMPLATE.to_owned(),
+            site_name: String::new(),
+            formats: vec![fluxion_web::ExportFormat::Json],
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ServerHeartbeatConfig {
@@ -1362,17 +1955,282 @@ impl Default for ServerHeartbeatConfig {
     }
 }
 
-impl Default for AppConfig {
-    /// Default configuration for single Solax inverter
+/// Commercial license, needed for fleet features of FluxION Server
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LicenseConfig {
+    /// Key issued by SOLARE; None for non-commercial use
+    pub key: Option<String>,
+}
+
+impl LicenseConfig {
+    /// Configured key, ignoring an empty option
+    pub fn key(&self) -> Option<&str> {
+        self.key
+            .as_deref()
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthcheckPingConfig {
+    pub enabled: bool,
+    /// Check URL, e.g. `https://hc-ping.com/<uuid>`; failures go to `<url>/fail`
+    pub ping_url: String,
+    /// How often to look for a new planning cycle
+    pub check_interval_seconds: u64,
+}
+
+impl Default for HealthcheckPingConfig {
     fn default() -> Self {
         Self {
-            inverters: vec![InverterConfig {
-                id: "main_inverter".to_string(),
-                inverter_type: fluxion_core::InverterType::Solax,
-                entity_prefix: "solax".to_string(),
-                topology: "independent".to_string(),
-                slaves: None,
+            enabled: false,
+            ping_url: String::new(),
+            check_interval_seconds: 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhooksConfig {
+    pub enabled: bool,
+    /// URLs receiving a JSON POST for every new schedule and mode change
+    pub urls: Vec<String>,
+    /// Retries of a failed delivery, with exponential backoff from 2 seconds
+    pub max_retries: u32,
+}
+
+impl Default for WebhooksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            urls: Vec::new(),
+            max_retries: 5,
+        }
+    }
+}
+
+impl WebhooksConfig {
+    /// First URL that is not http(s)
+    fn invalid_url(&self) -> Option<&str> {
+        self.urls
+            .iter()
+            .map(String::as_str)
+            .find(|url| !url.starts_with("http://") && !url.starts_with("https://"))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrpcConfig {
+    pub enabled: bool,
+    /// Port of the Query, Control and Streaming services
+    pub port: u16,
+    /// Interface to listen on; anything but loopback needs an API key
+    pub bind_address: std::net::IpAddr,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: fluxion_grpc::DEFAULT_GRPC_PORT,
+            bind_address: fluxion_grpc::DEFAULT_GRPC_BIND_ADDRESS,
+        }
+    }
+}
+
+impl GrpcConfig {
+    /// Port conflicts of an enabled server
+    fn port_error(&self) -> Option<&'static str> {
+        if !self.enabled {
+            None
+        } else if self.port == 0 {
+            Some("Must not be 0")
+        } else if self.port == WEB_PORT {
+            Some("Already used by the web server")
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecisionLogConfig {
+    pub enabled: bool,
+    /// Decisions older than this are deleted
+    pub retention_days: u32,
+}
+
+impl Default for DecisionLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            retention_days: 90,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistorianConfig {
+    pub enabled: bool,
+    /// Time between two telemetry samples
+    pub sample_interval_secs: u64,
+    /// Raw samples older than this are deleted; 5-minute averages are kept
+    pub raw_retention_days: u32,
+}
+
+impl Default for HistorianConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            sample_interval_secs: 10,
+            raw_retention_days: 7,
+        }
+    }
+}
+
+impl HistorianConfig {
+    fn error(&self) -> Option<(&'static str, &'static str)> {
+        if !self.enabled {
+            None
+        } else if self.sample_interval_secs == 0 || self.sample_interval_secs > 300 {
+            Some(("sample_interval_secs", "must be between 1 and 300 seconds"))
+        } else if self.raw_retention_days == 0 {
+            Some(("raw_retention_days", "must be at least 1 day"))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WasmPluginsConfig {
+    pub enabled: bool,
+    /// Directory scanned for `.wasm` strategy plugins
+    pub directory: String,
+    /// Time between two scans for new, changed or deleted modules
+    pub scan_interval_secs: u64,
+}
+
+impl Default for WasmPluginsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            directory: "/data/plugins".to_owned(),
+            scan_interval_secs: 10,
+        }
+    }
+}
+
+impl WasmPluginsConfig {
+    fn error(&self) -> Option<(&'static str, &'static str)> {
+        if !self.enabled {
+            None
+        } else if self.directory.trim().is_empty() {
+            Some(("directory", "must not be empty"))
+        } else if self.scan_interval_secs == 0 {
+            Some(("scan_interval_secs", "must be at least 1 second"))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavingsConfig {
+    pub enabled: bool,
+}
+
+impl Default for SavingsConfig {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    /// The main loop counts as stuck after not running for this long
+    pub stall_timeout_seconds: u64,
+    /// Touched while the main loop runs, for a Docker `HEALTHCHECK`; empty to disable
+    pub heartbeat_file: String,
+    /// Exit when the main loop is stuck so the supervisor restarts FluxION
+    pub exit_on_stall: bool,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stall_timeout_seconds: 300,
+            heartbeat_file: "/tmp/fluxion-heartbeat".to_owned(),
+            exit_on_stall: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    /// Broker host, e.g. `core-mosquitto` for the Home Assistant add-on
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// MQTT client id; also prefixes the discovery unique ids
+    pub client_id: String,
+    /// Topics are published under `<base_topic>/...`
+    pub base_topic: String,
+    /// Publish Home Assistant MQTT discovery configs
+    pub discovery: bool,
+    pub discovery_prefix: String,
+    pub publish_interval_seconds: u64,
+    /// Expose debug mode as a switch and accept `ON`/`OFF` on `<base_topic>/debug_mode/set`
+    pub debug_mode_switch: bool,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "core-mosquitto".to_owned(),
+            port: 1883,
+            username: None,
+            password: None,
+            client_id: "fluxion".to_owned(),
+            base_topic: "fluxion".to_owned(),
+            discovery: true,
+            discovery_prefix: "homeassistant".to_owned(),
+            publish_interval_seconds: 30,
+            debug_mode_switch: true,
+        }
+    }
+}
+
+impl Default for AppConfig {
+    /// Default configuration for single Solax inverter
+    fn default() -> Self {
+        Self {
+            schema_version: fluxion_core::CONFIG_SCHEMA_VERSION,
+            inverters: vec![InverterConfig {
+                id: "main_inverter".to_string(),
+                inverter_type: fluxion_core::InverterType::Solax,
+                entity_prefix: "solax".to_string(),
+                topology: "independent".to_string(),
+                slaves: None,
                 master: None,
+                battery: None,
+                execution: None,
             }],
             pricing: PricingConfig {
                 spot_price_entity: "sensor.current_spot_electricity_price_15min".to_string(),
@@ -1386,9 +2244,18 @@ impl Default for AppConfig {
                 hdo_sensor_entity: default_hdo_sensor_entity(),
                 hdo_low_tariff_czk: default_hdo_low_tariff_czk(),
                 hdo_high_tariff_czk: default_hdo_high_tariff_czk(),
+                hdo_source: fluxion_core::HdoSource::default(),
+                hdo_preset: fluxion_core::HdoPreset::default(),
+                hdo_windows: Vec::new(),
+                ote_fallback: OteFallbackConfig::default(),
+                nord_pool: NordPoolConfig::default(),
+                tibber: TibberConfig::default(),
+                billing_currency: None,
+                sell_price: SellPriceConfig::default(),
             },
             control: ControlConfig {
                 maximum_export_power_w: 5000,
+                inverter_max_ac_power_w: 0,
                 force_charge_hours: 4,
                 force_discharge_hours: 2,
                 min_battery_soc: 10.0,
@@ -1402,9 +2269,21 @@ impl Default for AppConfig {
                 hardware_min_battery_soc: default_hardware_min_soc(),
                 min_consecutive_force_blocks: default_min_consecutive_force_blocks(),
                 default_battery_mode: default_battery_mode(),
+                safe_state_mode: default_safe_state_mode(),
+                partial_charge_enabled: false,
+                charge_power_modulation_enabled: false,
+                max_grid_import_kw: 0.0,
+                optimization_horizon_hours: 0,
+                battery_degradation: fluxion_core::BatteryDegradationConfig::default(),
+                export_cap_windows: Vec::new(),
+                schedule_guard: fluxion_core::ScheduleGuardMode::default(),
+                soc_tracking_tolerance_pct: default_soc_tracking_tolerance(),
+                replan_drift_pct: default_replan_drift(),
+                calendar: fluxion_core::CalendarConfig::default(),
             },
             system: SystemConfig {
                 debug_mode: true, // Safe default
+                developer_mode: false,
                 update_interval_secs: 60,
                 log_level: "info".to_string(),
                 ha_base_url: None,
@@ -1418,6 +2297,29 @@ impl Default for AppConfig {
             solar_forecast: SolarForecastConfig::default(),
             remote_access: RemoteAccessConfig::default(),
             server_heartbeat: ServerHeartbeatConfig::default(),
+            license: LicenseConfig::default(),
+            healthcheck_ping: HealthcheckPingConfig::default(),
+            webhooks: WebhooksConfig::default(),
+            dhw: fluxion_core::dhw::DhwConfig::default(),
+            heat_pump: fluxion_core::heat_pump::HeatPumpConfig::default(),
+            grpc: GrpcConfig::default(),
+            modbus: fluxion_modbus::ModbusConfig::default(),
+            solax_local: fluxion_adapters::SolaxLocalConfig::default(),
+            victron: fluxion_modbus::VictronConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            mqtt: MqttConfig::default(),
+            logging: LoggingConfig::default(),
+            grid_quality: GridQualityConfig::default(),
+            peak_demand: PeakDemandConfig::default(),
+            weather: WeatherConfig::default(),
+            export: ExportConfig::default(),
+            decision_log: DecisionLogConfig::default(),
+            savings: SavingsConfig::default(),
+            historian: HistorianConfig::default(),
+            wasm_plugins: WasmPluginsConfig::default(),
+            submeter: fluxion_core::submeter::SubmeterConfig::default(),
+            web_auth: fluxion_web::WebAuthConfig::default(),
+            branding: fluxion_web::BrandingConfig::default(),
         }
     }
 }
@@ -1427,8 +2329,9 @@ impl AppConfig {
     pub fn load() -> Result<Self> {
         // Try HA addon options first (/data/options.json)
         if let Ok(options_str) = std::fs::read_to_string("/data/options.json") {
-            let config: AppConfig =
+            let raw =
                 serde_json::from_str(&options_str).context("Failed to parse HA addon options")?;
+            let config = Self::from_migrated(raw, "HA addon options")?;
             info!("✅ Loaded configuration from HA addon options");
             config.validate()?;
             return Ok(config);
@@ -1436,8 +2339,8 @@ impl AppConfig {
 
         // Try config.toml for development
         if let Ok(config_str) = std::fs::read_to_string("config.toml") {
-            let config: AppConfig =
-                toml::from_str(&config_str).context("Failed to parse config.toml")?;
+            let raw = toml::from_str(&config_str).context("Failed to parse config.toml")?;
+            let config = Self::from_migrated(raw, "config.toml")?;
             info!("✅ Loaded configuration from config.toml");
             config.validate()?;
             return Ok(config);
@@ -1445,8 +2348,8 @@ impl AppConfig {
 
         // Try config.json for development
         if let Ok(config_str) = std::fs::read_to_string("config.json") {
-            let config: AppConfig =
-                serde_json::from_str(&config_str).context("Failed to parse config.json")?;
+            let raw = serde_json::from_str(&config_str).context("Failed to parse config.json")?;
+            let config = Self::from_migrated(raw, "config.json")?;
             info!("✅ Loaded configuration from config.json");
             config.validate()?;
             return Ok(config);
@@ -1459,6 +2362,16 @@ impl AppConfig {
         Ok(config)
     }
 
+    /// Deserialize a raw config after migrating it to the current schema
+    ///
+    /// Files owned by the user or by Home Assistant are migrated in memory
+    /// only; the web UI copy is rewritten by the persistence layer.
+    pub(crate) fn from_migrated(mut raw: serde_json::Value, source: &str) -> Result<Self> {
+        let report = fluxion_core::migrate_config(&mut raw);
+        log_migration(&report, source);
+        serde_json::from_value(raw).with_context(|| format!("Failed to parse {source}"))
+    }
+
     /// Load from environment variables (development/testing)
     fn from_env() -> Self {
         let mut config = Self::default();
@@ -1493,6 +2406,33 @@ impl AppConfig {
         config
     }
 
+    /// First of `ids` that is not a configured inverter
+    fn unknown_inverter<'a>(&self, mut ids: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+        ids.find(|id| !self.inverters.iter().any(|i| i.id == *id))
+    }
+
+    /// Whether a Modbus or Victron device is configured for `inverter_id`
+    fn has_modbus_device(&self, inverter_id: &str) -> bool {
+        let modbus = self.modbus.enabled
+            && self
+                .modbus
+                .devices
+                .iter()
+                .any(|d| d.inverter_id == inverter_id);
+        modbus || (self.victron.enabled && self.victron.inverter_id == inverter_id)
+    }
+
+    /// First inverter set to Modbus execution without a device to execute through
+    fn modbus_execution_without_device(&self) -> Option<&str> {
+        self.inverters
+            .iter()
+            .find(|inv| {
+                inv.execution == Some(fluxion_core::ExecutionBackendKind::Modbus)
+                    && !self.has_modbus_device(&inv.id)
+            })
+            .map(|inv| inv.id.as_str())
+    }
+
     /// Validate configuration with detailed error reporting
     pub fn validate_detailed(&self) -> ValidationResult {
         let mut result = ValidationResult::success();
@@ -1548,6 +2488,27 @@ impl AppConfig {
                     );
                 }
             }
+
+            if let Some(battery) = &inverter.battery {
+                if battery.capacity_kwh <= 0.0 {
+                    result.add_error(
+                        format!("{prefix}.battery.capacity_kwh"),
+                        "Battery capacity must be greater than 0",
+                    );
+                }
+                if battery.max_charge_rate_kw <= 0.0 {
+                    result.add_error(
+                        format!("{prefix}.battery.max_charge_rate_kw"),
+                        "Charge rate must be greater than 0",
+                    );
+                }
+                if inverter.topology == "slave" {
+                    result.add_warning(
+                        format!("{prefix}.battery"),
+                        "Slave batteries follow their master; set the combined battery on the master",
+                    );
+                }
+            }
         }
 
         // Validate pricing
@@ -1583,6 +2544,68 @@ impl AppConfig {
                 ),
             );
         }
+        if self
+            .pricing
+            .ote_fallback
+            .eur_czk_rate
+            .is_some_and(|rate| rate <= 0.0)
+        {
+            result.add_error(
+                "pricing.ote_fallback.eur_czk_rate",
+                "EUR/CZK rate must be greater than 0",
+            );
+        }
+        if let Some(e) = self.pricing.sell_price.error() {
+            result.add_error("pricing.sell_price", e);
+        }
+        if let Some(e) = self.pricing.hdo_error() {
+            result.add_error("pricing.hdo_windows", e);
+        }
+        if self
+            .pricing
+            .billing_currency
+            .as_deref()
+            .is_some_and(|code| !is_currency_code(code))
+        {
+            result.add_error(
+                "pricing.billing_currency",
+                "Must be a three-letter currency code listed by the CNB, e.g. CZK or EUR",
+            );
+        }
+        if self.pricing.nord_pool.enabled {
+            let nord_pool = &self.pricing.nord_pool;
+            if !NORD_POOL_AREAS.contains(&nord_pool.area.to_uppercase().as_str()) {
+                result.add_error(
+                    "pricing.nord_pool.area",
+                    format!(
+                        "Unknown bidding area '{}', expected one of: {}",
+                        nord_pool.area,
+                        NORD_POOL_AREAS.join(", ")
+                    ),
+                );
+            }
+            if !NORD_POOL_CURRENCIES.contains(&nord_pool.currency.to_uppercase().as_str()) {
+                result.add_error(
+                    "pricing.nord_pool.currency",
+                    format!(
+                        "Unsupported currency '{}', expected one of: {}",
+                        nord_pool.currency,
+                        NORD_POOL_CURRENCIES.join(", ")
+                    ),
+                );
+            }
+        }
+        if self.pricing.tibber.enabled || self.pricing.tibber.use_for_consumption {
+            if self.pricing.tibber.api_token.trim().is_empty() {
+                result.add_error("pricing.tibber.api_token", "Tibber API token is required");
+            }
+            if self.pricing.tibber.enabled && self.pricing.nord_pool.enabled {
+                result.add_error(
+                    "pricing.tibber.enabled",
+                    "Tibber and Nord Pool cannot both be the price source",
+                );
+            }
+        }
 
         // Validate control parameters
         // CRITICAL: maximum_export_power_w must be set correctly to avoid grid penalties
@@ -1619,6 +2642,33 @@ impl AppConfig {
         if self.control.battery_efficiency <= 0.0 || self.control.battery_efficiency > 1.0 {
             result.add_error("control.battery_efficiency", "Must be between 0.0 and 1.0");
         }
+        let degradation = &self.control.battery_degradation;
+        if degradation.depth_exponent < 1.0 {
+            result.add_error(
+                "control.battery_degradation.depth_exponent",
+                "Must be at least 1.0",
+            );
+        }
+        if degradation.temperature_doubling_c <= 0.0 {
+            result.add_error(
+                "control.battery_degradation.temperature_doubling_c",
+                "Must be positive",
+            );
+        }
+        if degradation.calendar_aging_czk_per_day < 0.0 {
+            result.add_error(
+                "control.battery_degradation.calendar_aging_czk_per_day",
+                "Must be non-negative",
+            );
+        }
+        for (i, window) in self.control.export_cap_windows.iter().enumerate() {
+            if window.end <= window.start {
+                result.add_error(
+                    format!("control.export_cap_windows[{i}].end"),
+                    "Window must end after it starts",
+                );
+            }
+        }
 
         // Validate mode change interval
         if self.control.min_mode_change_interval_secs < 60 {
@@ -1651,6 +2701,24 @@ impl AppConfig {
             );
         }
 
+        // Validate grid import limit (main breaker)
+        if self.control.max_grid_import_kw < 0.0 {
+            result.add_error(
+                "control.max_grid_import_kw",
+                "Must be non-negative (0 = unlimited)",
+            );
+        }
+
+        // Validate quarter-hour peak demand limiting
+        if let Some((field, e)) = self.peak_demand.error() {
+            result.add_error(format!("peak_demand.{field}"), e);
+        }
+
+        // Validate weather forecast source and thresholds
+        if let Some((field, e)) = self.weather.error() {
+            result.add_error(format!("weather.{field}"), e);
+        }
+
         // Validate system
         if self.system.update_interval_secs < 10 {
             result.add_error("system.update_interval_secs", "Must be at least 10 seconds");
@@ -1665,6 +2733,274 @@ impl AppConfig {
             );
         }
 
+        // Validate export naming
+        if self.export.filename_template.trim().is_empty() {
+            result.add_error("export.filename_template", "Must not be empty");
+        }
+        if self.export.formats.is_empty() {
+            result.add_error("export.formats", "At least one format is required");
+        }
+
+        // Validate webhooks
+        if let Some(url) = self.webhooks.invalid_url() {
+            result.add_error(
+                "webhooks.urls",
+                format!("Must start with http:// or https://, got {url}"),
+            );
+        }
+
+        // Validate the hot water scheduler
+        if self.dhw.enabled
+            && let Err(e) = self.dhw.validate()
+        {
+            result.add_error("dhw", e);
+        }
+
+        // Validate heat pump coordination
+        if self.heat_pump.enabled
+            && let Err(e) = self.heat_pump.validate()
+        {
+            result.add_error("heat_pump", e);
+        }
+
+        // Validate the gRPC API
+        if let Some(e) = self.grpc.port_error() {
+            result.add_error("grpc.port", e);
+        }
+
+        // Validate the Modbus adapter
+        if self.modbus.enabled {
+            if let Err(e) = self.modbus.validate() {
+                result.add_error("modbus", e);
+            }
+            if let Some(id) =
+                self.unknown_inverter(self.modbus.devices.iter().map(|d| d.inverter_id.as_str()))
+            {
+                result.add_error(
+                    "modbus.devices",
+                    format!("Inverter '{id}' is not in [[inverters]]"),
+                );
+            }
+        }
+
+        // Validate the Solax local API adapter
+        if self.solax_local.enabled {
+            if let Err(e) = self.solax_local.validate() {
+                result.add_error("solax_local", e);
+            }
+            if let Some(id) = self.unknown_inverter(
+                self.solax_local
+                    .devices
+                    .iter()
+                    .map(|d| d.inverter_id.as_str()),
+            ) {
+                result.add_error(
+                    "solax_local.devices",
+                    format!("Inverter '{id}' is not in [[inverters]]"),
+                );
+            }
+            if self.modbus.enabled {
+                result.add_error(
+                    "solax_local.enabled",
+                    "Cannot be combined with modbus; enable one direct connection".to_owned(),
+                );
+            }
+        }
+
+        // Validate the Victron GX adapter
+        if self.victron.enabled {
+            if let Err(e) = self.victron.validate() {
+                result.add_error("victron", e);
+            }
+            if let Some(id) =
+                self.unknown_inverter(std::iter::once(self.victron.inverter_id.as_str()))
+            {
+                result.add_error(
+                    "victron.inverter_id",
+                    format!("Inverter '{id}' is not in [[inverters]]"),
+                );
+            }
+            if self.modbus.enabled || self.solax_local.enabled {
+                result.add_error(
+                    "victron.enabled",
+                    "Cannot be combined with modbus or solax_local; enable one direct connection"
+                        .to_owned(),
+                );
+            }
+        }
+
+        // Validate per-inverter execution backends
+        if let Some(id) = self.modbus_execution_without_device() {
+            result.add_error(
+                "inverters.execution",
+                format!(
+                    "Inverter '{id}' uses modbus execution but has no modbus or victron device"
+                ),
+            );
+        }
+
+        // Validate web authentication
+        let web_auth = &self.web_auth;
+        if web_auth.username.is_some() != web_auth.password.is_some() {
+            result.add_error(
+                "web_auth.password",
+                "Username and password must be set together",
+            );
+        }
+        if web_auth
+            .api_tokens
+            .iter()
+            .any(|t| t.len() < MIN_WEB_TOKEN_LEN)
+        {
+            result.add_error(
+                "web_auth.api_tokens",
+                format!("Tokens must have at least {MIN_WEB_TOKEN_LEN} characters"),
+            );
+        }
+        if web_auth.session_hours == 0 {
+            result.add_error("web_auth.session_hours", "Must be at least 1 hour");
+        }
+        if let Some(guest_token) = &web_auth.guest_token {
+            if guest_token.len() < MIN_WEB_TOKEN_LEN {
+                result.add_error(
+                    "web_auth.guest_token",
+                    format!("Token must have at least {MIN_WEB_TOKEN_LEN} characters"),
+                );
+            } else if web_auth.api_tokens.contains(guest_token) {
+                result.add_error("web_auth.guest_token", "Must differ from the API tokens");
+            }
+        }
+
+        // Validate license
+        if self
+            .license
+            .key()
+            .is_some_and(|key| key.len() < MIN_LICENSE_KEY_LEN)
+        {
+            result.add_error(
+                "license.key",
+                format!("Must have at least {MIN_LICENSE_KEY_LEN} characters"),
+            );
+        }
+
+        // Validate branding
+        let branding = &self.branding;
+        if branding
+            .product_name
+            .as_deref()
+            .is_some_and(|name| name.contains(BRANDING_FORBIDDEN_CHARS))
+        {
+            result.add_error("branding.product_name", "Must not contain < > & \" ' or `");
+        }
+        if branding
+            .logo_path
+            .as_deref()
+            .is_some_and(|logo| !is_logo_file(logo))
+        {
+            result.add_error(
+                "branding.logo_path",
+                "Must be an existing PNG, JPEG, SVG or WebP file",
+            );
+        }
+        for (name, value) in branding.colors.iter() {
+            if !fluxion_web::branding::is_hex_color(value) {
+                result.add_error(
+                    format!("branding.colors.{name}"),
+                    "Must be a #rgb or #rrggbb color",
+                );
+            }
+        }
+
+        // Validate decision log
+        if self.decision_log.enabled && self.decision_log.retention_days == 0 {
+            result.add_error("decision_log.retention_days", "Must be at least 1 day");
+        }
+
+        // Validate historian
+        if let Some((field, e)) = self.historian.error() {
+            result.add_error(format!("historian.{field}"), e);
+        }
+
+        // Validate WASM plugins
+        if let Some((field, e)) = self.wasm_plugins.error() {
+            result.add_error(format!("wasm_plugins.{field}"), e);
+        }
+
+        // Validate sub-metering, which stores its readings in the historian
+        if self.submeter.enabled {
+            if let Err(e) = self.submeter.validate() {
+                result.add_error("submeter", e);
+            }
+            if !self.historian.enabled {
+                result.add_error("submeter", "requires historian.enabled");
+            }
+        }
+
+        // Validate watchdog
+        if self.watchdog.enabled && self.watchdog.stall_timeout_seconds < 30 {
+            result.add_error(
+                "watchdog.stall_timeout_seconds",
+                "Must be at least 30 seconds",
+            );
+        }
+
+        // Validate native solar forecast providers
+        let solar = &self.solar_forecast;
+        if solar.solcast.enabled && solar.forecast_solar.enabled {
+            result.add_error(
+                "solar_forecast.solcast.enabled",
+                "Solcast and Forecast.Solar cannot both be the forecast source",
+            );
+        }
+        if solar.solcast.enabled {
+            if solar.solcast.api_key.trim().is_empty() {
+                result.add_error(
+                    "solar_forecast.solcast.api_key",
+                    "Solcast API key is required",
+                );
+            }
+            if solar.solcast.daily_limit == 0 {
+                result.add_error(
+                    "solar_forecast.solcast.daily_limit",
+                    "Must be greater than 0",
+                );
+            }
+        }
+        if solar.uses_native_provider() {
+            if !(-90.0..=90.0).contains(&solar.latitude) {
+                result.add_error("solar_forecast.latitude", "Must be between -90 and 90");
+            }
+            if !(-180.0..=180.0).contains(&solar.longitude) {
+                result.add_error("solar_forecast.longitude", "Must be between -180 and 180");
+            }
+            if solar.strings.is_empty() {
+                result.add_error(
+                    "solar_forecast.strings",
+                    "At least one PV string is required",
+                );
+            }
+            for (i, string) in solar.strings.iter().enumerate() {
+                if !(0.0..=360.0).contains(&string.azimuth) {
+                    result.add_error(
+                        format!("solar_forecast.strings[{i}].azimuth"),
+                        "Must be between 0 and 360",
+                    );
+                }
+                if !(0.0..=90.0).contains(&string.tilt) {
+                    result.add_error(
+                        format!("solar_forecast.strings[{i}].tilt"),
+                        "Must be between 0 and 90",
+                    );
+                }
+                if string.kwp <= 0.0 {
+                    result.add_error(
+                        format!("solar_forecast.strings[{i}].kwp"),
+                        "Must be greater than 0",
+                    );
+                }
+            }
+        }
+
         result
     }
 
@@ -1711,6 +3047,15 @@ impl AppConfig {
                     );
                 }
             }
+
+            if let Some(battery) = &inverter.battery
+                && (battery.capacity_kwh <= 0.0 || battery.max_charge_rate_kw <= 0.0)
+            {
+                anyhow::bail!(
+                    "Inverter '{}' battery needs a positive capacity_kwh and max_charge_rate_kw",
+                    inverter.id
+                );
+            }
         }
 
         // Validate pricing
@@ -1737,6 +3082,52 @@ impl AppConfig {
                 self.pricing.fixed_sell_prices.len()
             );
         }
+        if self
+            .pricing
+            .ote_fallback
+            .eur_czk_rate
+            .is_some_and(|rate| rate <= 0.0)
+        {
+            anyhow::bail!("ote_fallback.eur_czk_rate must be greater than 0");
+        }
+        if let Some(e) = self.pricing.sell_price.error() {
+            anyhow::bail!("pricing.sell_price: {e}");
+        }
+        if let Some(e) = self.pricing.hdo_error() {
+            anyhow::bail!("pricing.hdo_windows: {e}");
+        }
+        if let Some(code) = &self.pricing.billing_currency
+            && !is_currency_code(code)
+        {
+            anyhow::bail!(
+                "pricing.billing_currency must be a three-letter currency code, got '{code}'"
+            );
+        }
+        if self.pricing.nord_pool.enabled {
+            let nord_pool = &self.pricing.nord_pool;
+            if !NORD_POOL_AREAS.contains(&nord_pool.area.to_uppercase().as_str()) {
+                anyhow::bail!(
+                    "nord_pool.area must be one of {}, got '{}'",
+                    NORD_POOL_AREAS.join(", "),
+                    nord_pool.area
+                );
+            }
+            if !NORD_POOL_CURRENCIES.contains(&nord_pool.currency.to_uppercase().as_str()) {
+                anyhow::bail!(
+                    "nord_pool.currency must be one of {}, got '{}'",
+                    NORD_POOL_CURRENCIES.join(", "),
+                    nord_pool.currency
+                );
+            }
+        }
+        if self.pricing.tibber.enabled || self.pricing.tibber.use_for_consumption {
+            if self.pricing.tibber.api_token.trim().is_empty() {
+                anyhow::bail!("tibber.api_token is required when Tibber is used");
+            }
+            if self.pricing.tibber.enabled && self.pricing.nord_pool.enabled {
+                anyhow::bail!("tibber and nord_pool cannot both be enabled");
+            }
+        }
 
         // Validate control parameters
         // CRITICAL: maximum_export_power_w must be set correctly to avoid grid penalties
@@ -1787,25 +3178,244 @@ impl AppConfig {
             );
         }
 
-        if self.control.force_charge_hours == 0 {
-            warn!("force_charge_hours is 0 - no charging will be scheduled");
+        if self.control.force_charge_hours == 0 {
+            warn!("force_charge_hours is 0 - no charging will be scheduled");
+        }
+        if self.control.force_discharge_hours == 0 {
+            warn!("force_discharge_hours is 0 - no discharging will be scheduled");
+        }
+        if self.control.max_grid_import_kw < 0.0 {
+            anyhow::bail!("max_grid_import_kw must be non-negative (0 = unlimited)");
+        }
+        if let Some((field, e)) = self.peak_demand.error() {
+            anyhow::bail!("peak_demand.{field} {e}");
+        }
+        if let Some((field, e)) = self.weather.error() {
+            anyhow::bail!("weather.{field} {e}");
+        }
+
+        // Note: charge planning parameters (max_battery_charge_rate_kw, evening_target_soc, evening_peak_start_hour)
+        // use serde defaults and are validated by the core scheduler
+
+        // Validate system
+        if self.system.update_interval_secs < 10 {
+            anyhow::bail!("update_interval_secs must be at least 10 seconds");
+        }
+        if self.system.update_interval_secs > 600 {
+            warn!(
+                "update_interval_secs is very high ({}s), consider reducing",
+                self.system.update_interval_secs
+            );
+        }
+
+        // Validate export naming
+        if self.export.filename_template.trim().is_empty() {
+            anyhow::bail!("export.filename_template must not be empty");
+        }
+        if self.export.formats.is_empty() {
+            anyhow::bail!("export.formats must list at least one format");
+        }
+
+        // Validate webhooks
+        if let Some(url) = self.webhooks.invalid_url() {
+            anyhow::bail!("webhooks.urls must start with http:// or https://, got {url}");
+        }
+
+        // Validate the hot water scheduler
+        if self.dhw.enabled
+            && let Err(e) = self.dhw.validate()
+        {
+            anyhow::bail!("dhw: {e}");
+        }
+
+        // Validate heat pump coordination
+        if self.heat_pump.enabled
+            && let Err(e) = self.heat_pump.validate()
+        {
+            anyhow::bail!("heat_pump: {e}");
+        }
+
+        // Validate the gRPC API
+        if let Some(e) = self.grpc.port_error() {
+            anyhow::bail!("grpc.port: {e}");
+        }
+
+        // Validate the Modbus adapter
+        if self.modbus.enabled {
+            if let Err(e) = self.modbus.validate() {
+                anyhow::bail!("modbus: {e}");
+            }
+            if let Some(id) =
+                self.unknown_inverter(self.modbus.devices.iter().map(|d| d.inverter_id.as_str()))
+            {
+                anyhow::bail!("modbus.devices: inverter '{id}' is not in [[inverters]]");
+            }
+        }
+
+        // Validate the Solax local API adapter
+        if self.solax_local.enabled {
+            if let Err(e) = self.solax_local.validate() {
+                anyhow::bail!("solax_local: {e}");
+            }
+            if let Some(id) = self.unknown_inverter(
+                self.solax_local
+                    .devices
+                    .iter()
+                    .map(|d| d.inverter_id.as_str()),
+            ) {
+                anyhow::bail!("solax_local.devices: inverter '{id}' is not in [[inverters]]");
+            }
+            if self.modbus.enabled {
+                anyhow::bail!("solax_local cannot be combined with modbus");
+            }
+        }
+
+        // Validate the Victron GX adapter
+        if self.victron.enabled {
+            if let Err(e) = self.victron.validate() {
+                anyhow::bail!("victron: {e}");
+            }
+            if let Some(id) =
+                self.unknown_inverter(std::iter::once(self.victron.inverter_id.as_str()))
+            {
+                anyhow::bail!("victron.inverter_id: inverter '{id}' is not in [[inverters]]");
+            }
+            if self.modbus.enabled || self.solax_local.enabled {
+                anyhow::bail!("victron cannot be combined with modbus or solax_local");
+            }
+        }
+
+        // Validate per-inverter execution backends
+        if let Some(id) = self.modbus_execution_without_device() {
+            anyhow::bail!(
+                "inverters.execution: inverter '{id}' uses modbus execution but has no modbus or victron device"
+            );
+        }
+
+        // Validate web authentication
+        let web_auth = &self.web_auth;
+        if web_auth.username.is_some() != web_auth.password.is_some() {
+            anyhow::bail!("web_auth.username and web_auth.password must be set together");
+        }
+        if web_auth
+            .api_tokens
+            .iter()
+            .any(|t| t.len() < MIN_WEB_TOKEN_LEN)
+        {
+            anyhow::bail!("web_auth.api_tokens must have at least {MIN_WEB_TOKEN_LEN} characters");
+        }
+        if web_auth.session_hours == 0 {
+            anyhow::bail!("web_auth.session_hours must be at least 1 hour");
+        }
+        if let Some(guest_token) = &web_auth.guest_token {
+            if guest_token.len() < MIN_WEB_TOKEN_LEN {
+                anyhow::bail!(
+                    "web_auth.guest_token must have at least {MIN_WEB_TOKEN_LEN} characters"
+                );
+            }
+            if web_auth.api_tokens.contains(guest_token) {
+                anyhow::bail!("web_auth.guest_token must differ from web_auth.api_tokens");
+            }
+        }
+
+        // Validate license
+        if self
+            .license
+            .key()
+            .is_some_and(|key| key.len() < MIN_LICENSE_KEY_LEN)
+        {
+            anyhow::bail!("license.key must have at least {MIN_LICENSE_KEY_LEN} characters");
+        }
+
+        // Validate branding
+        let branding = &self.branding;
+        if branding
+            .product_name
+            .as_deref()
+            .is_some_and(|name| name.contains(BRANDING_FORBIDDEN_CHARS))
+        {
+            anyhow::bail!("branding.product_name must not contain < > & \" ' or `");
+        }
+        if let Some(logo) = branding
+            .logo_path
+            .as_deref()
+            .filter(|logo| !is_logo_file(logo))
+        {
+            anyhow::bail!(
+                "branding.logo_path must be an existing PNG, JPEG, SVG or WebP file, got {}",
+                logo.display()
+            );
+        }
+        if let Some((name, value)) = branding
+            .colors
+            .iter()
+            .find(|(_, value)| !fluxion_web::branding::is_hex_color(value))
+        {
+            anyhow::bail!("branding.colors.{name} must be a #rgb or #rrggbb color, got {value}");
+        }
+
+        // Validate decision log
+        if self.decision_log.enabled && self.decision_log.retention_days == 0 {
+            anyhow::bail!("decision_log.retention_days must be at least 1 day");
         }
-        if self.control.force_discharge_hours == 0 {
-            warn!("force_discharge_hours is 0 - no discharging will be scheduled");
+
+        // Validate historian
+        if let Some((field, e)) = self.historian.error() {
+            anyhow::bail!("historian.{field} {e}");
         }
 
-        // Note: charge planning parameters (max_battery_charge_rate_kw, evening_target_soc, evening_peak_start_hour)
-        // use serde defaults and are validated by the core scheduler
+        // Validate WASM plugins
+        if let Some((field, e)) = self.wasm_plugins.error() {
+            anyhow::bail!("wasm_plugins.{field} {e}");
+        }
 
-        // Validate system
-        if self.system.update_interval_secs < 10 {
-            anyhow::bail!("update_interval_secs must be at least 10 seconds");
+        // Validate sub-metering, which stores its readings in the historian
+        if self.submeter.enabled {
+            if let Err(e) = self.submeter.validate() {
+                anyhow::bail!("submeter: {e}");
+            }
+            if !self.historian.enabled {
+                anyhow::bail!("submeter requires historian.enabled");
+            }
         }
-        if self.system.update_interval_secs > 600 {
-            warn!(
-                "update_interval_secs is very high ({}s), consider reducing",
-                self.system.update_interval_secs
-            );
+
+        // Validate watchdog
+        if self.watchdog.enabled && self.watchdog.stall_timeout_seconds < 30 {
+            anyhow::bail!("watchdog.stall_timeout_seconds must be at least 30 seconds");
+        }
+
+        // Validate native solar forecast providers
+        let solar = &self.solar_forecast;
+        if solar.solcast.enabled && solar.forecast_solar.enabled {
+            anyhow::bail!("solcast and forecast_solar cannot both be enabled");
+        }
+        if solar.solcast.enabled {
+            if solar.solcast.api_key.trim().is_empty() {
+                anyhow::bail!("solar_forecast.solcast.api_key is required when Solcast is used");
+            }
+            if solar.solcast.daily_limit == 0 {
+                anyhow::bail!("solar_forecast.solcast.daily_limit must be greater than 0");
+            }
+        }
+        if solar.uses_native_provider() {
+            if !(-90.0..=90.0).contains(&solar.latitude)
+                || !(-180.0..=180.0).contains(&solar.longitude)
+            {
+                anyhow::bail!("solar_forecast.latitude/longitude are out of range");
+            }
+            if solar.strings.is_empty() {
+                anyhow::bail!("solar_forecast.strings must contain at least one PV string");
+            }
+            for string in &solar.strings {
+                if !(0.0..=360.0).contains(&string.azimuth)
+                    || !(0.0..=90.0).contains(&string.tilt)
+                    || string.kwp <= 0.0
+                {
+                    anyhow::bail!(
+                        "solar_forecast.strings: azimuth must be 0-360, tilt 0-90 and kwp greater than 0"
+                    );
+                }
+            }
         }
 
         Ok(())
@@ -1843,6 +3453,7 @@ impl From<AppConfig> for fluxion_core::SystemConfig {
         };
 
         fluxion_core::SystemConfig {
+            schema_version: app_config.schema_version,
             inverters: app_config
                 .inverters
                 .iter()
@@ -1859,6 +3470,7 @@ impl From<AppConfig> for fluxion_core::SystemConfig {
                         },
                         _ => fluxion_core::InverterTopology::Independent,
                     },
+                    battery: inv.battery.clone(),
                 })
                 .collect(),
             pricing_config: fluxion_core::PricingConfig {
@@ -1915,6 +3527,9 @@ impl From<AppConfig> for fluxion_core::SystemConfig {
                 hdo_sensor_entity: app_config.pricing.hdo_sensor_entity,
                 hdo_low_tariff_czk: app_config.pricing.hdo_low_tariff_czk,
                 hdo_high_tariff_czk: app_config.pricing.hdo_high_tariff_czk,
+                hdo_source: app_config.pricing.hdo_source,
+                hdo_preset: app_config.pricing.hdo_preset,
+                hdo_windows: app_config.pricing.hdo_windows.clone(),
             },
             control_config: fluxion_core::ControlConfig {
                 force_charge_hours: app_config.control.force_charge_hours,
@@ -1922,6 +3537,7 @@ impl From<AppConfig> for fluxion_core::SystemConfig {
                 min_battery_soc: app_config.control.min_battery_soc,
                 max_battery_soc: app_config.control.max_battery_soc,
                 maximum_export_power_w: app_config.control.maximum_export_power_w,
+                inverter_max_ac_power_w: app_config.control.inverter_max_ac_power_w,
                 battery_capacity_kwh: app_config.control.battery_capacity_kwh,
                 battery_wear_cost_czk_per_kwh: app_config.control.battery_wear_cost_czk_per_kwh,
                 battery_efficiency: app_config.control.battery_efficiency,
@@ -1950,6 +3566,27 @@ impl From<AppConfig> for fluxion_core::SystemConfig {
                     }
                     _ => fluxion_core::InverterOperationMode::SelfUse, // Default or "SELFUSE"
                 },
+                safe_state_mode: match app_config.control.safe_state_mode.to_uppercase().as_str() {
+                    "SELFUSE" | "SELF_USE" => fluxion_core::InverterOperationMode::SelfUse,
+                    "BACKUPMODE" | "BACKUP" | "BACK_UP_MODE" => {
+                        fluxion_core::InverterOperationMode::BackUpMode
+                    }
+                    _ => fluxion_core::InverterOperationMode::NoChargeNoDischarge, // Default
+                },
+                partial_charge_enabled: app_config.control.partial_charge_enabled,
+                charge_power_modulation_enabled: app_config.control.charge_power_modulation_enabled,
+                max_grid_import_kw: app_config.control.max_grid_import_kw,
+                optimization_horizon_hours: app_config.control.optimization_horizon_hours,
+                battery_degradation: app_config.control.battery_degradation.clone(),
+                export_cap_windows: app_config.control.export_cap_windows.clone(),
+                schedule_guard: app_config.control.schedule_guard,
+                soc_tracking_tolerance_pct: app_config.control.soc_tracking_tolerance_pct,
+                replan_drift_pct: app_config.control.replan_drift_pct,
+                calendar: app_config.control.calendar.clone(),
+                special_days: Vec::new(),
+                weather: app_config.weather.planning.clone(),
+                weather_outlook: fluxion_core::WeatherOutlook::default(),
+                tariff_schedule: fluxion_core::TariffSchedule::default(),
             },
             system_config: fluxion_core::SystemSettingsConfig {
                 update_interval_secs: app_config.system.update_interval_secs,
@@ -2490,6 +4127,42 @@ impl From<AppConfig> for fluxion_core::SystemConfig {
             remote_access: fluxion_core::RemoteAccessConfigCore {
                 enabled: app_config.remote_access.enabled,
             },
+            logging: fluxion_core::LoggingConfigCore {
+                // HA addon schema uses "warning", tracing expects "warn"
+                level: match app_config.system.log_level.to_lowercase().as_str() {
+                    "warning" => "warn".to_string(),
+                    "" => "info".to_string(),
+                    level => level.to_string(),
+                },
+                module_levels: app_config.logging.module_levels,
+                file_enabled: app_config.logging.file_enabled,
+                directory: app_config.logging.directory,
+                max_files: app_config.logging.max_files.max(1),
+            },
+            grid_quality: fluxion_core::GridQualityConfigCore {
+                enabled: app_config.grid_quality.enabled,
+                overvoltage_threshold_v: app_config.grid_quality.overvoltage_threshold_v,
+                sustained_minutes: app_config.grid_quality.sustained_minutes.max(1),
+                frequency_min_hz: app_config.grid_quality.frequency_min_hz,
+                frequency_max_hz: app_config.grid_quality.frequency_max_hz,
+                overvoltage_export_percent: app_config
+                    .grid_quality
+                    .overvoltage_export_percent
+                    .clamp(0.0, 100.0),
+            },
+            peak_demand: fluxion_core::PeakDemandConfigCore {
+                enabled: app_config.peak_demand.enabled,
+                max_import_kw: app_config.peak_demand.max_import_kw,
+                trigger_percent: app_config.peak_demand.trigger_percent,
+            },
+            weather: fluxion_core::WeatherConfigCore {
+                enabled: app_config.weather.enabled,
+                source: app_config.weather.source,
+                entity_id: app_config.weather.entity_id,
+                latitude: app_config.weather.latitude,
+                longitude: app_config.weather.longitude,
+                fetch_interval_seconds: app_config.weather.fetch_interval_seconds,
+            },
         }
     }
 }
@@ -2624,6 +4297,111 @@ mod tests {
         assert!(config.validate().is_ok());
     }
 
+    #[test]
+    fn test_validate_sell_price_source() {
+        let mut config = AppConfig::default();
+        config.pricing.sell_price.entity = Some("sensor.export_price".to_owned());
+        config.pricing.sell_price.margin_czk = 0.3;
+        assert!(config.validate().is_ok());
+
+        config.pricing.sell_price.ote = true;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("pricing.sell_price")
+        );
+
+        config.pricing.sell_price.entity = None;
+        assert!(config.validate().is_ok());
+        assert!(config.validate_detailed().valid);
+    }
+
+    #[test]
+    fn test_validate_peak_demand() {
+        let mut config = AppConfig::default();
+        config.peak_demand.max_import_kw = 0.0;
+        // Not checked while disabled
+        assert!(config.validate().is_ok());
+
+        config.peak_demand.enabled = true;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("peak_demand.max_import_kw")
+        );
+
+        config.peak_demand.max_import_kw = 17.3;
+        config.peak_demand.trigger_percent = 120.0;
+        assert!(!config.validate_detailed().valid);
+
+        config.peak_demand.trigger_percent = 85.0;
+        assert!(config.validate().is_ok());
+        assert!(config.validate_detailed().valid);
+    }
+
+    #[test]
+    fn test_validate_weather() {
+        let mut config = AppConfig::default();
+        config.weather.entity_id = "sensor.outside".to_owned();
+        // Not checked while disabled
+        assert!(config.validate().is_ok());
+
+        config.weather.enabled = true;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("weather.entity_id")
+        );
+
+        config.weather.source = fluxion_core::WeatherSource::OpenMeteo;
+        config.weather.planning.clear_threshold_pct = 80.0;
+        assert!(!config.validate_detailed().valid);
+
+        config.weather.planning.clear_threshold_pct = 20.0;
+        assert!(config.validate().is_ok());
+        assert!(config.validate_detailed().valid);
+    }
+
+    #[test]
+    fn test_validate_hdo_windows() {
+        let mut config = AppConfig::default();
+        config.pricing.hdo_source = fluxion_core::HdoSource::Manual;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("pricing.hdo_windows")
+        );
+
+        #[derive(Deserialize)]
+        struct Windows {
+            hdo_windows: Vec<fluxion_core::TariffWindow>,
+        }
+        let windows: Windows = toml::from_str(
+            r#"
+            hdo_windows = [
+                { start = "22:00", end = "06:00" },
+                { days = ["Sat", "Sun"], start = "13:00", end = "13:00" },
+            ]
+            "#,
+        )
+        .unwrap();
+        config.pricing.hdo_windows = windows.hdo_windows;
+        assert!(config.pricing.hdo_windows[0].days.is_empty());
+        assert!(!config.validate_detailed().valid);
+
+        config.pricing.hdo_windows.truncate(1);
+        assert!(config.validate().is_ok());
+        assert!(config.validate_detailed().valid);
+    }
+
     #[test]
     fn test_validate_update_interval_too_low() {
         let mut config = AppConfig::default();
@@ -2639,6 +4417,248 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_webhook_urls() {
+        let mut config = AppConfig::default();
+        config.webhooks.urls = vec!["https://example.com/hook".to_owned()];
+        assert!(config.validate().is_ok());
+
+        config.webhooks.urls.push("example.com/hook".to_owned());
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("webhooks.urls")
+        );
+    }
+
+    #[test]
+    fn test_validate_grpc_port() {
+        let mut config = AppConfig::default();
+        assert!(config.grpc.bind_address.is_loopback());
+        config.grpc.port = WEB_PORT;
+        assert!(config.validate().is_ok());
+
+        config.grpc.enabled = true;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("grpc.port")
+        );
+
+        config.grpc.port = fluxion_grpc::DEFAULT_GRPC_PORT;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_heat_pump() {
+        let mut config = AppConfig::default();
+        config.heat_pump.enabled = true;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("heat_pump")
+        );
+
+        config.heat_pump.entity_id = "climate.heat_pump".to_owned();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_modbus_devices() {
+        let mut config = AppConfig::default();
+        config.modbus.enabled = true;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("modbus")
+        );
+
+        config
+            .modbus
+            .devices
+            .push(fluxion_modbus::ModbusDeviceConfig {
+                inverter_id: "garage".to_owned(),
+                host: "192.168.1.50".to_owned(),
+                port: 502,
+                unit_id: 1,
+            });
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("modbus.devices")
+        );
+
+        config.modbus.devices[0].inverter_id = config.inverters[0].id.clone();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_solax_local_devices() {
+        let mut config = AppConfig::default();
+        config.solax_local.enabled = true;
+        config
+            .solax_local
+            .devices
+            .push(fluxion_adapters::SolaxLocalDeviceConfig {
+                inverter_id: config.inverters[0].id.clone(),
+                host: "192.168.1.60".to_owned(),
+                serial: String::new(),
+            });
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("solax_local")
+        );
+
+        config.solax_local.devices[0].serial = "SXXXXXXXXX".to_owned();
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_historian() {
+        let mut config = AppConfig::default();
+        config.historian.sample_interval_secs = 0;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("historian.sample_interval_secs")
+        );
+        assert!(!config.validate_detailed().valid);
+
+        config.historian.enabled = false;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_wasm_plugins() {
+        let mut config = AppConfig::default();
+        config.wasm_plugins.scan_interval_secs = 0;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("wasm_plugins.scan_interval_secs")
+        );
+        assert!(!config.validate_detailed().valid);
+
+        config.wasm_plugins.enabled = false;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_submeter() {
+        let mut config = AppConfig::default();
+        config.submeter.enabled = true;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("device")
+        );
+
+        config
+            .submeter
+            .devices
+            .push(fluxion_core::submeter::SubmeterDevice {
+                name: "Heat pump".to_owned(),
+                entity_id: "sensor.heat_pump_power".to_owned(),
+            });
+        assert!(config.validate().is_ok());
+
+        config.historian.enabled = false;
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("historian")
+        );
+        assert!(!config.validate_detailed().valid);
+    }
+
+    #[test]
+    fn test_validate_victron() {
+        let mut config = AppConfig {
+            victron: fluxion_modbus::VictronConfig {
+                enabled: true,
+                inverter_id: "garage".to_owned(),
+                host: "192.168.1.70".to_owned(),
+                ..fluxion_modbus::VictronConfig::default()
+            },
+            ..AppConfig::default()
+        };
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("victron.inverter_id")
+        );
+
+        config.victron.inverter_id = config.inverters[0].id.clone();
+        assert!(config.validate().is_ok());
+
+        config.victron.force_charge_power_w = 50;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_execution_backends() {
+        let inverter: InverterConfig = toml::from_str(
+            r#"
+            id = "main_inverter"
+            inverter_type = "solax"
+            entity_prefix = "solax"
+            topology = "independent"
+            execution = "modbus"
+            "#,
+        )
+        .unwrap();
+        let mut config = AppConfig {
+            inverters: vec![inverter],
+            ..AppConfig::default()
+        };
+        assert_eq!(
+            config.inverters[0].execution,
+            Some(fluxion_core::ExecutionBackendKind::Modbus)
+        );
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("inverters.execution")
+        );
+
+        config.victron = fluxion_modbus::VictronConfig {
+            enabled: true,
+            inverter_id: "main_inverter".to_owned(),
+            host: "192.168.1.70".to_owned(),
+            ..fluxion_modbus::VictronConfig::default()
+        };
+        assert!(config.validate().is_ok());
+
+        config.victron.enabled = false;
+        config.inverters[0].execution = Some(fluxion_core::ExecutionBackendKind::DryRun);
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_update_interval_duration() {
         let config = AppConfig::default();
@@ -2691,6 +4711,8 @@ mod tests {
                     topology: "master".to_string(),
                     slaves: Some(vec!["slave_1".to_string()]),
                     master: None,
+                    battery: None,
+                    execution: None,
                 },
                 InverterConfig {
                     id: "slave_1".to_string(),
@@ -2699,6 +4721,8 @@ mod tests {
                     topology: "slave".to_string(),
                     slaves: None,
                     master: Some("master".to_string()),
+                    battery: None,
+                    execution: None,
                 },
             ],
             ..AppConfig::default()
@@ -2710,7 +4734,7 @@ mod tests {
     /// Test that the HA addon options.json format can be correctly parsed into AppConfig.
     /// This test validates that the field names used in fluxion/config.yaml match our Rust structs.
     /// The HA addon uses slightly different field names (e.g., "vendor" instead of "inverter_type"),
-    /// so we rely on serde aliases for compatibility.
+    /// which the config migration renames before deserialization.
     #[test]
     fn test_ha_addon_options_format() {
         // This JSON matches the structure of /data/options.json as defined in fluxion/config.yaml
@@ -2785,8 +4809,11 @@ mod tests {
             }
         }"#;
 
-        let config: AppConfig = serde_json::from_str(ha_addon_json)
-            .expect("Failed to parse HA addon options format - check field name compatibility!");
+        let config = AppConfig::from_migrated(
+            serde_json::from_str(ha_addon_json).unwrap(),
+            "HA addon options",
+        )
+        .expect("Failed to parse HA addon options format - check field name compatibility!");
 
         // Verify critical fields were correctly parsed
         assert_eq!(config.inverters.len(), 1);
@@ -2794,7 +4821,7 @@ mod tests {
         assert_eq!(
             config.inverters[0].inverter_type,
             fluxion_core::InverterType::Solax,
-            "vendor field should map to inverter_type via serde alias"
+            "vendor field should map to inverter_type via the config migration"
         );
         assert_eq!(config.inverters[0].topology, "independent");
 
@@ -2861,15 +4888,18 @@ mod tests {
             }
         }"#;
 
-        let config: AppConfig = serde_json::from_str(ha_addon_json)
-            .expect("Failed to parse HA addon master/slave config");
+        let config = AppConfig::from_migrated(
+            serde_json::from_str(ha_addon_json).unwrap(),
+            "HA addon options",
+        )
+        .expect("Failed to parse HA addon master/slave config");
 
         // Verify master configuration
         assert_eq!(config.inverters[0].topology, "master");
         assert_eq!(
             config.inverters[0].slaves,
             Some(vec!["slave_inv".to_string()]),
-            "slave_ids should map to slaves via serde alias"
+            "slave_ids should map to slaves via the config migration"
         );
 
         // Verify slave configuration
@@ -2877,7 +4907,7 @@ mod tests {
         assert_eq!(
             config.inverters[1].master,
             Some("master_inv".to_string()),
-            "master_id should map to master via serde alias"
+            "master_id should map to master via the config migration"
         );
 
         // Configuration should be valid
@@ -2886,4 +4916,28 @@ mod tests {
             "HA addon master/slave config should be valid"
         );
     }
+
+    /// Every vendor's config value parses back to its inverter type, in both field spellings
+    #[test]
+    fn test_inverter_vendor_values() {
+        for inverter_type in fluxion_core::InverterType::all() {
+            let value = inverter_type.to_config_value();
+            let from_serde: fluxion_core::InverterType =
+                serde_json::from_value(serde_json::json!(value)).unwrap();
+            assert_eq!(from_serde, *inverter_type);
+            assert_eq!(
+                value.parse::<fluxion_core::InverterType>().unwrap(),
+                *inverter_type
+            );
+        }
+
+        // Sunsynk is the Deye platform under another brand; "vendor" is renamed by the migration
+        let mut raw: serde_json::Value = toml::from_str(
+            "[[inverters]]\nid = \"inv\"\nvendor = \"sunsynk\"\nentity_prefix = \"deye\"\ntopology = \"independent\"",
+        )
+        .unwrap();
+        fluxion_core::migrate_config(&mut raw);
+        let inverter: InverterConfig = serde_json::from_value(raw["inverters"][0].take()).unwrap();
+        assert_eq!(inverter.inverter_type, fluxion_core::InverterType::Deye);
+    }
 }
@@ -75,16 +75,48 @@ impl ConfigPersistence {
             self.config_path.display()
         ))?;
 
-        let persisted: PersistedConfig =
+        let mut raw: serde_json::Value =
             serde_json::from_str(&contents).context("Failed to parse config JSON")?;
+        let report = raw.get_mut("config").map(fluxion_core::migrate_config);
+        let mut persisted: PersistedConfig =
+            serde_json::from_value(raw).context("Failed to parse config JSON")?;
 
         info!(
             "✅ Loaded configuration from {}",
             self.config_path.display()
         );
+
+        if let Some(report) = report {
+            super::log_migration(&report, &self.config_path.display().to_string());
+            if report.migrated() {
+                persisted.metadata = ConfigMetadata {
+                    modified_by: "migration".to_string(),
+                    ..ConfigMetadata::default()
+                };
+                // Keep the pre-migration file; without a backup the original stays in place
+                let backup_path = self.backup_path(report.from_version);
+                match fs::write(&backup_path, &contents) {
+                    Ok(()) => {
+                        info!("💾 Saved pre-migration config to {}", backup_path.display());
+                        if let Err(e) = self.write(&persisted) {
+                            warn!("Failed to save migrated config: {e}");
+                        }
+                    }
+                    Err(e) => warn!(
+                        "Failed to back up config to {}, not saving the migration: {e}",
+                        backup_path.display()
+                    ),
+                }
+            }
+        }
         Ok(persisted)
     }
 
+    /// Copy of the config as it was before migrating from `version`
+    pub fn backup_path(&self, version: u32) -> PathBuf {
+        self.config_path.with_extension(format!("v{version}.json"))
+    }
+
     /// Save configuration to persistent storage
     ///
     /// # Errors
@@ -101,7 +133,16 @@ impl ConfigPersistence {
                 version: "1.0.0".to_string(),
             },
         };
+        self.write(&persisted)?;
+
+        info!(
+            "✅ Saved configuration to {} (modified by: {modified_by})",
+            self.config_path.display()
+        );
+        Ok(())
+    }
 
+    fn write(&self, persisted: &PersistedConfig) -> Result<()> {
         // Ensure parent directory exists
         if let Some(parent) = self.config_path.parent() {
             fs::create_dir_all(parent).context(format!(
@@ -110,18 +151,13 @@ impl ConfigPersistence {
             ))?;
         }
 
-        let json = serde_json::to_string_pretty(&persisted)?;
+        let json = serde_json::to_string_pretty(persisted)?;
         let temp_path = self.config_path.with_extension("bak");
         fs::write(&temp_path, json).context(format!(
             "Failed to write config to {}",
             self.config_path.display()
         ))?;
         fs::rename(&temp_path, &self.config_path)?;
-
-        info!(
-            "✅ Saved configuration to {} (modified by: {modified_by})",
-            self.config_path.display()
-        );
         Ok(())
     }
     /// Check if persistent config exists
@@ -130,6 +166,13 @@ impl ConfigPersistence {
     }
 }
 
+/// True when no web UI config has been persisted yet (first start of the addon)
+///
+/// Must be called before [`load_config_with_fallback`], which creates the file.
+pub fn is_first_run() -> bool {
+    !ConfigPersistence::default_production().exists()
+}
+
 /// Load configuration with fallback logic
 ///
 /// 1. Try /data/config.json (web UI config)
@@ -197,4 +240,40 @@ mod tests {
         assert_eq!(loaded.metadata.modified_by, "test");
         assert_eq!(loaded.config.system.debug_mode, config.system.debug_mode);
     }
+
+    #[test]
+    fn test_load_migrates_and_backs_up() {
+        let (persistence, _temp_dir) = create_test_persistence();
+        persistence.save(&AppConfig::default(), "test").unwrap();
+
+        // Rewrite the saved file the way a pre-versioning release wrote it
+        let mut raw: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&persistence.config_path).unwrap()).unwrap();
+        let config = raw["config"].as_object_mut().unwrap();
+        config.remove("schema_version");
+        let inverter = config["inverters"][0].as_object_mut().unwrap();
+        let inverter_type = inverter.remove("inverter_type").unwrap();
+        inverter.insert("vendor".to_owned(), inverter_type);
+        let original = serde_json::to_string_pretty(&raw).unwrap();
+        fs::write(&persistence.config_path, &original).unwrap();
+
+        let loaded = persistence.load().unwrap();
+        assert_eq!(loaded.metadata.modified_by, "migration");
+        assert_eq!(
+            loaded.config.schema_version,
+            fluxion_core::CONFIG_SCHEMA_VERSION
+        );
+        assert_eq!(
+            fs::read_to_string(persistence.backup_path(1)).unwrap(),
+            original
+        );
+
+        let saved = fs::read_to_string(&persistence.config_path).unwrap();
+        assert!(saved.contains("\"inverter_type\""));
+        assert!(!saved.contains("\"vendor\""));
+        assert_eq!(
+            persistence.load().unwrap().metadata.modified_by,
+            "migration"
+        );
+    }
 }
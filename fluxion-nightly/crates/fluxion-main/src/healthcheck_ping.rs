@@ -0,0 +1,266 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use fluxion_core::WebQuerySender;
+use fluxion_web::status::StatusReport;
+use tracing::{debug, info, warn};
+
+use crate::config::HealthcheckPingConfig;
+
+/// Spawns a background task that pings a healthchecks.io-style URL after each planning cycle.
+pub fn spawn_healthcheck_ping_task(config: HealthcheckPingConfig, query_sender: WebQuerySender) {
+    info!(
+        check_interval_seconds = config.check_interval_seconds,
+        "Starting healthcheck ping"
+    );
+
+    fluxion_core::TaskSupervisor::global().spawn("healthcheck_ping", move || {
+        run_ping_loop(config.clone(), query_sender.clone())
+    });
+}
+
+async fn run_ping_loop(config: HealthcheckPingConfig, query_sender: WebQuerySender) {
+    let client = reqwest::Client::new();
+    let interval = Duration::from_secs(config.check_interval_seconds.max(10));
+    let urls = PingUrls::new(&config.ping_url);
+    let mut state = PingState::default();
+
+    loop {
+        let now = Utc::now();
+        let report = match query_sender.query_dashboard().await {
+            Ok(dashboard) => StatusReport::from_dashboard(&dashboard, now),
+            Err(e) => {
+                warn!(error = %e, "Failed to query dashboard for healthcheck ping");
+                StatusReport::unreachable(now)
+            }
+        };
+        state.report(&client, &urls, &report).await;
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Success and failure URLs of a healthchecks.io-style check
+#[derive(Debug)]
+struct PingUrls {
+    ping: String,
+    fail: String,
+}
+
+impl PingUrls {
+    fn new(ping_url: &str) -> Self {
+        let ping = ping_url.trim_end_matches('/').to_owned();
+        let fail = format!("{ping}/fail");
+        Self { ping, fail }
+    }
+}
+
+/// What has been reported so far; a rejected ping is retried on the next check
+#[derive(Debug, Default)]
+struct PingState {
+    last_pinged_schedule: Option<DateTime<Utc>>,
+    reported_down: bool,
+}
+
+impl PingState {
+    async fn report(&mut self, client: &reqwest::Client, urls: &PingUrls, report: &StatusReport) {
+        if report.is_up() {
+            // One ping per planning cycle; a missing ping is what raises the alert
+            if report.schedule_generated_at != self.last_pinged_schedule
+                && send_ping(client, &urls.ping).await
+            {
+                self.last_pinged_schedule = report.schedule_generated_at;
+                self.reported_down = false;
+            }
+        } else if !self.reported_down && send_ping(client, &urls.fail).await {
+            // Signal the failure right away instead of waiting for the grace period
+            self.reported_down = true;
+        }
+    }
+}
+
+async fn send_ping(client: &reqwest::Client, url: &str) -> bool {
+    match client.get(url).send().await {
+        Ok(resp) if resp.status().is_success() => {
+            debug!("Healthcheck ping sent");
+            true
+        }
+        Ok(resp) => {
+            warn!(status = %resp.status(), "Healthcheck ping rejected");
+            false
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to send healthcheck ping");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_core::web_bridge::{ScheduleData, SystemHealthData, WebQueryResponse};
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicU16, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// HTTP endpoint recording request paths and answering with `status`
+    struct CheckServer {
+        url: String,
+        paths: Arc<Mutex<Vec<String>>>,
+        status: Arc<AtomicU16>,
+    }
+
+    impl CheckServer {
+        async fn start() -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}/ping/abc", listener.local_addr().unwrap());
+            let paths = Arc::new(Mutex::new(Vec::new()));
+            let status = Arc::new(AtomicU16::new(200));
+            let (recorded, answer) = (paths.clone(), status.clone());
+            tokio::spawn(async move {
+                while let Ok((mut stream, _)) = listener.accept().await {
+                    let mut request = vec![0_u8; 4096];
+                    let n = stream.read(&mut request).await.unwrap();
+                    let request = String::from_utf8_lossy(&request[..n]).into_owned();
+                    let path = request.split(' ').nth(1).unwrap_or_default().to_owned();
+                    recorded.lock().push(path);
+                    let response = format!(
+                        "HTTP/1.1 {} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                        answer.load(Ordering::SeqCst)
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                }
+            });
+            Self { url, paths, status }
+        }
+
+        fn take_paths(&self) -> Vec<String> {
+            std::mem::take(&mut *self.paths.lock())
+        }
+    }
+
+    fn report(generated_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> StatusReport {
+        StatusReport::from_dashboard(
+            &WebQueryResponse {
+                timestamp: now,
+                debug_mode: false,
+                inverters: vec![],
+                schedule: generated_at.map(|at| ScheduleData {
+                    current_mode: "SelfUse".to_owned(),
+                    current_reason: String::new(),
+                    current_decision_reason: None,
+                    current_strategy: None,
+                    expected_profit: None,
+                    next_change: None,
+                    blocks_today: 96,
+                    target_soc_max: 100.0,
+                    target_soc_min: 10.0,
+                    total_expected_profit: None,
+                    total_blocks_scheduled: 96,
+                    schedule_hours: 24.0,
+                    schedule_generated_at: at,
+                    schedule_ends_at: None,
+                }),
+                prices: None,
+                health: SystemHealthData {
+                    inverter_source: true,
+                    price_source: true,
+                    last_update: now,
+                    errors: vec![],
+                },
+                timezone: None,
+                battery_soc_history: None,
+                battery_soc_prediction: None,
+                pv_generation_history: None,
+                battery_power_history: None,
+                grid_power_history: None,
+                consumption_stats: None,
+                hdo_schedule: None,
+                pricing_fees: None,
+                solar_forecast: None,
+            },
+            now,
+        )
+    }
+
+    #[test]
+    fn test_fail_url_is_below_the_ping_url() {
+        for ping_url in ["https://hc-ping.com/abc", "https://hc-ping.com/abc/"] {
+            let urls = PingUrls::new(ping_url);
+            assert_eq!(urls.ping, "https://hc-ping.com/abc");
+            assert_eq!(urls.fail, "https://hc-ping.com/abc/fail");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_one_ping_per_planning_cycle() {
+        let server = CheckServer::start().await;
+        let (client, urls) = (reqwest::Client::new(), PingUrls::new(&server.url));
+        let mut state = PingState::default();
+        let now = Utc::now();
+
+        state.report(&client, &urls, &report(Some(now), now)).await;
+        state.report(&client, &urls, &report(Some(now), now)).await;
+        assert_eq!(server.take_paths(), ["/ping/abc"]);
+
+        let next_cycle = now + chrono::Duration::minutes(15);
+        state
+            .report(&client, &urls, &report(Some(next_cycle), next_cycle))
+            .await;
+        assert_eq!(server.take_paths(), ["/ping/abc"]);
+    }
+
+    #[tokio::test]
+    async fn test_failure_is_signalled_once_and_rejections_are_retried() {
+        let server = CheckServer::start().await;
+        let (client, urls) = (reqwest::Client::new(), PingUrls::new(&server.url));
+        let mut state = PingState::default();
+        let now = Utc::now();
+        let down = StatusReport::unreachable(now);
+
+        // Rejected: the failure is sent again on the next check
+        server.status.store(503, Ordering::SeqCst);
+        state.report(&client, &urls, &down).await;
+        assert!(!state.reported_down);
+
+        server.status.store(200, Ordering::SeqCst);
+        state.report(&client, &urls, &down).await;
+        state.report(&client, &urls, &down).await;
+        assert_eq!(server.take_paths(), ["/ping/abc/fail", "/ping/abc/fail"]);
+
+        // Back up: pinged again and ready to report the next failure
+        state.report(&client, &urls, &report(Some(now), now)).await;
+        assert!(!state.reported_down);
+        assert_eq!(server.take_paths(), ["/ping/abc"]);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_check_is_retried() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let urls = PingUrls::new(&format!("http://{}/ping", listener.local_addr().unwrap()));
+        drop(listener);
+        let mut state = PingState::default();
+        let now = Utc::now();
+
+        state
+            .report(&reqwest::Client::new(), &urls, &report(Some(now), now))
+            .await;
+
+        assert_eq!(state.last_pinged_schedule, None);
+    }
+}
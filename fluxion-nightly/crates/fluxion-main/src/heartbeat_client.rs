@@ -26,7 +26,11 @@ use crate::config::ServerHeartbeatConfig;
 use crate::version::VERSION;
 
 /// Spawns a background task that periodically sends heartbeats to the central server.
-pub fn spawn_heartbeat_task(config: ServerHeartbeatConfig, query_sender: WebQuerySender) {
+pub fn spawn_heartbeat_task(
+    config: ServerHeartbeatConfig,
+    query_sender: WebQuerySender,
+    license_key: Option<String>,
+) {
     info!(
         server_url = %config.server_url,
         instance_id = %config.instance_id,
@@ -34,78 +38,90 @@ pub fn spawn_heartbeat_task(config: ServerHeartbeatConfig, query_sender: WebQuer
         "Starting heartbeat client"
     );
 
-    tokio::spawn(async move {
-        let client = reqwest::Client::new();
-        let interval = Duration::from_secs(config.interval_seconds);
-        let url = format!("{}/api/heartbeat", config.server_url.trim_end_matches('/'));
-        let mut first_heartbeat = true;
+    fluxion_core::TaskSupervisor::global().spawn("heartbeat", move || {
+        run_heartbeat_loop(config.clone(), query_sender.clone(), license_key.clone())
+    });
+}
 
-        loop {
-            // Query current system state for heartbeat payload
-            let (strategy_name, battery_soc, telemetry, sync_data) =
-                match query_sender.query_dashboard().await {
-                    Ok(dashboard) => {
-                        let strategy = dashboard
-                            .schedule
-                            .as_ref()
-                            .and_then(|s| s.current_strategy.clone());
-                        let soc = dashboard.inverters.first().map(|i| i.battery_soc);
-                        let telemetry = build_telemetry_snapshot(&dashboard);
-                        let sync = if first_heartbeat {
-                            first_heartbeat = false;
-                            Some(build_sync_data(&dashboard))
-                        } else {
-                            None
-                        };
-                        (strategy, soc, Some(telemetry), sync)
-                    }
-                    Err(e) => {
-                        warn!(error = %e, "Failed to query dashboard for heartbeat");
-                        (None, None, None, None)
-                    }
-                };
+async fn run_heartbeat_loop(
+    config: ServerHeartbeatConfig,
+    query_sender: WebQuerySender,
+    license_key: Option<String>,
+) {
+    let client = reqwest::Client::new();
+    let interval = Duration::from_secs(config.interval_seconds);
+    let url = format!("{}/api/heartbeat", config.server_url.trim_end_matches('/'));
+    let mut first_heartbeat = true;
 
-            let request = HeartbeatRequest {
-                instance_id: config.instance_id.clone(),
-                shared_secret: config.shared_secret.clone(),
-                timestamp: Utc::now(),
-                fluxion_version: VERSION.to_owned(),
-                status: HeartbeatStatus {
-                    friendly_name: config.friendly_name.clone(),
-                    online: true,
-                    strategy_name,
-                    battery_soc,
-                },
-                telemetry,
-                sync_data,
+    loop {
+        // Query current system state for heartbeat payload
+        let (strategy_name, battery_soc, telemetry, sync_data) =
+            match query_sender.query_dashboard().await {
+                Ok(dashboard) => {
+                    let strategy = dashboard
+                        .schedule
+                        .as_ref()
+                        .and_then(|s| s.current_strategy.clone());
+                    let soc = dashboard.inverters.first().map(|i| i.battery_soc);
+                    let telemetry = build_telemetry_snapshot(&dashboard);
+                    let sync = if first_heartbeat {
+                        first_heartbeat = false;
+                        Some(build_sync_data(&dashboard))
+                    } else {
+                        None
+                    };
+                    (strategy, soc, Some(telemetry), sync)
+                }
+                Err(e) => {
+                    warn!(error = %e, "Failed to query dashboard for heartbeat");
+                    (None, None, None, None)
+                }
             };
 
-            match client.post(&url).json(&request).send().await {
-                Ok(resp) => {
-                    if resp.status().is_success() {
-                        match resp.json::<HeartbeatResponse>().await {
-                            Ok(hr) if hr.ok => {
-                                info!("Heartbeat sent successfully");
-                            }
-                            Ok(hr) => {
-                                warn!(message = ?hr.message, "Heartbeat rejected by server");
-                            }
-                            Err(e) => {
-                                warn!(error = %e, "Failed to parse heartbeat response");
+        let request = HeartbeatRequest {
+            instance_id: config.instance_id.clone(),
+            shared_secret: config.shared_secret.clone(),
+            timestamp: Utc::now(),
+            fluxion_version: VERSION.to_owned(),
+            status: HeartbeatStatus {
+                friendly_name: config.friendly_name.clone(),
+                online: true,
+                strategy_name,
+                battery_soc,
+            },
+            telemetry,
+            sync_data,
+            license_key: license_key.clone(),
+        };
+
+        match client.post(&url).json(&request).send().await {
+            Ok(resp) => {
+                if resp.status().is_success() {
+                    match resp.json::<HeartbeatResponse>().await {
+                        Ok(hr) if hr.ok => {
+                            info!("Heartbeat sent successfully");
+                            if let Some(message) = hr.message {
+                                warn!(%message, "Heartbeat accepted with a notice");
                             }
                         }
-                    } else {
-                        warn!(status = %resp.status(), "Heartbeat request failed");
+                        Ok(hr) => {
+                            warn!(message = ?hr.message, "Heartbeat rejected by server");
+                        }
+                        Err(e) => {
+                            warn!(error = %e, "Failed to parse heartbeat response");
+                        }
                     }
-                }
-                Err(e) => {
-                    error!(error = %e, "Failed to send heartbeat");
+                } else {
+                    warn!(status = %resp.status(), "Heartbeat request failed");
                 }
             }
-
-            tokio::time::sleep(interval).await;
+            Err(e) => {
+                error!(error = %e, "Failed to send heartbeat");
+            }
         }
-    });
+
+        tokio::time::sleep(interval).await;
+    }
 }
 
 fn build_telemetry_snapshot(dashboard: &WebQueryResponse) -> TelemetrySnapshot {
@@ -202,6 +218,10 @@ fn build_telemetry_snapshot(dashboard: &WebQueryResponse) -> TelemetrySnapshot {
                 expected_profit: b.expected_profit,
                 reason: b.reason.clone(),
                 is_historical: b.is_historical,
+                solar_forecast_kwh: b.forecast.map(|f| f.solar_kwh),
+                consumption_forecast_kwh: b.forecast.map(|f| f.consumption_kwh),
+                solar_actual_kwh: b.actual.map(|a| a.solar_kwh),
+                consumption_actual_kwh: b.actual.map(|a| a.consumption_kwh),
             })
             .collect::<Vec<_>>();
 
@@ -0,0 +1,226 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use fluxion_shared::license::{
+    LicenseFeature, LicenseValidationRequest, LicenseValidationResponse,
+};
+use fluxion_web::{LicenseInfo, LicenseState, LicenseStatus};
+use reqwest::StatusCode;
+use tracing::{info, warn};
+
+use crate::config::ServerHeartbeatConfig;
+
+/// Time between checks once the server has answered
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// Time between retries while the server cannot be reached
+const RETRY_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// How long an answer stays trusted while the server cannot be reached
+const OFFLINE_GRACE: chrono::Duration = chrono::Duration::days(7);
+
+/// Spawns a background task that validates the license key against the heartbeat server.
+pub fn spawn_license_task(key: String, server: ServerHeartbeatConfig, state: LicenseState) {
+    if server.server_url.is_empty() {
+        warn!(
+            "license.key is set but server_heartbeat.server_url is not; license stays unverified"
+        );
+        state.set(LicenseInfo {
+            status: LicenseStatus::Unverified,
+            message: Some("No license server configured (server_heartbeat.server_url)".to_owned()),
+            ..LicenseInfo::unlicensed()
+        });
+        return;
+    }
+
+    info!(server_url = %server.server_url, "Starting license check");
+    fluxion_core::TaskSupervisor::global().spawn("license", move || {
+        run_license_loop(key.clone(), server.clone(), state.clone())
+    });
+}
+
+async fn run_license_loop(key: String, server: ServerHeartbeatConfig, state: LicenseState) {
+    let client = reqwest::Client::new();
+    let url = format!(
+        "{}/api/license/validate",
+        server.server_url.trim_end_matches('/')
+    );
+    let request = LicenseValidationRequest {
+        instance_id: server.instance_id,
+        shared_secret: server.shared_secret,
+        license_key: key,
+    };
+
+    loop {
+        let result = validate(&client, &url, &request).await;
+        let answered = result.is_ok();
+        let license = next_license_info(&state.info(), result, Utc::now());
+        match license.status {
+            LicenseStatus::Valid => info!(licensee = ?license.licensee, "License valid"),
+            _ => warn!(status = ?license.status, message = ?license.message, "License not valid"),
+        }
+        state.set(license);
+
+        tokio::time::sleep(if answered {
+            CHECK_INTERVAL
+        } else {
+            RETRY_INTERVAL
+        })
+        .await;
+    }
+}
+
+async fn validate(
+    client: &reqwest::Client,
+    url: &str,
+    request: &LicenseValidationRequest,
+) -> Result<LicenseValidationResponse, String> {
+    let resp = client
+        .post(url)
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    // A wrong shared secret is an answer too; the body says why
+    if !resp.status().is_success() && resp.status() != StatusCode::UNAUTHORIZED {
+        return Err(format!("server returned {}", resp.status()));
+    }
+    resp.json().await.map_err(|e| e.to_string())
+}
+
+/// License state after a check that ended with `result`
+fn next_license_info(
+    previous: &LicenseInfo,
+    result: Result<LicenseValidationResponse, String>,
+    now: DateTime<Utc>,
+) -> LicenseInfo {
+    match result {
+        Ok(response) => LicenseInfo {
+            status: if response.valid {
+                LicenseStatus::Valid
+            } else {
+                LicenseStatus::Invalid
+            },
+            fleet: response.valid && response.features.contains(&LicenseFeature::Fleet),
+            licensee: response.licensee,
+            expires_at: response.expires_at,
+            message: response.message,
+            checked_at: Some(now),
+        },
+        // A server outage must not drop a license right away
+        Err(error)
+            if previous
+                .checked_at
+                .is_some_and(|checked| now - checked < OFFLINE_GRACE) =>
+        {
+            if previous.expires_at.is_some_and(|expires| expires <= now) {
+                return LicenseInfo {
+                    status: LicenseStatus::Invalid,
+                    fleet: false,
+                    message: Some("License has expired".to_owned()),
+                    ..previous.clone()
+                };
+            }
+            LicenseInfo {
+                message: Some(format!("License server unreachable: {error}")),
+                ..previous.clone()
+            }
+        }
+        Err(error) => LicenseInfo {
+            status: LicenseStatus::Unverified,
+            fleet: false,
+            message: Some(format!("License server unreachable: {error}")),
+            ..previous.clone()
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn valid_response(now: DateTime<Utc>) -> LicenseValidationResponse {
+        LicenseValidationResponse {
+            valid: true,
+            server_time: now,
+            licensee: Some("Example Solar".to_owned()),
+            expires_at: Some(now + chrono::Duration::days(30)),
+            features: vec![LicenseFeature::Fleet],
+            message: None,
+        }
+    }
+
+    #[test]
+    fn server_answer_replaces_license() {
+        let now = Utc::now();
+        let license = next_license_info(&LicenseInfo::checking(), Ok(valid_response(now)), now);
+        assert_eq!(license.status, LicenseStatus::Valid);
+        assert!(license.fleet);
+        assert_eq!(license.licensee.as_deref(), Some("Example Solar"));
+
+        let rejected = LicenseValidationResponse {
+            valid: false,
+            features: Vec::new(),
+            message: Some("Unknown license key".to_owned()),
+            ..valid_response(now)
+        };
+        let license = next_license_info(&license, Ok(rejected), now);
+        assert_eq!(license.status, LicenseStatus::Invalid);
+        assert!(!license.fleet);
+    }
+
+    #[test]
+    fn outage_keeps_license_during_grace_period() {
+        let now = Utc::now();
+        let valid = next_license_info(&LicenseInfo::checking(), Ok(valid_response(now)), now);
+
+        let license = next_license_info(
+            &valid,
+            Err("timeout".to_owned()),
+            now + chrono::Duration::days(2),
+        );
+        assert_eq!(license.status, LicenseStatus::Valid);
+        assert!(license.fleet);
+        assert!(license.message.is_some());
+
+        let license = next_license_info(
+            &valid,
+            Err("timeout".to_owned()),
+            now + chrono::Duration::days(8),
+        );
+        assert_eq!(license.status, LicenseStatus::Unverified);
+        assert!(!license.fleet);
+
+        // Never answered: nothing to keep
+        let license = next_license_info(&LicenseInfo::checking(), Err("timeout".to_owned()), now);
+        assert_eq!(license.status, LicenseStatus::Unverified);
+    }
+
+    #[test]
+    fn outage_does_not_extend_expired_license() {
+        let now = Utc::now();
+        let mut response = valid_response(now);
+        response.expires_at = Some(now + chrono::Duration::days(1));
+        let valid = next_license_info(&LicenseInfo::checking(), Ok(response), now);
+
+        let license = next_license_info(
+            &valid,
+            Err("timeout".to_owned()),
+            now + chrono::Duration::days(2),
+        );
+        assert_eq!(license.status, LicenseStatus::Invalid);
+        assert!(!license.fleet);
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Tracing subscriber setup.
+//!
+//! Logs always go to stdout. The level filter and the optional rotating file
+//! output sit behind reload layers so the `logging` config section can change
+//! them at runtime.
+
+use fluxion_core::LoggingConfigCore;
+use parking_lot::Mutex;
+use tracing::{Subscriber, info, warn};
+use tracing_appender::{
+    non_blocking::WorkerGuard,
+    rolling::{RollingFileAppender, Rotation},
+};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry, fmt,
+    layer::{Layered, SubscriberExt},
+    reload,
+    util::SubscriberInitExt,
+};
+
+/// File name prefix of the rotated log files (`fluxion.YYYY-MM-DD.log`)
+pub const LOG_FILE_PREFIX: &str = "fluxion";
+
+type FilteredRegistry = Layered<reload::Layer<EnvFilter, Registry>, Registry>;
+type FileLayer = Box<dyn Layer<FilteredRegistry> + Send + Sync>;
+
+/// Currently active file output
+struct FileOutput {
+    directory: String,
+    max_files: usize,
+    // Dropping the guard flushes and stops the background writer
+    _guard: WorkerGuard,
+}
+
+/// Handle for changing the installed subscriber at runtime
+pub struct LoggingHandle {
+    filter: reload::Handle<EnvFilter, Registry>,
+    file: reload::Handle<Option<FileLayer>, FilteredRegistry>,
+    file_output: Mutex<Option<FileOutput>>,
+}
+
+/// Install the global subscriber.
+///
+/// Starts with `RUST_LOG` (or `info`) until the configuration is loaded and
+/// applied with [`LoggingHandle::apply_startup`].
+pub fn init() -> LoggingHandle {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    let (subscriber, handle) = subscriber(filter);
+    subscriber.init();
+    handle
+}
+
+/// Stdout subscriber starting with `filter`, and the handle to reconfigure it
+fn subscriber(filter: EnvFilter) -> (impl Subscriber + Send + Sync, LoggingHandle) {
+    let (filter_layer, filter) = reload::Layer::new(filter);
+    let (file_layer, file) = reload::Layer::new(None::<FileLayer>);
+
+    let subscriber = tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(file_layer)
+        .with(fmt::layer());
+
+    let handle = LoggingHandle {
+        filter,
+        file,
+        file_output: Mutex::new(None),
+    };
+    (subscriber, handle)
+}
+
+impl LoggingHandle {
+    /// Apply the configuration loaded at startup.
+    ///
+    /// An explicit `RUST_LOG` takes precedence over the configured levels here;
+    /// later runtime changes from the config API always apply.
+    pub fn apply_startup(&self, config: &LoggingConfigCore) {
+        if std::env::var_os("RUST_LOG").is_some() {
+            info!("📝 RUST_LOG is set, ignoring configured log levels until changed via config");
+        } else {
+            self.apply_filter(config);
+        }
+        self.apply_file_output(config);
+    }
+
+    /// Apply a changed logging configuration
+    pub fn apply(&self, config: &LoggingConfigCore) {
+        self.apply_filter(config);
+        self.apply_file_output(config);
+    }
+
+    fn apply_filter(&self, config: &LoggingConfigCore) {
+        let directives = config.filter_directives();
+        match EnvFilter::try_new(&directives) {
+            Ok(filter) => {
+                if let Err(e) = self.filter.reload(filter) {
+                    warn!("Failed to reload log filter: {e}");
+                }
+            }
+            Err(e) => warn!("Invalid log filter '{directives}', keeping previous: {e}"),
+        }
+    }
+
+    fn apply_file_output(&self, config: &LoggingConfigCore) {
+        let mut current = self.file_output.lock();
+
+        let unchanged = match current.as_ref() {
+            Some(output) => {
+                config.file_enabled
+                    && output.directory == config.directory
+                    && output.max_files == config.max_files
+            }
+            None => !config.file_enabled,
+        };
+        if unchanged {
+            return;
+        }
+
+        if !config.file_enabled {
+            if let Err(e) = self.file.reload(None) {
+                warn!("Failed to disable file logging: {e}");
+                return;
+            }
+            *current = None;
+            info!("📝 File logging disabled");
+            return;
+        }
+
+        let appender = match RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_FILE_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(config.max_files.max(1))
+            .build(&config.directory)
+        {
+            Ok(appender) => appender,
+            Err(e) => {
+                warn!(
+                    "Failed to create log directory {}, file logging stays unchanged: {e}",
+                    config.directory
+                );
+                return;
+            }
+        };
+
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        let layer: FileLayer = Box::new(fmt::layer().with_ansi(false).with_writer(writer));
+        if let Err(e) = self.file.reload(Some(layer)) {
+            warn!("Failed to enable file logging: {e}");
+            return;
+        }
+
+        *current = Some(FileOutput {
+            directory: config.directory.clone(),
+            max_files: config.max_files,
+            _guard: guard,
+        });
+        info!(
+            "📝 Logging to {} (daily rotation, keeping {} files)",
+            config.directory, config.max_files
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(level: &str, module_levels: &[&str]) -> LoggingConfigCore {
+        LoggingConfigCore {
+            level: level.to_owned(),
+            module_levels: module_levels.iter().map(|&d| d.to_owned()).collect(),
+            ..LoggingConfigCore::default()
+        }
+    }
+
+    fn active_filter(handle: &LoggingHandle) -> String {
+        handle.filter.with_current(ToString::to_string).unwrap()
+    }
+
+    #[test]
+    fn test_configured_levels_become_the_filter() {
+        let (_subscriber, handle) = subscriber(EnvFilter::new("info"));
+        let cases = [
+            // (level, module levels, resulting filter; module directives are listed first)
+            ("debug", &[][..], "debug"),
+            (
+                "warn",
+                &[" fluxion_core::scheduling=debug ", "", "fluxion_web=trace"][..],
+                "fluxion_core::scheduling=debug,fluxion_web=trace,warn",
+            ),
+            ("", &["fluxion_main=info"][..], "fluxion_main=info"),
+            ("off", &[][..], "off"),
+        ];
+        for (level, module_levels, expected) in cases {
+            handle.apply(&config(level, module_levels));
+            assert_eq!(
+                active_filter(&handle),
+                expected,
+                "{level} {module_levels:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_filter_keeps_the_previous_one() {
+        let (_subscriber, handle) = subscriber(EnvFilter::new("info"));
+        handle.apply(&config("warn", &[]));
+
+        handle.apply(&config("warn", &["fluxion_core=loud"]));
+
+        assert_eq!(active_filter(&handle), "warn");
+    }
+
+    #[test]
+    fn test_file_output_is_written_and_can_be_disabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let (subscriber, handle) = subscriber(EnvFilter::new("info"));
+        let file_config = LoggingConfigCore {
+            file_enabled: true,
+            directory: dir.path().display().to_string(),
+            ..LoggingConfigCore::default()
+        };
+
+        tracing::subscriber::with_default(subscriber, || {
+            handle.apply(&file_config);
+            info!("written to the log file");
+            // Disabling drops the writer, which flushes it
+            handle.apply(&LoggingConfigCore::default());
+            info!("stdout only");
+        });
+
+        let files: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 1);
+        let name = files[0].file_name().unwrap().to_string_lossy().into_owned();
+        assert!(
+            name.starts_with("fluxion.") && name.ends_with(".log"),
+            "{name}"
+        );
+        let contents = std::fs::read_to_string(&files[0]).unwrap();
+        assert!(contents.contains("written to the log file"));
+        assert!(!contents.contains("stdout only"));
+        assert!(handle.file_output.lock().is_none());
+    }
+}
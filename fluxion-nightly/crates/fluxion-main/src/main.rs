@@ -11,23 +11,28 @@
 // For commercial licensing, please contact: info@solare.cz
 
 mod config;
+mod healthcheck_ping;
 mod heartbeat_client;
+mod license_client;
+mod logging;
+mod mqtt_publisher;
 mod version;
+mod watchdog;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use bevy_app::{ScheduleRunnerPlugin, TaskPoolPlugin, prelude::*};
-use std::{sync::Arc, time::Duration};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use tracing::{info, warn};
-use tracing_subscriber::FmtSubscriber;
 
 use fluxion_adapters::{
-    CzSpotPriceAdapter, HaClientResource, HaPlugin, HomeAssistantClient,
-    HomeAssistantInverterAdapter, PriceAdapterTimezoneHandle,
+    CzSpotPriceAdapter, HaAlertNotifier, HaClientResource, HaDhwController, HaPlugin,
+    HaPowerSensorReader, HomeAssistantClient, HomeAssistantInverterAdapter,
+    PriceAdapterTimezoneHandle,
 };
 use fluxion_core::{
     ConfigUpdateSender, FluxionCorePlugin, PluginManagerResource, SystemConfig, TimezoneConfig,
     UserControlPersistence, UserControlResource, UserControlUpdateSender, WebQuerySender,
-    plugin_adapters::create_plugin_manager,
+    plugin_adapters::create_plugin_manager, setup_defaults::propose_control_defaults,
 };
 use fluxion_i18n::I18n;
 use fluxion_web::{PluginApiState, RemoteAccessApiState, UserControlApiState};
@@ -75,19 +80,14 @@ fn main() -> Result<()> {
 }
 
 fn initialize_and_run() -> Result<()> {
-    // Initialize tracing with env filter support
-    // Respects RUST_LOG environment variable
-    let subscriber = FmtSubscriber::builder()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
-        .finish();
-
-    tracing::subscriber::set_global_default(subscriber).expect("setting default subscriber failed");
+    // Initialize tracing (stdout, reloadable filter and optional file output)
+    // Respects RUST_LOG environment variable until the config is loaded
+    let logging = logging::init();
 
     // Load configuration with web UI fallback
+    let first_run = config::is_first_run();
     let config = config::load_config_with_fallback()?;
+    logging.apply_startup(&SystemConfig::from(config.clone()).logging);
 
     info!("🚀 Starting FluxION - PV Plant Automation (MVP)");
     info!("📋 Configuration Summary:");
@@ -157,10 +157,129 @@ fn initialize_and_run() -> Result<()> {
     );
 
     // Create data sources
+    // Modbus writes check this flag, which follows debug mode toggled at runtime
+    let shared_debug_mode = fluxion_core::SharedDebugMode::new(config.system.debug_mode);
+    let ha_inverter_source: Arc<dyn fluxion_core::InverterDataSource> = Arc::new(
+        HomeAssistantInverterAdapter::new(ha_client.clone(), mapper.clone()),
+    );
+    // Victron has no Home Assistant mapping to fall back to: the GX is the only source
+    let victron_source: Option<Arc<dyn fluxion_core::InverterDataSource>> =
+        config.victron.enabled.then(|| {
+            Arc::new(fluxion_modbus::VictronGxSource::new(
+                &config.victron,
+                shared_debug_mode.clone(),
+            )) as Arc<dyn fluxion_core::InverterDataSource>
+        });
+    // Direct connection first, Home Assistant when the device is unreachable
+    let direct_source: Option<Arc<dyn fluxion_core::InverterDataSource>> = if config.modbus.enabled
+    {
+        Some(Arc::new(fluxion_modbus::ModbusInverterSource::new(
+            &config.modbus,
+            shared_debug_mode.clone(),
+        )))
+    } else if config.solax_local.enabled {
+        Some(Arc::new(fluxion_adapters::SolaxLocalApiAdapter::new(
+            &config.solax_local,
+            shared_debug_mode.clone(),
+        )?))
+    } else {
+        None
+    };
+    let modbus_source = victron_source.clone().or_else(|| {
+        config
+            .modbus
+            .enabled
+            .then(|| direct_source.clone())
+            .flatten()
+    });
     let inverter_source: Arc<dyn fluxion_core::InverterDataSource> =
-        Arc::new(HomeAssistantInverterAdapter::new(ha_client.clone(), mapper));
+        match (victron_source, direct_source) {
+            (Some(victron), _) => victron,
+            (None, Some(direct)) => {
+                Arc::new(fluxion_core::failover_source::FailoverInverterSource::new(
+                    direct,
+                    ha_inverter_source,
+                ))
+            }
+            (None, None) => ha_inverter_source,
+        };
+    // Inverters with an explicit execution backend get their mode changes routed to it
+    let mut execution_backends: HashMap<String, Arc<dyn fluxion_core::ExecutionBackend>> =
+        HashMap::new();
+    for inverter in &config.inverters {
+        let Some(kind) = inverter.execution else {
+            continue;
+        };
+        let backend: Arc<dyn fluxion_core::ExecutionBackend> = match kind {
+            fluxion_core::ExecutionBackendKind::HomeAssistant => {
+                Arc::new(fluxion_adapters::HomeAssistantExecutionBackend::new(
+                    ha_client.clone(),
+                    mapper.clone(),
+                ))
+            }
+            fluxion_core::ExecutionBackendKind::Modbus => {
+                let source = modbus_source
+                    .clone()
+                    .context("Modbus execution requires [modbus] or [victron]")?;
+                Arc::new(fluxion_modbus::ModbusExecutionBackend::new(source))
+            }
+            fluxion_core::ExecutionBackendKind::DryRun
+                if config.victron.enabled && config.victron.inverter_id == inverter.id =>
+            {
+                Arc::new(fluxion_core::DryRunBackend::new(Arc::new(
+                    config.victron.planner(),
+                )))
+            }
+            fluxion_core::ExecutionBackendKind::DryRun => {
+                Arc::new(fluxion_core::DryRunBackend::new(mapper.clone()))
+            }
+        };
+        info!(
+            "🎛️ Mode changes for {} go through {}",
+            inverter.id,
+            backend.name()
+        );
+        execution_backends.insert(inverter.id.clone(), backend);
+    }
+    let inverter_source: Arc<dyn fluxion_core::InverterDataSource> =
+        if execution_backends.is_empty() {
+            inverter_source
+        } else {
+            Arc::new(fluxion_core::ExecutionRouter::new(
+                inverter_source,
+                execution_backends,
+            ))
+        };
     info!("🔌 Inverter data source: {}", inverter_source.name());
 
+    // The same entity mapping checks as the recorded-fixture tests, against live states
+    let mapping_validator = fluxion_adapters::HaMappingValidator::new(
+        ha_client.clone(),
+        mapper.clone(),
+        config
+            .inverters
+            .iter()
+            .map(|inv| inv.entity_prefix.replace('.', "_"))
+            .collect(),
+    );
+    let mapping_check_state = fluxion_web::MappingCheckState::new(Arc::new(mapping_validator));
+
+    // Until the wizard is completed, propose control defaults from the detected hardware
+    let setup_wizard_state =
+        fluxion_web::SetupWizardState::new(std::path::Path::new("./data"), first_run);
+    if setup_wizard_state.is_pending()
+        && let Some(inverter) = config.inverters.first()
+    {
+        let detector = HomeAssistantInverterAdapter::new(ha_client.clone(), mapper);
+        let inverter_id = inverter.id.clone();
+        let control_config = SystemConfig::from(config.clone()).control_config;
+        let wizard = setup_wizard_state.clone();
+        runtime_handle.spawn(async move {
+            let detected = detector.detect_hardware(&inverter_id).await;
+            wizard.set_proposal(propose_control_defaults(&detected, &control_config));
+        });
+    }
+
     // Create spot price adapter (always create, but may not be used)
     let spot_adapter = if let Some(tomorrow_entity) = &config.pricing.tomorrow_price_entity {
         info!("💰 Using separate tomorrow sensor: {}", tomorrow_entity);
@@ -176,36 +295,204 @@ fn initialize_and_run() -> Result<()> {
     // Get the timezone handle from the spot adapter for later synchronization
     // This allows the timezone_sync_system to update the adapter's timezone
     // when the Home Assistant timezone changes
-    let price_adapter_tz_handle = PriceAdapterTimezoneHandle::new(spot_adapter.timezone_handle());
+    let spot_timezone = spot_adapter.timezone_handle();
+    let price_adapter_tz_handle = PriceAdapterTimezoneHandle::new(spot_timezone.clone());
     info!("🌍 Price adapter timezone handle created for HA timezone sync");
 
-    // Wrap spot adapter in configurable source that respects use_spot_prices_to_buy/sell flags
-    let price_source: Arc<dyn fluxion_core::PriceDataSource> =
-        Arc::new(fluxion_adapters::ConfigurablePriceDataSource::new(
+    // Market prices in another currency than the billing one are converted
+    // at the CNB daily rate (or the configured fixed EUR/CZK rate)
+    let mut exchange_rates = fluxion_core::pricing::exchange_rate::ExchangeRateService::new();
+    if let Some(rate) = config.pricing.ote_fallback.eur_czk_rate {
+        exchange_rates = exchange_rates.with_fixed_rate("EUR", rate);
+    }
+    let exchange_rates = Arc::new(exchange_rates);
+    let billing_currency = config.pricing.billing_currency.clone();
+    let in_billing_currency = |source: Arc<dyn fluxion_core::PriceDataSource>,
+                               currency: &str|
+     -> Arc<dyn fluxion_core::PriceDataSource> {
+        match &billing_currency {
+            Some(billing) if !billing.eq_ignore_ascii_case(currency) => {
+                info!(
+                    "💱 Converting {} prices from {currency} to {billing}",
+                    source.name()
+                );
+                Arc::new(
+                    fluxion_core::pricing::exchange_rate::ConvertedPriceSource::new(
+                        source,
+                        exchange_rates.clone(),
+                        currency,
+                        billing,
+                    ),
+                )
+            }
+            _ => source,
+        }
+    };
+
+    // Tibber and Nord Pool users read prices from there; otherwise optionally
+    // fall back to prices fetched directly from OTE
+    let tibber = &config.pricing.tibber;
+    let spot_source: Arc<dyn fluxion_core::PriceDataSource> = if tibber.enabled {
+        info!("💰 Using Tibber contract prices");
+        Arc::new(fluxion_adapters::TibberPriceAdapter::new(
+            tibber.api_token.clone(),
+            tibber.home_id.clone(),
+        ))
+    } else if config.pricing.nord_pool.enabled {
+        info!(
+            "💰 Using Nord Pool day-ahead prices for area {} ({})",
+            config.pricing.nord_pool.area, config.pricing.nord_pool.currency
+        );
+        in_billing_currency(
+            Arc::new(fluxion_adapters::NordPoolPriceAdapter::new(
+                config.pricing.nord_pool.area.clone(),
+                config.pricing.nord_pool.currency.clone(),
+            )),
+            &config.pricing.nord_pool.currency,
+        )
+    } else if config.pricing.ote_fallback.enabled {
+        info!("💰 OTE day-ahead prices enabled as fallback price source");
+        Arc::new(fluxion_core::failover_source::FallbackPriceSource::new(
             Arc::new(spot_adapter),
-            config.pricing.use_spot_prices_to_buy,
-            config.pricing.use_spot_prices_to_sell,
-            config.pricing.fixed_buy_prices.clone(),
-            config.pricing.fixed_sell_prices.clone(),
-            config.pricing.spot_sell_fee,
-        ));
-    info!("💰 Price data source: {}", price_source.name());
+            in_billing_currency(
+                Arc::new(fluxion_core::pricing::ote::OtePriceDataSource::new(
+                    exchange_rates.clone(),
+                )),
+                "CZK",
+            ),
+        ))
+    } else {
+        Arc::new(spot_adapter)
+    };
 
-    let history_source: Arc<dyn fluxion_core::traits::ConsumptionHistoryDataSource> = Arc::new(
-        fluxion_adapters::HaConsumptionHistoryAdapter::new(ha_client.clone()),
+    // Wrap spot adapter in configurable source that respects use_spot_prices_to_buy/sell flags
+    let mut configurable_price_source = fluxion_adapters::ConfigurablePriceDataSource::new(
+        spot_source,
+        config.pricing.use_spot_prices_to_buy,
+        config.pricing.use_spot_prices_to_sell,
+        config.pricing.fixed_buy_prices.clone(),
+        config.pricing.fixed_sell_prices.clone(),
+        config.pricing.spot_sell_fee,
     );
+    // Export prices from a dedicated sell price sensor or OTE override the spot-based ones
+    let sell_price = &config.pricing.sell_price;
+    let sell_price_source: Option<Arc<dyn fluxion_core::PriceDataSource>> =
+        if let Some(entity) = &sell_price.entity {
+            info!("💰 Using sell prices from {entity}");
+            Some(Arc::new(
+                CzSpotPriceAdapter::new(ha_client.clone(), entity.clone())
+                    .with_timezone_handle(spot_timezone),
+            ))
+        } else if sell_price.ote {
+            info!("💰 Using OTE day-ahead prices as sell prices");
+            Some(in_billing_currency(
+                Arc::new(fluxion_core::pricing::ote::OtePriceDataSource::new(
+                    exchange_rates.clone(),
+                )),
+                "CZK",
+            ))
+        } else {
+            None
+        };
+    if let Some(source) = sell_price_source {
+        configurable_price_source =
+            configurable_price_source.with_sell_price_source(source, sell_price.margin_czk);
+    }
+    let price_source: Arc<dyn fluxion_core::PriceDataSource> = Arc::new(configurable_price_source);
+    info!("💰 Price data source: {}", price_source.name());
+
+    let history_source: Arc<dyn fluxion_core::traits::ConsumptionHistoryDataSource> =
+        if tibber.use_for_consumption {
+            Arc::new(fluxion_adapters::TibberConsumptionAdapter::new(
+                tibber.api_token.clone(),
+                tibber.home_id.clone(),
+                config.history.solar_production_entity.clone(),
+            ))
+        } else {
+            Arc::new(fluxion_adapters::HaConsumptionHistoryAdapter::new(
+                ha_client.clone(),
+            ))
+        };
     info!("📊 History data source: {}", history_source.name());
 
+    // Solcast and Forecast.Solar replace the HA forecast sensors when enabled
+    let solar = &config.solar_forecast;
+    let pv_strings = solar
+        .strings
+        .iter()
+        .map(|string| fluxion_adapters::PvString {
+            azimuth: string.azimuth,
+            tilt: string.tilt,
+            kwp: string.kwp,
+        })
+        .collect::<Vec<_>>();
+    let solar_forecast_source: Option<Arc<dyn fluxion_core::SolarForecastDataSource>> =
+        if solar.solcast.enabled {
+            Some(Arc::new(fluxion_adapters::SolcastAdapter::new(
+                solar.solcast.api_key.clone(),
+                solar.latitude,
+                solar.longitude,
+                pv_strings,
+                solar.solcast.daily_limit,
+            )))
+        } else if solar.forecast_solar.enabled {
+            Some(Arc::new(fluxion_adapters::ForecastSolarAdapter::new(
+                solar.forecast_solar.api_key.clone(),
+                solar.latitude,
+                solar.longitude,
+                pv_strings,
+            )))
+        } else {
+            None
+        };
+    if let Some(source) = &solar_forecast_source {
+        info!(
+            "☀️ Solar forecast source: {} ({} PV strings)",
+            source.name(),
+            solar.strings.len()
+        );
+    }
+
+    let weather = &config.weather;
+    let weather_source: Option<Arc<dyn fluxion_core::WeatherDataSource>> = if !weather.enabled {
+        None
+    } else {
+        match weather.source {
+            fluxion_core::WeatherSource::HomeAssistant => Some(Arc::new(
+                fluxion_adapters::HaWeatherAdapter::new(ha_client.clone(), &weather.entity_id),
+            )),
+            fluxion_core::WeatherSource::OpenMeteo => Some(Arc::new(
+                fluxion_adapters::OpenMeteoAdapter::new(weather.latitude, weather.longitude),
+            )),
+        }
+    };
+    if let Some(source) = &weather_source {
+        info!("🌦️ Weather forecast source: {}", source.name());
+    }
+
     // Convert AppConfig to SystemConfig for ECS
     let system_config = SystemConfig::from(config.clone());
 
-    // Create shared plugin manager with built-in strategies
+    // Create shared plugin manager with built-in strategies and the external
+    // plugins registered before the last restart
+    let plugin_registry = Arc::new(fluxion_plugins::PluginRegistry::load("/data/plugins.json"));
     let plugin_manager = create_plugin_manager(
         Some(&system_config.strategies_config),
         &system_config.control_config,
+        Some(&plugin_registry),
     );
     let plugin_manager = Arc::new(RwLock::new(plugin_manager));
     info!("🔌 Plugin manager initialized with built-in strategies");
+    let what_if_planner = fluxion_core::what_if::WhatIfPlanner::new(plugin_manager.clone());
+    let what_if_planner_for_web = what_if_planner.clone();
+    fluxion_core::plugin_adapters::spawn_plugin_reachability_check(plugin_registry.clone());
+    if config.wasm_plugins.enabled {
+        fluxion_core::plugin_adapters::spawn_wasm_plugin_watcher(
+            std::path::PathBuf::from(&config.wasm_plugins.directory),
+            config.wasm_plugins.scan_interval_secs,
+            plugin_manager.clone(),
+        );
+    }
 
     // Configure debug mode
     let debug_config = if config.system.debug_mode {
@@ -218,6 +505,110 @@ fn initialize_and_run() -> Result<()> {
     let execution_config =
         fluxion_core::ExecutionConfig::new(config.control.min_mode_change_interval_secs);
 
+    // Grid quality statistics and events survive restarts
+    let grid_quality_monitor = fluxion_core::grid_quality::GridQualityMonitor::load(
+        fluxion_core::grid_quality::DEFAULT_GRID_QUALITY_PATH,
+    );
+    // Export measured during export cap windows, for the compliance report
+    let export_cap_monitor = fluxion_core::export_cap::ExportCapMonitor::load(
+        fluxion_core::export_cap::DEFAULT_EXPORT_CAP_PATH,
+    );
+    // User-defined alert rules; HA notifications and webhooks go out in the background
+    let alert_manager =
+        fluxion_core::alerts::AlertManager::load(fluxion_core::alerts::DEFAULT_ALERTS_PATH)
+            .with_dispatcher(Some(Arc::new(HaAlertNotifier::new(ha_client.clone()))));
+    // Hot water target temperatures follow prices and solar surplus
+    let dhw_planner = {
+        let planner = fluxion_core::dhw::DhwPlanner::new(config.dhw.clone());
+        if config.dhw.enabled {
+            info!(
+                "🚿 Hot water scheduler enabled for {}",
+                config.dhw.entity_id
+            );
+            planner.with_controller(Arc::new(HaDhwController::new(ha_client.clone())))
+        } else {
+            planner
+        }
+    };
+    // Heat pump setpoint is raised in very cheap winter blocks
+    let heat_pump_coordinator = {
+        let coordinator =
+            fluxion_core::heat_pump::HeatPumpCoordinator::new(config.heat_pump.clone());
+        if config.heat_pump.enabled {
+            info!(
+                "🌡️ Heat pump pre-heating enabled for {}",
+                config.heat_pump.entity_id
+            );
+            coordinator.with_controller(Arc::new(HaDhwController::new(ha_client.clone())))
+        } else {
+            coordinator
+        }
+    };
+    // POST new schedules and mode changes to the user's automations
+    let webhook_dispatcher =
+        (config.webhooks.enabled && !config.webhooks.urls.is_empty()).then(|| {
+            info!(
+                "🪝 Webhooks enabled for {} URL(s)",
+                config.webhooks.urls.len()
+            );
+            fluxion_core::webhooks::WebhookDispatcher::spawn(
+                config.webhooks.urls.clone(),
+                config.webhooks.max_retries,
+            )
+        });
+    // Executed block decisions for auditing through /api/decisions
+    let decision_log = if config.decision_log.enabled {
+        match fluxion_core::decision_log::DecisionLog::open(
+            fluxion_core::decision_log::DEFAULT_DECISION_LOG_PATH,
+            config.decision_log.retention_days,
+        ) {
+            Ok(log) => Some(log),
+            Err(e) => {
+                warn!("Decision log unavailable: {e:#}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    // Telemetry historian for charts and backtests that outlive HA's recorder
+    let historian = if config.historian.enabled {
+        match fluxion_core::historian::Historian::open(
+            fluxion_core::historian::DEFAULT_HISTORIAN_PATH,
+            config.historian.raw_retention_days,
+            config.historian.sample_interval_secs,
+        ) {
+            Ok(historian) => Some(historian),
+            Err(e) => {
+                warn!("Historian unavailable: {e:#}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+    // Appliance power readings, stored and priced in the historian
+    let submeter_channel = (config.submeter.enabled && historian.is_some()).then(|| {
+        fluxion_core::submeter::spawn_submeter_worker(
+            &config.submeter,
+            Arc::new(HaPowerSensorReader::new(ha_client.clone())),
+        )
+    });
+    // Realized versus expected profit for /api/savings
+    let savings_ledger = if config.savings.enabled {
+        match fluxion_core::savings::SavingsLedger::open(
+            fluxion_core::savings::DEFAULT_SAVINGS_PATH,
+        ) {
+            Ok(ledger) => Some(ledger),
+            Err(e) => {
+                warn!("Savings ledger unavailable: {e:#}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
     // Create message passing channel for web queries
     let (query_sender, query_channel) = WebQuerySender::new();
 
@@ -266,12 +657,46 @@ fn initialize_and_run() -> Result<()> {
         "🌍 Initialized i18n with language: {}",
         language.display_name()
     );
+    if !fluxion_i18n::Language::available_in(std::path::Path::new(
+        fluxion_i18n::DEFAULT_LOCALES_DIR,
+    ))
+    .contains(&language)
+    {
+        warn!(
+            "🌍 No {} locale files in {}, using English",
+            language.code(),
+            fluxion_i18n::DEFAULT_LOCALES_DIR
+        );
+    }
 
     // Spawn heartbeat client if enabled
     if config.server_heartbeat.enabled {
         heartbeat_client::spawn_heartbeat_task(
             config.server_heartbeat.clone(),
             query_sender.clone(),
+            config.license.key().map(str::to_owned),
+        );
+    }
+
+    // Validate the commercial license key; control never depends on the result
+    let license_state = match config.license.key() {
+        Some(key) => {
+            let state = fluxion_web::LicenseState::new(fluxion_web::LicenseInfo::checking());
+            license_client::spawn_license_task(
+                key.to_owned(),
+                config.server_heartbeat.clone(),
+                state.clone(),
+            );
+            state
+        }
+        None => fluxion_web::LicenseState::new(fluxion_web::LicenseInfo::unlicensed()),
+    };
+
+    // Ping the healthcheck URL after each planning cycle if enabled
+    if config.healthcheck_ping.enabled && !config.healthcheck_ping.ping_url.is_empty() {
+        healthcheck_ping::spawn_healthcheck_ping_task(
+            config.healthcheck_ping.clone(),
+            query_sender.clone(),
         );
     }
 
@@ -284,28 +709,130 @@ fn initialize_and_run() -> Result<()> {
         warn!("Failed to serialize config to JSON: {e}");
         serde_json::json!({})
     });
-    let config_sender_for_web = config_update_sender.clone();
-    let plugin_api_state = PluginApiState::new(plugin_manager.clone());
+    // Shared by the web config API and the MQTT debug mode switch
+    let config_state = fluxion_web::ConfigApiState::new(
+        config_json,
+        "/data/config.json",
+        Some(config_update_sender),
+        i18n.clone(),
+    );
+
+    // Publish telemetry to an MQTT broker if enabled
+    if config.mqtt.enabled && !config.mqtt.host.is_empty() {
+        mqtt_publisher::spawn_mqtt_publisher_task(
+            config.mqtt.clone(),
+            query_sender.clone(),
+            config_state.clone(),
+        );
+    }
+
+    // Installer self-test over the same data sources the scheduler uses
+    let self_test_state = fluxion_web::SelfTestState::new(
+        fluxion_core::self_test::SelfTest::new(
+            inverter_source.clone(),
+            price_source.clone(),
+            config.inverters.iter().map(|inv| inv.id.clone()).collect(),
+        ),
+        config_state.clone(),
+    );
+
+    let plugin_api_state =
+        PluginApiState::new(plugin_manager.clone()).with_registry(plugin_registry.clone());
     let remote_access_state = RemoteAccessApiState::new(
         std::path::Path::new("./data"),
         8099,
-        "FluxION".to_string(),
+        config.branding.product_name().to_string(),
     );
+    let api_key_state = fluxion_web::ApiKeyApiState::new(std::path::Path::new("./data"));
+    let auth_state = fluxion_web::AuthState::new(config.web_auth.clone())
+        .with_api_keys(api_key_state.store.clone());
+    let grid_quality_for_web = grid_quality_monitor.clone();
+    let export_cap_for_web = export_cap_monitor.clone();
+    let alert_manager_for_web = alert_manager.clone();
+    let dhw_planner_for_web = dhw_planner.clone();
+    let decision_log_for_web = decision_log.clone();
+    let savings_ledger_for_web = savings_ledger.clone();
+    let historian_for_web = historian.clone();
+    let submeter_devices_for_web = submeter_channel
+        .is_some()
+        .then(|| config.submeter.devices.clone());
+    // Backtests replay the historian's telemetry and prices
+    let backtest_db_path = historian
+        .as_ref()
+        .and_then(|h| h.path().map(std::path::Path::to_path_buf))
+        .unwrap_or_else(|| std::path::PathBuf::from("/home/daniel/Repositories/solare/fluxion/fluxion/crates/fluxion-integration-tests/solax_data.db"));
+    let ecs_inspector = config
+        .system
+        .developer_mode
+        .then(fluxion_core::inspector::EcsInspector::default);
+    let ecs_inspector_for_web = ecs_inspector.clone();
+    let branding = config.branding.clone();
+    let export_config = fluxion_web::ScheduledExportConfig {
+        filename_template: config.export.filename_template.clone(),
+        site_name: config.export.site_name.clone(),
+        formats: config.export.formats.clone(),
+        ..fluxion_web::ScheduledExportConfig::default()
+    };
+    // gRPC API for external orchestrators, sharing the web server's state and API keys
+    if config.grpc.enabled {
+        let grpc_state = fluxion_grpc::GrpcState {
+            query_sender: query_sender.clone(),
+            user_control: user_control_api_state.clone(),
+            api_keys: Some(api_key_state.store.clone()),
+        };
+        let addr = std::net::SocketAddr::new(config.grpc.bind_address, config.grpc.port);
+        if grpc_state.is_open_on(addr) {
+            tracing::error!(
+                "❌ gRPC server not started: {addr} is reachable from other hosts and no API key exists; create one or bind to 127.0.0.1"
+            );
+        } else {
+            tokio::spawn(async move {
+                if let Err(e) = fluxion_grpc::start_grpc_server(grpc_state, addr).await {
+                    tracing::error!("❌ gRPC server failed: {}", e);
+                }
+            });
+        }
+    }
     tokio::spawn(async move {
-        if let Err(e) = fluxion_web::start_web_server(
+        let deps = fluxion_web::WebServerDeps {
             query_sender,
-            i18n_for_server,
-            8099,
-            config_json,
-            Some(config_sender_for_web),
-            Some(std::path::PathBuf::from("/home/daniel/Repositories/solare/fluxion/fluxion/crates/fluxion-integration-tests/solax_data.db")), // Backtest DB path - set to enable backtest feature
-            Some(plugin_api_state), // Plugin API with shared PluginManager
-            Some(fluxion_web::ScheduledExportConfig::default()), // Daily export at 23:55 for debugging
-            Some(user_control_api_state), // User control API state
-            Some(remote_access_state), // Remote access pairing API
-        )
-        .await
-        {
+            i18n: i18n_for_server,
+            port: 8099,
+            config_state,
+            // Backtest DB path - set to enable backtest feature
+            backtest_db_path: Some(backtest_db_path),
+            // Plugin API with shared PluginManager
+            plugin_api_state: Some(plugin_api_state),
+            // Daily export at 23:55 for debugging
+            scheduled_export_config: Some(export_config),
+            user_control_api_state: Some(user_control_api_state),
+            // Remote access pairing API
+            remote_access_state: Some(remote_access_state),
+            // Scoped API keys for external automation
+            api_key_state: Some(api_key_state),
+            // First-run defaults wizard
+            setup_wizard_state: Some(setup_wizard_state),
+            // Live entity mapping validation
+            mapping_check_state: Some(mapping_check_state),
+            grid_quality_monitor: Some(grid_quality_for_web),
+            export_cap_monitor: Some(export_cap_for_web),
+            // Installer self-test of the control path
+            self_test_state: Some(self_test_state),
+            decision_log: decision_log_for_web,
+            savings_ledger: savings_ledger_for_web,
+            // Login for the standalone server
+            auth_state: Some(auth_state),
+            // Developer-mode ECS state snapshot
+            ecs_inspector: ecs_inspector_for_web,
+            branding,
+            license_state: Some(license_state),
+            alert_manager: Some(alert_manager_for_web),
+            dhw_planner: Some(dhw_planner_for_web),
+            historian: historian_for_web,
+            submeter_devices: submeter_devices_for_web,
+            what_if_planner: Some(what_if_planner_for_web),
+        };
+        if let Err(e) = fluxion_web::start_web_server(deps).await {
             tracing::error!("❌ Web server failed: {}", e);
         }
     });
@@ -313,6 +840,7 @@ fn initialize_and_run() -> Result<()> {
     // Create Bevy app with full configuration
     info!("🎮 Starting ECS application...");
 
+    let watchdog_config = config.watchdog.enabled.then(|| config.watchdog.clone());
     let mut app = App::new();
     app
         // Add TaskPoolPlugin to initialize async task pools
@@ -323,6 +851,7 @@ fn initialize_and_run() -> Result<()> {
         .insert_resource(config)
         .insert_resource(system_config)
         .insert_resource(debug_config)
+        .insert_resource(shared_debug_mode)
         .insert_resource(execution_config)
         .insert_resource(query_channel)
         .insert_resource(config_update_channel)
@@ -335,10 +864,50 @@ fn initialize_and_run() -> Result<()> {
             history_source,
         ))
         .insert_resource(PluginManagerResource(plugin_manager))
+        .insert_resource(fluxion_core::PluginRegistryResource(plugin_registry))
+        .insert_resource(what_if_planner)
+        .insert_resource(grid_quality_monitor)
+        .insert_resource(export_cap_monitor)
+        .insert_resource(alert_manager)
+        .insert_resource(dhw_planner)
+        .insert_resource(heat_pump_coordinator)
         .insert_resource(UserControlResource::new(user_control_state))
         .insert_resource(user_control_update_channel)
+        .insert_resource(fluxion_core::LoggingReloadHandle(Arc::new(move |cfg| {
+            logging.apply(cfg);
+        })))
         .init_resource::<fluxion_core::async_systems::BackupDischargeMinSoc>()
         .init_resource::<fluxion_core::async_systems::HdoScheduleData>();
+    if let Some(source) = solar_forecast_source {
+        app.insert_resource(fluxion_core::SolarForecastDataSourceResource(source));
+    }
+    if let Some(source) = weather_source {
+        app.insert_resource(fluxion_core::WeatherDataSourceResource(source));
+    }
+    if let Some(log) = decision_log {
+        app.insert_resource(log);
+    }
+    if let Some(dispatcher) = webhook_dispatcher {
+        app.insert_resource(dispatcher);
+    }
+    if let Some(ledger) = savings_ledger {
+        app.insert_resource(ledger);
+    }
+    if let Some(historian) = historian {
+        app.insert_resource(historian);
+    }
+    if let Some(channel) = submeter_channel {
+        app.insert_resource(channel);
+    }
+    if let Some(inspector) = ecs_inspector {
+        info!("🔬 Developer mode: ECS inspector at /api/v1/debug/ecs");
+        app.insert_resource(inspector);
+    }
+    // Let systemd/Docker restart FluxION when the main loop gets stuck
+    if let Some(watchdog_config) = watchdog_config {
+        app.insert_resource(watchdog::spawn_watchdog(watchdog_config))
+            .add_systems(Update, watchdog::record_tick);
+    }
 
     info!("✅ Starting main loop...");
 
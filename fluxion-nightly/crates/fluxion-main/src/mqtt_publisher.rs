@@ -0,0 +1,463 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use std::time::Duration;
+
+use fluxion_core::{InverterData, WebQueryResponse, WebQuerySender};
+use fluxion_web::ConfigApiState;
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use serde_json::{Value, json};
+use tracing::{debug, info, warn};
+
+use crate::config::MqttConfig;
+
+/// Delay before polling the event loop again after a connection error
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// One message to publish
+#[derive(Debug, Clone, PartialEq)]
+struct Message {
+    topic: String,
+    payload: Value,
+    retain: bool,
+}
+
+/// Spawns a background task that publishes telemetry to an MQTT broker.
+///
+/// With `debug_mode_switch` enabled the task also accepts `ON`/`OFF` on
+/// `<base_topic>/debug_mode/set` and applies them through `config_state`.
+pub fn spawn_mqtt_publisher_task(
+    config: MqttConfig,
+    query_sender: WebQuerySender,
+    config_state: ConfigApiState,
+) {
+    info!(
+        host = %config.host,
+        port = config.port,
+        base_topic = %config.base_topic,
+        "Starting MQTT publisher"
+    );
+
+    fluxion_core::TaskSupervisor::global().spawn("mqtt_publisher", move || {
+        run_publisher(config.clone(), query_sender.clone(), config_state.clone())
+    });
+}
+
+async fn run_publisher(
+    config: MqttConfig,
+    query_sender: WebQuerySender,
+    config_state: ConfigApiState,
+) {
+    let availability_topic = format!("{}/status", config.base_topic);
+    let debug_mode_command_topic = format!("{}/debug_mode/set", config.base_topic);
+    let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_last_will(LastWill::new(
+        &availability_topic,
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if let Some(username) = &config.username {
+        options.set_credentials(username, config.password.clone().unwrap_or_default());
+    }
+
+    let (client, mut eventloop) = AsyncClient::new(options, 128);
+    let mut ticker =
+        tokio::time::interval(Duration::from_secs(config.publish_interval_seconds.max(5)));
+    let mut connected = false;
+    // Inverter ids the discovery configs were sent for on this connection
+    let mut discovered: Option<Vec<String>> = None;
+
+    loop {
+        tokio::select! {
+            event = eventloop.poll() => match event {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    info!("MQTT connected to {}:{}", config.host, config.port);
+                    connected = true;
+                    discovered = None;
+                    publish(&client, &Message {
+                        topic: availability_topic.clone(),
+                        payload: Value::String("online".to_owned()),
+                        retain: true,
+                    });
+                    if config.debug_mode_switch
+                        && let Err(e) = client.try_subscribe(&debug_mode_command_topic, QoS::AtLeastOnce)
+                    {
+                        warn!(topic = %debug_mode_command_topic, error = %e, "Failed to subscribe to MQTT topic");
+                    }
+                }
+                Ok(Event::Incoming(Packet::Publish(message)))
+                    if config.debug_mode_switch && message.topic == debug_mode_command_topic =>
+                {
+                    match parse_switch_payload(&message.payload) {
+                        Some(enabled) => {
+                            info!(enabled, "Debug mode toggled over MQTT");
+                            config_state.set_debug_mode(enabled);
+                            // Confirm right away instead of waiting for the next tick
+                            publish(&client, &debug_mode_message(&config.base_topic, enabled));
+                        }
+                        None => warn!(
+                            payload = %String::from_utf8_lossy(&message.payload),
+                            "Ignoring MQTT debug mode command, expected ON or OFF"
+                        ),
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    if connected {
+                        warn!(error = %e, "MQTT connection lost");
+                    } else {
+                        debug!(error = %e, "MQTT connection failed");
+                    }
+                    connected = false;
+                    tokio::time::sleep(RECONNECT_DELAY).await;
+                }
+            },
+            _ = ticker.tick(), if connected => {
+                let dashboard = match query_sender.query_dashboard().await {
+                    Ok(dashboard) => dashboard,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to query dashboard for MQTT publish");
+                        continue;
+                    }
+                };
+
+                let inverter_ids: Vec<String> =
+                    dashboard.inverters.iter().map(|inv| inv.id.clone()).collect();
+                if config.discovery && discovered.as_ref() != Some(&inverter_ids) {
+                    for message in discovery_messages(&config, &dashboard) {
+                        publish(&client, &message);
+                    }
+                    discovered = Some(inverter_ids);
+                }
+                for message in state_messages(&config.base_topic, &dashboard) {
+                    publish(&client, &message);
+                }
+                if config.debug_mode_switch {
+                    publish(&client, &debug_mode_message(&config.base_topic, dashboard.debug_mode));
+                }
+            }
+        }
+    }
+}
+
+fn publish(client: &AsyncClient, message: &Message) {
+    // Non-blocking: the event loop is driven by the same task
+    if let Err(e) = client.try_publish(
+        &message.topic,
+        QoS::AtLeastOnce,
+        message.retain,
+        message.payload_bytes(),
+    ) {
+        warn!(topic = %message.topic, error = %e, "Failed to queue MQTT message");
+    }
+}
+
+impl Message {
+    fn payload_bytes(&self) -> Vec<u8> {
+        match &self.payload {
+            Value::String(s) => s.clone().into_bytes(),
+            other => other.to_string().into_bytes(),
+        }
+    }
+}
+
+/// State topics: one per inverter, the active schedule block and the prices
+fn state_messages(base_topic: &str, dashboard: &WebQueryResponse) -> Vec<Message> {
+    let mut messages: Vec<Message> = dashboard
+        .inverters
+        .iter()
+        .map(|inv| Message {
+            topic: format!("{base_topic}/inverter/{}/state", inv.id),
+            payload: inverter_payload(inv),
+            retain: true,
+        })
+        .collect();
+
+    if let Some(schedule) = &dashboard.schedule {
+        messages.push(Message {
+            topic: format!("{base_topic}/schedule/current"),
+            payload: json!({
+                "mode": schedule.current_mode,
+                "reason": schedule.current_reason,
+                "strategy": schedule.current_strategy,
+                "expected_profit": schedule.expected_profit,
+                "next_change": schedule.next_change,
+                "target_soc_min": schedule.target_soc_min,
+                "target_soc_max": schedule.target_soc_max,
+                "generated_at": schedule.schedule_generated_at,
+            }),
+            retain: true,
+        });
+    }
+
+    if let Some(prices) = &dashboard.prices {
+        messages.push(Message {
+            topic: format!("{base_topic}/prices/current"),
+            payload: json!({
+                "current": prices.current_price,
+                "today_min": prices.today_min_price,
+                "today_max": prices.today_max_price,
+                "today_avg": prices.today_avg_price,
+                "tomorrow_avg": prices.tomorrow_avg_price,
+            }),
+            retain: true,
+        });
+    }
+
+    messages
+}
+
+/// Switch state for the debug mode, as `ON`/`OFF`
+fn debug_mode_message(base_topic: &str, enabled: bool) -> Message {
+    Message {
+        topic: format!("{base_topic}/debug_mode/state"),
+        payload: Value::String(if enabled { "ON" } else { "OFF" }.to_owned()),
+        retain: true,
+    }
+}
+
+/// `ON`/`OFF` (as sent by a Home Assistant MQTT switch), also `true`/`false` and `1`/`0`
+fn parse_switch_payload(payload: &[u8]) -> Option<bool> {
+    match std::str::from_utf8(payload)
+        .ok()?
+        .trim()
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "on" | "true" | "1" => Some(true),
+        "off" | "false" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn inverter_payload(inv: &InverterData) -> Value {
+    json!({
+        "mode": inv.mode,
+        "actual_mode": inv.actual_mode,
+        "mode_synced": inv.mode_synced,
+        "online": inv.online,
+        "battery_soc": inv.battery_soc,
+        "battery_power_w": inv.battery_power_w,
+        "grid_power_w": inv.grid_power_w,
+        "pv_power_w": inv.pv_power_w,
+        "house_load_w": inv.house_load_w,
+        "telemetry_source": inv.telemetry_source,
+    })
+}
+
+/// Home Assistant MQTT discovery configs for the state topics
+fn discovery_messages(config: &MqttConfig, dashboard: &WebQueryResponse) -> Vec<Message> {
+    let base = &config.base_topic;
+    let device = json!({
+        "identifiers": [format!("fluxion_{}", config.client_id)],
+        "name": "FluxION",
+        "manufacturer": "SOLARE",
+        "sw_version": env!("CARGO_PKG_VERSION"),
+    });
+    let sensor =
+        |object_id: String, name: String, state_topic: String, field: &str, extra: Value| {
+            let mut payload = json!({
+                "name": name,
+                "unique_id": format!("{}_{object_id}", config.client_id),
+                "object_id": format!("fluxion_{object_id}"),
+                "state_topic": state_topic,
+                "value_template": format!("{{{{ value_json.{field} }}}}"),
+                "availability_topic": format!("{base}/status"),
+                "device": device,
+            });
+            if let (Some(payload), Some(extra)) = (payload.as_object_mut(), extra.as_object()) {
+                payload.extend(extra.clone());
+            }
+            Message {
+                topic: format!(
+                    "{}/sensor/{}/{object_id}/config",
+                    config.discovery_prefix, config.client_id
+                ),
+                payload,
+                retain: true,
+            }
+        };
+
+    let power =
+        json!({"unit_of_measurement": "W", "device_class": "power", "state_class": "measurement"});
+    let mut messages = Vec::new();
+    for inv in &dashboard.inverters {
+        let topic = format!("{base}/inverter/{}/state", inv.id);
+        for (key, name, field, extra) in [
+            (
+                "battery_soc",
+                "Battery SOC",
+                "battery_soc",
+                json!({"unit_of_measurement": "%", "device_class": "battery", "state_class": "measurement"}),
+            ),
+            (
+                "battery_power",
+                "Battery power",
+                "battery_power_w",
+                power.clone(),
+            ),
+            ("grid_power", "Grid power", "grid_power_w", power.clone()),
+            ("pv_power", "PV power", "pv_power_w", power.clone()),
+            ("mode", "Inverter mode", "mode", json!({})),
+        ] {
+            messages.push(sensor(
+                format!("{}_{key}", inv.id),
+                format!("{} {name}", inv.id),
+                topic.clone(),
+                field,
+                extra,
+            ));
+        }
+    }
+
+    messages.push(sensor(
+        "schedule_mode".to_owned(),
+        "Scheduled mode".to_owned(),
+        format!("{base}/schedule/current"),
+        "mode",
+        json!({}),
+    ));
+    messages.push(sensor(
+        "spot_price".to_owned(),
+        "Spot price".to_owned(),
+        format!("{base}/prices/current"),
+        "current",
+        json!({"state_class": "measurement"}),
+    ));
+
+    if config.debug_mode_switch {
+        messages.push(Message {
+            topic: format!(
+                "{}/switch/{}/debug_mode/config",
+                config.discovery_prefix, config.client_id
+            ),
+            payload: json!({
+                "name": "Debug mode",
+                "unique_id": format!("{}_debug_mode", config.client_id),
+                "object_id": "fluxion_debug_mode",
+                "state_topic": format!("{base}/debug_mode/state"),
+                "command_topic": format!("{base}/debug_mode/set"),
+                "availability_topic": format!("{base}/status"),
+                "icon": "mdi:shield-bug",
+                "device": device,
+            }),
+            retain: true,
+        });
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dashboard() -> WebQueryResponse {
+        let inverter: InverterData = serde_json::from_value(json!({
+            "id": "main", "topology": "independent", "mode": "Self-Use", "mode_reason": "",
+            "actual_mode": null, "mode_synced": true, "battery_soc": 64.0,
+            "battery_power_w": 800.0, "battery_voltage_v": 0.0, "battery_current_a": 0.0,
+            "battery_temperature_c": 0.0, "grid_power_w": -1200.0, "grid_voltage_v": 0.0,
+            "grid_frequency_hz": 0.0, "pv_power_w": 3500.0, "pv1_power_w": 0.0,
+            "pv2_power_w": 0.0, "daily_energy_kwh": 0.0, "total_energy_kwh": 0.0,
+            "online": true, "run_mode": "", "error_code": 0, "inverter_temperature_c": 0.0,
+        }))
+        .unwrap();
+        WebQueryResponse {
+            timestamp: chrono::Utc::now(),
+            debug_mode: false,
+            inverters: vec![inverter],
+            schedule: None,
+            prices: None,
+            health: fluxion_core::SystemHealthData {
+                inverter_source: true,
+                price_source: true,
+                last_update: chrono::Utc::now(),
+                errors: vec![],
+            },
+            timezone: None,
+            battery_soc_history: None,
+            battery_soc_prediction: None,
+            pv_generation_history: None,
+            battery_power_history: None,
+            grid_power_history: None,
+            consumption_stats: None,
+            hdo_schedule: None,
+            pricing_fees: None,
+            solar_forecast: None,
+        }
+    }
+
+    #[test]
+    fn test_state_messages_per_inverter() {
+        let messages = state_messages("fluxion", &dashboard());
+
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].topic, "fluxion/inverter/main/state");
+        assert_eq!(messages[0].payload["battery_soc"], 64.0);
+        assert_eq!(messages[0].payload["mode"], "Self-Use");
+    }
+
+    #[test]
+    fn test_discovery_points_at_state_topics() {
+        let config = MqttConfig::default();
+        let messages = discovery_messages(&config, &dashboard());
+
+        let soc = messages
+            .iter()
+            .find(|m| m.topic == "homeassistant/sensor/fluxion/main_battery_soc/config")
+            .unwrap();
+        assert_eq!(soc.payload["state_topic"], "fluxion/inverter/main/state");
+        assert_eq!(
+            soc.payload["value_template"],
+            "{{ value_json.battery_soc }}"
+        );
+        assert_eq!(soc.payload["unit_of_measurement"], "%");
+        assert_eq!(soc.payload["availability_topic"], "fluxion/status");
+        assert!(messages.iter().all(|m| m.retain));
+    }
+
+    #[test]
+    fn test_debug_mode_switch_discovery() {
+        let config = MqttConfig::default();
+        let messages = discovery_messages(&config, &dashboard());
+
+        let switch = messages
+            .iter()
+            .find(|m| m.topic == "homeassistant/switch/fluxion/debug_mode/config")
+            .unwrap();
+        assert_eq!(switch.payload["state_topic"], "fluxion/debug_mode/state");
+        assert_eq!(switch.payload["command_topic"], "fluxion/debug_mode/set");
+
+        let disabled = MqttConfig {
+            debug_mode_switch: false,
+            ..MqttConfig::default()
+        };
+        assert!(
+            discovery_messages(&disabled, &dashboard())
+                .iter()
+                .all(|m| !m.topic.contains("/switch/"))
+        );
+    }
+
+    #[test]
+    fn test_parse_switch_payload() {
+        assert_eq!(parse_switch_payload(b"ON"), Some(true));
+        assert_eq!(parse_switch_payload(b"off\n"), Some(false));
+        assert_eq!(parse_switch_payload(b"toggle"), None);
+        assert_eq!(
+            debug_mode_message("fluxion", true).payload_bytes(),
+            b"ON".to_vec()
+        );
+    }
+}
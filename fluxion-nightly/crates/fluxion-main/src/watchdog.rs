@@ -0,0 +1,258 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Liveness watchdog for the ECS main loop.
+//!
+//! Scheduling and inverter control run in the main loop, which also waits on
+//! Home Assistant requests. A deadlocked channel or a hung request stops the
+//! loop without crashing the process, so a thread outside the loop checks that
+//! it keeps running. While it does, the thread notifies systemd (`WATCHDOG=1`
+//! for units with `WatchdogSec=`) and touches a heartbeat file for Docker's
+//! `HEALTHCHECK`. Once the loop stalls the notifications stop and FluxION
+//! exits, so the supervisor restarts it instead of leaving it frozen.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use bevy_ecs::prelude::*;
+use tracing::{error, info, warn};
+
+use crate::config::WatchdogConfig;
+
+/// How often the loop is checked and liveness reported
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// When the main loop last ran
+#[derive(Debug)]
+pub struct Liveness {
+    started: Instant,
+    last_tick_ms: AtomicU64,
+}
+
+impl Liveness {
+    fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            last_tick_ms: AtomicU64::new(0),
+        }
+    }
+
+    fn tick(&self) {
+        let elapsed = u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX);
+        self.last_tick_ms.store(elapsed, Ordering::Relaxed);
+    }
+
+    /// Time since the main loop last ran (or since the watchdog started)
+    fn since_last_tick(&self) -> Duration {
+        let last_tick = Duration::from_millis(self.last_tick_ms.load(Ordering::Relaxed));
+        self.started.elapsed().saturating_sub(last_tick)
+    }
+}
+
+/// Shared with the watchdog thread; updated on every main loop iteration
+#[derive(Resource, Debug, Clone)]
+pub struct WatchdogLiveness(Arc<Liveness>);
+
+/// Marks the main loop as alive
+pub fn record_tick(liveness: Res<WatchdogLiveness>) {
+    liveness.0.tick();
+}
+
+/// Starts the watchdog thread; insert the returned resource and add [`record_tick`]
+pub fn spawn_watchdog(config: WatchdogConfig) -> WatchdogLiveness {
+    let liveness = Arc::new(Liveness::new());
+    let notifier = systemd::Notifier::from_env();
+    let interval = notifier
+        .as_ref()
+        .and_then(systemd::Notifier::watchdog_timeout)
+        .map_or(CHECK_INTERVAL, |timeout| CHECK_INTERVAL.min(timeout / 2));
+
+    info!(
+        stall_timeout_seconds = config.stall_timeout_seconds,
+        systemd = notifier.is_some(),
+        heartbeat_file = %config.heartbeat_file,
+        "Starting main loop watchdog"
+    );
+
+    // A plain thread, so a starved async runtime cannot stop the checks
+    let thread_liveness = liveness.clone();
+    if let Err(e) = std::thread::Builder::new()
+        .name("watchdog".to_owned())
+        .spawn(move || run_watchdog(&config, &thread_liveness, notifier.as_ref(), interval))
+    {
+        warn!(error = %e, "Failed to start main loop watchdog");
+    }
+
+    WatchdogLiveness(liveness)
+}
+
+fn run_watchdog(
+    config: &WatchdogConfig,
+    liveness: &Liveness,
+    notifier: Option<&systemd::Notifier>,
+    interval: Duration,
+) {
+    let stall_timeout = Duration::from_secs(config.stall_timeout_seconds);
+    let mut ready = false;
+
+    loop {
+        std::thread::sleep(interval);
+
+        let stalled_for = liveness.since_last_tick();
+        if stalled_for >= stall_timeout {
+            error!(
+                stalled_seconds = stalled_for.as_secs(),
+                "Main loop is not running, stopping liveness notifications"
+            );
+            if config.exit_on_stall {
+                error!("Exiting so the supervisor restarts FluxION");
+                std::process::exit(1);
+            }
+            continue;
+        }
+
+        if let Some(notifier) = notifier {
+            if !ready {
+                ready = notifier.notify("READY=1");
+            }
+            notifier.notify("WATCHDOG=1");
+        }
+        if !config.heartbeat_file.is_empty()
+            && let Err(e) = std::fs::write(&config.heartbeat_file, chrono::Utc::now().to_rfc3339())
+        {
+            warn!(error = %e, path = %config.heartbeat_file, "Failed to write heartbeat file");
+        }
+    }
+}
+
+/// `sd_notify` protocol: datagrams to the socket systemd passes in `$NOTIFY_SOCKET`
+#[cfg(target_os = "linux")]
+mod systemd {
+    use std::os::linux::net::SocketAddrExt;
+    use std::os::unix::net::{SocketAddr, UnixDatagram};
+    use std::time::Duration;
+
+    use tracing::warn;
+
+    pub struct Notifier {
+        socket: UnixDatagram,
+        addr: SocketAddr,
+        watchdog_timeout: Option<Duration>,
+    }
+
+    impl Notifier {
+        /// Notifier for the socket of the systemd service, if running as one
+        pub fn from_env() -> Option<Self> {
+            let path = std::env::var("NOTIFY_SOCKET").ok()?;
+            // Only this process is watched, not helpers that inherit the variable
+            let watched = std::env::var("WATCHDOG_PID")
+                .ok()
+                .is_none_or(|pid| pid == std::process::id().to_string());
+            let watchdog_timeout = std::env::var("WATCHDOG_USEC")
+                .ok()
+                .and_then(|usec| usec.parse().ok())
+                .filter(|_| watched)
+                .map(Duration::from_micros);
+            Self::new(&path, watchdog_timeout)
+        }
+
+        pub(super) fn new(path: &str, watchdog_timeout: Option<Duration>) -> Option<Self> {
+            let addr = match path.strip_prefix('@') {
+                Some(name) => SocketAddr::from_abstract_name(name),
+                None => SocketAddr::from_pathname(path),
+            };
+            let result = addr.and_then(|addr| Ok((UnixDatagram::unbound()?, addr)));
+            match result {
+                Ok((socket, addr)) => Some(Self {
+                    socket,
+                    addr,
+                    watchdog_timeout,
+                }),
+                Err(e) => {
+                    warn!(error = %e, path, "Invalid systemd notify socket");
+                    None
+                }
+            }
+        }
+
+        /// `WatchdogSec=` of the unit, if set
+        pub fn watchdog_timeout(&self) -> Option<Duration> {
+            self.watchdog_timeout
+        }
+
+        /// Send a state update, e.g. `WATCHDOG=1`
+        pub fn notify(&self, state: &str) -> bool {
+            match self.socket.send_to_addr(state.as_bytes(), &self.addr) {
+                Ok(_) => true,
+                Err(e) => {
+                    warn!(error = %e, state, "Failed to notify systemd");
+                    false
+                }
+            }
+        }
+    }
+}
+
+/// systemd only runs on Linux
+#[cfg(not(target_os = "linux"))]
+mod systemd {
+    use std::time::Duration;
+
+    pub struct Notifier;
+
+    impl Notifier {
+        pub fn from_env() -> Option<Self> {
+            None
+        }
+
+        pub fn watchdog_timeout(&self) -> Option<Duration> {
+            None
+        }
+
+        pub fn notify(&self, _state: &str) -> bool {
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stall_is_measured_from_last_tick() {
+        let liveness = Liveness::new();
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(liveness.since_last_tick() >= Duration::from_millis(20));
+
+        liveness.tick();
+        assert!(liveness.since_last_tick() < Duration::from_millis(20));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_notification_reaches_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notify.sock");
+        let receiver = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+
+        let notifier =
+            systemd::Notifier::new(path.to_str().unwrap(), Some(Duration::from_secs(60))).unwrap();
+        assert!(notifier.notify("WATCHDOG=1"));
+
+        let mut buf = [0; 32];
+        let len = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..len], b"WATCHDOG=1");
+        assert_eq!(notifier.watchdog_timeout(), Some(Duration::from_secs(60)));
+    }
+}
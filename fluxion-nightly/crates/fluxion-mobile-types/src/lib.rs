@@ -18,9 +18,32 @@
 
 use serde::{Deserialize, Serialize};
 
-/// Current mobile API version. Bump when making breaking changes.
+/// Mobile API version of `/mobile/api/state`. Bump when making breaking changes.
 pub const API_VERSION: u8 = 1;
 
+/// Version of the endpoints under `/mobile/api/v2`
+pub const API_VERSION_V2: u8 = 2;
+
+/// Mobile API versions served by this build, oldest first
+pub const SUPPORTED_API_VERSIONS: &[u8] = &[API_VERSION, API_VERSION_V2];
+
+/// Newest API version both sides speak
+///
+/// `server_versions` comes from [`VersionResponse::api_versions`]; servers that
+/// predate negotiation send none and only speak version 1.
+pub fn negotiate_api_version(client_versions: &[u8], server_versions: &[u8]) -> Option<u8> {
+    let server_versions = if server_versions.is_empty() {
+        &[API_VERSION][..]
+    } else {
+        server_versions
+    };
+    client_versions
+        .iter()
+        .copied()
+        .filter(|v| server_versions.contains(v))
+        .max()
+}
+
 // ==================== State response ====================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,6 +53,10 @@ pub struct MobileStateResponse {
     pub battery_soc: f32,
     pub mode: String,
     pub mode_reason: String,
+    /// Kind of the structured reason (e.g. `export_cap`) for filtering and
+    /// localization in the app; absent on older servers or free-form reasons
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode_reason_kind: Option<String>,
     pub solar_w: f32,
     pub grid_w: f32,
     pub load_w: f32,
@@ -38,8 +65,27 @@ pub struct MobileStateResponse {
     pub currency: String,
     pub user_control: MobileUserControl,
     pub chart_data: Vec<MobileChartPoint>,
+    /// `full` or `readonly`, kept for app builds that predate roles
     pub access_mode: String,
+    /// Role of the requesting device; absent on older servers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<DeviceRole>,
     pub timestamp: String,
+    /// Summary of the next hours of the plan; absent on older servers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<MobilePreview>,
+    /// IANA timezone of the server (e.g. `Europe/Prague`); absent when unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// BCP 47 locale of the UI language (e.g. `cs-CZ`); absent on older servers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// `current_price` formatted for `locale` (e.g. `3,25 Kč/kWh`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_price_formatted: Option<String>,
+    /// Installer branding; absent on older servers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branding: Option<MobileBranding>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +96,7 @@ pub struct MobileUserControl {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct MobileTimeSlot {
     pub id: String,
     pub start: String,
@@ -62,18 +109,155 @@ pub struct MobileChartPoint {
     pub time: String,
     pub price: f32,
     pub mode: String,
+    /// Kind of the block's structured reason; absent on older servers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason_kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobilePreview {
+    pub horizon_hours: i64,
+    pub charge_kwh: Option<f32>,
+    pub charge_cost: Option<f32>,
+    pub discharge_kwh: Option<f32>,
+    pub peak_discharge_time: Option<String>,
+    pub peak_discharge_price: Option<f32>,
+    pub expected_profit: Option<f32>,
+    pub soc_end: Option<f32>,
+    pub actions: Vec<MobilePreviewAction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobilePreviewAction {
+    pub start: String,
+    pub end: String,
+    pub mode: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileBranding {
+    /// Shown instead of "FluxION"
+    pub product_name: String,
+    /// `#rrggbb` accent color; absent keeps the app default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accent_color: Option<String>,
 }
 
 // ==================== Version response ====================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct VersionResponse {
     pub version: String,
+    /// API versions the server speaks; empty on servers that only speak version 1
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_versions: Vec<u8>,
+}
+
+// ==================== v2: schedule ====================
+
+/// `GET /mobile/api/v2/schedule`: the whole plan with reasons and SOC series
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileScheduleResponse {
+    pub api_version: u8,
+    pub timestamp: String,
+    pub timezone: Option<String>,
+    pub currency: String,
+    pub current_mode: String,
+    pub current_reason: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_reason_kind: Option<String>,
+    pub next_change: Option<String>,
+    pub target_soc_min: Option<f32>,
+    pub target_soc_max: Option<f32>,
+    /// Every block of the schedule, today's past blocks included
+    /// (96 a day with 15-minute blocks)
+    pub blocks: Vec<MobileScheduleBlock>,
+    /// Measured battery SOC of the last hours
+    pub soc_history: Vec<MobileSocPoint>,
+    /// SOC the schedule is expected to leave the battery at
+    pub soc_prediction: Vec<MobileSocPoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileScheduleBlock {
+    pub start: String,
+    pub end: String,
+    pub price: f32,
+    pub mode: String,
+    /// Reason in the UI language
+    pub reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason_kind: Option<String>,
+    pub strategy: Option<String>,
+    pub target_soc: Option<f32>,
+    /// SOC planned at the end of the block
+    pub planned_soc: Option<f32>,
+    pub expected_profit: Option<f32>,
+    /// Block lies in the past
+    pub is_historical: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileSocPoint {
+    pub time: String,
+    pub soc: f32,
+}
+
+// ==================== v2: consumption ====================
+
+/// `GET /mobile/api/v2/consumption`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileConsumptionResponse {
+    pub api_version: u8,
+    pub timestamp: String,
+    /// Average daily grid import over the last `ema_days` days
+    pub ema_kwh: Option<f32>,
+    pub ema_days: usize,
+    pub today_import_kwh: Option<f32>,
+    pub yesterday_import_kwh: Option<f32>,
+    pub today_solar_kwh: Option<f32>,
+    pub today_export_kwh: Option<f32>,
+    /// Average consumption per local hour (24 values); empty until learned
+    pub hourly_profile: Vec<f32>,
+}
+
+// ==================== v2: daily history ====================
+
+/// `GET /mobile/api/v2/history?page=&per_page=`: one page of daily summaries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileHistoryPage {
+    pub api_version: u8,
+    /// 1-based page; page 1 holds the newest days
+    pub page: u32,
+    pub per_page: u32,
+    /// Days with data over all pages
+    pub total_days: u32,
+    pub has_more: bool,
+    pub currency: String,
+    /// Newest first
+    pub days: Vec<MobileDailySummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MobileDailySummary {
+    /// Local date, `YYYY-MM-DD`
+    pub date: String,
+    /// Blocks accounted that day
+    pub blocks: usize,
+    pub expected_profit: f32,
+    pub realized_profit: f32,
+    pub actual_cost: f32,
+    /// Cost without the battery
+    pub baseline_cost: f32,
+    pub grid_import_kwh: f32,
+    pub grid_export_kwh: f32,
 }
 
 // ==================== Control request/response ====================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct MobileControlRequest {
     pub charge_from_grid_enabled: Option<bool>,
     pub forced_mode: Option<String>,
@@ -91,6 +275,43 @@ pub struct MobileControlResponse {
     pub error: Option<String>,
 }
 
+// ==================== Device roles ====================
+
+/// Header carrying the device token from the QR payload on every request
+pub const DEVICE_TOKEN_HEADER: &str = "X-Fluxion-Device";
+
+/// What a paired device may do, ordered from least to most privileged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceRole {
+    /// Reads state only
+    #[serde(alias = "readonly")]
+    Viewer,
+    /// Also changes user control and engages the safe state
+    Controller,
+    /// Also resumes scheduling from the safe state
+    #[serde(alias = "full")]
+    Admin,
+}
+
+impl DeviceRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Viewer => "viewer",
+            Self::Controller => "controller",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Legacy `access_mode` value shown by app builds that predate roles
+    pub fn access_mode(self) -> &'static str {
+        match self {
+            Self::Viewer => "readonly",
+            Self::Controller | Self::Admin => "full",
+        }
+    }
+}
+
 // ==================== QR pairing payload ====================
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -99,12 +320,16 @@ pub struct QrPayload {
     pub onion: String,
     pub key: String,
     pub name: String,
-    #[serde(default = "default_qr_mode")]
-    pub mode: String,
+    /// Older QR codes carry `mode` (`full` / `readonly`) instead
+    #[serde(default = "default_qr_role", alias = "mode")]
+    pub role: DeviceRole,
+    /// Sent as [`DEVICE_TOKEN_HEADER`]; absent in QR codes from older servers
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token: String,
 }
 
-fn default_qr_mode() -> String {
-    "full".to_owned()
+fn default_qr_role() -> DeviceRole {
+    DeviceRole::Admin
 }
 
 #[cfg(test)]
@@ -118,13 +343,29 @@ mod tests {
             onion: "test.onion".to_owned(),
             key: "base64key==".to_owned(),
             name: "FluxION Home".to_owned(),
-            mode: "full".to_owned(),
+            role: DeviceRole::Controller,
+            token: "device-token".to_owned(),
         };
         let json = serde_json::to_string(&payload).unwrap();
         let parsed: QrPayload = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.v, 1);
         assert_eq!(parsed.onion, "test.onion");
-        assert_eq!(parsed.mode, "full");
+        assert_eq!(parsed.role, DeviceRole::Controller);
+        assert_eq!(parsed.token, "device-token");
+    }
+
+    #[test]
+    fn test_legacy_qr_payload_mode() {
+        let json = r#"{"v":1,"onion":"test.onion","key":"k","name":"Home","mode":"readonly"}"#;
+        let parsed: QrPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.role, DeviceRole::Viewer);
+        assert!(parsed.token.is_empty());
+
+        let json = r#"{"v":1,"onion":"test.onion","key":"k","name":"Home"}"#;
+        let parsed: QrPayload = serde_json::from_str(json).unwrap();
+        assert_eq!(parsed.role, DeviceRole::Admin);
+        assert!(DeviceRole::Viewer < DeviceRole::Controller);
+        assert!(DeviceRole::Controller < DeviceRole::Admin);
     }
 
     #[test]
@@ -135,6 +376,7 @@ mod tests {
             battery_soc: 72.5,
             mode: "SelfUse".to_owned(),
             mode_reason: "Solar".to_owned(),
+            mode_reason_kind: Some("export_cap".to_owned()),
             solar_w: 1000.0,
             grid_w: 0.0,
             load_w: 800.0,
@@ -148,13 +390,45 @@ mod tests {
             },
             chart_data: vec![],
             access_mode: "full".to_owned(),
+            role: None,
             timestamp: "2026-01-31T10:00:00Z".to_owned(),
+            preview: None,
+            timezone: None,
+            locale: None,
+            current_price_formatted: None,
+            branding: None,
         };
         let json = serde_json::to_string(&response).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed["ui_version"], "0.2.35");
         assert_eq!(parsed["api_version"], API_VERSION);
         assert_eq!(parsed["battery_soc"], 72.5);
+        assert_eq!(parsed["mode_reason_kind"], "export_cap");
+    }
+
+    #[test]
+    fn test_negotiate_api_version() {
+        assert_eq!(
+            negotiate_api_version(SUPPORTED_API_VERSIONS, SUPPORTED_API_VERSIONS),
+            Some(API_VERSION_V2)
+        );
+        // v1 app against a v2 server
+        assert_eq!(
+            negotiate_api_version(&[API_VERSION], SUPPORTED_API_VERSIONS),
+            Some(API_VERSION)
+        );
+        // v2 app against a server that predates negotiation
+        assert_eq!(
+            negotiate_api_version(SUPPORTED_API_VERSIONS, &[]),
+            Some(API_VERSION)
+        );
+        assert_eq!(negotiate_api_version(&[API_VERSION_V2], &[]), None);
+    }
+
+    #[test]
+    fn test_version_response_from_old_server() {
+        let parsed: VersionResponse = serde_json::from_str(r#"{"version":"0.2.35"}"#).unwrap();
+        assert!(parsed.api_versions.is_empty());
     }
 
     #[test]
@@ -0,0 +1,226 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Minimal Modbus TCP client: read holding registers and write registers.
+
+use anyhow::{Context, Result, bail};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+const READ_HOLDING_REGISTERS: u8 = 0x03;
+const WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+
+/// Most registers one read request may ask for
+const MAX_READ_REGISTERS: u16 = 125;
+
+/// Most registers one write request may carry
+const MAX_WRITE_REGISTERS: usize = 123;
+
+#[derive(Debug, Default)]
+struct Connection {
+    stream: Option<TcpStream>,
+    transaction_id: u16,
+}
+
+/// Modbus TCP connection to one unit, reconnected after any failure
+#[derive(Debug)]
+pub struct ModbusTcpClient {
+    addr: String,
+    unit_id: u8,
+    timeout: Duration,
+    connection: Mutex<Connection>,
+}
+
+impl ModbusTcpClient {
+    #[must_use]
+    pub fn new(addr: impl Into<String>, unit_id: u8, timeout: Duration) -> Self {
+        Self {
+            addr: addr.into(),
+            unit_id,
+            timeout,
+            connection: Mutex::default(),
+        }
+    }
+
+    /// Read `count` holding registers starting at `address`
+    pub async fn read_holding_registers(&self, address: u16, count: u16) -> Result<Vec<u16>> {
+        let mut registers = Vec::with_capacity(usize::from(count));
+        let mut offset = 0;
+        while offset < count {
+            let chunk = (count - offset).min(MAX_READ_REGISTERS);
+            let start = address
+                .checked_add(offset)
+                .context("register address out of range")?;
+            let mut pdu = vec![READ_HOLDING_REGISTERS];
+            pdu.extend_from_slice(&start.to_be_bytes());
+            pdu.extend_from_slice(&chunk.to_be_bytes());
+
+            let response = self.request(&pdu).await?;
+            let byte_count = usize::from(*response.get(1).context("short response")?);
+            let data = response.get(2..2 + byte_count).context("short response")?;
+            if byte_count != usize::from(chunk) * 2 {
+                bail!("expected {chunk} registers at {start}, got {byte_count} bytes");
+            }
+            let (pairs, _) = data.as_chunks::<2>();
+            registers.extend(pairs.iter().map(|b| u16::from_be_bytes(*b)));
+            offset += chunk;
+        }
+        Ok(registers)
+    }
+
+    /// Write `values` to consecutive holding registers starting at `address`
+    pub async fn write_registers(&self, address: u16, values: &[u16]) -> Result<()> {
+        if values.is_empty() || values.len() > MAX_WRITE_REGISTERS {
+            bail!("cannot write {} registers in one request", values.len());
+        }
+        let count = u16::try_from(values.len())?;
+        let mut pdu = vec![WRITE_MULTIPLE_REGISTERS];
+        pdu.extend_from_slice(&address.to_be_bytes());
+        pdu.extend_from_slice(&count.to_be_bytes());
+        pdu.push(u8::try_from(values.len() * 2)?);
+        for value in values {
+            pdu.extend_from_slice(&value.to_be_bytes());
+        }
+
+        let response = self.request(&pdu).await?;
+        if response.get(1..5) != Some(&pdu[1..5]) {
+            bail!("write to {address} was not confirmed");
+        }
+        Ok(())
+    }
+
+    /// Send one request PDU and return the response PDU
+    async fn request(&self, pdu: &[u8]) -> Result<Vec<u8>> {
+        let mut connection = self.connection.lock().await;
+        let result = tokio::time::timeout(self.timeout, self.exchange(&mut connection, pdu))
+            .await
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("timed out after {:?}", self.timeout)));
+        if result.is_err() {
+            // The stream may hold a late response; start over on the next request
+            connection.stream = None;
+        }
+        result.with_context(|| format!("Modbus request to {} failed", self.addr))
+    }
+
+    async fn exchange(&self, connection: &mut Connection, pdu: &[u8]) -> Result<Vec<u8>> {
+        if connection.stream.is_none() {
+            debug!("Connecting to Modbus TCP {}", self.addr);
+            let stream = TcpStream::connect(&self.addr).await?;
+            stream.set_nodelay(true)?;
+            connection.stream = Some(stream);
+        }
+        connection.transaction_id = connection.transaction_id.wrapping_add(1);
+        let transaction_id = connection.transaction_id;
+        let stream = connection.stream.as_mut().context("not connected")?;
+
+        // MBAP header: transaction, protocol 0, length of unit id + PDU, unit id
+        let mut frame = Vec::with_capacity(7 + pdu.len());
+        frame.extend_from_slice(&transaction_id.to_be_bytes());
+        frame.extend_from_slice(&[0, 0]);
+        frame.extend_from_slice(&u16::try_from(pdu.len() + 1)?.to_be_bytes());
+        frame.push(self.unit_id);
+        frame.extend_from_slice(pdu);
+        stream.write_all(&frame).await?;
+
+        let mut header = [0_u8; 7];
+        stream.read_exact(&mut header).await?;
+        let length = usize::from(u16::from_be_bytes([header[4], header[5]]));
+        if length < 2 {
+            bail!("invalid response length {length}");
+        }
+        let mut response = vec![0_u8; length - 1];
+        stream.read_exact(&mut response).await?;
+
+        if u16::from_be_bytes([header[0], header[1]]) != transaction_id {
+            bail!("response to another transaction");
+        }
+        if response[0] == pdu[0] | 0x80 {
+            bail!(
+                "exception {} for function {:#04x}",
+                response.get(1).copied().unwrap_or_default(),
+                pdu[0]
+            );
+        }
+        if response[0] != pdu[0] {
+            bail!("unexpected function {:#04x} in response", response[0]);
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_device::FakeDevice;
+    use std::collections::HashMap;
+    use std::sync::atomic::Ordering;
+
+    async fn client(device: &std::sync::Arc<FakeDevice>) -> ModbusTcpClient {
+        let port = device.serve().await;
+        ModbusTcpClient::new(format!("127.0.0.1:{port}"), 1, Duration::from_secs(1))
+    }
+
+    #[tokio::test]
+    async fn test_long_reads_are_split_into_requests() {
+        let device = FakeDevice::new((0..300).map(|a| (a, a * 2)).collect());
+        let client = client(&device).await;
+
+        let registers = client.read_holding_registers(10, 260).await.unwrap();
+
+        assert_eq!(registers.len(), 260);
+        assert_eq!(registers[0], 20);
+        assert_eq!(registers[259], 538);
+    }
+
+    #[tokio::test]
+    async fn test_written_registers_read_back() {
+        let device = FakeDevice::new(HashMap::from([(100, 0), (101, 0), (102, 0)]));
+        let client = client(&device).await;
+
+        client.write_registers(100, &[7, 0xFFFF]).await.unwrap();
+
+        assert_eq!(
+            client.read_holding_registers(100, 3).await.unwrap(),
+            [7, 0xFFFF, 0]
+        );
+        assert_eq!(device.writes.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_exceptions_and_oversized_writes_fail() {
+        let device = FakeDevice::new(HashMap::from([(100, 1)]));
+        let client = client(&device).await;
+
+        // Illegal data address
+        let error = client.read_holding_registers(200, 1).await.unwrap_err();
+        assert!(format!("{error:#}").contains("exception 2"));
+        // The connection is set up again after the failure
+        assert_eq!(client.read_holding_registers(100, 1).await.unwrap(), [1]);
+
+        assert!(client.write_registers(100, &[]).await.is_err());
+        assert!(client.write_registers(100, &[0; 124]).await.is_err());
+        assert_eq!(device.writes.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_device_fails() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let client = ModbusTcpClient::new(addr.to_string(), 1, Duration::from_secs(1));
+
+        assert!(client.read_holding_registers(0, 1).await.is_err());
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use anyhow::Result;
+use async_trait::async_trait;
+use fluxion_core::{ExecutionBackend, InverterCommand, InverterDataSource, InverterOperationMode};
+use std::sync::Arc;
+
+/// Execution backend writing mode changes straight to a Modbus device
+///
+/// Wraps [`crate::ModbusInverterSource`] or [`crate::VictronGxSource`]. Unlike
+/// the failover source, mode changes never fall back to Home Assistant.
+pub struct ModbusExecutionBackend {
+    source: Arc<dyn InverterDataSource>,
+}
+
+impl ModbusExecutionBackend {
+    /// Apply mode changes through `source`
+    #[must_use]
+    pub fn new(source: Arc<dyn InverterDataSource>) -> Self {
+        Self { source }
+    }
+}
+
+impl std::fmt::Debug for ModbusExecutionBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ModbusExecutionBackend")
+            .field("source", &self.source.name())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl ExecutionBackend for ModbusExecutionBackend {
+    async fn apply_mode(&self, inverter_id: &str, mode: InverterOperationMode) -> Result<()> {
+        self.source
+            .write_command(inverter_id, &InverterCommand::SetMode(mode))
+            .await
+    }
+
+    fn name(&self) -> &'static str {
+        "Modbus"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_device::{STORAGE_ADDRESS, modbus_source, raw, sunspec_device};
+    use crate::sunspec::{CHARGE_LIMIT, DISCHARGE_LIMIT, storage};
+    use std::sync::atomic::Ordering;
+
+    /// Storage control registers after a mode change (rates with scale factor -2)
+    #[derive(Debug, PartialEq)]
+    struct Registers {
+        limits: u16,
+        discharge_rate: u16,
+        charge_rate: u16,
+        grid_charging: u16,
+    }
+
+    #[tokio::test]
+    async fn test_every_mode_is_written_to_storage_control() {
+        let cases = [
+            (
+                InverterOperationMode::ForceCharge,
+                Registers {
+                    limits: DISCHARGE_LIMIT,
+                    discharge_rate: raw(-10000),
+                    charge_rate: 10000,
+                    grid_charging: 1,
+                },
+            ),
+            (
+                InverterOperationMode::ForceDischarge,
+                Registers {
+                    limits: CHARGE_LIMIT,
+                    discharge_rate: 10000,
+                    charge_rate: raw(-10000),
+                    grid_charging: 0,
+                },
+            ),
+            (
+                InverterOperationMode::BackUpMode,
+                Registers {
+                    limits: DISCHARGE_LIMIT,
+                    discharge_rate: 0,
+                    charge_rate: 10000,
+                    grid_charging: 0,
+                },
+            ),
+            (
+                InverterOperationMode::NoChargeNoDischarge,
+                Registers {
+                    limits: CHARGE_LIMIT | DISCHARGE_LIMIT,
+                    discharge_rate: 0,
+                    charge_rate: 0,
+                    grid_charging: 0,
+                },
+            ),
+        ];
+
+        for (mode, expected) in cases {
+            let device = sunspec_device(true);
+            let backend =
+                ModbusExecutionBackend::new(Arc::new(modbus_source(&device, false).await));
+
+            backend.apply_mode("inv1", mode).await.unwrap();
+
+            let register =
+                |offset: usize| device.register(STORAGE_ADDRESS + u16::try_from(offset).unwrap());
+            let written = Registers {
+                limits: register(storage::STOR_CTL_MOD),
+                discharge_rate: register(storage::OUT_W_RTE),
+                charge_rate: register(storage::IN_W_RTE),
+                grid_charging: register(storage::CHA_GRI_SET),
+            };
+            assert_eq!(written, expected, "{mode:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_current_mode_and_debug_mode_write_nothing() {
+        // The fake device starts out in self-use
+        let device = sunspec_device(true);
+        let backend = ModbusExecutionBackend::new(Arc::new(modbus_source(&device, false).await));
+        backend
+            .apply_mode("inv1", InverterOperationMode::SelfUse)
+            .await
+            .unwrap();
+        assert_eq!(device.writes.load(Ordering::SeqCst), 0);
+
+        let device = sunspec_device(true);
+        let backend = ModbusExecutionBackend::new(Arc::new(modbus_source(&device, true).await));
+        backend
+            .apply_mode("inv1", InverterOperationMode::ForceCharge)
+            .await
+            .unwrap();
+        assert_eq!(device.writes.load(Ordering::SeqCst), 0);
+        assert_eq!(backend.name(), "Modbus");
+    }
+
+    #[tokio::test]
+    async fn test_unknown_inverter_is_an_error() {
+        let device = sunspec_device(true);
+        let backend = ModbusExecutionBackend::new(Arc::new(modbus_source(&device, false).await));
+
+        assert!(
+            backend
+                .apply_mode("inv2", InverterOperationMode::ForceCharge)
+                .await
+                .is_err()
+        );
+    }
+}
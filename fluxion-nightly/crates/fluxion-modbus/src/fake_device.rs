@@ -0,0 +1,168 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! In-process Modbus TCP server for tests.
+
+use crate::{ModbusConfig, ModbusDeviceConfig, ModbusInverterSource};
+use fluxion_core::SharedDebugMode;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// In-process Modbus TCP server over a register map
+#[derive(Default)]
+pub(crate) struct FakeDevice {
+    registers: Mutex<HashMap<u16, u16>>,
+    /// Write requests served so far
+    pub(crate) writes: AtomicUsize,
+}
+
+impl FakeDevice {
+    pub(crate) fn new(registers: HashMap<u16, u16>) -> Arc<Self> {
+        Arc::new(Self {
+            registers: Mutex::new(registers),
+            writes: AtomicUsize::new(0),
+        })
+    }
+
+    pub(crate) fn register(&self, address: u16) -> u16 {
+        self.registers.lock()[&address]
+    }
+
+    fn handle(&self, pdu: &[u8]) -> Vec<u8> {
+        let address = u16::from_be_bytes([pdu[1], pdu[2]]);
+        let count = u16::from_be_bytes([pdu[3], pdu[4]]);
+        let range = address..address.saturating_add(count);
+        let mut registers = self.registers.lock();
+        if !range.clone().all(|a| registers.contains_key(&a)) {
+            return vec![pdu[0] | 0x80, 2];
+        }
+        match pdu[0] {
+            0x03 => {
+                let mut response = vec![0x03, u8::try_from(count * 2).unwrap()];
+                for a in range {
+                    response.extend_from_slice(&registers[&a].to_be_bytes());
+                }
+                response
+            }
+            0x10 => {
+                for (a, value) in range.zip(pdu[6..].as_chunks::<2>().0) {
+                    registers.insert(a, u16::from_be_bytes(*value));
+                }
+                self.writes.fetch_add(1, Ordering::SeqCst);
+                pdu[..5].to_vec()
+            }
+            _ => vec![pdu[0] | 0x80, 1],
+        }
+    }
+
+    /// Serve on a free local port and return it
+    pub(crate) async fn serve(self: &Arc<Self>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let device = Arc::clone(self);
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let device = Arc::clone(&device);
+                tokio::spawn(async move {
+                    let mut header = [0_u8; 7];
+                    while stream.read_exact(&mut header).await.is_ok() {
+                        let length = usize::from(u16::from_be_bytes([header[4], header[5]]));
+                        let mut pdu = vec![0_u8; length - 1];
+                        if stream.read_exact(&mut pdu).await.is_err() {
+                            return;
+                        }
+                        let response = device.handle(&pdu);
+                        let mut frame = header[..4].to_vec();
+                        frame.extend_from_slice(
+                            &u16::try_from(response.len() + 1).unwrap().to_be_bytes(),
+                        );
+                        frame.push(header[6]);
+                        frame.extend_from_slice(&response);
+                        stream.write_all(&frame).await.unwrap();
+                    }
+                });
+            }
+        });
+        port
+    }
+}
+
+/// Raw register value of a signed 16-bit number
+pub(crate) fn raw(value: i16) -> u16 {
+    u16::from_be_bytes(value.to_be_bytes())
+}
+
+/// First data register of the storage model of [`sunspec_device`]
+pub(crate) const STORAGE_ADDRESS: u16 = 40124;
+
+/// Common model, three-phase inverter, storage (optional) and meter at 40000
+pub(crate) fn sunspec_device(with_storage: bool) -> Arc<FakeDevice> {
+    let mut inverter = vec![0; 50];
+    inverter[12] = 3000; // W
+    inverter[14] = 5000; // Hz
+    inverter[15] = raw(-2);
+    inverter[29] = 5000; // DCW
+    inverter[36] = 4; // St: MPPT
+
+    let mut storage = vec![0; 24];
+    storage[0] = 5000; // WChaMax
+    storage[6] = 6500; // ChaState
+    storage[10] = 10000; // OutWRte
+    storage[11] = 10000; // InWRte
+    storage[20] = raw(-2);
+    storage[23] = raw(-2);
+
+    let mut meter = vec![0; 105];
+    meter[16] = raw(-1000); // W: exporting
+
+    let mut models = vec![(1, vec![0; 66]), (103, inverter)];
+    if with_storage {
+        models.push((124, storage));
+    }
+    models.push((203, meter));
+
+    let mut registers = HashMap::from([(40000, 0x5375), (40001, 0x6e53)]);
+    let mut address = 40002;
+    for (id, data) in models {
+        registers.insert(address, id);
+        registers.insert(address + 1, u16::try_from(data.len()).unwrap());
+        for (offset, value) in (address + 2..).zip(data) {
+            registers.insert(offset, value);
+        }
+        address = *registers.keys().max().unwrap() + 1;
+    }
+    registers.insert(address, 0xFFFF);
+    registers.insert(address + 1, 0);
+    FakeDevice::new(registers)
+}
+
+/// Source polling `device` as inverter `inv1`
+pub(crate) async fn modbus_source(
+    device: &Arc<FakeDevice>,
+    debug_mode: bool,
+) -> ModbusInverterSource {
+    let config = ModbusConfig {
+        enabled: true,
+        devices: vec![ModbusDeviceConfig {
+            inverter_id: "inv1".to_owned(),
+            host: "127.0.0.1".to_owned(),
+            port: device.serve().await,
+            unit_id: 1,
+        }],
+        ..ModbusConfig::default()
+    };
+    ModbusInverterSource::new(&config, SharedDebugMode::new(debug_mode))
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Direct Modbus TCP access to SunSpec-compatible inverters.
+//!
+//! [`ModbusInverterSource`] implements [`fluxion_core::InverterDataSource`] without Home
+//! Assistant: it discovers the SunSpec models of each device, polls them for telemetry and
+//! executes mode changes through the storage model (124) control registers.
+//!
+//! Writes are guarded: nothing is written while debug mode is on, only the storage control
+//! registers are ever touched, values are range-checked against the device scale factors
+//! and every mode change is read back to confirm it was applied.
+//!
+//! [`VictronGxSource`] drives Victron ESS systems through a GX device the same way, with
+//! modes expressed as grid setpoints instead of storage control modes.
+
+mod client;
+mod execution;
+#[cfg(test)]
+mod fake_device;
+mod source;
+pub mod sunspec;
+mod victron;
+
+pub use client::ModbusTcpClient;
+pub use execution::ModbusExecutionBackend;
+pub use source::ModbusInverterSource;
+pub use victron::{VictronConfig, VictronGxSource};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Modbus TCP adapter settings (`[modbus]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModbusConfig {
+    pub enabled: bool,
+    pub devices: Vec<ModbusDeviceConfig>,
+    /// How long a poll result is reused before the device is read again
+    pub poll_interval_secs: u64,
+    /// Timeout of one Modbus request
+    pub timeout_ms: u64,
+    /// Set when the meter reports export as positive power instead of the SunSpec
+    /// convention (positive = import)
+    pub meter_export_positive: bool,
+}
+
+impl Default for ModbusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            devices: Vec::new(),
+            poll_interval_secs: 5,
+            timeout_ms: 3000,
+            meter_export_positive: false,
+        }
+    }
+}
+
+/// One SunSpec device, mapped to a configured inverter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModbusDeviceConfig {
+    /// ID of the inverter in `[[inverters]]` this device serves
+    pub inverter_id: String,
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    #[serde(default = "default_unit_id")]
+    pub unit_id: u8,
+}
+
+fn default_port() -> u16 {
+    502
+}
+
+fn default_unit_id() -> u8 {
+    1
+}
+
+impl ModbusConfig {
+    /// Check the settings
+    ///
+    /// # Errors
+    /// Describes the first invalid setting
+    pub fn validate(&self) -> Result<(), String> {
+        if self.devices.is_empty() {
+            return Err("at least one device is required".to_owned());
+        }
+        let mut ids = HashSet::new();
+        for device in &self.devices {
+            if device.inverter_id.is_empty() {
+                return Err("device inverter_id cannot be empty".to_owned());
+            }
+            if !ids.insert(device.inverter_id.as_str()) {
+                return Err(format!(
+                    "inverter '{}' has more than one device",
+                    device.inverter_id
+                ));
+            }
+            if device.host.trim().is_empty() {
+                return Err(format!("device '{}' has no host", device.inverter_id));
+            }
+            if device.port == 0 {
+                return Err(format!("device '{}' has port 0", device.inverter_id));
+            }
+        }
+        if !(1..=3600).contains(&self.poll_interval_secs) {
+            return Err("poll_interval_secs must be between 1 and 3600".to_owned());
+        }
+        if !(100..=60_000).contains(&self.timeout_ms) {
+            return Err("timeout_ms must be between 100 and 60000".to_owned());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,412 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! [`InverterDataSource`] over Modbus TCP.
+
+use crate::client::ModbusTcpClient;
+use crate::sunspec::{
+    self, InverterReading, ModelBlock, StorageControl, StorageReading, SunSpecMap, storage,
+};
+use crate::{ModbusConfig, ModbusDeviceConfig};
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use fluxion_core::{
+    GenericInverterState, InverterCommand, InverterDataSource, InverterOperationMode,
+    SharedDebugMode,
+};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Force-charge power when no charge power limit was commanded (% of `WChaMax`)
+const DEFAULT_CHARGE_RATE_PCT: f32 = 100.0;
+
+/// Lowest force-charge power; 0% would leave the battery idle instead of charging
+const MIN_CHARGE_RATE_PCT: f32 = 1.0;
+
+/// Rate difference treated as equal when comparing read-back values (%)
+const RATE_TOLERANCE_PCT: f32 = 0.5;
+
+#[derive(Debug)]
+struct Device {
+    client: ModbusTcpClient,
+    /// Discovered on first use
+    map: tokio::sync::Mutex<Option<SunSpecMap>>,
+    last_poll: Mutex<Option<(Instant, GenericInverterState)>>,
+    charge_rate_pct: Mutex<f32>,
+}
+
+impl Device {
+    fn new(config: &ModbusDeviceConfig, timeout: Duration) -> Self {
+        Self {
+            client: ModbusTcpClient::new(
+                format!("{}:{}", config.host, config.port),
+                config.unit_id,
+                timeout,
+            ),
+            map: tokio::sync::Mutex::default(),
+            last_poll: Mutex::default(),
+            charge_rate_pct: Mutex::new(DEFAULT_CHARGE_RATE_PCT),
+        }
+    }
+
+    async fn map(&self) -> Result<SunSpecMap> {
+        let mut map = self.map.lock().await;
+        if let Some(found) = *map {
+            return Ok(found);
+        }
+        let found = sunspec::discover(&self.client).await?;
+        info!(
+            "🔌 SunSpec models found: inverter {:?}, storage {:?}, MPPT {:?}, meter {:?}",
+            found.inverter.map(|b| b.id),
+            found.storage.map(|b| b.id),
+            found.mppt.map(|b| b.id),
+            found.meter.map(|b| b.id)
+        );
+        *map = Some(found);
+        Ok(found)
+    }
+
+    async fn storage(&self, inverter_id: &str) -> Result<(ModelBlock, StorageReading)> {
+        let block = self.map().await?.storage.with_context(|| {
+            format!("{inverter_id} has no SunSpec storage model, cannot control the battery")
+        })?;
+        let registers = block.read(&self.client, storage::LENGTH).await?;
+        Ok((block, StorageReading::parse(&registers)))
+    }
+}
+
+/// Inverter data source talking SunSpec over Modbus TCP
+#[derive(Debug)]
+pub struct ModbusInverterSource {
+    devices: HashMap<String, Device>,
+    poll_interval: Duration,
+    meter_export_positive: bool,
+    debug_mode: SharedDebugMode,
+}
+
+impl ModbusInverterSource {
+    /// One connection per configured device; writes are skipped while `debug_mode` is on
+    #[must_use]
+    pub fn new(config: &ModbusConfig, debug_mode: SharedDebugMode) -> Self {
+        let timeout = Duration::from_millis(config.timeout_ms);
+        Self {
+            devices: config
+                .devices
+                .iter()
+                .map(|d| (d.inverter_id.clone(), Device::new(d, timeout)))
+                .collect(),
+            poll_interval: Duration::from_secs(config.poll_interval_secs),
+            meter_export_positive: config.meter_export_positive,
+            debug_mode,
+        }
+    }
+
+    fn device(&self, inverter_id: &str) -> Result<&Device> {
+        self.devices
+            .get(inverter_id)
+            .with_context(|| format!("no Modbus device configured for {inverter_id}"))
+    }
+
+    async fn poll(&self, inverter_id: &str, device: &Device) -> Result<GenericInverterState> {
+        let map = device.map().await?;
+        let client = &device.client;
+
+        let inverter = match map.inverter {
+            Some(block) => Some(InverterReading::parse(&block.read(client, 0).await?)),
+            None => None,
+        };
+        let storage = match map.storage {
+            Some(block) => StorageReading::parse(&block.read(client, storage::LENGTH).await?),
+            None => StorageReading::default(),
+        };
+        let meter_w = match map.meter {
+            Some(block) => sunspec::meter_power_w(&block.read(client, 0).await?),
+            None => None,
+        };
+        let modules = match map.mppt {
+            Some(block) => sunspec::mppt_modules(&block.read(client, 0).await?),
+            None => Vec::new(),
+        };
+
+        let battery_soc = storage
+            .soc_percent
+            .with_context(|| format!("{inverter_id} reports no battery state of charge"))?;
+        let inverter = inverter.unwrap_or_default();
+
+        // Battery modules show up as MPPT modules labelled "StPwr"/"StCha"/... on hybrids
+        let pv_modules: Vec<Option<f32>> = modules
+            .iter()
+            .filter(|(label, _)| !label.starts_with("St"))
+            .map(|(_, w)| *w)
+            .collect();
+        let pv_power_w = if pv_modules.is_empty() {
+            inverter.dc_power_w
+        } else {
+            Some(pv_modules.iter().flatten().sum())
+        };
+
+        // SunSpec meters report import as positive; FluxION uses positive = export
+        let grid_power_w = meter_w.map(|w| if self.meter_export_positive { w } else { -w });
+        let battery_power_w = pv_power_w.zip(inverter.ac_power_w).map(|(pv, ac)| pv - ac);
+        let house_load_w = inverter
+            .ac_power_w
+            .zip(grid_power_w)
+            .map(|(ac, grid)| (ac - grid).max(0.0));
+
+        Ok(GenericInverterState {
+            inverter_id: inverter_id.to_owned(),
+            battery_soc,
+            work_mode: storage.control.mode(),
+            grid_power_w: grid_power_w.unwrap_or_default(),
+            battery_power_w: battery_power_w.unwrap_or_default(),
+            pv_power_w: pv_power_w.unwrap_or_default().max(0.0),
+            online: map.inverter.is_none() || inverter.online(),
+            house_load_w,
+            grid_import_w: grid_power_w.map(|w| (-w).max(0.0)),
+            grid_export_w: grid_power_w.map(|w| w.max(0.0)),
+            inverter_frequency_hz: inverter.frequency_hz,
+            inverter_voltage_v: inverter.voltage_v,
+            inverter_current_a: inverter.current_a,
+            inverter_power_w: inverter.ac_power_w,
+            pv1_power_w: pv_modules.first().copied().flatten(),
+            pv2_power_w: pv_modules.get(1).copied().flatten(),
+            pv3_power_w: pv_modules.get(2).copied().flatten(),
+            pv4_power_w: pv_modules.get(3).copied().flatten(),
+            battery_voltage_v: storage.battery_voltage_v,
+            total_yield_kwh: inverter.energy_total_kwh,
+            inverter_temperature_c: inverter.temperature_c,
+            ..GenericInverterState::default()
+        })
+    }
+
+    async fn set_mode(
+        &self,
+        inverter_id: &str,
+        device: &Device,
+        mode: InverterOperationMode,
+    ) -> Result<()> {
+        let target = StorageControl::for_mode(mode, *device.charge_rate_pct.lock());
+        if self.debug_mode.is_enabled() {
+            info!("🔍 DEBUG MODE: Would set {inverter_id} to {mode:?} over Modbus ({target:?})");
+            return Ok(());
+        }
+
+        let (block, current) = device.storage(inverter_id).await?;
+        if matches(&current.control, &target) {
+            debug!("{inverter_id} is already in {mode:?}");
+            return Ok(());
+        }
+        let sf = current
+            .rate_sf
+            .with_context(|| format!("{inverter_id} reports no charge rate scale factor"))?;
+        let rates = [
+            sunspec::encode_rate(target.discharge_rate_pct, sf)?,
+            sunspec::encode_rate(target.charge_rate_pct, sf)?,
+        ];
+
+        // Rates and grid charging first, so enabling the limits applies the new values at once
+        let client = &device.client;
+        client
+            .write_registers(block.register(storage::OUT_W_RTE), &rates)
+            .await?;
+        client
+            .write_registers(
+                block.register(storage::CHA_GRI_SET),
+                &[u16::from(target.grid_charging)],
+            )
+            .await?;
+        client
+            .write_registers(block.register(storage::STOR_CTL_MOD), &[target.limits])
+            .await?;
+        *device.last_poll.lock() = None;
+
+        let (_, applied) = device.storage(inverter_id).await?;
+        if !matches(&applied.control, &target) {
+            bail!(
+                "{inverter_id} did not apply {mode:?}: expected {target:?}, read back {:?}",
+                applied.control
+            );
+        }
+        info!("✅ {inverter_id} set to {mode:?} over Modbus");
+        Ok(())
+    }
+
+    async fn set_charge_power(&self, inverter_id: &str, device: &Device, watts: u32) -> Result<()> {
+        let (_, current) = device.storage(inverter_id).await?;
+        let max_w = current
+            .max_charge_w
+            .filter(|w| *w > 0.0)
+            .with_context(|| format!("{inverter_id} reports no maximum charge power"))?;
+        #[expect(
+            clippy::cast_precision_loss,
+            reason = "charge power is far below f32 precision limits"
+        )]
+        let pct = (watts as f32 / max_w * 100.0).clamp(MIN_CHARGE_RATE_PCT, 100.0);
+        *device.charge_rate_pct.lock() = pct;
+
+        // Takes effect on the next force charge, or now when already force charging
+        if current.control.mode() == InverterOperationMode::ForceCharge {
+            self.set_mode(inverter_id, device, InverterOperationMode::ForceCharge)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether read-back control values equal the target, within register rounding
+fn matches(actual: &StorageControl, target: &StorageControl) -> bool {
+    actual.limits == target.limits
+        && actual.grid_charging == target.grid_charging
+        && (actual.discharge_rate_pct - target.discharge_rate_pct).abs() < RATE_TOLERANCE_PCT
+        && (actual.charge_rate_pct - target.charge_rate_pct).abs() < RATE_TOLERANCE_PCT
+}
+
+#[async_trait]
+impl InverterDataSource for ModbusInverterSource {
+    async fn read_state(&self, inverter_id: &str) -> Result<GenericInverterState> {
+        let device = self.device(inverter_id)?;
+        if let Some((at, state)) = device.last_poll.lock().as_ref()
+            && at.elapsed() < self.poll_interval
+        {
+            return Ok(state.clone());
+        }
+
+        let state = self.poll(inverter_id, device).await?;
+        *device.last_poll.lock() = Some((Instant::now(), state.clone()));
+        Ok(state)
+    }
+
+    async fn write_command(&self, inverter_id: &str, command: &InverterCommand) -> Result<()> {
+        let device = self.device(inverter_id)?;
+        match command {
+            InverterCommand::SetMode(mode) => self.set_mode(inverter_id, device, *mode).await,
+            InverterCommand::SetChargePowerLimit(watts) => {
+                self.set_charge_power(inverter_id, device, *watts).await
+            }
+            InverterCommand::SetExportLimit(_) => {
+                bail!("export limits are not supported over SunSpec storage control")
+            }
+        }
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        for (inverter_id, device) in &self.devices {
+            if let Err(e) = device.map().await {
+                warn!("⚠️ Modbus device for {inverter_id} unreachable: {e:#}");
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn name(&self) -> &'static str {
+        "modbus"
+    }
+
+    fn supports_charge_power_limit(&self, inverter_id: &str) -> bool {
+        self.devices.contains_key(inverter_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_device::{STORAGE_ADDRESS, modbus_source, raw, sunspec_device};
+    use std::sync::atomic::Ordering;
+
+    #[tokio::test]
+    async fn test_read_state_decodes_sunspec_models() {
+        let device = sunspec_device(true);
+        let source = modbus_source(&device, false).await;
+
+        let state = source.read_state("inv1").await.unwrap();
+        assert!((state.battery_soc - 65.0).abs() < 0.01);
+        assert_eq!(state.work_mode, InverterOperationMode::SelfUse);
+        assert!((state.pv_power_w - 5000.0).abs() < 0.01);
+        assert!((state.grid_power_w - 1000.0).abs() < 0.01);
+        assert!((state.battery_power_w - 2000.0).abs() < 0.01);
+        assert_eq!(state.house_load_w, Some(2000.0));
+        assert_eq!(state.inverter_frequency_hz, Some(50.0));
+        assert!(state.online);
+
+        assert!(source.read_state("inv2").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_force_charge_writes_storage_control_and_reads_back() {
+        let device = sunspec_device(true);
+        let source = modbus_source(&device, false).await;
+        source.read_state("inv1").await.unwrap();
+
+        source
+            .write_command("inv1", &InverterCommand::SetChargePowerLimit(2500))
+            .await
+            .unwrap();
+        source
+            .write_command(
+                "inv1",
+                &InverterCommand::SetMode(InverterOperationMode::ForceCharge),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            device.register(STORAGE_ADDRESS + 3),
+            sunspec::DISCHARGE_LIMIT
+        );
+        assert_eq!(device.register(STORAGE_ADDRESS + 10), raw(-5000));
+        assert_eq!(device.register(STORAGE_ADDRESS + 15), 1);
+        // The cached poll is dropped after a write
+        let state = source.read_state("inv1").await.unwrap();
+        assert_eq!(state.work_mode, InverterOperationMode::ForceCharge);
+
+        // Already applied: nothing more is written
+        let writes = device.writes.load(Ordering::SeqCst);
+        source
+            .write_command(
+                "inv1",
+                &InverterCommand::SetMode(InverterOperationMode::ForceCharge),
+            )
+            .await
+            .unwrap();
+        assert_eq!(device.writes.load(Ordering::SeqCst), writes);
+    }
+
+    #[tokio::test]
+    async fn test_debug_mode_and_missing_storage_never_write() {
+        let device = sunspec_device(true);
+        let source = modbus_source(&device, true).await;
+        source
+            .write_command(
+                "inv1",
+                &InverterCommand::SetMode(InverterOperationMode::ForceDischarge),
+            )
+            .await
+            .unwrap();
+        assert_eq!(device.writes.load(Ordering::SeqCst), 0);
+
+        let device = sunspec_device(false);
+        let source = modbus_source(&device, false).await;
+        assert!(
+            source
+                .write_command(
+                    "inv1",
+                    &InverterCommand::SetMode(InverterOperationMode::ForceDischarge),
+                )
+                .await
+                .is_err()
+        );
+        assert_eq!(device.writes.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,470 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! SunSpec register maps.
+//!
+//! A SunSpec device starts with the `SunS` marker, followed by a chain of models,
+//! each an ID register, a length register and `length` data registers. Offsets
+//! below are relative to the first data register of a model.
+
+use crate::client::ModbusTcpClient;
+use anyhow::{Context, Result, bail};
+use fluxion_core::InverterOperationMode;
+
+/// Addresses the SunSpec marker is looked for at, most common first
+pub const BASE_ADDRESSES: [u16; 3] = [40000, 0, 50000];
+
+/// "SunS"
+const SUNS_MARKER: [u16; 2] = [0x5375, 0x6e53];
+
+/// Model ID ending the chain
+const END_MODEL: u16 = 0xFFFF;
+
+/// Longest model chain followed before giving up
+const MAX_MODELS: usize = 64;
+
+pub const MODEL_INVERTER_SINGLE_PHASE: u16 = 101;
+pub const MODEL_INVERTER_THREE_PHASE: u16 = 103;
+pub const MODEL_STORAGE: u16 = 124;
+pub const MODEL_MPPT: u16 = 160;
+pub const MODEL_METER_FIRST: u16 = 201;
+pub const MODEL_METER_LAST: u16 = 204;
+
+/// Inverter models 101-103
+mod inverter {
+    pub const A: usize = 0;
+    pub const A_SF: usize = 4;
+    pub const PHV_PHA: usize = 8;
+    pub const V_SF: usize = 11;
+    pub const W: usize = 12;
+    pub const W_SF: usize = 13;
+    pub const HZ: usize = 14;
+    pub const HZ_SF: usize = 15;
+    pub const WH: usize = 22;
+    pub const WH_SF: usize = 24;
+    pub const DCW: usize = 29;
+    pub const DCW_SF: usize = 30;
+    pub const TMP_CAB: usize = 31;
+    pub const TMP_SF: usize = 35;
+    pub const ST: usize = 36;
+    pub const LENGTH: usize = 50;
+}
+
+/// Storage model 124
+pub mod storage {
+    pub const W_CHA_MAX: usize = 0;
+    pub const STOR_CTL_MOD: usize = 3;
+    pub const CHA_STATE: usize = 6;
+    pub const IN_BAT_V: usize = 8;
+    pub const OUT_W_RTE: usize = 10;
+    pub const IN_W_RTE: usize = 11;
+    pub const CHA_GRI_SET: usize = 15;
+    pub const W_CHA_MAX_SF: usize = 16;
+    pub const CHA_STATE_SF: usize = 20;
+    pub const IN_BAT_V_SF: usize = 22;
+    pub const IN_OUT_W_RTE_SF: usize = 23;
+    pub const LENGTH: usize = 24;
+}
+
+/// Meter models 201-204
+mod meter {
+    pub const W: usize = 16;
+    pub const W_SF: usize = 20;
+    pub const LENGTH: usize = 21;
+}
+
+/// MPPT model 160: fixed part, then one block per module
+mod mppt {
+    pub const DCW_SF: usize = 2;
+    pub const N: usize = 6;
+    pub const MODULES: usize = 8;
+    pub const MODULE_LENGTH: usize = 20;
+    pub const MODULE_ID_STR: usize = 1;
+    pub const MODULE_ID_STR_LENGTH: usize = 8;
+    pub const MODULE_DCW: usize = 11;
+}
+
+/// Operating state (`St`) values of an inverter that is switched off or faulted
+const ST_OFF: u16 = 1;
+const ST_FAULT: u16 = 7;
+
+/// `StorCtl_Mod` bit enabling the charge rate limit (`InWRte`)
+pub const CHARGE_LIMIT: u16 = 1;
+/// `StorCtl_Mod` bit enabling the discharge rate limit (`OutWRte`)
+pub const DISCHARGE_LIMIT: u16 = 2;
+
+/// Data registers of one model
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelBlock {
+    pub id: u16,
+    pub address: u16,
+    pub length: u16,
+}
+
+/// Models FluxION uses, as found on the device
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SunSpecMap {
+    pub inverter: Option<ModelBlock>,
+    pub storage: Option<ModelBlock>,
+    pub mppt: Option<ModelBlock>,
+    pub meter: Option<ModelBlock>,
+}
+
+/// Find the SunSpec marker and walk the model chain
+pub async fn discover(client: &ModbusTcpClient) -> Result<SunSpecMap> {
+    for base in BASE_ADDRESSES {
+        // Devices answer unmapped addresses with an exception, so try the next base
+        if client.read_holding_registers(base, 2).await.ok().as_deref() != Some(&SUNS_MARKER) {
+            continue;
+        }
+
+        let mut map = SunSpecMap::default();
+        let mut address = base + 2;
+        for _ in 0..MAX_MODELS {
+            let header = client.read_holding_registers(address, 2).await?;
+            let (id, length) = (header[0], header[1]);
+            if id == END_MODEL {
+                return Ok(map);
+            }
+            let block = ModelBlock {
+                id,
+                address: address + 2,
+                length,
+            };
+            let slot = match id {
+                MODEL_INVERTER_SINGLE_PHASE..=MODEL_INVERTER_THREE_PHASE => &mut map.inverter,
+                MODEL_STORAGE => &mut map.storage,
+                MODEL_MPPT => &mut map.mppt,
+                MODEL_METER_FIRST..=MODEL_METER_LAST => &mut map.meter,
+                _ => &mut None,
+            };
+            slot.get_or_insert(block);
+            address = address
+                .checked_add(2 + length)
+                .context("SunSpec model chain runs past the register space")?;
+        }
+        bail!("SunSpec model chain at {base} has no end marker");
+    }
+    bail!("no SunSpec device found (looked at {BASE_ADDRESSES:?})")
+}
+
+impl ModelBlock {
+    /// Read all data registers, failing if the model is shorter than `min_length`
+    pub async fn read(&self, client: &ModbusTcpClient, min_length: usize) -> Result<Vec<u16>> {
+        if usize::from(self.length) < min_length {
+            bail!(
+                "model {} has {} registers, expected at least {min_length}",
+                self.id,
+                self.length
+            );
+        }
+        client
+            .read_holding_registers(self.address, self.length)
+            .await
+    }
+
+    /// Address of the register at `offset`
+    #[must_use]
+    pub fn register(&self, offset: usize) -> u16 {
+        self.address + u16::try_from(offset).unwrap_or(u16::MAX)
+    }
+}
+
+// ==================== Values ====================
+
+fn int16(raw: u16) -> Option<f32> {
+    let value = i16::from_be_bytes(raw.to_be_bytes());
+    (value != i16::MIN).then(|| f32::from(value))
+}
+
+fn uint16(raw: u16) -> Option<f32> {
+    (raw != u16::MAX).then(|| f32::from(raw))
+}
+
+fn acc32(high: u16, low: u16) -> Option<f32> {
+    (high != 0 || low != 0).then(|| f32::from(high) * 65536.0 + f32::from(low))
+}
+
+/// Scale factor register, None when not implemented or implausible
+fn scale_factor(raw: u16) -> Option<i32> {
+    let value = i16::from_be_bytes(raw.to_be_bytes());
+    (-10..=10).contains(&value).then(|| i32::from(value))
+}
+
+fn scaled(value: Option<f32>, sf: u16) -> Option<f32> {
+    Some(value? * 10_f32.powi(scale_factor(sf)?))
+}
+
+// ==================== Readings ====================
+
+/// Inverter model values
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct InverterReading {
+    /// AC output power (W)
+    pub ac_power_w: Option<f32>,
+    pub current_a: Option<f32>,
+    pub voltage_v: Option<f32>,
+    pub frequency_hz: Option<f32>,
+    /// Lifetime AC energy (kWh)
+    pub energy_total_kwh: Option<f32>,
+    pub dc_power_w: Option<f32>,
+    pub temperature_c: Option<f32>,
+    /// Operating state (`St`)
+    pub state: Option<u16>,
+}
+
+impl InverterReading {
+    #[must_use]
+    pub fn parse(r: &[u16]) -> Self {
+        use inverter::{
+            A, A_SF, DCW, DCW_SF, HZ, HZ_SF, LENGTH, PHV_PHA, ST, TMP_CAB, TMP_SF, V_SF, W, W_SF,
+            WH, WH_SF,
+        };
+        if r.len() < LENGTH {
+            return Self::default();
+        }
+        Self {
+            ac_power_w: scaled(int16(r[W]), r[W_SF]),
+            current_a: scaled(uint16(r[A]), r[A_SF]),
+            voltage_v: scaled(uint16(r[PHV_PHA]), r[V_SF]),
+            frequency_hz: scaled(uint16(r[HZ]), r[HZ_SF]),
+            energy_total_kwh: scaled(acc32(r[WH], r[WH + 1]), r[WH_SF]).map(|wh| wh / 1000.0),
+            dc_power_w: scaled(int16(r[DCW]), r[DCW_SF]),
+            temperature_c: scaled(int16(r[TMP_CAB]), r[TMP_SF]),
+            state: (r[ST] != u16::MAX).then_some(r[ST]),
+        }
+    }
+
+    /// Producing or ready; off and faulted inverters count as offline
+    #[must_use]
+    pub fn online(&self) -> bool {
+        self.state.is_some_and(|st| st != ST_OFF && st != ST_FAULT)
+    }
+}
+
+/// Storage model values
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StorageReading {
+    pub soc_percent: Option<f32>,
+    pub battery_voltage_v: Option<f32>,
+    /// Setpoint for maximum charge (W), the base of the rate percentages
+    pub max_charge_w: Option<f32>,
+    pub control: StorageControl,
+    /// Scale factor of `OutWRte`/`InWRte`
+    pub rate_sf: Option<i32>,
+}
+
+impl StorageReading {
+    #[must_use]
+    pub fn parse(r: &[u16]) -> Self {
+        use storage::{
+            CHA_GRI_SET, CHA_STATE, CHA_STATE_SF, IN_BAT_V, IN_BAT_V_SF, IN_OUT_W_RTE_SF, IN_W_RTE,
+            LENGTH, OUT_W_RTE, STOR_CTL_MOD, W_CHA_MAX, W_CHA_MAX_SF,
+        };
+        if r.len() < LENGTH {
+            return Self::default();
+        }
+        let rate = |raw| scaled(int16(raw), r[IN_OUT_W_RTE_SF]).unwrap_or(100.0);
+        Self {
+            soc_percent: scaled(uint16(r[CHA_STATE]), r[CHA_STATE_SF]),
+            battery_voltage_v: scaled(uint16(r[IN_BAT_V]), r[IN_BAT_V_SF]),
+            max_charge_w: scaled(uint16(r[W_CHA_MAX]), r[W_CHA_MAX_SF]),
+            control: StorageControl {
+                limits: r[STOR_CTL_MOD] & (CHARGE_LIMIT | DISCHARGE_LIMIT),
+                discharge_rate_pct: rate(r[OUT_W_RTE]),
+                charge_rate_pct: rate(r[IN_W_RTE]),
+                grid_charging: r[CHA_GRI_SET] == 1,
+            },
+            rate_sf: scale_factor(r[IN_OUT_W_RTE_SF]),
+        }
+    }
+}
+
+/// Meter model power, positive when importing from the grid (W)
+#[must_use]
+pub fn meter_power_w(r: &[u16]) -> Option<f32> {
+    if r.len() < meter::LENGTH {
+        return None;
+    }
+    scaled(int16(r[meter::W]), r[meter::W_SF])
+}
+
+/// DC power of each MPPT module with its label (W)
+#[must_use]
+pub fn mppt_modules(r: &[u16]) -> Vec<(String, Option<f32>)> {
+    use mppt::{
+        DCW_SF, MODULE_DCW, MODULE_ID_STR, MODULE_ID_STR_LENGTH, MODULE_LENGTH, MODULES, N,
+    };
+    let Some(&count) = r.get(N) else {
+        return Vec::new();
+    };
+    let (modules, _) = r
+        .get(MODULES..)
+        .unwrap_or_default()
+        .as_chunks::<MODULE_LENGTH>();
+    modules
+        .iter()
+        .take(usize::from(count))
+        .map(|module| {
+            let label: String = module[MODULE_ID_STR..MODULE_ID_STR + MODULE_ID_STR_LENGTH]
+                .iter()
+                .flat_map(|reg| reg.to_be_bytes())
+                .take_while(|&b| b != 0)
+                .map(char::from)
+                .collect();
+            (label, scaled(uint16(module[MODULE_DCW]), r[DCW_SF]))
+        })
+        .collect()
+}
+
+// ==================== Storage control ====================
+
+/// Battery control registers of the storage model
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StorageControl {
+    /// `StorCtl_Mod`: which of the rate limits are active
+    pub limits: u16,
+    /// `OutWRte`: discharge limit in % of the maximum; negative forces charging
+    pub discharge_rate_pct: f32,
+    /// `InWRte`: charge limit in % of the maximum; negative forces discharging
+    pub charge_rate_pct: f32,
+    /// `ChaGriSet`: charging from the grid allowed
+    pub grid_charging: bool,
+}
+
+impl Default for StorageControl {
+    fn default() -> Self {
+        Self::for_mode(InverterOperationMode::SelfUse, 100.0)
+    }
+}
+
+impl StorageControl {
+    /// Control values putting the battery into `mode`
+    ///
+    /// `charge_rate_pct` is the force-charge power in % of the maximum charge rate.
+    #[must_use]
+    pub fn for_mode(mode: InverterOperationMode, charge_rate_pct: f32) -> Self {
+        let (limits, discharge_rate_pct, charge_rate_pct, grid_charging) = match mode {
+            InverterOperationMode::SelfUse => (0, 100.0, 100.0, false),
+            // Keep the charge for outages, let PV top it up
+            InverterOperationMode::BackUpMode => (DISCHARGE_LIMIT, 0.0, 100.0, false),
+            InverterOperationMode::ForceCharge => (
+                DISCHARGE_LIMIT,
+                -charge_rate_pct.clamp(0.0, 100.0),
+                100.0,
+                true,
+            ),
+            InverterOperationMode::ForceDischarge => (CHARGE_LIMIT, 100.0, -100.0, false),
+            InverterOperationMode::NoChargeNoDischarge => {
+                (CHARGE_LIMIT | DISCHARGE_LIMIT, 0.0, 0.0, false)
+            }
+        };
+        Self {
+            limits,
+            discharge_rate_pct,
+            charge_rate_pct,
+            grid_charging,
+        }
+    }
+
+    /// Operation mode these values amount to
+    #[must_use]
+    pub fn mode(&self) -> InverterOperationMode {
+        let discharge_limited = self.limits & DISCHARGE_LIMIT != 0;
+        let charge_limited = self.limits & CHARGE_LIMIT != 0;
+        if discharge_limited && self.discharge_rate_pct < 0.0 {
+            InverterOperationMode::ForceCharge
+        } else if charge_limited && self.charge_rate_pct < 0.0 {
+            InverterOperationMode::ForceDischarge
+        } else if discharge_limited && self.discharge_rate_pct.abs() < 0.5 {
+            if charge_limited && self.charge_rate_pct.abs() < 0.5 {
+                InverterOperationMode::NoChargeNoDischarge
+            } else {
+                InverterOperationMode::BackUpMode
+            }
+        } else {
+            InverterOperationMode::SelfUse
+        }
+    }
+}
+
+/// Raw register value of a rate in %, refusing values the register cannot hold
+pub fn encode_rate(rate_pct: f32, sf: i32) -> Result<u16> {
+    let raw = (rate_pct / 10_f32.powi(sf)).round();
+    if !raw.is_finite() || raw <= f32::from(i16::MIN) || raw > f32::from(i16::MAX) {
+        bail!("rate {rate_pct}% does not fit the register with scale factor {sf}");
+    }
+    #[expect(clippy::cast_possible_truncation, reason = "range checked above")]
+    let value = raw as i16;
+    Ok(u16::from_be_bytes(value.to_be_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_device::raw;
+
+    #[test]
+    fn test_storage_control_round_trips_every_mode() {
+        for mode in [
+            InverterOperationMode::SelfUse,
+            InverterOperationMode::BackUpMode,
+            InverterOperationMode::ForceCharge,
+            InverterOperationMode::ForceDischarge,
+            InverterOperationMode::NoChargeNoDischarge,
+        ] {
+            assert_eq!(StorageControl::for_mode(mode, 50.0).mode(), mode);
+        }
+    }
+
+    #[test]
+    fn test_values_are_scaled_by_their_scale_factor() {
+        let cases = [
+            // (value, scale factor, expected)
+            (Some(5000.0), raw(-2), Some(50.0)),
+            (Some(5000.0), raw(0), Some(5000.0)),
+            (Some(12.0), raw(3), Some(12_000.0)),
+            (Some(-1000.0), raw(-1), Some(-100.0)),
+            (Some(1.0), raw(10), Some(1e10)),
+            // Scale factor not implemented or implausible
+            (Some(5000.0), raw(i16::MIN), None),
+            (Some(5000.0), raw(11), None),
+            (Some(5000.0), raw(-11), None),
+            // Value not implemented
+            (None, raw(-2), None),
+        ];
+        for (value, sf, expected) in cases {
+            assert_eq!(scaled(value, sf), expected, "{value:?} with sf {sf:#06x}");
+        }
+    }
+
+    #[test]
+    fn test_encode_rate_checks_register_range() {
+        assert_eq!(
+            encode_rate(-100.0, -2).unwrap(),
+            u16::from_be_bytes((-10000_i16).to_be_bytes())
+        );
+        assert_eq!(encode_rate(55.0, 0).unwrap(), 55);
+        assert!(encode_rate(100.0, -3).is_err());
+    }
+
+    #[test]
+    fn test_not_implemented_values_are_none() {
+        let mut r = vec![0_u16; storage::LENGTH];
+        r[storage::CHA_STATE] = u16::MAX;
+        r[storage::IN_BAT_V] = 5120;
+        r[storage::IN_BAT_V_SF] = u16::from_be_bytes((-1_i16).to_be_bytes());
+        let reading = StorageReading::parse(&r);
+        assert_eq!(reading.soc_percent, None);
+        assert_eq!(reading.battery_voltage_v, Some(512.0));
+    }
+}
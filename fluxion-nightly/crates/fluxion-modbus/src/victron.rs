@@ -0,0 +1,482 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Victron GX (Cerbo GX, Venus GX) ESS control over Modbus TCP.
+//!
+//! Victron systems have no battery modes: the ESS assistant regulates grid power to
+//! a setpoint. Modes therefore go through [`GridSetpointPlanner`], which turns force
+//! charge into a grid import setpoint, force discharge into an export setpoint and
+//! holding into a zero discharge cap. Everything lives on the GX system unit
+//! (`com.victronenergy.system` and `com.victronenergy.settings`, unit 100).
+
+use crate::client::ModbusTcpClient;
+use anyhow::{Context, Result, bail};
+use async_trait::async_trait;
+use fluxion_core::{
+    GenericInverterState, GridSetpointPlan, GridSetpointPlanner, InverterCommand,
+    InverterDataSource, InverterOperationMode, SharedDebugMode,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// PV on output L1-L3, on grid input L1-L3, on generator L1-L3, consumption L1-L3, grid L1-L3
+const AC_BLOCK: u16 = 808;
+const AC_BLOCK_LENGTH: u16 = 15;
+/// Battery voltage, current, power and SOC
+const BATTERY_BLOCK: u16 = 840;
+const BATTERY_BLOCK_LENGTH: u16 = 4;
+/// DC-coupled PV power
+const PV_DC_POWER: u16 = 850;
+/// ESS grid setpoint (int16, W)
+const AC_POWER_SETPOINT: u16 = 2700;
+/// ESS maximum discharge power (uint16, 10 W steps)
+const MAX_DISCHARGE_POWER: u16 = 2704;
+/// ESS maximum feed-in power (int16, 100 W steps)
+const MAX_FEED_IN_POWER: u16 = 2706;
+
+/// All-ones register value: the -1 "no limit" of the GX settings
+const NO_LIMIT: u16 = u16::MAX;
+
+/// Victron GX settings (`[victron]`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VictronConfig {
+    pub enabled: bool,
+    /// ID of the inverter in `[[inverters]]` the GX system serves
+    pub inverter_id: String,
+    pub host: String,
+    pub port: u16,
+    /// Unit ID of the GX system and settings services (100 on every GX)
+    pub unit_id: u8,
+    /// How long a poll result is reused before the GX is read again
+    pub poll_interval_secs: u64,
+    /// Timeout of one Modbus request
+    pub timeout_ms: u64,
+    /// Grid setpoint in self-use; a small import keeps the battery from leaking to the grid
+    pub idle_setpoint_w: i32,
+    /// Grid import setpoint while force charging
+    pub force_charge_power_w: u32,
+    /// Grid export setpoint while force discharging
+    pub force_discharge_power_w: u32,
+}
+
+impl Default for VictronConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            inverter_id: String::new(),
+            host: String::new(),
+            port: 502,
+            unit_id: 100,
+            poll_interval_secs: 5,
+            timeout_ms: 3000,
+            idle_setpoint_w: 50,
+            force_charge_power_w: 3000,
+            force_discharge_power_w: 3000,
+        }
+    }
+}
+
+impl VictronConfig {
+    /// Check the settings
+    ///
+    /// # Errors
+    /// Describes the first invalid setting
+    pub fn validate(&self) -> Result<(), String> {
+        if self.inverter_id.is_empty() {
+            return Err("inverter_id cannot be empty".to_owned());
+        }
+        if self.host.trim().is_empty() {
+            return Err("host cannot be empty".to_owned());
+        }
+        if self.port == 0 {
+            return Err("port cannot be 0".to_owned());
+        }
+        if !(1..=3600).contains(&self.poll_interval_secs) {
+            return Err("poll_interval_secs must be between 1 and 3600".to_owned());
+        }
+        if !(100..=60_000).contains(&self.timeout_ms) {
+            return Err("timeout_ms must be between 100 and 60000".to_owned());
+        }
+        if !(-1000..=1000).contains(&self.idle_setpoint_w) {
+            return Err("idle_setpoint_w must be between -1000 and 1000".to_owned());
+        }
+        for (name, watts) in [
+            ("force_charge_power_w", self.force_charge_power_w),
+            ("force_discharge_power_w", self.force_discharge_power_w),
+        ] {
+            if !(100..=32_000).contains(&watts) {
+                return Err(format!("{name} must be between 100 and 32000"));
+            }
+        }
+        Ok(())
+    }
+
+    /// How generic modes map to setpoints for this system
+    #[must_use]
+    pub fn planner(&self) -> GridSetpointPlanner {
+        GridSetpointPlanner {
+            idle_setpoint_w: self.idle_setpoint_w,
+            charge_power_w: self.force_charge_power_w,
+            discharge_power_w: self.force_discharge_power_w,
+        }
+    }
+}
+
+fn signed(raw: u16) -> i16 {
+    i16::from_be_bytes(raw.to_be_bytes())
+}
+
+fn unsigned(value: i16) -> u16 {
+    u16::from_be_bytes(value.to_be_bytes())
+}
+
+/// Inverter data source driving a Victron ESS through its grid setpoint
+#[derive(Debug)]
+pub struct VictronGxSource {
+    inverter_id: String,
+    client: ModbusTcpClient,
+    planner: Mutex<GridSetpointPlanner>,
+    poll_interval: Duration,
+    last_poll: Mutex<Option<(Instant, GenericInverterState)>>,
+    debug_mode: SharedDebugMode,
+}
+
+impl VictronGxSource {
+    /// Writes are skipped while `debug_mode` is on
+    #[must_use]
+    pub fn new(config: &VictronConfig, debug_mode: SharedDebugMode) -> Self {
+        Self {
+            inverter_id: config.inverter_id.clone(),
+            client: ModbusTcpClient::new(
+                format!("{}:{}", config.host, config.port),
+                config.unit_id,
+                Duration::from_millis(config.timeout_ms),
+            ),
+            planner: Mutex::new(config.planner()),
+            poll_interval: Duration::from_secs(config.poll_interval_secs),
+            last_poll: Mutex::default(),
+            debug_mode,
+        }
+    }
+
+    fn check_inverter(&self, inverter_id: &str) -> Result<()> {
+        if inverter_id != self.inverter_id {
+            bail!("no Victron GX configured for {inverter_id}");
+        }
+        Ok(())
+    }
+
+    async fn read_setpoint(&self) -> Result<GridSetpointPlan> {
+        let setpoint = self
+            .client
+            .read_holding_registers(AC_POWER_SETPOINT, 1)
+            .await?;
+        let max_discharge = self
+            .client
+            .read_holding_registers(MAX_DISCHARGE_POWER, 1)
+            .await?;
+        Ok(GridSetpointPlan {
+            grid_setpoint_w: i32::from(signed(setpoint[0])),
+            max_discharge_w: (max_discharge[0] != NO_LIMIT)
+                .then(|| u32::from(max_discharge[0]) * 10),
+        })
+    }
+
+    async fn poll(&self) -> Result<GenericInverterState> {
+        let ac = self
+            .client
+            .read_holding_registers(AC_BLOCK, AC_BLOCK_LENGTH)
+            .await?;
+        let battery = self
+            .client
+            .read_holding_registers(BATTERY_BLOCK, BATTERY_BLOCK_LENGTH)
+            .await?;
+        let pv_dc = self.client.read_holding_registers(PV_DC_POWER, 1).await?;
+        let setpoint = self.read_setpoint().await?;
+
+        let pv_ac_w: f32 = ac[..9].iter().map(|w| f32::from(*w)).sum();
+        let house_load_w: f32 = ac[9..12].iter().map(|w| f32::from(*w)).sum();
+        // The GX reports import as positive; FluxION uses positive = export
+        let grid_power_w: f32 = -ac[12..15]
+            .iter()
+            .map(|w| f32::from(signed(*w)))
+            .sum::<f32>();
+
+        Ok(GenericInverterState {
+            inverter_id: self.inverter_id.clone(),
+            battery_soc: f32::from(battery[3]),
+            work_mode: self.planner.lock().mode_of(&setpoint),
+            grid_power_w,
+            battery_power_w: f32::from(signed(battery[2])),
+            pv_power_w: pv_ac_w + f32::from(pv_dc[0]),
+            online: true,
+            house_load_w: Some(house_load_w),
+            grid_import_w: Some((-grid_power_w).max(0.0)),
+            grid_export_w: Some(grid_power_w.max(0.0)),
+            l1_power_w: Some(-f32::from(signed(ac[12]))),
+            l2_power_w: Some(-f32::from(signed(ac[13]))),
+            l3_power_w: Some(-f32::from(signed(ac[14]))),
+            battery_voltage_v: Some(f32::from(battery[0]) / 10.0),
+            battery_current_a: Some(f32::from(signed(battery[1])) / 10.0),
+            ..GenericInverterState::default()
+        })
+    }
+
+    async fn apply(&self, mode: InverterOperationMode) -> Result<()> {
+        let target = self.planner.lock().setpoint(mode);
+        if self.debug_mode.is_enabled() {
+            info!(
+                "🔍 DEBUG MODE: Would set {} to {mode:?} over Victron ESS ({target:?})",
+                self.inverter_id
+            );
+            return Ok(());
+        }
+
+        if self.read_setpoint().await? == target {
+            debug!("{} is already in {mode:?}", self.inverter_id);
+            return Ok(());
+        }
+        let setpoint = i16::try_from(target.grid_setpoint_w)
+            .context("grid setpoint does not fit the ESS register")?;
+        #[expect(
+            clippy::integer_division,
+            reason = "the register counts whole 10 W steps"
+        )]
+        let max_discharge = match target.max_discharge_w {
+            Some(watts) => u16::try_from(watts / 10)
+                .ok()
+                .filter(|raw| *raw != NO_LIMIT)
+                .context("discharge cap does not fit the ESS register")?,
+            None => NO_LIMIT,
+        };
+
+        // Cap discharge first so a force charge never starts by draining the battery
+        self.client
+            .write_registers(MAX_DISCHARGE_POWER, &[max_discharge])
+            .await?;
+        self.client
+            .write_registers(AC_POWER_SETPOINT, &[unsigned(setpoint)])
+            .await?;
+        *self.last_poll.lock() = None;
+
+        let applied = self.read_setpoint().await?;
+        if applied != target {
+            bail!(
+                "{} did not apply {mode:?}: expected {target:?}, read back {applied:?}",
+                self.inverter_id
+            );
+        }
+        info!("✅ {} set to {mode:?} over Victron ESS", self.inverter_id);
+        Ok(())
+    }
+
+    async fn set_charge_power(&self, watts: u32) -> Result<()> {
+        let current = self.read_setpoint().await?;
+        let force_charging = {
+            let mut planner = self.planner.lock();
+            let force_charging = planner.mode_of(&current) == InverterOperationMode::ForceCharge;
+            planner.charge_power_w = watts.clamp(100, 32_000);
+            force_charging
+        };
+
+        // Takes effect on the next force charge, or now when already force charging
+        if force_charging {
+            self.apply(InverterOperationMode::ForceCharge).await?;
+        }
+        Ok(())
+    }
+
+    async fn set_export_limit(&self, watts: u32) -> Result<()> {
+        #[expect(
+            clippy::integer_division,
+            reason = "the register counts whole 100 W steps, rounded down to stay under the limit"
+        )]
+        let raw =
+            i16::try_from(watts / 100).context("export limit does not fit the ESS register")?;
+        if self.debug_mode.is_enabled() {
+            info!(
+                "🔍 DEBUG MODE: Would limit {} feed-in to {watts}W",
+                self.inverter_id
+            );
+            return Ok(());
+        }
+        self.client
+            .write_registers(MAX_FEED_IN_POWER, &[unsigned(raw)])
+            .await
+    }
+}
+
+#[async_trait]
+impl InverterDataSource for VictronGxSource {
+    async fn read_state(&self, inverter_id: &str) -> Result<GenericInverterState> {
+        self.check_inverter(inverter_id)?;
+        if let Some((at, state)) = self.last_poll.lock().as_ref()
+            && at.elapsed() < self.poll_interval
+        {
+            return Ok(state.clone());
+        }
+
+        let state = self.poll().await?;
+        *self.last_poll.lock() = Some((Instant::now(), state.clone()));
+        Ok(state)
+    }
+
+    async fn write_command(&self, inverter_id: &str, command: &InverterCommand) -> Result<()> {
+        self.check_inverter(inverter_id)?;
+        match command {
+            InverterCommand::SetMode(mode) => self.apply(*mode).await,
+            InverterCommand::SetChargePowerLimit(watts) => self.set_charge_power(*watts).await,
+            InverterCommand::SetExportLimit(watts) => self.set_export_limit(*watts).await,
+        }
+    }
+
+    async fn health_check(&self) -> Result<bool> {
+        Ok(self
+            .client
+            .read_holding_registers(BATTERY_BLOCK, BATTERY_BLOCK_LENGTH)
+            .await
+            .is_ok())
+    }
+
+    fn name(&self) -> &'static str {
+        "victron"
+    }
+
+    fn supports_charge_power_limit(&self, inverter_id: &str) -> bool {
+        inverter_id == self.inverter_id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fake_device::{FakeDevice, raw};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::Ordering;
+
+    /// GX exporting 1 kW with the battery charging at 2 kW, ESS in self-use
+    fn gx_device() -> Arc<FakeDevice> {
+        let mut registers: HashMap<u16, u16> = (AC_BLOCK..AC_BLOCK + AC_BLOCK_LENGTH)
+            .map(|a| (a, 0))
+            .collect();
+        registers.insert(808, 1500); // PV on output L1
+        registers.insert(817, 400); // Consumption L1
+        registers.insert(818, 300);
+        registers.insert(819, 300);
+        registers.insert(820, raw(-1000)); // Grid L1: exporting
+        registers.extend([(840, 520), (841, raw(385)), (842, raw(2000)), (843, 64)]);
+        registers.insert(PV_DC_POWER, 2500);
+        registers.insert(AC_POWER_SETPOINT, 50);
+        registers.insert(MAX_DISCHARGE_POWER, NO_LIMIT);
+        registers.insert(MAX_FEED_IN_POWER, NO_LIMIT);
+        FakeDevice::new(registers)
+    }
+
+    async fn victron_source(device: &Arc<FakeDevice>, debug_mode: bool) -> VictronGxSource {
+        let config = VictronConfig {
+            enabled: true,
+            inverter_id: "victron".to_owned(),
+            host: "127.0.0.1".to_owned(),
+            port: device.serve().await,
+            ..VictronConfig::default()
+        };
+        VictronGxSource::new(&config, SharedDebugMode::new(debug_mode))
+    }
+
+    #[tokio::test]
+    async fn test_read_state_decodes_system_registers() {
+        let device = gx_device();
+        let source = victron_source(&device, false).await;
+
+        let state = source.read_state("victron").await.unwrap();
+        assert!((state.battery_soc - 64.0).abs() < 0.01);
+        assert_eq!(state.work_mode, InverterOperationMode::SelfUse);
+        assert!((state.grid_power_w - 1000.0).abs() < 0.01);
+        assert!((state.battery_power_w - 2000.0).abs() < 0.01);
+        assert!((state.pv_power_w - 4000.0).abs() < 0.01);
+        assert_eq!(state.house_load_w, Some(1000.0));
+        assert_eq!(state.battery_voltage_v, Some(52.0));
+
+        assert!(source.read_state("other").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_modes_become_grid_setpoints() {
+        let device = gx_device();
+        let source = victron_source(&device, false).await;
+
+        source
+            .write_command("victron", &InverterCommand::SetChargePowerLimit(2500))
+            .await
+            .unwrap();
+        source
+            .write_command(
+                "victron",
+                &InverterCommand::SetMode(InverterOperationMode::ForceCharge),
+            )
+            .await
+            .unwrap();
+        assert_eq!(device.register(AC_POWER_SETPOINT), 2500);
+        assert_eq!(device.register(MAX_DISCHARGE_POWER), 0);
+        let state = source.read_state("victron").await.unwrap();
+        assert_eq!(state.work_mode, InverterOperationMode::ForceCharge);
+
+        // Already applied: nothing more is written
+        let writes = device.writes.load(Ordering::SeqCst);
+        source
+            .write_command(
+                "victron",
+                &InverterCommand::SetMode(InverterOperationMode::ForceCharge),
+            )
+            .await
+            .unwrap();
+        assert_eq!(device.writes.load(Ordering::SeqCst), writes);
+
+        source
+            .write_command(
+                "victron",
+                &InverterCommand::SetMode(InverterOperationMode::ForceDischarge),
+            )
+            .await
+            .unwrap();
+        assert_eq!(device.register(AC_POWER_SETPOINT), raw(-3000));
+        assert_eq!(device.register(MAX_DISCHARGE_POWER), NO_LIMIT);
+
+        source
+            .write_command("victron", &InverterCommand::SetExportLimit(4000))
+            .await
+            .unwrap();
+        assert_eq!(device.register(MAX_FEED_IN_POWER), 40);
+    }
+
+    #[tokio::test]
+    async fn test_debug_mode_never_writes() {
+        let device = gx_device();
+        let source = victron_source(&device, true).await;
+        source
+            .write_command(
+                "victron",
+                &InverterCommand::SetMode(InverterOperationMode::NoChargeNoDischarge),
+            )
+            .await
+            .unwrap();
+        source
+            .write_command("victron", &InverterCommand::SetExportLimit(0))
+            .await
+            .unwrap();
+        assert_eq!(device.writes.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,183 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Fallback scheduler used when no strategy plugin yields a valid decision.
+//!
+//! Deliberately simple so it cannot fail: charge during the cheapest quarter
+//! of the remaining blocks and let the battery cover the house otherwise.
+
+use crate::protocol::{BlockDecision, EvaluationRequest, OperationMode};
+
+/// Decision UID of every fallback decision
+pub const FALLBACK_DECISION_UID: &str = "fallback:price_percentile";
+
+/// Blocks at or below this price percentile charge from the grid
+const CHARGE_PERCENTILE: f32 = 0.25;
+
+/// Blocks at or above this price percentile are considered expensive
+const EXPENSIVE_PERCENTILE: f32 = 0.75;
+
+/// Price-percentile rules standing in for the strategy plugins
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FallbackScheduler;
+
+impl FallbackScheduler {
+    /// Decide the mode of `request.block` from its rank among the remaining prices
+    #[must_use]
+    pub fn decide(&self, request: &EvaluationRequest) -> BlockDecision {
+        let price = request.block.effective_price_czk_per_kwh;
+        let mut prices: Vec<f32> = request
+            .all_blocks
+            .iter()
+            .map(|b| b.effective_price_czk_per_kwh)
+            .filter(|p| p.is_finite())
+            .collect();
+        if prices.is_empty() && price.is_finite() {
+            prices.push(price);
+        }
+        prices.sort_by(f32::total_cmp);
+
+        let cheap = percentile(&prices, CHARGE_PERCENTILE);
+        let expensive = percentile(&prices, EXPENSIVE_PERCENTILE);
+        let highest = prices.last().copied();
+        let battery = &request.battery;
+
+        let (mode, reason) = match (cheap, expensive) {
+            // Flat prices leave nothing to gain from charging
+            (Some(cheap), Some(_))
+                if price <= cheap
+                    && highest.is_some_and(|highest| price < highest)
+                    && battery.current_soc_percent < battery.max_soc_percent =>
+            {
+                (
+                    OperationMode::ForceCharge,
+                    format!("Fallback: charge at {price:.2} CZK/kWh (cheap, ≤ {cheap:.2})"),
+                )
+            }
+            (_, Some(expensive)) if price >= expensive => (
+                OperationMode::SelfUse,
+                format!(
+                    "Fallback: battery covers load at {price:.2} CZK/kWh (expensive, ≥ {expensive:.2})"
+                ),
+            ),
+            _ => (
+                OperationMode::SelfUse,
+                "Fallback: self-use (no strategy plugin produced a valid decision)".to_owned(),
+            ),
+        };
+
+        BlockDecision {
+            block_start: request.block.block_start,
+            duration_minutes: request.block.duration_minutes,
+            mode,
+            reason,
+            decision_reason: None,
+            priority: 0,
+            strategy_name: Some("Fallback".to_owned()),
+            confidence: None,
+            expected_profit_czk: None,
+            decision_uid: Some(FALLBACK_DECISION_UID.to_owned()),
+            curtailed_solar_kwh: None,
+            considered_alternatives: Vec::new(),
+        }
+    }
+}
+
+/// Nearest-rank percentile of ascending `sorted` prices
+#[expect(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn percentile(sorted: &[f32], fraction: f32) -> Option<f32> {
+    let last = sorted.len().checked_sub(1)?;
+    let index = (last as f32 * fraction).round() as usize;
+    sorted.get(index.min(last)).copied()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::protocol::{BatteryState, ForecastData, HistoricalData, PriceBlock};
+    use chrono::{Duration, TimeZone, Utc};
+
+    pub(crate) fn request(prices: &[f32], current: usize, soc: f32) -> EvaluationRequest {
+        let start = Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap();
+        let blocks: Vec<PriceBlock> = (0_i64..)
+            .zip(prices)
+            .map(|(i, &price)| PriceBlock {
+                block_start: start + Duration::minutes(15 * i),
+                duration_minutes: 15,
+                price_czk_per_kwh: price,
+                effective_price_czk_per_kwh: price,
+                spot_sell_price_czk_per_kwh: None,
+                tariff: None,
+            })
+            .collect();
+        EvaluationRequest {
+            block: blocks[current].clone(),
+            battery: BatteryState {
+                current_soc_percent: soc,
+                capacity_kwh: 10.0,
+                max_charge_rate_kw: 5.0,
+                min_soc_percent: 10.0,
+                max_soc_percent: 100.0,
+                efficiency: 0.95,
+                wear_cost_czk_per_kwh: 0.1,
+                temperature_c: None,
+            },
+            forecast: ForecastData {
+                solar_kwh: 0.0,
+                consumption_kwh: 0.25,
+                grid_export_price_czk_per_kwh: 0.5,
+            },
+            all_blocks: blocks,
+            historical: HistoricalData {
+                grid_import_today_kwh: None,
+                consumption_today_kwh: None,
+                hourly_consumption_profile: None,
+            },
+            backup_discharge_min_soc: 10.0,
+            hdo_raw_data: None,
+            solar_forecast_total_today_kwh: 0.0,
+            solar_forecast_remaining_today_kwh: 0.0,
+            solar_forecast_tomorrow_kwh: 0.0,
+            battery_avg_charge_price_czk_per_kwh: 0.0,
+            weather: None,
+        }
+    }
+
+    const PRICES: [f32; 8] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+
+    #[test]
+    fn charges_in_cheapest_quarter_only() {
+        let scheduler = FallbackScheduler;
+        let cheap = scheduler.decide(&request(&PRICES, 0, 50.0));
+        assert_eq!(cheap.mode, OperationMode::ForceCharge);
+        assert_eq!(cheap.decision_uid.as_deref(), Some(FALLBACK_DECISION_UID));
+
+        for current in [3, 7] {
+            let decision = scheduler.decide(&request(&PRICES, current, 50.0));
+            assert_eq!(decision.mode, OperationMode::SelfUse);
+        }
+    }
+
+    #[test]
+    fn full_battery_or_flat_prices_stay_in_self_use() {
+        let scheduler = FallbackScheduler;
+        let full = scheduler.decide(&request(&PRICES, 0, 100.0));
+        assert_eq!(full.mode, OperationMode::SelfUse);
+
+        let flat = scheduler.decide(&request(&[3.0; 8], 0, 20.0));
+        assert_eq!(flat.mode, OperationMode::SelfUse);
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Circuit breaker and latency statistics for strategy plugins.
+//!
+//! The [`PluginManager`](crate::PluginManager) keeps a [`PluginHealthTracker`]
+//! per plugin. After [`CircuitBreakerSettings::failure_threshold`] consecutive
+//! failures (errors, timeouts or invalid decisions) the circuit opens and the
+//! plugin is skipped. Once the backoff has elapsed a single trial call is let
+//! through: success closes the circuit, another failure reopens it with twice
+//! the backoff, up to [`CircuitBreakerSettings::max_backoff`].
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// When the circuit breaker opens and how long it stays open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerSettings {
+    /// Consecutive failures that open the circuit
+    pub failure_threshold: u32,
+    /// Backoff after the circuit opens for the first time
+    pub base_backoff: Duration,
+    /// Longest backoff between retries
+    pub max_backoff: Duration,
+}
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 3,
+            base_backoff: Duration::from_secs(60),
+            max_backoff: Duration::from_secs(3600),
+        }
+    }
+}
+
+/// State of a plugin's circuit breaker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Plugin is evaluated normally
+    Closed,
+    /// Plugin is skipped until the backoff has elapsed
+    Open,
+    /// Backoff has elapsed, the next call is a trial
+    HalfOpen,
+}
+
+/// How a plugin call went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallOutcome {
+    /// Valid decision within the timeout
+    Success,
+    /// Error or invalid decision
+    Failure(String),
+    /// No answer within the plugin's timeout
+    Timeout,
+}
+
+/// Health of a plugin as reported by `GET /api/plugins/{name}/health`
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginHealth {
+    pub name: String,
+    pub circuit: CircuitState,
+    /// Seconds until the next trial call while the circuit is open
+    pub retry_in_secs: Option<u64>,
+    /// Per-call timeout enforced by the manager
+    pub timeout_ms: Option<u64>,
+    pub calls: u64,
+    pub successes: u64,
+    pub failures: u64,
+    pub timeouts: u64,
+    pub consecutive_failures: u32,
+    /// How often the circuit has opened since the last success
+    pub times_opened: u32,
+    pub last_latency_ms: Option<f64>,
+    pub avg_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<f64>,
+    pub last_error: Option<String>,
+    pub last_success: Option<DateTime<Utc>>,
+    pub last_failure: Option<DateTime<Utc>>,
+}
+
+/// Circuit breaker and call statistics of one plugin
+#[derive(Debug, Default)]
+pub struct PluginHealthTracker {
+    open_until: Option<Instant>,
+    half_open: bool,
+    times_opened: u32,
+    consecutive_failures: u32,
+    calls: u64,
+    successes: u64,
+    failures: u64,
+    timeouts: u64,
+    total_latency: Duration,
+    last_latency: Option<Duration>,
+    max_latency: Duration,
+    last_error: Option<String>,
+    last_success: Option<DateTime<Utc>>,
+    last_failure: Option<DateTime<Utc>>,
+}
+
+impl PluginHealthTracker {
+    /// Whether the plugin may be called now
+    ///
+    /// Returns `Some(true)` for a trial call after the backoff, which is the
+    /// moment to clear the plugin's own failure state.
+    pub fn admit(&mut self, now: Instant) -> Option<bool> {
+        match self.open_until {
+            None => Some(false),
+            Some(until) if now >= until => {
+                self.open_until = None;
+                self.half_open = true;
+                Some(true)
+            }
+            Some(_) => None,
+        }
+    }
+
+    /// Record the outcome and latency of a call, returning whether it opened
+    /// the circuit
+    pub fn record(
+        &mut self,
+        outcome: &CallOutcome,
+        latency: Duration,
+        now: Instant,
+        settings: &CircuitBreakerSettings,
+    ) -> bool {
+        self.calls += 1;
+        self.total_latency += latency;
+        self.last_latency = Some(latency);
+        self.max_latency = self.max_latency.max(latency);
+
+        let error = match outcome {
+            CallOutcome::Success => {
+                self.successes += 1;
+                self.consecutive_failures = 0;
+                self.times_opened = 0;
+                self.half_open = false;
+                self.last_success = Some(Utc::now());
+                return false;
+            }
+            CallOutcome::Failure(error) => error.clone(),
+            CallOutcome::Timeout => {
+                self.timeouts += 1;
+                format!("timed out after {} ms", latency.as_millis())
+            }
+        };
+        self.failures += 1;
+        self.consecutive_failures += 1;
+        self.last_error = Some(error);
+        self.last_failure = Some(Utc::now());
+
+        if self.half_open || self.consecutive_failures >= settings.failure_threshold {
+            let backoff = settings
+                .base_backoff
+                .saturating_mul(1 << self.times_opened.min(16))
+                .min(settings.max_backoff);
+            self.open_until = Some(now + backoff);
+            self.half_open = false;
+            self.times_opened += 1;
+            return true;
+        }
+        false
+    }
+
+    /// Current circuit state
+    #[must_use]
+    pub fn state(&self, now: Instant) -> CircuitState {
+        match self.open_until {
+            Some(until) if now < until => CircuitState::Open,
+            Some(_) => CircuitState::HalfOpen,
+            None if self.half_open => CircuitState::HalfOpen,
+            None => CircuitState::Closed,
+        }
+    }
+
+    /// Snapshot for the health endpoint
+    #[must_use]
+    pub fn snapshot(&self, name: &str, timeout: Option<Duration>, now: Instant) -> PluginHealth {
+        let millis = |d: Duration| d.as_secs_f64() * 1000.0;
+        PluginHealth {
+            name: name.to_owned(),
+            circuit: self.state(now),
+            retry_in_secs: self
+                .open_until
+                .filter(|until| *until > now)
+                .map(|until| (until - now).as_secs()),
+            timeout_ms: timeout.map(|t| u64::try_from(t.as_millis()).unwrap_or(u64::MAX)),
+            calls: self.calls,
+            successes: self.successes,
+            failures: self.failures,
+            timeouts: self.timeouts,
+            consecutive_failures: self.consecutive_failures,
+            times_opened: self.times_opened,
+            last_latency_ms: self.last_latency.map(millis),
+            avg_latency_ms: u32::try_from(self.calls)
+                .ok()
+                .filter(|calls| *calls > 0)
+                .map(|calls| millis(self.total_latency / calls)),
+            max_latency_ms: (self.calls > 0).then(|| millis(self.max_latency)),
+            last_error: self.last_error.clone(),
+            last_success: self.last_success,
+            last_failure: self.last_failure,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_opens_and_backs_off() {
+        let settings = CircuitBreakerSettings::default();
+        let mut tracker = PluginHealthTracker::default();
+        let start = Instant::now();
+        let fail = CallOutcome::Failure("boom".to_owned());
+        let ms = Duration::from_millis(20);
+
+        for _ in 0..2 {
+            assert_eq!(tracker.admit(start), Some(false));
+            assert!(!tracker.record(&fail, ms, start, &settings));
+        }
+        assert_eq!(tracker.state(start), CircuitState::Closed);
+        assert!(tracker.record(&CallOutcome::Timeout, ms, start, &settings));
+        assert_eq!(tracker.state(start), CircuitState::Open);
+        assert_eq!(tracker.admit(start + Duration::from_secs(59)), None);
+
+        // Failed trial doubles the backoff
+        let trial = start + Duration::from_secs(60);
+        assert_eq!(tracker.admit(trial), Some(true));
+        assert_eq!(tracker.state(trial), CircuitState::HalfOpen);
+        assert!(tracker.record(&fail, ms, trial, &settings));
+        assert_eq!(tracker.admit(trial + Duration::from_secs(119)), None);
+
+        let retry = trial + Duration::from_secs(120);
+        assert_eq!(tracker.admit(retry), Some(true));
+        assert!(!tracker.record(&CallOutcome::Success, ms, retry, &settings));
+        assert_eq!(tracker.state(retry), CircuitState::Closed);
+
+        let health = tracker.snapshot("slow", Some(Duration::from_secs(5)), retry);
+        assert_eq!((health.calls, health.failures, health.timeouts), (5, 4, 1));
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.timeout_ms, Some(5000));
+        assert_eq!(health.last_error.as_deref(), Some("boom"));
+        assert!((health.avg_latency_ms.unwrap() - 20.0).abs() < 1e-6);
+    }
+}
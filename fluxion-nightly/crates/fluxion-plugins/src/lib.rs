@@ -17,7 +17,11 @@
 //! ## Architecture
 //!
 //! - **PluginManager**: Coordinates strategy plugins and merges their decisions
+//! - **PluginHealthTracker**: Per-plugin circuit breaker and latency statistics
+//! - **FallbackScheduler**: Price-percentile rules used when no plugin yields a valid decision
 //! - **Protocol Types**: JSON-serializable types for plugin communication
+//! - **PluginRegistry**: External HTTP plugin registrations persisted across restarts
+//! - **WasmPluginDirectory**: Sandboxed `.wasm` strategies, hot-loaded from a directory
 //!
 //! ## Plugin Interface
 //!
@@ -26,9 +30,18 @@
 //! - `priority()`: Decision priority (0-100)
 //! - `is_enabled()`: Whether the plugin is active
 //! - `evaluate()`: Returns a `BlockDecision` for a given context
+//! - `describe()`: Returns `PluginDocs` (inputs, decision logic, parameters)
 
+pub mod fallback;
+pub mod health;
 pub mod manager;
 pub mod protocol;
+pub mod registry;
+pub mod wasm;
 
+pub use fallback::{FALLBACK_DECISION_UID, FallbackScheduler};
+pub use health::{CircuitBreakerSettings, CircuitState, PluginHealth};
 pub use manager::{Plugin, PluginManager};
 pub use protocol::*;
+pub use registry::{PluginRegistry, RegisteredPlugin};
+pub use wasm::{WasmPlugin, WasmPluginChange, WasmPluginDirectory};
@@ -12,10 +12,16 @@
 
 //! Plugin manager for coordinating strategy plugins.
 
-use crate::protocol::{BlockDecision, EvaluationRequest, OperationMode};
+use crate::fallback::FallbackScheduler;
+use crate::health::{
+    CallOutcome, CircuitBreakerSettings, CircuitState, PluginHealth, PluginHealthTracker,
+};
+use crate::protocol::{BlockDecision, EvaluationRequest, PluginDescription, PluginDocs};
 use std::collections::HashMap;
-use std::sync::Arc;
-use tracing::{debug, warn};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
 
 /// Trait for strategy plugins
 pub trait Plugin: Send + Sync {
@@ -30,6 +36,23 @@ pub trait Plugin: Send + Sync {
 
     /// Evaluate a block and return a decision
     fn evaluate(&self, request: &EvaluationRequest) -> anyhow::Result<BlockDecision>;
+
+    /// Describe what the plugin does (inputs, decision logic, parameters)
+    fn describe(&self) -> PluginDocs {
+        PluginDocs::default()
+    }
+
+    /// Longest the manager waits for a decision before counting a failure
+    ///
+    /// `None` evaluates in place, which suits in-process strategies that
+    /// cannot hang.
+    fn timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Clear the plugin's own failure state before the circuit breaker lets
+    /// a trial call through
+    fn reset_failures(&self) {}
 }
 
 /// Plugin registration entry
@@ -37,6 +60,7 @@ struct PluginEntry {
     plugin: Arc<dyn Plugin>,
     enabled: bool,
     priority_override: Option<u8>,
+    health: Mutex<PluginHealthTracker>,
 }
 
 impl std::fmt::Debug for PluginEntry {
@@ -45,7 +69,7 @@ impl std::fmt::Debug for PluginEntry {
             .field("name", &self.plugin.name())
             .field("enabled", &self.enabled)
             .field("priority_override", &self.priority_override)
-            .finish()
+            .finish_non_exhaustive()
     }
 }
 
@@ -54,14 +78,21 @@ impl PluginEntry {
         self.priority_override
             .unwrap_or_else(|| self.plugin.priority())
     }
+
+    fn health(&self) -> MutexGuard<'_, PluginHealthTracker> {
+        self.health.lock().unwrap_or_else(PoisonError::into_inner)
+    }
 }
 
 /// Manages strategy plugins and coordinates evaluation
 #[derive(Debug)]
 pub struct PluginManager {
     plugins: HashMap<String, PluginEntry>,
-    /// Fallback mode when no plugins are available or all fail
-    fallback_mode: OperationMode,
+    /// Takes over when no plugin is available or none yields a valid decision
+    fallback: FallbackScheduler,
+    circuit_breaker: CircuitBreakerSettings,
+    /// Bumped whenever the plugins or their settings change, see [`Self::revision`]
+    revision: u64,
 }
 
 impl Default for PluginManager {
@@ -76,10 +107,19 @@ impl PluginManager {
     pub fn new() -> Self {
         Self {
             plugins: HashMap::new(),
-            fallback_mode: OperationMode::SelfUse,
+            fallback: FallbackScheduler,
+            circuit_breaker: CircuitBreakerSettings::default(),
+            revision: 0,
         }
     }
 
+    /// Use different circuit breaker settings
+    #[must_use]
+    pub fn with_circuit_breaker(mut self, settings: CircuitBreakerSettings) -> Self {
+        self.circuit_breaker = settings;
+        self
+    }
+
     /// Register a plugin
     pub fn register(&mut self, plugin: Arc<dyn Plugin>) {
         let name = plugin.name().to_owned();
@@ -94,14 +134,72 @@ impl PluginManager {
                 plugin,
                 enabled: true,
                 priority_override: None,
+                health: Mutex::default(),
             },
         );
+        self.revision += 1;
+    }
+
+    /// Register a plugin, keeping the enabled state and priority override of
+    /// a plugin already registered under the same name
+    pub fn replace(&mut self, plugin: Arc<dyn Plugin>) {
+        let name = plugin.name().to_owned();
+        match self.plugins.get_mut(&name) {
+            Some(entry) => {
+                debug!("Replacing plugin: {name}");
+                entry.plugin = plugin;
+                entry.health = Mutex::default();
+                self.revision += 1;
+            }
+            None => self.register(plugin),
+        }
+    }
+
+    /// Copy of the manager for a counterfactual schedule
+    ///
+    /// Shares the plugins and keeps their enabled states and priority
+    /// overrides, with the plugins named in `disabled` turned off. Health
+    /// starts fresh so the run doesn't count toward the live circuit breakers;
+    /// plugins whose circuit is currently open are left out.
+    #[must_use]
+    pub fn fork(&self, disabled: &[String]) -> Self {
+        let now = Instant::now();
+        let plugins = self
+            .plugins
+            .iter()
+            .filter(|(_, entry)| entry.health().state(now) != CircuitState::Open)
+            .map(|(name, entry)| {
+                let entry = PluginEntry {
+                    plugin: Arc::clone(&entry.plugin),
+                    enabled: entry.enabled && !disabled.contains(name),
+                    priority_override: entry.priority_override,
+                    health: Mutex::default(),
+                };
+                (name.clone(), entry)
+            })
+            .collect();
+        Self {
+            plugins,
+            fallback: FallbackScheduler,
+            circuit_breaker: self.circuit_breaker,
+            revision: self.revision,
+        }
+    }
+
+    /// Remove a plugin
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let removed = self.plugins.remove(name).is_some();
+        if removed {
+            self.revision += 1;
+        }
+        removed
     }
 
     /// Enable or disable a plugin
     pub fn set_enabled(&mut self, name: &str, enabled: bool) -> bool {
         if let Some(entry) = self.plugins.get_mut(name) {
             entry.enabled = enabled;
+            self.revision += 1;
             true
         } else {
             false
@@ -112,12 +210,23 @@ impl PluginManager {
     pub fn set_priority(&mut self, name: &str, priority: u8) -> bool {
         if let Some(entry) = self.plugins.get_mut(name) {
             entry.priority_override = Some(priority);
+            self.revision += 1;
             true
         } else {
             false
         }
     }
 
+    /// Counter that changes whenever a plugin is registered, replaced or
+    /// removed, or its enabled state or priority changes
+    ///
+    /// Lets the scheduler notice that the current plan was made with a
+    /// different set of plugins.
+    #[must_use]
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
     /// Get list of registered plugins
     #[must_use]
     pub fn list_plugins(&self) -> Vec<(&str, u8, bool)> {
@@ -133,26 +242,91 @@ impl PluginManager {
             .collect()
     }
 
+    /// Circuit breaker state and call statistics of a plugin
+    #[must_use]
+    pub fn health(&self, name: &str) -> Option<PluginHealth> {
+        let entry = self.plugins.get(name)?;
+        let health = entry.health();
+        Some(health.snapshot(name, entry.plugin.timeout(), Instant::now()))
+    }
+
+    /// Get documentation of all registered plugins
+    ///
+    /// Enabled plugins come first, each group ordered by priority (highest first).
+    #[must_use]
+    pub fn describe_plugins(&self) -> Vec<PluginDescription> {
+        let mut descriptions: Vec<PluginDescription> = self
+            .plugins
+            .iter()
+            .map(|(name, entry)| PluginDescription {
+                name: name.clone(),
+                priority: entry.effective_priority(),
+                enabled: entry.enabled && entry.plugin.is_enabled(),
+                docs: entry.plugin.describe(),
+            })
+            .collect();
+
+        descriptions.sort_by(|a, b| {
+            b.enabled
+                .cmp(&a.enabled)
+                .then_with(|| b.priority.cmp(&a.priority))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        descriptions
+    }
+
     /// Evaluate all enabled plugins and return their decisions
     pub fn evaluate_all(&self, request: &EvaluationRequest) -> Vec<BlockDecision> {
         let mut decisions = Vec::new();
 
         for (name, entry) in &self.plugins {
-            if !entry.enabled || !entry.plugin.is_enabled() {
+            if !entry.enabled {
+                continue;
+            }
+            let admitted = entry.health().admit(Instant::now());
+            match admitted {
+                None => continue,
+                Some(true) => {
+                    info!("Retrying plugin {} after circuit breaker backoff", name);
+                    entry.plugin.reset_failures();
+                }
+                Some(false) => {}
+            }
+            if !entry.plugin.is_enabled() {
                 continue;
             }
 
-            match entry.plugin.evaluate(request) {
-                Ok(mut decision) => {
-                    // Apply priority override if set
-                    if let Some(priority) = entry.priority_override {
-                        decision.priority = priority;
+            let started = Instant::now();
+            let outcome = match call_with_timeout(&entry.plugin, request) {
+                Ok(mut decision) => match validate_decision(&decision, request) {
+                    Ok(()) => {
+                        // Apply priority override if set
+                        if let Some(priority) = entry.priority_override {
+                            decision.priority = priority;
+                        }
+                        decisions.push(decision);
+                        CallOutcome::Success
+                    }
+                    Err(problem) => {
+                        warn!("Plugin {} returned an invalid decision: {}", name, problem);
+                        CallOutcome::Failure(format!("invalid decision: {problem}"))
                     }
-                    decisions.push(decision);
+                },
+                Err(CallError::Timeout(timeout)) => {
+                    warn!("Plugin {} timed out after {:?}", name, timeout);
+                    CallOutcome::Timeout
                 }
-                Err(e) => {
+                Err(CallError::Failed(e)) => {
                     warn!("Plugin {} failed evaluation: {}", name, e);
+                    CallOutcome::Failure(format!("{e:#}"))
                 }
+            };
+            let now = Instant::now();
+            let opened = entry
+                .health()
+                .record(&outcome, now - started, now, &self.circuit_breaker);
+            if opened {
+                warn!("Circuit breaker opened for plugin {}", name);
             }
         }
 
@@ -165,6 +339,8 @@ impl PluginManager {
     /// 1. Highest priority wins
     /// 2. If tied, highest confidence wins
     /// 3. If still tied, highest expected profit wins
+    ///
+    /// Without any decision the [`FallbackScheduler`] decides instead.
     #[must_use]
     pub fn merge_decisions(
         &self,
@@ -172,40 +348,9 @@ impl PluginManager {
         request: &EvaluationRequest,
     ) -> BlockDecision {
         if decisions.is_empty() {
-            // Fallback decision
-            return BlockDecision {
-                block_start: request.block.block_start,
-                duration_minutes: request.block.duration_minutes,
-                mode: self.fallback_mode,
-                reason: "No strategy plugins available".to_owned(),
-                priority: 0,
-                strategy_name: Some("Fallback".to_owned()),
-                confidence: None,
-                expected_profit_czk: None,
-                decision_uid: Some("fallback:no_plugins".to_owned()),
-            };
+            return self.fallback.decide(request);
         }
-
-        // Sort by priority (desc), then confidence (desc), then profit (desc)
-        decisions.sort_by(|a, b| {
-            b.priority
-                .cmp(&a.priority)
-                .then_with(|| {
-                    let a_conf = a.confidence.unwrap_or(0.0);
-                    let b_conf = b.confidence.unwrap_or(0.0);
-                    b_conf
-                        .partial_cmp(&a_conf)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                })
-                .then_with(|| {
-                    let a_profit = a.expected_profit_czk.unwrap_or(0.0);
-                    let b_profit = b.expected_profit_czk.unwrap_or(0.0);
-                    b_profit
-                        .partial_cmp(&a_profit)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                })
-        });
-
+        rank_decisions(&mut decisions);
         decisions.into_iter().next().expect("decisions not empty")
     }
 
@@ -215,4 +360,333 @@ impl PluginManager {
         let decisions = self.evaluate_all(request);
         self.merge_decisions(decisions, request)
     }
+
+    /// Evaluate all plugins and return the merged decision together with the
+    /// outranked decisions of the other plugins, best first
+    #[must_use]
+    pub fn evaluate_ranked(
+        &self,
+        request: &EvaluationRequest,
+    ) -> (BlockDecision, Vec<BlockDecision>) {
+        let mut decisions = self.evaluate_all(request);
+        if decisions.is_empty() {
+            return (self.fallback.decide(request), Vec::new());
+        }
+        rank_decisions(&mut decisions);
+        let winner = decisions.remove(0);
+        (winner, decisions)
+    }
+}
+
+/// Sort by priority (desc), then confidence (desc), then profit (desc)
+fn rank_decisions(decisions: &mut [BlockDecision]) {
+    decisions.sort_by(|a, b| {
+        b.priority
+            .cmp(&a.priority)
+            .then_with(|| {
+                let a_conf = a.confidence.unwrap_or(0.0);
+                let b_conf = b.confidence.unwrap_or(0.0);
+                b_conf
+                    .partial_cmp(&a_conf)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .then_with(|| {
+                let a_profit = a.expected_profit_czk.unwrap_or(0.0);
+                let b_profit = b.expected_profit_czk.unwrap_or(0.0);
+                b_profit
+                    .partial_cmp(&a_profit)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    });
+}
+
+enum CallError {
+    Timeout(Duration),
+    Failed(anyhow::Error),
+}
+
+/// Evaluate on a helper thread when the plugin has a timeout, so a hung
+/// plugin delays planning by at most that long
+fn call_with_timeout(
+    plugin: &Arc<dyn Plugin>,
+    request: &EvaluationRequest,
+) -> Result<BlockDecision, CallError> {
+    let Some(timeout) = plugin.timeout() else {
+        return plugin.evaluate(request).map_err(CallError::Failed);
+    };
+    let (tx, rx) = std::sync::mpsc::sync_channel(1);
+    let plugin = Arc::clone(plugin);
+    let request = request.clone();
+    std::thread::Builder::new()
+        .name(format!("plugin-{}", plugin.name()))
+        .spawn(move || {
+            let _ = tx.send(plugin.evaluate(&request));
+        })
+        .map_err(|e| CallError::Failed(e.into()))?;
+    match rx.recv_timeout(timeout) {
+        Ok(result) => result.map_err(CallError::Failed),
+        Err(RecvTimeoutError::Timeout) => Err(CallError::Timeout(timeout)),
+        Err(RecvTimeoutError::Disconnected) => {
+            Err(CallError::Failed(anyhow::anyhow!("plugin panicked")))
+        }
+    }
+}
+
+/// Reject decisions for another block or with non-finite numbers
+fn validate_decision(decision: &BlockDecision, request: &EvaluationRequest) -> Result<(), String> {
+    if decision.block_start != request.block.block_start {
+        return Err(format!(
+            "decision is for {} instead of {}",
+            decision.block_start, request.block.block_start
+        ));
+    }
+    let numbers = [
+        ("confidence", decision.confidence),
+        ("expected profit", decision.expected_profit_czk),
+        ("curtailed solar", decision.curtailed_solar_kwh),
+    ];
+    if let Some((field, _)) = numbers
+        .iter()
+        .find(|(_, value)| value.is_some_and(|v| !v.is_finite()))
+    {
+        return Err(format!("{field} is not a finite number"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DocumentedPlugin {
+        name: &'static str,
+        priority: u8,
+    }
+
+    impl Plugin for DocumentedPlugin {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn priority(&self) -> u8 {
+            self.priority
+        }
+
+        fn is_enabled(&self) -> bool {
+            true
+        }
+
+        fn evaluate(&self, _request: &EvaluationRequest) -> anyhow::Result<BlockDecision> {
+            anyhow::bail!("not used")
+        }
+
+        fn describe(&self) -> PluginDocs {
+            PluginDocs {
+                summary: format!("{} summary", self.name),
+                ..PluginDocs::default()
+            }
+        }
+    }
+
+    #[test]
+    fn test_describe_plugins_orders_enabled_by_priority() {
+        let mut manager = PluginManager::new();
+        for (name, priority) in [("low", 10), ("high", 90), ("off", 100)] {
+            manager.register(Arc::new(DocumentedPlugin { name, priority }));
+        }
+        manager.set_enabled("off", false);
+
+        let docs = manager.describe_plugins();
+        let names: Vec<&str> = docs.iter().map(|d| d.name.as_str()).collect();
+
+        assert_eq!(names, ["high", "low", "off"]);
+        assert!(!docs[2].enabled);
+        assert_eq!(docs[0].docs.summary, "high summary");
+    }
+
+    #[test]
+    fn test_revision_tracks_plugin_changes() {
+        let mut manager = PluginManager::new();
+        manager.register(Arc::new(DocumentedPlugin {
+            name: "a",
+            priority: 10,
+        }));
+        let registered = manager.revision();
+
+        manager.set_enabled("a", false);
+        let disabled = manager.revision();
+        assert_ne!(disabled, registered);
+        manager.set_priority("a", 50);
+        assert_ne!(manager.revision(), disabled);
+
+        // Unknown plugins change nothing
+        let before = manager.revision();
+        assert!(!manager.set_enabled("missing", false));
+        assert!(!manager.unregister("missing"));
+        assert_eq!(manager.revision(), before);
+        assert!(manager.unregister("a"));
+        assert_ne!(manager.revision(), before);
+    }
+
+    #[test]
+    fn test_failing_and_invalid_plugins_use_fallback() {
+        let mut manager = PluginManager::new();
+        manager.register(Arc::new(DocumentedPlugin {
+            name: "broken",
+            priority: 50,
+        }));
+        let request = crate::fallback::tests::request(&[1.0, 2.0, 3.0, 4.0], 0, 50.0);
+
+        let decision = manager.evaluate(&request);
+        assert_eq!(
+            decision.decision_uid.as_deref(),
+            Some(crate::FALLBACK_DECISION_UID)
+        );
+
+        let mut other_block = decision.clone();
+        other_block.block_start += chrono::Duration::minutes(15);
+        assert!(validate_decision(&other_block, &request).is_err());
+        let mut nan_profit = decision.clone();
+        nan_profit.expected_profit_czk = Some(f32::NAN);
+        assert!(validate_decision(&nan_profit, &request).is_err());
+        assert!(validate_decision(&decision, &request).is_ok());
+    }
+
+    struct FixedPlugin {
+        name: &'static str,
+        priority: u8,
+        mode: crate::OperationMode,
+    }
+
+    impl Plugin for FixedPlugin {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn priority(&self) -> u8 {
+            self.priority
+        }
+
+        fn is_enabled(&self) -> bool {
+            true
+        }
+
+        fn evaluate(&self, request: &EvaluationRequest) -> anyhow::Result<BlockDecision> {
+            let mut decision = FallbackScheduler.decide(request);
+            decision.mode = self.mode;
+            decision.priority = self.priority;
+            decision.strategy_name = Some(self.name.to_owned());
+            Ok(decision)
+        }
+    }
+
+    #[test]
+    fn test_evaluate_ranked_returns_outranked_decisions() {
+        let mut manager = PluginManager::new();
+        for (name, priority, mode) in [
+            ("low", 10, crate::OperationMode::ForceCharge),
+            ("high", 90, crate::OperationMode::SelfUse),
+            ("mid", 50, crate::OperationMode::ForceDischarge),
+        ] {
+            manager.register(Arc::new(FixedPlugin {
+                name,
+                priority,
+                mode,
+            }));
+        }
+        let request = crate::fallback::tests::request(&[1.0, 2.0, 3.0, 4.0], 0, 50.0);
+
+        let (winner, outranked) = manager.evaluate_ranked(&request);
+        assert_eq!(winner.strategy_name.as_deref(), Some("high"));
+        let names: Vec<_> = outranked
+            .iter()
+            .filter_map(|d| d.strategy_name.as_deref())
+            .collect();
+        assert_eq!(names, ["mid", "low"]);
+
+        assert!(PluginManager::new().evaluate_ranked(&request).1.is_empty());
+    }
+
+    struct HangingPlugin {
+        calls: std::sync::atomic::AtomicU32,
+    }
+
+    impl Plugin for HangingPlugin {
+        fn name(&self) -> &'static str {
+            "hanging"
+        }
+
+        fn priority(&self) -> u8 {
+            100
+        }
+
+        fn is_enabled(&self) -> bool {
+            true
+        }
+
+        fn evaluate(&self, _request: &EvaluationRequest) -> anyhow::Result<BlockDecision> {
+            self.calls
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+            std::thread::sleep(Duration::from_millis(500));
+            anyhow::bail!("too late")
+        }
+
+        fn timeout(&self) -> Option<Duration> {
+            Some(Duration::from_millis(20))
+        }
+    }
+
+    #[test]
+    fn test_timeouts_open_circuit_breaker() {
+        let plugin = Arc::new(HangingPlugin {
+            calls: std::sync::atomic::AtomicU32::new(0),
+        });
+        let mut manager = PluginManager::new();
+        manager.register(Arc::clone(&plugin) as Arc<dyn Plugin>);
+        let request = crate::fallback::tests::request(&[1.0, 2.0, 3.0, 4.0], 0, 50.0);
+
+        let started = Instant::now();
+        for _ in 0..5 {
+            let decision = manager.evaluate(&request);
+            assert_eq!(
+                decision.decision_uid.as_deref(),
+                Some(crate::FALLBACK_DECISION_UID)
+            );
+        }
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert_eq!(plugin.calls.load(std::sync::atomic::Ordering::Relaxed), 3);
+
+        let health = manager.health("hanging").unwrap();
+        assert_eq!(health.circuit, crate::CircuitState::Open);
+        assert_eq!((health.calls, health.timeouts), (3, 3));
+        assert_eq!(health.timeout_ms, Some(20));
+        assert!(health.retry_in_secs.is_some());
+        assert!(manager.health("missing").is_none());
+    }
+
+    #[test]
+    fn test_fork_disables_plugins_without_touching_the_original() {
+        let mut manager = PluginManager::new();
+        for (name, priority, mode) in [
+            ("high", 90, crate::OperationMode::SelfUse),
+            ("mid", 50, crate::OperationMode::ForceDischarge),
+        ] {
+            manager.register(Arc::new(FixedPlugin {
+                name,
+                priority,
+                mode,
+            }));
+        }
+        manager.set_priority("mid", 40);
+        let request = crate::fallback::tests::request(&[1.0, 2.0, 3.0, 4.0], 0, 50.0);
+
+        let fork = manager.fork(&["high".to_owned()]);
+        let decision = fork.evaluate(&request);
+        assert_eq!(decision.strategy_name.as_deref(), Some("mid"));
+        assert_eq!(decision.priority, 40);
+
+        let decision = manager.evaluate(&request);
+        assert_eq!(decision.strategy_name.as_deref(), Some("high"));
+        assert_eq!(fork.health("high").unwrap().calls, 0);
+    }
 }
@@ -16,7 +16,7 @@
 //! to communicate with Fluxion via HTTP/REST.
 
 use crate::manager::Plugin;
-use crate::protocol::{BlockDecision, EvaluationRequest, PluginManifest};
+use crate::protocol::{BlockDecision, EvaluationRequest, PluginDocs, PluginManifest};
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::time::Duration;
 use tracing::{debug, error, warn};
@@ -107,11 +107,6 @@ impl HttpPlugin {
         self.failure_count.load(Ordering::Relaxed)
     }
 
-    /// Reset the failure count (e.g., after a successful health check)
-    pub fn reset_failures(&self) {
-        self.failure_count.store(0, Ordering::Relaxed);
-    }
-
     /// Record a failure
     fn record_failure(&self) {
         let prev = self.failure_count.fetch_add(1, Ordering::Relaxed);
@@ -147,6 +142,21 @@ impl Plugin for HttpPlugin {
         self.manifest.enabled
     }
 
+    fn describe(&self) -> PluginDocs {
+        self.manifest.docs.clone().unwrap_or_else(|| PluginDocs {
+            summary: self.manifest.description.clone(),
+            ..PluginDocs::default()
+        })
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(self.timeout)
+    }
+
+    fn reset_failures(&self) {
+        self.failure_count.store(0, Ordering::Relaxed);
+    }
+
     fn evaluate(&self, request: &EvaluationRequest) -> anyhow::Result<BlockDecision> {
         debug!(
             "HttpPlugin {} evaluating block at {}",
@@ -209,16 +219,19 @@ impl Plugin for HttpPlugin {
 }
 
 /// Registration request from an external plugin
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct PluginRegistrationRequest {
     /// Plugin manifest
     pub manifest: PluginManifest,
     /// Callback URL for evaluation requests
     pub callback_url: String,
+    /// Per-call timeout in milliseconds (default: 5000)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
 }
 
 /// Response to a registration request
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
 pub struct PluginRegistrationResponse {
     /// Whether registration was successful
     pub success: bool,
@@ -239,6 +252,7 @@ mod tests {
             description: "Test plugin".to_owned(),
             default_priority: 50,
             enabled: true,
+            docs: None,
         }
     }
 
@@ -14,7 +14,9 @@
 //! These types are JSON-serializable and language-agnostic.
 
 use chrono::{DateTime, Utc};
+pub use fluxion_types::tariff::Tariff;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
 /// Price block information
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +34,9 @@ pub struct PriceBlock {
     /// Only populated when use_spot_prices_to_sell is enabled.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub spot_sell_price_czk_per_kwh: Option<f32>,
+    /// HDO grid tariff of the block, when the tariff schedule is known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tariff: Option<Tariff>,
 }
 
 /// Battery state information
@@ -51,6 +56,9 @@ pub struct BatteryState {
     pub efficiency: f32,
     /// Wear cost per kWh cycled (CZK/kWh)
     pub wear_cost_czk_per_kwh: f32,
+    /// Battery temperature (°C), when reported by the inverter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature_c: Option<f32>,
 }
 
 /// Forecast data for the evaluation
@@ -64,6 +72,19 @@ pub struct ForecastData {
     pub grid_export_price_czk_per_kwh: f32,
 }
 
+/// Weather forecast for the evaluated block and the following day
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WeatherConditions {
+    /// Cloud cover during this block (%)
+    pub cloud_cover_pct: Option<f32>,
+    /// Air temperature during this block (°C)
+    pub temperature_c: Option<f32>,
+    /// Mean daylight cloud cover of the next local day (%)
+    pub next_day_cloud_cover_pct: Option<f32>,
+    /// Lowest temperature of the next local day (°C)
+    pub next_day_min_temperature_c: Option<f32>,
+}
+
 /// Historical data for strategy analysis
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoricalData {
@@ -93,7 +114,7 @@ pub struct EvaluationRequest {
     /// Backup discharge minimum SOC from inverter (%)
     pub backup_discharge_min_soc: f32,
     /// Raw HDO (grid tariff) sensor data for V3 strategy
-    /// Contains JSON with low/high tariff periods
+    /// Contains JSON with low/high tariff periods; prefer the parsed `tariff` of each block
     #[serde(default)]
     pub hdo_raw_data: Option<String>,
     /// Total solar production forecast for today (kWh)
@@ -113,6 +134,10 @@ pub struct EvaluationRequest {
     /// Used to calculate arbitrage profit during discharge
     #[serde(default)]
     pub battery_avg_charge_price_czk_per_kwh: f32,
+
+    /// Weather forecast, when a weather source is configured
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weather: Option<WeatherConditions>,
 }
 
 /// Operation mode decision
@@ -168,6 +193,9 @@ pub struct BlockDecision {
     pub mode: OperationMode,
     /// Human-readable reason for this decision
     pub reason: String,
+    /// Structured reason for this decision, when the plugin provides one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision_reason: Option<fluxion_types::DecisionReason>,
     /// Plugin priority (0-100, higher wins in conflicts)
     pub priority: u8,
     /// Name of the strategy/plugin that generated this decision
@@ -182,10 +210,28 @@ pub struct BlockDecision {
     /// Unique identifier for the decision logic path
     #[serde(default)]
     pub decision_uid: Option<String>,
+    /// PV energy the decision loses to the inverter AC limit or export cap (kWh)
+    #[serde(default)]
+    pub curtailed_solar_kwh: Option<f32>,
+    /// Other modes the plugin weighed for this block and why it passed on them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub considered_alternatives: Vec<ConsideredAlternative>,
 }
 
-/// Plugin manifest describing a strategy plugin
+/// A mode a plugin considered but did not choose
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsideredAlternative {
+    /// Mode that was considered
+    pub mode: OperationMode,
+    /// Profit the mode would have made (CZK)
+    #[serde(default)]
+    pub expected_profit_czk: Option<f32>,
+    /// Why the plugin chose something else
+    pub rejection_reason: String,
+}
+
+/// Plugin manifest describing a strategy plugin
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PluginManifest {
     /// Unique plugin name
     pub name: String,
@@ -198,8 +244,63 @@ pub struct PluginManifest {
     /// Whether the plugin is enabled by default
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    /// Structured documentation shown on the strategy docs page
+    #[serde(default)]
+    pub docs: Option<PluginDocs>,
 }
 
 fn default_enabled() -> bool {
     true
 }
+
+/// Structured documentation of what a strategy plugin does
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct PluginDocs {
+    /// Short description of the strategy's goal
+    pub summary: String,
+    /// Data the strategy reads when deciding (prices, SOC, forecasts, ...)
+    #[serde(default)]
+    pub inputs: Vec<String>,
+    /// Decision rules, in the order they are applied
+    #[serde(default)]
+    pub decision_logic: Vec<String>,
+    /// Tunable parameters with their current values
+    #[serde(default)]
+    pub parameters: Vec<PluginParameterDoc>,
+}
+
+/// A documented strategy parameter
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PluginParameterDoc {
+    /// Config key of the parameter
+    pub name: String,
+    /// Current value
+    #[schema(value_type = Object)]
+    pub value: serde_json::Value,
+    /// What the parameter controls
+    pub description: String,
+}
+
+impl PluginParameterDoc {
+    /// Document parameter `name` with its current `value`
+    pub fn new(name: &str, value: impl Serialize, description: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            value: serde_json::to_value(value).unwrap_or_default(),
+            description: description.to_owned(),
+        }
+    }
+}
+
+/// Documentation of a registered plugin together with its runtime state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginDescription {
+    /// Unique plugin name
+    pub name: String,
+    /// Effective priority (0-100)
+    pub priority: u8,
+    /// Whether the plugin currently takes part in decisions
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub docs: PluginDocs,
+}
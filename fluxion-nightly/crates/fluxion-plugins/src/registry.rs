@@ -0,0 +1,355 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Persistent registry of external HTTP plugins.
+//!
+//! Plugins registered through `POST /api/plugins/register` are stored with
+//! their priority override and enabled flag in a JSON file, and registered
+//! again on startup. A plugin whose callback URL does not answer at startup
+//! stays registered and is reported by [`PluginRegistry::warnings`] until it
+//! answers or registers again.
+
+use crate::manager::PluginManager;
+use crate::protocol::{HttpPlugin, PluginManifest};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Timeout of an evaluation call when the plugin did not register with one
+pub const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// A stored external plugin registration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredPlugin {
+    pub manifest: PluginManifest,
+    pub callback_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    /// Priority set through `PUT /api/plugins/{name}/priority`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority_override: Option<u8>,
+    /// Enabled flag set through `PUT /api/plugins/{name}/enabled`
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl RegisteredPlugin {
+    /// Registration with the default priority and enabled
+    #[must_use]
+    pub fn new(manifest: PluginManifest, callback_url: String, timeout_ms: Option<u64>) -> Self {
+        Self {
+            manifest,
+            callback_url,
+            timeout_ms,
+            priority_override: None,
+            enabled: true,
+        }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS))
+    }
+
+    /// Plugin evaluating through the callback URL
+    #[must_use]
+    pub fn to_plugin(&self) -> HttpPlugin {
+        match self.timeout_ms {
+            Some(_) => HttpPlugin::with_settings(
+                self.manifest.clone(),
+                self.callback_url.clone(),
+                self.timeout(),
+                crate::CircuitBreakerSettings::default().failure_threshold,
+            ),
+            None => HttpPlugin::new(self.manifest.clone(), self.callback_url.clone()),
+        }
+    }
+
+    /// Register the plugin with its stored priority override and enabled flag
+    pub fn register(&self, manager: &mut PluginManager) {
+        manager.register(Arc::new(self.to_plugin()));
+        if let Some(priority) = self.priority_override {
+            manager.set_priority(self.name(), priority);
+        }
+        if !self.enabled {
+            manager.set_enabled(self.name(), false);
+        }
+    }
+
+    /// Whether anything answers at the callback URL
+    ///
+    /// Any HTTP response counts, as the URL only has to accept evaluation
+    /// POSTs.
+    fn probe(&self) -> Result<(), String> {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(self.timeout())
+            .build()
+            .map_err(|e| e.to_string())?;
+        client
+            .get(&self.callback_url)
+            .send()
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// External plugin registrations persisted in a JSON file
+#[derive(Debug)]
+pub struct PluginRegistry {
+    path: PathBuf,
+    plugins: Mutex<Vec<RegisteredPlugin>>,
+    /// Error per plugin that did not answer the startup check
+    unreachable: Mutex<BTreeMap<String, String>>,
+}
+
+impl PluginRegistry {
+    /// Read the registry from `path`; a missing or unreadable file is empty
+    #[must_use]
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let plugins = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("⚠️ Failed to parse {}: {e}", path.display());
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            path,
+            plugins: Mutex::new(plugins),
+            unreachable: Mutex::default(),
+        }
+    }
+
+    /// File the registry is stored in
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// All stored registrations
+    #[must_use]
+    pub fn plugins(&self) -> Vec<RegisteredPlugin> {
+        self.lock_plugins().clone()
+    }
+
+    /// Stored registration of `name`
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<RegisteredPlugin> {
+        self.lock_plugins()
+            .iter()
+            .find(|p| p.name() == name)
+            .cloned()
+    }
+
+    /// Register every stored plugin in `manager`
+    pub fn register_all(&self, manager: &mut PluginManager) {
+        let plugins = self.lock_plugins();
+        for plugin in plugins.iter() {
+            plugin.register(manager);
+        }
+        if !plugins.is_empty() {
+            info!(
+                "🔌 Restored {} external plugins from {}",
+                plugins.len(),
+                self.path.display()
+            );
+        }
+    }
+
+    /// Store a registration, replacing the manifest, URL and timeout of one
+    /// with the same name but keeping its priority override and enabled flag
+    ///
+    /// A plugin registering again has proven it is reachable.
+    pub fn upsert(&self, plugin: RegisteredPlugin) -> std::io::Result<()> {
+        self.lock_unreachable().remove(plugin.name());
+        let mut plugins = self.lock_plugins();
+        match plugins.iter_mut().find(|p| p.name() == plugin.name()) {
+            Some(existing) => {
+                existing.manifest = plugin.manifest;
+                existing.callback_url = plugin.callback_url;
+                existing.timeout_ms = plugin.timeout_ms;
+            }
+            None => plugins.push(plugin),
+        }
+        self.save(&plugins)
+    }
+
+    /// Change a stored registration; `Ok(false)` if `name` is not stored
+    pub fn update(
+        &self,
+        name: &str,
+        change: impl FnOnce(&mut RegisteredPlugin),
+    ) -> std::io::Result<bool> {
+        let mut plugins = self.lock_plugins();
+        let Some(plugin) = plugins.iter_mut().find(|p| p.name() == name) else {
+            return Ok(false);
+        };
+        change(plugin);
+        self.save(&plugins)?;
+        Ok(true)
+    }
+
+    /// Forget a registration; `Ok(false)` if `name` is not stored
+    pub fn remove(&self, name: &str) -> std::io::Result<bool> {
+        self.lock_unreachable().remove(name);
+        let mut plugins = self.lock_plugins();
+        let original_len = plugins.len();
+        plugins.retain(|p| p.name() != name);
+        if plugins.len() == original_len {
+            return Ok(false);
+        }
+        self.save(&plugins)?;
+        Ok(true)
+    }
+
+    /// Probe the callback URL of every stored plugin
+    ///
+    /// Blocks for up to each plugin's timeout. Unreachable plugins stay
+    /// registered, so they recover by themselves once their service is up.
+    pub fn check_reachability(&self) {
+        for plugin in self.plugins() {
+            match plugin.probe() {
+                Ok(()) => {
+                    self.lock_unreachable().remove(plugin.name());
+                }
+                Err(e) => {
+                    warn!(
+                        "⚠️ External plugin {} is not reachable at {}: {e}",
+                        plugin.name(),
+                        plugin.callback_url
+                    );
+                    self.lock_unreachable().insert(plugin.name().to_owned(), e);
+                }
+            }
+        }
+    }
+
+    /// One message per stored plugin that failed the reachability check
+    #[must_use]
+    pub fn warnings(&self) -> Vec<String> {
+        let plugins = self.plugins();
+        self.lock_unreachable()
+            .iter()
+            .map(|(name, error)| {
+                let url = plugins
+                    .iter()
+                    .find(|p| p.name() == name)
+                    .map_or("", |p| p.callback_url.as_str());
+                format!(
+                    "External plugin '{name}' is not reachable at {url} ({error}). \
+                     It stays registered and is retried automatically."
+                )
+            })
+            .collect()
+    }
+
+    fn lock_plugins(&self) -> MutexGuard<'_, Vec<RegisteredPlugin>> {
+        self.plugins.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock_unreachable(&self) -> MutexGuard<'_, BTreeMap<String, String>> {
+        self.unreachable
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn save(&self, plugins: &[RegisteredPlugin]) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(plugins).map_err(std::io::Error::other)?;
+        std::fs::write(&self.path, json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Plugin;
+
+    fn registration(name: &str, callback_url: &str) -> RegisteredPlugin {
+        RegisteredPlugin::new(
+            PluginManifest {
+                name: name.to_owned(),
+                version: "1.0.0".to_owned(),
+                description: String::new(),
+                default_priority: 60,
+                enabled: true,
+                docs: None,
+            },
+            callback_url.to_owned(),
+            Some(200),
+        )
+    }
+
+    #[test]
+    fn test_registry_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plugins.json");
+
+        let registry = PluginRegistry::load(&path);
+        registry
+            .upsert(registration("ml", "http://127.0.0.1:1/evaluate"))
+            .unwrap();
+        registry
+            .upsert(registration("rules", "http://127.0.0.1:1/rules"))
+            .unwrap();
+        assert!(
+            registry
+                .update("ml", |p| {
+                    p.priority_override = Some(95);
+                    p.enabled = false;
+                })
+                .unwrap()
+        );
+        assert!(registry.remove("rules").unwrap());
+        assert!(!registry.update("missing", |_| {}).unwrap());
+
+        let restored = PluginRegistry::load(&path);
+        assert_eq!(
+            serde_json::to_value(restored.plugins()).unwrap(),
+            serde_json::to_value(registry.plugins()).unwrap()
+        );
+        let mut manager = PluginManager::new();
+        restored.register_all(&mut manager);
+        assert_eq!(manager.list_plugins(), [("ml", 95, false)]);
+        assert_eq!(
+            registration("ml", "http://x").to_plugin().timeout(),
+            Some(Duration::from_millis(200))
+        );
+
+        // Nothing listens on port 1
+        restored.check_reachability();
+        let warnings = restored.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'ml'"));
+        restored
+            .upsert(registration("ml", "http://127.0.0.1:2/evaluate"))
+            .unwrap();
+        assert_eq!(restored.warnings(), Vec::<String>::new());
+        let ml = &restored.plugins()[0];
+        assert_eq!(ml.callback_url, "http://127.0.0.1:2/evaluate");
+        assert_eq!((ml.priority_override, ml.enabled), (Some(95), false));
+    }
+}
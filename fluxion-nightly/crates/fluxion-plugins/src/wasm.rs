@@ -0,0 +1,562 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+//! Sandboxed WebAssembly strategy plugins.
+//!
+//! A `.wasm` file dropped into the plugin directory is compiled with wasmtime,
+//! instantiated without any imports (no WASI, no host functions, so no file,
+//! network or clock access) and registered as `wasm:<file stem>`. Changed
+//! files are reloaded and removed files unregistered on the next
+//! [`WasmPluginDirectory::scan`].
+//!
+//! ## Module contract
+//!
+//! The module exports its `memory` and:
+//! - `fluxion_alloc(len: i32) -> i32`: buffer of `len` bytes for the host to write into
+//! - `fluxion_evaluate(ptr: i32, len: i32) -> i64`: reads an [`EvaluationRequest`]
+//!   as JSON from the buffer and returns a [`BlockDecision`] as JSON, packed as
+//!   `(ptr << 32) | len`
+//! - optionally `fluxion_manifest() -> i64`: a [`PluginManifest`] as JSON, packed
+//!   the same way; priority and description default to 50 and an empty string
+//!
+//! Every call gets a fixed fuel budget, so a runaway loop traps instead of
+//! stalling planning, and linear memory is capped. A trapped instance is
+//! replaced before the next evaluation, and like [`HttpPlugin`](crate::HttpPlugin)
+//! the plugin is disabled after consecutive failures.
+
+use crate::manager::{Plugin, PluginManager};
+use crate::protocol::{BlockDecision, EvaluationRequest, PluginDocs, PluginManifest};
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+use tracing::{debug, error, info, warn};
+use wasmtime::{
+    Config, Engine, Instance, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc,
+};
+
+/// Prefix of the registered names of WASM plugins
+pub const WASM_PLUGIN_PREFIX: &str = "wasm:";
+
+/// Instructions (roughly) a plugin may execute per call
+const FUEL_PER_CALL: u64 = 1_000_000_000;
+
+/// Largest linear memory a plugin may use (64 MiB)
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+
+/// Largest manifest or decision a plugin may return
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+
+/// Priority of plugins without a manifest
+const DEFAULT_PRIORITY: u8 = 50;
+
+/// Consecutive failures before a plugin is disabled
+const MAX_FAILURES: u32 = 3;
+
+/// Engine shared by all plugins, with fuel metering
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        Engine::new(&config).expect("Fuel metering is supported by every target")
+    })
+}
+
+/// Registered name of the plugin in `path`
+#[must_use]
+pub fn plugin_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    format!("{WASM_PLUGIN_PREFIX}{stem}")
+}
+
+/// Split a packed `(ptr << 32) | len` result
+fn unpack(packed: i64) -> (usize, usize) {
+    #[expect(clippy::cast_sign_loss)]
+    let packed = packed as u64;
+    let ptr = usize::try_from(packed >> 32).unwrap_or(usize::MAX);
+    let len = usize::try_from(packed & 0xFFFF_FFFF).unwrap_or(usize::MAX);
+    (ptr, len)
+}
+
+/// One instance of a plugin module and its exports
+struct WasmInstance {
+    store: Store<StoreLimits>,
+    /// Fuel available to every call
+    fuel: u64,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    evaluate: TypedFunc<(i32, i32), i64>,
+    manifest: Option<TypedFunc<(), i64>>,
+}
+
+impl WasmInstance {
+    fn new(module: &Module, fuel: u64) -> Result<Self> {
+        if let Some(import) = module.imports().next() {
+            anyhow::bail!(
+                "Modules must not import anything, found {}::{}",
+                import.module(),
+                import.name()
+            );
+        }
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_MEMORY_BYTES)
+            .instances(1)
+            .build();
+        let mut store = Store::new(engine(), limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(fuel)?;
+        let instance = Instance::new(&mut store, module, &[]).context("Failed to instantiate")?;
+        Ok(Self {
+            memory: instance
+                .get_memory(&mut store, "memory")
+                .context("Missing `memory` export")?,
+            alloc: instance
+                .get_typed_func(&mut store, "fluxion_alloc")
+                .context("Missing `fluxion_alloc(i32) -> i32` export")?,
+            evaluate: instance
+                .get_typed_func(&mut store, "fluxion_evaluate")
+                .context("Missing `fluxion_evaluate(i32, i32) -> i64` export")?,
+            manifest: instance.get_typed_func(&mut store, "fluxion_manifest").ok(),
+            store,
+            fuel,
+        })
+    }
+
+    fn read(&self, packed: i64) -> Result<Vec<u8>> {
+        let (ptr, len) = unpack(packed);
+        anyhow::ensure!(
+            len <= MAX_OUTPUT_BYTES,
+            "Output of {len} bytes exceeds {MAX_OUTPUT_BYTES}"
+        );
+        ptr.checked_add(len)
+            .and_then(|end| self.memory.data(&self.store).get(ptr..end))
+            .map(<[u8]>::to_vec)
+            .context("Output lies outside linear memory")
+    }
+
+    fn write(&mut self, input: &[u8]) -> Result<(i32, i32)> {
+        let len = i32::try_from(input.len()).context("Input too large")?;
+        let ptr = self
+            .alloc
+            .call(&mut self.store, len)
+            .context("fluxion_alloc trapped")?;
+        let start = usize::try_from(ptr).context("fluxion_alloc returned a negative pointer")?;
+        start
+            .checked_add(input.len())
+            .and_then(|end| self.memory.data_mut(&mut self.store).get_mut(start..end))
+            .context("fluxion_alloc returned a buffer outside linear memory")?
+            .copy_from_slice(input);
+        Ok((ptr, len))
+    }
+
+    fn manifest(&mut self) -> Result<Option<PluginManifest>> {
+        let Some(manifest) = self.manifest.clone() else {
+            return Ok(None);
+        };
+        self.store.set_fuel(self.fuel)?;
+        let packed = manifest
+            .call(&mut self.store, ())
+            .context("fluxion_manifest trapped")?;
+        serde_json::from_slice(&self.read(packed)?)
+            .map(Some)
+            .context("Invalid manifest JSON")
+    }
+
+    fn evaluate(&mut self, request: &[u8]) -> Result<Vec<u8>> {
+        self.store.set_fuel(self.fuel)?;
+        let (ptr, len) = self.write(request)?;
+        let packed = self
+            .evaluate
+            .clone()
+            .call(&mut self.store, (ptr, len))
+            .context("fluxion_evaluate trapped")?;
+        self.read(packed)
+    }
+}
+
+/// Strategy plugin compiled from a `.wasm` file
+pub struct WasmPlugin {
+    manifest: PluginManifest,
+    path: PathBuf,
+    module: Module,
+    fuel: u64,
+    /// Replaced after a failed call so a trap cannot corrupt the next one
+    instance: Mutex<Option<WasmInstance>>,
+    /// Number of consecutive failures
+    failure_count: AtomicU32,
+}
+
+impl std::fmt::Debug for WasmPlugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WasmPlugin")
+            .field("name", &self.manifest.name)
+            .field("path", &self.path)
+            .field("priority", &self.manifest.default_priority)
+            .field("failure_count", &self.failure_count.load(Ordering::Relaxed))
+            .finish_non_exhaustive()
+    }
+}
+
+impl WasmPlugin {
+    /// Compile and instantiate the module in `path`
+    ///
+    /// # Errors
+    /// The file cannot be read, is not a valid module, imports anything, lacks
+    /// a required export or its manifest is invalid
+    pub fn load(path: &Path) -> Result<Self> {
+        let bytes =
+            std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_bytes(path, &bytes, FUEL_PER_CALL)
+    }
+
+    fn from_bytes(path: &Path, bytes: &[u8], fuel: u64) -> Result<Self> {
+        let module = Module::new(engine(), bytes).context("Invalid WebAssembly module")?;
+        let mut instance = WasmInstance::new(&module, fuel)?;
+        let name = plugin_name(path);
+        let manifest = match instance.manifest()? {
+            Some(manifest) => PluginManifest { name, ..manifest },
+            None => PluginManifest {
+                name,
+                version: String::new(),
+                description: String::new(),
+                default_priority: DEFAULT_PRIORITY,
+                enabled: true,
+                docs: None,
+            },
+        };
+        Ok(Self {
+            manifest,
+            path: path.to_path_buf(),
+            module,
+            fuel,
+            instance: Mutex::new(Some(instance)),
+            failure_count: AtomicU32::new(0),
+        })
+    }
+
+    /// File the plugin was loaded from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the current failure count
+    pub fn failure_count(&self) -> u32 {
+        self.failure_count.load(Ordering::Relaxed)
+    }
+
+    fn call(&self, request: &EvaluationRequest) -> Result<BlockDecision> {
+        let json = serde_json::to_vec(request)?;
+        let mut slot = self
+            .instance
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Plugin instance lock poisoned"))?;
+        let mut instance = match slot.take() {
+            Some(instance) => instance,
+            None => WasmInstance::new(&self.module, self.fuel)?,
+        };
+        let decision = instance.evaluate(&json)?;
+        *slot = Some(instance);
+        serde_json::from_slice(&decision).context("Invalid decision JSON")
+    }
+}
+
+impl Plugin for WasmPlugin {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn priority(&self) -> u8 {
+        self.manifest.default_priority
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.failure_count.load(Ordering::Relaxed) < MAX_FAILURES && self.manifest.enabled
+    }
+
+    fn describe(&self) -> PluginDocs {
+        self.manifest.docs.clone().unwrap_or_else(|| PluginDocs {
+            summary: self.manifest.description.clone(),
+            ..PluginDocs::default()
+        })
+    }
+
+    fn reset_failures(&self) {
+        self.failure_count.store(0, Ordering::Relaxed);
+    }
+
+    fn evaluate(&self, request: &EvaluationRequest) -> Result<BlockDecision> {
+        debug!(
+            "WasmPlugin {} evaluating block at {}",
+            self.manifest.name, request.block.block_start
+        );
+        match self.call(request) {
+            Ok(mut decision) => {
+                self.failure_count.store(0, Ordering::Relaxed);
+                decision.priority = self.manifest.default_priority;
+                if decision.strategy_name.is_none() {
+                    decision.strategy_name = Some(self.manifest.name.clone());
+                }
+                Ok(decision)
+            }
+            Err(e) => {
+                let failures = self.failure_count.fetch_add(1, Ordering::Relaxed) + 1;
+                error!("WasmPlugin {} failed: {e:#}", self.manifest.name);
+                if failures == MAX_FAILURES {
+                    warn!(
+                        "Plugin {} has {failures} consecutive failures, auto-disabling",
+                        self.manifest.name
+                    );
+                }
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Change found by [`WasmPluginDirectory::scan`]
+#[derive(Debug)]
+pub enum WasmPluginChange {
+    /// New or modified module
+    Loaded(Arc<WasmPlugin>),
+    /// Module file was deleted
+    Removed(String),
+}
+
+impl WasmPluginChange {
+    /// Register or unregister the plugin
+    pub fn apply(self, manager: &mut PluginManager) {
+        match self {
+            WasmPluginChange::Loaded(plugin) => {
+                info!(
+                    "Loaded WASM plugin {} from {}",
+                    plugin.name(),
+                    plugin.path().display()
+                );
+                manager.replace(plugin);
+            }
+            WasmPluginChange::Removed(name) => {
+                info!("Unloaded WASM plugin {name}");
+                manager.unregister(&name);
+            }
+        }
+    }
+}
+
+/// Directory of `.wasm` plugins, tracked by modification time
+#[derive(Debug)]
+pub struct WasmPluginDirectory {
+    dir: PathBuf,
+    /// Modification time of every module seen, with its name once loaded
+    seen: HashMap<PathBuf, (SystemTime, Option<String>)>,
+}
+
+impl WasmPluginDirectory {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            seen: HashMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Load new and modified modules and report removed ones
+    ///
+    /// A module that fails to load is retried once its file changes.
+    pub fn scan(&mut self) -> Vec<WasmPluginChange> {
+        let mut present: HashMap<PathBuf, SystemTime> = HashMap::new();
+        match std::fs::read_dir(&self.dir) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.extension().is_some_and(|ext| ext == "wasm")
+                        && let Ok(modified) = entry.metadata().and_then(|m| m.modified())
+                    {
+                        present.insert(path, modified);
+                    }
+                }
+            }
+            Err(e) => debug!(
+                "WASM plugin directory {} unreadable: {e}",
+                self.dir.display()
+            ),
+        }
+
+        let mut changes = Vec::new();
+        self.seen.retain(|path, (_, name)| {
+            let kept = present.contains_key(path);
+            if !kept && let Some(name) = name.take() {
+                changes.push(WasmPluginChange::Removed(name));
+            }
+            kept
+        });
+        for (path, modified) in present {
+            if self
+                .seen
+                .get(&path)
+                .is_some_and(|(seen, _)| *seen == modified)
+            {
+                continue;
+            }
+            let name = match WasmPlugin::load(&path) {
+                Ok(plugin) => {
+                    let name = plugin.name().to_owned();
+                    changes.push(WasmPluginChange::Loaded(Arc::new(plugin)));
+                    Some(name)
+                }
+                Err(e) => {
+                    warn!("Failed to load WASM plugin {}: {e:#}", path.display());
+                    // A previously loaded version is unloaded
+                    if let Some((_, Some(name))) = self.seen.get(&path) {
+                        changes.push(WasmPluginChange::Removed(name.clone()));
+                    }
+                    None
+                }
+            };
+            self.seen.insert(path, (modified, name));
+        }
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write;
+
+    const DECISION: &str = r#"{"block_start":"BLOCK_START","duration_minutes":15,"mode":"ForceCharge","reason":"cheap","priority":0}"#;
+
+    /// Module answering every request with a fixed decision at offset 0
+    fn module(block_start: &str, evaluate_body: &str, manifest: Option<&str>) -> Vec<u8> {
+        let decision = DECISION.replace("BLOCK_START", block_start);
+        let mut wat = format!(
+            r#"(module
+                (memory (export "memory") 1)
+                (data (i32.const 0) "{}")
+                (func (export "fluxion_alloc") (param i32) (result i32) (i32.const 4096))
+                (func (export "fluxion_evaluate") (param i32 i32) (result i64)
+                    {evaluate_body}
+                    (i64.const {})))"#,
+            decision.replace('"', "\\\""),
+            decision.len()
+        );
+        if let Some(manifest) = manifest {
+            wat.truncate(wat.len() - 1);
+            write!(
+                wat,
+                r#"(data (i32.const 2048) "{}")
+                (func (export "fluxion_manifest") (result i64)
+                    (i64.const {})))"#,
+                manifest.replace('"', "\\\""),
+                (2048_i64 << 32) | i64::try_from(manifest.len()).unwrap()
+            )
+            .unwrap();
+        }
+        wat.into_bytes()
+    }
+
+    fn request() -> EvaluationRequest {
+        crate::fallback::tests::request(&[1.0, 2.0, 3.0, 4.0], 0, 50.0)
+    }
+
+    #[test]
+    fn test_module_decision_and_manifest() {
+        let request = request();
+        let block_start = request.block.block_start.to_rfc3339();
+        let manifest = r#"{"name":"ignored","version":"1.0.0","description":"Cheap hours","default_priority":70}"#;
+        let bytes = module(&block_start, "", Some(manifest));
+        let plugin =
+            WasmPlugin::from_bytes(Path::new("/data/plugins/cheap.wasm"), &bytes, FUEL_PER_CALL)
+                .unwrap();
+
+        assert_eq!(plugin.name(), "wasm:cheap");
+        assert_eq!(plugin.priority(), 70);
+        assert_eq!(plugin.describe().summary, "Cheap hours");
+        let decision = plugin.evaluate(&request).unwrap();
+        assert_eq!(decision.mode, crate::OperationMode::ForceCharge);
+        assert_eq!(decision.priority, 70);
+        assert_eq!(decision.strategy_name.as_deref(), Some("wasm:cheap"));
+    }
+
+    #[test]
+    fn test_runaway_and_importing_modules() {
+        let request = request();
+        let block_start = request.block.block_start.to_rfc3339();
+        let looping = module(&block_start, "(loop $spin (br $spin))", None);
+        let plugin = WasmPlugin::from_bytes(Path::new("spin.wasm"), &looping, 100_000).unwrap();
+        assert_eq!(plugin.priority(), DEFAULT_PRIORITY);
+        for _ in 0..MAX_FAILURES {
+            assert!(plugin.evaluate(&request).is_err());
+        }
+        assert!(!plugin.is_enabled());
+
+        let importing = br#"(module (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32))))"#;
+        let err =
+            WasmPlugin::from_bytes(Path::new("wasi.wasm"), importing, FUEL_PER_CALL).unwrap_err();
+        assert!(format!("{err:#}").contains("must not import"));
+    }
+
+    #[test]
+    fn test_directory_hot_loading() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cheap.wasm");
+        let mut directory = WasmPluginDirectory::new(dir.path());
+        let mut manager = PluginManager::new();
+        std::fs::write(&path, module("2025-06-01T10:00:00Z", "", None)).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        for change in directory.scan() {
+            change.apply(&mut manager);
+        }
+        assert_eq!(manager.list_plugins().len(), 1);
+        manager.set_priority("wasm:cheap", 90);
+        assert!(directory.scan().is_empty());
+
+        let touch = |secs: u64| {
+            let file = std::fs::File::options().write(true).open(&path).unwrap();
+            file.set_modified(SystemTime::now() + std::time::Duration::from_secs(secs))
+                .unwrap();
+        };
+        let apply = |changes: Vec<WasmPluginChange>, manager: &mut PluginManager| {
+            for change in changes {
+                change.apply(manager);
+            }
+        };
+
+        // A rewritten module replaces the plugin and keeps its settings
+        std::fs::write(&path, module("2025-06-01T11:00:00Z", "", None)).unwrap();
+        touch(60);
+        apply(directory.scan(), &mut manager);
+        assert_eq!(manager.list_plugins(), vec![("wasm:cheap", 90, true)]);
+
+        // A broken rewrite unloads it
+        std::fs::write(&path, b"not wasm").unwrap();
+        touch(120);
+        let changes = directory.scan();
+        assert!(
+            matches!(changes.as_slice(), [WasmPluginChange::Removed(name)] if name == "wasm:cheap")
+        );
+        apply(changes, &mut manager);
+        assert_eq!(manager.list_plugins(), []);
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(directory.scan().is_empty());
+    }
+}
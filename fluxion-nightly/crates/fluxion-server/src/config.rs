@@ -11,9 +11,14 @@
 // For commercial licensing, please contact: info@solare.cz
 
 use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Utc};
+use fluxion_shared::license::LicenseFeature;
 use serde::Deserialize;
 use std::path::Path;
 
+/// Shortest accepted license key, so keys cannot be guessed
+const MIN_LICENSE_KEY_LEN: usize = 16;
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct ServerConfig {
     pub server: ServerSettings,
@@ -23,6 +28,8 @@ pub struct ServerConfig {
     pub email: EmailSettings,
     #[serde(default)]
     pub database: DatabaseSettings,
+    #[serde(default)]
+    pub license: LicenseSettings,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -57,6 +64,30 @@ pub struct EmailSettings {
     #[serde(default = "default_use_tls")]
     pub use_tls: bool,
     pub admin_recipients: Vec<String>,
+    /// Product name used in alert subjects and bodies
+    #[serde(default = "default_product_name")]
+    pub product_name: String,
+}
+
+/// Commercial license keys issued to instances
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LicenseSettings {
+    /// Store telemetry only from instances with a valid fleet license
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub keys: Vec<LicenseKeySettings>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LicenseKeySettings {
+    pub key: String,
+    pub licensee: String,
+    /// None for perpetual licenses
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default = "default_license_features")]
+    pub features: Vec<LicenseFeature>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -91,6 +122,14 @@ fn default_use_tls() -> bool {
     true
 }
 
+fn default_product_name() -> String {
+    "FluxION".to_owned()
+}
+
+fn default_license_features() -> Vec<LicenseFeature> {
+    vec![LicenseFeature::Fleet]
+}
+
 fn default_db_path() -> String {
     "./data/fluxion-server.db".to_owned()
 }
@@ -139,6 +178,14 @@ impl ServerConfig {
         if self.email.admin_recipients.is_empty() {
             bail!("email.admin_recipients must contain at least one address");
         }
+        for (i, license) in self.license.keys.iter().enumerate() {
+            if license.key.len() < MIN_LICENSE_KEY_LEN {
+                bail!("license.keys[{i}].key must have at least {MIN_LICENSE_KEY_LEN} characters");
+            }
+            if self.license.keys[..i].iter().any(|l| l.key == license.key) {
+                bail!("license.keys[{i}].key is listed twice");
+            }
+        }
         Ok(())
     }
 }
@@ -167,6 +167,18 @@ impl Database {
             let _ = conn.execute_batch(&sql);
         }
 
+        // Migrate schedule_blocks: add forecast vs realized energy columns
+        let schedule_block_columns = [
+            "solar_forecast_kwh REAL",
+            "consumption_forecast_kwh REAL",
+            "solar_actual_kwh REAL",
+            "consumption_actual_kwh REAL",
+        ];
+        for col_def in &schedule_block_columns {
+            let sql = format!("ALTER TABLE schedule_blocks ADD COLUMN {col_def}");
+            let _ = conn.execute_batch(&sql);
+        }
+
         Ok(Self {
             conn: Mutex::new(conn),
         })
@@ -387,8 +399,8 @@ impl Database {
     ) -> Result<()> {
         let conn = self.conn.lock().expect("database mutex poisoned");
         let mut stmt = conn.prepare(
-            "INSERT INTO schedule_blocks (instance_id, snapshot_id, block_ts, price_czk, operation, target_soc, strategy, expected_profit, reason, is_historical)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            "INSERT INTO schedule_blocks (instance_id, snapshot_id, block_ts, price_czk, operation, target_soc, strategy, expected_profit, reason, is_historical, solar_forecast_kwh, consumption_forecast_kwh, solar_actual_kwh, consumption_actual_kwh)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
         )?;
 
         for block in &schedule.blocks {
@@ -403,6 +415,10 @@ impl Database {
                 block.expected_profit,
                 block.reason,
                 block.is_historical,
+                block.solar_forecast_kwh,
+                block.consumption_forecast_kwh,
+                block.solar_actual_kwh,
+                block.consumption_actual_kwh,
             ])?;
         }
 
@@ -23,6 +23,7 @@ use fluxion_shared::heartbeat::{HeartbeatRequest, HeartbeatResponse};
 
 use crate::config::ServerConfig;
 use crate::db::Database;
+use crate::license;
 use crate::notifications::EmailNotifier;
 
 #[derive(Debug, Clone)]
@@ -39,10 +40,10 @@ pub struct HeartbeatState {
 #[expect(clippy::unused_async, reason = "axum handler must be async")]
 pub async fn heartbeat_handler(
     State(state): State<HeartbeatState>,
-    Json(request): Json<HeartbeatRequest>,
+    Json(mut request): Json<HeartbeatRequest>,
 ) -> impl IntoResponse {
     // Validate shared secret
-    if request.shared_secret != state.config.auth.shared_secret {
+    if !license::secrets_match(&request.shared_secret, &state.config.auth.shared_secret) {
         warn!(
             instance_id = %request.instance_id,
             "Heartbeat rejected: invalid shared secret"
@@ -57,6 +58,23 @@ pub async fn heartbeat_handler(
         );
     }
 
+    // Without a required license only the status is kept; telemetry is a fleet feature
+    let fleet_allowed = license::fleet_allowed(
+        &state.config.license,
+        request.license_key.as_deref(),
+        Utc::now(),
+    );
+    if !fleet_allowed && (request.telemetry.is_some() || request.sync_data.is_some()) {
+        info!(
+            instance_id = %request.instance_id,
+            "Telemetry not stored: no valid fleet license"
+        );
+        request.telemetry = None;
+        request.sync_data = None;
+    }
+    // Keys stay out of the heartbeat log
+    request.license_key = None;
+
     // Check if client was previously offline (for recovery notification)
     let was_offline = state
         .db
@@ -104,11 +122,10 @@ pub async fn heartbeat_handler(
         {
             Ok(snapshot_id) => {
                 if let Some(ref schedule) = snapshot.schedule
-                    && let Err(e) = state.db.insert_schedule_blocks(
-                        &request.instance_id,
-                        snapshot_id,
-                        schedule,
-                    )
+                    && let Err(e) =
+                        state
+                            .db
+                            .insert_schedule_blocks(&request.instance_id, snapshot_id, schedule)
                 {
                     warn!(error = %e, "Failed to insert schedule blocks");
                 }
@@ -188,7 +205,9 @@ pub async fn heartbeat_handler(
         Json(HeartbeatResponse {
             ok: true,
             server_time: Utc::now(),
-            message: None,
+            message: (!fleet_allowed).then(|| {
+                "Telemetry is stored for licensed instances only; status was recorded".to_owned()
+            }),
         }),
     )
 }
@@ -14,5 +14,6 @@ pub mod config;
 pub mod dashboard;
 pub mod db;
 pub mod heartbeat;
+pub mod license;
 pub mod monitor;
 pub mod notifications;
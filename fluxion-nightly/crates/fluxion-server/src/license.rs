@@ -0,0 +1,123 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use axum::Json;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use chrono::{DateTime, Utc};
+use subtle::ConstantTimeEq;
+use tracing::{info, warn};
+
+use fluxion_shared::license::{
+    LicenseFeature, LicenseValidationRequest, LicenseValidationResponse,
+};
+
+use crate::config::LicenseSettings;
+use crate::heartbeat::HeartbeatState;
+
+/// Compare a presented secret in constant time, so the response time doesn't
+/// reveal how much of a guess was right
+#[must_use]
+pub fn secrets_match(given: &str, expected: &str) -> bool {
+    given.as_bytes().ct_eq(expected.as_bytes()).into()
+}
+
+/// Check `key` against the configured license keys at `now`
+#[must_use]
+pub fn check_license(
+    settings: &LicenseSettings,
+    key: &str,
+    now: DateTime<Utc>,
+) -> LicenseValidationResponse {
+    let invalid = |message: &str| LicenseValidationResponse {
+        valid: false,
+        server_time: now,
+        licensee: None,
+        expires_at: None,
+        features: Vec::new(),
+        message: Some(message.to_owned()),
+    };
+
+    // Compare against every key, so the time taken doesn't depend on which one matched
+    let key = key.trim();
+    let license = settings.keys.iter().fold(None, |found, license| {
+        if secrets_match(key, &license.key) {
+            Some(license)
+        } else {
+            found
+        }
+    });
+    let Some(license) = license else {
+        return invalid("Unknown license key");
+    };
+    if license.expires_at.is_some_and(|expires| expires <= now) {
+        return LicenseValidationResponse {
+            licensee: Some(license.licensee.clone()),
+            expires_at: license.expires_at,
+            ..invalid("License has expired")
+        };
+    }
+
+    LicenseValidationResponse {
+        valid: true,
+        server_time: now,
+        licensee: Some(license.licensee.clone()),
+        expires_at: license.expires_at,
+        features: license.features.clone(),
+        message: None,
+    }
+}
+
+/// Whether an instance presenting `key` may use fleet features
+#[must_use]
+pub fn fleet_allowed(settings: &LicenseSettings, key: Option<&str>, now: DateTime<Utc>) -> bool {
+    !settings.required
+        || key.is_some_and(|key| {
+            let license = check_license(settings, key, now);
+            license.valid && license.features.contains(&LicenseFeature::Fleet)
+        })
+}
+
+#[expect(clippy::unused_async, reason = "axum handler must be async")]
+pub async fn license_validate_handler(
+    State(state): State<HeartbeatState>,
+    Json(request): Json<LicenseValidationRequest>,
+) -> impl IntoResponse {
+    let now = Utc::now();
+    if !secrets_match(&request.shared_secret, &state.config.auth.shared_secret) {
+        warn!(
+            instance_id = %request.instance_id,
+            "License check rejected: invalid shared secret"
+        );
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(LicenseValidationResponse {
+                valid: false,
+                server_time: now,
+                licensee: None,
+                expires_at: None,
+                features: Vec::new(),
+                message: Some("Invalid shared secret".to_owned()),
+            }),
+        );
+    }
+
+    let response = check_license(&state.config.license, &request.license_key, now);
+    info!(
+        instance_id = %request.instance_id,
+        valid = response.valid,
+        licensee = ?response.licensee,
+        "License checked"
+    );
+    (StatusCode::OK, Json(response))
+}
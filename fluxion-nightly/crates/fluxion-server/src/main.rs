@@ -21,6 +21,7 @@ use fluxion_server::config::ServerConfig;
 use fluxion_server::dashboard::{self, DashboardState};
 use fluxion_server::db::Database;
 use fluxion_server::heartbeat::{self, HeartbeatState};
+use fluxion_server::license;
 use fluxion_server::monitor;
 use fluxion_server::notifications::EmailNotifier;
 
@@ -98,6 +99,10 @@ async fn main() -> anyhow::Result<()> {
     let app = Router::new()
         .route("/", get(dashboard::dashboard_handler))
         .with_state(dashboard_state)
+        .route(
+            "/api/license/validate",
+            post(license::license_validate_handler).with_state(heartbeat_state.clone()),
+        )
         .route(
             "/api/heartbeat",
             post(heartbeat::heartbeat_handler).with_state(heartbeat_state),
@@ -23,6 +23,7 @@ pub struct EmailNotifier {
     transport: AsyncSmtpTransport<Tokio1Executor>,
     from: Mailbox,
     admin_recipients: Vec<String>,
+    product_name: String,
 }
 
 impl EmailNotifier {
@@ -51,6 +52,7 @@ impl EmailNotifier {
             transport,
             from,
             admin_recipients: config.admin_recipients.clone(),
+            product_name: config.product_name.clone(),
         })
     }
 
@@ -65,20 +67,22 @@ impl EmailNotifier {
         friendly_name: &str,
         last_seen: &str,
     ) -> Result<()> {
-        let subject = format!("FluxION Alert: {friendly_name} is offline");
+        let product = &self.product_name;
+        let subject = format!("{product} Alert: {friendly_name} is offline");
         let body = format!(
-            "FluxION instance '{friendly_name}' (ID: {instance_id}) has gone offline.\n\n\
+            "{product} instance '{friendly_name}' (ID: {instance_id}) has gone offline.\n\n\
              Last heartbeat received: {last_seen}\n\n\
-             Please check the instance status at your FluxION Server dashboard."
+             Please check the instance status at your {product} Server dashboard."
         );
 
         self.send_to_all(&subject, &body).await
     }
 
     pub async fn send_recovery_alert(&self, instance_id: &str, friendly_name: &str) -> Result<()> {
-        let subject = format!("FluxION Recovery: {friendly_name} is back online");
+        let product = &self.product_name;
+        let subject = format!("{product} Recovery: {friendly_name} is back online");
         let body = format!(
-            "FluxION instance '{friendly_name}' (ID: {instance_id}) has recovered and is back online."
+            "{product} instance '{friendly_name}' (ID: {instance_id}) has recovered and is back online."
         );
 
         self.send_to_all(&subject, &body).await
@@ -19,12 +19,15 @@ use chrono::Utc;
 use serde_json::json;
 
 use fluxion_server::config::{
-    AuthSettings, DatabaseSettings, EmailSettings, HeartbeatSettings, ServerConfig, ServerSettings,
+    AuthSettings, DatabaseSettings, EmailSettings, HeartbeatSettings, LicenseKeySettings,
+    LicenseSettings, ServerConfig, ServerSettings,
 };
 use fluxion_server::dashboard::{self, DashboardState};
 use fluxion_server::db::Database;
 use fluxion_server::heartbeat::{self, HeartbeatState};
+use fluxion_server::license;
 use fluxion_server::notifications::EmailNotifier;
+use fluxion_shared::license::LicenseFeature;
 
 const TEST_SECRET: &str = "test-secret-for-integration-tests";
 
@@ -50,8 +53,35 @@ fn test_config() -> ServerConfig {
             from_address: "test@example.com".to_owned(),
             use_tls: false,
             admin_recipients: vec!["admin@example.com".to_owned()],
+            product_name: "FluxION".to_owned(),
         },
         database: DatabaseSettings::default(),
+        license: LicenseSettings::default(),
+    }
+}
+
+const TEST_LICENSE_KEY: &str = "license-key-for-integration-tests";
+
+fn licensed_config() -> ServerConfig {
+    ServerConfig {
+        license: LicenseSettings {
+            required: true,
+            keys: vec![
+                LicenseKeySettings {
+                    key: TEST_LICENSE_KEY.to_owned(),
+                    licensee: "Example Solar".to_owned(),
+                    expires_at: None,
+                    features: vec![LicenseFeature::Fleet],
+                },
+                LicenseKeySettings {
+                    key: "expired-license-key-for-tests".to_owned(),
+                    licensee: "Former Customer".to_owned(),
+                    expires_at: Some(Utc::now() - chrono::Duration::days(1)),
+                    features: vec![LicenseFeature::Fleet],
+                },
+            ],
+        },
+        ..test_config()
     }
 }
 
@@ -63,7 +93,11 @@ struct TestServer {
 
 impl TestServer {
     async fn start() -> Self {
-        let config = Arc::new(test_config());
+        Self::start_with(test_config()).await
+    }
+
+    async fn start_with(config: ServerConfig) -> Self {
+        let config = Arc::new(config);
         let db = Arc::new(Database::open(":memory:").expect("Failed to open in-memory database"));
         let notifier =
             Arc::new(EmailNotifier::new(&config.email).expect("Failed to create test notifier"));
@@ -81,6 +115,10 @@ impl TestServer {
         let app = Router::new()
             .route("/", get(dashboard::dashboard_handler))
             .with_state(dashboard_state)
+            .route(
+                "/api/license/validate",
+                post(license::license_validate_handler).with_state(heartbeat_state.clone()),
+            )
             .route(
                 "/api/heartbeat",
                 post(heartbeat::heartbeat_handler).with_state(heartbeat_state),
@@ -537,6 +575,140 @@ async fn heartbeat_without_optional_status_fields() {
     assert!(clients[0].battery_soc.is_none());
 }
 
+// ---------------------------------------------------------------------------
+// License — validation and fleet gating
+// ---------------------------------------------------------------------------
+
+async fn validate_license(server: &TestServer, secret: &str, key: &str) -> reqwest::Response {
+    server
+        .client
+        .post(server.url("/api/license/validate"))
+        .json(&json!({
+            "instance_id": "lic-1",
+            "shared_secret": secret,
+            "license_key": key
+        }))
+        .send()
+        .await
+        .expect("Failed to send license request")
+}
+
+#[tokio::test]
+async fn license_validation_accepts_configured_key() {
+    let server = TestServer::start_with(licensed_config()).await;
+    let resp = validate_license(&server, TEST_SECRET, TEST_LICENSE_KEY).await;
+    assert_eq!(resp.status(), 200);
+
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["valid"], true);
+    assert_eq!(body["licensee"], "Example Solar");
+    assert_eq!(body["features"], json!(["fleet"]));
+}
+
+#[tokio::test]
+async fn license_validation_rejects_unknown_and_expired_keys() {
+    let server = TestServer::start_with(licensed_config()).await;
+
+    let body: serde_json::Value = validate_license(&server, TEST_SECRET, "not-a-license-key")
+        .await
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["valid"], false);
+    assert_eq!(body["message"], "Unknown license key");
+
+    let body: serde_json::Value =
+        validate_license(&server, TEST_SECRET, "expired-license-key-for-tests")
+            .await
+            .json()
+            .await
+            .unwrap();
+    assert_eq!(body["valid"], false);
+    assert_eq!(body["licensee"], "Former Customer");
+    assert_eq!(body["message"], "License has expired");
+}
+
+#[test]
+fn check_license_valid_expired_and_tampered() {
+    let settings = licensed_config().license;
+    let now = Utc::now();
+
+    let valid = license::check_license(&settings, &format!(" {TEST_LICENSE_KEY}\n"), now);
+    assert!(valid.valid);
+    assert_eq!(valid.licensee.as_deref(), Some("Example Solar"));
+
+    let expired = license::check_license(&settings, "expired-license-key-for-tests", now);
+    assert!(!expired.valid);
+    assert_eq!(expired.message.as_deref(), Some("License has expired"));
+
+    // One changed character, a truncated key and an extended key are all unknown
+    let mut flipped = TEST_LICENSE_KEY.to_owned().into_bytes();
+    flipped[TEST_LICENSE_KEY.len() - 1] ^= 1;
+    let flipped = String::from_utf8(flipped).unwrap();
+    let truncated = TEST_LICENSE_KEY.strip_suffix("s").unwrap();
+    let extended = format!("{TEST_LICENSE_KEY}x");
+    for tampered in [flipped.as_str(), truncated, extended.as_str(), ""] {
+        let response = license::check_license(&settings, tampered, now);
+        assert!(!response.valid, "{tampered:?} was accepted");
+        assert_eq!(response.licensee, None);
+        assert_eq!(response.message.as_deref(), Some("Unknown license key"));
+    }
+}
+
+#[test]
+fn secrets_match_only_identical_secrets() {
+    assert!(license::secrets_match(TEST_SECRET, TEST_SECRET));
+    assert!(!license::secrets_match(
+        TEST_SECRET.strip_prefix("t").unwrap(),
+        TEST_SECRET
+    ));
+    assert!(!license::secrets_match(
+        &format!("{TEST_SECRET}!"),
+        TEST_SECRET
+    ));
+    assert!(!license::secrets_match("", TEST_SECRET));
+}
+
+#[tokio::test]
+async fn license_validation_requires_shared_secret() {
+    let server = TestServer::start_with(licensed_config()).await;
+    let resp = validate_license(&server, "wrong-secret", TEST_LICENSE_KEY).await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[tokio::test]
+async fn required_license_keeps_status_but_drops_telemetry() {
+    let server = TestServer::start_with(licensed_config()).await;
+    let mut body = basic_heartbeat("unlicensed");
+    body["telemetry"] = sample_telemetry();
+    body["sync_data"] = sample_sync_data();
+
+    let resp = server.post_heartbeat(&body).await;
+    assert_eq!(resp.status(), 200);
+    let response: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(response["ok"], true);
+    assert!(response["message"].is_string());
+
+    let clients = server.db.get_all_clients().unwrap();
+    assert_eq!(clients[0].status, "online");
+    assert_eq!(clients[0].battery_soc, Some(65.0));
+    assert!(clients[0].latest_telemetry_json.is_none());
+    assert!(clients[0].battery_capacity_kwh.is_none());
+    assert_eq!(server.db.telemetry_snapshot_count("unlicensed").unwrap(), 0);
+}
+
+#[tokio::test]
+async fn required_license_stores_telemetry_of_licensed_instance() {
+    let server = TestServer::start_with(licensed_config()).await;
+    let mut body = basic_heartbeat("licensed");
+    body["telemetry"] = sample_telemetry();
+    body["license_key"] = json!(TEST_LICENSE_KEY);
+
+    let response: serde_json::Value = server.post_heartbeat(&body).await.json().await.unwrap();
+    assert!(response.get("message").is_none());
+    assert_eq!(server.db.telemetry_snapshot_count("licensed").unwrap(), 1);
+}
+
 // ---------------------------------------------------------------------------
 // Dashboard — rendering
 // ---------------------------------------------------------------------------
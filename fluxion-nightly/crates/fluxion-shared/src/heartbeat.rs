@@ -26,6 +26,9 @@ pub struct HeartbeatRequest {
     pub telemetry: Option<TelemetrySnapshot>,
     #[serde(default)]
     pub sync_data: Option<ClientSyncData>,
+    /// Commercial license key; servers requiring one store telemetry only with it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license_key: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
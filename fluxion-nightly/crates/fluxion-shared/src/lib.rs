@@ -11,4 +11,5 @@
 // For commercial licensing, please contact: info@solare.cz
 
 pub mod heartbeat;
+pub mod license;
 pub mod telemetry;
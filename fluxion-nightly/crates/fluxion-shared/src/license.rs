@@ -0,0 +1,46 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Features a commercial license can unlock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseFeature {
+    /// Telemetry and schedule history stored by FluxION Server
+    Fleet,
+    /// Feature introduced by a newer server
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LicenseValidationRequest {
+    pub instance_id: String,
+    pub shared_secret: String,
+    pub license_key: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LicenseValidationResponse {
+    pub valid: bool,
+    pub server_time: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub licensee: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub features: Vec<LicenseFeature>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
@@ -90,6 +90,15 @@ pub struct ScheduleBlockTelemetry {
     pub expected_profit: Option<f32>,
     pub reason: Option<String>,
     pub is_historical: bool,
+    // Forecast used at decision time and realized energy (kWh)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solar_forecast_kwh: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consumption_forecast_kwh: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub solar_actual_kwh: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub consumption_actual_kwh: Option<f32>,
 }
 
 /// Full schedule snapshot included in telemetry.
@@ -0,0 +1,569 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+
+//! Household appliance load profiles for synthetic test days.
+//!
+//! Appliance profiles are layered on top of the base [`ConsumptionProfile`]
+//! of a [`SyntheticDayConfig`] to model the large, time-shifted loads that
+//! dominate real households: heat pumps following the outdoor temperature,
+//! EV charging sessions and hot water boiler cycles.
+//!
+//! Profiles can be taken from the built-in presets or imported from a TOML
+//! or JSON file, and each one can be toggled on or off individually.
+//!
+//! [`ConsumptionProfile`]: crate::synthetic_data::ConsumptionProfile
+//! [`SyntheticDayConfig`]: crate::synthetic_data::SyntheticDayConfig
+
+use anyhow::{Context, Result, ensure};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Number of 15-minute blocks in a day
+const BLOCKS_PER_DAY: usize = 96;
+
+/// Duration of one block in hours
+const BLOCK_HOURS: f32 = 0.25;
+
+/// A single appliance contributing to household consumption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplianceProfile {
+    /// Display name (e.g., "Heat pump")
+    pub name: String,
+
+    /// Whether this appliance is included in the generated consumption
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Load model of the appliance
+    #[serde(flatten)]
+    pub kind: ApplianceKind,
+}
+
+/// Load model of an appliance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ApplianceKind {
+    /// Heat pump whose electrical power follows the outdoor temperature
+    ///
+    /// The outdoor temperature follows a daily curve between `min_temp_c`
+    /// (around 05:00) and `max_temp_c` (around 17:00). Power scales linearly
+    /// from zero at `heating_limit_c` to `rated_kw` at `design_temp_c`.
+    HeatPump {
+        /// Electrical power at the design temperature (kW)
+        rated_kw: f32,
+        /// Outdoor temperature the heat pump is sized for (°C)
+        design_temp_c: f32,
+        /// Outdoor temperature above which no heating is needed (°C)
+        heating_limit_c: f32,
+        /// Daily minimum outdoor temperature (°C)
+        min_temp_c: f32,
+        /// Daily maximum outdoor temperature (°C)
+        max_temp_c: f32,
+    },
+
+    /// EV charging session at constant power
+    ///
+    /// Charging starts at `arrival_hour` and runs until `energy_kwh` has been
+    /// delivered or the car leaves at `departure_hour`. Sessions may wrap
+    /// past midnight.
+    EvSession {
+        /// Charging power (kW)
+        charge_kw: f32,
+        /// Energy to deliver in the session (kWh)
+        energy_kwh: f32,
+        /// Hour the car is plugged in (0-23)
+        arrival_hour: u8,
+        /// Hour the car leaves (0-23)
+        departure_hour: u8,
+    },
+
+    /// Hot water boiler heating in allowed windows
+    ///
+    /// The daily energy is split across `periods` in proportion to their
+    /// length; each window heats at full power from its start until its
+    /// share is reached.
+    Boiler {
+        /// Heating element power (kW)
+        power_kw: f32,
+        /// Energy needed per day (kWh)
+        daily_energy_kwh: f32,
+        /// Allowed heating windows as (start_hour, end_hour), typically HDO
+        periods: Vec<(u8, u8)>,
+    },
+
+    /// Custom per-block load, e.g. from a metered appliance (96 values)
+    Custom {
+        /// 96 values for each 15-minute block (kW)
+        blocks_kw: Vec<f32>,
+    },
+}
+
+/// File format for imported appliance profiles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApplianceFile {
+    #[serde(default)]
+    pub appliances: Vec<ApplianceProfile>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// IDs of the built-in appliance presets
+pub const APPLIANCE_PRESET_IDS: &[&str] = &["heat_pump", "ev_overnight", "boiler_hdo"];
+
+impl ApplianceProfile {
+    /// Air-to-water heat pump on a cold winter day
+    /// ~3 kW at -12 °C, outdoor -4 to 3 °C, ~40 kWh/day
+    pub fn heat_pump() -> Self {
+        Self {
+            name: "Heat pump".to_string(),
+            enabled: true,
+            kind: ApplianceKind::HeatPump {
+                rated_kw: 3.0,
+                design_temp_c: -12.0,
+                heating_limit_c: 16.0,
+                min_temp_c: -4.0,
+                max_temp_c: 3.0,
+            },
+        }
+    }
+
+    /// EV plugged in at 18:00, 20 kWh at 7.4 kW before 07:00
+    pub fn ev_overnight() -> Self {
+        Self {
+            name: "EV overnight".to_string(),
+            enabled: true,
+            kind: ApplianceKind::EvSession {
+                charge_kw: 7.4,
+                energy_kwh: 20.0,
+                arrival_hour: 18,
+                departure_hour: 7,
+            },
+        }
+    }
+
+    /// 2 kW boiler, 6 kWh/day, heating only in HDO low tariff windows
+    pub fn boiler_hdo() -> Self {
+        Self {
+            name: "Boiler (HDO)".to_string(),
+            enabled: true,
+            kind: ApplianceKind::Boiler {
+                power_kw: 2.0,
+                daily_energy_kwh: 6.0,
+                periods: vec![(0, 6), (13, 15), (20, 22)],
+            },
+        }
+    }
+
+    /// Look up a built-in preset by ID
+    pub fn preset(id: &str) -> Option<Self> {
+        match id {
+            "heat_pump" => Some(Self::heat_pump()),
+            "ev_overnight" | "ev" => Some(Self::ev_overnight()),
+            "boiler_hdo" | "boiler" => Some(Self::boiler_hdo()),
+            _ => None,
+        }
+    }
+
+    /// Look up a built-in preset by ID (case-insensitive), failing on unknown IDs
+    pub fn require_preset(id: &str) -> Result<Self> {
+        Self::preset(&id.trim().to_lowercase()).with_context(|| {
+            format!(
+                "Unknown appliance preset '{}'. Available: {}",
+                id,
+                APPLIANCE_PRESET_IDS.join(", ")
+            )
+        })
+    }
+
+    /// Check that the profile describes a physically meaningful load
+    ///
+    /// Hours must be 0-23 (boiler windows may end at 24), powers and energies
+    /// finite and non-negative, and custom profiles must have one value per
+    /// 15-minute block.
+    pub fn validate(&self) -> Result<()> {
+        self.kind
+            .validate()
+            .with_context(|| format!("Invalid appliance '{}'", self.name))
+    }
+
+    /// Load appliance profiles from a TOML or JSON file
+    ///
+    /// Both formats contain an `appliances` list; JSON files may also hold
+    /// the bare list.
+    pub fn load_file(path: impl AsRef<Path>) -> Result<Vec<Self>> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read appliance file: {}", path.display()))?;
+
+        let is_json = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+
+        let appliances = if is_json {
+            match serde_json::from_str::<Vec<Self>>(&content) {
+                Ok(appliances) => appliances,
+                Err(_) => {
+                    let file: ApplianceFile =
+                        serde_json::from_str(&content).with_context(|| {
+                            format!("Failed to parse appliance JSON: {}", path.display())
+                        })?;
+                    file.appliances
+                }
+            }
+        } else {
+            let file: ApplianceFile = toml::from_str(&content)
+                .with_context(|| format!("Failed to parse appliance TOML: {}", path.display()))?;
+            file.appliances
+        };
+
+        for appliance in &appliances {
+            appliance.validate()?;
+        }
+        Ok(appliances)
+    }
+
+    /// Resolve a comma-separated list of preset IDs and file paths
+    ///
+    /// Entries ending in `.toml` or `.json` are imported from file, all
+    /// others are looked up as presets.
+    pub fn resolve_list(spec: &str) -> Result<Vec<Self>> {
+        let mut appliances = Vec::new();
+        for entry in spec.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let lower = entry.to_lowercase();
+            if lower.ends_with(".toml") || lower.ends_with(".json") {
+                appliances.extend(Self::load_file(entry)?);
+            } else {
+                appliances.push(Self::require_preset(entry)?);
+            }
+        }
+        Ok(appliances)
+    }
+
+    /// Consumption of this appliance for a block (kWh for the 15-minute period)
+    ///
+    /// Disabled appliances consume nothing.
+    pub fn consumption_for_block(&self, block_index: usize) -> f32 {
+        if !self.enabled {
+            return 0.0;
+        }
+        self.kind.consumption_for_block(block_index)
+    }
+
+    /// Total daily consumption of this appliance in kWh
+    pub fn total_daily_consumption_kwh(&self) -> f32 {
+        (0..BLOCKS_PER_DAY)
+            .map(|i| self.consumption_for_block(i))
+            .sum()
+    }
+}
+
+impl ApplianceKind {
+    /// Check parameter ranges, see [`ApplianceProfile::validate`]
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::HeatPump {
+                rated_kw,
+                design_temp_c,
+                heating_limit_c,
+                min_temp_c,
+                max_temp_c,
+            } => {
+                ensure_non_negative("rated_kw", *rated_kw)?;
+                for (field, value) in [
+                    ("design_temp_c", design_temp_c),
+                    ("heating_limit_c", heating_limit_c),
+                    ("min_temp_c", min_temp_c),
+                    ("max_temp_c", max_temp_c),
+                ] {
+                    ensure!(value.is_finite(), "{field} must be a finite number");
+                }
+            }
+
+            Self::EvSession {
+                charge_kw,
+                energy_kwh,
+                arrival_hour,
+                departure_hour,
+            } => {
+                ensure_non_negative("charge_kw", *charge_kw)?;
+                ensure_non_negative("energy_kwh", *energy_kwh)?;
+                ensure!(
+                    *arrival_hour < 24,
+                    "arrival_hour must be 0-23, got {arrival_hour}"
+                );
+                ensure!(
+                    *departure_hour < 24,
+                    "departure_hour must be 0-23, got {departure_hour}"
+                );
+            }
+
+            Self::Boiler {
+                power_kw,
+                daily_energy_kwh,
+                periods,
+            } => {
+                ensure_non_negative("power_kw", *power_kw)?;
+                ensure_non_negative("daily_energy_kwh", *daily_energy_kwh)?;
+                for &(start, end) in periods {
+                    ensure!(
+                        start < end && end <= 24,
+                        "heating period ({start}, {end}) must satisfy start < end <= 24"
+                    );
+                }
+            }
+
+            Self::Custom { blocks_kw } => {
+                ensure!(
+                    blocks_kw.len() == BLOCKS_PER_DAY,
+                    "blocks_kw must have {BLOCKS_PER_DAY} values, got {}",
+                    blocks_kw.len()
+                );
+                for &kw in blocks_kw {
+                    ensure_non_negative("blocks_kw", kw)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumption for a block (kWh for the 15-minute period)
+    pub fn consumption_for_block(&self, block_index: usize) -> f32 {
+        match self {
+            Self::HeatPump {
+                rated_kw,
+                design_temp_c,
+                heating_limit_c,
+                min_temp_c,
+                max_temp_c,
+            } => {
+                let temp = outdoor_temp_c(block_index, *min_temp_c, *max_temp_c);
+                let span = heating_limit_c - design_temp_c;
+                if span <= 0.0 {
+                    return 0.0;
+                }
+                let load_factor = ((heating_limit_c - temp) / span).clamp(0.0, 1.0);
+                rated_kw * load_factor * BLOCK_HOURS
+            }
+
+            Self::EvSession {
+                charge_kw,
+                energy_kwh,
+                arrival_hour,
+                departure_hour,
+            } => {
+                let start = *arrival_hour as usize * 4;
+                let end = *departure_hour as usize * 4;
+                let window_blocks = (end + BLOCKS_PER_DAY - start) % BLOCKS_PER_DAY;
+                let offset = (block_index + BLOCKS_PER_DAY - start) % BLOCKS_PER_DAY;
+                // Arrival == departure means the car stays all day
+                if window_blocks != 0 && offset >= window_blocks {
+                    return 0.0;
+                }
+                let block_kwh = charge_kw * BLOCK_HOURS;
+                let delivered = offset as f32 * block_kwh;
+                (energy_kwh - delivered).clamp(0.0, block_kwh)
+            }
+
+            Self::Boiler {
+                power_kw,
+                daily_energy_kwh,
+                periods,
+            } => {
+                let hour = block_index / 4;
+                let total_hours: usize = periods
+                    .iter()
+                    .map(|&(start, end)| (end as usize).saturating_sub(start as usize))
+                    .sum();
+                if total_hours == 0 {
+                    return 0.0;
+                }
+                let Some(&(start, end)) = periods
+                    .iter()
+                    .find(|&&(start, end)| hour >= start as usize && hour < end as usize)
+                else {
+                    return 0.0;
+                };
+                let period_hours = (end - start) as f32;
+                let share_kwh = daily_energy_kwh * period_hours / total_hours as f32;
+                let block_kwh = power_kw * BLOCK_HOURS;
+                let offset = block_index - start as usize * 4;
+                let delivered = offset as f32 * block_kwh;
+                (share_kwh - delivered).clamp(0.0, block_kwh)
+            }
+
+            Self::Custom { blocks_kw } => {
+                blocks_kw.get(block_index).copied().unwrap_or(0.0) * BLOCK_HOURS
+            }
+        }
+    }
+}
+
+fn ensure_non_negative(field: &str, value: f32) -> Result<()> {
+    ensure!(
+        value.is_finite() && value >= 0.0,
+        "{field} must be a finite number >= 0, got {value}"
+    );
+    Ok(())
+}
+
+/// Outdoor temperature for a block, coldest around 05:00 and warmest around 17:00
+fn outdoor_temp_c(block_index: usize, min_temp_c: f32, max_temp_c: f32) -> f32 {
+    let hour = block_index as f32 * BLOCK_HOURS;
+    let mid = (min_temp_c + max_temp_c) / 2.0;
+    let amplitude = (max_temp_c - min_temp_c) / 2.0;
+    let phase = (hour - 5.0) / 24.0 * 2.0 * std::f32::consts::PI;
+    mid - amplitude * phase.cos()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heat_pump_draws_more_when_colder() {
+        let heat_pump = ApplianceProfile::heat_pump();
+
+        // 05:00 is the coldest time of day, 17:00 the warmest
+        let night = heat_pump.consumption_for_block(20);
+        let afternoon = heat_pump.consumption_for_block(68);
+
+        assert!(
+            night > afternoon,
+            "Heat pump should draw more at 05:00 ({}) than at 17:00 ({})",
+            night,
+            afternoon
+        );
+        assert!(night <= 3.0 * BLOCK_HOURS + 0.001);
+    }
+
+    #[test]
+    fn test_ev_session_delivers_requested_energy_across_midnight() {
+        let ev = ApplianceProfile::ev_overnight();
+
+        let total = ev.total_daily_consumption_kwh();
+        assert!(
+            (total - 20.0).abs() < 0.01,
+            "EV should deliver 20 kWh, got {}",
+            total
+        );
+
+        // Charging starts on arrival at 18:00, nothing during the day
+        assert!(ev.consumption_for_block(72) > 0.0);
+        assert_eq!(ev.consumption_for_block(48), 0.0);
+    }
+
+    #[test]
+    fn test_boiler_heats_only_in_allowed_periods() {
+        let boiler = ApplianceProfile::boiler_hdo();
+
+        let total = boiler.total_daily_consumption_kwh();
+        assert!((total - 6.0).abs() < 0.01, "Boiler total: {}", total);
+
+        // 10:00 is outside all HDO windows
+        assert_eq!(boiler.consumption_for_block(40), 0.0);
+        // 00:00 is the start of the night window
+        assert!(boiler.consumption_for_block(0) > 0.0);
+    }
+
+    #[test]
+    fn test_disabled_appliance_consumes_nothing() {
+        let mut heat_pump = ApplianceProfile::heat_pump();
+        heat_pump.enabled = false;
+
+        assert_eq!(heat_pump.total_daily_consumption_kwh(), 0.0);
+    }
+
+    #[test]
+    fn test_appliance_file_roundtrip() {
+        let toml_str = r#"
+[[appliances]]
+name = "Garage EV"
+type = "EvSession"
+charge_kw = 11.0
+energy_kwh = 30.0
+arrival_hour = 22
+departure_hour = 6
+
+[[appliances]]
+name = "Old boiler"
+enabled = false
+type = "Boiler"
+power_kw = 2.0
+daily_energy_kwh = 5.0
+periods = [[0, 6]]
+"#;
+        let file: ApplianceFile = toml::from_str(toml_str).unwrap();
+
+        assert_eq!(file.appliances.len(), 2);
+        assert!(file.appliances[0].enabled);
+        assert!(!file.appliances[1].enabled);
+        assert!(matches!(
+            file.appliances[0].kind,
+            ApplianceKind::EvSession { .. }
+        ));
+    }
+
+    #[test]
+    fn test_resolve_list_rejects_unknown_preset() {
+        let appliances = ApplianceProfile::resolve_list("heat_pump, ev").unwrap();
+        assert_eq!(appliances.len(), 2);
+
+        assert!(ApplianceProfile::resolve_list("sauna").is_err());
+    }
+
+    #[test]
+    fn test_validate_rejects_out_of_range_profiles() {
+        for preset in APPLIANCE_PRESET_IDS {
+            ApplianceProfile::preset(preset)
+                .unwrap()
+                .validate()
+                .unwrap();
+        }
+
+        let profile = |kind| ApplianceProfile {
+            name: "Bad".to_string(),
+            enabled: true,
+            kind,
+        };
+
+        let late_ev = profile(ApplianceKind::EvSession {
+            charge_kw: 7.4,
+            energy_kwh: 20.0,
+            arrival_hour: 30,
+            departure_hour: 7,
+        });
+        assert!(late_ev.validate().is_err());
+
+        let negative_boiler = profile(ApplianceKind::Boiler {
+            power_kw: -2.0,
+            daily_energy_kwh: 6.0,
+            periods: vec![(0, 6)],
+        });
+        assert!(negative_boiler.validate().is_err());
+
+        let reversed_boiler = profile(ApplianceKind::Boiler {
+            power_kw: 2.0,
+            daily_energy_kwh: 6.0,
+            periods: vec![(22, 6)],
+        });
+        assert!(reversed_boiler.validate().is_err());
+
+        let short_custom = profile(ApplianceKind::Custom {
+            blocks_kw: vec![1.0; 24],
+        });
+        assert!(short_custom.validate().is_err());
+
+        let nan_heat_pump = profile(ApplianceKind::HeatPump {
+            rated_kw: f32::NAN,
+            design_temp_c: -12.0,
+            heating_limit_c: 16.0,
+            min_temp_c: -4.0,
+            max_temp_c: 3.0,
+        });
+        assert!(nan_heat_pump.validate().is_err());
+    }
+}
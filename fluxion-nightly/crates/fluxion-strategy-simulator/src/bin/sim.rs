@@ -8,6 +8,7 @@ use anyhow::{Context, Result};
 use chrono::NaiveDate;
 use clap::Parser;
 use fluxion_strategy_simulator::{
+    appliances::ApplianceProfile,
     cli::{
         BatchArgs, BatchConfig, Cli, Commands, CompareArgs, CsvFormatter, DataLoader,
         JsonExportLoader, RunArgs, SqliteLoader, SyntheticLoader, TableFormatter,
@@ -16,8 +17,9 @@ use fluxion_strategy_simulator::{
     simulation_engine::SimulationEngine,
     state::SimulationConfig,
     strategies::{StrategyRegistry, StrategySelection},
-    synthetic_data::{ConsumptionProfile, SolarProfile, SyntheticDayConfig},
+    synthetic_data::{ConsumptionProfile, IntraBlockVariation, SolarProfile, SyntheticDayConfig},
 };
+use fluxion_types::UserControlState;
 use std::collections::HashMap;
 use std::fs;
 use std::sync::Arc;
@@ -129,6 +131,11 @@ fn run_command(args: RunArgs) -> Result<()> {
             _ => SolarProfile::none(),
         };
 
+        let appliances = match &args.appliances {
+            Some(spec) => ApplianceProfile::resolve_list(spec)?,
+            None => Vec::new(),
+        };
+
         let day_config = SyntheticDayConfig {
             date: chrono::Utc::now().date_naive(),
             consumption: ConsumptionProfile::default(),
@@ -143,6 +150,8 @@ fn run_command(args: RunArgs) -> Result<()> {
             ]),
             hdo_low_tariff_czk: 0.50,
             hdo_high_tariff_czk: 1.80,
+            appliances,
+            intra_block: args.intra_block.then(IntraBlockVariation::default),
         };
 
         Box::new(SyntheticLoader { config: day_config })
@@ -156,6 +165,12 @@ fn run_command(args: RunArgs) -> Result<()> {
         Arc::new(StrategyRegistry::new_with_defaults())
     };
 
+    let user_control = args
+        .user_control
+        .as_deref()
+        .map(load_user_control)
+        .transpose()?;
+
     // Load data
     let day = loader.load(date)?;
 
@@ -183,6 +198,9 @@ fn run_command(args: RunArgs) -> Result<()> {
         include_no_battery,
         include_naive,
         battery_capacity_kwh: day.battery_capacity_kwh,
+        export_limit_kw: args.export_limit_kw,
+        inverter_ac_limit_kw: args.inverter_ac_limit_kw,
+        user_control,
         ..SimulationConfig::default()
     };
 
@@ -242,6 +260,11 @@ fn compare_command(args: CompareArgs) -> Result<()> {
         csv_path: args.csv_path,
         solar: args.solar,
         strategy_config: args.strategy_config,
+        user_control: args.user_control,
+        appliances: args.appliances,
+        intra_block: args.intra_block,
+        export_limit_kw: args.export_limit_kw,
+        inverter_ac_limit_kw: args.inverter_ac_limit_kw,
     };
 
     // Run the simulation
@@ -319,6 +342,7 @@ fn build_run_args_from_scenario(
         ScenarioSource::Synthetic {
             price_scenario,
             consumption_profile: _,
+            appliances: _,
         } => (None, None, None, price_scenario.clone()),
         ScenarioSource::Database { db_path, date } => (
             Some(db_path.clone()),
@@ -329,6 +353,13 @@ fn build_run_args_from_scenario(
         ScenarioSource::Json { json_path } => (None, Some(json_path.clone()), None, "".to_string()),
     };
 
+    let appliances = match &scenario.source {
+        ScenarioSource::Synthetic { appliances, .. } if !appliances.is_empty() => {
+            Some(appliances.join(","))
+        }
+        _ => None,
+    };
+
     // Build CSV path
     let csv_path = if output_config.format == "csv" || output_config.format == "both" {
         Some(format!("{}/{}.csv", output_dir, scenario.name))
@@ -357,6 +388,11 @@ fn build_run_args_from_scenario(
         csv_path,
         solar: "none".to_string(), // Batch mode uses scenario-defined solar (TODO: add to batch config)
         strategy_config: None,     // Batch mode doesn't support strategy config overrides yet
+        user_control: None,
+        appliances,
+        intra_block: false,
+        export_limit_kw: None,
+        inverter_ac_limit_kw: None,
     })
 }
 
@@ -378,6 +414,18 @@ fn validate_run_args(args: &RunArgs) -> Result<()> {
         );
     }
 
+    // Validate power limits
+    for (name, limit) in [
+        ("export limit", args.export_limit_kw),
+        ("inverter AC limit", args.inverter_ac_limit_kw),
+    ] {
+        if let Some(kw) = limit
+            && kw <= 0.0
+        {
+            anyhow::bail!("Invalid {}: {} kW. Must be greater than 0.", name, kw);
+        }
+    }
+
     // Check for conflicting data sources
     let source_count = [args.from_db.is_some(), args.from_json.is_some()]
         .iter()
@@ -538,6 +586,14 @@ fn load_strategy_config(path: &str) -> Result<HashMap<String, serde_json::Value>
     Ok(overrides)
 }
 
+/// Load a user-control state (the JSON FluxION saves in data/user_control.json).
+fn load_user_control(path: &str) -> Result<UserControlState> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read user control state from {}", path))?;
+    serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse user control JSON from {}", path))
+}
+
 /// Convert a TOML value to a serde_json::Value.
 fn toml_to_json(value: toml::Value) -> serde_json::Value {
     match value {
@@ -185,6 +185,49 @@ pub struct RunArgs {
     )]
     pub solar: String,
 
+    /// Appliance loads to add (comma-separated presets or TOML/JSON files)
+    #[arg(
+        long,
+        value_name = "LIST",
+        help = "Appliance loads to add to synthetic consumption",
+        long_help = "Comma-separated appliance presets and/or profile files:\n  \
+          - heat_pump: Air-to-water heat pump on a cold day (~3 kW at -12 °C)\n  \
+          - ev_overnight: EV plugged in at 18:00, 20 kWh at 7.4 kW\n  \
+          - boiler_hdo: 2 kW boiler, 6 kWh/day in HDO windows\n  \
+          - <file>.toml / <file>.json: import profiles from a file\n\
+          \nExample: --appliances heat_pump,my_ev.toml\n\
+          Ignored when using --from-db or --from-json"
+    )]
+    pub appliances: Option<String>,
+
+    /// Vary solar and load inside each block (1-minute cloud dips and load spikes)
+    #[arg(
+        long,
+        help = "Add 1-minute solar dips and load spikes inside synthetic blocks",
+        long_help = "Redistributes each synthetic block's solar and consumption over 1-minute\n\
+          steps with passing clouds and a short appliance spike. Block energies are\n\
+          unchanged; self-use blocks are then evaluated step by step so charge/discharge\n\
+          rate limits apply to the peaks. --from-db data uses its recorded 5-minute samples.\n\
+          \nIgnored when using --from-db or --from-json"
+    )]
+    pub intra_block: bool,
+
+    /// Grid export limit in kW (PV surplus above it is curtailed)
+    #[arg(
+        long,
+        value_name = "KW",
+        help = "Grid export limit in kW (default: unlimited)"
+    )]
+    pub export_limit_kw: Option<f32>,
+
+    /// Inverter AC output limit in kW (PV above it is clipped)
+    #[arg(
+        long,
+        value_name = "KW",
+        help = "Inverter AC output limit in kW (default: unlimited)"
+    )]
+    pub inverter_ac_limit_kw: Option<f32>,
+
     /// TOML file with strategy config overrides (for C-strategies)
     #[arg(
         long,
@@ -196,6 +239,18 @@ pub struct RunArgs {
           \nExample: --strategy-config c10_experiment.toml"
     )]
     pub strategy_config: Option<String>,
+
+    /// User-control state to replay (JSON, as saved in data/user_control.json)
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "User-control JSON file to replay",
+        long_help = "Path to a user-control JSON file (the format of data/user_control.json).\n\
+          Its charge/discharge restrictions and fixed time slots are applied to the\n\
+          non-baseline strategies, so the cost of the restrictions can be compared.\n\
+          \nExample: --user-control data/user_control.json"
+    )]
+    pub user_control: Option<String>,
 }
 
 #[derive(Parser)]
@@ -284,6 +339,49 @@ pub struct CompareArgs {
     )]
     pub solar: String,
 
+    /// Appliance loads to add (comma-separated presets or TOML/JSON files)
+    #[arg(
+        long,
+        value_name = "LIST",
+        help = "Appliance loads to add to synthetic consumption",
+        long_help = "Comma-separated appliance presets and/or profile files:\n  \
+          - heat_pump: Air-to-water heat pump on a cold day (~3 kW at -12 °C)\n  \
+          - ev_overnight: EV plugged in at 18:00, 20 kWh at 7.4 kW\n  \
+          - boiler_hdo: 2 kW boiler, 6 kWh/day in HDO windows\n  \
+          - <file>.toml / <file>.json: import profiles from a file\n\
+          \nExample: --appliances heat_pump,my_ev.toml\n\
+          Ignored when using --from-db or --from-json"
+    )]
+    pub appliances: Option<String>,
+
+    /// Vary solar and load inside each block (1-minute cloud dips and load spikes)
+    #[arg(
+        long,
+        help = "Add 1-minute solar dips and load spikes inside synthetic blocks",
+        long_help = "Redistributes each synthetic block's solar and consumption over 1-minute\n\
+          steps with passing clouds and a short appliance spike. Block energies are\n\
+          unchanged; self-use blocks are then evaluated step by step so charge/discharge\n\
+          rate limits apply to the peaks. --from-db data uses its recorded 5-minute samples.\n\
+          \nIgnored when using --from-db or --from-json"
+    )]
+    pub intra_block: bool,
+
+    /// Grid export limit in kW (PV surplus above it is curtailed)
+    #[arg(
+        long,
+        value_name = "KW",
+        help = "Grid export limit in kW (default: unlimited)"
+    )]
+    pub export_limit_kw: Option<f32>,
+
+    /// Inverter AC output limit in kW (PV above it is clipped)
+    #[arg(
+        long,
+        value_name = "KW",
+        help = "Inverter AC output limit in kW (default: unlimited)"
+    )]
+    pub inverter_ac_limit_kw: Option<f32>,
+
     /// TOML file with strategy config overrides (for C-strategies)
     #[arg(
         long,
@@ -295,6 +393,18 @@ pub struct CompareArgs {
           \nExample: --strategy-config c10_experiment.toml"
     )]
     pub strategy_config: Option<String>,
+
+    /// User-control state to replay (JSON, as saved in data/user_control.json)
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "User-control JSON file to replay",
+        long_help = "Path to a user-control JSON file (the format of data/user_control.json).\n\
+          Its charge/discharge restrictions and fixed time slots are applied to the\n\
+          non-baseline strategies, so the cost of the restrictions can be compared.\n\
+          \nExample: --user-control data/user_control.json"
+    )]
+    pub user_control: Option<String>,
 }
 
 #[derive(Parser)]
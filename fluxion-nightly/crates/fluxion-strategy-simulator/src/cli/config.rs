@@ -61,6 +61,10 @@ pub enum ScenarioSource {
         /// Consumption profile (optional, defaults to peak_based)
         #[serde(default = "default_consumption_profile")]
         consumption_profile: String,
+
+        /// Appliance presets and/or profile files to add (optional)
+        #[serde(default)]
+        appliances: Vec<String>,
     },
 
     /// Load from SQLite database
@@ -162,14 +166,21 @@ name = "volatile_test"
 type = "synthetic"
 price_scenario = "volatile"
 
-# Scenario 3: Historical data from database
+# Scenario 3: Synthetic day with appliance loads (presets or .toml/.json files)
+[[scenarios]]
+name = "heat_pump_and_ev"
+type = "synthetic"
+price_scenario = "usual_day"
+appliances = ["heat_pump", "ev_overnight"]
+
+# Scenario 4: Historical data from database
 [[scenarios]]
 name = "historical_2026_01_15"
 type = "database"
 db_path = "solax_data.db"
 date = "2026-01-15"
 
-# Scenario 4: JSON export
+# Scenario 5: JSON export
 [[scenarios]]
 name = "json_export_test"
 type = "json"
@@ -6,6 +6,7 @@
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use fluxion_core::strategy::PowerProfile;
 use rusqlite::Connection;
 use serde::Deserialize;
 use std::path::Path;
@@ -178,10 +179,10 @@ impl DataLoader for SqliteLoader {
                 1.4, 1.2, 1.0, 0.8, 0.5, 0.4,  // 18-23: evening peak
             ];
 
-            let (consumption_kwh, solar_kwh) = if samples_in_block.is_empty() {
+            let (consumption_kwh, solar_kwh, recorded) = if samples_in_block.is_empty() {
                 // No data: use profile-based consumption
                 let profile_kwh = hourly_consumption_profile[hour] / 4.0; // hourly -> 15-min
-                (profile_kwh, 0.0)
+                (profile_kwh, 0.0, false)
             } else {
                 let consumption: f32 = samples_in_block.iter().map(|(c, _)| c).sum();
                 let solar: f32 = samples_in_block.iter().map(|(_, s)| s).sum();
@@ -191,12 +192,22 @@ impl DataLoader for SqliteLoader {
                 // realtime_power reads zero during bypass mode.
                 if consumption < 0.01 {
                     let profile_kwh = hourly_consumption_profile[hour] / 4.0;
-                    (profile_kwh, solar)
+                    (profile_kwh, solar, false)
                 } else {
-                    (consumption, solar)
+                    (consumption, solar, true)
                 }
             };
 
+            // Keep the recorded 5-minute shape for sub-block evaluation, unless
+            // consumption was replaced by the fallback profile
+            let power_profile = (recorded && samples_in_block.len() > 1).then(|| {
+                PowerProfile {
+                    solar_kw: samples_in_block.iter().map(|(_, s)| s * 12.0).collect(),
+                    load_kw: samples_in_block.iter().map(|(c, _)| c * 12.0).collect(),
+                }
+                .scaled_to(solar_kwh, consumption_kwh, 15)
+            });
+
             total_consumption += consumption_kwh;
             total_solar += solar_kwh;
 
@@ -229,6 +240,7 @@ impl DataLoader for SqliteLoader {
                 grid_fee_czk_per_kwh,
                 effective_price_czk_per_kwh,
                 is_hdo_low_tariff: is_hdo_low,
+                power_profile,
             });
         }
 
@@ -338,6 +350,7 @@ impl DataLoader for JsonExportLoader {
                 grid_fee_czk_per_kwh,
                 effective_price_czk_per_kwh,
                 is_hdo_low_tariff: is_hdo_low,
+                power_profile: None,
             });
         }
 
@@ -356,6 +369,7 @@ impl DataLoader for JsonExportLoader {
                 grid_fee_czk_per_kwh: 1.80,
                 effective_price_czk_per_kwh: 4.3,
                 is_hdo_low_tariff: false,
+                power_profile: None,
             });
             total_consumption += consumption_per_block;
         }
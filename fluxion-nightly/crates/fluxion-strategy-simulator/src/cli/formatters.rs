@@ -30,6 +30,7 @@ impl TableFormatter {
             Cell::new("Savings vs\nNo Battery").add_attribute(Attribute::Bold),
             Cell::new("Grid Import\n(kWh)").add_attribute(Attribute::Bold),
             Cell::new("Grid Export\n(kWh)").add_attribute(Attribute::Bold),
+            Cell::new("Curtailed\n(kWh)").add_attribute(Attribute::Bold),
             Cell::new("Cycles").add_attribute(Attribute::Bold),
             Cell::new("Final SOC\n(%)").add_attribute(Attribute::Bold),
         ]);
@@ -76,6 +77,7 @@ impl TableFormatter {
                 Cell::new(savings_str),
                 Cell::new(format!("{:.2}", result.total_grid_import_kwh)),
                 Cell::new(format!("{:.2}", result.total_grid_export_kwh)),
+                Cell::new(format!("{:.2}", result.total_curtailed_kwh)),
                 Cell::new(format!("{:.2}", cycles)),
                 Cell::new(format!("{:.1}", result.current_soc)),
             ]);
@@ -164,6 +166,7 @@ impl CsvFormatter {
             header.push(format!("{}_soc_percent", prefix));
             header.push(format!("{}_block_cost_czk", prefix));
             header.push(format!("{}_cumulative_cost_czk", prefix));
+            header.push(format!("{}_curtailed_kwh", prefix));
             header.push(format!("{}_reason", prefix));
         }
 
@@ -211,6 +214,7 @@ impl CsvFormatter {
                     row.push(format!("{:.2}", soc));
                     row.push(format!("{:.4}", block_cost));
                     row.push(format!("{:.4}", cumulative_cost));
+                    row.push(format!("{:.4}", eval.energy_flows.curtailed_solar_kwh));
                     row.push(escaped_reason);
                 } else {
                     // No evaluation for this block yet
@@ -219,6 +223,7 @@ impl CsvFormatter {
                     row.push("".to_string());
                     row.push("".to_string());
                     row.push("".to_string());
+                    row.push("".to_string());
                 }
             }
 
@@ -19,10 +19,12 @@
 //! # Features
 //!
 //! - **Synthetic Day Generation**: Create test days with configurable consumption profiles
+//! - **Appliance Profiles**: Layer heat pump, EV and boiler loads onto a day, from presets or files
 //! - **Price Scenarios**: Pre-defined price patterns (usual day, elevated, volatile, negative)
 //! - **Multi-Strategy Comparison**: Compare V1-V4 strategies plus baselines
 //! - **Interactive Simulation**: Step through days with real-time recalculation
 //! - **Override System**: Modify SOC, load, and prices at any point
+//! - **User Control**: Replay restrictions and fixed slots to price them
 //!
 //! # Example
 //!
@@ -44,6 +46,7 @@
 //! }
 //! ```
 
+pub mod appliances;
 pub mod cli;
 pub mod price_scenarios;
 pub mod simulation_engine;
@@ -52,6 +55,7 @@ pub mod strategies;
 pub mod synthetic_data;
 
 // Re-exports for convenience
+pub use appliances::{APPLIANCE_PRESET_IDS, ApplianceKind, ApplianceProfile};
 pub use price_scenarios::{PRICE_PRESETS, PriceScenario, PriceScenarioPreset};
 pub use simulation_engine::SimulationEngine;
 pub use state::{
@@ -62,6 +66,6 @@ pub use strategies::{
     WinterAdaptiveC10Config, WinterAdaptiveC10Strategy,
 };
 pub use synthetic_data::{
-    ConsumptionProfile, SolarProfile, SyntheticBlock, SyntheticDay, SyntheticDayConfig,
-    SyntheticDayGenerator,
+    ConsumptionProfile, IntraBlockVariation, SolarProfile, SyntheticBlock, SyntheticDay,
+    SyntheticDayConfig, SyntheticDayGenerator,
 };
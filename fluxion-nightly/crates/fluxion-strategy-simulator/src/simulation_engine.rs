@@ -11,12 +11,16 @@
 //! - Handles overrides and re-simulation
 
 use crate::state::{SimulationConfig, SimulationState, SocOverride};
-use crate::strategies::StrategyRegistry;
+use crate::strategies::{NaiveSelfUseStrategy, StrategyRegistry};
 use crate::synthetic_data::{SyntheticDay, SyntheticDayConfig, SyntheticDayGenerator};
 use anyhow::Result;
 use chrono::Utc;
-use fluxion_core::strategy::EvaluationContext;
+use fluxion_core::strategy::{
+    BlockEvaluation, CurtailmentLimits, CurtailmentOutcome, EconomicStrategy, EvaluationContext,
+    SubBlockBattery, simulate_self_use,
+};
 use fluxion_types::config::ControlConfig;
+use fluxion_types::inverter::InverterOperationMode;
 use fluxion_types::pricing::TimeBlockPrice;
 use std::sync::Arc;
 
@@ -291,6 +295,19 @@ impl SimulationEngine {
             max_battery_soc: state.config.max_soc,
             ..ControlConfig::default()
         };
+        let control_config = ControlConfig {
+            maximum_export_power_w: state
+                .config
+                .export_limit_kw
+                .map_or(control_config.maximum_export_power_w, kw_to_watts),
+            inverter_max_ac_power_w: state.config.inverter_ac_limit_kw.map_or(0, kw_to_watts),
+            ..control_config
+        };
+        let limits = CurtailmentLimits::from_kw(
+            state.config.inverter_ac_limit_kw,
+            state.config.export_limit_kw,
+            current_price_block.duration_minutes,
+        );
 
         // Calculate export price
         let export_price = price * state.config.export_price_ratio;
@@ -358,7 +375,62 @@ impl SimulationEngine {
 
             // Get strategy and evaluate
             if let Some(strategy) = self.registry.get(&strategy_id) {
-                let eval = strategy.evaluate(&context);
+                let mut eval = strategy.evaluate(&context);
+
+                // Replay user restrictions and fixed slots, as the scheduler
+                // would apply them to the plan
+                if let Some(user_control) = &state.config.user_control
+                    && strategy_id != "no_battery"
+                    && strategy_id != "naive"
+                {
+                    let mode = user_control.mode_at(
+                        block.timestamp,
+                        eval.mode,
+                        control_config.default_battery_mode,
+                    );
+                    if mode != eval.mode {
+                        eval = user_control_evaluation(&context, mode, &eval);
+                    }
+                }
+
+                // Strategies assume all PV surplus can be exported; clip it to
+                // the inverter and grid limits before costing. Self-use blocks
+                // with a power profile are replayed step by step instead, so
+                // short peaks hit the battery rate limits.
+                let curtailment = match &block.power_profile {
+                    Some(profile) if eval.mode == InverterOperationMode::SelfUse => {
+                        let profile = profile.scaled_to(block.solar_kwh, consumption, 15);
+                        let battery = SubBlockBattery {
+                            soc_percent: current_soc,
+                            capacity_kwh: if strategy_id == "no_battery" {
+                                0.0
+                            } else {
+                                state.config.battery_capacity_kwh
+                            },
+                            efficiency: state.config.battery_efficiency,
+                            min_soc_percent: state.config.min_soc,
+                            max_soc_percent: state.config.max_soc,
+                            max_charge_kw: state.config.max_charge_rate_kw,
+                            max_discharge_kw: state.config.max_charge_rate_kw,
+                            export_limit_kw: state.config.export_limit_kw,
+                        };
+                        eval.energy_flows = simulate_self_use(&profile, 15, &battery).flows;
+
+                        // The export cap was applied per step; the AC limit still
+                        // applies to the block
+                        let ac_limit = CurtailmentLimits {
+                            export_kwh: None,
+                            ..limits
+                        };
+                        let step_curtailed = eval.energy_flows.curtailed_solar_kwh;
+                        let outcome = ac_limit.apply(&mut eval.energy_flows);
+                        CurtailmentOutcome {
+                            curtailed_solar_kwh: outcome.curtailed_solar_kwh + step_curtailed,
+                            ..outcome
+                        }
+                    }
+                    _ => limits.apply(&mut eval.energy_flows),
+                };
 
                 // Calculate new SOC based on mode and energy flows
                 let new_soc = self.calculate_new_soc(current_soc, &eval, &state.config);
@@ -381,6 +453,7 @@ impl SimulationEngine {
                     result.total_grid_export_kwh += eval.energy_flows.grid_export_kwh;
                     result.total_battery_charge_kwh += eval.energy_flows.battery_charge_kwh;
                     result.total_battery_discharge_kwh += eval.energy_flows.battery_discharge_kwh;
+                    result.total_curtailed_kwh += curtailment.curtailed_solar_kwh;
 
                     // Update cost totals (using engine-calculated costs, not strategy-provided)
                     // Only real measurable costs (grid import/export)
@@ -431,11 +504,71 @@ impl SimulationEngine {
     }
 }
 
+/// Evaluation of a block forced into `mode` by user control
+fn user_control_evaluation(
+    context: &EvaluationContext,
+    mode: InverterOperationMode,
+    planned: &BlockEvaluation,
+) -> BlockEvaluation {
+    let reason = format!("User control: {mode:?} instead of {:?}", planned.mode);
+
+    if matches!(
+        mode,
+        InverterOperationMode::SelfUse | InverterOperationMode::BackUpMode
+    ) {
+        let mut eval = NaiveSelfUseStrategy.evaluate(context);
+        eval.mode = mode;
+        eval.strategy_name.clone_from(&planned.strategy_name);
+        eval.reason = reason;
+        return eval;
+    }
+
+    let config = context.control_config;
+    let hours = context.price_block.duration_minutes as f32 / 60.0;
+    let rate_kwh = config.max_battery_charge_rate_kw * hours;
+    let stored_kwh = config.battery_capacity_kwh * context.current_battery_soc / 100.0;
+    let room_kwh =
+        (config.battery_capacity_kwh * config.max_battery_soc / 100.0 - stored_kwh).max(0.0);
+    let available_kwh =
+        (stored_kwh - config.battery_capacity_kwh * config.min_battery_soc / 100.0).max(0.0);
+
+    let mut eval = BlockEvaluation::new(
+        context.price_block.block_start,
+        context.price_block.duration_minutes,
+        mode,
+        planned.strategy_name.clone(),
+    );
+    let flows = &mut eval.energy_flows;
+    flows.solar_generation_kwh = context.solar_forecast_kwh;
+    flows.household_consumption_kwh = context.consumption_forecast_kwh;
+    match mode {
+        InverterOperationMode::ForceCharge => flows.battery_charge_kwh = rate_kwh.min(room_kwh),
+        InverterOperationMode::ForceDischarge => {
+            flows.battery_discharge_kwh = rate_kwh.min(available_kwh);
+        }
+        // Battery idle
+        _ => {}
+    }
+    let grid_kwh = context.consumption_forecast_kwh + flows.battery_charge_kwh
+        - context.solar_forecast_kwh
+        - flows.battery_discharge_kwh * config.battery_efficiency;
+    flows.grid_import_kwh = grid_kwh.max(0.0);
+    flows.grid_export_kwh = (-grid_kwh).max(0.0);
+    eval.reason = reason;
+    eval
+}
+
+fn kw_to_watts(kw: f32) -> u32 {
+    (kw.max(0.0) * 1000.0).round() as u32
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::price_scenarios::PriceScenario;
-    use crate::synthetic_data::{ConsumptionProfile, SolarProfile, SyntheticDayConfig};
+    use crate::synthetic_data::{
+        ConsumptionProfile, IntraBlockVariation, SolarProfile, SyntheticDayConfig,
+    };
 
     #[test]
     fn test_create_simulation() {
@@ -451,6 +584,8 @@ mod tests {
             hdo_periods: None,
             hdo_low_tariff_czk: 0.50,
             hdo_high_tariff_czk: 1.80,
+            appliances: Vec::new(),
+            intra_block: None,
         };
 
         let sim_config = SimulationConfig::default();
@@ -505,6 +640,78 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_intra_block_load_spikes_exceed_discharge_rate() {
+        let engine = SimulationEngine::new();
+        let flat_day = SyntheticDayConfig {
+            consumption: ConsumptionProfile::Constant { load_kw: 1.0 },
+            solar: SolarProfile::None,
+            ..SyntheticDayConfig::default()
+        };
+        let spiky_day = SyntheticDayConfig {
+            intra_block: Some(IntraBlockVariation {
+                steps: 15,
+                cloud_dip: 0.0,
+                cloud_probability: 0.0,
+                load_spike_kw: 10.0,
+            }),
+            ..flat_day.clone()
+        };
+        let sim_config = SimulationConfig {
+            max_charge_rate_kw: 3.0,
+            ..SimulationConfig::default()
+        };
+
+        let run = |day| {
+            let mut state = engine.create_simulation(day, sim_config.clone()).unwrap();
+            engine.run_to_completion(&mut state).unwrap();
+            state
+        };
+        let flat = run(flat_day);
+        let spiky = run(spiky_day);
+
+        let first_block_import = |state: &SimulationState, id: &str| {
+            state.strategy_results.get(id).unwrap().evaluations[0]
+                .energy_flows
+                .grid_import_kwh
+        };
+        // Same energy without a battery
+        assert!(
+            (first_block_import(&flat, "no_battery") - first_block_import(&spiky, "no_battery"))
+                .abs()
+                < 1e-3
+        );
+        // The battery covers the flat 1 kW but not the part of a spike above 3 kW
+        assert!(first_block_import(&spiky, "naive") > first_block_import(&flat, "naive") + 0.03);
+    }
+
+    #[test]
+    fn test_export_limit_curtails_solar() {
+        let engine = SimulationEngine::new();
+
+        let day_config = SyntheticDayConfig {
+            solar: SolarProfile::Typical {
+                peak_kw: 12.0,
+                sunrise_hour: 6,
+                sunset_hour: 20,
+            },
+            ..SyntheticDayConfig::default()
+        };
+        let sim_config = SimulationConfig {
+            export_limit_kw: Some(3.0),
+            ..SimulationConfig::default()
+        };
+
+        let mut state = engine.create_simulation(day_config, sim_config).unwrap();
+        engine.run_to_completion(&mut state).unwrap();
+
+        let no_battery = state.strategy_results.get("no_battery").unwrap();
+        assert!(no_battery.total_curtailed_kwh > 0.0);
+        for eval in &no_battery.evaluations {
+            assert!(eval.energy_flows.grid_export_kwh <= 3.0 * 0.25 + 1e-4);
+        }
+    }
+
     #[test]
     fn test_v4_beats_no_battery() {
         let engine = SimulationEngine::new();
@@ -534,6 +741,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_user_control_restricts_strategy_modes() {
+        let engine = SimulationEngine::new();
+
+        let run = |user_control| {
+            let sim_config = SimulationConfig {
+                user_control,
+                ..SimulationConfig::default()
+            };
+            let mut state = engine
+                .create_simulation(SyntheticDayConfig::default(), sim_config)
+                .unwrap();
+            engine.run_to_completion(&mut state).unwrap();
+            state
+        };
+        let restricted = run(Some(fluxion_types::UserControlState {
+            disallow_charge: true,
+            disallow_discharge: true,
+            ..Default::default()
+        }));
+
+        let v4 = restricted
+            .strategy_results
+            .get("winter_adaptive_v4")
+            .unwrap();
+        assert!(v4.evaluations.iter().all(|eval| !matches!(
+            eval.mode,
+            InverterOperationMode::ForceCharge | InverterOperationMode::ForceDischarge
+        )));
+        // Baselines ignore user control
+        let naive = restricted.strategy_results.get("naive").unwrap();
+        assert!(
+            naive
+                .evaluations
+                .iter()
+                .all(|eval| !eval.reason.starts_with("User control"))
+        );
+    }
+
     #[test]
     fn test_load_override_affects_costs() {
         let engine = SimulationEngine::new();
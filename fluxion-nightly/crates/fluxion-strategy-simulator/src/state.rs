@@ -11,6 +11,7 @@ use crate::SyntheticDay;
 use crate::strategies::StrategySelection;
 use chrono::{DateTime, Utc};
 use fluxion_core::strategy::BlockEvaluation;
+use fluxion_types::UserControlState;
 use fluxion_types::inverter::InverterOperationMode;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -139,6 +140,9 @@ pub struct StrategySimulationResult {
     /// Total export revenue (CZK)
     pub total_export_revenue_czk: f32,
 
+    /// Total PV curtailed by inverter or export limits (kWh)
+    pub total_curtailed_kwh: f32,
+
     /// Net cost (import cost - export revenue) (CZK)
     pub net_cost_czk: f32,
 
@@ -167,6 +171,7 @@ impl StrategySimulationResult {
             total_battery_discharge_kwh: 0.0,
             total_import_cost_czk: 0.0,
             total_export_revenue_czk: 0.0,
+            total_curtailed_kwh: 0.0,
             net_cost_czk: 0.0,
             current_soc: initial_soc,
             current_mode: InverterOperationMode::SelfUse,
@@ -193,6 +198,7 @@ impl StrategySimulationResult {
         self.total_battery_discharge_kwh = 0.0;
         self.total_import_cost_czk = 0.0;
         self.total_export_revenue_czk = 0.0;
+        self.total_curtailed_kwh = 0.0;
         self.net_cost_czk = 0.0;
         self.current_soc = initial_soc;
         self.current_mode = InverterOperationMode::SelfUse;
@@ -286,6 +292,19 @@ pub struct SimulationConfig {
 
     /// Export price ratio (fraction of import price)
     pub export_price_ratio: f32,
+
+    /// Grid export limit (kW), None = unlimited
+    #[serde(default)]
+    pub export_limit_kw: Option<f32>,
+
+    /// Inverter AC output limit (kW), None = unlimited
+    #[serde(default)]
+    pub inverter_ac_limit_kw: Option<f32>,
+
+    /// Restrictions and fixed slots applied to every strategy except the
+    /// baselines, to see what they cost
+    #[serde(default)]
+    pub user_control: Option<UserControlState>,
 }
 
 impl Default for SimulationConfig {
@@ -307,6 +326,9 @@ impl Default for SimulationConfig {
             hdo_low_tariff_czk: 0.50,
             hdo_high_tariff_czk: 1.80,
             export_price_ratio: 0.80,
+            export_limit_kw: None,
+            inverter_ac_limit_kw: None,
+            user_control: None,
         }
     }
 }
@@ -346,6 +368,7 @@ impl SimulationResultsSummary {
                 net_cost_czk: result.net_cost_czk,
                 grid_import_kwh: result.total_grid_import_kwh,
                 grid_export_kwh: result.total_grid_export_kwh,
+                curtailed_kwh: result.total_curtailed_kwh,
                 battery_cycles: result.battery_cycles(state.config.battery_capacity_kwh),
                 final_soc: result.current_soc,
                 savings_vs_no_battery: 0.0, // Calculated below
@@ -430,6 +453,10 @@ pub struct StrategyResultSummary {
     /// Grid export (kWh)
     pub grid_export_kwh: f32,
 
+    /// PV curtailed by inverter or export limits (kWh)
+    #[serde(default)]
+    pub curtailed_kwh: f32,
+
     /// Battery cycles
     pub battery_cycles: f32,
 
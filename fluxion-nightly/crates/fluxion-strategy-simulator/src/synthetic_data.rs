@@ -8,9 +8,12 @@
 //! that can be combined with price scenarios to create realistic
 //! test days for strategy evaluation.
 
+use crate::appliances::ApplianceProfile;
 use crate::price_scenarios::PriceScenario;
 use anyhow::Result;
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use fluxion_core::strategy::PowerProfile;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 /// Configuration for generating a synthetic test day
@@ -43,6 +46,15 @@ pub struct SyntheticDayConfig {
 
     /// HDO high tariff grid fee (CZK/kWh)
     pub hdo_high_tariff_czk: f32,
+
+    /// Appliance loads added on top of the consumption profile
+    /// Disabled entries are kept but contribute nothing
+    #[serde(default)]
+    pub appliances: Vec<ApplianceProfile>,
+
+    /// Power variation inside each block; None = flat blocks
+    #[serde(default)]
+    pub intra_block: Option<IntraBlockVariation>,
 }
 
 impl Default for SyntheticDayConfig {
@@ -62,10 +74,24 @@ impl Default for SyntheticDayConfig {
             ]),
             hdo_low_tariff_czk: 0.50,
             hdo_high_tariff_czk: 1.80,
+            appliances: Vec::new(),
+            intra_block: None,
         }
     }
 }
 
+impl SyntheticDayConfig {
+    /// Total consumption for a block: base profile plus enabled appliances (kWh)
+    pub fn consumption_for_block(&self, block_index: usize) -> f32 {
+        self.consumption.consumption_for_block(block_index)
+            + self
+                .appliances
+                .iter()
+                .map(|a| a.consumption_for_block(block_index))
+                .sum::<f32>()
+    }
+}
+
 /// Consumption profile types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -265,6 +291,59 @@ impl SolarProfile {
     }
 }
 
+/// Short-term variation within a block, so the engine can evaluate self-use
+/// at sub-block resolution
+///
+/// Block energies are preserved; only their distribution inside the block changes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntraBlockVariation {
+    /// Steps per block (15 = 1-minute resolution)
+    pub steps: usize,
+
+    /// Fraction of PV lost while a cloud passes (0-1)
+    pub cloud_dip: f32,
+
+    /// Probability that a step is shaded (0-1)
+    pub cloud_probability: f32,
+
+    /// Appliance spike added to one step of every block (kW)
+    pub load_spike_kw: f32,
+}
+
+impl Default for IntraBlockVariation {
+    fn default() -> Self {
+        Self {
+            steps: 15,
+            cloud_dip: 0.7,
+            cloud_probability: 0.3,
+            load_spike_kw: 3.0,
+        }
+    }
+}
+
+impl IntraBlockVariation {
+    /// Distribute the block energies over `steps` with cloud dips and a load spike
+    pub fn profile_for_block(&self, solar_kwh: f32, consumption_kwh: f32) -> PowerProfile {
+        let mut rng = rand::thread_rng();
+        let steps = self.steps.max(1);
+        let dip = self.cloud_dip.clamp(0.0, 1.0);
+
+        let solar_kw = (0..steps)
+            .map(|_| {
+                if rng.gen_bool(f64::from(self.cloud_probability.clamp(0.0, 1.0))) {
+                    1.0 - dip
+                } else {
+                    1.0
+                }
+            })
+            .collect();
+        let mut load_kw = vec![1.0; steps];
+        load_kw[rng.gen_range(0..steps)] += self.load_spike_kw.max(0.0);
+
+        PowerProfile { solar_kw, load_kw }.scaled_to(solar_kwh, consumption_kwh, 15)
+    }
+}
+
 /// Generated synthetic day data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyntheticDay {
@@ -316,6 +395,10 @@ pub struct SyntheticBlock {
 
     /// Whether this block is in HDO low-tariff period
     pub is_hdo_low_tariff: bool,
+
+    /// Solar and load power inside the block; None = flat
+    #[serde(default)]
+    pub power_profile: Option<PowerProfile>,
 }
 
 /// Generator for synthetic test days
@@ -343,7 +426,7 @@ impl SyntheticDayGenerator {
             let hour = i / 4;
 
             // Get consumption and solar for this block
-            let consumption_kwh = config.consumption.consumption_for_block(i);
+            let consumption_kwh = config.consumption_for_block(i);
             let solar_kwh = config.solar.generation_for_block(i);
 
             total_consumption += consumption_kwh;
@@ -379,6 +462,10 @@ impl SyntheticDayGenerator {
                 grid_fee_czk_per_kwh,
                 effective_price_czk_per_kwh,
                 is_hdo_low_tariff: is_hdo_low,
+                power_profile: config
+                    .intra_block
+                    .as_ref()
+                    .map(|v| v.profile_for_block(solar_kwh, consumption_kwh)),
             });
         }
 
@@ -457,6 +544,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_enabled_appliances_add_to_consumption() {
+        let base = SyntheticDayConfig::default();
+        let base_day = SyntheticDayGenerator::generate(&base).unwrap();
+
+        let mut disabled_ev = ApplianceProfile::ev_overnight();
+        disabled_ev.enabled = false;
+        let config = SyntheticDayConfig {
+            appliances: vec![ApplianceProfile::boiler_hdo(), disabled_ev],
+            ..base
+        };
+        let day = SyntheticDayGenerator::generate(&config).unwrap();
+
+        // Only the 6 kWh boiler counts, the disabled EV is ignored
+        let added = day.total_consumption_kwh - base_day.total_consumption_kwh;
+        assert!((added - 6.0).abs() < 0.01, "Added consumption: {}", added);
+    }
+
     #[test]
     fn test_typical_solar_profile_bell_curve() {
         let solar = SolarProfile::Typical {
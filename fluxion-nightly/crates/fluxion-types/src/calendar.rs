@@ -0,0 +1,253 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Public holidays, vacations and other special days.
+//!
+//! Households use energy differently on weekends, public holidays and while
+//! away. This module classifies local calendar dates into a [`DayKind`]:
+//! Czech public holidays are built in, vacation ranges are entered by the user
+//! (`/api/calendar`) and stored with the user control state.
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// How a local calendar day is expected to be used
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DayKind {
+    Workday,
+    Weekend,
+    /// Public holiday
+    Holiday,
+    /// Inside a user-defined vacation range
+    Vacation,
+}
+
+impl DayKind {
+    /// Whether consumption follows the weekend profile on this day
+    pub fn is_weekend_like(self) -> bool {
+        self != Self::Workday
+    }
+
+    /// Holidays and vacations, the days that change planning beyond the weekend profile
+    pub fn is_special(self) -> bool {
+        matches!(self, Self::Holiday | Self::Vacation)
+    }
+}
+
+/// Calendar settings (`[control.calendar]`)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// Treat Czech public holidays as special days
+    pub czech_holidays: bool,
+    /// Plan no forced discharge on holidays and vacations
+    pub suppress_discharge: bool,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        Self {
+            czech_holidays: true,
+            suppress_discharge: true,
+        }
+    }
+}
+
+impl CalendarConfig {
+    /// Kind of the local calendar `date`; vacations win over holidays and weekends
+    pub fn day_kind(&self, date: NaiveDate, vacations: &[VacationRange]) -> DayKind {
+        if vacations.iter().any(|vacation| vacation.contains(date)) {
+            DayKind::Vacation
+        } else if self.czech_holidays && czech_public_holiday(date).is_some() {
+            DayKind::Holiday
+        } else if matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+            DayKind::Weekend
+        } else {
+            DayKind::Workday
+        }
+    }
+}
+
+/// A user-defined vacation, inclusive of both dates
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VacationRange {
+    pub id: String,
+    /// First local date of the vacation
+    pub start: NaiveDate,
+    /// Last local date of the vacation
+    pub end: NaiveDate,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+impl VacationRange {
+    /// Create a vacation range with a new ID
+    pub fn new(start: NaiveDate, end: NaiveDate, note: Option<String>) -> Self {
+        Self {
+            id: format!("vacation_{}", Utc::now().timestamp_millis()),
+            start,
+            end,
+            note,
+        }
+    }
+
+    /// Whether `date` falls within the vacation
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+}
+
+/// A holiday or vacation day as a UTC range, for planning
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecialDay {
+    /// Local calendar date
+    pub date: NaiveDate,
+    /// Local midnight starting the day
+    pub start: DateTime<Utc>,
+    /// Local midnight starting the next day
+    pub end: DateTime<Utc>,
+    pub kind: DayKind,
+    /// Holiday name or vacation note
+    pub name: String,
+}
+
+impl SpecialDay {
+    /// Whether `at` falls within this day
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start <= at && at < self.end
+    }
+}
+
+/// Easter Sunday of `year` in the Gregorian calendar (anonymous algorithm)
+pub fn easter_sunday(year: i32) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32).unwrap_or_default()
+}
+
+/// Name of the Czech public holiday on `date`, if any
+pub fn czech_public_holiday(date: NaiveDate) -> Option<&'static str> {
+    let fixed = match (date.month(), date.day()) {
+        (1, 1) => Some("New Year's Day"),
+        (5, 1) => Some("Labour Day"),
+        (5, 8) => Some("Liberation Day"),
+        (7, 5) => Some("Saints Cyril and Methodius Day"),
+        (7, 6) => Some("Jan Hus Day"),
+        (9, 28) => Some("Czech Statehood Day"),
+        (10, 28) => Some("Independent Czechoslovak State Day"),
+        (11, 17) => Some("Struggle for Freedom and Democracy Day"),
+        (12, 24) => Some("Christmas Eve"),
+        (12, 25) => Some("Christmas Day"),
+        (12, 26) => Some("St. Stephen's Day"),
+        _ => None,
+    };
+    fixed.or_else(|| {
+        let easter = easter_sunday(date.year());
+        if date == easter - chrono::Duration::days(2) {
+            Some("Good Friday")
+        } else if date == easter + chrono::Duration::days(1) {
+            Some("Easter Monday")
+        } else {
+            None
+        }
+    })
+}
+
+/// Czech public holidays of `year` in date order
+pub fn czech_public_holidays(year: i32) -> Vec<(NaiveDate, &'static str)> {
+    let Some(first) = NaiveDate::from_ymd_opt(year, 1, 1) else {
+        return Vec::new();
+    };
+    first
+        .iter_days()
+        .take_while(|date| date.year() == year)
+        .filter_map(|date| czech_public_holiday(date).map(|name| (date, name)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_easter_dates() {
+        assert_eq!(easter_sunday(2024), date(2024, 3, 31));
+        assert_eq!(easter_sunday(2025), date(2025, 4, 20));
+        assert_eq!(easter_sunday(2026), date(2026, 4, 5));
+    }
+
+    #[test]
+    fn test_czech_public_holidays() {
+        let holidays = czech_public_holidays(2025);
+        assert_eq!(holidays.len(), 13);
+        assert_eq!(czech_public_holiday(date(2025, 4, 18)), Some("Good Friday"));
+        assert_eq!(
+            czech_public_holiday(date(2025, 4, 21)),
+            Some("Easter Monday")
+        );
+        assert_eq!(czech_public_holiday(date(2025, 4, 22)), None);
+    }
+
+    #[test]
+    fn test_day_kind_precedence() {
+        let config = CalendarConfig::default();
+        let vacations = vec![VacationRange::new(
+            date(2025, 12, 27),
+            date(2026, 1, 2),
+            Some("Skiing".to_owned()),
+        )];
+
+        // Wednesday, Saturday, holiday, holiday inside the vacation
+        assert_eq!(
+            config.day_kind(date(2025, 12, 17), &vacations),
+            DayKind::Workday
+        );
+        assert_eq!(
+            config.day_kind(date(2025, 12, 20), &vacations),
+            DayKind::Weekend
+        );
+        assert_eq!(
+            config.day_kind(date(2025, 12, 24), &vacations),
+            DayKind::Holiday
+        );
+        assert_eq!(
+            config.day_kind(date(2026, 1, 1), &vacations),
+            DayKind::Vacation
+        );
+
+        let no_holidays = CalendarConfig {
+            czech_holidays: false,
+            ..CalendarConfig::default()
+        };
+        assert_eq!(
+            no_holidays.day_kind(date(2025, 12, 24), &[]),
+            DayKind::Workday
+        );
+    }
+}
@@ -11,17 +11,30 @@
 // For commercial licensing, please contact: info@solare.cz
 
 use bevy_ecs::prelude::Resource;
+use chrono::{DateTime, Utc};
 use fluxion_i18n::Language;
 use serde::{Deserialize, Serialize};
 
+use crate::calendar::{CalendarConfig, SpecialDay};
 use crate::history::ConsumptionHistoryConfig;
 use crate::inverter::{InverterOperationMode, InverterType};
+use crate::tariff::{HdoPreset, HdoSource, TariffSchedule, TariffWindow};
+use crate::weather::{DayWeather, WeatherConfigCore, WeatherOutlook, WeatherPlanningConfig};
+
+pub mod migration;
+
+pub use migration::{
+    CONFIG_SCHEMA_VERSION, MigrationReport, UNVERSIONED_SCHEMA_VERSION, migrate_config,
+};
 
 // ============= System Configuration =============
 
 /// Central configuration resource for the FluxION system
 #[derive(Resource, Debug, Clone, Serialize, Deserialize)]
 pub struct SystemConfig {
+    /// Version of the config layout, see [`migration`]
+    #[serde(default = "migration::unversioned_schema_version")]
+    pub schema_version: u32,
     pub inverters: Vec<InverterConfig>,
     #[serde(rename = "pricing")]
     pub pricing_config: PricingConfig,
@@ -37,6 +50,14 @@ pub struct SystemConfig {
     pub solar_forecast: SolarForecastConfigCore,
     #[serde(default, rename = "remote_access")]
     pub remote_access: RemoteAccessConfigCore,
+    #[serde(default, rename = "logging")]
+    pub logging: LoggingConfigCore,
+    #[serde(default, rename = "grid_quality")]
+    pub grid_quality: GridQualityConfigCore,
+    #[serde(default, rename = "peak_demand")]
+    pub peak_demand: PeakDemandConfigCore,
+    #[serde(default, rename = "weather")]
+    pub weather: WeatherConfigCore,
 }
 
 /// Configuration for a single inverter
@@ -46,6 +67,22 @@ pub struct InverterConfig {
     pub inverter_type: InverterType,
     pub entity_prefix: String,
     pub topology: InverterTopology,
+    /// Battery behind this inverter; set on two or more commanded inverters
+    /// to plan them as separate batteries instead of one
+    #[serde(default)]
+    pub battery: Option<InverterBatteryConfig>,
+}
+
+/// Battery parameters of one inverter for coordinated multi-inverter scheduling
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InverterBatteryConfig {
+    pub capacity_kwh: f32,
+    pub max_charge_rate_kw: f32,
+    /// Export limit of this inverter (W)
+    pub maximum_export_power_w: u32,
+    /// Wear cost per kWh cycled; cheaper batteries are cycled first
+    #[serde(default)]
+    pub wear_cost_czk_per_kwh: f32,
 }
 
 /// Inverter topology for multi-inverter setups
@@ -57,6 +94,19 @@ pub enum InverterTopology {
     Slave { master_id: String },
 }
 
+/// What the schedule guard does with physically impossible blocks
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleGuardMode {
+    /// Don't check the schedule
+    Off,
+    /// Record findings in the block debug info, keep the plan
+    Flag,
+    /// Record findings and fall back to the default mode or clamp the power
+    #[default]
+    Repair,
+}
+
 /// Schedule for fixed prices (flat or hourly)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -121,6 +171,18 @@ pub struct PricingConfig {
     /// This is added to spot prices during high tariff hours to get effective buy price
     #[serde(default = "default_hdo_high_tariff_czk")]
     pub hdo_high_tariff_czk: f32,
+
+    /// Where the HDO low tariff windows come from
+    #[serde(default)]
+    pub hdo_source: HdoSource,
+
+    /// Distribution rate whose typical windows the `preset` source uses
+    #[serde(default)]
+    pub hdo_preset: HdoPreset,
+
+    /// Weekly low tariff windows for the `manual` source (local time)
+    #[serde(default)]
+    pub hdo_windows: Vec<TariffWindow>,
 }
 
 /// Control configuration
@@ -131,6 +193,10 @@ pub struct ControlConfig {
     pub min_battery_soc: f32,
     pub max_battery_soc: f32,
     pub maximum_export_power_w: u32,
+    /// Rated AC output of the inverter (watts), 0 = not modeled
+    /// PV beyond this (after DC battery charging) is clipped by the inverter
+    #[serde(default)]
+    pub inverter_max_ac_power_w: u32,
     pub battery_capacity_kwh: f32,
     pub battery_wear_cost_czk_per_kwh: f32,
     pub battery_efficiency: f32,
@@ -178,6 +244,131 @@ pub struct ControlConfig {
     /// Default: SelfUse (normal self-consumption mode)
     #[serde(default = "default_battery_operation_mode")]
     pub default_battery_mode: InverterOperationMode,
+
+    /// Mode inverters are forced into when the emergency safe state is engaged
+    /// Default: NoChargeNoDischarge (battery idle, grid powers the house)
+    #[serde(default = "default_safe_state_mode")]
+    pub safe_state_mode: InverterOperationMode,
+
+    /// Allow force-charge blocks below the maximum charge rate
+    /// Requires an inverter with charge-current control; the scheduler then spreads
+    /// a charge over more (cheaper) blocks at reduced power when that costs less
+    /// or keeps the grid import under `max_grid_import_kw`
+    #[serde(default)]
+    pub partial_charge_enabled: bool,
+
+    /// Lower the power of force-charge runs that would fill the battery before
+    /// they end, so the missing energy is spread over the whole run
+    #[serde(default)]
+    pub charge_power_modulation_enabled: bool,
+
+    /// Main breaker limit for grid import in kW, 0 = unlimited
+    /// Force charging plus expected household load is kept under this limit
+    #[serde(default)]
+    pub max_grid_import_kw: f32,
+
+    /// Hours ahead planned by the strategy plugins, 0 = whole price horizon
+    /// Later blocks are filled by the cheap price-percentile heuristic, which
+    /// keeps schedule generation fast on small devices
+    #[serde(default)]
+    pub optimization_horizon_hours: u32,
+
+    /// Battery degradation model replacing the flat wear cost when enabled
+    #[serde(default)]
+    pub battery_degradation: BatteryDegradationConfig,
+
+    /// Announced windows during which grid export must stay under a cap
+    /// (e.g. distributor testing); overrides `maximum_export_power_w`
+    #[serde(default)]
+    pub export_cap_windows: Vec<ExportCapWindow>,
+
+    /// Feasibility check of the optimized schedule before execution
+    #[serde(default)]
+    pub schedule_guard: ScheduleGuardMode,
+
+    /// Tolerance band (SOC %) around the planned SOC trajectory, 0 = no drift correction
+    /// Outside the band the executor charges earlier, pauses charging or stops discharging
+    #[serde(default = "default_soc_tracking_tolerance")]
+    pub soc_tracking_tolerance_pct: f32,
+
+    /// Drift (SOC %) between the battery and the planned trajectory that triggers a replan
+    /// of the remaining blocks, 0 = never replan on drift
+    #[serde(default = "default_replan_drift")]
+    pub replan_drift_pct: f32,
+
+    /// Public holidays and vacations
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+
+    /// Holidays and vacations in the planning horizon, filled in before planning
+    #[serde(skip)]
+    pub special_days: Vec<SpecialDay>,
+
+    /// How planning reacts to the weather forecast
+    #[serde(default)]
+    pub weather: WeatherPlanningConfig,
+
+    /// Weather forecast for the current planning run; empty without a weather source
+    #[serde(skip)]
+    pub weather_outlook: WeatherOutlook,
+
+    /// HDO tariff schedule for the current planning run; empty when unknown
+    #[serde(skip)]
+    pub tariff_schedule: TariffSchedule,
+}
+
+impl ControlConfig {
+    /// Export cap (watts) in force at `at`, the strictest of overlapping windows
+    #[must_use]
+    pub fn export_cap_at(&self, at: DateTime<Utc>) -> Option<u32> {
+        self.export_cap_windows
+            .iter()
+            .filter(|w| w.contains(at))
+            .map(|w| w.max_export_w)
+            .min()
+    }
+
+    /// Strictest export cap (watts) of the windows overlapping `[start, end)`
+    #[must_use]
+    pub fn export_cap_during(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<u32> {
+        self.export_cap_windows
+            .iter()
+            .filter(|w| w.overlaps(start, end))
+            .map(|w| w.max_export_w)
+            .min()
+    }
+
+    /// Holiday or vacation covering `at`, if any
+    #[must_use]
+    pub fn special_day_at(&self, at: DateTime<Utc>) -> Option<&SpecialDay> {
+        self.special_days.iter().find(|day| day.contains(at))
+    }
+
+    /// SOC (%) to keep at `at` because the next day is forecast cold and cloudy
+    #[must_use]
+    pub fn weather_reserve_soc_at(&self, at: DateTime<Utc>) -> Option<f32> {
+        if self.weather.cold_cloudy_reserve_soc <= 0.0 {
+            return None;
+        }
+        self.weather_outlook
+            .next_day_after(at)
+            .filter(|day| self.weather.is_cold_cloudy(day))
+            .map(|_| {
+                (self.min_battery_soc + self.weather.cold_cloudy_reserve_soc)
+                    .min(self.max_battery_soc)
+            })
+    }
+
+    /// Clear day whose morning contains `at`, when pre-charging is skipped on clear days
+    #[must_use]
+    pub fn clear_morning_at(&self, at: DateTime<Utc>) -> Option<&DayWeather> {
+        if !self.weather.skip_precharge_on_clear_day {
+            return None;
+        }
+        self.weather_outlook
+            .day_at(at)
+            .filter(|day| at < day.morning_end && self.weather.is_clear(day))
+    }
 }
 
 // Default value functions for serde
@@ -204,6 +395,15 @@ fn default_min_consecutive_force_blocks() -> usize {
 fn default_battery_operation_mode() -> InverterOperationMode {
     InverterOperationMode::SelfUse
 }
+fn default_safe_state_mode() -> InverterOperationMode {
+    InverterOperationMode::NoChargeNoDischarge
+}
+fn default_soc_tracking_tolerance() -> f32 {
+    5.0
+}
+fn default_replan_drift() -> f32 {
+    10.0
+}
 fn default_spot_buy_fee() -> f32 {
     0.5
 }
@@ -231,6 +431,7 @@ impl Default for ControlConfig {
             min_battery_soc: 10.0,
             max_battery_soc: 100.0,
             maximum_export_power_w: 10000,
+            inverter_max_ac_power_w: 0,
             battery_capacity_kwh: 20.0,
             battery_wear_cost_czk_per_kwh: 0.125,
             battery_efficiency: 0.95,
@@ -243,10 +444,96 @@ impl Default for ControlConfig {
             evening_peak_start_hour: 17,
             min_consecutive_force_blocks: 2,
             default_battery_mode: InverterOperationMode::SelfUse,
+            safe_state_mode: InverterOperationMode::NoChargeNoDischarge,
+            partial_charge_enabled: false,
+            charge_power_modulation_enabled: false,
+            max_grid_import_kw: 0.0,
+            optimization_horizon_hours: 0,
+            battery_degradation: BatteryDegradationConfig::default(),
+            export_cap_windows: Vec::new(),
+            schedule_guard: ScheduleGuardMode::Repair,
+            soc_tracking_tolerance_pct: default_soc_tracking_tolerance(),
+            replan_drift_pct: default_replan_drift(),
+            calendar: CalendarConfig::default(),
+            special_days: Vec::new(),
+            weather: WeatherPlanningConfig::default(),
+            weather_outlook: WeatherOutlook::default(),
+            tariff_schedule: TariffSchedule::default(),
         }
     }
 }
 
+/// Battery degradation model used to cost battery cycling
+///
+/// When disabled every kWh cycled costs `battery_wear_cost_czk_per_kwh`.
+/// When enabled that cost is the wear at `reference_depth_percent` and
+/// `reference_temperature_c`, scaled for deeper/shallower cycles and hotter
+/// cells, plus calendar aging that grows while the battery sits at high SOC.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatteryDegradationConfig {
+    pub enabled: bool,
+    /// Depth of discharge (%) at which a kWh costs the flat wear cost
+    pub reference_depth_percent: f32,
+    /// Cycle life exponent: cycles to end of life ~ depth^-exponent (1.0 = depth independent)
+    pub depth_exponent: f32,
+    /// Cell temperature (°C) at which no derating applies
+    pub reference_temperature_c: f32,
+    /// Wear doubles for every this many °C above the reference temperature
+    pub temperature_doubling_c: f32,
+    /// Calendar aging cost per day at moderate SOC (CZK/day)
+    pub calendar_aging_czk_per_day: f32,
+    /// SOC (%) above which calendar aging is multiplied by `high_soc_calendar_multiplier`
+    pub high_soc_threshold_percent: f32,
+    pub high_soc_calendar_multiplier: f32,
+    /// Latest measured battery temperature (°C), filled in at runtime
+    #[serde(skip)]
+    pub battery_temperature_c: Option<f32>,
+}
+
+impl Default for BatteryDegradationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            reference_depth_percent: 80.0,
+            depth_exponent: 1.3,
+            reference_temperature_c: 25.0,
+            temperature_doubling_c: 10.0,
+            calendar_aging_czk_per_day: 0.0,
+            high_soc_threshold_percent: 80.0,
+            high_soc_calendar_multiplier: 2.0,
+            battery_temperature_c: None,
+        }
+    }
+}
+
+/// Time window with a grid export cap, e.g. announced distributor testing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportCapWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Maximum grid export during the window (watts), 0 = no export
+    #[serde(default)]
+    pub max_export_w: u32,
+    /// Free-form note, e.g. the distributor's announcement reference
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl ExportCapWindow {
+    /// True while `at` falls inside the window
+    #[must_use]
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start <= at && at < self.end
+    }
+
+    /// True when the window overlaps `[start, end)`
+    #[must_use]
+    pub fn overlaps(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> bool {
+        self.start < end && start < self.end
+    }
+}
+
 /// System settings configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemSettingsConfig {
@@ -1334,6 +1621,120 @@ pub struct RemoteAccessConfigCore {
     pub enabled: bool,
 }
 
+// ============================================================================
+// Logging Configuration
+// ============================================================================
+
+/// Log levels accepted by `level` and per-module directives
+pub const LOG_LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "off"];
+
+/// Logging configuration (applied at runtime via a reloadable filter)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoggingConfigCore {
+    /// Default level for all modules
+    pub level: String,
+    /// Per-module overrides as `target=level`, e.g. `fluxion_core::scheduling=debug`
+    pub module_levels: Vec<String>,
+    /// Also write logs to daily rotated files
+    pub file_enabled: bool,
+    /// Directory for log files
+    pub directory: String,
+    /// Number of daily log files to keep
+    pub max_files: usize,
+}
+
+impl Default for LoggingConfigCore {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            module_levels: Vec::new(),
+            file_enabled: false,
+            directory: "/data/logs".to_string(),
+            max_files: 7,
+        }
+    }
+}
+
+impl LoggingConfigCore {
+    /// Build an `EnvFilter`-compatible directive string, e.g. `info,fluxion_core=debug`
+    pub fn filter_directives(&self) -> String {
+        std::iter::once(self.level.trim())
+            .chain(self.module_levels.iter().map(|d| d.trim()))
+            .filter(|d| !d.is_empty())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+// ============================================================================
+// Grid Quality Configuration
+// ============================================================================
+
+/// Grid voltage/frequency observer settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GridQualityConfigCore {
+    /// Track grid voltage and frequency and react to sustained over-voltage
+    pub enabled: bool,
+    /// Voltage considered too high (EN 50160: 230 V + 10 %)
+    pub overvoltage_threshold_v: f32,
+    /// How long the average voltage must stay above the threshold (minutes)
+    pub sustained_minutes: u32,
+    /// Lower bound of the normal frequency band (Hz)
+    pub frequency_min_hz: f32,
+    /// Upper bound of the normal frequency band (Hz)
+    pub frequency_max_hz: f32,
+    /// Share of the export limit kept while over-voltage persists (0-100 %)
+    pub overvoltage_export_percent: f32,
+}
+
+impl Default for GridQualityConfigCore {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            overvoltage_threshold_v: 253.0,
+            sustained_minutes: 10,
+            frequency_min_hz: 49.8,
+            frequency_max_hz: 50.2,
+            overvoltage_export_percent: 50.0,
+        }
+    }
+}
+
+// ============================================================================
+// Peak Demand Configuration
+// ============================================================================
+
+/// Quarter-hour peak demand limiting settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PeakDemandConfigCore {
+    /// Keep the 15-minute average grid import below the contracted maximum
+    pub enabled: bool,
+    /// Contracted quarter-hour maximum (kW)
+    pub max_import_kw: f32,
+    /// Average import, as a share of the maximum, at which shaving starts (%)
+    pub trigger_percent: f32,
+}
+
+impl Default for PeakDemandConfigCore {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_import_kw: 10.0,
+            trigger_percent: 90.0,
+        }
+    }
+}
+
+impl PeakDemandConfigCore {
+    /// Average import at which shaving starts (W)
+    pub fn trigger_w(&self) -> f32 {
+        self.max_import_kw * 1000.0 * self.trigger_percent.clamp(0.0, 100.0) / 100.0
+    }
+}
+
 // ============================================================================
 // Solar Forecast Configuration
 // ============================================================================
@@ -0,0 +1,186 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+//! Config schema versions and the migrations between them.
+//!
+//! Every stored config carries a `schema_version`. When a key is renamed or
+//! moved, bump [`CONFIG_SCHEMA_VERSION`] and append a [`Migration`] that
+//! rewrites the old layout. Configs are migrated as raw JSON before they are
+//! deserialized, so values under renamed keys survive an upgrade instead of
+//! being dropped by serde as unknown fields.
+
+use serde_json::{Map, Value};
+
+/// Schema version written by this release
+pub const CONFIG_SCHEMA_VERSION: u32 = 2;
+
+/// Version of configs written before `schema_version` existed
+pub const UNVERSIONED_SCHEMA_VERSION: u32 = 1;
+
+/// Default for configs deserialized without a `schema_version`
+///
+/// Same as for raw configs in [`schema_version`], so both paths agree that
+/// such a config predates versioning.
+pub fn unversioned_schema_version() -> u32 {
+    UNVERSIONED_SCHEMA_VERSION
+}
+
+/// Rewrite of the config from the previous schema version
+pub struct Migration {
+    /// Version the config has after the migration
+    pub to_version: u32,
+    pub description: &'static str,
+    pub apply: fn(&mut Map<String, Value>),
+}
+
+/// All migrations, ordered by `to_version`
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    to_version: 2,
+    description: "inverter keys vendor, slave_ids and master_id renamed to inverter_type, slaves and master",
+    apply: rename_inverter_keys,
+}];
+
+/// Outcome of [`migrate_config`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Descriptions of the applied migrations, in order
+    pub applied: Vec<&'static str>,
+}
+
+impl MigrationReport {
+    /// Whether the config was changed and should be written back
+    pub fn migrated(&self) -> bool {
+        self.from_version != self.to_version
+    }
+
+    /// Whether the config comes from a newer release than this one
+    pub fn is_newer(&self) -> bool {
+        self.from_version > CONFIG_SCHEMA_VERSION
+    }
+}
+
+/// Schema version of a raw config, [`UNVERSIONED_SCHEMA_VERSION`] when missing
+pub fn schema_version(config: &Value) -> u32 {
+    config
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .and_then(|v| u32::try_from(v).ok())
+        .unwrap_or(UNVERSIONED_SCHEMA_VERSION)
+}
+
+/// Apply the pending migrations to a raw config and stamp the current version
+///
+/// Configs from a newer release are left untouched.
+pub fn migrate_config(config: &mut Value) -> MigrationReport {
+    let from_version = schema_version(config);
+    let mut report = MigrationReport {
+        from_version,
+        to_version: from_version,
+        applied: Vec::new(),
+    };
+    let Some(object) = config.as_object_mut() else {
+        return report;
+    };
+    if from_version >= CONFIG_SCHEMA_VERSION {
+        return report;
+    }
+
+    for migration in MIGRATIONS.iter().filter(|m| m.to_version > from_version) {
+        (migration.apply)(object);
+        report.applied.push(migration.description);
+    }
+    object.insert(
+        "schema_version".to_owned(),
+        Value::from(CONFIG_SCHEMA_VERSION),
+    );
+    report.to_version = CONFIG_SCHEMA_VERSION;
+    report
+}
+
+/// Move `from` to `to` unless `to` is already set
+fn rename_key(object: &mut Map<String, Value>, from: &str, to: &str) {
+    if let Some(value) = object.remove(from) {
+        object.entry(to).or_insert(value);
+    }
+}
+
+/// 1 -> 2: the HA addon options used different names for the inverter keys
+fn rename_inverter_keys(config: &mut Map<String, Value>) {
+    let Some(inverters) = config.get_mut("inverters").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for inverter in inverters.iter_mut().filter_map(Value::as_object_mut) {
+        rename_key(inverter, "vendor", "inverter_type");
+        rename_key(inverter, "slave_ids", "slaves");
+        rename_key(inverter, "master_id", "master");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrations_are_ordered() {
+        let versions: Vec<u32> = MIGRATIONS.iter().map(|m| m.to_version).collect();
+
+        assert!(versions.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(versions.last(), Some(&CONFIG_SCHEMA_VERSION));
+    }
+
+    #[test]
+    fn test_unversioned_config_is_migrated() {
+        let mut config = json!({
+            "inverters": [
+                { "id": "main", "vendor": "solax", "slave_ids": ["garage"] },
+                { "id": "garage", "inverter_type": "solax", "vendor": "ignored", "master_id": "main" }
+            ]
+        });
+
+        let report = migrate_config(&mut config);
+
+        assert!(report.migrated());
+        assert_eq!(report.from_version, UNVERSIONED_SCHEMA_VERSION);
+        assert_eq!(report.applied.len(), 1);
+        assert_eq!(schema_version(&config), CONFIG_SCHEMA_VERSION);
+        assert_eq!(config["inverters"][0]["inverter_type"], "solax");
+        assert_eq!(config["inverters"][0]["slaves"], json!(["garage"]));
+        assert_eq!(config["inverters"][1]["inverter_type"], "solax");
+        assert_eq!(config["inverters"][1]["master"], "main");
+        assert_eq!(config["inverters"][1].get("vendor"), None);
+
+        let again = migrate_config(&mut config);
+        assert!(!again.migrated());
+        assert!(again.applied.is_empty());
+    }
+
+    #[test]
+    fn test_missing_version_defaults_agree() {
+        // A config deserialized without `schema_version` must not claim the current
+        // layout, or it would skip the migrations the raw path applies
+        assert_eq!(unversioned_schema_version(), schema_version(&json!({})));
+        assert!(unversioned_schema_version() < CONFIG_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_newer_config_is_left_alone() {
+        let mut config = json!({ "schema_version": CONFIG_SCHEMA_VERSION + 1, "inverters": [{ "vendor": "solax" }] });
+
+        let report = migrate_config(&mut config);
+
+        assert!(report.is_newer());
+        assert!(!report.migrated());
+        assert_eq!(config["inverters"][0]["vendor"], "solax");
+    }
+}
@@ -29,10 +29,14 @@ pub enum InverterType {
     Solax,
     /// Solax Ultra inverters (uses battery_total_capacity_charge sensor)
     SolaxUltra,
-    // Future inverter types can be added here:
-    // Fronius,
-    // Sma,
-    // Huawei,
+    /// GoodWe ET/EH hybrids (HA `goodwe` integration)
+    #[serde(rename = "goodwe")]
+    GoodWe,
+    /// Huawei SUN2000 with LUNA2000 battery (HA `huawei_solar` integration)
+    Huawei,
+    /// Deye and Sunsynk hybrids (HA Sunsynk/Solarman integrations)
+    #[serde(alias = "sunsynk")]
+    Deye,
 }
 
 impl InverterType {
@@ -41,6 +45,9 @@ impl InverterType {
         match self {
             Self::Solax => "Solax",
             Self::SolaxUltra => "Solax Ultra",
+            Self::GoodWe => "GoodWe ET/EH",
+            Self::Huawei => "Huawei Luna",
+            Self::Deye => "Deye/Sunsynk",
         }
     }
 
@@ -49,12 +56,21 @@ impl InverterType {
         match self {
             Self::Solax => "solax",
             Self::SolaxUltra => "solax-ultra",
+            Self::GoodWe => "goodwe",
+            Self::Huawei => "huawei",
+            Self::Deye => "deye",
         }
     }
 
     /// List all supported inverter types
     pub fn all() -> &'static [InverterType] {
-        &[Self::Solax, Self::SolaxUltra]
+        &[
+            Self::Solax,
+            Self::SolaxUltra,
+            Self::GoodWe,
+            Self::Huawei,
+            Self::Deye,
+        ]
     }
 }
 
@@ -71,6 +87,9 @@ impl FromStr for InverterType {
         match s.to_lowercase().as_str() {
             "solax" => Ok(Self::Solax),
             "solax-ultra" => Ok(Self::SolaxUltra),
+            "goodwe" => Ok(Self::GoodWe),
+            "huawei" => Ok(Self::Huawei),
+            "deye" | "sunsynk" => Ok(Self::Deye),
             _ => Err(anyhow::anyhow!(
                 "Unknown inverter type: '{}'. Supported types: {}",
                 s,
@@ -467,4 +486,7 @@ pub enum InverterCommand {
 
     /// Set export power limit (watts)
     SetExportLimit(u32),
+
+    /// Limit battery charging power (watts), used for partial-power force charging
+    SetChargePowerLimit(u32),
 }
@@ -10,22 +10,34 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
+pub mod calendar;
 pub mod config;
 pub mod day_profile;
 pub mod health;
 pub mod history;
 pub mod inverter;
 pub mod pricing;
+pub mod reason;
 pub mod scheduling;
+pub mod tariff;
 pub mod user_control;
+pub mod weather;
 pub mod web;
 
 // Re-export common types for convenience
+pub use calendar::{CalendarConfig, DayKind, SpecialDay, VacationRange};
 pub use config::{ControlConfig, SystemConfig};
 pub use day_profile::DayMetrics;
 pub use health::{HealthStatus, SystemHealthData};
 pub use history::{ConsumptionHistory, ConsumptionHistoryConfig};
 pub use inverter::{Inverter, InverterOperationMode, InverterType};
 pub use pricing::{PriceAnalysis, SpotPriceData};
+pub use reason::DecisionReason;
 pub use scheduling::{BlockDebugInfo, OperationSchedule, ScheduledMode, StrategyEvaluation};
-pub use user_control::{FixedTimeSlot, UserControlState};
+pub use tariff::{HdoPreset, HdoSource, Tariff, TariffSchedule, TariffWindow};
+pub use user_control::{
+    ArchivedTimeSlot, BackupReserve, CONTROL_PRECEDENCE, FixedTimeSlot, MAX_ARCHIVED_SLOTS,
+    SafeStateActivation, UserControlIssue, UserControlIssueKind, UserControlState,
+    UserControlValidation,
+};
+pub use weather::{WeatherConfigCore, WeatherOutlook, WeatherPlanningConfig, WeatherPoint};
@@ -14,6 +14,8 @@ use bevy_ecs::prelude::Component;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::reason::DecisionReason;
+
 // ============= Pricing Components (FluxION MVP) =============
 
 /// Spot price data from HA price integration
@@ -46,7 +48,7 @@ impl Default for SpotPriceData {
 }
 
 /// A single time block with price
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TimeBlockPrice {
     /// Start time of this block
     pub block_start: DateTime<Utc>,
@@ -181,394 +183,6 @@ pub struct PriceData {
     pub tomorrow_median_price: Option<f32>,
 }
 
-/// Compact representation of decision reasons to save space in exports
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type")]
-pub enum DecisionReason {
-    /// Self-Use - Normal operation (price)
-    SelfUseNormal { price: f32 },
-
-    /// Winter-Adaptive - Standard operation
-    WinterAdaptiveStandard,
-
-    /// Winter-Adaptive - Charging for horizon/target
-    WinterAdaptiveCharge {
-        price: f32,
-        avg_price: f32,
-        expected_profit: f32,
-    },
-
-    /// Winter-Adaptive - Urgent charge for today
-    WinterAdaptiveUrgentCharge {
-        price: f32,
-        avg_price: f32,
-        expected_profit: f32,
-    },
-
-    /// Winter-Adaptive - Discharging during expensive block
-    WinterAdaptiveDischarge { price: f32, expected_profit: f32 },
-
-    /// Winter-Adaptive - Holding charge during cheap block
-    WinterAdaptiveHold {
-        current_price: f32,
-        threshold_price: f32,
-        expected_profit: f32,
-    },
-
-    /// Winter-Adaptive - Preserving for tomorrow
-    WinterAdaptivePreserve {
-        tomorrow_avg: f32,
-        current_price: f32,
-        expected_profit: f32,
-    },
-
-    /// Winter-Peak-Discharge - Peak price discharge
-    WinterPeakDischarge { price: f32 },
-
-    /// Time-Aware Charge - Cheapest block
-    TimeAwareCharge { price: f32 },
-
-    /// Merged with adjacent charge blocks for EEPROM protection
-    EepromMergedGap { gap_filled: u8, max_gap: u8 },
-
-    /// Extended sequence for EEPROM protection
-    EepromExtendedSequence { mode: String, min_blocks: u8 },
-
-    /// Custom reason (fallback for new patterns)
-    Custom { reason: String },
-}
-
-impl DecisionReason {
-    /// Create from legacy string format for backward compatibility
-    pub fn from_string(reason: &str) -> Self {
-        // Parse common patterns
-        if reason.starts_with("Self-Use - Normal operation")
-            && let Some(price) = extract_price_from_reason(reason)
-        {
-            return Self::SelfUseNormal { price };
-        }
-
-        if reason == "Winter-Adaptive - Standard operation (expected profit: 0.00 CZK)" {
-            return Self::WinterAdaptiveStandard;
-        }
-
-        if reason.starts_with("Winter-Adaptive - Charging for horizon/target")
-            && let Some((price, avg, profit)) = extract_charge_info(reason)
-        {
-            return Self::WinterAdaptiveCharge {
-                price,
-                avg_price: avg,
-                expected_profit: profit,
-            };
-        }
-
-        if reason.starts_with("Winter-Adaptive - Urgent charge for today")
-            && let Some((price, avg, profit)) = extract_charge_info(reason)
-        {
-            return Self::WinterAdaptiveUrgentCharge {
-                price,
-                avg_price: avg,
-                expected_profit: profit,
-            };
-        }
-
-        if reason.starts_with("Winter-Adaptive - Discharging during expensive block")
-            && let Some((price, profit)) = extract_discharge_info(reason)
-        {
-            return Self::WinterAdaptiveDischarge {
-                price,
-                expected_profit: profit,
-            };
-        }
-
-        if reason.starts_with("Winter-Adaptive - Holding charge during cheap block")
-            && let Some((current, threshold, profit)) = extract_hold_info(reason)
-        {
-            return Self::WinterAdaptiveHold {
-                current_price: current,
-                threshold_price: threshold,
-                expected_profit: profit,
-            };
-        }
-
-        if reason.starts_with("Winter-Adaptive - Preserving for tomorrow")
-            && let Some((tomorrow_avg, current, profit)) = extract_preserve_info(reason)
-        {
-            return Self::WinterAdaptivePreserve {
-                tomorrow_avg,
-                current_price: current,
-                expected_profit: profit,
-            };
-        }
-
-        if reason.starts_with("Winter-Peak-Discharge - Peak price")
-            && let Some(price) = extract_price_from_reason(reason)
-        {
-            return Self::WinterPeakDischarge { price };
-        }
-
-        if reason.starts_with("Time-Aware Charge - Cheapest block")
-            && let Some(price) = extract_price_from_reason(reason)
-        {
-            return Self::TimeAwareCharge { price };
-        }
-
-        if reason.starts_with("Merged with adjacent charge blocks")
-            && let Some((gap, max_gap)) = extract_gap_info(reason)
-        {
-            return Self::EepromMergedGap {
-                gap_filled: gap,
-                max_gap,
-            };
-        }
-
-        if reason.starts_with("Extended")
-            && reason.contains("sequence for EEPROM protection")
-            && let Some((mode, min_blocks)) = extract_extended_info(reason)
-        {
-            return Self::EepromExtendedSequence { mode, min_blocks };
-        }
-
-        // Fallback to custom reason
-        Self::Custom {
-            reason: reason.to_string(),
-        }
-    }
-
-    /// Convert back to human-readable string for display
-    pub fn to_display_string(&self) -> String {
-        match self {
-            Self::SelfUseNormal { price } => {
-                format!("Self-Use - Normal operation ({:.2} CZK/kWh)", price)
-            }
-            Self::WinterAdaptiveStandard => "Winter-Adaptive - Standard operation".to_string(),
-            Self::WinterAdaptiveCharge {
-                price,
-                avg_price,
-                expected_profit,
-            } => format!(
-                "Winter-Adaptive - Charging for horizon/target ({:.2} CZK/kWh) (avg: {:.2}) (expected profit: {:.2} CZK)",
-                price, avg_price, expected_profit
-            ),
-            Self::WinterAdaptiveUrgentCharge {
-                price,
-                avg_price,
-                expected_profit,
-            } => format!(
-                "Winter-Adaptive - Urgent charge for today ({:.2} CZK/kWh) (avg: {:.2}) (expected profit: {:.2} CZK)",
-                price, avg_price, expected_profit
-            ),
-            Self::WinterAdaptiveDischarge {
-                price,
-                expected_profit,
-            } => format!(
-                "Winter-Adaptive - Discharging during expensive block ({:.2} CZK/kWh) (expected profit: {:.2} CZK)",
-                price, expected_profit
-            ),
-            Self::WinterAdaptiveHold {
-                current_price,
-                threshold_price,
-                expected_profit,
-            } => format!(
-                "Winter-Adaptive - Holding charge during cheap block ({:.2} < {:.2}) (expected profit: {:.2} CZK)",
-                current_price, threshold_price, expected_profit
-            ),
-            Self::WinterAdaptivePreserve {
-                tomorrow_avg,
-                current_price,
-                expected_profit,
-            } => format!(
-                "Winter-Adaptive - Preserving for tomorrow (avg {:.2} > {:.2}) (expected profit: {:.2} CZK)",
-                tomorrow_avg, current_price, expected_profit
-            ),
-            Self::WinterPeakDischarge { price } => {
-                format!("Winter-Peak-Discharge - Peak price ({:.2} CZK/kWh)", price)
-            }
-            Self::TimeAwareCharge { price } => {
-                format!("Time-Aware Charge - Cheapest block ({:.2} CZK/kWh)", price)
-            }
-            Self::EepromMergedGap {
-                gap_filled,
-                max_gap,
-            } => format!(
-                "Merged with adjacent charge blocks (gap {}/{} filled for EEPROM protection)",
-                gap_filled, max_gap
-            ),
-            Self::EepromExtendedSequence { mode, min_blocks } => format!(
-                "Extended {} sequence for EEPROM protection (min {} blocks)",
-                mode, min_blocks
-            ),
-            Self::Custom { reason } => reason.clone(),
-        }
-    }
-}
-
-// Helper functions to extract values from legacy strings
-fn extract_price_from_reason(reason: &str) -> Option<f32> {
-    // Extract price like "(3.22 CZK/kWh)"
-    if let Some(start) = reason.find('(')
-        && let Some(end) = reason[start..].find(' ')
-        && let Ok(price) = reason[start + 1..start + end].parse::<f32>()
-    {
-        return Some(price);
-    }
-    None
-}
-
-fn extract_charge_info(reason: &str) -> Option<(f32, f32, f32)> {
-    // Extract: price (avg: X) (expected profit: Y CZK)
-    let price = extract_price_from_reason(reason)?;
-
-    let avg = if let Some(avg_start) = reason.find("(avg: ") {
-        if let Some(avg_end) = reason[avg_start + 6..].find(')') {
-            reason[avg_start + 6..avg_start + 6 + avg_end]
-                .parse::<f32>()
-                .ok()?
-        } else {
-            return None;
-        }
-    } else {
-        return None;
-    };
-
-    let profit = if let Some(profit_start) = reason.find("(expected profit: ") {
-        if let Some(profit_end) = reason[profit_start + 18..].find(' ') {
-            reason[profit_start + 18..profit_start + 18 + profit_end]
-                .parse::<f32>()
-                .ok()?
-        } else {
-            return None;
-        }
-    } else {
-        return None;
-    };
-
-    Some((price, avg, profit))
-}
-
-fn extract_discharge_info(reason: &str) -> Option<(f32, f32)> {
-    let price = extract_price_from_reason(reason)?;
-
-    let profit = if let Some(profit_start) = reason.find("(expected profit: ") {
-        if let Some(profit_end) = reason[profit_start + 18..].find(' ') {
-            reason[profit_start + 18..profit_start + 18 + profit_end]
-                .parse::<f32>()
-                .ok()?
-        } else {
-            return None;
-        }
-    } else {
-        return None;
-    };
-
-    Some((price, profit))
-}
-
-fn extract_hold_info(reason: &str) -> Option<(f32, f32, f32)> {
-    // Extract: (2.498 < 3.123) (expected profit: 0.00 CZK)
-    if let Some(start) = reason.find('(')
-        && let Some(less_than) = reason[start..].find(" < ")
-    {
-        let current_start = start + 1;
-        let current_end = start + less_than;
-        let current = reason[current_start..current_end].parse::<f32>().ok()?;
-
-        let threshold_start = start + less_than + 3;
-        if let Some(end_paren) = reason[threshold_start..].find(')') {
-            let threshold = reason[threshold_start..threshold_start + end_paren]
-                .parse::<f32>()
-                .ok()?;
-
-            let profit = if let Some(profit_start) = reason.find("(expected profit: ") {
-                if let Some(profit_end) = reason[profit_start + 18..].find(' ') {
-                    reason[profit_start + 18..profit_start + 18 + profit_end]
-                        .parse::<f32>()
-                        .ok()?
-                } else {
-                    return None;
-                }
-            } else {
-                return None;
-            };
-
-            return Some((current, threshold, profit));
-        }
-    }
-    None
-}
-
-fn extract_preserve_info(reason: &str) -> Option<(f32, f32, f32)> {
-    // Extract: (avg 4.27 > 2.90) (expected profit: 0.00 CZK)
-    if let Some(avg_start) = reason.find("(avg ")
-        && let Some(greater_than) = reason[avg_start + 5..].find(" > ")
-    {
-        let tomorrow_avg = reason[avg_start + 5..avg_start + 5 + greater_than]
-            .parse::<f32>()
-            .ok()?;
-
-        let current_start = avg_start + 5 + greater_than + 3;
-        if let Some(end_paren) = reason[current_start..].find(')') {
-            let current = reason[current_start..current_start + end_paren]
-                .parse::<f32>()
-                .ok()?;
-
-            let profit = if let Some(profit_start) = reason.find("(expected profit: ") {
-                if let Some(profit_end) = reason[profit_start + 18..].find(' ') {
-                    reason[profit_start + 18..profit_start + 18 + profit_end]
-                        .parse::<f32>()
-                        .ok()?
-                } else {
-                    return None;
-                }
-            } else {
-                return None;
-            };
-
-            return Some((tomorrow_avg, current, profit));
-        }
-    }
-    None
-}
-
-fn extract_gap_info(reason: &str) -> Option<(u8, u8)> {
-    // Extract: (gap 1/2 filled for EEPROM protection)
-    if let Some(gap_start) = reason.find("(gap ")
-        && let Some(slash_pos) = reason[gap_start + 5..].find('/')
-    {
-        let gap_filled = reason[gap_start + 5..gap_start + 5 + slash_pos]
-            .parse::<u8>()
-            .ok()?;
-
-        let max_start = gap_start + 5 + slash_pos + 1;
-        if let Some(space_pos) = reason[max_start..].find(' ') {
-            let max_gap = reason[max_start..max_start + space_pos]
-                .parse::<u8>()
-                .ok()?;
-            return Some((gap_filled, max_gap));
-        }
-    }
-    None
-}
-
-fn extract_extended_info(reason: &str) -> Option<(String, u8)> {
-    // Extract: Extended charge sequence for EEPROM protection (min 3 blocks)
-    if let Some(extended_start) = reason.find("Extended ")
-        && let Some(sequence_pos) = reason[extended_start + 9..].find(" sequence")
-    {
-        let mode = reason[extended_start + 9..extended_start + 9 + sequence_pos].to_string();
-
-        if let Some(min_start) = reason.find("(min ")
-            && let Some(blocks_pos) = reason[min_start + 5..].find(" blocks")
-        {
-            let min_blocks = reason[min_start + 5..min_start + 5 + blocks_pos]
-                .parse::<u8>()
-                .ok()?;
-            return Some((mode, min_blocks));
-        }
-    }
-    None
-}
-
 /// Individual price block (for Web API)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PriceBlockData {
@@ -579,6 +193,8 @@ pub struct PriceBlockData {
     pub strategy: Option<String>,     // Strategy that chose this mode
     pub expected_profit: Option<f32>, // Expected profit for this block (CZK)
     pub reason: Option<String>,       // Detailed reason for the decision
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision_reason: Option<DecisionReason>, // Structured reason for the decision
     // Note: Debug info omitted for now to avoid circular dependency with strategy crate
     // #[serde(skip_serializing_if = "Option::is_none")]
     // pub debug_info: Option<crate::strategy::BlockDebugInfo>,
@@ -636,10 +252,12 @@ impl CompactPriceBlockData {
             target_soc: block.target_soc.map(|soc| (soc * 10.0).round() / 10.0), // Round to 1 decimal
             strategy_code: block.strategy.as_ref().map(|s| strategy_to_code(s)),
             expected_profit: block.expected_profit.map(|p| (p * 100.0).round() / 100.0),
-            reason: block
-                .reason
-                .as_ref()
-                .map(|r| DecisionReason::from_string(r)),
+            reason: block.decision_reason.clone().or_else(|| {
+                block
+                    .reason
+                    .as_ref()
+                    .map(|r| DecisionReason::from_string(r))
+            }),
             is_historical: block.is_historical,
         }
     }
@@ -659,6 +277,7 @@ impl CompactPriceBlockData {
             strategy: self.strategy_code.as_ref().map(|c| code_to_strategy(c)),
             expected_profit: self.expected_profit,
             reason: self.reason.as_ref().map(|r| r.to_display_string()),
+            decision_reason: self.reason.clone(),
             is_historical: self.is_historical,
         }
     }
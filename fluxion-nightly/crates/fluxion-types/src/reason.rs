@@ -0,0 +1,667 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Structured decision reasons for scheduled blocks
+
+use fluent::fluent_args;
+use fluxion_i18n::I18n;
+use serde::{Deserialize, Serialize};
+
+use crate::inverter::InverterOperationMode;
+
+/// Why a block got its mode, with the values the decision was based on
+///
+/// Carried next to the human-readable reason string, so reasons can be localized,
+/// filtered by [`DecisionReason::kind`] and exported compactly.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DecisionReason {
+    /// Self-Use - Normal operation (price)
+    SelfUseNormal { price: f32 },
+
+    /// Winter-Adaptive - Standard operation
+    WinterAdaptiveStandard,
+
+    /// Winter-Adaptive - Charging for horizon/target
+    WinterAdaptiveCharge {
+        price: f32,
+        avg_price: f32,
+        expected_profit: f32,
+    },
+
+    /// Winter-Adaptive - Urgent charge for today
+    WinterAdaptiveUrgentCharge {
+        price: f32,
+        avg_price: f32,
+        expected_profit: f32,
+    },
+
+    /// Winter-Adaptive - Discharging during expensive block
+    WinterAdaptiveDischarge { price: f32, expected_profit: f32 },
+
+    /// Winter-Adaptive - Holding charge during cheap block
+    WinterAdaptiveHold {
+        current_price: f32,
+        threshold_price: f32,
+        expected_profit: f32,
+    },
+
+    /// Winter-Adaptive - Preserving for tomorrow
+    WinterAdaptivePreserve {
+        tomorrow_avg: f32,
+        current_price: f32,
+        expected_profit: f32,
+    },
+
+    /// Winter-Peak-Discharge - Peak price discharge
+    WinterPeakDischarge { price: f32 },
+
+    /// Time-Aware Charge - Cheapest block
+    TimeAwareCharge { price: f32 },
+
+    /// Merged with adjacent charge blocks for EEPROM protection
+    EepromMergedGap { gap_filled: u8, max_gap: u8 },
+
+    /// Extended sequence for EEPROM protection
+    EepromExtendedSequence { mode: String, min_blocks: u8 },
+
+    /// Legacy scheduler - one of the cheapest blocks
+    CheapestBlock { price: f32 },
+
+    /// Legacy scheduler - one of the most expensive blocks
+    PeakPrice { price: f32 },
+
+    /// Legacy scheduler - normal operation
+    NormalOperation { price: f32 },
+
+    /// Block locked by a user fixed slot or pin
+    UserOverride {
+        slot_id: String,
+        note: Option<String>,
+    },
+
+    /// Beyond the optimization horizon, decided by price percentile
+    BeyondHorizon { mode: InverterOperationMode },
+
+    /// Converted to keep the temporary backup reserve
+    BackupReserve {
+        from: InverterOperationMode,
+        min_soc: f32,
+    },
+
+    /// Converted because the user disallowed the planned mode
+    UserRestriction { from: InverterOperationMode },
+
+    /// Forced discharge skipped on a public holiday or vacation
+    SpecialDay { name: String },
+
+    /// Forced discharge skipped to keep the reserve before a cold cloudy day
+    WeatherReserve { min_soc: f32 },
+
+    /// Grid charge skipped on a clear morning
+    ClearDay { cloud_cover_pct: f32 },
+
+    /// Forced discharge skipped inside an export cap window
+    ExportCap { cap_w: u32 },
+
+    /// Forced sequence shorter than the minimum, converted to the default mode
+    ShortSequence {
+        from: InverterOperationMode,
+        blocks: usize,
+        min_blocks: usize,
+    },
+
+    /// Block that cannot work, repaired by the schedule guard
+    ScheduleGuard {
+        from: InverterOperationMode,
+        issue: String,
+    },
+
+    /// Custom reason (fallback for new patterns)
+    Custom { reason: String },
+}
+
+impl DecisionReason {
+    /// Create from legacy string format for backward compatibility
+    pub fn from_string(reason: &str) -> Self {
+        Self::parse(reason).unwrap_or_else(|| Self::Custom {
+            reason: reason.to_string(),
+        })
+    }
+
+    /// Recognize a legacy reason string, None when it matches no known pattern
+    pub fn parse(reason: &str) -> Option<Self> {
+        // Parse common patterns
+        if reason.starts_with("Self-Use - Normal operation")
+            && let Some(price) = extract_price_from_reason(reason)
+        {
+            return Some(Self::SelfUseNormal { price });
+        }
+
+        if reason == "Winter-Adaptive - Standard operation (expected profit: 0.00 CZK)" {
+            return Some(Self::WinterAdaptiveStandard);
+        }
+
+        if reason.starts_with("Winter-Adaptive - Charging for horizon/target")
+            && let Some((price, avg, profit)) = extract_charge_info(reason)
+        {
+            return Some(Self::WinterAdaptiveCharge {
+                price,
+                avg_price: avg,
+                expected_profit: profit,
+            });
+        }
+
+        if reason.starts_with("Winter-Adaptive - Urgent charge for today")
+            && let Some((price, avg, profit)) = extract_charge_info(reason)
+        {
+            return Some(Self::WinterAdaptiveUrgentCharge {
+                price,
+                avg_price: avg,
+                expected_profit: profit,
+            });
+        }
+
+        if reason.starts_with("Winter-Adaptive - Discharging during expensive block")
+            && let Some((price, profit)) = extract_discharge_info(reason)
+        {
+            return Some(Self::WinterAdaptiveDischarge {
+                price,
+                expected_profit: profit,
+            });
+        }
+
+        if reason.starts_with("Winter-Adaptive - Holding charge during cheap block")
+            && let Some((current, threshold, profit)) = extract_hold_info(reason)
+        {
+            return Some(Self::WinterAdaptiveHold {
+                current_price: current,
+                threshold_price: threshold,
+                expected_profit: profit,
+            });
+        }
+
+        if reason.starts_with("Winter-Adaptive - Preserving for tomorrow")
+            && let Some((tomorrow_avg, current, profit)) = extract_preserve_info(reason)
+        {
+            return Some(Self::WinterAdaptivePreserve {
+                tomorrow_avg,
+                current_price: current,
+                expected_profit: profit,
+            });
+        }
+
+        if reason.starts_with("Winter-Peak-Discharge - Peak price")
+            && let Some(price) = extract_price_from_reason(reason)
+        {
+            return Some(Self::WinterPeakDischarge { price });
+        }
+
+        if reason.starts_with("Time-Aware Charge - Cheapest block")
+            && let Some(price) = extract_price_from_reason(reason)
+        {
+            return Some(Self::TimeAwareCharge { price });
+        }
+
+        if reason.starts_with("Merged with adjacent charge blocks")
+            && let Some((gap, max_gap)) = extract_gap_info(reason)
+        {
+            return Some(Self::EepromMergedGap {
+                gap_filled: gap,
+                max_gap,
+            });
+        }
+
+        if reason.starts_with("Extended")
+            && reason.contains("sequence for EEPROM protection")
+            && let Some((mode, min_blocks)) = extract_extended_info(reason)
+        {
+            return Some(Self::EepromExtendedSequence { mode, min_blocks });
+        }
+
+        None
+    }
+
+    /// Convert back to human-readable string for display
+    pub fn to_display_string(&self) -> String {
+        match self {
+            Self::SelfUseNormal { price } => {
+                format!("Self-Use - Normal operation ({:.2} CZK/kWh)", price)
+            }
+            Self::WinterAdaptiveStandard => "Winter-Adaptive - Standard operation".to_string(),
+            Self::WinterAdaptiveCharge {
+                price,
+                avg_price,
+                expected_profit,
+            } => format!(
+                "Winter-Adaptive - Charging for horizon/target ({:.2} CZK/kWh) (avg: {:.2}) (expected profit: {:.2} CZK)",
+                price, avg_price, expected_profit
+            ),
+            Self::WinterAdaptiveUrgentCharge {
+                price,
+                avg_price,
+                expected_profit,
+            } => format!(
+                "Winter-Adaptive - Urgent charge for today ({:.2} CZK/kWh) (avg: {:.2}) (expected profit: {:.2} CZK)",
+                price, avg_price, expected_profit
+            ),
+            Self::WinterAdaptiveDischarge {
+                price,
+                expected_profit,
+            } => format!(
+                "Winter-Adaptive - Discharging during expensive block ({:.2} CZK/kWh) (expected profit: {:.2} CZK)",
+                price, expected_profit
+            ),
+            Self::WinterAdaptiveHold {
+                current_price,
+                threshold_price,
+                expected_profit,
+            } => format!(
+                "Winter-Adaptive - Holding charge during cheap block ({:.2} < {:.2}) (expected profit: {:.2} CZK)",
+                current_price, threshold_price, expected_profit
+            ),
+            Self::WinterAdaptivePreserve {
+                tomorrow_avg,
+                current_price,
+                expected_profit,
+            } => format!(
+                "Winter-Adaptive - Preserving for tomorrow (avg {:.2} > {:.2}) (expected profit: {:.2} CZK)",
+                tomorrow_avg, current_price, expected_profit
+            ),
+            Self::WinterPeakDischarge { price } => {
+                format!("Winter-Peak-Discharge - Peak price ({:.2} CZK/kWh)", price)
+            }
+            Self::TimeAwareCharge { price } => {
+                format!("Time-Aware Charge - Cheapest block ({:.2} CZK/kWh)", price)
+            }
+            Self::EepromMergedGap {
+                gap_filled,
+                max_gap,
+            } => format!(
+                "Merged with adjacent charge blocks (gap {}/{} filled for EEPROM protection)",
+                gap_filled, max_gap
+            ),
+            Self::EepromExtendedSequence { mode, min_blocks } => format!(
+                "Extended {} sequence for EEPROM protection (min {} blocks)",
+                mode, min_blocks
+            ),
+            Self::CheapestBlock { price } => format!("Cheapest block ({:.2} CZK/kWh)", price),
+            Self::PeakPrice { price } => format!("Peak price ({:.2} CZK/kWh)", price),
+            Self::NormalOperation { price } => {
+                format!("Normal operation ({:.2} CZK/kWh)", price)
+            }
+            Self::UserOverride { note, .. } => note.as_ref().map_or_else(
+                || "User Override - User-locked time slot".to_string(),
+                |note| format!("User Override - {note}"),
+            ),
+            Self::BeyondHorizon { mode } => {
+                format!("Beyond optimization horizon: {mode:?} by price percentile")
+            }
+            Self::BackupReserve { from, min_soc } => {
+                format!("Converted from {from:?} - {min_soc:.0}% backup reserve")
+            }
+            Self::UserRestriction { from } => {
+                format!("Converted from {from:?} - user restriction")
+            }
+            Self::SpecialDay { name } => format!("Converted from ForceDischarge - {name}"),
+            Self::WeatherReserve { min_soc } => format!(
+                "Converted from ForceDischarge - keeping {min_soc:.0}% before a cold cloudy day"
+            ),
+            Self::ClearDay { cloud_cover_pct } => {
+                format!("Converted from ForceCharge - clear day, {cloud_cover_pct:.0}% cloud cover")
+            }
+            Self::ExportCap { cap_w } => {
+                format!("Converted from ForceDischarge - export cap window {cap_w}W")
+            }
+            Self::ShortSequence {
+                from,
+                blocks,
+                min_blocks,
+            } => format!(
+                "Converted from {from:?} ({blocks}-block sequence < {min_blocks} min required)"
+            ),
+            Self::ScheduleGuard { from, issue } => {
+                format!("Converted from {from:?} (schedule guard: {issue})")
+            }
+            Self::Custom { reason } => reason.clone(),
+        }
+    }
+
+    /// Stable snake_case identifier of the reason, for filtering
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::SelfUseNormal { .. } => "self_use_normal",
+            Self::WinterAdaptiveStandard => "winter_adaptive_standard",
+            Self::WinterAdaptiveCharge { .. } => "winter_adaptive_charge",
+            Self::WinterAdaptiveUrgentCharge { .. } => "winter_adaptive_urgent_charge",
+            Self::WinterAdaptiveDischarge { .. } => "winter_adaptive_discharge",
+            Self::WinterAdaptiveHold { .. } => "winter_adaptive_hold",
+            Self::WinterAdaptivePreserve { .. } => "winter_adaptive_preserve",
+            Self::WinterPeakDischarge { .. } => "winter_peak_discharge",
+            Self::TimeAwareCharge { .. } => "time_aware_charge",
+            Self::EepromMergedGap { .. } => "eeprom_merged_gap",
+            Self::EepromExtendedSequence { .. } => "eeprom_extended_sequence",
+            Self::CheapestBlock { .. } => "cheapest_block",
+            Self::PeakPrice { .. } => "peak_price",
+            Self::NormalOperation { .. } => "normal_operation",
+            Self::UserOverride { .. } => "user_override",
+            Self::BeyondHorizon { .. } => "beyond_horizon",
+            Self::BackupReserve { .. } => "backup_reserve",
+            Self::UserRestriction { .. } => "user_restriction",
+            Self::SpecialDay { .. } => "special_day",
+            Self::WeatherReserve { .. } => "weather_reserve",
+            Self::ClearDay { .. } => "clear_day",
+            Self::ExportCap { .. } => "export_cap",
+            Self::ShortSequence { .. } => "short_sequence",
+            Self::ScheduleGuard { .. } => "schedule_guard",
+            Self::Custom { .. } => "custom",
+        }
+    }
+
+    /// Translated reason, falling back to [`Self::to_display_string`] for reasons
+    /// without a translation
+    pub fn localize(&self, i18n: &I18n, currency: &str) -> String {
+        self.translate(i18n, currency)
+            .unwrap_or_else(|| self.to_display_string())
+    }
+
+    /// Translated reason, None for reasons without a translation
+    pub fn translate(&self, i18n: &I18n, currency: &str) -> Option<String> {
+        let price = |price: &f32| format!("{price:.2}");
+        let mode = |mode: &InverterOperationMode| format!("{mode:?}");
+        let soc = |soc: &f32| format!("{soc:.0}");
+        let translated = match self {
+            Self::CheapestBlock { price: p } => i18n.format(
+                "reason-cheapest-block",
+                Some(&fluent_args!["price" => price(p), "currency" => currency]),
+            ),
+            Self::PeakPrice { price: p } => i18n.format(
+                "reason-peak-price",
+                Some(&fluent_args!["price" => price(p), "currency" => currency]),
+            ),
+            Self::NormalOperation { price: p } => i18n.format(
+                "reason-normal-operation",
+                Some(&fluent_args!["price" => price(p), "currency" => currency]),
+            ),
+            Self::UserOverride { note: None, .. } => i18n.get("reason-user-override"),
+            Self::UserOverride {
+                note: Some(note), ..
+            } => i18n.format(
+                "reason-user-override-note",
+                Some(&fluent_args!["note" => note.as_str()]),
+            ),
+            Self::BeyondHorizon { mode: m } => i18n.format(
+                "reason-beyond-horizon",
+                Some(&fluent_args!["mode" => mode(m)]),
+            ),
+            Self::BackupReserve { from, min_soc } => i18n.format(
+                "reason-override-backup-reserve",
+                Some(&fluent_args!["from" => mode(from), "min_soc" => soc(min_soc)]),
+            ),
+            Self::UserRestriction { from } => i18n.format(
+                "reason-override-user-restriction",
+                Some(&fluent_args!["from" => mode(from)]),
+            ),
+            Self::SpecialDay { name } => i18n.format(
+                "reason-override-special-day",
+                Some(&fluent_args!["name" => name.as_str()]),
+            ),
+            Self::WeatherReserve { min_soc } => i18n.format(
+                "reason-override-weather-reserve",
+                Some(&fluent_args!["min_soc" => soc(min_soc)]),
+            ),
+            Self::ClearDay { cloud_cover_pct } => i18n.format(
+                "reason-override-clear-day",
+                Some(&fluent_args!["cloud_cover" => soc(cloud_cover_pct)]),
+            ),
+            Self::ExportCap { cap_w } => i18n.format(
+                "reason-override-export-cap",
+                Some(&fluent_args!["cap_w" => *cap_w]),
+            ),
+            Self::ShortSequence {
+                from,
+                blocks,
+                min_blocks,
+            } => i18n.format(
+                "reason-override-short-sequence",
+                Some(&fluent_args![
+                    "from" => mode(from),
+                    "blocks" => *blocks,
+                    "min_blocks" => *min_blocks
+                ]),
+            ),
+            Self::ScheduleGuard { from, issue } => i18n.format(
+                "reason-override-schedule-guard",
+                Some(&fluent_args!["from" => mode(from), "issue" => issue.as_str()]),
+            ),
+            _ => return None,
+        };
+        translated.ok()
+    }
+}
+
+// Helper functions to extract values from legacy strings
+fn extract_price_from_reason(reason: &str) -> Option<f32> {
+    // Extract price like "(3.22 CZK/kWh)"
+    if let Some(start) = reason.find('(')
+        && let Some(end) = reason[start..].find(' ')
+        && let Ok(price) = reason[start + 1..start + end].parse::<f32>()
+    {
+        return Some(price);
+    }
+    None
+}
+
+fn extract_expected_profit(reason: &str) -> Option<f32> {
+    // Extract: (expected profit: Y CZK)
+    let profit_start = reason.find("(expected profit: ")? + 18;
+    let profit_end = reason[profit_start..].find(' ')?;
+    reason[profit_start..profit_start + profit_end]
+        .parse::<f32>()
+        .ok()
+}
+
+fn extract_charge_info(reason: &str) -> Option<(f32, f32, f32)> {
+    // Extract: price (avg: X) (expected profit: Y CZK)
+    let price = extract_price_from_reason(reason)?;
+
+    let avg_start = reason.find("(avg: ")? + 6;
+    let avg_end = reason[avg_start..].find(')')?;
+    let avg = reason[avg_start..avg_start + avg_end].parse::<f32>().ok()?;
+
+    let profit = extract_expected_profit(reason)?;
+
+    Some((price, avg, profit))
+}
+
+fn extract_discharge_info(reason: &str) -> Option<(f32, f32)> {
+    let price = extract_price_from_reason(reason)?;
+
+    let profit = extract_expected_profit(reason)?;
+
+    Some((price, profit))
+}
+
+fn extract_hold_info(reason: &str) -> Option<(f32, f32, f32)> {
+    // Extract: (2.498 < 3.123) (expected profit: 0.00 CZK)
+    if let Some(start) = reason.find('(')
+        && let Some(less_than) = reason[start..].find(" < ")
+    {
+        let current_start = start + 1;
+        let current_end = start + less_than;
+        let current = reason[current_start..current_end].parse::<f32>().ok()?;
+
+        let threshold_start = start + less_than + 3;
+        if let Some(end_paren) = reason[threshold_start..].find(')') {
+            let threshold = reason[threshold_start..threshold_start + end_paren]
+                .parse::<f32>()
+                .ok()?;
+
+            let profit = extract_expected_profit(reason)?;
+
+            return Some((current, threshold, profit));
+        }
+    }
+    None
+}
+
+fn extract_preserve_info(reason: &str) -> Option<(f32, f32, f32)> {
+    // Extract: (avg 4.27 > 2.90) (expected profit: 0.00 CZK)
+    if let Some(avg_start) = reason.find("(avg ")
+        && let Some(greater_than) = reason[avg_start + 5..].find(" > ")
+    {
+        let tomorrow_avg = reason[avg_start + 5..avg_start + 5 + greater_than]
+            .parse::<f32>()
+            .ok()?;
+
+        let current_start = avg_start + 5 + greater_than + 3;
+        if let Some(end_paren) = reason[current_start..].find(')') {
+            let current = reason[current_start..current_start + end_paren]
+                .parse::<f32>()
+                .ok()?;
+
+            let profit = extract_expected_profit(reason)?;
+
+            return Some((tomorrow_avg, current, profit));
+        }
+    }
+    None
+}
+
+fn extract_gap_info(reason: &str) -> Option<(u8, u8)> {
+    // Extract: (gap 1/2 filled for EEPROM protection)
+    if let Some(gap_start) = reason.find("(gap ")
+        && let Some(slash_pos) = reason[gap_start + 5..].find('/')
+    {
+        let gap_filled = reason[gap_start + 5..gap_start + 5 + slash_pos]
+            .parse::<u8>()
+            .ok()?;
+
+        let max_start = gap_start + 5 + slash_pos + 1;
+        if let Some(space_pos) = reason[max_start..].find(' ') {
+            let max_gap = reason[max_start..max_start + space_pos]
+                .parse::<u8>()
+                .ok()?;
+            return Some((gap_filled, max_gap));
+        }
+    }
+    None
+}
+
+fn extract_extended_info(reason: &str) -> Option<(String, u8)> {
+    // Extract: Extended charge sequence for EEPROM protection (min 3 blocks)
+    if let Some(extended_start) = reason.find("Extended ")
+        && let Some(sequence_pos) = reason[extended_start + 9..].find(" sequence")
+    {
+        let mode = reason[extended_start + 9..extended_start + 9 + sequence_pos].to_string();
+
+        if let Some(min_start) = reason.find("(min ")
+            && let Some(blocks_pos) = reason[min_start + 5..].find(" blocks")
+        {
+            let min_blocks = reason[min_start + 5..min_start + 5 + blocks_pos]
+                .parse::<u8>()
+                .ok()?;
+            return Some((mode, min_blocks));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_i18n::Language;
+
+    #[test]
+    fn test_parse_legacy_reasons() {
+        assert_eq!(
+            DecisionReason::parse("Time-Aware Charge - Cheapest block (2.50 CZK/kWh)"),
+            Some(DecisionReason::TimeAwareCharge { price: 2.5 })
+        );
+        assert_eq!(DecisionReason::parse("Something new"), None);
+        assert_eq!(
+            DecisionReason::from_string("Something new").kind(),
+            "custom"
+        );
+    }
+
+    #[test]
+    fn test_serde_tag_and_localize() {
+        let reason = DecisionReason::ExportCap { cap_w: 0 };
+        let json = serde_json::to_value(&reason).unwrap();
+        assert_eq!(json["type"], "ExportCap");
+        assert_eq!(
+            serde_json::from_value::<DecisionReason>(json).unwrap(),
+            reason
+        );
+
+        let i18n = I18n::new(Language::Czech).unwrap();
+        let localized = DecisionReason::CheapestBlock { price: 1.5 }.localize(&i18n, "Kč");
+        assert!(localized.contains("1.50"), "{localized}");
+        assert_ne!(
+            localized,
+            DecisionReason::CheapestBlock { price: 1.5 }.to_display_string()
+        );
+
+        // Legacy strategy reasons have no translation and keep their English text
+        let legacy = DecisionReason::TimeAwareCharge { price: 2.0 };
+        assert_eq!(legacy.translate(&i18n, "Kč"), None);
+        assert_eq!(legacy.localize(&i18n, "Kč"), legacy.to_display_string());
+    }
+
+    #[test]
+    fn test_override_reasons_translate() {
+        use InverterOperationMode::{ForceCharge, ForceDischarge};
+        let reasons = [
+            DecisionReason::UserOverride {
+                slot_id: "a".to_owned(),
+                note: None,
+            },
+            DecisionReason::UserOverride {
+                slot_id: "a".to_owned(),
+                note: Some("Guests".to_owned()),
+            },
+            DecisionReason::BeyondHorizon { mode: ForceCharge },
+            DecisionReason::BackupReserve {
+                from: ForceDischarge,
+                min_soc: 80.0,
+            },
+            DecisionReason::UserRestriction { from: ForceCharge },
+            DecisionReason::SpecialDay {
+                name: "Christmas".to_owned(),
+            },
+            DecisionReason::WeatherReserve { min_soc: 40.0 },
+            DecisionReason::ClearDay {
+                cloud_cover_pct: 10.0,
+            },
+            DecisionReason::ExportCap { cap_w: 0 },
+            DecisionReason::ShortSequence {
+                from: ForceCharge,
+                blocks: 1,
+                min_blocks: 2,
+            },
+            DecisionReason::ScheduleGuard {
+                from: ForceCharge,
+                issue: "battery full".to_owned(),
+            },
+        ];
+        for language in [Language::English, Language::Czech] {
+            let i18n = I18n::new(language).unwrap();
+            for reason in &reasons {
+                assert!(
+                    reason.translate(&i18n, "CZK").is_some(),
+                    "{language:?}: {reason:?}"
+                );
+            }
+        }
+    }
+}
@@ -34,21 +34,49 @@ pub struct ScheduledMode {
     /// Operation mode for this block
     pub mode: InverterOperationMode,
 
-    /// Human-readable reason for this mode
     /// Human-readable reason for this mode
     pub reason: String,
 
+    /// Structured reason for this mode, for localization and filtering
+    /// None when the deciding strategy only gave a free-form reason
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decision_reason: Option<crate::reason::DecisionReason>,
+
     /// Unique identifier for the decision logic that chose this mode
     /// Format: "strategy_name:decision_point" (e.g., "winter_adaptive_v2:negative_price")
     /// Only available via /export endpoint for debugging
     #[serde(skip_serializing_if = "Option::is_none")]
     pub decision_uid: Option<String>,
 
+    /// Charge power for a partial-power force-charge block (kW)
+    /// None = the inverter's maximum charge rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charge_power_kw: Option<f32>,
+
+    /// Planned battery SOC at the end of this block (%)
+    /// The executor corrects drift from this trajectory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_soc: Option<f32>,
+
+    /// Solar and consumption forecast the decision was based on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forecast: Option<BlockForecast>,
+
     /// Debug info captured during scheduling
     #[serde(skip_serializing_if = "Option::is_none")]
     pub debug_info: Option<BlockDebugInfo>,
 }
 
+/// Energy forecast for one block, as seen by the strategy at decision time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BlockForecast {
+    /// Forecast solar generation (kWh)
+    pub solar_kwh: f32,
+
+    /// Forecast household consumption (kWh)
+    pub consumption_kwh: f32,
+}
+
 /// Debug information about strategy evaluation for a block
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StrategyEvaluation {
@@ -63,6 +91,10 @@ pub struct StrategyEvaluation {
 
     /// Detailed reasoning for this strategy's decision
     pub reason: String,
+
+    /// Why this option lost to the winning decision (None for the winner)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejection_reason: Option<String>,
 }
 
 /// Debug information captured during block scheduling
@@ -89,6 +121,15 @@ pub struct OperationSchedule {
 
     /// What price data version this schedule is based on
     pub based_on_price_version: DateTime<Utc>,
+
+    /// Battery SOC at `generated_at`, where the planned SOC trajectory starts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_soc: Option<f32>,
+
+    /// Per-inverter deviations from `scheduled_blocks` in coordinated
+    /// multi-inverter plans, each limited to its `target_inverters`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inverter_blocks: Vec<ScheduledMode>,
 }
 
 impl Default for OperationSchedule {
@@ -97,6 +138,8 @@ impl Default for OperationSchedule {
             scheduled_blocks: Vec::new(),
             generated_at: Utc::now(),
             based_on_price_version: Utc::now(),
+            initial_soc: None,
+            inverter_blocks: Vec::new(),
         }
     }
 }
@@ -120,6 +163,29 @@ impl OperationSchedule {
         })
     }
 
+    /// Get the scheduled mode for one inverter at the current time
+    ///
+    /// A per-inverter block takes precedence over the shared plan.
+    pub fn get_current_mode_for_inverter(
+        &self,
+        now: DateTime<Utc>,
+        inverter_id: &str,
+    ) -> Option<&ScheduledMode> {
+        self.inverter_blocks
+            .iter()
+            .find(|block| {
+                let block_end =
+                    block.block_start + chrono::Duration::minutes(block.duration_minutes as i64);
+                now >= block.block_start
+                    && now < block_end
+                    && block
+                        .target_inverters
+                        .as_ref()
+                        .is_some_and(|ids| ids.iter().any(|id| id == inverter_id))
+            })
+            .or_else(|| self.get_current_mode(now))
+    }
+
     /// Check if schedule needs regeneration based on price data version
     pub fn needs_regeneration(&self, price_data_version: DateTime<Utc>) -> bool {
         self.based_on_price_version != price_data_version
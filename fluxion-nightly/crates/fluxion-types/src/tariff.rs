@@ -0,0 +1,225 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! HDO grid tariff schedule.
+//!
+//! Czech distribution rates with two tariffs switch between the low and the
+//! high grid fee by an HDO (ripple control) signal. The low tariff windows come
+//! from a Home Assistant sensor with the distributor's schedule, from weekly
+//! windows in the config, or from a rate preset. They are resolved into a
+//! [`TariffSchedule`] of absolute periods that pricing, the strategies and the
+//! chart read the tariff of a block from.
+
+use std::fmt;
+
+use chrono::{DateTime, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Grid tariff in effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tariff {
+    Low,
+    High,
+}
+
+impl fmt::Display for Tariff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tariff::Low => write!(f, "low"),
+            Tariff::High => write!(f, "high"),
+        }
+    }
+}
+
+/// Where the low tariff windows come from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HdoSource {
+    /// Schedule published by the distributor, read from `hdo_sensor_entity`
+    #[default]
+    Sensor,
+    /// Weekly windows from `hdo_windows`
+    Manual,
+    /// Typical windows of the `hdo_preset` rate
+    Preset,
+}
+
+/// Distribution rate with typical low tariff windows
+///
+/// The exact times depend on the distributor and the HDO code of the
+/// installation; use the sensor or manual windows when they are known.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HdoPreset {
+    /// Heat pump rate, 20 hours of low tariff a day
+    #[default]
+    D57d,
+    /// Storage heating rate, 8 hours of low tariff a day
+    D25d,
+}
+
+impl HdoPreset {
+    /// Typical low tariff windows of the rate, the same every day
+    pub fn windows(self) -> Vec<TariffWindow> {
+        let ranges: &[(u32, u32)] = match self {
+            // Four one-hour high tariff blocks spread over the day
+            HdoPreset::D57d => &[(0, 8), (9, 12), (13, 16), (17, 19), (20, 24)],
+            HdoPreset::D25d => &[(0, 6), (13, 15)],
+        };
+        ranges
+            .iter()
+            .map(|&(start, end)| TariffWindow {
+                days: Vec::new(),
+                start: NaiveTime::from_hms_opt(start, 0, 0).unwrap_or(NaiveTime::MIN),
+                end: NaiveTime::from_hms_opt(end % 24, 0, 0).unwrap_or(NaiveTime::MIN),
+            })
+            .collect()
+    }
+}
+
+/// Low tariff window repeating every week, in local time
+///
+/// An end at or before the start (e.g. `22:00`-`06:00`, or `00:00` for
+/// midnight) ends on the next day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TariffWindow {
+    /// Days the window starts on, every day when empty
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TariffWindow {
+    /// Whether the window starts on `weekday`
+    pub fn applies_on(&self, weekday: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&weekday)
+    }
+
+    /// Whether the window continues past midnight
+    pub fn wraps_midnight(&self) -> bool {
+        self.end <= self.start
+    }
+}
+
+/// Span of time with a single tariff
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TariffPeriod {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl TariffPeriod {
+    /// Whether `at` falls within the period
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start <= at && at < self.end
+    }
+}
+
+/// Low tariff periods over the days the tariff is known for
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TariffSchedule {
+    pub source: HdoSource,
+    /// Local days covered by the schedule, sorted
+    pub days: Vec<TariffPeriod>,
+    /// Low tariff periods, sorted and not overlapping
+    pub low_periods: Vec<TariffPeriod>,
+}
+
+impl TariffSchedule {
+    /// Tariff in effect at `at`, `None` outside the covered days
+    pub fn tariff_at(&self, at: DateTime<Utc>) -> Option<Tariff> {
+        if !self.days.iter().any(|day| day.contains(at)) {
+            return None;
+        }
+        if self.low_periods.iter().any(|period| period.contains(at)) {
+            Some(Tariff::Low)
+        } else {
+            Some(Tariff::High)
+        }
+    }
+
+    /// Whether the schedule has no days
+    pub fn is_empty(&self) -> bool {
+        self.days.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_presets_cover_rate_hours() {
+        let low_hours = |preset: HdoPreset| -> i64 {
+            preset
+                .windows()
+                .iter()
+                .map(|window| {
+                    let minutes = (window.end - window.start).num_minutes();
+                    if window.wraps_midnight() {
+                        minutes + 24 * 60
+                    } else {
+                        minutes
+                    }
+                })
+                .sum::<i64>()
+                / 60
+        };
+
+        assert_eq!(low_hours(HdoPreset::D57d), 20);
+        assert_eq!(low_hours(HdoPreset::D25d), 8);
+    }
+
+    #[test]
+    fn test_window_days_and_midnight() {
+        let window = TariffWindow {
+            days: vec![Weekday::Sat, Weekday::Sun],
+            start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+        };
+
+        assert!(window.applies_on(Weekday::Sun));
+        assert!(!window.applies_on(Weekday::Mon));
+        assert!(window.wraps_midnight());
+    }
+
+    #[test]
+    fn test_tariff_at() {
+        let schedule = TariffSchedule {
+            source: HdoSource::Manual,
+            days: vec![TariffPeriod {
+                start: at("2025-01-10T00:00:00Z"),
+                end: at("2025-01-11T00:00:00Z"),
+            }],
+            low_periods: vec![TariffPeriod {
+                start: at("2025-01-10T02:00:00Z"),
+                end: at("2025-01-10T06:00:00Z"),
+            }],
+        };
+
+        assert_eq!(
+            schedule.tariff_at(at("2025-01-10T03:00:00Z")),
+            Some(Tariff::Low)
+        );
+        assert_eq!(
+            schedule.tariff_at(at("2025-01-10T06:00:00Z")),
+            Some(Tariff::High)
+        );
+        assert_eq!(schedule.tariff_at(at("2025-01-11T03:00:00Z")), None);
+    }
+}
@@ -16,10 +16,16 @@
 //! - Enabling/disabling FluxION mode changes
 //! - Disallowing specific modes (charge/discharge)
 //! - Fixed time slots that override the generated schedule
+//! - Archive of deleted fixed slots, so deletions are auditable and reversible
+//! - Emergency safe state that suspends scheduling until manually resumed
+//! - Vacation ranges planned like holidays
+//! - Temporary backup reserve (raised minimum SOC) ahead of storms or planned outages
+//! - Validation of conflicting inputs with a fixed precedence order
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::calendar::VacationRange;
 use crate::inverter::InverterOperationMode;
 
 /// User control state - persisted to ./data/user_control.json
@@ -45,8 +51,36 @@ pub struct UserControlState {
     /// When user control state was last modified.
     #[serde(default)]
     pub last_modified: Option<DateTime<Utc>>,
+
+    /// Active emergency safe state, if any.
+    /// While set, the scheduler is suspended and inverters are held in the safe mode.
+    #[serde(default)]
+    pub safe_state: Option<SafeStateActivation>,
+
+    /// Deleted fixed slots, newest first (at most [`MAX_ARCHIVED_SLOTS`]).
+    #[serde(default)]
+    pub archived_slots: Vec<ArchivedTimeSlot>,
+
+    /// Vacations, planned like holidays (weekend consumption, no forced discharge).
+    #[serde(default)]
+    pub vacations: Vec<VacationRange>,
+
+    /// One-off block pins from the dashboard, starting within the next day.
+    /// They win over fixed slots and are dropped once their block has passed.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_blocks: Vec<FixedTimeSlot>,
+
+    /// Temporarily raised minimum SOC, dropped once it ends.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_reserve: Option<BackupReserve>,
 }
 
+/// How far ahead a block can be pinned.
+pub const MAX_PIN_AHEAD_HOURS: i64 = 24;
+
+/// Maximum number of deleted slots kept in the archive.
+pub const MAX_ARCHIVED_SLOTS: usize = 100;
+
 fn default_enabled() -> bool {
     true
 }
@@ -59,6 +93,11 @@ impl Default for UserControlState {
             disallow_discharge: false,
             fixed_time_slots: Vec::new(),
             last_modified: None,
+            safe_state: None,
+            archived_slots: Vec::new(),
+            vacations: Vec::new(),
+            pinned_blocks: Vec::new(),
+            backup_reserve: None,
         }
     }
 }
@@ -68,11 +107,47 @@ impl UserControlState {
     pub fn cleanup_expired_slots(&mut self) {
         let now = Utc::now();
         self.fixed_time_slots.retain(|slot| !slot.has_passed(now));
+        self.pinned_blocks.retain(|pin| !pin.has_passed(now));
+        if self
+            .backup_reserve
+            .as_ref()
+            .is_some_and(|reserve| reserve.has_passed(now))
+        {
+            self.backup_reserve = None;
+        }
+    }
+
+    /// Minimum SOC required by the backup reserve at `time`, if one covers it.
+    pub fn backup_reserve_soc_at(&self, time: DateTime<Utc>) -> Option<f32> {
+        self.backup_reserve
+            .as_ref()
+            .filter(|reserve| reserve.covers(time))
+            .map(|reserve| reserve.min_soc)
     }
 
-    /// Get the fixed slot covering a specific time, if any.
+    /// Get the pinned block or fixed slot covering a specific time, if any.
+    ///
+    /// A pin wins over a fixed slot covering the same time.
     pub fn get_fixed_slot_at(&self, time: DateTime<Utc>) -> Option<&FixedTimeSlot> {
-        self.fixed_time_slots.iter().find(|slot| slot.covers(time))
+        self.pinned_blocks
+            .iter()
+            .chain(&self.fixed_time_slots)
+            .find(|slot| slot.covers(time))
+    }
+
+    /// Add a pin, replacing pins that overlap it.
+    pub fn pin_block(&mut self, pin: FixedTimeSlot) {
+        self.pinned_blocks
+            .retain(|other| other.to <= pin.from || pin.to <= other.from);
+        self.pinned_blocks.push(pin);
+        self.pinned_blocks.sort_by_key(|other| other.from);
+    }
+
+    /// Remove a pin. Returns `false` if no pin has the given ID.
+    pub fn unpin_block(&mut self, id: &str) -> bool {
+        let before = self.pinned_blocks.len();
+        self.pinned_blocks.retain(|pin| pin.id != id);
+        self.pinned_blocks.len() != before
     }
 
     /// Check if a mode is allowed given current restrictions.
@@ -84,11 +159,88 @@ impl UserControlState {
         }
     }
 
+    /// Mode to run at `time` when the schedule plans `planned`.
+    ///
+    /// Used to replay user control in simulations: a disabled FluxION leaves
+    /// the inverter in SelfUse, a fixed slot wins over the plan, and a
+    /// disallowed force mode falls back to `fallback`.
+    pub fn mode_at(
+        &self,
+        time: DateTime<Utc>,
+        planned: InverterOperationMode,
+        fallback: InverterOperationMode,
+    ) -> InverterOperationMode {
+        if !self.enabled {
+            return InverterOperationMode::SelfUse;
+        }
+        if let Some(slot) = self.get_fixed_slot_at(time) {
+            return slot.mode;
+        }
+        if self.is_mode_allowed(planned) {
+            planned
+        } else {
+            fallback
+        }
+    }
+
     /// Check if there are any active restrictions.
     pub fn has_restrictions(&self) -> bool {
         self.disallow_charge || self.disallow_discharge
     }
 
+    /// Check if the emergency safe state is engaged.
+    pub fn is_safe_state_active(&self) -> bool {
+        self.safe_state.is_some()
+    }
+
+    /// Move a fixed slot to the archive.
+    ///
+    /// Returns `None` if no fixed slot has the given ID.
+    pub fn archive_slot(
+        &mut self,
+        id: &str,
+        deleted_by: impl Into<String>,
+    ) -> Option<&ArchivedTimeSlot> {
+        let index = self.fixed_time_slots.iter().position(|s| s.id == id)?;
+        let slot = self.fixed_time_slots.remove(index);
+        self.archived_slots.insert(
+            0,
+            ArchivedTimeSlot {
+                slot,
+                deleted_by: deleted_by.into(),
+                deleted_at: Utc::now(),
+            },
+        );
+        self.archived_slots.truncate(MAX_ARCHIVED_SLOTS);
+        self.archived_slots.first()
+    }
+
+    /// Move an archived slot back to the fixed slots.
+    ///
+    /// Returns `None` if no archived slot has the given ID.
+    pub fn restore_slot(&mut self, id: &str) -> Option<&FixedTimeSlot> {
+        let index = self.archived_slots.iter().position(|s| s.slot.id == id)?;
+        let archived = self.archived_slots.remove(index);
+        self.fixed_time_slots.push(archived.slot);
+        self.fixed_time_slots.last()
+    }
+
+    /// Replace all fixed slots, archiving active slots missing from `slots`.
+    pub fn replace_slots(&mut self, slots: Vec<FixedTimeSlot>, deleted_by: &str) {
+        let now = Utc::now();
+        let removed: Vec<String> = self
+            .fixed_time_slots
+            .iter()
+            .filter(|old| !old.has_passed(now))
+            .filter(|old| !slots.iter().any(|slot| slot.id == old.id))
+            .map(|old| old.id.clone())
+            .collect();
+        for id in removed {
+            self.archive_slot(&id, deleted_by);
+        }
+        self.fixed_time_slots = slots;
+    }
+
     /// Get the number of active (non-expired) fixed time slots.
     pub fn active_slot_count(&self) -> usize {
         let now = Utc::now();
@@ -99,6 +251,363 @@ impl UserControlState {
     }
 }
 
+/// Order in which user control inputs are applied, highest precedence first.
+///
+/// Returned by the user control API so clients can explain why an input has no effect.
+pub const CONTROL_PRECEDENCE: [&str; 7] = [
+    "safe_state: holds inverters in the safe mode and suspends scheduling",
+    "enabled: when false, FluxION stops sending mode commands",
+    "pinned_blocks: a one-off pin replaces fixed slots and the strategy decision for its blocks",
+    "fixed_time_slots: a locked slot replaces the strategy decision for its time range",
+    "restrictions: disallowed charge/discharge decisions fall back to the default mode",
+    "backup_reserve: below the reserve SOC the battery charges, at the reserve it is held",
+    "strategy: the generated schedule",
+];
+
+/// Kind of problem found in a user control state.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UserControlIssueKind {
+    /// Slot end is not after its start.
+    InvalidSlotRange,
+    /// A new slot ends in the past and would never apply.
+    SlotInPast,
+    /// Two slots cover the same time, so only one of them could apply.
+    OverlappingSlots,
+    /// Slot mode is blocked by a charge/discharge restriction.
+    SlotModeDisallowed,
+    /// Slots exist but FluxION is disabled, so they are not executed.
+    SlotsInactiveWhileDisabled,
+    /// Slots exist but the safe state overrides them.
+    SlotsInactiveDuringSafeState,
+    /// Vacation ends before it starts.
+    InvalidVacationRange,
+    /// Backup reserve SOC is out of range or its window is empty or already over.
+    InvalidBackupReserve,
+    /// Backup reserve is set while charging is disallowed, so it can't be charged from the grid.
+    BackupReserveChargeDisallowed,
+}
+
+impl UserControlIssueKind {
+    /// Errors reject a change; everything else is reported as a warning.
+    pub fn is_error(self) -> bool {
+        matches!(
+            self,
+            Self::InvalidSlotRange
+                | Self::SlotInPast
+                | Self::OverlappingSlots
+                | Self::SlotModeDisallowed
+                | Self::InvalidVacationRange
+                | Self::InvalidBackupReserve
+        )
+    }
+}
+
+/// A single conflict or warning found by [`UserControlState::validate`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UserControlIssue {
+    pub kind: UserControlIssueKind,
+    pub message: String,
+    /// Slots involved in the issue, if any.
+    #[serde(default)]
+    pub slot_ids: Vec<String>,
+}
+
+/// Result of validating a user control state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UserControlValidation {
+    pub errors: Vec<UserControlIssue>,
+    pub warnings: Vec<UserControlIssue>,
+}
+
+impl UserControlValidation {
+    fn push(&mut self, kind: UserControlIssueKind, message: String, slot_ids: Vec<String>) {
+        let issue = UserControlIssue {
+            kind,
+            message,
+            slot_ids,
+        };
+        if kind.is_error() {
+            self.errors.push(issue);
+        } else {
+            self.warnings.push(issue);
+        }
+    }
+}
+
+impl UserControlState {
+    /// Check the state for contradicting inputs.
+    ///
+    /// Expired slots are ignored since they no longer affect the schedule.
+    pub fn validate(&self, now: DateTime<Utc>) -> UserControlValidation {
+        let mut result = UserControlValidation::default();
+        let slots: Vec<&FixedTimeSlot> = self
+            .fixed_time_slots
+            .iter()
+            .filter(|slot| !slot.has_passed(now))
+            .collect();
+        let pins: Vec<&FixedTimeSlot> = self
+            .pinned_blocks
+            .iter()
+            .filter(|pin| !pin.has_passed(now))
+            .collect();
+
+        for slot in slots.iter().chain(&pins) {
+            if slot.from >= slot.to {
+                result.push(
+                    UserControlIssueKind::InvalidSlotRange,
+                    format!("Slot {} must end after it starts", slot.id),
+                    vec![slot.id.clone()],
+                );
+            }
+
+            if !self.is_mode_allowed(slot.mode) {
+                let restriction = if slot.mode == InverterOperationMode::ForceCharge {
+                    "charging is disallowed"
+                } else {
+                    "discharging is disallowed"
+                };
+                result.push(
+                    UserControlIssueKind::SlotModeDisallowed,
+                    format!(
+                        "Slot {} uses {:?} but {restriction}; remove the slot or lift the restriction",
+                        slot.id, slot.mode
+                    ),
+                    vec![slot.id.clone()],
+                );
+            }
+        }
+
+        for (i, a) in slots.iter().enumerate() {
+            for b in &slots[i + 1..] {
+                if a.from < b.to && b.from < a.to {
+                    result.push(
+                        UserControlIssueKind::OverlappingSlots,
+                        format!(
+                            "Slots {} and {} overlap between {} and {}",
+                            a.id,
+                            b.id,
+                            a.from.max(b.from).format("%Y-%m-%d %H:%M UTC"),
+                            a.to.min(b.to).format("%Y-%m-%d %H:%M UTC")
+                        ),
+                        vec![a.id.clone(), b.id.clone()],
+                    );
+                }
+            }
+        }
+
+        for vacation in &self.vacations {
+            if vacation.end < vacation.start {
+                result.push(
+                    UserControlIssueKind::InvalidVacationRange,
+                    format!("Vacation {} must not end before it starts", vacation.id),
+                    Vec::new(),
+                );
+            }
+        }
+
+        if let Some(reserve) = &self.backup_reserve
+            && !reserve.has_passed(now)
+        {
+            if !(0.0..=100.0).contains(&reserve.min_soc) {
+                result.push(
+                    UserControlIssueKind::InvalidBackupReserve,
+                    format!(
+                        "Backup reserve of {}% must be between 0% and 100%",
+                        reserve.min_soc
+                    ),
+                    Vec::new(),
+                );
+            }
+            if reserve.from >= reserve.until {
+                result.push(
+                    UserControlIssueKind::InvalidBackupReserve,
+                    "Backup reserve must end after it starts".to_owned(),
+                    Vec::new(),
+                );
+            }
+            if self.disallow_charge {
+                result.push(
+                    UserControlIssueKind::BackupReserveChargeDisallowed,
+                    "Charging is disallowed, so the backup reserve is only held, not charged up to"
+                        .to_owned(),
+                    Vec::new(),
+                );
+            }
+        }
+
+        if !slots.is_empty() {
+            let slot_ids: Vec<String> = slots.iter().map(|slot| slot.id.clone()).collect();
+            if self.is_safe_state_active() {
+                result.push(
+                    UserControlIssueKind::SlotsInactiveDuringSafeState,
+                    "Fixed slots are not applied while the safe state is active".to_owned(),
+                    slot_ids,
+                );
+            } else if !self.enabled {
+                result.push(
+                    UserControlIssueKind::SlotsInactiveWhileDisabled,
+                    "Fixed slots are not executed while FluxION is disabled".to_owned(),
+                    slot_ids,
+                );
+            }
+        }
+
+        result
+    }
+
+    /// Validate a change from `self` to `new_state`.
+    ///
+    /// Only errors introduced by the change are reported, so a state that was already
+    /// inconsistent (e.g. persisted by an older version) can still be edited and cleaned up.
+    pub fn validate_change(
+        &self,
+        new_state: &UserControlState,
+        now: DateTime<Utc>,
+    ) -> UserControlValidation {
+        let existing = self.validate(now).errors;
+        let mut result = new_state.validate(now);
+        result.errors.retain(|issue| !existing.contains(issue));
+
+        for slot in new_state
+            .fixed_time_slots
+            .iter()
+            .chain(&new_state.pinned_blocks)
+        {
+            let is_new_or_changed =
+                !self.fixed_time_slots.contains(slot) && !self.pinned_blocks.contains(slot);
+            if is_new_or_changed && slot.has_passed(now) {
+                result.push(
+                    UserControlIssueKind::SlotInPast,
+                    format!("Slot {} ends in the past and would never apply", slot.id),
+                    vec![slot.id.clone()],
+                );
+            }
+        }
+
+        if let Some(reserve) = &new_state.backup_reserve
+            && self.backup_reserve.as_ref() != Some(reserve)
+            && reserve.has_passed(now)
+        {
+            result.push(
+                UserControlIssueKind::InvalidBackupReserve,
+                "Backup reserve ends in the past and would never apply".to_owned(),
+                Vec::new(),
+            );
+        }
+
+        result
+    }
+}
+
+/// Record of an emergency safe state activation.
+///
+/// Persisted together with the rest of the user control state so the safe state
+/// survives restarts and is only cleared by an explicit resume.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SafeStateActivation {
+    /// Mode requested by the caller. `None` means the configured safe mode.
+    #[serde(default)]
+    pub mode: Option<InverterOperationMode>,
+
+    /// Who triggered the safe state (user name, device or API client).
+    pub triggered_by: String,
+
+    /// When the safe state was triggered.
+    pub triggered_at: DateTime<Utc>,
+
+    /// Optional reason given by the caller (e.g., "electrician working on DB board").
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl SafeStateActivation {
+    /// Create a new activation record stamped with the current time.
+    pub fn new(
+        triggered_by: impl Into<String>,
+        mode: Option<InverterOperationMode>,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            mode,
+            triggered_by: triggered_by.into(),
+            triggered_at: Utc::now(),
+            reason,
+        }
+    }
+
+    /// Resolve the mode to hold inverters in, falling back to the configured safe mode.
+    pub fn effective_mode(&self, configured: InverterOperationMode) -> InverterOperationMode {
+        self.mode.unwrap_or(configured)
+    }
+}
+
+/// Temporarily raised minimum SOC, e.g. ahead of a storm warning or a planned grid outage.
+///
+/// Unlike the inverter's backup discharge minimum, it only applies between `from` and
+/// `until`: the scheduler charges the battery up to `min_soc` and holds it there instead
+/// of discharging, then plans normally again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupReserve {
+    /// Minimum SOC to keep (%).
+    pub min_soc: f32,
+
+    /// Start of the reserve window.
+    pub from: DateTime<Utc>,
+
+    /// End of the reserve window.
+    pub until: DateTime<Utc>,
+
+    /// Optional reason (e.g., "storm warning", "grid maintenance 8:00-14:00").
+    #[serde(default)]
+    pub reason: Option<String>,
+
+    /// When the reserve was set.
+    pub created_at: DateTime<Utc>,
+}
+
+impl BackupReserve {
+    /// Create a new backup reserve stamped with the current time.
+    pub fn new(
+        min_soc: f32,
+        from: DateTime<Utc>,
+        until: DateTime<Utc>,
+        reason: Option<String>,
+    ) -> Self {
+        Self {
+            min_soc,
+            from,
+            until,
+            reason,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Check if the reserve applies at a specific time.
+    pub fn covers(&self, time: DateTime<Utc>) -> bool {
+        time >= self.from && time < self.until
+    }
+
+    /// Check if the reserve window is over.
+    pub fn has_passed(&self, now: DateTime<Utc>) -> bool {
+        now >= self.until
+    }
+}
+
+/// A deleted fixed time slot, kept for auditing and restore.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ArchivedTimeSlot {
+    /// The slot as it was when deleted.
+    #[serde(flatten)]
+    pub slot: FixedTimeSlot,
+
+    /// Who deleted the slot (user name, device or API client).
+    pub deleted_by: String,
+
+    /// When the slot was deleted.
+    pub deleted_at: DateTime<Utc>,
+}
+
 /// A user-defined fixed time slot that overrides the generated schedule.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct FixedTimeSlot {
@@ -145,6 +654,19 @@ impl FixedTimeSlot {
         }
     }
 
+    /// Create a one-off pin for the blocks in `from..to`.
+    pub fn pin(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        mode: InverterOperationMode,
+        note: Option<String>,
+    ) -> Self {
+        Self {
+            id: format!("pin_{}", Utc::now().timestamp_millis()),
+            ..Self::new(from, to, mode, note)
+        }
+    }
+
     /// Check if this slot is currently active (now is within from..to).
     pub fn is_active(&self, now: DateTime<Utc>) -> bool {
         now >= self.from && now < self.to
@@ -178,6 +700,23 @@ mod tests {
         assert!(!state.disallow_charge);
         assert!(!state.disallow_discharge);
         assert!(state.fixed_time_slots.is_empty());
+        assert!(!state.is_safe_state_active());
+    }
+
+    #[test]
+    fn test_safe_state_effective_mode() {
+        let activation = SafeStateActivation::new("tester", None, None);
+        assert_eq!(
+            activation.effective_mode(InverterOperationMode::NoChargeNoDischarge),
+            InverterOperationMode::NoChargeNoDischarge
+        );
+
+        let activation =
+            SafeStateActivation::new("tester", Some(InverterOperationMode::SelfUse), None);
+        assert_eq!(
+            activation.effective_mode(InverterOperationMode::NoChargeNoDischarge),
+            InverterOperationMode::SelfUse
+        );
     }
 
     #[test]
@@ -203,6 +742,45 @@ mod tests {
         assert!(state.is_mode_allowed(InverterOperationMode::SelfUse));
     }
 
+    #[test]
+    fn test_mode_at() {
+        let now = Utc::now();
+        let mut state = UserControlState {
+            disallow_charge: true,
+            ..Default::default()
+        };
+        state.fixed_time_slots.push(FixedTimeSlot::new(
+            now,
+            now + Duration::hours(1),
+            InverterOperationMode::ForceDischarge,
+            None,
+        ));
+        let fallback = InverterOperationMode::NoChargeNoDischarge;
+
+        // A fixed slot wins over the plan
+        assert_eq!(
+            state.mode_at(now, InverterOperationMode::SelfUse, fallback),
+            InverterOperationMode::ForceDischarge
+        );
+        // Outside it a disallowed mode falls back
+        let later = now + Duration::hours(2);
+        assert_eq!(
+            state.mode_at(later, InverterOperationMode::ForceCharge, fallback),
+            fallback
+        );
+        assert_eq!(
+            state.mode_at(later, InverterOperationMode::ForceDischarge, fallback),
+            InverterOperationMode::ForceDischarge
+        );
+
+        // Disabled FluxION leaves the inverter in SelfUse
+        state.enabled = false;
+        assert_eq!(
+            state.mode_at(now, InverterOperationMode::ForceDischarge, fallback),
+            InverterOperationMode::SelfUse
+        );
+    }
+
     #[test]
     fn test_fixed_slot_covers() {
         let now = Utc::now();
@@ -249,6 +827,93 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_validate_overlapping_slots() {
+        let now = Utc::now();
+        let mut state = UserControlState::default();
+        state.fixed_time_slots.push(FixedTimeSlot::new(
+            now,
+            now + Duration::hours(2),
+            InverterOperationMode::ForceCharge,
+            None,
+        ));
+        assert!(state.validate(now).errors.is_empty());
+
+        let mut overlapping = FixedTimeSlot::new(
+            now + Duration::hours(1),
+            now + Duration::hours(3),
+            InverterOperationMode::SelfUse,
+            None,
+        );
+        overlapping.id = "slot_other".to_owned();
+        state.fixed_time_slots.push(overlapping);
+
+        let errors = state.validate(now).errors;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, UserControlIssueKind::OverlappingSlots);
+        assert_eq!(errors[0].slot_ids.len(), 2);
+    }
+
+    #[test]
+    fn test_validate_change_rejects_disallowed_slot_mode() {
+        let now = Utc::now();
+        let old = UserControlState::default();
+        let mut new = old.clone();
+        new.fixed_time_slots.push(FixedTimeSlot::new(
+            now,
+            now + Duration::hours(1),
+            InverterOperationMode::ForceCharge,
+            None,
+        ));
+        assert!(old.validate_change(&new, now).errors.is_empty());
+
+        // Restricting charging afterwards conflicts with the existing slot
+        let mut restricted = new.clone();
+        restricted.disallow_charge = true;
+        let errors = new.validate_change(&restricted, now).errors;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, UserControlIssueKind::SlotModeDisallowed);
+
+        // Already inconsistent state can still be edited without re-reporting the conflict
+        let mut edited = restricted.clone();
+        edited.enabled = false;
+        let result = restricted.validate_change(&edited, now);
+        assert!(result.errors.is_empty());
+        assert_eq!(
+            result.warnings[0].kind,
+            UserControlIssueKind::SlotsInactiveWhileDisabled
+        );
+    }
+
+    #[test]
+    fn test_validate_change_rejects_past_slot() {
+        let now = Utc::now();
+        let old = UserControlState::default();
+        let mut new = old.clone();
+        new.fixed_time_slots.push(FixedTimeSlot::new(
+            now - Duration::hours(2),
+            now - Duration::hours(1),
+            InverterOperationMode::SelfUse,
+            None,
+        ));
+        let errors = old.validate_change(&new, now).errors;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, UserControlIssueKind::SlotInPast);
+    }
+
+    #[test]
+    fn test_validate_change_rejects_inverted_vacation() {
+        let now = Utc::now();
+        let today = now.date_naive();
+        let old = UserControlState::default();
+        let mut new = old.clone();
+        new.vacations
+            .push(VacationRange::new(today, today - Duration::days(1), None));
+        let errors = old.validate_change(&new, now).errors;
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, UserControlIssueKind::InvalidVacationRange);
+    }
+
     #[test]
     fn test_get_fixed_slot_at() {
         let now = Utc::now();
@@ -274,4 +939,231 @@ mod tests {
         );
         assert!(state.get_fixed_slot_at(now + Duration::hours(2)).is_none());
     }
+
+    #[test]
+    fn test_archive_and_restore_slot() {
+        let now = Utc::now();
+        let mut state = UserControlState::default();
+        let slot = FixedTimeSlot::new(
+            now,
+            now + Duration::hours(1),
+            InverterOperationMode::ForceCharge,
+            Some("Cheap night".to_string()),
+        );
+        let id = slot.id.clone();
+        state.fixed_time_slots.push(slot);
+
+        let archived = state.archive_slot(&id, "alice").unwrap();
+        assert_eq!(archived.deleted_by, "alice");
+        assert!(state.fixed_time_slots.is_empty());
+        assert_eq!(state.archived_slots.len(), 1);
+        assert!(state.archive_slot(&id, "alice").is_none());
+
+        let restored = state.restore_slot(&id).unwrap();
+        assert_eq!(restored.note.as_deref(), Some("Cheap night"));
+        assert!(state.archived_slots.is_empty());
+        assert_eq!(state.fixed_time_slots.len(), 1);
+        assert!(state.restore_slot(&id).is_none());
+    }
+
+    #[test]
+    fn test_archive_is_capped_newest_first() {
+        let now = Utc::now();
+        let mut state = UserControlState::default();
+
+        for i in 0..=MAX_ARCHIVED_SLOTS {
+            let mut slot = FixedTimeSlot::new(
+                now,
+                now + Duration::hours(1),
+                InverterOperationMode::SelfUse,
+                None,
+            );
+            slot.id = format!("slot_{i}");
+            state.fixed_time_slots.push(slot);
+            state.archive_slot(&format!("slot_{i}"), "web");
+        }
+
+        assert_eq!(state.archived_slots.len(), MAX_ARCHIVED_SLOTS);
+        assert_eq!(
+            state.archived_slots[0].slot.id,
+            format!("slot_{MAX_ARCHIVED_SLOTS}")
+        );
+        assert!(state.archived_slots.iter().all(|s| s.slot.id != "slot_0"));
+    }
+
+    #[test]
+    fn test_replace_slots_archives_dropped_slots() {
+        let now = Utc::now();
+        let kept = FixedTimeSlot::new(
+            now + Duration::hours(1),
+            now + Duration::hours(2),
+            InverterOperationMode::ForceCharge,
+            None,
+        );
+        let mut dropped = FixedTimeSlot::new(
+            now + Duration::hours(3),
+            now + Duration::hours(4),
+            InverterOperationMode::SelfUse,
+            None,
+        );
+        dropped.id = "slot_dropped".to_string();
+        let mut state = UserControlState {
+            fixed_time_slots: vec![kept.clone(), dropped.clone()],
+            ..Default::default()
+        };
+
+        state.replace_slots(vec![kept.clone()], "mobile");
+
+        assert_eq!(state.fixed_time_slots.len(), 1);
+        assert_eq!(state.fixed_time_slots[0].id, kept.id);
+        assert_eq!(state.archived_slots.len(), 1);
+        assert_eq!(state.archived_slots[0].slot.id, dropped.id);
+        assert_eq!(state.archived_slots[0].deleted_by, "mobile");
+    }
+
+    #[test]
+    fn test_pins_win_over_slots_and_replace_overlapping_pins() {
+        let now = Utc::now();
+        let slot = FixedTimeSlot::new(
+            now,
+            now + Duration::hours(2),
+            InverterOperationMode::ForceCharge,
+            None,
+        );
+        let mut state = UserControlState {
+            fixed_time_slots: vec![slot.clone()],
+            ..Default::default()
+        };
+
+        let mut first = FixedTimeSlot::pin(
+            now + Duration::minutes(30),
+            now + Duration::minutes(60),
+            InverterOperationMode::SelfUse,
+            None,
+        );
+        first.id = "pin_first".to_string();
+        state.pin_block(first);
+        assert!(state.pinned_blocks[0].id.starts_with("pin_"));
+        assert_eq!(
+            state
+                .get_fixed_slot_at(now + Duration::minutes(45))
+                .map(|s| s.mode),
+            Some(InverterOperationMode::SelfUse)
+        );
+        assert_eq!(
+            state
+                .get_fixed_slot_at(now + Duration::minutes(90))
+                .map(|s| &s.id),
+            Some(&slot.id)
+        );
+
+        state.pin_block(FixedTimeSlot::pin(
+            now + Duration::minutes(45),
+            now + Duration::minutes(60),
+            InverterOperationMode::ForceDischarge,
+            None,
+        ));
+        assert_eq!(state.pinned_blocks.len(), 1);
+        assert_eq!(
+            state.pinned_blocks[0].mode,
+            InverterOperationMode::ForceDischarge
+        );
+
+        let id = state.pinned_blocks[0].id.clone();
+        assert!(state.unpin_block(&id));
+        assert!(!state.unpin_block(&id));
+    }
+
+    #[test]
+    fn test_validate_change_rejects_disallowed_pin_mode() {
+        let now = Utc::now();
+        let old = UserControlState {
+            disallow_discharge: true,
+            ..Default::default()
+        };
+        let mut new = old.clone();
+        new.pin_block(FixedTimeSlot::pin(
+            now + Duration::minutes(15),
+            now + Duration::minutes(30),
+            InverterOperationMode::ForceDischarge,
+            None,
+        ));
+
+        let result = old.validate_change(&new, now);
+
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(
+            result.errors[0].kind,
+            UserControlIssueKind::SlotModeDisallowed
+        );
+    }
+
+    #[test]
+    fn test_backup_reserve_window_and_cleanup() {
+        let now = Utc::now();
+        let mut state = UserControlState {
+            backup_reserve: Some(BackupReserve::new(
+                80.0,
+                now - Duration::hours(1),
+                now + Duration::hours(12),
+                Some("Storm warning".to_string()),
+            )),
+            ..Default::default()
+        };
+
+        assert_eq!(state.backup_reserve_soc_at(now), Some(80.0));
+        assert_eq!(state.backup_reserve_soc_at(now + Duration::hours(12)), None);
+        assert_eq!(state.backup_reserve_soc_at(now - Duration::hours(2)), None);
+
+        state.cleanup_expired_slots();
+        assert!(state.backup_reserve.is_some());
+
+        state.backup_reserve.as_mut().unwrap().until = now - Duration::minutes(1);
+        state.cleanup_expired_slots();
+        assert!(state.backup_reserve.is_none());
+    }
+
+    #[test]
+    fn test_validate_change_rejects_invalid_backup_reserve() {
+        let now = Utc::now();
+        let old = UserControlState::default();
+
+        let mut new = old.clone();
+        new.backup_reserve = Some(BackupReserve::new(
+            120.0,
+            now,
+            now + Duration::hours(12),
+            None,
+        ));
+        let result = old.validate_change(&new, now);
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(
+            result.errors[0].kind,
+            UserControlIssueKind::InvalidBackupReserve
+        );
+
+        new.backup_reserve = Some(BackupReserve::new(
+            80.0,
+            now - Duration::hours(12),
+            now - Duration::hours(1),
+            None,
+        ));
+        let result = old.validate_change(&new, now);
+        assert_eq!(result.errors.len(), 1);
+
+        // Charging disallowed: the reserve is accepted with a warning
+        new.disallow_charge = true;
+        new.backup_reserve = Some(BackupReserve::new(
+            80.0,
+            now,
+            now + Duration::hours(12),
+            None,
+        ));
+        let result = old.validate_change(&new, now);
+        assert!(result.errors.is_empty());
+        assert_eq!(
+            result.warnings[0].kind,
+            UserControlIssueKind::BackupReserveChargeDisallowed
+        );
+    }
 }
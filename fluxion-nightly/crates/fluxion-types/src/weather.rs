@@ -0,0 +1,247 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Weather forecast inputs for planning.
+//!
+//! An hourly cloud cover and temperature forecast is read from a Home Assistant
+//! weather entity or from Open-Meteo. The scheduler summarizes it per local day
+//! ([`DayWeather`]) to keep a reserve before cold cloudy days and to skip the
+//! morning pre-charge before clear days; strategy plugins receive the raw
+//! values for each block.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Longest gap after a forecast point that it still describes
+const POINT_VALIDITY: Duration = Duration::hours(3);
+
+/// Where the weather forecast is read from
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WeatherSource {
+    /// Hourly forecast of a Home Assistant `weather.*` entity
+    #[default]
+    HomeAssistant,
+    /// Open-Meteo API, no account needed
+    OpenMeteo,
+}
+
+/// Weather forecast source settings (`[weather]`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeatherConfigCore {
+    /// Fetch a weather forecast and adjust planning to it
+    pub enabled: bool,
+    pub source: WeatherSource,
+    /// Weather entity with an hourly forecast (`home_assistant` source)
+    pub entity_id: String,
+    /// Location for the `open_meteo` source
+    pub latitude: f64,
+    pub longitude: f64,
+    /// Fetch interval in seconds
+    pub fetch_interval_seconds: u64,
+}
+
+impl Default for WeatherConfigCore {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: WeatherSource::HomeAssistant,
+            entity_id: "weather.forecast_home".to_owned(),
+            latitude: 50.08,
+            longitude: 14.42,
+            fetch_interval_seconds: 3600,
+        }
+    }
+}
+
+/// How planning reacts to the weather forecast
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeatherPlanningConfig {
+    /// Days with a minimum temperature at or below this are cold (°C)
+    pub cold_threshold_c: f32,
+    /// Days with a mean cloud cover at or above this are cloudy (%)
+    pub cloudy_threshold_pct: f32,
+    /// Days with a mean cloud cover at or below this are clear (%)
+    pub clear_threshold_pct: f32,
+    /// SOC kept above the minimum on the day before a cold cloudy day (%)
+    pub cold_cloudy_reserve_soc: f32,
+    /// Skip forced charging in the morning of a clear day
+    pub skip_precharge_on_clear_day: bool,
+    /// Local hour at which the morning of a clear day ends
+    pub morning_end_hour: u32,
+}
+
+impl Default for WeatherPlanningConfig {
+    fn default() -> Self {
+        Self {
+            cold_threshold_c: 0.0,
+            cloudy_threshold_pct: 75.0,
+            clear_threshold_pct: 25.0,
+            cold_cloudy_reserve_soc: 20.0,
+            skip_precharge_on_clear_day: true,
+            morning_end_hour: 10,
+        }
+    }
+}
+
+impl WeatherPlanningConfig {
+    /// Whether `day` is cold and cloudy, with little solar and high consumption
+    pub fn is_cold_cloudy(&self, day: &DayWeather) -> bool {
+        day.min_temperature_c
+            .is_some_and(|temperature| temperature <= self.cold_threshold_c)
+            && day
+                .cloud_cover_pct
+                .is_some_and(|cover| cover >= self.cloudy_threshold_pct)
+    }
+
+    /// Whether `day` is clear enough for solar to charge the battery
+    pub fn is_clear(&self, day: &DayWeather) -> bool {
+        day.cloud_cover_pct
+            .is_some_and(|cover| cover <= self.clear_threshold_pct)
+    }
+}
+
+/// Forecast weather at one point in time
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WeatherPoint {
+    pub time: DateTime<Utc>,
+    /// Cloud cover (0-100 %)
+    pub cloud_cover_pct: Option<f32>,
+    /// Air temperature (°C)
+    pub temperature_c: Option<f32>,
+}
+
+/// Forecast summary of one local calendar day
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayWeather {
+    /// Local calendar date
+    pub date: NaiveDate,
+    /// Local midnight starting the day
+    pub start: DateTime<Utc>,
+    /// Local midnight starting the next day
+    pub end: DateTime<Utc>,
+    /// End of the morning, see [`WeatherPlanningConfig::morning_end_hour`]
+    pub morning_end: DateTime<Utc>,
+    /// Mean cloud cover during daylight hours (%)
+    pub cloud_cover_pct: Option<f32>,
+    /// Lowest forecast temperature (°C)
+    pub min_temperature_c: Option<f32>,
+}
+
+impl DayWeather {
+    /// Whether `at` falls within the day
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.start <= at && at < self.end
+    }
+}
+
+/// Weather forecast prepared for one planning run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WeatherOutlook {
+    /// Forecast points sorted by time
+    pub points: Vec<WeatherPoint>,
+    /// Consecutive local days covered by the forecast
+    pub days: Vec<DayWeather>,
+}
+
+impl WeatherOutlook {
+    /// Forecast point describing `at`, if the forecast covers it
+    pub fn point_at(&self, at: DateTime<Utc>) -> Option<&WeatherPoint> {
+        let index = self.points.partition_point(|point| point.time <= at);
+        self.points[..index]
+            .last()
+            .filter(|point| at - point.time < POINT_VALIDITY)
+    }
+
+    /// Index of the day containing `at` in [`Self::days`]
+    fn day_index(&self, at: DateTime<Utc>) -> Option<usize> {
+        self.days.iter().position(|day| day.contains(at))
+    }
+
+    /// The day containing `at`
+    pub fn day_at(&self, at: DateTime<Utc>) -> Option<&DayWeather> {
+        self.day_index(at).map(|index| &self.days[index])
+    }
+
+    /// The day after the one containing `at`
+    pub fn next_day_after(&self, at: DateTime<Utc>) -> Option<&DayWeather> {
+        self.day_index(at)
+            .and_then(|index| self.days.get(index + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, cloud_cover_pct: f32, min_temperature_c: f32) -> DayWeather {
+        let date: NaiveDate = date.parse().unwrap();
+        let start = date.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        DayWeather {
+            date,
+            start,
+            end: start + Duration::days(1),
+            morning_end: start + Duration::hours(10),
+            cloud_cover_pct: Some(cloud_cover_pct),
+            min_temperature_c: Some(min_temperature_c),
+        }
+    }
+
+    #[test]
+    fn test_days_are_classified_by_thresholds() {
+        let config = WeatherPlanningConfig::default();
+
+        assert!(config.is_cold_cloudy(&day("2025-01-10", 90.0, -3.0)));
+        assert!(!config.is_cold_cloudy(&day("2025-01-10", 90.0, 4.0)));
+        assert!(!config.is_cold_cloudy(&day("2025-01-10", 40.0, -3.0)));
+        assert!(config.is_clear(&day("2025-01-10", 10.0, -3.0)));
+        assert!(!config.is_clear(&day("2025-01-10", 40.0, -3.0)));
+    }
+
+    #[test]
+    fn test_outlook_lookups() {
+        let at = |s: &str| s.parse::<DateTime<Utc>>().unwrap();
+        let outlook = WeatherOutlook {
+            points: vec![
+                WeatherPoint {
+                    time: at("2025-01-10T06:00:00Z"),
+                    cloud_cover_pct: Some(80.0),
+                    temperature_c: Some(-2.0),
+                },
+                WeatherPoint {
+                    time: at("2025-01-10T07:00:00Z"),
+                    cloud_cover_pct: Some(60.0),
+                    temperature_c: Some(-1.0),
+                },
+            ],
+            days: vec![day("2025-01-10", 70.0, -2.0), day("2025-01-11", 20.0, 1.0)],
+        };
+
+        assert_eq!(
+            outlook
+                .point_at(at("2025-01-10T07:30:00Z"))
+                .and_then(|point| point.cloud_cover_pct),
+            Some(60.0)
+        );
+        assert_eq!(outlook.point_at(at("2025-01-10T05:59:00Z")), None);
+        assert_eq!(outlook.point_at(at("2025-01-10T12:00:00Z")), None);
+        assert_eq!(
+            outlook
+                .next_day_after(at("2025-01-10T20:00:00Z"))
+                .map(|day| day.date),
+            "2025-01-11".parse().ok()
+        );
+        assert_eq!(outlook.next_day_after(at("2025-01-11T20:00:00Z")), None);
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Alert rule management.
+
+use crate::api_keys::deny;
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, put},
+};
+use fluxion_core::alerts::{AlertManager, AlertRuleSpec};
+use tracing::{error, info};
+
+/// GET /api/alerts — rules, alerts showing on the dashboard and recent firings
+async fn list_alerts_handler(State(manager): State<AlertManager>) -> Response {
+    Json(manager.overview()).into_response()
+}
+
+/// POST /api/alerts
+async fn create_alert_handler(
+    State(manager): State<AlertManager>,
+    Json(spec): Json<AlertRuleSpec>,
+) -> Response {
+    if let Err(e) = spec.validate() {
+        return deny(StatusCode::BAD_REQUEST, &e);
+    }
+    match manager.create(spec) {
+        Ok(rule) => {
+            info!("🔔 Created alert rule '{}' ({})", rule.spec.name, rule.id);
+            (StatusCode::CREATED, Json(rule)).into_response()
+        }
+        Err(e) => {
+            error!("Failed to create alert rule: {e:#}");
+            deny(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to save alert rule",
+            )
+        }
+    }
+}
+
+/// PUT /api/alerts/{id}
+async fn update_alert_handler(
+    State(manager): State<AlertManager>,
+    Path(id): Path<String>,
+    Json(spec): Json<AlertRuleSpec>,
+) -> Response {
+    if let Err(e) = spec.validate() {
+        return deny(StatusCode::BAD_REQUEST, &e);
+    }
+    match manager.update(&id, spec) {
+        Ok(Some(rule)) => {
+            info!("🔔 Updated alert rule '{}' ({})", rule.spec.name, rule.id);
+            Json(rule).into_response()
+        }
+        Ok(None) => deny(StatusCode::NOT_FOUND, "Alert rule not found"),
+        Err(e) => {
+            error!("Failed to update alert rule: {e:#}");
+            deny(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to save alert rule",
+            )
+        }
+    }
+}
+
+/// DELETE /api/alerts/{id}
+async fn delete_alert_handler(
+    State(manager): State<AlertManager>,
+    Path(id): Path<String>,
+) -> Response {
+    match manager.delete(&id) {
+        Ok(true) => {
+            info!("🔔 Deleted alert rule {id}");
+            Json(serde_json::json!({ "ok": true })).into_response()
+        }
+        Ok(false) => deny(StatusCode::NOT_FOUND, "Alert rule not found"),
+        Err(e) => {
+            error!("Failed to delete alert rule: {e:#}");
+            deny(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to save alert rules",
+            )
+        }
+    }
+}
+
+/// Build the router for alert rule endpoints.
+pub fn alert_routes(manager: AlertManager) -> Router {
+    Router::new()
+        .route(
+            "/api/alerts",
+            get(list_alerts_handler).post(create_alert_handler),
+        )
+        .route(
+            "/api/alerts/{id}",
+            put(update_alert_handler).delete(delete_alert_handler),
+        )
+        .with_state(manager)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn send(app: &Router, method: &str, uri: &str, body: Option<&str>) -> StatusCode {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_owned())))
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_alert_crud() {
+        let manager = AlertManager::default();
+        let app = alert_routes(manager.clone());
+        let rule = r#"{"name":"Negative prices","condition":{"type":"negative_price"},
+                      "actions":[{"type":"dashboard"},{"type":"webhook","url":"https://example.com/hook"}]}"#;
+
+        assert_eq!(
+            send(&app, "POST", "/api/alerts", Some(rule)).await,
+            StatusCode::CREATED
+        );
+        let id = manager.overview().rules[0].id.clone();
+        assert!(manager.overview().rules[0].spec.enabled);
+
+        let invalid = r#"{"name":"Low","condition":{"type":"battery_below","soc_percent":20,
+                         "duration_minutes":0},"actions":[{"type":"ha_notification"}]}"#;
+        assert_eq!(
+            send(&app, "PUT", &format!("/api/alerts/{id}"), Some(invalid)).await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            send(&app, "PUT", "/api/alerts/missing", Some(rule)).await,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(send(&app, "GET", "/api/alerts", None).await, StatusCode::OK);
+        assert_eq!(
+            send(&app, "DELETE", &format!("/api/alerts/{id}"), None).await,
+            StatusCode::OK
+        );
+        assert_eq!(manager.overview().rules.len(), 0);
+    }
+}
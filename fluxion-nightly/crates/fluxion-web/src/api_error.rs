@@ -0,0 +1,229 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Consistent JSON error envelope for the web API.
+//!
+//! Every error response sent to an API client has the same shape:
+//! `{"code": "not_found", "message": "...", "details": ...}`. Many handlers
+//! still return a bare status code, plain text or their own JSON object, so
+//! [`json_error_middleware`] normalizes the body on the way out. Existing JSON
+//! fields (such as `error` or validation `issues`) are kept so older clients
+//! keep working. Browser navigations (`Accept: text/html`) are left alone.
+
+use axum::{
+    Json,
+    body::{Body, Bytes, to_bytes},
+    extract::Request,
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// Error bodies larger than this are replaced rather than rewritten
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+/// JSON error body returned by every API endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ApiError {
+    /// Machine-readable code, e.g. `not_found` or `service_unavailable`
+    pub code: String,
+    /// Human-readable description of the error
+    pub message: String,
+    /// Optional structured context (validation issues, original body, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<Value>,
+}
+
+impl ApiError {
+    /// Error with the canonical code for `status`
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            code: status_code_name(status),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// Attach structured details
+    #[must_use]
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+/// snake_case code for a status, e.g. `404` -> `not_found`
+pub fn status_code_name(status: StatusCode) -> String {
+    status.canonical_reason().map_or_else(
+        || format!("http_{}", status.as_u16()),
+        |reason| {
+            reason
+                .chars()
+                .filter_map(|c| match c {
+                    ' ' | '-' => Some('_'),
+                    c if c.is_ascii_alphanumeric() => Some(c.to_ascii_lowercase()),
+                    _ => None,
+                })
+                .collect()
+        },
+    )
+}
+
+/// Default message when a handler gave none
+fn default_message(status: StatusCode) -> String {
+    status.canonical_reason().unwrap_or("Error").to_owned()
+}
+
+/// Whether the request comes from a browser expecting an HTML page
+fn wants_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/html"))
+}
+
+/// Build the envelope for an error response body
+fn envelope(status: StatusCode, body: &[u8], is_json: bool) -> Value {
+    if is_json && let Ok(value) = serde_json::from_slice::<Value>(body) {
+        let Value::Object(mut map) = value else {
+            return json_value(&ApiError::new(status, default_message(status)).with_details(value));
+        };
+        let message = map
+            .get("message")
+            .or_else(|| map.get("error"))
+            .and_then(Value::as_str)
+            .map_or_else(|| default_message(status), ToOwned::to_owned);
+        map.entry("code")
+            .or_insert_with(|| Value::String(status_code_name(status)));
+        map.insert("message".to_owned(), Value::String(message));
+        return Value::Object(map);
+    }
+
+    let text = String::from_utf8_lossy(body);
+    let text = text.trim();
+    // Ad-hoc HTML error pages carry no useful message for an API client
+    let message = if text.is_empty() || text.starts_with('<') {
+        default_message(status)
+    } else {
+        text.to_owned()
+    };
+    json_value(&ApiError::new(status, message))
+}
+
+fn json_value(error: &ApiError) -> Value {
+    serde_json::to_value(error).unwrap_or(Value::Null)
+}
+
+/// Error response with an explicit status
+pub fn error_response(status: StatusCode, message: impl Into<String>) -> Response {
+    (status, Json(ApiError::new(status, message))).into_response()
+}
+
+/// Middleware rewriting 4xx/5xx bodies into the [`ApiError`] envelope
+pub async fn json_error_middleware(request: Request, next: Next) -> Response {
+    let html = wants_html(request.headers());
+    let response = next.run(request).await;
+    let status = response.status();
+    if html || !(status.is_client_error() || status.is_server_error()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let is_json = parts
+        .headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json"));
+    let bytes = match to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Failed to buffer {status} error body: {e}");
+            Bytes::default()
+        }
+    };
+
+    let body = envelope(status, &bytes, is_json).to_string();
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_status_code_name() {
+        assert_eq!(status_code_name(StatusCode::NOT_FOUND), "not_found");
+        assert_eq!(
+            status_code_name(StatusCode::SERVICE_UNAVAILABLE),
+            "service_unavailable"
+        );
+        assert_eq!(
+            status_code_name(StatusCode::UNPROCESSABLE_ENTITY),
+            "unprocessable_entity"
+        );
+        assert_eq!(
+            status_code_name(StatusCode::from_u16(499).unwrap()),
+            "http_499"
+        );
+    }
+
+    #[test]
+    fn test_envelope_for_empty_and_text_bodies() {
+        let empty = envelope(StatusCode::SERVICE_UNAVAILABLE, b"", false);
+        assert_eq!(
+            empty,
+            json!({"code": "service_unavailable", "message": "Service Unavailable"})
+        );
+
+        let text = envelope(StatusCode::BAD_REQUEST, b"Invalid block index\n", false);
+        assert_eq!(text["code"], "bad_request");
+        assert_eq!(text["message"], "Invalid block index");
+
+        let html = envelope(StatusCode::NOT_FOUND, b"<h1>Not here</h1>", false);
+        assert_eq!(html["message"], "Not Found");
+    }
+
+    #[test]
+    fn test_envelope_keeps_existing_json_fields() {
+        let body = json!({"error": "Invalid config", "issues": [{"field": "x"}]});
+        let out = envelope(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            body.to_string().as_bytes(),
+            true,
+        );
+        assert_eq!(out["code"], "unprocessable_entity");
+        assert_eq!(out["message"], "Invalid config");
+        assert_eq!(out["error"], "Invalid config");
+        assert_eq!(out["issues"][0]["field"], "x");
+
+        let custom = envelope(
+            StatusCode::CONFLICT,
+            br#"{"code":"run_locked","message":"Run is in use"}"#,
+            true,
+        );
+        assert_eq!(custom["code"], "run_locked");
+
+        let array = envelope(StatusCode::BAD_REQUEST, b"[1,2]", true);
+        assert_eq!(array["details"], json!([1, 2]));
+    }
+}
@@ -0,0 +1,924 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! API keys for external automation clients.
+//!
+//! Provides:
+//! - Scoped API keys (`read:telemetry`, `write:user-control`, `write:config`)
+//! - Per-key usage statistics
+//! - Middleware enforcing the scopes on every request
+//! - Admin page and endpoints for creating and revoking keys
+//!
+//! Requests arriving from the Home Assistant ingress proxy are trusted and never
+//! need a key. Loopback is not trusted: the Tor hidden service forwards to
+//! 127.0.0.1, so loopback peers may only use the mobile API, whose devices are
+//! authenticated by Tor client authorization. Enforcement for other clients only
+//! starts once at least one key exists, so existing setups keep working until keys
+//! are created. Key management and pairing are only reachable through ingress,
+//! whether or not keys exist.
+
+use askama::Template;
+use axum::{
+    Json, Router,
+    extract::{ConnectInfo, Path, Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{Html, IntoResponse, Response},
+    routing::{delete, get},
+};
+use chrono::{DateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::{Path as FsPath, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+/// Prefix of generated keys, makes them easy to recognise in logs and secret scanners
+const KEY_PREFIX: &str = "flx_";
+
+/// Number of leading key characters kept in plain text for display
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+/// Address of the Home Assistant supervisor ingress proxy
+const HA_INGRESS_PROXY: IpAddr = IpAddr::V4(Ipv4Addr::new(172, 30, 32, 2));
+
+/// Minimum interval between usage statistics writes to disk
+const USAGE_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Permission granted to an API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+pub enum ApiKeyScope {
+    /// Read dashboards, telemetry, schedules and configuration
+    #[serde(rename = "read:telemetry")]
+    ReadTelemetry,
+    /// Change user control state (enable/disable, restrictions, slots, safe state)
+    #[serde(rename = "write:user-control")]
+    WriteUserControl,
+    /// Change configuration and plugins
+    #[serde(rename = "write:config")]
+    WriteConfig,
+}
+
+impl ApiKeyScope {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ReadTelemetry => "read:telemetry",
+            Self::WriteUserControl => "write:user-control",
+            Self::WriteConfig => "write:config",
+        }
+    }
+}
+
+/// Usage statistics tracked per key
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyUsage {
+    /// Requests accepted with this key
+    pub total_requests: u64,
+    /// Requests rejected because the key lacked the required scope
+    pub denied_requests: u64,
+    pub last_used: Option<DateTime<Utc>>,
+    pub last_path: Option<String>,
+}
+
+/// Persisted API key metadata. The key itself is only stored as a SHA-256 hash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyEntry {
+    pub id: String,
+    pub name: String,
+    /// First characters of the key, shown in the admin page to tell keys apart
+    pub display_prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<ApiKeyScope>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub usage: ApiKeyUsage,
+}
+
+/// Result of checking a presented key against a required scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiKeyCheck {
+    Granted { key_id: String },
+    MissingScope { key_id: String },
+    UnknownKey,
+}
+
+/// File-backed store of API keys, cached in memory for per-request lookups
+#[derive(Debug)]
+pub struct ApiKeyStore {
+    path: PathBuf,
+    keys: RwLock<Vec<ApiKeyEntry>>,
+    last_flush: Mutex<Instant>,
+}
+
+fn hash_key(key: &str) -> String {
+    Sha256::digest(key.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut out, byte| {
+            let _ = write!(out, "{byte:02x}");
+            out
+        })
+}
+
+/// Generate a new random key (`flx_` + 32 random bytes, base64url).
+fn generate_key() -> String {
+    use base64::Engine as _;
+    let mut bytes = [0_u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    format!(
+        "{KEY_PREFIX}{}",
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    )
+}
+
+impl ApiKeyStore {
+    /// Open the store at `<data_dir>/api_keys.json`.
+    #[must_use]
+    pub fn new(data_dir: &FsPath) -> Self {
+        let path = data_dir.join("api_keys.json");
+        let keys = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("⚠️ Failed to parse {}: {e}", path.display());
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            path,
+            keys: RwLock::new(keys),
+            last_flush: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// All keys, without any secret material beyond the hash
+    #[must_use]
+    pub fn list(&self) -> Vec<ApiKeyEntry> {
+        self.keys.read().clone()
+    }
+
+    #[must_use]
+    pub fn has_keys(&self) -> bool {
+        !self.keys.read().is_empty()
+    }
+
+    /// Create a new key. Returns `(entry, plaintext_key)`; the plaintext is never stored.
+    pub fn create(
+        &self,
+        name: &str,
+        scopes: &[ApiKeyScope],
+    ) -> std::io::Result<(ApiKeyEntry, String)> {
+        let key = generate_key();
+        let mut scopes = scopes.to_vec();
+        scopes.sort_by_key(|s| s.as_str());
+        scopes.dedup();
+
+        let entry = ApiKeyEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_owned(),
+            display_prefix: key.chars().take(DISPLAY_PREFIX_LEN).collect(),
+            key_hash: hash_key(&key),
+            scopes,
+            created_at: Utc::now(),
+            usage: ApiKeyUsage::default(),
+        };
+
+        let mut keys = self.keys.write();
+        keys.push(entry.clone());
+        self.save(&keys)?;
+        Ok((entry, key))
+    }
+
+    /// Revoke a key. Returns `false` if no key has the given id.
+    pub fn revoke(&self, id: &str) -> std::io::Result<bool> {
+        let mut keys = self.keys.write();
+        let original_len = keys.len();
+        keys.retain(|k| k.id != id);
+        if keys.len() == original_len {
+            return Ok(false);
+        }
+        self.save(&keys)?;
+        Ok(true)
+    }
+
+    /// Whether `key` belongs to a stored key, regardless of its scopes
+    #[must_use]
+    pub fn knows(&self, key: &str) -> bool {
+        let hash = hash_key(key);
+        self.keys.read().iter().any(|k| k.key_hash == hash)
+    }
+
+    /// Check a presented key against the required scope and record usage.
+    pub fn check(&self, key: &str, scope: ApiKeyScope, path: &str) -> ApiKeyCheck {
+        let hash = hash_key(key);
+        let mut keys = self.keys.write();
+        let Some(entry) = keys.iter_mut().find(|k| k.key_hash == hash) else {
+            return ApiKeyCheck::UnknownKey;
+        };
+
+        let key_id = entry.id.clone();
+        let granted = entry.scopes.contains(&scope);
+        if granted {
+            entry.usage.total_requests += 1;
+        } else {
+            entry.usage.denied_requests += 1;
+        }
+        entry.usage.last_used = Some(Utc::now());
+        entry.usage.last_path = Some(path.to_owned());
+
+        // Usage is best-effort, only flushed periodically to avoid a write per request
+        let mut last_flush = self.last_flush.lock();
+        if last_flush.elapsed() >= USAGE_FLUSH_INTERVAL {
+            *last_flush = Instant::now();
+            if let Err(e) = self.save(&keys) {
+                warn!("⚠️ Failed to persist API key usage: {e}");
+            }
+        }
+
+        if granted {
+            ApiKeyCheck::Granted { key_id }
+        } else {
+            ApiKeyCheck::MissingScope { key_id }
+        }
+    }
+
+    fn save(&self, keys: &[ApiKeyEntry]) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(keys).map_err(std::io::Error::other)?;
+        std::fs::write(&self.path, json)
+    }
+}
+
+/// Shared state for API key management and enforcement
+#[derive(Debug, Clone)]
+pub struct ApiKeyApiState {
+    pub store: Arc<ApiKeyStore>,
+}
+
+impl ApiKeyApiState {
+    #[must_use]
+    pub fn new(data_dir: &FsPath) -> Self {
+        Self {
+            store: Arc::new(ApiKeyStore::new(data_dir)),
+        }
+    }
+}
+
+// ==================== Enforcement ====================
+
+/// Access requirement of a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteAccess {
+    /// No key needed (health checks, CORS preflight)
+    Public,
+    /// Key must carry the scope
+    Scope(ApiKeyScope),
+    /// Only HA ingress; API keys are never enough
+    TrustedOnly,
+}
+
+/// Map a request to the access it requires
+#[must_use]
+pub fn required_access(method: &Method, path: &str) -> RouteAccess {
+    // Uptime monitors poll the health and status endpoints without a key;
+    // standalone logins must be reachable before any credential exists
+    if method == Method::OPTIONS
+        || path == "/health"
+        || path == "/status.json"
+        || path == "/login"
+        || path.starts_with("/api/auth/")
+    {
+        return RouteAccess::Public;
+    }
+
+    // Managing keys, pairing devices and reading logs or ECS state (HA URLs,
+    // entity IDs, pairing details) must not be possible with a key alone
+    if path.starts_with("/api/keys")
+        || path == "/api-keys"
+        || path.starts_with("/api/remote")
+        || path == "/remote-access"
+        || path.starts_with("/api/logs")
+        || path.starts_with("/api/debug")
+    {
+        return RouteAccess::TrustedOnly;
+    }
+
+    if method == Method::GET || method == Method::HEAD {
+        return RouteAccess::Scope(ApiKeyScope::ReadTelemetry);
+    }
+
+    if path.starts_with("/api/user-control")
+        || path.starts_with("/api/system/safe-state")
+        || path.starts_with("/api/system/self-test")
+        || path.starts_with("/api/schedule/pin")
+        || path.starts_with("/api/backup-reserve")
+        || path.starts_with("/api/calendar/vacations")
+        || path.starts_with("/mobile/api/control")
+        || path.starts_with("/mobile/api/safe-state")
+    {
+        RouteAccess::Scope(ApiKeyScope::WriteUserControl)
+    } else if is_compute_only(method, path) {
+        RouteAccess::Scope(ApiKeyScope::ReadTelemetry)
+    } else {
+        // Anything not listed above may change the system
+        RouteAccess::Scope(ApiKeyScope::WriteConfig)
+    }
+}
+
+/// Simulator, backtest, what-if and strategy wizard calls only compute results,
+/// they don't change the system
+///
+/// Deleting saved simulator runs removes shared history, so it is not included.
+fn is_compute_only(method: &Method, path: &str) -> bool {
+    if method == Method::DELETE && path.starts_with("/api/simulator/runs") {
+        return false;
+    }
+    path.starts_with("/api/simulator/")
+        || path.starts_with("/api/backtest/")
+        || path.starts_with("/api/strategy-wizard/")
+        || path == "/api/schedule/what-if"
+}
+
+/// Extract a key from `Authorization: Bearer <key>` or `X-API-Key: <key>`
+pub(crate) fn presented_key(headers: &HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let header = headers.get("X-API-Key").and_then(|v| v.to_str().ok());
+
+    bearer
+        .or(header)
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// Requests from the HA ingress proxy are already authenticated by Home Assistant
+///
+/// Ingress headers are not considered, any LAN client can set them.
+pub(crate) fn is_trusted_peer(peer: Option<IpAddr>) -> bool {
+    peer == Some(HA_INGRESS_PROXY)
+}
+
+/// Mobile API requests over the Tor hidden service, authenticated by Tor client authorization
+pub(crate) fn is_tor_mobile_request(peer: Option<IpAddr>, path: &str) -> bool {
+    peer.is_some_and(|ip| ip.is_loopback()) && path.starts_with("/mobile/")
+}
+
+pub(crate) fn deny(status: StatusCode, message: &str) -> Response {
+    (status, Json(serde_json::json!({ "error": message }))).into_response()
+}
+
+/// Middleware enforcing API key scopes on all routes
+pub async fn require_api_key(
+    State(state): State<ApiKeyApiState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let access = required_access(request.method(), request.uri().path());
+    if access == RouteAccess::Public {
+        return next.run(request).await;
+    }
+
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if is_trusted_peer(peer) {
+        return next.run(request).await;
+    }
+
+    let RouteAccess::Scope(scope) = access else {
+        return deny(
+            StatusCode::FORBIDDEN,
+            "Only available through Home Assistant",
+        );
+    };
+
+    if is_tor_mobile_request(peer, request.uri().path()) || !state.store.has_keys() {
+        return next.run(request).await;
+    }
+
+    // The login middleware already limited guests to reading the dashboard
+    if scope == ApiKeyScope::ReadTelemetry
+        && request
+            .extensions()
+            .get::<crate::auth::GuestView>()
+            .is_some()
+    {
+        return next.run(request).await;
+    }
+
+    let Some(key) = presented_key(request.headers()) else {
+        return deny(StatusCode::UNAUTHORIZED, "API key required");
+    };
+
+    match state.store.check(&key, scope, request.uri().path()) {
+        ApiKeyCheck::Granted { .. } => next.run(request).await,
+        ApiKeyCheck::MissingScope { key_id } => {
+            warn!(
+                "🔑 API key {key_id} denied: missing scope {} for {} {}",
+                scope.as_str(),
+                request.method(),
+                request.uri().path()
+            );
+            deny(
+                StatusCode::FORBIDDEN,
+                &format!("API key lacks scope {}", scope.as_str()),
+            )
+        }
+        ApiKeyCheck::UnknownKey => {
+            warn!(
+                "🔑 Unknown API key from {}",
+                peer.map_or_else(|| "unknown".to_owned(), |ip| ip.to_string())
+            );
+            deny(StatusCode::UNAUTHORIZED, "Invalid API key")
+        }
+    }
+}
+
+// ==================== Management endpoints ====================
+
+#[derive(Template)]
+#[template(path = "api_keys.html")]
+struct ApiKeysPageTemplate {
+    ingress_path: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct CreateKeyRequest {
+    name: String,
+    scopes: Vec<ApiKeyScope>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct CreateKeyResponse {
+    key: ApiKeyResponse,
+    /// Plaintext key, shown only once
+    secret: String,
+}
+
+#[derive(Serialize, ToSchema)]
+pub(crate) struct ApiKeyResponse {
+    id: String,
+    name: String,
+    display_prefix: String,
+    scopes: Vec<ApiKeyScope>,
+    created_at: String,
+    usage: ApiKeyUsage,
+}
+
+impl From<ApiKeyEntry> for ApiKeyResponse {
+    fn from(entry: ApiKeyEntry) -> Self {
+        Self {
+            id: entry.id,
+            name: entry.name,
+            display_prefix: entry.display_prefix,
+            scopes: entry.scopes,
+            created_at: entry.created_at.to_rfc3339(),
+            usage: entry.usage,
+        }
+    }
+}
+
+/// GET /api/keys
+async fn list_keys_handler(State(state): State<ApiKeyApiState>) -> impl IntoResponse {
+    let keys: Vec<ApiKeyResponse> = state
+        .store
+        .list()
+        .into_iter()
+        .map(ApiKeyResponse::from)
+        .collect();
+    Json(keys)
+}
+
+/// POST /api/keys
+async fn create_key_handler(
+    State(state): State<ApiKeyApiState>,
+    Json(req): Json<CreateKeyRequest>,
+) -> impl IntoResponse {
+    let name = req.name.trim();
+    if name.is_empty() {
+        return deny(StatusCode::BAD_REQUEST, "name must not be empty");
+    }
+    if req.scopes.is_empty() {
+        return deny(StatusCode::BAD_REQUEST, "at least one scope is required");
+    }
+
+    match state.store.create(name, &req.scopes) {
+        Ok((entry, secret)) => {
+            info!(
+                "🔑 Created API key '{}' (id={}, scopes={:?})",
+                entry.name, entry.id, entry.scopes
+            );
+            Json(CreateKeyResponse {
+                key: entry.into(),
+                secret,
+            })
+            .into_response()
+        }
+        Err(e) => {
+            error!("Failed to create API key: {e}");
+            deny(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to create API key",
+            )
+        }
+    }
+}
+
+/// DELETE /api/keys/{id}
+async fn revoke_key_handler(
+    State(state): State<ApiKeyApiState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match state.store.revoke(&id) {
+        Ok(true) => {
+            info!("🔑 Revoked API key {id}");
+            Json(serde_json::json!({ "ok": true })).into_response()
+        }
+        Ok(false) => deny(StatusCode::NOT_FOUND, "API key not found"),
+        Err(e) => {
+            error!("Failed to revoke API key: {e}");
+            deny(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to revoke API key",
+            )
+        }
+    }
+}
+
+/// GET /api-keys — management page
+async fn page_handler(headers: HeaderMap) -> impl IntoResponse {
+    let ingress_path = crate::extract_ingress_path(&headers);
+    let template = ApiKeysPageTemplate { ingress_path };
+    match template.render() {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => {
+            error!("Template render error: {e}");
+            Html(format!("<h1>Error</h1><p>{e}</p>")).into_response()
+        }
+    }
+}
+
+/// Build the router for API key management endpoints.
+pub fn api_key_routes(state: ApiKeyApiState) -> Router {
+    Router::new()
+        .route("/api-keys", get(page_handler))
+        .route("/api/keys", get(list_keys_handler).post(create_key_handler))
+        .route("/api/keys/{id}", delete(revoke_key_handler))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_store_crud_and_check() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = ApiKeyStore::new(tmp.path());
+        assert!(!store.has_keys());
+
+        let (entry, secret) = store
+            .create("Node-RED", &[ApiKeyScope::ReadTelemetry])
+            .unwrap();
+        assert!(secret.starts_with(KEY_PREFIX));
+        assert!(secret.starts_with(&entry.display_prefix));
+        assert_ne!(entry.key_hash, secret);
+
+        assert_eq!(
+            store.check(&secret, ApiKeyScope::ReadTelemetry, "/chart-data"),
+            ApiKeyCheck::Granted {
+                key_id: entry.id.clone()
+            }
+        );
+        assert_eq!(
+            store.check(&secret, ApiKeyScope::WriteConfig, "/api/config/update"),
+            ApiKeyCheck::MissingScope {
+                key_id: entry.id.clone()
+            }
+        );
+        assert_eq!(
+            store.check("flx_bogus", ApiKeyScope::ReadTelemetry, "/"),
+            ApiKeyCheck::UnknownKey
+        );
+
+        let usage = &store.list()[0].usage;
+        assert_eq!(usage.total_requests, 1);
+        assert_eq!(usage.denied_requests, 1);
+        assert_eq!(usage.last_path.as_deref(), Some("/api/config/update"));
+
+        // Reload from disk
+        let reloaded = ApiKeyStore::new(tmp.path());
+        assert_eq!(reloaded.list().len(), 1);
+        assert!(reloaded.knows(&secret));
+        assert!(!reloaded.knows("flx_bogus"));
+
+        assert!(store.revoke(&entry.id).unwrap());
+        assert!(!store.revoke(&entry.id).unwrap());
+        assert!(!store.has_keys());
+    }
+
+    #[test]
+    fn test_required_access() {
+        assert_eq!(
+            required_access(&Method::GET, "/health"),
+            RouteAccess::Public
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/status.json"),
+            RouteAccess::Public
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/auth/login"),
+            RouteAccess::Public
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/keys"),
+            RouteAccess::TrustedOnly
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/logs/download"),
+            RouteAccess::TrustedOnly
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/debug/ecs"),
+            RouteAccess::TrustedOnly
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/remote-access/devices/d1/rotate-key"),
+            RouteAccess::TrustedOnly
+        );
+        assert_eq!(
+            required_access(&Method::GET, "/api/config"),
+            RouteAccess::Scope(ApiKeyScope::ReadTelemetry)
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/config/update"),
+            RouteAccess::Scope(ApiKeyScope::WriteConfig)
+        );
+        assert_eq!(
+            required_access(&Method::PUT, "/api/alerts/alert_1"),
+            RouteAccess::Scope(ApiKeyScope::WriteConfig)
+        );
+        assert_eq!(
+            required_access(&Method::PUT, "/api/ui/preferences"),
+            RouteAccess::Scope(ApiKeyScope::WriteConfig)
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/setup/complete"),
+            RouteAccess::Scope(ApiKeyScope::WriteConfig)
+        );
+        assert_eq!(
+            required_access(&Method::PUT, "/api/user-control/enabled"),
+            RouteAccess::Scope(ApiKeyScope::WriteUserControl)
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/simulator/create"),
+            RouteAccess::Scope(ApiKeyScope::ReadTelemetry)
+        );
+        assert_eq!(
+            required_access(&Method::DELETE, "/api/simulator/runs/abc"),
+            RouteAccess::Scope(ApiKeyScope::WriteConfig)
+        );
+    }
+
+    #[test]
+    fn test_mutating_routes_fail_closed() {
+        use crate::openapi::{self, ROUTES};
+
+        // Unknown or newly added changes need the broadest scope
+        assert_eq!(
+            required_access(&Method::POST, "/api/something-new"),
+            RouteAccess::Scope(ApiKeyScope::WriteConfig)
+        );
+
+        let compute_only = [
+            "/api/schedule/what-if",
+            "/api/strategy-wizard/evaluate",
+            "/api/backtest/simulate",
+            "/api/backtest/sweep",
+            "/api/backtest/compare",
+            "/api/simulator/create",
+            "/api/simulator/batch",
+            "/api/simulator/{id}",
+            "/api/simulator/{id}/step",
+            "/api/simulator/{id}/run",
+            "/api/simulator/{id}/override/soc",
+            "/api/simulator/{id}/override/load",
+            "/api/simulator/{id}/override/price",
+            "/api/simulator/{id}/save",
+            "/api/simulator/{id}/reset",
+            "/api/simulator/runs/{run_id}/open",
+        ];
+        for route in ROUTES {
+            let method = match route.method {
+                openapi::Method::Get => continue,
+                openapi::Method::Post => Method::POST,
+                openapi::Method::Put => Method::PUT,
+                openapi::Method::Delete => Method::DELETE,
+            };
+            let path = route
+                .path
+                .split('/')
+                .map(|segment| {
+                    if segment.starts_with('{') {
+                        "x"
+                    } else {
+                        segment
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            let access = required_access(&method, &path);
+            if compute_only.contains(&route.path) {
+                assert_eq!(
+                    access,
+                    RouteAccess::Scope(ApiKeyScope::ReadTelemetry),
+                    "{method} {path}"
+                );
+            } else {
+                assert_ne!(
+                    access,
+                    RouteAccess::Scope(ApiKeyScope::ReadTelemetry),
+                    "{method} {path} is writable with a read-only key"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_schedule_pins_need_user_control() {
+        assert_eq!(
+            required_access(&Method::POST, "/api/schedule/pin"),
+            RouteAccess::Scope(ApiKeyScope::WriteUserControl)
+        );
+        assert_eq!(
+            required_access(&Method::DELETE, "/api/schedule/pin/pin_1"),
+            RouteAccess::Scope(ApiKeyScope::WriteUserControl)
+        );
+    }
+
+    #[test]
+    fn test_backup_reserve_needs_user_control() {
+        assert_eq!(
+            required_access(&Method::GET, "/api/backup-reserve"),
+            RouteAccess::Scope(ApiKeyScope::ReadTelemetry)
+        );
+        assert_eq!(
+            required_access(&Method::PUT, "/api/backup-reserve"),
+            RouteAccess::Scope(ApiKeyScope::WriteUserControl)
+        );
+        assert_eq!(
+            required_access(&Method::DELETE, "/api/backup-reserve"),
+            RouteAccess::Scope(ApiKeyScope::WriteUserControl)
+        );
+    }
+
+    #[test]
+    fn test_vacations_need_user_control() {
+        assert_eq!(
+            required_access(&Method::GET, "/api/calendar"),
+            RouteAccess::Scope(ApiKeyScope::ReadTelemetry)
+        );
+        assert_eq!(
+            required_access(&Method::POST, "/api/calendar/vacations"),
+            RouteAccess::Scope(ApiKeyScope::WriteUserControl)
+        );
+        assert_eq!(
+            required_access(&Method::DELETE, "/api/calendar/vacations/vac_1"),
+            RouteAccess::Scope(ApiKeyScope::WriteUserControl)
+        );
+    }
+
+    #[test]
+    fn test_presented_key_and_trust() {
+        let mut headers = HeaderMap::new();
+        assert!(presented_key(&headers).is_none());
+        headers.insert("X-API-Key", "flx_abc".parse().unwrap());
+        assert_eq!(presented_key(&headers).as_deref(), Some("flx_abc"));
+        headers.insert(
+            axum::http::header::AUTHORIZATION,
+            "Bearer flx_def".parse().unwrap(),
+        );
+        assert_eq!(presented_key(&headers).as_deref(), Some("flx_def"));
+
+        assert!(is_trusted_peer(Some(HA_INGRESS_PROXY)));
+        assert!(!is_trusted_peer(Some(LOCALHOST)));
+        assert!(!is_trusted_peer(Some(LAN_PEER)));
+        assert!(!is_trusted_peer(None));
+
+        assert!(is_tor_mobile_request(Some(LOCALHOST), "/mobile/api/state"));
+        assert!(!is_tor_mobile_request(Some(LOCALHOST), "/api/config"));
+        assert!(!is_tor_mobile_request(Some(LAN_PEER), "/mobile/api/state"));
+    }
+
+    const LOCALHOST: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    const LAN_PEER: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20));
+
+    /// Run a request from `peer` through the middleware, returning the status
+    async fn status_for(
+        state: &ApiKeyApiState,
+        peer: IpAddr,
+        method: Method,
+        path: &str,
+        key: Option<&str>,
+    ) -> StatusCode {
+        use tower::ServiceExt;
+
+        let app =
+            Router::new()
+                .fallback(|| async { "ok" })
+                .layer(axum::middleware::from_fn_with_state(
+                    state.clone(),
+                    require_api_key,
+                ));
+        let mut request = Request::builder().method(method).uri(path);
+        if let Some(key) = key {
+            request = request.header("X-API-Key", key);
+        }
+        let mut request = request.body(axum::body::Body::empty()).unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::new(peer, 40000)));
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn test_key_management_needs_ingress_without_keys() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = ApiKeyApiState::new(tmp.path());
+
+        assert_eq!(
+            status_for(&state, LAN_PEER, Method::POST, "/api/keys", None).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status_for(&state, LOCALHOST, Method::POST, "/api/keys", None).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status_for(&state, HA_INGRESS_PROXY, Method::POST, "/api/keys", None).await,
+            StatusCode::OK
+        );
+        // Other routes stay open until the first key is created
+        assert_eq!(
+            status_for(&state, LAN_PEER, Method::GET, "/api/config", None).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_loopback_peer_needs_key() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = ApiKeyApiState::new(tmp.path());
+        let (_, secret) = state
+            .store
+            .create("Node-RED", &[ApiKeyScope::ReadTelemetry])
+            .unwrap();
+
+        assert_eq!(
+            status_for(&state, LOCALHOST, Method::GET, "/api/config", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_for(&state, LOCALHOST, Method::GET, "/api/config", Some(&secret)).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_for(&state, LOCALHOST, Method::GET, "/api/keys", Some(&secret)).await,
+            StatusCode::FORBIDDEN
+        );
+        // Paired devices over Tor keep using the mobile API
+        assert_eq!(
+            status_for(&state, LOCALHOST, Method::GET, "/mobile/api/state", None).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status_for(&state, LAN_PEER, Method::GET, "/mobile/api/state", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status_for(&state, HA_INGRESS_PROXY, Method::GET, "/api/config", None).await,
+            StatusCode::OK
+        );
+    }
+}
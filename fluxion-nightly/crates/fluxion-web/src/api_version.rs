@@ -0,0 +1,223 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Versioned API paths and deprecation of the unversioned aliases.
+//!
+//! The API is served under `/api/v1/...`. Handlers stay registered on the
+//! unversioned `/api/...` paths and [`api_version_middleware`] runs in front of
+//! the router, mapping the versioned path onto them, so login, API key scopes
+//! and per-route layers only ever see one path. Unversioned requests keep
+//! working but are marked with a `Deprecation` header and a `Link` to their
+//! successor. `/mobile/api/...` has its own versioning and is left alone.
+
+use axum::{
+    Json,
+    extract::Request,
+    http::{HeaderValue, Uri, header, uri::PathAndQuery},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use std::borrow::Cow;
+use tracing::trace;
+use utoipa::ToSchema;
+
+/// Current API version
+pub const CURRENT_API_VERSION: &str = "v1";
+
+/// API versions this build understands
+pub const SUPPORTED_API_VERSIONS: &[&str] = &["v1"];
+
+const VERSIONED_PREFIX: &str = "/api/v1";
+
+/// Version-independent paths, never reported as deprecated
+const UNVERSIONED_PATHS: &[&str] = &["/api/version"];
+
+/// How a request path relates to API versioning
+#[derive(Debug, PartialEq, Eq)]
+enum ApiPath {
+    /// `/api/v1/...`, carrying the internal path the router knows
+    Versioned(String),
+    /// `/api/...` without a version, with its successor path
+    Deprecated(String),
+    /// Pages, static assets, mobile API and version-independent endpoints
+    Other,
+}
+
+fn classify(path: &str) -> ApiPath {
+    if let Some(rest) = path.strip_prefix(VERSIONED_PREFIX)
+        && (rest.is_empty() || rest.starts_with('/'))
+    {
+        return ApiPath::Versioned(format!("/api{rest}"));
+    }
+    let Some(rest) = path.strip_prefix("/api/") else {
+        return ApiPath::Other;
+    };
+    // Unknown versions (`/api/v2/...`) fall through to a plain 404
+    let looks_versioned = rest
+        .strip_prefix('v')
+        .is_some_and(|v| v.starts_with(|c: char| c.is_ascii_digit()));
+    if looks_versioned || UNVERSIONED_PATHS.contains(&path) {
+        ApiPath::Other
+    } else {
+        ApiPath::Deprecated(format!("{VERSIONED_PREFIX}/{rest}"))
+    }
+}
+
+/// Public path of a route registered as `/api/...`, e.g. `/api/config` -> `/api/v1/config`
+pub fn versioned_path(path: &str) -> Cow<'_, str> {
+    match classify(path) {
+        ApiPath::Deprecated(successor) => Cow::Owned(successor),
+        ApiPath::Versioned(_) | ApiPath::Other => Cow::Borrowed(path),
+    }
+}
+
+/// Replace the path of a URI, keeping the query string
+fn with_path(uri: &Uri, path: &str) -> Option<Uri> {
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path.to_owned(),
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(PathAndQuery::try_from(path_and_query).ok()?);
+    Uri::from_parts(parts).ok()
+}
+
+/// Middleware mapping `/api/v1/...` onto the registered routes and marking
+/// unversioned `/api/...` calls as deprecated.
+/// Must wrap the router (not be added with `Router::layer`) so it runs before routing.
+pub async fn api_version_middleware(mut request: Request, next: Next) -> Response {
+    match classify(request.uri().path()) {
+        ApiPath::Versioned(path) => {
+            if let Some(uri) = with_path(request.uri(), &path) {
+                *request.uri_mut() = uri;
+            }
+            next.run(request).await
+        }
+        ApiPath::Deprecated(successor) => {
+            trace!("Deprecated unversioned API call: {}", request.uri().path());
+            let ingress_path = request
+                .headers()
+                .get("X-Ingress-Path")
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_owned();
+            let mut response = next.run(request).await;
+            let headers = response.headers_mut();
+            headers.insert("Deprecation", HeaderValue::from_static("true"));
+            if let Ok(link) = HeaderValue::from_str(&format!(
+                "<{ingress_path}{successor}>; rel=\"successor-version\""
+            )) {
+                headers.insert(header::LINK, link);
+            }
+            response
+        }
+        ApiPath::Other => next.run(request).await,
+    }
+}
+
+/// Response of `GET /api/version`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiVersionInfo {
+    /// FluxION version
+    pub version: &'static str,
+    /// Current API version
+    pub api_version: &'static str,
+    /// All API versions served by this build
+    #[schema(value_type = Vec<String>)]
+    pub supported_api_versions: &'static [&'static str],
+    /// Path prefix of the current API version
+    pub base_path: &'static str,
+}
+
+/// API version handler
+pub async fn api_version_handler() -> impl IntoResponse {
+    Json(ApiVersionInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        api_version: CURRENT_API_VERSION,
+        supported_api_versions: SUPPORTED_API_VERSIONS,
+        base_path: VERSIONED_PREFIX,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{Router, body::Body, http::StatusCode, routing::get};
+    use tower::{Layer, ServiceExt};
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            classify("/api/v1/config"),
+            ApiPath::Versioned("/api/config".to_owned())
+        );
+        assert_eq!(
+            classify("/api/v1/simulator/abc/step"),
+            ApiPath::Versioned("/api/simulator/abc/step".to_owned())
+        );
+        assert_eq!(
+            classify("/api/config"),
+            ApiPath::Deprecated("/api/v1/config".to_owned())
+        );
+        assert_eq!(classify("/api/version"), ApiPath::Other);
+        assert_eq!(classify("/api/v2/config"), ApiPath::Other);
+        assert_eq!(classify("/mobile/api/state"), ApiPath::Other);
+        assert_eq!(classify("/chart-data"), ApiPath::Other);
+
+        assert_eq!(versioned_path("/api/history"), "/api/v1/history");
+        assert_eq!(versioned_path("/api/version"), "/api/version");
+        assert_eq!(versioned_path("/health"), "/health");
+    }
+
+    #[tokio::test]
+    async fn test_versioned_and_deprecated_paths() {
+        let router = Router::new()
+            .route("/api/history", get(|| async { "history" }))
+            .route("/api/version", get(api_version_handler));
+        let app = axum::middleware::from_fn(api_version_middleware).layer(router);
+
+        let request = |uri: &str| Request::builder().uri(uri).body(Body::empty()).unwrap();
+
+        let versioned = app
+            .clone()
+            .oneshot(request("/api/v1/history?days=7"))
+            .await
+            .unwrap();
+        assert_eq!(versioned.status(), StatusCode::OK);
+        assert!(versioned.headers().get("Deprecation").is_none());
+
+        let legacy = app
+            .clone()
+            .oneshot(request("/api/history?days=7"))
+            .await
+            .unwrap();
+        assert_eq!(legacy.status(), StatusCode::OK);
+        assert_eq!(legacy.headers()["Deprecation"], "true");
+        assert_eq!(
+            legacy.headers()[header::LINK],
+            "</api/v1/history>; rel=\"successor-version\""
+        );
+
+        let version = app.clone().oneshot(request("/api/version")).await.unwrap();
+        assert!(version.headers().get("Deprecation").is_none());
+        let body = axum::body::to_bytes(version.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let info: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(info["api_version"], "v1");
+        assert_eq!(info["supported_api_versions"], serde_json::json!(["v1"]));
+
+        let unknown = app.oneshot(request("/api/v2/history")).await.unwrap();
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+    }
+}
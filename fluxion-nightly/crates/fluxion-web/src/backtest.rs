@@ -6,53 +6,122 @@
 //!
 //! This module provides REST API endpoints for the strategy backtesting feature.
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use askama::Template;
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::{Html, IntoResponse},
 };
 use chrono::NaiveDate;
 use fluxion_backtest::{
-    BacktestMetadata, DataSource, DayAnalysis, SqliteDataSource, StrategyChoice,
-    StrategyConfigOverrides, calculate_comparison, simulate_day,
+    BacktestMetadata, DataSource, DayAnalysis, MAX_RANGE_DAYS, OptimalityGap, SqliteDataSource,
+    StrategyChoice, StrategyConfigOverrides, SweepRequest, SweepResult, calculate_comparison,
+    calculate_optimality_gap, run_sweep, simulate_day, simulate_range, sweep_size,
 };
+use fluxion_core::TimeFormatter;
 use fluxion_i18n::I18n;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, error};
+use utoipa::ToSchema;
 
 /// State for backtest handlers
 #[derive(Clone, Debug)]
 pub struct BacktestState {
     pub data_source: Arc<SqliteDataSource>,
     pub i18n: Arc<I18n>,
+    pub sweeps: Arc<SweepJobs>,
 }
 
 impl BacktestState {
     /// Create a new backtest state with the given database path
+    ///
+    /// Days are cut at local midnight in the timezone of `time_formatter`.
     #[must_use]
-    pub fn new(db_path: PathBuf, i18n: Arc<I18n>) -> Self {
+    pub fn new(db_path: PathBuf, i18n: Arc<I18n>, time_formatter: TimeFormatter) -> Self {
         Self {
-            data_source: Arc::new(SqliteDataSource::new(db_path)),
+            data_source: Arc::new(
+                SqliteDataSource::new(db_path).with_time_formatter(time_formatter),
+            ),
             i18n,
+            sweeps: Arc::default(),
         }
     }
 }
 
+/// Finished sweeps kept for polling; older ones are dropped
+const MAX_FINISHED_SWEEPS: usize = 8;
+
+/// State of a background parameter sweep
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum SweepStatus {
+    Running { completed: usize, total: usize },
+    Done { result: Box<SweepResult> },
+    Failed { error: String },
+}
+
+/// Parameter sweeps by id, at most one running at a time
+#[derive(Debug, Default)]
+pub struct SweepJobs {
+    next_id: AtomicU64,
+    jobs: parking_lot::Mutex<BTreeMap<u64, SweepStatus>>,
+}
+
+impl SweepJobs {
+    /// Register a new running sweep, unless one is already running
+    fn start(&self, total: usize) -> Option<u64> {
+        let mut jobs = self.jobs.lock();
+        if jobs
+            .values()
+            .any(|job| matches!(job, SweepStatus::Running { .. }))
+        {
+            return None;
+        }
+        while jobs.len() >= MAX_FINISHED_SWEEPS {
+            jobs.pop_first();
+        }
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        jobs.insert(
+            id,
+            SweepStatus::Running {
+                completed: 0,
+                total,
+            },
+        );
+        Some(id)
+    }
+
+    fn update(&self, id: u64, status: SweepStatus) {
+        self.jobs.lock().insert(id, status);
+    }
+
+    fn get(&self, id: u64) -> Option<SweepStatus> {
+        self.jobs.lock().get(&id).cloned()
+    }
+}
+
 /// Backtest page template
 #[derive(Template)]
 #[template(path = "backtest.html")]
 pub struct BacktestTemplate {
-    #[expect(dead_code)]
     pub i18n: Arc<I18n>,
     pub ingress_path: String,
     pub available_days: Vec<String>,
     pub strategies: Vec<StrategyInfoJson>,
 }
 
+impl BacktestTemplate {
+    /// Helper method for translations in templates
+    pub fn t(&self, key: &str) -> String {
+        self.i18n.get(key).unwrap_or_else(|_| key.to_owned())
+    }
+}
+
 /// Strategy info for JSON serialization
 #[derive(Clone, Serialize, Deserialize)]
 pub struct StrategyInfoJson {
@@ -179,7 +248,7 @@ pub async fn day_data_handler(
 }
 
 /// Request body for simulation endpoint
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct SimulateRequest {
     pub date: String,
     pub strategy: String,
@@ -208,17 +277,9 @@ pub async fn simulate_handler(
         }
     };
 
-    let strategy = match request.strategy.as_str() {
-        "actual" => StrategyChoice::Actual,
-        "self_use" => StrategyChoice::SelfUse,
-        "winter_adaptive" => StrategyChoice::WinterAdaptive,
-        _ => {
-            return (
-                axum::http::StatusCode::BAD_REQUEST,
-                format!("Unknown strategy: {}", request.strategy),
-            )
-                .into_response();
-        }
+    let strategy = match parse_strategy(&request.strategy) {
+        Ok(s) => s,
+        Err(e) => return (axum::http::StatusCode::BAD_REQUEST, e).into_response(),
     };
 
     match simulate_day(
@@ -240,7 +301,7 @@ pub async fn simulate_handler(
 }
 
 /// Request body for comparison endpoint
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CompareRequest {
     pub date: String,
     pub left_strategy: String,
@@ -257,6 +318,16 @@ pub struct CompareResponse {
     pub left: DayAnalysis,
     pub right: DayAnalysis,
     pub comparison: ComparisonInfo,
+    /// Distance of both sides from the perfect-foresight optimum
+    pub optimal: Option<OptimalInfo>,
+}
+
+/// Gap to the optimal hindsight schedule for both compared strategies
+#[derive(Serialize)]
+pub struct OptimalInfo {
+    pub net_cost_czk: f64,
+    pub left: OptimalityGap,
+    pub right: OptimalityGap,
 }
 
 #[derive(Serialize)]
@@ -288,15 +359,6 @@ pub async fn compare_handler(
         }
     };
 
-    let parse_strategy = |s: &str| -> Result<StrategyChoice, String> {
-        match s {
-            "actual" => Ok(StrategyChoice::Actual),
-            "self_use" => Ok(StrategyChoice::SelfUse),
-            "winter_adaptive" => Ok(StrategyChoice::WinterAdaptive),
-            _ => Err(format!("Unknown strategy: {s}")),
-        }
-    };
-
     let left_strategy = match parse_strategy(&request.left_strategy) {
         Ok(s) => s,
         Err(e) => return (axum::http::StatusCode::BAD_REQUEST, e).into_response(),
@@ -360,7 +422,10 @@ pub async fn compare_handler(
         format!("{} and {} have equal costs", left.strategy, right.strategy)
     };
 
+    let optimal = optimal_info(&state, date, &left, &right);
+
     let response = CompareResponse {
+        optimal,
         left,
         right,
         comparison: ComparisonInfo {
@@ -374,6 +439,188 @@ pub async fn compare_handler(
     Json(response).into_response()
 }
 
+/// Query parameters of the range endpoint
+#[derive(Deserialize)]
+pub struct RangeQuery {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Comma-separated strategy ids; the first is the baseline
+    #[serde(default = "default_range_strategies")]
+    pub strategies: String,
+}
+
+fn default_range_strategies() -> String {
+    "actual,self_use,winter_adaptive,optimal_hindsight".to_owned()
+}
+
+/// Handler to simulate strategies over a date range
+///
+/// The battery SOC is carried across midnight; returns per-day and total
+/// costs of each strategy compared to the first one.
+pub async fn range_handler(
+    State(state): State<BacktestState>,
+    Query(query): Query<RangeQuery>,
+) -> impl IntoResponse {
+    debug!(
+        "Range simulation requested for {} to {} with {}",
+        query.from, query.to, query.strategies
+    );
+
+    let strategies = match query
+        .strategies
+        .split(',')
+        .map(|s| parse_strategy(s.trim()))
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(s) => s,
+        Err(e) => return (axum::http::StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    if query.from > query.to || (query.to - query.from).num_days() >= MAX_RANGE_DAYS {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            format!("Range must be ordered and shorter than {MAX_RANGE_DAYS} days"),
+        )
+            .into_response();
+    }
+
+    // A month of simulations takes a while, keep it off the async workers
+    let data_source = Arc::clone(&state.data_source);
+    let result = tokio::task::spawn_blocking(move || {
+        simulate_range(
+            data_source.as_ref(),
+            query.from,
+            query.to,
+            &strategies,
+            None,
+        )
+    })
+    .await;
+
+    match result {
+        Ok(Ok(range)) => Json(range).into_response(),
+        Ok(Err(e)) => {
+            error!("Failed to simulate {} to {}: {}", query.from, query.to, e);
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to simulate range: {e}"),
+            )
+                .into_response()
+        }
+        Err(e) => {
+            error!("Range simulation task failed: {}", e);
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+/// Response of a started sweep
+#[derive(Serialize)]
+pub struct SweepStartedResponse {
+    pub id: u64,
+    /// Configurations to evaluate, the default one included
+    pub total: usize,
+}
+
+/// Handler to start a parameter sweep in the background
+///
+/// Poll `/api/backtest/sweep/{id}` for progress and the result.
+pub async fn sweep_start_handler(
+    State(state): State<BacktestState>,
+    Json(request): Json<SweepRequest>,
+) -> impl IntoResponse {
+    let total = match sweep_size(&request) {
+        Ok(n) => n + 1,
+        Err(e) => return (axum::http::StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+    let Some(id) = state.sweeps.start(total) else {
+        return (
+            axum::http::StatusCode::CONFLICT,
+            "Another parameter sweep is still running",
+        )
+            .into_response();
+    };
+    debug!(
+        "Parameter sweep {} started: {} configurations over {} to {}",
+        id, total, request.from, request.to
+    );
+
+    let sweeps = Arc::clone(&state.sweeps);
+    let data_source = Arc::clone(&state.data_source);
+    tokio::task::spawn_blocking(move || {
+        let result = run_sweep(data_source.as_ref(), &request, |completed, total| {
+            sweeps.update(id, SweepStatus::Running { completed, total });
+        });
+        let status = match result {
+            Ok(result) => SweepStatus::Done {
+                result: Box::new(result),
+            },
+            Err(e) => {
+                error!("Parameter sweep {} failed: {}", id, e);
+                SweepStatus::Failed {
+                    error: e.to_string(),
+                }
+            }
+        };
+        sweeps.update(id, status);
+    });
+
+    (
+        axum::http::StatusCode::ACCEPTED,
+        Json(SweepStartedResponse { id, total }),
+    )
+        .into_response()
+}
+
+/// Handler to get progress or the result of a parameter sweep
+pub async fn sweep_status_handler(
+    State(state): State<BacktestState>,
+    Path(id): Path<u64>,
+) -> impl IntoResponse {
+    match state.sweeps.get(id) {
+        Some(status) => Json(status).into_response(),
+        None => (axum::http::StatusCode::NOT_FOUND, "Unknown sweep").into_response(),
+    }
+}
+
+/// Parse a strategy id as used by the backtest page
+fn parse_strategy(s: &str) -> Result<StrategyChoice, String> {
+    match s {
+        "actual" => Ok(StrategyChoice::Actual),
+        "self_use" => Ok(StrategyChoice::SelfUse),
+        "winter_adaptive" => Ok(StrategyChoice::WinterAdaptive),
+        "optimal_hindsight" => Ok(StrategyChoice::OptimalHindsight),
+        _ => Err(format!("Unknown strategy: {s}")),
+    }
+}
+
+/// Measure both compared strategies against the optimal hindsight schedule,
+/// using self-use as the naive baseline.
+///
+/// This is informational, so a failure here does not fail the comparison.
+fn optimal_info(
+    state: &BacktestState,
+    date: NaiveDate,
+    left: &DayAnalysis,
+    right: &DayAnalysis,
+) -> Option<OptimalInfo> {
+    let run = |strategy| simulate_day(state.data_source.as_ref(), date, &strategy, None);
+    match (
+        run(StrategyChoice::SelfUse),
+        run(StrategyChoice::OptimalHindsight),
+    ) {
+        (Ok(baseline), Ok(optimal)) => Some(OptimalInfo {
+            net_cost_czk: optimal.net_cost_czk,
+            left: calculate_optimality_gap(&baseline, left, &optimal),
+            right: calculate_optimality_gap(&baseline, right, &optimal),
+        }),
+        (Err(e), _) | (_, Err(e)) => {
+            debug!("Optimal baseline unavailable for {}: {}", date, e);
+            None
+        }
+    }
+}
+
 /// Extract ingress path from request headers
 fn extract_ingress_path(headers: &axum::http::HeaderMap) -> String {
     headers
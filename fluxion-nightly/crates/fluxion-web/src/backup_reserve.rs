@@ -0,0 +1,198 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Backup reserve API.
+//!
+//! Provides endpoints for:
+//! - Showing the active backup reserve
+//! - Raising the minimum SOC for a time window (e.g. 80% for the next 12 hours ahead of a
+//!   storm warning or planned grid outage)
+//! - Clearing the reserve early
+//!
+//! The reserve is stored in the user control state, so it is validated, persisted and sent
+//! to the scheduler like fixed slots. It is dropped automatically once its window ends.
+
+use axum::{Json, extract::State, http::StatusCode};
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::UserControlChangeType;
+use fluxion_types::user_control::{BackupReserve, UserControlIssue};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::user_control_api::{UserControlApiState, UserControlChangeError};
+use utoipa::ToSchema;
+
+/// Response for GET /api/backup-reserve
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BackupReserveStatus {
+    /// Whether the reserve applies right now
+    pub active: bool,
+    #[schema(value_type = Option<Object>)]
+    pub reserve: Option<BackupReserve>,
+}
+
+/// GET /api/backup-reserve - Current backup reserve
+pub async fn get_backup_reserve(
+    State(state): State<UserControlApiState>,
+) -> Json<BackupReserveStatus> {
+    let now = Utc::now();
+    let reserve = state
+        .state
+        .read()
+        .backup_reserve
+        .clone()
+        .filter(|reserve| !reserve.has_passed(now));
+    Json(BackupReserveStatus {
+        active: reserve.as_ref().is_some_and(|reserve| reserve.covers(now)),
+        reserve,
+    })
+}
+
+/// Request for PUT /api/backup-reserve
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct SetBackupReserveRequest {
+    /// Minimum SOC to keep (%)
+    pub min_soc: f32,
+    /// Start of the window, defaults to now
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// End of the window
+    #[serde(default)]
+    pub until: Option<DateTime<Utc>>,
+    /// Length of the window from `from`, used when `until` is not given
+    #[serde(default)]
+    pub hours: Option<u32>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Response for PUT /api/backup-reserve
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SetBackupReserveResponse {
+    pub success: bool,
+    #[schema(value_type = Object)]
+    pub reserve: BackupReserve,
+    #[schema(value_type = Vec<Object>)]
+    pub warnings: Vec<UserControlIssue>,
+}
+
+/// PUT /api/backup-reserve - Set the backup reserve, replacing any existing one
+pub async fn set_backup_reserve(
+    State(state): State<UserControlApiState>,
+    Json(request): Json<SetBackupReserveRequest>,
+) -> Result<Json<SetBackupReserveResponse>, UserControlChangeError> {
+    let from = request.from.unwrap_or_else(Utc::now);
+    let until = match (request.until, request.hours) {
+        (Some(until), _) => until,
+        (None, Some(hours)) => from + Duration::hours(i64::from(hours)),
+        (None, None) => return Err(StatusCode::BAD_REQUEST.into()),
+    };
+    let reserve = BackupReserve::new(request.min_soc, from, until, request.reason);
+
+    let (_, warnings) =
+        state.update(UserControlChangeType::BackupReserveChanged, |user_state| {
+            user_state.backup_reserve = Some(reserve.clone());
+            Ok(())
+        })?;
+
+    info!(
+        "🔋 Backup reserve: Keeping {:.0}% from {} until {}",
+        reserve.min_soc,
+        reserve.from.format("%Y-%m-%d %H:%M"),
+        reserve.until.format("%Y-%m-%d %H:%M")
+    );
+
+    Ok(Json(SetBackupReserveResponse {
+        success: true,
+        reserve,
+        warnings,
+    }))
+}
+
+/// DELETE /api/backup-reserve - Clear the backup reserve
+pub async fn clear_backup_reserve(
+    State(state): State<UserControlApiState>,
+) -> Result<StatusCode, UserControlChangeError> {
+    state.update(UserControlChangeType::BackupReserveChanged, |user_state| {
+        user_state
+            .backup_reserve
+            .take()
+            .map(|_| ())
+            .ok_or(StatusCode::NOT_FOUND)
+    })?;
+
+    info!("🔋 Backup reserve: Cleared");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_types::UserControlState;
+
+    fn api_state(dir: &tempfile::TempDir) -> UserControlApiState {
+        UserControlApiState::new(
+            UserControlState::default(),
+            dir.path().join("user_control.json").to_string_lossy(),
+            None,
+        )
+    }
+
+    fn request(min_soc: f32, hours: Option<u32>) -> SetBackupReserveRequest {
+        SetBackupReserveRequest {
+            min_soc,
+            from: None,
+            until: None,
+            hours,
+            reason: Some("Storm warning".to_owned()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_and_clear_backup_reserve() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = api_state(&dir);
+
+        let Json(set) = set_backup_reserve(State(state.clone()), Json(request(80.0, Some(12))))
+            .await
+            .unwrap();
+        assert_eq!(set.reserve.until - set.reserve.from, Duration::hours(12));
+
+        let Json(status) = get_backup_reserve(State(state.clone())).await;
+        assert!(status.active);
+        assert_eq!(status.reserve, Some(set.reserve));
+
+        let status = clear_backup_reserve(State(state.clone())).await.unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert!(state.state.read().backup_reserve.is_none());
+        assert!(matches!(
+            clear_backup_reserve(State(state)).await,
+            Err(UserControlChangeError::Status(StatusCode::NOT_FOUND))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_backup_reserve() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = api_state(&dir);
+
+        let result = set_backup_reserve(State(state.clone()), Json(request(80.0, None))).await;
+        assert!(matches!(
+            result,
+            Err(UserControlChangeError::Status(StatusCode::BAD_REQUEST))
+        ));
+
+        let result = set_backup_reserve(State(state.clone()), Json(request(150.0, Some(12)))).await;
+        assert!(matches!(result, Err(UserControlChangeError::Conflict(_))));
+        assert!(state.state.read().backup_reserve.is_none());
+    }
+}
@@ -0,0 +1,239 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! White-label branding: product name, logo and color palette.
+//!
+//! Installers reselling FluxION set a [`BrandingConfig`]; [`install`] makes it
+//! available to every template (`crate::branding::product_name()` and
+//! friends), the mobile state response and the translations (`$product`).
+
+use axum::{
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use fluxion_i18n::{DEFAULT_PRODUCT_NAME, I18n};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing::{error, info};
+
+/// Branding applied across the web UI, mobile app and translations
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrandingConfig {
+    /// Shown instead of "FluxION" in page titles, headers and the mobile app
+    pub product_name: Option<String>,
+    /// PNG, JPEG, SVG or WebP file shown in the dashboard header
+    pub logo_path: Option<PathBuf>,
+    pub colors: BrandColors,
+}
+
+/// Colors replacing the default palette (`#rgb` or `#rrggbb`)
+///
+/// The high-contrast theme keeps its own palette.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrandColors {
+    /// Buttons, links and highlights
+    pub accent: Option<String>,
+    pub success: Option<String>,
+    pub warning: Option<String>,
+    pub error: Option<String>,
+    /// Page background
+    pub background: Option<String>,
+    /// Card and panel background
+    pub surface: Option<String>,
+    pub text: Option<String>,
+}
+
+impl BrandColors {
+    /// CSS variable of each color, in declaration order
+    fn variables(&self) -> [(&'static str, Option<&str>); 7] {
+        [
+            ("--info", self.accent.as_deref()),
+            ("--success", self.success.as_deref()),
+            ("--warning", self.warning.as_deref()),
+            ("--error", self.error.as_deref()),
+            ("--bg-primary", self.background.as_deref()),
+            ("--bg-secondary", self.surface.as_deref()),
+            ("--text-primary", self.text.as_deref()),
+        ]
+    }
+
+    /// Name and value of every color that is set
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        const NAMES: [&str; 7] = [
+            "accent",
+            "success",
+            "warning",
+            "error",
+            "background",
+            "surface",
+            "text",
+        ];
+        NAMES
+            .into_iter()
+            .zip(self.variables())
+            .filter_map(|(name, (_, value))| value.map(|v| (name, v)))
+    }
+}
+
+impl BrandingConfig {
+    /// Configured product name, or "FluxION"
+    #[must_use]
+    pub fn product_name(&self) -> &str {
+        self.product_name
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .unwrap_or(DEFAULT_PRODUCT_NAME)
+    }
+
+    /// `:root` overrides for the configured colors, empty without any
+    #[must_use]
+    pub fn palette_css(&self) -> String {
+        let mut declarations = String::new();
+        for (variable, value) in self.colors.variables() {
+            if let Some(value) = value.filter(|v| is_hex_color(v)) {
+                let _ = write!(declarations, "{variable}:{value};");
+            }
+        }
+        if declarations.is_empty() {
+            return declarations;
+        }
+        format!(":root:not([data-theme=\"high-contrast\"]){{{declarations}}}")
+    }
+}
+
+/// Whether `value` is a `#rgb` or `#rrggbb` color
+#[must_use]
+pub fn is_hex_color(value: &str) -> bool {
+    value
+        .strip_prefix('#')
+        .is_some_and(|hex| matches!(hex.len(), 3 | 6) && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+static BRANDING: OnceLock<BrandingConfig> = OnceLock::new();
+
+/// Apply `config` to the templates and pass its product name to `i18n`
+///
+/// Only the first call takes effect; branding does not change at runtime.
+pub fn install(config: BrandingConfig, i18n: &I18n) {
+    i18n.set_product_name(config.product_name());
+    if config != BrandingConfig::default() {
+        info!("🎨 Branding: {}", config.product_name());
+    }
+    let _ = BRANDING.set(config);
+}
+
+/// Branding in effect, the default until [`install`] runs
+#[must_use]
+pub fn current() -> &'static BrandingConfig {
+    BRANDING.get_or_init(BrandingConfig::default)
+}
+
+/// Product name for templates
+#[must_use]
+pub fn product_name() -> &'static str {
+    current().product_name()
+}
+
+/// Palette overrides for templates
+#[must_use]
+pub fn palette_css() -> String {
+    current().palette_css()
+}
+
+/// Whether a logo is configured
+#[must_use]
+pub fn has_logo() -> bool {
+    current().logo_path.is_some()
+}
+
+/// GET /branding/logo — the configured logo file
+pub async fn logo_handler() -> Response {
+    let Some(path) = &current().logo_path else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let content_type = match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("svg") => "image/svg+xml",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        _ => "image/png",
+    };
+    match tokio::fs::read(path).await {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, content_type),
+                (header::CACHE_CONTROL, "public, max-age=3600"),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to read logo {}: {e}", path.display());
+            StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn product_name_defaults_to_fluxion() {
+        let mut config = BrandingConfig::default();
+        assert_eq!(config.product_name(), "FluxION");
+        config.product_name = Some("  ".to_owned());
+        assert_eq!(config.product_name(), "FluxION");
+        config.product_name = Some("SolarBox".to_owned());
+        assert_eq!(config.product_name(), "SolarBox");
+    }
+
+    #[test]
+    fn palette_css_covers_set_colors_only() {
+        let mut config = BrandingConfig::default();
+        assert_eq!(config.palette_css(), "");
+
+        config.colors.accent = Some("#ff6600".to_owned());
+        config.colors.background = Some("#fff".to_owned());
+        config.colors.text = Some("red;}body{display:none".to_owned());
+        assert_eq!(
+            config.palette_css(),
+            ":root:not([data-theme=\"high-contrast\"]){--info:#ff6600;--bg-primary:#fff;}"
+        );
+        assert_eq!(
+            config.colors.iter().collect::<Vec<_>>(),
+            vec![
+                ("accent", "#ff6600"),
+                ("background", "#fff"),
+                ("text", "red;}body{display:none")
+            ]
+        );
+    }
+
+    #[test]
+    fn hex_colors() {
+        assert!(is_hex_color("#abc"));
+        assert!(is_hex_color("#A1B2C3"));
+        assert!(!is_hex_color("abc"));
+        assert!(!is_hex_color("#abcd"));
+        assert!(!is_hex_color("#ggg"));
+    }
+}
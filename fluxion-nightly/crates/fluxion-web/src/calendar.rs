@@ -0,0 +1,189 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Holiday and vacation calendar API.
+//!
+//! Provides endpoints for:
+//! - Listing the built-in Czech public holidays and the user's vacations
+//! - Adding a vacation range (local dates, both inclusive)
+//! - Removing a vacation
+//!
+//! Vacations are stored in the user control state, so they are validated,
+//! persisted and sent to the scheduler like fixed slots.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+};
+use chrono::{Datelike, NaiveDate, Utc};
+use fluxion_core::UserControlChangeType;
+use fluxion_types::calendar::{VacationRange, czech_public_holidays};
+use fluxion_types::user_control::UserControlIssue;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::user_control_api::{UserControlApiState, UserControlChangeError};
+use utoipa::ToSchema;
+
+/// A public holiday in API response format
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HolidayResponse {
+    pub date: NaiveDate,
+    pub name: &'static str,
+}
+
+/// Response for GET /api/calendar
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CalendarResponse {
+    /// Czech public holidays of this and next year
+    pub holidays: Vec<HolidayResponse>,
+    #[schema(value_type = Vec<Object>)]
+    pub vacations: Vec<VacationRange>,
+}
+
+/// GET /api/calendar - Public holidays and vacations
+pub async fn get_calendar(State(state): State<UserControlApiState>) -> Json<CalendarResponse> {
+    let year = Utc::now().year();
+    let holidays = (year..=year + 1)
+        .flat_map(czech_public_holidays)
+        .map(|(date, name)| HolidayResponse { date, name })
+        .collect();
+    let vacations = state.state.read().vacations.clone();
+    Json(CalendarResponse {
+        holidays,
+        vacations,
+    })
+}
+
+/// Request for POST /api/calendar/vacations
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateVacationRequest {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Response for POST /api/calendar/vacations
+#[derive(Debug, Serialize, ToSchema)]
+pub struct VacationResponse {
+    pub success: bool,
+    #[schema(value_type = Object)]
+    pub vacation: VacationRange,
+    #[schema(value_type = Vec<Object>)]
+    pub warnings: Vec<UserControlIssue>,
+}
+
+/// POST /api/calendar/vacations - Add a vacation
+pub async fn create_vacation(
+    State(state): State<UserControlApiState>,
+    Json(request): Json<CreateVacationRequest>,
+) -> Result<Json<VacationResponse>, UserControlChangeError> {
+    let vacation = VacationRange::new(request.start, request.end, request.note);
+    let (_, warnings) = state.update(UserControlChangeType::VacationsChanged, |user_state| {
+        user_state.vacations.push(vacation.clone());
+        Ok(())
+    })?;
+
+    info!(
+        "📅 Calendar: Added vacation {} from {} to {}",
+        vacation.id, vacation.start, vacation.end
+    );
+
+    Ok(Json(VacationResponse {
+        success: true,
+        vacation,
+        warnings,
+    }))
+}
+
+/// DELETE /api/calendar/vacations/:id - Remove a vacation
+pub async fn delete_vacation(
+    State(state): State<UserControlApiState>,
+    Path(vacation_id): Path<String>,
+) -> Result<StatusCode, UserControlChangeError> {
+    state.update(UserControlChangeType::VacationsChanged, |user_state| {
+        let before = user_state.vacations.len();
+        user_state.vacations.retain(|v| v.id != vacation_id);
+        if user_state.vacations.len() == before {
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Ok(())
+    })?;
+
+    info!("📅 Calendar: Removed vacation {}", vacation_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_types::UserControlState;
+
+    fn api_state(dir: &tempfile::TempDir) -> UserControlApiState {
+        UserControlApiState::new(
+            UserControlState::default(),
+            dir.path().join("user_control.json").to_string_lossy(),
+            None,
+        )
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_vacation() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = api_state(&dir);
+
+        let Json(created) = create_vacation(
+            State(state.clone()),
+            Json(CreateVacationRequest {
+                start: date(2025, 8, 1),
+                end: date(2025, 8, 14),
+                note: Some("Seaside".to_owned()),
+            }),
+        )
+        .await
+        .unwrap();
+        let Json(calendar) = get_calendar(State(state.clone())).await;
+        assert_eq!(calendar.vacations, vec![created.vacation.clone()]);
+        assert!(calendar.holidays.len() >= 26);
+
+        let status = delete_vacation(State(state.clone()), Path(created.vacation.id))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        assert_eq!(state.state.read().vacations, Vec::new());
+    }
+
+    #[tokio::test]
+    async fn test_rejects_inverted_vacation() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = api_state(&dir);
+
+        let result = create_vacation(
+            State(state.clone()),
+            Json(CreateVacationRequest {
+                start: date(2025, 8, 14),
+                end: date(2025, 8, 1),
+                note: None,
+            }),
+        )
+        .await;
+
+        assert!(matches!(result, Err(UserControlChangeError::Conflict(_))));
+        assert_eq!(state.state.read().vacations, Vec::new());
+    }
+}
@@ -0,0 +1,297 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+//! Long-range chart series from the historian.
+//!
+//! Each series is downsampled with Largest-Triangle-Three-Buckets so a year of
+//! data stays a payload the browser can draw while keeping peaks visible.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::historian::{Historian, HistoryPoint, HistoryResolution};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+/// Most points per series in a response
+const MAX_CHART_POINTS: usize = 1000;
+
+/// Time span shown by the long-range chart
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChartRange {
+    #[default]
+    #[serde(rename = "7d")]
+    Week,
+    #[serde(rename = "30d")]
+    Month,
+    #[serde(rename = "1y")]
+    Year,
+}
+
+impl ChartRange {
+    fn duration(self) -> Duration {
+        match self {
+            ChartRange::Week => Duration::days(7),
+            ChartRange::Month => Duration::days(30),
+            ChartRange::Year => Duration::days(365),
+        }
+    }
+}
+
+/// Historian resolution the series are read at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum ChartResolution {
+    /// Coarsest resolution that still gives more points than are drawn
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "15m")]
+    FifteenMinutes,
+    #[serde(rename = "1h")]
+    Hour,
+}
+
+impl ChartResolution {
+    fn resolve(self, range: ChartRange) -> HistoryResolution {
+        match self {
+            ChartResolution::Auto => match range {
+                ChartRange::Week => HistoryResolution::FifteenMinutes,
+                ChartRange::Month | ChartRange::Year => HistoryResolution::Hour,
+            },
+            ChartResolution::FiveMinutes => HistoryResolution::FiveMinutes,
+            ChartResolution::FifteenMinutes => HistoryResolution::FifteenMinutes,
+            ChartResolution::Hour => HistoryResolution::Hour,
+        }
+    }
+}
+
+/// Query parameters of `/chart-data/history`
+#[derive(Debug, Deserialize)]
+pub struct ChartHistoryQuery {
+    #[serde(default)]
+    pub range: ChartRange,
+    #[serde(default)]
+    pub resolution: ChartResolution,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct SeriesPoint {
+    pub timestamp: DateTime<Utc>,
+    pub value: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChartHistoryResponse {
+    pub range: ChartRange,
+    /// Resolution the series were read at before downsampling
+    pub resolution: HistoryResolution,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Battery SOC (%)
+    pub soc: Vec<SeriesPoint>,
+    /// PV power (W)
+    pub pv: Vec<SeriesPoint>,
+    /// Spot price (CZK/kWh)
+    pub price: Vec<SeriesPoint>,
+    /// Net grid cost since `from` (CZK), export at spot price counted as income
+    pub cost: Vec<SeriesPoint>,
+}
+
+/// Largest-Triangle-Three-Buckets downsampling to at most `threshold` points
+///
+/// Keeps the first and last point and from each bucket in between the point
+/// spanning the largest triangle with its neighbours.
+#[expect(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn lttb(points: &[SeriesPoint], threshold: usize) -> Vec<SeriesPoint> {
+    if threshold < 3 || points.len() <= threshold {
+        return points.to_vec();
+    }
+    let x = |p: &SeriesPoint| p.timestamp.timestamp() as f64;
+    let y = |p: &SeriesPoint| f64::from(p.value);
+    let every = (points.len() - 2) as f64 / (threshold - 2) as f64;
+    let edge = |i: usize| ((i as f64 * every) as usize + 1).min(points.len() - 1);
+
+    let mut sampled = Vec::with_capacity(threshold);
+    let mut a = &points[0];
+    sampled.push(*a);
+    for i in 0..threshold - 2 {
+        // Average of the next bucket is the third corner
+        let next = &points[edge(i + 1)..edge(i + 2).max(edge(i + 1) + 1)];
+        let len = next.len() as f64;
+        let avg_x = next.iter().map(x).sum::<f64>() / len;
+        let avg_y = next.iter().map(y).sum::<f64>() / len;
+
+        let area = |p: &SeriesPoint| {
+            ((x(a) - avg_x) * (y(p) - y(a)) - (x(a) - x(p)) * (avg_y - y(a))).abs()
+        };
+        let bucket = &points[edge(i)..edge(i + 1)];
+        if let Some(best) = bucket.iter().max_by(|p, q| area(p).total_cmp(&area(q))) {
+            sampled.push(*best);
+            a = best;
+        }
+    }
+    sampled.push(points[points.len() - 1]);
+    sampled
+}
+
+/// Cumulative grid cost over the history, each bucket at the price in effect at its start
+fn cumulative_cost(
+    history: &[HistoryPoint],
+    prices: &[(DateTime<Utc>, f32)],
+    bucket_hours: f32,
+) -> Vec<SeriesPoint> {
+    let mut total = 0.0;
+    let mut price_idx = 0;
+    history
+        .iter()
+        .map(|point| {
+            while prices
+                .get(price_idx + 1)
+                .is_some_and(|(start, _)| *start <= point.timestamp)
+            {
+                price_idx += 1;
+            }
+            let price = prices
+                .get(price_idx)
+                .filter(|(start, _)| *start <= point.timestamp)
+                .map_or(0.0, |(_, price)| *price);
+            // Grid power is positive while exporting
+            total -= point.grid_power_w / 1000.0 * bucket_hours * price;
+            SeriesPoint {
+                timestamp: point.timestamp,
+                value: total,
+            }
+        })
+        .collect()
+}
+
+fn series(history: &[HistoryPoint], value: impl Fn(&HistoryPoint) -> f32) -> Vec<SeriesPoint> {
+    let points: Vec<SeriesPoint> = history
+        .iter()
+        .map(|point| SeriesPoint {
+            timestamp: point.timestamp,
+            value: value(point),
+        })
+        .collect();
+    lttb(&points, MAX_CHART_POINTS)
+}
+
+/// GET /chart-data/history?range=7d|30d|1y&resolution=auto — downsampled long-range series
+pub async fn chart_history_handler(
+    State(historian): State<Historian>,
+    Query(query): Query<ChartHistoryQuery>,
+) -> Response {
+    let to = Utc::now();
+    let from = to - query.range.duration();
+    let resolution = query.resolution.resolve(query.range);
+
+    // SQLite calls block
+    let result = tokio::task::spawn_blocking(move || {
+        let history = historian.query(from, to, resolution)?;
+        historian
+            .query_prices(from, to)
+            .map(|prices| (history, prices))
+    })
+    .await;
+    let (history, prices) = match result {
+        Ok(Ok(data)) => data,
+        Ok(Err(e)) => {
+            error!("Failed to read chart history: {e:#}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+        Err(e) => {
+            error!("Chart history query failed: {e}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    #[expect(clippy::cast_precision_loss)]
+    let bucket_hours = resolution.bucket_secs().unwrap_or(3600) as f32 / 3600.0;
+    let price_points: Vec<SeriesPoint> = prices
+        .iter()
+        .map(|(timestamp, value)| SeriesPoint {
+            timestamp: (*timestamp).max(from),
+            value: *value,
+        })
+        .collect();
+    Json(ChartHistoryResponse {
+        range: query.range,
+        resolution,
+        from,
+        to,
+        soc: series(&history, |p| p.battery_soc),
+        pv: series(&history, |p| p.pv_power_w),
+        price: lttb(&price_points, MAX_CHART_POINTS),
+        cost: lttb(
+            &cumulative_cost(&history, &prices, bucket_hours),
+            MAX_CHART_POINTS,
+        ),
+    })
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(minutes: i64, value: f32) -> SeriesPoint {
+        let start: DateTime<Utc> = "2025-06-01T00:00:00Z".parse().unwrap();
+        SeriesPoint {
+            timestamp: start + Duration::minutes(minutes),
+            value,
+        }
+    }
+
+    #[test]
+    fn test_lttb_keeps_ends_and_peaks() {
+        let mut points: Vec<SeriesPoint> = (0..5000).map(|i| point(i, 10.0)).collect();
+        points[2500].value = 95.0;
+
+        let sampled = lttb(&points, 100);
+        assert_eq!(sampled.len(), 100);
+        assert_eq!(sampled[0], points[0]);
+        assert_eq!(sampled[99], points[4999]);
+        assert!(sampled.contains(&points[2500]));
+        assert!(sampled.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+    }
+
+    #[test]
+    fn test_cost_uses_price_of_block() {
+        let at = |minutes: i64, grid_power_w: f32| HistoryPoint {
+            timestamp: point(minutes, 0.0).timestamp,
+            battery_soc: 50.0,
+            battery_power_w: 0.0,
+            pv_power_w: 0.0,
+            grid_power_w,
+            house_load_w: 0.0,
+        };
+        // Import 4 kW for an hour at 2 CZK, then export 2 kW at 5 CZK
+        let history = [at(0, -4000.0), at(60, 2000.0)];
+        let prices = [
+            (point(0, 0.0).timestamp, 2.0),
+            (point(60, 0.0).timestamp, 5.0),
+        ];
+
+        let cost = cumulative_cost(&history, &prices, 1.0);
+        assert_eq!(cost[0].value, 8.0);
+        assert_eq!(cost[1].value, -2.0);
+    }
+}
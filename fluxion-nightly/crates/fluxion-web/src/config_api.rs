@@ -10,13 +10,16 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
-use crate::validation;
+use crate::{config_preview, validation};
 use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use fluxion_core::TimeFormatter;
 use fluxion_core::resources::SystemConfig;
+use fluxion_i18n::{DEFAULT_LOCALES_DIR, I18n, Language};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::info;
+use tracing::{info, warn};
+use utoipa::ToSchema;
 
 /// Shared state for config API endpoints
 #[derive(Clone)]
@@ -27,6 +30,8 @@ pub struct ConfigApiState {
     pub config_path: String,
     /// Sender for config update events to ECS
     pub config_update_sender: Option<fluxion_core::ConfigUpdateSender>,
+    /// Shared translations, switched in place when `system.language` changes
+    pub i18n: Arc<I18n>,
 }
 
 impl std::fmt::Debug for ConfigApiState {
@@ -35,6 +40,7 @@ impl std::fmt::Debug for ConfigApiState {
             .field("config", &"<RwLock>")
             .field("config_path", &self.config_path)
             .field("config_update_sender", &self.config_update_sender.is_some())
+            .field("language", &self.i18n.language())
             .finish()
     }
 }
@@ -45,26 +51,159 @@ impl ConfigApiState {
         config: serde_json::Value,
         config_path: impl Into<String>,
         config_update_sender: Option<fluxion_core::ConfigUpdateSender>,
+        i18n: Arc<I18n>,
     ) -> Self {
         Self {
             config: Arc::new(RwLock::new(config)),
             config_path: config_path.into(),
             config_update_sender,
+            i18n,
+        }
+    }
+
+    /// Write the config to persistent storage and notify the ECS
+    pub(crate) fn persist_and_notify(&self, config: &serde_json::Value) {
+        if let Err(e) = self.persist_as(config, "web_ui") {
+            // When running outside HA, persistence may fail - that's OK
+            info!(
+                "Configuration updated in memory (persistence skipped: {})",
+                e
+            );
+        }
+        self.notify(config);
+    }
+
+    /// Write the config, recording `modified_by`, and notify the ECS
+    ///
+    /// The ECS is only notified once the config has been saved.
+    pub(crate) fn persist_and_notify_as(
+        &self,
+        config: &serde_json::Value,
+        modified_by: &str,
+    ) -> std::io::Result<()> {
+        self.persist_as(config, modified_by)?;
+        self.notify(config);
+        Ok(())
+    }
+
+    /// Write the config to persistent storage, recording `modified_by`
+    ///
+    /// The file is replaced atomically so a crash never leaves a truncated config.
+    fn persist_as(&self, config: &serde_json::Value, modified_by: &str) -> std::io::Result<()> {
+        let persisted = serde_json::json!({
+            "config": config,
+            "metadata": {
+                "last_modified": chrono::Utc::now().to_rfc3339(),
+                "modified_by": modified_by,
+                "version": "1.0.0"
+            }
+        });
+
+        let temp_path = format!("{}.tmp", self.config_path);
+        std::fs::write(
+            &temp_path,
+            serde_json::to_string_pretty(&persisted).unwrap(),
+        )?;
+        std::fs::rename(&temp_path, &self.config_path)?;
+        info!("✅ Configuration updated and saved to {}", self.config_path);
+        Ok(())
+    }
+
+    /// Send the merged config to the ECS if a sender is available
+    fn notify(&self, config: &serde_json::Value) {
+        if let Some(sender) = &self.config_update_sender {
+            // Send the merged config (not the partial update)
+            let event = fluxion_core::ConfigUpdateEvent::full_update(config.clone());
+            if let Err(e) = sender.send_update(event) {
+                info!("Failed to send config update event to ECS: {e}");
+            } else {
+                info!("🔄 Configuration update event sent to ECS");
+            }
+        }
+    }
+
+    /// Current `system.debug_mode`
+    #[must_use]
+    pub fn debug_mode(&self) -> bool {
+        self.config
+            .read()
+            .pointer("/system/debug_mode")
+            .and_then(serde_json::Value::as_bool)
+            .unwrap_or(true)
+    }
+
+    /// Set `system.debug_mode`, persist it and apply it to the ECS
+    ///
+    /// Used by the web API and by external switches (MQTT) alike, so every
+    /// path goes through the same persisted config.
+    pub fn set_debug_mode(&self, enabled: bool) {
+        let mut current_config = self.config.write();
+        validation::merge_json(
+            &mut current_config,
+            serde_json::json!({ "system": { "debug_mode": enabled } }),
+        );
+        info!(
+            "🔍 Debug mode {} via config API",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        self.persist_and_notify(&current_config);
+    }
+
+    /// Switch the shared translations to the configured `system.language`
+    pub(crate) fn apply_language(&self, config: &serde_json::Value) {
+        let Some(language) = config
+            .pointer("/system/language")
+            .cloned()
+            .and_then(|value| serde_json::from_value::<Language>(value).ok())
+        else {
+            return;
+        };
+        if language == self.i18n.language() {
+            return;
+        }
+        match self.i18n.set_language(language) {
+            Ok(()) => info!("🌍 Switched UI language to {}", language.display_name()),
+            Err(e) => warn!("Failed to switch UI language to {}: {e}", language.code()),
         }
     }
 }
 
 /// Response for GET /api/config
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ConfigResponse {
     /// Current configuration
+    #[schema(value_type = Object)]
     pub config: serde_json::Value,
     /// Configuration metadata
     pub metadata: ConfigMetadataResponse,
+    /// Languages the UI can offer (embedded plus runtime locales)
+    pub available_languages: Vec<LanguageOption>,
+}
+
+/// A selectable UI language
+#[derive(Serialize, ToSchema)]
+pub struct LanguageOption {
+    /// Value for `system.language`
+    #[schema(value_type = String)]
+    pub id: Language,
+    /// Language code (e.g., "de")
+    pub code: &'static str,
+    /// Native display name
+    pub name: &'static str,
+}
+
+impl From<Language> for LanguageOption {
+    fn from(language: Language) -> Self {
+        Self {
+            id: language,
+            code: language.code(),
+            name: language.display_name(),
+        }
+    }
 }
 
 /// Configuration metadata
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ConfigMetadataResponse {
     /// When the config was last modified (in persistent storage)
     pub last_modified: Option<String>,
@@ -77,14 +216,15 @@ pub struct ConfigMetadataResponse {
 }
 
 /// Request body for POST /api/config/validate
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ValidateRequest {
     /// Configuration to validate
+    #[schema(value_type = Object)]
     pub config: serde_json::Value,
 }
 
 /// Response for POST /api/config/validate
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ValidateResponse {
     /// Whether the configuration is valid
     pub valid: bool,
@@ -97,7 +237,7 @@ pub struct ValidateResponse {
 }
 
 /// A validation issue
-#[derive(Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ValidationIssue {
     /// Field path (e.g., "control.min_battery_soc")
     pub field: String,
@@ -108,9 +248,10 @@ pub struct ValidationIssue {
 }
 
 /// Request body for POST /api/config/update
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateConfigRequest {
     /// New configuration
+    #[schema(value_type = Object)]
     pub config: serde_json::Value,
     /// Whether to create a backup before updating
     #[serde(default = "default_create_backup")]
@@ -122,7 +263,7 @@ fn default_create_backup() -> bool {
 }
 
 /// Response for POST /api/config/update
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct UpdateConfigResponse {
     /// Whether the update was successful
     pub success: bool,
@@ -139,7 +280,7 @@ pub struct UpdateConfigResponse {
 }
 
 /// Request body for POST /api/config/reset
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ResetSectionRequest {
     /// Section to reset (system, inverters, pricing, control, strategies)
     #[expect(dead_code, reason = "Section reset not yet implemented")]
@@ -182,6 +323,10 @@ pub async fn get_config_handler(
             version: "1.0.0".to_owned(),
             restart_required: false,
         },
+        available_languages: Language::available_in(std::path::Path::new(DEFAULT_LOCALES_DIR))
+            .into_iter()
+            .map(LanguageOption::from)
+            .collect(),
     }))
 }
 
@@ -190,34 +335,42 @@ pub async fn validate_config_handler(
     State(state): State<ConfigApiState>,
     Json(request): Json<ValidateRequest>,
 ) -> Json<ValidateResponse> {
-    // Merge partial config with current config
-    let mut config_to_validate = state.config.read().clone();
-    validation::merge_json(&mut config_to_validate, request.config);
+    let current = state.config.read().clone();
+    Json(config_preview::preview_config(&current, request.config).validation)
+}
 
-    // Parse as SystemConfig and validate
-    let validation = match serde_json::from_value::<SystemConfig>(config_to_validate) {
+/// Parse a merged config as SystemConfig and validate it
+///
+/// Returns the parsed config when it has a valid structure. `restart_required`
+/// is left for the caller, which knows what changed.
+pub(crate) fn validate_merged(
+    config: serde_json::Value,
+) -> (ValidateResponse, Option<SystemConfig>) {
+    match serde_json::from_value::<SystemConfig>(config) {
         Ok(config) => {
             let (errors, warnings) = validation::validate_config(&config);
-            ValidateResponse {
+            let response = ValidateResponse {
                 valid: errors.is_empty(),
                 errors,
                 warnings,
-                restart_required: false, // TODO: Check if restart needed based on changed fields
-            }
+                restart_required: false,
+            };
+            (response, Some(config))
         }
-        Err(e) => ValidateResponse {
-            valid: false,
-            errors: vec![ValidationIssue {
-                field: "config".to_owned(),
-                message: format!("Invalid configuration structure: {e}"),
-                severity: "error".to_owned(),
-            }],
-            warnings: Vec::new(),
-            restart_required: false,
-        },
-    };
-
-    Json(validation)
+        Err(e) => {
+            let response = ValidateResponse {
+                valid: false,
+                errors: vec![ValidationIssue {
+                    field: "config".to_owned(),
+                    message: format!("Invalid configuration structure: {e}"),
+                    severity: "error".to_owned(),
+                }],
+                warnings: Vec::new(),
+                restart_required: false,
+            };
+            (response, None)
+        }
+    }
 }
 
 /// POST /api/config/update - Update configuration
@@ -225,32 +378,11 @@ pub async fn update_config_handler(
     State(state): State<ConfigApiState>,
     Json(request): Json<UpdateConfigRequest>,
 ) -> Result<Json<UpdateConfigResponse>, StatusCode> {
-    // Merge partial config with current config
-    let mut config_to_validate = state.config.read().clone();
-    validation::merge_json(&mut config_to_validate, request.config.clone());
-
-    // Validate the new configuration
-    let validation = match serde_json::from_value::<SystemConfig>(config_to_validate) {
-        Ok(config) => {
-            let (errors, warnings) = validation::validate_config(&config);
-            ValidateResponse {
-                valid: errors.is_empty(),
-                errors,
-                warnings,
-                restart_required: false, // TODO: Check if restart needed
-            }
-        }
-        Err(e) => ValidateResponse {
-            valid: false,
-            errors: vec![ValidationIssue {
-                field: "config".to_owned(),
-                message: format!("Invalid configuration structure: {e}"),
-                severity: "error".to_owned(),
-            }],
-            warnings: Vec::new(),
-            restart_required: false,
-        },
-    };
+    // Validate the partial config merged with the current config
+    let current = state.config.read().clone();
+    let preview = config_preview::preview_config(&current, request.config.clone());
+    let restart_required = preview.restart_required;
+    let validation = preview.validation;
 
     if !validation.valid {
         return Ok(Json(UpdateConfigResponse {
@@ -266,7 +398,7 @@ pub async fn update_config_handler(
     // Create backup if requested
     let backup_id = if request.create_backup {
         // TODO: Implement backup creation
-        Some(chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string())
+        Some(time_formatter(&state.config.read()).file_stamp(chrono::Utc::now()))
     } else {
         None
     };
@@ -275,56 +407,93 @@ pub async fn update_config_handler(
     let mut current_config = state.config.write();
     validation::merge_json(&mut current_config, request.config);
 
-    // Save to persistent storage (optional when running outside HA)
-    let persisted = serde_json::json!({
-        "config": *current_config,
-        "metadata": {
-            "last_modified": chrono::Utc::now().to_rfc3339(),
-            "modified_by": "web_ui",
-            "version": "1.0.0"
-        }
-    });
-
-    match std::fs::write(
-        &state.config_path,
-        serde_json::to_string_pretty(&persisted).unwrap(),
-    ) {
-        Ok(()) => {
-            info!(
-                "✅ Configuration updated and saved to {}",
-                state.config_path
-            );
-        }
-        Err(e) => {
-            // When running outside HA, persistence may fail - that's OK
-            info!(
-                "Configuration updated in memory (persistence skipped: {})",
-                e
-            );
-        }
-    }
-
-    // Send ConfigUpdateEvent to ECS if sender is available
-    if let Some(sender) = &state.config_update_sender {
-        // Send the merged config (not the partial update)
-        let event = fluxion_core::ConfigUpdateEvent::full_update(current_config.clone());
-        if let Err(e) = sender.send_update(event) {
-            info!("Failed to send config update event to ECS: {e}");
-        } else {
-            info!("🔄 Configuration update event sent to ECS");
-        }
-    }
+    state.persist_and_notify(&current_config);
+    state.apply_language(&current_config);
 
     Ok(Json(UpdateConfigResponse {
         success: true,
         validation,
         backup_id,
         applied: true,
-        restart_required: false,
+        restart_required,
         error: None,
     }))
 }
 
+/// Request body for PUT /api/config/language
+#[derive(Deserialize, ToSchema)]
+pub struct SetLanguageRequest {
+    /// Language code or name (e.g., "cs", "czech")
+    pub language: String,
+}
+
+/// Response for PUT /api/config/language
+#[derive(Serialize, ToSchema)]
+pub struct SetLanguageResponse {
+    /// The language now in use
+    pub language: LanguageOption,
+}
+
+/// PUT /api/config/language - Switch the UI language without a restart
+pub async fn set_language_handler(
+    State(state): State<ConfigApiState>,
+    Json(request): Json<SetLanguageRequest>,
+) -> Result<Json<SetLanguageResponse>, (StatusCode, String)> {
+    let language = Language::from_code(&request.language)
+        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+    if !Language::available_in(std::path::Path::new(DEFAULT_LOCALES_DIR)).contains(&language) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "No locale files for {} in {DEFAULT_LOCALES_DIR}",
+                language.code()
+            ),
+        ));
+    }
+
+    state.i18n.set_language(language).map_err(|e| {
+        warn!("Failed to switch UI language to {}: {e}", language.code());
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    })?;
+    info!("🌍 Switched UI language to {}", language.display_name());
+
+    let mut current_config = state.config.write();
+    validation::merge_json(
+        &mut current_config,
+        serde_json::json!({ "system": { "language": language } }),
+    );
+    state.persist_and_notify(&current_config);
+
+    Ok(Json(SetLanguageResponse {
+        language: language.into(),
+    }))
+}
+
+/// Body for GET/PUT /api/config/debug-mode
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct DebugModeBody {
+    /// Whether FluxION only logs actions instead of executing them
+    pub enabled: bool,
+}
+
+/// GET /api/config/debug-mode - Current debug mode (for HA RESTful switches)
+pub async fn get_debug_mode_handler(State(state): State<ConfigApiState>) -> Json<DebugModeBody> {
+    Json(DebugModeBody {
+        enabled: state.debug_mode(),
+    })
+}
+
+/// PUT /api/config/debug-mode - Toggle debug mode without editing the whole config
+pub async fn set_debug_mode_handler(
+    State(state): State<ConfigApiState>,
+    Json(request): Json<DebugModeBody>,
+) -> Json<DebugModeBody> {
+    state.set_debug_mode(request.enabled);
+    Json(DebugModeBody {
+        enabled: state.debug_mode(),
+    })
+}
+
 /// POST /api/config/reset - Reset a configuration section to defaults
 pub async fn reset_section_handler(
     State(_state): State<ConfigApiState>,
@@ -351,12 +520,21 @@ pub async fn reset_section_handler(
     }))
 }
 
+/// Formatter for the HA timezone stored in the config (`system.timezone`)
+pub(crate) fn time_formatter(config: &serde_json::Value) -> TimeFormatter {
+    TimeFormatter::from_timezone_name(
+        config
+            .pointer("/system/timezone")
+            .and_then(serde_json::Value::as_str),
+    )
+}
+
 /// GET /api/config/export - Export configuration as downloadable file
 pub async fn export_config_handler(State(state): State<ConfigApiState>) -> impl IntoResponse {
     let config = state.config.read().clone();
     let filename = format!(
         "fluxion_config_{}.json",
-        chrono::Utc::now().format("%Y%m%d_%H%M%S")
+        time_formatter(&config).file_stamp(chrono::Utc::now())
     );
 
     let json_string = serde_json::to_string_pretty(&config).unwrap_or_default();
@@ -375,3 +553,64 @@ pub async fn export_config_handler(State(state): State<ConfigApiState>) -> impl
 
     (headers, json_string)
 }
+
+/// GET /api/logs/download - Download the current (most recent) log file
+pub async fn download_log_handler(State(state): State<ConfigApiState>) -> impl IntoResponse {
+    let directory = state
+        .config
+        .read()
+        .pointer("/logging/directory")
+        .and_then(serde_json::Value::as_str)
+        .map_or_else(
+            || fluxion_core::LoggingConfigCore::default().directory,
+            ToOwned::to_owned,
+        );
+
+    let Some(path) = latest_log_file(std::path::Path::new(&directory)) else {
+        return (
+            StatusCode::NOT_FOUND,
+            "No log file found. Enable file logging in the logging configuration.",
+        )
+            .into_response();
+    };
+
+    let contents = match tokio::fs::read(&path).await {
+        Ok(contents) => contents,
+        Err(e) => {
+            warn!("Failed to read log file {}: {e}", path.display());
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to read log file").into_response();
+        }
+    };
+
+    let filename = path
+        .file_name()
+        .map_or_else(|| "fluxion.log".into(), |n| n.to_string_lossy());
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "text/plain; charset=utf-8".parse().unwrap(),
+    );
+    if let Ok(value) = format!("attachment; filename=\"{filename}\"").parse() {
+        headers.insert(axum::http::header::CONTENT_DISPOSITION, value);
+    }
+
+    (headers, contents).into_response()
+}
+
+/// Find the most recently modified `fluxion*.log` file in the log directory
+fn latest_log_file(directory: &std::path::Path) -> Option<std::path::PathBuf> {
+    std::fs::read_dir(directory)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            name.starts_with("fluxion") && name.ends_with(".log")
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
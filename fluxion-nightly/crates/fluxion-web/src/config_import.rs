@@ -0,0 +1,345 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+//! Import of a previously exported configuration.
+//!
+//! Accepts the JSON from `/api/config/export` (or a copy of
+//! `/data/config.json` with its `metadata`) as the raw body or as a file in a
+//! multipart form. The config is migrated to the current schema and validated
+//! in full before it replaces the running one, so a setup can be moved between
+//! Home Assistant instances.
+
+use axum::Json;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Multipart, Request, State};
+use axum::http::{StatusCode, header};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::config_api::{ConfigApiState, ValidateResponse, ValidationIssue, validate_merged};
+use crate::config_preview::{ChangeImpact, ConfigChange, diff_config};
+use utoipa::ToSchema;
+
+/// Response for POST /api/config/import
+#[derive(Serialize, ToSchema)]
+pub struct ImportConfigResponse {
+    /// Whether the imported config was applied
+    pub success: bool,
+    pub validation: ValidateResponse,
+    /// Schema version of the uploaded config when it had to be migrated
+    pub migrated_from: Option<u32>,
+    /// Differences to the config that was running before
+    pub changes: Vec<ConfigChange>,
+    /// Whether some changes take effect after a restart
+    pub restart_required: bool,
+    /// Error message if the import failed
+    pub error: Option<String>,
+}
+
+impl ImportConfigResponse {
+    fn failed(validation: ValidateResponse, migrated_from: Option<u32>, error: String) -> Self {
+        Self {
+            success: false,
+            validation,
+            migrated_from,
+            changes: Vec::new(),
+            restart_required: false,
+            error: Some(error),
+        }
+    }
+}
+
+fn unreadable(message: String) -> ImportConfigResponse {
+    ImportConfigResponse::failed(
+        ValidateResponse {
+            valid: false,
+            errors: vec![ValidationIssue {
+                field: "config".to_owned(),
+                message: message.clone(),
+                severity: "error".to_owned(),
+            }],
+            warnings: Vec::new(),
+            restart_required: false,
+        },
+        None,
+        message,
+    )
+}
+
+/// Content of the uploaded file, or of the first field without a file
+async fn multipart_file(mut multipart: Multipart) -> Result<Bytes, String> {
+    let mut first = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| format!("Invalid multipart upload: {e}"))?
+    {
+        let is_file = field.file_name().is_some();
+        let content = field
+            .bytes()
+            .await
+            .map_err(|e| format!("Invalid multipart upload: {e}"))?;
+        if is_file {
+            return Ok(content);
+        }
+        first.get_or_insert(content);
+    }
+    first.ok_or_else(|| "No file found in the multipart upload".to_owned())
+}
+
+/// Config JSON from a raw or multipart request body
+pub async fn upload_json(request: Request) -> Result<serde_json::Value, String> {
+    let is_multipart = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| {
+            content_type
+                .to_ascii_lowercase()
+                .starts_with("multipart/form-data")
+        });
+    let body = if is_multipart {
+        let multipart = Multipart::from_request(request, &())
+            .await
+            .map_err(|e| e.body_text())?;
+        multipart_file(multipart).await?
+    } else {
+        Bytes::from_request(request, &())
+            .await
+            .map_err(|e| e.body_text())?
+    };
+    let body = std::str::from_utf8(&body).map_err(|_| "Upload is not UTF-8 text".to_owned())?;
+    serde_json::from_str(body).map_err(|e| format!("Upload is not valid JSON: {e}"))
+}
+
+/// Migrate and validate an uploaded config against the running one
+///
+/// Returns the normalized config to apply when it is valid.
+pub fn prepare_import(
+    current: &serde_json::Value,
+    mut uploaded: serde_json::Value,
+) -> (ImportConfigResponse, Option<serde_json::Value>) {
+    // Copies of /data/config.json wrap the config with its metadata
+    if uploaded.get("metadata").is_some()
+        && let Some(config) = uploaded.get_mut("config").filter(|c| c.is_object())
+    {
+        uploaded = config.take();
+    }
+
+    let report = fluxion_core::migrate_config(&mut uploaded);
+    let migrated_from = report.migrated().then_some(report.from_version);
+    if report.is_newer() {
+        return (
+            unreadable(format!(
+                "Config schema version {} comes from a newer FluxION (this one supports {})",
+                report.from_version,
+                fluxion_core::CONFIG_SCHEMA_VERSION
+            )),
+            None,
+        );
+    }
+
+    let (mut validation, parsed) = validate_merged(uploaded);
+    let normalized = parsed.and_then(|config| serde_json::to_value(config).ok());
+    let Some(normalized) = normalized.filter(|_| validation.valid) else {
+        return (
+            ImportConfigResponse::failed(
+                validation,
+                migrated_from,
+                "Configuration validation failed".to_owned(),
+            ),
+            None,
+        );
+    };
+
+    let changes = diff_config(current, &normalized);
+    let restart_required = changes
+        .iter()
+        .any(|c| c.impact == ChangeImpact::RequiresRestart);
+    validation.restart_required = restart_required;
+    let response = ImportConfigResponse {
+        success: true,
+        validation,
+        migrated_from,
+        changes,
+        restart_required,
+        error: None,
+    };
+    (response, Some(normalized))
+}
+
+/// POST /api/config/import - Replace the configuration with an exported one
+pub async fn import_config_handler(
+    State(state): State<ConfigApiState>,
+    request: Request,
+) -> (StatusCode, Json<ImportConfigResponse>) {
+    let uploaded = match upload_json(request).await {
+        Ok(uploaded) => uploaded,
+        Err(message) => return (StatusCode::BAD_REQUEST, Json(unreadable(message))),
+    };
+
+    let mut current_config = state.config.write();
+    let (response, config) = prepare_import(&current_config, uploaded);
+    let Some(config) = config else {
+        return (StatusCode::UNPROCESSABLE_ENTITY, Json(response));
+    };
+
+    info!(
+        "📥 Importing configuration ({} changed keys)",
+        response.changes.len()
+    );
+    // Only replace the running config once it is saved, so a restart keeps it
+    if let Err(e) = state.persist_and_notify_as(&config, "import") {
+        warn!("Failed to save the imported configuration: {e}");
+        let message = format!("Failed to save configuration: {e}");
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ImportConfigResponse::failed(
+                response.validation,
+                response.migrated_from,
+                message,
+            )),
+        );
+    }
+    *current_config = config;
+    state.apply_language(&current_config);
+
+    (StatusCode::OK, Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_i18n::{I18n, Language};
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn upload(content_type: &str, body: &'static str) -> Request {
+        Request::builder()
+            .method("POST")
+            .uri("/api/config/import")
+            .header(header::CONTENT_TYPE, content_type)
+            .body(axum::body::Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_upload_json_from_multipart() {
+        let body = "--XyZ\r\n\
+            Content-Disposition: form-data; name=\"note\"\r\n\r\n\
+            moving house\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"file\"; filename=\"fluxion_config.json\"\r\n\
+            Content-Type: application/json\r\n\r\n\
+            {\"system\": {\"debug_mode\": true}}\r\n\
+            --XyZ--\r\n";
+
+        let json = upload_json(upload("multipart/form-data; boundary=\"XyZ\"", body))
+            .await
+            .unwrap();
+
+        assert_eq!(json, json!({ "system": { "debug_mode": true } }));
+        assert_eq!(
+            upload_json(upload("application/json", "{\"a\": 1}"))
+                .await
+                .unwrap(),
+            json!({ "a": 1 })
+        );
+        assert!(
+            upload_json(upload("application/json", "not json"))
+                .await
+                .is_err()
+        );
+        assert!(
+            upload_json(upload("multipart/form-data; boundary=XyZ", "--XyZ--\r\n"))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_save_is_a_server_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = ConfigApiState::new(
+            json!({}),
+            dir.path()
+                .join("missing")
+                .join("config.json")
+                .display()
+                .to_string(),
+            None,
+            Arc::new(I18n::new(Language::English).unwrap()),
+        );
+        let config = json!({
+            "inverters": [],
+            "pricing": {
+                "spot_price_entity": "sensor.spot_price",
+                "use_spot_prices_to_buy": true,
+                "use_spot_prices_to_sell": true,
+                "fixed_buy_price_czk": 4.0,
+                "fixed_sell_price_czk": 2.0,
+            },
+            "control": fluxion_core::ControlConfig::default(),
+            "system": {
+                "update_interval_secs": 60,
+                "debug_mode": true,
+                "display_currency": "CZK",
+            },
+        });
+        let request = Request::builder()
+            .method("POST")
+            .uri("/api/config/import")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(config.to_string()))
+            .unwrap();
+
+        let (status, Json(response)) = import_config_handler(State(state.clone()), request).await;
+
+        assert_eq!(
+            status,
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "{:?}",
+            response.error
+        );
+        assert!(!response.success);
+        assert!(
+            response
+                .error
+                .is_some_and(|e| e.starts_with("Failed to save configuration"))
+        );
+        // The running config is left alone
+        assert_eq!(*state.config.read(), json!({}));
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let uploaded = json!({ "schema_version": fluxion_core::CONFIG_SCHEMA_VERSION + 1 });
+
+        let (response, config) = prepare_import(&json!({}), uploaded);
+
+        assert!(!response.success);
+        assert_eq!(config, None);
+    }
+
+    #[test]
+    fn test_invalid_structure_is_rejected() {
+        let uploaded = json!({
+            "config": { "inverters": "main" },
+            "metadata": { "modified_by": "web_ui" }
+        });
+
+        let (response, config) = prepare_import(&json!({}), uploaded);
+
+        assert!(!response.success);
+        assert_eq!(response.migrated_from, Some(1));
+        assert_eq!(config, None);
+    }
+}
@@ -0,0 +1,228 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+//! Dry-run preview of a config change.
+//!
+//! Validates a proposed (partial) config against the running one and lists
+//! every changed key with its old and new value, classified by how the change
+//! takes effect, so the UI can show users what they are about to save.
+
+use axum::{Json, extract::State};
+use serde::Serialize;
+
+use crate::config_api::{ConfigApiState, ValidateRequest, ValidateResponse, validate_merged};
+use crate::validation;
+use utoipa::ToSchema;
+
+/// Keys read once at startup; changes take effect after a restart
+const RESTART_PATHS: &[&str] = &[
+    "inverters",
+    "system.update_interval_secs",
+    "history",
+    "remote_access",
+];
+
+/// Keys that change what the inverters are allowed to do
+const SAFETY_PATHS: &[&str] = &[
+    "inverters",
+    "system.debug_mode",
+    "control.min_battery_soc",
+    "control.max_battery_soc",
+    "control.hardware_min_battery_soc",
+    "control.maximum_export_power_w",
+    "control.inverter_max_ac_power_w",
+    "control.max_battery_charge_rate_kw",
+    "control.max_grid_import_kw",
+    "control.export_cap_windows",
+    "control.safe_state_mode",
+    "control.schedule_guard",
+    "peak_demand",
+];
+
+/// How a changed key takes effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeImpact {
+    /// Applied to the running system and the next planning cycle
+    HotApply,
+    /// Read at startup, applied after a restart
+    RequiresRestart,
+}
+
+/// One changed config key
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ConfigChange {
+    /// Dotted path (e.g., "control.min_battery_soc")
+    pub path: String,
+    /// `None` when the key is new
+    #[schema(value_type = Option<Object>)]
+    pub old: Option<serde_json::Value>,
+    /// `None` when the key is removed
+    #[schema(value_type = Option<Object>)]
+    pub new: Option<serde_json::Value>,
+    pub impact: ChangeImpact,
+    /// Changes limits or protections of the battery and the grid connection
+    pub affects_safety: bool,
+}
+
+/// Response for POST /api/config/preview
+#[derive(Serialize, ToSchema)]
+pub struct ConfigPreviewResponse {
+    pub validation: ValidateResponse,
+    pub changes: Vec<ConfigChange>,
+    /// Whether any change needs a restart
+    pub restart_required: bool,
+    /// Whether any change affects safety
+    pub affects_safety: bool,
+}
+
+/// Whether `path` is `prefix` or lies below it
+fn under(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+fn change(
+    path: String,
+    old: Option<serde_json::Value>,
+    new: Option<serde_json::Value>,
+) -> ConfigChange {
+    ConfigChange {
+        impact: if RESTART_PATHS.iter().any(|p| under(&path, p)) {
+            ChangeImpact::RequiresRestart
+        } else {
+            ChangeImpact::HotApply
+        },
+        affects_safety: SAFETY_PATHS.iter().any(|p| under(&path, p)),
+        path,
+        old,
+        new,
+    }
+}
+
+/// Leaf differences between two configs; arrays are compared as a whole
+pub fn diff_config(old: &serde_json::Value, new: &serde_json::Value) -> Vec<ConfigChange> {
+    let mut changes = Vec::new();
+    diff_into(&mut changes, "", Some(old), Some(new));
+    changes
+}
+
+fn diff_into(
+    changes: &mut Vec<ConfigChange>,
+    path: &str,
+    old: Option<&serde_json::Value>,
+    new: Option<&serde_json::Value>,
+) {
+    if old == new {
+        return;
+    }
+    if let (Some(serde_json::Value::Object(old)), Some(serde_json::Value::Object(new))) = (old, new)
+    {
+        let mut keys: Vec<&String> = old.keys().chain(new.keys()).collect();
+        keys.sort();
+        keys.dedup();
+        for key in keys {
+            let child = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            diff_into(changes, &child, old.get(key), new.get(key));
+        }
+        return;
+    }
+    changes.push(change(path.to_owned(), old.cloned(), new.cloned()));
+}
+
+/// Validate `proposed` merged into `current` and diff the result against `current`
+pub fn preview_config(
+    current: &serde_json::Value,
+    proposed: serde_json::Value,
+) -> ConfigPreviewResponse {
+    let mut merged = current.clone();
+    validation::merge_json(&mut merged, proposed);
+
+    let (mut validation, parsed) = validate_merged(merged.clone());
+    // Compare the normalized config when it parses so defaulted keys don't show up
+    let merged = parsed
+        .and_then(|config| serde_json::to_value(config).ok())
+        .unwrap_or(merged);
+    let changes = diff_config(current, &merged);
+    let restart_required = changes
+        .iter()
+        .any(|c| c.impact == ChangeImpact::RequiresRestart);
+    validation.restart_required = restart_required;
+
+    ConfigPreviewResponse {
+        validation,
+        affects_safety: changes.iter().any(|c| c.affects_safety),
+        restart_required,
+        changes,
+    }
+}
+
+/// POST /api/config/preview - Validate a proposed config and list what would change
+pub async fn preview_config_handler(
+    State(state): State<ConfigApiState>,
+    Json(request): Json<ValidateRequest>,
+) -> Json<ConfigPreviewResponse> {
+    let current = state.config.read().clone();
+    Json(preview_config(&current, request.config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_diff_lists_changed_leaves() {
+        let old = json!({
+            "control": { "min_battery_soc": 10.0, "force_charge_hours": 4 },
+            "inverters": [{ "id": "main" }],
+            "system": { "debug_mode": true }
+        });
+        let new = json!({
+            "control": { "min_battery_soc": 20.0, "force_charge_hours": 4, "evening_target_soc": 80.0 },
+            "inverters": [{ "id": "main" }, { "id": "garage" }],
+            "system": { "debug_mode": true }
+        });
+
+        let changes = diff_config(&old, &new);
+
+        let paths: Vec<&str> = changes.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "control.evening_target_soc",
+                "control.min_battery_soc",
+                "inverters"
+            ]
+        );
+        assert_eq!(changes[0].old, None);
+        assert_eq!(changes[1].old, Some(json!(10.0)));
+        assert_eq!(changes[1].new, Some(json!(20.0)));
+        assert_eq!(changes[0].impact, ChangeImpact::HotApply);
+        assert!(!changes[0].affects_safety);
+        assert!(changes[1].affects_safety);
+        assert_eq!(changes[2].impact, ChangeImpact::RequiresRestart);
+    }
+
+    #[test]
+    fn test_prefix_match_respects_key_boundaries() {
+        assert!(under("peak_demand.limit_kw", "peak_demand"));
+        assert!(under("inverters", "inverters"));
+        assert!(!under(
+            "control.min_battery_soc_extra",
+            "control.min_battery_soc"
+        ));
+    }
+}
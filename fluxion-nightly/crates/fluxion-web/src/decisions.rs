@@ -0,0 +1,163 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Audit log of executed block decisions.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::decision_log::{DecisionLog, DecisionRecord};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+/// Query parameters of `/api/decisions` (RFC 3339 timestamps)
+#[derive(Debug, Deserialize)]
+pub struct DecisionsQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecisionsResponse {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub decisions: Vec<DecisionRecord>,
+}
+
+/// GET /api/decisions?from=&to= — executed blocks starting in the range (default: last 24 hours)
+pub async fn decisions_handler(
+    State(log): State<DecisionLog>,
+    Query(query): Query<DecisionsQuery>,
+) -> Response {
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - Duration::hours(24));
+    if from >= to {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "`from` must be before `to`" })),
+        )
+            .into_response();
+    }
+
+    // SQLite calls block
+    match tokio::task::spawn_blocking(move || log.query(from, to)).await {
+        Ok(Ok(decisions)) => Json(DecisionsResponse {
+            from,
+            to,
+            decisions,
+        })
+        .into_response(),
+        Ok(Err(e)) => {
+            error!("Failed to query decision log: {e:#}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+        Err(e) => {
+            error!("Decision log query failed: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_types::inverter::InverterOperationMode;
+
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn record(block_start: &str, executed_mode: InverterOperationMode) -> DecisionRecord {
+        DecisionRecord {
+            inverter_id: "main".to_owned(),
+            block_start: block_start.parse().unwrap(),
+            duration_minutes: 15,
+            planned_mode: InverterOperationMode::ForceCharge,
+            executed_mode,
+            reason: "Cheapest block".to_owned(),
+            decision_uid: None,
+            override_reason: None,
+            debug_mode: false,
+            soc_start: Some(40.0),
+            predicted_soc_end: Some(45.0),
+            actual_soc_end: None,
+        }
+    }
+
+    fn query(from: &str, to: &str) -> Query<DecisionsQuery> {
+        Query(DecisionsQuery {
+            from: Some(from.parse().unwrap()),
+            to: Some(to.parse().unwrap()),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_decisions_in_range_are_listed() {
+        let log = DecisionLog::open_in_memory(30).unwrap();
+        log.record(&record(
+            "2025-06-01T10:00:00Z",
+            InverterOperationMode::ForceCharge,
+        ))
+        .unwrap();
+        log.record(&record(
+            "2025-06-01T10:15:00Z",
+            InverterOperationMode::SelfUse,
+        ))
+        .unwrap();
+        log.record(&record(
+            "2025-06-01T12:00:00Z",
+            InverterOperationMode::SelfUse,
+        ))
+        .unwrap();
+
+        let response = decisions_handler(
+            State(log),
+            query("2025-06-01T10:00:00Z", "2025-06-01T11:00:00Z"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let decisions = body(response).await;
+        let decisions = decisions["decisions"].as_array().unwrap();
+        assert_eq!(decisions.len(), 2);
+        assert_eq!(decisions[0]["inverter_id"], "main");
+        assert_eq!(decisions[1]["block_start"], "2025-06-01T10:15:00Z");
+    }
+
+    #[tokio::test]
+    async fn test_empty_range_lists_nothing() {
+        let response = decisions_handler(
+            State(DecisionLog::open_in_memory(30).unwrap()),
+            query("2025-06-01T10:00:00Z", "2025-06-01T11:00:00Z"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body(response).await["decisions"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_inverted_range_is_rejected() {
+        let response = decisions_handler(
+            State(DecisionLog::open_in_memory(30).unwrap()),
+            query("2025-06-01T11:00:00Z", "2025-06-01T10:00:00Z"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
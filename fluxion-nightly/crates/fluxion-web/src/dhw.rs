@@ -0,0 +1,53 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Hot water target temperature plan.
+
+use axum::{Json, Router, extract::State, response::IntoResponse, routing::get};
+use chrono::Utc;
+use fluxion_core::dhw::DhwPlanner;
+
+/// GET /api/dhw — planned hot water targets and the last written one
+async fn dhw_plan_handler(State(planner): State<DhwPlanner>) -> impl IntoResponse {
+    Json(planner.overview(Utc::now()))
+}
+
+/// Build the router for the hot water plan.
+pub fn dhw_routes(planner: DhwPlanner) -> Router {
+    Router::new()
+        .route("/api/dhw", get(dhw_plan_handler))
+        .with_state(planner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_disabled_planner_reports_empty_plan() {
+        let response = dhw_routes(DhwPlanner::default())
+            .oneshot(Request::get("/api/dhw").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let overview: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(overview["enabled"], false);
+        assert_eq!(overview["plan"], serde_json::json!([]));
+    }
+}
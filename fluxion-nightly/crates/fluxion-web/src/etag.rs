@@ -0,0 +1,124 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! ETag / If-None-Match support for heavy JSON endpoints.
+//!
+//! The response body is hashed and sent as a weak ETag (weak because the
+//! compression layer may re-encode the body). When the client already has the
+//! same representation, a bodyless `304 Not Modified` is returned instead,
+//! which matters over HA ingress and Tor where bandwidth is limited.
+
+use axum::{
+    body::{Body, to_bytes},
+    extract::Request,
+    http::{HeaderValue, Method, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use tracing::{error, trace};
+
+/// Compute a weak ETag for a response body
+fn weak_etag(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    // 16 bytes of the digest are plenty to detect changes
+    let hex = digest[..16]
+        .iter()
+        .fold(String::with_capacity(32), |mut out, byte| {
+            let _ = write!(out, "{byte:02x}");
+            out
+        });
+    format!("W/\"{hex}\"")
+}
+
+/// Check whether an `If-None-Match` header value matches the ETag
+fn if_none_match_matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = etag.trim_start_matches("W/");
+    if_none_match
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == opaque)
+}
+
+/// Middleware adding an ETag to successful GET responses and answering
+/// `If-None-Match` with `304 Not Modified`.
+pub async fn etag_middleware(request: Request, next: Next) -> Response {
+    if request.method() != Method::GET {
+        return next.run(request).await;
+    }
+
+    let if_none_match = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(ToOwned::to_owned);
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            error!("Failed to buffer response body for ETag: {e}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let etag = weak_etag(&bytes);
+    let Ok(etag_value) = HeaderValue::from_str(&etag) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    if if_none_match.is_some_and(|inm| if_none_match_matches(&inm, &etag)) {
+        trace!("ETag {etag} matched, returning 304");
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag_value)]).into_response();
+    }
+
+    parts.headers.insert(header::ETAG, etag_value);
+    parts
+        .headers
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weak_etag_is_stable() {
+        let a = weak_etag(b"{\"prices\":[1,2,3]}");
+        let b = weak_etag(b"{\"prices\":[1,2,3]}");
+        let c = weak_etag(b"{\"prices\":[1,2,4]}");
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(a.starts_with("W/\""));
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = weak_etag(b"data");
+        assert!(if_none_match_matches(&etag, &etag));
+        // Strong form of the same tag still matches (weak comparison)
+        assert!(if_none_match_matches(etag.trim_start_matches("W/"), &etag));
+        assert!(if_none_match_matches(
+            &format!("W/\"other\", {etag}"),
+            &etag
+        ));
+        assert!(if_none_match_matches("*", &etag));
+        assert!(!if_none_match_matches("W/\"other\"", &etag));
+    }
+}
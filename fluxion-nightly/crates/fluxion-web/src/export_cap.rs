@@ -0,0 +1,118 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Export cap window compliance report.
+
+use axum::{
+    Json,
+    extract::State,
+    response::{IntoResponse, Response},
+};
+use fluxion_core::export_cap::{ComplianceStatus, ExportCapMonitor, WindowReport};
+use std::fmt::Write as _;
+
+/// GET /api/export-cap — configured windows and measured export during each
+pub async fn export_cap_handler(State(monitor): State<ExportCapMonitor>) -> Response {
+    Json(monitor.report()).into_response()
+}
+
+/// GET /api/export-cap/report.csv — compliance report for the distributor
+pub async fn export_cap_csv_handler(State(monitor): State<ExportCapMonitor>) -> Response {
+    let csv = compliance_csv(&monitor.report().windows);
+
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "text/csv; charset=utf-8".parse().unwrap(),
+    );
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        "attachment; filename=\"export_cap_compliance.csv\""
+            .parse()
+            .unwrap(),
+    );
+
+    (headers, csv).into_response()
+}
+
+fn compliance_csv(windows: &[WindowReport]) -> String {
+    let mut csv = String::from(
+        "start,end,max_export_w,status,peak_export_w,exported_kwh,seconds_over_cap,first_violation_at,note\n",
+    );
+    for report in windows {
+        let status = match report.status {
+            ComplianceStatus::Upcoming => "upcoming",
+            ComplianceStatus::Active => "active",
+            ComplianceStatus::Compliant => "compliant",
+            ComplianceStatus::Violated => "violated",
+            ComplianceStatus::NotMonitored => "not_monitored",
+        };
+        let c = &report.compliance;
+        let _ = writeln!(
+            csv,
+            "{},{},{},{status},{:.0},{:.3},{},{},{}",
+            c.window.start.to_rfc3339(),
+            c.window.end.to_rfc3339(),
+            c.window.max_export_w,
+            c.peak_export_w,
+            c.exported_kwh,
+            c.seconds_over_cap,
+            c.first_violation_at
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+            c.window
+                .note
+                .as_deref()
+                .unwrap_or_default()
+                .replace(',', " "),
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use fluxion_core::export_cap::WindowCompliance;
+    use fluxion_core::resources::ExportCapWindow;
+
+    #[test]
+    fn test_compliance_csv_rows() {
+        let start = Utc.with_ymd_and_hms(2025, 6, 1, 8, 0, 0).unwrap();
+        let windows = vec![WindowReport {
+            status: ComplianceStatus::Compliant,
+            compliance: WindowCompliance {
+                window: ExportCapWindow {
+                    start,
+                    end: start + chrono::Duration::hours(2),
+                    max_export_w: 0,
+                    note: Some("ČEZ test, feeder 12".to_owned()),
+                },
+                samples: 720,
+                peak_export_w: 31.4,
+                exported_kwh: 0.012,
+                seconds_over_cap: 0,
+                first_violation_at: None,
+            },
+        }];
+
+        let csv = compliance_csv(&windows);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[1],
+            "2025-06-01T08:00:00+00:00,2025-06-01T10:00:00+00:00,0,compliant,31,0.012,0,,ČEZ test  feeder 12"
+        );
+    }
+}
@@ -0,0 +1,471 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Tabular exports (CSV and Parquet).
+//!
+//! The compact JSON export nests everything in one document. For pandas or
+//! Excel the same data is flattened into two tables: one row per price block
+//! (plan, forecast and realized energy) and one row per history sample
+//! (SOC, PV, battery and grid power). Each table is encoded on its own.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use fluxion_core::WebQueryResponse;
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, FloatType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde::{Deserialize, Serialize};
+
+/// File format of an export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Compact JSON document (default)
+    #[default]
+    Json,
+    /// One CSV file per table
+    Csv,
+    /// One Parquet file per table
+    Parquet,
+}
+
+impl ExportFormat {
+    /// File name extension, without the dot
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+        }
+    }
+
+    /// `Content-Type` of a download
+    #[must_use]
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::Parquet => "application/vnd.apache.parquet",
+        }
+    }
+}
+
+/// Table of a CSV or Parquet export
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportTable {
+    /// One row per 15-minute price block
+    #[default]
+    Blocks,
+    /// One row per history sample
+    Samples,
+}
+
+impl ExportTable {
+    pub const ALL: [Self; 2] = [Self::Blocks, Self::Samples];
+
+    /// Suffix of the table's file name
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Blocks => "blocks",
+            Self::Samples => "samples",
+        }
+    }
+}
+
+/// Encode `table` of `response` as CSV or Parquet
+///
+/// Returns `None` for [`ExportFormat::Json`], which is not tabular.
+pub fn encode_table(
+    response: &WebQueryResponse,
+    table: ExportTable,
+    format: ExportFormat,
+) -> Option<Result<Vec<u8>, String>> {
+    let table = match table {
+        ExportTable::Blocks => blocks_table(response),
+        ExportTable::Samples => samples_table(response),
+    };
+    match format {
+        ExportFormat::Json => None,
+        ExportFormat::Csv => Some(table.to_csv().map_err(|e| e.to_string())),
+        ExportFormat::Parquet => Some(table.to_parquet().map_err(|e| e.to_string())),
+    }
+}
+
+/// Values of one column
+enum Values {
+    Time(Vec<DateTime<Utc>>),
+    Number(Vec<Option<f32>>),
+    Text(Vec<Option<String>>),
+    Flag(Vec<bool>),
+}
+
+struct Column {
+    name: &'static str,
+    values: Values,
+}
+
+/// Columns of equal length
+struct Table {
+    rows: usize,
+    columns: Vec<Column>,
+}
+
+impl Table {
+    fn new(rows: usize) -> Self {
+        Self {
+            rows,
+            columns: Vec::new(),
+        }
+    }
+
+    fn column(mut self, name: &'static str, values: Values) -> Self {
+        self.columns.push(Column { name, values });
+        self
+    }
+
+    fn to_csv(&self) -> Result<Vec<u8>, csv::Error> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(self.columns.iter().map(|c| c.name))?;
+        for row in 0..self.rows {
+            writer.write_record(self.columns.iter().map(|c| cell(&c.values, row)))?;
+        }
+        writer.into_inner().map_err(|e| e.into_error().into())
+    }
+
+    fn to_parquet(&self) -> parquet::errors::Result<Vec<u8>> {
+        let fields: String = self
+            .columns
+            .iter()
+            .map(|column| match column.values {
+                Values::Time(_) => {
+                    format!("required int64 {} (TIMESTAMP(MILLIS,true));", column.name)
+                }
+                Values::Number(_) => format!("optional float {};", column.name),
+                Values::Text(_) => format!("optional binary {} (UTF8);", column.name),
+                Values::Flag(_) => format!("required boolean {};", column.name),
+            })
+            .collect();
+        let schema = Arc::new(parse_message_type(&format!(
+            "message fluxion_export {{ {fields} }}"
+        ))?);
+
+        let mut buffer = Vec::new();
+        let mut writer = SerializedFileWriter::new(
+            &mut buffer,
+            schema,
+            Arc::new(WriterProperties::builder().build()),
+        )?;
+        let mut row_group = writer.next_row_group()?;
+        for column in &self.columns {
+            let Some(mut writer) = row_group.next_column()? else {
+                break;
+            };
+            match &column.values {
+                Values::Time(times) => {
+                    let millis: Vec<i64> = times.iter().map(DateTime::timestamp_millis).collect();
+                    writer
+                        .typed::<Int64Type>()
+                        .write_batch(&millis, None, None)?;
+                }
+                Values::Number(numbers) => {
+                    let values: Vec<f32> = numbers.iter().flatten().copied().collect();
+                    writer.typed::<FloatType>().write_batch(
+                        &values,
+                        Some(&definition_levels(numbers)),
+                        None,
+                    )?;
+                }
+                Values::Text(texts) => {
+                    let values: Vec<ByteArray> = texts
+                        .iter()
+                        .flatten()
+                        .map(|text| ByteArray::from(text.as_str()))
+                        .collect();
+                    writer.typed::<ByteArrayType>().write_batch(
+                        &values,
+                        Some(&definition_levels(texts)),
+                        None,
+                    )?;
+                }
+                Values::Flag(flags) => {
+                    writer.typed::<BoolType>().write_batch(flags, None, None)?;
+                }
+            }
+            writer.close()?;
+        }
+        row_group.close()?;
+        writer.close()?;
+        Ok(buffer)
+    }
+}
+
+/// Parquet definition levels of an optional column (1 = present)
+fn definition_levels<T>(values: &[Option<T>]) -> Vec<i16> {
+    values.iter().map(|v| i16::from(v.is_some())).collect()
+}
+
+/// CSV text of one cell; missing values are empty
+fn cell(values: &Values, row: usize) -> String {
+    match values {
+        Values::Time(times) => times[row].to_rfc3339_opts(SecondsFormat::Secs, true),
+        Values::Number(numbers) => numbers[row].map(|n| n.to_string()).unwrap_or_default(),
+        Values::Text(texts) => texts[row].clone().unwrap_or_default(),
+        Values::Flag(flags) => flags[row].to_string(),
+    }
+}
+
+/// One row per price block: price, plan, forecast and realized energy
+fn blocks_table(response: &WebQueryResponse) -> Table {
+    let blocks = response
+        .prices
+        .as_ref()
+        .map(|prices| prices.blocks.as_slice())
+        .unwrap_or_default();
+    let number = |value: fn(&fluxion_core::PriceBlockData) -> Option<f32>| {
+        Values::Number(blocks.iter().map(value).collect())
+    };
+    let text = |value: fn(&fluxion_core::PriceBlockData) -> Option<&String>| {
+        Values::Text(blocks.iter().map(|b| value(b).cloned()).collect())
+    };
+
+    Table::new(blocks.len())
+        .column(
+            "timestamp",
+            Values::Time(blocks.iter().map(|b| b.timestamp).collect()),
+        )
+        .column("price_czk_per_kwh", number(|b| Some(b.price)))
+        .column(
+            "mode",
+            Values::Text(blocks.iter().map(|b| Some(b.block_type.clone())).collect()),
+        )
+        .column("target_soc", number(|b| b.target_soc))
+        .column("strategy", text(|b| b.strategy.as_ref()))
+        .column("expected_profit_czk", number(|b| b.expected_profit))
+        .column("reason", text(|b| b.reason.as_ref()))
+        .column("decision_uid", text(|b| b.decision_uid.as_ref()))
+        .column(
+            "solar_forecast_kwh",
+            number(|b| b.forecast.map(|f| f.solar_kwh)),
+        )
+        .column(
+            "consumption_forecast_kwh",
+            number(|b| b.forecast.map(|f| f.consumption_kwh)),
+        )
+        .column(
+            "solar_actual_kwh",
+            number(|b| b.actual.map(|a| a.solar_kwh)),
+        )
+        .column(
+            "consumption_actual_kwh",
+            number(|b| b.actual.map(|a| a.consumption_kwh)),
+        )
+        .column(
+            "grid_import_actual_kwh",
+            number(|b| b.actual.map(|a| a.grid_import_kwh)),
+        )
+        .column(
+            "grid_export_actual_kwh",
+            number(|b| b.actual.map(|a| a.grid_export_kwh)),
+        )
+        .column(
+            "historical",
+            Values::Flag(blocks.iter().map(|b| b.is_historical).collect()),
+        )
+}
+
+/// Measurements of one history sample; the histories are sampled separately
+#[derive(Default)]
+struct Sample {
+    battery_soc: Option<f32>,
+    pv_power_w: Option<f32>,
+    battery_power_w: Option<f32>,
+    grid_import_w: Option<f32>,
+    grid_export_w: Option<f32>,
+}
+
+/// One row per history timestamp, merging SOC, PV, battery and grid history
+fn samples_table(response: &WebQueryResponse) -> Table {
+    let mut samples: BTreeMap<DateTime<Utc>, Sample> = BTreeMap::new();
+    for point in response.battery_soc_history.iter().flatten() {
+        samples.entry(point.timestamp).or_default().battery_soc = Some(point.soc);
+    }
+    for point in response.pv_generation_history.iter().flatten() {
+        samples.entry(point.timestamp).or_default().pv_power_w = Some(point.power_w);
+    }
+    for point in response.battery_power_history.iter().flatten() {
+        samples.entry(point.timestamp).or_default().battery_power_w = Some(point.power_w);
+    }
+    for point in response.grid_power_history.iter().flatten() {
+        let sample = samples.entry(point.timestamp).or_default();
+        sample.grid_import_w = Some(point.import_w);
+        sample.grid_export_w = Some(point.export_w);
+    }
+
+    let number =
+        |value: fn(&Sample) -> Option<f32>| Values::Number(samples.values().map(value).collect());
+    Table::new(samples.len())
+        .column("timestamp", Values::Time(samples.keys().copied().collect()))
+        .column("battery_soc", number(|s| s.battery_soc))
+        .column("pv_power_w", number(|s| s.pv_power_w))
+        .column("battery_power_w", number(|s| s.battery_power_w))
+        .column("grid_import_w", number(|s| s.grid_import_w))
+        .column("grid_export_w", number(|s| s.grid_export_w))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_core::web_bridge::BatterySocHistoryPoint;
+    use fluxion_core::{
+        BlockActual, GridPowerHistoryPoint, PriceBlockData, PriceData, SystemHealthData,
+    };
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+
+    fn response(now: DateTime<Utc>) -> WebQueryResponse {
+        let block = |offset: i64, reason: Option<&str>| PriceBlockData {
+            timestamp: now + chrono::Duration::minutes(15 * offset),
+            price: 2.5,
+            block_type: "charge".to_owned(),
+            target_soc: Some(80.0),
+            planned_soc: None,
+            decision_reason: None,
+            strategy: Some("Winter-Adaptive".to_owned()),
+            expected_profit: None,
+            reason: reason.map(ToOwned::to_owned),
+            decision_uid: None,
+            debug_info: None,
+            forecast: None,
+            actual: Some(BlockActual {
+                block_start: now,
+                solar_kwh: 0.5,
+                consumption_kwh: 0.25,
+                grid_import_kwh: 0.0,
+                grid_export_kwh: 0.25,
+            }),
+            tariff: None,
+            is_historical: offset < 0,
+        };
+        WebQueryResponse {
+            timestamp: now,
+            debug_mode: false,
+            inverters: vec![],
+            schedule: None,
+            prices: Some(PriceData {
+                current_price: 2.5,
+                min_price: 2.5,
+                max_price: 2.5,
+                avg_price: 2.5,
+                blocks: vec![block(-1, Some("Cheap, \"night\" charge")), block(0, None)],
+                today_min_price: 2.5,
+                today_max_price: 2.5,
+                today_avg_price: 2.5,
+                today_median_price: 2.5,
+                tomorrow_min_price: None,
+                tomorrow_max_price: None,
+                tomorrow_avg_price: None,
+                tomorrow_median_price: None,
+            }),
+            health: SystemHealthData {
+                inverter_source: true,
+                price_source: true,
+                last_update: now,
+                errors: vec![],
+            },
+            timezone: None,
+            battery_soc_history: Some(vec![
+                BatterySocHistoryPoint {
+                    timestamp: now - chrono::Duration::minutes(15),
+                    soc: 40.0,
+                },
+                BatterySocHistoryPoint {
+                    timestamp: now,
+                    soc: 42.0,
+                },
+            ]),
+            battery_soc_prediction: None,
+            pv_generation_history: None,
+            battery_power_history: None,
+            grid_power_history: Some(vec![GridPowerHistoryPoint {
+                timestamp: now,
+                import_w: 1200.0,
+                export_w: 0.0,
+            }]),
+            consumption_stats: None,
+            hdo_schedule: None,
+            pricing_fees: None,
+            solar_forecast: None,
+        }
+    }
+
+    #[test]
+    fn test_csv_tables() {
+        let now: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+        let response = response(now);
+
+        let blocks = encode_table(&response, ExportTable::Blocks, ExportFormat::Csv)
+            .unwrap()
+            .unwrap();
+        let blocks = String::from_utf8(blocks).unwrap();
+        let lines: Vec<&str> = blocks.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("timestamp,price_czk_per_kwh,mode,target_soc,strategy"));
+        // Reasons with commas and quotes stay one cell
+        assert_eq!(
+            lines[1],
+            "2025-06-01T09:45:00Z,2.5,charge,80,Winter-Adaptive,,\"Cheap, \"\"night\"\" charge\",,,,0.5,0.25,0,0.25,true"
+        );
+
+        let samples = encode_table(&response, ExportTable::Samples, ExportFormat::Csv)
+            .unwrap()
+            .unwrap();
+        let samples = String::from_utf8(samples).unwrap();
+        let lines: Vec<&str> = samples.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "timestamp,battery_soc,pv_power_w,battery_power_w,grid_import_w,grid_export_w",
+                "2025-06-01T09:45:00Z,40,,,,",
+                "2025-06-01T10:00:00Z,42,,,1200,0",
+            ]
+        );
+
+        assert!(encode_table(&response, ExportTable::Blocks, ExportFormat::Json).is_none());
+    }
+
+    #[test]
+    fn test_parquet_table_reads_back() {
+        let now: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+
+        let parquet = encode_table(&response(now), ExportTable::Blocks, ExportFormat::Parquet)
+            .unwrap()
+            .unwrap();
+
+        let reader = SerializedFileReader::new(axum::body::Bytes::from(parquet)).unwrap();
+        let metadata = reader.metadata();
+        assert_eq!(metadata.file_metadata().num_rows(), 2);
+        assert_eq!(metadata.file_metadata().schema_descr().num_columns(), 15);
+        let rows: Vec<String> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap().to_string())
+            .collect();
+        assert!(rows[0].contains(r#"reason: "Cheap, "night" charge""#));
+        assert!(rows[0].contains("historical: true"));
+        assert!(rows[1].contains("reason: null"));
+    }
+}
@@ -0,0 +1,117 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Grid voltage/frequency quality report and event export.
+
+use axum::{
+    Json,
+    extract::State,
+    response::{IntoResponse, Response},
+};
+use fluxion_core::grid_quality::{GridEvent, GridEventKind, GridQualityMonitor};
+use std::fmt::Write as _;
+
+/// GET /api/grid-quality — daily statistics, excursion events and current state
+pub async fn grid_quality_handler(State(monitor): State<GridQualityMonitor>) -> Response {
+    Json(monitor.report()).into_response()
+}
+
+/// GET /api/grid-quality/events.csv — excursion events for the grid operator
+pub async fn grid_quality_events_csv_handler(
+    State(monitor): State<GridQualityMonitor>,
+) -> Response {
+    let csv = events_csv(&monitor.report().events);
+
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "text/csv; charset=utf-8".parse().unwrap(),
+    );
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        "attachment; filename=\"grid_quality_events.csv\""
+            .parse()
+            .unwrap(),
+    );
+
+    (headers, csv).into_response()
+}
+
+#[expect(clippy::cast_precision_loss)]
+fn events_csv(events: &[GridEvent]) -> String {
+    let mut csv = String::from("kind,inverter_id,started_at,ended_at,duration_minutes,peak\n");
+    for event in events {
+        let kind = match event.kind {
+            GridEventKind::Overvoltage => "overvoltage",
+            GridEventKind::FrequencyLow => "frequency_low",
+            GridEventKind::FrequencyHigh => "frequency_high",
+        };
+        let (ended_at, duration) = match event.ended_at {
+            Some(end) => (
+                end.to_rfc3339(),
+                format!(
+                    "{:.1}",
+                    (end - event.started_at).num_seconds() as f64 / 60.0
+                ),
+            ),
+            None => (String::new(), String::new()),
+        };
+        let _ = writeln!(
+            csv,
+            "{kind},{},{},{ended_at},{duration},{:.2}",
+            event.inverter_id.replace(',', " "),
+            event.started_at.to_rfc3339(),
+            event.peak
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_events_csv_rows() {
+        let started_at = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        let events = vec![
+            GridEvent {
+                kind: GridEventKind::Overvoltage,
+                inverter_id: "main".to_owned(),
+                started_at,
+                ended_at: Some(started_at + chrono::Duration::minutes(25)),
+                peak: 254.6,
+            },
+            GridEvent {
+                kind: GridEventKind::FrequencyHigh,
+                inverter_id: "main".to_owned(),
+                started_at,
+                ended_at: None,
+                peak: 50.31,
+            },
+        ];
+
+        let csv = events_csv(&events);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[1],
+            "overvoltage,main,2025-06-01T12:00:00+00:00,2025-06-01T12:25:00+00:00,25.0,254.60"
+        );
+        assert_eq!(
+            lines[2],
+            "frequency_high,main,2025-06-01T12:00:00+00:00,,,50.31"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Glossary entries behind the inline help tooltips.
+//!
+//! Content lives in the `help` FTL domain as `help-{topic}-title`,
+//! `help-{topic}-summary` and `help-{topic}-body` (Markdown).
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use fluxion_i18n::I18n;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Glossary topics, in display order
+pub const HELP_TOPICS: [&str; 4] = ["hdo", "effective-price", "soc-floor", "eeprom-protection"];
+
+#[derive(Debug, Serialize)]
+pub struct HelpEntry {
+    pub topic: String,
+    pub title: String,
+    pub summary: String,
+    /// Markdown
+    pub body: String,
+}
+
+fn help_entry(i18n: &I18n, topic: &str) -> Option<HelpEntry> {
+    if !HELP_TOPICS.contains(&topic) {
+        return None;
+    }
+    let field = |name: &str| i18n.get(&format!("help-{topic}-{name}")).ok();
+    Some(HelpEntry {
+        topic: topic.to_owned(),
+        title: field("title")?,
+        summary: field("summary")?,
+        body: field("body")?,
+    })
+}
+
+/// GET /api/help — all glossary entries
+pub async fn help_index_handler(State(i18n): State<Arc<I18n>>) -> Response {
+    let entries: Vec<HelpEntry> = HELP_TOPICS
+        .iter()
+        .filter_map(|topic| help_entry(&i18n, topic))
+        .collect();
+    Json(entries).into_response()
+}
+
+/// GET /api/help/{topic} — a single glossary entry
+pub async fn help_topic_handler(
+    State(i18n): State<Arc<I18n>>,
+    Path(topic): Path<String>,
+) -> Response {
+    match help_entry(&i18n, &topic) {
+        Some(entry) => Json(entry).into_response(),
+        None => (StatusCode::NOT_FOUND, "Help topic not found").into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_i18n::Language;
+
+    #[test]
+    fn test_help_entries() {
+        let i18n = I18n::new(Language::Czech).unwrap();
+        for topic in HELP_TOPICS {
+            let entry = help_entry(&i18n, topic).unwrap();
+            assert_ne!(entry.title, "");
+            assert!(entry.body.contains("**"));
+        }
+        assert!(help_entry(&i18n, "config").is_none());
+    }
+}
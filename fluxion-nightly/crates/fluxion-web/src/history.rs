@@ -0,0 +1,187 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+//! Plant telemetry from the built-in historian.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::historian::{Historian, HistoryPoint, HistoryResolution};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+/// Most buckets returned by one query
+const MAX_POINTS: i64 = 20_000;
+
+/// Query parameters of `/api/history` (RFC 3339 timestamps)
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// `raw`, `5m` (default), `15m` or `1h`
+    #[serde(default)]
+    pub resolution: HistoryResolution,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryResponse {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub resolution: HistoryResolution,
+    pub points: Vec<HistoryPoint>,
+}
+
+fn bad_request(message: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(serde_json::json!({ "error": message })),
+    )
+        .into_response()
+}
+
+/// GET /api/history?from=&to=&resolution= — plant telemetry in the range (default: last 24 hours)
+pub async fn history_handler(
+    State(historian): State<Historian>,
+    Query(query): Query<HistoryQuery>,
+) -> Response {
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - Duration::hours(24));
+    if from >= to {
+        return bad_request("`from` must be before `to`");
+    }
+    // Raw samples come at the sampling interval, buckets at their length
+    let step_secs = query.resolution.bucket_secs().unwrap_or_else(|| {
+        i64::try_from(historian.sample_interval().as_secs()).unwrap_or(i64::MAX)
+    });
+    if (to - from).num_seconds() > MAX_POINTS.saturating_mul(step_secs.max(1)) {
+        return bad_request("Range too long for the resolution; use a coarser one");
+    }
+
+    let resolution = query.resolution;
+    // SQLite calls block
+    match tokio::task::spawn_blocking(move || historian.query(from, to, resolution)).await {
+        Ok(Ok(points)) => Json(HistoryResponse {
+            from,
+            to,
+            resolution,
+            points,
+        })
+        .into_response(),
+        Ok(Err(e)) => {
+            error!("Failed to query historian: {e:#}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+        Err(e) => {
+            error!("Historian query failed: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_core::historian::PlantSample;
+
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn sample(inverter_id: &str, timestamp: DateTime<Utc>, soc: f32) -> PlantSample {
+        PlantSample {
+            inverter_id: inverter_id.to_owned(),
+            timestamp,
+            battery_soc: soc,
+            battery_power_w: 0.0,
+            pv_power_w: 1000.0,
+            grid_power_w: 0.0,
+            house_load_w: 500.0,
+        }
+    }
+
+    fn query(
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        resolution: HistoryResolution,
+    ) -> Query<HistoryQuery> {
+        Query(HistoryQuery {
+            from: Some(from),
+            to: Some(to),
+            resolution,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_raw_samples_are_combined_per_plant() {
+        let historian = Historian::open_in_memory(7, 10).unwrap();
+        let at: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+        historian
+            .record(&[sample("main", at, 40.0), sample("garage", at, 60.0)], at)
+            .unwrap();
+
+        let response = history_handler(
+            State(historian),
+            query(at, at + Duration::hours(1), HistoryResolution::Raw),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let history = body(response).await;
+        assert_eq!(history["resolution"], "raw");
+        let points = history["points"].as_array().unwrap();
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0]["battery_soc"], 50.0);
+        assert_eq!(points[0]["pv_power_w"], 2000.0);
+    }
+
+    #[tokio::test]
+    async fn test_empty_historian_returns_no_points() {
+        let to: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+        let response = history_handler(
+            State(Historian::open_in_memory(7, 10).unwrap()),
+            query(to - Duration::hours(24), to, HistoryResolution::FiveMinutes),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(body(response).await["points"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_bad_ranges_are_rejected() {
+        let to: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+
+        let inverted = history_handler(
+            State(Historian::open_in_memory(7, 10).unwrap()),
+            query(to, to - Duration::hours(1), HistoryResolution::Raw),
+        )
+        .await;
+        assert_eq!(inverted.status(), StatusCode::BAD_REQUEST);
+
+        // A year of 10 s samples is far above the point limit
+        let too_long = history_handler(
+            State(Historian::open_in_memory(7, 10).unwrap()),
+            query(to - Duration::days(365), to, HistoryResolution::Raw),
+        )
+        .await;
+        assert_eq!(too_long.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body(too_long).await["error"],
+            "Range too long for the resolution; use a coarser one"
+        );
+    }
+}
@@ -0,0 +1,95 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Developer-mode ECS inspector.
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use fluxion_core::inspector::EcsInspector;
+
+/// GET /api/debug/ecs — latest snapshot of key ECS resources
+pub async fn ecs_inspector_handler(State(inspector): State<EcsInspector>) -> Response {
+    match inspector.snapshot() {
+        Some(snapshot) => Json(snapshot).into_response(),
+        None => (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "ECS has not produced a snapshot yet" })),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::prelude::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use fluxion_core::DebugModeConfig;
+    use fluxion_core::inspector::ecs_inspector_system;
+
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_of_the_ecs_is_returned() {
+        let inspector = EcsInspector::default();
+        let mut world = World::new();
+        world.insert_resource(inspector.clone());
+        world.insert_resource(DebugModeConfig::default());
+        world.insert_resource(
+            serde_json::from_value::<fluxion_core::SystemConfig>(serde_json::json!({
+                "inverters": [],
+                "pricing": {
+                    "spot_price_entity": "sensor.spot_price",
+                    "use_spot_prices_to_buy": true,
+                    "use_spot_prices_to_sell": true,
+                    "fixed_buy_price_czk": 4.0,
+                    "fixed_sell_price_czk": 2.0,
+                },
+                "control": fluxion_core::ControlConfig::default(),
+                "system": {
+                    "update_interval_secs": 60,
+                    "debug_mode": true,
+                    "display_currency": "CZK",
+                },
+            }))
+            .unwrap(),
+        );
+        world.run_system_once(ecs_inspector_system).unwrap();
+
+        let response = ecs_inspector_handler(State(inspector)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let snapshot = body(response).await;
+        assert_eq!(snapshot["debug_mode"], true);
+        assert!(snapshot["prices"].is_null());
+        assert_eq!(snapshot["health"]["inverters"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_missing_snapshot_is_unavailable() {
+        let response = ecs_inspector_handler(State(EcsInspector::default())).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            body(response).await["error"],
+            "ECS has not produced a snapshot yet"
+        );
+    }
+}
@@ -10,46 +10,97 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
+mod alerts;
+mod api_error;
+mod api_keys;
+mod api_version;
+mod auth;
 mod backtest;
+mod backup_reserve;
+pub mod branding;
+mod calendar;
+mod chart_history;
 mod config_api;
+mod config_import;
+mod config_preview;
+mod decisions;
+mod dhw;
+mod etag;
+mod export_cap;
+mod export_formats;
+mod grid_quality;
+mod help;
+mod history;
+mod inspector;
+mod license;
+mod mapping_check;
+mod metrics;
+mod openapi;
 mod plugin_api;
+mod preview;
 pub mod remote_access;
 mod routes;
+mod safe_state_api;
+mod savings;
+mod self_test;
+mod setup_wizard;
 mod simulator;
+mod simulator_runs;
+pub mod status;
+mod strategy_config_api;
+mod strategy_wizard;
+mod submeter;
+mod tariff;
+mod ui_preferences;
+mod upcoming;
 mod user_control_api;
 mod validation;
+mod what_if;
 
+pub use api_keys::{ApiKeyApiState, ApiKeyCheck, ApiKeyScope, ApiKeyStore};
+pub use auth::{AuthState, WebAuthConfig};
 pub use backtest::BacktestState;
+pub use branding::BrandingConfig;
 pub use config_api::ConfigApiState;
+pub use export_formats::{ExportFormat, ExportTable};
+pub use license::{LicenseInfo, LicenseState, LicenseStatus};
+pub use mapping_check::MappingCheckState;
 pub use plugin_api::PluginApiState;
 pub use remote_access::{
     MobileApiState, RemoteAccessApiState, mobile_api_routes, remote_access_routes,
 };
-use routes::{DashboardTemplate, LiveDataTemplate};
+use routes::{BatteryPowerPoint, DashboardTemplate, GridPowerPoint, LiveDataTemplate};
+pub use self_test::SelfTestState;
+pub use setup_wizard::SetupWizardState;
 pub use simulator::SimulatorState;
-pub use user_control_api::{UserControlApiState, UserControlUpdateSender};
+pub use user_control_api::{UserControlApiState, UserControlChangeError, UserControlUpdateSender};
 
 use askama::Template;
 use axum::{
     Json, Router,
-    extract::State,
+    extract::{Query, State},
     response::{
         Html, IntoResponse,
         sse::{Event, Sse},
     },
     routing::get,
 };
-use chrono::{Local, NaiveTime, Offset};
-use fluxion_core::{ConfigUpdateSender, WebQueryResponse, WebQuerySender};
+use chrono::{DateTime, NaiveTime, Utc};
+use fluxion_core::web_bridge::QueryError;
+use fluxion_core::{
+    DecisionReason, PriceBlockData, TimeFormatter, WebQueryResponse, WebQuerySender,
+};
 use fluxion_i18n::I18n;
 use fluxion_types::UserControlState;
 use parking_lot::RwLock;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio_stream::{StreamExt, wrappers::IntervalStream};
+use tower::Layer;
+use tower_http::compression::CompressionLayer;
 use tower_http::cors::CorsLayer;
 use tracing::{debug, error, info, trace};
 
@@ -60,6 +111,8 @@ pub struct AppState {
     pub i18n: Arc<I18n>,
     /// User control state for dashboard rendering
     pub user_control_state: Option<Arc<RwLock<UserControlState>>>,
+    /// Naming of downloaded exports
+    pub export_config: ScheduledExportConfig,
 }
 
 impl std::fmt::Debug for AppState {
@@ -68,10 +121,14 @@ impl std::fmt::Debug for AppState {
             .field("query_sender", &"<WebQuerySender>")
             .field("i18n", &self.i18n)
             .field("user_control_state", &self.user_control_state.is_some())
+            .field("export_config", &self.export_config)
             .finish()
     }
 }
 
+/// Default export file name: `fluxion_daily_20250330_235500.json`
+pub const DEFAULT_EXPORT_FILENAME_TEMPLATE: &str = "fluxion_{kind}_{stamp}";
+
 /// Configuration for scheduled daily data export
 /// Exports full day's data at a specified time for debugging purposes
 #[derive(Clone, Debug)]
@@ -80,6 +137,72 @@ pub struct ScheduledExportConfig {
     pub export_dir: PathBuf,
     /// Time of day to run the export (default: 23:55)
     pub export_time: NaiveTime,
+    /// File name without extension, for scheduled and downloaded exports
+    ///
+    /// Tokens: `{kind}` (`daily` or `export`), `{date}`, `{time}`, `{stamp}`
+    /// (local clock), `{site}` and `{version}`.
+    pub filename_template: String,
+    /// Installation name for `{site}`, so exports collected centrally can be told apart
+    pub site_name: String,
+    /// Formats written by the scheduled export; CSV and Parquet write one
+    /// file per table (`<name>_blocks.csv`, `<name>_samples.csv`)
+    pub formats: Vec<ExportFormat>,
+}
+
+impl ScheduledExportConfig {
+    /// File name of a JSON export of `kind` taken at `time`
+    ///
+    /// Characters that are unsafe in file names are replaced by `_`.
+    #[must_use]
+    pub fn file_name(&self, kind: &str, formatter: &TimeFormatter, time: DateTime<Utc>) -> String {
+        format!("{}.json", self.file_stem(kind, *formatter, time))
+    }
+
+    /// File name of one table of a CSV or Parquet export
+    #[must_use]
+    pub fn table_file_name(
+        &self,
+        kind: &str,
+        formatter: &TimeFormatter,
+        time: DateTime<Utc>,
+        table: ExportTable,
+        format: ExportFormat,
+    ) -> String {
+        format!(
+            "{}_{}.{}",
+            self.file_stem(kind, *formatter, time),
+            table.name(),
+            format.extension()
+        )
+    }
+
+    /// File name without extension
+    fn file_stem(&self, kind: &str, formatter: TimeFormatter, time: DateTime<Utc>) -> String {
+        let local = formatter.to_local(time);
+        let site = if self.site_name.trim().is_empty() {
+            "fluxion"
+        } else {
+            self.site_name.trim()
+        };
+        let name: String = self
+            .filename_template
+            .replace("{kind}", kind)
+            .replace("{date}", &local.format("%Y-%m-%d").to_string())
+            .replace("{time}", &local.format("%H%M%S").to_string())
+            .replace("{stamp}", &formatter.file_stamp(time))
+            .replace("{site}", site)
+            .replace("{version}", env!("CARGO_PKG_VERSION"))
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        name.trim_start_matches('.').to_owned()
+    }
 }
 
 impl Default for ScheduledExportConfig {
@@ -89,118 +212,139 @@ impl Default for ScheduledExportConfig {
             export_dir: PathBuf::from("./data/exports"),
             // 23:55 - 5 minutes before midnight to capture full day's data
             export_time: NaiveTime::from_hms_opt(23, 55, 0).expect("valid time"),
+            filename_template: DEFAULT_EXPORT_FILENAME_TEMPLATE.to_owned(),
+            site_name: String::new(),
+            formats: vec![ExportFormat::Json],
         }
     }
 }
 
 /// Spawn background task for scheduled daily data export
-/// Runs at the configured time each day and saves export data to a file
+/// Runs at the configured time each day and saves export data to a file.
+/// Supervised, so a panic during an export restarts the task instead of ending it.
+fn spawn_scheduled_export_task(
+    query_sender: WebQuerySender,
+    config: ScheduledExportConfig,
+    user_control_state: Option<Arc<RwLock<UserControlState>>>,
+) {
+    fluxion_core::TaskSupervisor::global().spawn("scheduled_export", move || {
+        run_scheduled_export(
+            query_sender.clone(),
+            config.clone(),
+            user_control_state.clone(),
+        )
+    });
+}
+
 #[expect(clippy::integer_division)]
-fn spawn_scheduled_export_task(query_sender: WebQuerySender, config: ScheduledExportConfig) {
-    tokio::spawn(async move {
-        info!(
-            "📅 Scheduled export enabled: will export at {} to {:?}",
-            config.export_time.format("%H:%M"),
-            config.export_dir
+async fn run_scheduled_export(
+    query_sender: WebQuerySender,
+    config: ScheduledExportConfig,
+    user_control_state: Option<Arc<RwLock<UserControlState>>>,
+) {
+    info!(
+        "📅 Scheduled export enabled: will export at {} to {:?}",
+        config.export_time.format("%H:%M"),
+        config.export_dir
+    );
+
+    // Create export directory if it doesn't exist
+    if let Err(e) = tokio::fs::create_dir_all(&config.export_dir).await {
+        error!(
+            "❌ Failed to create export directory {:?}: {}",
+            config.export_dir, e
         );
+        return;
+    }
 
-        // Create export directory if it doesn't exist
-        if let Err(e) = tokio::fs::create_dir_all(&config.export_dir).await {
-            error!(
-                "❌ Failed to create export directory {:?}: {}",
-                config.export_dir, e
-            );
-            return;
-        }
+    loop {
+        // Export time is a wall-clock time in the HA timezone
+        let formatter = query_sender
+            .query_dashboard()
+            .await
+            .map(|response| response.time_formatter())
+            .unwrap_or_default();
+        let now = Utc::now();
+        let next_export = formatter.next_daily(config.export_time, now);
 
-        loop {
-            // Calculate time until next scheduled export
-            let now = Local::now();
-            let today_export_time = now
-                .date_naive()
-                .and_time(config.export_time)
-                .and_local_timezone(Local)
-                .single();
-
-            let next_export = if let Some(today_time) = today_export_time {
-                if now.time() < config.export_time {
-                    // Export time is still ahead today
-                    today_time
-                } else {
-                    // Export time has passed today, schedule for tomorrow
-                    let tomorrow = now.date_naive() + chrono::Duration::days(1);
-                    tomorrow
-                        .and_time(config.export_time)
-                        .and_local_timezone(Local)
-                        .single()
-                        .expect("valid tomorrow time")
-                }
-            } else {
-                // Fallback: try tomorrow if today's time conversion fails
-                let tomorrow = now.date_naive() + chrono::Duration::days(1);
-                tomorrow
-                    .and_time(config.export_time)
-                    .and_local_timezone(Local)
-                    .single()
-                    .expect("valid tomorrow time")
-            };
+        let duration_until_export = (next_export - now)
+            .to_std()
+            .unwrap_or(Duration::from_secs(60));
 
-            let duration_until_export = (next_export - now)
-                .to_std()
-                .unwrap_or(Duration::from_secs(60));
+        info!(
+            "📅 Next scheduled export at {} (in {} hours {} minutes)",
+            formatter.date_time(next_export),
+            duration_until_export.as_secs() / 3600,
+            (duration_until_export.as_secs() % 3600) / 60
+        );
 
-            info!(
-                "📅 Next scheduled export at {} (in {} hours {} minutes)",
-                next_export.format("%Y-%m-%d %H:%M:%S"),
-                duration_until_export.as_secs() / 3600,
-                (duration_until_export.as_secs() % 3600) / 60
-            );
+        // Sleep until export time
+        tokio::time::sleep(duration_until_export).await;
 
-            // Sleep until export time
-            tokio::time::sleep(duration_until_export).await;
+        // Perform the export
+        info!("📦 Starting scheduled daily export...");
 
-            // Perform the export
-            info!("📦 Starting scheduled daily export...");
+        match query_sender.query_dashboard().await {
+            Ok(response) => {
+                let user_control = user_control_state.as_ref().map(|uc| uc.read().clone());
+                write_scheduled_export(&config, &response, user_control.as_ref()).await;
+            }
+            Err(e) => {
+                error!("❌ Failed to query dashboard for scheduled export: {}", e);
+            }
+        }
 
-            match query_sender.query_dashboard().await {
-                Ok(response) => {
-                    // Generate filename with date
-                    let filename = format!(
-                        "fluxion_daily_{}.json",
-                        Local::now().format("%Y%m%d_%H%M%S")
-                    );
-                    let filepath = config.export_dir.join(&filename);
-
-                    // Create compact export data (reusing existing function)
-                    let export_data = create_compact_export(&response);
-
-                    match serde_json::to_string_pretty(&export_data) {
-                        Ok(json_string) => match tokio::fs::write(&filepath, &json_string).await {
-                            Ok(()) => {
-                                info!(
-                                    "✅ Daily export saved: {} ({} bytes)",
-                                    filepath.display(),
-                                    json_string.len()
-                                );
-                            }
-                            Err(e) => {
-                                error!("❌ Failed to write export file: {}", e);
-                            }
-                        },
-                        Err(e) => {
-                            error!("❌ Failed to serialize export data: {}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!("❌ Failed to query dashboard for scheduled export: {}", e);
-                }
+        // Small delay to avoid potential double-execution edge cases
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
+/// Write one daily export in every configured format
+async fn write_scheduled_export(
+    config: &ScheduledExportConfig,
+    response: &WebQueryResponse,
+    user_control: Option<&UserControlState>,
+) {
+    let formatter = response.time_formatter();
+    let now = Utc::now();
+    let mut files = Vec::new();
+    for &format in &config.formats {
+        if format == ExportFormat::Json {
+            // Create compact export data (reusing existing function)
+            let export_data = create_compact_export(response, user_control);
+            match serde_json::to_vec_pretty(&export_data) {
+                Ok(json) => files.push((config.file_name("daily", &formatter, now), json)),
+                Err(e) => error!("❌ Failed to serialize export data: {}", e),
             }
+            continue;
+        }
+        for table in ExportTable::ALL {
+            match export_formats::encode_table(response, table, format) {
+                Some(Ok(bytes)) => files.push((
+                    config.table_file_name("daily", &formatter, now, table, format),
+                    bytes,
+                )),
+                Some(Err(e)) => error!("❌ Failed to encode {:?} export: {}", format, e),
+                None => {}
+            }
+        }
+    }
 
-            // Small delay to avoid potential double-execution edge cases
-            tokio::time::sleep(Duration::from_secs(60)).await;
+    for (filename, bytes) in files {
+        let filepath = config.export_dir.join(&filename);
+        match tokio::fs::write(&filepath, &bytes).await {
+            Ok(()) => {
+                info!(
+                    "✅ Daily export saved: {} ({} bytes)",
+                    filepath.display(),
+                    bytes.len()
+                );
+            }
+            Err(e) => {
+                error!("❌ Failed to write export file: {}", e);
+            }
         }
-    });
+    }
 }
 
 /// Extract ingress path from request headers
@@ -222,18 +366,78 @@ fn extract_ingress_path(headers: &axum::http::HeaderMap) -> String {
     path
 }
 
-/// Start the web server with message passing to ECS
+/// Everything the web server shares with the rest of FluxION
 ///
-/// # Arguments
-/// * `query_sender` - Channel sender to query ECS World
-/// * `i18n` - Internationalization support
-/// * `port` - Port to listen on (8099 for HA Ingress)
-/// * `config_json` - Current configuration as JSON
-/// * `config_update_sender` - Optional channel for config updates
-/// * `backtest_db_path` - Optional path to backtest database
-/// * `plugin_api_state` - Optional plugin API state for plugin management
-/// * `scheduled_export_config` - Optional config for daily scheduled exports (for debugging)
-/// * `user_control_api_state` - Optional user control API state for user override features
+/// Optional parts leave their routes out, or answer them as unavailable.
+pub struct WebServerDeps {
+    /// Channel sender to query ECS World
+    pub query_sender: WebQuerySender,
+    /// Internationalization support
+    pub i18n: Arc<I18n>,
+    /// Port to listen on (8099 for HA Ingress)
+    pub port: u16,
+    /// Config API state (current config JSON, persistence and ECS updates)
+    pub config_state: ConfigApiState,
+    /// Optional path to backtest database
+    pub backtest_db_path: Option<std::path::PathBuf>,
+    /// Optional plugin API state for plugin management
+    pub plugin_api_state: Option<PluginApiState>,
+    /// Optional config for daily scheduled exports (for debugging)
+    pub scheduled_export_config: Option<ScheduledExportConfig>,
+    /// Optional user control API state for user override features
+    pub user_control_api_state: Option<UserControlApiState>,
+    /// Optional remote access (Tor pairing) API state
+    pub remote_access_state: Option<RemoteAccessApiState>,
+    /// Optional API key store; when set, scopes are enforced on all routes
+    pub api_key_state: Option<ApiKeyApiState>,
+    /// Optional first-run setup wizard state
+    pub setup_wizard_state: Option<SetupWizardState>,
+    /// Optional live entity mapping validation
+    pub mapping_check_state: Option<MappingCheckState>,
+    /// Optional grid voltage/frequency quality log
+    pub grid_quality_monitor: Option<fluxion_core::grid_quality::GridQualityMonitor>,
+    /// Optional export cap window compliance log
+    pub export_cap_monitor: Option<fluxion_core::export_cap::ExportCapMonitor>,
+    /// Optional installer self-test of the control path
+    pub self_test_state: Option<SelfTestState>,
+    /// Optional log of executed block decisions
+    pub decision_log: Option<fluxion_core::decision_log::DecisionLog>,
+    /// Optional ledger of realized versus expected profit
+    pub savings_ledger: Option<fluxion_core::savings::SavingsLedger>,
+    /// Optional standalone login; when enabled, mutating routes need a token or session
+    pub auth_state: Option<AuthState>,
+    /// Optional ECS state snapshot, set in developer mode
+    pub ecs_inspector: Option<fluxion_core::inspector::EcsInspector>,
+    /// Installer product name, logo and colors
+    pub branding: BrandingConfig,
+    /// Optional commercial license status
+    pub license_state: Option<LicenseState>,
+    /// Optional user-defined price and battery alert rules
+    pub alert_manager: Option<fluxion_core::alerts::AlertManager>,
+    /// Optional hot water target temperature plan
+    pub dhw_planner: Option<fluxion_core::dhw::DhwPlanner>,
+    /// Optional telemetry history beyond Home Assistant's recorder
+    pub historian: Option<fluxion_core::historian::Historian>,
+    /// Sub-metered appliances, when their readings go to the historian
+    pub submeter_devices: Option<Vec<fluxion_core::submeter::SubmeterDevice>>,
+    /// Optional inputs of the live schedule for what-if schedules
+    pub what_if_planner: Option<fluxion_core::what_if::WhatIfPlanner>,
+}
+
+impl std::fmt::Debug for WebServerDeps {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebServerDeps")
+            .field("query_sender", &"<WebQuerySender>")
+            .field("port", &self.port)
+            .field("config_state", &self.config_state)
+            .field("backtest_db_path", &self.backtest_db_path)
+            .field("api_keys", &self.api_key_state.is_some())
+            .field("auth", &self.auth_state.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Start the web server with message passing to ECS
 ///
 /// # HA Ingress Support
 /// When running as HA addon, routes are accessible via:
@@ -246,29 +450,57 @@ fn extract_ingress_path(headers: &axum::http::HeaderMap) -> String {
 ///
 /// # Errors
 /// Returns error if server fails to bind or serve
-#[expect(clippy::too_many_arguments)]
 #[expect(clippy::too_many_lines)]
-pub async fn start_web_server(
-    query_sender: WebQuerySender,
-    i18n: Arc<I18n>,
-    port: u16,
-    config_json: serde_json::Value,
-    config_update_sender: Option<ConfigUpdateSender>,
-    backtest_db_path: Option<std::path::PathBuf>,
-    plugin_api_state: Option<PluginApiState>,
-    scheduled_export_config: Option<ScheduledExportConfig>,
-    user_control_api_state: Option<UserControlApiState>,
-    remote_access_state: Option<RemoteAccessApiState>,
-) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn start_web_server(deps: WebServerDeps) -> Result<(), Box<dyn std::error::Error>> {
+    let WebServerDeps {
+        query_sender,
+        i18n,
+        port,
+        config_state,
+        backtest_db_path,
+        plugin_api_state,
+        scheduled_export_config,
+        user_control_api_state,
+        remote_access_state,
+        api_key_state,
+        setup_wizard_state,
+        mapping_check_state,
+        grid_quality_monitor,
+        export_cap_monitor,
+        self_test_state,
+        decision_log,
+        savings_ledger,
+        auth_state,
+        ecs_inspector,
+        branding,
+        license_state,
+        alert_manager,
+        dhw_planner,
+        historian,
+        submeter_devices,
+        what_if_planner,
+    } = deps;
+
+    // Extract user control state from API state for dashboard rendering and exports
+    let user_control_state = user_control_api_state
+        .as_ref()
+        .map(|uc| Arc::clone(&uc.state));
+
+    // Backtest days and savings periods are energy days in the HA timezone
+    let local_time_formatter = config_api::time_formatter(&config_state.config.read());
+
     // Spawn scheduled export task if configured
+    let export_config = scheduled_export_config.clone().unwrap_or_default();
     if let Some(export_config) = scheduled_export_config {
-        spawn_scheduled_export_task(query_sender.clone(), export_config);
+        spawn_scheduled_export_task(
+            query_sender.clone(),
+            export_config,
+            user_control_state.clone(),
+        );
     }
 
-    // Extract user control state from API state for dashboard rendering
-    let user_control_state = user_control_api_state
-        .as_ref()
-        .map(|uc| Arc::clone(&uc.state));
+    branding::install(branding, &i18n);
+    ui_preferences::install(std::path::Path::new("./data"));
 
     // Pre-clone values needed for mobile API routes (before they're moved)
     let mobile_query_sender = query_sender.clone();
@@ -279,16 +511,42 @@ pub async fn start_web_server(
         query_sender,
         i18n: i18n.clone(),
         user_control_state,
+        export_config,
     };
-    let config_state =
-        config_api::ConfigApiState::new(config_json, "/data/config.json", config_update_sender);
-
     let mut app = Router::new()
         .route("/", get(index_handler))
+        .route("/guest", get(guest_handler))
         .route("/stream", get(stream_handler))
-        .route("/chart-data", get(chart_data_handler))
+        .route("/branding/logo", get(branding::logo_handler))
+        .route(
+            "/chart-data",
+            get(chart_data_handler).layer(axum::middleware::from_fn(etag::etag_middleware)),
+        )
+        // No ETag: every export carries its generation timestamp
         .route("/export", get(export_handler))
+        .route("/api/preview", get(preview::preview_handler))
+        .merge(upcoming::schedule_routes())
+        .route("/api/tariff", get(tariff::tariff_handler))
+        .route("/api/openapi.json", get(openapi::openapi_handler))
+        .route("/api/version", get(api_version::api_version_handler))
+        .route(
+            "/api/ui/preferences",
+            get(ui_preferences::get_preferences_handler)
+                .put(ui_preferences::update_preferences_handler),
+        )
         .route("/health", get(health_handler))
+        .route("/health/tasks", get(tasks_health_handler))
+        .route("/health/queries", get(queries_health_handler))
+        .route("/status.json", get(status::status_json_handler))
+        .route("/metrics", get(metrics::metrics_handler))
+        .route(
+            "/api/help",
+            get(help::help_index_handler).with_state(i18n.clone()),
+        )
+        .route(
+            "/api/help/{topic}",
+            get(help::help_topic_handler).with_state(i18n.clone()),
+        )
         // Config API routes
         .route(
             "/api/config",
@@ -299,23 +557,58 @@ pub async fn start_web_server(
             axum::routing::post(config_api::validate_config_handler)
                 .with_state(config_state.clone()),
         )
+        .route(
+            "/api/config/preview",
+            axum::routing::post(config_preview::preview_config_handler)
+                .with_state(config_state.clone()),
+        )
         .route(
             "/api/config/update",
             axum::routing::post(config_api::update_config_handler).with_state(config_state.clone()),
         )
+        .route(
+            "/api/config/language",
+            axum::routing::put(config_api::set_language_handler).with_state(config_state.clone()),
+        )
+        .route(
+            "/api/config/debug-mode",
+            get(config_api::get_debug_mode_handler)
+                .put(config_api::set_debug_mode_handler)
+                .with_state(config_state.clone()),
+        )
         .route(
             "/api/config/reset",
             axum::routing::post(config_api::reset_section_handler).with_state(config_state.clone()),
         )
+        .route(
+            "/api/config/strategies",
+            get(strategy_config_api::list_strategies_handler).with_state(config_state.clone()),
+        )
+        .route(
+            "/api/config/strategies/{key}",
+            axum::routing::put(strategy_config_api::update_strategy_handler)
+                .with_state(config_state.clone()),
+        )
+        .route(
+            "/api/config/import",
+            axum::routing::post(config_import::import_config_handler)
+                .with_state(config_state.clone()),
+        )
         .route(
             "/api/config/export",
-            get(config_api::export_config_handler).with_state(config_state),
+            get(config_api::export_config_handler).with_state(config_state.clone()),
+        )
+        .route(
+            "/api/logs/download",
+            get(config_api::download_log_handler).with_state(config_state),
         );
 
     // Add backtest routes if database path is provided
+    let mut wizard_history = None;
     if let Some(db_path) = backtest_db_path {
         info!("📊 Backtest feature enabled with database: {:?}", db_path);
-        let backtest_state = backtest::BacktestState::new(db_path, i18n);
+        let backtest_state = backtest::BacktestState::new(db_path, i18n, local_time_formatter);
+        wizard_history = Some(Arc::clone(&backtest_state.data_source));
 
         app = app
             .route(
@@ -334,6 +627,19 @@ pub async fn start_web_server(
                 "/api/backtest/simulate",
                 axum::routing::post(backtest::simulate_handler).with_state(backtest_state.clone()),
             )
+            .route(
+                "/api/backtest/range",
+                get(backtest::range_handler).with_state(backtest_state.clone()),
+            )
+            .route(
+                "/api/backtest/sweep",
+                axum::routing::post(backtest::sweep_start_handler)
+                    .with_state(backtest_state.clone()),
+            )
+            .route(
+                "/api/backtest/sweep/{id}",
+                get(backtest::sweep_status_handler).with_state(backtest_state.clone()),
+            )
             .route(
                 "/api/backtest/compare",
                 axum::routing::post(backtest::compare_handler).with_state(backtest_state),
@@ -365,17 +671,32 @@ pub async fn start_web_server(
             )
             .route(
                 "/api/plugins/{name}/enabled",
-                axum::routing::put(plugin_api::update_enabled_handler).with_state(plugin_state),
-            );
+                axum::routing::put(plugin_api::update_enabled_handler)
+                    .with_state(plugin_state.clone()),
+            )
+            .route(
+                "/api/plugins/{name}/health",
+                get(plugin_api::plugin_health_handler).with_state(plugin_state.clone()),
+            )
+            .route(
+                "/api/strategies/docs",
+                get(plugin_api::strategy_docs_handler).with_state(plugin_state),
+            )
+            .route("/strategies", get(plugin_api::strategy_docs_page_handler));
     }
 
     // Add strategy simulator routes
     {
         info!("🧪 Strategy Simulator API enabled");
-        let simulator_state = simulator::SimulatorState::new();
+        let simulator_state =
+            simulator::SimulatorState::new().with_run_store(std::path::Path::new("./data"));
         app = app
             // Simulator page
             .route("/simulator", get(simulator::simulator_page_handler))
+            .route(
+                "/simulator/runs/{run_id}",
+                get(simulator::saved_run_page_handler),
+            )
             // API endpoints
             .route(
                 "/api/simulator/presets",
@@ -386,6 +707,10 @@ pub async fn start_web_server(
                 axum::routing::post(simulator::create_simulation_handler)
                     .with_state(simulator_state.clone()),
             )
+            .route(
+                "/api/simulator/batch",
+                axum::routing::post(simulator::batch_handler).with_state(simulator_state.clone()),
+            )
             .route(
                 "/api/simulator/{id}",
                 get(simulator::get_simulation_handler).with_state(simulator_state.clone()),
@@ -417,6 +742,25 @@ pub async fn start_web_server(
                 axum::routing::put(simulator::override_price_handler)
                     .with_state(simulator_state.clone()),
             )
+            .route(
+                "/api/simulator/{id}/save",
+                axum::routing::post(simulator::save_run_handler)
+                    .with_state(simulator_state.clone()),
+            )
+            .route(
+                "/api/simulator/runs",
+                get(simulator::list_runs_handler).with_state(simulator_state.clone()),
+            )
+            .route(
+                "/api/simulator/runs/{run_id}/open",
+                axum::routing::post(simulator::open_run_handler)
+                    .with_state(simulator_state.clone()),
+            )
+            .route(
+                "/api/simulator/runs/{run_id}",
+                axum::routing::delete(simulator::delete_run_handler)
+                    .with_state(simulator_state.clone()),
+            )
             .route(
                 "/api/simulator/{id}/reset",
                 axum::routing::post(simulator::reset_handler).with_state(simulator_state.clone()),
@@ -458,7 +802,48 @@ pub async fn start_web_server(
             )
             .route(
                 "/api/user-control/slots/{id}",
-                axum::routing::delete(user_control_api::delete_slot).with_state(uc_state),
+                axum::routing::delete(user_control_api::delete_slot).with_state(uc_state.clone()),
+            )
+            .route(
+                "/api/user-control/slots/{id}/restore",
+                axum::routing::post(user_control_api::restore_slot).with_state(uc_state.clone()),
+            )
+            .route(
+                "/api/schedule/pin",
+                axum::routing::post(user_control_api::pin_block).with_state(uc_state.clone()),
+            )
+            .route(
+                "/api/schedule/pin/{id}",
+                axum::routing::delete(user_control_api::unpin_block).with_state(uc_state.clone()),
+            )
+            // Holidays and vacations (vacations are stored with the user control state)
+            .route(
+                "/api/calendar",
+                get(calendar::get_calendar).with_state(uc_state.clone()),
+            )
+            .route(
+                "/api/calendar/vacations",
+                axum::routing::post(calendar::create_vacation).with_state(uc_state.clone()),
+            )
+            .route(
+                "/api/calendar/vacations/{id}",
+                axum::routing::delete(calendar::delete_vacation).with_state(uc_state.clone()),
+            )
+            // Temporary backup reserve (stored with the user control state)
+            .route(
+                "/api/backup-reserve",
+                get(backup_reserve::get_backup_reserve)
+                    .put(backup_reserve::set_backup_reserve)
+                    .delete(backup_reserve::clear_backup_reserve)
+                    .with_state(uc_state.clone()),
+            )
+            // Emergency safe state (stored alongside user control state)
+            .route(
+                "/api/system/safe-state",
+                get(safe_state_api::get_safe_state)
+                    .post(safe_state_api::engage_safe_state)
+                    .delete(safe_state_api::resume_safe_state)
+                    .with_state(uc_state),
             );
     }
 
@@ -469,6 +854,7 @@ pub async fn start_web_server(
     // Add remote access routes (self-contained state, merged after main state)
     if let Some(ra_state) = remote_access_state {
         info!("Remote Access API enabled");
+        let device_store = Arc::clone(&ra_state.device_store);
         app = app.merge(remote_access_routes(ra_state));
 
         // Add mobile-facing API routes (served over Tor to mobile devices)
@@ -477,17 +863,194 @@ pub async fn start_web_server(
             i18n: mobile_i18n.clone(),
             user_control_api_state: mobile_uc_api.clone(),
             ui_version: env!("CARGO_PKG_VERSION").to_owned(),
+            device_store,
+            savings: savings_ledger.clone().map(|ledger| savings::SavingsState {
+                ledger,
+                time_formatter: local_time_formatter,
+            }),
         };
         app = app.merge(mobile_api_routes(mobile_state));
     }
 
+    // First-run setup wizard
+    if let Some(setup_state) = setup_wizard_state {
+        if setup_state.is_pending() {
+            info!("🧙 First run: setup wizard available at /setup");
+        }
+        app = app.merge(setup_wizard::setup_wizard_routes(setup_state));
+    }
+
+    // Strategy recommendation wizard (history when recorded, presets otherwise)
+    app = app.merge(strategy_wizard::strategy_wizard_routes(
+        strategy_wizard::StrategyWizardState::new(wizard_history),
+    ));
+
+    // Entity mapping checks against the live Home Assistant states
+    if let Some(check_state) = mapping_check_state {
+        app = app.route(
+            "/api/mapping/validate",
+            get(mapping_check::validate_mapping_handler).with_state(check_state),
+        );
+    }
+
+    // Grid quality statistics and excursion events
+    if let Some(monitor) = grid_quality_monitor {
+        app = app
+            .route(
+                "/api/grid-quality",
+                get(grid_quality::grid_quality_handler).with_state(monitor.clone()),
+            )
+            .route(
+                "/api/grid-quality/events.csv",
+                get(grid_quality::grid_quality_events_csv_handler).with_state(monitor),
+            );
+    }
+
+    // End-to-end self-test for installers
+    if let Some(test_state) = self_test_state {
+        app = app.route(
+            "/api/system/self-test",
+            axum::routing::post(self_test::self_test_handler).with_state(test_state),
+        );
+    }
+
+    // Export cap windows and their compliance report
+    if let Some(monitor) = export_cap_monitor {
+        app = app
+            .route(
+                "/api/export-cap",
+                get(export_cap::export_cap_handler).with_state(monitor.clone()),
+            )
+            .route(
+                "/api/export-cap/report.csv",
+                get(export_cap::export_cap_csv_handler).with_state(monitor),
+            );
+    }
+
+    // Why each past block ran in the mode it did
+    if let Some(log) = decision_log {
+        app = app.route(
+            "/api/decisions",
+            get(decisions::decisions_handler).with_state(log),
+        );
+    }
+
+    // Telemetry kept after Home Assistant purges its history
+    if let Some(historian) = historian {
+        // Per-appliance kWh and cost
+        if let Some(devices) = submeter_devices {
+            let submeter_state = submeter::SubmeterState {
+                historian: historian.clone(),
+                devices,
+                time_formatter: local_time_formatter,
+            };
+            app = app.route(
+                "/api/submeter",
+                get(submeter::submeter_handler).with_state(submeter_state),
+            );
+        }
+        app = app.route(
+            "/api/history",
+            get(history::history_handler).with_state(historian.clone()),
+        );
+        app = app.route(
+            "/chart-data/history",
+            get(chart_history::chart_history_handler).with_state(historian),
+        );
+    }
+
+    // Whether the expected profit of past blocks materialized
+    if let Some(ledger) = savings_ledger {
+        let savings_state = savings::SavingsState {
+            ledger,
+            time_formatter: local_time_formatter,
+        };
+        app = app.route(
+            "/api/savings",
+            get(savings::savings_handler).with_state(savings_state),
+        );
+    }
+
+    // Live ECS state for developers
+    if let Some(inspector) = ecs_inspector {
+        app = app.route(
+            "/api/debug/ecs",
+            get(inspector::ecs_inspector_handler).with_state(inspector),
+        );
+    }
+
+    // Alternative schedules from the live planning inputs
+    if let Some(planner) = what_if_planner {
+        app = app.route(
+            "/api/schedule/what-if",
+            axum::routing::post(what_if::what_if_handler).with_state(planner),
+        );
+    }
+
+    // Commercial license status for the dashboard notice
+    if let Some(license_state) = license_state {
+        app = app.route(
+            "/api/license",
+            get(license::license_handler).with_state(license_state),
+        );
+    }
+
+    // User-defined price and battery alerts
+    if let Some(manager) = alert_manager {
+        app = app.merge(alerts::alert_routes(manager));
+    }
+
+    // Price-aware hot water targets, drawn under the price chart
+    if let Some(planner) = dhw_planner {
+        app = app.merge(dhw::dhw_routes(planner));
+    }
+
+    // API keys for external automation clients (enforcement wraps every route above)
+    if let Some(key_state) = api_key_state {
+        info!("🔑 API key enforcement enabled");
+        app = app
+            .merge(api_keys::api_key_routes(key_state.clone()))
+            .layer(axum::middleware::from_fn_with_state(
+                key_state,
+                api_keys::require_api_key,
+            ));
+    }
+
+    // Login for the standalone server (outermost, so it runs before the API key scopes)
+    if let Some(auth_state) = auth_state {
+        app = app.merge(auth::auth_routes(auth_state.clone()));
+        if auth_state.is_enabled() {
+            info!("🔒 Web authentication enabled for changes outside Home Assistant");
+        }
+        app = app.layer(axum::middleware::from_fn_with_state(
+            auth_state,
+            auth::require_login,
+        ));
+    }
+
+    // Uniform JSON error bodies for API clients (browser page loads keep their HTML)
+    app = app.layer(axum::middleware::from_fn(api_error::json_error_middleware));
+
+    // gzip/brotli for all responses (SSE and tiny bodies are skipped by the default predicate)
+    let app = app.layer(CompressionLayer::new());
+
+    // `/api/v1/...` is mapped onto the routes above before routing happens
+    let app = axum::middleware::from_fn(api_version::api_version_middleware).layer(app);
+
     let addr = format!("0.0.0.0:{port}");
     info!("🌐 Starting web server on {addr}");
     info!("📱 Standalone: http://localhost:{}/", port);
     info!("🏠 HA Ingress: http://homeassistant:8123/api/hassio_ingress/fluxion/");
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    // Peer address is needed to tell HA ingress/localhost apart from external clients
+    axum::serve(
+        listener,
+        axum::ServiceExt::<axum::extract::Request>::into_make_service_with_connect_info::<
+            std::net::SocketAddr,
+        >(app),
+    )
+    .await?;
 
     Ok(())
 }
@@ -496,24 +1059,49 @@ pub async fn start_web_server(
 async fn index_handler(
     State(app_state): State<AppState>,
     headers: axum::http::HeaderMap,
-) -> impl IntoResponse {
-    debug!("Dashboard page requested");
-    let ingress_path = extract_ingress_path(&headers);
+    guest: Option<axum::Extension<auth::GuestView>>,
+) -> axum::response::Response {
+    render_dashboard(&app_state, &headers, guest.is_some()).await
+}
 
-    // Get user control state for dashboard rendering
+/// Read-only guest dashboard handler
+/// The login middleware checks the token and marks the request as guest
+async fn guest_handler(
+    State(app_state): State<AppState>,
+    headers: axum::http::HeaderMap,
+    guest: Option<axum::Extension<auth::GuestView>>,
+) -> axum::response::Response {
+    if guest.is_none() {
+        return axum::http::StatusCode::NOT_FOUND.into_response();
+    }
+    render_dashboard(&app_state, &headers, true).await
+}
+
+/// Render the dashboard, without controls for guests
+async fn render_dashboard(
+    app_state: &AppState,
+    headers: &axum::http::HeaderMap,
+    guest: bool,
+) -> axum::response::Response {
+    debug!("Dashboard page requested (guest: {guest})");
+    let ingress_path = extract_ingress_path(headers);
+
+    // Get user control state for dashboard rendering (guests get no control panel)
     let user_control = app_state
         .user_control_state
         .as_ref()
+        .filter(|_| !guest)
         .map(|uc| uc.read().clone());
 
     match app_state.query_sender.query_dashboard().await {
         Ok(response) => {
-            let template = DashboardTemplate::from_query_response(
+            let mut template = DashboardTemplate::from_query_response(
                 response,
                 app_state.i18n.clone(),
                 ingress_path,
                 user_control,
             );
+            template.guest = guest;
             // Askama 0.14: use .render() and convert to axum Html response
             match template.render() {
                 Ok(html) => Html(html).into_response(),
@@ -599,6 +1187,10 @@ struct ChartDataJson {
     profits: Vec<Option<f32>>,
     current_time_label: Option<String>,
     current_battery_soc: Option<f32>,
+    /// Battery power per history block (W, positive = charge)
+    battery_power_history: Vec<BatteryPowerPoint>,
+    /// Grid import/export per history block (W)
+    grid_power_history: Vec<GridPowerPoint>,
     // Stacked bar data for HDO display
     spot_prices: Vec<f32>,
     grid_fees: Vec<f32>,
@@ -645,18 +1237,11 @@ async fn chart_data_handler(State(app_state): State<AppState>) -> impl IntoRespo
                 .filter(|sf| sf.available)
                 .map(|sf| sf.tomorrow_kwh);
 
-            // Calculate UTC offset from timezone string
-            let utc_offset_minutes = response
-                .timezone
-                .as_ref()
-                .and_then(|tz_name| tz_name.parse::<chrono_tz::Tz>().ok())
-                .map(|tz| {
-                    let now = chrono::Utc::now().with_timezone(&tz);
-                    #[expect(clippy::integer_division)]
-                    {
-                        now.offset().fix().local_minus_utc() / 60
-                    }
-                });
+            // UTC offset of the HA timezone (None when it is unknown)
+            let formatter = response.time_formatter();
+            let utc_offset_minutes = formatter
+                .timezone_name()
+                .map(|_| formatter.utc_offset_minutes(Utc::now()));
 
             // Extract chart data from template
             if let Some(prices) = template.prices {
@@ -668,6 +1253,8 @@ async fn chart_data_handler(State(app_state): State<AppState>) -> impl IntoRespo
                     profits: prices.chart_data.profits,
                     current_time_label: prices.chart_data.current_time_label,
                     current_battery_soc,
+                    battery_power_history: prices.chart_data.battery_power_history,
+                    grid_power_history: prices.chart_data.grid_power_history,
                     // Stacked bar data
                     spot_prices: prices.chart_data.spot_prices,
                     grid_fees: prices.chart_data.grid_fees,
@@ -686,29 +1273,68 @@ async fn chart_data_handler(State(app_state): State<AppState>) -> impl IntoRespo
                 (axum::http::StatusCode::NO_CONTENT, "").into_response()
             }
         }
-        Err(_) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "").into_response(),
+        Err(e) => query_error_response(e),
     }
 }
 
+/// Query of the export endpoint
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    /// `json` (default), `csv` or `parquet`
+    #[serde(default)]
+    format: ExportFormat,
+    /// Table of a CSV or Parquet export: `blocks` (default) or `samples`
+    #[serde(default)]
+    table: ExportTable,
+}
+
 /// Export data endpoint - returns compact JSON for analysis
-/// Optimized format with abbreviated field names, Unix timestamps, and encoded decision reasons
-async fn export_handler(State(app_state): State<AppState>) -> impl IntoResponse {
+/// Optimized format with abbreviated field names, Unix timestamps, and encoded decision reasons.
+/// `?format=csv` or `?format=parquet` return one flat table instead (`&table=samples`
+/// for the history samples, per-block rows otherwise).
+async fn export_handler(
+    State(app_state): State<AppState>,
+    Query(query): Query<ExportQuery>,
+) -> impl IntoResponse {
     match app_state.query_sender.query_dashboard().await {
         Ok(response) => {
-            // Format timestamp for filename
-            let filename = format!(
-                "fluxion_export_{}.json",
-                response.timestamp.format("%Y%m%d_%H%M%S")
-            );
-
-            // Create compact JSON structure with space optimizations
-            let export_data = create_compact_export(&response);
-
-            let json_string = match serde_json::to_string_pretty(&export_data) {
-                Ok(json) => json,
-                Err(e) => {
-                    error!("Failed to serialize compact export data: {}", e);
-                    return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "").into_response();
+            let formatter = response.time_formatter();
+            let (filename, body) = if let Some(encoded) =
+                export_formats::encode_table(&response, query.table, query.format)
+            {
+                let filename = app_state.export_config.table_file_name(
+                    "export",
+                    &formatter,
+                    response.timestamp,
+                    query.table,
+                    query.format,
+                );
+                match encoded {
+                    Ok(bytes) => (filename, bytes),
+                    Err(e) => {
+                        error!("Failed to encode {:?} export: {}", query.format, e);
+                        return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "").into_response();
+                    }
+                }
+            } else {
+                let filename =
+                    app_state
+                        .export_config
+                        .file_name("export", &formatter, response.timestamp);
+
+                // Create compact JSON structure with space optimizations
+                let user_control = app_state
+                    .user_control_state
+                    .as_ref()
+                    .map(|uc| uc.read().clone());
+                let export_data = create_compact_export(&response, user_control.as_ref());
+
+                match serde_json::to_vec_pretty(&export_data) {
+                    Ok(json) => (filename, json),
+                    Err(e) => {
+                        error!("Failed to serialize compact export data: {}", e);
+                        return (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "").into_response();
+                    }
                 }
             };
 
@@ -716,7 +1342,7 @@ async fn export_handler(State(app_state): State<AppState>) -> impl IntoResponse
             let mut headers = axum::http::HeaderMap::new();
             headers.insert(
                 axum::http::header::CONTENT_TYPE,
-                "application/json".parse().unwrap(),
+                query.format.content_type().parse().unwrap(),
             );
             headers.insert(
                 axum::http::header::CONTENT_DISPOSITION,
@@ -725,11 +1351,11 @@ async fn export_handler(State(app_state): State<AppState>) -> impl IntoResponse
                     .unwrap(),
             );
 
-            (headers, json_string).into_response()
+            (headers, body).into_response()
         }
         Err(e) => {
             error!("Failed to query dashboard data for export: {}", e);
-            (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "").into_response()
+            query_error_response(e)
         }
     }
 }
@@ -745,18 +1371,91 @@ async fn health_handler(State(app_state): State<AppState>) -> impl IntoResponse
                 (axum::http::StatusCode::SERVICE_UNAVAILABLE, "DEGRADED")
             }
         }
+        Err(e) if e.is_overload() => (axum::http::StatusCode::SERVICE_UNAVAILABLE, "OVERLOADED"),
         Err(_) => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "ERROR"),
     }
 }
 
+/// Response for a failed ECS query
+/// 503 with `Retry-After` while the ECS is too busy, 500 otherwise
+pub(crate) fn query_error_response(error: QueryError) -> axum::response::Response {
+    if error.is_overload() {
+        let mut response = api_error::error_response(
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            "System is busy, retry shortly",
+        );
+        response.headers_mut().insert(
+            axum::http::header::RETRY_AFTER,
+            axum::http::HeaderValue::from_static("5"),
+        );
+        response
+    } else {
+        api_error::error_response(
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to query system state",
+        )
+    }
+}
+
+/// Web query queue status handler
+/// Reports whether the ECS keeps up with dashboard and API queries
+async fn queries_health_handler(State(app_state): State<AppState>) -> impl IntoResponse {
+    let health = app_state.query_sender.health();
+    let status = if health.keeping_up {
+        axum::http::StatusCode::OK
+    } else {
+        axum::http::StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(health))
+}
+
+/// Background task status handler
+/// Lists supervised background tasks with their state and restart counts
+async fn tasks_health_handler() -> impl IntoResponse {
+    let supervisor = fluxion_core::TaskSupervisor::global();
+    let status = if supervisor.all_healthy() {
+        axum::http::StatusCode::OK
+    } else {
+        axum::http::StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(serde_json::json!({
+            "healthy": supervisor.all_healthy(),
+            "tasks": supervisor.snapshot(),
+        })),
+    )
+}
+
 /// Create compact export data with space optimizations
 #[expect(clippy::too_many_lines)]
-fn create_compact_export(response: &WebQueryResponse) -> serde_json::Value {
+fn create_compact_export(
+    response: &WebQueryResponse,
+    user_control: Option<&UserControlState>,
+) -> serde_json::Value {
+    let compact_slot = |slot: &fluxion_types::FixedTimeSlot| {
+        serde_json::json!({
+            "id": slot.id,
+            "from": slot.from.timestamp(),
+            "to": slot.to.timestamp(),
+            "mode": format!("{:?}", slot.mode),
+            "note": slot.note,
+        })
+    };
+
+    // Energy day the "today" figures refer to (local midnight to midnight)
+    let day = response.time_formatter().energy_day_at(response.timestamp);
+
     serde_json::json!({
         // Metadata with abbreviated keys
         "meta": {
             "ts": response.timestamp.timestamp(),
             "tz": response.timezone,
+            "day": {
+                "date": day.date.to_string(),
+                "from": day.start.timestamp(),
+                "to": day.end.timestamp(),
+            },
             "dbg": response.debug_mode,
             "ver": "2.0",
             "desc": "FluxION compact export"
@@ -812,6 +1511,7 @@ fn create_compact_export(response: &WebQueryResponse) -> serde_json::Value {
             serde_json::json!({
                 "mode": sched.current_mode,
                 "reason": sched.current_reason,
+                "reason_code": sched.current_decision_reason,
                 "strategy": sched.current_strategy,
                 "profit": sched.expected_profit.map(round_2_decimals),
                 "next": sched.next_change.map(|dt| dt.timestamp()),
@@ -839,8 +1539,13 @@ fn create_compact_export(response: &WebQueryResponse) -> serde_json::Value {
                         "soc": block.target_soc.map(round_1_decimal),
                         "st": block.strategy.as_ref().map(|s| abbreviate_strategy(s)),
                         "pr": block.expected_profit.map(round_2_decimals),
-                        "r": block.reason.as_ref().map(|r| abbreviate_reason(r)),
+                        "r": compact_reason(block),
                         "uid": block.decision_uid.as_ref(),
+                        // Forecast used at decision time vs realized energy (kWh)
+                        "pv_fc": block.forecast.map(|f| round_2_decimals(f.solar_kwh)),
+                        "load_fc": block.forecast.map(|f| round_2_decimals(f.consumption_kwh)),
+                        "pv_act": block.actual.map(|a| round_2_decimals(a.solar_kwh)),
+                        "load_act": block.actual.map(|a| round_2_decimals(a.consumption_kwh)),
                         "h": block.is_historical
                     })
                 }).collect::<Vec<_>>(),
@@ -891,6 +1596,22 @@ fn create_compact_export(response: &WebQueryResponse) -> serde_json::Value {
                 "today_kwh": stats.today_import_kwh.map(round_2_decimals),
                 "yesterday_kwh": stats.yesterday_import_kwh.map(round_2_decimals),
             })
+        }),
+
+        // User control, including deleted slots and who deleted them
+        "uc": user_control.map(|uc| {
+            serde_json::json!({
+                "en": uc.enabled,
+                "no_chg": uc.disallow_charge,
+                "no_dis": uc.disallow_discharge,
+                "slots": uc.fixed_time_slots.iter().map(compact_slot).collect::<Vec<_>>(),
+                "archived": uc.archived_slots.iter().map(|archived| {
+                    let mut slot = compact_slot(&archived.slot);
+                    slot["by"] = serde_json::json!(archived.deleted_by);
+                    slot["at"] = serde_json::json!(archived.deleted_at.timestamp());
+                    slot
+                }).collect::<Vec<_>>(),
+            })
         })
     })
 }
@@ -929,12 +1650,65 @@ fn abbreviate_strategy(strategy: &str) -> String {
     }
 }
 
+/// Structured reason of a block, recognized from the legacy text when the schedule has none
+fn compact_reason(block: &PriceBlockData) -> Option<DecisionReason> {
+    let reason = block
+        .decision_reason
+        .clone()
+        .or_else(|| block.reason.as_deref().map(DecisionReason::from_string))?;
+    if let DecisionReason::Custom { reason } = &reason {
+        return Some(DecisionReason::Custom {
+            reason: abbreviate_reason(reason),
+        });
+    }
+    Some(reason)
+}
+
 fn abbreviate_reason(reason: &str) -> String {
-    // For now, just truncate long reasons to save space
-    // Later we could implement the full DecisionReason enum parsing
+    // Free-form reasons are truncated to save space
     if reason.len() > 50 {
         format!("{}...", reason.get(..50).unwrap_or_default())
     } else {
         reason.to_owned()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_file_name_template() {
+        let prague = TimeFormatter::from_timezone_name(Some("Europe/Prague"));
+        let time = "2025-03-30T21:55:00Z".parse().unwrap();
+
+        let default = ScheduledExportConfig::default();
+        assert_eq!(
+            default.file_name("daily", &prague, time),
+            "fluxion_daily_20250330_235500.json"
+        );
+
+        let fleet = ScheduledExportConfig {
+            filename_template: "{site}/{date}_{kind}_v{version}".to_owned(),
+            site_name: "Brno Farm 2".to_owned(),
+            ..ScheduledExportConfig::default()
+        };
+        assert_eq!(
+            fleet.file_name("daily", &prague, time),
+            format!(
+                "Brno_Farm_2_2025-03-30_daily_v{}.json",
+                env!("CARGO_PKG_VERSION")
+            )
+        );
+        assert_eq!(
+            default.table_file_name(
+                "daily",
+                &prague,
+                time,
+                ExportTable::Samples,
+                ExportFormat::Parquet
+            ),
+            "fluxion_daily_20250330_235500_samples.parquet"
+        );
+    }
+}
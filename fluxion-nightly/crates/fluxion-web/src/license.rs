@@ -0,0 +1,160 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Commercial license status shown in the web UI.
+//!
+//! The key is validated against FluxION Server by fluxion-main; this module
+//! only holds the latest result. Control never depends on it: without a valid
+//! license the instance keeps working and only fleet features are withheld.
+
+use axum::{Json, extract::State, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Outcome of the latest license check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LicenseStatus {
+    /// No key configured (non-commercial use)
+    Unlicensed,
+    /// Key configured, first check still running
+    Checking,
+    Valid,
+    /// The server rejected the key or it expired
+    Invalid,
+    /// The server could not be reached for too long to trust an earlier check
+    Unverified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LicenseInfo {
+    pub status: LicenseStatus,
+    pub licensee: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Fleet telemetry on FluxION Server is unlocked
+    pub fleet: bool,
+    pub message: Option<String>,
+    /// Last time the server answered
+    pub checked_at: Option<DateTime<Utc>>,
+}
+
+impl LicenseInfo {
+    #[must_use]
+    pub fn unlicensed() -> Self {
+        Self {
+            status: LicenseStatus::Unlicensed,
+            licensee: None,
+            expires_at: None,
+            fleet: false,
+            message: None,
+            checked_at: None,
+        }
+    }
+
+    #[must_use]
+    pub fn checking() -> Self {
+        Self {
+            status: LicenseStatus::Checking,
+            ..Self::unlicensed()
+        }
+    }
+}
+
+/// Latest license check, written by the license task and read by the web API
+#[derive(Debug, Clone)]
+pub struct LicenseState {
+    info: Arc<RwLock<LicenseInfo>>,
+}
+
+impl LicenseState {
+    #[must_use]
+    pub fn new(info: LicenseInfo) -> Self {
+        Self {
+            info: Arc::new(RwLock::new(info)),
+        }
+    }
+
+    #[must_use]
+    pub fn info(&self) -> LicenseInfo {
+        self.info.read().clone()
+    }
+
+    pub fn set(&self, info: LicenseInfo) {
+        *self.info.write() = info;
+    }
+}
+
+/// GET /api/license — current license status
+pub async fn license_handler(State(state): State<LicenseState>) -> impl IntoResponse {
+    Json(state.info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::response::Response;
+
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn checked(status: LicenseStatus, message: Option<&str>) -> LicenseInfo {
+        LicenseInfo {
+            status,
+            licensee: Some("Example Solar".to_owned()),
+            expires_at: Some(Utc::now() + chrono::Duration::days(30)),
+            fleet: status == LicenseStatus::Valid,
+            message: message.map(str::to_owned),
+            checked_at: Some(Utc::now()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_valid_license_unlocks_fleet() {
+        let state = LicenseState::new(LicenseInfo::checking());
+        state.set(checked(LicenseStatus::Valid, None));
+
+        let json = body(license_handler(State(state)).await.into_response()).await;
+
+        assert_eq!(json["status"], "valid");
+        assert_eq!(json["licensee"], "Example Solar");
+        assert_eq!(json["fleet"], true);
+    }
+
+    #[tokio::test]
+    async fn test_expired_and_tampered_licenses_are_invalid() {
+        for message in ["License has expired", "Unknown license key"] {
+            let state = LicenseState::new(checked(LicenseStatus::Invalid, Some(message)));
+
+            let json = body(license_handler(State(state)).await.into_response()).await;
+
+            assert_eq!(json["status"], "invalid");
+            assert_eq!(json["fleet"], false);
+            assert_eq!(json["message"], message);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unlicensed_by_default() {
+        let state = LicenseState::new(LicenseInfo::unlicensed());
+
+        let json = body(license_handler(State(state)).await.into_response()).await;
+
+        assert_eq!(json["status"], "unlicensed");
+        assert_eq!(json["licensee"], serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,155 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Live check of the configured entity mapper against Home Assistant.
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use fluxion_core::mapping_check::{MappingReport, MappingValidator};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::error;
+
+/// State for the mapping validation endpoint
+#[derive(Clone)]
+pub struct MappingCheckState {
+    validator: Arc<dyn MappingValidator>,
+}
+
+impl MappingCheckState {
+    pub fn new(validator: Arc<dyn MappingValidator>) -> Self {
+        Self { validator }
+    }
+}
+
+impl std::fmt::Debug for MappingCheckState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappingCheckState").finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct MappingCheckResponse {
+    /// All required entities of every inverter passed
+    valid: bool,
+    reports: Vec<MappingReport>,
+}
+
+/// GET /api/mapping/validate — run the entity mapping checks against live states
+pub async fn validate_mapping_handler(State(state): State<MappingCheckState>) -> Response {
+    match state.validator.validate().await {
+        Ok(reports) => Json(MappingCheckResponse {
+            valid: reports.iter().all(MappingReport::is_valid),
+            reports,
+        })
+        .into_response(),
+        Err(e) => {
+            error!("Failed to validate entity mapping: {e}");
+            (
+                StatusCode::BAD_GATEWAY,
+                format!("Failed to read Home Assistant states: {e}"),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use fluxion_core::mapping_check::{EntityCheck, EntityCheckStatus};
+
+    struct TestValidator {
+        reachable: bool,
+        soc_status: EntityCheckStatus,
+    }
+
+    #[async_trait]
+    impl MappingValidator for TestValidator {
+        async fn validate(&self) -> anyhow::Result<Vec<MappingReport>> {
+            anyhow::ensure!(self.reachable, "connection refused");
+            Ok(vec![MappingReport {
+                inverter_id: "main".to_owned(),
+                vendor: "Solax".to_owned(),
+                checks: vec![EntityCheck {
+                    name: "battery_soc".to_owned(),
+                    entity_id: "sensor.solax_battery_capacity".to_owned(),
+                    required: true,
+                    status: self.soc_status,
+                    detail: None,
+                }],
+            }])
+        }
+    }
+
+    async fn validate(validator: TestValidator) -> Response {
+        validate_mapping_handler(State(MappingCheckState::new(Arc::new(validator)))).await
+    }
+
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_passing_checks_are_valid() {
+        let response = validate(TestValidator {
+            reachable: true,
+            soc_status: EntityCheckStatus::Ok,
+        })
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let report = body(response).await;
+        assert_eq!(report["valid"], true);
+        assert_eq!(report["reports"][0]["inverter_id"], "main");
+    }
+
+    #[tokio::test]
+    async fn test_failed_required_check_is_invalid() {
+        let response = validate(TestValidator {
+            reachable: true,
+            soc_status: EntityCheckStatus::Missing,
+        })
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let report = body(response).await;
+        assert_eq!(report["valid"], false);
+        assert_eq!(report["reports"][0]["checks"][0]["status"], "missing");
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_home_assistant_is_a_bad_gateway() {
+        let response = validate(TestValidator {
+            reachable: false,
+            soc_status: EntityCheckStatus::Ok,
+        })
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            bytes,
+            "Failed to read Home Assistant states: connection refused"
+        );
+    }
+}
@@ -0,0 +1,354 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Prometheus text exposition of the live state.
+//!
+//! Gauges come from the dashboard snapshot; counters from
+//! [`fluxion_core::metrics::Metrics`]. The schedule mode is exported as a
+//! state set: one `fluxion_schedule_mode` series per mode, 1 for the active one.
+
+use axum::{
+    extract::State,
+    http::{StatusCode, header},
+    response::IntoResponse,
+};
+use fluxion_core::metrics::Metrics;
+use fluxion_core::{InverterData, WebQueryResponse};
+use fluxion_types::inverter::InverterOperationMode;
+use std::fmt::Write as _;
+use tracing::error;
+
+use crate::AppState;
+
+/// Content type of the Prometheus text format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+const MODES: [InverterOperationMode; 5] = [
+    InverterOperationMode::SelfUse,
+    InverterOperationMode::ForceCharge,
+    InverterOperationMode::ForceDischarge,
+    InverterOperationMode::BackUpMode,
+    InverterOperationMode::NoChargeNoDischarge,
+];
+
+/// Render all metrics; `response` is `None` when the core could not be queried
+#[must_use]
+pub fn render_metrics(response: Option<&WebQueryResponse>, metrics: &Metrics) -> String {
+    let mut out = String::new();
+
+    header_line(
+        &mut out,
+        "fluxion_up",
+        "gauge",
+        "1 if the core answered the scrape",
+    );
+    sample(
+        &mut out,
+        "fluxion_up",
+        &[],
+        f32::from(u8::from(response.is_some())),
+    );
+
+    if let Some(response) = response {
+        let inverters = &response.inverters;
+        inverter_gauge(
+            &mut out,
+            "fluxion_battery_soc_percent",
+            "Battery state of charge",
+            inverters,
+            |inv| inv.battery_soc,
+        );
+        inverter_gauge(
+            &mut out,
+            "fluxion_pv_power_watts",
+            "PV generation power",
+            inverters,
+            |inv| inv.pv_power_w,
+        );
+        inverter_gauge(
+            &mut out,
+            "fluxion_grid_import_watts",
+            "Power imported from the grid",
+            inverters,
+            |inv| inv.grid_import_w.unwrap_or((-inv.grid_power_w).max(0.0)),
+        );
+        inverter_gauge(
+            &mut out,
+            "fluxion_grid_export_watts",
+            "Power exported to the grid",
+            inverters,
+            |inv| inv.grid_export_w.unwrap_or(inv.grid_power_w.max(0.0)),
+        );
+
+        if let Some(prices) = &response.prices {
+            header_line(
+                &mut out,
+                "fluxion_spot_price",
+                "gauge",
+                "Current spot price per kWh",
+            );
+            sample(&mut out, "fluxion_spot_price", &[], prices.current_price);
+        }
+
+        if let Some(schedule) = &response.schedule {
+            header_line(
+                &mut out,
+                "fluxion_schedule_mode",
+                "gauge",
+                "Scheduled operation mode (1 = active)",
+            );
+            for mode in MODES {
+                let name = mode.to_string();
+                let active = f32::from(u8::from(schedule.current_mode == name));
+                sample(
+                    &mut out,
+                    "fluxion_schedule_mode",
+                    &[("mode", &name)],
+                    active,
+                );
+            }
+        }
+    }
+
+    render_counters(&mut out, metrics);
+
+    out
+}
+
+/// Event counters kept by the core since start
+fn render_counters(out: &mut String, metrics: &Metrics) {
+    header_line(
+        out,
+        "fluxion_mode_changes_total",
+        "counter",
+        "Inverter mode changes since start",
+    );
+    counter(out, "fluxion_mode_changes_total", metrics.mode_changes());
+    header_line(
+        out,
+        "fluxion_ha_query_errors_total",
+        "counter",
+        "Failed Home Assistant API requests since start",
+    );
+    counter(
+        out,
+        "fluxion_ha_query_errors_total",
+        metrics.ha_query_errors(),
+    );
+    header_line(
+        out,
+        "fluxion_web_query_overflows_total",
+        "counter",
+        "Web queries refused because the core queue was full",
+    );
+    counter(
+        out,
+        "fluxion_web_query_overflows_total",
+        metrics.web_query_overflows(),
+    );
+    header_line(
+        out,
+        "fluxion_web_query_timeouts_total",
+        "counter",
+        "Web queries the core did not answer in time",
+    );
+    counter(
+        out,
+        "fluxion_web_query_timeouts_total",
+        metrics.web_query_timeouts(),
+    );
+    header_line(
+        out,
+        "fluxion_schedule_generations_total",
+        "counter",
+        "Schedules generated since start",
+    );
+    counter(
+        out,
+        "fluxion_schedule_generations_total",
+        metrics.schedule_generations(),
+    );
+    if let Some(elapsed) = metrics.last_schedule_generation() {
+        header_line(
+            out,
+            "fluxion_schedule_generation_seconds",
+            "gauge",
+            "Duration of the latest schedule generation",
+        );
+        sample(
+            out,
+            "fluxion_schedule_generation_seconds",
+            &[],
+            elapsed.as_secs_f32(),
+        );
+    }
+}
+
+/// One gauge series per inverter, labelled with the inverter id
+fn inverter_gauge(
+    out: &mut String,
+    name: &str,
+    help: &str,
+    inverters: &[InverterData],
+    value: impl Fn(&InverterData) -> f32,
+) {
+    header_line(out, name, "gauge", help);
+    for inv in inverters {
+        sample(out, name, &[("inverter", &inv.id)], value(inv));
+    }
+}
+
+fn header_line(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f32) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{key}=\"{}\"", escape_label(value)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {value}");
+}
+
+fn counter(out: &mut String, name: &str, value: u64) {
+    let _ = writeln!(out, "{name} {value}");
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// GET /metrics — Prometheus scrape endpoint
+pub async fn metrics_handler(State(app_state): State<AppState>) -> impl IntoResponse {
+    let response = match app_state.query_sender.query_dashboard().await {
+        Ok(response) => Some(response),
+        Err(e) => {
+            error!("Failed to query dashboard data for metrics: {e}");
+            None
+        }
+    };
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, CONTENT_TYPE)],
+        render_metrics(response.as_ref(), Metrics::global()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_without_core_exposes_counters() {
+        let metrics = Metrics::default();
+        metrics.record_mode_change();
+        metrics.record_ha_query_error();
+        metrics.record_ha_query_error();
+        metrics.record_web_query_overflow();
+
+        let text = render_metrics(None, &metrics);
+        assert!(text.contains("fluxion_up 0\n"));
+        assert!(text.contains("# TYPE fluxion_mode_changes_total counter\n"));
+        assert!(text.contains("fluxion_mode_changes_total 1\n"));
+        assert!(text.contains("fluxion_ha_query_errors_total 2\n"));
+        assert!(text.contains("fluxion_web_query_overflows_total 1\n"));
+        assert!(text.contains("fluxion_web_query_timeouts_total 0\n"));
+        assert!(!text.contains("fluxion_battery_soc_percent"));
+        assert!(text.contains("fluxion_schedule_generations_total 0\n"));
+        assert!(!text.contains("fluxion_schedule_generation_seconds"));
+
+        metrics.record_schedule_generation(std::time::Duration::from_millis(1500));
+        let text = render_metrics(None, &metrics);
+        assert!(text.contains("fluxion_schedule_generations_total 1\n"));
+        assert!(text.contains("fluxion_schedule_generation_seconds 1.5\n"));
+    }
+
+    #[test]
+    fn test_render_inverter_gauges() {
+        let inverter: InverterData = serde_json::from_value(serde_json::json!({
+            "id": "main", "topology": "independent", "mode": "Self-Use", "mode_reason": "",
+            "actual_mode": null, "mode_synced": true, "battery_soc": 64.0,
+            "battery_power_w": 0.0, "battery_voltage_v": 0.0, "battery_current_a": 0.0,
+            "battery_temperature_c": 0.0, "grid_power_w": -1200.0, "grid_voltage_v": 0.0,
+            "grid_frequency_hz": 0.0, "pv_power_w": 3500.0, "pv1_power_w": 0.0,
+            "pv2_power_w": 0.0, "daily_energy_kwh": 0.0, "total_energy_kwh": 0.0,
+            "online": true, "run_mode": "", "error_code": 0, "inverter_temperature_c": 0.0,
+        }))
+        .unwrap();
+        let response = WebQueryResponse {
+            timestamp: chrono::Utc::now(),
+            debug_mode: false,
+            inverters: vec![inverter],
+            schedule: None,
+            prices: None,
+            health: fluxion_core::SystemHealthData {
+                inverter_source: true,
+                price_source: true,
+                last_update: chrono::Utc::now(),
+                errors: vec![],
+            },
+            timezone: None,
+            battery_soc_history: None,
+            battery_soc_prediction: None,
+            pv_generation_history: None,
+            battery_power_history: None,
+            grid_power_history: None,
+            consumption_stats: None,
+            hdo_schedule: None,
+            pricing_fees: None,
+            solar_forecast: None,
+        };
+
+        let text = render_metrics(Some(&response), &Metrics::default());
+        assert!(text.contains("fluxion_up 1\n"));
+        assert!(text.contains("fluxion_battery_soc_percent{inverter=\"main\"} 64\n"));
+        assert!(text.contains("fluxion_pv_power_watts{inverter=\"main\"} 3500\n"));
+        assert!(text.contains("fluxion_grid_import_watts{inverter=\"main\"} 1200\n"));
+        assert!(text.contains("fluxion_grid_export_watts{inverter=\"main\"} 0\n"));
+    }
+
+    #[test]
+    fn test_label_values_are_escaped() {
+        let mut out = String::new();
+        sample(&mut out, "m", &[("inverter", "a\"b\\c")], 1.5);
+        assert_eq!(out, "m{inverter=\"a\\\"b\\\\c\"} 1.5\n");
+    }
+
+    #[test]
+    fn test_sample_label_formatting() {
+        let mut out = String::new();
+        sample(&mut out, "m", &[], 2.0);
+        sample(
+            &mut out,
+            "m",
+            &[("inverter", "main"), ("phase", "l1\nl2")],
+            0.25,
+        );
+        assert_eq!(out, "m 2\nm{inverter=\"main\",phase=\"l1\\nl2\"} 0.25\n");
+
+        let mut out = String::new();
+        header_line(&mut out, "m_total", "counter", "Things counted");
+        counter(&mut out, "m_total", 7);
+        assert_eq!(
+            out,
+            "# HELP m_total Things counted\n# TYPE m_total counter\nm_total 7\n"
+        );
+    }
+}
@@ -0,0 +1,1074 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! OpenAPI 3.1 description of the web API, served at `/api/v1/openapi.json`.
+//!
+//! Routes are registered in several modules (and some only when their feature
+//! is configured), so the document is built from the [`ROUTES`] table rather
+//! than from handler annotations. A test parses the router sources and fails
+//! when a route is added without a matching entry here. `/api/...` routes are
+//! published under their versioned `/api/v1/...` path. JSON request and
+//! response bodies reference the `ToSchema` types of their handlers.
+
+use crate::api_error::ApiError;
+use crate::api_keys::{ApiKeyResponse, CreateKeyRequest, CreateKeyResponse};
+use crate::api_version::{ApiVersionInfo, versioned_path};
+use crate::auth::LoginRequest;
+use crate::backtest::{CompareRequest, SimulateRequest};
+use crate::backup_reserve::{
+    BackupReserveStatus, SetBackupReserveRequest, SetBackupReserveResponse,
+};
+use crate::calendar::{CalendarResponse, CreateVacationRequest, VacationResponse};
+use crate::config_api::{
+    ConfigResponse, DebugModeBody, ResetSectionRequest, SetLanguageRequest, SetLanguageResponse,
+    UpdateConfigRequest, UpdateConfigResponse, ValidateRequest, ValidateResponse,
+};
+use crate::config_import::ImportConfigResponse;
+use crate::config_preview::ConfigPreviewResponse;
+use crate::plugin_api::{EnabledUpdateRequest, PriorityUpdateRequest};
+use crate::remote_access::PairRequest;
+use crate::safe_state_api::{EngageSafeStateRequest, SafeStateResponse};
+use crate::setup_wizard::SetupStatusResponse;
+use crate::simulator::{
+    BatchRunRequest, CreateSimulationRequest, LoadOverrideRequest, PriceOverrideRequest,
+    SaveRunRequest, SocOverrideRequest, StepRequest,
+};
+use crate::strategy_config_api::{StrategySettings, StrategyUpdateRequest};
+use crate::strategy_wizard::EvaluateRequest;
+use crate::ui_preferences::UiPreferences;
+use crate::user_control_api::{
+    CreateSlotRequest, DeleteSlotResponse, GetUserControlResponse, PinBlockRequest,
+    SetEnabledRequest, SetEnabledResponse, SetRestrictionsRequest, SetRestrictionsResponse,
+    SlotResponse, UpdateSlotRequest,
+};
+use axum::{Json, response::IntoResponse};
+use fluxion_backtest::SweepRequest;
+use fluxion_core::alerts::{AlertRule, AlertRuleSpec};
+use fluxion_core::self_test::SelfTestReport;
+use fluxion_core::what_if::{WhatIfRequest, WhatIfResponse};
+use fluxion_mobile_types::{MobileControlRequest, VersionResponse};
+use fluxion_plugins::{PluginRegistrationRequest, PluginRegistrationResponse};
+use std::borrow::Cow;
+use utoipa::ToSchema;
+use utoipa::openapi::{
+    ComponentsBuilder, ContentBuilder, HttpMethod, InfoBuilder, OpenApi, OpenApiBuilder, PathItem,
+    PathsBuilder, Ref, RefOr, Required, ResponseBuilder,
+    path::{OperationBuilder, ParameterBuilder, ParameterIn},
+    request_body::RequestBodyBuilder,
+    schema::{ArrayBuilder, ObjectBuilder, Schema, Type},
+};
+
+/// HTTP method of a documented route
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Delete,
+}
+
+/// JSON body of a request or response, described by a `ToSchema` type
+#[derive(Debug, Clone, Copy)]
+pub struct BodySchema {
+    name: fn() -> Cow<'static, str>,
+    /// Adds the type (and the types it refers to) to the components
+    register: fn(ComponentsBuilder) -> ComponentsBuilder,
+    /// The body is a JSON array of the type
+    list: bool,
+}
+
+impl BodySchema {
+    fn schema(&self) -> RefOr<Schema> {
+        let item = Ref::from_schema_name((self.name)());
+        if self.list {
+            ArrayBuilder::new().items(item).build().into()
+        } else {
+            item.into()
+        }
+    }
+}
+
+/// `T` and every type its schema refers to
+fn register<T: ToSchema>(components: ComponentsBuilder) -> ComponentsBuilder {
+    let mut nested = Vec::new();
+    T::schemas(&mut nested);
+    components.schema_from::<T>().schemas_from_iter(nested)
+}
+
+const fn schema<T: ToSchema>() -> BodySchema {
+    BodySchema {
+        name: T::name,
+        register: register::<T>,
+        list: false,
+    }
+}
+
+const fn list_of<T: ToSchema>() -> BodySchema {
+    BodySchema {
+        list: true,
+        ..schema::<T>()
+    }
+}
+
+/// One documented route
+#[derive(Debug, Clone, Copy)]
+pub struct RouteDoc {
+    pub method: Method,
+    pub path: &'static str,
+    pub tag: &'static str,
+    pub summary: &'static str,
+    pub request: Option<BodySchema>,
+    /// Whether the request body may be left out
+    pub request_optional: bool,
+    pub response: Option<BodySchema>,
+}
+
+impl RouteDoc {
+    const fn body(mut self, schema: BodySchema) -> Self {
+        self.request = Some(schema);
+        self
+    }
+
+    const fn optional_body(mut self, schema: BodySchema) -> Self {
+        self.request_optional = true;
+        self.body(schema)
+    }
+
+    const fn returns(mut self, schema: BodySchema) -> Self {
+        self.response = Some(schema);
+        self
+    }
+}
+
+const fn route(
+    method: Method,
+    path: &'static str,
+    tag: &'static str,
+    summary: &'static str,
+) -> RouteDoc {
+    RouteDoc {
+        method,
+        path,
+        tag,
+        summary,
+        request: None,
+        request_optional: false,
+        response: None,
+    }
+}
+
+use Method::{Delete, Get, Post, Put};
+
+/// Every route served by fluxion-web.
+/// Optional routes (simulator, backtest, plugins, ...) are listed too; they
+/// return 404 when the feature is not configured.
+pub const ROUTES: &[RouteDoc] = &[
+    // Pages
+    route(Get, "/", "pages", "Dashboard"),
+    route(
+        Get,
+        "/guest",
+        "pages",
+        "Read-only guest dashboard (`?token=`)",
+    ),
+    route(Get, "/backtest", "pages", "Backtest page"),
+    route(Get, "/strategies", "pages", "Strategy documentation page"),
+    route(Get, "/simulator", "pages", "Simulator page"),
+    route(
+        Get,
+        "/simulator/runs/{run_id}",
+        "pages",
+        "Saved simulator run page",
+    ),
+    route(Get, "/setup", "pages", "Setup wizard page"),
+    route(Get, "/strategy-wizard", "pages", "Strategy wizard page"),
+    route(Get, "/remote-access", "pages", "Remote access page"),
+    route(Get, "/api-keys", "pages", "API key management page"),
+    route(Get, "/login", "pages", "Login page"),
+    // Dashboard data
+    route(
+        Get,
+        "/stream",
+        "dashboard",
+        "Server-sent events with live dashboard updates",
+    ),
+    route(Get, "/branding/logo", "dashboard", "Configured logo"),
+    route(
+        Get,
+        "/chart-data",
+        "dashboard",
+        "Price chart data (supports ETag)",
+    ),
+    route(
+        Get,
+        "/chart-data/history",
+        "dashboard",
+        "Historical chart data",
+    ),
+    route(
+        Get,
+        "/export",
+        "dashboard",
+        "Schedule export (JSON, CSV or iCal)",
+    ),
+    route(Get, "/api/openapi.json", "system", "This OpenAPI document"),
+    route(
+        Get,
+        "/api/version",
+        "system",
+        "FluxION and supported API versions",
+    )
+    .returns(schema::<ApiVersionInfo>()),
+    // Health and status
+    route(Get, "/health", "system", "Liveness check"),
+    route(Get, "/health/tasks", "system", "Background task health"),
+    route(Get, "/health/queries", "system", "ECS query queue health"),
+    route(Get, "/status.json", "system", "Compact system status"),
+    route(Get, "/metrics", "system", "Prometheus metrics"),
+    route(Get, "/api/license", "system", "License status"),
+    route(Get, "/api/debug/ecs", "system", "ECS inspector"),
+    route(
+        Post,
+        "/api/system/self-test",
+        "system",
+        "Run the inverter self-test",
+    )
+    .returns(schema::<SelfTestReport>()),
+    route(Get, "/api/system/safe-state", "system", "Safe state status")
+        .returns(schema::<SafeStateResponse>()),
+    route(
+        Post,
+        "/api/system/safe-state",
+        "system",
+        "Engage safe state",
+    )
+    .optional_body(schema::<EngageSafeStateRequest>())
+    .returns(schema::<SafeStateResponse>()),
+    route(
+        Delete,
+        "/api/system/safe-state",
+        "system",
+        "Resume from safe state",
+    )
+    .returns(schema::<SafeStateResponse>()),
+    route(
+        Get,
+        "/api/mapping/validate",
+        "system",
+        "Validate entity mapping",
+    ),
+    route(Get, "/api/logs/download", "system", "Download the log file"),
+    route(Get, "/api/help", "system", "Help topics"),
+    route(Get, "/api/help/{topic}", "system", "Help topic"),
+    // Schedule
+    route(Get, "/api/preview", "schedule", "Schedule preview"),
+    route(Get, "/api/schedule", "schedule", "Planned schedule"),
+    route(
+        Get,
+        "/api/schedule/upcoming",
+        "schedule",
+        "Upcoming mode changes",
+    ),
+    route(
+        Post,
+        "/api/schedule/what-if",
+        "schedule",
+        "Plan a what-if scenario",
+    )
+    .body(schema::<WhatIfRequest>())
+    .returns(schema::<WhatIfResponse>()),
+    route(
+        Post,
+        "/api/schedule/pin",
+        "schedule",
+        "Pin a schedule block",
+    )
+    .body(schema::<PinBlockRequest>())
+    .returns(schema::<SlotResponse>()),
+    route(
+        Delete,
+        "/api/schedule/pin/{id}",
+        "schedule",
+        "Remove a block pin",
+    )
+    .returns(schema::<SlotResponse>()),
+    route(Get, "/api/decisions", "schedule", "Decision log"),
+    route(Get, "/api/tariff", "schedule", "Grid tariff"),
+    route(Get, "/api/dhw", "schedule", "Hot water plan"),
+    // Analytics
+    route(Get, "/api/history", "analytics", "Recorded history"),
+    route(Get, "/api/savings", "analytics", "Savings report"),
+    route(Get, "/api/submeter", "analytics", "Submeter readings"),
+    route(
+        Get,
+        "/api/grid-quality",
+        "analytics",
+        "Grid quality summary",
+    ),
+    route(
+        Get,
+        "/api/grid-quality/events.csv",
+        "analytics",
+        "Grid quality events as CSV",
+    ),
+    route(Get, "/api/export-cap", "analytics", "Export cap status"),
+    route(
+        Get,
+        "/api/export-cap/report.csv",
+        "analytics",
+        "Export cap report as CSV",
+    ),
+    // Configuration
+    route(Get, "/api/config", "config", "Current configuration")
+        .returns(schema::<ConfigResponse>()),
+    route(
+        Post,
+        "/api/config/validate",
+        "config",
+        "Validate a configuration",
+    )
+    .body(schema::<ValidateRequest>())
+    .returns(schema::<ValidateResponse>()),
+    route(
+        Post,
+        "/api/config/preview",
+        "config",
+        "Preview the effect of a configuration",
+    )
+    .body(schema::<ValidateRequest>())
+    .returns(schema::<ConfigPreviewResponse>()),
+    route(
+        Post,
+        "/api/config/update",
+        "config",
+        "Update the configuration",
+    )
+    .body(schema::<UpdateConfigRequest>())
+    .returns(schema::<UpdateConfigResponse>()),
+    route(Put, "/api/config/language", "config", "Set the UI language")
+        .body(schema::<SetLanguageRequest>())
+        .returns(schema::<SetLanguageResponse>()),
+    route(Get, "/api/config/debug-mode", "config", "Debug mode status")
+        .returns(schema::<DebugModeBody>()),
+    route(Put, "/api/config/debug-mode", "config", "Set debug mode")
+        .body(schema::<DebugModeBody>())
+        .returns(schema::<DebugModeBody>()),
+    route(
+        Get,
+        "/api/ui/preferences",
+        "config",
+        "Dashboard display preferences",
+    )
+    .returns(schema::<UiPreferences>()),
+    route(
+        Put,
+        "/api/ui/preferences",
+        "config",
+        "Set the dashboard theme",
+    )
+    .body(schema::<UiPreferences>())
+    .returns(schema::<UiPreferences>()),
+    route(
+        Post,
+        "/api/config/reset",
+        "config",
+        "Reset a configuration section",
+    )
+    .body(schema::<ResetSectionRequest>())
+    .returns(schema::<UpdateConfigResponse>()),
+    route(Get, "/api/config/strategies", "config", "Strategy settings")
+        .returns(list_of::<StrategySettings>()),
+    route(
+        Put,
+        "/api/config/strategies/{key}",
+        "config",
+        "Update a strategy",
+    )
+    .body(schema::<StrategyUpdateRequest>())
+    .returns(schema::<UpdateConfigResponse>()),
+    route(
+        Post,
+        "/api/config/import",
+        "config",
+        "Import a configuration",
+    )
+    .returns(schema::<ImportConfigResponse>()),
+    route(
+        Get,
+        "/api/config/export",
+        "config",
+        "Export the configuration",
+    ),
+    route(
+        Get,
+        "/api/setup/proposal",
+        "config",
+        "Setup wizard proposal",
+    )
+    .returns(schema::<SetupStatusResponse>()),
+    route(
+        Post,
+        "/api/setup/complete",
+        "config",
+        "Finish the setup wizard",
+    )
+    .returns(schema::<SetupStatusResponse>()),
+    route(
+        Post,
+        "/api/strategy-wizard/evaluate",
+        "config",
+        "Evaluate wizard answers",
+    )
+    .body(schema::<EvaluateRequest>()),
+    // User control
+    route(
+        Get,
+        "/api/user-control",
+        "user-control",
+        "User control state",
+    )
+    .returns(schema::<GetUserControlResponse>()),
+    route(
+        Put,
+        "/api/user-control/enabled",
+        "user-control",
+        "Enable or disable FluxION",
+    )
+    .body(schema::<SetEnabledRequest>())
+    .returns(schema::<SetEnabledResponse>()),
+    route(
+        Put,
+        "/api/user-control/restrictions",
+        "user-control",
+        "Set mode restrictions",
+    )
+    .body(schema::<SetRestrictionsRequest>())
+    .returns(schema::<SetRestrictionsResponse>()),
+    route(
+        Post,
+        "/api/user-control/slots",
+        "user-control",
+        "Create a fixed time slot",
+    )
+    .body(schema::<CreateSlotRequest>())
+    .returns(schema::<SlotResponse>()),
+    route(
+        Put,
+        "/api/user-control/slots/{id}",
+        "user-control",
+        "Update a fixed time slot",
+    )
+    .body(schema::<UpdateSlotRequest>())
+    .returns(schema::<SlotResponse>()),
+    route(
+        Delete,
+        "/api/user-control/slots/{id}",
+        "user-control",
+        "Delete a fixed time slot",
+    )
+    .returns(schema::<DeleteSlotResponse>()),
+    route(
+        Post,
+        "/api/user-control/slots/{id}/restore",
+        "user-control",
+        "Restore a deleted slot",
+    )
+    .returns(schema::<SlotResponse>()),
+    route(
+        Get,
+        "/api/calendar",
+        "user-control",
+        "Special days and vacations",
+    )
+    .returns(schema::<CalendarResponse>()),
+    route(
+        Post,
+        "/api/calendar/vacations",
+        "user-control",
+        "Add a vacation",
+    )
+    .body(schema::<CreateVacationRequest>())
+    .returns(schema::<VacationResponse>()),
+    route(
+        Delete,
+        "/api/calendar/vacations/{id}",
+        "user-control",
+        "Delete a vacation",
+    ),
+    route(Get, "/api/backup-reserve", "user-control", "Backup reserve")
+        .returns(schema::<BackupReserveStatus>()),
+    route(
+        Put,
+        "/api/backup-reserve",
+        "user-control",
+        "Set a backup reserve",
+    )
+    .body(schema::<SetBackupReserveRequest>())
+    .returns(schema::<SetBackupReserveResponse>()),
+    route(
+        Delete,
+        "/api/backup-reserve",
+        "user-control",
+        "Clear the backup reserve",
+    ),
+    route(Get, "/api/alerts", "user-control", "Alerts"),
+    route(Post, "/api/alerts", "user-control", "Create an alert")
+        .body(schema::<AlertRuleSpec>())
+        .returns(schema::<AlertRule>()),
+    route(Put, "/api/alerts/{id}", "user-control", "Update an alert")
+        .body(schema::<AlertRuleSpec>())
+        .returns(schema::<AlertRule>()),
+    route(
+        Delete,
+        "/api/alerts/{id}",
+        "user-control",
+        "Delete an alert",
+    ),
+    // Backtest
+    route(
+        Get,
+        "/api/backtest/days",
+        "backtest",
+        "Days with recorded data",
+    ),
+    route(
+        Get,
+        "/api/backtest/day/{date}",
+        "backtest",
+        "Recorded data for a day",
+    ),
+    route(Post, "/api/backtest/simulate", "backtest", "Simulate a day")
+        .body(schema::<SimulateRequest>()),
+    route(
+        Get,
+        "/api/backtest/range",
+        "backtest",
+        "Backtest a date range",
+    ),
+    route(
+        Post,
+        "/api/backtest/sweep",
+        "backtest",
+        "Start a parameter sweep",
+    )
+    .body(schema::<SweepRequest>()),
+    route(
+        Get,
+        "/api/backtest/sweep/{id}",
+        "backtest",
+        "Parameter sweep status",
+    ),
+    route(
+        Post,
+        "/api/backtest/compare",
+        "backtest",
+        "Compare strategies",
+    )
+    .body(schema::<CompareRequest>()),
+    // Plugins
+    route(Get, "/api/plugins", "plugins", "Registered plugins"),
+    route(
+        Post,
+        "/api/plugins/register",
+        "plugins",
+        "Register a plugin",
+    )
+    .body(schema::<PluginRegistrationRequest>())
+    .returns(schema::<PluginRegistrationResponse>()),
+    route(
+        Delete,
+        "/api/plugins/{name}",
+        "plugins",
+        "Unregister a plugin",
+    ),
+    route(
+        Put,
+        "/api/plugins/{name}/priority",
+        "plugins",
+        "Set plugin priority",
+    )
+    .body(schema::<PriorityUpdateRequest>()),
+    route(
+        Put,
+        "/api/plugins/{name}/enabled",
+        "plugins",
+        "Enable or disable a plugin",
+    )
+    .body(schema::<EnabledUpdateRequest>()),
+    route(
+        Get,
+        "/api/plugins/{name}/health",
+        "plugins",
+        "Plugin health",
+    ),
+    route(
+        Get,
+        "/api/strategies/docs",
+        "plugins",
+        "Strategy documentation",
+    ),
+    // Simulator
+    route(
+        Get,
+        "/api/simulator/presets",
+        "simulator",
+        "Scenario presets",
+    ),
+    route(
+        Post,
+        "/api/simulator/create",
+        "simulator",
+        "Create a simulation",
+    )
+    .body(schema::<CreateSimulationRequest>()),
+    route(
+        Post,
+        "/api/simulator/batch",
+        "simulator",
+        "Run a batch of simulations",
+    )
+    .body(schema::<BatchRunRequest>()),
+    route(Get, "/api/simulator/{id}", "simulator", "Simulation state"),
+    route(
+        Delete,
+        "/api/simulator/{id}",
+        "simulator",
+        "Delete a simulation",
+    ),
+    route(
+        Post,
+        "/api/simulator/{id}/step",
+        "simulator",
+        "Advance a simulation",
+    )
+    .body(schema::<StepRequest>()),
+    route(
+        Post,
+        "/api/simulator/{id}/run",
+        "simulator",
+        "Run a simulation to the end",
+    ),
+    route(
+        Get,
+        "/api/simulator/{id}/results",
+        "simulator",
+        "Simulation results",
+    ),
+    route(
+        Put,
+        "/api/simulator/{id}/override/soc",
+        "simulator",
+        "Override SOC",
+    )
+    .body(schema::<SocOverrideRequest>()),
+    route(
+        Put,
+        "/api/simulator/{id}/override/load",
+        "simulator",
+        "Override load",
+    )
+    .body(schema::<LoadOverrideRequest>()),
+    route(
+        Put,
+        "/api/simulator/{id}/override/price",
+        "simulator",
+        "Override prices",
+    )
+    .body(schema::<PriceOverrideRequest>()),
+    route(Post, "/api/simulator/{id}/save", "simulator", "Save a run")
+        .body(schema::<SaveRunRequest>()),
+    route(
+        Post,
+        "/api/simulator/{id}/reset",
+        "simulator",
+        "Reset a simulation",
+    ),
+    route(Get, "/api/simulator/runs", "simulator", "Saved runs"),
+    route(
+        Post,
+        "/api/simulator/runs/{run_id}/open",
+        "simulator",
+        "Open a saved run",
+    ),
+    route(
+        Delete,
+        "/api/simulator/runs/{run_id}",
+        "simulator",
+        "Delete a saved run",
+    ),
+    route(
+        Get,
+        "/api/simulator/blocks/{id}/{block}",
+        "simulator",
+        "Block detail",
+    ),
+    // Authentication and API keys
+    route(Post, "/api/auth/login", "auth", "Log in").body(schema::<LoginRequest>()),
+    route(Post, "/api/auth/logout", "auth", "Log out"),
+    route(Get, "/api/auth/status", "auth", "Login status"),
+    route(Get, "/api/keys", "auth", "API keys").returns(list_of::<ApiKeyResponse>()),
+    route(Post, "/api/keys", "auth", "Create an API key")
+        .body(schema::<CreateKeyRequest>())
+        .returns(schema::<CreateKeyResponse>()),
+    route(Delete, "/api/keys/{id}", "auth", "Revoke an API key"),
+    // Remote access
+    route(Get, "/api/remote/status", "remote", "Remote access status"),
+    route(Post, "/api/remote/pair", "remote", "Pair a mobile device").body(schema::<PairRequest>()),
+    route(Get, "/api/remote/devices", "remote", "Paired devices"),
+    route(
+        Delete,
+        "/api/remote/devices/{id}",
+        "remote",
+        "Revoke a device",
+    ),
+    route(
+        Get,
+        "/api/remote-access/devices",
+        "remote",
+        "Paired devices",
+    ),
+    route(
+        Delete,
+        "/api/remote-access/devices/{id}",
+        "remote",
+        "Revoke a device",
+    ),
+    route(
+        Post,
+        "/api/remote-access/devices/{id}/rotate-key",
+        "remote",
+        "Rotate a device key",
+    ),
+    route(Get, "/mobile/api/version", "mobile", "Mobile API version")
+        .returns(schema::<VersionResponse>()),
+    route(Get, "/mobile/api/ui", "mobile", "Mobile UI bundle"),
+    route(
+        Get,
+        "/mobile/api/state",
+        "mobile",
+        "Mobile state snapshot (supports ETag)",
+    ),
+    route(
+        Post,
+        "/mobile/api/control",
+        "mobile",
+        "Mobile control command",
+    )
+    .body(schema::<MobileControlRequest>()),
+    route(
+        Post,
+        "/mobile/api/safe-state",
+        "mobile",
+        "Engage safe state",
+    )
+    .optional_body(schema::<EngageSafeStateRequest>()),
+    route(
+        Delete,
+        "/mobile/api/safe-state",
+        "mobile",
+        "Resume from safe state",
+    ),
+    route(
+        Get,
+        "/mobile/api/v2/schedule",
+        "mobile",
+        "Full schedule with reasons and SOC series (supports ETag)",
+    ),
+    route(
+        Get,
+        "/mobile/api/v2/consumption",
+        "mobile",
+        "Consumption statistics",
+    ),
+    route(
+        Get,
+        "/mobile/api/v2/history",
+        "mobile",
+        "Paginated daily summaries",
+    ),
+];
+
+/// Path parameter names, e.g. `["id", "block"]` for `/api/simulator/blocks/{id}/{block}`
+fn path_params(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+}
+
+fn operation_id(route: &RouteDoc) -> String {
+    let method = match route.method {
+        // `/api-keys` (page) and `/api/keys` (API) would otherwise collide
+        Method::Get if route.tag == "pages" => "page",
+        Method::Get => "get",
+        Method::Post => "post",
+        Method::Put => "put",
+        Method::Delete => "delete",
+    };
+    let path: String = route
+        .path
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let path = path
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    if path.is_empty() {
+        method.to_owned()
+    } else {
+        format!("{method}_{path}")
+    }
+}
+
+/// Build the OpenAPI document from [`ROUTES`]
+pub fn openapi() -> OpenApi {
+    let error_response = ResponseBuilder::new()
+        .description("Error, see the `ApiError` envelope")
+        .content(
+            "application/json",
+            ContentBuilder::new()
+                .schema(Some(Ref::from_schema_name("ApiError")))
+                .build(),
+        )
+        .build();
+
+    let mut components = ComponentsBuilder::new().schema_from::<ApiError>();
+    let mut paths = PathsBuilder::new();
+    for route in ROUTES {
+        let mut success = ResponseBuilder::new().description("Success");
+        if let Some(response) = route.response {
+            components = (response.register)(components);
+            success = success.content(
+                "application/json",
+                ContentBuilder::new()
+                    .schema(Some(response.schema()))
+                    .build(),
+            );
+        }
+        let mut operation = OperationBuilder::new()
+            .tag(route.tag)
+            .summary(Some(route.summary))
+            .operation_id(Some(operation_id(route)))
+            .response("200", success.build())
+            .response("4XX", error_response.clone())
+            .response("5XX", error_response.clone());
+        for name in path_params(route.path) {
+            operation = operation.parameter(
+                ParameterBuilder::new()
+                    .name(name)
+                    .parameter_in(ParameterIn::Path)
+                    .required(Required::True)
+                    .schema(Some(ObjectBuilder::new().schema_type(Type::String))),
+            );
+        }
+        if let Some(request) = route.request {
+            components = (request.register)(components);
+            operation = operation.request_body(Some(
+                RequestBodyBuilder::new()
+                    .content(
+                        "application/json",
+                        ContentBuilder::new().schema(Some(request.schema())).build(),
+                    )
+                    .required(Some(if route.request_optional {
+                        Required::False
+                    } else {
+                        Required::True
+                    }))
+                    .build(),
+            ));
+        }
+        let method = match route.method {
+            Method::Get => HttpMethod::Get,
+            Method::Post => HttpMethod::Post,
+            Method::Put => HttpMethod::Put,
+            Method::Delete => HttpMethod::Delete,
+        };
+        paths = paths.path(
+            versioned_path(route.path),
+            PathItem::new(method, operation.build()),
+        );
+    }
+
+    OpenApiBuilder::new()
+        .info(
+            InfoBuilder::new()
+                .title("FluxION API")
+                .version(env!("CARGO_PKG_VERSION"))
+                .description(Some(
+                    "FluxION web API. Errors use a JSON envelope with `code`, `message` and optional `details`.",
+                ))
+                .build(),
+        )
+        .paths(paths.build())
+        .components(Some(components.build()))
+        .build()
+}
+
+/// OpenAPI document handler
+pub async fn openapi_handler() -> impl IntoResponse {
+    Json(openapi())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Router sources, cut before their test modules (which register dummy routes)
+    const SOURCES: &[&str] = &[
+        include_str!("lib.rs"),
+        include_str!("alerts.rs"),
+        include_str!("api_keys.rs"),
+        include_str!("auth.rs"),
+        include_str!("dhw.rs"),
+        include_str!("setup_wizard.rs"),
+        include_str!("strategy_wizard.rs"),
+        include_str!("upcoming.rs"),
+        include_str!("remote_access/api.rs"),
+        include_str!("remote_access/mobile_api.rs"),
+    ];
+
+    fn registered_paths() -> HashSet<String> {
+        let mut paths = HashSet::new();
+        for source in SOURCES {
+            let source = source.split("#[cfg(test)]").next().unwrap_or_default();
+            for chunk in source.split(".route(").skip(1) {
+                let chunk = chunk.trim_start();
+                if let Some(rest) = chunk.strip_prefix('"')
+                    && let Some((path, _)) = rest.split_once('"')
+                {
+                    paths.insert(path.to_owned());
+                }
+            }
+        }
+        paths
+    }
+
+    #[test]
+    fn test_every_route_is_documented() {
+        let documented: HashSet<&str> = ROUTES.iter().map(|r| r.path).collect();
+        let registered = registered_paths();
+        assert!(
+            registered.len() > 100,
+            "route scan found {}",
+            registered.len()
+        );
+        let mut missing: Vec<_> = registered
+            .iter()
+            .filter(|p| !documented.contains(p.as_str()))
+            .collect();
+        missing.sort();
+        assert!(missing.is_empty(), "undocumented routes: {missing:?}");
+        let mut stale: Vec<_> = documented
+            .iter()
+            .filter(|p| !registered.contains(**p))
+            .collect();
+        stale.sort();
+        assert!(
+            stale.is_empty(),
+            "documented routes not registered: {stale:?}"
+        );
+    }
+
+    #[test]
+    fn test_openapi_document() {
+        let doc = serde_json::to_value(openapi()).unwrap();
+        assert!(doc["openapi"].as_str().unwrap().starts_with("3."));
+        assert!(doc["components"]["schemas"]["ApiError"].is_object());
+
+        let block = &doc["paths"]["/api/v1/simulator/blocks/{id}/{block}"]["get"];
+        let params: Vec<_> = block["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|p| p["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(params, ["id", "block"]);
+
+        // Several methods on one path end up in the same path item
+        let safe_state = &doc["paths"]["/api/v1/system/safe-state"];
+        assert!(safe_state["get"].is_object());
+        assert!(safe_state["post"].is_object());
+        assert!(safe_state["delete"].is_object());
+
+        // Unversioned aliases are not published; version discovery stays put
+        assert!(doc["paths"]["/api/config"].is_null());
+        assert!(doc["paths"]["/api/v1/config"]["get"].is_object());
+        assert!(doc["paths"]["/api/version"]["get"].is_object());
+
+        let ids: HashSet<_> = ROUTES.iter().map(operation_id).collect();
+        assert_eq!(ids.len(), ROUTES.len(), "operation ids must be unique");
+    }
+
+    fn collect_refs<'a>(value: &'a serde_json::Value, refs: &mut Vec<&'a str>) {
+        match value {
+            serde_json::Value::Object(map) => {
+                if let Some(target) = map.get("$ref").and_then(|r| r.as_str()) {
+                    refs.push(target);
+                }
+                map.values().for_each(|v| collect_refs(v, refs));
+            }
+            serde_json::Value::Array(items) => items.iter().for_each(|v| collect_refs(v, refs)),
+            serde_json::Value::Null
+            | serde_json::Value::Bool(_)
+            | serde_json::Value::Number(_)
+            | serde_json::Value::String(_) => {}
+        }
+    }
+
+    #[test]
+    fn test_request_and_response_schemas() {
+        let doc = serde_json::to_value(openapi()).unwrap();
+        let json_schema =
+            |body: &serde_json::Value| body["content"]["application/json"]["schema"].clone();
+
+        let update = &doc["paths"]["/api/v1/config/update"]["post"];
+        assert_eq!(update["requestBody"]["required"], true);
+        assert_eq!(
+            json_schema(&update["requestBody"])["$ref"],
+            "#/components/schemas/UpdateConfigRequest"
+        );
+        assert_eq!(
+            json_schema(&update["responses"]["200"])["$ref"],
+            "#/components/schemas/UpdateConfigResponse"
+        );
+        assert_eq!(
+            json_schema(&update["responses"]["4XX"])["$ref"],
+            "#/components/schemas/ApiError"
+        );
+
+        // Safe state can be engaged without a body
+        let engage = &doc["paths"]["/api/v1/system/safe-state"]["post"];
+        assert_eq!(engage["requestBody"]["required"], false);
+
+        let strategies =
+            json_schema(&doc["paths"]["/api/v1/config/strategies"]["get"]["responses"]["200"]);
+        assert_eq!(strategies["type"], "array");
+        assert_eq!(
+            strategies["items"]["$ref"],
+            "#/components/schemas/StrategySettings"
+        );
+
+        // Types from other crates and nested types are registered too
+        let schemas = &doc["components"]["schemas"];
+        assert!(schemas["AlertRuleSpec"]["properties"]["condition"].is_object());
+        assert!(schemas["ValidateResponse"].is_object());
+
+        let mut refs = Vec::new();
+        collect_refs(&doc, &mut refs);
+        assert!(refs.len() > 100, "found {} references", refs.len());
+        for target in refs {
+            let name = target.strip_prefix("#/components/schemas/").unwrap();
+            assert!(schemas[name].is_object(), "unresolved reference {target}");
+        }
+    }
+}
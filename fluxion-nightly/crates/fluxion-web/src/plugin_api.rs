@@ -12,36 +12,69 @@
 
 //! Plugin management API endpoints.
 //!
-//! Provides REST API for managing external strategy plugins:
+//! Provides REST API for managing external strategy plugins (HTTP services and
+//! hot-loaded WASM modules):
 //! - List registered plugins
 //! - Register new external plugins
 //! - Unregister plugins
 //! - Update plugin priorities
+//! - Report circuit breaker state, latency and errors per plugin
+//! - Document what each strategy does (`describe()`)
 
+use askama::Template;
 use axum::{
     Json,
     extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{HeaderMap, StatusCode},
+    response::{Html, IntoResponse},
 };
 use fluxion_plugins::{
-    HttpPlugin, PluginManager, PluginRegistrationRequest, PluginRegistrationResponse,
+    PluginDescription, PluginManager, PluginRegistrationRequest, PluginRegistrationResponse,
+    PluginRegistry, RegisteredPlugin,
 };
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::{debug, info, warn};
+use tracing::{debug, error, info, warn};
+use utoipa::ToSchema;
+
+/// Shortest per-call timeout an external plugin may register with
+const MIN_TIMEOUT_MS: u64 = 100;
+
+/// Longest per-call timeout an external plugin may register with
+const MAX_TIMEOUT_MS: u64 = 30_000;
 
 /// State for plugin API handlers
 #[derive(Clone, Debug)]
 pub struct PluginApiState {
     pub plugin_manager: Arc<RwLock<PluginManager>>,
+    /// Where external plugin registrations are persisted (not persisted if None)
+    pub registry: Option<Arc<PluginRegistry>>,
 }
 
 impl PluginApiState {
     /// Create a new plugin API state
     pub fn new(plugin_manager: Arc<RwLock<PluginManager>>) -> Self {
-        Self { plugin_manager }
+        Self {
+            plugin_manager,
+            registry: None,
+        }
+    }
+
+    /// Persist external plugin registrations and their settings in `registry`
+    #[must_use]
+    pub fn with_registry(mut self, registry: Arc<PluginRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+
+    /// Apply a change to the stored registration of `name`, if it is stored
+    fn update_registry(&self, name: &str, change: impl FnOnce(&mut RegisteredPlugin)) {
+        if let Some(registry) = &self.registry
+            && let Err(e) = registry.update(name, change)
+        {
+            warn!("⚠️ Failed to persist plugin registry: {e}");
+        }
     }
 }
 
@@ -62,7 +95,7 @@ pub struct PluginListResponse {
 }
 
 /// Priority update request
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct PriorityUpdateRequest {
     pub priority: u8,
 }
@@ -74,6 +107,19 @@ pub struct PriorityUpdateResponse {
     pub message: String,
 }
 
+/// Strategy documentation response
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyDocsResponse {
+    pub strategies: Vec<PluginDescription>,
+    pub count: usize,
+}
+
+#[derive(Template)]
+#[template(path = "strategy_docs.html")]
+struct StrategyDocsTemplate {
+    ingress_path: String,
+}
+
 /// List all registered plugins
 ///
 /// GET /api/plugins
@@ -90,6 +136,8 @@ pub async fn list_plugins_handler(State(state): State<PluginApiState>) -> impl I
             enabled,
             plugin_type: if name.starts_with("http:") {
                 "external".to_owned()
+            } else if name.starts_with(fluxion_plugins::wasm::WASM_PLUGIN_PREFIX) {
+                "wasm".to_owned()
             } else {
                 "builtin".to_owned()
             },
@@ -148,13 +196,41 @@ pub async fn register_plugin_handler(
         );
     }
 
-    // Create the HTTP plugin
-    let plugin = HttpPlugin::new(request.manifest.clone(), request.callback_url);
-    let plugin_id = format!("http:{}", request.manifest.name);
+    if request
+        .timeout_ms
+        .is_some_and(|ms| !(MIN_TIMEOUT_MS..=MAX_TIMEOUT_MS).contains(&ms))
+    {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(PluginRegistrationResponse {
+                success: false,
+                error: Some(format!(
+                    "timeout_ms must be between {MIN_TIMEOUT_MS} and {MAX_TIMEOUT_MS}"
+                )),
+                plugin_id: None,
+            }),
+        );
+    }
+
+    let name = request.manifest.name.clone();
+    let plugin_id = format!("http:{name}");
+    let registration =
+        RegisteredPlugin::new(request.manifest, request.callback_url, request.timeout_ms);
+
+    // Persist it; a stored priority override and enabled flag survive re-registration
+    let registration = match &state.registry {
+        Some(registry) => {
+            if let Err(e) = registry.upsert(registration.clone()) {
+                warn!("⚠️ Failed to persist plugin registry: {e}");
+            }
+            registry.get(&name).unwrap_or(registration)
+        }
+        None => registration,
+    };
 
     // Register the plugin
     let mut manager = state.plugin_manager.write();
-    manager.register(Arc::new(plugin));
+    registration.register(&mut manager);
 
     info!("Successfully registered plugin: {}", plugin_id);
 
@@ -177,6 +253,13 @@ pub async fn unregister_plugin_handler(
 ) -> impl IntoResponse {
     info!("Unregistering plugin: {}", name);
 
+    // Forget the stored registration so it is not restored on restart
+    if let Some(registry) = &state.registry
+        && let Err(e) = registry.remove(&name)
+    {
+        warn!("⚠️ Failed to persist plugin registry: {e}");
+    }
+
     let mut manager = state.plugin_manager.write();
 
     // Disable the plugin (we can't actually remove it from the HashMap
@@ -218,6 +301,7 @@ pub async fn update_priority_handler(
     let mut manager = state.plugin_manager.write();
 
     if manager.set_priority(&name, request.priority) {
+        state.update_registry(&name, |p| p.priority_override = Some(request.priority));
         info!(
             "Successfully updated priority for '{}' to {}",
             name, request.priority
@@ -244,7 +328,7 @@ pub async fn update_priority_handler(
 /// Enable or disable a plugin
 ///
 /// PUT /api/plugins/{name}/enabled
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, ToSchema)]
 pub struct EnabledUpdateRequest {
     pub enabled: bool,
 }
@@ -262,6 +346,7 @@ pub async fn update_enabled_handler(
     let mut manager = state.plugin_manager.write();
 
     if manager.set_enabled(&name, request.enabled) {
+        state.update_registry(&name, |p| p.enabled = request.enabled);
         let state_str = if request.enabled {
             "enabled"
         } else {
@@ -286,3 +371,48 @@ pub async fn update_enabled_handler(
         )
     }
 }
+
+/// Circuit breaker state, latency and error statistics of a plugin
+///
+/// GET /api/plugins/{name}/health
+pub async fn plugin_health_handler(
+    State(state): State<PluginApiState>,
+    Path(name): Path<String>,
+) -> impl IntoResponse {
+    let health = state.plugin_manager.read().health(&name);
+    match health {
+        Some(health) => Json(health).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(serde_json::json!({
+                "success": false,
+                "error": format!("Plugin '{}' not found", name)
+            })),
+        )
+            .into_response(),
+    }
+}
+
+/// Documentation of all registered strategies, enabled ones first
+///
+/// GET /api/strategies/docs
+pub async fn strategy_docs_handler(State(state): State<PluginApiState>) -> impl IntoResponse {
+    let strategies = state.plugin_manager.read().describe_plugins();
+    let count = strategies.len();
+    Json(StrategyDocsResponse { strategies, count })
+}
+
+/// Strategy documentation page
+///
+/// GET /strategies
+pub async fn strategy_docs_page_handler(headers: HeaderMap) -> impl IntoResponse {
+    let ingress_path = crate::extract_ingress_path(&headers);
+    let template = StrategyDocsTemplate { ingress_path };
+    match template.render() {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => {
+            error!("Template render error: {e}");
+            Html(format!("<h1>Error</h1><p>{e}</p>")).into_response()
+        }
+    }
+}
@@ -0,0 +1,377 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! "What will tonight do?" preview of the next hours of the schedule.
+//!
+//! Summarizes the planned blocks into a few key numbers (how much the battery
+//! will charge from the grid and at what cost, when the peak discharge happens)
+//! plus the list of non-self-use actions, so users can sanity-check the plan
+//! before going to bed.
+
+use axum::{Json, extract::State, response::IntoResponse};
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::web_bridge::BatterySocPredictionPoint;
+use fluxion_core::{PriceBlockData, WebQueryResponse};
+use serde::Serialize;
+use tracing::error;
+
+use crate::AppState;
+
+/// How far ahead the preview looks
+pub const PREVIEW_HORIZON_HOURS: i64 = 12;
+
+/// Fallback block length when it can't be derived from consecutive blocks
+const DEFAULT_BLOCK_MINUTES: i64 = 15;
+
+/// Summary of the planned actions for the next hours
+#[derive(Debug, Clone, Serialize)]
+pub struct SchedulePreview {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub horizon_hours: i64,
+    /// Energy charged from the grid in force-charge blocks (kWh), from the SOC prediction
+    pub expected_charge_kwh: Option<f32>,
+    /// Cost of that energy at spot price plus buy fee (CZK)
+    pub expected_charge_cost_czk: Option<f32>,
+    /// Energy discharged in force-discharge blocks (kWh)
+    pub expected_discharge_kwh: Option<f32>,
+    /// Most expensive planned discharge block
+    pub peak_discharge: Option<PeakDischarge>,
+    /// Sum of the strategies' expected profit over the horizon (CZK)
+    pub expected_profit_czk: Option<f32>,
+    pub soc_start: Option<f32>,
+    pub soc_end: Option<f32>,
+    pub soc_min: Option<f32>,
+    /// Consecutive charge/discharge/backup blocks merged into actions
+    pub actions: Vec<PreviewAction>,
+    /// IANA timezone the UI should display the times in
+    pub timezone: Option<String>,
+}
+
+/// Highest-priced discharge block in the preview window
+#[derive(Debug, Clone, Serialize)]
+pub struct PeakDischarge {
+    pub at: DateTime<Utc>,
+    pub price_czk: f32,
+}
+
+/// A run of consecutive blocks with the same non-self-use mode
+#[derive(Debug, Clone, Serialize)]
+pub struct PreviewAction {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Block type as used by the chart: "charge", "discharge" or "backup"
+    pub mode: String,
+    pub strategy: Option<String>,
+    pub avg_price_czk: f32,
+}
+
+/// Build the preview for the window `[now, now + horizon)` from a dashboard snapshot
+pub fn build_preview(response: &WebQueryResponse, now: DateTime<Utc>) -> SchedulePreview {
+    let to = now + Duration::hours(PREVIEW_HORIZON_HOURS);
+    let blocks: Vec<&PriceBlockData> = response
+        .prices
+        .as_ref()
+        .map(|p| p.blocks.iter().collect())
+        .unwrap_or_default();
+
+    let block_len = block_length(&blocks);
+
+    // Include the block currently in progress
+    let window: Vec<&PriceBlockData> = blocks
+        .into_iter()
+        .filter(|b| b.timestamp + block_len > now && b.timestamp < to)
+        .collect();
+
+    let prediction = response.battery_soc_prediction.as_deref().unwrap_or(&[]);
+    let capacity_kwh = response
+        .inverters
+        .first()
+        .and_then(|inv| inv.battery_capacity_kwh)
+        .filter(|c| *c > 0.0);
+    let buy_fee = response
+        .pricing_fees
+        .as_ref()
+        .map_or(0.0, |fees| fees.buy_fee_czk);
+
+    // Energy moved in a block, from the predicted SOC change
+    let block_energy_kwh = |block: &PriceBlockData| -> Option<f32> {
+        let capacity = capacity_kwh?;
+        let start = soc_at(prediction, block.timestamp)?;
+        let end = soc_at(prediction, block.timestamp + block_len)?;
+        Some((end - start) / 100.0 * capacity)
+    };
+
+    let mut charge_kwh: Option<f32> = None;
+    let mut charge_cost: Option<f32> = None;
+    let mut discharge_kwh: Option<f32> = None;
+    let mut peak_discharge: Option<PeakDischarge> = None;
+    let mut profit: Option<f32> = None;
+
+    for block in &window {
+        if let Some(p) = block.expected_profit {
+            *profit.get_or_insert(0.0) += p;
+        }
+        match block.block_type.as_str() {
+            "charge" => {
+                if let Some(kwh) = block_energy_kwh(block) {
+                    let kwh = kwh.max(0.0);
+                    *charge_kwh.get_or_insert(0.0) += kwh;
+                    *charge_cost.get_or_insert(0.0) += kwh * (block.price + buy_fee);
+                }
+            }
+            "discharge" => {
+                if let Some(kwh) = block_energy_kwh(block) {
+                    *discharge_kwh.get_or_insert(0.0) += (-kwh).max(0.0);
+                }
+                if peak_discharge
+                    .as_ref()
+                    .is_none_or(|peak| block.price > peak.price_czk)
+                {
+                    peak_discharge = Some(PeakDischarge {
+                        at: block.timestamp,
+                        price_czk: block.price,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let window_soc: Vec<f32> = prediction
+        .iter()
+        .filter(|p| p.timestamp >= now && p.timestamp <= to)
+        .map(|p| p.soc)
+        .collect();
+
+    SchedulePreview {
+        from: now,
+        to,
+        horizon_hours: PREVIEW_HORIZON_HOURS,
+        expected_charge_kwh: charge_kwh,
+        expected_charge_cost_czk: charge_cost,
+        expected_discharge_kwh: discharge_kwh,
+        peak_discharge,
+        expected_profit_czk: profit,
+        soc_start: response
+            .inverters
+            .first()
+            .map(|inv| inv.battery_soc)
+            .or_else(|| window_soc.first().copied()),
+        soc_end: window_soc.last().copied(),
+        soc_min: window_soc.iter().copied().reduce(f32::min),
+        actions: merge_actions(&window, block_len),
+        timezone: response.timezone.clone(),
+    }
+}
+
+/// Length of the schedule blocks, from the spacing of consecutive blocks
+pub(crate) fn block_length(blocks: &[&PriceBlockData]) -> Duration {
+    let block_minutes = blocks
+        .windows(2)
+        .map(|w| (w[1].timestamp - w[0].timestamp).num_minutes())
+        .find(|m| *m > 0)
+        .unwrap_or(DEFAULT_BLOCK_MINUTES);
+    Duration::minutes(block_minutes)
+}
+
+/// Predicted SOC at a time: the last prediction point at or before it
+fn soc_at(prediction: &[BatterySocPredictionPoint], time: DateTime<Utc>) -> Option<f32> {
+    prediction
+        .iter()
+        .take_while(|p| p.timestamp <= time)
+        .last()
+        .map(|p| p.soc)
+}
+
+/// Merge consecutive blocks of the same non-self-use mode into actions
+#[expect(clippy::cast_precision_loss)]
+fn merge_actions(blocks: &[&PriceBlockData], block_len: Duration) -> Vec<PreviewAction> {
+    let mut actions: Vec<(PreviewAction, usize)> = Vec::new();
+
+    for block in blocks {
+        if !matches!(block.block_type.as_str(), "charge" | "discharge" | "backup") {
+            continue;
+        }
+        if let Some((last, count)) = actions.last_mut()
+            && last.mode == block.block_type
+            && last.to == block.timestamp
+        {
+            last.to = block.timestamp + block_len;
+            last.avg_price_czk += block.price;
+            *count += 1;
+            continue;
+        }
+        actions.push((
+            PreviewAction {
+                from: block.timestamp,
+                to: block.timestamp + block_len,
+                mode: block.block_type.clone(),
+                strategy: block.strategy.clone(),
+                avg_price_czk: block.price,
+            },
+            1,
+        ));
+    }
+
+    actions
+        .into_iter()
+        .map(|(mut action, count)| {
+            action.avg_price_czk /= count as f32;
+            action
+        })
+        .collect()
+}
+
+/// GET /api/preview - Summary of the planned actions for the next 12 hours
+pub async fn preview_handler(State(app_state): State<AppState>) -> impl IntoResponse {
+    match app_state.query_sender.query_dashboard().await {
+        Ok(response) => Json(build_preview(&response, Utc::now())).into_response(),
+        Err(e) => {
+            error!("Failed to query dashboard data for preview: {e}");
+            crate::query_error_response(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_core::web_bridge::PricingFees;
+    use fluxion_core::{PriceData, SystemHealthData};
+
+    fn block(timestamp: DateTime<Utc>, price: f32, block_type: &str) -> PriceBlockData {
+        PriceBlockData {
+            timestamp,
+            price,
+            block_type: block_type.to_owned(),
+            target_soc: None,
+            planned_soc: None,
+            decision_reason: None,
+            strategy: Some("Test".to_owned()),
+            expected_profit: Some(1.0),
+            reason: None,
+            decision_uid: None,
+            debug_info: None,
+            forecast: None,
+            actual: None,
+            tariff: None,
+            is_historical: false,
+        }
+    }
+
+    fn response(now: DateTime<Utc>) -> WebQueryResponse {
+        let q = Duration::minutes(15);
+        let blocks = vec![
+            block(now, 2.0, "charge"),
+            block(now + q, 2.2, "charge"),
+            block(now + q * 2, 4.0, "self-use"),
+            block(now + q * 3, 6.0, "discharge"),
+            block(now + q * 4, 7.0, "discharge"),
+        ];
+        let soc = [20.0, 30.0, 40.0, 40.0, 35.0, 30.0];
+        WebQueryResponse {
+            timestamp: now,
+            debug_mode: false,
+            inverters: vec![],
+            schedule: None,
+            prices: Some(PriceData {
+                current_price: 2.0,
+                min_price: 2.0,
+                max_price: 7.0,
+                avg_price: 4.0,
+                blocks,
+                today_min_price: 2.0,
+                today_max_price: 7.0,
+                today_avg_price: 4.0,
+                today_median_price: 4.0,
+                tomorrow_min_price: None,
+                tomorrow_max_price: None,
+                tomorrow_avg_price: None,
+                tomorrow_median_price: None,
+            }),
+            health: SystemHealthData {
+                inverter_source: true,
+                price_source: true,
+                last_update: now,
+                errors: vec![],
+            },
+            timezone: None,
+            battery_soc_history: None,
+            battery_soc_prediction: Some(
+                soc.iter()
+                    .enumerate()
+                    .map(|(i, soc)| BatterySocPredictionPoint {
+                        timestamp: now + q * i32::try_from(i).unwrap(),
+                        soc: *soc,
+                    })
+                    .collect(),
+            ),
+            pv_generation_history: None,
+            battery_power_history: None,
+            grid_power_history: None,
+            consumption_stats: None,
+            hdo_schedule: None,
+            pricing_fees: Some(PricingFees {
+                buy_fee_czk: 0.5,
+                sell_fee_czk: 0.5,
+            }),
+            solar_forecast: None,
+        }
+    }
+
+    #[test]
+    fn test_preview_actions_and_peak() {
+        let now = Utc::now();
+        let preview = build_preview(&response(now), now);
+
+        assert_eq!(preview.actions.len(), 2);
+        assert_eq!(preview.actions[0].mode, "charge");
+        assert_eq!(preview.actions[0].to, now + Duration::minutes(30));
+        assert!((preview.actions[0].avg_price_czk - 2.1).abs() < 1e-4);
+
+        let peak = preview.peak_discharge.unwrap();
+        assert_eq!(peak.at, now + Duration::minutes(60));
+        assert!((peak.price_czk - 7.0).abs() < f32::EPSILON);
+        assert!((preview.expected_profit_czk.unwrap() - 5.0).abs() < 1e-4);
+        assert!((preview.soc_min.unwrap() - 20.0).abs() < f32::EPSILON);
+
+        // No battery capacity known, so energy figures are unavailable
+        assert!(preview.expected_charge_kwh.is_none());
+    }
+
+    #[test]
+    fn test_preview_energy_from_soc_prediction() {
+        let now = Utc::now();
+        let mut response = response(now);
+        let mut inverter: fluxion_core::InverterData = serde_json::from_value(serde_json::json!({
+            "id": "inv", "topology": "independent", "mode": "SelfUse", "mode_reason": "",
+            "actual_mode": null, "mode_synced": true, "battery_soc": 20.0,
+            "battery_power_w": 0.0, "battery_voltage_v": 0.0, "battery_current_a": 0.0,
+            "battery_temperature_c": 0.0, "grid_power_w": 0.0, "grid_voltage_v": 0.0,
+            "grid_frequency_hz": 0.0, "pv_power_w": 0.0, "pv1_power_w": 0.0,
+            "pv2_power_w": 0.0, "daily_energy_kwh": 0.0, "total_energy_kwh": 0.0,
+            "online": true, "run_mode": "", "error_code": 0, "inverter_temperature_c": 0.0,
+        }))
+        .unwrap();
+        inverter.battery_capacity_kwh = Some(10.0);
+        response.inverters.push(inverter);
+
+        let preview = build_preview(&response, now);
+        // 20% -> 40% of 10 kWh
+        assert!((preview.expected_charge_kwh.unwrap() - 2.0).abs() < 1e-4);
+        // 1 kWh at 2.5 + 1 kWh at 2.7
+        assert!((preview.expected_charge_cost_czk.unwrap() - 5.2).abs() < 1e-4);
+        // 40% -> 30%
+        assert!((preview.expected_discharge_kwh.unwrap() - 1.0).abs() < 1e-4);
+    }
+}
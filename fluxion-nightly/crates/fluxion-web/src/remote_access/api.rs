@@ -17,12 +17,13 @@ use axum::{
     response::{Html, IntoResponse},
     routing::{delete, get, post},
 };
-use fluxion_mobile_types::QrPayload;
+use fluxion_mobile_types::{DeviceRole, QrPayload};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tracing::{error, info};
 
-use super::{DeviceStore, TorManager};
+use super::{DeviceEntry, DeviceStore, TorManager};
+use utoipa::ToSchema;
 
 #[derive(Template)]
 #[template(path = "remote_access.html")]
@@ -53,15 +54,25 @@ struct StatusResponse {
     device_count: usize,
 }
 
-#[derive(Deserialize)]
-struct PairRequest {
+#[derive(Deserialize, ToSchema)]
+pub(crate) struct PairRequest {
     name: String,
-    #[serde(default = "default_access_mode")]
-    mode: String,
+    /// Older pages send `mode` (`full` / `readonly`)
+    #[serde(default = "default_role", alias = "mode")]
+    role: String,
 }
 
-fn default_access_mode() -> String {
-    "full".to_owned()
+fn default_role() -> String {
+    "viewer".to_owned()
+}
+
+fn parse_role(role: &str) -> Option<DeviceRole> {
+    match role {
+        "viewer" | "readonly" => Some(DeviceRole::Viewer),
+        "controller" => Some(DeviceRole::Controller),
+        "admin" | "full" => Some(DeviceRole::Admin),
+        _ => None,
+    }
 }
 
 #[derive(Serialize)]
@@ -75,9 +86,10 @@ struct PairResponse {
 struct DeviceResponse {
     id: String,
     name: String,
-    access_mode: String,
+    role: DeviceRole,
     created_at: String,
     last_seen: Option<String>,
+    key_rotated_at: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -105,18 +117,15 @@ async fn pair_handler(
     State(state): State<RemoteAccessApiState>,
     Json(req): Json<PairRequest>,
 ) -> impl IntoResponse {
-    let mode = match req.mode.as_str() {
-        "full" | "readonly" => req.mode.clone(),
-        _ => {
-            return (
-                axum::http::StatusCode::BAD_REQUEST,
-                Json(serde_json::json!({"error": "mode must be 'full' or 'readonly'"})),
-            )
-                .into_response();
-        }
+    let Some(role) = parse_role(&req.role) else {
+        return (
+            axum::http::StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({"error": "role must be 'viewer', 'controller' or 'admin'"})),
+        )
+            .into_response();
     };
 
-    let (entry, privkey_b64) = match state.device_store.register_device(&req.name, &mode) {
+    let (entry, privkey_b64, token) = match state.device_store.register_device(&req.name, role) {
         Ok(result) => result,
         Err(e) => {
             error!("Failed to register device: {e}");
@@ -133,7 +142,23 @@ async fn pair_handler(
         error!("Failed to reload Tor after pairing: {e}");
     }
 
-    // Build QR payload
+    info!(
+        "Paired device '{}' (id={}, role={})",
+        entry.name,
+        entry.id,
+        entry.role.as_str()
+    );
+
+    Json(pair_response(&state, entry, privkey_b64, token)).into_response()
+}
+
+/// QR code handing a device its onion address, client-auth key and token
+fn pair_response(
+    state: &RemoteAccessApiState,
+    entry: DeviceEntry,
+    privkey_b64: String,
+    token: String,
+) -> PairResponse {
     let onion_address = state
         .tor_manager
         .lock()
@@ -145,27 +170,22 @@ async fn pair_handler(
         onion: onion_address,
         key: privkey_b64,
         name: state.instance_name.clone(),
-        mode: entry.access_mode.clone(),
+        role: entry.role,
+        token,
     })
     .expect("QrPayload serialization cannot fail");
 
     // Generate QR code SVG
     let qr_svg = render_qr_svg(&qr_payload);
 
-    info!(
-        "Paired device '{}' (id={}, mode={})",
-        entry.name, entry.id, entry.access_mode
-    );
-
-    Json(PairResponse {
+    PairResponse {
         device_id: entry.id,
         qr_payload,
         qr_svg,
-    })
-    .into_response()
+    }
 }
 
-/// GET /api/remote/devices
+/// GET /api/remote-access/devices
 async fn devices_handler(State(state): State<RemoteAccessApiState>) -> impl IntoResponse {
     let devices: Vec<DeviceResponse> = state
         .device_store
@@ -174,16 +194,17 @@ async fn devices_handler(State(state): State<RemoteAccessApiState>) -> impl Into
         .map(|d| DeviceResponse {
             id: d.id,
             name: d.name,
-            access_mode: d.access_mode,
+            role: d.role,
             created_at: d.created_at.to_rfc3339(),
             last_seen: d.last_seen.map(|dt| dt.to_rfc3339()),
+            key_rotated_at: d.key_rotated_at.map(|dt| dt.to_rfc3339()),
         })
         .collect();
 
     Json(devices)
 }
 
-/// DELETE /api/remote/devices/{id}
+/// DELETE /api/remote-access/devices/{id}
 async fn revoke_handler(
     State(state): State<RemoteAccessApiState>,
     Path(device_id): Path<String>,
@@ -212,6 +233,40 @@ async fn revoke_handler(
     }
 }
 
+/// POST /api/remote-access/devices/{id}/rotate-key
+///
+/// Replaces the device's client-auth key and token; the device re-pairs with the returned QR code.
+async fn rotate_key_handler(
+    State(state): State<RemoteAccessApiState>,
+    Path(device_id): Path<String>,
+) -> impl IntoResponse {
+    match state.device_store.rotate_key(&device_id) {
+        Ok(Some((entry, privkey_b64, token))) => {
+            if let Err(e) = state.tor_manager.lock().reload() {
+                error!("Failed to reload Tor after key rotation: {e}");
+            }
+            info!(
+                "Rotated client-auth key of device '{}' (id={})",
+                entry.name, entry.id
+            );
+            Json(pair_response(&state, entry, privkey_b64, token)).into_response()
+        }
+        Ok(None) => (
+            axum::http::StatusCode::NOT_FOUND,
+            Json(serde_json::json!({"error": "Device not found"})),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Failed to rotate device key: {e}");
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to rotate device key"})),
+            )
+                .into_response()
+        }
+    }
+}
+
 /// Render a QR code as SVG from the given payload string.
 fn render_qr_svg(payload: &str) -> String {
     use qrcode::QrCode;
@@ -250,6 +305,13 @@ pub fn remote_access_routes(state: RemoteAccessApiState) -> Router {
         .route("/remote-access", get(page_handler))
         .route("/api/remote/status", get(status_handler))
         .route("/api/remote/pair", post(pair_handler))
+        .route("/api/remote-access/devices", get(devices_handler))
+        .route("/api/remote-access/devices/{id}", delete(revoke_handler))
+        .route(
+            "/api/remote-access/devices/{id}/rotate-key",
+            post(rotate_key_handler),
+        )
+        // Paths used before device management moved to /api/remote-access
         .route("/api/remote/devices", get(devices_handler))
         .route("/api/remote/devices/{id}", delete(revoke_handler))
         .with_state(state)
@@ -288,11 +350,22 @@ mod tests {
             onion: "xyz.onion".to_owned(),
             key: "base64key==".to_owned(),
             name: "FluxION Home".to_owned(),
-            mode: "full".to_owned(),
+            role: DeviceRole::Viewer,
+            token: "device-token".to_owned(),
         };
         let s = serde_json::to_string(&payload).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&s).unwrap();
         assert_eq!(parsed["v"], 1);
-        assert_eq!(parsed["mode"], "full");
+        assert_eq!(parsed["role"], "viewer");
+        assert_eq!(parsed["token"], "device-token");
+    }
+
+    #[test]
+    fn test_parse_role() {
+        assert_eq!(parse_role("viewer"), Some(DeviceRole::Viewer));
+        assert_eq!(parse_role("readonly"), Some(DeviceRole::Viewer));
+        assert_eq!(parse_role("controller"), Some(DeviceRole::Controller));
+        assert_eq!(parse_role("full"), Some(DeviceRole::Admin));
+        assert_eq!(parse_role("root"), None);
     }
 }
@@ -10,26 +10,42 @@
 //
 // For commercial licensing, please contact: info@solare.cz
 
-use chrono::{DateTime, Utc};
-use rand_core::OsRng;
+use chrono::{DateTime, Duration, Utc};
+use fluxion_mobile_types::DeviceRole;
+use rand_core::{OsRng, RngCore};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
 use std::path::{Path, PathBuf};
 use x25519_dalek::{PublicKey, StaticSecret};
 
+/// How stale `last_seen` may get before a request rewrites it
+const LAST_SEEN_RESOLUTION: Duration = Duration::minutes(1);
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeviceEntry {
     pub id: String,
     pub name: String,
-    pub access_mode: String,
+    /// Stored as `access_mode` (`full` / `readonly`) by older versions
+    #[serde(alias = "access_mode")]
+    pub role: DeviceRole,
+    /// SHA-256 of the device token; None for devices paired before roles
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_sha256: Option<String>,
     pub pubkey_base32: String,
     pub created_at: DateTime<Utc>,
     pub last_seen: Option<DateTime<Utc>>,
+    /// When the client-auth key and token were last reissued
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_rotated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug)]
 pub struct DeviceStore {
     devices_path: PathBuf,
     auth_dir: PathBuf,
+    /// Serializes read-modify-write cycles of `devices.json`
+    write_lock: parking_lot::Mutex<()>,
 }
 
 /// Generate a new x25519 keypair for Tor client authorization.
@@ -53,6 +69,23 @@ pub fn encode_privkey_base64(secret: &StaticSecret) -> String {
     base64::engine::general_purpose::STANDARD.encode(secret.to_bytes())
 }
 
+/// Generate the token a device presents to identify itself (32 random bytes, base64url).
+fn generate_device_token() -> String {
+    use base64::Engine as _;
+    let mut bytes = [0_u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut out, byte| {
+            let _ = write!(out, "{byte:02x}");
+            out
+        })
+}
+
 impl DeviceStore {
     #[must_use]
     pub fn new(data_dir: &Path) -> Self {
@@ -61,6 +94,7 @@ impl DeviceStore {
         Self {
             devices_path,
             auth_dir,
+            write_lock: parking_lot::Mutex::new(()),
         }
     }
 
@@ -82,17 +116,18 @@ impl DeviceStore {
         std::fs::write(&self.devices_path, json)
     }
 
-    /// Register a new device: generates keypair, writes .auth file, persists metadata.
-    /// Returns `(device_entry, private_key_base64)`.
+    /// Register a new device: generates keypair and token, writes .auth file, persists metadata.
+    /// Returns `(device_entry, private_key_base64, device_token)`.
     pub fn register_device(
         &self,
         name: &str,
-        access_mode: &str,
-    ) -> std::io::Result<(DeviceEntry, String)> {
+        role: DeviceRole,
+    ) -> std::io::Result<(DeviceEntry, String, String)> {
         let (secret, public) = generate_client_keypair();
         let device_id = uuid::Uuid::new_v4().to_string();
         let pubkey_b32 = encode_pubkey_base32(&public);
         let privkey_b64 = encode_privkey_base64(&secret);
+        let token = generate_device_token();
 
         // Write Tor authorized_clients file
         self.write_auth_file(&device_id, &pubkey_b32)?;
@@ -100,21 +135,82 @@ impl DeviceStore {
         let entry = DeviceEntry {
             id: device_id,
             name: name.to_owned(),
-            access_mode: access_mode.to_owned(),
+            role,
+            token_sha256: Some(hash_token(&token)),
             pubkey_base32: pubkey_b32,
             created_at: Utc::now(),
             last_seen: None,
+            key_rotated_at: None,
         };
 
+        let _guard = self.write_lock.lock();
         let mut devices = self.load_devices();
         devices.push(entry.clone());
         self.save_devices(&devices)?;
 
-        Ok((entry, privkey_b64))
+        Ok((entry, privkey_b64, token))
+    }
+
+    /// Device that was issued `token`, if it is still paired.
+    #[must_use]
+    pub fn find_by_token(&self, token: &str) -> Option<DeviceEntry> {
+        let digest = hash_token(token);
+        self.load_devices()
+            .into_iter()
+            .find(|d| d.token_sha256.as_deref() == Some(digest.as_str()))
+    }
+
+    /// Issue a new client-auth keypair and token for a paired device.
+    ///
+    /// The old key stops working once Tor reloads, so the device has to scan
+    /// the new QR code. Returns None for unknown devices, otherwise
+    /// `(device_entry, private_key_base64, device_token)`.
+    pub fn rotate_key(
+        &self,
+        device_id: &str,
+    ) -> std::io::Result<Option<(DeviceEntry, String, String)>> {
+        let _guard = self.write_lock.lock();
+        let mut devices = self.load_devices();
+        let Some(entry) = devices.iter_mut().find(|d| d.id == device_id) else {
+            return Ok(None);
+        };
+
+        let (secret, public) = generate_client_keypair();
+        let token = generate_device_token();
+        entry.pubkey_base32 = encode_pubkey_base32(&public);
+        entry.token_sha256 = Some(hash_token(&token));
+        entry.key_rotated_at = Some(Utc::now());
+        let entry = entry.clone();
+
+        self.write_auth_file(&entry.id, &entry.pubkey_base32)?;
+        self.save_devices(&devices)?;
+        Ok(Some((entry, encode_privkey_base64(&secret), token)))
+    }
+
+    /// Record that `device` made a request at `now`.
+    ///
+    /// Writes at most once per [`LAST_SEEN_RESOLUTION`]; returns whether it did.
+    pub fn mark_seen(&self, device: &DeviceEntry, now: DateTime<Utc>) -> std::io::Result<bool> {
+        if device
+            .last_seen
+            .is_some_and(|seen| now - seen < LAST_SEEN_RESOLUTION)
+        {
+            return Ok(false);
+        }
+
+        let _guard = self.write_lock.lock();
+        let mut devices = self.load_devices();
+        let Some(entry) = devices.iter_mut().find(|d| d.id == device.id) else {
+            return Ok(false);
+        };
+        entry.last_seen = Some(now);
+        self.save_devices(&devices)?;
+        Ok(true)
     }
 
     /// Revoke a device: remove .auth file and device metadata.
     pub fn revoke_device(&self, device_id: &str) -> std::io::Result<bool> {
+        let _guard = self.write_lock.lock();
         let mut devices = self.load_devices();
         let original_len = devices.len();
         devices.retain(|d| d.id != device_id);
@@ -173,11 +269,17 @@ mod tests {
         let store = DeviceStore::new(tmp.path());
 
         // Register
-        let (entry, privkey) = store.register_device("My Phone", "full").unwrap();
+        let (entry, privkey, token) = store
+            .register_device("My Phone", DeviceRole::Controller)
+            .unwrap();
         assert_eq!(entry.name, "My Phone");
-        assert_eq!(entry.access_mode, "full");
+        assert_eq!(entry.role, DeviceRole::Controller);
         assert!(!privkey.is_empty());
 
+        // Token lookup
+        assert_eq!(store.find_by_token(&token).unwrap().id, entry.id);
+        assert!(store.find_by_token("unknown").is_none());
+
         // Load
         let devices = store.load_devices();
         assert_eq!(devices.len(), 1);
@@ -199,8 +301,53 @@ mod tests {
         assert!(store.load_devices().is_empty());
         assert!(!auth_path.exists());
 
+        assert!(store.find_by_token(&token).is_none());
+
         // Revoke nonexistent
         let revoked = store.revoke_device("nonexistent").unwrap();
         assert!(!revoked);
     }
+
+    #[test]
+    fn test_rotate_key_and_last_seen() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = DeviceStore::new(tmp.path());
+        let (entry, privkey, token) = store.register_device("Tablet", DeviceRole::Viewer).unwrap();
+
+        // Rotation replaces key and token but keeps the device
+        let (rotated, new_privkey, new_token) = store.rotate_key(&entry.id).unwrap().unwrap();
+        assert_eq!(rotated.id, entry.id);
+        assert_eq!(rotated.role, DeviceRole::Viewer);
+        assert_ne!(rotated.pubkey_base32, entry.pubkey_base32);
+        assert_ne!(new_privkey, privkey);
+        assert!(rotated.key_rotated_at.is_some());
+        assert!(store.find_by_token(&token).is_none());
+        assert_eq!(store.find_by_token(&new_token).unwrap().id, entry.id);
+        let auth_content = std::fs::read_to_string(
+            tmp.path()
+                .join("tor")
+                .join("authorized_clients")
+                .join(format!("{}.auth", entry.id)),
+        )
+        .unwrap();
+        assert!(auth_content.ends_with(&rotated.pubkey_base32));
+        assert!(store.rotate_key("nonexistent").unwrap().is_none());
+
+        // last_seen is written once per resolution interval
+        let now = Utc::now();
+        assert!(store.mark_seen(&rotated, now).unwrap());
+        let seen = store.find_by_token(&new_token).unwrap();
+        assert_eq!(seen.last_seen, Some(now));
+        assert!(!store.mark_seen(&seen, now + Duration::seconds(30)).unwrap());
+        assert!(store.mark_seen(&seen, now + Duration::minutes(2)).unwrap());
+    }
+
+    #[test]
+    fn test_legacy_device_entry() {
+        let json = r#"[{"id":"d1","name":"Old Phone","access_mode":"readonly",
+            "pubkey_base32":"ABC","created_at":"2026-01-31T10:00:00Z","last_seen":null}]"#;
+        let devices: Vec<DeviceEntry> = serde_json::from_str(json).unwrap();
+        assert_eq!(devices[0].role, DeviceRole::Viewer);
+        assert!(devices[0].token_sha256.is_none());
+    }
 }
@@ -12,26 +12,35 @@
 
 use askama::Template;
 use axum::{
-    Json, Router,
-    extract::{Query, State},
-    response::{Html, IntoResponse},
+    Extension, Json, Router,
+    extract::{ConnectInfo, Query, Request, State},
+    http::{HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{Html, IntoResponse, Response},
     routing::{get, post},
 };
-use chrono::Utc;
+use chrono::{Duration, Utc};
+use fluxion_core::savings::{SavingsPeriod, SavingsSummary, summarize};
 use fluxion_core::{
-    UserControlChangeType, UserControlPersistence, UserControlUpdateEvent, WebQuerySender,
+    TimeFormatter, UserControlChangeType, UserControlPersistence, UserControlUpdateEvent,
+    WebQuerySender,
 };
 use fluxion_i18n::I18n;
 use fluxion_mobile_types::{
-    MobileChartPoint, MobileControlRequest, MobileControlResponse, MobileStateResponse,
-    MobileTimeSlot, MobileUserControl, VersionResponse, API_VERSION,
+    API_VERSION, API_VERSION_V2, DEVICE_TOKEN_HEADER, DeviceRole, MobileBranding, MobileChartPoint,
+    MobileConsumptionResponse, MobileControlRequest, MobileControlResponse, MobileDailySummary,
+    MobileHistoryPage, MobilePreview, MobilePreviewAction, MobileScheduleBlock,
+    MobileScheduleResponse, MobileSocPoint, MobileStateResponse, MobileTimeSlot, MobileUserControl,
+    SUPPORTED_API_VERSIONS, VersionResponse,
 };
 use serde::Deserialize;
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tracing::error;
+use tracing::{error, warn};
 
-use super::MobileBundleTemplate;
+use super::{DeviceEntry, DeviceStore, MobileBundleTemplate};
 use crate::UserControlApiState;
+use crate::safe_state_api::{self, EngageSafeStateRequest};
 
 /// Shared state for mobile-facing API endpoints (served over Tor to mobile devices).
 #[derive(Clone, Debug)]
@@ -40,8 +49,21 @@ pub struct MobileApiState {
     pub i18n: Arc<I18n>,
     pub user_control_api_state: Option<UserControlApiState>,
     pub ui_version: String,
+    /// Paired devices, to resolve the role of Tor requests
+    pub device_store: Arc<DeviceStore>,
+    /// Savings ledger behind the v2 daily history; None when not recorded
+    pub savings: Option<crate::savings::SavingsState>,
 }
 
+/// Length of a schedule block, used for the last block of the plan
+const BLOCK_MINUTES: i64 = 15;
+
+/// Days of savings history paged by `/mobile/api/v2/history`
+const HISTORY_DAYS: i64 = 365;
+
+const DEFAULT_PER_PAGE: u32 = 14;
+const MAX_PER_PAGE: u32 = 90;
+
 // ==================== Query params ====================
 
 #[derive(Deserialize)]
@@ -50,15 +72,98 @@ struct UiBundleQuery {
     initial: Option<u8>,
 }
 
+#[derive(Deserialize)]
+struct HistoryQuery {
+    #[serde(default = "default_page")]
+    page: u32,
+    #[serde(default = "default_per_page")]
+    per_page: u32,
+}
+
+fn default_page() -> u32 {
+    1
+}
+
+fn default_per_page() -> u32 {
+    DEFAULT_PER_PAGE
+}
+
+// ==================== Device roles ====================
+
+/// Role a mobile route needs
+fn required_role(method: &Method, path: &str) -> DeviceRole {
+    match (method, path) {
+        // Resuming hands control back to the scheduler after an emergency
+        (&Method::DELETE, "/mobile/api/safe-state") => DeviceRole::Admin,
+        (&Method::POST, "/mobile/api/control" | "/mobile/api/safe-state") => DeviceRole::Controller,
+        _ => DeviceRole::Viewer,
+    }
+}
+
+/// Paired device presenting `headers`
+///
+/// Devices paired before roles existed send no token; they can only view.
+fn paired_device(store: &DeviceStore, headers: &HeaderMap) -> Option<DeviceEntry> {
+    headers
+        .get(DEVICE_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|token| store.find_by_token(token.trim()))
+}
+
+/// Middleware enforcing device roles on requests over the Tor hidden service
+///
+/// Tor client authorization only proves the device is paired; the role comes
+/// from the device token. LAN and HA requests are covered by API keys and login.
+async fn require_device_role(
+    State(state): State<MobileApiState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    if !crate::api_keys::is_tor_mobile_request(peer, request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let device = paired_device(&state.device_store, request.headers());
+    if let Some(device) = &device
+        && let Err(e) = state.device_store.mark_seen(device, Utc::now())
+    {
+        warn!("📱 Failed to record last seen of device {}: {e}", device.id);
+    }
+    let role = device.map_or(DeviceRole::Viewer, |device| device.role);
+    let required = required_role(request.method(), request.uri().path());
+    if role < required {
+        warn!(
+            "📱 Mobile device with role {} denied: {} {} needs {}",
+            role.as_str(),
+            request.method(),
+            request.uri().path(),
+            required.as_str()
+        );
+        return crate::api_keys::deny(
+            StatusCode::FORBIDDEN,
+            &format!("Device role {} is not allowed to do this", role.as_str()),
+        );
+    }
+
+    request.extensions_mut().insert(role);
+    next.run(request).await
+}
+
 // ==================== Handlers ====================
 
 /// GET /mobile/api/version — return the current UI bundle version.
 ///
 /// Lightweight endpoint for mobile clients to check if their cached UI is outdated
-/// without downloading the full bundle.
+/// without downloading the full bundle. Also lists the API versions served, so
+/// the app can pick the newest one both sides speak.
 async fn version_handler(State(state): State<MobileApiState>) -> Json<VersionResponse> {
     Json(VersionResponse {
         version: state.ui_version.clone(),
+        api_versions: SUPPORTED_API_VERSIONS.to_vec(),
     })
 }
 
@@ -69,10 +174,12 @@ async fn version_handler(State(state): State<MobileApiState>) -> Json<VersionRes
 /// avoid a second Tor round-trip on first launch.
 async fn ui_bundle_handler(
     State(state): State<MobileApiState>,
+    role: Option<Extension<DeviceRole>>,
     Query(params): Query<UiBundleQuery>,
 ) -> impl IntoResponse {
+    let role = role.map(|Extension(role)| role);
     let initial_state = if params.initial == Some(1) {
-        build_state_json(&state).await.ok()
+        build_state_json(&state, role).await.ok()
     } else {
         None
     };
@@ -106,8 +213,11 @@ async fn ui_bundle_handler(
 }
 
 /// GET /mobile/api/state — return current system state as JSON snapshot.
-async fn state_handler(State(state): State<MobileApiState>) -> impl IntoResponse {
-    match build_state_response(&state).await {
+async fn state_handler(
+    State(state): State<MobileApiState>,
+    role: Option<Extension<DeviceRole>>,
+) -> impl IntoResponse {
+    match build_state_response(&state, role.map(|Extension(role)| role)).await {
         Ok(response) => Json(response).into_response(),
         Err(e) => {
             error!("Failed to build mobile state: {e}");
@@ -120,12 +230,97 @@ async fn state_handler(State(state): State<MobileApiState>) -> impl IntoResponse
     }
 }
 
+/// GET /mobile/api/v2/schedule — the whole schedule with reasons and SOC series.
+async fn schedule_handler(State(state): State<MobileApiState>) -> impl IntoResponse {
+    match build_schedule_response(&state).await {
+        Ok(response) => Json(response).into_response(),
+        Err(e) => {
+            error!("Failed to build mobile schedule: {e}");
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to query schedule"})),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// GET /mobile/api/v2/consumption — learned consumption and today's energy.
+async fn consumption_handler(State(state): State<MobileApiState>) -> impl IntoResponse {
+    let response = match state.query_sender.query_dashboard().await {
+        Ok(response) => response,
+        Err(e) => {
+            error!("Failed to query mobile consumption: {e}");
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to query system state"})),
+            )
+                .into_response();
+        }
+    };
+
+    let inv = response.inverters.first();
+    let stats = response.consumption_stats.as_ref();
+    Json(MobileConsumptionResponse {
+        api_version: API_VERSION_V2,
+        timestamp: response.time_formatter().rfc3339(response.timestamp),
+        ema_kwh: stats.and_then(|s| s.ema_kwh),
+        ema_days: stats.map_or(0, |s| s.ema_days),
+        today_import_kwh: stats.and_then(|s| s.today_import_kwh),
+        yesterday_import_kwh: stats.and_then(|s| s.yesterday_import_kwh),
+        today_solar_kwh: inv.and_then(|i| i.today_solar_energy_kwh),
+        today_export_kwh: inv.and_then(|i| i.grid_export_today_kwh),
+        hourly_profile: stats
+            .and_then(|s| s.hourly_consumption_profile.clone())
+            .unwrap_or_default(),
+    })
+    .into_response()
+}
+
+/// GET /mobile/api/v2/history?page=&per_page= — daily savings summaries, newest first.
+async fn history_handler(
+    State(state): State<MobileApiState>,
+    Query(query): Query<HistoryQuery>,
+) -> impl IntoResponse {
+    let Some(savings) = state.savings else {
+        return (
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({"error": "History not available"})),
+        )
+            .into_response();
+    };
+
+    // SQLite calls block
+    let to = Utc::now();
+    let from = to - Duration::days(HISTORY_DAYS);
+    let ledger = savings.ledger.clone();
+    let blocks = match tokio::task::spawn_blocking(move || ledger.query(from, to)).await {
+        Ok(Ok(blocks)) => blocks,
+        Ok(Err(e)) => {
+            error!("Failed to query savings ledger: {e:#}");
+            return (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({"error": "Failed to query history"})),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            error!("Savings ledger query failed: {e}");
+            return axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let days = summarize(&blocks, SavingsPeriod::Day, &savings.time_formatter);
+    Json(history_page(days, query.page, query.per_page)).into_response()
+}
+
 /// POST /mobile/api/control — accept bulk control changes from mobile device.
 ///
-/// Returns `403 Forbidden` for read-only devices (TODO: enforce via auth middleware).
+/// Viewer devices get `403 Forbidden` from [`require_device_role`].
 /// Returns the updated state snapshot so the app can refresh its cache immediately.
 async fn control_handler(
     State(state): State<MobileApiState>,
+    role: Option<Extension<DeviceRole>>,
     Json(req): Json<MobileControlRequest>,
 ) -> impl IntoResponse {
     let Some(uc_api) = &state.user_control_api_state else {
@@ -136,10 +331,8 @@ async fn control_handler(
             .into_response();
     };
 
-    // Apply changes to user control state
-    let new_state = {
-        let mut user_state = uc_api.state.write();
-
+    // Apply changes to user control state, rejecting changes that introduce conflicts
+    let change = crate::user_control_api::apply_change(uc_api, |user_state| {
         if let Some(charge_enabled) = req.charge_from_grid_enabled {
             user_state.disallow_charge = !charge_enabled;
         }
@@ -166,7 +359,7 @@ async fn control_handler(
 
         // Replace fixed time slots if provided
         if let Some(slots) = &req.fixed_time_slots {
-            user_state.fixed_time_slots = slots
+            let replaced: Vec<_> = slots
                 .iter()
                 .filter_map(|s| {
                     let from = chrono::DateTime::parse_from_rfc3339(&s.start)
@@ -189,11 +382,31 @@ async fn control_handler(
                         created_at: Utc::now(),
                     })
                 })
+                // The app may send back slots that expired meanwhile; drop them
+                .filter(|slot| !slot.has_passed(Utc::now()))
                 .collect();
+
+            // Slots the app no longer sends were deleted on the phone; keep them restorable
+            user_state.replace_slots(replaced, "mobile");
         }
 
-        user_state.last_modified = Some(Utc::now());
-        user_state.clone()
+        Ok(())
+    });
+    let new_state = match change {
+        Ok((new_state, _warnings)) => new_state,
+        Err(e) => {
+            warn!("Rejected mobile control changes: {}", e.message());
+            return (
+                axum::http::StatusCode::CONFLICT,
+                Json(MobileControlResponse {
+                    ok: false,
+                    applied_at: Utc::now().to_rfc3339(),
+                    state: None,
+                    error: Some(e.message()),
+                }),
+            )
+                .into_response();
+        }
     };
 
     // Persist to disk
@@ -219,7 +432,7 @@ async fn control_handler(
     }
 
     // Return updated state snapshot
-    let state_response = build_state_response(&state).await.ok();
+    let state_response = build_state_response(&state, role.map(|r| r.0)).await.ok();
 
     Json(MobileControlResponse {
         ok: true,
@@ -230,8 +443,49 @@ async fn control_handler(
     .into_response()
 }
 
+/// POST /mobile/api/safe-state — engage the emergency safe state from the mobile app.
+async fn safe_state_engage_handler(
+    State(state): State<MobileApiState>,
+    body: Option<Json<EngageSafeStateRequest>>,
+) -> impl IntoResponse {
+    let Some(uc_api) = &state.user_control_api_state else {
+        return user_control_unavailable();
+    };
+    let request = body.map(|Json(r)| r).unwrap_or_default();
+    let triggered_by = request
+        .triggered_by
+        .clone()
+        .filter(|s| !s.trim().is_empty())
+        .map_or_else(|| "mobile".to_owned(), |name| format!("mobile: {name}"));
+
+    match safe_state_api::engage(uc_api, &request, triggered_by) {
+        Ok(response) => Json(response).into_response(),
+        Err(status) => status.into_response(),
+    }
+}
+
+/// DELETE /mobile/api/safe-state — resume normal scheduling from the mobile app.
+async fn safe_state_resume_handler(State(state): State<MobileApiState>) -> impl IntoResponse {
+    let Some(uc_api) = &state.user_control_api_state else {
+        return user_control_unavailable();
+    };
+
+    match safe_state_api::resume(uc_api, "mobile") {
+        Ok(response) => Json(response).into_response(),
+        Err(status) => status.into_response(),
+    }
+}
+
 // ==================== Helpers ====================
 
+fn user_control_unavailable() -> axum::response::Response {
+    (
+        axum::http::StatusCode::SERVICE_UNAVAILABLE,
+        Json(serde_json::json!({"error": "User control not available"})),
+    )
+        .into_response()
+}
+
 fn parse_mobile_mode(mode: &str) -> Option<fluxion_types::InverterOperationMode> {
     use fluxion_types::InverterOperationMode;
     match mode {
@@ -244,18 +498,26 @@ fn parse_mobile_mode(mode: &str) -> Option<fluxion_types::InverterOperationMode>
     }
 }
 
-async fn build_state_json(state: &MobileApiState) -> Result<String, String> {
-    let response = build_state_response(state).await?;
+async fn build_state_json(
+    state: &MobileApiState,
+    role: Option<DeviceRole>,
+) -> Result<String, String> {
+    let response = build_state_response(state, role).await?;
     serde_json::to_string(&response).map_err(|e| e.to_string())
 }
 
-async fn build_state_response(state: &MobileApiState) -> Result<MobileStateResponse, String> {
+/// State snapshot for a device with `role` (None outside Tor, where API keys apply)
+async fn build_state_response(
+    state: &MobileApiState,
+    role: Option<DeviceRole>,
+) -> Result<MobileStateResponse, String> {
     let response = state
         .query_sender
         .query_dashboard()
         .await
         .map_err(|e| format!("Dashboard query failed: {e}"))?;
 
+    let formatter = response.time_formatter();
     let inv = response.inverters.first();
 
     let battery_soc = inv.map_or(0.0, |i| i.battery_soc);
@@ -273,6 +535,11 @@ async fn build_state_response(state: &MobileApiState) -> Result<MobileStateRespo
         || inv.map_or(String::new(), |i| i.mode_reason.clone()),
         |s| s.current_reason.clone(),
     );
+    let mode_reason_kind = response
+        .schedule
+        .as_ref()
+        .and_then(|s| s.current_decision_reason.as_ref())
+        .map(|r| r.kind().to_owned());
 
     let current_price = response.prices.as_ref().map(|p| p.current_price);
 
@@ -284,9 +551,10 @@ async fn build_state_response(state: &MobileApiState) -> Result<MobileStateRespo
             p.blocks
                 .iter()
                 .map(|b| MobileChartPoint {
-                    time: b.timestamp.format("%H:%M").to_string(),
+                    time: formatter.hour_minute(b.timestamp),
                     price: b.price,
                     mode: b.block_type.clone(),
+                    reason_kind: b.decision_reason.as_ref().map(|r| r.kind().to_owned()),
                 })
                 .collect()
         })
@@ -323,6 +591,7 @@ async fn build_state_response(state: &MobileApiState) -> Result<MobileStateRespo
         battery_soc,
         mode,
         mode_reason,
+        mode_reason_kind,
         solar_w,
         grid_w,
         load_w,
@@ -331,18 +600,206 @@ async fn build_state_response(state: &MobileApiState) -> Result<MobileStateRespo
         currency: "CZK".to_owned(),
         user_control,
         chart_data,
-        access_mode: "full".to_owned(), // TODO: derive from device auth header
-        timestamp: response.timestamp.to_rfc3339(),
+        access_mode: role.map_or("full", DeviceRole::access_mode).to_owned(),
+        role,
+        timestamp: formatter.rfc3339(response.timestamp),
+        preview: Some(mobile_preview(
+            &crate::preview::build_preview(&response, Utc::now()),
+            formatter,
+        )),
+        timezone: formatter.timezone_name().map(str::to_owned),
+        locale: Some(state.i18n.language().locale_tag().to_owned()),
+        current_price_formatted: current_price
+            .map(|price| format!("{}/kWh", state.i18n.currency(f64::from(price), 2, "CZK"))),
+        branding: Some(mobile_branding(crate::branding::current())),
     })
 }
 
+/// Schedule with every block, translated reasons and SOC series
+async fn build_schedule_response(state: &MobileApiState) -> Result<MobileScheduleResponse, String> {
+    let response = state
+        .query_sender
+        .query_dashboard()
+        .await
+        .map_err(|e| format!("Dashboard query failed: {e}"))?;
+
+    let formatter = response.time_formatter();
+    let schedule = response.schedule.as_ref();
+    let price_blocks = response
+        .prices
+        .as_ref()
+        .map_or(&[][..], |p| p.blocks.as_slice());
+
+    let blocks = price_blocks
+        .iter()
+        .enumerate()
+        .map(|(i, b)| {
+            let end = price_blocks.get(i + 1).map_or_else(
+                || b.timestamp + Duration::minutes(BLOCK_MINUTES),
+                |next| next.timestamp,
+            );
+            MobileScheduleBlock {
+                start: formatter.rfc3339(b.timestamp),
+                end: formatter.rfc3339(end),
+                price: b.price,
+                mode: b.block_type.clone(),
+                // Structured reasons are shown translated, free-form ones as the strategy wrote them
+                reason: b
+                    .decision_reason
+                    .as_ref()
+                    .and_then(|r| r.translate(&state.i18n, "CZK"))
+                    .or_else(|| b.reason.clone()),
+                reason_kind: b.decision_reason.as_ref().map(|r| r.kind().to_owned()),
+                strategy: b.strategy.clone(),
+                target_soc: b.target_soc,
+                planned_soc: b.planned_soc,
+                expected_profit: b.expected_profit,
+                is_historical: b.is_historical,
+            }
+        })
+        .collect();
+
+    let soc_history = response
+        .battery_soc_history
+        .iter()
+        .flatten()
+        .map(|p| MobileSocPoint {
+            time: formatter.rfc3339(p.timestamp),
+            soc: p.soc,
+        })
+        .collect();
+    let soc_prediction = response
+        .battery_soc_prediction
+        .iter()
+        .flatten()
+        .map(|p| MobileSocPoint {
+            time: formatter.rfc3339(p.timestamp),
+            soc: p.soc,
+        })
+        .collect();
+
+    Ok(MobileScheduleResponse {
+        api_version: API_VERSION_V2,
+        timestamp: formatter.rfc3339(response.timestamp),
+        timezone: formatter.timezone_name().map(str::to_owned),
+        currency: "CZK".to_owned(),
+        current_mode: schedule.map_or_else(String::new, |s| s.current_mode.clone()),
+        current_reason: schedule.map_or_else(String::new, |s| {
+            s.current_decision_reason
+                .as_ref()
+                .and_then(|r| r.translate(&state.i18n, "CZK"))
+                .unwrap_or_else(|| s.current_reason.clone())
+        }),
+        current_reason_kind: schedule
+            .and_then(|s| s.current_decision_reason.as_ref())
+            .map(|r| r.kind().to_owned()),
+        next_change: schedule
+            .and_then(|s| s.next_change)
+            .map(|t| formatter.rfc3339(t)),
+        target_soc_min: schedule.map(|s| s.target_soc_min),
+        target_soc_max: schedule.map(|s| s.target_soc_max),
+        blocks,
+        soc_history,
+        soc_prediction,
+    })
+}
+
+/// Page `page` (1-based) of the daily summaries `days` (oldest first), newest day first
+fn history_page(days: Vec<SavingsSummary>, page: u32, per_page: u32) -> MobileHistoryPage {
+    let page = page.max(1);
+    let per_page = per_page.clamp(1, MAX_PER_PAGE);
+    let total_days = u32::try_from(days.len()).unwrap_or(u32::MAX);
+    let skip = usize::try_from((page - 1).saturating_mul(per_page)).unwrap_or(usize::MAX);
+    let shown: Vec<MobileDailySummary> = days
+        .into_iter()
+        .rev()
+        .skip(skip)
+        .take(per_page as usize)
+        .map(|day| MobileDailySummary {
+            date: day.period_start.format("%Y-%m-%d").to_string(),
+            blocks: day.blocks,
+            expected_profit: day.expected_profit_czk,
+            realized_profit: day.realized_profit_czk,
+            actual_cost: day.actual_cost_czk,
+            baseline_cost: day.baseline_cost_czk,
+            grid_import_kwh: day.grid_import_kwh,
+            grid_export_kwh: day.grid_export_kwh,
+        })
+        .collect();
+
+    MobileHistoryPage {
+        api_version: API_VERSION_V2,
+        page,
+        per_page,
+        total_days,
+        has_more: u64::from(page) * u64::from(per_page) < u64::from(total_days),
+        currency: "CZK".to_owned(),
+        days: shown,
+    }
+}
+
+fn mobile_branding(branding: &crate::branding::BrandingConfig) -> MobileBranding {
+    MobileBranding {
+        product_name: branding.product_name().to_owned(),
+        accent_color: branding
+            .colors
+            .accent
+            .clone()
+            .filter(|c| crate::branding::is_hex_color(c)),
+    }
+}
+
+fn mobile_preview(
+    preview: &crate::preview::SchedulePreview,
+    formatter: TimeFormatter,
+) -> MobilePreview {
+    MobilePreview {
+        horizon_hours: preview.horizon_hours,
+        charge_kwh: preview.expected_charge_kwh,
+        charge_cost: preview.expected_charge_cost_czk,
+        discharge_kwh: preview.expected_discharge_kwh,
+        peak_discharge_time: preview
+            .peak_discharge
+            .as_ref()
+            .map(|p| formatter.rfc3339(p.at)),
+        peak_discharge_price: preview.peak_discharge.as_ref().map(|p| p.price_czk),
+        expected_profit: preview.expected_profit_czk,
+        soc_end: preview.soc_end,
+        actions: preview
+            .actions
+            .iter()
+            .map(|a| MobilePreviewAction {
+                start: formatter.rfc3339(a.from),
+                end: formatter.rfc3339(a.to),
+                mode: a.mode.clone(),
+            })
+            .collect(),
+    }
+}
+
 /// Build the router for mobile-facing API endpoints.
 pub fn mobile_api_routes(state: MobileApiState) -> Router {
+    let role_layer = axum::middleware::from_fn_with_state(state.clone(), require_device_role);
     Router::new()
         .route("/mobile/api/version", get(version_handler))
         .route("/mobile/api/ui", get(ui_bundle_handler))
-        .route("/mobile/api/state", get(state_handler))
+        .route(
+            "/mobile/api/state",
+            get(state_handler).layer(axum::middleware::from_fn(crate::etag::etag_middleware)),
+        )
         .route("/mobile/api/control", post(control_handler))
+        .route(
+            "/mobile/api/safe-state",
+            post(safe_state_engage_handler).delete(safe_state_resume_handler),
+        )
+        // v2: the v1 endpoints above stay unchanged for older app builds
+        .route(
+            "/mobile/api/v2/schedule",
+            get(schedule_handler).layer(axum::middleware::from_fn(crate::etag::etag_middleware)),
+        )
+        .route("/mobile/api/v2/consumption", get(consumption_handler))
+        .route("/mobile/api/v2/history", get(history_handler))
+        .layer(role_layer)
         .with_state(state)
 }
 
@@ -367,6 +824,55 @@ mod tests {
         assert_eq!(parse_mobile_mode("Invalid"), None);
     }
 
+    #[test]
+    fn test_required_role() {
+        assert_eq!(
+            required_role(&Method::GET, "/mobile/api/state"),
+            DeviceRole::Viewer
+        );
+        assert_eq!(
+            required_role(&Method::POST, "/mobile/api/control"),
+            DeviceRole::Controller
+        );
+        assert_eq!(
+            required_role(&Method::POST, "/mobile/api/safe-state"),
+            DeviceRole::Controller
+        );
+        assert_eq!(
+            required_role(&Method::DELETE, "/mobile/api/safe-state"),
+            DeviceRole::Admin
+        );
+        assert_eq!(
+            required_role(&Method::GET, "/mobile/api/v2/history"),
+            DeviceRole::Viewer
+        );
+    }
+
+    #[test]
+    fn test_device_role_from_token() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = DeviceStore::new(tmp.path());
+        let (_, _, viewer_token) = store.register_device("Shared", DeviceRole::Viewer).unwrap();
+        let (_, _, controller_token) = store
+            .register_device("Owner", DeviceRole::Controller)
+            .unwrap();
+
+        let headers = |token: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(DEVICE_TOKEN_HEADER, token.parse().unwrap());
+            headers
+        };
+        let role = |headers: &HeaderMap| paired_device(&store, headers).map(|d| d.role);
+        assert_eq!(
+            role(&headers(&controller_token)),
+            Some(DeviceRole::Controller)
+        );
+        assert_eq!(role(&headers(&viewer_token)), Some(DeviceRole::Viewer));
+        // No or unknown token: legacy pairing, view only
+        assert_eq!(role(&HeaderMap::new()), None);
+        assert_eq!(role(&headers("forged")), None);
+    }
+
     #[test]
     fn test_control_request_deserialization() {
         let json = r#"{
@@ -395,10 +901,44 @@ mod tests {
     fn test_version_response_serialization() {
         let response = VersionResponse {
             version: "0.2.35".to_owned(),
+            api_versions: SUPPORTED_API_VERSIONS.to_vec(),
         };
         let json = serde_json::to_string(&response).unwrap();
         let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed["version"], "0.2.35");
+        assert_eq!(parsed["api_versions"], serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn test_history_page() {
+        let day = |d: u32| SavingsSummary {
+            period_start: chrono::NaiveDate::from_ymd_opt(2026, 3, d).unwrap(),
+            blocks: 96,
+            expected_profit_czk: 10.0,
+            realized_profit_czk: 8.0,
+            actual_cost_czk: 20.0,
+            baseline_cost_czk: 28.0,
+            grid_import_kwh: 5.0,
+            grid_export_kwh: 1.0,
+        };
+        let days: Vec<_> = (1..=5).map(day).collect();
+
+        let first = history_page(days.clone(), 1, 2);
+        assert_eq!(first.total_days, 5);
+        assert!(first.has_more);
+        let dates: Vec<_> = first.days.iter().map(|d| d.date.as_str()).collect();
+        assert_eq!(dates, ["2026-03-05", "2026-03-04"]);
+
+        let last = history_page(days.clone(), 3, 2);
+        assert!(!last.has_more);
+        assert_eq!(last.days.len(), 1);
+        assert_eq!(last.days[0].date, "2026-03-01");
+
+        // Out of range pages are empty, page 0 and oversized pages are clamped
+        assert!(history_page(days.clone(), 4, 2).days.is_empty());
+        let clamped = history_page(days, 0, 1000);
+        assert_eq!((clamped.page, clamped.per_page), (1, MAX_PER_PAGE));
+        assert_eq!(clamped.days.len(), 5);
     }
 
     #[test]
@@ -409,6 +949,7 @@ mod tests {
             battery_soc: 72.5,
             mode: "SelfUse".to_owned(),
             mode_reason: "Solar covers load".to_owned(),
+            mode_reason_kind: None,
             solar_w: 1250.0,
             grid_w: -150.0,
             load_w: 1100.0,
@@ -424,9 +965,19 @@ mod tests {
                 time: "10:00".to_owned(),
                 price: 3.25,
                 mode: "self-use".to_owned(),
+                reason_kind: Some("normal_operation".to_owned()),
             }],
-            access_mode: "full".to_owned(),
+            access_mode: DeviceRole::Viewer.access_mode().to_owned(),
+            role: Some(DeviceRole::Viewer),
             timestamp: "2026-01-31T10:05:00Z".to_owned(),
+            preview: None,
+            timezone: None,
+            locale: Some("cs-CZ".to_owned()),
+            current_price_formatted: Some("3,25\u{a0}Kč/kWh".to_owned()),
+            branding: Some(MobileBranding {
+                product_name: "SolarBox".to_owned(),
+                accent_color: None,
+            }),
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -434,6 +985,11 @@ mod tests {
         assert_eq!(parsed["ui_version"], "0.2.35");
         assert_eq!(parsed["api_version"], 1);
         assert_eq!(parsed["battery_soc"], 72.5);
-        assert_eq!(parsed["access_mode"], "full");
+        assert_eq!(parsed["access_mode"], "readonly");
+        assert_eq!(parsed["role"], "viewer");
+        assert_eq!(parsed["locale"], "cs-CZ");
+        assert_eq!(parsed["current_price_formatted"], "3,25\u{a0}Kč/kWh");
+        assert_eq!(parsed["branding"]["product_name"], "SolarBox");
+        assert!(parsed["branding"].get("accent_color").is_none());
     }
 }
@@ -15,6 +15,7 @@ mod keygen;
 mod mobile_api;
 mod tor;
 
+pub(crate) use api::PairRequest;
 pub use api::{MobileBundleTemplate, RemoteAccessApiState, remote_access_routes};
 pub use keygen::{DeviceEntry, DeviceStore};
 pub use mobile_api::{MobileApiState, mobile_api_routes};
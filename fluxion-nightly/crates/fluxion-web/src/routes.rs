@@ -11,13 +11,31 @@
 // For commercial licensing, please contact: info@solare.cz
 
 use askama::Template;
-use chrono::Timelike;
-use chrono_tz::Tz;
-use fluxion_core::{InverterData, ScheduleData, SystemHealthData, WebQueryResponse};
+use chrono::{DateTime, DurationRound, TimeDelta, Timelike, Utc};
+use fluxion_core::{
+    InverterData, ScheduleData, SystemHealthData, Tariff, TimeFormatter, WebQueryResponse,
+};
 use fluxion_i18n::I18n;
 use fluxion_types::UserControlState;
 use std::sync::Arc;
 
+/// Price value as passed by Askama, either by value or (nested) reference
+pub trait TemplatePrice {
+    fn czk(&self) -> f64;
+}
+
+impl TemplatePrice for f32 {
+    fn czk(&self) -> f64 {
+        f64::from(*self)
+    }
+}
+
+impl<T: TemplatePrice + ?Sized> TemplatePrice for &T {
+    fn czk(&self) -> f64 {
+        (**self).czk()
+    }
+}
+
 /// Price data for Chart.js rendering
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct PriceDataWithChart {
@@ -59,6 +77,26 @@ pub struct PvHistoryPoint {
     pub power_w: f32,
 }
 
+/// Battery power history point for Chart.js (positive = charge)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BatteryPowerPoint {
+    pub label: String,
+    pub power_w: f32,
+}
+
+/// Grid import/export history point for Chart.js
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GridPowerPoint {
+    pub label: String,
+    pub import_w: f32,
+    pub export_w: f32,
+}
+
+/// Chart label of the 15-minute block a history sample was taken in
+fn block_label(formatter: TimeFormatter, time: DateTime<Utc>) -> String {
+    formatter.chart_label(time.duration_trunc(TimeDelta::minutes(15)).unwrap_or(time))
+}
+
 /// Chart data for Chart.js
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ChartData {
@@ -66,6 +104,8 @@ pub struct ChartData {
     pub prices: Vec<f32>,
     pub modes: Vec<String>,
     pub target_socs: Vec<Option<f32>>,
+    /// Planned SOC trajectory (end of each block)
+    pub planned_socs: Vec<Option<f32>>,
     pub strategies: Vec<Option<String>>,
     pub profits: Vec<Option<f32>>,
     pub current_time_label: Option<String>,
@@ -73,6 +113,8 @@ pub struct ChartData {
     pub battery_soc_prediction: Vec<SocPredictionPoint>,
     pub current_battery_soc: Option<f32>,
     pub pv_generation_history: Vec<PvHistoryPoint>,
+    pub battery_power_history: Vec<BatteryPowerPoint>,
+    pub grid_power_history: Vec<GridPowerPoint>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub debug_info: Vec<Option<fluxion_core::strategy::BlockDebugInfo>>,
     pub is_historical: Vec<bool>, // True for past blocks (shows regenerated schedule, not actual history)
@@ -121,6 +163,11 @@ impl LiveDataTemplate {
     pub fn t(&self, key: &str) -> String {
         self.i18n.get(key).unwrap_or_else(|_| key.to_owned())
     }
+
+    /// Price in the current locale, e.g. `3,25 Kč/kWh`
+    pub fn price(&self, value: impl TemplatePrice) -> String {
+        format!("{}/kWh", self.i18n.currency(value.czk(), 2, "CZK"))
+    }
 }
 
 /// Dashboard template
@@ -147,6 +194,8 @@ pub struct DashboardTemplate {
     pub solar_forecast: Option<fluxion_core::web_bridge::SolarForecastInfo>,
     /// User control state for dashboard panel
     pub user_control: Option<UserControlState>,
+    /// Read-only guest view: controls, configuration and tool links are left out
+    pub guest: bool,
 }
 
 impl DashboardTemplate {
@@ -155,6 +204,11 @@ impl DashboardTemplate {
         self.i18n.get(key).unwrap_or_else(|_| key.to_owned())
     }
 
+    /// Price in the current locale, e.g. `3,25 Kč/kWh`
+    pub fn price(&self, value: impl TemplatePrice) -> String {
+        format!("{}/kWh", self.i18n.currency(value.czk(), 2, "CZK"))
+    }
+
     /// Create template from ECS query response
     #[expect(
         clippy::too_many_lines,
@@ -166,45 +220,14 @@ impl DashboardTemplate {
         ingress_path: String,
         user_control: Option<UserControlState>,
     ) -> Self {
-        let timezone = response.timezone.clone();
-
-        // Format last update time in the correct timezone
-        let last_update_formatted = if let Some(tz_name) = &timezone {
-            if let Ok(tz) = tz_name.parse::<Tz>() {
-                response
-                    .health
-                    .last_update
-                    .with_timezone(&tz)
-                    .format("%Y-%m-%d %H:%M:%S")
-                    .to_string()
-            } else {
-                response
-                    .health
-                    .last_update
-                    .format("%Y-%m-%d %H:%M:%S")
-                    .to_string()
-            }
-        } else {
-            response
-                .health
-                .last_update
-                .format("%Y-%m-%d %H:%M:%S")
-                .to_string()
-        };
-
-        // Format next change time if available
-        let next_change_formatted = if let Some(ref schedule) = response.schedule {
-            schedule.next_change.map(|next| {
-                if let Some(tz_name) = &timezone
-                    && let Ok(tz) = tz_name.parse::<Tz>()
-                {
-                    return next.with_timezone(&tz).format("%H:%M:%S").to_string();
-                }
-                next.format("%H:%M:%S").to_string()
-            })
-        } else {
-            None
-        };
+        // All times are shown in the HA timezone
+        let formatter = response.time_formatter();
+        let last_update_formatted = formatter.date_time(response.health.last_update);
+        let next_change_formatted = response
+            .schedule
+            .as_ref()
+            .and_then(|schedule| schedule.next_change)
+            .map(|next| formatter.time(next));
 
         // Extract hourly consumption profile before response.prices moves it
         let hourly_consumption_profile = response
@@ -218,6 +241,7 @@ impl DashboardTemplate {
             let mut prices = Vec::new();
             let mut modes = Vec::new();
             let mut target_socs = Vec::new();
+            let mut planned_socs = Vec::new();
             let mut strategies = Vec::new();
             let mut profits = Vec::new();
             let mut debug_info_vec = Vec::new();
@@ -251,45 +275,27 @@ impl DashboardTemplate {
                 .and_then(|t| t.with_nanosecond(0))
                 .unwrap_or(now);
 
-            let current_time_label = if let Some(tz_name) = &timezone {
-                if let Ok(tz) = tz_name.parse::<Tz>() {
-                    Some(
-                        rounded_time
-                            .with_timezone(&tz)
-                            .format("%m-%d %H:%M")
-                            .to_string(),
-                    )
-                } else {
-                    Some(rounded_time.format("%m-%d %H:%M").to_string())
-                }
-            } else {
-                Some(rounded_time.format("%m-%d %H:%M").to_string())
-            };
+            let current_time_label = Some(formatter.chart_label(rounded_time));
 
             for block in &price_data.blocks {
-                // Format time label in the correct timezone
-                let label = if let Some(tz_name) = &timezone {
-                    if let Ok(tz) = tz_name.parse::<Tz>() {
-                        block
-                            .timestamp
-                            .with_timezone(&tz)
-                            .format("%m-%d %H:%M")
-                            .to_string()
-                    } else {
-                        block.timestamp.format("%m-%d %H:%M").to_string()
-                    }
-                } else {
-                    block.timestamp.format("%m-%d %H:%M").to_string()
-                };
+                let label = formatter.chart_label(block.timestamp);
 
                 labels.push(label);
                 prices.push(block.price);
                 target_socs.push(block.target_soc);
+                planned_socs.push(block.planned_soc);
                 strategies.push(block.strategy.clone());
                 profits.push(block.expected_profit);
                 debug_info_vec.push(block.debug_info.clone());
                 is_historical_vec.push(block.is_historical); // Track if block is past (regenerated schedule)
-                reasons.push(block.reason.clone());
+                // Structured reasons are shown translated, free-form ones as the strategy wrote them
+                reasons.push(
+                    block
+                        .decision_reason
+                        .as_ref()
+                        .and_then(|reason| reason.translate(&i18n, "CZK"))
+                        .or_else(|| block.reason.clone()),
+                );
                 decision_uids.push(block.decision_uid.clone());
 
                 // Map mode for display
@@ -306,8 +312,18 @@ impl DashboardTemplate {
                 spot_prices.push(spot_price);
 
                 // Determine if block is in low tariff period based on HDO schedule
-                let block_time_str = block.timestamp.format("%H:%M").to_string();
-                let (grid_fee, tariff_type) = if let Some(hdo) = hdo_schedule {
+                // (HDO periods are local wall-clock times)
+                let block_time_str = formatter.hour_minute(block.timestamp);
+                let (grid_fee, tariff_type) = if let (Some(hdo), Some(tariff)) =
+                    (hdo_schedule, block.tariff)
+                {
+                    // Tariff resolved from the HDO schedule on the planning side
+                    let fee = match tariff {
+                        Tariff::Low => hdo.low_tariff_czk,
+                        Tariff::High => hdo.high_tariff_czk,
+                    };
+                    (fee, tariff.to_string())
+                } else if let Some(hdo) = hdo_schedule {
                     // Log HDO info for first block only
                     if labels.len() == 1 {
                         tracing::debug!(
@@ -369,12 +385,12 @@ impl DashboardTemplate {
                             history.first().map_or(0.0, |p| p.soc),
                             history
                                 .first()
-                                .map(|p| p.timestamp.format("%H:%M").to_string())
+                                .map(|p| formatter.hour_minute(p.timestamp))
                                 .unwrap_or_default(),
                             history.last().map_or(0.0, |p| p.soc),
                             history
                                 .last()
-                                .map(|p| p.timestamp.format("%H:%M").to_string())
+                                .map(|p| formatter.hour_minute(p.timestamp))
                                 .unwrap_or_default()
                         );
                     }
@@ -382,19 +398,7 @@ impl DashboardTemplate {
                     history
                         .iter()
                         .map(|point| {
-                            let label = if let Some(tz_name) = &timezone {
-                                if let Ok(tz) = tz_name.parse::<Tz>() {
-                                    point
-                                        .timestamp
-                                        .with_timezone(&tz)
-                                        .format("%m-%d %H:%M")
-                                        .to_string()
-                                } else {
-                                    point.timestamp.format("%m-%d %H:%M").to_string()
-                                }
-                            } else {
-                                point.timestamp.format("%m-%d %H:%M").to_string()
-                            };
+                            let label = formatter.chart_label(point.timestamp);
                             SocHistoryPoint {
                                 label,
                                 soc: point.soc,
@@ -423,12 +427,12 @@ impl DashboardTemplate {
                             prediction.first().map_or(0.0, |p| p.soc),
                             prediction
                                 .first()
-                                .map(|p| p.timestamp.format("%H:%M").to_string())
+                                .map(|p| formatter.hour_minute(p.timestamp))
                                 .unwrap_or_default(),
                             prediction.last().map_or(0.0, |p| p.soc),
                             prediction
                                 .last()
-                                .map(|p| p.timestamp.format("%H:%M").to_string())
+                                .map(|p| formatter.hour_minute(p.timestamp))
                                 .unwrap_or_default()
                         );
                     }
@@ -436,19 +440,7 @@ impl DashboardTemplate {
                     prediction
                         .iter()
                         .map(|point| {
-                            let label = if let Some(tz_name) = &timezone {
-                                if let Ok(tz) = tz_name.parse::<Tz>() {
-                                    point
-                                        .timestamp
-                                        .with_timezone(&tz)
-                                        .format("%m-%d %H:%M")
-                                        .to_string()
-                                } else {
-                                    point.timestamp.format("%m-%d %H:%M").to_string()
-                                }
-                            } else {
-                                point.timestamp.format("%m-%d %H:%M").to_string()
-                            };
+                            let label = formatter.chart_label(point.timestamp);
                             SocPredictionPoint {
                                 label,
                                 soc: point.soc,
@@ -477,12 +469,12 @@ impl DashboardTemplate {
                             pv_history.first().map_or(0.0, |p| p.power_w),
                             pv_history
                                 .first()
-                                .map(|p| p.timestamp.format("%H:%M").to_string())
+                                .map(|p| formatter.hour_minute(p.timestamp))
                                 .unwrap_or_default(),
                             pv_history.last().map_or(0.0, |p| p.power_w),
                             pv_history
                                 .last()
-                                .map(|p| p.timestamp.format("%H:%M").to_string())
+                                .map(|p| formatter.hour_minute(p.timestamp))
                                 .unwrap_or_default()
                         );
                     }
@@ -490,19 +482,7 @@ impl DashboardTemplate {
                     pv_history
                         .iter()
                         .map(|point| {
-                            let label = if let Some(tz_name) = &timezone {
-                                if let Ok(tz) = tz_name.parse::<Tz>() {
-                                    point
-                                        .timestamp
-                                        .with_timezone(&tz)
-                                        .format("%m-%d %H:%M")
-                                        .to_string()
-                                } else {
-                                    point.timestamp.format("%m-%d %H:%M").to_string()
-                                }
-                            } else {
-                                point.timestamp.format("%m-%d %H:%M").to_string()
-                            };
+                            let label = formatter.chart_label(point.timestamp);
                             PvHistoryPoint {
                                 label,
                                 power_w: point.power_w,
@@ -512,6 +492,27 @@ impl DashboardTemplate {
                 },
             );
 
+            // Power flows, keyed by the block they were sampled in
+            let battery_power_history = response
+                .battery_power_history
+                .iter()
+                .flatten()
+                .map(|point| BatteryPowerPoint {
+                    label: block_label(formatter, point.timestamp),
+                    power_w: point.power_w,
+                })
+                .collect();
+            let grid_power_history = response
+                .grid_power_history
+                .iter()
+                .flatten()
+                .map(|point| GridPowerPoint {
+                    label: block_label(formatter, point.timestamp),
+                    import_w: point.import_w,
+                    export_w: point.export_w,
+                })
+                .collect();
+
             PriceDataWithChart {
                 current_price: price_data.current_price,
                 min_price: price_data.min_price,
@@ -530,6 +531,7 @@ impl DashboardTemplate {
                     prices,
                     modes,
                     target_socs,
+                    planned_socs,
                     strategies,
                     profits,
                     current_time_label,
@@ -537,6 +539,8 @@ impl DashboardTemplate {
                     battery_soc_prediction,
                     current_battery_soc,
                     pv_generation_history,
+                    battery_power_history,
+                    grid_power_history,
                     debug_info: debug_info_vec,
                     is_historical: is_historical_vec,
                     reasons,
@@ -573,6 +577,7 @@ impl DashboardTemplate {
             consumption_stats: response.consumption_stats,
             solar_forecast: response.solar_forecast,
             user_control,
+            guest: false,
         }
     }
 }
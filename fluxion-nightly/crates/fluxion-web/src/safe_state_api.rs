@@ -0,0 +1,291 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Emergency safe state API.
+//!
+//! Provides endpoints for:
+//! - Engaging the safe state (inverters forced to the configured safe mode)
+//! - Resuming normal scheduling
+//! - Querying who engaged the safe state and when
+//!
+//! The safe state is stored in the user control state, so it survives restarts
+//! and is only cleared by an explicit resume.
+
+use axum::{
+    Json,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use chrono::Utc;
+use fluxion_core::UserControlChangeType;
+use fluxion_types::SafeStateActivation;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::user_control_api::{UserControlApiState, parse_operation_mode, persist_and_notify};
+use utoipa::ToSchema;
+
+/// Headers set by Home Assistant ingress identifying the logged-in user
+const HA_USER_HEADERS: [&str; 2] = ["X-Remote-User-Display-Name", "X-Remote-User-Name"];
+
+/// Request for POST /api/system/safe-state
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct EngageSafeStateRequest {
+    /// Optional mode override ("SelfUse", "BackUpMode", "NoChargeNoDischarge").
+    /// When absent, the configured `control.safe_state_mode` is used.
+    #[serde(default)]
+    pub mode: Option<String>,
+    /// Optional reason shown in the dashboard and logs
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Optional explicit caller name (falls back to HA user headers)
+    #[serde(default)]
+    pub triggered_by: Option<String>,
+}
+
+/// Response for safe state endpoints
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SafeStateResponse {
+    pub active: bool,
+    /// Requested mode override; `None` means the configured safe mode
+    pub mode: Option<String>,
+    pub triggered_by: Option<String>,
+    pub triggered_at: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl From<Option<&SafeStateActivation>> for SafeStateResponse {
+    fn from(activation: Option<&SafeStateActivation>) -> Self {
+        Self {
+            active: activation.is_some(),
+            mode: activation.and_then(|a| a.mode).map(|m| format!("{m:?}")),
+            triggered_by: activation.map(|a| a.triggered_by.clone()),
+            triggered_at: activation.map(|a| a.triggered_at.to_rfc3339()),
+            reason: activation.and_then(|a| a.reason.clone()),
+        }
+    }
+}
+
+// ==================== GET /api/system/safe-state ====================
+
+/// GET /api/system/safe-state - Get current safe state
+pub async fn get_safe_state(State(state): State<UserControlApiState>) -> Json<SafeStateResponse> {
+    let user_state = state.state.read();
+    Json(SafeStateResponse::from(user_state.safe_state.as_ref()))
+}
+
+// ==================== POST /api/system/safe-state ====================
+
+/// POST /api/system/safe-state - Engage the emergency safe state
+pub async fn engage_safe_state(
+    State(state): State<UserControlApiState>,
+    headers: HeaderMap,
+    body: Option<Json<EngageSafeStateRequest>>,
+) -> Result<Json<SafeStateResponse>, StatusCode> {
+    let request = body.map(|Json(r)| r).unwrap_or_default();
+    let triggered_by = request
+        .triggered_by
+        .clone()
+        .filter(|s| !s.trim().is_empty())
+        .or_else(|| caller_from_headers(&headers))
+        .unwrap_or_else(|| "web".to_owned());
+
+    engage(&state, &request, triggered_by).map(Json)
+}
+
+// ==================== DELETE /api/system/safe-state ====================
+
+/// DELETE /api/system/safe-state - Resume normal scheduling
+pub async fn resume_safe_state(
+    State(state): State<UserControlApiState>,
+    headers: HeaderMap,
+) -> Result<Json<SafeStateResponse>, StatusCode> {
+    let resumed_by = caller_from_headers(&headers).unwrap_or_else(|| "web".to_owned());
+    resume(&state, &resumed_by).map(Json)
+}
+
+// ==================== Shared logic ====================
+
+/// Engage the safe state, persist it and notify the ECS.
+///
+/// Engaging while already active keeps the original activation record so the
+/// first trigger is not lost.
+pub(crate) fn engage(
+    state: &UserControlApiState,
+    request: &EngageSafeStateRequest,
+    triggered_by: String,
+) -> Result<SafeStateResponse, StatusCode> {
+    let mode = match request.mode.as_deref() {
+        None | Some("") => None,
+        Some(mode_str) => match parse_operation_mode(mode_str) {
+            // Force charge/discharge are never a "safe" state
+            Some(
+                m @ (fluxion_types::InverterOperationMode::SelfUse
+                | fluxion_types::InverterOperationMode::BackUpMode
+                | fluxion_types::InverterOperationMode::NoChargeNoDischarge),
+            ) => Some(m),
+            Some(
+                fluxion_types::InverterOperationMode::ForceCharge
+                | fluxion_types::InverterOperationMode::ForceDischarge,
+            )
+            | None => return Err(StatusCode::BAD_REQUEST),
+        },
+    };
+
+    let new_state = {
+        let mut user_state = state.state.write();
+        if let Some(existing) = &user_state.safe_state {
+            info!(
+                "🛑 Safe state already engaged by {} at {}",
+                existing.triggered_by,
+                existing.triggered_at.to_rfc3339()
+            );
+            return Ok(SafeStateResponse::from(Some(existing)));
+        }
+        user_state.safe_state = Some(SafeStateActivation::new(
+            triggered_by,
+            mode,
+            request.reason.clone(),
+        ));
+        user_state.last_modified = Some(Utc::now());
+        user_state.clone()
+    };
+
+    if let Some(activation) = &new_state.safe_state {
+        warn!(
+            "🛑 SAFE STATE ENGAGED by {} at {} (mode: {}, reason: {})",
+            activation.triggered_by,
+            activation.triggered_at.to_rfc3339(),
+            activation
+                .mode
+                .map_or_else(|| "configured".to_owned(), |m| format!("{m:?}")),
+            activation.reason.as_deref().unwrap_or("-")
+        );
+    }
+
+    persist_and_notify(state, &new_state, UserControlChangeType::SafeStateChanged)?;
+
+    Ok(SafeStateResponse::from(new_state.safe_state.as_ref()))
+}
+
+/// Clear the safe state, persist it and notify the ECS.
+pub(crate) fn resume(
+    state: &UserControlApiState,
+    resumed_by: &str,
+) -> Result<SafeStateResponse, StatusCode> {
+    let (previous, new_state) = {
+        let mut user_state = state.state.write();
+        let Some(previous) = user_state.safe_state.take() else {
+            return Ok(SafeStateResponse::from(None));
+        };
+        user_state.last_modified = Some(Utc::now());
+        (previous, user_state.clone())
+    };
+
+    warn!(
+        "▶️ SAFE STATE RESUMED by {} (was engaged by {} for {} min)",
+        resumed_by,
+        previous.triggered_by,
+        (Utc::now() - previous.triggered_at).num_minutes()
+    );
+
+    persist_and_notify(state, &new_state, UserControlChangeType::SafeStateChanged)?;
+
+    Ok(SafeStateResponse::from(None))
+}
+
+/// Identify the caller from Home Assistant ingress user headers
+pub(crate) fn caller_from_headers(headers: &HeaderMap) -> Option<String> {
+    HA_USER_HEADERS.iter().find_map(|name| {
+        headers
+            .get(*name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(ToOwned::to_owned)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_types::{InverterOperationMode, UserControlState};
+
+    fn test_state(dir: &tempfile::TempDir) -> UserControlApiState {
+        UserControlApiState::new(
+            UserControlState::default(),
+            dir.path().join("user_control.json").to_string_lossy(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_engage_and_resume() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(&dir);
+
+        let request = EngageSafeStateRequest {
+            reason: Some("Electrician".to_owned()),
+            ..Default::default()
+        };
+        let response = engage(&state, &request, "alice".to_owned()).unwrap();
+        assert!(response.active);
+        assert_eq!(response.triggered_by.as_deref(), Some("alice"));
+        assert!(response.mode.is_none());
+        assert!(state.state.read().is_safe_state_active());
+
+        // Second engage keeps the original trigger
+        let response = engage(&state, &request, "bob".to_owned()).unwrap();
+        assert_eq!(response.triggered_by.as_deref(), Some("alice"));
+
+        let response = resume(&state, "alice").unwrap();
+        assert!(!response.active);
+        assert!(!state.state.read().is_safe_state_active());
+    }
+
+    #[test]
+    fn test_engage_rejects_force_modes() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = test_state(&dir);
+
+        let request = EngageSafeStateRequest {
+            mode: Some("ForceCharge".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            engage(&state, &request, "alice".to_owned()).unwrap_err(),
+            StatusCode::BAD_REQUEST
+        );
+
+        let request = EngageSafeStateRequest {
+            mode: Some("SelfUse".to_owned()),
+            ..Default::default()
+        };
+        let response = engage(&state, &request, "alice".to_owned()).unwrap();
+        assert_eq!(
+            response.mode,
+            Some(format!("{:?}", InverterOperationMode::SelfUse))
+        );
+    }
+
+    #[test]
+    fn test_caller_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(caller_from_headers(&headers).is_none());
+
+        headers.insert("X-Remote-User-Name", "jan".parse().unwrap());
+        assert_eq!(caller_from_headers(&headers).as_deref(), Some("jan"));
+
+        headers.insert("X-Remote-User-Display-Name", "Jan Novak".parse().unwrap());
+        assert_eq!(caller_from_headers(&headers).as_deref(), Some("Jan Novak"));
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Realized versus expected profit report.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::TimeFormatter;
+use fluxion_core::savings::{SavingsLedger, SavingsPeriod, SavingsSummary, summarize, total};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+/// State of the savings report
+#[derive(Clone, Debug)]
+pub struct SavingsState {
+    pub ledger: SavingsLedger,
+    /// Periods are cut on the local clock of the HA timezone
+    pub time_formatter: TimeFormatter,
+}
+
+/// Query parameters of `/api/savings` (RFC 3339 timestamps)
+#[derive(Debug, Deserialize)]
+pub struct SavingsQuery {
+    #[serde(default)]
+    pub period: SavingsPeriod,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SavingsResponse {
+    pub period: SavingsPeriod,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// IANA timezone the periods are cut in
+    pub timezone: Option<String>,
+    /// Sum over the whole range, if any block was accounted
+    pub total: Option<SavingsSummary>,
+    pub periods: Vec<SavingsSummary>,
+}
+
+/// Range covered when `from` is not given
+fn default_range(period: SavingsPeriod) -> Duration {
+    match period {
+        SavingsPeriod::Day => Duration::days(30),
+        SavingsPeriod::Week => Duration::weeks(12),
+        SavingsPeriod::Month => Duration::days(365),
+    }
+}
+
+/// GET /api/savings?period=day|week|month&from=&to= — expected and realized profit per period
+pub async fn savings_handler(
+    State(state): State<SavingsState>,
+    Query(query): Query<SavingsQuery>,
+) -> Response {
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - default_range(query.period));
+    if from >= to {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "`from` must be before `to`" })),
+        )
+            .into_response();
+    }
+
+    // SQLite calls block
+    let ledger = state.ledger.clone();
+    let blocks = match tokio::task::spawn_blocking(move || ledger.query(from, to)).await {
+        Ok(Ok(blocks)) => blocks,
+        Ok(Err(e)) => {
+            error!("Failed to query savings ledger: {e:#}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+        Err(e) => {
+            error!("Savings ledger query failed: {e}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    Json(SavingsResponse {
+        period: query.period,
+        from,
+        to,
+        timezone: state.time_formatter.timezone_name().map(str::to_owned),
+        total: total(&blocks, &state.time_formatter),
+        periods: summarize(&blocks, query.period, &state.time_formatter),
+    })
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn state() -> SavingsState {
+        SavingsState {
+            ledger: SavingsLedger::open_in_memory().unwrap(),
+            time_formatter: TimeFormatter::from_timezone_name(Some("Europe/Prague")),
+        }
+    }
+
+    fn query(period: SavingsPeriod, from: &str, to: &str) -> Query<SavingsQuery> {
+        Query(SavingsQuery {
+            period,
+            from: Some(from.parse().unwrap()),
+            to: Some(to.parse().unwrap()),
+        })
+    }
+
+    fn block(block_start: &str) -> fluxion_core::savings::BlockSavings {
+        fluxion_core::savings::BlockSavings {
+            block_start: block_start.parse().unwrap(),
+            duration_minutes: 15,
+            expected_profit_czk: Some(1.0),
+            import_price_czk_per_kwh: 4.0,
+            export_price_czk_per_kwh: 1.0,
+            solar_kwh: 0.0,
+            consumption_kwh: 0.5,
+            grid_import_kwh: 0.0,
+            grid_export_kwh: 0.0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recorded_blocks_are_summed_per_period() {
+        let state = state();
+        state.ledger.record(&block("2025-06-01T08:00:00Z")).unwrap();
+        state.ledger.record(&block("2025-06-02T08:00:00Z")).unwrap();
+        state.ledger.record(&block("2025-06-02T09:00:00Z")).unwrap();
+        // Outside of the requested range
+        state.ledger.record(&block("2025-06-05T08:00:00Z")).unwrap();
+
+        let response = savings_handler(
+            State(state),
+            query(
+                SavingsPeriod::Day,
+                "2025-06-01T00:00:00Z",
+                "2025-06-03T00:00:00Z",
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let report = body(response).await;
+        assert_eq!(report["timezone"], "Europe/Prague");
+        assert_eq!(report["total"]["blocks"], 3);
+        let periods = report["periods"].as_array().unwrap();
+        assert_eq!(periods.len(), 2);
+        assert_eq!(periods[1]["period_start"], "2025-06-02");
+        assert_eq!(periods[1]["blocks"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_empty_ledger_reports_no_total() {
+        let response = savings_handler(
+            State(state()),
+            query(
+                SavingsPeriod::Month,
+                "2025-01-01T00:00:00Z",
+                "2025-06-01T00:00:00Z",
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let report = body(response).await;
+        assert!(report["total"].is_null());
+        assert_eq!(report["periods"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_inverted_range_is_rejected() {
+        let response = savings_handler(
+            State(state()),
+            query(
+                SavingsPeriod::Day,
+                "2025-06-02T00:00:00Z",
+                "2025-06-01T00:00:00Z",
+            ),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(body(response).await["error"], "`from` must be before `to`");
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Installer self-test of the control path.
+
+use axum::{Json, extract::State};
+use fluxion_core::self_test::{SelfTest, SelfTestReport};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::ConfigApiState;
+
+/// State for the self-test endpoint
+#[derive(Clone)]
+pub struct SelfTestState {
+    self_test: Arc<SelfTest>,
+    /// Debug mode decides whether the inverter write is real
+    config: ConfigApiState,
+}
+
+impl SelfTestState {
+    #[must_use]
+    pub fn new(self_test: SelfTest, config: ConfigApiState) -> Self {
+        Self {
+            self_test: Arc::new(self_test),
+            config,
+        }
+    }
+}
+
+impl std::fmt::Debug for SelfTestState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SelfTestState")
+            .field("self_test", &self.self_test)
+            .finish_non_exhaustive()
+    }
+}
+
+/// POST /api/system/self-test — read, no-op write, HA round-trip and price checks
+pub async fn self_test_handler(State(state): State<SelfTestState>) -> Json<SelfTestReport> {
+    let report = state.self_test.run(state.config.debug_mode()).await;
+    if report.passed {
+        info!("✅ Self-test passed ({} checks)", report.checks.len());
+    } else {
+        warn!(
+            "❌ Self-test failed: {}",
+            report
+                .checks
+                .iter()
+                .filter(|c| c.status == fluxion_core::self_test::CheckStatus::Fail)
+                .map(|c| c.name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    Json(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Result;
+    use async_trait::async_trait;
+    use chrono::{Duration, Utc};
+    use fluxion_core::self_test::CheckStatus;
+    use fluxion_core::{
+        GenericInverterState, InverterCommand, InverterDataSource, PriceDataSource,
+    };
+    use fluxion_i18n::{I18n, Language};
+    use fluxion_types::pricing::{SpotPriceData, TimeBlockPrice};
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[derive(Default)]
+    struct TestInverter {
+        writes: AtomicU32,
+        down: bool,
+    }
+
+    #[async_trait]
+    impl InverterDataSource for TestInverter {
+        async fn read_state(&self, inverter_id: &str) -> Result<GenericInverterState> {
+            anyhow::ensure!(!self.down, "Home Assistant unreachable");
+            Ok(GenericInverterState {
+                inverter_id: inverter_id.to_owned(),
+                battery_soc: 60.0,
+                ..GenericInverterState::default()
+            })
+        }
+
+        async fn write_command(&self, _: &str, _: &InverterCommand) -> Result<()> {
+            self.writes.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(!self.down)
+        }
+
+        fn name(&self) -> &'static str {
+            "test"
+        }
+    }
+
+    struct TestPrices;
+
+    #[async_trait]
+    impl PriceDataSource for TestPrices {
+        async fn read_prices(&self) -> Result<SpotPriceData> {
+            let start = Utc::now() - Duration::minutes(20);
+            Ok(SpotPriceData {
+                time_block_prices: (0..8)
+                    .map(|i| TimeBlockPrice {
+                        block_start: start + Duration::minutes(15 * i),
+                        duration_minutes: 15,
+                        price_czk_per_kwh: 2.0,
+                        effective_price_czk_per_kwh: 2.0,
+                        spot_sell_price_czk_per_kwh: None,
+                    })
+                    .collect(),
+                ..SpotPriceData::default()
+            })
+        }
+
+        async fn health_check(&self) -> Result<bool> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &'static str {
+            "test"
+        }
+    }
+
+    fn state(inverter: Arc<TestInverter>, debug_mode: bool) -> SelfTestState {
+        SelfTestState::new(
+            SelfTest::new(inverter, Arc::new(TestPrices), vec!["main".to_owned()]),
+            ConfigApiState::new(
+                serde_json::json!({ "system": { "debug_mode": debug_mode } }),
+                "config.json",
+                None,
+                Arc::new(I18n::new(Language::English).unwrap()),
+            ),
+        )
+    }
+
+    fn status(report: &SelfTestReport, name: &str) -> CheckStatus {
+        report
+            .checks
+            .iter()
+            .find(|c| c.name == name)
+            .map(|c| c.status)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_live_mode_writes_to_the_inverter() {
+        let inverter = Arc::new(TestInverter::default());
+
+        let Json(report) = self_test_handler(State(state(inverter.clone(), false))).await;
+
+        assert!(report.passed);
+        assert!(!report.debug_mode);
+        assert_eq!(status(&report, "inverter_write"), CheckStatus::Pass);
+        assert_eq!(inverter.writes.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn test_debug_mode_is_taken_from_the_config() {
+        let inverter = Arc::new(TestInverter::default());
+
+        let Json(report) = self_test_handler(State(state(inverter.clone(), true))).await;
+
+        assert!(report.debug_mode);
+        assert_eq!(status(&report, "inverter_write"), CheckStatus::Skipped);
+        assert_eq!(inverter.writes.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_inverter_fails_the_report() {
+        let inverter = Arc::new(TestInverter {
+            down: true,
+            ..TestInverter::default()
+        });
+
+        let Json(report) = self_test_handler(State(state(inverter, false))).await;
+
+        assert!(!report.passed);
+        assert_eq!(status(&report, "inverter_read"), CheckStatus::Fail);
+    }
+}
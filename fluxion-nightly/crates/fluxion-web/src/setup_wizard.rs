@@ -0,0 +1,165 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! First-run setup wizard.
+//!
+//! On first start the hardware is read from Home Assistant in the background
+//! and turned into a [`SetupProposal`]. The `/setup` page shows the proposal
+//! next to the configured values; accepting it goes through the regular
+//! `/api/config/update` endpoint, after which the wizard is marked complete.
+//!
+//! A pending wizard is recorded by a marker file in the data directory, so it
+//! is offered again after a restart until the user accepts or skips it.
+
+use askama::Template;
+use axum::{
+    Json, Router,
+    extract::State,
+    http::HeaderMap,
+    response::{Html, IntoResponse},
+    routing::{get, post},
+};
+use fluxion_core::setup_defaults::SetupProposal;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+/// Marker file in the data directory present while the wizard is pending
+pub const SETUP_PENDING_FILE: &str = "setup_pending";
+
+/// Shared state of the setup wizard
+#[derive(Clone, Debug)]
+pub struct SetupWizardState {
+    proposal: Arc<RwLock<Option<SetupProposal>>>,
+    pending: Arc<AtomicBool>,
+    marker: PathBuf,
+}
+
+impl SetupWizardState {
+    /// Create the wizard state from the marker in `data_dir`
+    ///
+    /// On `first_run` the marker is created; afterwards the wizard stays
+    /// pending until it is completed, across restarts.
+    #[must_use]
+    pub fn new(data_dir: &Path, first_run: bool) -> Self {
+        let marker = data_dir.join(SETUP_PENDING_FILE);
+        if first_run
+            && let Err(e) =
+                std::fs::create_dir_all(data_dir).and_then(|()| std::fs::write(&marker, b""))
+        {
+            warn!(
+                "⚠️ Failed to record pending setup wizard in {}: {e}",
+                marker.display()
+            );
+        }
+        Self {
+            proposal: Arc::new(RwLock::new(None)),
+            pending: Arc::new(AtomicBool::new(first_run || marker.exists())),
+            marker,
+        }
+    }
+
+    /// Store the proposal once hardware detection has finished
+    pub fn set_proposal(&self, proposal: SetupProposal) {
+        *self.proposal.write() = Some(proposal);
+    }
+
+    /// True until the user accepts or skips the wizard
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed)
+    }
+}
+
+/// Response of `GET /api/setup/proposal`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SetupStatusResponse {
+    pub pending: bool,
+    /// `None` while hardware detection is still running
+    #[schema(value_type = Option<Object>)]
+    pub proposal: Option<SetupProposal>,
+}
+
+/// GET /api/setup/proposal — wizard status and proposed defaults
+async fn proposal_handler(State(state): State<SetupWizardState>) -> Json<SetupStatusResponse> {
+    Json(SetupStatusResponse {
+        pending: state.is_pending(),
+        proposal: state.proposal.read().clone(),
+    })
+}
+
+/// POST /api/setup/complete — stop offering the wizard (accepted or skipped)
+async fn complete_handler(State(state): State<SetupWizardState>) -> Json<SetupStatusResponse> {
+    if state.pending.swap(false, Ordering::Relaxed) {
+        info!("🧙 Setup wizard completed");
+    }
+    match tokio::fs::remove_file(&state.marker).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => warn!(
+            "⚠️ Failed to clear setup wizard marker {}: {e}",
+            state.marker.display()
+        ),
+    }
+    proposal_handler(State(state)).await
+}
+
+#[derive(Template)]
+#[template(path = "setup.html")]
+struct SetupPageTemplate {
+    ingress_path: String,
+}
+
+/// GET /setup — wizard page
+async fn page_handler(headers: HeaderMap) -> impl IntoResponse {
+    let ingress_path = crate::extract_ingress_path(&headers);
+    let template = SetupPageTemplate { ingress_path };
+    match template.render() {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => {
+            error!("Template render error: {e}");
+            Html(format!("<h1>Error</h1><p>{e}</p>")).into_response()
+        }
+    }
+}
+
+/// Build the router for the setup wizard.
+pub fn setup_wizard_routes(state: SetupWizardState) -> Router {
+    Router::new()
+        .route("/setup", get(page_handler))
+        .route("/api/setup/proposal", get(proposal_handler))
+        .route("/api/setup/complete", post(complete_handler))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_pending_survives_restart_until_completed() {
+        let tmp = tempfile::tempdir().unwrap();
+
+        assert!(SetupWizardState::new(tmp.path(), true).is_pending());
+        // Restarted before the user finished the wizard
+        let restarted = SetupWizardState::new(tmp.path(), false);
+        assert!(restarted.is_pending());
+
+        let Json(status) = complete_handler(State(restarted)).await;
+        assert!(!status.pending);
+        assert!(!SetupWizardState::new(tmp.path(), false).is_pending());
+    }
+}
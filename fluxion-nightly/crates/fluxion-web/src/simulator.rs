@@ -16,17 +16,30 @@ use axum::{
     response::{Html, IntoResponse},
 };
 use fluxion_strategy_simulator::{
-    ConsumptionProfile, PRICE_PRESETS, PriceScenario, SimulationConfig, SimulationEngine,
-    SimulationState, SocOverride, StrategyInfo, SyntheticDayConfig,
-    state::SimulationResultsSummary, strategies::StrategySelection,
+    APPLIANCE_PRESET_IDS, ApplianceProfile, ConsumptionProfile, PRICE_PRESETS, PriceScenario,
+    SimulationConfig, SimulationEngine, SimulationState, SocOverride, StrategyInfo,
+    SyntheticDayConfig, state::SimulationResultsSummary, strategies::StrategySelection,
 };
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
-use tracing::{error, info};
+use tokio::sync::Semaphore;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::simulator_runs::{RunStore, SavedRunSummary};
+
+/// Maximum number of saved runs returned by the listing endpoint
+const SAVED_RUNS_LIST_LIMIT: usize = 100;
+
+/// Maximum number of combinations accepted by one batch request
+const BATCH_MAX_COMBINATIONS: usize = 64;
+
+/// Default and maximum number of batch combinations simulated at once
+const BATCH_MAX_CONCURRENCY: usize = 4;
+
 /// State for simulator API handlers
 #[derive(Clone)]
 pub struct SimulatorState {
@@ -34,12 +47,15 @@ pub struct SimulatorState {
     simulations: Arc<RwLock<HashMap<Uuid, SimulationState>>>,
     /// Simulation engine
     engine: Arc<SimulationEngine>,
+    /// Saved run history (None if the database could not be opened)
+    runs: Option<Arc<RunStore>>,
 }
 
 impl std::fmt::Debug for SimulatorState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("SimulatorState")
             .field("simulations_count", &self.simulations.read().len())
+            .field("run_history", &self.runs.is_some())
             .finish_non_exhaustive()
     }
 }
@@ -51,9 +67,29 @@ impl SimulatorState {
         Self {
             simulations: Arc::new(RwLock::new(HashMap::new())),
             engine: Arc::new(SimulationEngine::new()),
+            runs: None,
         }
     }
 
+    /// Enable saved run history in `<data_dir>/simulator_runs.db`
+    ///
+    /// Falls back to an in-memory history if the database cannot be opened.
+    #[must_use]
+    pub fn with_run_store(mut self, data_dir: &std::path::Path) -> Self {
+        let store = RunStore::open(data_dir).or_else(|e| {
+            warn!(
+                "⚠️ Failed to open simulator run history in {}, runs are kept until restart: {e}",
+                data_dir.display()
+            );
+            RunStore::open_in_memory()
+        });
+        match store {
+            Ok(store) => self.runs = Some(Arc::new(store)),
+            Err(e) => error!("Failed to create simulator run history: {e}"),
+        }
+        self
+    }
+
     /// Clean up old simulations (call periodically)
     pub fn cleanup_old_simulations(&self, max_age_secs: i64) {
         let now = chrono::Utc::now();
@@ -75,6 +111,8 @@ impl Default for SimulatorState {
 #[template(path = "simulator.html")]
 pub struct SimulatorTemplate {
     pub ingress_path: String,
+    /// Saved run to open on page load (empty for a new simulation)
+    pub run_id: String,
 }
 
 /// Extract ingress path from request headers
@@ -89,9 +127,28 @@ fn extract_ingress_path(headers: &axum::http::HeaderMap) -> String {
 /// GET /simulator
 /// Simulator page handler
 pub async fn simulator_page_handler(headers: axum::http::HeaderMap) -> impl IntoResponse {
-    let ingress_path = extract_ingress_path(&headers);
+    render_simulator_page(&headers, String::new())
+}
+
+/// GET /simulator/runs/{run_id}
+/// Simulator page opening a saved run (shareable link)
+pub async fn saved_run_page_handler(
+    headers: axum::http::HeaderMap,
+    Path(run_id): Path<Uuid>,
+) -> impl IntoResponse {
+    render_simulator_page(&headers, run_id.to_string())
+}
 
-    let template = SimulatorTemplate { ingress_path };
+fn render_simulator_page(
+    headers: &axum::http::HeaderMap,
+    run_id: String,
+) -> axum::response::Response {
+    let ingress_path = extract_ingress_path(headers);
+
+    let template = SimulatorTemplate {
+        ingress_path,
+        run_id,
+    };
 
     match template.render() {
         Ok(html) => Html(html).into_response(),
@@ -113,6 +170,7 @@ pub struct PresetsResponse {
     pub consumption_profiles: Vec<PresetInfo>,
     pub price_scenarios: Vec<PresetInfo>,
     pub strategies: Vec<StrategyInfo>,
+    pub appliances: Vec<PresetInfo>,
 }
 
 /// Preset information
@@ -124,7 +182,7 @@ pub struct PresetInfo {
 }
 
 /// Create simulation request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateSimulationRequest {
     /// Date for simulation (optional, defaults to today)
     pub date: Option<chrono::NaiveDate>,
@@ -140,11 +198,76 @@ pub struct CreateSimulationRequest {
     pub battery_capacity_kwh: Option<f32>,
     /// Strategies to enable
     pub strategies: Option<Vec<String>>,
+    /// Appliance preset IDs to add to consumption (optional)
+    /// Valid values: "heat_pump", "ev_overnight", "boiler_hdo"
+    pub appliances: Option<Vec<String>>,
+    /// Imported appliance profiles to add to consumption (optional)
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub custom_appliances: Option<Vec<ApplianceProfile>>,
+    /// Grid export limit in kW (optional, defaults to unlimited)
+    pub export_limit_kw: Option<f32>,
+    /// Inverter AC output limit in kW (optional, defaults to unlimited)
+    pub inverter_ac_limit_kw: Option<f32>,
+    /// User-control restrictions and fixed slots to replay (optional)
+    #[schema(value_type = Option<Object>)]
+    pub user_control: Option<fluxion_types::UserControlState>,
     /// Include baselines (deprecated - use explicit strategy selection instead)
     #[expect(dead_code)]
     pub include_baselines: Option<bool>,
 }
 
+/// One (scenario, consumption profile, strategy set) combination of a batch
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct BatchCombination {
+    /// Price scenario ID (optional, defaults to "usual_day")
+    pub price_scenario: Option<String>,
+    /// Consumption profile ID (optional, defaults to "peak_based")
+    pub consumption_profile: Option<String>,
+    /// Strategies to compare (optional, defaults to "v4_global")
+    pub strategies: Option<Vec<String>>,
+}
+
+/// Batch run request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct BatchRunRequest {
+    /// Date for all simulations (optional, defaults to today)
+    pub date: Option<chrono::NaiveDate>,
+    /// Initial SOC (optional, defaults to 50%)
+    pub initial_soc: Option<f32>,
+    /// Battery capacity (optional, defaults to 10 kWh)
+    pub battery_capacity_kwh: Option<f32>,
+    /// Combinations to simulate, one matrix row each
+    pub combinations: Vec<BatchCombination>,
+    /// Simulations run at the same time (optional, capped at 4)
+    pub concurrency: Option<usize>,
+}
+
+/// Comparison matrix returned by a batch run
+#[derive(Debug, Serialize)]
+pub struct BatchRunResponse {
+    /// Strategy IDs appearing in any row (matrix columns)
+    pub strategies: Vec<String>,
+    /// One row per requested combination, in request order
+    pub rows: Vec<BatchRow>,
+    /// Number of rows each strategy had the lowest net cost in
+    pub wins: BTreeMap<String, usize>,
+}
+
+/// Result of one batch combination
+#[derive(Debug, Serialize)]
+pub struct BatchRow {
+    pub price_scenario: String,
+    pub consumption_profile: String,
+    /// Net cost per strategy ID (CZK)
+    pub net_cost_czk: BTreeMap<String, f32>,
+    /// Strategy with the lowest net cost
+    pub best_strategy: Option<String>,
+    /// Full results summary (None if the simulation failed)
+    pub results: Option<SimulationResultsSummary>,
+    /// Why the simulation failed
+    pub error: Option<String>,
+}
+
 /// Convert consumption profile string ID to enum
 fn parse_consumption_profile(id: &str) -> ConsumptionProfile {
     match id {
@@ -183,14 +306,29 @@ fn map_strategy_id(frontend_id: &str) -> String {
     }
 }
 /// Step simulation request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct StepRequest {
     /// Number of blocks to step (defaults to 1)
     pub blocks: Option<usize>,
 }
 
+/// Save run request
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct SaveRunRequest {
+    /// Run name (optional, defaults to "<scenario> <date>")
+    pub name: Option<String>,
+}
+
+/// Response for an opened saved run
+#[derive(Debug, Serialize)]
+pub struct OpenRunResponse {
+    pub run: SavedRunSummary,
+    /// Live simulation session restored from the run
+    pub simulation: SimulationSnapshot,
+}
+
 /// SOC override request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct SocOverrideRequest {
     /// Block index (optional, defaults to current)
     pub block_index: Option<usize>,
@@ -201,7 +339,7 @@ pub struct SocOverrideRequest {
 }
 
 /// Load override request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct LoadOverrideRequest {
     /// Block index (optional, defaults to current block)
     pub block_index: Option<usize>,
@@ -210,7 +348,7 @@ pub struct LoadOverrideRequest {
 }
 
 /// Price override request
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct PriceOverrideRequest {
     /// Block index (optional, defaults to current block)
     pub block_index: Option<usize>,
@@ -362,13 +500,76 @@ pub async fn presets_handler(State(state): State<SimulatorState>) -> impl IntoRe
 
     let strategies: Vec<StrategyInfo> = state.engine.registry().list_strategies().to_vec();
 
+    let appliances: Vec<PresetInfo> = APPLIANCE_PRESET_IDS
+        .iter()
+        .filter_map(|id| {
+            ApplianceProfile::preset(id).map(|profile| PresetInfo {
+                id: (*id).to_owned(),
+                description: format!("{:.1} kWh/day", profile.total_daily_consumption_kwh()),
+                name: profile.name,
+            })
+        })
+        .collect();
+
     Json(PresetsResponse {
         consumption_profiles,
         price_scenarios,
         strategies,
+        appliances,
     })
 }
 
+/// Build sim config - map frontend strategy IDs to backend IDs
+///
+/// Defaults to the V4 strategy when no strategies are selected.
+fn strategy_config(
+    selected_strategies: Option<Vec<String>>,
+    battery_capacity_kwh: f32,
+) -> SimulationConfig {
+    let selected_strategies = selected_strategies.unwrap_or_else(|| vec!["v4_global".to_owned()]);
+
+    // Check if baselines are explicitly selected
+    let include_naive = selected_strategies.iter().any(|s| s == "naive");
+    let include_no_battery = selected_strategies.iter().any(|s| s == "no_battery");
+
+    // Filter out baseline IDs and map the rest to backend IDs
+    let strategies: Vec<StrategySelection> = selected_strategies
+        .into_iter()
+        .filter(|id| id != "naive" && id != "no_battery")
+        .map(|id| StrategySelection {
+            strategy_id: map_strategy_id(&id),
+            enabled: true,
+            config_overrides: None,
+        })
+        .collect();
+
+    SimulationConfig {
+        strategies,
+        include_no_battery,
+        include_naive,
+        battery_capacity_kwh,
+        ..SimulationConfig::default()
+    }
+}
+
+/// Presets first, then any imported profiles, rejecting unknown presets and
+/// out-of-range profiles like the CLI does
+fn resolve_appliances(
+    preset_ids: &[String],
+    custom: Vec<ApplianceProfile>,
+) -> Result<Vec<ApplianceProfile>, String> {
+    let mut appliances = preset_ids
+        .iter()
+        .map(|id| ApplianceProfile::require_preset(id))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    for appliance in custom {
+        appliance.validate().map_err(|e| format!("{e:#}"))?;
+        appliances.push(appliance);
+    }
+    Ok(appliances)
+}
+
 /// POST /api/simulator/create
 /// Create a new simulation session
 pub async fn create_simulation_handler(
@@ -389,6 +590,21 @@ pub async fn create_simulation_handler(
         .as_deref()
         .map_or(PriceScenario::UsualDay, parse_price_scenario);
 
+    let appliances = match resolve_appliances(
+        &request.appliances.unwrap_or_default(),
+        request.custom_appliances.unwrap_or_default(),
+    ) {
+        Ok(appliances) => appliances,
+        Err(e) => {
+            warn!("Rejected simulation appliances: {}", e);
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": e })),
+            )
+                .into_response();
+        }
+    };
+
     // Build day config
     let day_config = SyntheticDayConfig {
         date: request
@@ -402,34 +618,15 @@ pub async fn create_simulation_handler(
         hdo_periods: None,
         hdo_low_tariff_czk: 0.50,
         hdo_high_tariff_czk: 1.80,
+        appliances,
+        intra_block: None,
     };
 
-    // Build sim config - map frontend strategy IDs to backend IDs
-    let selected_strategies = request
-        .strategies
-        .unwrap_or_else(|| vec!["v4_global".to_owned()]);
-
-    // Check if baselines are explicitly selected
-    let include_naive = selected_strategies.iter().any(|s| s == "naive");
-    let include_no_battery = selected_strategies.iter().any(|s| s == "no_battery");
-
-    // Filter out baseline IDs and map the rest to backend IDs
-    let strategies: Vec<StrategySelection> = selected_strategies
-        .into_iter()
-        .filter(|id| id != "naive" && id != "no_battery")
-        .map(|id| StrategySelection {
-            strategy_id: map_strategy_id(&id),
-            enabled: true,
-            config_overrides: None,
-        })
-        .collect();
-
     let sim_config = SimulationConfig {
-        strategies,
-        include_no_battery,
-        include_naive,
-        battery_capacity_kwh: day_config.battery_capacity_kwh,
-        ..SimulationConfig::default()
+        export_limit_kw: request.export_limit_kw.filter(|kw| *kw > 0.0),
+        inverter_ac_limit_kw: request.inverter_ac_limit_kw.filter(|kw| *kw > 0.0),
+        user_control: request.user_control,
+        ..strategy_config(request.strategies, day_config.battery_capacity_kwh)
     };
 
     // Create simulation
@@ -455,6 +652,167 @@ pub async fn create_simulation_handler(
     }
 }
 
+/// Shared day settings of a batch request
+#[derive(Debug, Clone, Copy)]
+struct BatchDay {
+    date: chrono::NaiveDate,
+    initial_soc: f32,
+    battery_capacity_kwh: f32,
+}
+
+/// Simulate one batch combination to completion
+fn run_batch_combination(
+    engine: &SimulationEngine,
+    day: BatchDay,
+    combination: BatchCombination,
+) -> BatchRow {
+    let price_scenario = combination
+        .price_scenario
+        .unwrap_or_else(|| "usual_day".to_owned());
+    let consumption_profile = combination
+        .consumption_profile
+        .unwrap_or_else(|| "peak_based".to_owned());
+
+    let day_config = SyntheticDayConfig {
+        date: day.date,
+        consumption: parse_consumption_profile(&consumption_profile),
+        solar: fluxion_strategy_simulator::SolarProfile::None,
+        price_scenario: parse_price_scenario(&price_scenario),
+        initial_soc: day.initial_soc,
+        battery_capacity_kwh: day.battery_capacity_kwh,
+        hdo_periods: None,
+        hdo_low_tariff_czk: 0.50,
+        hdo_high_tariff_czk: 1.80,
+        appliances: Vec::new(),
+        intra_block: None,
+    };
+    let sim_config = strategy_config(combination.strategies, day.battery_capacity_kwh);
+
+    let result = engine
+        .create_simulation(day_config, sim_config)
+        .and_then(|mut simulation| {
+            engine.run_to_completion(&mut simulation)?;
+            Ok(SimulationResultsSummary::from_state(&simulation))
+        });
+
+    let mut row = BatchRow {
+        price_scenario,
+        consumption_profile,
+        net_cost_czk: BTreeMap::new(),
+        best_strategy: None,
+        results: None,
+        error: None,
+    };
+    match result {
+        Ok(summary) => {
+            row.net_cost_czk = summary
+                .strategies
+                .iter()
+                .map(|s| (s.strategy_id.clone(), s.net_cost_czk))
+                .collect();
+            row.best_strategy = summary.ranking.first().cloned();
+            row.results = Some(summary);
+        }
+        Err(e) => row.error = Some(e.to_string()),
+    }
+    row
+}
+
+/// Run all combinations, at most `concurrency` at a time
+pub(crate) async fn run_batch(
+    engine: Arc<SimulationEngine>,
+    request: BatchRunRequest,
+) -> BatchRunResponse {
+    let day = BatchDay {
+        date: request
+            .date
+            .unwrap_or_else(|| chrono::Utc::now().date_naive()),
+        initial_soc: request.initial_soc.unwrap_or(50.0),
+        battery_capacity_kwh: request.battery_capacity_kwh.unwrap_or(10.0),
+    };
+    let concurrency = request
+        .concurrency
+        .unwrap_or(BATCH_MAX_CONCURRENCY)
+        .clamp(1, BATCH_MAX_CONCURRENCY);
+    let limit = Arc::new(Semaphore::new(concurrency));
+
+    let mut tasks = Vec::with_capacity(request.combinations.len());
+    for combination in request.combinations {
+        let engine = engine.clone();
+        let limit = limit.clone();
+        let label = (
+            combination.price_scenario.clone().unwrap_or_default(),
+            combination.consumption_profile.clone().unwrap_or_default(),
+        );
+        let task = tokio::spawn(async move {
+            let _permit = limit.acquire_owned().await;
+            tokio::task::spawn_blocking(move || run_batch_combination(&engine, day, combination))
+                .await
+        });
+        tasks.push((label, task));
+    }
+
+    let mut rows = Vec::with_capacity(tasks.len());
+    for ((price_scenario, consumption_profile), task) in tasks {
+        let row = match task.await {
+            Ok(Ok(row)) => row,
+            Ok(Err(e)) | Err(e) => BatchRow {
+                price_scenario,
+                consumption_profile,
+                net_cost_czk: BTreeMap::new(),
+                best_strategy: None,
+                results: None,
+                error: Some(format!("Simulation task failed: {e}")),
+            },
+        };
+        rows.push(row);
+    }
+
+    let mut strategies: Vec<String> = Vec::new();
+    let mut wins = BTreeMap::new();
+    for row in &rows {
+        for id in row.net_cost_czk.keys() {
+            if !strategies.contains(id) {
+                strategies.push(id.clone());
+            }
+        }
+        if let Some(best) = &row.best_strategy {
+            *wins.entry(best.clone()).or_insert(0) += 1;
+        }
+    }
+
+    BatchRunResponse {
+        strategies,
+        rows,
+        wins,
+    }
+}
+
+/// POST /api/simulator/batch
+/// Run a matrix of scenario, consumption profile and strategy set combinations
+pub async fn batch_handler(
+    State(state): State<SimulatorState>,
+    Json(request): Json<BatchRunRequest>,
+) -> impl IntoResponse {
+    if request.combinations.is_empty() || request.combinations.len() > BATCH_MAX_COMBINATIONS {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!(
+                    "Batch must contain between 1 and {BATCH_MAX_COMBINATIONS} combinations"
+                )
+            })),
+        )
+            .into_response();
+    }
+
+    info!(
+        "Running simulator batch of {} combinations",
+        request.combinations.len()
+    );
+    Json(run_batch(state.engine.clone(), request).await).into_response()
+}
+
 /// GET /api/simulator/{id}
 /// Get current simulation state
 pub async fn get_simulation_handler(
@@ -636,6 +994,132 @@ pub async fn delete_handler(
     }
 }
 
+fn run_history_unavailable() -> axum::response::Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        "Simulator run history is not available",
+    )
+        .into_response()
+}
+
+/// POST /api/simulator/{id}/save
+/// Save simulation inputs and results to the run history
+pub async fn save_run_handler(
+    State(state): State<SimulatorState>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<SaveRunRequest>,
+) -> impl IntoResponse {
+    let Some(runs) = state.runs.clone() else {
+        return run_history_unavailable();
+    };
+
+    // Copy the session out so the SQLite write does not hold the lock
+    let Some(simulation) = state.simulations.read().get(&id).cloned() else {
+        return (StatusCode::NOT_FOUND, "Simulation not found").into_response();
+    };
+
+    let name = request
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map_or_else(
+            || {
+                format!(
+                    "{} {}",
+                    simulation.day.price_scenario_name, simulation.day.date
+                )
+            },
+            ToOwned::to_owned,
+        );
+
+    match runs
+        .run_blocking(move |runs| runs.save(&name, &simulation))
+        .await
+    {
+        Ok(run) => {
+            info!("Saved simulation {} as run {} ('{}')", id, run.id, run.name);
+            Json(run).into_response()
+        }
+        Err(e) => {
+            error!("Failed to save simulator run: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to save run").into_response()
+        }
+    }
+}
+
+/// GET /api/simulator/runs
+/// List saved runs, newest first
+pub async fn list_runs_handler(State(state): State<SimulatorState>) -> impl IntoResponse {
+    let Some(runs) = state.runs.clone() else {
+        return run_history_unavailable();
+    };
+
+    match runs
+        .run_blocking(|runs| runs.list(SAVED_RUNS_LIST_LIMIT))
+        .await
+    {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => {
+            error!("Failed to list simulator runs: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to list runs").into_response()
+        }
+    }
+}
+
+/// POST /api/simulator/runs/{run_id}/open
+/// Restore a saved run into a new live simulation session
+pub async fn open_run_handler(
+    State(state): State<SimulatorState>,
+    Path(run_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let Some(runs) = state.runs.clone() else {
+        return run_history_unavailable();
+    };
+
+    let (run, mut simulation) = match runs.run_blocking(move |runs| runs.load(run_id)).await {
+        Ok(Some(loaded)) => loaded,
+        Ok(None) => return (StatusCode::NOT_FOUND, "Run not found").into_response(),
+        Err(e) => {
+            error!("Failed to load simulator run {}: {}", run_id, e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to load run").into_response();
+        }
+    };
+
+    // Every visitor of a shared link gets an own session to play with
+    simulation.id = Uuid::new_v4();
+    simulation.last_updated = chrono::Utc::now();
+
+    let snapshot = SimulationSnapshot::from_state(&simulation);
+    state.simulations.write().insert(simulation.id, simulation);
+
+    Json(OpenRunResponse {
+        run,
+        simulation: snapshot,
+    })
+    .into_response()
+}
+
+/// DELETE /api/simulator/runs/{run_id}
+/// Delete a saved run
+pub async fn delete_run_handler(
+    State(state): State<SimulatorState>,
+    Path(run_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let Some(runs) = state.runs.clone() else {
+        return run_history_unavailable();
+    };
+
+    match runs.run_blocking(move |runs| runs.delete(run_id)).await {
+        Ok(true) => Json(serde_json::json!({"success": true})).into_response(),
+        Ok(false) => (StatusCode::NOT_FOUND, "Run not found").into_response(),
+        Err(e) => {
+            error!("Failed to delete simulator run {}: {}", run_id, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete run").into_response()
+        }
+    }
+}
+
 /// GET /api/simulator/blocks/{id}/{block}
 /// Get detailed info for a specific block
 #[expect(clippy::integer_division)]
@@ -687,3 +1171,40 @@ pub async fn block_detail_handler(
         (StatusCode::NOT_FOUND, "Simulation not found").into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_batch_returns_matrix_in_request_order() {
+        let request = BatchRunRequest {
+            date: chrono::NaiveDate::from_ymd_opt(2025, 1, 15),
+            initial_soc: None,
+            battery_capacity_kwh: None,
+            combinations: vec![
+                BatchCombination {
+                    price_scenario: Some("volatile".to_owned()),
+                    consumption_profile: Some("constant".to_owned()),
+                    strategies: Some(vec!["naive".to_owned(), "no_battery".to_owned()]),
+                },
+                BatchCombination {
+                    strategies: Some(vec!["no_battery".to_owned()]),
+                    ..BatchCombination::default()
+                },
+            ],
+            concurrency: Some(1),
+        };
+
+        let response = run_batch(Arc::new(SimulationEngine::new()), request).await;
+
+        assert_eq!(response.rows.len(), 2);
+        assert_eq!(response.rows[0].price_scenario, "volatile");
+        assert_eq!(response.rows[1].price_scenario, "usual_day");
+        assert!(response.rows.iter().all(|row| row.error.is_none()));
+        assert_eq!(response.rows[0].net_cost_czk.len(), 2);
+        assert_eq!(response.rows[1].net_cost_czk.len(), 1);
+        assert_eq!(response.strategies.len(), 2);
+        assert_eq!(response.wins.values().sum::<usize>(), 2);
+    }
+}
@@ -0,0 +1,306 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+
+//! Persistent history of strategy simulator runs.
+//!
+//! A saved run stores the complete [`SimulationState`] (synthetic day,
+//! configuration, overrides and per-strategy results) in SQLite, so it can be
+//! reopened later from a shareable `/simulator/runs/{id}` link.
+
+use chrono::{DateTime, Utc};
+use fluxion_strategy_simulator::SimulationState;
+use parking_lot::Mutex;
+use rusqlite::{OptionalExtension, params};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// File name of the run database inside the data directory
+pub const RUNS_DB_FILE: &str = "simulator_runs.db";
+
+/// Listing entry of a saved run (without the full state)
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedRunSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// Simulated date (YYYY-MM-DD)
+    pub date: String,
+    pub price_scenario: String,
+    /// Block the run had reached when saved (96 = complete)
+    pub current_block: usize,
+    /// Strategy with the lowest net cost
+    pub best_strategy: Option<String>,
+    pub best_net_cost_czk: Option<f32>,
+}
+
+/// Why a run store operation failed
+#[derive(Debug)]
+pub enum RunStoreError {
+    /// The data directory could not be created
+    Io(std::io::Error),
+    /// SQLite query or connection failure
+    Database(rusqlite::Error),
+    /// A simulation could not be (de)serialized
+    State(serde_json::Error),
+    /// The blocking task running the query panicked or was cancelled
+    Task(tokio::task::JoinError),
+}
+
+impl std::fmt::Display for RunStoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "data directory: {e}"),
+            Self::Database(e) => write!(f, "database: {e}"),
+            Self::State(e) => write!(f, "simulation state: {e}"),
+            Self::Task(e) => write!(f, "run store task: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RunStoreError {}
+
+impl From<std::io::Error> for RunStoreError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<rusqlite::Error> for RunStoreError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Database(e)
+    }
+}
+
+impl From<serde_json::Error> for RunStoreError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::State(e)
+    }
+}
+
+impl From<tokio::task::JoinError> for RunStoreError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        Self::Task(e)
+    }
+}
+
+/// SQLite store of saved simulator runs
+#[derive(Debug)]
+pub struct RunStore {
+    conn: Mutex<rusqlite::Connection>,
+}
+
+impl RunStore {
+    /// Open (or create) the store at `<data_dir>/simulator_runs.db`
+    pub fn open(data_dir: &Path) -> Result<Self, RunStoreError> {
+        std::fs::create_dir_all(data_dir)?;
+        Self::init(rusqlite::Connection::open(data_dir.join(RUNS_DB_FILE))?)
+    }
+
+    /// Open an in-memory store (nothing survives a restart)
+    pub fn open_in_memory() -> Result<Self, RunStoreError> {
+        Self::init(rusqlite::Connection::open_in_memory()?)
+    }
+
+    fn init(conn: rusqlite::Connection) -> Result<Self, RunStoreError> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS simulator_runs (
+                id                 TEXT PRIMARY KEY,
+                name               TEXT NOT NULL,
+                created_at         TEXT NOT NULL,
+                date               TEXT NOT NULL,
+                price_scenario     TEXT NOT NULL,
+                current_block      INTEGER NOT NULL,
+                best_strategy      TEXT,
+                best_net_cost_czk  REAL,
+                state_json         TEXT NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_simulator_runs_created
+                ON simulator_runs(created_at DESC);",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Run `f` against the store on the blocking thread pool
+    ///
+    /// SQLite calls block, so async handlers must not make them directly.
+    pub async fn run_blocking<T, F>(self: &Arc<Self>, f: F) -> Result<T, RunStoreError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Self) -> Result<T, RunStoreError> + Send + 'static,
+    {
+        let store = Arc::clone(self);
+        tokio::task::spawn_blocking(move || f(&store)).await?
+    }
+
+    /// Save a simulation under a new run ID
+    pub fn save(
+        &self,
+        name: &str,
+        simulation: &SimulationState,
+    ) -> Result<SavedRunSummary, RunStoreError> {
+        let state_json = serde_json::to_string(simulation)?;
+
+        let best = simulation
+            .ranked_strategies()
+            .first()
+            .map(|(_, result)| (result.strategy_name.clone(), result.net_cost_czk));
+
+        let summary = SavedRunSummary {
+            id: Uuid::new_v4(),
+            name: name.to_owned(),
+            created_at: Utc::now(),
+            date: simulation.day.date.to_string(),
+            price_scenario: simulation.day.price_scenario_name.clone(),
+            current_block: simulation.current_block,
+            best_strategy: best.as_ref().map(|(name, _)| name.clone()),
+            best_net_cost_czk: best.map(|(_, cost)| cost),
+        };
+
+        self.conn.lock().execute(
+            "INSERT INTO simulator_runs
+                (id, name, created_at, date, price_scenario, current_block,
+                 best_strategy, best_net_cost_czk, state_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                summary.id.to_string(),
+                summary.name,
+                summary.created_at,
+                summary.date,
+                summary.price_scenario,
+                summary.current_block,
+                summary.best_strategy,
+                summary.best_net_cost_czk,
+                state_json,
+            ],
+        )?;
+
+        Ok(summary)
+    }
+
+    /// Most recent runs first
+    pub fn list(&self, limit: usize) -> Result<Vec<SavedRunSummary>, RunStoreError> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, created_at, date, price_scenario, current_block,
+                    best_strategy, best_net_cost_czk
+             FROM simulator_runs
+             ORDER BY created_at DESC
+             LIMIT ?1",
+        )?;
+        let runs = stmt
+            .query_map(params![limit], row_to_summary)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(runs)
+    }
+
+    /// Load a saved run with its full simulation state
+    pub fn load(
+        &self,
+        id: Uuid,
+    ) -> Result<Option<(SavedRunSummary, SimulationState)>, RunStoreError> {
+        let conn = self.conn.lock();
+        let row = conn
+            .query_row(
+                "SELECT id, name, created_at, date, price_scenario, current_block,
+                        best_strategy, best_net_cost_czk, state_json
+                 FROM simulator_runs
+                 WHERE id = ?1",
+                params![id.to_string()],
+                |row| Ok((row_to_summary(row)?, row.get::<_, String>(8)?)),
+            )
+            .optional()?;
+
+        let Some((summary, state_json)) = row else {
+            return Ok(None);
+        };
+        Ok(Some((summary, serde_json::from_str(&state_json)?)))
+    }
+
+    /// Delete a run. Returns `false` if no run has the given ID.
+    pub fn delete(&self, id: Uuid) -> Result<bool, RunStoreError> {
+        let deleted = self.conn.lock().execute(
+            "DELETE FROM simulator_runs WHERE id = ?1",
+            params![id.to_string()],
+        )?;
+        Ok(deleted > 0)
+    }
+}
+
+fn row_to_summary(row: &rusqlite::Row<'_>) -> rusqlite::Result<SavedRunSummary> {
+    let id: String = row.get(0)?;
+    Ok(SavedRunSummary {
+        id: Uuid::parse_str(&id).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        name: row.get(1)?,
+        created_at: row.get(2)?,
+        date: row.get(3)?,
+        price_scenario: row.get(4)?,
+        current_block: row.get(5)?,
+        best_strategy: row.get(6)?,
+        best_net_cost_czk: row.get(7)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_strategy_simulator::{SimulationConfig, SimulationEngine, SyntheticDayConfig};
+
+    fn completed_simulation() -> SimulationState {
+        let engine = SimulationEngine::new();
+        let mut simulation = engine
+            .create_simulation(SyntheticDayConfig::default(), SimulationConfig::default())
+            .unwrap();
+        engine.run_to_completion(&mut simulation).unwrap();
+        simulation
+    }
+
+    #[test]
+    fn test_save_and_load_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let simulation = completed_simulation();
+
+        let saved = {
+            let store = RunStore::open(tmp.path()).unwrap();
+            store.save("Volatile winter day", &simulation).unwrap()
+        };
+        assert_eq!(saved.current_block, 96);
+        assert!(saved.best_strategy.is_some());
+
+        // Reopen to make sure the run survives a restart
+        let store = RunStore::open(tmp.path()).unwrap();
+        let (summary, loaded) = store.load(saved.id).unwrap().unwrap();
+
+        assert_eq!(summary.name, "Volatile winter day");
+        assert_eq!(loaded.current_block, simulation.current_block);
+        assert_eq!(
+            loaded.strategy_results.len(),
+            simulation.strategy_results.len()
+        );
+        assert!(store.load(Uuid::new_v4()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_list_newest_first_and_delete() {
+        let store = RunStore::open_in_memory().unwrap();
+        let simulation = completed_simulation();
+
+        let first = store.save("first", &simulation).unwrap();
+        let second = store.save("second", &simulation).unwrap();
+
+        let runs = store.list(10).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].id, second.id);
+
+        assert!(store.delete(first.id).unwrap());
+        assert!(!store.delete(first.id).unwrap());
+        assert_eq!(store.list(10).unwrap().len(), 1);
+    }
+}
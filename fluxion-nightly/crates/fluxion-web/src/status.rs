@@ -0,0 +1,181 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Machine-readable status for external uptime monitors.
+//!
+//! `/status.json` is shaped for UptimeKuma's "HTTP(s) - Json Query" monitor
+//! (query `status`, expected value `up`). FluxION counts as up only while it
+//! keeps planning: the schedule must have been regenerated recently and both
+//! the inverter and price sources must be healthy.
+
+use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::metrics::Metrics;
+use fluxion_core::{SystemHealthData, WebQueryResponse};
+use serde::Serialize;
+use tracing::error;
+
+use crate::AppState;
+
+/// A schedule older than this means the planning loop has stalled.
+///
+/// The schedule is regenerated with every price refresh (a few minutes).
+pub const MAX_SCHEDULE_AGE_MINUTES: i64 = 30;
+
+/// Status of the planning loop as reported to uptime monitors
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusReport {
+    /// `"up"` or `"down"`
+    pub status: &'static str,
+    /// A recent schedule exists
+    pub planning: bool,
+    pub inverter_source: bool,
+    pub price_source: bool,
+    pub schedule_generated_at: Option<DateTime<Utc>>,
+    pub schedule_age_seconds: Option<i64>,
+    pub current_mode: Option<String>,
+    /// Duration of the latest schedule generation
+    pub schedule_generation_ms: Option<u64>,
+    /// Number of active system errors (details are on the dashboard)
+    pub error_count: usize,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl StatusReport {
+    /// Build the report from a dashboard snapshot taken at `now`
+    #[must_use]
+    pub fn from_dashboard(response: &WebQueryResponse, now: DateTime<Utc>) -> Self {
+        let schedule = response.schedule.as_ref();
+        Self::new(
+            schedule.map(|s| s.schedule_generated_at),
+            schedule.map(|s| s.current_mode.clone()),
+            &response.health,
+            now,
+        )
+    }
+
+    /// Report used when the core could not be queried at all
+    #[must_use]
+    pub fn unreachable(now: DateTime<Utc>) -> Self {
+        Self {
+            status: "down",
+            planning: false,
+            inverter_source: false,
+            price_source: false,
+            schedule_generated_at: None,
+            schedule_age_seconds: None,
+            current_mode: None,
+            schedule_generation_ms: None,
+            error_count: 0,
+            checked_at: now,
+        }
+    }
+
+    fn new(
+        schedule_generated_at: Option<DateTime<Utc>>,
+        current_mode: Option<String>,
+        health: &SystemHealthData,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let age = schedule_generated_at.map(|at| now - at);
+        let planning = age.is_some_and(|age| age <= Duration::minutes(MAX_SCHEDULE_AGE_MINUTES));
+        let up = planning && health.inverter_source && health.price_source;
+
+        Self {
+            status: if up { "up" } else { "down" },
+            planning,
+            inverter_source: health.inverter_source,
+            price_source: health.price_source,
+            schedule_generated_at,
+            schedule_age_seconds: age.map(|age| age.num_seconds()),
+            current_mode,
+            schedule_generation_ms: None,
+            error_count: health.errors.len(),
+            checked_at: now,
+        }
+    }
+
+    /// True when FluxION is planning and its data sources are healthy
+    #[must_use]
+    pub fn is_up(&self) -> bool {
+        self.status == "up"
+    }
+}
+
+/// GET /status.json — planning status for uptime monitors
+///
+/// Answers 503 while down so plain HTTP monitors alert as well.
+pub async fn status_json_handler(State(app_state): State<AppState>) -> impl IntoResponse {
+    let now = Utc::now();
+    let mut report = match app_state.query_sender.query_dashboard().await {
+        Ok(response) => StatusReport::from_dashboard(&response, now),
+        Err(e) => {
+            error!("Failed to query dashboard data for status: {e}");
+            StatusReport::unreachable(now)
+        }
+    };
+    report.schedule_generation_ms = Metrics::global()
+        .last_schedule_generation()
+        .and_then(|elapsed| u64::try_from(elapsed.as_millis()).ok());
+    let status = if report.is_up() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn health(inverter_source: bool, price_source: bool) -> SystemHealthData {
+        SystemHealthData {
+            inverter_source,
+            price_source,
+            last_update: Utc::now(),
+            errors: vec!["price fetch slow".to_owned()],
+        }
+    }
+
+    #[test]
+    fn test_recent_schedule_with_healthy_sources_is_up() {
+        let now = Utc::now();
+        let report = StatusReport::new(
+            Some(now - Duration::minutes(4)),
+            Some("SelfUse".to_owned()),
+            &health(true, true),
+            now,
+        );
+
+        assert!(report.is_up());
+        assert_eq!(report.schedule_age_seconds, Some(240));
+        assert_eq!(report.error_count, 1);
+    }
+
+    #[test]
+    fn test_stale_schedule_or_failed_source_is_down() {
+        let now = Utc::now();
+        let stale = Some(now - Duration::minutes(MAX_SCHEDULE_AGE_MINUTES + 1));
+        let recent = Some(now - Duration::minutes(1));
+
+        let report = StatusReport::new(stale, None, &health(true, true), now);
+        assert!(!report.is_up());
+        assert!(!report.planning);
+
+        assert!(!StatusReport::new(recent, None, &health(true, false), now).is_up());
+        assert!(!StatusReport::new(None, None, &health(true, true), now).is_up());
+
+        let json = serde_json::to_value(StatusReport::unreachable(now)).unwrap();
+        assert_eq!(json["status"], "down");
+    }
+}
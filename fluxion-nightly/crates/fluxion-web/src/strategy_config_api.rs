@@ -0,0 +1,294 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Per-strategy settings for the built-in strategies.
+//!
+//! Lists every entry of the `strategies` config section with its enabled
+//! flag, priority and tuning parameters, and updates one strategy at a time.
+//! Updates go through the same validation and persistence as
+//! `/api/config/update`, so the running plugin manager picks them up without
+//! a restart.
+
+use axum::Json;
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::config_api::{ConfigApiState, UpdateConfigResponse, ValidateResponse, ValidationIssue};
+use crate::{config_preview, validation};
+use utoipa::ToSchema;
+
+/// Highest priority a strategy can have
+const MAX_PRIORITY: u8 = 100;
+
+/// Settings of one built-in strategy
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StrategySettings {
+    /// Key under `strategies` (e.g. "winter_adaptive_v10")
+    pub key: String,
+    /// `None` for sections without an on/off switch (e.g. "seasonal")
+    pub enabled: Option<bool>,
+    /// `None` for sections that don't take part in conflict resolution
+    pub priority: Option<u8>,
+    /// Every other setting of the strategy
+    #[schema(value_type = Object)]
+    pub parameters: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Request body for PUT /api/config/strategies/{key}
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct StrategyUpdateRequest {
+    pub enabled: Option<bool>,
+    /// Priority for conflict resolution (0-100, higher wins)
+    pub priority: Option<u8>,
+    /// Parameters to change; only existing keys of the strategy are accepted
+    #[serde(default)]
+    #[schema(value_type = Object)]
+    pub parameters: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Settings of every strategy in the config, sorted by key
+pub fn strategy_settings(config: &serde_json::Value) -> Vec<StrategySettings> {
+    let Some(strategies) = config
+        .get("strategies")
+        .and_then(serde_json::Value::as_object)
+    else {
+        return Vec::new();
+    };
+    let mut settings: Vec<StrategySettings> = strategies
+        .iter()
+        .filter_map(|(key, value)| {
+            let mut parameters = value.as_object()?.clone();
+            let enabled = parameters.remove("enabled").and_then(|v| v.as_bool());
+            let priority = parameters
+                .remove("priority")
+                .and_then(|v| v.as_u64())
+                .and_then(|p| u8::try_from(p).ok());
+            Some(StrategySettings {
+                key: key.clone(),
+                enabled,
+                priority,
+                parameters,
+            })
+        })
+        .collect();
+    settings.sort_by(|a, b| a.key.cmp(&b.key));
+    settings
+}
+
+/// Partial config that applies `request` to the strategy `key`
+///
+/// Rejects unknown strategies and settings the strategy doesn't have, so a
+/// typo can't silently add a key that nothing reads.
+pub fn strategy_patch(
+    config: &serde_json::Value,
+    key: &str,
+    request: StrategyUpdateRequest,
+) -> Result<serde_json::Value, ValidationIssue> {
+    let issue = |field: String, message: String| ValidationIssue {
+        field,
+        message,
+        severity: "error".to_owned(),
+    };
+    let Some(current) = config
+        .get("strategies")
+        .and_then(|s| s.get(key))
+        .and_then(serde_json::Value::as_object)
+    else {
+        return Err(issue(
+            format!("strategies.{key}"),
+            format!("Unknown strategy '{key}'"),
+        ));
+    };
+
+    let mut patch = request.parameters;
+    if let Some(enabled) = request.enabled {
+        patch.insert("enabled".to_owned(), enabled.into());
+    }
+    if let Some(priority) = request.priority {
+        if priority > MAX_PRIORITY {
+            return Err(issue(
+                format!("strategies.{key}.priority"),
+                format!("Priority must be between 0 and {MAX_PRIORITY}"),
+            ));
+        }
+        patch.insert("priority".to_owned(), priority.into());
+    }
+    if let Some(unknown) = patch.keys().find(|name| !current.contains_key(*name)) {
+        return Err(issue(
+            format!("strategies.{key}.{unknown}"),
+            format!("Strategy '{key}' has no setting '{unknown}'"),
+        ));
+    }
+
+    Ok(serde_json::json!({ "strategies": { key: patch } }))
+}
+
+fn rejected(validation: ValidateResponse, error: &str) -> UpdateConfigResponse {
+    UpdateConfigResponse {
+        success: false,
+        validation,
+        backup_id: None,
+        applied: false,
+        restart_required: false,
+        error: Some(error.to_owned()),
+    }
+}
+
+/// GET /api/config/strategies - Settings of every built-in strategy
+pub async fn list_strategies_handler(
+    State(state): State<ConfigApiState>,
+) -> Json<Vec<StrategySettings>> {
+    Json(strategy_settings(&state.config.read()))
+}
+
+/// PUT /api/config/strategies/{key} - Enable, disable, reprioritize or tune one strategy
+pub async fn update_strategy_handler(
+    State(state): State<ConfigApiState>,
+    Path(key): Path<String>,
+    Json(request): Json<StrategyUpdateRequest>,
+) -> (StatusCode, Json<UpdateConfigResponse>) {
+    let mut current_config = state.config.write();
+
+    let patch = match strategy_patch(&current_config, &key, request) {
+        Ok(patch) => patch,
+        Err(issue) => {
+            let status = if issue.field == format!("strategies.{key}") {
+                StatusCode::NOT_FOUND
+            } else {
+                StatusCode::BAD_REQUEST
+            };
+            let error = issue.message.clone();
+            let validation = ValidateResponse {
+                valid: false,
+                errors: vec![issue],
+                warnings: Vec::new(),
+                restart_required: false,
+            };
+            return (status, Json(rejected(validation, &error)));
+        }
+    };
+
+    let preview = config_preview::preview_config(&current_config, patch.clone());
+    if !preview.validation.valid {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(rejected(
+                preview.validation,
+                "Configuration validation failed",
+            )),
+        );
+    }
+
+    info!(
+        "🎛️ Updating strategy '{key}' ({} changed settings)",
+        preview.changes.len()
+    );
+    validation::merge_json(&mut current_config, patch);
+    state.persist_and_notify(&current_config);
+
+    (
+        StatusCode::OK,
+        Json(UpdateConfigResponse {
+            success: true,
+            validation: preview.validation,
+            backup_id: None,
+            applied: true,
+            restart_required: preview.restart_required,
+            error: None,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config() -> serde_json::Value {
+        json!({
+            "strategies": {
+                "winter_adaptive_v10": { "enabled": true, "priority": 100, "target_battery_soc": 95.0 },
+                "fixed_price_arbitrage": { "enabled": false, "priority": 85, "min_profit_threshold_czk": 3.0 },
+                "seasonal": { "force_season": null }
+            }
+        })
+    }
+
+    #[test]
+    fn test_settings_split_switches_from_parameters() {
+        let settings = strategy_settings(&config());
+
+        let keys: Vec<&str> = settings.iter().map(|s| s.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec!["fixed_price_arbitrage", "seasonal", "winter_adaptive_v10"]
+        );
+        assert_eq!(settings[0].enabled, Some(false));
+        assert_eq!(settings[0].priority, Some(85));
+        assert_eq!(
+            settings[0].parameters.get("min_profit_threshold_czk"),
+            Some(&json!(3.0))
+        );
+        assert_eq!(settings[1].enabled, None);
+        assert_eq!(settings[1].priority, None);
+    }
+
+    #[test]
+    fn test_patch_only_touches_known_settings() {
+        let request = StrategyUpdateRequest {
+            enabled: Some(true),
+            priority: Some(60),
+            parameters: json!({ "min_profit_threshold_czk": 2.5 })
+                .as_object()
+                .cloned()
+                .unwrap(),
+        };
+
+        let patch = strategy_patch(&config(), "fixed_price_arbitrage", request).unwrap();
+
+        assert_eq!(
+            patch,
+            json!({ "strategies": { "fixed_price_arbitrage": {
+                "enabled": true, "priority": 60, "min_profit_threshold_czk": 2.5
+            } } })
+        );
+    }
+
+    #[test]
+    fn test_patch_rejects_unknown_strategy_and_settings() {
+        let unknown = strategy_patch(
+            &config(),
+            "unified_smart_charge",
+            StrategyUpdateRequest::default(),
+        );
+        assert_eq!(
+            unknown.unwrap_err().field,
+            "strategies.unified_smart_charge"
+        );
+
+        let no_switch = StrategyUpdateRequest {
+            enabled: Some(true),
+            ..StrategyUpdateRequest::default()
+        };
+        let err = strategy_patch(&config(), "seasonal", no_switch).unwrap_err();
+        assert_eq!(err.field, "strategies.seasonal.enabled");
+
+        let too_high = StrategyUpdateRequest {
+            priority: Some(101),
+            ..StrategyUpdateRequest::default()
+        };
+        let err = strategy_patch(&config(), "winter_adaptive_v10", too_high).unwrap_err();
+        assert_eq!(err.field, "strategies.winter_adaptive_v10.priority");
+    }
+}
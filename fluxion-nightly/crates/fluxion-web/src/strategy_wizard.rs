@@ -0,0 +1,450 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Strategy recommendation wizard.
+//!
+//! Runs the recent days recorded in the backtest database, or a set of
+//! simulator presets, through every strategy and ranks them by expected
+//! annual savings against the self-use baseline. Each option carries the
+//! config patch that enables it; the `/strategy-wizard` page applies the
+//! chosen one through the regular `/api/config/update` endpoint.
+
+use askama::Template;
+use axum::{
+    Json, Router,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{Html, IntoResponse},
+    routing::{get, post},
+};
+use chrono::NaiveDate;
+use fluxion_backtest::{DataSource, SqliteDataSource, StrategyChoice, simulate_day};
+use fluxion_strategy_simulator::{PRICE_PRESETS, SimulationEngine};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{debug, error, info};
+
+use crate::simulator::{BatchCombination, BatchRunRequest, run_batch};
+use utoipa::ToSchema;
+
+/// Recent history days evaluated by default
+const DEFAULT_HISTORY_DAYS: usize = 14;
+
+/// Maximum number of history days per evaluation
+const MAX_HISTORY_DAYS: usize = 60;
+
+/// Days per year used to annualize the average daily savings
+const DAYS_PER_YEAR: f64 = 365.0;
+
+/// Strategies of which only one should run at a time, by config key
+const EXCLUSIVE_STRATEGY_KEYS: &[&str] = &[
+    "winter_adaptive",
+    "winter_adaptive_v2",
+    "winter_adaptive_v3",
+    "winter_adaptive_v4",
+    "winter_adaptive_v5",
+    "winter_adaptive_v7",
+    "winter_adaptive_v8",
+    "winter_adaptive_v9",
+    "winter_adaptive_v10",
+    "winter_adaptive_v20",
+    "fixed_price_arbitrage",
+];
+
+/// Shared state of the strategy wizard
+#[derive(Clone)]
+pub struct StrategyWizardState {
+    /// Recorded history (None if the backtest database is not configured)
+    history: Option<Arc<SqliteDataSource>>,
+    engine: Arc<SimulationEngine>,
+}
+
+impl std::fmt::Debug for StrategyWizardState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StrategyWizardState")
+            .field("history", &self.history)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StrategyWizardState {
+    #[must_use]
+    pub fn new(history: Option<Arc<SqliteDataSource>>) -> Self {
+        Self {
+            history,
+            engine: Arc::new(SimulationEngine::new()),
+        }
+    }
+}
+
+/// Data the strategies are evaluated on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WizardSource {
+    /// Recent days from the backtest database
+    #[default]
+    History,
+    /// Synthetic simulator days
+    Presets,
+}
+
+/// Request body for POST /api/strategy-wizard/evaluate
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct EvaluateRequest {
+    #[serde(default)]
+    pub source: WizardSource,
+    /// Number of most recent history days (optional, defaults to 14)
+    pub days: Option<usize>,
+    /// Price scenario IDs for presets (optional, defaults to all)
+    pub price_scenarios: Option<Vec<String>>,
+    /// Consumption profile ID for presets (optional, defaults to "peak_based")
+    pub consumption_profile: Option<String>,
+    /// Battery capacity for presets (optional, defaults to 10 kWh)
+    pub battery_capacity_kwh: Option<f32>,
+}
+
+/// Expected outcome of one strategy
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyOption {
+    pub strategy_id: String,
+    pub average_daily_cost_czk: f64,
+    /// Savings against the baseline, extrapolated to a year (CZK)
+    pub annual_savings_czk: f64,
+    /// Patch for `/api/config/update` enabling this strategy (None for
+    /// simulator-only strategies)
+    pub config: Option<serde_json::Value>,
+}
+
+/// Response of POST /api/strategy-wizard/evaluate
+#[derive(Debug, Serialize)]
+pub struct WizardEvaluation {
+    pub source: WizardSource,
+    /// Strategy the savings are measured against
+    pub baseline: String,
+    /// Days the averages are taken over
+    pub evaluated_days: usize,
+    /// Options ranked by annual savings (best first)
+    pub options: Vec<StrategyOption>,
+    /// Best option that can be applied to the configuration
+    pub recommended: Option<String>,
+}
+
+/// Config key enabling a backtest or simulator strategy
+fn config_key(strategy_id: &str) -> Option<&'static str> {
+    match strategy_id {
+        "winter_adaptive" | "winter_adaptive_v1" => Some("winter_adaptive"),
+        "self_use" | "naive" => Some("self_use"),
+        other => EXCLUSIVE_STRATEGY_KEYS
+            .iter()
+            .find(|k| **k == other)
+            .copied(),
+    }
+}
+
+/// Config patch enabling `key` and disabling the strategies it replaces
+fn config_patch(key: &str) -> serde_json::Value {
+    let mut strategies: serde_json::Map<String, serde_json::Value> = EXCLUSIVE_STRATEGY_KEYS
+        .iter()
+        .map(|k| ((*k).to_owned(), serde_json::json!({ "enabled": *k == key })))
+        .collect();
+    if key == "self_use" {
+        strategies.insert(
+            "self_use".to_owned(),
+            serde_json::json!({ "enabled": true }),
+        );
+    }
+    serde_json::json!({ "strategies": strategies })
+}
+
+/// Rank strategies by their average daily cost against `baseline`
+#[expect(clippy::cast_precision_loss)]
+fn rank_options(
+    source: WizardSource,
+    baseline: &str,
+    evaluated_days: usize,
+    daily_costs: &BTreeMap<String, Vec<f64>>,
+) -> WizardEvaluation {
+    let average = |costs: &[f64]| costs.iter().sum::<f64>() / costs.len().max(1) as f64;
+    let baseline_cost = daily_costs.get(baseline).map(|c| average(c));
+
+    let mut options: Vec<StrategyOption> = daily_costs
+        .iter()
+        .filter(|(_, costs)| !costs.is_empty())
+        .map(|(id, costs)| {
+            let average_daily_cost_czk = average(costs);
+            StrategyOption {
+                strategy_id: id.clone(),
+                average_daily_cost_czk,
+                annual_savings_czk: baseline_cost
+                    .map_or(0.0, |b| (b - average_daily_cost_czk) * DAYS_PER_YEAR),
+                config: config_key(id).map(config_patch),
+            }
+        })
+        .collect();
+    options.sort_by(|a, b| b.annual_savings_czk.total_cmp(&a.annual_savings_czk));
+
+    let recommended = options
+        .iter()
+        .find(|o| o.config.is_some())
+        .map(|o| o.strategy_id.clone());
+
+    WizardEvaluation {
+        source,
+        baseline: baseline.to_owned(),
+        evaluated_days,
+        options,
+        recommended,
+    }
+}
+
+/// Run the most recent `days` recorded days through the backtest strategies
+///
+/// Days on which any strategy fails to simulate are skipped, so all options
+/// are averaged over the same days.
+fn evaluate_history<D: DataSource>(
+    data_source: &D,
+    days: usize,
+) -> Result<WizardEvaluation, String> {
+    let strategies = [
+        ("self_use", StrategyChoice::SelfUse),
+        ("winter_adaptive", StrategyChoice::WinterAdaptive),
+    ];
+
+    let available = data_source
+        .get_available_days()
+        .map_err(|e| format!("Failed to read recorded days: {e}"))?;
+    let recent: Vec<NaiveDate> = available.iter().rev().take(days).copied().collect();
+
+    let mut daily_costs: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    let mut evaluated_days = 0;
+    for date in recent {
+        let results: Result<Vec<f64>, _> = strategies
+            .iter()
+            .map(|(_, choice)| {
+                simulate_day(data_source, date, choice, None).map(|a| a.net_cost_czk)
+            })
+            .collect();
+        match results {
+            Ok(costs) => {
+                for ((id, _), cost) in strategies.iter().zip(costs) {
+                    daily_costs.entry((*id).to_owned()).or_default().push(cost);
+                }
+                evaluated_days += 1;
+            }
+            Err(e) => debug!("Skipping {} in strategy wizard: {}", date, e),
+        }
+    }
+
+    if evaluated_days == 0 {
+        return Err("No recorded day could be simulated".to_owned());
+    }
+    Ok(rank_options(
+        WizardSource::History,
+        "self_use",
+        evaluated_days,
+        &daily_costs,
+    ))
+}
+
+/// Run the chosen price presets through every simulator strategy
+async fn evaluate_presets(
+    engine: Arc<SimulationEngine>,
+    request: EvaluateRequest,
+) -> WizardEvaluation {
+    let strategies: Vec<String> = engine
+        .registry()
+        .list_strategies()
+        .iter()
+        .map(|s| s.id.clone())
+        .collect();
+    let price_scenarios = request
+        .price_scenarios
+        .unwrap_or_else(|| PRICE_PRESETS.iter().map(|p| p.id.to_owned()).collect());
+
+    let batch = run_batch(
+        engine,
+        BatchRunRequest {
+            date: None,
+            initial_soc: None,
+            battery_capacity_kwh: request.battery_capacity_kwh,
+            combinations: price_scenarios
+                .into_iter()
+                .map(|scenario| BatchCombination {
+                    price_scenario: Some(scenario),
+                    consumption_profile: request.consumption_profile.clone(),
+                    strategies: Some(strategies.clone()),
+                })
+                .collect(),
+            concurrency: None,
+        },
+    )
+    .await;
+
+    let rows: Vec<_> = batch.rows.iter().filter(|r| r.error.is_none()).collect();
+    let mut daily_costs: BTreeMap<String, Vec<f64>> = BTreeMap::new();
+    for row in &rows {
+        for (id, cost) in &row.net_cost_czk {
+            daily_costs
+                .entry(id.clone())
+                .or_default()
+                .push(f64::from(*cost));
+        }
+    }
+
+    rank_options(WizardSource::Presets, "naive", rows.len(), &daily_costs)
+}
+
+/// POST /api/strategy-wizard/evaluate — rank strategies for this home
+async fn evaluate_handler(
+    State(state): State<StrategyWizardState>,
+    Json(request): Json<EvaluateRequest>,
+) -> impl IntoResponse {
+    match request.source {
+        WizardSource::History => {
+            let Some(history) = state.history.clone() else {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(serde_json::json!({
+                        "error": "No recorded history available, choose presets instead"
+                    })),
+                )
+                    .into_response();
+            };
+            let days = request
+                .days
+                .unwrap_or(DEFAULT_HISTORY_DAYS)
+                .clamp(1, MAX_HISTORY_DAYS);
+            info!("🧭 Strategy wizard: evaluating the last {} days", days);
+            match tokio::task::spawn_blocking(move || evaluate_history(history.as_ref(), days))
+                .await
+            {
+                Ok(Ok(evaluation)) => Json(evaluation).into_response(),
+                Ok(Err(e)) => (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(serde_json::json!({ "error": e })),
+                )
+                    .into_response(),
+                Err(e) => {
+                    error!("Strategy wizard evaluation failed: {}", e);
+                    (StatusCode::INTERNAL_SERVER_ERROR, "Evaluation failed").into_response()
+                }
+            }
+        }
+        WizardSource::Presets => {
+            info!("🧭 Strategy wizard: evaluating simulator presets");
+            Json(evaluate_presets(state.engine.clone(), request).await).into_response()
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "strategy_wizard.html")]
+struct StrategyWizardTemplate {
+    ingress_path: String,
+    has_history: bool,
+}
+
+/// GET /strategy-wizard — wizard page
+async fn page_handler(
+    State(state): State<StrategyWizardState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let template = StrategyWizardTemplate {
+        ingress_path: crate::extract_ingress_path(&headers),
+        has_history: state.history.is_some(),
+    };
+    match template.render() {
+        Ok(html) => Html(html).into_response(),
+        Err(e) => {
+            error!("Template render error: {e}");
+            Html(format!("<h1>Error</h1><p>{e}</p>")).into_response()
+        }
+    }
+}
+
+/// Build the router for the strategy wizard.
+pub fn strategy_wizard_routes(state: StrategyWizardState) -> Router {
+    Router::new()
+        .route("/strategy-wizard", get(page_handler))
+        .route("/api/strategy-wizard/evaluate", post(evaluate_handler))
+        .with_state(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_options_are_ranked_against_the_baseline() {
+        let daily_costs = BTreeMap::from([
+            ("naive".to_owned(), vec![40.0, 60.0]),
+            ("no_battery".to_owned(), vec![80.0, 80.0]),
+            ("winter_adaptive_c10".to_owned(), vec![10.0, 10.0]),
+            ("winter_adaptive_v4".to_owned(), vec![30.0, 40.0]),
+        ]);
+
+        let evaluation = rank_options(WizardSource::Presets, "naive", 2, &daily_costs);
+
+        let ids: Vec<&str> = evaluation
+            .options
+            .iter()
+            .map(|o| o.strategy_id.as_str())
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                "winter_adaptive_c10",
+                "winter_adaptive_v4",
+                "naive",
+                "no_battery"
+            ]
+        );
+        assert!((evaluation.options[1].annual_savings_czk - 5475.0).abs() < 1e-6);
+        // The simulator-only strategy cannot be applied, so the next one is recommended
+        assert_eq!(
+            evaluation.recommended.as_deref(),
+            Some("winter_adaptive_v4")
+        );
+    }
+
+    #[test]
+    fn test_config_patch_enables_only_the_chosen_strategy() {
+        let patch = config_patch(config_key("winter_adaptive_v1").unwrap());
+
+        assert_eq!(patch["strategies"]["winter_adaptive"]["enabled"], true);
+        assert_eq!(patch["strategies"]["winter_adaptive_v4"]["enabled"], false);
+        assert!(patch["strategies"].get("self_use").is_none());
+        assert_eq!(
+            config_patch("self_use")["strategies"]["self_use"]["enabled"],
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn test_presets_are_evaluated_with_the_simulator() {
+        let evaluation = evaluate_presets(
+            Arc::new(SimulationEngine::new()),
+            EvaluateRequest {
+                source: WizardSource::Presets,
+                price_scenarios: Some(vec!["volatile".to_owned()]),
+                ..EvaluateRequest::default()
+            },
+        )
+        .await;
+
+        assert_eq!(evaluation.evaluated_days, 1);
+        assert_eq!(evaluation.baseline, "naive");
+        assert!(evaluation.options.len() > 2);
+        assert!(evaluation.recommended.is_some());
+    }
+}
@@ -0,0 +1,244 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+//! Per-appliance energy and cost report.
+
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::TimeFormatter;
+use fluxion_core::historian::Historian;
+use fluxion_core::savings::SavingsPeriod;
+use fluxion_core::submeter::{DeviceUsage, SubmeterDevice, summarize};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+/// State of the sub-metering report
+#[derive(Clone)]
+pub struct SubmeterState {
+    pub historian: Historian,
+    pub devices: Vec<SubmeterDevice>,
+    /// Periods are cut on the local clock of the HA timezone
+    pub time_formatter: TimeFormatter,
+}
+
+/// Query parameters of `/api/submeter` (RFC 3339 timestamps)
+#[derive(Debug, Deserialize)]
+pub struct SubmeterQuery {
+    #[serde(default)]
+    pub period: SavingsPeriod,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Energy and cost of one appliance over the whole range
+#[derive(Debug, Serialize)]
+pub struct DeviceTotal {
+    pub name: String,
+    pub entity_id: String,
+    pub energy_kwh: f32,
+    pub cost_czk: f32,
+    pub unpriced_kwh: f32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubmeterResponse {
+    pub period: SavingsPeriod,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// IANA timezone the periods are cut in
+    pub timezone: Option<String>,
+    /// Every configured appliance, most expensive first
+    pub devices: Vec<DeviceTotal>,
+    pub periods: Vec<DeviceUsage>,
+}
+
+/// Range covered when `from` is not given
+fn default_range(period: SavingsPeriod) -> Duration {
+    match period {
+        SavingsPeriod::Day => Duration::days(30),
+        SavingsPeriod::Week => Duration::weeks(12),
+        SavingsPeriod::Month => Duration::days(365),
+    }
+}
+
+fn totals(devices: &[SubmeterDevice], periods: &[DeviceUsage]) -> Vec<DeviceTotal> {
+    let mut totals: Vec<DeviceTotal> = devices
+        .iter()
+        .map(|device| {
+            let usage = periods.iter().filter(|u| u.device == device.name);
+            DeviceTotal {
+                name: device.name.clone(),
+                entity_id: device.entity_id.clone(),
+                energy_kwh: usage.clone().map(|u| u.energy_kwh).sum(),
+                cost_czk: usage.clone().map(|u| u.cost_czk).sum(),
+                unpriced_kwh: usage.map(|u| u.unpriced_kwh).sum(),
+            }
+        })
+        .collect();
+    totals.sort_by(|a, b| b.cost_czk.total_cmp(&a.cost_czk));
+    totals
+}
+
+/// GET /api/submeter?period=day|week|month&from=&to= — kWh and cost per appliance and period
+pub async fn submeter_handler(
+    State(state): State<SubmeterState>,
+    Query(query): Query<SubmeterQuery>,
+) -> Response {
+    let to = query.to.unwrap_or_else(Utc::now);
+    let from = query.from.unwrap_or(to - default_range(query.period));
+    if from >= to {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "`from` must be before `to`" })),
+        )
+            .into_response();
+    }
+
+    // SQLite calls block
+    let historian = state.historian.clone();
+    let energy = match tokio::task::spawn_blocking(move || historian.query_submeter(from, to)).await
+    {
+        Ok(Ok(energy)) => energy,
+        Ok(Err(e)) => {
+            error!("Failed to query sub-meter readings: {e:#}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+        Err(e) => {
+            error!("Sub-meter query failed: {e}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let periods = summarize(&energy, query.period, &state.time_formatter);
+    Json(SubmeterResponse {
+        period: query.period,
+        from,
+        to,
+        timezone: state.time_formatter.timezone_name().map(str::to_owned),
+        devices: totals(&state.devices, &periods),
+        periods,
+    })
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_core::submeter::SubmeterReading;
+
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn device(name: &str, entity_id: &str) -> SubmeterDevice {
+        SubmeterDevice {
+            name: name.to_owned(),
+            entity_id: entity_id.to_owned(),
+        }
+    }
+
+    fn reading(device: &str, timestamp: &str, energy_kwh: f32) -> SubmeterReading {
+        SubmeterReading {
+            device: device.to_owned(),
+            timestamp: timestamp.parse().unwrap(),
+            power_w: energy_kwh * 4000.0,
+            energy_kwh,
+        }
+    }
+
+    fn state() -> SubmeterState {
+        SubmeterState {
+            historian: Historian::open_in_memory(7, 60).unwrap(),
+            devices: vec![
+                device("Dishwasher", "sensor.dishwasher_power"),
+                device("Heat pump", "sensor.heat_pump_power"),
+            ],
+            time_formatter: TimeFormatter::from_timezone_name(Some("Europe/Prague")),
+        }
+    }
+
+    fn query(from: &str, to: &str) -> Query<SubmeterQuery> {
+        Query(SubmeterQuery {
+            period: SavingsPeriod::Day,
+            from: Some(from.parse().unwrap()),
+            to: Some(to.parse().unwrap()),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_devices_are_costed_and_sorted_by_cost() {
+        let state = state();
+        state
+            .historian
+            .record_prices(&[("2025-06-01T09:00:00Z".parse().unwrap(), 4.0)])
+            .unwrap();
+        state
+            .historian
+            .record_submeter(&[
+                // Read before the first known price
+                reading("Heat pump", "2025-06-01T08:00:00Z", 0.5),
+                reading("Heat pump", "2025-06-01T10:00:00Z", 2.0),
+                reading("Dishwasher", "2025-06-01T10:00:00Z", 1.0),
+            ])
+            .unwrap();
+
+        let response = submeter_handler(
+            State(state),
+            query("2025-06-01T00:00:00Z", "2025-06-02T00:00:00Z"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let report = body(response).await;
+        let devices = report["devices"].as_array().unwrap();
+        assert_eq!(devices[0]["name"], "Heat pump");
+        assert_eq!(devices[0]["entity_id"], "sensor.heat_pump_power");
+        assert_eq!(devices[0]["energy_kwh"], 2.5);
+        assert_eq!(devices[0]["cost_czk"], 8.0);
+        assert_eq!(devices[0]["unpriced_kwh"], 0.5);
+        assert_eq!(devices[1]["name"], "Dishwasher");
+        assert_eq!(devices[1]["cost_czk"], 4.0);
+        assert_eq!(report["periods"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_devices_without_readings_are_listed_with_zero() {
+        let response = submeter_handler(
+            State(state()),
+            query("2025-06-01T00:00:00Z", "2025-06-02T00:00:00Z"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let report = body(response).await;
+        assert_eq!(report["devices"].as_array().unwrap().len(), 2);
+        assert_eq!(report["devices"][0]["energy_kwh"], 0.0);
+        assert_eq!(report["periods"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_inverted_range_is_rejected() {
+        let response = submeter_handler(
+            State(state()),
+            query("2025-06-02T00:00:00Z", "2025-06-01T00:00:00Z"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Active HDO grid tariff and its periods over the price horizon.
+
+use axum::{Json, extract::State, response::IntoResponse};
+use chrono::{DateTime, Utc};
+use fluxion_core::{HdoSource, PriceBlockData, Tariff, WebQueryResponse};
+use serde::Serialize;
+use tracing::error;
+
+use crate::AppState;
+use crate::preview::block_length;
+
+/// Tariff now and over the price blocks
+#[derive(Debug, Clone, Serialize)]
+pub struct TariffOverview {
+    pub now: DateTime<Utc>,
+    /// `None` when no HDO schedule is known
+    pub source: Option<HdoSource>,
+    pub current_tariff: Option<Tariff>,
+    pub low_tariff_czk: Option<f32>,
+    pub high_tariff_czk: Option<f32>,
+    /// Consecutive blocks with the same known tariff
+    pub periods: Vec<TariffSegment>,
+}
+
+/// A run of blocks with the same tariff
+#[derive(Debug, Clone, Serialize)]
+pub struct TariffSegment {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub tariff: Tariff,
+}
+
+/// Build the tariff overview from a dashboard snapshot
+pub fn build_tariff_overview(response: &WebQueryResponse, now: DateTime<Utc>) -> TariffOverview {
+    let blocks: Vec<&PriceBlockData> = response
+        .prices
+        .as_ref()
+        .map(|p| p.blocks.iter().collect())
+        .unwrap_or_default();
+    let block_len = block_length(&blocks);
+
+    let mut periods: Vec<TariffSegment> = Vec::new();
+    for block in &blocks {
+        let Some(tariff) = block.tariff else {
+            continue;
+        };
+        let to = block.timestamp + block_len;
+        if let Some(last) = periods.last_mut()
+            && last.tariff == tariff
+            && last.to == block.timestamp
+        {
+            last.to = to;
+            continue;
+        }
+        periods.push(TariffSegment {
+            from: block.timestamp,
+            to,
+            tariff,
+        });
+    }
+
+    let hdo = response.hdo_schedule.as_ref();
+    TariffOverview {
+        now,
+        source: hdo.map(|hdo| hdo.source),
+        current_tariff: hdo.and_then(|hdo| hdo.current_tariff),
+        low_tariff_czk: hdo.map(|hdo| hdo.low_tariff_czk),
+        high_tariff_czk: hdo.map(|hdo| hdo.high_tariff_czk),
+        periods,
+    }
+}
+
+/// GET /api/tariff - Active HDO tariff and the tariff periods of the price blocks
+pub async fn tariff_handler(State(app_state): State<AppState>) -> impl IntoResponse {
+    match app_state.query_sender.query_dashboard().await {
+        Ok(response) => Json(build_tariff_overview(&response, Utc::now())).into_response(),
+        Err(e) => {
+            error!("Failed to query dashboard data for tariff overview: {e}");
+            crate::query_error_response(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use fluxion_core::web_bridge::HdoScheduleInfo;
+    use fluxion_core::{PriceData, SystemHealthData};
+
+    fn block(timestamp: DateTime<Utc>, tariff: Option<Tariff>) -> PriceBlockData {
+        PriceBlockData {
+            timestamp,
+            price: 3.0,
+            block_type: "self-use".to_owned(),
+            target_soc: None,
+            planned_soc: None,
+            decision_reason: None,
+            strategy: None,
+            expected_profit: None,
+            reason: None,
+            decision_uid: None,
+            debug_info: None,
+            forecast: None,
+            actual: None,
+            tariff,
+            is_historical: false,
+        }
+    }
+
+    fn response(blocks: Vec<PriceBlockData>, now: DateTime<Utc>) -> WebQueryResponse {
+        WebQueryResponse {
+            timestamp: now,
+            debug_mode: false,
+            inverters: vec![],
+            schedule: None,
+            prices: Some(PriceData {
+                current_price: 3.0,
+                min_price: 3.0,
+                max_price: 3.0,
+                avg_price: 3.0,
+                blocks,
+                today_min_price: 3.0,
+                today_max_price: 3.0,
+                today_avg_price: 3.0,
+                today_median_price: 3.0,
+                tomorrow_min_price: None,
+                tomorrow_max_price: None,
+                tomorrow_avg_price: None,
+                tomorrow_median_price: None,
+            }),
+            health: SystemHealthData {
+                inverter_source: true,
+                price_source: true,
+                last_update: now,
+                errors: vec![],
+            },
+            timezone: None,
+            battery_soc_history: None,
+            battery_soc_prediction: None,
+            pv_generation_history: None,
+            battery_power_history: None,
+            grid_power_history: None,
+            consumption_stats: None,
+            hdo_schedule: Some(HdoScheduleInfo {
+                source: HdoSource::Preset,
+                current_tariff: Some(Tariff::Low),
+                low_tariff_periods: vec![],
+                low_tariff_czk: 0.5,
+                high_tariff_czk: 1.8,
+                last_updated: None,
+            }),
+            pricing_fees: None,
+            solar_forecast: None,
+        }
+    }
+
+    #[test]
+    fn test_blocks_are_merged_into_tariff_periods() {
+        let now: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+        let q = Duration::minutes(15);
+        let blocks = vec![
+            block(now, Some(Tariff::Low)),
+            block(now + q, Some(Tariff::Low)),
+            block(now + q * 2, Some(Tariff::High)),
+            block(now + q * 3, None),
+        ];
+
+        let overview = build_tariff_overview(&response(blocks, now), now);
+
+        assert_eq!(overview.source, Some(HdoSource::Preset));
+        assert_eq!(overview.current_tariff, Some(Tariff::Low));
+        assert_eq!(overview.periods.len(), 2);
+        assert_eq!(overview.periods[0].to, now + q * 2);
+        assert_eq!(overview.periods[1].tariff, Tariff::High);
+        assert_eq!(overview.periods[1].to, now + q * 3);
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Display preferences of the web UI, shared by every browser.
+//!
+//! The theme is stored in `<data_dir>/ui_preferences.json` and rendered into
+//! each page, so a wall tablet follows it without any setup on the device.
+//! `auto` picks the light or dark theme from the browser's
+//! `prefers-color-scheme` (and high contrast from `prefers-contrast`).
+//! A device can still override the theme locally with `?theme=<name>`.
+
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+/// Dashboard color theme
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    /// Follow the browser's color scheme and contrast preference
+    #[default]
+    Auto,
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl Theme {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Dark => "dark",
+            Self::Light => "light",
+            Self::HighContrast => "high-contrast",
+        }
+    }
+}
+
+/// Preferences served at `/api/ui/preferences`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct UiPreferences {
+    pub theme: Theme,
+}
+
+/// Preferences kept in memory and persisted as JSON
+#[derive(Debug)]
+pub struct UiPreferenceStore {
+    /// None keeps the preferences in memory only
+    path: Option<PathBuf>,
+    preferences: RwLock<UiPreferences>,
+}
+
+impl UiPreferenceStore {
+    /// Open the store at `<data_dir>/ui_preferences.json`
+    #[must_use]
+    pub fn new(data_dir: &Path) -> Self {
+        let path = data_dir.join("ui_preferences.json");
+        let preferences = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+                warn!("⚠️ Failed to parse {}: {e}", path.display());
+                UiPreferences::default()
+            }),
+            Err(_) => UiPreferences::default(),
+        };
+        Self {
+            path: Some(path),
+            preferences: RwLock::new(preferences),
+        }
+    }
+
+    fn in_memory() -> Self {
+        Self {
+            path: None,
+            preferences: RwLock::new(UiPreferences::default()),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self) -> UiPreferences {
+        *self.preferences.read()
+    }
+
+    /// Replace the preferences and persist them
+    pub fn set(&self, preferences: UiPreferences) -> std::io::Result<()> {
+        let mut current = self.preferences.write();
+        if let Some(path) = &self.path {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            let json = serde_json::to_string_pretty(&preferences).map_err(std::io::Error::other)?;
+            std::fs::write(path, json)?;
+        }
+        *current = preferences;
+        Ok(())
+    }
+}
+
+static STORE: OnceLock<UiPreferenceStore> = OnceLock::new();
+
+/// Load the stored preferences; before this they only live in memory
+pub fn install(data_dir: &Path) {
+    let store = UiPreferenceStore::new(data_dir);
+    let theme = store.get().theme;
+    if theme != Theme::Auto {
+        info!("🎨 Dashboard theme: {}", theme.as_str());
+    }
+    let _ = STORE.set(store);
+}
+
+fn store() -> &'static UiPreferenceStore {
+    STORE.get_or_init(UiPreferenceStore::in_memory)
+}
+
+/// Stored theme, rendered into every page
+#[must_use]
+pub fn theme() -> &'static str {
+    store().get().theme.as_str()
+}
+
+/// GET /api/ui/preferences
+pub async fn get_preferences_handler() -> impl IntoResponse {
+    Json(store().get())
+}
+
+/// PUT /api/ui/preferences - Change the shared display preferences
+pub async fn update_preferences_handler(
+    Json(preferences): Json<UiPreferences>,
+) -> impl IntoResponse {
+    match store().set(preferences) {
+        Ok(()) => {
+            info!("🎨 Dashboard theme set to {}", preferences.theme.as_str());
+            Json(preferences).into_response()
+        }
+        Err(e) => {
+            error!("Failed to save UI preferences: {e}");
+            crate::api_error::error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to save preferences: {e}"),
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preferences_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = UiPreferenceStore::new(dir.path());
+        assert_eq!(store.get().theme, Theme::Auto);
+
+        store
+            .set(UiPreferences {
+                theme: Theme::HighContrast,
+            })
+            .unwrap();
+        let reopened = UiPreferenceStore::new(dir.path());
+        assert_eq!(reopened.get().theme, Theme::HighContrast);
+
+        let json = std::fs::read_to_string(dir.path().join("ui_preferences.json")).unwrap();
+        assert!(json.contains("\"high-contrast\""));
+        assert!(serde_json::from_str::<UiPreferences>(r#"{"theme":"sepia"}"#).is_err());
+    }
+}
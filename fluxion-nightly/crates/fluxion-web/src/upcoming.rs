@@ -0,0 +1,291 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Planned modes for the next few hours, for the live dashboard timeline.
+//!
+//! Only the mode runs and the time of the next mode change are returned, so
+//! the dashboard can poll it often and count down to the change locally.
+//! The full plan is served separately and carries no request time, so clients
+//! can revalidate it with `If-None-Match`.
+
+use axum::{Json, Router, extract::State, response::IntoResponse, routing::get};
+use chrono::{DateTime, Duration, Utc};
+use fluxion_core::{PriceBlockData, ScheduleData, WebQueryResponse};
+use serde::Serialize;
+use tracing::error;
+
+use crate::AppState;
+use crate::etag;
+use crate::preview::block_length;
+
+/// How far ahead the timeline looks
+pub const UPCOMING_HORIZON_HOURS: i64 = 6;
+
+/// Planned modes from now until the end of the horizon
+#[derive(Debug, Clone, Serialize)]
+pub struct UpcomingSchedule {
+    pub now: DateTime<Utc>,
+    pub horizon_hours: i64,
+    /// Consecutive blocks with the same mode, clipped to `[now, now + horizon)`
+    pub segments: Vec<ModeSegment>,
+    /// When the current mode ends, if another one follows within the horizon
+    pub next_change: Option<DateTime<Utc>>,
+    /// IANA timezone the UI should display the times in
+    pub timezone: Option<String>,
+}
+
+/// The full plan: current schedule summary and every planned block
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedSchedule {
+    pub schedule: Option<ScheduleData>,
+    pub blocks: Vec<PriceBlockData>,
+    /// IANA timezone the UI should display the times in
+    pub timezone: Option<String>,
+}
+
+/// A run of blocks with the same planned mode
+#[derive(Debug, Clone, Serialize)]
+pub struct ModeSegment {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Block type as used by the chart, e.g. "charge", "discharge" or "self-use"
+    pub mode: String,
+    pub strategy: Option<String>,
+}
+
+/// Build the timeline for `[now, now + horizon)` from a dashboard snapshot
+pub fn build_upcoming(response: &WebQueryResponse, now: DateTime<Utc>) -> UpcomingSchedule {
+    let end = now + Duration::hours(UPCOMING_HORIZON_HOURS);
+    let blocks: Vec<&PriceBlockData> = response
+        .prices
+        .as_ref()
+        .map(|p| p.blocks.iter().collect())
+        .unwrap_or_default();
+    let block_len = block_length(&blocks);
+
+    let mut segments: Vec<ModeSegment> = Vec::new();
+    // Include the block currently in progress
+    for block in blocks
+        .iter()
+        .filter(|b| b.timestamp + block_len > now && b.timestamp < end)
+    {
+        let from = block.timestamp.max(now);
+        let to = (block.timestamp + block_len).min(end);
+        if let Some(last) = segments.last_mut()
+            && last.mode == block.block_type
+            && last.to == from
+        {
+            last.to = to;
+            continue;
+        }
+        segments.push(ModeSegment {
+            from,
+            to,
+            mode: block.block_type.clone(),
+            strategy: block.strategy.clone(),
+        });
+    }
+
+    UpcomingSchedule {
+        now,
+        horizon_hours: UPCOMING_HORIZON_HOURS,
+        next_change: segments.get(1).map(|next| next.from),
+        segments,
+        timezone: response.timezone.clone(),
+    }
+}
+
+/// GET /api/schedule/upcoming - Planned modes for the next 6 hours
+pub async fn upcoming_handler(State(app_state): State<AppState>) -> impl IntoResponse {
+    match app_state.query_sender.query_dashboard().await {
+        Ok(response) => Json(build_upcoming(&response, Utc::now())).into_response(),
+        Err(e) => {
+            error!("Failed to query dashboard data for upcoming schedule: {e}");
+            crate::query_error_response(e)
+        }
+    }
+}
+
+/// Take the plan out of a dashboard snapshot
+pub fn build_planned(response: WebQueryResponse) -> PlannedSchedule {
+    PlannedSchedule {
+        schedule: response.schedule,
+        blocks: response.prices.map(|p| p.blocks).unwrap_or_default(),
+        timezone: response.timezone,
+    }
+}
+
+/// GET /api/schedule - The full plan, only changes when the schedule does
+pub async fn schedule_handler(State(app_state): State<AppState>) -> impl IntoResponse {
+    match app_state.query_sender.query_dashboard().await {
+        Ok(response) => Json(build_planned(response)).into_response(),
+        Err(e) => {
+            error!("Failed to query dashboard data for schedule: {e}");
+            crate::query_error_response(e)
+        }
+    }
+}
+
+/// Schedule read routes; the full plan is revalidated with ETags
+pub fn schedule_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/schedule",
+            get(schedule_handler).layer(axum::middleware::from_fn(etag::etag_middleware)),
+        )
+        .route("/api/schedule/upcoming", get(upcoming_handler))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fluxion_core::{PriceData, SystemHealthData};
+
+    fn block(timestamp: DateTime<Utc>, block_type: &str) -> PriceBlockData {
+        PriceBlockData {
+            timestamp,
+            price: 3.0,
+            block_type: block_type.to_owned(),
+            target_soc: None,
+            planned_soc: None,
+            decision_reason: None,
+            strategy: Some("Test".to_owned()),
+            expected_profit: None,
+            reason: None,
+            decision_uid: None,
+            debug_info: None,
+            forecast: None,
+            actual: None,
+            tariff: None,
+            is_historical: false,
+        }
+    }
+
+    fn response(blocks: Vec<PriceBlockData>, now: DateTime<Utc>) -> WebQueryResponse {
+        WebQueryResponse {
+            timestamp: now,
+            debug_mode: false,
+            inverters: vec![],
+            schedule: None,
+            prices: Some(PriceData {
+                current_price: 3.0,
+                min_price: 3.0,
+                max_price: 3.0,
+                avg_price: 3.0,
+                blocks,
+                today_min_price: 3.0,
+                today_max_price: 3.0,
+                today_avg_price: 3.0,
+                today_median_price: 3.0,
+                tomorrow_min_price: None,
+                tomorrow_max_price: None,
+                tomorrow_avg_price: None,
+                tomorrow_median_price: None,
+            }),
+            health: SystemHealthData {
+                inverter_source: true,
+                price_source: true,
+                last_update: now,
+                errors: vec![],
+            },
+            timezone: Some("Europe/Prague".to_owned()),
+            battery_soc_history: None,
+            battery_soc_prediction: None,
+            pv_generation_history: None,
+            battery_power_history: None,
+            grid_power_history: None,
+            consumption_stats: None,
+            hdo_schedule: None,
+            pricing_fees: None,
+            solar_forecast: None,
+        }
+    }
+
+    #[test]
+    fn test_blocks_are_merged_into_segments_within_horizon() {
+        let start: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+        let now = start + Duration::minutes(5);
+        let q = Duration::minutes(15);
+        let mut blocks = vec![
+            block(start - q, "charge"),
+            block(start, "self-use"),
+            block(start + q, "self-use"),
+            block(start + q * 2, "charge"),
+        ];
+        blocks.extend((3..40).map(|i| block(start + q * i, "discharge")));
+
+        let upcoming = build_upcoming(&response(blocks, now), now);
+
+        assert_eq!(upcoming.segments.len(), 3);
+        assert_eq!(upcoming.segments[0].mode, "self-use");
+        assert_eq!(upcoming.segments[0].from, now);
+        assert_eq!(upcoming.next_change, Some(start + q * 2));
+        assert_eq!(upcoming.segments[2].to, now + Duration::hours(6));
+        assert_eq!(upcoming.timezone.as_deref(), Some("Europe/Prague"));
+    }
+
+    #[test]
+    fn test_no_change_without_following_segment() {
+        let now: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+
+        let upcoming = build_upcoming(&response(vec![block(now, "self-use")], now), now);
+
+        assert_eq!(upcoming.segments.len(), 1);
+        assert!(upcoming.next_change.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_is_revalidated_with_etag() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode, header};
+        use fluxion_core::web_bridge::WebQuerySender;
+        use fluxion_i18n::{I18n, Language};
+        use std::sync::Arc;
+        use tower::ServiceExt;
+
+        let now: DateTime<Utc> = "2025-06-01T10:00:00Z".parse().unwrap();
+        let (query_sender, mut channel) =
+            WebQuerySender::with_limits(4, std::time::Duration::from_secs(1));
+        tokio::spawn(async move {
+            while let Some(request) = channel.receiver.recv().await {
+                let _ = request
+                    .response_tx
+                    .send(response(vec![block(now, "charge")], now));
+            }
+        });
+        let app = schedule_routes().with_state(AppState {
+            query_sender,
+            i18n: Arc::new(I18n::new(Language::English).unwrap()),
+            user_control_state: None,
+            export_config: crate::ScheduledExportConfig::default(),
+        });
+
+        let first = app
+            .clone()
+            .oneshot(Request::get("/api/schedule").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers()[header::ETAG].clone();
+
+        let second = app
+            .oneshot(
+                Request::get("/api/schedule")
+                    .header(header::IF_NONE_MATCH, etag)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+    }
+}
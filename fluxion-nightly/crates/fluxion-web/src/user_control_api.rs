@@ -16,20 +16,32 @@
 //! - Enabling/disabling FluxION mode changes
 //! - Setting charge/discharge restrictions
 //! - Managing fixed time slot overrides
+//! - Archiving deleted slots (who/when) and restoring them
+//! - Pinning single schedule blocks to a mode while the optimizer plans around them
+//!
+//! Every change is validated against the rest of the user control state before it is
+//! applied. Changes that introduce a conflict (overlapping slots, a slot mode blocked by a
+//! restriction, ...) are rejected with `409 Conflict`, the list of conflicts, and the
+//! precedence order in which the inputs are applied.
 
 use axum::{
     Json,
     extract::{Path, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
 };
 use chrono::{DateTime, Utc};
 use fluxion_core::{UserControlChangeType, UserControlPersistence, UserControlUpdateEvent};
-use fluxion_types::user_control::FixedTimeSlot;
+use fluxion_types::user_control::{
+    ArchivedTimeSlot, BackupReserve, CONTROL_PRECEDENCE, FixedTimeSlot, MAX_PIN_AHEAD_HOURS,
+    UserControlIssue,
+};
 use fluxion_types::{InverterOperationMode, UserControlState};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
 
 /// Channel sender type for user control updates to ECS
 pub type UserControlUpdateSender = fluxion_core::UserControlUpdateSender;
@@ -68,22 +80,48 @@ impl UserControlApiState {
             update_sender,
         }
     }
+
+    /// Validate and commit a change, then persist it and notify the ECS
+    ///
+    /// Entry point for callers outside the HTTP handlers, such as the gRPC API.
+    pub fn update(
+        &self,
+        change_type: UserControlChangeType,
+        change: impl FnOnce(&mut UserControlState) -> Result<(), StatusCode>,
+    ) -> Result<(UserControlState, Vec<UserControlIssue>), UserControlChangeError> {
+        let (new_state, warnings) = apply_change(self, change)?;
+        persist_and_notify(self, &new_state, change_type)?;
+        Ok((new_state, warnings))
+    }
 }
 
 // ==================== GET /api/user-control ====================
 
 /// Response for GET /api/user-control
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct GetUserControlResponse {
     pub enabled: bool,
     pub disallow_charge: bool,
     pub disallow_discharge: bool,
     pub fixed_time_slots: Vec<FixedTimeSlotResponse>,
+    /// Pinned schedule blocks; they expire with the block
+    pub pinned_blocks: Vec<FixedTimeSlotResponse>,
+    /// Temporarily raised minimum SOC, see /api/backup-reserve
+    #[schema(value_type = Option<Object>)]
+    pub backup_reserve: Option<BackupReserve>,
+    /// Deleted slots, newest first; can be restored
+    pub archived_slots: Vec<ArchivedSlotResponse>,
     pub last_modified: Option<String>,
+    /// Conflicts and warnings in the current state
+    #[schema(value_type = Vec<Object>)]
+    pub conflicts: Vec<UserControlIssue>,
+    /// Order in which user inputs are applied, highest precedence first
+    #[schema(value_type = Vec<String>)]
+    pub precedence: &'static [&'static str],
 }
 
 /// Fixed time slot in API response format
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct FixedTimeSlotResponse {
     pub id: String,
     pub from: String,
@@ -106,12 +144,32 @@ impl From<&FixedTimeSlot> for FixedTimeSlotResponse {
     }
 }
 
+/// Archived (deleted) slot in API response format
+#[derive(Serialize, ToSchema)]
+pub struct ArchivedSlotResponse {
+    #[serde(flatten)]
+    pub slot: FixedTimeSlotResponse,
+    pub deleted_by: String,
+    pub deleted_at: String,
+}
+
+impl From<&ArchivedTimeSlot> for ArchivedSlotResponse {
+    fn from(archived: &ArchivedTimeSlot) -> Self {
+        Self {
+            slot: FixedTimeSlotResponse::from(&archived.slot),
+            deleted_by: archived.deleted_by.clone(),
+            deleted_at: archived.deleted_at.to_rfc3339(),
+        }
+    }
+}
+
 /// GET /api/user-control - Get current user control state
 pub async fn get_user_control(
     State(state): State<UserControlApiState>,
 ) -> Json<GetUserControlResponse> {
     let mut current_state = state.state.read().clone();
     current_state.cleanup_expired_slots(); // Clean up on read
+    let validation = current_state.validate(Utc::now());
 
     Json(GetUserControlResponse {
         enabled: current_state.enabled,
@@ -122,36 +180,53 @@ pub async fn get_user_control(
             .iter()
             .map(FixedTimeSlotResponse::from)
             .collect(),
+        pinned_blocks: current_state
+            .pinned_blocks
+            .iter()
+            .map(FixedTimeSlotResponse::from)
+            .collect(),
+        backup_reserve: current_state.backup_reserve.clone(),
+        archived_slots: current_state
+            .archived_slots
+            .iter()
+            .map(ArchivedSlotResponse::from)
+            .collect(),
         last_modified: current_state.last_modified.map(|t| t.to_rfc3339()),
+        conflicts: validation
+            .errors
+            .into_iter()
+            .chain(validation.warnings)
+            .collect(),
+        precedence: &CONTROL_PRECEDENCE,
     })
 }
 
 // ==================== PUT /api/user-control/enabled ====================
 
 /// Request for PUT /api/user-control/enabled
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct SetEnabledRequest {
     pub enabled: bool,
 }
 
 /// Response for PUT /api/user-control/enabled
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct SetEnabledResponse {
     pub success: bool,
     pub enabled: bool,
+    #[schema(value_type = Vec<Object>)]
+    pub warnings: Vec<UserControlIssue>,
 }
 
 /// PUT /api/user-control/enabled - Set FluxION enabled state
 pub async fn set_enabled(
     State(state): State<UserControlApiState>,
     Json(request): Json<SetEnabledRequest>,
-) -> Result<Json<SetEnabledResponse>, StatusCode> {
-    let new_state = {
-        let mut user_state = state.state.write();
+) -> Result<Json<SetEnabledResponse>, UserControlChangeError> {
+    let (new_state, warnings) = apply_change(&state, |user_state| {
         user_state.enabled = request.enabled;
-        user_state.last_modified = Some(Utc::now());
-        user_state.clone()
-    };
+        Ok(())
+    })?;
 
     info!(
         "🎛️ User control: FluxION {}",
@@ -167,46 +242,45 @@ pub async fn set_enabled(
     Ok(Json(SetEnabledResponse {
         success: true,
         enabled: request.enabled,
+        warnings,
     }))
 }
 
 // ==================== PUT /api/user-control/restrictions ====================
 
 /// Request for PUT /api/user-control/restrictions
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct SetRestrictionsRequest {
     pub disallow_charge: Option<bool>,
     pub disallow_discharge: Option<bool>,
 }
 
 /// Response for PUT /api/user-control/restrictions
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct SetRestrictionsResponse {
     pub success: bool,
     pub disallow_charge: bool,
     pub disallow_discharge: bool,
+    #[schema(value_type = Vec<Object>)]
+    pub warnings: Vec<UserControlIssue>,
 }
 
 /// PUT /api/user-control/restrictions - Set charge/discharge restrictions
 pub async fn set_restrictions(
     State(state): State<UserControlApiState>,
     Json(request): Json<SetRestrictionsRequest>,
-) -> Result<Json<SetRestrictionsResponse>, StatusCode> {
-    let (disallow_charge, disallow_discharge, new_state) = {
-        let mut user_state = state.state.write();
+) -> Result<Json<SetRestrictionsResponse>, UserControlChangeError> {
+    let (new_state, warnings) = apply_change(&state, |user_state| {
         if let Some(dc) = request.disallow_charge {
             user_state.disallow_charge = dc;
         }
         if let Some(dd) = request.disallow_discharge {
             user_state.disallow_discharge = dd;
         }
-        user_state.last_modified = Some(Utc::now());
-        (
-            user_state.disallow_charge,
-            user_state.disallow_discharge,
-            user_state.clone(),
-        )
-    };
+        Ok(())
+    })?;
+    let (disallow_charge, disallow_discharge) =
+        (new_state.disallow_charge, new_state.disallow_discharge);
 
     info!(
         "🎛️ User control restrictions: disallow_charge={}, disallow_discharge={}",
@@ -223,13 +297,14 @@ pub async fn set_restrictions(
         success: true,
         disallow_charge,
         disallow_discharge,
+        warnings,
     }))
 }
 
 // ==================== POST /api/user-control/slots ====================
 
 /// Request for POST /api/user-control/slots
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateSlotRequest {
     pub from: DateTime<Utc>,
     pub to: DateTime<Utc>,
@@ -238,38 +313,32 @@ pub struct CreateSlotRequest {
 }
 
 /// Response for slot operations
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct SlotResponse {
     pub success: bool,
     pub slot: Option<FixedTimeSlotResponse>,
     pub error: Option<String>,
+    #[schema(value_type = Vec<Object>)]
+    pub warnings: Vec<UserControlIssue>,
 }
 
 /// POST /api/user-control/slots - Create a new fixed time slot
 pub async fn create_slot(
     State(state): State<UserControlApiState>,
     Json(request): Json<CreateSlotRequest>,
-) -> Result<Json<SlotResponse>, StatusCode> {
+) -> Result<Json<SlotResponse>, UserControlChangeError> {
     // Parse mode
     let mode = parse_operation_mode(&request.mode).ok_or_else(|| {
         error!("Invalid mode: {}", request.mode);
         StatusCode::BAD_REQUEST
     })?;
 
-    // Validate times
-    if request.from >= request.to {
-        error!("Invalid time range: from >= to");
-        return Err(StatusCode::BAD_REQUEST);
-    }
-
     let slot = FixedTimeSlot::new(request.from, request.to, mode, request.note);
 
-    let new_state = {
-        let mut user_state = state.state.write();
+    let (new_state, warnings) = apply_change(&state, |user_state| {
         user_state.fixed_time_slots.push(slot.clone());
-        user_state.last_modified = Some(Utc::now());
-        user_state.clone()
-    };
+        Ok(())
+    })?;
 
     info!(
         "🎛️ User control: Created fixed slot {} ({:?}) from {} to {}",
@@ -285,13 +354,14 @@ pub async fn create_slot(
         success: true,
         slot: Some(FixedTimeSlotResponse::from(&slot)),
         error: None,
+        warnings,
     }))
 }
 
 // ==================== PUT /api/user-control/slots/:id ====================
 
 /// Request for PUT /api/user-control/slots/:id
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateSlotRequest {
     pub from: Option<DateTime<Utc>>,
     pub to: Option<DateTime<Utc>>,
@@ -304,45 +374,34 @@ pub async fn update_slot(
     State(state): State<UserControlApiState>,
     Path(slot_id): Path<String>,
     Json(request): Json<UpdateSlotRequest>,
-) -> Result<Json<SlotResponse>, StatusCode> {
-    let (updated_slot, new_state) = {
-        let mut user_state = state.state.write();
-
-        // Find slot index first to avoid borrow issues
-        let slot_idx = user_state
+) -> Result<Json<SlotResponse>, UserControlChangeError> {
+    let (new_state, warnings) = apply_change(&state, |user_state| {
+        let slot = user_state
             .fixed_time_slots
-            .iter()
-            .position(|s| s.id == slot_id)
+            .iter_mut()
+            .find(|s| s.id == slot_id)
             .ok_or(StatusCode::NOT_FOUND)?;
 
-        // Scope the mutable borrow of the slot
-        {
-            let slot = &mut user_state.fixed_time_slots[slot_idx];
-            if let Some(from) = request.from {
-                slot.from = from;
-            }
-            if let Some(to) = request.to {
-                slot.to = to;
-            }
-            if let Some(mode_str) = &request.mode {
-                slot.mode = parse_operation_mode(mode_str).ok_or(StatusCode::BAD_REQUEST)?;
-            }
-            if request.note.is_some() {
-                slot.note.clone_from(&request.note);
-            }
-
-            // Validate times
-            if slot.from >= slot.to {
-                return Err(StatusCode::BAD_REQUEST);
-            }
+        if let Some(from) = request.from {
+            slot.from = from;
         }
-
-        user_state.last_modified = Some(Utc::now());
-        (
-            user_state.fixed_time_slots[slot_idx].clone(),
-            user_state.clone(),
-        )
-    };
+        if let Some(to) = request.to {
+            slot.to = to;
+        }
+        if let Some(mode_str) = &request.mode {
+            slot.mode = parse_operation_mode(mode_str).ok_or(StatusCode::BAD_REQUEST)?;
+        }
+        if request.note.is_some() {
+            slot.note.clone_from(&request.note);
+        }
+        Ok(())
+    })?;
+    let updated_slot = new_state
+        .fixed_time_slots
+        .iter()
+        .find(|s| s.id == slot_id)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
 
     info!("🎛️ User control: Updated fixed slot {}", slot_id);
 
@@ -352,46 +411,292 @@ pub async fn update_slot(
         success: true,
         slot: Some(FixedTimeSlotResponse::from(&updated_slot)),
         error: None,
+        warnings,
     }))
 }
 
 // ==================== DELETE /api/user-control/slots/:id ====================
 
 /// Response for DELETE /api/user-control/slots/:id
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct DeleteSlotResponse {
     pub success: bool,
+    /// The slot as archived; restore it with POST /api/user-control/slots/:id/restore
+    pub archived: Option<ArchivedSlotResponse>,
 }
 
 /// DELETE /api/user-control/slots/:id - Delete a fixed time slot
+///
+/// The slot is moved to the archive together with who deleted it and when.
 pub async fn delete_slot(
     State(state): State<UserControlApiState>,
     Path(slot_id): Path<String>,
+    headers: HeaderMap,
 ) -> Result<Json<DeleteSlotResponse>, StatusCode> {
-    let new_state = {
-        let mut user_state = state.state.write();
-        let initial_len = user_state.fixed_time_slots.len();
-        user_state.fixed_time_slots.retain(|s| s.id != slot_id);
+    let deleted_by =
+        crate::safe_state_api::caller_from_headers(&headers).unwrap_or_else(|| "web".to_owned());
 
-        if user_state.fixed_time_slots.len() == initial_len {
-            return Err(StatusCode::NOT_FOUND);
-        }
+    let (new_state, archived) = {
+        let mut user_state = state.state.write();
+        let archived = user_state
+            .archive_slot(&slot_id, deleted_by.as_str())
+            .map(ArchivedSlotResponse::from)
+            .ok_or(StatusCode::NOT_FOUND)?;
 
         user_state.last_modified = Some(Utc::now());
-        user_state.clone()
+        (user_state.clone(), archived)
     };
 
-    info!("🎛️ User control: Deleted fixed slot {}", slot_id);
+    info!(
+        "🎛️ User control: Deleted fixed slot {} (archived, deleted by {})",
+        slot_id, deleted_by
+    );
 
     persist_and_notify(&state, &new_state, UserControlChangeType::SlotRemoved)?;
 
-    Ok(Json(DeleteSlotResponse { success: true }))
+    Ok(Json(DeleteSlotResponse {
+        success: true,
+        archived: Some(archived),
+    }))
+}
+
+// ==================== POST /api/user-control/slots/:id/restore ====================
+
+/// POST /api/user-control/slots/:id/restore - Restore an archived slot
+///
+/// The restored slot is validated like a new one, so a slot that overlaps another
+/// or already ended is rejected with `409 Conflict`.
+pub async fn restore_slot(
+    State(state): State<UserControlApiState>,
+    Path(slot_id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<SlotResponse>, UserControlChangeError> {
+    let (new_state, warnings) = apply_change(&state, |user_state| {
+        user_state
+            .restore_slot(&slot_id)
+            .map(|_| ())
+            .ok_or(StatusCode::NOT_FOUND)
+    })?;
+    let restored = new_state
+        .fixed_time_slots
+        .iter()
+        .find(|s| s.id == slot_id)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let restored_by =
+        crate::safe_state_api::caller_from_headers(&headers).unwrap_or_else(|| "web".to_owned());
+    info!(
+        "🎛️ User control: Restored fixed slot {} (restored by {})",
+        slot_id, restored_by
+    );
+
+    persist_and_notify(&state, &new_state, UserControlChangeType::SlotAdded)?;
+
+    Ok(Json(SlotResponse {
+        success: true,
+        slot: Some(FixedTimeSlotResponse::from(&restored)),
+        error: None,
+        warnings,
+    }))
+}
+
+// ==================== POST /api/schedule/pin ====================
+
+/// Length of one schedule block
+const BLOCK_MINUTES: i64 = 15;
+
+/// Request for POST /api/schedule/pin
+#[derive(Deserialize, ToSchema)]
+pub struct PinBlockRequest {
+    /// Start of the block; rounded down to the block boundary
+    pub from: DateTime<Utc>,
+    /// End of the pin; defaults to the end of the block containing `from`
+    pub to: Option<DateTime<Utc>>,
+    pub mode: String,
+    pub note: Option<String>,
+}
+
+/// POST /api/schedule/pin - Pin schedule blocks to a mode
+///
+/// Pins take precedence over fixed slots and the optimizer re-plans the rest of the
+/// day around them. A new pin replaces any pin it overlaps. Pins are one-off: they
+/// are dropped once they end.
+pub async fn pin_block(
+    State(state): State<UserControlApiState>,
+    Json(request): Json<PinBlockRequest>,
+) -> Result<Json<SlotResponse>, UserControlChangeError> {
+    let mode = parse_operation_mode(&request.mode).ok_or_else(|| {
+        error!("Invalid mode: {}", request.mode);
+        StatusCode::BAD_REQUEST
+    })?;
+
+    let block_seconds = BLOCK_MINUTES * 60;
+    let from_ts = request.from.timestamp();
+    let from = DateTime::from_timestamp(from_ts - from_ts.rem_euclid(block_seconds), 0)
+        .ok_or(StatusCode::BAD_REQUEST)?;
+    let to = request
+        .to
+        .unwrap_or(from + chrono::Duration::minutes(BLOCK_MINUTES));
+    if to <= from {
+        error!("Invalid pin: ends at {} before it starts at {}", to, from);
+        return Err(StatusCode::BAD_REQUEST.into());
+    }
+    if from > Utc::now() + chrono::Duration::hours(MAX_PIN_AHEAD_HOURS) {
+        error!(
+            "Invalid pin: starts more than {}h ahead ({})",
+            MAX_PIN_AHEAD_HOURS, from
+        );
+        return Err(StatusCode::BAD_REQUEST.into());
+    }
+
+    let pin = FixedTimeSlot::pin(from, to, mode, request.note);
+
+    let (new_state, warnings) = apply_change(&state, |user_state| {
+        user_state.pin_block(pin.clone());
+        Ok(())
+    })?;
+
+    info!(
+        "🎛️ User control: Pinned {:?} from {} to {} ({})",
+        pin.mode,
+        pin.from.format("%H:%M"),
+        pin.to.format("%H:%M"),
+        pin.id
+    );
+
+    persist_and_notify(&state, &new_state, UserControlChangeType::PinsChanged)?;
+
+    Ok(Json(SlotResponse {
+        success: true,
+        slot: Some(FixedTimeSlotResponse::from(&pin)),
+        error: None,
+        warnings,
+    }))
+}
+
+// ==================== DELETE /api/schedule/pin/:id ====================
+
+/// DELETE /api/schedule/pin/:id - Remove a pin and hand the block back to the optimizer
+pub async fn unpin_block(
+    State(state): State<UserControlApiState>,
+    Path(pin_id): Path<String>,
+) -> Result<Json<SlotResponse>, StatusCode> {
+    let (new_state, removed) = {
+        let mut user_state = state.state.write();
+        let removed = user_state
+            .pinned_blocks
+            .iter()
+            .find(|pin| pin.id == pin_id)
+            .map(FixedTimeSlotResponse::from)
+            .ok_or(StatusCode::NOT_FOUND)?;
+        user_state.unpin_block(&pin_id);
+
+        user_state.last_modified = Some(Utc::now());
+        (user_state.clone(), removed)
+    };
+
+    info!("🎛️ User control: Unpinned schedule block {}", pin_id);
+
+    persist_and_notify(&state, &new_state, UserControlChangeType::PinsChanged)?;
+
+    Ok(Json(SlotResponse {
+        success: true,
+        slot: Some(removed),
+        error: None,
+        warnings: Vec::new(),
+    }))
 }
 
 // ==================== Helper Functions ====================
 
+/// Body of a rejected user control change
+#[derive(Serialize)]
+pub struct UserControlConflictResponse {
+    pub success: bool,
+    pub error: String,
+    pub conflicts: Vec<UserControlIssue>,
+    pub precedence: &'static [&'static str],
+}
+
+/// Why a user control change was not applied
+#[derive(Debug)]
+pub enum UserControlChangeError {
+    /// Malformed request, unknown slot, or persistence failure
+    Status(StatusCode),
+    /// The change introduces conflicts with the rest of the state
+    Conflict(Vec<UserControlIssue>),
+}
+
+impl UserControlChangeError {
+    /// Human-readable summary of the conflicts
+    #[must_use]
+    pub fn message(&self) -> String {
+        match self {
+            Self::Status(status) => status.to_string(),
+            Self::Conflict(conflicts) => conflicts
+                .iter()
+                .map(|issue| issue.message.as_str())
+                .collect::<Vec<_>>()
+                .join("; "),
+        }
+    }
+}
+
+impl From<StatusCode> for UserControlChangeError {
+    fn from(status: StatusCode) -> Self {
+        Self::Status(status)
+    }
+}
+
+impl IntoResponse for UserControlChangeError {
+    fn into_response(self) -> Response {
+        let error = self.message();
+        match self {
+            Self::Status(status) => status.into_response(),
+            Self::Conflict(conflicts) => (
+                StatusCode::CONFLICT,
+                Json(UserControlConflictResponse {
+                    success: false,
+                    error,
+                    conflicts,
+                    precedence: &CONTROL_PRECEDENCE,
+                }),
+            )
+                .into_response(),
+        }
+    }
+}
+
+/// Apply a change to a copy of the state, validate it, and commit it if it introduces
+/// no conflicts. Returns the new state and any warnings.
+///
+/// The write lock is held for the whole check so concurrent changes can't interleave.
+pub(crate) fn apply_change(
+    api_state: &UserControlApiState,
+    change: impl FnOnce(&mut UserControlState) -> Result<(), StatusCode>,
+) -> Result<(UserControlState, Vec<UserControlIssue>), UserControlChangeError> {
+    let mut user_state = api_state.state.write();
+    let mut candidate = user_state.clone();
+    change(&mut candidate)?;
+
+    let now = Utc::now();
+    let validation = user_state.validate_change(&candidate, now);
+    if !validation.errors.is_empty() {
+        warn!(
+            "🎛️ User control change rejected: {} conflict(s)",
+            validation.errors.len()
+        );
+        return Err(UserControlChangeError::Conflict(validation.errors));
+    }
+
+    candidate.last_modified = Some(now);
+    *user_state = candidate.clone();
+    Ok((candidate, validation.warnings))
+}
+
 /// Parse operation mode from string
-fn parse_operation_mode(mode_str: &str) -> Option<InverterOperationMode> {
+pub(crate) fn parse_operation_mode(mode_str: &str) -> Option<InverterOperationMode> {
     match mode_str {
         "SelfUse" => Some(InverterOperationMode::SelfUse),
         "ForceCharge" => Some(InverterOperationMode::ForceCharge),
@@ -403,7 +708,7 @@ fn parse_operation_mode(mode_str: &str) -> Option<InverterOperationMode> {
 }
 
 /// Persist state to disk and notify ECS
-fn persist_and_notify(
+pub(crate) fn persist_and_notify(
     api_state: &UserControlApiState,
     new_state: &UserControlState,
     change_type: UserControlChangeType,
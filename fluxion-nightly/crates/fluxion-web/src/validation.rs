@@ -12,6 +12,7 @@
 
 use crate::config_api::ValidationIssue;
 use fluxion_core::resources::SystemConfig;
+use fluxion_types::config::LOG_LEVELS;
 
 /// Merge two JSON values recursively
 /// `target` is modified in place with values from `source`
@@ -149,6 +150,35 @@ pub fn validate_config(config: &SystemConfig) -> (Vec<ValidationIssue>, Vec<Vali
         });
     }
 
+    if control.max_grid_import_kw < 0.0 {
+        errors.push(ValidationIssue {
+            field: "control.max_grid_import_kw".to_owned(),
+            message: "Grid import limit cannot be negative (0 = unlimited)".to_owned(),
+            severity: "error".to_owned(),
+        });
+    } else if control.partial_charge_enabled
+        && control.max_grid_import_kw > 0.0
+        && control.max_grid_import_kw <= control.average_household_load_kw
+    {
+        warnings.push(ValidationIssue {
+            field: "control.max_grid_import_kw".to_owned(),
+            message:
+                "Grid import limit leaves no headroom for charging above the average household load"
+                    .to_owned(),
+            severity: "warning".to_owned(),
+        });
+    }
+
+    if (1..12).contains(&control.optimization_horizon_hours) {
+        warnings.push(ValidationIssue {
+            field: "control.optimization_horizon_hours".to_owned(),
+            message:
+                "Optimization horizon under 12 hours cannot plan a night charge for the evening peak"
+                    .to_owned(),
+            severity: "warning".to_owned(),
+        });
+    }
+
     // ============= Pricing Settings =============
     let pricing = &config.pricing_config;
 
@@ -201,6 +231,118 @@ pub fn validate_config(config: &SystemConfig) -> (Vec<ValidationIssue>, Vec<Vali
         });
     }
 
+    // ============= Logging Settings =============
+    let logging = &config.logging;
+
+    if !LOG_LEVELS.contains(&logging.level.as_str()) {
+        errors.push(ValidationIssue {
+            field: "logging.level".to_owned(),
+            message: format!("Log level must be one of: {}", LOG_LEVELS.join(", ")),
+            severity: "error".to_owned(),
+        });
+    }
+
+    for directive in &logging.module_levels {
+        let valid = directive.split_once('=').is_some_and(|(module, level)| {
+            !module.trim().is_empty() && LOG_LEVELS.contains(&level.trim())
+        });
+        if !valid {
+            errors.push(ValidationIssue {
+                field: "logging.module_levels".to_owned(),
+                message: format!("Invalid module level '{directive}', expected 'module=level'"),
+                severity: "error".to_owned(),
+            });
+        }
+    }
+
+    if logging.file_enabled && logging.directory.trim().is_empty() {
+        errors.push(ValidationIssue {
+            field: "logging.directory".to_owned(),
+            message: "Log directory is required when file logging is enabled".to_owned(),
+            severity: "error".to_owned(),
+        });
+    }
+
+    // ============= Export Cap Windows =============
+    for (i, window) in config.control_config.export_cap_windows.iter().enumerate() {
+        if window.end <= window.start {
+            errors.push(ValidationIssue {
+                field: format!("control.export_cap_windows[{i}].end"),
+                message: "Window must end after it starts".to_owned(),
+                severity: "error".to_owned(),
+            });
+        }
+    }
+
+    // ============= Grid Quality Settings =============
+    let grid_quality = &config.grid_quality;
+
+    if grid_quality.frequency_min_hz >= grid_quality.frequency_max_hz {
+        errors.push(ValidationIssue {
+            field: "grid_quality.frequency_min_hz".to_owned(),
+            message: "Minimum frequency must be below maximum frequency".to_owned(),
+            severity: "error".to_owned(),
+        });
+    }
+
+    if !(0.0..=100.0).contains(&grid_quality.overvoltage_export_percent) {
+        errors.push(ValidationIssue {
+            field: "grid_quality.overvoltage_export_percent".to_owned(),
+            message: "Export share must be between 0 and 100%".to_owned(),
+            severity: "error".to_owned(),
+        });
+    }
+
+    if grid_quality.sustained_minutes == 0 {
+        errors.push(ValidationIssue {
+            field: "grid_quality.sustained_minutes".to_owned(),
+            message: "Averaging window must be at least 1 minute".to_owned(),
+            severity: "error".to_owned(),
+        });
+    }
+
+    // ============= Peak Demand Settings =============
+    let peak_demand = &config.peak_demand;
+
+    if peak_demand.enabled
+        && (peak_demand.max_import_kw.is_nan() || peak_demand.max_import_kw <= 0.0)
+    {
+        errors.push(ValidationIssue {
+            field: "peak_demand.max_import_kw".to_owned(),
+            message: "Contracted maximum must be greater than 0 kW".to_owned(),
+            severity: "error".to_owned(),
+        });
+    }
+
+    if !(1.0..=100.0).contains(&peak_demand.trigger_percent) {
+        errors.push(ValidationIssue {
+            field: "peak_demand.trigger_percent".to_owned(),
+            message: "Trigger must be between 1 and 100%".to_owned(),
+            severity: "error".to_owned(),
+        });
+    }
+
+    // ============= Weather Settings =============
+    let weather = &config.control_config.weather;
+
+    if !(0.0..=100.0).contains(&weather.cloudy_threshold_pct)
+        || !(0.0..=weather.cloudy_threshold_pct).contains(&weather.clear_threshold_pct)
+    {
+        errors.push(ValidationIssue {
+            field: "control.weather.clear_threshold_pct".to_owned(),
+            message: "Clear threshold must be between 0% and the cloudy threshold".to_owned(),
+            severity: "error".to_owned(),
+        });
+    }
+
+    if !(0.0..=100.0).contains(&weather.cold_cloudy_reserve_soc) {
+        errors.push(ValidationIssue {
+            field: "control.weather.cold_cloudy_reserve_soc".to_owned(),
+            message: "Reserve must be between 0 and 100%".to_owned(),
+            severity: "error".to_owned(),
+        });
+    }
+
     (errors, warnings)
 }
 
@@ -215,6 +357,7 @@ mod tests {
 
     fn default_config() -> SystemConfig {
         SystemConfig {
+            schema_version: fluxion_core::CONFIG_SCHEMA_VERSION,
             inverters: vec![],
             pricing_config: PricingConfig {
                 spot_price_entity: "sensor.spot_price".to_owned(),
@@ -228,6 +371,9 @@ mod tests {
                 hdo_sensor_entity: "sensor.cez_hdo_raw_data".to_owned(),
                 hdo_low_tariff_czk: 0.50,
                 hdo_high_tariff_czk: 1.80,
+                hdo_source: fluxion_core::HdoSource::default(),
+                hdo_preset: fluxion_core::HdoPreset::default(),
+                hdo_windows: Vec::new(),
             },
             control_config: ControlConfig::default(),
             system_config: SystemSettingsConfig {
@@ -241,6 +387,10 @@ mod tests {
             history: ConsumptionHistoryConfig::default(),
             solar_forecast: fluxion_core::resources::SolarForecastConfigCore::default(),
             remote_access: RemoteAccessConfigCore::default(),
+            logging: fluxion_types::config::LoggingConfigCore::default(),
+            grid_quality: fluxion_types::config::GridQualityConfigCore::default(),
+            peak_demand: fluxion_types::config::PeakDemandConfigCore::default(),
+            weather: fluxion_types::weather::WeatherConfigCore::default(),
         }
     }
 
@@ -264,6 +414,78 @@ mod tests {
         assert!(errors.iter().any(|e| e.field == "control.min_battery_soc"));
     }
 
+    #[test]
+    fn test_logging_levels() {
+        let mut config = default_config();
+        config.logging.module_levels = vec!["fluxion_core=debug".to_owned()];
+        let (errors, _) = validate_config(&config);
+        assert!(errors.is_empty());
+
+        config.logging.level = "verbose".to_owned();
+        config.logging.module_levels = vec!["fluxion_core".to_owned()];
+        let (errors, _) = validate_config(&config);
+        assert!(errors.iter().any(|e| e.field == "logging.level"));
+        assert!(errors.iter().any(|e| e.field == "logging.module_levels"));
+    }
+
+    #[test]
+    fn test_peak_demand_bounds() {
+        let mut config = default_config();
+        config.peak_demand.max_import_kw = 0.0;
+        let (errors, _) = validate_config(&config);
+        assert!(errors.is_empty());
+
+        config.peak_demand.enabled = true;
+        config.peak_demand.trigger_percent = 0.0;
+        let (errors, _) = validate_config(&config);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.field == "peak_demand.max_import_kw")
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.field == "peak_demand.trigger_percent")
+        );
+    }
+
+    #[test]
+    fn test_weather_thresholds() {
+        let mut config = default_config();
+        config.control_config.weather.clear_threshold_pct = 90.0;
+        config.control_config.weather.cold_cloudy_reserve_soc = 120.0;
+        let (errors, _) = validate_config(&config);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.field == "control.weather.clear_threshold_pct")
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.field == "control.weather.cold_cloudy_reserve_soc")
+        );
+    }
+
+    #[test]
+    fn test_grid_quality_bounds() {
+        let mut config = default_config();
+        config.grid_quality.frequency_min_hz = 50.5;
+        config.grid_quality.overvoltage_export_percent = 150.0;
+        let (errors, _) = validate_config(&config);
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.field == "grid_quality.frequency_min_hz")
+        );
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.field == "grid_quality.overvoltage_export_percent")
+        );
+    }
+
     #[test]
     fn test_min_soc_higher_than_max() {
         let mut config = default_config();
@@ -0,0 +1,156 @@
+// Copyright (c) 2025 SOLARE S.R.O.
+//
+// This file is part of FluxION.
+//
+// Licensed under the Creative Commons Attribution-NonCommercial-NoDerivatives 4.0 International
+// (CC BY-NC-ND 4.0). You may use and share this file for non-commercial purposes only and you may not
+// create derivatives. See <https://creativecommons.org/licenses/by-nc-nd/4.0/>.
+//
+// This software is provided "AS IS", without warranty of any kind.
+//
+// For commercial licensing, please contact: info@solare.cz
+
+//! Counterfactual schedules against the live planning inputs.
+
+use axum::{
+    Json,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use fluxion_core::what_if::{WhatIfError, WhatIfPlanner, WhatIfRequest};
+use tracing::error;
+
+/// POST /api/schedule/what-if — plan again with hypothetical overrides
+///
+/// The live schedule stays as it is; the response holds the alternative
+/// schedule and the blocks it decides differently.
+pub async fn what_if_handler(
+    State(planner): State<WhatIfPlanner>,
+    Json(request): Json<WhatIfRequest>,
+) -> Response {
+    let result = tokio::task::spawn_blocking(move || planner.run(&request)).await;
+    match result {
+        Ok(Ok(response)) => Json(response).into_response(),
+        Ok(Err(e)) => {
+            let status = match e {
+                WhatIfError::NoLiveSchedule => StatusCode::SERVICE_UNAVAILABLE,
+                WhatIfError::InvalidOverride(_) | WhatIfError::UnknownStrategy(_) => {
+                    StatusCode::BAD_REQUEST
+                }
+            };
+            (status, Json(serde_json::json!({ "error": e.to_string() }))).into_response()
+        }
+        Err(e) => {
+            error!("What-if planning panicked: {e}");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({ "error": "What-if planning failed" })),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+    use fluxion_core::scheduling::ScheduleConfig;
+    use fluxion_core::what_if::PlanningInputs;
+    use fluxion_core::{ControlConfig, TimeBlockPrice};
+    use fluxion_plugins::PluginManager;
+    use parking_lot::RwLock;
+    use std::sync::Arc;
+
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn planner_with_live_schedule() -> WhatIfPlanner {
+        let plugin_manager = Arc::new(RwLock::new(PluginManager::new()));
+        let planner = WhatIfPlanner::new(plugin_manager.clone());
+        let start = Utc::now() + Duration::hours(1);
+        let inputs = PlanningInputs {
+            time_block_prices: [1.0, 5.0, 1.0, 5.0]
+                .into_iter()
+                .zip(0_i64..)
+                .map(|(price, i)| TimeBlockPrice {
+                    block_start: start + Duration::minutes(15 * i),
+                    duration_minutes: 15,
+                    price_czk_per_kwh: price,
+                    effective_price_czk_per_kwh: price + 0.5,
+                    spot_sell_price_czk_per_kwh: Some(price - 0.2),
+                })
+                .collect(),
+            control_config: ControlConfig::default(),
+            schedule_config: ScheduleConfig::default(),
+            current_battery_soc: 50.0,
+            solar_forecast: None,
+            consumption_forecast: None,
+            backup_discharge_min_soc: 10.0,
+            grid_import_today_kwh: None,
+            hdo_raw_data: None,
+            solar_forecast_total_today_kwh: 0.0,
+            solar_forecast_remaining_today_kwh: 0.0,
+            solar_forecast_tomorrow_kwh: 0.0,
+            user_control: None,
+            hourly_consumption_profile: None,
+        };
+        let live = inputs.plan(&plugin_manager.read());
+        planner.record(inputs, &live);
+        planner
+    }
+
+    #[tokio::test]
+    async fn test_what_if_plans_with_overrides() {
+        let request = WhatIfRequest {
+            battery_soc: Some(90.0),
+            ..WhatIfRequest::default()
+        };
+        let response = what_if_handler(State(planner_with_live_schedule()), Json(request)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let what_if = body(response).await;
+        assert_eq!(what_if["battery_soc"], 90.0);
+        assert_eq!(
+            what_if["schedule"]["scheduled_blocks"]
+                .as_array()
+                .unwrap()
+                .len(),
+            4
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_live_schedule_is_unavailable() {
+        let planner = WhatIfPlanner::new(Arc::new(RwLock::new(PluginManager::new())));
+        let response = what_if_handler(State(planner), Json(WhatIfRequest::default())).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            body(response).await["error"],
+            "No live schedule has been planned yet"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bad_overrides_are_rejected() {
+        let too_full = WhatIfRequest {
+            battery_soc: Some(120.0),
+            ..WhatIfRequest::default()
+        };
+        let response = what_if_handler(State(planner_with_live_schedule()), Json(too_full)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let unknown = WhatIfRequest {
+            disabled_strategies: vec!["Nope".to_owned()],
+            ..WhatIfRequest::default()
+        };
+        let response = what_if_handler(State(planner_with_live_schedule()), Json(unknown)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(body(response).await["error"], "Unknown strategy 'Nope'");
+    }
+}